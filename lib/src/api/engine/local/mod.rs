@@ -46,12 +46,13 @@ use crate::channel;
 use crate::dbs::Response;
 use crate::dbs::Session;
 use crate::kvs::Datastore;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::kvs::ExportOptions;
 use crate::opt::IntoEndpoint;
 use crate::sql::Array;
 use crate::sql::Query;
 use crate::sql::Statement;
 use crate::sql::Statements;
-use crate::sql::Strand;
 use crate::sql::Value;
 use indexmap::IndexMap;
 use std::collections::BTreeMap;
@@ -408,9 +409,11 @@ async fn export(
 	sess: &Session,
 	ns: String,
 	db: String,
+	stable: bool,
 	chn: channel::Sender<Vec<u8>>,
 ) -> Result<()> {
-	if let Err(error) = kvs.export(sess, ns, db, chn).await?.await {
+	let cfg = ExportOptions::new().with_stable_iteration(stable);
+	if let Err(error) = kvs.export_with_options(sess, ns, db, cfg, chn).await?.await {
 		if let crate::error::Db::Channel(message) = error {
 			// This is not really an error. Just logging it for improved visibility.
 			trace!("{message}");
@@ -450,15 +453,15 @@ async fn router(
 	match method {
 		Method::Use => {
 			match &mut params[..] {
-				[Value::Strand(Strand(ns)), Value::Strand(Strand(db))] => {
-					session.ns = Some(mem::take(ns));
-					session.db = Some(mem::take(db));
+				[Value::Strand(ns), Value::Strand(db)] => {
+					session.ns = Some(mem::take(ns).as_string());
+					session.db = Some(mem::take(db).as_string());
 				}
-				[Value::Strand(Strand(ns)), Value::None] => {
-					session.ns = Some(mem::take(ns));
+				[Value::Strand(ns), Value::None] => {
+					session.ns = Some(mem::take(ns).as_string());
 				}
-				[Value::None, Value::Strand(Strand(db))] => {
-					session.db = Some(mem::take(db));
+				[Value::None, Value::Strand(db)] => {
+					session.db = Some(mem::take(db).as_string());
 				}
 				_ => unreachable!(),
 			}
@@ -482,7 +485,7 @@ async fn router(
 		}
 		Method::Authenticate => {
 			let token = match &mut params[..] {
-				[Value::Strand(Strand(token))] => mem::take(token),
+				[Value::Strand(token)] => mem::take(token).as_string(),
 				_ => unreachable!(),
 			};
 			crate::iam::verify::token(kvs, session, &token).await?;
@@ -553,13 +556,14 @@ async fn router(
 			let ns = session.ns.clone().unwrap_or_default();
 			let db = session.db.clone().unwrap_or_default();
 			let (tx, rx) = channel::new(1);
+			let stable = matches!(param.other.first(), Some(Value::Bool(true)));
 
 			match (param.file, param.sender) {
 				(Some(path), None) => {
 					let (mut writer, mut reader) = io::duplex(10_240);
 
 					// Write to channel.
-					let export = export(kvs, session, ns, db, tx);
+					let export = export(kvs, session, ns, db, stable, tx);
 
 					// Read from channel and write to pipe.
 					let bridge = async move {
@@ -600,7 +604,7 @@ async fn router(
 					let session = session.clone();
 					tokio::spawn(async move {
 						let export = async {
-							if let Err(error) = export(&kvs, &session, ns, db, tx).await {
+							if let Err(error) = export(&kvs, &session, ns, db, stable, tx).await {
 								let _ = backup.send(Err(error)).await;
 							}
 						};
@@ -652,7 +656,7 @@ async fn router(
 		Method::Version => Ok(DbResponse::Other(crate::env::VERSION.into())),
 		Method::Set => {
 			let (key, value) = match &mut params[..2] {
-				[Value::Strand(Strand(key)), value] => (mem::take(key), mem::take(value)),
+				[Value::Strand(key), value] => (mem::take(key).as_string(), mem::take(value)),
 				_ => unreachable!(),
 			};
 			match kvs.compute(value, &*session, Some(vars.clone())).await? {
@@ -662,8 +666,8 @@ async fn router(
 			Ok(DbResponse::Other(Value::None))
 		}
 		Method::Unset => {
-			if let [Value::Strand(Strand(key))] = &params[..1] {
-				vars.remove(key);
+			if let [Value::Strand(key)] = &params[..1] {
+				vars.remove(key.as_str());
 			}
 			Ok(DbResponse::Other(Value::None))
 		}
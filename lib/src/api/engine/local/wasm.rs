@@ -17,6 +17,7 @@ use crate::kvs::Datastore;
 use crate::opt::auth::Root;
 use flume::Receiver;
 use flume::Sender;
+use futures::future;
 use futures::StreamExt;
 use futures_concurrency::stream::Merge as _;
 use std::collections::BTreeMap;
@@ -154,6 +155,14 @@ pub(crate) fn router(
 
 fn run_maintenance(kvs: Arc<Datastore>, tick_interval: Duration, stop_signal: Receiver<()>) {
 	spawn_local(async move {
+		// Heartbeats are sent from their own task, so that a slow tick
+		// does not cause this node to be considered dead by the cluster
+		let (heartbeats, heartbeats_handle) =
+			future::abortable(run_heartbeats(kvs.clone(), tick_interval));
+		spawn_local(async move {
+			let _ = heartbeats.await;
+		});
+
 		let mut interval = time::interval(tick_interval);
 		// Don't bombard the database if we miss some ticks
 		interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
@@ -172,5 +181,19 @@ fn run_maintenance(kvs: Arc<Datastore>, tick_interval: Duration, stop_signal: Re
 				Err(error) => error!("Error running node agent tick: {error}"),
 			}
 		}
+
+		heartbeats_handle.abort();
 	});
 }
+
+async fn run_heartbeats(kvs: Arc<Datastore>, heartbeat_interval: Duration) {
+	let mut interval = time::interval(heartbeat_interval);
+	// Don't bombard the database if we miss some heartbeats
+	interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+	loop {
+		interval.tick().await;
+		if let Err(error) = kvs.heartbeat().await {
+			error!("Error sending node heartbeat: {error}");
+		}
+	}
+}
@@ -25,7 +25,6 @@ use crate::dbs::Status;
 use crate::opt::IntoEndpoint;
 use crate::sql::serde::deserialize;
 use crate::sql::Array;
-use crate::sql::Strand;
 use crate::sql::Value;
 #[cfg(not(target_arch = "wasm32"))]
 use futures::TryStreamExt;
@@ -335,11 +334,11 @@ async fn router(
 			let path = base_url.join(SQL_PATH)?;
 			let mut request = client.post(path).headers(headers.clone());
 			let (ns, db) = match &mut params[..] {
-				[Value::Strand(Strand(ns)), Value::Strand(Strand(db))] => {
-					(Some(mem::take(ns)), Some(mem::take(db)))
+				[Value::Strand(ns), Value::Strand(db)] => {
+					(Some(mem::take(ns).as_string()), Some(mem::take(db).as_string()))
 				}
-				[Value::Strand(Strand(ns)), Value::None] => (Some(mem::take(ns)), None),
-				[Value::None, Value::Strand(Strand(db))] => (None, Some(mem::take(db))),
+				[Value::Strand(ns), Value::None] => (Some(mem::take(ns).as_string()), None),
+				[Value::None, Value::Strand(db)] => (None, Some(mem::take(db).as_string())),
 				_ => unreachable!(),
 			};
 			let ns = match ns {
@@ -415,7 +414,7 @@ async fn router(
 		Method::Authenticate => {
 			let path = base_url.join(SQL_PATH)?;
 			let token = match &mut params[..1] {
-				[Value::Strand(Strand(token))] => mem::take(token),
+				[Value::Strand(token)] => mem::take(token).as_string(),
 				_ => unreachable!(),
 			};
 			let request =
@@ -497,11 +496,14 @@ async fn router(
 		#[cfg(not(target_arch = "wasm32"))]
 		Method::Export => {
 			let path = base_url.join(Method::Export.as_str())?;
-			let request = client
+			let mut request = client
 				.get(path)
 				.headers(headers.clone())
 				.auth(auth)
 				.header(ACCEPT, "application/octet-stream");
+			if let Some(Value::Bool(true)) = param.other.first() {
+				request = request.query(&[("stable", "true")]);
+			}
 			let value = export(request, (param.file, param.sender)).await?;
 			Ok(DbResponse::Other(value))
 		}
@@ -532,7 +534,7 @@ async fn router(
 		Method::Set => {
 			let path = base_url.join(SQL_PATH)?;
 			let (key, value) = match &mut params[..2] {
-				[Value::Strand(Strand(key)), value] => (mem::take(key), value.to_string()),
+				[Value::Strand(key), value] => (mem::take(key).as_string(), value.to_string()),
 				_ => unreachable!(),
 			};
 			let request = client
@@ -546,8 +548,8 @@ async fn router(
 			Ok(DbResponse::Other(Value::None))
 		}
 		Method::Unset => {
-			if let [Value::Strand(Strand(key))] = &params[..1] {
-				vars.remove(key);
+			if let [Value::Strand(key)] = &params[..1] {
+				vars.remove(key.as_str());
 			}
 			Ok(DbResponse::Other(Value::None))
 		}
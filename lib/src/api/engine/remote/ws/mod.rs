@@ -15,7 +15,6 @@ use crate::api::Surreal;
 use crate::dbs::Status;
 use crate::opt::IntoEndpoint;
 use crate::sql::Array;
-use crate::sql::Strand;
 use crate::sql::Value;
 use serde::Deserialize;
 use std::marker::PhantomData;
@@ -121,7 +120,7 @@ impl DbResponse {
 							value => Ok(vec![value]),
 						},
 						Status::Err => match response.result {
-							Value::Strand(Strand(message)) => Err(Error::Query(message).into()),
+							Value::Strand(message) => Err(Error::Query(message.as_string()).into()),
 							message => Err(Error::Query(message.to_string()).into()),
 						},
 					})
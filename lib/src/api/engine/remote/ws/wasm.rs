@@ -16,7 +16,6 @@ use crate::api::Result;
 use crate::api::Surreal;
 use crate::engine::IntervalStream;
 use crate::sql::serde::{deserialize, serialize};
-use crate::sql::Strand;
 use crate::sql::Value;
 use flume::Receiver;
 use flume::Sender;
@@ -194,13 +193,13 @@ pub(crate) fn router(
 						};
 						match method {
 							Method::Set => {
-								if let [Value::Strand(Strand(key)), value] = &params[..2] {
-									vars.insert(key.to_owned(), value.clone());
+								if let [Value::Strand(key), value] = &params[..2] {
+									vars.insert(key.as_str().to_owned(), value.clone());
 								}
 							}
 							Method::Unset => {
-								if let [Value::Strand(Strand(key))] = &params[..1] {
-									vars.remove(key);
+								if let [Value::Strand(key)] = &params[..1] {
+									vars.remove(key.as_str());
 								}
 							}
 							_ => {}
@@ -6,6 +6,7 @@ use crate::api::Error;
 use crate::api::ExtraFeatures;
 use crate::api::Result;
 use crate::opt::ExportDestination;
+use crate::sql::Value;
 use channel::Receiver;
 use futures::Stream;
 use futures::StreamExt;
@@ -23,9 +24,22 @@ use std::task::Poll;
 pub struct Export<'r, C: Connection, R> {
 	pub(super) router: Result<&'r Router<C>>,
 	pub(super) target: ExportDestination,
+	pub(super) stable: bool,
 	pub(super) response: PhantomData<R>,
 }
 
+impl<C, R> Export<'_, C, R>
+where
+	C: Connection,
+{
+	/// Scans each batch of records in its own transaction, instead of
+	/// scanning every record in one long-running transaction
+	pub fn stable(mut self) -> Self {
+		self.stable = true;
+		self
+	}
+}
+
 impl<'r, Client> IntoFuture for Export<'r, Client, PathBuf>
 where
 	Client: Connection,
@@ -41,7 +55,11 @@ where
 			}
 			let mut conn = Client::new(Method::Export);
 			match self.target {
-				ExportDestination::File(path) => conn.execute_unit(router, Param::file(path)).await,
+				ExportDestination::File(path) => {
+					let mut param = Param::file(path);
+					param.other = vec![Value::Bool(self.stable)];
+					conn.execute_unit(router, param).await
+				}
 				ExportDestination::Memory => unreachable!(),
 			}
 		})
@@ -66,7 +84,9 @@ where
 			let ExportDestination::Memory = self.target else {
 				unreachable!();
 			};
-			conn.execute_unit(router, Param::sender(tx)).await?;
+			let mut param = Param::sender(tx);
+			param.other = vec![Value::Bool(self.stable)];
+			conn.execute_unit(router, param).await?;
 			Ok(Backup {
 				rx,
 			})
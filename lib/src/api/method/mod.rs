@@ -976,6 +976,9 @@ where
 	/// // Export to a file
 	/// db.export("backup.sql").await?;
 	///
+	/// // Export to a file, scanning each batch of records in its own transaction
+	/// db.export("backup.sql").stable().await?;
+	///
 	/// // Export to a stream of bytes
 	/// let mut backup = db.export(()).await?;
 	/// while let Some(result) = backup.next().await {
@@ -995,6 +998,7 @@ where
 		Export {
 			router: self.router.extract(),
 			target: target.into_export_destination(),
+			stable: false,
 			response: PhantomData,
 		}
 	}
@@ -11,7 +11,6 @@ use crate::sql::Array;
 use crate::sql::Object;
 use crate::sql::Statement;
 use crate::sql::Statements;
-use crate::sql::Strand;
 use crate::sql::Value;
 use indexmap::IndexMap;
 use serde::de::DeserializeOwned;
@@ -109,9 +108,9 @@ where
 			match to_value(bindings) {
 				Ok(mut bindings) => {
 					if let Value::Array(Array(array)) = &mut bindings {
-						if let [Value::Strand(Strand(key)), value] = &mut array[..] {
+						if let [Value::Strand(key), value] = &mut array[..] {
 							let mut map = BTreeMap::new();
-							map.insert(mem::take(key), mem::take(value));
+							map.insert(mem::take(key).as_string(), mem::take(value));
 							bindings = map.into();
 						}
 					}
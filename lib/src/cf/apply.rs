@@ -0,0 +1,332 @@
+use crate::cf::{ChangeSet, TableMutation, TableMutations};
+use crate::ctx::Context;
+use crate::dbs::{Options, Transaction};
+use crate::err::Error;
+use crate::sql::statements::{DeleteStatement, UpdateStatement};
+use crate::sql::{Data, Object, Output, Thing, Value, Values};
+use crate::vs::Versionstamp;
+use derive::Store;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// A hook which resolves a conflicting replicated change. It is given the record id,
+/// the local record (if it exists) and the replicated record (if it was not deleted),
+/// and returns the record to store, or `None` to delete the record.
+pub type MergeHook =
+	Arc<dyn Fn(&Thing, Option<Value>, Option<Value>) -> Option<Value> + Send + Sync>;
+
+/// Determines what happens when a replicated change is older than
+/// the last change which was applied to the same record.
+#[derive(Clone, Default)]
+pub enum ConflictPolicy {
+	/// Keep the most recent change, and skip the older one
+	#[default]
+	LastWriteWins,
+	/// Abort applying the change set with an error
+	Error,
+	/// Resolve the conflict with a custom merge hook
+	Merge(MergeHook),
+}
+
+impl fmt::Debug for ConflictPolicy {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::LastWriteWins => f.write_str("LastWriteWins"),
+			Self::Error => f.write_str("Error"),
+			Self::Merge(_) => f.write_str("Merge"),
+		}
+	}
+}
+
+/// The versionstamps of the last replicated changes applied
+/// to each of the fields of a record in a mergeable table.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store)]
+#[revisioned(revision = 1)]
+pub(crate) struct FieldVersions {
+	/// The versionstamp at which the record was last deleted
+	deleted: Option<Versionstamp>,
+	/// The versionstamp at which each top-level field was last changed
+	fields: BTreeMap<String, Versionstamp>,
+}
+
+impl FieldVersions {
+	/// Checks whether a replicated change is older than a more recent change to the
+	/// record, either to one of the fields which it sets or removes, or a deletion.
+	fn conflicts(&self, vs: Versionstamp, local: &Option<Value>, remote: &Option<Value>) -> bool {
+		let newer = |f: &String| matches!(self.fields.get(f), Some(v) if *v >= vs);
+		match remote {
+			// The record was updated
+			Some(remote) => {
+				if local.is_none() && matches!(self.deleted, Some(d) if d >= vs) {
+					return true;
+				}
+				let fields = |v: Option<&Value>| match v {
+					Some(Value::Object(v)) => v.keys().cloned().collect::<Vec<_>>(),
+					_ => Vec::new(),
+				};
+				fields(local.as_ref()).iter().chain(fields(Some(remote)).iter()).any(newer)
+			}
+			// The record was deleted
+			None => self.fields.keys().any(newer),
+		}
+	}
+
+	/// Records the versionstamp of a change which was resolved with a merge hook,
+	/// for every field of the resolved record, or for its deletion.
+	fn resolve(&mut self, vs: Versionstamp, val: &Option<Value>) {
+		match val {
+			Some(Value::Object(v)) => {
+				for field in v.keys().filter(|f| *f != "id") {
+					let last = self.fields.entry(field.clone()).or_insert(vs);
+					*last = (*last).max(vs);
+				}
+			}
+			Some(_) => (),
+			None => {
+				self.deleted = Some(self.deleted.map_or(vs, |d| d.max(vs)));
+				self.fields.clear();
+			}
+		}
+	}
+
+	/// Merges a replicated change into the local record, field by field,
+	/// keeping whichever value of each field was changed most recently.
+	/// Fields which were written locally have no versionstamp, and are
+	/// always overwritten. Returns `None` if the change should be skipped.
+	fn merge(
+		&mut self,
+		vs: Versionstamp,
+		local: Option<Value>,
+		remote: Option<Value>,
+	) -> Option<Option<Value>> {
+		match remote {
+			// The record was updated
+			Some(remote) => {
+				// A record which was deleted more recently stays deleted
+				if local.is_none() && matches!(self.deleted, Some(d) if d >= vs) {
+					return None;
+				}
+				let local = match local {
+					Some(Value::Object(v)) => v,
+					_ => Object::default(),
+				};
+				let remote = match remote {
+					Value::Object(v) => v,
+					_ => Object::default(),
+				};
+				let mut merged = local.clone();
+				for field in local.keys().chain(remote.keys()) {
+					if field == "id" {
+						continue;
+					}
+					if matches!(self.fields.get(field), Some(v) if *v >= vs) {
+						continue;
+					}
+					match remote.get(field) {
+						Some(v) => merged.insert(field.clone(), v.clone()),
+						None => merged.remove(field),
+					};
+					self.fields.insert(field.clone(), vs);
+				}
+				Some(Some(merged.into()))
+			}
+			// The record was deleted
+			None => {
+				// Fields which were changed more recently are kept
+				if self.fields.values().any(|v| *v >= vs) {
+					return None;
+				}
+				self.deleted = Some(vs);
+				self.fields.clear();
+				Some(None)
+			}
+		}
+	}
+}
+
+/// Applies all the mutations of a change set within the given transaction
+pub(crate) async fn apply(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	cs: ChangeSet,
+	policy: &ConflictPolicy,
+) -> Result<(), Error> {
+	let ChangeSet(vs, mutations) = cs;
+	for TableMutations(tb, mutations) in mutations.0 {
+		// Check whether the table merges changes field by field
+		let merge = match txn.lock().await.get_and_cache_tb(opt.ns(), opt.db(), &tb).await {
+			Ok(tb) => tb.merge,
+			Err(Error::TbNotFound {
+				..
+			}) => false,
+			Err(e) => return Err(e),
+		};
+		for mutation in mutations {
+			let (rid, remote) = match mutation {
+				TableMutation::Set(rid, v) => (rid, Some(v)),
+				TableMutation::Del(rid) => (rid, None),
+			};
+			let val = match merge {
+				true => {
+					// Fetch the versionstamps of the fields of this record
+					let key = crate::key::table::fv::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
+					let mut fv = match txn.lock().await.get(key.clone()).await? {
+						Some(v) => FieldVersions::from(v),
+						None => FieldVersions::default(),
+					};
+					let local = local(opt, txn, &rid).await?;
+					// Check whether this change is older than any field it changes
+					let conflict = fv.conflicts(vs, &local, &remote);
+					match policy {
+						ConflictPolicy::Error if conflict => {
+							return Err(Error::ReplicationConflict {
+								thing: rid,
+							})
+						}
+						ConflictPolicy::Merge(hook) if conflict => {
+							let val = hook(&rid, local, remote);
+							fv.resolve(vs, &val);
+							txn.lock().await.set(key, fv).await?;
+							val
+						}
+						// Merge the change into the record field by field
+						_ => match fv.merge(vs, local, remote) {
+							Some(val) => {
+								txn.lock().await.set(key, fv).await?;
+								val
+							}
+							None => continue,
+						},
+					}
+				}
+				false => {
+					// Fetch the versionstamp of the last change applied to this record
+					let key = crate::key::table::rv::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
+					let last = txn.lock().await.get(key.clone()).await?;
+					// Check whether this change is older than the record
+					match last {
+						Some(last) if last.as_slice() >= vs.as_slice() => match policy {
+							ConflictPolicy::LastWriteWins => continue,
+							ConflictPolicy::Error => {
+								return Err(Error::ReplicationConflict {
+									thing: rid,
+								})
+							}
+							ConflictPolicy::Merge(hook) => {
+								let local = local(opt, txn, &rid).await?;
+								hook(&rid, local, remote)
+							}
+						},
+						_ => {
+							txn.lock().await.set(key, vs.to_vec()).await?;
+							remote
+						}
+					}
+				}
+			};
+			// Write the record
+			write(ctx, opt, txn, rid, val).await?;
+		}
+	}
+	Ok(())
+}
+
+/// Fetches the local record which a replicated change is applied to
+async fn local(opt: &Options, txn: &Transaction, rid: &Thing) -> Result<Option<Value>, Error> {
+	let key = crate::key::thing::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
+	match txn.lock().await.get(key).await? {
+		Some(v) => {
+			let mut val = Value::from(v);
+			crate::doc::resolve(opt, txn, rid, &mut val).await?;
+			Ok(Some(val))
+		}
+		None => Ok(None),
+	}
+}
+
+/// Stores or deletes a record, processing its fields, events and indexes
+async fn write(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	rid: Thing,
+	val: Option<Value>,
+) -> Result<(), Error> {
+	let what = Values(vec![Value::Thing(rid)]);
+	match val {
+		Some(v) => {
+			let stm = UpdateStatement {
+				what,
+				data: Some(Data::ContentExpression(v)),
+				output: Some(Output::None),
+				..UpdateStatement::default()
+			};
+			stm.compute(ctx, opt, txn, None).await?;
+		}
+		None => {
+			let stm = DeleteStatement {
+				what,
+				output: Some(Output::None),
+				..DeleteStatement::default()
+			};
+			stm.compute(ctx, opt, txn, None).await?;
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sql::test::Parse;
+
+	#[test]
+	fn merge_fields() {
+		let mut fv = FieldVersions::default();
+		let local = Value::parse("{ id: person:test, name: 'Tobie', age: 30 }");
+		let remote = Value::parse("{ id: person:test, name: 'Jaime', age: 31 }");
+		// A newer change overwrites every field
+		let out = fv.merge([0, 0, 0, 0, 0, 0, 0, 0, 0, 2], Some(local), Some(remote));
+		assert_eq!(out, Some(Some(Value::parse("{ id: person:test, name: 'Jaime', age: 31 }"))));
+		// An older change only sets the fields which were not changed since
+		let local = Value::parse("{ id: person:test, name: 'Jaime', age: 31 }");
+		let remote = Value::parse("{ id: person:test, name: 'Tobie', age: 30, city: 'London' }");
+		let out = fv.merge([0, 0, 0, 0, 0, 0, 0, 0, 0, 1], Some(local), Some(remote));
+		assert_eq!(
+			out,
+			Some(Some(Value::parse("{ id: person:test, name: 'Jaime', age: 31, city: 'London' }")))
+		);
+		// An older delete does not remove a more recently changed record
+		let out = fv.merge([0, 0, 0, 0, 0, 0, 0, 0, 0, 1], None, None);
+		assert_eq!(out, None);
+		// A newer delete removes the record
+		let out = fv.merge([0, 0, 0, 0, 0, 0, 0, 0, 0, 3], None, None);
+		assert_eq!(out, Some(None));
+		// An older change does not recreate a deleted record
+		let remote = Value::parse("{ id: person:test, name: 'Tobie' }");
+		let out = fv.merge([0, 0, 0, 0, 0, 0, 0, 0, 0, 2], None, Some(remote));
+		assert_eq!(out, None);
+	}
+
+	#[test]
+	fn conflicting_fields() {
+		let mut fv = FieldVersions::default();
+		let local = Some(Value::parse("{ id: person:test, name: 'Tobie' }"));
+		let remote = Some(Value::parse("{ id: person:test, name: 'Jaime' }"));
+		fv.merge([0, 0, 0, 0, 0, 0, 0, 0, 0, 2], local.clone(), remote.clone());
+		// Changes at the same or an older versionstamp conflict, for updates and deletes
+		assert!(fv.conflicts([0, 0, 0, 0, 0, 0, 0, 0, 0, 2], &local, &remote));
+		assert!(fv.conflicts([0, 0, 0, 0, 0, 0, 0, 0, 0, 2], &local, &None));
+		assert!(fv.conflicts([0, 0, 0, 0, 0, 0, 0, 0, 0, 1], &local, &remote));
+		// Newer changes do not conflict
+		assert!(!fv.conflicts([0, 0, 0, 0, 0, 0, 0, 0, 0, 3], &local, &remote));
+		assert!(!fv.conflicts([0, 0, 0, 0, 0, 0, 0, 0, 0, 3], &local, &None));
+		// Changes to other fields do not conflict
+		let other = Some(Value::parse("{ id: person:test, age: 30 }"));
+		assert!(!fv.conflicts([0, 0, 0, 0, 0, 0, 0, 0, 0, 1], &None, &other));
+	}
+}
@@ -1,8 +1,10 @@
+pub(crate) mod apply;
 pub(crate) mod gc;
 pub(crate) mod mutations;
 pub(crate) mod reader;
 pub(crate) mod writer;
 
+pub use self::apply::{ConflictPolicy, MergeHook};
 pub use self::gc::*;
 pub use self::mutations::*;
 pub use self::reader::read;
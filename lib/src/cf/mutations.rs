@@ -1,8 +1,9 @@
+use crate::err::Error;
 use crate::sql::array::Array;
 use crate::sql::object::Object;
 use crate::sql::thing::Thing;
 use crate::sql::value::Value;
-use crate::vs::to_u128_be;
+use crate::vs::{to_u128_be, try_u128_to_versionstamp};
 use derive::Store;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
@@ -91,6 +92,46 @@ impl ChangeSet {
 	}
 }
 
+impl TryFrom<Value> for ChangeSet {
+	type Error = Error;
+	/// Converts a change set, as output by `SHOW CHANGES`, back into its structured form
+	fn try_from(value: Value) -> Result<Self, Self::Error> {
+		let err = || Error::TryFrom(value.to_string(), "ChangeSet");
+		let (vs, changes) = match &value {
+			Value::Object(o) => match (o.get("versionstamp"), o.get("changes")) {
+				(Some(vs), Some(Value::Array(changes))) => (vs, changes),
+				_ => return Err(err()),
+			},
+			_ => return Err(err()),
+		};
+		let vs = u128::try_from(vs.clone()).map_err(|_| err())?;
+		let vs = try_u128_to_versionstamp(vs).map_err(|_| err())?;
+		let mut buf: Vec<TableMutations> = Vec::new();
+		for change in changes.iter() {
+			let (tb, mutation) = match change {
+				Value::Object(o) => match (o.get("update"), o.get("delete")) {
+					(Some(v), None) => match v.rid() {
+						Value::Thing(t) => (t.tb.clone(), TableMutation::Set(t, v.clone())),
+						_ => return Err(err()),
+					},
+					(None, Some(v)) => match v.rid() {
+						Value::Thing(t) => (t.tb.clone(), TableMutation::Del(t)),
+						_ => return Err(err()),
+					},
+					_ => return Err(err()),
+				},
+				_ => return Err(err()),
+			};
+			// Group consecutive mutations on the same table
+			match buf.last_mut() {
+				Some(TableMutations(last, muts)) if *last == tb => muts.push(mutation),
+				_ => buf.push(TableMutations(tb, vec![mutation])),
+			}
+		}
+		Ok(ChangeSet(vs, DatabaseMutation(buf)))
+	}
+}
+
 impl Display for TableMutation {
 	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
 		match self {
@@ -174,4 +215,26 @@ mod tests {
 			r#"{"changes":[{"update":{"id":"mytb:tobie","note":"surreal"}},{"delete":{"id":"mytb:tobie"}}],"versionstamp":1}"#
 		);
 	}
+
+	#[test]
+	fn deserialization() {
+		use super::*;
+		use crate::sql::test::Parse;
+		let cs = ChangeSet(
+			[0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
+			DatabaseMutation(vec![TableMutations(
+				"mytb".to_string(),
+				vec![
+					TableMutation::Set(
+						Thing::from(("mytb".to_string(), "tobie".to_string())),
+						Value::parse("{ id: mytb:tobie, note: 'surreal' }"),
+					),
+					TableMutation::Del(Thing::from(("mytb".to_string(), "tobie".to_string()))),
+				],
+			)]),
+		);
+		let out = ChangeSet::try_from(cs.clone().into_value()).unwrap();
+		assert_eq!(out, cs);
+		assert!(ChangeSet::try_from(Value::from("test")).is_err());
+	}
 }
@@ -14,15 +14,17 @@ use std::collections::HashMap;
 // value = serialized table mutations
 type PreparedWrite = (Vec<u8>, Vec<u8>, Vec<u8>, crate::kvs::Val);
 
+#[derive(Clone)]
 pub struct Writer {
 	buf: Buffer,
 }
 
+#[derive(Clone)]
 pub struct Buffer {
 	pub b: HashMap<ChangeKey, TableMutations>,
 }
 
-#[derive(Hash, Eq, PartialEq, Debug)]
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
 pub struct ChangeKey {
 	pub ns: String,
 	pub db: String,
@@ -22,7 +22,7 @@ pub static MAX_COMPUTATION_DEPTH: Lazy<u8> = Lazy::new(|| {
 });
 
 /// Specifies the names of parameters which can not be specified in a query.
-pub const PROTECTED_PARAM_NAMES: &[&str] = &["auth", "scope", "token", "session"];
+pub const PROTECTED_PARAM_NAMES: &[&str] = &["auth", "scope", "token", "session", "request_id"];
 
 /// The characters which are supported in server record IDs.
 pub const ID_CHARS: [char; 36] = [
@@ -35,3 +35,39 @@ pub const SERVER_NAME: &str = "SurrealDB";
 
 /// Datastore processor batch size for scan operations
 pub const PROCESSOR_BATCH_SIZE: u32 = 50;
+
+/// The number of times a batch of an index being built in the background
+/// is retried, when its transaction conflicts with concurrent writes
+pub const INDEX_BUILD_RETRIES: usize = 10;
+
+/// The approximate number of bytes which the groups of a GROUP BY clause
+/// can hold in memory, before being spilled to temporary files
+pub const GROUP_MEMORY_LIMIT: usize = 64 * 1024 * 1024;
+
+/// The minimum number of encoded bytes which a value of a DEDUP field
+/// must have, before it is stored once under the hash of its content
+pub const DEDUP_MIN_SIZE: usize = 64;
+
+/// The number of keys across which the changes to the statistics of an
+/// index are spread, so that concurrent writes to an index do not conflict
+pub const INDEX_STATS_SHARDS: u32 = 16;
+
+/// The number of keys which the background scrubbing task verifies
+/// on each tick, when checksums are enabled on the datastore
+pub const SCRUB_BATCH_SIZE: u32 = 1000;
+
+/// The minimum number of seconds between the batches which the
+/// background scrubbing task verifies
+pub const SCRUB_INTERVAL: u64 = 10;
+
+/// The number of edges which are fetched at once when checking the
+/// relations of an edge table for dangling edges
+pub const CHECK_BATCH_SIZE: u32 = 1000;
+
+/// The number of values of a sequence which are allocated to a node at
+/// once, when a sequence is defined without a BATCH clause
+pub const SEQUENCE_BATCH_SIZE: u32 = 100;
+
+/// The number of records which are fetched at once when validating the
+/// records of a table against the definition of an ALTER statement
+pub const ALTER_BATCH_SIZE: u32 = 1000;
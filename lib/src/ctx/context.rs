@@ -3,9 +3,10 @@ use crate::ctx::reason::Reason;
 use crate::dbs::capabilities::FuncTarget;
 #[cfg(feature = "http")]
 use crate::dbs::capabilities::NetTarget;
-use crate::dbs::{Capabilities, Notification};
+use crate::dbs::{Capabilities, MemoryTracker, Notification, PermissionCache, Profiler};
 use crate::err::Error;
 use crate::idx::planner::QueryPlanner;
+use crate::kvs::{RunningQueries, SlowQueries};
 use crate::sql::value::Value;
 use channel::Sender;
 use std::borrow::Cow;
@@ -43,6 +44,16 @@ pub struct Context<'a> {
 	notifications: Option<Sender<Notification>>,
 	// An optional query planner
 	query_planner: Option<&'a QueryPlanner<'a>>,
+	// An optional cache of the computed permissions
+	permissions: Option<&'a PermissionCache>,
+	// An optional profiler of the current statement
+	profiler: Option<&'a Profiler>,
+	// An optional registry of the running statements
+	queries: Option<&'a RunningQueries>,
+	// An optional log of the slow statements
+	slow_queries: Option<&'a SlowQueries>,
+	// An optional tracker of the memory held by the current statement
+	memory: Option<Arc<MemoryTracker>>,
 	// Capabilities
 	capabilities: Arc<Capabilities>,
 }
@@ -74,6 +85,11 @@ impl<'a> Context<'a> {
 			cancelled: Arc::new(AtomicBool::new(false)),
 			notifications: None,
 			query_planner: None,
+			permissions: None,
+			profiler: None,
+			queries: None,
+			slow_queries: None,
+			memory: None,
 			capabilities: Arc::new(Capabilities::default()),
 		}
 	}
@@ -87,10 +103,35 @@ impl<'a> Context<'a> {
 			cancelled: Arc::new(AtomicBool::new(false)),
 			notifications: parent.notifications.clone(),
 			query_planner: parent.query_planner,
+			permissions: parent.permissions,
+			profiler: parent.profiler,
+			queries: parent.queries,
+			slow_queries: parent.slow_queries,
+			memory: parent.memory.clone(),
 			capabilities: parent.capabilities.clone(),
 		}
 	}
 
+	/// Create a context without a parent, which shares the deadline, the
+	/// cancellation, and the capabilities of this context, but not of its
+	/// parents. This can be moved onto a task which is spawned on the runtime.
+	pub(crate) fn detach(&self) -> Context<'static> {
+		Context {
+			values: HashMap::default(),
+			parent: None,
+			deadline: self.deadline,
+			cancelled: self.cancelled.clone(),
+			notifications: self.notifications.clone(),
+			query_planner: None,
+			permissions: None,
+			profiler: None,
+			queries: None,
+			slow_queries: None,
+			memory: self.memory.clone(),
+			capabilities: self.capabilities.clone(),
+		}
+	}
+
 	/// Add a value to the context. It overwrites any previously set values
 	/// with the same key.
 	pub fn add_value<K, V>(&mut self, key: K, value: V)
@@ -134,6 +175,31 @@ impl<'a> Context<'a> {
 		self.query_planner = Some(qp);
 	}
 
+	/// Set the cache of the permissions computed by the current statement
+	pub(crate) fn set_permissions(&mut self, pc: &'a PermissionCache) {
+		self.permissions = Some(pc);
+	}
+
+	/// Set the profiler of the current statement, if it is profiled
+	pub(crate) fn set_profiler(&mut self, pr: Option<&'a Profiler>) {
+		self.profiler = pr;
+	}
+
+	/// Set the registry of the statements running on the datastore
+	pub(crate) fn set_queries(&mut self, qs: &'a RunningQueries) {
+		self.queries = Some(qs);
+	}
+
+	/// Set the log of the slow statements on the datastore
+	pub(crate) fn set_slow_queries(&mut self, sq: &'a SlowQueries) {
+		self.slow_queries = Some(sq);
+	}
+
+	/// Set the tracker of the memory held by the current statement
+	pub(crate) fn set_memory(&mut self, mt: Arc<MemoryTracker>) {
+		self.memory = Some(mt);
+	}
+
 	/// Get the timeout for this operation, if any. This is useful for
 	/// checking if a long job should be started or not.
 	pub fn timeout(&self) -> Option<Duration> {
@@ -148,6 +214,31 @@ impl<'a> Context<'a> {
 		self.query_planner
 	}
 
+	/// Get the cache of the permissions computed by the current statement
+	pub(crate) fn get_permissions(&self) -> Option<&PermissionCache> {
+		self.permissions
+	}
+
+	/// Get the profiler of the current statement, if it is profiled
+	pub(crate) fn get_profiler(&self) -> Option<&Profiler> {
+		self.profiler
+	}
+
+	/// Get the registry of the statements running on the datastore
+	pub(crate) fn get_queries(&self) -> Option<&RunningQueries> {
+		self.queries
+	}
+
+	/// Get the log of the slow statements on the datastore
+	pub(crate) fn get_slow_queries(&self) -> Option<&SlowQueries> {
+		self.slow_queries
+	}
+
+	/// Get the tracker of the memory held by the current statement
+	pub(crate) fn get_memory(&self) -> Option<&Arc<MemoryTracker>> {
+		self.memory.as_ref()
+	}
+
 	/// Check if the context is done. If it returns `None` the operation may
 	/// proceed, otherwise the operation should be stopped.
 	pub fn done(&self) -> Option<Reason> {
@@ -176,6 +267,11 @@ impl<'a> Context<'a> {
 		matches!(self.done(), Some(Reason::Timedout))
 	}
 
+	/// Check if the context is not ok to continue, because it was cancelled.
+	pub fn is_cancelled(&self) -> bool {
+		matches!(self.done(), Some(Reason::Canceled))
+	}
+
 	/// Get a value from the context. If no value is stored under the
 	/// provided key, then this will return None.
 	pub fn value(&self, key: &str) -> Option<&Value> {
@@ -0,0 +1,396 @@
+use crate::dbs::memory::{estimate, estimate_all, Reservation};
+use crate::err::Error;
+use crate::sql::array::Array;
+use crate::sql::group::Groups;
+use crate::sql::value::Value;
+use std::collections::HashMap;
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs::{self, File};
+#[cfg(not(target_arch = "wasm32"))]
+use std::hash::{Hash, Hasher};
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::mem;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+
+/// The number of partitions which groups are spilled into
+#[cfg(not(target_arch = "wasm32"))]
+const PARTITIONS: usize = 16;
+
+/// The number of times the groups of a partition are spilled into
+/// further partitions, when they do not fit within the memory budget
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_DEPTH: usize = 4;
+
+/// Collects the records of a statement with a GROUP BY clause into their
+/// groups, as the records are processed. The groups are held in memory up
+/// to the memory budget of the aggregation, or to the memory limit of the
+/// statement, whichever is reached first, after which all groups are spilled
+/// to temporary files, partitioned on the hash of the group, and merged one
+/// partition at a time, once all of the records have been processed. A
+/// partition whose groups do not fit within the budget is partitioned again.
+pub(crate) struct HashAggregate {
+	/// The GROUP BY clause of the statement
+	group: Groups,
+	/// The approximate number of bytes which can be held in memory
+	budget: usize,
+	/// The approximate number of bytes currently held in memory
+	size: usize,
+	/// The memory held by the groups, as accounted for by the statement
+	memory: Reservation,
+	/// The number of records which have been collected
+	count: usize,
+	/// The groups which are currently held in memory
+	groups: HashMap<Array, Array>,
+	/// The partitions which groups have been spilled to
+	#[cfg(not(target_arch = "wasm32"))]
+	spill: Option<Spill>,
+	/// Whether the groups held in memory have been output
+	done: bool,
+}
+
+impl HashAggregate {
+	/// Create a new aggregation for a GROUP BY clause
+	pub(crate) fn new(group: Groups, budget: usize, memory: Reservation) -> Self {
+		Self {
+			group,
+			budget,
+			size: 0,
+			memory,
+			count: 0,
+			groups: HashMap::new(),
+			#[cfg(not(target_arch = "wasm32"))]
+			spill: None,
+			done: false,
+		}
+	}
+
+	/// The number of records which have been collected
+	pub(crate) fn len(&self) -> usize {
+		self.count
+	}
+
+	/// Whether any groups have been spilled out of memory
+	#[cfg(test)]
+	pub(crate) fn is_spilled(&self) -> bool {
+		#[cfg(not(target_arch = "wasm32"))]
+		{
+			self.spill.is_some()
+		}
+		#[cfg(target_arch = "wasm32")]
+		{
+			false
+		}
+	}
+
+	/// Add a record to its group
+	pub(crate) async fn push(&mut self, obj: Value) -> Result<(), Error> {
+		// Compute the group of this record
+		let key: Array = self.group.iter().map(|g| obj.pick(g)).collect::<Vec<_>>().into();
+		// Track the memory held by this record
+		self.count += 1;
+		let mut bytes = estimate(&obj);
+		// Add the record to its group
+		match self.groups.get_mut(&key) {
+			Some(v) => v.push(obj),
+			None => {
+				bytes += estimate_all(&key);
+				self.groups.insert(key, Array::from(obj));
+			}
+		}
+		self.size += bytes;
+		let res = self.memory.alloc(bytes);
+		// Spill the groups if over the memory budget, or the memory limit
+		#[cfg(not(target_arch = "wasm32"))]
+		if self.size > self.budget || res.is_err() {
+			return self.spill().await;
+		}
+		res
+	}
+
+	/// Fetch the next partition of groups, once all records have been
+	/// collected. The groups of a partition are not output in any order.
+	pub(crate) async fn next_partition(&mut self) -> Result<Option<HashMap<Array, Array>>, Error> {
+		#[cfg(not(target_arch = "wasm32"))]
+		if self.spill.is_some() {
+			// Spill any groups which are still in memory
+			if !self.groups.is_empty() {
+				self.spill().await?;
+			}
+			// Merge the groups of the next partition
+			let budget = self.budget;
+			return match self.spill.take() {
+				Some(mut s) => {
+					let (s, res) = blocking(move || {
+						let res = s.next(budget);
+						Ok((s, res))
+					})
+					.await?;
+					self.spill = Some(s);
+					res
+				}
+				None => Ok(None),
+			};
+		}
+		// Output the groups held in memory
+		match mem::replace(&mut self.done, true) {
+			false => Ok(Some(mem::take(&mut self.groups))),
+			true => Ok(None),
+		}
+	}
+
+	/// Write all of the groups held in memory to their partitions
+	#[cfg(not(target_arch = "wasm32"))]
+	async fn spill(&mut self) -> Result<(), Error> {
+		let groups = mem::take(&mut self.groups);
+		let spill = self.spill.take();
+		let spill = blocking(move || {
+			let mut s = match spill {
+				Some(s) => s,
+				None => Spill::new(std::env::temp_dir(), 0)?,
+			};
+			for (key, vals) in groups {
+				s.write(key, vals)?;
+			}
+			Ok(s)
+		})
+		.await?;
+		self.spill = Some(spill);
+		self.size = 0;
+		self.memory.clear();
+		Ok(())
+	}
+}
+
+/// Runs the file operations of the spilled groups on a thread where blocking
+/// is acceptable, so that they do not block the tasks of the async executor
+#[cfg(not(target_arch = "wasm32"))]
+async fn blocking<T, F>(f: F) -> Result<T, Error>
+where
+	F: FnOnce() -> Result<T, Error> + Send + 'static,
+	T: Send + 'static,
+{
+	tokio::task::spawn_blocking(f).await.map_err(|e| Error::Internal(e.to_string()))?
+}
+
+/// The temporary files which groups are spilled to
+#[cfg(not(target_arch = "wasm32"))]
+struct Spill {
+	/// The temporary directory holding the partitions
+	dir: PathBuf,
+	/// The number of times the groups have been partitioned
+	depth: usize,
+	/// The partitions which have not yet been merged
+	files: Vec<Option<BufWriter<File>>>,
+	/// The next partition to merge
+	next: usize,
+	/// The partitions of a partition which did not fit in memory
+	inner: Option<Box<Spill>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Spill {
+	fn new(parent: PathBuf, depth: usize) -> Result<Self, Error> {
+		let dir = parent.join(format!("surrealdb-group-{}", uuid::Uuid::new_v4()));
+		fs::create_dir_all(&dir)?;
+		let files = (0..PARTITIONS)
+			.map(|i| File::create(dir.join(i.to_string())).map(|f| Some(BufWriter::new(f))))
+			.collect::<Result<_, _>>()?;
+		Ok(Self {
+			dir,
+			depth,
+			files,
+			next: 0,
+			inner: None,
+		})
+	}
+
+	/// Append the records of a group to its partition
+	fn write(&mut self, key: Array, vals: Array) -> Result<(), Error> {
+		// Each level of partitioning hashes the groups differently
+		let mut hasher = std::collections::hash_map::DefaultHasher::new();
+		self.depth.hash(&mut hasher);
+		key.hash(&mut hasher);
+		let i = hasher.finish() as usize % PARTITIONS;
+		if let Some(f) = self.files[i].as_mut() {
+			let buf: Vec<u8> = (&Value::from(vec![Value::from(key), Value::from(vals)])).into();
+			f.write_all(&(buf.len() as u32).to_le_bytes())?;
+			f.write_all(&buf)?;
+		}
+		Ok(())
+	}
+
+	/// Read and merge the groups of the next partition. A partition whose groups do
+	/// not fit within the budget is spilled into partitions of its own, which are then
+	/// merged one at a time. The records of a single group are always held in memory.
+	fn next(&mut self, budget: usize) -> Result<Option<HashMap<Array, Array>>, Error> {
+		loop {
+			// Merge the partitions of a partition which did not fit in memory
+			if let Some(s) = self.inner.as_mut() {
+				match s.next(budget)? {
+					Some(v) => return Ok(Some(v)),
+					None => self.inner = None,
+				}
+			}
+			let i = self.next;
+			if i == PARTITIONS {
+				return Ok(None);
+			}
+			self.next += 1;
+			// Finish writing to the partition
+			if let Some(mut f) = self.files[i].take() {
+				f.flush()?;
+			}
+			// Read back each spilled group
+			let path = self.dir.join(i.to_string());
+			let mut file = BufReader::new(File::open(&path)?);
+			let mut groups: HashMap<Array, Array> = HashMap::new();
+			let mut size = 0;
+			while let Some((key, vals)) = Self::read(&mut file)? {
+				size += estimate_all(&vals);
+				// Merge the records with the other spills of this group
+				match groups.get_mut(&key) {
+					Some(v) => v.0.extend(vals.0),
+					None => {
+						size += estimate_all(&key);
+						groups.insert(key, vals);
+					}
+				}
+				// Partition the groups again if they do not fit in memory
+				if size > budget && groups.len() > 1 && self.depth < MAX_DEPTH {
+					let mut inner = Spill::new(self.dir.clone(), self.depth + 1)?;
+					for (key, vals) in groups.drain() {
+						inner.write(key, vals)?;
+					}
+					while let Some((key, vals)) = Self::read(&mut file)? {
+						inner.write(key, vals)?;
+					}
+					self.inner = Some(Box::new(inner));
+					break;
+				}
+			}
+			// Remove the merged partition
+			fs::remove_file(&path)?;
+			if self.inner.is_none() {
+				return Ok(Some(groups));
+			}
+		}
+	}
+
+	/// Read the next spilled group of a partition
+	fn read(file: &mut BufReader<File>) -> Result<Option<(Array, Array)>, Error> {
+		loop {
+			let mut len = [0u8; 4];
+			match file.read_exact(&mut len) {
+				Ok(_) => (),
+				Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+				Err(e) => return Err(e.into()),
+			}
+			let mut buf = vec![0u8; u32::from_le_bytes(len) as usize];
+			file.read_exact(&mut buf)?;
+			if let Value::Array(mut v) = Value::from(&buf) {
+				if let (Some(Value::Array(vals)), Some(Value::Array(key))) = (v.pop(), v.pop()) {
+					return Ok(Some((key, vals)));
+				}
+			}
+		}
+	}
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for Spill {
+	fn drop(&mut self) {
+		let dir = mem::take(&mut self.dir);
+		// Remove the partitions without blocking the async executor, if running on one
+		match tokio::runtime::Handle::try_current() {
+			Ok(rt) => {
+				rt.spawn_blocking(move || fs::remove_dir_all(dir));
+			}
+			Err(_) => {
+				let _ = fs::remove_dir_all(dir);
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::dbs::MemoryTracker;
+	use crate::sql::idiom::Idiom;
+	use crate::sql::value::value;
+	use crate::sql::Group;
+	use std::sync::Arc;
+
+	async fn aggregate(budget: usize) -> HashAggregate {
+		aggregate_with(budget, Reservation::default()).await
+	}
+
+	async fn aggregate_with(budget: usize, memory: Reservation) -> HashAggregate {
+		let group = Groups(vec![Group(Idiom::from("age".to_owned()))]);
+		let mut agg = HashAggregate::new(group, budget, memory);
+		for i in 0..100 {
+			let v = value(&format!("{{ id: {i}, age: {} }}", i % 7)).unwrap().1;
+			agg.push(v).await.unwrap();
+		}
+		agg
+	}
+
+	async fn collect(mut agg: HashAggregate) -> Vec<(Array, usize)> {
+		let mut out = Vec::new();
+		while let Some(p) = agg.next_partition().await.unwrap() {
+			out.extend(p.into_iter().map(|(k, v)| (k, v.len())));
+		}
+		out.sort();
+		out
+	}
+
+	#[tokio::test]
+	async fn groups_in_memory() {
+		let agg = aggregate(usize::MAX).await;
+		assert!(!agg.is_spilled());
+		assert_eq!(agg.len(), 100);
+		let out = collect(agg).await;
+		assert_eq!(out.len(), 7);
+		assert_eq!(out[0], (Array::from(vec![Value::from(0)]), 15));
+	}
+
+	#[tokio::test]
+	async fn groups_spilled_to_disk() {
+		let agg = aggregate(1024).await;
+		assert!(agg.is_spilled());
+		assert_eq!(collect(agg).await, collect(aggregate(usize::MAX).await).await);
+	}
+
+	#[tokio::test]
+	async fn groups_spilled_at_memory_limit() {
+		let mem = Arc::new(MemoryTracker::new(Some(1024)));
+		let agg = aggregate_with(usize::MAX, Reservation::new(Some(&mem))).await;
+		assert!(agg.is_spilled());
+		assert_eq!(collect(agg).await, collect(aggregate(usize::MAX).await).await);
+	}
+
+	#[tokio::test]
+	async fn partitions_spilled_again() {
+		let group = Groups(vec![Group(Idiom::from("age".to_owned()))]);
+		let mut agg = HashAggregate::new(group.clone(), 64, Reservation::default());
+		let mut all = HashAggregate::new(group, usize::MAX, Reservation::default());
+		for i in 0..200 {
+			let v = value(&format!("{{ id: {i}, age: {} }}", i % 50)).unwrap().1;
+			agg.push(v.clone()).await.unwrap();
+			all.push(v).await.unwrap();
+		}
+		// The partitions which do not fit within the budget are partitioned again
+		let mut parts = 0;
+		let mut out = Vec::new();
+		while let Some(p) = agg.next_partition().await.unwrap() {
+			parts += 1;
+			out.extend(p.into_iter().map(|(k, v)| (k, v.len())));
+		}
+		assert!(parts > PARTITIONS);
+		out.sort();
+		assert_eq!(out, collect(all).await);
+	}
+}
@@ -2,6 +2,7 @@ use std::hash::Hash;
 use std::net::IpAddr;
 use std::{collections::HashSet, sync::Arc};
 
+use crate::sql::ParseLimits;
 use ipnet::IpNet;
 use url::Url;
 
@@ -221,6 +222,8 @@ pub struct Capabilities {
 	deny_funcs: Arc<Targets<FuncTarget>>,
 	allow_net: Arc<Targets<NetTarget>>,
 	deny_net: Arc<Targets<NetTarget>>,
+
+	parse_limits: ParseLimits,
 }
 
 impl std::fmt::Display for Capabilities {
@@ -243,6 +246,8 @@ impl Default for Capabilities {
 			deny_funcs: Arc::new(Targets::None),
 			allow_net: Arc::new(Targets::None),
 			deny_net: Arc::new(Targets::None),
+
+			parse_limits: ParseLimits::default(),
 		}
 	}
 }
@@ -257,6 +262,8 @@ impl Capabilities {
 			deny_funcs: Arc::new(Targets::None),
 			allow_net: Arc::new(Targets::All),
 			deny_net: Arc::new(Targets::None),
+
+			parse_limits: ParseLimits::default(),
 		}
 	}
 
@@ -290,6 +297,11 @@ impl Capabilities {
 		self
 	}
 
+	pub fn with_parse_limits(mut self, parse_limits: ParseLimits) -> Self {
+		self.parse_limits = parse_limits;
+		self
+	}
+
 	pub fn allows_scripting(&self) -> bool {
 		self.scripting
 	}
@@ -305,6 +317,10 @@ impl Capabilities {
 	pub fn allows_network_target(&self, target: &NetTarget) -> bool {
 		self.allow_net.matches(target) && !self.deny_net.matches(target)
 	}
+
+	pub fn parse_limits(&self) -> &ParseLimits {
+		&self.parse_limits
+	}
 }
 
 #[cfg(test)]
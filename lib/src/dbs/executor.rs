@@ -1,22 +1,32 @@
 use crate::ctx::Context;
+use crate::dbs::memory::estimate;
 use crate::dbs::response::Response;
+use crate::dbs::seed::Generator;
+use crate::dbs::MemoryTracker;
 use crate::dbs::Notification;
 use crate::dbs::Options;
 use crate::dbs::QueryType;
+use crate::dbs::SharedOptions;
 use crate::dbs::Transaction;
 use crate::err::Error;
 use crate::iam::Action;
 use crate::iam::ResourceKind;
+use crate::key::thing;
 use crate::kvs::Datastore;
 use crate::sql::paths::DB;
 use crate::sql::paths::NS;
 use crate::sql::query::Query;
 use crate::sql::statement::Statement;
 use crate::sql::value::Value;
+use crate::sql::Array;
 use crate::sql::Base;
+use crate::sql::Datetime;
+use crate::sql::Thing;
+use crate::sql::Values;
 use channel::Receiver;
 use futures::lock::Mutex;
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::instrument;
 use trice::Instant;
 
@@ -24,14 +34,23 @@ pub(crate) struct Executor<'a> {
 	err: bool,
 	kvs: &'a Datastore,
 	txn: Option<Transaction>,
+	op: SharedOptions,
+	/// The savepoints in the transaction, with the number of notifications sent,
+	/// and the number of session parameters set, before each
+	savepoints: Vec<(String, usize, usize)>,
+	/// The session parameters set in the transaction, which are kept once it commits
+	params: Vec<(String, Value, Option<Duration>)>,
 }
 
 impl<'a> Executor<'a> {
-	pub fn new(kvs: &'a Datastore) -> Executor<'a> {
+	pub fn new(kvs: &'a Datastore, op: SharedOptions) -> Executor<'a> {
 		Executor {
 			kvs,
 			txn: None,
 			err: false,
+			op,
+			savepoints: vec![],
+			params: vec![],
 		}
 	}
 
@@ -45,6 +64,8 @@ impl<'a> Executor<'a> {
 	///   - couldn't create transaction (sets err flag)
 	///   - a transaction has already begun
 	async fn begin(&mut self, write: bool) -> bool {
+		// A read-only datastore only supports read transactions
+		let write = write && !self.kvs.is_read_only();
 		match self.txn.as_ref() {
 			Some(_) => false,
 			None => match self.kvs.transaction(write, false).await {
@@ -68,6 +89,8 @@ impl<'a> Executor<'a> {
 	/// otherwise returns `Ok`.
 	async fn commit(&mut self, local: bool) -> Result<(), Error> {
 		if local {
+			// Forget the savepoints of the transaction
+			self.savepoints.clear();
 			// Extract the transaction
 			if let Some(txn) = self.txn.take() {
 				let mut txn = txn.lock().await;
@@ -75,6 +98,9 @@ impl<'a> Executor<'a> {
 					// Cancel and ignore any error because the error flag was
 					// already set
 					let _ = txn.cancel().await;
+				} else if self.kvs.is_read_only() {
+					// Nothing was written to a read-only datastore
+					txn.cancel().await?;
 				} else {
 					let r = match txn.complete_changes(false).await {
 						Ok(_) => txn.commit().await,
@@ -87,16 +113,32 @@ impl<'a> Executor<'a> {
 						// the transaction didn't commit. Detect that and tell
 						// the user.
 						self.err = true;
+						self.params.clear();
 						return Err(e);
 					}
 				}
 			}
+			// Keep the session parameters unless the transaction failed
+			match self.err {
+				true => self.params.clear(),
+				false => self.keep_params(),
+			}
 		}
 		Ok(())
 	}
 
+	/// Keeps the session parameters set in the transaction for the following queries
+	fn keep_params(&mut self) {
+		for (name, val, ttl) in std::mem::take(&mut self.params) {
+			self.op.set_param(&name, val, ttl);
+		}
+	}
+
 	async fn cancel(&mut self, local: bool) {
 		if local {
+			// Forget the savepoints and session parameters of the transaction
+			self.savepoints.clear();
+			self.params.clear();
 			// Extract the transaction
 			if let Some(txn) = self.txn.take() {
 				let mut txn = txn.lock().await;
@@ -110,7 +152,10 @@ impl<'a> Executor<'a> {
 	fn buf_cancel(&self, v: Response) -> Response {
 		Response {
 			time: v.time,
+			memory: v.memory,
 			result: Err(Error::QueryCancelled),
+			statement: v.statement,
+			span: v.span,
 			query_type: QueryType::Other,
 		}
 	}
@@ -119,6 +164,7 @@ impl<'a> Executor<'a> {
 		match &self.err {
 			true => Response {
 				time: v.time,
+				memory: v.memory,
 				result: match v.result {
 					Ok(_) => Err(commit_error
 						.as_ref()
@@ -128,12 +174,31 @@ impl<'a> Executor<'a> {
 						.unwrap_or(Error::QueryNotExecuted)),
 					Err(e) => Err(e),
 				},
+				statement: v.statement,
+				span: v.span,
 				query_type: QueryType::Other,
 			},
 			_ => v,
 		}
 	}
 
+	/// Substitute the nondeterministic functions of a statement with literal values.
+	/// Custom functions are fetched within the current transaction, if there is one.
+	async fn resolve(&self, ctx: &Context<'_>, opt: &Options, stm: &mut Statement) {
+		match &self.txn {
+			Some(txn) => stm.resolve(ctx, opt, txn).await,
+			None => {
+				// Any function which writes to the datastore is left to be processed
+				let Ok(run) = self.kvs.transaction(false, false).await else {
+					return;
+				};
+				let txn = run.enclose();
+				stm.resolve(ctx, opt, &txn).await;
+				let _ = txn.lock().await.cancel().await;
+			}
+		}
+	}
+
 	/// Consume the live query notifications
 	async fn clear(&self, _: &Context<'_>, rcv: Receiver<Notification>) {
 		while rcv.try_recv().is_ok() {
@@ -155,6 +220,21 @@ impl<'a> Executor<'a> {
 		}
 	}
 
+	/// Discard the live query notifications which were sent after a savepoint
+	async fn truncate(&self, opt: &Options, rcv: Receiver<Notification>, len: usize) {
+		let mut keep = Vec::with_capacity(len);
+		while let Ok(v) = rcv.try_recv() {
+			if keep.len() < len {
+				keep.push(v);
+			}
+		}
+		if let Some(chn) = &opt.sender {
+			for v in keep {
+				let _ = chn.send(v).await;
+			}
+		}
+	}
+
 	async fn set_ns(&self, ctx: &mut Context<'_>, opt: &mut Options, ns: &str) {
 		let mut session = ctx.value("session").unwrap_or(&Value::None).clone();
 		session.put(NS.as_ref(), ns.to_owned().into());
@@ -169,6 +249,120 @@ impl<'a> Executor<'a> {
 		opt.set_db(Some(db.into()));
 	}
 
+	/// Check whether a statement should write its index entries in batches.
+	/// This applies to updates and deletes of a whole indexed table which
+	/// are not part of an explicit transaction.
+	async fn batch_size(&self, opt: &Options, stm: &Statement) -> Option<u32> {
+		// Batching is only enabled when configured
+		let size = self.kvs.index_batch_size()?;
+		// Statements within a transaction must be atomic
+		if self.txn.is_some() || opt.valid_for_db().is_err() {
+			return None;
+		}
+		// Only bulk statements on a single table are batched
+		let tb = match stm {
+			Statement::Update(stm) if !stm.only => stm.what.0.as_slice(),
+			Statement::Delete(stm) if !stm.only => stm.what.0.as_slice(),
+			_ => return None,
+		};
+		let tb = match tb {
+			[Value::Table(tb)] => tb,
+			_ => return None,
+		};
+		// Only tables with indexes are batched
+		let mut run = self.kvs.transaction(false, false).await.ok()?;
+		let ixs = run.all_tb_indexes(opt.ns(), opt.db(), tb).await;
+		let _ = run.cancel().await;
+		match ixs {
+			Ok(ixs) if !ixs.is_empty() => Some(size),
+			_ => None,
+		}
+	}
+
+	/// Process a bulk statement in a separate transaction for each batch of records
+	async fn execute_batched(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		stm: &Statement,
+		size: u32,
+		rcv: Receiver<Notification>,
+	) -> Result<Value, Error> {
+		// Get the table to process
+		let tb = match stm {
+			Statement::Update(stm) => stm.what.0[0].to_owned(),
+			Statement::Delete(stm) => stm.what.0[0].to_owned(),
+			_ => unreachable!(),
+		};
+		let tb = tb.as_raw_string();
+		let end = thing::suffix(opt.ns(), opt.db(), &tb);
+		let mut beg = thing::prefix(opt.ns(), opt.db(), &tb);
+		let mut out = Array::new();
+		loop {
+			// Create a new transaction for this batch
+			let txn = Arc::new(Mutex::new(self.kvs.transaction(true, false).await?));
+			// Fetch the next batch of records
+			let res = txn.lock().await.scan(beg.clone()..end.clone(), size).await;
+			let keys = match res {
+				Ok(v) => v.into_iter().map(|(k, _)| k).collect::<Vec<_>>(),
+				Err(e) => {
+					let _ = txn.lock().await.cancel().await;
+					return Err(e);
+				}
+			};
+			// Continue from the last record of the batch
+			if let Some(k) = keys.last() {
+				beg = k.clone();
+				beg.push(0x00);
+			}
+			// Process the records of this batch
+			let ids = keys
+				.iter()
+				.map(|k| {
+					let key: thing::Thing = k.into();
+					Value::from(Thing::from((key.tb, key.id)))
+				})
+				.collect::<Vec<_>>();
+			let mut stm = stm.clone();
+			match &mut stm {
+				Statement::Update(stm) => stm.what = Values(ids),
+				Statement::Delete(stm) => stm.what = Values(ids),
+				_ => unreachable!(),
+			}
+			let res = match stm.compute(ctx, opt, &txn, None).await {
+				Ok(_) if ctx.is_timedout() => Err(Error::QueryTimedout),
+				Ok(_) if ctx.is_cancelled() => Err(Error::QueryKilled),
+				Ok(v) => Ok(v),
+				Err(e) => Err(e),
+			};
+			// Commit the batch, or cancel it on failure
+			match res {
+				Ok(v) => {
+					if let Err(e) = txn.lock().await.commit().await {
+						self.clear(ctx, rcv.clone()).await;
+						return Err(Error::QueryNotExecutedDetail {
+							message: e.to_string(),
+						});
+					}
+					self.flush(ctx, rcv.clone()).await;
+					if let Value::Array(mut v) = v {
+						out.append(&mut v.0);
+					}
+				}
+				Err(e) => {
+					let _ = txn.lock().await.cancel().await;
+					self.clear(ctx, rcv.clone()).await;
+					return Err(e);
+				}
+			}
+			// Stop once all records are processed
+			if keys.len() < size as usize {
+				break;
+			}
+		}
+		Ok(out.into())
+	}
+
 	#[instrument(level = "debug", name = "executor", skip_all)]
 	pub async fn execute(
 		&mut self,
@@ -184,8 +378,12 @@ impl<'a> Executor<'a> {
 		let mut buf: Vec<Response> = vec![];
 		// Initialise array of responses
 		let mut out: Vec<Response> = vec![];
+		// Check how NONE values should be output
+		let mut nones = self.op.get().none_output.unwrap_or_default();
+		// The $now parameter is only pinned if it is not set by the query
+		let mut pin_now = ctx.value("now").is_none();
 		// Process all statements in query
-		for stm in qry.into_iter() {
+		for (index, mut stm) in qry.into_iter().enumerate() {
 			// Log the statement
 			debug!("Executing: {}", stm);
 			// Reset errors
@@ -194,12 +392,31 @@ impl<'a> Executor<'a> {
 			}
 			// Get the statement start time
 			let now = Instant::now();
+			// Track the memory held by the statement
+			let memory = Arc::new(MemoryTracker::new(self.kvs.statement_memory_limit()));
+			// Pin the $now parameter to the statement start time
+			if pin_now {
+				ctx.add_value("now", Value::from(Datetime::default()));
+			}
+			// Seed the random values generated by the statement
+			let mut rng = Generator::new(opt.seed, index);
+			// Substitute nondeterministic functions with literal values
+			if opt.deterministic {
+				rng.wrap(self.resolve(&ctx, &opt, &mut stm)).await;
+			}
 			// Check if this is a LIVE statement
 			let is_stm_live = matches!(stm, Statement::Live(_));
-			// Check if this is a KILL statement
-			let is_stm_kill = matches!(stm, Statement::Kill(_));
+			// Check if this is a KILL statement for a live query
+			let is_stm_kill = matches!(stm, Statement::Kill(ref v) if !v.query);
 			// Check if this is a RETURN statement
 			let is_stm_output = matches!(stm, Statement::Output(_));
+			// Check if this statement changes the schema
+			let is_stm_schema =
+				matches!(stm, Statement::Define(_) | Statement::Remove(_) | Statement::Alter(_));
+			// Check if this statement writes its index entries in batches
+			let batch = self.batch_size(&opt, &stm).await;
+			// Check if this statement is logged when it is slow
+			let slow = self.kvs.slow_queries().inspect(&opt, &stm);
 			// Process a single statement
 			let res = match stm {
 				// Specify runtime options
@@ -215,6 +432,7 @@ impl<'a> Executor<'a> {
 						"TABLES" => opt.with_tables(stm.what),
 						"IMPORT" => opt.with_import(stm.what),
 						"FORCE" => opt.with_force(stm.what),
+						"DETERMINISTIC" => opt.with_deterministic(stm.what),
 						_ => break,
 					};
 					// Continue
@@ -245,6 +463,53 @@ impl<'a> Executor<'a> {
 					self.txn = None;
 					continue;
 				}
+				// Create a savepoint in a running transaction
+				Statement::Savepoint(stm) => match self.txn.clone() {
+					Some(_) if self.err => Err(Error::QueryNotExecuted),
+					Some(txn) => {
+						txn.lock().await.savepoint(&stm.name);
+						self.savepoints.push((stm.name.to_raw(), recv.len(), self.params.len()));
+						Ok(Value::None)
+					}
+					None => Err(Error::SavepointOutsideTransaction),
+				},
+				// Roll back a running transaction to a savepoint
+				Statement::Rollback(stm) => match self.txn.clone() {
+					Some(txn) => match txn.lock().await.rollback_to_savepoint(&stm.name).await {
+						Ok(_) => {
+							// Discard the notifications of the rolled back changes
+							if let Some(pos) =
+								self.savepoints.iter().rposition(|(v, _, _)| *v == stm.name.0)
+							{
+								self.savepoints.truncate(pos + 1);
+								let (_, len, params) = self.savepoints[pos];
+								self.params.truncate(params);
+								self.truncate(&opt, recv.clone(), len).await;
+							}
+							// The transaction can continue from the savepoint
+							self.err = false;
+							Ok(Value::None)
+						}
+						Err(e) => Err(e),
+					},
+					None => Err(Error::SavepointOutsideTransaction),
+				},
+				// Release a savepoint in a running transaction
+				Statement::Release(stm) => match self.txn.clone() {
+					Some(_) if self.err => Err(Error::QueryNotExecuted),
+					Some(txn) => match txn.lock().await.release_savepoint(&stm.name) {
+						Ok(_) => {
+							if let Some(pos) =
+								self.savepoints.iter().rposition(|(v, _, _)| *v == stm.name.0)
+							{
+								self.savepoints.truncate(pos);
+							}
+							Ok(Value::None)
+						}
+						Err(e) => Err(e),
+					},
+					None => Err(Error::SavepointOutsideTransaction),
+				},
 				// Switch to a different NS or DB
 				Statement::Use(stm) => {
 					if let Some(ref ns) = stm.ns {
@@ -255,6 +520,25 @@ impl<'a> Executor<'a> {
 					}
 					Ok(Value::None)
 				}
+				// Set an execution option on the session
+				Statement::SetOption(stm) => {
+					// Allowed to run?
+					match opt
+						.is_allowed(Action::Edit, ResourceKind::Option, &Base::Db)
+						.and_then(|_| self.op.set(&stm.name, stm.what))
+					{
+						Ok(_) => {
+							// Apply the option to the following statements
+							let op = self.op.get();
+							nones = op.none_output.unwrap_or_default();
+							opt = op.apply(opt);
+							Ok(Value::None)
+						}
+						Err(e) => Err(e),
+					}
+				}
+				// Reject mutating statements on a read-only datastore
+				_ if self.kvs.is_read_only() && stm.writeable() => Err(Error::DsReadonly),
 				// Process param definition statements
 				Statement::Set(stm) => {
 					// Create a transaction
@@ -266,10 +550,19 @@ impl<'a> Executor<'a> {
 						// The transaction began successfully
 						false => {
 							// Check the statement
-							match stm.compute(&ctx, &opt, &self.txn(), None).await {
+							match rng.wrap(stm.compute(&ctx, &opt, &self.txn(), None)).await {
 								Ok(val) => {
 									// Check if writeable
 									let writeable = stm.writeable();
+									// Keep the parameter for the following queries, once committed
+									if stm.session {
+										let ttl = stm.ttl.map(|v| v.0);
+										self.params.push((stm.name.clone(), val.clone(), ttl));
+									}
+									// A $now parameter set by the query is not pinned
+									if stm.name == "now" {
+										pin_now = false;
+									}
 									// Set the parameter
 									ctx.add_value(stm.name, val);
 									// Finalise transaction, returning nothing unless it couldn't commit
@@ -289,6 +582,10 @@ impl<'a> Executor<'a> {
 											}
 										}
 									} else {
+										// Nothing was written, so the parameters of a local transaction are kept
+										if loc {
+											self.keep_params();
+										}
 										self.cancel(loc).await;
 										self.clear(&ctx, recv.clone()).await;
 										Ok(Value::None)
@@ -304,6 +601,19 @@ impl<'a> Executor<'a> {
 						}
 					}
 				}
+				// Process bulk statements in batches
+				_ if batch.is_some() && !self.err => {
+					let mut ctx = Context::new(&ctx);
+					ctx.set_memory(memory.clone());
+					if let Some(timeout) = stm.timeout().or(opt.timeout) {
+						ctx.add_timeout(timeout);
+					}
+					// Register the statement so that it can be cancelled
+					let _running =
+						self.kvs.queries().register(&opt, stm.to_string(), ctx.add_cancel());
+					let size = batch.unwrap_or_default();
+					rng.wrap(self.execute_batched(&ctx, &opt, &stm, size, recv.clone())).await
+				}
 				// Process all other normal statements
 				_ => match self.err {
 					// This transaction has failed
@@ -319,14 +629,23 @@ impl<'a> Executor<'a> {
 							// The transaction began successfully
 							false => {
 								let mut ctx = Context::new(&ctx);
+								ctx.set_memory(memory.clone());
+								// Register the statement so that it can be cancelled
+								let _running = self.kvs.queries().register(
+									&opt,
+									stm.to_string(),
+									ctx.add_cancel(),
+								);
 								// Process the statement
-								let res = match stm.timeout() {
+								let res = match stm.timeout().or(opt.timeout) {
 									// There is a timeout clause
 									Some(timeout) => {
 										// Set statement timeout
 										ctx.add_timeout(timeout);
 										// Process the statement
-										let res = stm.compute(&ctx, &opt, &self.txn(), None).await;
+										let res = rng
+											.wrap(stm.compute(&ctx, &opt, &self.txn(), None))
+											.await;
 										// Catch statement timeout
 										match ctx.is_timedout() {
 											true => Err(Error::QueryTimedout),
@@ -334,13 +653,20 @@ impl<'a> Executor<'a> {
 										}
 									}
 									// There is no timeout clause
-									None => stm.compute(&ctx, &opt, &self.txn(), None).await,
+									None => {
+										rng.wrap(stm.compute(&ctx, &opt, &self.txn(), None)).await
+									}
 								};
 								// Catch global timeout
 								let res = match ctx.is_timedout() {
 									true => Err(Error::QueryTimedout),
 									false => res,
 								};
+								// Catch statement cancellation
+								let res = match ctx.is_cancelled() {
+									true => Err(Error::QueryKilled),
+									false => res,
+								};
 								// Finalise transaction and return the result.
 								if res.is_ok() && stm.writeable() {
 									if let Err(e) = self.commit(loc).await {
@@ -368,16 +694,37 @@ impl<'a> Executor<'a> {
 					}
 				},
 			};
+			// Log the statement if it was slow
+			if let Some(q) = slow {
+				self.kvs.slow_queries().record(q, now.elapsed());
+			}
+			// Invalidate prepared statements after a schema change
+			if is_stm_schema && res.is_ok() {
+				self.kvs.schema_changed();
+			}
+			// Account for the memory held by the output
+			if let Ok(v) = &res {
+				memory.observe(estimate(v));
+			}
+			// Convert any NONE values in the output
+			let res = res.map(|mut v| {
+				v.output_nones(nones);
+				v
+			});
 			// Produce the response
 			let res = Response {
 				// Get the statement end time
 				time: now.elapsed(),
+				// Get the statement peak memory
+				memory: memory.peak(),
 				// TODO: Replace with `inspect_err` once stable.
 				result: res.map_err(|e| {
 					// Mark the error.
 					self.err = true;
 					e
 				}),
+				statement: index,
+				span: None,
 				query_type: match (is_stm_live, is_stm_kill) {
 					(true, _) => QueryType::Live,
 					(_, true) => QueryType::Kill,
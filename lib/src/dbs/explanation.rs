@@ -1,36 +1,64 @@
 use crate::ctx::Context;
-use crate::dbs::Iterable;
-use crate::sql::{Explain, Object, Value};
+use crate::dbs::{Iterable, Options, Transaction};
+use crate::err::Error;
+use crate::sql::{Duration, Explain, Object, Value};
 use std::collections::HashMap;
+use std::time;
 
 #[derive(Default)]
 pub(super) struct Explanation(Vec<ExplainItem>);
 
 impl Explanation {
-	pub(super) fn new(
+	pub(super) async fn new(
 		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
 		e: Option<&Explain>,
 		iterables: &Vec<Iterable>,
-	) -> (bool, Option<Self>) {
+	) -> Result<(bool, Option<Self>), Error> {
 		match e {
-			None => (true, None),
+			None => Ok((true, None)),
 			Some(e) => {
 				let mut exp = Self::default();
 				for i in iterables {
-					exp.add_iter(i);
+					exp.add_iter(opt, txn, i).await?;
 				}
 				if let Some(qp) = ctx.get_query_planner() {
 					for reason in qp.fallbacks() {
 						exp.add_fallback(reason.to_string());
 					}
 				}
-				(e.0, Some(exp))
+				Ok((e.0, Some(exp)))
 			}
 		}
 	}
 
-	fn add_iter(&mut self, iter: &Iterable) {
-		self.0.push(ExplainItem::new_iter(iter));
+	async fn add_iter(
+		&mut self,
+		opt: &Options,
+		txn: &Transaction,
+		iter: &Iterable,
+	) -> Result<(), Error> {
+		let mut item = ExplainItem::new_iter(iter);
+		if let Iterable::Index(_, _, io) = iter {
+			if let Some(v) = io.estimate(opt, txn).await? {
+				item.details.push(("estimate", v));
+			}
+		}
+		self.0.push(item);
+		Ok(())
+	}
+
+	/// Annotates the iterators, in the order they were explained,
+	/// with the number of records they actually yielded and the time they took
+	pub(super) fn add_actuals(&mut self, actuals: Vec<(usize, time::Duration)>) {
+		for (item, (rows, elapsed)) in self.0.iter_mut().zip(actuals) {
+			let actual = Object::from(HashMap::from([
+				("rows", Value::from(rows)),
+				("elapsed", Value::from(Duration::from(elapsed))),
+			]));
+			item.details.push(("actual", actual.into()));
+		}
 	}
 
 	pub(super) fn add_fetch(&mut self, count: usize) {
@@ -106,10 +134,27 @@ impl ExplainItem {
 				name: "Iterate Index".into(),
 				details: vec![("table", Value::from(t.0.to_owned())), ("plan", io.explain())],
 			},
+			Iterable::IndexEdges(e, _, io) => Self {
+				name: "Iterate Index Edges".into(),
+				details: vec![
+					("from", Value::Thing(e.from.to_owned())),
+					("table", Value::from(e.what[0].0.to_owned())),
+					("plan", io.explain()),
+				],
+			},
+			Iterable::Archive(t) => Self {
+				name: "Iterate Archive".into(),
+				details: vec![("table", Value::from(t.0.to_owned()))],
+			},
 		}
 	}
 }
 
+/// Describes an input value, as it is explained
+pub(super) fn describe(iter: &Iterable) -> Value {
+	ExplainItem::new_iter(iter).into()
+}
+
 impl From<ExplainItem> for Value {
 	fn from(i: ExplainItem) -> Self {
 		let explain = Object::from(HashMap::from([
@@ -0,0 +1,72 @@
+use crate::sql::strand::Strand;
+use crate::sql::value::Value;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// The longest string which is interned, as longer
+/// strings are rarely repeated across many records
+const MAX_LENGTH: usize = 64;
+
+/// The most distinct strings which are interned for a statement,
+/// so that a statement with few repeated strings holds little memory
+const MAX_STRINGS: usize = 4096;
+
+/// Shares the storage of the strings which are repeated across the values of
+/// a statement, such as table names and enum strings, so that each distinct
+/// string is only held in memory once, however many records contain it
+#[derive(Default)]
+pub(crate) struct Interner {
+	/// The strings which have been interned so far
+	strings: HashSet<Arc<String>>,
+}
+
+impl Interner {
+	/// Shares the storage of the strings of a value
+	/// with the equal strings which were interned before
+	pub(crate) fn intern(&mut self, val: &mut Value) {
+		match val {
+			Value::Strand(v) => self.strand(v),
+			Value::Array(v) => v.iter_mut().for_each(|v| self.intern(v)),
+			Value::Object(v) => v.values_mut().for_each(|v| self.intern(v)),
+			_ => {}
+		}
+	}
+
+	fn strand(&mut self, v: &mut Strand) {
+		if v.len() > MAX_LENGTH {
+			return;
+		}
+		match self.strings.get(&v.0) {
+			Some(s) => v.0 = s.clone(),
+			None if self.strings.len() < MAX_STRINGS => {
+				self.strings.insert(v.0.clone());
+			}
+			None => {}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sql::test::Parse;
+
+	#[test]
+	fn repeated_strings_share_storage() {
+		let mut int = Interner::default();
+		let mut a = Value::parse("{ status: 'open', tags: ['open', 'new'] }");
+		let mut b = Value::parse("{ status: 'open' }");
+		int.intern(&mut a);
+		int.intern(&mut b);
+		let strand = |v: &Value| match v {
+			Value::Strand(v) => v.0.clone(),
+			_ => unreachable!(),
+		};
+		let open = strand(&a.pick(&["status".into()]));
+		assert!(Arc::ptr_eq(&open, &strand(&a.pick(&["tags".into(), 0.into()]))));
+		assert!(Arc::ptr_eq(&open, &strand(&b.pick(&["status".into()]))));
+		assert!(!Arc::ptr_eq(&open, &strand(&a.pick(&["tags".into(), 1.into()]))));
+		// The values are unchanged
+		assert_eq!(a, Value::parse("{ status: 'open', tags: ['open', 'new'] }"));
+	}
+}
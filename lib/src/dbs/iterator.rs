@@ -1,9 +1,15 @@
 use crate::ctx::Canceller;
 use crate::ctx::Context;
+use crate::dbs::aggregate::HashAggregate;
 #[cfg(not(target_arch = "wasm32"))]
 use crate::dbs::distinct::AsyncDistinct;
 use crate::dbs::distinct::SyncDistinct;
 use crate::dbs::explanation::Explanation;
+use crate::dbs::intern::Interner;
+use crate::dbs::memory::{estimate, Reservation};
+use crate::dbs::profile::{Profile, Profiler};
+use crate::dbs::window;
+use crate::dbs::PermissionCache;
 use crate::dbs::Statement;
 use crate::dbs::{Options, Transaction};
 use crate::doc::Document;
@@ -12,8 +18,9 @@ use crate::idx::ft::docids::DocId;
 use crate::idx::planner::executor::IteratorRef;
 use crate::idx::planner::plan::IndexOption;
 use crate::sql::array::Array;
+use crate::sql::dir::Dir;
 use crate::sql::edges::Edges;
-use crate::sql::field::Field;
+use crate::sql::field::{Field, Fields};
 use crate::sql::range::Range;
 use crate::sql::table::Table;
 use crate::sql::thing::Thing;
@@ -21,8 +28,9 @@ use crate::sql::value::Value;
 use async_recursion::async_recursion;
 use std::borrow::Cow;
 use std::cmp::Ordering;
-use std::collections::BTreeMap;
 use std::mem;
+use std::time::Duration;
+use trice::Instant;
 
 pub(crate) enum Iterable {
 	Value(Value),
@@ -33,12 +41,16 @@ pub(crate) enum Iterable {
 	Mergeable(Thing, Value),
 	Relatable(Thing, Thing, Thing),
 	Index(Table, IteratorRef, IndexOption),
+	IndexEdges(Edges, IteratorRef, IndexOption),
+	Archive(Table),
 }
 
 pub(crate) struct Processed {
 	pub(crate) ir: Option<IteratorRef>,
 	pub(crate) rid: Option<Thing>,
 	pub(crate) doc_id: Option<DocId>,
+	/// The direction of the edge, when the record is iterated as an edge
+	pub(crate) dir: Option<Dir>,
 	pub(crate) val: Operable,
 }
 
@@ -69,6 +81,18 @@ pub(crate) struct Iterator {
 	results: Vec<Value>,
 	// Iterator input values
 	entries: Vec<Iterable>,
+	// Iterator input values are already in the requested order
+	ordered: bool,
+	// Iterator output results, collected into the groups of a GROUP BY clause
+	groups: Option<HashAggregate>,
+	// The memory held by the output results, which is released when dropped
+	memory: Reservation,
+	// The strings which are shared between the scanned records and the output results
+	interner: Interner,
+	// The records yielded by, and the time taken by, each input value, when analysed
+	actuals: Option<Vec<(usize, Duration)>>,
+	// The records output by, and the time taken by, each stage, when profiled
+	profile: Option<Profile>,
 }
 
 impl Iterator {
@@ -82,6 +106,12 @@ impl Iterator {
 		self.entries.push(val)
 	}
 
+	/// Ingests an iterable which yields the records in the order of the statement
+	pub fn ingest_ordered(&mut self, val: Iterable) {
+		self.ordered = true;
+		self.entries.push(val)
+	}
+
 	/// Prepares a value for processing
 	pub async fn prepare(
 		&mut self,
@@ -96,7 +126,7 @@ impl Iterator {
 			Value::Table(v) => match stm.data() {
 				// There is a data clause so fetch a record id
 				Some(data) => match stm {
-					Statement::Create(_) => {
+					Statement::Create(_) | Statement::Upsert(_) => {
 						let id = match data.rid(ctx, opt, txn).await? {
 							// Generate a new id from the id field
 							Some(id) => id.generate(&v, false)?,
@@ -112,7 +142,7 @@ impl Iterator {
 				},
 				// There is no data clause so create a record id
 				None => match stm {
-					Statement::Create(_) => {
+					Statement::Create(_) | Statement::Upsert(_) => {
 						// Generate a new random table id
 						self.ingest(Iterable::Thing(v.generate()))
 					}
@@ -159,8 +189,8 @@ impl Iterator {
 				}
 			}
 			Value::Range(v) => {
-				// Check if this is a create statement
-				if let Statement::Create(_) = stm {
+				// Check if this is a create or upsert statement
+				if let Statement::Create(_) | Statement::Upsert(_) = stm {
 					return Err(Error::InvalidStatementTarget {
 						value: v.to_string(),
 					});
@@ -178,8 +208,8 @@ impl Iterator {
 				self.ingest(Iterable::Range(*v));
 			}
 			Value::Edges(v) => {
-				// Check if this is a create statement
-				if let Statement::Create(_) = stm {
+				// Check if this is a create or upsert statement
+				if let Statement::Create(_) | Statement::Upsert(_) = stm {
 					return Err(Error::InvalidStatementTarget {
 						value: v.to_string(),
 					});
@@ -270,40 +300,91 @@ impl Iterator {
 	) -> Result<Value, Error> {
 		// Log the statement
 		trace!("Iterating: {}", stm);
+		// Reuse the computed permissions across records
+		let permissions = PermissionCache::default();
+		// Measure the WHERE clause if the statement is profiled
+		let profiler = stm.profile().then(Profiler::default);
 		// Enable context override
 		let mut cancel_ctx = Context::new(ctx);
 		self.run = cancel_ctx.add_cancel();
+		cancel_ctx.set_permissions(&permissions);
+		cancel_ctx.set_profiler(profiler.as_ref());
 		// Process the query LIMIT clause
 		self.setup_limit(&cancel_ctx, opt, txn, stm).await?;
 		// Process the query START clause
 		self.setup_start(&cancel_ctx, opt, txn, stm).await?;
 		// Extract the expected behaviour depending on the presence of EXPLAIN with or without FULL
-		let (do_iterate, mut explanation) = Explanation::new(ctx, stm.explain(), &self.entries);
+		let (do_iterate, mut explanation) =
+			Explanation::new(ctx, opt, txn, stm.explain(), &self.entries).await?;
+		// Measure each input value if the statement is analysed or profiled
+		if stm.analyze() || stm.profile() {
+			self.actuals = Some(Vec::with_capacity(self.entries.len()));
+		}
+		// Measure each stage if the statement is profiled
+		if stm.profile() {
+			self.profile = Some(Profile::new(&self.entries));
+		}
 
 		if do_iterate {
+			// Collect the records into their groups as they are processed
+			if let (Some(_), Some(g), None) = (stm.expr(), stm.group(), stm.split()) {
+				let memory = Reservation::new(ctx.get_memory());
+				self.groups = Some(HashAggregate::new(g.clone(), opt.group_limit, memory));
+			}
+			// Account for the memory held by the output results
+			self.memory = Reservation::new(ctx.get_memory());
 			// Process prepared values
 			self.iterate(&cancel_ctx, opt, txn, stm).await?;
 			// Return any document errors
 			if let Some(e) = self.error.take() {
 				return Err(e);
 			}
+			// Report what each input value actually yielded
+			if let Some(a) = self.actuals.take() {
+				if let Some(p) = &mut self.profile {
+					p.add_scan(&a);
+				}
+				if let Some(e) = &mut explanation {
+					e.add_actuals(a);
+				}
+			}
+			// Report what the WHERE clause actually matched
+			if let (Some(p), Some(pr), Some(_)) = (&mut self.profile, &profiler, stm.conds()) {
+				p.add_filter(pr);
+			}
 			// Process any SPLIT clause
+			let (len, now) = (self.yielded(), Instant::now());
 			self.output_split(ctx, opt, txn, stm).await?;
+			self.profiled("split", stm.split().is_some(), len, now);
 			// Process any GROUP clause
+			let (len, now) = (self.yielded(), Instant::now());
 			self.output_group(ctx, opt, txn, stm).await?;
+			self.profiled("group", stm.group().is_some(), len, now);
+			// Process any window functions
+			let (len, now) = (self.yielded(), Instant::now());
+			self.output_window(ctx, opt, txn, stm).await?;
+			self.profiled("window", stm.expr().is_some_and(Fields::has_window), len, now);
 			// Process any ORDER clause
+			let (len, now) = (self.yielded(), Instant::now());
 			self.output_order(ctx, opt, txn, stm).await?;
+			self.profiled("order", stm.order().is_some(), len, now);
 			// Process any START clause
+			let (len, now) = (self.yielded(), Instant::now());
 			self.output_start(ctx, opt, txn, stm).await?;
+			self.profiled("start", self.start.is_some(), len, now);
 			// Process any LIMIT clause
+			let (len, now) = (self.yielded(), Instant::now());
 			self.output_limit(ctx, opt, txn, stm).await?;
+			self.profiled("limit", self.limit.is_some(), len, now);
 
 			if let Some(e) = &mut explanation {
 				e.add_fetch(self.results.len());
 				self.results.clear();
 			} else {
 				// Process any FETCH clause
+				let (len, now) = (self.yielded(), Instant::now());
 				self.output_fetch(ctx, opt, txn, stm).await?;
+				self.profiled("fetch", stm.fetch().is_some(), len, now);
 			}
 		}
 
@@ -398,71 +479,88 @@ impl Iterator {
 	) -> Result<(), Error> {
 		if let Some(fields) = stm.expr() {
 			if let Some(groups) = stm.group() {
-				// Create the new grouped collection
-				let mut grp: BTreeMap<Array, Array> = BTreeMap::new();
-				// Get the query result
-				let res = mem::take(&mut self.results);
-				// Loop over each value
-				for obj in res {
-					// Create a new column set
-					let mut arr = Array::with_capacity(groups.len());
-					// Loop over each group clause
-					for group in groups.iter() {
-						// Get the value at the path
-						let val = obj.pick(group);
-						// Set the value at the path
-						arr.push(val);
-					}
-					// Add to grouped collection
-					match grp.get_mut(&arr) {
-						Some(v) => v.push(obj),
-						None => {
-							grp.insert(arr, Array::from(obj));
+				// Get the records collected into their groups
+				let mut grp = match self.groups.take() {
+					Some(v) => v,
+					// The records were split after being processed
+					None => {
+						let memory = Reservation::new(ctx.get_memory());
+						let mut grp = HashAggregate::new(groups.clone(), opt.group_limit, memory);
+						self.memory.clear();
+						for obj in mem::take(&mut self.results) {
+							grp.push(obj).await?;
 						}
+						grp
 					}
-				}
-				// Loop over each grouped collection
-				for (_, vals) in grp {
-					// Create a new value
-					let mut obj = Value::base();
-					// Save the collected values
-					let vals = Value::from(vals);
-					// Loop over each group clause
-					for field in fields.other() {
-						// Process the field
-						if let Field::Single {
-							expr,
-							alias,
-						} = field
-						{
-							let idiom = alias
-								.as_ref()
-								.map(Cow::Borrowed)
-								.unwrap_or_else(|| Cow::Owned(expr.to_idiom()));
-							match expr {
-								Value::Function(f) if f.is_aggregate() => {
-									let x =
-										vals.all().get(ctx, opt, txn, None, idiom.as_ref()).await?;
-									let x = f.aggregate(x).compute(ctx, opt, txn, None).await?;
-									obj.set(ctx, opt, txn, idiom.as_ref(), x).await?;
-								}
-								_ => {
-									let x = vals.first();
-									let x = if let Some(alias) = alias {
-										let cur = (&x).into();
-										alias.compute(ctx, opt, txn, Some(&cur)).await?
-									} else {
-										let cur = (&x).into();
-										expr.compute(ctx, opt, txn, Some(&cur)).await?
-									};
-									obj.set(ctx, opt, txn, idiom.as_ref(), x).await?;
+				};
+				// Create the new grouped collection
+				let mut out = Vec::new();
+				// Loop over each partition of the groups
+				while let Some(part) = grp.next_partition().await? {
+					// Loop over each grouped collection
+					for (arr, vals) in part {
+						// Create a new value
+						let mut obj = Value::base();
+						// Save the collected values
+						let vals = Value::from(vals);
+						// Loop over each group clause
+						for field in fields.other() {
+							// Process the field
+							if let Field::Single {
+								expr,
+								alias,
+							} = field
+							{
+								let idiom = alias
+									.as_ref()
+									.map(Cow::Borrowed)
+									.unwrap_or_else(|| Cow::Owned(expr.to_idiom()));
+								match expr {
+									Value::Function(f) if f.is_aggregate() => {
+										let x = vals
+											.all()
+											.get(ctx, opt, txn, None, idiom.as_ref())
+											.await?;
+										let x = f.aggregate(x).compute(ctx, opt, txn, None).await?;
+										obj.set(ctx, opt, txn, idiom.as_ref(), x).await?;
+									}
+									_ => {
+										let x = vals.first();
+										let x = if let Some(alias) = alias {
+											let cur = (&x).into();
+											alias.compute(ctx, opt, txn, Some(&cur)).await?
+										} else {
+											let cur = (&x).into();
+											expr.compute(ctx, opt, txn, Some(&cur)).await?
+										};
+										obj.set(ctx, opt, txn, idiom.as_ref(), x).await?;
+									}
 								}
 							}
 						}
+						// Add the object to the grouped collection
+						out.push((arr, obj));
 					}
-					// Add the object to the results
-					self.results.push(obj);
 				}
+				// Output the groups in the order of their values
+				out.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+				self.results = out.into_iter().map(|(_, v)| v).collect();
+			}
+		}
+		Ok(())
+	}
+
+	#[inline]
+	async fn output_window(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+	) -> Result<(), Error> {
+		if let Some(fields) = stm.expr() {
+			if fields.has_window() {
+				window::compute(ctx, opt, txn, fields, &mut self.results).await?;
 			}
 		}
 		Ok(())
@@ -477,6 +575,10 @@ impl Iterator {
 		stm: &Statement<'_>,
 	) -> Result<(), Error> {
 		if let Some(orders) = stm.order() {
+			// The records were iterated in order
+			if self.ordered {
+				return Ok(());
+			}
 			// Sort the full result set
 			self.results.sort_by(|a, b| {
 				// Loop over each order clause
@@ -572,7 +674,9 @@ impl Iterator {
 		for v in mem::take(&mut self.entries) {
 			// Distinct is passed only for iterators that really requires it
 			let dis = SyncDistinct::requires_distinct(ctx, distinct.as_mut(), &v);
+			let (len, now) = (self.yielded(), Instant::now());
 			v.iterate(ctx, opt, txn, stm, self, dis).await?;
+			self.measured(len, now);
 		}
 		// Everything processed ok
 		Ok(())
@@ -589,8 +693,8 @@ impl Iterator {
 	) -> Result<(), Error> {
 		// Prevent deep recursion
 		let opt = &opt.dive(4)?;
-		// Check if iterating in parallel
-		match stm.parallel() {
+		// Check if iterating in parallel, unless each input value is measured
+		match stm.parallel() && self.actuals.is_none() {
 			// Run statements sequentially
 			false => {
 				// If any iterator requires distinct, we new to create a global distinct instance
@@ -599,7 +703,9 @@ impl Iterator {
 				for v in mem::take(&mut self.entries) {
 					// Distinct is passed only for iterators that really requires it
 					let dis = SyncDistinct::requires_distinct(ctx, distinct.as_mut(), &v);
+					let (len, now) = (self.yielded(), Instant::now());
 					v.iterate(ctx, opt, txn, stm, self, dis).await?;
+					self.measured(len, now);
 				}
 				// Everything processed ok
 				Ok(())
@@ -646,7 +752,7 @@ impl Iterator {
 				let aproc = async {
 					// Process all processed values
 					while let Ok(r) = vals.recv().await {
-						self.result(r, stm);
+						self.result(r, stm).await;
 					}
 					// Shutdown the executor
 					let _ = end.send(()).await;
@@ -663,6 +769,32 @@ impl Iterator {
 		}
 	}
 
+	/// The number of results which have been yielded so far
+	fn yielded(&self) -> usize {
+		self.results.len() + self.groups.as_ref().map_or(0, HashAggregate::len)
+	}
+
+	/// Records the results yielded by an input value, and the time it took, when analysed
+	fn measured(&mut self, len: usize, now: Instant) {
+		let yielded = self.yielded();
+		if let Some(a) = &mut self.actuals {
+			a.push((yielded - len, now.elapsed()));
+		}
+	}
+
+	/// Records the results output by a stage, and the time it took, when profiled
+	fn profiled(&mut self, name: &'static str, ran: bool, len: usize, now: Instant) {
+		let yielded = self.yielded();
+		if let (Some(p), true) = (&mut self.profile, ran) {
+			p.add_stage(name, len, yielded, now.elapsed());
+		}
+	}
+
+	/// Outputs the profile of the statement, if it was profiled
+	pub fn profile(&mut self, rows: usize) -> Option<Value> {
+		self.profile.take().map(|p| p.output(rows))
+	}
+
 	/// Process a new record Thing and Value
 	pub async fn process(
 		&mut self,
@@ -670,16 +802,22 @@ impl Iterator {
 		opt: &Options,
 		txn: &Transaction,
 		stm: &Statement<'_>,
-		pro: Processed,
+		mut pro: Processed,
 	) {
+		// Share the storage of the strings of the scanned record
+		match &mut pro.val {
+			Operable::Value(v) | Operable::Mergeable(v, _) | Operable::Relatable(_, v, _) => {
+				self.interner.intern(v)
+			}
+		}
 		// Process the document
 		let res = Document::process(ctx, opt, txn, stm, pro).await;
 		// Process the result
-		self.result(res, stm);
+		self.result(res, stm).await;
 	}
 
 	/// Accept a processed record result
-	fn result(&mut self, res: Result<Value, Error>, stm: &Statement<'_>) {
+	async fn result(&mut self, res: Result<Value, Error>, stm: &Statement<'_>) {
 		// Process the result
 		match res {
 			Err(Error::Ignore) => {
@@ -690,10 +828,34 @@ impl Iterator {
 				self.run.cancel();
 				return;
 			}
-			Ok(v) => self.results.push(v),
+			Ok(mut v) => match &mut self.groups {
+				// Collect the record into its group
+				Some(g) => {
+					self.interner.intern(&mut v);
+					if let Err(e) = g.push(v).await {
+						self.error = Some(e);
+						self.run.cancel();
+					}
+					return;
+				}
+				None => {
+					// Share the storage of the strings of the output record
+					self.interner.intern(&mut v);
+					// Account for the memory held by the record
+					if let Err(e) = self.memory.alloc(estimate(&v)) {
+						self.error = Some(e);
+						self.run.cancel();
+						return;
+					}
+					self.results.push(v)
+				}
+			},
 		}
 		// Check if we can exit
-		if stm.group().is_none() && stm.order().is_none() {
+		if stm.group().is_none()
+			&& (stm.order().is_none() || self.ordered)
+			&& !stm.expr().is_some_and(Fields::has_window)
+		{
 			if let Some(l) = self.limit {
 				if let Some(s) = self.start {
 					if self.results.len() == l + s {
@@ -0,0 +1,160 @@
+use crate::err::Error;
+use crate::sql::array::Array;
+use crate::sql::value::Value;
+use std::mem;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Accounts for the approximate memory held by the values which a statement
+/// produces, which may be produced in parallel, and enforces a limit on it
+#[derive(Default)]
+pub(crate) struct MemoryTracker {
+	/// The maximum number of bytes the statement can hold, if limited
+	limit: Option<usize>,
+	/// The number of bytes currently held by the statement
+	used: AtomicUsize,
+	/// The highest number of bytes held by the statement at once
+	peak: AtomicUsize,
+}
+
+impl MemoryTracker {
+	pub(crate) fn new(limit: Option<usize>) -> Self {
+		Self {
+			limit,
+			..Self::default()
+		}
+	}
+
+	/// Accounts for a value which is held by the statement
+	pub(crate) fn alloc(&self, bytes: usize) -> Result<(), Error> {
+		let used = self.used.fetch_add(bytes, Ordering::Relaxed) + bytes;
+		self.observe(used);
+		match self.limit {
+			Some(limit) if used > limit => Err(Error::MemoryLimitExceeded {
+				limit,
+			}),
+			_ => Ok(()),
+		}
+	}
+
+	/// Accounts for a value which is no longer held by the statement
+	pub(crate) fn release(&self, bytes: usize) {
+		self.used.fetch_sub(bytes, Ordering::Relaxed);
+	}
+
+	/// Accounts for the statement holding a number of bytes at once
+	pub(crate) fn observe(&self, bytes: usize) {
+		self.peak.fetch_max(bytes, Ordering::Relaxed);
+	}
+
+	/// The highest number of bytes held by the statement at once
+	pub(crate) fn peak(&self) -> usize {
+		self.peak.load(Ordering::Relaxed)
+	}
+}
+
+/// The memory held by the values of an iterator, or of the groups of a GROUP BY
+/// clause, which is accounted for by the statement until it is dropped, so that
+/// the memory held by a nested statement is only counted while it is running
+#[derive(Default)]
+pub(crate) struct Reservation {
+	/// The tracker of the memory held by the statement, if any
+	tracker: Option<Arc<MemoryTracker>>,
+	/// The number of bytes which are currently reserved
+	bytes: usize,
+}
+
+impl Reservation {
+	pub(crate) fn new(tracker: Option<&Arc<MemoryTracker>>) -> Self {
+		Self {
+			tracker: tracker.cloned(),
+			bytes: 0,
+		}
+	}
+
+	/// Reserves the memory held by a value
+	pub(crate) fn alloc(&mut self, bytes: usize) -> Result<(), Error> {
+		match &self.tracker {
+			Some(t) => {
+				self.bytes += bytes;
+				t.alloc(bytes)
+			}
+			None => Ok(()),
+		}
+	}
+
+	/// Releases all of the memory which is reserved
+	pub(crate) fn clear(&mut self) {
+		if let Some(t) = &self.tracker {
+			t.release(mem::take(&mut self.bytes));
+		}
+	}
+}
+
+impl Drop for Reservation {
+	fn drop(&mut self) {
+		self.clear();
+	}
+}
+
+/// Approximate the number of bytes a value holds in memory
+pub(crate) fn estimate(v: &Value) -> usize {
+	mem::size_of::<Value>()
+		+ match v {
+			// The storage of an interned strand is shared between its copies
+			Value::Strand(v) if Arc::strong_count(&v.0) > 1 => 0,
+			Value::Strand(v) => v.len(),
+			Value::Bytes(v) => v.len(),
+			Value::Thing(v) => v.tb.len() + v.id.to_string().len(),
+			Value::Array(v) => estimate_all(v),
+			Value::Object(v) => v.iter().map(|(k, v)| k.len() + estimate(v)).sum(),
+			_ => 0,
+		}
+}
+
+/// Approximate the number of bytes the values of an array hold in memory
+pub(crate) fn estimate_all(v: &Array) -> usize {
+	v.iter().map(estimate).sum()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn peak_and_limit() {
+		let mem = MemoryTracker::new(Some(100));
+		mem.alloc(60).unwrap();
+		mem.observe(80);
+		assert_eq!(mem.peak(), 80);
+		mem.alloc(30).unwrap();
+		assert_eq!(mem.peak(), 90);
+		assert!(matches!(
+			mem.alloc(20),
+			Err(Error::MemoryLimitExceeded {
+				limit: 100
+			})
+		));
+		assert_eq!(mem.peak(), 110);
+	}
+
+	#[test]
+	fn reservations_are_released() {
+		let mem = Arc::new(MemoryTracker::new(Some(100)));
+		let mut res = Reservation::new(Some(&mem));
+		res.alloc(60).unwrap();
+		// A nested reservation is released once dropped
+		{
+			let mut nested = Reservation::new(Some(&mem));
+			nested.alloc(30).unwrap();
+		}
+		res.alloc(30).unwrap();
+		assert_eq!(mem.peak(), 90);
+		// Cleared reservations can be reserved again
+		res.clear();
+		res.alloc(90).unwrap();
+		drop(res);
+		Reservation::new(Some(&mem)).alloc(100).unwrap();
+		assert_eq!(mem.peak(), 100);
+	}
+}
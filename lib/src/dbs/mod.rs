@@ -2,17 +2,24 @@
 //! In this module we essentially manage the entire lifecycle of a database request acting as the
 //! glue between the API and the response. In this module we use channels as a transport layer
 //! and executors to process the operations. This module also gives a `context` to the transaction.
+mod aggregate;
 mod distinct;
 mod executor;
 mod explanation;
+mod intern;
 mod iterator;
+mod memory;
 mod notification;
 mod options;
+mod permissions;
+mod profile;
 mod response;
 mod session;
 mod statement;
 mod transaction;
+mod traversal;
 mod variables;
+mod window;
 
 pub use self::notification::*;
 pub use self::options::*;
@@ -21,13 +28,18 @@ pub use self::session::*;
 
 pub(crate) use self::executor::*;
 pub(crate) use self::iterator::*;
+pub(crate) use self::memory::MemoryTracker;
+pub(crate) use self::permissions::*;
+pub(crate) use self::profile::Profiler;
 pub(crate) use self::statement::*;
 pub(crate) use self::transaction::*;
+pub(crate) use self::traversal::*;
 pub(crate) use self::variables::*;
 
 pub mod capabilities;
 pub use self::capabilities::Capabilities;
 pub mod node;
+pub(crate) mod seed;
 
 mod processor;
 #[cfg(test)]
@@ -1,3 +1,4 @@
+use crate::dbs::NoneOutput;
 use crate::sql::{Object, Uuid, Value};
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Debug, Display};
@@ -27,6 +28,14 @@ pub struct Notification {
 	pub result: Value,
 }
 
+impl Notification {
+	/// Outputs the `NONE` values of the result as set by the `NONE_OUTPUT` option of a session
+	pub fn with_none_output(mut self, out: NoneOutput) -> Self {
+		self.result.output_nones(out);
+		self
+	}
+}
+
 impl Display for Notification {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		let obj: Object = map! {
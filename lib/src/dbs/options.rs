@@ -6,6 +6,7 @@ use crate::iam::{Action, Auth, ResourceKind, Role};
 use crate::sql::Base;
 use channel::Sender;
 use std::sync::Arc;
+use std::time::Duration;
 use uuid::Uuid;
 
 /// An Options is passed around when processing a set of query
@@ -34,6 +35,8 @@ pub struct Options {
 	pub force: bool,
 	/// Should we run permissions checks?
 	pub perms: bool,
+	/// Should permission denials explain which rule denied access?
+	pub debug_perms: bool,
 	/// Should we error if tables don't exist?
 	pub strict: bool,
 	/// Should we process field queries?
@@ -48,6 +51,16 @@ pub struct Options {
 	pub futures: bool,
 	/// Should we process variable field projections?
 	pub projections: bool,
+	/// Should nondeterministic functions be resolved before processing?
+	pub deterministic: bool,
+	/// The seed of the random values and record ids which are generated
+	pub seed: Option<u64>,
+	/// How many records should be scanned in each batch?
+	pub batch: u32,
+	/// How many bytes can GROUP BY clauses hold in memory?
+	pub group_limit: usize,
+	/// The timeout of statements without a timeout clause
+	pub timeout: Option<Duration>,
 	/// The channel over which we send notifications
 	pub sender: Option<Sender<Notification>>,
 	/// Datastore capabilities
@@ -70,6 +83,7 @@ impl Options {
 			dive: 0,
 			live: false,
 			perms: true,
+			debug_perms: false,
 			force: false,
 			strict: false,
 			fields: true,
@@ -78,6 +92,11 @@ impl Options {
 			indexes: true,
 			futures: false,
 			projections: false,
+			deterministic: false,
+			seed: None,
+			batch: cnf::PROCESSOR_BATCH_SIZE,
+			group_limit: cnf::GROUP_MEMORY_LIMIT,
+			timeout: None,
 			auth_enabled: true,
 			sender: None,
 			auth: Arc::new(Auth::default()),
@@ -208,6 +227,48 @@ impl Options {
 		self
 	}
 
+	/// Specify whether nondeterministic functions should be resolved
+	/// to literal values before statements are processed, with chaining.
+	pub fn with_deterministic(mut self, deterministic: bool) -> Self {
+		self.deterministic = deterministic;
+		self
+	}
+
+	/// Specify the seed from which random values and record
+	/// ids are generated in each statement, with chaining.
+	pub fn with_seed(mut self, seed: Option<u64>) -> Self {
+		self.seed = seed;
+		self
+	}
+
+	/// Specify how many records are scanned in each
+	/// batch when iterating, with support for chaining.
+	pub fn with_batch(mut self, batch: u32) -> Self {
+		self.batch = batch;
+		self
+	}
+
+	/// Specify how many bytes the groups of a GROUP BY clause can
+	/// hold in memory before being spilled, with support for chaining.
+	pub fn with_group_limit(mut self, group_limit: usize) -> Self {
+		self.group_limit = group_limit;
+		self
+	}
+
+	/// Specify whether permission denials should report the
+	/// rule which denied access, with support for chaining.
+	pub fn with_debug_perms(mut self, debug_perms: bool) -> Self {
+		self.debug_perms = debug_perms;
+		self
+	}
+
+	/// Specify the timeout of statements which do not
+	/// have a timeout clause, with support for chaining.
+	pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+		self.timeout = timeout;
+		self
+	}
+
 	/// Create a new Options object for a subquery
 	pub fn with_import(mut self, import: bool) -> Self {
 		self.fields = !import;
@@ -0,0 +1,266 @@
+use crate::ctx::Context;
+use crate::dbs::{Options, Transaction};
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::sql::expression::Expression;
+use crate::sql::function::Function;
+use crate::sql::operator::Operator;
+use crate::sql::part::Part;
+use crate::sql::permission::Permission;
+use crate::sql::value::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::slice;
+use std::sync::Mutex;
+
+/// The results of the PERMISSIONS clauses computed by a statement, keyed
+/// on the permission expression, and on the values of the document fields
+/// and parameters which that expression depends on.
+#[derive(Default)]
+pub(crate) struct PermissionCache {
+	results: Mutex<HashMap<Value, HashMap<Vec<Value>, bool>>>,
+}
+
+/// A value, which a permission expression depends on,
+/// and which can change from one record to the next.
+#[derive(Debug, Eq, PartialEq)]
+enum Input<'a> {
+	/// A top-level field of the current document
+	Field(&'a Part),
+	/// A parameter, such as `$value` or `$auth`
+	Param(&'a str),
+}
+
+/// Check whether a PERMISSIONS clause allows access to a document,
+/// reusing the results computed earlier in the current statement.
+pub(crate) async fn permitted(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	e: &Value,
+	doc: &CursorDoc<'_>,
+) -> Result<bool, Error> {
+	match ctx.get_permissions() {
+		Some(pc) => pc.allows(ctx, opt, txn, e, doc).await,
+		None => Ok(e.compute(ctx, opt, txn, Some(doc)).await?.is_truthy()),
+	}
+}
+
+/// Describe why a PERMISSIONS clause denied access to a document, when
+/// permission debugging is enabled on the session. The error reports the
+/// rule which was checked, along with the evaluated value of each operand
+/// of the permission expression, such as the document fields and parameters.
+pub(crate) async fn denied(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	perms: &Permission,
+	target: String,
+	action: &str,
+	doc: &CursorDoc<'_>,
+) -> Result<Error, Error> {
+	let values = match perms {
+		Permission::Specific(e) => explain(ctx, opt, txn, e, doc).await?,
+		_ => Value::Object(Default::default()),
+	};
+	Ok(Error::PermissionDenied {
+		target,
+		action: action.to_owned(),
+		rule: perms.to_string(),
+		values: values.to_string(),
+	})
+}
+
+/// Compute the operands of a permission expression, skipping literal values
+async fn explain(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	e: &Value,
+	doc: &CursorDoc<'_>,
+) -> Result<Value, Error> {
+	let mut out = BTreeMap::new();
+	let mut stack = vec![e];
+	while let Some(v) = stack.pop() {
+		match v {
+			Value::Expression(x) => match x.as_ref() {
+				Expression::Unary {
+					v,
+					..
+				} => stack.push(v),
+				Expression::Binary {
+					l,
+					r,
+					..
+				} => {
+					stack.push(r);
+					stack.push(l);
+				}
+			},
+			v if v.is_static() => (),
+			v => {
+				let res = v.compute(ctx, opt, txn, Some(doc)).await?;
+				out.insert(v.to_string(), res);
+			}
+		}
+	}
+	Ok(out.into())
+}
+
+impl PermissionCache {
+	/// Check whether a PERMISSIONS clause allows access to a document.
+	/// Expressions which do not depend on the document are only computed
+	/// once per statement, and the result of any other expression is reused
+	/// for each document with the same values for the fields it references.
+	pub(crate) async fn allows(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		e: &Value,
+		doc: &CursorDoc<'_>,
+	) -> Result<bool, Error> {
+		// Find the values which the expression depends on
+		let mut inputs = Vec::new();
+		if !collect_inputs(e, &mut inputs) {
+			return Ok(e.compute(ctx, opt, txn, Some(doc)).await?.is_truthy());
+		}
+		// Fetch the current value of each input
+		let key: Vec<Value> = inputs
+			.iter()
+			.map(|i| match i {
+				Input::Field(p) => doc.doc.pick(slice::from_ref(*p)),
+				Input::Param(p) => ctx.value(p).cloned().unwrap_or_default(),
+			})
+			.collect();
+		// Check if this has already been computed
+		let cached = self.results.lock().unwrap().get(e).and_then(|r| r.get(&key)).copied();
+		if let Some(v) = cached {
+			return Ok(v);
+		}
+		// Process the permission expression
+		let v = e.compute(ctx, opt, txn, Some(doc)).await?.is_truthy();
+		// Store the result for the next documents
+		self.results.lock().unwrap().entry(e.clone()).or_default().insert(key, v);
+		Ok(v)
+	}
+}
+
+/// Collect the document fields and parameters which an expression depends on.
+/// Returns false if the result of the expression can not be reused, because
+/// it depends on the whole document, on the graph edges of the record, or on
+/// a function which may return a different value each time it is called.
+fn collect_inputs<'a>(v: &'a Value, out: &mut Vec<Input<'a>>) -> bool {
+	match v {
+		Value::Param(p) => match p.as_str() {
+			"this" | "self" => false,
+			p => {
+				push(out, Input::Param(p));
+				true
+			}
+		},
+		Value::Idiom(i) => {
+			let mut parts = i.iter();
+			match parts.next() {
+				Some(p @ Part::Field(_)) => push(out, Input::Field(p)),
+				Some(Part::Start(v)) => {
+					if !collect_inputs(v, out) {
+						return false;
+					}
+				}
+				_ => return false,
+			}
+			parts.all(|p| match p {
+				Part::Graph(_) => false,
+				Part::Where(v) | Part::Value(v) | Part::Start(v) => collect_inputs(v, out),
+				Part::Method(_, a) => a.iter().all(|v| collect_inputs(v, out)),
+				_ => true,
+			})
+		}
+		Value::Array(v) => v.iter().all(|v| collect_inputs(v, out)),
+		Value::Object(v) => v.values().all(|v| collect_inputs(v, out)),
+		Value::Cast(v) => collect_inputs(&v.1, out),
+		Value::Expression(v) => match v.as_ref() {
+			Expression::Unary {
+				v,
+				..
+			} => collect_inputs(v, out),
+			Expression::Binary {
+				o: Operator::Matches(_) | Operator::FuzzyMatches(_, _) | Operator::Knn(_),
+				..
+			} => false,
+			Expression::Binary {
+				l,
+				r,
+				..
+			} => collect_inputs(l, out) && collect_inputs(r, out),
+		},
+		Value::Function(f) => match f.as_ref() {
+			Function::Normal(..) if f.is_nondeterministic() => false,
+			Function::Normal(n, _) if n.starts_with("http::") || n.starts_with("search::") => false,
+			Function::Normal(n, _) if n == "sleep" => false,
+			Function::Normal(_, a) => a.iter().all(|v| collect_inputs(v, out)),
+			_ => false,
+		},
+		Value::Model(_)
+		| Value::Block(_)
+		| Value::Range(_)
+		| Value::Edges(_)
+		| Value::Future(_)
+		| Value::Subquery(_)
+		| Value::Query(_) => false,
+		_ => true,
+	}
+}
+
+fn push<'a>(out: &mut Vec<Input<'a>>, i: Input<'a>) {
+	if !out.contains(&i) {
+		out.push(i);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::sql::value::value;
+
+	fn analyse(sql: &str) -> Option<Vec<String>> {
+		let v = value(sql).unwrap().1;
+		let mut out = Vec::new();
+		collect_inputs(&v, &mut out).then(|| {
+			out.iter()
+				.map(|i| match i {
+					Input::Field(p) => p.to_string(),
+					Input::Param(p) => format!("${p}"),
+				})
+				.collect()
+		})
+	}
+
+	#[test]
+	fn independent_of_document() {
+		assert_eq!(analyse("true"), Some(vec![]));
+		assert_eq!(analyse("$auth.admin = true"), Some(vec!["$auth".to_owned()]));
+	}
+
+	#[test]
+	fn depends_on_fields() {
+		assert_eq!(
+			analyse("user = $auth.id OR public.enabled = true"),
+			Some(vec![".user".to_owned(), "$auth".to_owned(), ".public".to_owned()])
+		);
+		assert_eq!(
+			analyse("$value > 10 AND string::len(name) > 0"),
+			Some(vec!["$value".to_owned(), ".name".to_owned()])
+		);
+	}
+
+	#[test]
+	fn not_reusable() {
+		assert_eq!(analyse("$this.user = $auth.id"), None);
+		assert_eq!(analyse("->likes->user CONTAINS $auth.id"), None);
+		assert_eq!(analyse("rand::bool()"), None);
+		assert_eq!(analyse("(SELECT * FROM user WHERE admin = true)"), None);
+		assert_eq!(analyse("content @@ 'secret'"), None);
+	}
+}
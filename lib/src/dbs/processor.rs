@@ -1,4 +1,3 @@
-use crate::cnf::PROCESSOR_BATCH_SIZE;
 use crate::ctx::Context;
 #[cfg(not(target_arch = "wasm32"))]
 use crate::dbs::distinct::AsyncDistinct;
@@ -8,8 +7,9 @@ use crate::err::Error;
 use crate::idx::planner::executor::IteratorRef;
 use crate::idx::planner::plan::IndexOption;
 use crate::key::{graph, thing};
+use crate::kvs::archive;
 use crate::sql::dir::Dir;
-use crate::sql::{Edges, Range, Table, Thing, Value};
+use crate::sql::{Edges, Range, Table, TableType, Thing, Value};
 #[cfg(not(target_arch = "wasm32"))]
 use channel::Sender;
 use std::ops::Bound;
@@ -98,7 +98,11 @@ impl<'a> Processor<'a> {
 				Iterable::Range(v) => self.process_range(ctx, opt, txn, stm, v).await?,
 				Iterable::Edges(e) => self.process_edge(ctx, opt, txn, stm, e).await?,
 				Iterable::Index(t, ir, io) => {
-					self.process_index(ctx, opt, txn, stm, t, ir, io).await?
+					self.process_index(ctx, opt, txn, stm, t, ir, io, None).await?
+				}
+				Iterable::IndexEdges(e, ir, io) => {
+					let t = e.what[0].clone();
+					self.process_index(ctx, opt, txn, stm, t, ir, io, Some(&e)).await?
 				}
 				Iterable::Mergeable(v, o) => {
 					self.process_mergeable(ctx, opt, txn, stm, v, o).await?
@@ -106,6 +110,7 @@ impl<'a> Processor<'a> {
 				Iterable::Relatable(f, v, w) => {
 					self.process_relatable(ctx, opt, txn, stm, f, v, w).await?
 				}
+				Iterable::Archive(v) => self.process_archive(ctx, opt, txn, stm, v).await?,
 			}
 		}
 		Ok(())
@@ -124,6 +129,7 @@ impl<'a> Processor<'a> {
 			ir: None,
 			rid: None,
 			doc_id: None,
+			dir: None,
 			val: Operable::Value(v),
 		};
 		// Process the document record
@@ -153,6 +159,7 @@ impl<'a> Processor<'a> {
 			ir: None,
 			rid: Some(v),
 			doc_id: None,
+			dir: None,
 			val,
 		};
 		self.process(ctx, opt, txn, stm, pro).await?;
@@ -186,6 +193,7 @@ impl<'a> Processor<'a> {
 			ir: None,
 			rid: Some(v),
 			doc_id: None,
+			dir: None,
 			val,
 		};
 		self.process(ctx, opt, txn, stm, pro).await?;
@@ -221,6 +229,7 @@ impl<'a> Processor<'a> {
 			ir: None,
 			rid: Some(v),
 			doc_id: None,
+			dir: None,
 			val,
 		};
 		self.process(ctx, opt, txn, stm, pro).await?;
@@ -254,13 +263,13 @@ impl<'a> Processor<'a> {
 				None => {
 					let min = beg.clone();
 					let max = end.clone();
-					txn.clone().lock().await.scan(min..max, PROCESSOR_BATCH_SIZE).await?
+					txn.clone().lock().await.scan(min..max, opt.batch).await?
 				}
 				Some(ref mut beg) => {
 					beg.push(0x00);
 					let min = beg.clone();
 					let max = end.clone();
-					txn.clone().lock().await.scan(min..max, PROCESSOR_BATCH_SIZE).await?
+					txn.clone().lock().await.scan(min..max, opt.batch).await?
 				}
 			};
 			// If there are key-value entries then fetch them
@@ -288,6 +297,7 @@ impl<'a> Processor<'a> {
 						ir: None,
 						rid: Some(rid),
 						doc_id: None,
+						dir: None,
 						val,
 					};
 					self.process(ctx, opt, txn, stm, pro).await?;
@@ -300,6 +310,69 @@ impl<'a> Processor<'a> {
 		Ok(())
 	}
 
+	async fn process_archive(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+		v: Table,
+	) -> Result<(), Error> {
+		// Prepare the start and end keys
+		let beg = crate::key::table::ar::prefix(opt.ns(), opt.db(), &v);
+		let end = crate::key::table::ar::suffix(opt.ns(), opt.db(), &v);
+		// Prepare the next holder key
+		let mut nxt: Option<Vec<u8>> = None;
+		// Loop until no more keys
+		loop {
+			// Check if the context is finished
+			if ctx.is_done() {
+				break;
+			}
+			// Get the next batch of key-value entries
+			let min = match nxt {
+				None => beg.clone(),
+				Some(ref mut beg) => {
+					beg.push(0x00);
+					beg.clone()
+				}
+			};
+			let res = txn.clone().lock().await.scan(min..end.clone(), opt.batch).await?;
+			// If there are no more key-value entries then we are done
+			if res.is_empty() {
+				break;
+			}
+			// Get total results
+			let n = res.len();
+			// Loop over results
+			for (i, (k, v)) in res.into_iter().enumerate() {
+				// Check the context
+				if ctx.is_done() {
+					break;
+				}
+				// Ready the next
+				if n == i + 1 {
+					nxt = Some(k.clone());
+				}
+				// Decompress the archived record
+				let key = crate::key::table::ar::Ar::decode(&k)?;
+				let val = archive::decode(&v)?;
+				let rid = Thing::from((key.tb, key.id));
+				// Process the record
+				let pro = Processed {
+					ir: None,
+					rid: Some(rid),
+					doc_id: None,
+					dir: None,
+					val: Operable::Value(val),
+				};
+				self.process(ctx, opt, txn, stm, pro).await?;
+			}
+		}
+		// Everything ok
+		Ok(())
+	}
+
 	async fn process_range(
 		&mut self,
 		ctx: &Context<'_>,
@@ -343,13 +416,13 @@ impl<'a> Processor<'a> {
 				None => {
 					let min = beg.clone();
 					let max = end.clone();
-					txn.clone().lock().await.scan(min..max, PROCESSOR_BATCH_SIZE).await?
+					txn.clone().lock().await.scan(min..max, opt.batch).await?
 				}
 				Some(ref mut beg) => {
 					beg.push(0x00);
 					let min = beg.clone();
 					let max = end.clone();
-					txn.clone().lock().await.scan(min..max, PROCESSOR_BATCH_SIZE).await?
+					txn.clone().lock().await.scan(min..max, opt.batch).await?
 				}
 			};
 			// If there are key-value entries then fetch them
@@ -377,6 +450,7 @@ impl<'a> Processor<'a> {
 						ir: None,
 						rid: Some(rid),
 						doc_id: None,
+						dir: None,
 						val,
 					};
 					self.process(ctx, opt, txn, stm, pro).await?;
@@ -397,74 +471,8 @@ impl<'a> Processor<'a> {
 		stm: &Statement<'_>,
 		e: Edges,
 	) -> Result<(), Error> {
-		// Pull out options
-		let ns = opt.ns();
-		let db = opt.db();
-		let tb = &e.from.tb;
-		let id = &e.from.id;
 		// Fetch start and end key pairs
-		let keys = match e.what.len() {
-			0 => match e.dir {
-				// /ns/db/tb/id
-				Dir::Both => {
-					vec![(graph::prefix(ns, db, tb, id), graph::suffix(ns, db, tb, id))]
-				}
-				// /ns/db/tb/id/IN
-				Dir::In => vec![(
-					graph::egprefix(ns, db, tb, id, &e.dir),
-					graph::egsuffix(ns, db, tb, id, &e.dir),
-				)],
-				// /ns/db/tb/id/OUT
-				Dir::Out => vec![(
-					graph::egprefix(ns, db, tb, id, &e.dir),
-					graph::egsuffix(ns, db, tb, id, &e.dir),
-				)],
-			},
-			_ => match e.dir {
-				// /ns/db/tb/id/IN/TB
-				Dir::In => e
-					.what
-					.iter()
-					.map(|v| v.to_string())
-					.map(|v| {
-						(
-							graph::ftprefix(ns, db, tb, id, &e.dir, &v),
-							graph::ftsuffix(ns, db, tb, id, &e.dir, &v),
-						)
-					})
-					.collect::<Vec<_>>(),
-				// /ns/db/tb/id/OUT/TB
-				Dir::Out => e
-					.what
-					.iter()
-					.map(|v| v.to_string())
-					.map(|v| {
-						(
-							graph::ftprefix(ns, db, tb, id, &e.dir, &v),
-							graph::ftsuffix(ns, db, tb, id, &e.dir, &v),
-						)
-					})
-					.collect::<Vec<_>>(),
-				// /ns/db/tb/id/IN/TB, /ns/db/tb/id/OUT/TB
-				Dir::Both => e
-					.what
-					.iter()
-					.map(|v| v.to_string())
-					.flat_map(|v| {
-						vec![
-							(
-								graph::ftprefix(ns, db, tb, id, &Dir::In, &v),
-								graph::ftsuffix(ns, db, tb, id, &Dir::In, &v),
-							),
-							(
-								graph::ftprefix(ns, db, tb, id, &Dir::Out, &v),
-								graph::ftsuffix(ns, db, tb, id, &Dir::Out, &v),
-							),
-						]
-					})
-					.collect::<Vec<_>>(),
-			},
-		};
+		let keys = edge_ranges(opt, txn, &e).await?;
 		//
 		for (beg, end) in keys.iter() {
 			// Prepare the next holder key
@@ -480,13 +488,13 @@ impl<'a> Processor<'a> {
 					None => {
 						let min = beg.clone();
 						let max = end.clone();
-						txn.lock().await.scan(min..max, PROCESSOR_BATCH_SIZE).await?
+						txn.lock().await.scan(min..max, opt.batch).await?
 					}
 					Some(ref mut beg) => {
 						beg.push(0x00);
 						let min = beg.clone();
 						let max = end.clone();
-						txn.lock().await.scan(min..max, PROCESSOR_BATCH_SIZE).await?
+						txn.lock().await.scan(min..max, opt.batch).await?
 					}
 				};
 				// If there are key-value entries then fetch them
@@ -523,6 +531,7 @@ impl<'a> Processor<'a> {
 							ir: None,
 							rid: Some(rid),
 							doc_id: None,
+							dir: Some(gra.eg),
 							val,
 						};
 						self.process(ctx, opt, txn, stm, pro).await?;
@@ -546,13 +555,16 @@ impl<'a> Processor<'a> {
 		table: Table,
 		ir: IteratorRef,
 		io: IndexOption,
+		edges: Option<&Edges>,
 	) -> Result<(), Error> {
 		// Check that the table exists
 		txn.lock().await.check_ns_db_tb(opt.ns(), opt.db(), &table.0, opt.strict).await?;
 		if let Some(pla) = ctx.get_query_planner() {
 			if let Some(exe) = pla.get_query_executor(&table.0) {
+				// Check if the records can be built from the index keys
+				let covering = io.is_covering().then(|| io.clone());
 				if let Some(mut iterator) = exe.new_iterator(opt, ir, io).await? {
-					let mut things = iterator.next_batch(txn, PROCESSOR_BATCH_SIZE).await?;
+					let mut things = iterator.next_batch(txn, opt.batch).await?;
 					while !things.is_empty() {
 						// Check if the context is finished
 						if ctx.is_done() {
@@ -569,28 +581,44 @@ impl<'a> Processor<'a> {
 							if !thing.tb.eq(table.as_str()) {
 								continue;
 							}
+							// If the record is not an edge of the traversed record we can skip
+							let dir = match edges {
+								Some(e) => match edge_dir(opt, txn, e, &thing).await? {
+									Some(dir) => Some(dir),
+									None => continue,
+								},
+								None => None,
+							};
 
-							// Fetch the data from the store
-							let key = thing::new(opt.ns(), opt.db(), &table.0, &thing.id);
-							let val = txn.lock().await.get(key.clone()).await?;
-							let rid = Thing::from((key.tb, key.id));
-							// Parse the data from the store
-							let val = Operable::Value(match val {
-								Some(v) => Value::from(v),
-								None => Value::None,
-							});
+							let (rid, val) = if let Some(io) = &covering {
+								// Build the data from the index key
+								(thing.clone(), io.covering_doc(&thing))
+							} else {
+								// Fetch the data from the store
+								let key = thing::new(opt.ns(), opt.db(), &table.0, &thing.id);
+								let val = txn.lock().await.get(key.clone()).await?;
+								let rid = Thing::from((key.tb, key.id));
+								// Parse the data from the store
+								let val = match val {
+									Some(v) => Value::from(v),
+									None => Value::None,
+								};
+								(rid, val)
+							};
+							let val = Operable::Value(val);
 							// Process the document record
 							let pro = Processed {
 								ir: Some(ir),
 								rid: Some(rid),
 								doc_id: Some(doc_id),
+								dir,
 								val,
 							};
 							self.process(ctx, opt, txn, stm, pro).await?;
 						}
 
 						// Collect the next batch of ids
-						things = iterator.next_batch(txn, PROCESSOR_BATCH_SIZE).await?;
+						things = iterator.next_batch(txn, opt.batch).await?;
 					}
 					// Everything ok
 					return Ok(());
@@ -602,3 +630,129 @@ impl<'a> Processor<'a> {
 		})
 	}
 }
+
+/// Returns the direction in which a record is an edge of the traversed record, if any
+async fn edge_dir(
+	opt: &Options,
+	txn: &Transaction,
+	e: &Edges,
+	rid: &Thing,
+) -> Result<Option<Dir>, Error> {
+	let (ns, db) = (opt.ns(), opt.db());
+	let dirs = match e.dir {
+		Dir::Both => vec![Dir::In, Dir::Out],
+		ref dir => vec![dir.clone()],
+	};
+	let mut run = txn.lock().await;
+	for dir in dirs {
+		if run.exi(graph::new(ns, db, &e.from.tb, &e.from.id, &dir, rid)).await? {
+			return Ok(Some(dir));
+		}
+	}
+	Ok(None)
+}
+
+/// Returns the key ranges holding the edges of a record in the given
+/// directions, skipping the tables which the table types rule out.
+pub(super) async fn edge_ranges(
+	opt: &Options,
+	txn: &Transaction,
+	e: &Edges,
+) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Error> {
+	// Pull out options
+	let ns = opt.ns();
+	let db = opt.db();
+	let tb = &e.from.tb;
+	let id = &e.from.id;
+	// Fetch start and end key pairs
+	let keys = match e.what.len() {
+		0 => match e.dir {
+			// /ns/db/tb/id
+			Dir::Both => {
+				vec![(graph::prefix(ns, db, tb, id), graph::suffix(ns, db, tb, id))]
+			}
+			// /ns/db/tb/id/IN
+			Dir::In => vec![(
+				graph::egprefix(ns, db, tb, id, &e.dir),
+				graph::egsuffix(ns, db, tb, id, &e.dir),
+			)],
+			// /ns/db/tb/id/OUT
+			Dir::Out => vec![(
+				graph::egprefix(ns, db, tb, id, &e.dir),
+				graph::egsuffix(ns, db, tb, id, &e.dir),
+			)],
+		},
+		_ => {
+			let dirs = match e.dir {
+				Dir::Both => vec![Dir::In, Dir::Out],
+				ref d => vec![d.clone()],
+			};
+			let mut keys = Vec::with_capacity(e.what.len() * dirs.len());
+			for v in e.what.iter().map(|v| v.to_string()) {
+				for dir in dirs.iter() {
+					// Skip the edges which the table types rule out
+					if !edges_possible(opt, txn, tb, dir, &v).await? {
+						continue;
+					}
+					// /ns/db/tb/id/IN/TB, /ns/db/tb/id/OUT/TB
+					keys.push((
+						graph::ftprefix(ns, db, tb, id, dir, &v),
+						graph::ftsuffix(ns, db, tb, id, dir, &v),
+					));
+				}
+			}
+			keys
+		}
+	};
+	Ok(keys)
+}
+
+/// Checks if a record of the table `tb` can have edges to the table `ft` in
+/// the given direction, given the types of the tables. Relation tables only
+/// connect the tables they are defined with, and normal tables hold no edges,
+/// so some traversals can be skipped without scanning the graph.
+async fn edges_possible(
+	opt: &Options,
+	txn: &Transaction,
+	tb: &str,
+	dir: &Dir,
+	ft: &str,
+) -> Result<bool, Error> {
+	let mut run = txn.lock().await;
+	// Tables which are not yet defined can hold any record
+	let tb_kind = match run.get_and_cache_tb(opt.ns(), opt.db(), tb).await {
+		Ok(v) => v.kind.clone(),
+		Err(Error::TbNotFound {
+			..
+		}) => TableType::Any,
+		Err(e) => return Err(e),
+	};
+	let ft_kind = match run.get_and_cache_tb(opt.ns(), opt.db(), ft).await {
+		Ok(v) => v.kind.clone(),
+		Err(Error::TbNotFound {
+			..
+		}) => TableType::Any,
+		Err(e) => return Err(e),
+	};
+	// The record is an edge, pointing to its in and out records
+	let outgoing = match &tb_kind {
+		TableType::Any => true,
+		TableType::Normal => false,
+		TableType::Relation(r) => match dir {
+			Dir::In => r.allows_from(ft),
+			Dir::Out => r.allows_to(ft),
+			Dir::Both => r.allows_from(ft) || r.allows_to(ft),
+		},
+	};
+	// The record is pointed to by the edges of the other table
+	let incoming = match &ft_kind {
+		TableType::Any => true,
+		TableType::Normal => false,
+		TableType::Relation(r) => match dir {
+			Dir::In => r.allows_to(tb),
+			Dir::Out => r.allows_from(tb),
+			Dir::Both => r.allows_from(tb) || r.allows_to(tb),
+		},
+	};
+	Ok(outgoing || incoming)
+}
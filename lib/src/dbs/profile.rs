@@ -0,0 +1,158 @@
+use crate::dbs::explanation::describe;
+use crate::dbs::Iterable;
+use crate::sql::{Duration, Object, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time;
+use trice::Instant;
+
+/// Measures the WHERE clause of a profiled statement, as it is
+/// checked against each of the records, which may happen in parallel
+#[derive(Default)]
+pub(crate) struct Profiler {
+	/// The number of records which were checked
+	checked: AtomicUsize,
+	/// The number of records which matched the condition
+	matched: AtomicUsize,
+	/// The time taken to check the records, in nanoseconds
+	elapsed: AtomicU64,
+}
+
+impl Profiler {
+	/// Records a record which was checked against the condition
+	pub(crate) fn filtered(&self, matched: bool, elapsed: time::Duration) {
+		self.checked.fetch_add(1, Ordering::Relaxed);
+		if matched {
+			self.matched.fetch_add(1, Ordering::Relaxed);
+		}
+		self.elapsed.fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+	}
+}
+
+/// The profile of a statement, which records the number of records
+/// output by each stage of the statement, and the time each stage took
+pub(super) struct Profile {
+	/// The time at which the statement started
+	started: Instant,
+	/// The input values which the statement iterates
+	iterables: Vec<Value>,
+	/// The stages of the statement, in the order they ran
+	stages: Vec<Stage>,
+}
+
+struct Stage {
+	/// The name of the stage
+	name: &'static str,
+	/// The number of records input to the stage
+	input: Option<usize>,
+	/// The number of records output by the stage
+	rows: usize,
+	/// The time taken by the stage
+	elapsed: time::Duration,
+	/// Any details about the stage
+	detail: Option<Value>,
+	/// The stages which ran as part of this stage
+	children: Vec<Stage>,
+}
+
+impl Profile {
+	pub(super) fn new(iterables: &[Iterable]) -> Self {
+		Self {
+			started: Instant::now(),
+			iterables: iterables.iter().map(describe).collect(),
+			stages: Vec::new(),
+		}
+	}
+
+	/// Records the records yielded by each input value, and the time taken
+	pub(super) fn add_scan(&mut self, actuals: &[(usize, time::Duration)]) {
+		let children = self
+			.iterables
+			.drain(..)
+			.zip(actuals)
+			.map(|(detail, (rows, elapsed))| Stage {
+				name: "iterate",
+				input: None,
+				rows: *rows,
+				elapsed: *elapsed,
+				detail: Some(detail),
+				children: vec![],
+			})
+			.collect::<Vec<_>>();
+		self.stages.push(Stage {
+			name: "scan",
+			input: None,
+			rows: children.iter().map(|s| s.rows).sum(),
+			elapsed: children.iter().map(|s| s.elapsed).sum(),
+			detail: None,
+			children,
+		});
+	}
+
+	/// Records the records checked by the WHERE clause, which are
+	/// checked as the records are scanned, and the time taken
+	pub(super) fn add_filter(&mut self, pr: &Profiler) {
+		self.stages.push(Stage {
+			name: "filter",
+			input: Some(pr.checked.load(Ordering::Relaxed)),
+			rows: pr.matched.load(Ordering::Relaxed),
+			elapsed: time::Duration::from_nanos(pr.elapsed.load(Ordering::Relaxed)),
+			detail: None,
+			children: vec![],
+		});
+	}
+
+	/// Records a stage which processed the full result set
+	pub(super) fn add_stage(
+		&mut self,
+		name: &'static str,
+		input: usize,
+		rows: usize,
+		elapsed: time::Duration,
+	) {
+		self.stages.push(Stage {
+			name,
+			input: Some(input),
+			rows,
+			elapsed,
+			detail: None,
+			children: vec![],
+		});
+	}
+
+	/// Outputs the profile tree of the statement
+	pub(super) fn output(self, rows: usize) -> Value {
+		Stage {
+			name: "statement",
+			input: None,
+			rows,
+			elapsed: self.started.elapsed(),
+			detail: None,
+			children: self.stages,
+		}
+		.into()
+	}
+}
+
+impl From<Stage> for Value {
+	fn from(s: Stage) -> Self {
+		let mut obj = HashMap::from([
+			("stage", Value::from(s.name)),
+			("rows", Value::from(s.rows)),
+			("elapsed", Value::from(Duration::from(s.elapsed))),
+		]);
+		if let Some(v) = s.input {
+			obj.insert("input", Value::from(v));
+		}
+		if let Some(v) = s.detail {
+			obj.insert("detail", v);
+		}
+		if !s.children.is_empty() {
+			obj.insert(
+				"children",
+				s.children.into_iter().map(Value::from).collect::<Vec<_>>().into(),
+			);
+		}
+		Value::from(Object::from(obj))
+	}
+}
@@ -1,4 +1,5 @@
 use crate::err::Error;
+use crate::sql::error::Span;
 use crate::sql::value::Value;
 use serde::ser::SerializeStruct;
 use serde::Deserialize;
@@ -21,7 +22,14 @@ pub enum QueryType {
 #[derive(Debug)]
 pub struct Response {
 	pub time: Duration,
+	/// The approximate peak number of bytes held in memory by the statement
+	pub memory: usize,
 	pub result: Result<Value, Error>,
+	/// The index of the statement in the query, with the first statement being 0
+	pub statement: usize,
+	/// The part of the query source containing the statement, if the statement
+	/// failed, and the query was executed from its source text
+	pub span: Option<Span>,
 	// Record the query type in case processing the response is necessary (such as tracking live queries).
 	pub query_type: QueryType,
 }
@@ -35,8 +43,20 @@ impl Response {
 	pub fn output(self) -> Result<Value, Error> {
 		self.result
 	}
+	/// Retrieve the response for serializing with the code and category of its error
+	pub fn detailed(&self) -> DetailedResponse<'_> {
+		DetailedResponse(self)
+	}
 }
 
+/// A response which is serialized with the code, name, and category of its
+/// error, along with the statement which failed, so that clients can handle
+/// errors programmatically. This is used
+/// for the JSON, CBOR, and MessagePack outputs, as the SDK clients decode the
+/// fields of a serialized [`Response`] positionally.
+#[derive(Debug)]
+pub struct DetailedResponse<'a>(&'a Response);
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub(crate) enum Status {
@@ -64,3 +84,59 @@ impl Serialize for Response {
 		val.end()
 	}
 }
+
+impl Serialize for DetailedResponse<'_> {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: serde::Serializer,
+	{
+		let e = match &self.0.result {
+			Ok(_) => return self.0.serialize(serializer),
+			Err(e) => e,
+		};
+		let mut val = serializer.serialize_struct(TOKEN, 8)?;
+		val.serialize_field("time", self.0.speed().as_str())?;
+		val.serialize_field("status", &Status::Err)?;
+		val.serialize_field("result", &Value::from(e.to_string()))?;
+		val.serialize_field("code", &e.code())?;
+		val.serialize_field("name", e.name())?;
+		val.serialize_field("category", &e.category())?;
+		val.serialize_field("statement", &self.0.statement)?;
+		match &self.0.span {
+			Some(span) => val.serialize_field("span", span)?,
+			None => val.skip_field("span")?,
+		}
+		val.end()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sql::Object;
+
+	#[test]
+	fn serialize_detailed_response() {
+		let res = Response {
+			time: Duration::from_millis(1),
+			memory: 0,
+			result: Err(Error::TbNotFound {
+				value: "person".to_owned(),
+			}),
+			statement: 2,
+			span: None,
+			query_type: QueryType::Other,
+		};
+		let val = crate::sql::to_value(res.detailed()).unwrap();
+		let obj = Object::try_from(val).unwrap();
+		assert_eq!(obj.get("status"), Some(&Value::from("ERR")));
+		assert_eq!(obj.get("code"), Some(&Value::from(4029)));
+		assert_eq!(obj.get("name"), Some(&Value::from("TB_NOT_FOUND")));
+		assert_eq!(obj.get("category"), Some(&Value::from("resource")));
+		assert_eq!(obj.get("statement"), Some(&Value::from(2)));
+		// The serialized response does not include the error details
+		let val = crate::sql::to_value(&res).unwrap();
+		let obj = Object::try_from(val).unwrap();
+		assert_eq!(obj.get("code"), None);
+	}
+}
@@ -0,0 +1,148 @@
+//! Random values which can be generated from a seed, so that the random values and record ids
+//! generated by a statement are the same each time it is run with the same seed.
+//!
+//! The generator of a statement is kept in a thread local while the statement is running, and
+//! is swapped in and out each time the statement is polled, as the statement may be polled on
+//! a different thread each time. Tasks which are spawned by a statement are not seeded.
+
+use pin_project_lite::pin_project;
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+thread_local! {
+	/// The generator of the statement which is running on this thread, if it is seeded.
+	static GENERATOR: RefCell<Option<StdRng>> = RefCell::new(None);
+}
+
+/// The random number generator of a statement, which is only seeded if the statement is
+/// run with a seed.
+#[derive(Debug)]
+pub(crate) struct Generator(Option<StdRng>);
+
+impl Generator {
+	/// Creates the generator of a statement, from the seed of its query and the position of
+	/// the statement within the query, so that each statement generates different values.
+	pub(crate) fn new(seed: Option<u64>, index: usize) -> Self {
+		Self(seed.map(|v| StdRng::seed_from_u64(v.wrapping_add(index as u64))))
+	}
+
+	/// Runs a function using this generator.
+	#[cfg(test)]
+	pub(crate) fn scope<R>(&mut self, f: impl FnOnce() -> R) -> R {
+		let _swap = Swap::new(&mut self.0);
+		f()
+	}
+
+	/// Returns a future which uses this generator whenever it is polled.
+	pub(crate) fn wrap<F: Future>(&mut self, inner: F) -> Seeded<'_, F> {
+		Seeded {
+			inner,
+			rng: &mut self.0,
+		}
+	}
+}
+
+pin_project! {
+	/// A future which uses the generator of a statement whenever it is polled.
+	pub(crate) struct Seeded<'a, F> {
+		#[pin]
+		inner: F,
+		rng: &'a mut Option<StdRng>,
+	}
+}
+
+impl<F: Future> Future for Seeded<'_, F> {
+	type Output = F::Output;
+	fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		let this = self.project();
+		let _swap = Swap::new(this.rng);
+		this.inner.poll(cx)
+	}
+}
+
+/// Swaps a generator into the thread local until dropped, so that it is restored even if
+/// the code using it panics.
+struct Swap<'a> {
+	rng: &'a mut Option<StdRng>,
+	prev: Option<StdRng>,
+}
+
+impl<'a> Swap<'a> {
+	fn new(rng: &'a mut Option<StdRng>) -> Self {
+		let prev = GENERATOR.with(|cell| cell.replace(rng.take()));
+		Self {
+			rng,
+			prev,
+		}
+	}
+}
+
+impl Drop for Swap<'_> {
+	fn drop(&mut self) {
+		*self.rng = GENERATOR.with(|cell| cell.replace(self.prev.take()));
+	}
+}
+
+/// Calls a function with the generator of the running statement if it is seeded, or with
+/// the generator of the thread otherwise.
+pub(crate) fn with<R>(f: impl FnOnce(&mut dyn RngCore) -> R) -> R {
+	GENERATOR.with(|cell| match cell.borrow_mut().as_mut() {
+		Some(rng) => f(rng),
+		None => f(&mut rand::thread_rng()),
+	})
+}
+
+/// Returns random bytes, for generating ids with `nanoid!`.
+pub(crate) fn bytes(len: usize) -> Vec<u8> {
+	let mut out = vec![0; len];
+	with(|rng| rng.fill_bytes(&mut out));
+	out
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rand::Rng;
+
+	fn sample() -> Vec<u64> {
+		(0..4).map(|_| with(|rng| rng.gen())).collect()
+	}
+
+	#[test]
+	fn seeded_values_repeat() {
+		let a = Generator::new(Some(42), 0).scope(sample);
+		let b = Generator::new(Some(42), 0).scope(sample);
+		let c = Generator::new(Some(42), 1).scope(sample);
+		assert_eq!(a, b);
+		assert_ne!(a, c);
+		assert_ne!(sample(), sample());
+	}
+
+	#[test]
+	fn seeded_values_continue() {
+		let mut gen = Generator::new(Some(7), 0);
+		let a = gen.scope(sample);
+		let b = gen.scope(sample);
+		assert_ne!(a, b);
+		let mut gen = Generator::new(Some(7), 0);
+		assert_eq!(gen.scope(sample), a);
+	}
+
+	#[tokio::test]
+	async fn seeded_futures() {
+		let mut gen = Generator::new(Some(42), 0);
+		let a = gen
+			.wrap(async {
+				let v = sample();
+				tokio::task::yield_now().await;
+				[v, sample()].concat()
+			})
+			.await;
+		let b = Generator::new(Some(42), 0).scope(|| [sample(), sample()].concat());
+		assert_eq!(a, b);
+	}
+}
@@ -1,8 +1,14 @@
 use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::err::Error;
 use crate::iam::Auth;
 use crate::iam::{Level, Role};
 use crate::sql::value::Value;
-use std::sync::Arc;
+use crate::sql::Datetime;
+use chrono::Utc;
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
 /// Specifies the current session information when processing a query.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
@@ -27,8 +33,200 @@ pub struct Session {
 	pub tk: Option<Value>,
 	/// The current scope authentication data
 	pub sd: Option<Value>,
+	/// The execution options of the session
+	pub op: SharedOptions,
 }
 
+/// The execution options which are set on a session with `SET OPTION`
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct SessionOptions {
+	/// Should we error if tables don't exist?
+	pub strict: Option<bool>,
+	/// The timeout of statements without a timeout clause
+	pub timeout: Option<Duration>,
+	/// How many records should be scanned in each batch?
+	pub batch_size: Option<u32>,
+	/// The output format of query responses, used by the server
+	pub format: Option<String>,
+	/// Should permission denials explain which rule denied access?
+	pub debug_permissions: Option<bool>,
+	/// How should `NONE` values be output in query responses?
+	pub none_output: Option<NoneOutput>,
+	/// The seed from which random values and record ids are generated
+	pub seed: Option<u64>,
+	/// The parameters which are set on the session with `LET ... FOR SESSION`
+	pub(crate) params: BTreeMap<String, SessionParam>,
+}
+
+/// A parameter which is kept on a session, until it expires
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct SessionParam {
+	/// The value of the parameter
+	value: Value,
+	/// The time at which the parameter expires, if any
+	expiry: Option<Datetime>,
+}
+
+impl SessionParam {
+	/// Check if the parameter has expired
+	fn is_expired(&self) -> bool {
+		self.expiry.as_ref().is_some_and(|v| v.0 <= Utc::now())
+	}
+}
+
+/// How `NONE` values are output in query responses
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum NoneOutput {
+	/// Output `NONE` values, keeping them distinct from `NULL` values
+	#[default]
+	Keep,
+	/// Output `NONE` values as `NULL` values
+	Null,
+	/// Omit object fields which are `NONE`, and output any other `NONE` values as `NULL` values
+	Omit,
+}
+
+impl SessionOptions {
+	/// Set an option from its name and value
+	pub fn set(&mut self, name: &str, value: Value) -> Result<(), Error> {
+		let invalid = |message: &str| Error::InvalidOption {
+			name: name.to_owned(),
+			message: message.to_owned(),
+		};
+		match name.to_ascii_uppercase().as_str() {
+			"STRICT" => match value {
+				Value::Bool(v) => self.strict = Some(v),
+				Value::None | Value::Null => self.strict = None,
+				_ => return Err(invalid("expected a boolean")),
+			},
+			"TIMEOUT" => match value {
+				Value::Duration(v) => self.timeout = Some(*v),
+				Value::None | Value::Null => self.timeout = None,
+				_ => return Err(invalid("expected a duration")),
+			},
+			"BATCH_SIZE" => match value {
+				Value::Number(v) if v.is_integer() && v.to_int() > 0 => {
+					self.batch_size = Some(v.to_int().min(u32::MAX as i64) as u32)
+				}
+				Value::None | Value::Null => self.batch_size = None,
+				_ => return Err(invalid("expected an integer greater than 0")),
+			},
+			"FORMAT" => match value {
+				Value::Strand(v) if matches!(v.as_str(), "json" | "cbor" | "pack") => {
+					self.format = Some(v.as_string())
+				}
+				Value::None | Value::Null => self.format = None,
+				_ => return Err(invalid("expected one of 'json', 'cbor' or 'pack'")),
+			},
+			"DEBUG_PERMISSIONS" => match value {
+				Value::Bool(v) => self.debug_permissions = Some(v),
+				Value::None | Value::Null => self.debug_permissions = None,
+				_ => return Err(invalid("expected a boolean")),
+			},
+			"NONE_OUTPUT" => match value {
+				Value::Strand(v) => match v.as_str() {
+					"keep" => self.none_output = Some(NoneOutput::Keep),
+					"null" => self.none_output = Some(NoneOutput::Null),
+					"omit" => self.none_output = Some(NoneOutput::Omit),
+					_ => return Err(invalid("expected one of 'keep', 'null' or 'omit'")),
+				},
+				Value::None | Value::Null => self.none_output = None,
+				_ => return Err(invalid("expected one of 'keep', 'null' or 'omit'")),
+			},
+			"SEED" => match value {
+				Value::Number(v) if v.is_integer() && v.to_int() >= 0 => {
+					self.seed = Some(v.to_int() as u64)
+				}
+				Value::None | Value::Null => self.seed = None,
+				_ => return Err(invalid("expected a positive integer")),
+			},
+			_ => return Err(invalid("the option does not exist")),
+		}
+		Ok(())
+	}
+
+	/// Merge the options into the options of a statement
+	pub(crate) fn apply(&self, mut opt: Options) -> Options {
+		if let Some(v) = self.strict {
+			opt = opt.with_strict(v);
+		}
+		if let Some(v) = self.batch_size {
+			opt = opt.with_batch(v);
+		}
+		if let Some(v) = self.debug_permissions {
+			opt = opt.with_debug_perms(v);
+		}
+		opt.with_seed(self.seed).with_timeout(self.timeout)
+	}
+}
+
+/// The execution options of a session, which are shared between the clones
+/// of the session, so that the options which are set by a query also apply
+/// to the following queries of the session.
+#[derive(Clone, Debug, Default)]
+pub struct SharedOptions(Arc<RwLock<SessionOptions>>);
+
+impl SharedOptions {
+	/// Retrieves the current options
+	pub fn get(&self) -> SessionOptions {
+		self.0.read().map(|v| v.clone()).unwrap_or_default()
+	}
+
+	/// Set an option from its name and value
+	pub fn set(&self, name: &str, value: Value) -> Result<(), Error> {
+		match self.0.write() {
+			Ok(mut v) => v.set(name, value),
+			Err(_) => Err(Error::Unreachable),
+		}
+	}
+
+	/// Set a parameter on the session, which expires after the ttl if specified.
+	/// Setting a parameter to `NONE` removes it from the session.
+	pub(crate) fn set_param(&self, name: &str, value: Value, ttl: Option<Duration>) {
+		if let Ok(mut v) = self.0.write() {
+			match value {
+				Value::None => {
+					v.params.remove(name);
+				}
+				value => {
+					// A ttl which can not be represented never expires
+					let expiry = ttl
+						.and_then(|d| chrono::Duration::from_std(d).ok())
+						.and_then(|d| Utc::now().checked_add_signed(d))
+						.map(Datetime::from);
+					v.params.insert(
+						name.to_owned(),
+						SessionParam {
+							value,
+							expiry,
+						},
+					);
+				}
+			}
+		}
+	}
+
+	/// Retrieves the parameters of the session which have not expired
+	pub(crate) fn params(&self) -> Vec<(String, Value)> {
+		match self.0.write() {
+			Ok(mut v) => {
+				v.params.retain(|_, p| !p.is_expired());
+				v.params.iter().map(|(k, p)| (k.clone(), p.value.clone())).collect()
+			}
+			Err(_) => vec![],
+		}
+	}
+}
+
+impl PartialEq for SharedOptions {
+	fn eq(&self, other: &Self) -> bool {
+		Arc::ptr_eq(&self.0, &other.0) || self.get() == other.get()
+	}
+}
+
+impl Eq for SharedOptions {}
+
 impl Session {
 	/// Set the selected namespace for the session
 	pub fn with_ns(mut self, ns: &str) -> Session {
@@ -54,6 +252,17 @@ impl Session {
 		self
 	}
 
+	/// Set the execution options of the session
+	pub fn with_options(mut self, options: SessionOptions) -> Session {
+		self.op = SharedOptions(Arc::new(RwLock::new(options)));
+		self
+	}
+
+	/// Retrieves the execution options of the session
+	pub fn options(&self) -> SessionOptions {
+		self.op.get()
+	}
+
 	/// Retrieves the selected namespace
 	pub(crate) fn ns(&self) -> Option<Arc<str>> {
 		self.ns.as_deref().map(Into::into)
@@ -82,6 +291,7 @@ impl Session {
 		ctx.add_value("token", val);
 		// Add session value
 		let val: Value = Value::from(map! {
+			"au".to_string() => self.au.level().to_string().into(),
 			"db".to_string() => self.db.to_owned().into(),
 			"id".to_string() => self.id.to_owned().into(),
 			"ip".to_string() => self.ip.to_owned().into(),
@@ -92,6 +302,10 @@ impl Session {
 			"tk".to_string() => self.tk.to_owned().into(),
 		});
 		ctx.add_value("session", val);
+		// Add session parameters
+		for (k, v) in self.op.params() {
+			ctx.add_value(k, v);
+		}
 		// Output context
 		ctx
 	}
@@ -132,6 +346,7 @@ impl Session {
 			sc: Some(sc.to_owned()),
 			tk: None,
 			sd: Some(rid),
+			op: SharedOptions::default(),
 		}
 	}
 
@@ -17,6 +17,7 @@ use crate::sql::statements::relate::RelateStatement;
 use crate::sql::statements::select::SelectStatement;
 use crate::sql::statements::show::ShowStatement;
 use crate::sql::statements::update::UpdateStatement;
+use crate::sql::statements::upsert::UpsertStatement;
 use crate::sql::Explain;
 use std::fmt;
 
@@ -30,6 +31,7 @@ pub(crate) enum Statement<'a> {
 	Relate(&'a RelateStatement),
 	Delete(&'a DeleteStatement),
 	Insert(&'a InsertStatement),
+	Upsert(&'a UpsertStatement),
 }
 
 impl<'a> From<&'a LiveStatement> for Statement<'a> {
@@ -80,6 +82,12 @@ impl<'a> From<&'a InsertStatement> for Statement<'a> {
 	}
 }
 
+impl<'a> From<&'a UpsertStatement> for Statement<'a> {
+	fn from(v: &'a UpsertStatement) -> Self {
+		Statement::Upsert(v)
+	}
+}
+
 impl<'a> fmt::Display for Statement<'a> {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		match self {
@@ -91,6 +99,7 @@ impl<'a> fmt::Display for Statement<'a> {
 			Statement::Relate(v) => write!(f, "{v}"),
 			Statement::Delete(v) => write!(f, "{v}"),
 			Statement::Insert(v) => write!(f, "{v}"),
+			Statement::Upsert(v) => write!(f, "{v}"),
 		}
 	}
 }
@@ -131,6 +140,7 @@ impl<'a> Statement<'a> {
 			Statement::Update(v) => v.data.as_ref(),
 			Statement::Relate(v) => v.data.as_ref(),
 			Statement::Insert(v) => v.update.as_ref(),
+			Statement::Upsert(v) => v.data.as_ref(),
 			_ => None,
 		}
 	}
@@ -202,6 +212,7 @@ impl<'a> Statement<'a> {
 			Statement::Relate(v) => v.output.as_ref(),
 			Statement::Delete(v) => v.output.as_ref(),
 			Statement::Insert(v) => v.output.as_ref(),
+			Statement::Upsert(v) => v.output.as_ref(),
 			_ => None,
 		}
 	}
@@ -216,6 +227,7 @@ impl<'a> Statement<'a> {
 			Statement::Relate(v) => v.parallel,
 			Statement::Delete(v) => v.parallel,
 			Statement::Insert(v) => v.parallel,
+			Statement::Upsert(v) => v.parallel,
 			_ => false,
 		}
 	}
@@ -227,4 +239,20 @@ impl<'a> Statement<'a> {
 			_ => None,
 		}
 	}
+	/// Returns whether the actual rows and elapsed time of each iterator are explained
+	#[inline]
+	pub fn analyze(&self) -> bool {
+		match self {
+			Statement::Select(v) => v.analyze,
+			_ => false,
+		}
+	}
+	/// Returns any PROFILE clause if specified
+	#[inline]
+	pub fn profile(&self) -> bool {
+		match self {
+			Statement::Select(v) => v.profile,
+			_ => false,
+		}
+	}
 }
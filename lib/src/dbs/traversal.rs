@@ -0,0 +1,629 @@
+use crate::ctx::Context;
+use crate::dbs::processor::edge_ranges;
+use crate::dbs::{Options, Transaction};
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::iam::Action;
+use crate::key::{graph, thing};
+use crate::sql::paths::ID;
+use crate::sql::statements::SelectStatement;
+use crate::sql::{
+	Cond, Depth, Dir, Edges, Expression, Field, Fields, Graph, Idiom, Operator, Part, Thing, Value,
+	Values,
+};
+use async_recursion::async_recursion;
+use std::collections::{HashMap, HashSet};
+
+/// A graph traversal, such as `->follows->user`, which can be evaluated from
+/// the graph keys, without fetching the edges and records it goes through,
+/// unless they are filtered by a condition
+pub(crate) struct Traversal<'a> {
+	/// The value the traversal starts from, or the current document
+	from: Option<&'a Value>,
+	/// The graph edges which are followed in order
+	hops: Vec<&'a Graph>,
+}
+
+impl<'a> Traversal<'a> {
+	/// Checks if the idiom only consists of graph traversals, which may be filtered
+	pub(crate) fn new(i: &'a Idiom) -> Option<Self> {
+		let (from, parts) = match i.first() {
+			Some(Part::Start(v)) => (Some(v), &i[1..]),
+			_ => (None, &i[..]),
+		};
+		let mut hops = Vec::with_capacity(parts.len());
+		for p in parts {
+			match p {
+				Part::Graph(g) if Self::is_plain(g) => hops.push(g),
+				_ => return None,
+			}
+		}
+		if hops.is_empty() {
+			return None;
+		}
+		Some(Self {
+			from,
+			hops,
+		})
+	}
+
+	/// The number of graph edges which are followed
+	pub(crate) fn hops(&self) -> usize {
+		self.hops.len()
+	}
+
+	/// Recursive edges are only followed when collecting paths
+	fn is_recursive(&self) -> bool {
+		self.hops.iter().any(|g| g.depth.is_some())
+	}
+
+	/// Aliased or projected edges have to be fetched
+	fn is_plain(g: &Graph) -> bool {
+		g.alias.is_none()
+			&& g.split.is_none()
+			&& g.group.is_none()
+			&& g.order.is_none()
+			&& g.limit.is_none()
+			&& g.start.is_none()
+	}
+
+	/// Counts the records reached by the traversal, as the number of items of
+	/// the array it evaluates to. Returns `None` if the traversal can not be
+	/// evaluated from the graph keys, and has to be computed instead.
+	pub(crate) async fn count(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&CursorDoc<'_>>,
+	) -> Result<Option<u64>, Error> {
+		self.walk(ctx, opt, txn, doc, u64::MAX).await
+	}
+
+	/// Checks if the traversal reaches at least one record, stopping at the
+	/// first one. Returns `None` if the traversal has to be computed instead.
+	pub(crate) async fn exists(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&CursorDoc<'_>>,
+	) -> Result<Option<bool>, Error> {
+		Ok(self.walk(ctx, opt, txn, doc, 1).await?.map(|n| n > 0))
+	}
+
+	/// Collects the paths followed by the traversal, each as the array of the
+	/// record it starts from, and of the edges and records it goes through.
+	pub(crate) async fn paths(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		let mut paths = Vec::new();
+		if let Some(from) = self.start(ctx, opt, txn, doc).await? {
+			collect_paths(ctx, opt, txn, vec![from], &self.hops, &mut paths).await?;
+		}
+		Ok(paths
+			.into_iter()
+			.map(|p| Value::from(p.into_iter().map(Value::Thing).collect::<Vec<_>>()))
+			.collect::<Vec<_>>()
+			.into())
+	}
+
+	/// Collects the records reached by the traversal, hop by hop, looking up
+	/// the next hop of all of the records reached by the previous hop at once,
+	/// without fetching the edges and records in between. Returns `None` if the
+	/// traversal can not be evaluated from the graph keys, and has to be computed.
+	pub(crate) async fn records(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&CursorDoc<'_>>,
+	) -> Result<Option<Value>, Error> {
+		// Permissions may hide some of the edges
+		if opt.check_perms(Action::View) || self.is_recursive() {
+			return Ok(None);
+		}
+		let Some(from) = self.start(ctx, opt, txn, doc).await? else {
+			return Ok(None);
+		};
+		let mut ids = vec![from];
+		for g in self.hops.iter() {
+			if ids.is_empty() {
+				break;
+			}
+			ids = next_hop(ctx, opt, txn, g, ids).await?;
+		}
+		Ok(Some(ids.into_iter().map(Value::Thing).collect::<Vec<_>>().into()))
+	}
+
+	async fn walk(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&CursorDoc<'_>>,
+		max: u64,
+	) -> Result<Option<u64>, Error> {
+		// Permissions may hide some of the edges
+		if opt.check_perms(Action::View) || self.is_recursive() {
+			return Ok(None);
+		}
+		match self.start(ctx, opt, txn, doc).await? {
+			Some(from) => Ok(Some(count_paths(ctx, opt, txn, from, &self.hops, max).await?)),
+			None => Ok(None),
+		}
+	}
+
+	/// The record the traversal starts from, if any
+	async fn start(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&CursorDoc<'_>>,
+	) -> Result<Option<Thing>, Error> {
+		Ok(match self.from {
+			Some(v) => match v.compute(ctx, opt, txn, doc).await? {
+				Value::Thing(v) => Some(v),
+				_ => None,
+			},
+			None => match doc.map(|doc| doc.doc.as_ref()) {
+				Some(Value::Object(v)) => v.rid(),
+				_ => None,
+			},
+		})
+	}
+}
+
+/// Counts the paths following the graph edges from the record, up to `max`
+#[cfg_attr(not(target_arch = "wasm32"), async_recursion)]
+#[cfg_attr(target_arch = "wasm32", async_recursion(?Send))]
+async fn count_paths(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	from: Thing,
+	hops: &[&Graph],
+	max: u64,
+) -> Result<u64, Error> {
+	let Some((g, rest)) = hops.split_first() else {
+		return Ok(1);
+	};
+	// A single record is looked up, instead of scanning the edges
+	if let Some(rid) = target(g) {
+		let n = lookup(opt, txn, &from, g, rid).await?;
+		if n == 0 || rest.is_empty() {
+			return Ok(n.min(max));
+		}
+		let m = count_paths(ctx, opt, txn, rid.clone(), rest, max).await?;
+		return Ok((n * m).min(max));
+	}
+	let e = Edges {
+		from,
+		dir: g.dir.clone(),
+		what: g.what.clone(),
+	};
+	let mut n = 0;
+	for (beg, end) in edge_ranges(opt, txn, &e).await? {
+		let mut beg = beg;
+		loop {
+			// Check if the context is finished
+			if ctx.is_done() {
+				return Ok(n);
+			}
+			// Get the next batch of graph keys
+			let res = txn.lock().await.scan(beg.clone()..end.clone(), opt.batch).await?;
+			let Some((k, _)) = res.last() else {
+				break;
+			};
+			// Ready the next
+			beg = k.clone();
+			beg.push(0x00);
+			// The last edges are counted without being decoded, unless filtered
+			if rest.is_empty() && g.cond.is_none() {
+				n += (res.len() as u64).min(max - n);
+			} else {
+				for (k, _) in res.iter() {
+					let gra: graph::Graph = k.into();
+					let rid = Thing::from((gra.ft, gra.fk));
+					if !is_visible(ctx, opt, txn, g, &rid, &gra.eg).await? {
+						continue;
+					}
+					n += match rest.is_empty() {
+						true => 1,
+						false => count_paths(ctx, opt, txn, rid, rest, max - n).await?,
+					};
+					if n >= max {
+						break;
+					}
+				}
+			}
+			if n >= max {
+				return Ok(n);
+			}
+		}
+	}
+	Ok(n)
+}
+
+/// Collects the paths following the graph edges from the last record of the path
+#[cfg_attr(not(target_arch = "wasm32"), async_recursion)]
+#[cfg_attr(target_arch = "wasm32", async_recursion(?Send))]
+async fn collect_paths(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	path: Vec<Thing>,
+	hops: &[&Graph],
+	paths: &mut Vec<Vec<Thing>>,
+) -> Result<(), Error> {
+	let Some((g, rest)) = hops.split_first() else {
+		paths.push(path);
+		return Ok(());
+	};
+	// A recursive edge is followed together with the record hop after it
+	if let Some(depth) = &g.depth {
+		let (node, rest) = match rest.split_first() {
+			Some((n, r)) => (Some(*n), r),
+			None => (None, rest),
+		};
+		let (edge, node) = step(g, node);
+		return collect_recursive(ctx, opt, txn, path, &[&edge, &node], depth, 0, rest, paths)
+			.await;
+	}
+	// A single record is looked up, instead of scanning the edges
+	if let Some(rid) = target(g) {
+		let n = lookup(opt, txn, &path[path.len() - 1], g, rid).await?;
+		if n > 0
+			&& (!opt.check_perms(Action::View) || is_visible(ctx, opt, txn, g, rid, &g.dir).await?)
+		{
+			for _ in 0..n {
+				let mut path = path.clone();
+				path.push(rid.clone());
+				collect_paths(ctx, opt, txn, path, rest, paths).await?;
+			}
+		}
+		return Ok(());
+	}
+	let e = Edges {
+		from: path[path.len() - 1].clone(),
+		dir: g.dir.clone(),
+		what: g.what.clone(),
+	};
+	for (beg, end) in edge_ranges(opt, txn, &e).await? {
+		let mut beg = beg;
+		loop {
+			// Check if the context is finished
+			if ctx.is_done() {
+				return Ok(());
+			}
+			// Get the next batch of graph keys
+			let res = txn.lock().await.scan(beg.clone()..end.clone(), opt.batch).await?;
+			let Some((k, _)) = res.last() else {
+				break;
+			};
+			// Ready the next
+			beg = k.clone();
+			beg.push(0x00);
+			// Extend the path with each record reached
+			for (k, _) in res.iter() {
+				let gra: graph::Graph = k.into();
+				let rid = Thing::from((gra.ft, gra.fk));
+				if !is_visible(ctx, opt, txn, g, &rid, &gra.eg).await? {
+					continue;
+				}
+				let mut path = path.clone();
+				path.push(rid);
+				collect_paths(ctx, opt, txn, path, rest, paths).await?;
+			}
+		}
+	}
+	Ok(())
+}
+
+/// Collects the paths repeating the step of a recursive edge from the last record of
+/// the path, between the minimum and maximum number of times, and then following the
+/// remaining graph edges. A path never returns to a record it already went through.
+#[cfg_attr(not(target_arch = "wasm32"), async_recursion)]
+#[cfg_attr(target_arch = "wasm32", async_recursion(?Send))]
+#[allow(clippy::too_many_arguments)]
+async fn collect_recursive(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	path: Vec<Thing>,
+	step: &[&Graph],
+	depth: &Depth,
+	n: u32,
+	rest: &[&Graph],
+	paths: &mut Vec<Vec<Thing>>,
+) -> Result<(), Error> {
+	if n >= depth.min {
+		collect_paths(ctx, opt, txn, path.clone(), rest, paths).await?;
+	}
+	if n >= depth.max {
+		return Ok(());
+	}
+	let mut next = Vec::new();
+	collect_paths(ctx, opt, txn, path.clone(), step, &mut next).await?;
+	for p in next {
+		// Skip the paths which return to a record already on the path
+		if path.contains(&p[p.len() - 1]) {
+			continue;
+		}
+		collect_recursive(ctx, opt, txn, p, step, depth, n + 1, rest, paths).await?;
+	}
+	Ok(())
+}
+
+/// Follows a recursive edge, together with the record hop after it, from a record,
+/// between the minimum and maximum number of times. Each record is returned once,
+/// at the shallowest depth at which it is reached, and the record the traversal
+/// starts from is never returned, so that cycles in the graph are not followed.
+pub(crate) async fn recurse(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	from: Thing,
+	g: &Graph,
+	node: Option<&Graph>,
+) -> Result<Vec<Thing>, Error> {
+	let Some(depth) = &g.depth else {
+		return Ok(vec![]);
+	};
+	let (edge, node) = step(g, node);
+	let step = [Part::Graph(edge), Part::Graph(node)];
+	let mut seen = HashSet::from([from.clone()]);
+	let mut frontier = vec![from];
+	let mut out = Vec::new();
+	for n in 1..=depth.max {
+		// Check if the context is finished
+		if frontier.is_empty() || ctx.is_done() {
+			break;
+		}
+		let mut next = Vec::new();
+		for rid in frontier {
+			if let Value::Array(v) =
+				Value::Thing(rid).get(ctx, opt, txn, None, &step).await?.flatten()
+			{
+				for v in v {
+					if let Value::Thing(v) = v {
+						if seen.insert(v.clone()) {
+							next.push(v);
+						}
+					}
+				}
+			}
+		}
+		if n >= depth.min {
+			out.extend(next.iter().cloned());
+		}
+		frontier = next;
+	}
+	Ok(out)
+}
+
+/// The edge of a recursive graph traversal, and the record hop after it, which
+/// reaches records of any table if the edge is not followed by a record hop
+fn step(g: &Graph, node: Option<&Graph>) -> (Graph, Graph) {
+	let edge = Graph {
+		depth: None,
+		..g.clone()
+	};
+	let node = match node {
+		Some(n) => n.clone(),
+		None => Graph {
+			dir: g.dir.clone(),
+			expr: Fields::all(),
+			..Graph::default()
+		},
+	};
+	(edge, node)
+}
+
+/// Looks up the records reached by a graph edge from each of the records, in
+/// order. The edges of each distinct record are only scanned once, and the
+/// records reached are filtered by the condition of the edge all at once.
+async fn next_hop(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	g: &Graph,
+	from: Vec<Thing>,
+) -> Result<Vec<Thing>, Error> {
+	let mut out = Vec::new();
+	// A single record is looked up, instead of scanning the edges
+	if let Some(rid) = target(g) {
+		let mut seen: HashMap<Thing, u64> = HashMap::new();
+		for f in from {
+			let n = match seen.get(&f) {
+				Some(n) => *n,
+				None => {
+					let n = lookup(opt, txn, &f, g, rid).await?;
+					seen.insert(f, n);
+					n
+				}
+			};
+			for _ in 0..n {
+				out.push(rid.clone());
+			}
+		}
+		return Ok(out);
+	}
+	let mut seen: HashMap<Thing, Vec<(Thing, Dir)>> = HashMap::new();
+	let mut hops = Vec::with_capacity(from.len());
+	for f in from {
+		if !seen.contains_key(&f) {
+			let e = Edges {
+				from: f.clone(),
+				dir: g.dir.clone(),
+				what: g.what.clone(),
+			};
+			let mut next = Vec::new();
+			for (beg, end) in edge_ranges(opt, txn, &e).await? {
+				let mut beg = beg;
+				loop {
+					// Check if the context is finished
+					if ctx.is_done() {
+						return Ok(out);
+					}
+					// Get the next batch of graph keys
+					let res = txn.lock().await.scan(beg.clone()..end.clone(), opt.batch).await?;
+					let Some((k, _)) = res.last() else {
+						break;
+					};
+					// Ready the next
+					beg = k.clone();
+					beg.push(0x00);
+					// Collect each record reached
+					for (k, _) in res.iter() {
+						let gra: graph::Graph = k.into();
+						next.push((Thing::from((gra.ft, gra.fk)), gra.eg));
+					}
+				}
+			}
+			seen.insert(f.clone(), next);
+		}
+		hops.push(f);
+	}
+	// Filter the records reached by the condition of the edge
+	let visible = match (&g.cond, &g.dir) {
+		(None, _) => None,
+		// The direction of each edge is exposed to the condition
+		(Some(_), Dir::Both) => {
+			let mut visible = HashSet::new();
+			for (rid, eg) in seen.values().flatten() {
+				if is_visible(ctx, opt, txn, g, rid, eg).await? {
+					visible.insert((rid.clone(), eg.clone()));
+				}
+			}
+			Some(visible)
+		}
+		// The records are all checked by a single statement
+		(Some(_), dir) => {
+			let rids = seen
+				.values()
+				.flatten()
+				.map(|(rid, _)| rid.clone())
+				.collect::<HashSet<_>>()
+				.into_iter()
+				.map(Value::Thing)
+				.collect::<Vec<_>>();
+			let mut ctx = Context::new(ctx);
+			ctx.add_value(
+				"direction",
+				Value::from(match dir {
+					Dir::In => "in",
+					_ => "out",
+				}),
+			);
+			let stm = SelectStatement {
+				expr: Fields(vec![Field::All], false),
+				what: Values(rids),
+				cond: g.cond.clone(),
+				..SelectStatement::default()
+			};
+			let res = stm.compute(&ctx, opt, txn, None).await?;
+			let res = match res {
+				Value::Array(v) => v.0,
+				_ => vec![],
+			};
+			Some(
+				res.iter()
+					.filter_map(|v| match v {
+						Value::Object(v) => v.rid().map(|rid| (rid, dir.clone())),
+						_ => None,
+					})
+					.collect(),
+			)
+		}
+	};
+	// Replay the records reached from each record, in order
+	for f in hops {
+		for (rid, eg) in seen[&f].iter() {
+			if let Some(visible) = &visible {
+				if !visible.contains(&(rid.clone(), eg.clone())) {
+					continue;
+				}
+			}
+			out.push(rid.clone());
+		}
+	}
+	Ok(out)
+}
+
+/// Checks if a record reached by a graph edge can be viewed, and matches the condition of the edge
+async fn is_visible(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	g: &Graph,
+	rid: &Thing,
+	eg: &Dir,
+) -> Result<bool, Error> {
+	if g.cond.is_none() && !opt.check_perms(Action::View) {
+		return Ok(true);
+	}
+	// Expose the direction of the edge as $direction
+	let mut ctx = Context::new(ctx);
+	ctx.add_value(
+		"direction",
+		Value::from(match eg {
+			Dir::In => "in",
+			_ => "out",
+		}),
+	);
+	let stm = SelectStatement {
+		expr: Fields(vec![Field::All], false),
+		what: Values(vec![Value::Thing(rid.clone())]),
+		cond: g.cond.clone(),
+		..SelectStatement::default()
+	};
+	Ok(matches!(stm.compute(&ctx, opt, txn, None).await?, Value::Array(a) if !a.is_empty()))
+}
+
+/// The single record reached by a graph edge, such as `->user:tobie`,
+/// whose condition only matches the id of a record of its tables
+fn target(g: &Graph) -> Option<&Thing> {
+	let Some(Cond(Value::Expression(e))) = &g.cond else {
+		return None;
+	};
+	match e.as_ref() {
+		Expression::Binary {
+			l: Value::Idiom(i),
+			o: Operator::Equal | Operator::Exact,
+			r: Value::Thing(t),
+		} if i.0 == ID.as_ref() && (g.what.is_empty() || g.what.iter().any(|w| w.0 == t.tb)) => Some(t),
+		_ => None,
+	}
+}
+
+/// Counts the edges between a record and an existing record, looking up their graph keys
+async fn lookup(
+	opt: &Options,
+	txn: &Transaction,
+	from: &Thing,
+	g: &Graph,
+	rid: &Thing,
+) -> Result<u64, Error> {
+	let dirs = match g.dir {
+		Dir::Both => vec![Dir::In, Dir::Out],
+		ref d => vec![d.clone()],
+	};
+	let mut run = txn.lock().await;
+	// The condition can only match a record which exists
+	if !run.exi(thing::new(opt.ns(), opt.db(), &rid.tb, &rid.id)).await? {
+		return Ok(0);
+	}
+	let mut n = 0;
+	for dir in dirs.iter() {
+		if run.exi(graph::new(opt.ns(), opt.db(), &from.tb, &from.id, dir, rid)).await? {
+			n += 1;
+		}
+	}
+	Ok(n)
+}
@@ -0,0 +1,298 @@
+use crate::ctx::Context;
+use crate::dbs::{Options, Transaction};
+use crate::err::Error;
+use crate::sql::array::Array;
+use crate::sql::field::{Field, Fields};
+use crate::sql::number::Number;
+use crate::sql::order::Orders;
+use crate::sql::part::Part;
+use crate::sql::value::Value;
+use crate::sql::window::Window;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// The inputs of a window function which were computed for a record
+struct Row {
+	/// The index of the record in the results
+	index: usize,
+	/// The computed function arguments
+	args: Value,
+	/// The computed values of the ORDER BY clause
+	order: Array,
+}
+
+/// Computes the window functions of a statement, once all of the records
+/// of the statement have been processed. Each record holds the inputs of
+/// each window function, which were computed against the record when it
+/// was processed, and which are replaced with the result of the function.
+pub(crate) async fn compute(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	fields: &Fields,
+	results: &mut [Value],
+) -> Result<(), Error> {
+	for field in fields.other() {
+		if let Field::Window {
+			expr,
+			alias,
+		} = field
+		{
+			let name = alias.clone().unwrap_or_else(|| expr.to_idiom());
+			// A single VALUE field is output as the whole record
+			let path: &[Part] = match fields.single() {
+				Some(_) => &[],
+				None => &name,
+			};
+			// Collect the records into their partitions
+			let mut partitions: HashMap<Array, Vec<Row>> = HashMap::new();
+			for (index, res) in results.iter().enumerate() {
+				let mut inputs = match res.pick(path) {
+					Value::Array(v) => v.0.into_iter(),
+					_ => continue,
+				};
+				let (args, partition, order) = (inputs.next(), inputs.next(), inputs.next());
+				let (Some(args), Some(Value::Array(partition)), Some(Value::Array(order))) =
+					(args, partition, order)
+				else {
+					continue;
+				};
+				partitions.entry(partition).or_default().push(Row {
+					index,
+					args,
+					order,
+				});
+			}
+			// Compute the window function over each partition
+			for (_, mut rows) in partitions {
+				if let Some(orders) = &expr.order {
+					rows.sort_by(|a, b| compare(&a.order, &b.order, orders));
+				}
+				for (index, val) in window(ctx, opt, txn, expr, &rows).await? {
+					results[index].put(path, val);
+				}
+			}
+		}
+	}
+	Ok(())
+}
+
+/// Compute a window function over the ordered rows of a partition
+async fn window(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	expr: &Window,
+	rows: &[Row],
+) -> Result<Vec<(usize, Value)>, Error> {
+	let mut out = Vec::with_capacity(rows.len());
+	// Find the position at which each group of peers ends,
+	// where peers are rows with the same ORDER BY values
+	let ends = peers(rows, expr.order.as_ref());
+	match expr.func.name() {
+		"row_number" => {
+			check(expr, 0, 0)?;
+			for (i, r) in rows.iter().enumerate() {
+				out.push((r.index, Value::from(i as i64 + 1)));
+			}
+		}
+		"rank" => {
+			check(expr, 0, 0)?;
+			let mut start = 0;
+			for (i, r) in rows.iter().enumerate() {
+				if i > 0 && ends[i] != ends[i - 1] {
+					start = i;
+				}
+				out.push((r.index, Value::from(start as i64 + 1)));
+			}
+		}
+		"dense_rank" => {
+			check(expr, 0, 0)?;
+			let mut rank: i64 = 1;
+			for (i, r) in rows.iter().enumerate() {
+				if i > 0 && ends[i] != ends[i - 1] {
+					rank += 1;
+				}
+				out.push((r.index, Value::from(rank)));
+			}
+		}
+		name @ ("lag" | "lead") => {
+			check(expr, 1, 3)?;
+			for (i, r) in rows.iter().enumerate() {
+				let args = match &r.args {
+					Value::Array(v) => v.as_slice(),
+					_ => &[],
+				};
+				// The offset of the row to output
+				let offset = match args.get(1) {
+					Some(v) => match v.clone().coerce_to_u64() {
+						Ok(v) => v as usize,
+						Err(_) => {
+							return Err(Error::InvalidArguments {
+								name: name.to_owned(),
+								message: String::from(
+									"The second argument must be a positive integer.",
+								),
+							})
+						}
+					},
+					None => 1,
+				};
+				// The row which is offset from the current row
+				let row = match name {
+					"lag" => i.checked_sub(offset),
+					_ => i.checked_add(offset),
+				};
+				let val = match row.and_then(|j| rows.get(j)) {
+					Some(Row {
+						args: Value::Array(v),
+						..
+					}) => v.first().cloned().unwrap_or_default(),
+					_ => args.get(2).cloned().unwrap_or_default(),
+				};
+				out.push((r.index, val));
+			}
+		}
+		// Compute the aggregate over the rows up to the last peer of
+		// each row, or over the whole partition without an ORDER BY,
+		// adding each group of peers to the frame in turn
+		name => {
+			let mut running = Running::new(name);
+			let mut frame = Vec::with_capacity(rows.len());
+			let mut i = 0;
+			while i < rows.len() {
+				let end = ends[i];
+				for r in &rows[i..end] {
+					// Aggregate the values of the frame as they are added
+					if running.as_mut().is_some_and(|v| !v.push(&r.args)) {
+						running = None;
+					}
+					frame.push(r.args.clone());
+				}
+				let val = match &running {
+					Some(v) => v.output(),
+					None => {
+						let vals = Value::from(frame.clone());
+						expr.func.aggregate(vals).compute(ctx, opt, txn, None).await?
+					}
+				};
+				for r in &rows[i..end] {
+					out.push((r.index, val.clone()));
+				}
+				i = end;
+			}
+		}
+	}
+	Ok(out)
+}
+
+/// The running result of an aggregate function, which is updated as each
+/// row is added to the window frame, so that the aggregate is not computed
+/// again over all of the rows of the frame for each row of the partition
+enum Running {
+	Count(i64),
+	Sum(Number),
+	Min(Option<Number>),
+	Max(Option<Number>),
+	Mean(f64, i64),
+}
+
+impl Running {
+	/// The running result of an aggregate function, if it can be computed incrementally
+	fn new(name: &str) -> Option<Self> {
+		match name {
+			"count" => Some(Self::Count(0)),
+			"math::sum" => Some(Self::Sum(Number::Int(0))),
+			"math::min" => Some(Self::Min(None)),
+			"math::max" => Some(Self::Max(None)),
+			"math::mean" => Some(Self::Mean(0.0, 0)),
+			_ => None,
+		}
+	}
+
+	/// Add a value to the frame, returning false if the value
+	/// can only be aggregated along with the whole frame
+	fn push(&mut self, val: &Value) -> bool {
+		match (self, val) {
+			(Self::Count(c), v) => *c += v.is_truthy() as i64,
+			(Self::Sum(s), Value::Number(n)) => *s = s.clone() + n.clone(),
+			(Self::Min(m), Value::Number(n)) => {
+				if m.as_ref().map_or(true, |m| n < m) {
+					*m = Some(n.clone());
+				}
+			}
+			(Self::Max(m), Value::Number(n)) => {
+				if m.as_ref().map_or(true, |m| n > m) {
+					*m = Some(n.clone());
+				}
+			}
+			(Self::Mean(s, c), Value::Number(n)) => {
+				*s += n.to_float();
+				*c += 1;
+			}
+			_ => return false,
+		}
+		true
+	}
+
+	/// The result of the aggregate function over the current frame
+	fn output(&self) -> Value {
+		match self {
+			Self::Count(c) => Value::from(*c),
+			Self::Sum(s) => Value::from(s.clone()),
+			Self::Min(m) | Self::Max(m) => m.clone().map(Value::from).unwrap_or_default(),
+			Self::Mean(s, c) => Value::from(*s / *c as f64),
+		}
+	}
+}
+
+/// Find the position at which the group of peers of each row ends
+fn peers(rows: &[Row], orders: Option<&Orders>) -> Vec<usize> {
+	let mut ends = vec![rows.len(); rows.len()];
+	if let Some(orders) = orders {
+		let mut start = 0;
+		for i in 1..=rows.len() {
+			if i == rows.len() || compare(&rows[i - 1].order, &rows[i].order, orders).is_ne() {
+				ends[start..i].fill(i);
+				start = i;
+			}
+		}
+	}
+	ends
+}
+
+/// Compare the ORDER BY values of two rows
+fn compare(a: &Array, b: &Array, orders: &Orders) -> Ordering {
+	for (i, order) in orders.iter().enumerate() {
+		// Random ordering does not order the rows
+		if order.random {
+			continue;
+		}
+		let (x, y) = (a.get(i).unwrap_or(&Value::None), b.get(i).unwrap_or(&Value::None));
+		// Reverse the ordering if DESC
+		let o = match order.direction {
+			true => x.compare(y, &[], order.collate, order.numeric),
+			false => y.compare(x, &[], order.collate, order.numeric),
+		};
+		match o {
+			Some(Ordering::Equal) | None => continue,
+			Some(o) => return o,
+		}
+	}
+	Ordering::Equal
+}
+
+/// Check the number of arguments of a window function
+fn check(expr: &Window, min: usize, max: usize) -> Result<(), Error> {
+	let len = expr.func.args().len();
+	if len < min || len > max {
+		return Err(Error::InvalidArguments {
+			name: expr.func.name().to_owned(),
+			message: match (min, max) {
+				(0, 0) => String::from("Expected no arguments."),
+				(min, max) => format!("Expected {min} to {max} arguments."),
+			},
+		});
+	}
+	Ok(())
+}
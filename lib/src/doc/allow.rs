@@ -1,5 +1,6 @@
 use crate::ctx::Context;
 use crate::dbs::Statement;
+use crate::dbs::{denied, permitted};
 use crate::dbs::{Options, Transaction};
 use crate::doc::Document;
 use crate::err::Error;
@@ -20,27 +21,36 @@ impl<'a> Document<'a> {
 				// Get the table
 				let tb = self.tb(opt, txn).await?;
 				// Get the permission clause
-				let perms = if stm.is_delete() {
-					&tb.permissions.delete
+				let (action, perms) = if stm.is_delete() {
+					("delete", &tb.permissions.delete)
 				} else if stm.is_select() {
-					&tb.permissions.select
+					("select", &tb.permissions.select)
 				} else if self.is_new() {
-					&tb.permissions.create
+					("create", &tb.permissions.create)
 				} else {
-					&tb.permissions.update
+					("update", &tb.permissions.update)
 				};
 				// Process the table permissions
-				match perms {
-					Permission::None => return Err(Error::Ignore),
-					Permission::Full => return Ok(()),
+				let allowed = match perms {
+					Permission::None => false,
+					Permission::Full => true,
 					Permission::Specific(e) => {
 						// Disable permissions
 						let opt = &opt.new_with_perms(false);
 						// Process the PERMISSION clause
-						if !e.compute(ctx, opt, txn, Some(&self.current)).await?.is_truthy() {
-							return Err(Error::Ignore);
-						}
+						permitted(ctx, opt, txn, e, &self.current).await?
 					}
+				};
+				// Explain the denial if debugging permissions
+				if !allowed {
+					if opt.debug_perms {
+						let opt = &opt.new_with_perms(false);
+						let target = format!("the `{}` table", tb.name);
+						return Err(
+							denied(ctx, opt, txn, perms, target, action, &self.current).await?
+						);
+					}
+					return Err(Error::Ignore);
 				}
 			}
 		}
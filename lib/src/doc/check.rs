@@ -1,8 +1,10 @@
 use crate::ctx::Context;
 use crate::dbs::Statement;
-use crate::dbs::{Options, Transaction};
+use crate::dbs::{Options, Transaction, Traversal};
 use crate::doc::Document;
 use crate::err::Error;
+use crate::sql::{Cond, Value};
+use trice::Instant;
 
 impl<'a> Document<'a> {
 	pub async fn check(
@@ -14,8 +16,14 @@ impl<'a> Document<'a> {
 	) -> Result<(), Error> {
 		// Check where condition
 		if let Some(cond) = stm.conds() {
-			// Check if the expression is truthy
-			if !cond.compute(ctx, opt, txn, Some(&self.current)).await?.is_truthy() {
+			// Measure the condition if the statement is profiled
+			let now = Instant::now();
+			// Check if the condition matches
+			let matched = self.matches(ctx, opt, txn, cond).await?;
+			if let Some(pr) = ctx.get_profiler() {
+				pr.filtered(matched, now.elapsed());
+			}
+			if !matched {
 				// Ignore this document
 				return Err(Error::Ignore);
 			}
@@ -23,4 +31,23 @@ impl<'a> Document<'a> {
 		// Carry on
 		Ok(())
 	}
+
+	async fn matches(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		cond: &Cond,
+	) -> Result<bool, Error> {
+		// A graph traversal only needs to reach a single record
+		if let Value::Idiom(i) = &cond.0 {
+			if let Some(t) = Traversal::new(i) {
+				if let Some(v) = t.exists(ctx, opt, txn, Some(&self.current)).await? {
+					return Ok(v);
+				}
+			}
+		}
+		// Check if the expression is truthy
+		Ok(cond.compute(ctx, opt, txn, Some(&self.current)).await?.is_truthy())
+	}
 }
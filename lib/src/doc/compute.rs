@@ -18,6 +18,15 @@ impl<'a> Document<'a> {
 		chn: Sender<Result<Value, Error>>,
 		mut pro: Processed,
 	) -> Result<(), Error> {
+		// Expose the direction of an iterated edge as $direction
+		let edge;
+		let ctx = match &pro.dir {
+			Some(dir) => {
+				edge = Document::edge_context(ctx, dir);
+				&edge
+			}
+			None => ctx,
+		};
 		// Loop over maximum two times
 		for _ in 0..2 {
 			// Check current context
@@ -25,11 +34,15 @@ impl<'a> Document<'a> {
 				break;
 			}
 			// Setup a new workable
-			let ins = match pro.val {
+			let mut ins = match pro.val {
 				Operable::Value(v) => (v, Workable::Normal),
 				Operable::Mergeable(v, o) => (v, Workable::Insert(o)),
 				Operable::Relatable(f, v, w) => (v, Workable::Relate(f, w)),
 			};
+			// Resolve the values of any deduplicated fields
+			if let Some(rid) = &pro.rid {
+				crate::doc::resolve(opt, txn, rid, &mut ins.0).await?;
+			}
 			// Setup a new document
 			let mut doc = Document::new(pro.ir, pro.rid.as_ref(), pro.doc_id, &ins.0, ins.1);
 			// Process the statement
@@ -40,6 +53,7 @@ impl<'a> Document<'a> {
 				Statement::Relate(_) => doc.relate(ctx, opt, txn, stm).await,
 				Statement::Delete(_) => doc.delete(ctx, opt, txn, stm).await,
 				Statement::Insert(_) => doc.insert(ctx, opt, txn, stm).await,
+				Statement::Upsert(_) => doc.upsert(ctx, opt, txn, stm).await,
 				_ => unreachable!(),
 			};
 			// Check the result
@@ -59,6 +73,7 @@ impl<'a> Document<'a> {
 					pro = Processed {
 						ir: None,
 						doc_id: None,
+						dir: None,
 						rid: Some(v),
 						val: match doc.extras {
 							Workable::Normal => Operable::Value(val),
@@ -0,0 +1,287 @@
+use crate::cnf::{DEDUP_MIN_SIZE, PROCESSOR_BATCH_SIZE};
+use crate::dbs::{Options, Transaction};
+use crate::doc::Document;
+use crate::err::Error;
+use crate::sql::id::Id;
+use crate::sql::idiom::Idiom;
+use crate::sql::part::Part;
+use crate::sql::statements::DefineFieldStatement;
+use crate::sql::thing::Thing;
+use crate::sql::value::Value;
+use derive::Store;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+
+/// The field of the object which refers to a deduplicated value
+const REF: &str = "$dedup";
+
+/// A value which is stored once for all of the records which hold it. Each
+/// record which holds the value stores its own reference key to the value,
+/// so that concurrent writes of records which hold the same value do not
+/// all update a single count of the records which hold it.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store)]
+#[revisioned(revision = 1)]
+struct Content {
+	/// The value which is held by the records
+	value: Value,
+}
+
+impl<'a> Document<'a> {
+	/// Replaces the values of any DEDUP fields with a reference to the
+	/// hash of their content, which is stored once for all of the records
+	/// of the table, alongside a reference key for each of the records
+	/// which refer to it. Returns the value to store for this document,
+	/// if it was changed.
+	pub async fn dedup(
+		&self,
+		opt: &Options,
+		txn: &Transaction,
+		remove: bool,
+	) -> Result<Option<Value>, Error> {
+		// Get the record id
+		let rid = match self.id {
+			Some(rid) => rid,
+			None => return Ok(None),
+		};
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Get the deduplicated fields of the table
+		let fds = run.all_tb_fields(opt.ns(), opt.db(), &rid.tb).await?;
+		let fds = fds.iter().filter(|fd| deduplicated(fd)).collect::<Vec<_>>();
+		if fds.is_empty() {
+			return Ok(None);
+		}
+		// The values referred to by the stored record
+		let mut old: HashSet<String> = HashSet::new();
+		let key = crate::key::thing::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
+		if let Some(val) = run.get(key).await? {
+			let val = Value::from(val);
+			for fd in fds.iter() {
+				if let Some(hash) = reference(&val.pick(&fd.name)) {
+					old.insert(hash.to_owned());
+				}
+			}
+		}
+		// Replace the values held by the document with references
+		let mut new: HashMap<String, Value> = HashMap::new();
+		let val = match remove {
+			true => None,
+			false => {
+				let mut val = self.current.doc.as_ref().clone();
+				for fd in fds.iter() {
+					let v = val.pick(&fd.name);
+					if let Some(hash) = hash(&v) {
+						val.put(
+							&fd.name,
+							Value::from(map! { REF.to_owned() => Value::from(hash.clone()) }),
+						);
+						new.insert(hash, v);
+					}
+				}
+				Some(val)
+			}
+		};
+		// Refer to the values which the record now holds
+		for (hash, value) in new.iter() {
+			if old.contains(hash) {
+				continue;
+			}
+			let key = crate::key::table::cr::new(opt.ns(), opt.db(), &rid.tb, hash, &rid.id);
+			run.set(key, vec![]).await?;
+			// Store the value if no other record holds it
+			let key = crate::key::table::cs::new(opt.ns(), opt.db(), &rid.tb, hash);
+			if !run.exi(key.clone()).await? {
+				let value = value.clone();
+				run.set(
+					key,
+					Content {
+						value,
+					},
+				)
+				.await?;
+			}
+		}
+		// Release the values which the record no longer holds
+		for hash in old.iter() {
+			if new.contains_key(hash) {
+				continue;
+			}
+			release(&mut run, opt.ns(), opt.db(), &rid.tb, hash, &rid.id).await?;
+		}
+		// Carry on
+		Ok(val)
+	}
+}
+
+/// Replaces the references held by a field which is no longer deduplicated,
+/// after the field is removed, or is defined again without DEDUP, with the
+/// values which they refer to, releasing any values which are no longer held
+pub(crate) async fn restore(
+	run: &mut crate::kvs::Transaction,
+	ns: &str,
+	db: &str,
+	tb: &str,
+	fd: &Idiom,
+) -> Result<(), Error> {
+	// Get the other deduplicated fields of the table
+	let fds = run.all_tb_fields(ns, db, tb).await?;
+	let fds = fds.iter().filter(|v| deduplicated(v) && v.name != *fd).collect::<Vec<_>>();
+	// Rewrite the records of the table in batches
+	let mut beg = crate::key::thing::prefix(ns, db, tb);
+	let end = crate::key::thing::suffix(ns, db, tb);
+	loop {
+		let batch = run.scan(beg.clone()..end.clone(), PROCESSOR_BATCH_SIZE).await?;
+		let last = match batch.last() {
+			Some((k, _)) => k.clone(),
+			None => break,
+		};
+		for (k, v) in batch {
+			let mut val = Value::from(v);
+			let hash = match reference(&val.pick(fd)) {
+				Some(hash) => hash.to_owned(),
+				None => continue,
+			};
+			// Store the value in the record itself
+			let key = crate::key::table::cs::new(ns, db, tb, &hash);
+			if let Some(v) = run.get(key).await? {
+				val.put(fd, Content::from(v).value);
+			}
+			run.set(k.clone(), val.clone()).await?;
+			// Release the value, unless another field of the record refers to it
+			if !fds.iter().any(|v| reference(&val.pick(&v.name)) == Some(hash.as_str())) {
+				let id = crate::key::thing::Thing::decode(&k)?.id;
+				release(run, ns, db, tb, &hash, &id).await?;
+			}
+		}
+		// Continue after the last record of the batch
+		beg = last;
+		beg.push(0x00);
+	}
+	Ok(())
+}
+
+/// Removes the reference from a record to a value, and removes the
+/// value once there are no other records which refer to it
+async fn release(
+	run: &mut crate::kvs::Transaction,
+	ns: &str,
+	db: &str,
+	tb: &str,
+	hash: &str,
+	id: &Id,
+) -> Result<(), Error> {
+	run.del(crate::key::table::cr::new(ns, db, tb, hash, id)).await?;
+	let beg = crate::key::table::cr::prefix(ns, db, tb, hash);
+	let end = crate::key::table::cr::suffix(ns, db, tb, hash);
+	if run.scan(beg..end, 1).await?.is_empty() {
+		run.del(crate::key::table::cs::new(ns, db, tb, hash)).await?;
+	}
+	Ok(())
+}
+
+/// Replaces any references held by the DEDUP fields of a record, with
+/// the values which they refer to, before the record is processed
+pub(crate) async fn resolve(
+	opt: &Options,
+	txn: &Transaction,
+	rid: &Thing,
+	val: &mut Value,
+) -> Result<(), Error> {
+	resolve_with(&mut *txn.lock().await, opt.ns(), opt.db(), &rid.tb, val).await
+}
+
+/// Replaces any references held by the DEDUP fields of a record, using
+/// a transaction which is already claimed
+pub(crate) async fn resolve_with(
+	run: &mut crate::kvs::Transaction,
+	ns: &str,
+	db: &str,
+	tb: &str,
+	val: &mut Value,
+) -> Result<(), Error> {
+	// Check if there is a record
+	if !val.is_some() {
+		return Ok(());
+	}
+	// Get the deduplicated fields of the table
+	let fds = run.all_tb_fields(ns, db, tb).await?;
+	// Parent fields are resolved before their child fields
+	for fd in fds.iter().filter(|fd| deduplicated(fd)) {
+		if let Some(hash) = reference(&val.pick(&fd.name)) {
+			let key = crate::key::table::cs::new(ns, db, tb, hash);
+			if let Some(v) = run.get(key).await? {
+				val.put(&fd.name, Content::from(v).value);
+			}
+		}
+	}
+	Ok(())
+}
+
+/// Check if the values of a field are deduplicated. Only fields which
+/// refer to a single value of the record can be deduplicated.
+fn deduplicated(fd: &DefineFieldStatement) -> bool {
+	fd.dedup && fd.name.iter().all(|p| matches!(p, Part::Field(_)))
+}
+
+/// Get the hash which a value refers to, if it is a reference
+fn reference(val: &Value) -> Option<&str> {
+	match val {
+		Value::Object(v) if v.len() == 1 => match v.get(REF) {
+			Some(Value::Strand(v)) => Some(v.as_str()),
+			_ => None,
+		},
+		_ => None,
+	}
+}
+
+/// Get the hash of the content of a value, if the value is large enough
+/// to be deduplicated
+fn hash(val: &Value) -> Option<String> {
+	let enc: Vec<u8> = val.into();
+	if enc.len() < DEDUP_MIN_SIZE {
+		return None;
+	}
+	let mut hasher = Sha256::new();
+	hasher.update(&enc);
+	Some(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sql::test::Parse;
+
+	#[test]
+	fn hash_small_values() {
+		assert_eq!(hash(&Value::from("small")), None);
+		assert_eq!(hash(&Value::None), None);
+	}
+
+	#[test]
+	fn hash_large_values() {
+		let a = Value::parse(
+			"{ body: 'a large template which is held by many of the records of a table' }",
+		);
+		let b = Value::parse(
+			"{ body: 'a large template which is held by many of the records of a table' }",
+		);
+		let c = Value::parse(
+			"{ body: 'a large template which is held by one of the records of a table' }",
+		);
+		assert!(hash(&a).is_some());
+		assert_eq!(hash(&a), hash(&b));
+		assert_ne!(hash(&a), hash(&c));
+	}
+
+	#[test]
+	fn reference_values() {
+		let val = Value::parse("{ '$dedup': 'abc' }");
+		assert_eq!(reference(&val), Some("abc"));
+		let val = Value::parse("{ '$dedup': 'abc', other: true }");
+		assert_eq!(reference(&val), None);
+		let val = Value::parse("{ '$dedup': 123 }");
+		assert_eq!(reference(&val), None);
+	}
+}
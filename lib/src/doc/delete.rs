@@ -17,12 +17,16 @@ impl<'a> Document<'a> {
 		self.check(ctx, opt, txn, stm).await?;
 		// Check if allowed
 		self.allow(ctx, opt, txn, stm).await?;
+		// Check the record is not a member
+		self.member(opt, txn).await?;
 		// Erase document
 		self.erase(ctx, opt, stm).await?;
 		// Purge index data
 		self.index(ctx, opt, txn, stm).await?;
 		// Purge record data
 		self.purge(ctx, opt, txn, stm).await?;
+		// Run reference actions
+		self.referenced(ctx, opt, txn, stm).await?;
 		// Run table queries
 		self.table(ctx, opt, txn, stm).await?;
 		// Run lives queries
@@ -34,4 +38,20 @@ impl<'a> Document<'a> {
 		// Yield document
 		self.pluck(ctx, opt, txn, stm).await
 	}
+	/// Check that the record is not a member of an enumerated table
+	async fn member(&self, opt: &Options, txn: &Transaction) -> Result<(), Error> {
+		// Get the record id
+		let rid = self.id.as_ref().unwrap();
+		// Get the table definition
+		let tb = self.tb(opt, txn).await?;
+		// Members can only be removed by redefining the table
+		if tb.enumerated.is_some() && tb.allows_member(&rid.id) {
+			return Err(Error::TableMemberDelete {
+				thing: rid.to_string(),
+				table: rid.tb.to_owned(),
+			});
+		}
+		// Carry on
+		Ok(())
+	}
 }
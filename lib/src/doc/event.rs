@@ -44,9 +44,17 @@ impl<'a> Document<'a> {
 			let val = ev.when.compute(&ctx, opt, txn, Some(&self.current)).await?;
 			// Execute event if value is truthy
 			if val.is_truthy() {
-				for v in ev.then.iter() {
-					v.compute(&ctx, opt, txn, Some(&self.current)).await?;
+				// Coalesce the writes made by the event
+				txn.lock().await.coalesce(true);
+				let res = async {
+					for v in ev.then.iter() {
+						v.compute(&ctx, opt, txn, Some(&self.current)).await?;
+					}
+					Ok::<(), Error>(())
 				}
+				.await;
+				txn.lock().await.coalesce(false);
+				res?;
 			}
 		}
 		// Carry on
@@ -1,10 +1,14 @@
 use crate::ctx::Context;
 use crate::dbs::Statement;
+use crate::dbs::Workable;
+use crate::dbs::{denied, permitted};
 use crate::dbs::{Options, Transaction};
 use crate::doc::Document;
 use crate::err::Error;
 use crate::iam::Action;
+use crate::sql::paths::EDGE;
 use crate::sql::permission::Permission;
+use crate::sql::thing::Thing;
 use crate::sql::value::Value;
 
 impl<'a> Document<'a> {
@@ -21,6 +25,27 @@ impl<'a> Document<'a> {
 		}
 		// Get the record id
 		let rid = self.id.as_ref().unwrap();
+		// Check the table can hold this type of record
+		let relation = matches!(self.extras, Workable::Relate(_, _))
+			|| self.initial.doc.pick(&*EDGE).is_true();
+		let tb = self.tb(opt, txn).await?;
+		if !tb.kind.allows_relation(relation) {
+			return Err(Error::TableCheck {
+				thing: rid.to_string(),
+				relation,
+				target_type: match relation {
+					true => "normal record".to_owned(),
+					false => "relation".to_owned(),
+				},
+			});
+		}
+		// Check the table can hold this record id
+		if !tb.allows_member(&rid.id) {
+			return Err(Error::TableMember {
+				thing: rid.to_string(),
+				table: rid.tb.to_owned(),
+			});
+		}
 		// Get the user applied input
 		let inp = self.initial.doc.changed(self.current.doc.as_ref());
 		// Loop through all field statements
@@ -118,10 +143,10 @@ impl<'a> Document<'a> {
 				// Check for a PERMISSIONS clause
 				if opt.check_perms(Action::Edit) {
 					// Get the permission clause
-					let perms = if self.is_new() {
-						&fd.permissions.create
+					let (action, perms) = if self.is_new() {
+						("create", &fd.permissions.create)
 					} else {
-						&fd.permissions.update
+						("update", &fd.permissions.update)
 					};
 					// Match the permission clause
 					match perms {
@@ -132,7 +157,23 @@ impl<'a> Document<'a> {
 						// The field PERMISSIONS clause
 						// is NONE, meaning that this
 						// change will be reverted.
-						Permission::None => val = old,
+						Permission::None => {
+							// Explain the denial of a changed field if debugging permissions
+							if opt.debug_perms && val != old {
+								let target = format!("the `{}` field", fd.name);
+								return Err(denied(
+									ctx,
+									opt,
+									txn,
+									perms,
+									target,
+									action,
+									&self.current,
+								)
+								.await?);
+							}
+							val = old
+						}
 						// The field PERMISSIONS clause
 						// is a custom expression, so
 						// we check the expression and
@@ -147,7 +188,21 @@ impl<'a> Document<'a> {
 							ctx.add_value("after", &val);
 							ctx.add_value("before", &old);
 							// Process the PERMISSION clause
-							if !e.compute(&ctx, opt, txn, Some(&self.current)).await?.is_truthy() {
+							if !permitted(&ctx, opt, txn, e, &self.current).await? {
+								// Explain the denial of a changed field if debugging permissions
+								if opt.debug_perms && val != old {
+									let target = format!("the `{}` field", fd.name);
+									return Err(denied(
+										&ctx,
+										opt,
+										txn,
+										perms,
+										target,
+										action,
+										&self.current,
+									)
+									.await?);
+								}
 								val = old
 							}
 						}
@@ -160,7 +215,51 @@ impl<'a> Document<'a> {
 				};
 			}
 		}
+		// Check the records referenced by the document
+		self.members(opt, txn).await?;
 		// Carry on
 		Ok(())
 	}
+	/// Check that records referenced by the document are members of their enumerated tables
+	async fn members(&self, opt: &Options, txn: &Transaction) -> Result<(), Error> {
+		// Collect the previously referenced records
+		let mut old = vec![];
+		things(self.initial.doc.as_ref(), &mut old);
+		// Collect the currently referenced records
+		let mut refs = vec![];
+		things(self.current.doc.as_ref(), &mut refs);
+		// Check each newly referenced record
+		for v in refs.into_iter().filter(|v| !old.contains(v)) {
+			// Get the table definition
+			let tb = txn.lock().await.get_and_cache_tb(opt.ns(), opt.db(), &v.tb).await;
+			match tb {
+				// The table can't be enumerated
+				Err(Error::TbNotFound {
+					..
+				}) => continue,
+				// There was an error
+				Err(e) => return Err(e),
+				// The record must be a member of the table
+				Ok(tb) if !tb.allows_member(&v.id) => {
+					return Err(Error::TableMember {
+						thing: v.to_string(),
+						table: v.tb.to_owned(),
+					})
+				}
+				Ok(_) => continue,
+			}
+		}
+		// Carry on
+		Ok(())
+	}
+}
+
+/// Collects the records which are nested within a value
+pub(crate) fn things<'v>(val: &'v Value, out: &mut Vec<&'v Thing>) {
+	match val {
+		Value::Thing(v) => out.push(v),
+		Value::Array(v) => v.iter().for_each(|v| things(v, out)),
+		Value::Object(v) => v.values().for_each(|v| things(v, out)),
+		_ => (),
+	}
 }
@@ -4,10 +4,15 @@ use crate::dbs::{Options, Transaction};
 use crate::doc::{CursorDoc, Document};
 use crate::err::Error;
 use crate::idx::ft::FtIndex;
+use crate::idx::stats::{IndexStats, IndexStatsDelta};
+use crate::idx::trees::hnsw::HnswIndex;
+use crate::idx::trees::mtree::MTreeIndex;
+use crate::idx::trees::rtree::RTreeIndex;
 use crate::idx::trees::store::TreeStoreType;
 use crate::idx::IndexKeyBase;
+use crate::kvs::Key;
 use crate::sql::array::Array;
-use crate::sql::index::{Index, SearchParams};
+use crate::sql::index::{HnswParams, Index, MTreeParams, RTreeParams, SearchParams};
 use crate::sql::statements::DefineIndexStatement;
 use crate::sql::{Part, Thing, Value};
 use crate::{key, kvs};
@@ -51,16 +56,7 @@ impl<'a> Document<'a> {
 				let mut ic = IndexOperation::new(opt, ix, o, n, rid);
 
 				// Index operation dispatching
-				match &ix.index {
-					Index::Uniq => ic.index_unique(&mut run).await?,
-					Index::Idx => ic.index_non_unique(&mut run).await?,
-					Index::Search(p) => ic.index_full_text(&mut run, p).await?,
-					Index::MTree(_) => {
-						return Err(Error::FeatureNotYetImplemented {
-							feature: "MTree indexing".to_string(),
-						})
-					}
-				};
+				ic.compute(&mut run).await?;
 			}
 		}
 		// Carry on
@@ -68,6 +64,30 @@ impl<'a> Document<'a> {
 	}
 }
 
+/// Index an existing record for an index which is being built in the background.
+/// The statistics of the index are not updated, and instead the changes to the
+/// statistics are returned, so that the builder can apply them. The entries of a
+/// record which was written since the build started are replaced, as document
+/// writes maintain the entries of every record while the index is being built.
+pub(crate) async fn build_record(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	ix: &DefineIndexStatement,
+	rid: &Thing,
+	doc: &Value,
+) -> Result<IndexStatsDelta, Error> {
+	// Calculate the values
+	let n =
+		build_opt_values(ctx, opt, txn, ix, &CursorDoc::new(None, Some(rid), None, doc)).await?;
+	// Claim transaction
+	let mut run = txn.lock().await;
+	// Replace any entries of the record
+	let mut ic = IndexOperation::new(opt, ix, n.clone(), n, rid);
+	ic.entries(&mut run).await?;
+	Ok(ic.delta)
+}
+
 /// Extract from the given document, the values required by the index and put then in an array.
 /// Eg. IF the index is composed of the columns `name` and `instrument`
 /// Given this doc: { "id": 1, "instrument":"piano", "name":"Tobie" }
@@ -82,6 +102,12 @@ async fn build_opt_values(
 	if !doc.doc.is_some() {
 		return Ok(None);
 	}
+	// Documents which do not match the index condition are not indexed
+	if let Some(cond) = &ix.cond {
+		if !cond.compute(ctx, opt, txn, Some(doc)).await?.is_truthy() {
+			return Ok(None);
+		}
+	}
 	let mut o = Vec::with_capacity(ix.cols.len());
 	for i in ix.cols.iter() {
 		let v = i.compute(ctx, opt, txn, Some(doc)).await?;
@@ -219,6 +245,8 @@ struct IndexOperation<'a> {
 	/// The new values (if existing)
 	n: Option<Vec<Value>>,
 	rid: &'a Thing,
+	/// The changes applied to the statistics of the index
+	delta: IndexStatsDelta,
 }
 
 impl<'a> IndexOperation<'a> {
@@ -235,9 +263,59 @@ impl<'a> IndexOperation<'a> {
 			o,
 			n,
 			rid,
+			delta: IndexStatsDelta::default(),
 		}
 	}
 
+	async fn compute(&mut self, run: &mut kvs::Transaction) -> Result<(), Error> {
+		self.entries(run).await?;
+		// Maintain the statistics of the index
+		IndexStats::update(run, self.opt.ns(), self.opt.db(), self.ix, self.delta).await
+	}
+
+	/// Writes the index entries, without updating the statistics of the index
+	async fn entries(&mut self, run: &mut kvs::Transaction) -> Result<(), Error> {
+		let ix = self.ix;
+		match &ix.index {
+			Index::Uniq | Index::UniqNulls(_) => self.index_unique(run).await?,
+			Index::Idx => self.index_non_unique(run).await?,
+			Index::Search(p) => self.index_full_text(run, p).await?,
+			Index::MTree(p) => self.index_mtree(run, p).await?,
+			Index::Hnsw(p) => self.index_hnsw(run, p).await?,
+			Index::RTree(p) => self.index_rtree(run, p).await?,
+		}
+		Ok(())
+	}
+
+	/// Records that an entry has been added to the index
+	fn added(&mut self, key: &Key) {
+		self.delta.count += 1;
+		self.delta.size += key.len() as i64;
+	}
+
+	/// Records that an entry has been removed from the index
+	fn removed(&mut self, key: &Key) {
+		self.delta.count -= 1;
+		self.delta.size -= key.len() as i64;
+	}
+
+	/// Records whether the record gained or lost its entries in the index
+	fn indexed(&mut self, removed: bool, added: bool) {
+		self.delta.records += added as i64 - removed as i64;
+	}
+
+	/// Checks if any record is indexed with the given values
+	async fn has_entries(&self, run: &mut kvs::Transaction, v: &Array) -> Result<bool, Error> {
+		let (beg, end) = crate::key::index::Index::range_all_ids(
+			self.opt.ns(),
+			self.opt.db(),
+			&self.ix.what,
+			&self.ix.name,
+			v,
+		);
+		Ok(!run.scan(beg..end, 1).await?.is_empty())
+	}
+
 	fn get_unique_index_key(&self, v: &'a Array) -> key::index::Index {
 		crate::key::index::Index::new(
 			self.opt.ns(),
@@ -261,15 +339,25 @@ impl<'a> IndexOperation<'a> {
 	}
 
 	async fn index_unique(&mut self, run: &mut kvs::Transaction) -> Result<(), Error> {
+		let (mut removed, mut added) = (false, false);
 		// Delete the old index data
 		if let Some(o) = self.o.take() {
 			let i = Indexable::new(o, self.ix);
 			for o in i {
-				let key = self.get_unique_index_key(&o);
-				match run.delc(key, Some(self.rid)).await {
+				let o = self.ix.collated(&o);
+				let key: Key = self.get_unique_index_key(&o).into();
+				match run.delc(key.clone(), Some(self.rid)).await {
 					Err(Error::TxConditionNotMet) => Ok(()),
 					Err(e) => Err(e),
-					Ok(v) => Ok(v),
+					Ok(v) => {
+						self.removed(&key);
+						// Each entry of a unique index is a distinct value
+						if !o.is_all_none_or_null() {
+							self.delta.distinct -= 1;
+						}
+						removed = true;
+						Ok(v)
+					}
 				}?
 			}
 		}
@@ -277,30 +365,46 @@ impl<'a> IndexOperation<'a> {
 		if let Some(n) = self.n.take() {
 			let i = Indexable::new(n, self.ix);
 			for n in i {
-				if !n.is_all_none_or_null() {
-					let key = self.get_unique_index_key(&n);
-					if run.putc(key, self.rid, None).await.is_err() {
-						let key = self.get_unique_index_key(&n);
+				if !self.ix.index.skips_unique(&n) {
+					let c = self.ix.collated(&n);
+					let key: Key = self.get_unique_index_key(&c).into();
+					if run.putc(key.clone(), self.rid, None).await.is_err() {
 						let val = run.get(key).await?.unwrap();
 						let rid: Thing = val.into();
 						return self.err_index_exists(rid, n);
 					}
+					self.added(&key);
+					if !c.is_all_none_or_null() {
+						self.delta.distinct += 1;
+					}
+					added = true;
 				}
 			}
 		}
+		self.indexed(removed, added);
 		Ok(())
 	}
 
 	async fn index_non_unique(&mut self, run: &mut kvs::Transaction) -> Result<(), Error> {
+		let (mut removed, mut added) = (false, false);
 		// Delete the old index data
 		if let Some(o) = self.o.take() {
 			let i = Indexable::new(o, self.ix);
 			for o in i {
-				let key = self.get_non_unique_index_key(&o);
-				match run.delc(key, Some(self.rid)).await {
+				let o = self.ix.collated(&o);
+				let key: Key = self.get_non_unique_index_key(&o).into();
+				match run.delc(key.clone(), Some(self.rid)).await {
 					Err(Error::TxConditionNotMet) => Ok(()),
 					Err(e) => Err(e),
-					Ok(v) => Ok(v),
+					Ok(v) => {
+						self.removed(&key);
+						// The value is no longer distinct once its last entry is removed
+						if !o.is_all_none_or_null() && !self.has_entries(run, &o).await? {
+							self.delta.distinct -= 1;
+						}
+						removed = true;
+						Ok(v)
+					}
 				}?
 			}
 		}
@@ -308,15 +412,23 @@ impl<'a> IndexOperation<'a> {
 		if let Some(n) = self.n.take() {
 			let i = Indexable::new(n, self.ix);
 			for n in i {
-				let key = self.get_non_unique_index_key(&n);
-				if run.putc(key, self.rid, None).await.is_err() {
-					let key = self.get_non_unique_index_key(&n);
+				let n = self.ix.collated(&n);
+				// The value is distinct if this is its first entry
+				let first = !n.is_all_none_or_null() && !self.has_entries(run, &n).await?;
+				let key: Key = self.get_non_unique_index_key(&n).into();
+				if run.putc(key.clone(), self.rid, None).await.is_err() {
 					let val = run.get(key).await?.unwrap();
 					let rid: Thing = val.into();
-					return self.err_index_exists(rid, n);
+					return self.err_index_exists(rid, n.into_owned());
+				}
+				self.added(&key);
+				if first {
+					self.delta.distinct += 1;
 				}
+				added = true;
 			}
 		}
+		self.indexed(removed, added);
 		Ok(())
 	}
 
@@ -332,18 +444,105 @@ impl<'a> IndexOperation<'a> {
 	}
 
 	async fn index_full_text(
-		&self,
+		&mut self,
 		run: &mut kvs::Transaction,
 		p: &SearchParams,
 	) -> Result<(), Error> {
 		let ikb = IndexKeyBase::new(self.opt, self.ix);
 		let az = run.get_db_analyzer(self.opt.ns(), self.opt.db(), p.az.as_str()).await?;
 		let mut ft = FtIndex::new(run, az, ikb, p, TreeStoreType::Write).await?;
+		let count = ft.doc_count();
 		if let Some(n) = &self.n {
 			ft.index_document(run, self.rid, n).await?;
 		} else {
 			ft.remove_document(run, self.rid).await?;
 		}
+		// The entries of a full-text index are the documents
+		self.delta.count = ft.doc_count() as i64 - count as i64;
+		self.delta.records = self.delta.count;
 		ft.finish(run).await
 	}
+
+	async fn index_mtree(
+		&mut self,
+		run: &mut kvs::Transaction,
+		p: &MTreeParams,
+	) -> Result<(), Error> {
+		let ikb = IndexKeyBase::new(self.opt, self.ix);
+		let mut mt = MTreeIndex::new(run, ikb, p, TreeStoreType::Write).await?;
+		// The size of a vector, in bytes
+		let size = p.dimension as i64 * 8;
+		// Delete the old index data
+		if let Some(o) = self.o.take() {
+			if mt.remove_document(run, self.rid, &o).await? {
+				self.delta.count -= 1;
+				self.delta.records -= 1;
+				self.delta.size -= size * o.len() as i64;
+			}
+		}
+		// Create the new index data
+		if let Some(n) = self.n.take() {
+			if mt.index_document(run, self.rid, &n).await? {
+				self.delta.count += 1;
+				self.delta.records += 1;
+			}
+			self.delta.size += size * n.len() as i64;
+		}
+		mt.finish(run).await
+	}
+
+	async fn index_hnsw(
+		&mut self,
+		run: &mut kvs::Transaction,
+		p: &HnswParams,
+	) -> Result<(), Error> {
+		let ikb = IndexKeyBase::new(self.opt, self.ix);
+		let mut hnsw = HnswIndex::new(run, ikb, p, TreeStoreType::Write).await?;
+		// The size of a vector, in bytes
+		let size = p.dimension as i64 * 8;
+		// Delete the old index data
+		if let Some(o) = self.o.take() {
+			if hnsw.remove_document(run, self.rid).await? {
+				self.delta.count -= 1;
+				self.delta.records -= 1;
+				self.delta.size -= size * o.len() as i64;
+			}
+		}
+		// Create the new index data
+		if let Some(n) = self.n.take() {
+			if hnsw.index_document(run, self.rid, &n).await? {
+				self.delta.count += 1;
+				self.delta.records += 1;
+			}
+			self.delta.size += size * n.len() as i64;
+		}
+		hnsw.finish(run).await
+	}
+	async fn index_rtree(
+		&mut self,
+		run: &mut kvs::Transaction,
+		p: &RTreeParams,
+	) -> Result<(), Error> {
+		let ikb = IndexKeyBase::new(self.opt, self.ix);
+		let mut rt = RTreeIndex::new(run, ikb, p, TreeStoreType::Write).await?;
+		// The size of a bounding box, in bytes
+		let size = 32;
+		// Delete the old index data
+		if let Some(o) = self.o.take() {
+			if rt.remove_document(run, self.rid).await? {
+				self.delta.count -= 1;
+				self.delta.records -= 1;
+				self.delta.size -= size * o.len() as i64;
+			}
+		}
+		// Create the new index data
+		if let Some(n) = self.n.take() {
+			if rt.index_document(run, self.rid, &n).await? {
+				self.delta.count += 1;
+				self.delta.records += 1;
+			}
+			self.delta.size += size * n.len() as i64;
+		}
+		rt.finish(run).await
+	}
 }
@@ -4,7 +4,11 @@
 //! - `current`: value after the transaction
 //! - `initial`: value before the transaction
 //! - `id`: traditionally an integer but can be an object or collection such as an array
+pub(crate) use self::dedup::{resolve, resolve_with, restore};
 pub(crate) use self::document::*;
+pub(crate) use self::field::things;
+pub(crate) use self::index::build_record;
+pub(crate) use self::refs::{backfill, unlink};
 
 mod document; // The entry point for a document to be processed
 
@@ -18,12 +22,14 @@ mod insert; // Processes a INSERT statement for this document
 mod relate; // Processes a RELATE statement for this document
 mod select; // Processes a SELECT statement for this document
 mod update; // Processes a UPDATE statement for this document
+mod upsert; // Processes a UPSERT statement for this document
 
 mod allow; // Checks whether the query can access this document
 mod alter; // Modifies and updates the fields in this document
 mod changefeeds; // Processes any change feeds relevant for this document
 mod check; // Checks whether the WHERE clauses matches this document
 mod clean; // Ensures records adhere to the table schema
+mod dedup; // Stores the values of deduplicated fields once for the table
 mod edges; // Attempts to store the edge data for this document
 mod empty; // Checks whether the specified document actually exists
 mod erase; // Removes all content and field data for this document
@@ -35,6 +41,7 @@ mod lives; // Processes any live queries relevant for this document
 mod merge; // Merges any field changes for an INSERT statement
 mod pluck; // Pulls the projected expressions from the document
 mod purge; // Deletes this document, and any edges or indexes
+mod refs; // Maintains the references to records linked to by this document
 mod reset; // Resets internal fields which were set for this document
 mod store; // Writes the document content to the storage engine
 mod table; // Processes any foreign tables relevant for this document
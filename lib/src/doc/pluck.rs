@@ -1,5 +1,6 @@
 use crate::ctx::Context;
 use crate::dbs::Statement;
+use crate::dbs::{denied, permitted};
 use crate::dbs::{Options, Transaction};
 use crate::doc::Document;
 use crate::err::Error;
@@ -66,6 +67,9 @@ impl<'a> Document<'a> {
 				Statement::Insert(_) => {
 					self.current.doc.compute(ctx, opt, txn, Some(&self.current)).await
 				}
+				Statement::Upsert(_) => {
+					self.current.doc.compute(ctx, opt, txn, Some(&self.current)).await
+				}
 				_ => Err(Error::Ignore),
 			},
 		}?;
@@ -80,7 +84,24 @@ impl<'a> Document<'a> {
 						// Process the field permissions
 						match &fd.permissions.select {
 							Permission::Full => (),
-							Permission::None => out.del(ctx, opt, txn, k).await?,
+							Permission::None => {
+								// Explain the denial if debugging permissions
+								if opt.debug_perms {
+									let perms = &fd.permissions.select;
+									let target = format!("the `{}` field", fd.name);
+									return Err(denied(
+										ctx,
+										opt,
+										txn,
+										perms,
+										target,
+										"select",
+										&self.current,
+									)
+									.await?);
+								}
+								out.del(ctx, opt, txn, k).await?
+							}
 							Permission::Specific(e) => {
 								// Disable permissions
 								let opt = &opt.new_with_perms(false);
@@ -90,11 +111,22 @@ impl<'a> Document<'a> {
 								let mut ctx = Context::new(ctx);
 								ctx.add_value("value", &val);
 								// Process the PERMISSION clause
-								if !e
-									.compute(&ctx, opt, txn, Some(&self.current))
-									.await?
-									.is_truthy()
-								{
+								if !permitted(&ctx, opt, txn, e, &self.current).await? {
+									// Explain the denial if debugging permissions
+									if opt.debug_perms {
+										let perms = &fd.permissions.select;
+										let target = format!("the `{}` field", fd.name);
+										return Err(denied(
+											&ctx,
+											opt,
+											txn,
+											perms,
+											target,
+											"select",
+											&self.current,
+										)
+										.await?);
+									}
 									out.del(&ctx, opt, txn, k).await?
 								}
 							}
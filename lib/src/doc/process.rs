@@ -5,9 +5,23 @@ use crate::dbs::{Operable, Transaction};
 use crate::dbs::{Options, Processed};
 use crate::doc::Document;
 use crate::err::Error;
+use crate::sql::dir::Dir;
 use crate::sql::value::Value;
 
 impl<'a> Document<'a> {
+	/// Returns a context which exposes the direction of an iterated edge as $direction
+	pub(super) fn edge_context<'c>(ctx: &'c Context<'_>, dir: &Dir) -> Context<'c> {
+		let mut ctx = Context::new(ctx);
+		ctx.add_value(
+			"direction",
+			Value::from(match dir {
+				Dir::In => "in",
+				_ => "out",
+			}),
+		);
+		ctx
+	}
+
 	#[allow(dead_code)]
 	pub(crate) async fn process(
 		ctx: &Context<'_>,
@@ -16,14 +30,27 @@ impl<'a> Document<'a> {
 		stm: &Statement<'_>,
 		mut pro: Processed,
 	) -> Result<Value, Error> {
+		// Expose the direction of an iterated edge as $direction
+		let edge;
+		let ctx = match &pro.dir {
+			Some(dir) => {
+				edge = Document::edge_context(ctx, dir);
+				&edge
+			}
+			None => ctx,
+		};
 		// Loop over maximum two times
 		for _ in 0..2 {
 			// Setup a new workable
-			let ins = match pro.val {
+			let mut ins = match pro.val {
 				Operable::Value(v) => (v, Workable::Normal),
 				Operable::Mergeable(v, o) => (v, Workable::Insert(o)),
 				Operable::Relatable(f, v, w) => (v, Workable::Relate(f, w)),
 			};
+			// Resolve the values of any deduplicated fields
+			if let Some(rid) = &pro.rid {
+				crate::doc::resolve(opt, txn, rid, &mut ins.0).await?;
+			}
 			// Setup a new document
 			let mut doc = Document::new(pro.ir, pro.rid.as_ref(), pro.doc_id, &ins.0, ins.1);
 			// Process the statement
@@ -34,6 +61,7 @@ impl<'a> Document<'a> {
 				Statement::Relate(_) => doc.relate(ctx, opt, txn, stm).await,
 				Statement::Delete(_) => doc.delete(ctx, opt, txn, stm).await,
 				Statement::Insert(_) => doc.insert(ctx, opt, txn, stm).await,
+				Statement::Upsert(_) => doc.upsert(ctx, opt, txn, stm).await,
 				_ => unreachable!(),
 			};
 			// Check the result
@@ -53,6 +81,7 @@ impl<'a> Document<'a> {
 					pro = Processed {
 						ir: None,
 						doc_id: None,
+						dir: None,
 						rid: Some(v),
 						val: match doc.extras {
 							Workable::Normal => Operable::Value(val),
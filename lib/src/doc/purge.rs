@@ -24,6 +24,10 @@ impl<'a> Document<'a> {
 		if !opt.force && !self.changed() {
 			return Ok(());
 		}
+		// Release the values of any deduplicated fields
+		self.dedup(opt, txn, true).await?;
+		// Purge the references to any linked records
+		self.refs(opt, txn, true).await?;
 		// Clone transaction
 		let run = txn.clone();
 		// Claim transaction
@@ -33,6 +37,14 @@ impl<'a> Document<'a> {
 			// Purge the record data
 			let key = crate::key::thing::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
 			run.del(key).await?;
+			// Purge the record expiry time
+			let key = crate::key::table::ex::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
+			if let Some(ts) = run.get(key.clone()).await? {
+				let ts = u64::from_be_bytes(ts.try_into().map_err(|_| Error::Unreachable)?);
+				run.del(crate::key::table::et::new(opt.ns(), opt.db(), &rid.tb, ts, &rid.id))
+					.await?;
+				run.del(key).await?;
+			}
 			// Purge the record edges
 			match (
 				self.initial.doc.pick(&*EDGE),
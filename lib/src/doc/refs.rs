@@ -0,0 +1,210 @@
+use crate::cnf::PROCESSOR_BATCH_SIZE;
+use crate::ctx::Context;
+use crate::dbs::Statement;
+use crate::dbs::{Options, Transaction};
+use crate::doc::field::things;
+use crate::doc::Document;
+use crate::err::Error;
+use crate::sql::data::Data;
+use crate::sql::idiom::Idiom;
+use crate::sql::kind::Kind;
+use crate::sql::operator::Operator;
+use crate::sql::reference::Reference;
+use crate::sql::statements::{DefineFieldStatement, DeleteStatement, UpdateStatement};
+use crate::sql::thing::Thing;
+use crate::sql::value::{Value, Values};
+
+impl<'a> Document<'a> {
+	/// Stores a reverse reference on each record which is linked to by
+	/// a field of this document with an ON DELETE clause, so that the
+	/// records which link to a record can be found when it is deleted.
+	pub async fn refs(&self, opt: &Options, txn: &Transaction, remove: bool) -> Result<(), Error> {
+		// Get the record id
+		let rid = match self.id {
+			Some(rid) => rid,
+			None => return Ok(()),
+		};
+		// Get the record link fields of the table
+		let fds = self.fd(opt, txn).await?;
+		let fds = fds.iter().filter(|fd| fd.reference.is_some()).collect::<Vec<_>>();
+		if fds.is_empty() {
+			return Ok(());
+		}
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Loop through all record link fields
+		for fd in fds {
+			let name = fd.name.to_string();
+			// Get the records linked to before and after the change
+			let before = self.initial.doc.pick(&fd.name);
+			let after = match remove {
+				true => Value::None,
+				false => self.current.doc.pick(&fd.name),
+			};
+			let (mut old, mut new) = (vec![], vec![]);
+			things(&before, &mut old);
+			things(&after, &mut new);
+			// Purge the references which were removed
+			for v in old.iter().filter(|v| !new.contains(v)) {
+				let key = crate::key::table::rf::new(opt.ns(), opt.db(), v, rid, &name);
+				run.del(key).await?;
+			}
+			// Store the references which were added
+			for v in new.iter().filter(|v| !old.contains(v)) {
+				let key = crate::key::table::rf::new(opt.ns(), opt.db(), v, rid, &name);
+				run.set(key, vec![]).await?;
+			}
+		}
+		// Carry on
+		Ok(())
+	}
+	/// Processes the ON DELETE clause of each field which links to this
+	/// document, on the records which link to it, once it is deleted.
+	pub async fn referenced(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		_stm: &Statement<'_>,
+	) -> Result<(), Error> {
+		// Check if forced
+		if !opt.force && !self.changed() {
+			return Ok(());
+		}
+		// Get the record id
+		let rid = match self.id {
+			Some(rid) => rid,
+			None => return Ok(()),
+		};
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Get and purge the references to this record
+		let beg = crate::key::table::rf::prefix(opt.ns(), opt.db(), &rid.tb, &rid.id);
+		let end = crate::key::table::rf::suffix(opt.ns(), opt.db(), &rid.tb, &rid.id);
+		let refs = run.getr(beg.clone()..end.clone(), u32::MAX).await?;
+		run.delr(beg..end, u32::MAX).await?;
+		// Find the records which still link to this record
+		let mut found: Vec<(Thing, DefineFieldStatement, Value)> = vec![];
+		for (k, _) in refs.iter() {
+			let rf = crate::key::table::rf::Rf::decode(k)?;
+			// Check the field still has an ON DELETE clause
+			let fds = run.all_tb_fields(opt.ns(), opt.db(), rf.ft).await?;
+			let fd = match fds
+				.iter()
+				.find(|fd| fd.reference.is_some() && fd.name.to_string() == rf.fd)
+			{
+				Some(fd) => fd,
+				None => continue,
+			};
+			// Check the record still links to this record
+			let key = crate::key::thing::new(opt.ns(), opt.db(), rf.ft, &rf.fk);
+			let val: Value = match run.get(key).await? {
+				Some(v) => v.into(),
+				None => continue,
+			};
+			let val = val.pick(&fd.name);
+			let mut links = vec![];
+			things(&val, &mut links);
+			if links.contains(&rid) {
+				found.push((Thing::from((rf.ft, rf.fk)), fd.clone(), val));
+			}
+		}
+		// Release the transaction
+		drop(run);
+		// Check that no restricting records link to this record
+		if let Some((other, fd, _)) =
+			found.iter().find(|(_, fd, _)| fd.reference == Some(Reference::Restrict))
+		{
+			return Err(Error::FieldReferenced {
+				thing: rid.to_string(),
+				field: fd.name.to_string(),
+				other: other.to_string(),
+			});
+		}
+		// Reference actions are not limited by permissions
+		let opt = &opt.new_with_perms(false);
+		// Process the ON DELETE clauses
+		for (other, fd, val) in found {
+			match fd.reference {
+				// Delete the record which links to this record
+				Some(Reference::Cascade) => {
+					let stm = DeleteStatement {
+						what: Values(vec![Value::from(other)]),
+						..DeleteStatement::default()
+					};
+					stm.compute(ctx, opt, txn, None).await?;
+				}
+				// Remove the link to this record from the record
+				Some(Reference::SetNull) => {
+					if let Some(sets) = unlink(&fd, &val, &[Value::from(rid.clone())]) {
+						let stm = UpdateStatement {
+							what: Values(vec![Value::from(other)]),
+							data: Some(Data::SetExpression(sets)),
+							..UpdateStatement::default()
+						};
+						stm.compute(ctx, opt, txn, None).await?;
+					}
+				}
+				_ => (),
+			}
+		}
+		// Carry on
+		Ok(())
+	}
+}
+
+/// Returns the changes which remove the links to the specified records
+/// from the value of a field, removing them from an array, or emptying
+/// the field, or `None` if the field must hold a record link
+pub(crate) fn unlink(
+	fd: &DefineFieldStatement,
+	val: &Value,
+	links: &[Value],
+) -> Option<Vec<(Idiom, Operator, Value)>> {
+	match (val, &fd.kind) {
+		(Value::Array(_), _) => {
+			Some(links.iter().map(|v| (fd.name.clone(), Operator::Dec, v.clone())).collect())
+		}
+		(_, None | Some(Kind::Any | Kind::Option(_))) => {
+			Some(vec![(fd.name.clone(), Operator::Equal, Value::None)])
+		}
+		_ => None,
+	}
+}
+
+/// Stores a reverse reference on each record which is linked to by a
+/// field of the records of a table, when an ON DELETE clause is added
+pub(crate) async fn backfill(
+	run: &mut crate::kvs::Transaction,
+	ns: &str,
+	db: &str,
+	tb: &str,
+	fd: &Idiom,
+) -> Result<(), Error> {
+	let name = fd.to_string();
+	// Scan the records of the table in batches
+	let mut beg = crate::key::thing::prefix(ns, db, tb);
+	let end = crate::key::thing::suffix(ns, db, tb);
+	loop {
+		let batch = run.scan(beg.clone()..end.clone(), PROCESSOR_BATCH_SIZE).await?;
+		let last = match batch.last() {
+			Some((k, _)) => k.clone(),
+			None => break,
+		};
+		for (k, v) in batch {
+			let val = Value::from(v).pick(fd);
+			let mut links = vec![];
+			things(&val, &mut links);
+			let rid = crate::key::thing::Thing::decode(&k)?;
+			let rid = Thing::from((rid.tb, rid.id));
+			for v in links {
+				let key = crate::key::table::rf::new(ns, db, v, &rid, &name);
+				run.set(key, vec![]).await?;
+			}
+		}
+		// Continue after the last record of the batch
+		beg = last;
+		beg.push(0x00);
+	}
+	Ok(())
+}
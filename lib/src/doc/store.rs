@@ -16,17 +16,39 @@ impl<'a> Document<'a> {
 		if !opt.force && !self.changed() {
 			return Ok(());
 		}
+		// Get the table definition
+		let tb = self.tb(opt, txn).await?;
 		// Check if the table is a view
-		if self.tb(opt, txn).await?.drop {
+		if tb.drop {
 			return Ok(());
 		}
+		// Store the values of any deduplicated fields
+		let val = self.dedup(opt, txn, false).await?;
+		// Store the references to any linked records
+		self.refs(opt, txn, false).await?;
 		// Claim transaction
 		let mut run = txn.lock().await;
 		// Get the record id
 		let rid = self.id.as_ref().unwrap();
 		// Store the record data
 		let key = crate::key::thing::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
-		run.set(key, self).await?;
+		match val {
+			Some(val) => run.set(key, val).await?,
+			None => run.set(key, self).await?,
+		}
+		// Reset the expiry time of the record
+		if let Some(expire) = &tb.expire {
+			let key = crate::key::table::ex::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
+			if let Some(ts) = run.get(key.clone()).await? {
+				let ts = u64::from_be_bytes(ts.try_into().map_err(|_| Error::Unreachable)?);
+				run.del(crate::key::table::et::new(opt.ns(), opt.db(), &rid.tb, ts, &rid.id))
+					.await?;
+			}
+			let ts = run.clock().value.saturating_add(expire.as_millis() as u64);
+			run.set(key, ts.to_be_bytes().to_vec()).await?;
+			let key = crate::key::table::et::new(opt.ns(), opt.db(), &rid.tb, ts, &rid.id);
+			run.set(key, vec![]).await?;
+		}
 		// Carry on
 		Ok(())
 	}
@@ -87,85 +87,30 @@ impl<'a> Document<'a> {
 						.collect::<Vec<_>>()
 						.into(),
 					};
-					// Check if a WHERE clause is specified
-					match &tb.cond {
-						// There is a WHERE clause specified
-						Some(cond) => {
-							match cond.compute(ctx, opt, txn, Some(&self.current)).await? {
-								v if v.is_truthy() => {
-									if !opt.force && act != Action::Create {
-										// Delete the old value
-										let act = Action::Delete;
-										// Modify the value in the table
-										let stm = UpdateStatement {
-											what: Values(vec![Value::from(old)]),
-											data: Some(
-												self.data(ctx, opt, txn, act, &tb.expr).await?,
-											),
-											..UpdateStatement::default()
-										};
-										// Execute the statement
-										stm.compute(ctx, opt, txn, None).await?;
-									}
-									if act != Action::Delete {
-										// Update the new value
-										let act = Action::Update;
-										// Modify the value in the table
-										let stm = UpdateStatement {
-											what: Values(vec![Value::from(rid)]),
-											data: Some(
-												self.data(ctx, opt, txn, act, &tb.expr).await?,
-											),
-											..UpdateStatement::default()
-										};
-										// Execute the statement
-										stm.compute(ctx, opt, txn, None).await?;
-									}
-								}
-								_ => {
-									if !opt.force && act != Action::Create {
-										// Update the new value
-										let act = Action::Update;
-										// Modify the value in the table
-										let stm = UpdateStatement {
-											what: Values(vec![Value::from(old)]),
-											data: Some(
-												self.data(ctx, opt, txn, act, &tb.expr).await?,
-											),
-											..UpdateStatement::default()
-										};
-										// Execute the statement
-										stm.compute(ctx, opt, txn, None).await?;
-									}
-								}
+					// Remove the previous record from its group
+					if !opt.force && act != Action::Create {
+						// Check if the previous record matched the WHERE clause
+						let matched = match &tb.cond {
+							Some(cond) => {
+								cond.compute(ctx, opt, txn, Some(&self.initial)).await?.is_truthy()
 							}
+							None => true,
+						};
+						if matched {
+							self.retract(ctx, opt, txn, old, &tb.expr).await?;
 						}
-						// No WHERE clause is specified
-						None => {
-							if !opt.force && act != Action::Create {
-								// Delete the old value
-								let act = Action::Delete;
-								// Modify the value in the table
-								let stm = UpdateStatement {
-									what: Values(vec![Value::from(old)]),
-									data: Some(self.data(ctx, opt, txn, act, &tb.expr).await?),
-									..UpdateStatement::default()
-								};
-								// Execute the statement
-								stm.compute(ctx, opt, txn, None).await?;
-							}
-							if act != Action::Delete {
-								// Update the new value
-								let act = Action::Update;
-								// Modify the value in the table
-								let stm = UpdateStatement {
-									what: Values(vec![Value::from(rid)]),
-									data: Some(self.data(ctx, opt, txn, act, &tb.expr).await?),
-									..UpdateStatement::default()
-								};
-								// Execute the statement
-								stm.compute(ctx, opt, txn, None).await?;
+					}
+					// Add the current record to its group
+					if act != Action::Delete {
+						// Check if the current record matches the WHERE clause
+						let matched = match &tb.cond {
+							Some(cond) => {
+								cond.compute(ctx, opt, txn, Some(&self.current)).await?.is_truthy()
 							}
+							None => true,
+						};
+						if matched {
+							self.apply(ctx, opt, txn, rid, &tb.expr).await?;
 						}
 					}
 				}
@@ -248,6 +193,62 @@ impl<'a> Document<'a> {
 		data.cut(ID.as_ref());
 		Ok(Data::ReplaceExpression(data))
 	}
+	/// Removes the previous record from its group in the foreign table, deleting the group
+	/// once no records remain in it
+	async fn retract(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		rid: Thing,
+		exp: &Fields,
+	) -> Result<(), Error> {
+		// Retract the values of the aggregated fields
+		let data = self.data(ctx, opt, txn, Action::Delete, &rid, exp).await?;
+		// Groups which were created before their records were counted are never deleted
+		let stm = match self.tally(opt, txn, &Action::Delete, &rid, "", &Value::None).await? {
+			1 => {
+				// Remove the auxiliary state of the group
+				let key = crate::key::table::ag::prefix(opt.ns(), opt.db(), &rid.tb, &rid.id);
+				txn.lock().await.delp(key, u32::MAX).await?;
+				// Delete the group in the table
+				Query::Delete(DeleteStatement {
+					what: Values(vec![Value::from(rid)]),
+					..DeleteStatement::default()
+				})
+			}
+			// Modify the group in the table
+			_ => Query::Update(UpdateStatement {
+				what: Values(vec![Value::from(rid)]),
+				data: Some(data),
+				..UpdateStatement::default()
+			}),
+		};
+		// Execute the statement
+		stm.compute(ctx, opt, txn, None).await?;
+		Ok(())
+	}
+	/// Adds the current record to its group in the foreign table
+	async fn apply(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		rid: Thing,
+		exp: &Fields,
+	) -> Result<(), Error> {
+		// Count the record in the group
+		self.tally(opt, txn, &Action::Update, &rid, "", &Value::None).await?;
+		// Modify the group in the table
+		let stm = UpdateStatement {
+			data: Some(self.data(ctx, opt, txn, Action::Update, &rid, exp).await?),
+			what: Values(vec![Value::from(rid)]),
+			..UpdateStatement::default()
+		};
+		// Execute the statement
+		stm.compute(ctx, opt, txn, None).await?;
+		Ok(())
+	}
 	//
 	async fn data(
 		&self,
@@ -255,6 +256,7 @@ impl<'a> Document<'a> {
 		opt: &Options,
 		txn: &Transaction,
 		act: Action,
+		rid: &Thing,
 		exp: &Fields,
 	) -> Result<Data, Error> {
 		//
@@ -290,13 +292,17 @@ impl<'a> Document<'a> {
 							let val = f.args()[0].compute(ctx, opt, txn, doc).await?;
 							self.chg(&mut ops, &act, idiom, val);
 						}
+						"count::distinct" => {
+							let val = f.args()[0].compute(ctx, opt, txn, doc).await?;
+							self.distinct(&mut ops, opt, txn, &act, rid, idiom, val).await?;
+						}
 						"math::min" | "time::min" => {
 							let val = f.args()[0].compute(ctx, opt, txn, doc).await?;
-							self.min(&mut ops, &act, idiom, val);
+							self.min(&mut ops, ctx, opt, txn, &act, rid, idiom, val).await?;
 						}
 						"math::max" | "time::max" => {
 							let val = f.args()[0].compute(ctx, opt, txn, doc).await?;
-							self.max(&mut ops, &act, idiom, val);
+							self.max(&mut ops, ctx, opt, txn, &act, rid, idiom, val).await?;
 						}
 						"math::mean" => {
 							let val = f.args()[0].compute(ctx, opt, txn, doc).await?;
@@ -330,45 +336,173 @@ impl<'a> Document<'a> {
 			val,
 		));
 	}
-	/// Set the new minimum value for the field in the foreign table
-	fn min(&self, ops: &mut Ops, act: &Action, key: Idiom, val: Value) {
-		if act == &Action::Update {
-			ops.push((
-				key.clone(),
-				Operator::Equal,
-				Value::Subquery(Box::new(Subquery::Ifelse(IfelseStatement {
-					exprs: vec![(
-						Value::Expression(Box::new(Expression::Binary {
+	/// Set the field in the foreign table to the value, if the field is not set yet,
+	/// or if the field compares to the value with the operator
+	fn swap(&self, ops: &mut Ops, key: Idiom, o: Operator, val: Value) {
+		ops.push((
+			key.clone(),
+			Operator::Equal,
+			Value::Subquery(Box::new(Subquery::Ifelse(IfelseStatement {
+				exprs: vec![(
+					Value::Expression(Box::new(Expression::Binary {
+						l: Value::Expression(Box::new(Expression::Binary {
 							l: Value::Idiom(key.clone()),
-							o: Operator::MoreThan,
-							r: val.clone(),
+							o: Operator::Equal,
+							r: Value::None,
 						})),
-						val,
-					)],
-					close: Some(Value::Idiom(key)),
-				}))),
-			));
-		}
-	}
-	/// Set the new maximum value for the field in the foreign table
-	fn max(&self, ops: &mut Ops, act: &Action, key: Idiom, val: Value) {
-		if act == &Action::Update {
-			ops.push((
-				key.clone(),
-				Operator::Equal,
-				Value::Subquery(Box::new(Subquery::Ifelse(IfelseStatement {
-					exprs: vec![(
-						Value::Expression(Box::new(Expression::Binary {
+						o: Operator::Or,
+						r: Value::Expression(Box::new(Expression::Binary {
 							l: Value::Idiom(key.clone()),
-							o: Operator::LessThan,
+							o,
 							r: val.clone(),
 						})),
-						val,
-					)],
-					close: Some(Value::Idiom(key)),
-				}))),
-			));
+					})),
+					val,
+				)],
+				close: Some(Value::Idiom(key)),
+			}))),
+		));
+	}
+	/// Set the new minimum value for the field in the foreign table. The values of the
+	/// field which remain in the group are only read once the minimum value is retracted.
+	#[allow(clippy::too_many_arguments)]
+	async fn min(
+		&self,
+		ops: &mut Ops,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		act: &Action,
+		rid: &Thing,
+		key: Idiom,
+		val: Value,
+	) -> Result<(), Error> {
+		if val.is_none_or_null() {
+			return Ok(());
+		}
+		let fd = key.to_hash();
+		let count = self.tally(opt, txn, act, rid, &fd, &val).await?;
+		match act {
+			Action::Update => self.swap(ops, key, Operator::MoreThan, val),
+			// The last record with the minimum value was removed from the group
+			_ if count == 1 && self.group(ctx, opt, txn, rid, &key).await? == val => {
+				let vals = self.values(opt, txn, rid, &fd).await?;
+				self.set(ops, key, vals.into_iter().min().unwrap_or_default());
+			}
+			_ => (),
+		}
+		Ok(())
+	}
+	/// Set the new maximum value for the field in the foreign table. The values of the
+	/// field which remain in the group are only read once the maximum value is retracted.
+	#[allow(clippy::too_many_arguments)]
+	async fn max(
+		&self,
+		ops: &mut Ops,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		act: &Action,
+		rid: &Thing,
+		key: Idiom,
+		val: Value,
+	) -> Result<(), Error> {
+		if val.is_none_or_null() {
+			return Ok(());
+		}
+		let fd = key.to_hash();
+		let count = self.tally(opt, txn, act, rid, &fd, &val).await?;
+		match act {
+			Action::Update => self.swap(ops, key, Operator::LessThan, val),
+			// The last record with the maximum value was removed from the group
+			_ if count == 1 && self.group(ctx, opt, txn, rid, &key).await? == val => {
+				let vals = self.values(opt, txn, rid, &fd).await?;
+				self.set(ops, key, vals.into_iter().max().unwrap_or_default());
+			}
+			_ => (),
+		}
+		Ok(())
+	}
+	/// Increment or decrement the number of distinct values for the field in the foreign
+	/// table, where the items of an array are counted individually
+	#[allow(clippy::too_many_arguments)]
+	async fn distinct(
+		&self,
+		ops: &mut Ops,
+		opt: &Options,
+		txn: &Transaction,
+		act: &Action,
+		rid: &Thing,
+		key: Idiom,
+		val: Value,
+	) -> Result<(), Error> {
+		let fd = key.to_hash();
+		let vals = match val {
+			Value::Array(v) => v.0,
+			v => vec![v],
+		};
+		// Count the values which are added to, or removed from, the group
+		let mut changed = 0;
+		for val in vals.iter().filter(|v| !v.is_none_or_null()) {
+			match (act, self.tally(opt, txn, act, rid, &fd, val).await?) {
+				(Action::Update, 0) | (Action::Delete, 1) => changed += 1,
+				_ => (),
+			}
+		}
+		self.chg(ops, act, key, Value::from(changed));
+		Ok(())
+	}
+	/// Returns the current value of a field of a group in the foreign table
+	async fn group(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		rid: &Thing,
+		key: &Idiom,
+	) -> Result<Value, Error> {
+		Value::from(rid.clone()).get(ctx, opt, txn, None, key).await
+	}
+	/// Adds or retracts a value of a field in the auxiliary state of a group in the foreign
+	/// table, returning the number of times the value was counted beforehand
+	async fn tally(
+		&self,
+		opt: &Options,
+		txn: &Transaction,
+		act: &Action,
+		rid: &Thing,
+		fd: &str,
+		val: &Value,
+	) -> Result<u64, Error> {
+		let key = crate::key::table::ag::new(opt.ns(), opt.db(), &rid.tb, &rid.id, fd, val);
+		let mut run = txn.lock().await;
+		let count = match run.get(key.clone()).await? {
+			Some(v) => u64::from_be_bytes(v.as_slice().try_into().unwrap_or_default()),
+			None => 0,
+		};
+		match act {
+			Action::Delete if count <= 1 => run.del(key).await?,
+			Action::Delete => run.set(key, (count - 1).to_be_bytes().to_vec()).await?,
+			_ => run.set(key, (count + 1).to_be_bytes().to_vec()).await?,
+		}
+		Ok(count)
+	}
+	/// Returns the distinct values of a field which remain in the auxiliary state of a
+	/// group in the foreign table
+	async fn values(
+		&self,
+		opt: &Options,
+		txn: &Transaction,
+		rid: &Thing,
+		fd: &str,
+	) -> Result<Vec<Value>, Error> {
+		let key = crate::key::table::ag::prefix_fd(opt.ns(), opt.db(), &rid.tb, &rid.id, fd);
+		let mut run = txn.lock().await;
+		let mut out = Vec::new();
+		for (k, _) in run.getp(key, u32::MAX).await? {
+			out.push(crate::key::table::ag::Ag::decode(&k)?.val);
 		}
+		Ok(out)
 	}
 	/// Set the new average value for the field in the foreign table
 	fn mean(&self, ops: &mut Ops, act: &Action, key: Idiom, val: Value) {
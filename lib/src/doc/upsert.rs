@@ -0,0 +1,117 @@
+use crate::ctx::Context;
+use crate::dbs::Statement;
+use crate::dbs::{Options, Transaction};
+use crate::doc::Document;
+use crate::err::Error;
+use crate::sql::value::Value;
+
+impl<'a> Document<'a> {
+	pub async fn upsert(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+	) -> Result<Value, Error> {
+		// Check whether current record exists
+		match self.current.doc.is_some() {
+			// The record already exists, either with the
+			// specified ID, or with a unique index value
+			// which conflicted with the new record, so we
+			// update the record unless conflicts are ignored
+			true => match stm {
+				Statement::Upsert(v) if v.ignore => Err(Error::Ignore),
+				_ => self.upsert_update(ctx, opt, txn, stm).await,
+			},
+			// The record does not exist, so we attempt to
+			// create the record with the specified ID, or
+			// with a new ID if none was specified.
+			false => {
+				// First of all let's try to create the record
+				match self.upsert_create(ctx, opt, txn, stm).await {
+					// We received an index exists error, so we
+					// ignore the error, and attempt to update
+					// the record which already has the unique
+					// index value, with the Record ID received
+					Err(Error::IndexExists {
+						thing,
+						..
+					}) => Err(Error::RetryWithId(thing)),
+					// If any other error was received, then let's
+					// pass that error through and return an error
+					Err(e) => Err(e),
+					// Otherwise the record creation succeeded
+					Ok(v) => Ok(v),
+				}
+			}
+		}
+	}
+	// Attempt to create the record
+	async fn upsert_create(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+	) -> Result<Value, Error> {
+		// Alter record data
+		self.alter(ctx, opt, txn, stm).await?;
+		// Merge fields data
+		self.field(ctx, opt, txn, stm).await?;
+		// Reset fields data
+		self.reset(ctx, opt, txn, stm).await?;
+		// Clean fields data
+		self.clean(ctx, opt, txn, stm).await?;
+		// Check if allowed
+		self.allow(ctx, opt, txn, stm).await?;
+		// Store index data
+		self.index(ctx, opt, txn, stm).await?;
+		// Store record data
+		self.store(ctx, opt, txn, stm).await?;
+		// Run table queries
+		self.table(ctx, opt, txn, stm).await?;
+		// Run lives queries
+		self.lives(ctx, opt, txn, stm).await?;
+		// Run change feeds queries
+		self.changefeeds(ctx, opt, txn, stm).await?;
+		// Run event queries
+		self.event(ctx, opt, txn, stm).await?;
+		// Yield document
+		self.pluck(ctx, opt, txn, stm).await
+	}
+	// Attempt to update the existing record
+	async fn upsert_update(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+	) -> Result<Value, Error> {
+		// Check if allowed
+		self.allow(ctx, opt, txn, stm).await?;
+		// Alter record data
+		self.alter(ctx, opt, txn, stm).await?;
+		// Merge fields data
+		self.field(ctx, opt, txn, stm).await?;
+		// Reset fields data
+		self.reset(ctx, opt, txn, stm).await?;
+		// Clean fields data
+		self.clean(ctx, opt, txn, stm).await?;
+		// Check if allowed
+		self.allow(ctx, opt, txn, stm).await?;
+		// Store index data
+		self.index(ctx, opt, txn, stm).await?;
+		// Store record data
+		self.store(ctx, opt, txn, stm).await?;
+		// Run table queries
+		self.table(ctx, opt, txn, stm).await?;
+		// Run lives queries
+		self.lives(ctx, opt, txn, stm).await?;
+		// Run change feeds queries
+		self.changefeeds(ctx, opt, txn, stm).await?;
+		// Run event queries
+		self.event(ctx, opt, txn, stm).await?;
+		// Yield document
+		self.pluck(ctx, opt, txn, stm).await
+	}
+}
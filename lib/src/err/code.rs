@@ -0,0 +1,284 @@
+use super::Error;
+use serde::Serialize;
+use std::fmt::{self, Display};
+
+/// The category of an error, which describes how a client can handle it
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum ErrorCategory {
+	/// The query, or a value used by the query, is invalid
+	Parse,
+	/// The query is not allowed to be run with the current authentication
+	Permission,
+	/// The query conflicted with a concurrent change, and can be retried
+	Conflict,
+	/// A resource does not exist, is not available, or a limit was exceeded
+	Resource,
+	/// There was a problem within the database itself
+	Internal,
+}
+
+impl Display for ErrorCategory {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Parse => f.write_str("parse"),
+			Self::Permission => f.write_str("permission"),
+			Self::Conflict => f.write_str("conflict"),
+			Self::Resource => f.write_str("resource"),
+			Self::Internal => f.write_str("internal"),
+		}
+	}
+}
+
+macro_rules! codes {
+	($($variant:ident => ($code:literal, $name:literal, $category:ident),)*) => {
+		impl Error {
+			/// Returns the stable numeric code of this error
+			pub fn code(&self) -> u32 {
+				match self {
+					$(Error::$variant { .. } => $code,)*
+				}
+			}
+			/// Returns the stable string code of this error
+			pub fn name(&self) -> &'static str {
+				match self {
+					$(Error::$variant { .. } => $name,)*
+				}
+			}
+			/// Returns the category of this error
+			pub fn category(&self) -> ErrorCategory {
+				match self {
+					$(Error::$variant { .. } => ErrorCategory::$category,)*
+				}
+			}
+		}
+
+		/// The code, name, and category of every error
+		#[cfg(test)]
+		const CODES: &[(u32, &str, ErrorCategory)] = &[
+			$(($code, $name, ErrorCategory::$category),)*
+		];
+	};
+}
+
+// The codes are grouped by category, in the ranges 1xxx (parse), 2xxx
+// (permission), 3xxx (conflict), 4xxx (resource), and 5xxx (internal).
+// Codes are never reused or changed, so a new error takes the next
+// unused code of its category, and a removed error leaves a gap.
+codes! {
+	Ignore => (5001, "IGNORE", Internal),
+	Break => (5002, "BREAK", Internal),
+	Continue => (5003, "CONTINUE", Internal),
+	RetryWithId => (5004, "RETRY_WITH_ID", Internal),
+	Unreachable => (5005, "UNREACHABLE", Internal),
+	Deprecated => (1001, "DEPRECATED", Parse),
+	Thrown => (1002, "THROWN", Parse),
+	Ds => (5006, "DS", Internal),
+	DsReadonly => (4001, "DS_READONLY", Resource),
+	CorruptValue => (5007, "CORRUPT_VALUE", Internal),
+	DsLocked => (4002, "DS_LOCKED", Resource),
+	Tx => (5027, "TX", Internal),
+	TxFailure => (5008, "TX_FAILURE", Internal),
+	TxFinished => (5009, "TX_FINISHED", Internal),
+	TxReadonly => (5010, "TX_READONLY", Internal),
+	TxConditionNotMet => (3002, "TX_CONDITION_NOT_MET", Conflict),
+	TxKeyAlreadyExists => (3003, "TX_KEY_ALREADY_EXISTS", Conflict),
+	TxKeyTooLarge => (4003, "TX_KEY_TOO_LARGE", Resource),
+	TxValueTooLarge => (4004, "TX_VALUE_TOO_LARGE", Resource),
+	TxTooLarge => (4005, "TX_TOO_LARGE", Resource),
+	NsEmpty => (1003, "NS_EMPTY", Parse),
+	DbEmpty => (1004, "DB_EMPTY", Parse),
+	QueryEmpty => (1005, "QUERY_EMPTY", Parse),
+	QueryRemaining => (1006, "QUERY_REMAINING", Parse),
+	InvalidQuery => (1007, "INVALID_QUERY", Parse),
+	QueryTooLarge => (4006, "QUERY_TOO_LARGE", Resource),
+	QueryTooManyStatements => (4007, "QUERY_TOO_MANY_STATEMENTS", Resource),
+	InvalidContent => (1008, "INVALID_CONTENT", Parse),
+	InvalidMerge => (1009, "INVALID_MERGE", Parse),
+	InvalidPatch => (1010, "INVALID_PATCH", Parse),
+	PatchTest => (1011, "PATCH_TEST", Parse),
+	HttpDisabled => (4008, "HTTP_DISABLED", Resource),
+	InvalidParam => (1012, "INVALID_PARAM", Parse),
+	InvalidOption => (1013, "INVALID_OPTION", Parse),
+	InvalidField => (1014, "INVALID_FIELD", Parse),
+	InvalidSplit => (1015, "INVALID_SPLIT", Parse),
+	InvalidOrder => (1016, "INVALID_ORDER", Parse),
+	InvalidGroup => (1017, "INVALID_GROUP", Parse),
+	InvalidLimit => (1018, "INVALID_LIMIT", Parse),
+	InvalidStart => (1019, "INVALID_START", Parse),
+	InvalidScript => (1020, "INVALID_SCRIPT", Parse),
+	InvalidFunction => (1021, "INVALID_FUNCTION", Parse),
+	InvalidArguments => (1022, "INVALID_ARGUMENTS", Parse),
+	InvalidUrl => (1023, "INVALID_URL", Parse),
+	QueryTimedout => (4009, "QUERY_TIMEDOUT", Resource),
+	QueryCancelled => (4010, "QUERY_CANCELLED", Resource),
+	QueryKilled => (4011, "QUERY_KILLED", Resource),
+	MemoryLimitExceeded => (4012, "MEMORY_LIMIT_EXCEEDED", Resource),
+	QueryNotExecuted => (1065, "QUERY_NOT_EXECUTED", Parse),
+	QueryNotExecutedDetail => (3005, "QUERY_NOT_EXECUTED_DETAIL", Conflict),
+	SavepointOutsideTransaction => (1024, "SAVEPOINT_OUTSIDE_TRANSACTION", Parse),
+	SavepointNotFound => (4013, "SAVEPOINT_NOT_FOUND", Resource),
+	NsNotAllowed => (2001, "NS_NOT_ALLOWED", Permission),
+	DbNotAllowed => (2002, "DB_NOT_ALLOWED", Permission),
+	NsNotFound => (4014, "NS_NOT_FOUND", Resource),
+	NtNotFound => (4015, "NT_NOT_FOUND", Resource),
+	NlNotFound => (4016, "NL_NOT_FOUND", Resource),
+	DbNotFound => (4017, "DB_NOT_FOUND", Resource),
+	DtNotFound => (4018, "DT_NOT_FOUND", Resource),
+	DlNotFound => (4019, "DL_NOT_FOUND", Resource),
+	FcNotFound => (4020, "FC_NOT_FOUND", Resource),
+	ScNotFound => (4021, "SC_NOT_FOUND", Resource),
+	ClAlreadyExists => (3006, "CL_ALREADY_EXISTS", Conflict),
+	NdNotFound => (4022, "ND_NOT_FOUND", Resource),
+	StNotFound => (4023, "ST_NOT_FOUND", Resource),
+	PaNotFound => (4024, "PA_NOT_FOUND", Resource),
+	CgNotFound => (4025, "CG_NOT_FOUND", Resource),
+	SqNotFound => (4026, "SQ_NOT_FOUND", Resource),
+	MgApplied => (3007, "MG_APPLIED", Conflict),
+	MgFailed => (5028, "MG_FAILED", Internal),
+	NqNotFound => (4027, "NQ_NOT_FOUND", Resource),
+	NqAlreadyExists => (3012, "NQ_ALREADY_EXISTS", Conflict),
+	QueryNotRunning => (4028, "QUERY_NOT_RUNNING", Resource),
+	TbNotFound => (4029, "TB_NOT_FOUND", Resource),
+	LvNotFound => (4030, "LV_NOT_FOUND", Resource),
+	LqNotFound => (4031, "LQ_NOT_FOUND", Resource),
+	AzNotFound => (4032, "AZ_NOT_FOUND", Resource),
+	IxNotFound => (4033, "IX_NOT_FOUND", Resource),
+	FdNotFound => (4034, "FD_NOT_FOUND", Resource),
+	UserRootNotFound => (4035, "USER_ROOT_NOT_FOUND", Resource),
+	UserNsNotFound => (4036, "USER_NS_NOT_FOUND", Resource),
+	UserDbNotFound => (4037, "USER_DB_NOT_FOUND", Resource),
+	RealtimeDisabled => (4038, "REALTIME_DISABLED", Resource),
+	ComputationDepthExceeded => (4039, "COMPUTATION_DEPTH_EXCEEDED", Resource),
+	InvalidStatementTarget => (1026, "INVALID_STATEMENT_TARGET", Parse),
+	CreateStatement => (1027, "CREATE_STATEMENT", Parse),
+	UpdateStatement => (1028, "UPDATE_STATEMENT", Parse),
+	RelateStatement => (1029, "RELATE_STATEMENT", Parse),
+	DeleteStatement => (1030, "DELETE_STATEMENT", Parse),
+	UpsertStatement => (1031, "UPSERT_STATEMENT", Parse),
+	InsertStatement => (1032, "INSERT_STATEMENT", Parse),
+	LiveStatement => (1033, "LIVE_STATEMENT", Parse),
+	KillStatement => (1034, "KILL_STATEMENT", Parse),
+	SingleOnlyOutput => (1035, "SINGLE_ONLY_OUTPUT", Parse),
+	TablePermissions => (2003, "TABLE_PERMISSIONS", Permission),
+	ParamPermissions => (2004, "PARAM_PERMISSIONS", Permission),
+	ParamChangePermissions => (2025, "PARAM_CHANGE_PERMISSIONS", Permission),
+	ConfigPermissions => (2005, "CONFIG_PERMISSIONS", Permission),
+	FunctionPermissions => (2006, "FUNCTION_PERMISSIONS", Permission),
+	PermissionDenied => (2007, "PERMISSION_DENIED", Permission),
+	TableIsView => (1036, "TABLE_IS_VIEW", Parse),
+	RecordExists => (3008, "RECORD_EXISTS", Conflict),
+	IndexExists => (3009, "INDEX_EXISTS", Conflict),
+	ReplicationConflict => (3010, "REPLICATION_CONFLICT", Conflict),
+	TableCheck => (1037, "TABLE_CHECK", Parse),
+	ParamCheck => (1061, "PARAM_CHECK", Parse),
+	ParamValue => (1062, "PARAM_VALUE", Parse),
+	TableMember => (1038, "TABLE_MEMBER", Parse),
+	TableMemberDelete => (1063, "TABLE_MEMBER_DELETE", Parse),
+	FieldCheck => (1039, "FIELD_CHECK", Parse),
+	FieldValue => (1040, "FIELD_VALUE", Parse),
+	FieldUndefined => (1041, "FIELD_UNDEFINED", Parse),
+	FieldReferenced => (3011, "FIELD_REFERENCED", Conflict),
+	FieldReferenceType => (1042, "FIELD_REFERENCE_TYPE", Parse),
+	FieldReferenceSetNull => (1064, "FIELD_REFERENCE_SET_NULL", Parse),
+	IdMismatch => (1043, "ID_MISMATCH", Parse),
+	IdInvalid => (1044, "ID_INVALID", Parse),
+	CoerceTo => (1045, "COERCE_TO", Parse),
+	ConvertTo => (1046, "CONVERT_TO", Parse),
+	LengthInvalid => (1047, "LENGTH_INVALID", Parse),
+	TryAdd => (1048, "TRY_ADD", Parse),
+	TrySub => (1049, "TRY_SUB", Parse),
+	TryMul => (1050, "TRY_MUL", Parse),
+	TryDiv => (1051, "TRY_DIV", Parse),
+	TryPow => (1052, "TRY_POW", Parse),
+	TryNeg => (1053, "TRY_NEG", Parse),
+	TryFrom => (1054, "TRY_FROM", Parse),
+	Http => (4040, "HTTP", Resource),
+	Channel => (5011, "CHANNEL", Internal),
+	Io => (5012, "IO", Internal),
+	Encode => (5013, "ENCODE", Internal),
+	Decode => (5014, "DECODE", Internal),
+	Revision => (5015, "REVISION", Internal),
+	CorruptedIndex => (5016, "CORRUPTED_INDEX", Internal),
+	NoIndexFoundForMatch => (4041, "NO_INDEX_FOUND_FOR_MATCH", Resource),
+	NoIndexFoundForKnn => (4042, "NO_INDEX_FOUND_FOR_KNN", Resource),
+	AnalyzerError => (5017, "ANALYZER_ERROR", Internal),
+	HighlightError => (5018, "HIGHLIGHT_ERROR", Internal),
+	PhraseSearchNotSupported => (1055, "PHRASE_SEARCH_NOT_SUPPORTED", Parse),
+	PhraseSearchRequiresRebuild => (4043, "PHRASE_SEARCH_REQUIRES_REBUILD", Resource),
+	Bincode => (5019, "BINCODE", Internal),
+	FstError => (5020, "FST_ERROR", Internal),
+	Utf8Error => (5021, "UTF8_ERROR", Internal),
+	FeatureNotYetImplemented => (5022, "FEATURE_NOT_YET_IMPLEMENTED", Internal),
+	DuplicatedMatchRef => (1056, "DUPLICATED_MATCH_REF", Parse),
+	InvalidVectorDimension => (1057, "INVALID_VECTOR_DIMENSION", Parse),
+	InvalidVectorType => (1058, "INVALID_VECTOR_TYPE", Parse),
+	InvalidVectorValue => (1059, "INVALID_VECTOR_VALUE", Parse),
+	TimestampOverflow => (5023, "TIMESTAMP_OVERFLOW", Internal),
+	Internal => (5024, "INTERNAL", Internal),
+	Unimplemented => (5025, "UNIMPLEMENTED", Internal),
+	CorruptedVersionstampInKey => (5026, "CORRUPTED_VERSIONSTAMP_IN_KEY", Internal),
+	InvalidLevel => (1060, "INVALID_LEVEL", Parse),
+	IamError => (2008, "IAM_ERROR", Permission),
+	ScriptingNotAllowed => (2009, "SCRIPTING_NOT_ALLOWED", Permission),
+	FunctionNotAllowed => (2010, "FUNCTION_NOT_ALLOWED", Permission),
+	NetTargetNotAllowed => (2011, "NET_TARGET_NOT_ALLOWED", Permission),
+	TokenMakingFailed => (2012, "TOKEN_MAKING_FAILED", Permission),
+	NoRecordFound => (2013, "NO_RECORD_FOUND", Permission),
+	SignupQueryFailed => (2014, "SIGNUP_QUERY_FAILED", Permission),
+	SigninQueryFailed => (2015, "SIGNIN_QUERY_FAILED", Permission),
+	ScopeNoSignup => (2016, "SCOPE_NO_SIGNUP", Permission),
+	ScopeNoSignin => (2017, "SCOPE_NO_SIGNIN", Permission),
+	NoScopeFound => (2018, "NO_SCOPE_FOUND", Permission),
+	MissingUserOrPass => (2019, "MISSING_USER_OR_PASS", Permission),
+	NoSigninTarget => (2020, "NO_SIGNIN_TARGET", Permission),
+	InvalidPass => (2021, "INVALID_PASS", Permission),
+	InvalidAuth => (2022, "INVALID_AUTH", Permission),
+	InvalidSignup => (2023, "INVALID_SIGNUP", Permission),
+	UnknownAuth => (2024, "UNKNOWN_AUTH", Permission),
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::HashSet;
+
+	#[test]
+	fn error_codes() {
+		let err = Error::TbNotFound {
+			value: "test".to_owned(),
+		};
+		assert_eq!(err.code(), 4029);
+		assert_eq!(err.name(), "TB_NOT_FOUND");
+		assert_eq!(err.category(), ErrorCategory::Resource);
+		assert_eq!(err.category().to_string(), "resource");
+		let err = Error::TxKeyAlreadyExists;
+		assert_eq!(err.category(), ErrorCategory::Conflict);
+		let err = Error::NsNotAllowed {
+			ns: "test".to_owned(),
+		};
+		assert_eq!(err.category(), ErrorCategory::Permission);
+	}
+
+	#[test]
+	fn error_codes_are_unique() {
+		let codes: HashSet<u32> = CODES.iter().map(|(code, _, _)| *code).collect();
+		assert_eq!(codes.len(), CODES.len());
+		let names: HashSet<&str> = CODES.iter().map(|(_, name, _)| *name).collect();
+		assert_eq!(names.len(), CODES.len());
+		for (code, name, category) in CODES.iter() {
+			assert_eq!(
+				code / 1000,
+				match category {
+					ErrorCategory::Parse => 1,
+					ErrorCategory::Permission => 2,
+					ErrorCategory::Conflict => 3,
+					ErrorCategory::Resource => 4,
+					ErrorCategory::Internal => 5,
+				},
+				"{name}"
+			);
+		}
+	}
+}
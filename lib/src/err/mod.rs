@@ -1,5 +1,6 @@
 use crate::iam::Error as IamError;
 use crate::idx::ft::MatchRef;
+use crate::sql::error::Diagnostic as ParserDiagnostic;
 use crate::sql::error::RenderedError as RenderedParserError;
 use crate::sql::idiom::Idiom;
 use crate::sql::thing::Thing;
@@ -17,6 +18,10 @@ use storekey::decode::Error as DecodeError;
 use storekey::encode::Error as EncodeError;
 use thiserror::Error;
 
+mod code;
+
+pub use self::code::ErrorCategory;
+
 /// An error originating from an embedded SurrealDB database.
 #[derive(Error, Debug)]
 #[non_exhaustive]
@@ -57,6 +62,23 @@ pub enum Error {
 	#[error("There was a problem with the underlying datastore: {0}")]
 	Ds(String),
 
+	/// The datastore was opened in read-only mode
+	#[error("Couldn't write to a datastore which was opened in read-only mode")]
+	DsReadonly,
+
+	/// The value stored at a key failed its checksum verification
+	#[error("The value stored at key {key} failed its checksum verification")]
+	CorruptValue {
+		key: String,
+	},
+
+	/// The datastore is already opened by another process
+	#[error("The datastore at '{path}' is locked by {owner}. If that process has crashed, open the datastore with the force unlock option")]
+	DsLocked {
+		path: String,
+		owner: String,
+	},
+
 	/// There was a problem with a datastore transaction
 	#[error("There was a problem with a datastore transaction: {0}")]
 	Tx(String),
@@ -113,6 +135,20 @@ pub enum Error {
 	#[error("Parse error: {0}")]
 	InvalidQuery(RenderedParserError),
 
+	/// The SQL query was longer than the configured limit
+	#[error("The SQL query is {size} bytes long, but the maximum query size is {max} bytes")]
+	QueryTooLarge {
+		size: usize,
+		max: usize,
+	},
+
+	/// The SQL query had more statements than the configured limit
+	#[error("The SQL query has {count} statements, but the maximum is {max} statements")]
+	QueryTooManyStatements {
+		count: usize,
+		max: usize,
+	},
+
 	/// There was an error with the SQL query
 	#[error("Can not use {value} in a CONTENT clause")]
 	InvalidContent {
@@ -148,6 +184,13 @@ pub enum Error {
 		name: String,
 	},
 
+	/// The session option does not exist, or its value is not valid
+	#[error("Found '{name}' but it is not possible to set this session option: {message}")]
+	InvalidOption {
+		name: String,
+		message: String,
+	},
+
 	#[error("Found '{field}' in SELECT clause on line {line}, but field is not an aggregate function, and is not present in GROUP BY expression")]
 	InvalidField {
 		line: usize,
@@ -216,6 +259,16 @@ pub enum Error {
 	#[error("The query was not executed due to a cancelled transaction")]
 	QueryCancelled,
 
+	/// The query was cancelled with a KILL QUERY statement
+	#[error("The query was killed before it completed")]
+	QueryKilled,
+
+	/// The query held more memory than the statement memory limit allows
+	#[error("The query was not executed because it exceeded the memory limit of {limit} bytes")]
+	MemoryLimitExceeded {
+		limit: usize,
+	},
+
 	/// The query did not execute, because the transaction has failed
 	#[error("The query was not executed due to a failed transaction")]
 	QueryNotExecuted,
@@ -226,6 +279,16 @@ pub enum Error {
 		message: String,
 	},
 
+	/// The savepoint was used outside of a BEGIN and COMMIT block
+	#[error("Savepoints can only be used within a transaction")]
+	SavepointOutsideTransaction,
+
+	/// The requested savepoint does not exist in the transaction
+	#[error("The savepoint '{value}' does not exist")]
+	SavepointNotFound {
+		value: String,
+	},
+
 	/// The permissions do not allow for changing to the specified namespace
 	#[error("You don't have permission to change to the {ns} namespace")]
 	NsNotAllowed {
@@ -310,12 +373,61 @@ pub enum Error {
 		value: String,
 	},
 
+	/// The requested config does not exist
+	#[error("The config '{value}' does not exist")]
+	CgNotFound {
+		value: String,
+	},
+
+	/// The requested sequence does not exist
+	#[error("The sequence '{value}' does not exist")]
+	SqNotFound {
+		value: String,
+	},
+
+	/// A migration which has already been applied can not be changed
+	#[error("The migration '{value}' has already been applied, and can not be changed")]
+	MgApplied {
+		value: u64,
+	},
+
+	/// A migration failed, so none of the migrations were applied
+	#[error("The migration '{value}' failed: {message}")]
+	MgFailed {
+		value: u64,
+		message: String,
+	},
+
+	/// The requested named query does not exist
+	#[error("The named query '{value}' does not exist")]
+	NqNotFound {
+		value: String,
+	},
+
+	/// The named query was registered by another user
+	#[error("The named query '{value}' is already registered by another user")]
+	NqAlreadyExists {
+		value: String,
+	},
+
+	/// The requested query is not running, or can not be cancelled
+	#[error("The query '{value}' is not running")]
+	QueryNotRunning {
+		value: String,
+	},
+
 	/// The requested table does not exist
 	#[error("The table '{value}' does not exist")]
 	TbNotFound {
 		value: String,
 	},
 
+	/// The storage of a table which contains records can not be changed
+	#[error("The storage of the table '{value}' can't change while it contains records")]
+	TbStorageInUse {
+		value: String,
+	},
+
 	/// The requested live query does not exist
 	#[error("The live query '{value}' does not exist")]
 	LvNotFound {
@@ -340,6 +452,12 @@ pub enum Error {
 		value: String,
 	},
 
+	/// The requested field does not exist
+	#[error("The field '{value}' does not exist")]
+	FdNotFound {
+		value: String,
+	},
+
 	/// The requested root user does not exist
 	#[error("The root user '{value}' does not exist")]
 	UserRootNotFound {
@@ -399,6 +517,12 @@ pub enum Error {
 		value: String,
 	},
 
+	/// Can not execute UPSERT statement using the specified value
+	#[error("Can not execute UPSERT statement using value '{value}'")]
+	UpsertStatement {
+		value: String,
+	},
+
 	/// Can not execute INSERT statement using the specified value
 	#[error("Can not execute INSERT statement using value '{value}'")]
 	InsertStatement {
@@ -433,12 +557,33 @@ pub enum Error {
 		name: String,
 	},
 
+	/// The permissions do not allow this parameter to be changed
+	#[error("You don't have permission to change the ${name} parameter")]
+	ParamChangePermissions {
+		name: String,
+	},
+
+	/// The permissions do not allow this config to be viewed
+	#[error("You don't have permission to view the {name} config")]
+	ConfigPermissions {
+		name: String,
+	},
+
 	/// The permissions do not allow this query to be run on this table
 	#[error("You don't have permission to run the fn::{name} function")]
 	FunctionPermissions {
 		name: String,
 	},
 
+	/// A PERMISSIONS clause denied access, reported when permission debugging is enabled
+	#[error("You don't have permission to {action} {target}, denied by `{rule}` with {values}")]
+	PermissionDenied {
+		target: String,
+		action: String,
+		rule: String,
+		values: String,
+	},
+
 	/// The specified table can not be written as it is setup as a foreign table view
 	#[error("Unable to write to the `{table}` table while setup as a view")]
 	TableIsView {
@@ -459,6 +604,50 @@ pub enum Error {
 		value: String,
 	},
 
+	/// A replicated change is older than the last change applied to the record
+	#[error("The replicated change to record `{thing}` conflicts with a more recent change")]
+	ReplicationConflict {
+		thing: Thing,
+	},
+
+	/// The specified record is not of the type which the table can hold
+	#[error("Found record: `{thing}` which is {}a relation, but expected a {target_type}", if *relation { "" } else { "not " })]
+	TableCheck {
+		thing: String,
+		relation: bool,
+		target_type: String,
+	},
+
+	/// The specified value did not conform to the parameter type check
+	#[error("Found {value} for parameter `${name}`, but expected a {check}")]
+	ParamCheck {
+		name: String,
+		value: String,
+		check: String,
+	},
+
+	/// The specified value did not conform to the parameter ASSERT clause
+	#[error("Found {value} for parameter `${name}`, but parameter must conform to: {check}")]
+	ParamValue {
+		name: String,
+		value: String,
+		check: String,
+	},
+
+	/// The specified record is not one of the members of an enumerated table
+	#[error("Found record: `{thing}` which is not a member of the enumerated table `{table}`")]
+	TableMember {
+		thing: String,
+		table: String,
+	},
+
+	/// The specified record is one of the members of an enumerated table, so can not be deleted
+	#[error("Found record: `{thing}` which is a member of the enumerated table `{table}`, and can not be deleted")]
+	TableMemberDelete {
+		thing: String,
+		table: String,
+	},
+
 	/// The specified field did not conform to the field type check
 	#[error("Found {value} for field `{field}`, with record `{thing}`, but expected a {check}")]
 	FieldCheck {
@@ -477,6 +666,33 @@ pub enum Error {
 		check: String,
 	},
 
+	/// The specified field is not defined on the schemafull table of the record
+	#[error("Found field `{field}`, with record `{thing}`, but the field is not defined on the schemafull table")]
+	FieldUndefined {
+		thing: String,
+		field: Idiom,
+	},
+
+	/// The specified record can not be deleted while another record links to it
+	#[error("Unable to delete record `{thing}`, as it is referenced by the field `{field}` of record `{other}`")]
+	FieldReferenced {
+		thing: String,
+		field: String,
+		other: String,
+	},
+
+	/// An ON DELETE clause was specified for a field which does not hold record links
+	#[error("The ON DELETE clause can only be specified for the field `{field}` if it has a record type")]
+	FieldReferenceType {
+		field: String,
+	},
+
+	/// An ON DELETE SET NULL clause was specified for a field which can not be emptied
+	#[error("The ON DELETE SET NULL clause can only be specified for the field `{field}` if it has an option or array type")]
+	FieldReferenceSetNull {
+		field: String,
+	},
+
 	/// Found a record id for the record but we are creating a specific record
 	#[error("Found {value} for the id field, but a specific record has been specified")]
 	IdMismatch {
@@ -572,6 +788,12 @@ pub enum Error {
 		value: String,
 	},
 
+	/// The query planner did not find an index able to support the KNN <|k|> operator on a given expression
+	#[error("There was no suitable vector index supporting the expression '{value}'")]
+	NoIndexFoundForKnn {
+		value: String,
+	},
+
 	/// Represents an error when analyzing a value
 	#[error("A value can't be analyzed: {0}")]
 	AnalyzerError(String),
@@ -580,6 +802,14 @@ pub enum Error {
 	#[error("A value can't be highlighted: {0}")]
 	HighlightError(String),
 
+	/// Represents an error when a phrase is searched in an index which does not store the offsets of the terms
+	#[error("A phrase search requires a search index defined with HIGHLIGHTS")]
+	PhraseSearchNotSupported,
+
+	/// Represents an error when a phrase is searched in an index which was built before the positions of the terms were stored
+	#[error("A phrase search requires the search index to be rebuilt, as it was built without the positions of the terms")]
+	PhraseSearchRequiresRebuild,
+
 	/// Represents an underlying error with Bincode serializing / deserializing
 	#[error("Bincode error: {0}")]
 	Bincode(#[from] BincodeError),
@@ -604,6 +834,26 @@ pub enum Error {
 		mr: MatchRef,
 	},
 
+	/// The size of the vector is incorrect
+	#[error("Incorrect vector dimension ({current}). Expected a vector of {expected} dimension.")]
+	InvalidVectorDimension {
+		current: usize,
+		expected: usize,
+	},
+
+	/// The type of the vector is incorrect
+	#[error("Incorrect vector type: {current}. Expected a vector of {expected}.")]
+	InvalidVectorType {
+		current: String,
+		expected: String,
+	},
+
+	/// The value cannot be converted to a vector
+	#[error("The value cannot be converted to a vector: {current}")]
+	InvalidVectorValue {
+		current: String,
+	},
+
 	/// Represents a failure in timestamp arithmetic related to database internals
 	#[error("Timestamp arithmetic error: {0}")]
 	TimestampOverflow(String),
@@ -690,6 +940,16 @@ pub enum Error {
 	UnknownAuth,
 }
 
+impl Error {
+	/// Returns the structured diagnostic of a query which failed to parse
+	pub fn diagnostic(&self) -> Option<&ParserDiagnostic> {
+		match self {
+			Error::InvalidQuery(e) => Some(&e.diagnostic),
+			_ => None,
+		}
+	}
+}
+
 impl From<Error> for String {
 	fn from(e: Error) -> String {
 		e.to_string()
@@ -1,6 +1,7 @@
 use crate::err::Error;
 use crate::sql::value::Value;
-use crate::sql::{Array, Bytes, Datetime, Duration, Kind, Number, Strand, Thing};
+use crate::sql::{Array, Bytes, Datetime, Duration, Kind, Number, Object, Strand, Thing, Uuid};
+use std::fmt;
 
 /// Implemented by types that are commonly used, in a certain way, as arguments.
 pub trait FromArg: Sized {
@@ -55,12 +56,24 @@ impl FromArg for Array {
 	}
 }
 
+impl FromArg for Object {
+	fn from_arg(arg: Value) -> Result<Self, Error> {
+		arg.coerce_to_object()
+	}
+}
+
 impl FromArg for Bytes {
 	fn from_arg(arg: Value) -> Result<Self, Error> {
 		arg.coerce_to_bytes()
 	}
 }
 
+impl FromArg for Uuid {
+	fn from_arg(arg: Value) -> Result<Self, Error> {
+		arg.coerce_to_uuid()
+	}
+}
+
 impl FromArg for i64 {
 	fn from_arg(arg: Value) -> Result<Self, Error> {
 		arg.coerce_to_i64()
@@ -109,10 +122,64 @@ impl FromArg for Vec<Datetime> {
 	}
 }
 
+/// The number of arguments which a function accepts.
+///
+/// Some functions only accept some of the numbers of arguments within the range, which are
+/// rejected when the function is run, so a number of arguments which this accepts may still
+/// be invalid.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Arity {
+	/// The fewest arguments accepted
+	pub min: usize,
+	/// The most arguments accepted, if there is a limit
+	pub max: Option<usize>,
+}
+
+impl Arity {
+	const fn new(min: usize, max: Option<usize>) -> Self {
+		Self {
+			min,
+			max,
+		}
+	}
+
+	/// Returns whether a number of arguments is accepted.
+	pub fn accepts(&self, len: usize) -> bool {
+		len >= self.min && !matches!(self.max, Some(max) if len > max)
+	}
+
+	/// Returns the arity of a function, from the type of its arguments.
+	pub fn of<A: FromArgs, R>(_: impl FnOnce(A) -> R) -> Self {
+		A::arity()
+	}
+
+	/// Returns the arity of a function which is passed a context before its arguments.
+	pub fn of_with<C, A: FromArgs, R>(_: impl FnOnce(C, A) -> R) -> Self {
+		A::arity()
+	}
+}
+
+impl fmt::Display for Arity {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match (self.min, self.max) {
+			(0, Some(0)) => write!(f, "no arguments"),
+			(1, Some(1)) => write!(f, "1 argument"),
+			(min, Some(max)) if min == max => write!(f, "{min} arguments"),
+			(min, Some(max)) if min + 1 == max => write!(f, "{min} or {max} arguments"),
+			(min, Some(max)) => write!(f, "{min} to {max} arguments"),
+			(0, None) => write!(f, "any number of arguments"),
+			(1, None) => write!(f, "at least 1 argument"),
+			(min, None) => write!(f, "at least {min} arguments"),
+		}
+	}
+}
+
 pub trait FromArgs: Sized {
 	/// Convert a collection of argument values into a certain argument format, failing if there are
 	/// too many or too few arguments, or if one of the arguments could not be converted.
 	fn from_args(name: &str, args: Vec<Value>) -> Result<Self, Error>;
+	/// The number of arguments which this argument format accepts.
+	fn arity() -> Arity;
 }
 
 // Take ownership of the raw arguments collection, and assume responsibility of validating the
@@ -121,6 +188,9 @@ impl FromArgs for Vec<Value> {
 	fn from_args(_name: &str, args: Vec<Value>) -> Result<Self, Error> {
 		Ok(args)
 	}
+	fn arity() -> Arity {
+		Arity::new(0, None)
+	}
 }
 
 impl FromArgs for Vec<Array> {
@@ -135,6 +205,9 @@ impl FromArgs for Vec<Array> {
 			})
 			.collect()
 	}
+	fn arity() -> Arity {
+		Arity::new(0, None)
+	}
 }
 
 /// Some functions take a fixed number of arguments.
@@ -165,6 +238,9 @@ macro_rules! impl_tuple {
 					},)*
 				))
 			}
+			fn arity() -> Arity {
+				Arity::new($len, Some($len))
+			}
 		}
 	}
 }
@@ -199,6 +275,9 @@ impl<A: FromArg> FromArgs for (Option<A>,) {
 		}
 		Ok((a,))
 	}
+	fn arity() -> Arity {
+		Arity::new(0, Some(1))
+	}
 }
 
 // Some functions take 1 or 2 arguments, so the second argument is optional.
@@ -226,6 +305,9 @@ impl<A: FromArg, B: FromArg> FromArgs for (A, Option<B>) {
 		}
 		Ok((a, b))
 	}
+	fn arity() -> Arity {
+		Arity::new(1, Some(2))
+	}
 }
 
 // Some functions take 2 or 3 arguments, so the third argument is optional.
@@ -262,6 +344,9 @@ impl<A: FromArg, B: FromArg, C: FromArg> FromArgs for (A, B, Option<C>) {
 		}
 		Ok((a, b, c))
 	}
+	fn arity() -> Arity {
+		Arity::new(2, Some(3))
+	}
 }
 
 // Some functions take 0, 1, or 2 arguments, so both arguments are optional.
@@ -297,6 +382,9 @@ impl<A: FromArg, B: FromArg> FromArgs for (Option<A>, Option<B>) {
 		}
 		Ok((a, b))
 	}
+	fn arity() -> Arity {
+		Arity::new(0, Some(2))
+	}
 }
 
 // Some functions optionally take 2 arguments, or don't take any at all.
@@ -331,6 +419,9 @@ impl<A: FromArg, B: FromArg> FromArgs for (Option<(A, B)>,) {
 		}
 		Ok((a.zip(b),))
 	}
+	fn arity() -> Arity {
+		Arity::new(0, Some(2))
+	}
 }
 
 // Some functions take 1, 2, or 3 arguments. It is safe to assume that, if the second argument is
@@ -371,4 +462,7 @@ impl<A: FromArg, B: FromArg, C: FromArg> FromArgs for (A, Option<B>, Option<C>)
 		}
 		Ok((a, b, c))
 	}
+	fn arity() -> Arity {
+		Arity::new(1, Some(3))
+	}
 }
@@ -0,0 +1,49 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::iam::Action;
+use crate::sql::permission::Permission;
+use crate::sql::value::Value;
+
+pub async fn get(
+	(ctx, opt, txn, doc): (
+		&Context<'_>,
+		Option<&Options>,
+		Option<&Transaction>,
+		Option<&CursorDoc<'_>>,
+	),
+	(name,): (String,),
+) -> Result<Value, Error> {
+	match (opt, txn) {
+		(Some(opt), Some(txn)) => {
+			// Get the config definition
+			let cg = txn.lock().await.get_and_cache_db_config(opt.ns(), opt.db(), &name).await?;
+			// Check permissions
+			if opt.check_perms(Action::View) {
+				match &cg.permissions {
+					Permission::Full => (),
+					Permission::None => {
+						return Err(Error::ConfigPermissions {
+							name,
+						})
+					}
+					Permission::Specific(e) => {
+						// Disable permissions
+						let opt = &opt.new_with_perms(false);
+						// Process the PERMISSION clause
+						if !e.compute(ctx, opt, txn, doc).await?.is_truthy() {
+							return Err(Error::ConfigPermissions {
+								name,
+							});
+						}
+					}
+				}
+			}
+			// Return the settings
+			Ok(cg.value.to_owned())
+		}
+		_ => Ok(Value::None),
+	}
+}
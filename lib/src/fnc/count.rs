@@ -1,4 +1,5 @@
 use crate::err::Error;
+use crate::sql::array::{Array, Uniq};
 use crate::sql::value::Value;
 
 pub fn count((arg,): (Option<Value>,)) -> Result<Value, Error> {
@@ -9,3 +10,19 @@ pub fn count((arg,): (Option<Value>,)) -> Result<Value, Error> {
 		})
 		.unwrap_or_else(|| 1.into()))
 }
+
+pub fn distinct((arg,): (Value,)) -> Result<Value, Error> {
+	let vals: Vec<Value> = match arg {
+		// The items of nested arrays are counted individually
+		Value::Array(v) => v
+			.into_iter()
+			.flat_map(|v| match v {
+				Value::Array(v) => v.0,
+				v => vec![v],
+			})
+			.collect(),
+		v => vec![v],
+	};
+	let vals: Array = vals.into_iter().filter(|v| !v.is_none_or_null()).collect();
+	Ok(vals.uniq().len().into())
+}
@@ -50,6 +50,13 @@ pub fn centroid((arg,): (Value,)) -> Result<Value, Error> {
 	Ok(centroid.map(Into::into).unwrap_or(Value::None))
 }
 
+pub fn contains(geometries: (Value, Value)) -> Result<Value, Error> {
+	Ok(match geometries {
+		(Value::Geometry(v), Value::Geometry(w)) => v.contains(&w).into(),
+		_ => Value::None,
+	})
+}
+
 pub fn distance(points: (Value, Value)) -> Result<Value, Error> {
 	Ok(match points {
 		(Value::Geometry(Geometry::Point(v)), Value::Geometry(Geometry::Point(w))) => {
@@ -1,5 +1,6 @@
 use crate::err::Error;
 use crate::fnc::util::math::bottom::Bottom;
+use crate::fnc::util::math::covariance::Covariance;
 use crate::fnc::util::math::deviation::Deviation;
 use crate::fnc::util::math::interquartile::Interquartile;
 use crate::fnc::util::math::mean::Mean;
@@ -34,6 +35,17 @@ pub fn ceil((arg,): (Number,)) -> Result<Value, Error> {
 	Ok(arg.ceil().into())
 }
 
+pub fn corr((x, y): (Vec<Number>, Vec<Number>)) -> Result<Value, Error> {
+	paired("math::corr", &x, &y)?;
+	Ok((x.covariance(&y, false) / (x.covariance(&x, false) * y.covariance(&y, false)).sqrt())
+		.into())
+}
+
+pub fn covar((x, y): (Vec<Number>, Vec<Number>)) -> Result<Value, Error> {
+	paired("math::covar", &x, &y)?;
+	Ok(x.covariance(&y, true).into())
+}
+
 pub fn fixed((arg, p): (Number, i64)) -> Result<Value, Error> {
 	if p > 0 {
 		Ok(arg.fixed(p as usize).into())
@@ -102,6 +114,17 @@ pub fn product((array,): (Vec<Number>,)) -> Result<Value, Error> {
 	Ok(array.into_iter().product::<Number>().into())
 }
 
+pub fn regr_intercept((x, y): (Vec<Number>, Vec<Number>)) -> Result<Value, Error> {
+	paired("math::regr_intercept", &x, &y)?;
+	let slope = x.covariance(&y, false) / x.covariance(&x, false);
+	Ok((y.mean() - slope * x.mean()).into())
+}
+
+pub fn regr_slope((x, y): (Vec<Number>, Vec<Number>)) -> Result<Value, Error> {
+	paired("math::regr_slope", &x, &y)?;
+	Ok((x.covariance(&y, false) / x.covariance(&x, false)).into())
+}
+
 pub fn round((arg,): (Number,)) -> Result<Value, Error> {
 	Ok(arg.round().into())
 }
@@ -143,3 +166,14 @@ pub fn trimean((mut array,): (Vec<Number>,)) -> Result<Value, Error> {
 pub fn variance((array,): (Vec<Number>,)) -> Result<Value, Error> {
 	Ok(array.variance(true).into())
 }
+
+/// Checks that the two sets of values of a statistics function can be paired
+fn paired(name: &str, x: &[Number], y: &[Number]) -> Result<(), Error> {
+	if x.len() != y.len() {
+		return Err(Error::InvalidArguments {
+			name: name.to_owned(),
+			message: String::from("The two arrays must have the same length."),
+		});
+	}
+	Ok(())
+}
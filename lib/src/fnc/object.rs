@@ -0,0 +1,45 @@
+use crate::err::Error;
+use crate::sql::array::Array;
+use crate::sql::object::Object;
+use crate::sql::value::Value;
+
+pub fn entries((object,): (Object,)) -> Result<Value, Error> {
+	Ok(Value::Array(Array(
+		object.0.into_iter().map(|(k, v)| Value::Array(Array(vec![k.into(), v]))).collect(),
+	)))
+}
+
+pub fn from_entries((array,): (Array,)) -> Result<Value, Error> {
+	let mut object = Object::default();
+	for entry in array {
+		match entry {
+			Value::Array(Array(mut v)) if v.len() == 2 => {
+				let value = v.pop().unwrap_or_default();
+				let key = v.pop().unwrap_or_default();
+				object.insert(key.as_raw_string(), value);
+			}
+			_ => {
+				return Err(Error::InvalidArguments {
+					name: String::from("object::from_entries"),
+					message: String::from("Every entry must be an array of a key and a value."),
+				})
+			}
+		}
+	}
+	Ok(object.into())
+}
+
+pub fn pivot((keys, values): (Array, Array)) -> Result<Value, Error> {
+	if keys.len() != values.len() {
+		return Err(Error::InvalidArguments {
+			name: String::from("object::pivot"),
+			message: String::from("The two arrays must have the same length."),
+		});
+	}
+	let mut object = Object::default();
+	for (key, value) in keys.into_iter().zip(values) {
+		// Later values of the same key replace earlier ones
+		object.insert(key.as_raw_string(), value);
+	}
+	Ok(object.into())
+}
@@ -196,6 +196,24 @@ pub(crate) async fn matches(
 	Ok(Value::Bool(false))
 }
 
+pub(crate) async fn knn(
+	ctx: &Context<'_>,
+	doc: Option<&CursorDoc<'_>>,
+	exp: &Expression,
+) -> Result<Value, Error> {
+	if let Some(doc) = doc {
+		if let Some(thg) = doc.rid {
+			if let Some(pla) = ctx.get_query_planner() {
+				if let Some(exe) = pla.get_query_executor(&thg.tb) {
+					// The nearest neighbours have been collected when the query was planned
+					return exe.knn(thg, exp);
+				}
+			}
+		}
+	}
+	Ok(Value::Bool(false))
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -1,4 +1,5 @@
 use crate::cnf::ID_CHARS;
+use crate::dbs::seed;
 use crate::err::Error;
 use crate::sql::uuid::Uuid;
 use crate::sql::value::Value;
@@ -10,33 +11,33 @@ use rand::Rng;
 use ulid::Ulid;
 
 pub fn rand(_: ()) -> Result<Value, Error> {
-	Ok(rand::random::<f64>().into())
+	Ok(seed::with(|rng| rng.gen::<f64>()).into())
 }
 
 pub fn bool(_: ()) -> Result<Value, Error> {
-	Ok(rand::random::<bool>().into())
+	Ok(seed::with(|rng| rng.gen::<bool>()).into())
 }
 
 pub fn r#enum(mut args: Vec<Value>) -> Result<Value, Error> {
 	Ok(match args.len() {
 		0 => Value::None,
 		1 => match args.remove(0) {
-			Value::Array(v) => v.into_iter().choose(&mut rand::thread_rng()).unwrap_or(Value::None),
+			Value::Array(v) => seed::with(|rng| v.into_iter().choose(rng)).unwrap_or(Value::None),
 			v => v,
 		},
-		_ => args.into_iter().choose(&mut rand::thread_rng()).unwrap(),
+		_ => seed::with(|rng| args.into_iter().choose(rng)).unwrap(),
 	})
 }
 
 pub fn float((range,): (Option<(f64, f64)>,)) -> Result<Value, Error> {
 	Ok(if let Some((min, max)) = range {
 		if max < min {
-			rand::thread_rng().gen_range(max..=min)
+			seed::with(|rng| rng.gen_range(max..=min))
 		} else {
-			rand::thread_rng().gen_range(min..=max)
+			seed::with(|rng| rng.gen_range(min..=max))
 		}
 	} else {
-		rand::random::<f64>()
+		seed::with(|rng| rng.gen::<f64>())
 	}
 	.into())
 }
@@ -48,8 +49,8 @@ pub fn guid((arg1, arg2): (Option<i64>, Option<i64>)) -> Result<Value, Error> {
 	let val = if let Some((min, max)) = arg1.zip(arg2) {
 		match min {
 			min if (1..=LIMIT).contains(&min) => match max {
-				max if min <= max && max <= LIMIT => rand::thread_rng().gen_range(min as usize..=max as usize),
-				max if max >= 1 && max <= min => rand::thread_rng().gen_range(max as usize..=min as usize),
+				max if min <= max && max <= LIMIT => seed::with(|rng| rng.gen_range(min as usize..=max as usize)),
+				max if max >= 1 && max <= min => seed::with(|rng| rng.gen_range(max as usize..=min as usize)),
 				_ => return Err(Error::InvalidArguments {
 					name: String::from("rand::guid"),
 					message: format!("To generate a guid of between X and Y characters in length, the 2 arguments must be positive numbers and no higher than {LIMIT}."),
@@ -73,18 +74,18 @@ pub fn guid((arg1, arg2): (Option<i64>, Option<i64>)) -> Result<Value, Error> {
 		20
 	};
 	// Generate the random guid
-	Ok(nanoid!(val, &ID_CHARS).into())
+	Ok(nanoid!(val, &ID_CHARS, seed::bytes).into())
 }
 
 pub fn int((range,): (Option<(i64, i64)>,)) -> Result<Value, Error> {
 	Ok(if let Some((min, max)) = range {
 		if max < min {
-			rand::thread_rng().gen_range(max..=min)
+			seed::with(|rng| rng.gen_range(max..=min))
 		} else {
-			rand::thread_rng().gen_range(min..=max)
+			seed::with(|rng| rng.gen_range(min..=max))
 		}
 	} else {
-		rand::random::<i64>()
+		seed::with(|rng| rng.gen::<i64>())
 	}
 	.into())
 }
@@ -96,8 +97,8 @@ pub fn string((arg1, arg2): (Option<i64>, Option<i64>)) -> Result<Value, Error>
 	let val = if let Some((min, max)) = arg1.zip(arg2) {
 		match min {
 			min if (1..=LIMIT).contains(&min) => match max {
-				max if min <= max && max <= LIMIT => rand::thread_rng().gen_range(min as usize..=max as usize),
-				max if max >= 1 && max <= min => rand::thread_rng().gen_range(max as usize..=min as usize),
+				max if min <= max && max <= LIMIT => seed::with(|rng| rng.gen_range(min as usize..=max as usize)),
+				max if max >= 1 && max <= min => seed::with(|rng| rng.gen_range(max as usize..=min as usize)),
 				_ => return Err(Error::InvalidArguments {
 					name: String::from("rand::string"),
 					message: format!("To generate a string of between X and Y characters in length, the 2 arguments must be positive numbers and no higher than {LIMIT}."),
@@ -121,7 +122,7 @@ pub fn string((arg1, arg2): (Option<i64>, Option<i64>)) -> Result<Value, Error>
 		32
 	};
 	// Generate the random string
-	Ok(Alphanumeric.sample_string(&mut rand::thread_rng(), val).into())
+	Ok(seed::with(|rng| Alphanumeric.sample_string(rng, val)).into())
 }
 
 pub fn time((range,): (Option<(i64, i64)>,)) -> Result<Value, Error> {
@@ -131,8 +132,8 @@ pub fn time((range,): (Option<(i64, i64)>,)) -> Result<Value, Error> {
 	let val = if let Some((min, max)) = range {
 		match min {
 			min if (1..=LIMIT).contains(&min) => match max {
-				max if min <= max && max <= LIMIT => rand::thread_rng().gen_range(min..=max),
-				max if max >= 1 && max <= min => rand::thread_rng().gen_range(max..=min),
+				max if min <= max && max <= LIMIT => seed::with(|rng| rng.gen_range(min..=max)),
+				max if max >= 1 && max <= min => seed::with(|rng| rng.gen_range(max..=min)),
 				_ => return Err(Error::InvalidArguments {
 					name: String::from("rand::time"),
 					message: format!("To generate a time between X and Y seconds, the 2 arguments must be positive numbers and no higher than {LIMIT}."),
@@ -144,18 +145,18 @@ pub fn time((range,): (Option<(i64, i64)>,)) -> Result<Value, Error> {
 			}),
 		}
 	} else {
-		rand::thread_rng().gen_range(0..=LIMIT)
+		seed::with(|rng| rng.gen_range(0..=LIMIT))
 	};
 	// Generate the random time
 	Ok(Utc.timestamp_opt(val, 0).earliest().unwrap().into())
 }
 
 pub fn ulid(_: ()) -> Result<Value, Error> {
-	Ok(Ulid::new().to_string().into())
+	Ok(seed::with(|mut rng| Ulid::with_source(&mut rng)).to_string().into())
 }
 
 pub fn uuid(_: ()) -> Result<Value, Error> {
-	Ok(Uuid::new().into())
+	Ok(Uuid::new_seeded().into())
 }
 
 pub mod uuid {
@@ -165,12 +166,12 @@ pub mod uuid {
 	use crate::sql::value::Value;
 
 	pub fn v4(_: ()) -> Result<Value, Error> {
-		Ok(Uuid::new_v4().into())
+		Ok(Uuid::new_v4_seeded().into())
 	}
 
 	#[cfg(uuid_unstable)]
 	pub fn v7(_: ()) -> Result<Value, Error> {
-		Ok(Uuid::new_v7().into())
+		Ok(Uuid::new_v7_seeded().into())
 	}
 	#[cfg(not(uuid_unstable))]
 	pub fn v7(_: ()) -> Result<Value, Error> {
@@ -9,6 +9,7 @@ use super::query::{QueryContext, QUERY_DATA_PROP_NAME};
 
 mod array;
 mod bytes;
+mod config;
 mod crypto;
 mod duration;
 mod encoding;
@@ -19,10 +20,12 @@ mod meta;
 mod parse;
 mod rand;
 mod search;
+mod sequence;
 mod session;
 mod string;
 mod time;
 mod r#type;
+mod uuid;
 mod vector;
 
 pub struct Package;
@@ -32,6 +35,7 @@ impl_module_def!(
 	"", // root path
 	"array" => (array::Package),
 	"bytes" => (bytes::Package),
+	"config" => (config::Package),
 	"count" => run,
 	"crypto" => (crypto::Package),
 	"duration" => (duration::Package),
@@ -45,11 +49,13 @@ impl_module_def!(
 	"rand" => (rand::Package),
 	"array" => (array::Package),
 	"search" => (search::Package),
+	"sequence" => (sequence::Package),
 	"session" => (session::Package),
 	"sleep" => fut Async,
 	"string" => (string::Package),
 	"time" => (time::Package),
 	"type" => (r#type::Package),
+	"uuid" => (uuid::Package),
 	"vector" => (vector::Package)
 );
 
@@ -1,7 +1,8 @@
 use crate::ctx::Context;
-use crate::dbs::Transaction;
+use crate::dbs::{Options, Transaction};
 use crate::doc::CursorDoc;
 use crate::err::Error;
+use crate::idx::ft::analyzer::Analyzer;
 use crate::idx::planner::executor::QueryExecutor;
 use crate::sql::{Thing, Value};
 
@@ -56,3 +57,15 @@ pub async fn offsets(
 		Ok(Value::None)
 	}
 }
+
+pub async fn analyze(
+	(opt, txn): (Option<&Options>, Option<&Transaction>),
+	(az, val): (String, String),
+) -> Result<Value, Error> {
+	if let (Some(opt), Some(txn)) = (opt, txn) {
+		let az = txn.lock().await.get_db_analyzer(opt.ns(), opt.db(), &az).await?;
+		Analyzer::from(az).analyze_sample(val)
+	} else {
+		Ok(Value::None)
+	}
+}
@@ -0,0 +1,16 @@
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::err::Error;
+use crate::sql::value::Value;
+
+pub async fn next(
+	(opt, txn): (Option<&Options>, Option<&Transaction>),
+	(name,): (String,),
+) -> Result<Value, Error> {
+	if let (Some(opt), Some(txn)) = (opt, txn) {
+		let v = txn.lock().await.next_in_sequence(opt.ns(), opt.db(), &name).await?;
+		Ok(v.into())
+	} else {
+		Ok(Value::None)
+	}
+}
@@ -0,0 +1,27 @@
+use super::mean::Mean;
+use crate::sql::number::Number;
+
+pub trait Covariance {
+	/// Covariance of two sets of Data of the same length
+	/// O(n) time complex
+	fn covariance(&self, other: &Self, sample: bool) -> f64;
+}
+
+impl Covariance for Vec<Number> {
+	fn covariance(&self, other: &Self, sample: bool) -> f64 {
+		match self.len() {
+			0 => f64::NAN,
+			1 => 0.0,
+			len => {
+				let (mx, my) = (self.mean(), other.mean());
+				let len = (len - sample as usize) as f64;
+				let out = self
+					.iter()
+					.zip(other.iter())
+					.map(|(x, y)| (x.to_float() - mx) * (y.to_float() - my))
+					.sum::<f64>() / len;
+				out
+			}
+		}
+	}
+}
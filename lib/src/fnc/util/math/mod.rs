@@ -3,6 +3,7 @@
 // https://rust-lang-nursery.github.io/rust-cookbook/science/mathematics/statistics.html
 
 pub mod bottom;
+pub mod covariance;
 pub mod deviation;
 pub mod interquartile;
 pub mod mean;
@@ -0,0 +1,14 @@
+use crate::err::Error;
+use crate::sql::uuid::Uuid;
+use crate::sql::value::Value;
+
+pub fn time((val,): (Uuid,)) -> Result<Value, Error> {
+	Ok(match val.time() {
+		Some(v) => v.into(),
+		None => Value::None,
+	})
+}
+
+pub fn version((val,): (Uuid,)) -> Result<Value, Error> {
+	Ok(val.get_version_num().into())
+}
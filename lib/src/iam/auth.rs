@@ -6,15 +6,26 @@ use super::{is_allowed, Action, Actor, Error, Level, Resource, Role};
 
 /// Specifies the current authentication for the datastore execution context.
 #[derive(Clone, Default, Debug, Eq, PartialEq, PartialOrd, Hash, Serialize, Deserialize)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct Auth {
 	actor: Actor,
+	/// Whether this is a system authentication, which is not stored in the database
+	#[revision(start = 2)]
+	system: bool,
 }
 
 impl Auth {
 	pub fn new(actor: Actor) -> Self {
 		Self {
 			actor,
+			system: false,
+		}
+	}
+
+	fn system(actor: Actor) -> Self {
+		Self {
+			actor,
+			system: true,
 		}
 	}
 
@@ -52,20 +63,25 @@ impl Auth {
 		matches!(self.level(), Level::Scope(_, _, _))
 	}
 
+	/// Check if the current auth is a system auth
+	pub fn is_system(&self) -> bool {
+		self.system
+	}
+
 	/// System Auth helpers
 	///
 	/// These are not stored in the database and are used for internal operations
 	/// Do not use for authentication
 	pub fn for_root(role: Role) -> Self {
-		Self::new(Actor::new("system_auth".into(), vec![role], Level::Root))
+		Self::system(Actor::new("system_auth".into(), vec![role], Level::Root))
 	}
 
 	pub fn for_ns(role: Role, ns: &str) -> Self {
-		Self::new(Actor::new("system_auth".into(), vec![role], (ns,).into()))
+		Self::system(Actor::new("system_auth".into(), vec![role], (ns,).into()))
 	}
 
 	pub fn for_db(role: Role, ns: &str, db: &str) -> Self {
-		Self::new(Actor::new("system_auth".into(), vec![role], (ns, db).into()))
+		Self::system(Actor::new("system_auth".into(), vec![role], (ns, db).into()))
 	}
 
 	pub fn for_sc(rid: String, ns: &str, db: &str, sc: &str) -> Self {
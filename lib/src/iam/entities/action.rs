@@ -52,6 +52,7 @@ impl From<&Statement<'_>> for Action {
 			Statement::Relate(_) => Action::Edit,
 			Statement::Delete(_) => Action::Edit,
 			Statement::Insert(_) => Action::Edit,
+			Statement::Upsert(_) => Action::Edit,
 		}
 	}
 }
@@ -26,6 +26,8 @@ pub enum ResourceKind {
 	Event,
 	Field,
 	Index,
+	Config,
+	Sequence,
 
 	// IAM
 	Actor,
@@ -47,6 +49,8 @@ impl std::fmt::Display for ResourceKind {
 			ResourceKind::Event => write!(f, "Event"),
 			ResourceKind::Field => write!(f, "Field"),
 			ResourceKind::Index => write!(f, "Index"),
+			ResourceKind::Config => write!(f, "Config"),
+			ResourceKind::Sequence => write!(f, "Sequence"),
 			ResourceKind::Actor => write!(f, "Actor"),
 		}
 	}
@@ -46,6 +46,8 @@ pub static DEFAULT_CEDAR_SCHEMA: Lazy<serde_json::Value> = Lazy::new(|| {
 					"Event": {"shape": {"type": "Resource"}, "memberOfTypes": ["Level"]},
 					"Field": {"shape": {"type": "Resource"}, "memberOfTypes": ["Level"]},
 					"Index": {"shape": {"type": "Resource"}, "memberOfTypes": ["Level"]},
+					"Config": {"shape": {"type": "Resource"}, "memberOfTypes": ["Level"]},
+					"Sequence": {"shape": {"type": "Resource"}, "memberOfTypes": ["Level"]},
 
 					// IAM resource types
 					"Role": {},
@@ -65,14 +67,14 @@ pub static DEFAULT_CEDAR_SCHEMA: Lazy<serde_json::Value> = Lazy::new(|| {
 					"View": {
 						"appliesTo": {
 							"principalTypes": [ "Actor" ],
-							"resourceTypes": [ "Any", "Namespace", "Database", "Scope", "Table", "Document", "Option", "Function", "Analyzer", "Parameter", "Event", "Field", "Index", "Actor" ],
+							"resourceTypes": [ "Any", "Namespace", "Database", "Scope", "Table", "Document", "Option", "Function", "Analyzer", "Parameter", "Event", "Field", "Index", "Config", "Sequence", "Actor" ],
 
 						},
 					},
 					"Edit": {
 						"appliesTo": {
 							"principalTypes": [ "Actor" ],
-							"resourceTypes": [ "Any", "Namespace", "Database", "Scope", "Table", "Document", "Option", "Function", "Analyzer", "Parameter", "Event", "Field", "Index", "Actor" ],
+							"resourceTypes": [ "Any", "Namespace", "Database", "Scope", "Table", "Document", "Option", "Function", "Analyzer", "Parameter", "Event", "Field", "Index", "Config", "Sequence", "Actor" ],
 						},
 					},
 				},
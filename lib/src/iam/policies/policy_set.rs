@@ -24,7 +24,7 @@ pub static POLICY_SET: Lazy<PolicySet> = Lazy::new(|| {
     ) when {
         principal.roles.contains(Role::"Editor") &&
         resource.level in principal.level &&
-        ["Namespace", "Database", "Scope", "Table", "Document", "Option", "Function", "Analyzer", "Parameter", "Event", "Field", "Index"].contains(resource.type)
+        ["Namespace", "Database", "Scope", "Table", "Document", "Option", "Function", "Analyzer", "Parameter", "Event", "Field", "Index", "Config", "Sequence"].contains(resource.type)
     };
 
     // Owner role can edit all resources on the same level hierarchy or below
@@ -0,0 +1,471 @@
+use crate::ctx::Context;
+use crate::dbs::{Options, Transaction};
+use crate::doc::build_record;
+use crate::err::Error;
+use crate::idx::stats::{IndexStats, IndexStatsDelta};
+use crate::idx::VersionedSerdeState;
+use crate::key::index::ib::Ib;
+use crate::key::index::ip::Ip;
+use crate::key::thing;
+use crate::kvs;
+use crate::kvs::Key;
+use crate::sql::index::Index;
+use crate::sql::statements::DefineIndexStatement;
+use crate::sql::{Object, Thing, Uuid, Value};
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+
+/// The progress of an index defined with the `CONCURRENTLY` option.
+/// The state only exists while the index is being built.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[revisioned(revision = 1)]
+pub(crate) struct BuildingState {
+	/// The key of the last record which has been indexed
+	cursor: Option<Key>,
+	/// The number of records indexed so far
+	count: u64,
+	/// The keys which split the records of the table into shards, which are
+	/// indexed in parallel, or empty if the records are indexed in order
+	splits: Vec<Key>,
+	/// The node of the cluster which is building the index
+	owner: Option<Uuid>,
+	/// The reason the build was stopped, if it was killed or failed. A stopped
+	/// build is not resumed until the index is defined again
+	failed: Option<String>,
+}
+
+/// The progress of one shard of an index which is being built in parallel.
+/// Each shard is stored separately, so that the workers do not conflict.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[revisioned(revision = 1)]
+struct ShardState {
+	/// The key of the last record of the shard which has been indexed
+	cursor: Option<Key>,
+	/// The number of records of the shard indexed so far
+	count: u64,
+	/// Whether every record of the shard has been indexed
+	done: bool,
+	/// The changes to the statistics of the index, applied once every shard is done
+	delta: IndexStatsDelta,
+}
+
+impl VersionedSerdeState for ShardState {}
+
+impl ShardState {
+	async fn get(
+		run: &mut kvs::Transaction,
+		ns: &str,
+		db: &str,
+		ix: &DefineIndexStatement,
+		shard: u32,
+	) -> Result<Self, Error> {
+		let key = Ip::new(ns, db, &ix.what, &ix.name, shard);
+		match run.get(key).await? {
+			Some(val) => Self::try_from_val(val),
+			None => Ok(Self::default()),
+		}
+	}
+
+	async fn set(
+		&self,
+		run: &mut kvs::Transaction,
+		ns: &str,
+		db: &str,
+		ix: &DefineIndexStatement,
+		shard: u32,
+	) -> Result<(), Error> {
+		let key = Ip::new(ns, db, &ix.what, &ix.name, shard);
+		run.set(key, self.try_to_val()?).await
+	}
+}
+
+impl VersionedSerdeState for BuildingState {}
+
+impl BuildingState {
+	pub(crate) async fn get(
+		run: &mut kvs::Transaction,
+		ns: &str,
+		db: &str,
+		ix: &DefineIndexStatement,
+	) -> Result<Option<Self>, Error> {
+		let key = Ib::new(ns, db, &ix.what, &ix.name);
+		match run.get(key).await? {
+			Some(val) => Ok(Some(Self::try_from_val(val)?)),
+			None => Ok(None),
+		}
+	}
+
+	pub(crate) async fn set(
+		&self,
+		run: &mut kvs::Transaction,
+		ns: &str,
+		db: &str,
+		ix: &DefineIndexStatement,
+	) -> Result<(), Error> {
+		let key = Ib::new(ns, db, &ix.what, &ix.name);
+		run.set(key, self.try_to_val()?).await
+	}
+
+	pub(crate) async fn del(
+		run: &mut kvs::Transaction,
+		ns: &str,
+		db: &str,
+		ix: &DefineIndexStatement,
+	) -> Result<(), Error> {
+		let key = Ib::new(ns, db, &ix.what, &ix.name);
+		run.del(key).await
+	}
+
+	/// Marks the build as stopped, so that it is not resumed by any node.
+	pub(crate) async fn fail(
+		run: &mut kvs::Transaction,
+		ns: &str,
+		db: &str,
+		ix: &DefineIndexStatement,
+		reason: String,
+	) -> Result<(), Error> {
+		if let Some(mut state) = Self::get(run, ns, db, ix).await? {
+			state.failed = Some(reason);
+			state.set(run, ns, db, ix).await?;
+		}
+		Ok(())
+	}
+
+	/// Checks if the build was killed or failed.
+	pub(crate) fn is_failed(&self) -> bool {
+		self.failed.is_some()
+	}
+
+	/// Checks if the build was taken over from the given node by another node of the cluster.
+	fn is_taken_over(&self, node: uuid::Uuid) -> bool {
+		matches!(&self.owner, Some(owner) if owner.0 != node)
+	}
+
+	/// The number of shards which are indexed in parallel.
+	fn shards(&self) -> u32 {
+		self.splits.len() as u32 + 1
+	}
+
+	/// Returns the range of the keys of the records in a shard.
+	fn shard_range(&self, ns: &str, db: &str, tb: &str, shard: u32) -> (Key, Key) {
+		let shard = shard as usize;
+		let beg = match shard {
+			0 => thing::prefix(ns, db, tb),
+			i => self.splits[i - 1].clone(),
+		};
+		let end = match self.splits.get(shard) {
+			Some(k) => k.clone(),
+			None => thing::suffix(ns, db, tb),
+		};
+		(beg, end)
+	}
+
+	/// Returns the progress of the build, as reported by the INFO statements.
+	pub(crate) async fn status(
+		self,
+		run: &mut kvs::Transaction,
+		ns: &str,
+		db: &str,
+		ix: &DefineIndexStatement,
+	) -> Result<Value, Error> {
+		let mut res = Object::default();
+		match self.failed {
+			Some(ref e) => {
+				res.insert("status".to_owned(), "failed".into());
+				res.insert("error".to_owned(), e.clone().into());
+			}
+			None => {
+				res.insert("status".to_owned(), "building".into());
+			}
+		}
+		if self.splits.is_empty() {
+			res.insert("count".to_owned(), self.count.into());
+			return Ok(res.into());
+		}
+		let mut count = 0;
+		for shard in 0..self.shards() {
+			count += ShardState::get(run, ns, db, ix, shard).await?.count;
+		}
+		res.insert("count".to_owned(), count.into());
+		res.insert("workers".to_owned(), self.shards().into());
+		Ok(res.into())
+	}
+}
+
+/// Claims the build of an index for a node, unless it is already being built by another
+/// node of the cluster. The given nodes are those whose lease has not expired, as they
+/// have sent a heartbeat within the node timeout. The builds of the other nodes are taken
+/// over, and builds which were killed or failed are not claimed.
+/// Returns `true` if the index should be built by the given node.
+pub(crate) async fn claim(
+	run: &mut kvs::Transaction,
+	ns: &str,
+	db: &str,
+	ix: &DefineIndexStatement,
+	node: uuid::Uuid,
+	alive: &[uuid::Uuid],
+) -> Result<bool, Error> {
+	let mut state = match BuildingState::get(run, ns, db, ix).await? {
+		Some(state) if !state.is_failed() => state,
+		_ => return Ok(false),
+	};
+	match &state.owner {
+		Some(owner) if owner.0 == node => return Ok(true),
+		Some(owner) if alive.contains(&owner.0) => return Ok(false),
+		_ => (),
+	}
+	if let Some(owner) = &state.owner {
+		trace!("Taking over the build of index {} from node {}", ix.name, owner.0);
+	}
+	state.owner = Some(node.into());
+	state.set(run, ns, db, ix).await?;
+	Ok(true)
+}
+
+/// Indexes the next batch of records for an index being built in the background.
+/// Returns `true` once every record of the table has been indexed, or once the build
+/// has been taken over by another node.
+pub(crate) async fn build_batch(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	ix: &DefineIndexStatement,
+) -> Result<bool, Error> {
+	// Fetch the next batch of records
+	let mut run = txn.lock().await;
+	let mut state = match BuildingState::get(&mut run, opt.ns(), opt.db(), ix).await? {
+		Some(state) => state,
+		None => return Ok(true),
+	};
+	// A build which was taken over by another node is left to that node
+	if state.is_taken_over(opt.id()?) {
+		return Ok(true);
+	}
+	let beg = match &state.cursor {
+		Some(c) => {
+			let mut k = c.clone();
+			k.push(0x00);
+			k
+		}
+		None => thing::prefix(opt.ns(), opt.db(), &ix.what),
+	};
+	let end = thing::suffix(opt.ns(), opt.db(), &ix.what);
+	let res = run.scan(beg..end, opt.batch).await?;
+	drop(run);
+	// Index every record of the batch
+	let delta = build_records(ctx, opt, txn, ix, &res).await?;
+	// Record the progress
+	let mut run = txn.lock().await;
+	IndexStats::update(&mut run, opt.ns(), opt.db(), ix, delta).await?;
+	if res.len() < opt.batch as usize {
+		BuildingState::del(&mut run, opt.ns(), opt.db(), ix).await?;
+		IndexStats::built(&mut run, opt.ns(), opt.db(), ix).await?;
+		return Ok(true);
+	}
+	state.count += res.len() as u64;
+	state.cursor = res.last().map(|(k, _)| k.clone());
+	state.set(&mut run, opt.ns(), opt.db(), ix).await?;
+	Ok(false)
+}
+
+/// Splits the records of the table into a shard for each worker, if the index has not yet
+/// started being built, and if its entries can be built in parallel. Returns the number
+/// of shards, which are each indexed with [`build_shard_batch`].
+pub(crate) async fn plan(
+	run: &mut kvs::Transaction,
+	ns: &str,
+	db: &str,
+	ix: &DefineIndexStatement,
+	workers: usize,
+) -> Result<u32, Error> {
+	let mut state = match BuildingState::get(run, ns, db, ix).await? {
+		Some(state) => state,
+		None => return Ok(1),
+	};
+	if !state.splits.is_empty() {
+		return Ok(state.shards());
+	}
+	// The trees of the other kinds of index are shared by every record,
+	// so concurrent transactions writing to them would always conflict
+	let independent = matches!(ix.index, Index::Idx | Index::Uniq | Index::UniqNulls(_));
+	if workers < 2 || !independent || state.cursor.is_some() {
+		return Ok(1);
+	}
+	// Split the range between the first and the last record
+	let beg = thing::prefix(ns, db, &ix.what);
+	let end = thing::suffix(ns, db, &ix.what);
+	let first = run.scan(beg.clone()..end.clone(), 1).await?;
+	let last = run.scanr(beg..end, 1).await?;
+	if let (Some((first, _)), Some((last, _))) = (first.first(), last.first()) {
+		state.splits = split(first, last, workers);
+	}
+	if !state.splits.is_empty() {
+		state.set(run, ns, db, ix).await?;
+	}
+	Ok(state.shards())
+}
+
+/// Returns the keys which split the range between two keys into evenly sized shards,
+/// by interpolating between the bytes which follow the prefix shared by both keys.
+fn split(first: &[u8], last: &[u8], shards: usize) -> Vec<Key> {
+	let common = first.iter().zip(last).take_while(|(a, b)| a == b).count();
+	let read = |k: &[u8]| {
+		let mut buf = [0u8; 8];
+		buf.iter_mut().zip(&k[common..]).for_each(|(b, v)| *b = *v);
+		u64::from_be_bytes(buf)
+	};
+	let (lo, hi) = (read(first), read(last));
+	let step = hi.saturating_sub(lo) / shards as u64;
+	if step == 0 {
+		return Vec::new();
+	}
+	(1..shards as u64)
+		.map(|i| {
+			let mut k = first[..common].to_vec();
+			k.extend_from_slice(&(lo + step * i).to_be_bytes());
+			k
+		})
+		.collect()
+}
+
+/// Indexes the next batch of records of a shard, for an index being built in parallel.
+/// Returns `true` once every record of the shard has been indexed, or once the build
+/// has been taken over by another node.
+pub(crate) async fn build_shard_batch(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	ix: &DefineIndexStatement,
+	shard: u32,
+) -> Result<bool, Error> {
+	// Fetch the next batch of records of the shard
+	let mut run = txn.lock().await;
+	let building = match BuildingState::get(&mut run, opt.ns(), opt.db(), ix).await? {
+		Some(state) => state,
+		None => return Ok(true),
+	};
+	// A build which was taken over by another node is left to that node
+	if building.is_taken_over(opt.id()?) {
+		return Ok(true);
+	}
+	let mut state = ShardState::get(&mut run, opt.ns(), opt.db(), ix, shard).await?;
+	if state.done {
+		return Ok(true);
+	}
+	let (beg, end) = building.shard_range(opt.ns(), opt.db(), &ix.what, shard);
+	let beg = match &state.cursor {
+		Some(c) => {
+			let mut k = c.clone();
+			k.push(0x00);
+			k
+		}
+		None => beg,
+	};
+	let res = run.scan(beg..end, opt.batch).await?;
+	drop(run);
+	// Index every record of the batch
+	state.delta += build_records(ctx, opt, txn, ix, &res).await?;
+	// Record the progress of the shard
+	let mut run = txn.lock().await;
+	state.count += res.len() as u64;
+	if let Some((k, _)) = res.last() {
+		state.cursor = Some(k.clone());
+	}
+	state.done = res.len() < opt.batch as usize;
+	state.set(&mut run, opt.ns(), opt.db(), ix, shard).await?;
+	Ok(state.done)
+}
+
+/// Completes an index being built in parallel, once every shard has been indexed,
+/// merging the statistics of the shards. Returns `true` if the index was completed.
+pub(crate) async fn finish_shards(
+	run: &mut kvs::Transaction,
+	ns: &str,
+	db: &str,
+	ix: &DefineIndexStatement,
+) -> Result<bool, Error> {
+	let building = match BuildingState::get(run, ns, db, ix).await? {
+		Some(state) => state,
+		None => return Ok(true),
+	};
+	let mut delta = IndexStatsDelta::default();
+	for shard in 0..building.shards() {
+		let state = ShardState::get(run, ns, db, ix, shard).await?;
+		if !state.done {
+			return Ok(false);
+		}
+		delta += state.delta;
+	}
+	for shard in 0..building.shards() {
+		run.del(Ip::new(ns, db, &ix.what, &ix.name, shard)).await?;
+	}
+	IndexStats::update(run, ns, db, ix, delta).await?;
+	BuildingState::del(run, ns, db, ix).await?;
+	IndexStats::built(run, ns, db, ix).await?;
+	Ok(true)
+}
+
+/// Indexes a batch of records, returning the changes to the statistics of the index.
+/// The records are written again, as a fence, so that a document write which changes
+/// any record of the batch conflicts with the batch, which is then indexed again.
+async fn build_records(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	ix: &DefineIndexStatement,
+	res: &[(Key, kvs::Val)],
+) -> Result<IndexStatsDelta, Error> {
+	let mut delta = IndexStatsDelta::default();
+	for (k, v) in res.iter() {
+		let key: thing::Thing = k.into();
+		let mut val: Value = v.into();
+		let rid = Thing::from((key.tb, key.id));
+		crate::doc::resolve(opt, txn, &rid, &mut val).await?;
+		delta += build_record(ctx, opt, txn, ix, &rid, &val).await?;
+		txn.lock().await.set(k.clone(), v.clone()).await?;
+	}
+	Ok(delta)
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn split_key_range() {
+		let splits = split(b"/*t*\x00\x01", b"/*t*\xff", 4);
+		assert_eq!(splits.len(), 3);
+		assert!(splits.windows(2).all(|w| w[0] < w[1]));
+		assert!(splits[0].as_slice() > b"/*t*\x00\x01".as_slice());
+		assert!(splits[2].as_slice() < b"/*t*\xff".as_slice());
+		assert!(splits.iter().all(|k| k.starts_with(b"/*t*")));
+	}
+
+	#[test]
+	fn split_adjacent_keys() {
+		assert!(split(b"/*t*a", b"/*t*a\x00", 4).is_empty());
+		assert!(split(b"/*t*a", b"/*t*b", 1).is_empty());
+	}
+
+	#[tokio::test]
+	async fn claim_builds() {
+		let ds = crate::kvs::Datastore::new("memory").await.unwrap();
+		let ix = DefineIndexStatement::default();
+		let (a, b) = (uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		// An index which is not being built can not be claimed
+		assert!(!claim(&mut tx, "ns", "db", &ix, a, &[a, b]).await.unwrap());
+		BuildingState::default().set(&mut tx, "ns", "db", &ix).await.unwrap();
+		assert!(claim(&mut tx, "ns", "db", &ix, a, &[a, b]).await.unwrap());
+		assert!(claim(&mut tx, "ns", "db", &ix, a, &[a, b]).await.unwrap());
+		// A build is not claimed while the lease of its owner has not expired
+		assert!(!claim(&mut tx, "ns", "db", &ix, b, &[a, b]).await.unwrap());
+		assert!(claim(&mut tx, "ns", "db", &ix, b, &[b]).await.unwrap());
+		assert!(!claim(&mut tx, "ns", "db", &ix, a, &[a, b]).await.unwrap());
+		// A build which was killed or failed is not claimed
+		BuildingState::fail(&mut tx, "ns", "db", &ix, "killed".to_owned()).await.unwrap();
+		assert!(!claim(&mut tx, "ns", "db", &ix, b, &[b]).await.unwrap());
+		tx.cancel().await.unwrap();
+	}
+}
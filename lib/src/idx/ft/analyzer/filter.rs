@@ -4,6 +4,7 @@ use crate::sql::filter::Filter as SqlFilter;
 use crate::sql::language::Language;
 use deunicode::deunicode;
 use rust_stemmers::{Algorithm, Stemmer};
+use std::collections::HashSet;
 
 pub(super) enum Filter {
 	Stemmer(Stemmer),
@@ -12,6 +13,7 @@ pub(super) enum Filter {
 	EdgeNgram(u16, u16),
 	Lowercase,
 	Uppercase,
+	Stopwords(HashSet<String>),
 }
 
 impl From<SqlFilter> for Filter {
@@ -44,6 +46,7 @@ impl From<SqlFilter> for Filter {
 				Filter::Stemmer(a)
 			}
 			SqlFilter::Uppercase => Filter::Uppercase,
+			SqlFilter::Stopwords(w) => Filter::Stopwords(w.into_iter().collect()),
 		}
 	}
 }
@@ -78,6 +81,7 @@ impl Filter {
 			Filter::Ngram(min, max) => Self::ngram(c, *min, *max),
 			Filter::Stemmer(s) => Self::stem(s, c),
 			Filter::Uppercase => Self::uppercase(c),
+			Filter::Stopwords(w) => Self::stopwords(w, c),
 		}
 	}
 
@@ -112,6 +116,15 @@ impl Filter {
 		Self::check_term(c, s.stem(&c.to_lowercase()).into())
 	}
 
+	#[inline]
+	fn stopwords(w: &HashSet<String>, c: &str) -> FilterResult {
+		if w.contains(c) {
+			FilterResult::Ignore
+		} else {
+			FilterResult::Term(Term::Unchanged)
+		}
+	}
+
 	#[inline]
 	fn ngram(c: &str, min: u16, max: u16) -> FilterResult {
 		let min = min as usize;
@@ -663,4 +676,13 @@ mod tests {
 			&["āl", "āle", "ia", "iac", "es", "est"],
 		);
 	}
+
+	#[test]
+	fn test_stopwords() {
+		test_analyzer(
+			"ANALYZER test TOKENIZERS blank FILTERS lowercase,stopwords('the','is');",
+			"The-Product-Name is the best",
+			&["the-product-name", "best"],
+		);
+	}
 }
@@ -2,15 +2,16 @@ use crate::err::Error;
 use crate::idx::ft::analyzer::tokenizer::{Tokenizer, Tokens};
 use crate::idx::ft::doclength::DocLength;
 use crate::idx::ft::offsets::{Offset, OffsetRecords};
+use crate::idx::ft::phrase::{self, Phrase};
 use crate::idx::ft::postings::TermFrequency;
 use crate::idx::ft::terms::{TermId, Terms};
 use crate::kvs::Transaction;
 use crate::sql::statements::DefineAnalyzerStatement;
 use crate::sql::tokenizer::Tokenizer as SqlTokenizer;
-use crate::sql::Value;
+use crate::sql::{Array, Object, Value};
 use filter::Filter;
 use std::collections::hash_map::Entry;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 
 mod filter;
 mod tokenizer;
@@ -21,7 +22,7 @@ impl Analyzers {
 	pub(crate) const LIKE: &'static str = "like";
 }
 
-pub(super) struct Analyzer {
+pub(crate) struct Analyzer {
 	t: Option<Vec<SqlTokenizer>>,
 	f: Option<Vec<Filter>>,
 }
@@ -36,26 +37,35 @@ impl From<DefineAnalyzerStatement> for Analyzer {
 }
 
 impl Analyzer {
+	/// Extracts the terms of a query string, along with its phrases.
+	/// The terms are deduplicated, and the phrases refer to the index of their terms.
 	pub(super) async fn extract_terms(
 		&self,
 		t: &Terms,
 		tx: &mut Transaction,
 		query_string: String,
-	) -> Result<Vec<Option<TermId>>, Error> {
-		let tokens = self.analyze(query_string)?;
-		// We first collect every unique terms
-		// as it can contains duplicates
-		let mut terms = HashSet::new();
-		for token in tokens.list() {
-			terms.insert(token);
+		fuzziness: u8,
+	) -> Result<(Vec<Vec<TermId>>, Vec<Phrase>), Error> {
+		let (text, phrases) = phrase::parse_query(&query_string);
+		let mut terms = QueryTerms::default();
+		let tokens = self.analyze(text)?;
+		for tk in tokens.list() {
+			terms.resolve(t, tx, tokens.get_token_string(tk)?, fuzziness).await?;
 		}
-		// Now we can extract the term ids
-		let mut res = Vec::with_capacity(terms.len());
-		for term in terms {
-			let opt_term_id = t.get_term_id(tx, tokens.get_token_string(term)?).await?;
-			res.push(opt_term_id);
+		let mut res = Vec::with_capacity(phrases.len());
+		for (phrase, distance) in phrases {
+			let tokens = self.analyze(phrase)?;
+			let mut positions: Vec<Vec<usize>> = Vec::new();
+			for (p, tk) in tokens.positions() {
+				let i = terms.resolve(t, tx, tokens.get_token_string(tk)?, fuzziness).await?;
+				match positions.get_mut(p as usize) {
+					Some(v) => v.push(i),
+					None => positions.push(vec![i]),
+				}
+			}
+			res.push(Phrase::new(positions, distance));
 		}
-		Ok(res)
+		Ok((terms.ids, res))
 	}
 
 	/// This method is used for indexing.
@@ -111,10 +121,10 @@ impl Analyzer {
 		// We then collect every unique terms and count the frequency and extract the offsets
 		let mut tfos: HashMap<&str, Vec<Offset>> = HashMap::new();
 		for (i, tks) in inputs.iter().enumerate() {
-			for tk in tks.list() {
+			for (p, tk) in tks.positions() {
 				dl += 1;
 				let s = tks.get_token_string(tk)?;
-				let o = tk.new_offset(i as u32);
+				let o = tk.new_offset(i as u32, p);
 				match tfos.entry(s) {
 					Entry::Vacant(e) => {
 						e.insert(vec![o]);
@@ -135,6 +145,23 @@ impl Analyzer {
 		Ok((dl, tfid, osid))
 	}
 
+	/// Analyzes a sample string, returning each token along with its position,
+	/// and the offsets of the characters of the input it was produced from.
+	pub(crate) fn analyze_sample(&self, input: String) -> Result<Value, Error> {
+		let tokens = self.analyze(input)?;
+		let mut res = Vec::with_capacity(tokens.list().len());
+		for (p, tk) in tokens.positions() {
+			let o = tk.new_offset(0, p);
+			res.push(Value::from(Object::from(map! {
+				"token".to_string() => Value::from(tokens.get_token_string(tk)?),
+				"position".to_string() => Value::from(p),
+				"start".to_string() => Value::from(o.start),
+				"end".to_string() => Value::from(o.end),
+			})));
+		}
+		Ok(Value::from(Array::from(res)))
+	}
+
 	fn analyze_content(&self, content: &[Value], tks: &mut Vec<Tokens>) -> Result<(), Error> {
 		for v in content {
 			self.analyze_value(v, tks)?;
@@ -144,7 +171,7 @@ impl Analyzer {
 
 	fn analyze_value(&self, val: &Value, tks: &mut Vec<Tokens>) -> Result<(), Error> {
 		match val {
-			Value::Strand(s) => tks.push(self.analyze(s.0.clone())?),
+			Value::Strand(s) => tks.push(self.analyze(s.as_str().to_owned())?),
 			Value::Number(n) => tks.push(self.analyze(n.to_string())?),
 			Value::Bool(b) => tks.push(self.analyze(b.to_string())?),
 			Value::Array(a) => {
@@ -173,10 +200,43 @@ impl Analyzer {
 	}
 }
 
+/// The unique terms of a query string
+#[derive(Default)]
+struct QueryTerms {
+	index: HashMap<String, usize>,
+	ids: Vec<Vec<TermId>>,
+}
+
+impl QueryTerms {
+	/// Returns the index of a term, after collecting the ids of the matching terms of the index.
+	/// The ids are the terms within the given edit distance, which is an exact match if the distance is 0.
+	async fn resolve(
+		&mut self,
+		t: &Terms,
+		tx: &mut Transaction,
+		term: &str,
+		fuzziness: u8,
+	) -> Result<usize, Error> {
+		if let Some(i) = self.index.get(term) {
+			return Ok(*i);
+		}
+		let term_ids = if fuzziness > 0 {
+			t.get_fuzzy_term_ids(tx, term, fuzziness).await?
+		} else {
+			t.get_term_id(tx, term).await?.into_iter().collect()
+		};
+		let i = self.ids.len();
+		self.ids.push(term_ids);
+		self.index.insert(term.to_owned(), i);
+		Ok(i)
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::Analyzer;
 	use crate::sql::statements::define::analyzer;
+	use crate::sql::Value;
 
 	pub(super) fn test_analyzer(def: &str, input: &str, expected: &[&str]) {
 		let (_, az) = analyzer(def).unwrap();
@@ -189,4 +249,18 @@ mod tests {
 		}
 		assert_eq!(&res, expected);
 	}
+
+	#[test]
+	fn test_analyze_sample() {
+		let (_, az) = analyzer("DEFINE ANALYZER test TOKENIZERS blank FILTERS lowercase").unwrap();
+		let a: Analyzer = az.into();
+		let res = a.analyze_sample("Hello  World".to_string()).unwrap();
+		let val = Value::parse(
+			"[
+				{ token: 'hello', position: 0, start: 0, end: 5 },
+				{ token: 'world', position: 1, start: 7, end: 12 },
+			]",
+		);
+		assert_eq!(res, val);
+	}
 }
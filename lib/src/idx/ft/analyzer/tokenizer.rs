@@ -65,6 +65,23 @@ impl Tokens {
 	pub(super) fn list(&self) -> &Vec<Token> {
 		&self.t
 	}
+
+	/// Returns the tokens along with their position.
+	/// The tokens produced from the same word (e.g. by n-gram filters) share the same position.
+	pub(super) fn positions(&self) -> Vec<(Position, &Token)> {
+		let mut res = Vec::with_capacity(self.t.len());
+		let mut position = 0;
+		let mut last = None;
+		for t in &self.t {
+			let start = t.start();
+			if last.is_some_and(|l| l != start) {
+				position += 1;
+			}
+			last = Some(start);
+			res.push((position, t));
+		}
+		res
+	}
 }
 
 #[derive(Clone, Debug, PartialOrd, PartialEq, Eq, Ord, Hash)]
@@ -103,16 +120,29 @@ impl Token {
 		}
 	}
 
-	pub(super) fn new_offset(&self, i: u32) -> Offset {
+	pub(super) fn new_offset(&self, i: u32, position: Position) -> Offset {
+		match self {
+			Token::Ref {
+				chars,
+				..
+			} => Offset::new(i, chars.0, chars.1, position),
+			Token::String {
+				chars,
+				..
+			} => Offset::new(i, chars.0, chars.1, position),
+		}
+	}
+
+	fn start(&self) -> Position {
 		match self {
 			Token::Ref {
 				chars,
 				..
-			} => Offset::new(i, chars.0, chars.1),
+			} => chars.0,
 			Token::String {
 				chars,
 				..
-			} => Offset::new(i, chars.0, chars.1),
+			} => chars.0,
 		}
 	}
 
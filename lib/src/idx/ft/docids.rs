@@ -25,7 +25,7 @@ pub(crate) struct DocIds {
 }
 
 impl DocIds {
-	pub(super) async fn new(
+	pub(in crate::idx) async fn new(
 		tx: &mut Transaction,
 		index_key_base: IndexKeyBase,
 		default_btree_order: u32,
@@ -37,8 +37,11 @@ impl DocIds {
 		} else {
 			State::new(default_btree_order)
 		};
-		let store =
-			TreeNodeStore::new(TreeNodeProvider::DocIds(index_key_base.clone()), store_type, 20);
+		let store = TreeNodeStore::new(
+			TreeNodeProvider::DocIds(index_key_base.clone()),
+			store_type,
+			tx.index_cache_size(),
+		);
 		Ok(Self {
 			state_key,
 			index_key_base,
@@ -78,7 +81,7 @@ impl DocIds {
 
 	/// Returns the doc_id for the given doc_key.
 	/// If the doc_id does not exists, a new one is created, and associated to the given key.
-	pub(super) async fn resolve_doc_id(
+	pub(in crate::idx) async fn resolve_doc_id(
 		&mut self,
 		tx: &mut Transaction,
 		doc_key: Key,
@@ -97,7 +100,7 @@ impl DocIds {
 		Ok(Resolved::New(doc_id))
 	}
 
-	pub(super) async fn remove_doc(
+	pub(in crate::idx) async fn remove_doc(
 		&mut self,
 		tx: &mut Transaction,
 		doc_key: Key,
@@ -119,7 +122,7 @@ impl DocIds {
 		}
 	}
 
-	pub(super) async fn get_doc_key(
+	pub(in crate::idx) async fn get_doc_key(
 		&self,
 		tx: &mut Transaction,
 		doc_id: DocId,
@@ -132,12 +135,15 @@ impl DocIds {
 		}
 	}
 
-	pub(super) async fn statistics(&self, tx: &mut Transaction) -> Result<BStatistics, Error> {
+	pub(in crate::idx) async fn statistics(
+		&self,
+		tx: &mut Transaction,
+	) -> Result<BStatistics, Error> {
 		let mut store = self.store.lock().await;
 		self.btree.statistics(tx, &mut store).await
 	}
 
-	pub(super) async fn finish(&mut self, tx: &mut Transaction) -> Result<(), Error> {
+	pub(in crate::idx) async fn finish(&mut self, tx: &mut Transaction) -> Result<(), Error> {
 		let updated = self.store.lock().await.finish(tx).await?;
 		if self.updated || updated {
 			let state = State {
@@ -172,20 +178,20 @@ impl State {
 }
 
 #[derive(Debug, PartialEq)]
-pub(super) enum Resolved {
+pub(in crate::idx) enum Resolved {
 	New(DocId),
 	Existing(DocId),
 }
 
 impl Resolved {
-	pub(super) fn doc_id(&self) -> &DocId {
+	pub(in crate::idx) fn doc_id(&self) -> &DocId {
 		match self {
 			Resolved::New(doc_id) => doc_id,
 			Resolved::Existing(doc_id) => doc_id,
 		}
 	}
 
-	pub(super) fn was_existing(&self) -> bool {
+	pub(in crate::idx) fn was_existing(&self) -> bool {
 		match self {
 			Resolved::New(_) => false,
 			Resolved::Existing(_) => true,
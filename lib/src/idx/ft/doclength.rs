@@ -29,8 +29,11 @@ impl DocLengths {
 		} else {
 			BState::new(default_btree_order)
 		};
-		let store =
-			TreeNodeStore::new(TreeNodeProvider::DocLengths(index_key_base), store_type, 20);
+		let store = TreeNodeStore::new(
+			TreeNodeProvider::DocLengths(index_key_base),
+			store_type,
+			tx.index_cache_size(),
+		);
 		Ok(Self {
 			state_key,
 			btree: BTree::new(state),
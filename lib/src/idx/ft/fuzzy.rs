@@ -0,0 +1,97 @@
+use crate::idx::trees::btree::KeyMatcher;
+use crate::kvs::Key;
+
+/// A Levenshtein automaton, which accepts the terms within a maximum edit distance of a term.
+/// The state of the automaton is the row of the edit distances between the prefixes
+/// of the term and the input consumed so far. Distances are bounded to `distance + 1`,
+/// and the state is dead when every distance of the row exceeds the maximum distance.
+pub(super) struct Levenshtein {
+	term: Vec<char>,
+	distance: u32,
+}
+
+impl Levenshtein {
+	pub(super) fn new(term: &str, distance: u8) -> Self {
+		Self {
+			term: term.chars().collect(),
+			distance: distance as u32,
+		}
+	}
+
+	fn start(&self) -> Vec<u32> {
+		(0..=self.term.len() as u32).map(|d| d.min(self.distance + 1)).collect()
+	}
+
+	fn step(&self, row: &[u32], c: char) -> Vec<u32> {
+		let limit = self.distance + 1;
+		let mut next = Vec::with_capacity(row.len());
+		next.push((row[0] + 1).min(limit));
+		for (i, t) in self.term.iter().enumerate() {
+			let substitution = row[i] + u32::from(*t != c);
+			let deletion = row[i + 1] + 1;
+			let insertion = next[i] + 1;
+			next.push(substitution.min(deletion).min(insertion).min(limit));
+		}
+		next
+	}
+
+	/// Consumes the input, and returns the resulting state, unless the automaton is dead
+	fn run(&self, input: &str) -> Option<Vec<u32>> {
+		let mut row = self.start();
+		for c in input.chars() {
+			row = self.step(&row, c);
+			if row.iter().all(|d| *d > self.distance) {
+				return None;
+			}
+		}
+		Some(row)
+	}
+
+	fn is_match(&self, row: &[u32]) -> bool {
+		row.last().map_or(false, |d| *d <= self.distance)
+	}
+}
+
+impl KeyMatcher for Levenshtein {
+	fn matches(&self, key: &Key) -> bool {
+		match std::str::from_utf8(key) {
+			Ok(term) => self.run(term).map_or(false, |row| self.is_match(&row)),
+			Err(_) => false,
+		}
+	}
+
+	fn may_match_prefix(&self, prefix: &[u8]) -> bool {
+		// A prefix may end in the middle of a character, which is ignored
+		let prefix = match std::str::from_utf8(prefix) {
+			Ok(prefix) => prefix,
+			Err(e) => std::str::from_utf8(&prefix[..e.valid_up_to()]).unwrap_or_default(),
+		};
+		self.run(prefix).is_some()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::idx::ft::fuzzy::Levenshtein;
+	use crate::idx::trees::btree::KeyMatcher;
+
+	#[test]
+	fn levenshtein_automaton() {
+		let a = Levenshtein::new("surrealdb", 2);
+		assert!(a.matches(&"surrealdb".into()));
+		assert!(a.matches(&"surealdb".into()));
+		assert!(a.matches(&"surrealdbs".into()));
+		assert!(a.matches(&"sureal_db".into()));
+		assert!(a.matches(&"surreal".into()));
+		assert!(!a.matches(&"surre".into()));
+		assert!(!a.matches(&"postgres".into()));
+		assert!(a.may_match_prefix(b"surr"));
+		assert!(a.may_match_prefix(b"xyurr"));
+		assert!(!a.may_match_prefix(b"xyz"));
+		let a = Levenshtein::new("été", 1);
+		assert!(!a.matches(&"ete".into()));
+		assert!(a.matches(&"eté".into()));
+		// A prefix ending in the middle of a character
+		assert!(a.may_match_prefix(&"é".as_bytes()[..1]));
+	}
+}
@@ -1,8 +1,10 @@
 pub(crate) mod analyzer;
 pub(crate) mod docids;
 mod doclength;
+mod fuzzy;
 mod highlighter;
 mod offsets;
+pub(super) mod phrase;
 mod postings;
 pub(super) mod scorer;
 pub(super) mod termdocs;
@@ -14,6 +16,7 @@ use crate::idx::ft::docids::{DocId, DocIds};
 use crate::idx::ft::doclength::DocLengths;
 use crate::idx::ft::highlighter::{Highlighter, Offseter};
 use crate::idx::ft::offsets::Offsets;
+use crate::idx::ft::phrase::Phrases;
 use crate::idx::ft::postings::Postings;
 use crate::idx::ft::scorer::BM25Scorer;
 use crate::idx::ft::termdocs::{TermDocs, TermsDocs};
@@ -73,6 +76,17 @@ pub(crate) struct FtStatistics {
 	postings: BStatistics,
 }
 
+impl FtStatistics {
+	/// The depth of the deepest tree of the index
+	pub(crate) fn max_depth(&self) -> u32 {
+		self.doc_ids
+			.max_depth
+			.max(self.terms.max_depth)
+			.max(self.doc_lengths.max_depth)
+			.max(self.postings.max_depth)
+	}
+}
+
 impl From<FtStatistics> for Value {
 	fn from(stats: FtStatistics) -> Self {
 		let mut res = Object::default();
@@ -85,10 +99,14 @@ impl From<FtStatistics> for Value {
 }
 
 #[derive(Default, Serialize, Deserialize)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 struct State {
 	total_docs_lengths: u128,
 	doc_count: u64,
+	/// Whether the positions of the terms are stored along with every offset,
+	/// which is not the case for indexes built before positions were stored
+	#[revision(start = 2)]
+	positions: bool,
 }
 
 impl VersionedSerdeState for State {}
@@ -105,7 +123,10 @@ impl FtIndex {
 		let state: State = if let Some(val) = tx.get(state_key.clone()).await? {
 			State::try_from_val(val)?
 		} else {
-			State::default()
+			State {
+				positions: true,
+				..Default::default()
+			}
 		};
 		let doc_ids = Arc::new(RwLock::new(
 			DocIds::new(tx, index_key_base.clone(), p.doc_ids_order, store_type).await?,
@@ -292,33 +313,42 @@ impl FtIndex {
 		Ok(())
 	}
 
+	/// Extracts the terms of a query string, and its phrases.
+	/// For each term of the query, the ids of the terms of the index within
+	/// the given edit distance are returned, which is an exact match if the distance is 0.
 	pub(super) async fn extract_terms(
 		&self,
 		tx: &mut Transaction,
 		query_string: String,
-	) -> Result<Vec<Option<TermId>>, Error> {
+		fuzziness: u8,
+	) -> Result<(Vec<Vec<TermId>>, Phrases), Error> {
 		let t = self.terms.read().await;
-		let terms = self.analyzer.extract_terms(&t, tx, query_string).await?;
-		Ok(terms)
+		let (terms, phrases) = self.analyzer.extract_terms(&t, tx, query_string, fuzziness).await?;
+		// The positions of the terms are only stored along with the offsets
+		if !phrases.is_empty() && !self.highlighting {
+			return Err(Error::PhraseSearchNotSupported);
+		}
+		// Documents indexed without positions would never match a phrase
+		if !phrases.is_empty() && !self.state.positions {
+			return Err(Error::PhraseSearchRequiresRebuild);
+		}
+		let phrases = Phrases::new(self.offsets.clone(), terms.clone(), phrases);
+		Ok((terms, phrases))
 	}
 
 	pub(super) async fn get_terms_docs(
 		&self,
 		tx: &mut Transaction,
-		terms: &Vec<Option<TermId>>,
-	) -> Result<Vec<Option<(TermId, RoaringTreemap)>>, Error> {
+		terms: &[Vec<TermId>],
+	) -> Result<Vec<Vec<(TermId, RoaringTreemap)>>, Error> {
 		let mut terms_docs = Vec::with_capacity(terms.len());
-		for opt_term_id in terms {
-			if let Some(term_id) = opt_term_id {
+		for term_ids in terms {
+			let mut term_docs = Vec::with_capacity(term_ids.len());
+			for term_id in term_ids {
 				let docs = self.term_docs.get_docs(tx, *term_id).await?;
-				if let Some(docs) = docs {
-					terms_docs.push(Some((*term_id, docs)));
-				} else {
-					terms_docs.push(Some((*term_id, RoaringTreemap::new())));
-				}
-			} else {
-				terms_docs.push(None);
+				term_docs.push((*term_id, docs.unwrap_or_default()));
 			}
+			terms_docs.push(term_docs);
 		}
 		Ok(terms_docs)
 	}
@@ -326,22 +356,28 @@ impl FtIndex {
 	pub(super) fn new_hits_iterator(
 		&self,
 		terms_docs: TermsDocs,
+		phrases: Phrases,
 	) -> Result<Option<HitsIterator>, Error> {
 		let mut hits: Option<RoaringTreemap> = None;
-		for opt_term_docs in terms_docs.iter() {
-			if let Some((_, term_docs)) = opt_term_docs {
-				if let Some(h) = hits {
-					hits = Some(h.bitand(term_docs));
-				} else {
-					hits = Some(term_docs.clone());
-				}
-			} else {
+		for term_docs in terms_docs.iter() {
+			// If one of the term is missing, there is no hit
+			if term_docs.is_empty() {
 				return Ok(None);
 			}
+			// A document is a hit if it contains any of the matching terms
+			let mut docs = RoaringTreemap::new();
+			for (_, d) in term_docs {
+				docs |= d;
+			}
+			if let Some(h) = hits {
+				hits = Some(h.bitand(docs));
+			} else {
+				hits = Some(docs);
+			}
 		}
 		if let Some(hits) = hits {
 			if !hits.is_empty() {
-				return Ok(Some(HitsIterator::new(self.doc_ids.clone(), hits)));
+				return Ok(Some(HitsIterator::new(self.doc_ids.clone(), hits, phrases)));
 			}
 		}
 		Ok(None)
@@ -366,7 +402,7 @@ impl FtIndex {
 		&self,
 		tx: &mut Transaction,
 		thg: &Thing,
-		terms: &[Option<TermId>],
+		terms: &[Vec<TermId>],
 		prefix: Value,
 		suffix: Value,
 		idiom: &Idiom,
@@ -390,7 +426,7 @@ impl FtIndex {
 		&self,
 		tx: &mut Transaction,
 		thg: &Thing,
-		terms: &[Option<TermId>],
+		terms: &[Vec<TermId>],
 	) -> Result<Value, Error> {
 		let doc_key: Key = thg.into();
 		if let Some(doc_id) = self.doc_ids.read().await.get_doc_id(tx, doc_key).await? {
@@ -406,6 +442,11 @@ impl FtIndex {
 		Ok(Value::None)
 	}
 
+	/// The number of documents in the index
+	pub(crate) fn doc_count(&self) -> u64 {
+		self.state.doc_count
+	}
+
 	pub(crate) async fn statistics(&self, tx: &mut Transaction) -> Result<FtStatistics, Error> {
 		// TODO do parallel execution
 		Ok(FtStatistics {
@@ -428,13 +469,15 @@ impl FtIndex {
 pub(crate) struct HitsIterator {
 	doc_ids: Arc<RwLock<DocIds>>,
 	iter: IntoIter,
+	phrases: Phrases,
 }
 
 impl HitsIterator {
-	fn new(doc_ids: Arc<RwLock<DocIds>>, hits: RoaringTreemap) -> Self {
+	fn new(doc_ids: Arc<RwLock<DocIds>>, hits: RoaringTreemap, phrases: Phrases) -> Self {
 		Self {
 			doc_ids,
 			iter: hits.into_iter(),
+			phrases,
 		}
 	}
 
@@ -443,6 +486,10 @@ impl HitsIterator {
 		tx: &mut Transaction,
 	) -> Result<Option<(Thing, DocId)>, Error> {
 		for doc_id in self.iter.by_ref() {
+			// The document must contain the phrases of the query
+			if !self.phrases.matches(tx, doc_id).await? {
+				continue;
+			}
 			if let Some(doc_key) = self.doc_ids.read().await.get_doc_key(tx, doc_id).await? {
 				return Ok(Some((doc_key.into(), doc_id)));
 			}
@@ -493,10 +540,19 @@ mod tests {
 		fti: &FtIndex,
 		qs: &str,
 	) -> (Option<HitsIterator>, BM25Scorer) {
-		let t = fti.extract_terms(tx, qs.to_string()).await.unwrap();
+		fuzzy_search(tx, fti, qs, 0).await
+	}
+
+	async fn fuzzy_search(
+		tx: &mut Transaction,
+		fti: &FtIndex,
+		qs: &str,
+		fuzziness: u8,
+	) -> (Option<HitsIterator>, BM25Scorer) {
+		let (t, p) = fti.extract_terms(tx, qs.to_string(), fuzziness).await.unwrap();
 		let td = Arc::new(fti.get_terms_docs(tx, &t).await.unwrap());
 		let scr = fti.new_scorer(td.clone()).unwrap().unwrap();
-		let hits = fti.new_hits_iterator(td).unwrap();
+		let hits = fti.new_hits_iterator(td, p).unwrap();
 		(hits, scr)
 	}
 
@@ -591,6 +647,19 @@ mod tests {
 
 			let (hits, _) = search(&mut tx, &fti, "dummy").await;
 			assert!(hits.is_none());
+
+			// Fuzzy search & score
+			let (hits, scr) = fuzzy_search(&mut tx, &fti, "helo", 1).await;
+			check_hits(&mut tx, hits, scr, vec![(&doc1, Some(0.0)), (&doc2, Some(0.0))]).await;
+
+			let (hits, scr) = fuzzy_search(&mut tx, &fti, "wrld", 1).await;
+			check_hits(&mut tx, hits, scr, vec![(&doc1, Some(0.4859746))]).await;
+
+			let (hits, scr) = fuzzy_search(&mut tx, &fti, "yelow", 1).await;
+			check_hits(&mut tx, hits, scr, vec![(&doc2, Some(0.4859746))]).await;
+
+			let (hits, _) = fuzzy_search(&mut tx, &fti, "dummy", 2).await;
+			assert!(hits.is_none());
 		}
 
 		{
@@ -6,6 +6,7 @@ use crate::kvs::{Transaction, Val};
 
 pub(super) type Position = u32;
 
+#[derive(Clone)]
 pub(super) struct Offsets {
 	index_key_base: IndexKeyBase,
 }
@@ -61,14 +62,17 @@ pub(super) struct Offset {
 	pub(super) index: u32,
 	pub(super) start: Position,
 	pub(super) end: Position,
+	/// The position of the token within the value, which is missing for indexes created before positions were stored
+	pub(super) position: Option<Position>,
 }
 
 impl Offset {
-	pub(super) fn new(index: u32, start: Position, end: Position) -> Self {
+	pub(super) fn new(index: u32, start: Position, end: Position, position: Position) -> Self {
 		Self {
 			index,
 			start,
 			end,
+			position: Some(position),
 		}
 	}
 }
@@ -96,6 +100,12 @@ impl TryFrom<OffsetRecords> for Val {
 			decompressed.push(o.start);
 			decompressed.push(o.end);
 		}
+		// The token positions are appended, so that offsets stored without them can still be read
+		if offsets.0.iter().all(|o| o.position.is_some()) {
+			for o in &offsets.0 {
+				decompressed.push(o.position.unwrap_or_default());
+			}
+		}
 		Ok(bincode::serialize(&decompressed)?)
 	}
 }
@@ -119,7 +129,18 @@ impl TryFrom<Val> for OffsetRecords {
 		for index in indexes {
 			let start = *iter.next().ok_or(Error::CorruptedIndex)?;
 			let end = *iter.next().ok_or(Error::CorruptedIndex)?;
-			res.push(Offset::new(index, start, end));
+			res.push(Offset {
+				index,
+				start,
+				end,
+				position: None,
+			});
+		}
+		// Read the token positions, if they were stored
+		if iter.len() == res.len() {
+			for (o, p) in res.iter_mut().zip(iter) {
+				o.position = Some(*p);
+			}
 		}
 		Ok(OffsetRecords(res))
 	}
@@ -132,10 +153,22 @@ mod tests {
 
 	#[test]
 	fn test_offset_records() {
-		let o =
-			OffsetRecords(vec![Offset::new(0, 1, 2), Offset::new(0, 11, 22), Offset::new(1, 3, 4)]);
+		let o = OffsetRecords(vec![
+			Offset::new(0, 1, 2, 0),
+			Offset::new(0, 11, 22, 3),
+			Offset::new(1, 3, 4, 0),
+		]);
 		let v: Val = o.clone().try_into().unwrap();
 		let o2 = v.try_into().unwrap();
 		assert_eq!(o, o2)
 	}
+
+	#[test]
+	fn test_offset_records_without_positions() {
+		let v: Val = bincode::serialize(&vec![2u32, 0, 1, 1, 2, 11, 22]).unwrap();
+		let o: OffsetRecords = v.try_into().unwrap();
+		assert_eq!(o.0.len(), 2);
+		assert!(o.0.iter().all(|o| o.position.is_none()));
+		assert_eq!((o.0[1].index, o.0[1].start, o.0[1].end), (1, 11, 22));
+	}
 }
@@ -0,0 +1,151 @@
+use crate::err::Error;
+use crate::idx::ft::docids::DocId;
+use crate::idx::ft::offsets::{Offsets, Position};
+use crate::idx::ft::terms::TermId;
+use crate::kvs::Transaction;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// A phrase of a query string.
+/// The terms of a phrase must appear in the same order in the document,
+/// and consecutive terms must be at most `distance` positions apart.
+/// A phrase requires adjacent terms, which is a distance of 1.
+#[derive(Debug, PartialEq)]
+pub(super) struct Phrase {
+	/// For every position of the phrase, the indexes of the query terms at this position
+	positions: Vec<Vec<usize>>,
+	distance: Position,
+}
+
+impl Phrase {
+	pub(super) fn new(positions: Vec<Vec<usize>>, distance: Position) -> Self {
+		Self {
+			positions,
+			distance,
+		}
+	}
+
+	async fn matches(
+		&self,
+		tx: &mut Transaction,
+		offsets: &Offsets,
+		doc_id: DocId,
+		terms: &[Vec<TermId>],
+	) -> Result<bool, Error> {
+		// The positions (within each indexed value) where the phrase can continue
+		let mut reached: Option<HashSet<(u32, Position)>> = None;
+		for groups in &self.positions {
+			// The positions matched by every term at this position of the phrase
+			let mut candidates: Option<HashSet<(u32, Position)>> = None;
+			for group in groups {
+				let mut found = HashSet::new();
+				for term_id in &terms[*group] {
+					if let Some(o) = offsets.get_offsets(tx, doc_id, *term_id).await? {
+						found.extend(o.0.iter().filter_map(|o| o.position.map(|p| (o.index, p))));
+					}
+				}
+				candidates = Some(match candidates {
+					Some(c) => c.intersection(&found).copied().collect(),
+					None => found,
+				});
+			}
+			let candidates = candidates.unwrap_or_default();
+			let next: HashSet<(u32, Position)> = match reached {
+				None => candidates,
+				Some(r) => candidates
+					.into_iter()
+					.filter(|(i, p)| {
+						r.iter().any(|(ri, rp)| ri == i && p > rp && p - rp <= self.distance)
+					})
+					.collect(),
+			};
+			if next.is_empty() {
+				return Ok(false);
+			}
+			reached = Some(next);
+		}
+		Ok(true)
+	}
+}
+
+/// The phrases of a query, which are checked against the offsets of the terms in a document
+#[derive(Clone)]
+pub(crate) struct Phrases {
+	offsets: Offsets,
+	terms: Arc<Vec<Vec<TermId>>>,
+	phrases: Arc<Vec<Phrase>>,
+}
+
+impl Phrases {
+	pub(super) fn new(offsets: Offsets, terms: Vec<Vec<TermId>>, phrases: Vec<Phrase>) -> Self {
+		Self {
+			offsets,
+			terms: Arc::new(terms),
+			phrases: Arc::new(phrases),
+		}
+	}
+
+	pub(super) fn is_empty(&self) -> bool {
+		self.phrases.is_empty()
+	}
+
+	/// Checks if every phrase of the query appears in the document
+	pub(crate) async fn matches(&self, tx: &mut Transaction, doc_id: DocId) -> Result<bool, Error> {
+		for phrase in self.phrases.iter() {
+			if !phrase.matches(tx, &self.offsets, doc_id, &self.terms).await? {
+				return Ok(false);
+			}
+		}
+		Ok(true)
+	}
+}
+
+/// Splits a query string into its free text, and its phrases with their distance.
+/// A phrase is enclosed within double quotes, and can be followed by `~N`
+/// to match terms which are at most N positions apart, instead of adjacent terms.
+pub(super) fn parse_query(qs: &str) -> (String, Vec<(String, Position)>) {
+	let mut text = String::with_capacity(qs.len());
+	let mut phrases = Vec::new();
+	let mut rest = qs;
+	while let Some(start) = rest.find('"') {
+		let Some(len) = rest[start + 1..].find('"') else {
+			break;
+		};
+		text.push_str(&rest[..start]);
+		text.push(' ');
+		let phrase = rest[start + 1..start + 1 + len].to_owned();
+		rest = &rest[start + len + 2..];
+		// Check for a proximity distance
+		let mut distance = 1;
+		if let Some(r) = rest.strip_prefix('~') {
+			let digits = r.chars().take_while(char::is_ascii_digit).count();
+			if let Ok(d) = r[..digits].parse::<Position>() {
+				distance = d.max(1);
+				rest = &r[digits..];
+			}
+		}
+		phrases.push((phrase, distance));
+	}
+	text.push_str(rest);
+	(text, phrases)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::idx::ft::phrase::parse_query;
+
+	#[test]
+	fn test_parse_query() {
+		assert_eq!(parse_query("hello world"), ("hello world".to_owned(), vec![]));
+		assert_eq!(
+			parse_query(r#"fast "red sports car" cheap"#),
+			("fast   cheap".to_owned(), vec![("red sports car".to_owned(), 1)])
+		);
+		assert_eq!(
+			parse_query(r#""red car"~3 "blue""#),
+			("   ".to_owned(), vec![("red car".to_owned(), 3), ("blue".to_owned(), 1)])
+		);
+		// An unterminated quote is free text
+		assert_eq!(parse_query(r#"red "car"#), (r#"red "car"#.to_owned(), vec![]));
+	}
+}
@@ -31,8 +31,11 @@ impl Postings {
 		} else {
 			BState::new(order)
 		};
-		let store =
-			TreeNodeStore::new(TreeNodeProvider::Postings(index_key_base.clone()), store_type, 20);
+		let store = TreeNodeStore::new(
+			TreeNodeProvider::Postings(index_key_base.clone()),
+			store_type,
+			tx.index_cache_size(),
+		);
 		Ok(Self {
 			state_key,
 			index_key_base,
@@ -7,7 +7,9 @@ use crate::kvs::Transaction;
 use roaring::RoaringTreemap;
 use std::sync::Arc;
 
-pub(in crate::idx) type TermsDocs = Arc<Vec<Option<(TermId, RoaringTreemap)>>>;
+/// For each term of a query, the terms of the index which match it, and their documents.
+/// A term matches a single term of the index, unless the search is fuzzy.
+pub(in crate::idx) type TermsDocs = Arc<Vec<Vec<(TermId, RoaringTreemap)>>>;
 
 pub(super) struct TermDocs {
 	index_key_base: IndexKeyBase,
@@ -1,4 +1,5 @@
 use crate::err::Error;
+use crate::idx::ft::fuzzy::Levenshtein;
 use crate::idx::trees::bkeys::FstKeys;
 use crate::idx::trees::btree::{BState, BStatistics, BTree, BTreeNodeStore};
 use crate::idx::trees::store::{TreeNodeProvider, TreeNodeStore, TreeStoreType};
@@ -34,8 +35,11 @@ impl Terms {
 		} else {
 			State::new(default_btree_order)
 		};
-		let store =
-			TreeNodeStore::new(TreeNodeProvider::Terms(index_key_base.clone()), store_type, 20);
+		let store = TreeNodeStore::new(
+			TreeNodeProvider::Terms(index_key_base.clone()),
+			store_type,
+			tx.index_cache_size(),
+		);
 		Ok(Self {
 			state_key,
 			index_key_base,
@@ -93,6 +97,19 @@ impl Terms {
 		self.btree.search(tx, &mut store, &term.into()).await
 	}
 
+	/// Returns the ids of the terms within the given edit distance of a term
+	pub(super) async fn get_fuzzy_term_ids(
+		&self,
+		tx: &mut Transaction,
+		term: &str,
+		distance: u8,
+	) -> Result<Vec<TermId>, Error> {
+		let automaton = Levenshtein::new(term, distance);
+		let mut store = self.store.lock().await;
+		let terms = self.btree.search_matching(tx, &mut store, &automaton).await?;
+		Ok(terms.into_iter().map(|(_, term_id)| term_id).collect())
+	}
+
 	pub(super) async fn remove_term_id(
 		&mut self,
 		tx: &mut Transaction,
@@ -1,5 +1,7 @@
+pub(crate) mod builder;
 pub(crate) mod ft;
 pub(crate) mod planner;
+pub(crate) mod stats;
 pub mod trees;
 
 use crate::dbs::Options;
@@ -18,6 +20,11 @@ use crate::key::index::bp::Bp;
 use crate::key::index::bs::Bs;
 use crate::key::index::bt::Bt;
 use crate::key::index::bu::Bu;
+use crate::key::index::hd::Hd;
+use crate::key::index::hn::Hn;
+use crate::key::index::rd::Rd;
+use crate::key::index::rn::Rn;
+use crate::key::index::vm::Vm;
 use crate::kvs::{Key, Val};
 use crate::sql::statements::DefineIndexStatement;
 use revision::Revisioned;
@@ -171,6 +178,61 @@ impl IndexKeyBase {
 		)
 		.into()
 	}
+
+	fn new_hd_key(&self, doc_id: DocId) -> Key {
+		Hd::new(
+			self.inner.ns.as_str(),
+			self.inner.db.as_str(),
+			self.inner.tb.as_str(),
+			self.inner.ix.as_str(),
+			doc_id,
+		)
+		.into()
+	}
+
+	fn new_hn_key(&self, node_id: Option<NodeId>) -> Key {
+		Hn::new(
+			self.inner.ns.as_str(),
+			self.inner.db.as_str(),
+			self.inner.tb.as_str(),
+			self.inner.ix.as_str(),
+			node_id,
+		)
+		.into()
+	}
+
+	fn new_rd_key(&self, doc_id: DocId) -> Key {
+		Rd::new(
+			self.inner.ns.as_str(),
+			self.inner.db.as_str(),
+			self.inner.tb.as_str(),
+			self.inner.ix.as_str(),
+			doc_id,
+		)
+		.into()
+	}
+
+	fn new_rn_key(&self, node_id: Option<NodeId>) -> Key {
+		Rn::new(
+			self.inner.ns.as_str(),
+			self.inner.db.as_str(),
+			self.inner.tb.as_str(),
+			self.inner.ix.as_str(),
+			node_id,
+		)
+		.into()
+	}
+
+	fn new_vm_key(&self, node_id: Option<NodeId>) -> Key {
+		Vm::new(
+			self.inner.ns.as_str(),
+			self.inner.db.as_str(),
+			self.inner.tb.as_str(),
+			self.inner.ix.as_str(),
+			node_id,
+		)
+		.into()
+	}
 }
 
 /// This trait provides `Revision` based default implementations for serialization/deserialization
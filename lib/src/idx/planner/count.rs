@@ -0,0 +1,153 @@
+use crate::ctx::Context;
+use crate::dbs::{Options, Transaction};
+use crate::err::Error;
+use crate::iam::Action;
+use crate::idx::stats::IndexStats;
+use crate::sql::index::{Index, Nulls};
+use crate::sql::statements::{DefineIndexStatement, SelectStatement};
+use crate::sql::{Field, Function, Idiom, Part, Value};
+use std::borrow::Cow;
+
+/// A count which can be answered from the statistics of an index
+enum Count<'a> {
+	/// `count()`: the number of records of the table
+	Records,
+	/// `count::distinct(field)`: the number of distinct values of a field
+	Distinct(&'a Idiom),
+}
+
+impl<'a> Count<'a> {
+	fn new(f: &'a Function) -> Option<Self> {
+		match f {
+			Function::Normal(n, a) if n == "count" && a.is_empty() => Some(Self::Records),
+			Function::Normal(n, a) if n == "count::distinct" && a.len() == 1 => match &a[0] {
+				Value::Idiom(i) => Some(Self::Distinct(i)),
+				_ => None,
+			},
+			_ => None,
+		}
+	}
+
+	/// Checks if the statistics of the index can answer this count
+	fn answered_by(&self, ix: &DefineIndexStatement) -> bool {
+		// Every record of the table must be indexed
+		if ix.cond.is_some() {
+			return false;
+		}
+		match self {
+			// Every record has at least one entry in the index
+			Self::Records => matches!(ix.index, Index::Idx | Index::UniqNulls(Nulls::NotDistinct)),
+			// The entries of the index are the values of the field
+			Self::Distinct(i) => {
+				matches!(ix.index, Index::Idx | Index::Uniq | Index::UniqNulls(_))
+					&& !ix.collate && !ix.numeric
+					&& ix.cols.len() == 1
+					&& ix.cols[0] == **i
+					&& !matches!(i.last(), Some(Part::Flatten))
+			}
+		}
+	}
+
+	fn value(&self, stats: &IndexStats) -> Option<u64> {
+		match self {
+			Self::Records => stats.records(),
+			Self::Distinct(_) => stats.distinct(),
+		}
+	}
+}
+
+/// Answers `SELECT count(), count::distinct(field) FROM table GROUP ALL`
+/// from the statistics of the indexes of the table, without scanning the records.
+/// Returns `None` if the statement can not be answered this way.
+pub(crate) async fn count_from_indexes(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	stm: &SelectStatement,
+) -> Result<Option<Value>, Error> {
+	// Only a plain GROUP ALL query on a single table
+	if !stm.group.as_ref().is_some_and(|g| g.is_empty())
+		|| stm.omit.is_some()
+		|| stm.only
+		|| stm.with.is_some()
+		|| stm.cond.is_some()
+		|| stm.split.is_some()
+		|| stm.order.is_some()
+		|| stm.limit.is_some()
+		|| stm.start.is_some()
+		|| stm.fetch.is_some()
+		|| stm.version.is_some()
+		|| stm.explain.is_some()
+		|| stm.profile
+	{
+		return Ok(None);
+	}
+	let tb = match stm.what.0.as_slice() {
+		[Value::Table(tb)] => tb,
+		_ => return Ok(None),
+	};
+	// Table permissions may hide some of the records
+	if opt.check_perms(Action::View) {
+		return Ok(None);
+	}
+	// Every field must be a count
+	let mut counts = Vec::with_capacity(stm.expr.len());
+	for field in stm.expr.iter() {
+		match field {
+			Field::Single {
+				expr,
+				alias,
+			} => match expr {
+				Value::Function(f) => match Count::new(f) {
+					Some(c) => {
+						let name = alias
+							.as_ref()
+							.map(Cow::Borrowed)
+							.unwrap_or_else(|| Cow::Owned(expr.to_idiom()));
+						counts.push((name, c));
+					}
+					None => return Ok(None),
+				},
+				_ => return Ok(None),
+			},
+			_ => return Ok(None),
+		}
+	}
+	if counts.is_empty() {
+		return Ok(None);
+	}
+	let mut run = txn.lock().await;
+	// Compute each count from the statistics of an index
+	let ixs = run.all_tb_indexes(opt.ns(), opt.db(), tb).await?;
+	let mut vals = Vec::with_capacity(counts.len());
+	for (_, c) in counts.iter() {
+		let mut val = None;
+		for ix in ixs.iter().filter(|ix| c.answered_by(ix)) {
+			let stats = IndexStats::get(&mut run, opt.ns(), opt.db(), ix).await?;
+			if let Some(v) = c.value(&stats) {
+				val = Some(v);
+				break;
+			}
+		}
+		match val {
+			Some(v) => vals.push(v),
+			None => return Ok(None),
+		}
+	}
+	// A grouped query on an empty table has no results
+	let beg = crate::key::thing::prefix(opt.ns(), opt.db(), tb);
+	let end = crate::key::thing::suffix(opt.ns(), opt.db(), tb);
+	if run.scan(beg..end, 1).await?.is_empty() {
+		return Ok(Some(Value::Array(Default::default())));
+	}
+	drop(run);
+	// Output the counts as the grouped result
+	let mut obj = Value::base();
+	for ((name, _), v) in counts.iter().zip(vals) {
+		if stm.expr.single().is_some() {
+			return Ok(Some(Value::from(vec![Value::from(v)])));
+		}
+		obj.set(ctx, opt, txn, name.as_ref(), v.into()).await?;
+	}
+	Ok(Some(Value::from(vec![obj])))
+}
@@ -1,21 +1,25 @@
 use crate::dbs::{Options, Transaction};
 use crate::err::Error;
 use crate::idx::ft::docids::{DocId, DocIds};
+use crate::idx::ft::phrase::Phrases;
 use crate::idx::ft::scorer::BM25Scorer;
 use crate::idx::ft::termdocs::TermsDocs;
 use crate::idx::ft::terms::TermId;
 use crate::idx::ft::{FtIndex, MatchRef};
 use crate::idx::planner::iterators::{
-	MatchesThingIterator, NonUniqueEqualThingIterator, ThingIterator, UniqueEqualThingIterator,
+	CollectedThingIterator, MatchesThingIterator, NonUniqueEqualThingIterator, OrderThingIterator,
+	PrefixEqualThingIterator, ThingIterator, UniqueEqualThingIterator,
 };
 use crate::idx::planner::plan::IndexOption;
 use crate::idx::planner::tree::IndexMap;
+use crate::idx::trees::hnsw::HnswIndex;
+use crate::idx::trees::rtree::{RTreeIndex, Rect};
 use crate::idx::trees::store::TreeStoreType;
 use crate::idx::IndexKeyBase;
 use crate::kvs;
 use crate::kvs::Key;
 use crate::sql::index::Index;
-use crate::sql::{Expression, Operator, Table, Thing, Value};
+use crate::sql::{Expression, Geometry, Operator, Table, Thing, Value};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -27,7 +31,9 @@ pub(crate) struct QueryExecutor {
 	ft_map: HashMap<String, FtIndex>,
 	mr_entries: HashMap<MatchRef, FtEntry>,
 	exp_entries: HashMap<Expression, FtEntry>,
-	iterators: Vec<Expression>,
+	knn_entries: HashMap<Expression, Arc<Vec<Thing>>>,
+	geo_entries: HashMap<Expression, Arc<Vec<Thing>>>,
+	iterators: Vec<Option<Expression>>,
 }
 
 impl QueryExecutor {
@@ -41,6 +47,8 @@ impl QueryExecutor {
 
 		let mut mr_entries = HashMap::default();
 		let mut exp_entries = HashMap::default();
+		let mut knn_entries = HashMap::default();
+		let mut geo_entries = HashMap::default();
 		let mut ft_map = HashMap::default();
 
 		// Create all the instances of FtIndex
@@ -63,6 +71,24 @@ impl QueryExecutor {
 					}
 					ft_map.insert(ixn, ft);
 				}
+			} else if let Index::Hnsw(p) = &io.ix().index {
+				// The nearest neighbours are collected upfront
+				if let Operator::Knn(k) = io.op() {
+					let ikb = IndexKeyBase::new(opt, io.ix());
+					let hnsw = HnswIndex::new(&mut run, ikb, p, TreeStoreType::Read).await?;
+					let res = hnsw.knn_search(&mut run, &io.array()[0], *k as usize).await?;
+					knn_entries.insert(exp, Arc::new(res));
+				}
+				continue;
+			} else if let Index::RTree(p) = &io.ix().index {
+				// The candidates whose bounding box intersects the searched area are collected upfront
+				if let Some(area) = Self::geo_area(&io) {
+					let ikb = IndexKeyBase::new(opt, io.ix());
+					let rt = RTreeIndex::new(&mut run, ikb, p, TreeStoreType::Read).await?;
+					let res = rt.search(&mut run, area).await?;
+					geo_entries.insert(exp, Arc::new(res));
+				}
+				continue;
 			}
 
 			if let Some(e) = entry {
@@ -82,13 +108,22 @@ impl QueryExecutor {
 			ft_map,
 			mr_entries,
 			exp_entries,
+			knn_entries,
+			geo_entries,
 			iterators: Vec::new(),
 		})
 	}
 
 	pub(super) fn add_iterator(&mut self, exp: Expression) -> IteratorRef {
 		let ir = self.iterators.len();
-		self.iterators.push(exp);
+		self.iterators.push(Some(exp));
+		ir as IteratorRef
+	}
+
+	/// Adds an iterator which scans an index without resolving an expression
+	pub(super) fn add_order_iterator(&mut self) -> IteratorRef {
+		let ir = self.iterators.len();
+		self.iterators.push(None);
 		ir as IteratorRef
 	}
 
@@ -97,7 +132,7 @@ impl QueryExecutor {
 	}
 
 	pub(crate) fn get_iterator_expression(&self, ir: IteratorRef) -> Option<&Expression> {
-		self.iterators.get(ir as usize)
+		self.iterators.get(ir as usize).and_then(|e| e.as_ref())
 	}
 
 	fn get_match_ref(match_ref: &Value) -> Option<MatchRef> {
@@ -115,12 +150,18 @@ impl QueryExecutor {
 		ir: IteratorRef,
 		io: IndexOption,
 	) -> Result<Option<ThingIterator>, Error> {
+		// The whole index is scanned in the order of its keys
+		if let Some(asc) = io.order() {
+			return Ok(Some(ThingIterator::Order(OrderThingIterator::new(opt, io.ix(), asc))));
+		}
 		match &io.ix().index {
 			Index::Idx => Self::new_index_iterator(opt, io),
-			Index::Uniq => Self::new_unique_index_iterator(opt, io),
+			Index::Uniq | Index::UniqNulls(_) => Self::new_unique_index_iterator(opt, io),
 			Index::Search {
 				..
 			} => self.new_search_index_iterator(ir, io).await,
+			Index::Hnsw(_) => Ok(self.new_knn_index_iterator(ir)),
+			Index::RTree(_) => Ok(self.new_geo_index_iterator(ir)),
 			_ => Err(Error::FeatureNotYetImplemented {
 				feature: "VectorSearch iterator".to_string(),
 			}),
@@ -128,6 +169,9 @@ impl QueryExecutor {
 	}
 
 	fn new_index_iterator(opt: &Options, io: IndexOption) -> Result<Option<ThingIterator>, Error> {
+		if io.op() == &Operator::Equal && io.is_prefix() {
+			return Ok(Some(Self::new_prefix_iterator(opt, io)));
+		}
 		if io.op() == &Operator::Equal {
 			return Ok(Some(ThingIterator::NonUniqueEqual(NonUniqueEqualThingIterator::new(
 				opt,
@@ -142,6 +186,9 @@ impl QueryExecutor {
 		opt: &Options,
 		io: IndexOption,
 	) -> Result<Option<ThingIterator>, Error> {
+		if io.op() == &Operator::Equal && io.is_prefix() {
+			return Ok(Some(Self::new_prefix_iterator(opt, io)));
+		}
 		if io.op() == &Operator::Equal {
 			return Ok(Some(ThingIterator::UniqueEqual(UniqueEqualThingIterator::new(
 				opt,
@@ -152,17 +199,27 @@ impl QueryExecutor {
 		Ok(None)
 	}
 
+	/// Scans the entries of a composite index whose first columns match the values
+	fn new_prefix_iterator(opt: &Options, io: IndexOption) -> ThingIterator {
+		ThingIterator::PrefixEqual(PrefixEqualThingIterator::new(opt, io.ix(), io.array()))
+	}
+
 	async fn new_search_index_iterator(
 		&self,
 		ir: IteratorRef,
 		io: IndexOption,
 	) -> Result<Option<ThingIterator>, Error> {
 		if let Some(exp) = self.iterators.get(ir as usize) {
-			if let Operator::Matches(_) = io.op() {
+			if let Operator::Matches(_) | Operator::FuzzyMatches(..) = io.op() {
 				let ixn = &io.ix().name.0;
 				if let Some(fti) = self.ft_map.get(ixn) {
 					if let Some(fte) = self.exp_entries.get(exp) {
-						let it = MatchesThingIterator::new(fti, fte.0.terms_docs.clone()).await?;
+						let it = MatchesThingIterator::new(
+							fti,
+							fte.0.terms_docs.clone(),
+							fte.0.phrases.clone(),
+						)
+						.await?;
 						return Ok(Some(ThingIterator::Matches(it)));
 					}
 				}
@@ -171,6 +228,36 @@ impl QueryExecutor {
 		Ok(None)
 	}
 
+	fn new_knn_index_iterator(&self, ir: IteratorRef) -> Option<ThingIterator> {
+		let exp = self.iterators.get(ir as usize)?;
+		let res = self.knn_entries.get(exp)?;
+		Some(ThingIterator::Knn(CollectedThingIterator::new(res.clone())))
+	}
+
+	fn new_geo_index_iterator(&self, ir: IteratorRef) -> Option<ThingIterator> {
+		let exp = self.iterators.get(ir as usize)?;
+		let res = self.geo_entries.get(exp)?;
+		Some(ThingIterator::Geo(CollectedThingIterator::new(res.clone())))
+	}
+
+	/// The area which contains the bounding box of every matching geometry
+	fn geo_area(io: &IndexOption) -> Option<Rect> {
+		match (io.op(), io.array().first()) {
+			(
+				Operator::Contain | Operator::Inside | Operator::Intersects,
+				Some(Value::Geometry(g)),
+			) => Rect::of(g),
+			(
+				Operator::LessThan | Operator::LessThanOrEqual,
+				Some(Value::Geometry(Geometry::Point(p))),
+			) => match io.array().get(1) {
+				Some(Value::Number(d)) => Some(Rect::around(p, d.to_float())),
+				_ => None,
+			},
+			_ => None,
+		}
+	}
+
 	pub(crate) async fn matches(
 		&self,
 		txn: &Transaction,
@@ -191,17 +278,14 @@ impl QueryExecutor {
 					if term_goals == 0 {
 						return Ok(Value::Bool(false));
 					}
-					for opt_td in ft.0.terms_docs.iter() {
-						if let Some((_, docs)) = opt_td {
-							if !docs.contains(doc_id) {
-								return Ok(Value::Bool(false));
-							}
-						} else {
-							// If one of the term is missing, it can't be a match
+					// Every term must be matched by at least one term of the document
+					for term_docs in ft.0.terms_docs.iter() {
+						if !term_docs.iter().any(|(_, docs)| docs.contains(doc_id)) {
 							return Ok(Value::Bool(false));
 						}
 					}
-					return Ok(Value::Bool(true));
+					// The document must contain the phrases of the query
+					return Ok(Value::Bool(ft.0.phrases.matches(&mut run, doc_id).await?));
 				}
 				return Ok(Value::Bool(false));
 			}
@@ -213,6 +297,18 @@ impl QueryExecutor {
 		})
 	}
 
+	/// Checks if a record is one of the nearest neighbours found for a KNN expression
+	pub(crate) fn knn(&self, thg: &Thing, exp: &Expression) -> Result<Value, Error> {
+		if thg.tb.eq(&self.table) {
+			if let Some(res) = self.knn_entries.get(exp) {
+				return Ok(Value::Bool(res.contains(thg)));
+			}
+		}
+		Err(Error::NoIndexFoundForKnn {
+			value: exp.to_string(),
+		})
+	}
+
 	fn get_ft_entry(&self, match_ref: &Value) -> Option<&FtEntry> {
 		if let Some(mr) = Self::get_match_ref(match_ref) {
 			self.mr_entries.get(&mr)
@@ -293,8 +389,9 @@ struct FtEntry(Arc<Inner>);
 struct Inner {
 	index_option: IndexOption,
 	doc_ids: Arc<RwLock<DocIds>>,
-	terms: Vec<Option<TermId>>,
+	terms: Vec<Vec<TermId>>,
 	terms_docs: TermsDocs,
+	phrases: Phrases,
 	scorer: Option<BM25Scorer>,
 }
 
@@ -305,7 +402,7 @@ impl FtEntry {
 		io: IndexOption,
 	) -> Result<Option<Self>, Error> {
 		if let Some(qs) = io.qs() {
-			let terms = ft.extract_terms(tx, qs.to_owned()).await?;
+			let (terms, phrases) = ft.extract_terms(tx, qs.to_owned(), io.fuzziness()).await?;
 			let terms_docs = Arc::new(ft.get_terms_docs(tx, &terms).await?);
 			Ok(Some(Self(Arc::new(Inner {
 				index_option: io,
@@ -313,6 +410,7 @@ impl FtEntry {
 				scorer: ft.new_scorer(terms_docs.clone())?,
 				terms,
 				terms_docs,
+				phrases,
 			}))))
 		} else {
 			Ok(None)
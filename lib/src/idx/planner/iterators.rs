@@ -1,17 +1,24 @@
 use crate::dbs::{Options, Transaction};
 use crate::err::Error;
 use crate::idx::ft::docids::{DocId, NO_DOC_ID};
+use crate::idx::ft::phrase::Phrases;
 use crate::idx::ft::termdocs::TermsDocs;
 use crate::idx::ft::{FtIndex, HitsIterator};
 use crate::key;
 use crate::kvs::Key;
 use crate::sql::statements::DefineIndexStatement;
 use crate::sql::{Array, Thing};
+use std::collections::VecDeque;
+use std::sync::Arc;
 
 pub(crate) enum ThingIterator {
 	NonUniqueEqual(NonUniqueEqualThingIterator),
 	UniqueEqual(UniqueEqualThingIterator),
+	PrefixEqual(PrefixEqualThingIterator),
+	Order(OrderThingIterator),
 	Matches(MatchesThingIterator),
+	Knn(CollectedThingIterator),
+	Geo(CollectedThingIterator),
 }
 
 impl ThingIterator {
@@ -23,7 +30,11 @@ impl ThingIterator {
 		match self {
 			ThingIterator::NonUniqueEqual(i) => i.next_batch(tx, size).await,
 			ThingIterator::UniqueEqual(i) => i.next_batch(tx, size).await,
+			ThingIterator::PrefixEqual(i) => i.next_batch(tx, size).await,
+			ThingIterator::Order(i) => i.next_batch(tx, size).await,
 			ThingIterator::Matches(i) => i.next_batch(tx, size).await,
+			ThingIterator::Knn(i) => i.next_batch(tx, size).await,
+			ThingIterator::Geo(i) => i.next_batch(tx, size).await,
 		}
 	}
 }
@@ -39,8 +50,9 @@ impl NonUniqueEqualThingIterator {
 		ix: &DefineIndexStatement,
 		v: &Array,
 	) -> Result<NonUniqueEqualThingIterator, Error> {
+		let v = ix.collated(v);
 		let (beg, end) =
-			key::index::Index::range_all_ids(opt.ns(), opt.db(), &ix.what, &ix.name, v);
+			key::index::Index::range_all_ids(opt.ns(), opt.db(), &ix.what, &ix.name, &v);
 		Ok(Self {
 			beg,
 			end,
@@ -70,7 +82,8 @@ pub(crate) struct UniqueEqualThingIterator {
 
 impl UniqueEqualThingIterator {
 	pub(super) fn new(opt: &Options, ix: &DefineIndexStatement, a: &Array) -> Result<Self, Error> {
-		let key = key::index::Index::new(opt.ns(), opt.db(), &ix.what, &ix.name, a, None).into();
+		let a = ix.collated(a);
+		let key = key::index::Index::new(opt.ns(), opt.db(), &ix.what, &ix.name, &a, None).into();
 		Ok(Self {
 			key: Some(key),
 		})
@@ -90,13 +103,94 @@ impl UniqueEqualThingIterator {
 	}
 }
 
+/// Iterates over the entries of a composite index starting with the given values
+pub(crate) struct PrefixEqualThingIterator {
+	beg: Vec<u8>,
+	end: Vec<u8>,
+}
+
+impl PrefixEqualThingIterator {
+	pub(super) fn new(opt: &Options, ix: &DefineIndexStatement, v: &Array) -> Self {
+		let v = ix.collated(v);
+		let (beg, end) =
+			key::index::Index::range_prefix_ids(opt.ns(), opt.db(), &ix.what, &ix.name, &v);
+		Self {
+			beg,
+			end,
+		}
+	}
+
+	async fn next_batch(
+		&mut self,
+		txn: &Transaction,
+		limit: u32,
+	) -> Result<Vec<(Thing, DocId)>, Error> {
+		let min = self.beg.clone();
+		let max = self.end.clone();
+		let res = txn.lock().await.scan(min..max, limit).await?;
+		if let Some((key, _)) = res.last() {
+			self.beg = key.clone();
+			self.beg.push(0x00);
+		}
+		let res = res.iter().map(|(_, val)| (val.into(), NO_DOC_ID)).collect();
+		Ok(res)
+	}
+}
+
+/// Iterates over all the entries of an index, in the order of the keys or in reverse order
+pub(crate) struct OrderThingIterator {
+	beg: Vec<u8>,
+	end: Vec<u8>,
+	asc: bool,
+}
+
+impl OrderThingIterator {
+	pub(super) fn new(opt: &Options, ix: &DefineIndexStatement, asc: bool) -> Self {
+		let rng = key::index::Index::range(opt.ns(), opt.db(), &ix.what, &ix.name);
+		Self {
+			beg: rng.start,
+			end: rng.end,
+			asc,
+		}
+	}
+
+	async fn next_batch(
+		&mut self,
+		txn: &Transaction,
+		limit: u32,
+	) -> Result<Vec<(Thing, DocId)>, Error> {
+		let min = self.beg.clone();
+		let max = self.end.clone();
+		let res = if self.asc {
+			let res = txn.lock().await.scan(min..max, limit).await?;
+			if let Some((key, _)) = res.last() {
+				self.beg = key.clone();
+				self.beg.push(0x00);
+			}
+			res
+		} else {
+			let res = txn.lock().await.scanr(min..max, limit).await?;
+			if let Some((key, _)) = res.last() {
+				self.end = key.clone();
+			}
+			res
+		};
+		let res = res.iter().map(|(_, val)| (val.into(), NO_DOC_ID)).collect();
+		Ok(res)
+	}
+}
+
 pub(crate) struct MatchesThingIterator {
 	hits: Option<HitsIterator>,
 }
 
 impl MatchesThingIterator {
-	pub(super) async fn new(fti: &FtIndex, terms_docs: TermsDocs) -> Result<Self, Error> {
-		let hits = fti.new_hits_iterator(terms_docs)?;
+	pub(super) async fn new(
+		fti: &FtIndex,
+		terms_docs: TermsDocs,
+		phrases: Phrases,
+	) -> Result<Self, Error> {
+		let hits = fti.new_hits_iterator(terms_docs, phrases)?;
 		Ok(Self {
 			hits,
 		})
@@ -122,3 +216,25 @@ impl MatchesThingIterator {
 		Ok(res)
 	}
 }
+
+/// Iterates over records which have been collected upfront
+pub(crate) struct CollectedThingIterator {
+	res: VecDeque<Thing>,
+}
+
+impl CollectedThingIterator {
+	pub(super) fn new(res: Arc<Vec<Thing>>) -> Self {
+		Self {
+			res: res.iter().cloned().collect(),
+		}
+	}
+
+	async fn next_batch(
+		&mut self,
+		_txn: &Transaction,
+		limit: u32,
+	) -> Result<Vec<(Thing, DocId)>, Error> {
+		let limit = (limit as usize).min(self.res.len());
+		Ok(self.res.drain(..limit).map(|thg| (thg, NO_DOC_ID)).collect())
+	}
+}
@@ -1,3 +1,4 @@
+pub(crate) mod count;
 pub(crate) mod executor;
 pub(crate) mod iterators;
 pub(crate) mod plan;
@@ -6,17 +7,27 @@ mod tree;
 use crate::ctx::Context;
 use crate::dbs::{Iterable, Iterator, Options, Transaction};
 use crate::err::Error;
+use crate::iam::Action;
+use crate::idx::builder::BuildingState;
 use crate::idx::planner::executor::QueryExecutor;
-use crate::idx::planner::plan::{Plan, PlanBuilder};
-use crate::idx::planner::tree::Tree;
+use crate::idx::planner::plan::{IndexOption, Plan, PlanBuilder};
+use crate::idx::planner::tree::{IndexMap, Tree};
+use crate::sql::index::{Index, Nulls};
+use crate::sql::statements::DefineIndexStatement;
 use crate::sql::with::With;
-use crate::sql::{Cond, Table};
+use crate::sql::{
+	Cond, Edges, Expression, Field, Fields, Idiom, Kind, Operator, Orders, Table, Value,
+};
 use std::collections::HashMap;
 
 pub(crate) struct QueryPlanner<'a> {
 	opt: &'a Options,
 	with: &'a Option<With>,
 	cond: &'a Option<Cond>,
+	/// The projected fields, if the query can be answered from an index alone
+	fields: Option<&'a Fields>,
+	/// The ordering of the results, with the projected fields it applies to
+	order: Option<(&'a Orders, &'a Fields)>,
 	/// There is one executor per table
 	executors: HashMap<String, QueryExecutor>,
 	requires_distinct: bool,
@@ -24,11 +35,19 @@ pub(crate) struct QueryPlanner<'a> {
 }
 
 impl<'a> QueryPlanner<'a> {
-	pub(crate) fn new(opt: &'a Options, with: &'a Option<With>, cond: &'a Option<Cond>) -> Self {
+	pub(crate) fn new(
+		opt: &'a Options,
+		with: &'a Option<With>,
+		cond: &'a Option<Cond>,
+		fields: Option<&'a Fields>,
+		order: Option<(&'a Orders, &'a Fields)>,
+	) -> Self {
 		Self {
 			opt,
 			with,
 			cond,
+			fields,
+			order,
 			executors: HashMap::default(),
 			requires_distinct: false,
 			fallbacks: vec![],
@@ -42,17 +61,27 @@ impl<'a> QueryPlanner<'a> {
 		t: Table,
 		it: &mut Iterator,
 	) -> Result<(), Error> {
+		// Collect the indexes which the query is forced to use on this table
+		let hints = self.forced_hints(&t);
+		let mut used = vec![];
 		match Tree::build(ctx, self.opt, txn, &t, self.cond).await? {
-			Some((node, im)) => {
+			Some((node, im, columns)) => {
 				let mut exe = QueryExecutor::new(self.opt, txn, &t, im).await?;
-				match PlanBuilder::build(node, self.with)? {
+				match PlanBuilder::build(node, columns, self.with)? {
 					Plan::SingleIndex(exp, io) => {
+						used.push(io.ix().name.0.clone());
+						let io = if self.is_covering(&exp, &io) {
+							io.with_covering()
+						} else {
+							io
+						};
 						let ir = exe.add_iterator(exp);
 						it.ingest(Iterable::Index(t.clone(), ir, io));
 						self.executors.insert(t.0.clone(), exe);
 					}
 					Plan::MultiIndex(v) => {
 						for (exp, io) in v {
+							used.push(io.ix().name.0.clone());
 							let ir = exe.add_iterator(exp);
 							it.ingest(Iterable::Index(t.clone(), ir, io));
 							self.requires_distinct = true;
@@ -60,21 +89,225 @@ impl<'a> QueryPlanner<'a> {
 						self.executors.insert(t.0.clone(), exe);
 					}
 					Plan::TableIterator(fallback) => {
-						if let Some(fallback) = fallback {
-							self.fallbacks.push(fallback);
+						if let Some(io) = self.order_index(txn, &t).await? {
+							used.push(io.ix().name.0.clone());
+							let ir = exe.add_order_iterator();
+							it.ingest_ordered(Iterable::Index(t.clone(), ir, io));
+						} else {
+							if let Some(fallback) = fallback {
+								self.fallbacks.push(fallback);
+							}
+							it.ingest(Iterable::Table(t.clone()));
 						}
 						self.executors.insert(t.0.clone(), exe);
-						it.ingest(Iterable::Table(t));
 					}
 				}
 			}
 			None => {
-				it.ingest(Iterable::Table(t));
+				if let Some(io) = self.order_index(txn, &t).await? {
+					used.push(io.ix().name.0.clone());
+					let mut exe =
+						QueryExecutor::new(self.opt, txn, &t, IndexMap::default()).await?;
+					let ir = exe.add_order_iterator();
+					it.ingest_ordered(Iterable::Index(t.clone(), ir, io));
+					self.executors.insert(t.0.clone(), exe);
+				} else {
+					it.ingest(Iterable::Table(t.clone()));
+				}
 			}
 		}
+		// Report the forced indexes which could not be used
+		if !hints.is_empty() {
+			self.check_hints(txn, &t, hints, &used).await?;
+		}
 		Ok(())
 	}
 
+	/// Finds an index whose keys are in the order of the ORDER BY clause, so that
+	/// the records can be iterated in order instead of being sorted in memory.
+	/// The ordered fields must be the first columns of the index, and must be
+	/// defined with a type whose values are stored in the index in sort order.
+	async fn order_index(
+		&self,
+		txn: &Transaction,
+		t: &Table,
+	) -> Result<Option<IndexOption>, Error> {
+		let Some((orders, fields)) = self.order else {
+			return Ok(None);
+		};
+		// Archived records are iterated separately
+		if matches!(self.with, Some(With::NoIndex | With::Archive(_))) {
+			return Ok(None);
+		}
+		// Every order must compare the raw values in the same direction
+		let Some(asc) = orders.first().map(|o| o.direction) else {
+			return Ok(None);
+		};
+		if orders.iter().any(|o| o.random || o.collate || o.numeric || o.direction != asc) {
+			return Ok(None);
+		}
+		// The ordered fields must be output unchanged
+		if !orders.iter().all(|o| is_projected(fields, &o.order)) {
+			return Ok(None);
+		}
+		let mut run = txn.lock().await;
+		let (ns, db) = (self.opt.ns(), self.opt.db());
+		let ixs = run.all_tb_indexes(ns, db, &t.0).await?;
+		let fds = run.all_tb_fields(ns, db, &t.0).await?;
+		// The types of the defined fields
+		let kinds: HashMap<&Idiom, &Kind> =
+			fds.iter().filter_map(|fd| fd.kind.as_ref().map(|k| (&fd.name, k))).collect();
+		for ix in ixs.iter() {
+			// Every record must have a single entry in the index
+			let unique = match ix.index {
+				Index::Idx | Index::UniqNulls(Nulls::NotDistinct) => false,
+				Index::Uniq => true,
+				_ => continue,
+			};
+			if ix.cond.is_some() || ix.collate || ix.numeric {
+				continue;
+			}
+			if ix.cols.len() < orders.len()
+				|| !orders.iter().zip(ix.cols.iter()).all(|(o, c)| o.order == *c)
+			{
+				continue;
+			}
+			if let Some(with) = self.with {
+				if !with.allows(&ix.what.0, &ix.name.0) {
+					continue;
+				}
+			}
+			// The keys of the index are sorted like the values of these types,
+			// and a unique index has no entry for the records without a value
+			let sorted = ix.cols.iter().all(|c| match kinds.get(c) {
+				Some(Kind::Option(_)) if unique => false,
+				Some(k) => is_sorted_kind(k),
+				None => false,
+			});
+			if !sorted {
+				continue;
+			}
+			// Indexes still being built in the background are not usable yet
+			if ix.concurrently && BuildingState::get(&mut run, ns, db, ix).await?.is_some() {
+				continue;
+			}
+			return Ok(Some(IndexOption::new_order(ix.clone(), ix.cols[0].clone(), asc)));
+		}
+		Ok(None)
+	}
+
+	/// Returns the indexes named by a WITH INDEX clause which apply to the table
+	fn forced_hints(&self, t: &Table) -> Vec<String> {
+		match self.with.as_ref().and_then(With::index_hints) {
+			Some(With::Index(hints)) => With::hints(hints, &t.0)
+				.into_iter()
+				.filter(|h| *h != "*")
+				.map(String::from)
+				.collect(),
+			_ => vec![],
+		}
+	}
+
+	/// Explains why the forced indexes of a table were not used by the query.
+	/// A forced index is not reported when another forced index is used instead.
+	async fn check_hints(
+		&mut self,
+		txn: &Transaction,
+		t: &Table,
+		hints: Vec<String>,
+		used: &[String],
+	) -> Result<(), Error> {
+		let ixs = txn.lock().await.all_tb_indexes(self.opt.ns(), self.opt.db(), &t.0).await?;
+		for h in hints {
+			let reason = if !ixs.iter().any(|ix| ix.name.0 == h) {
+				"the index is not defined on the table"
+			} else if !used.is_empty() {
+				// Another of the forced indexes is used
+				continue;
+			} else if self.cond.is_none() {
+				"the query has no condition"
+			} else {
+				"the condition can not be resolved with this index"
+			};
+			self.fallbacks.push(format!("Index hint '{h}' ignored on table '{}': {reason}", t.0));
+		}
+		Ok(())
+	}
+
+	/// Iterates the edges of a record from an index of the edge table, when the query
+	/// is forced to use an index of that table. Otherwise the edges are iterated from
+	/// the graph keys of the record.
+	pub(crate) async fn add_edge_iterables(
+		&mut self,
+		ctx: &Context<'_>,
+		txn: &Transaction,
+		e: Edges,
+		it: &mut Iterator,
+	) -> Result<(), Error> {
+		// Only the edges of a single table can be iterated from an index
+		let t = match e.what.0.as_slice() {
+			[t] => t.clone(),
+			_ => {
+				it.ingest(Iterable::Edges(e));
+				return Ok(());
+			}
+		};
+		let hints = self.forced_hints(&t);
+		if hints.is_empty() {
+			it.ingest(Iterable::Edges(e));
+			return Ok(());
+		}
+		let mut used = vec![];
+		match Tree::build(ctx, self.opt, txn, &t, self.cond).await? {
+			Some((node, im, columns)) => match PlanBuilder::build(node, columns, self.with)? {
+				Plan::SingleIndex(exp, io) => {
+					used.push(io.ix().name.0.clone());
+					let mut exe = QueryExecutor::new(self.opt, txn, &t, im).await?;
+					let ir = exe.add_iterator(exp);
+					it.ingest(Iterable::IndexEdges(e, ir, io));
+					self.executors.insert(t.0.clone(), exe);
+				}
+				_ => it.ingest(Iterable::Edges(e)),
+			},
+			None => it.ingest(Iterable::Edges(e)),
+		}
+		// Report the forced indexes which could not be used
+		self.check_hints(txn, &t, hints, &used).await
+	}
+
+	/// Checks if the records matched by this index can be built from the index keys.
+	/// This is the case when the condition is the indexed expression itself,
+	/// and when only the record id or the indexed column are projected.
+	fn is_covering(&self, exp: &Expression, io: &IndexOption) -> bool {
+		let ix = io.ix();
+		if ix.index != Index::Idx || ix.cols.len() != 1 || io.op() != &Operator::Equal {
+			return false;
+		}
+		// A collated index does not store the original values
+		if ix.collate || ix.numeric {
+			return false;
+		}
+		// Permissions require the whole document
+		if self.opt.check_perms(Action::View) {
+			return false;
+		}
+		// The index must be the only condition
+		match self.cond.as_ref().map(|c| &c.0) {
+			Some(Value::Expression(e)) if e.as_ref() == exp => {}
+			_ => return false,
+		}
+		match self.fields {
+			Some(fields) => fields.iter().all(|f| match f {
+				Field::Single {
+					expr: Value::Idiom(i),
+					..
+				} => i.is_id() || i == io.id(),
+				_ => false,
+			}),
+			None => false,
+		}
+	}
+
 	pub(crate) fn has_executors(&self) -> bool {
 		!self.executors.is_empty()
 	}
@@ -91,3 +324,49 @@ impl<'a> QueryPlanner<'a> {
 		&self.fallbacks
 	}
 }
+
+/// Checks if a field is output unchanged by the projections of the statement
+fn is_projected(fields: &Fields, i: &Idiom) -> bool {
+	// Single values are not ordered by their fields
+	if fields.1 {
+		return false;
+	}
+	let mut all = false;
+	let mut found = false;
+	for f in fields.iter() {
+		match f {
+			Field::All => all = true,
+			Field::Single {
+				expr,
+				alias,
+			} => {
+				let name = alias.clone().unwrap_or_else(|| expr.to_idiom());
+				if i.starts_with(&name) || name.starts_with(i) {
+					match expr {
+						Value::Idiom(v) if v == i && name == *i => found = true,
+						_ => return false,
+					}
+				}
+			}
+			Field::Window {
+				expr,
+				alias,
+			} => {
+				let name = alias.clone().unwrap_or_else(|| expr.to_idiom());
+				if i.starts_with(&name) || name.starts_with(i) {
+					return false;
+				}
+			}
+		}
+	}
+	all || found
+}
+
+/// The values of these types are stored in the index keys in the order they are sorted
+fn is_sorted_kind(k: &Kind) -> bool {
+	match k {
+		Kind::Bool | Kind::Int | Kind::String | Kind::Record(_) => true,
+		Kind::Option(k) => is_sorted_kind(k),
+		_ => false,
+	}
+}
@@ -1,9 +1,13 @@
+use crate::dbs::{Options, Transaction};
 use crate::err::Error;
 use crate::idx::ft::MatchRef;
-use crate::idx::planner::tree::Node;
+use crate::idx::planner::tree::{IndexColumn, Node};
+use crate::idx::stats::IndexStats;
+use crate::sql::index::Index;
+use crate::sql::paths::ID;
 use crate::sql::statements::DefineIndexStatement;
 use crate::sql::with::With;
-use crate::sql::{Array, Object};
+use crate::sql::{Array, Object, Thing};
 use crate::sql::{Expression, Idiom, Operator, Value};
 use std::collections::HashMap;
 use std::hash::Hash;
@@ -17,9 +21,13 @@ pub(super) struct PlanBuilder<'a> {
 }
 
 impl<'a> PlanBuilder<'a> {
-	pub(super) fn build(root: Node, with: &'a Option<With>) -> Result<Plan, Error> {
+	pub(super) fn build(
+		root: Node,
+		columns: Vec<IndexColumn>,
+		with: &'a Option<With>,
+	) -> Result<Plan, Error> {
 		if let Some(with) = with {
-			if matches!(with, With::NoIndex) {
+			if matches!(with.index_hints(), Some(With::NoIndex)) {
 				return Ok(Plan::TableIterator(Some("WITH NOINDEX".to_string())));
 			}
 		}
@@ -33,12 +41,24 @@ impl<'a> PlanBuilder<'a> {
 		if let Err(e) = b.eval_node(root) {
 			return Ok(Plan::TableIterator(Some(e.to_string())));
 		}
+		// A composite index can only be used if every boolean operator are AND
+		let prefix = if b.all_and {
+			b.eval_prefixes(&columns)
+		} else {
+			None
+		};
 		// If we didn't found any index, we're done with no index plan
-		if b.indexes.is_empty() {
+		if b.indexes.is_empty() && prefix.is_none() {
 			return Ok(Plan::TableIterator(Some("NO INDEX FOUND".to_string())));
 		}
 		// If every boolean operator are AND then we can use the single index plan
 		if b.all_and {
+			// A prefix over several columns is more selective than a single column index
+			if let Some((e, i)) = prefix {
+				if i.array().len() > 1 || b.indexes.is_empty() {
+					return Ok(Plan::SingleIndex(e, i));
+				}
+			}
 			if let Some((e, i)) = b.indexes.pop() {
 				return Ok(Plan::SingleIndex(e, i));
 			}
@@ -53,8 +73,8 @@ impl<'a> PlanBuilder<'a> {
 	// Check if we have an explicit list of index we can use
 	fn filter_index_option(&self, io: Option<IndexOption>) -> Option<IndexOption> {
 		if let Some(io) = &io {
-			if let Some(With::Index(ixs)) = self.with {
-				if !ixs.contains(&io.ix().name.0) {
+			if let Some(with) = self.with {
+				if !with.allows(&io.ix().what.0, &io.ix().name.0) {
 					return None;
 				}
 			}
@@ -62,6 +82,37 @@ impl<'a> PlanBuilder<'a> {
 		io
 	}
 
+	/// Finds the longest prefix of the columns of a composite index which is
+	/// constrained by equalities. The values of the prefix are scanned in the index,
+	/// and the remaining conditions are checked on each matched record.
+	fn eval_prefixes(&self, columns: &[IndexColumn]) -> Option<(Expression, IndexOption)> {
+		let mut res: Option<(Expression, IndexOption)> = None;
+		for c in columns.iter().filter(|c| c.col == 0) {
+			if let Some(with) = self.with {
+				if !with.allows(&c.ix.what.0, &c.ix.name.0) {
+					continue;
+				}
+			}
+			let mut a = vec![c.value.clone()];
+			while let Some(n) = columns.iter().find(|n| n.ix.name == c.ix.name && n.col == a.len())
+			{
+				a.push(n.value.clone());
+			}
+			if res.as_ref().map_or(true, |(_, io)| io.array().len() < a.len()) {
+				let io = IndexOption::new(
+					c.ix.clone(),
+					c.ix.cols[0].clone(),
+					Operator::Equal,
+					Array::from(a),
+					None,
+					None,
+				);
+				res = Some((c.exp.clone(), io));
+			}
+		}
+		res
+	}
+
 	fn eval_node(&mut self, node: Node) -> Result<(), String> {
 		match node {
 			Node::Expression {
@@ -123,6 +174,9 @@ pub(super) struct Inner {
 	qs: Option<String>,
 	op: Operator,
 	mr: Option<MatchRef>,
+	covering: bool,
+	/// Scans the whole index in key order, ascending if true
+	order: Option<bool>,
 }
 
 impl IndexOption {
@@ -141,9 +195,65 @@ impl IndexOption {
 			a,
 			qs,
 			mr,
+			covering: false,
+			order: None,
 		}))
 	}
 
+	/// Returns an option which scans the whole index in the order of its keys
+	pub(super) fn new_order(ix: DefineIndexStatement, id: Idiom, asc: bool) -> Self {
+		Self(Arc::new(Inner {
+			ix,
+			id,
+			op: Operator::Equal,
+			a: Array::default(),
+			qs: None,
+			mr: None,
+			covering: false,
+			order: Some(asc),
+		}))
+	}
+
+	/// Returns an option which answers the query from the index keys alone
+	pub(super) fn with_covering(self) -> Self {
+		let i = &self.0;
+		Self(Arc::new(Inner {
+			ix: i.ix.clone(),
+			id: i.id.clone(),
+			op: i.op.clone(),
+			a: i.a.clone(),
+			qs: i.qs.clone(),
+			mr: i.mr,
+			covering: true,
+			order: i.order,
+		}))
+	}
+
+	/// Checks if the records can be built from the index keys, without being fetched
+	pub(crate) fn is_covering(&self) -> bool {
+		self.0.covering
+	}
+
+	/// Checks if only a prefix of the columns of a composite index is matched
+	pub(crate) fn is_prefix(&self) -> bool {
+		self.0.order.is_none() && self.0.a.len() < self.0.ix.cols.len()
+	}
+
+	/// The direction of the scan, if the whole index is scanned in key order
+	pub(super) fn order(&self) -> Option<bool> {
+		self.0.order
+	}
+
+	/// Builds a record from the values stored in the index key
+	pub(crate) fn covering_doc(&self, rid: &Thing) -> Value {
+		let mut doc = Value::base();
+		doc.put(ID.as_ref(), Value::Thing(rid.to_owned()));
+		if let Some(v) = self.0.a.first() {
+			doc.put(&self.0.id, v.to_owned());
+		}
+		doc
+	}
+
 	pub(super) fn ix(&self) -> &DefineIndexStatement {
 		&self.0.ix
 	}
@@ -168,17 +278,87 @@ impl IndexOption {
 		self.0.mr.as_ref()
 	}
 
+	/// The maximum edit distance of the terms matched by a full-text search
+	pub(super) fn fuzziness(&self) -> u8 {
+		match self.0.op {
+			Operator::FuzzyMatches(_, distance) => distance,
+			_ => 0,
+		}
+	}
+
+	/// Estimates the number of records yielded by the index, and the number of keys read
+	/// to yield them, from the statistics of the index. Returns `None` when the statistics
+	/// have not been maintained, or when the operator can not be estimated.
+	pub(crate) async fn estimate(
+		&self,
+		opt: &Options,
+		txn: &Transaction,
+	) -> Result<Option<Value>, Error> {
+		let stats = {
+			let mut run = txn.lock().await;
+			IndexStats::get(&mut run, opt.ns(), opt.db(), self.ix()).await?
+		};
+		let rows = match (&self.ix().index, self.op()) {
+			_ if self.0.order.is_some() => stats.records(),
+			_ if self.is_prefix() => None,
+			(Index::Uniq | Index::UniqNulls(_), Operator::Equal | Operator::Exact) => {
+				stats.records().map(|n| n.min(1))
+			}
+			(Index::Idx, Operator::Equal | Operator::Exact) => {
+				match (stats.entries(), stats.distinct()) {
+					(Some(_), Some(0)) => Some(0),
+					(Some(n), Some(d)) => Some((n + d - 1) / d),
+					_ => None,
+				}
+			}
+			(_, Operator::Knn(k)) => stats.records().map(|n| n.min(*k as u64)),
+			_ => None,
+		};
+		Ok(rows.map(|rows| {
+			// Each record is read from the index, and then fetched unless the index covers it
+			let cost = if self.is_covering() {
+				rows
+			} else {
+				rows * 2
+			};
+			Value::Object(Object::from(HashMap::from([
+				("rows", Value::from(rows)),
+				("cost", Value::from(cost)),
+			])))
+		}))
+	}
+
 	pub(crate) fn explain(&self) -> Value {
+		if let Some(asc) = self.0.order {
+			return Value::Object(Object::from(HashMap::from([
+				("index", Value::from(self.ix().name.0.to_owned())),
+				(
+					"order",
+					Value::from(if asc {
+						"ASC"
+					} else {
+						"DESC"
+					}),
+				),
+			])));
+		}
 		let v = if self.0.a.len() == 1 {
 			self.0.a[0].clone()
 		} else {
 			Value::Array(self.0.a.clone())
 		};
-		Value::Object(Object::from(HashMap::from([
+		let mut e = HashMap::from([
 			("index", Value::from(self.ix().name.0.to_owned())),
 			("operator", Value::from(self.op().to_string())),
 			("value", v),
-		])))
+		]);
+		if self.is_covering() {
+			e.insert("covering", Value::Bool(true));
+		}
+		if self.is_prefix() {
+			e.insert("prefix", Value::Bool(true));
+		}
+		Value::Object(Object::from(e))
 	}
 }
 
@@ -1,10 +1,13 @@
 use crate::ctx::Context;
 use crate::dbs::{Options, Transaction};
 use crate::err::Error;
+use crate::idx::builder::BuildingState;
 use crate::idx::planner::plan::IndexOption;
-use crate::sql::index::Index;
+use crate::sql::index::{Index, Nulls};
 use crate::sql::statements::DefineIndexStatement;
-use crate::sql::{Array, Cond, Expression, Idiom, Operator, Subquery, Table, Value};
+use crate::sql::{
+	Array, Cond, Expression, Function, Geometry, Idiom, Operator, Part, Subquery, Table, Value,
+};
 use async_recursion::async_recursion;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -20,18 +23,20 @@ impl Tree {
 		txn: &'a Transaction,
 		table: &'a Table,
 		cond: &'a Option<Cond>,
-	) -> Result<Option<(Node, IndexMap)>, Error> {
+	) -> Result<Option<(Node, IndexMap, Vec<IndexColumn>)>, Error> {
 		let mut b = TreeBuilder {
 			ctx,
 			opt,
 			txn,
 			table,
+			cond: cond.as_ref().map(|c| &c.0),
 			indexes: None,
 			index_map: IndexMap::default(),
+			columns: Vec::new(),
 		};
 		let mut res = None;
 		if let Some(cond) = cond {
-			res = Some((b.eval_value(&cond.0).await?, b.index_map));
+			res = Some((b.eval_value(&cond.0).await?, b.index_map, b.columns));
 		}
 		Ok(res)
 	}
@@ -42,43 +47,93 @@ struct TreeBuilder<'a> {
 	opt: &'a Options,
 	txn: &'a Transaction,
 	table: &'a Table,
+	cond: Option<&'a Value>,
 	indexes: Option<Arc<[DefineIndexStatement]>>,
 	index_map: IndexMap,
+	columns: Vec<IndexColumn>,
 }
 
 impl<'a> TreeBuilder<'a> {
+	async fn load_indexes(&mut self) -> Result<Arc<[DefineIndexStatement]>, Error> {
+		if let Some(indexes) = &self.indexes {
+			return Ok(indexes.clone());
+		}
+		let indexes = self
+			.txn
+			.clone()
+			.lock()
+			.await
+			.all_tb_indexes(self.opt.ns(), self.opt.db(), &self.table.0)
+			.await?;
+		self.indexes = Some(indexes.clone());
+		Ok(indexes)
+	}
+
+	async fn is_usable(&self, ix: &DefineIndexStatement) -> Result<bool, Error> {
+		// Partial indexes are only usable if the query matches the index condition
+		if let Some(c) = &ix.cond {
+			if !matches!(self.cond, Some(cond) if implies(cond, c)) {
+				return Ok(false);
+			}
+		}
+		// Indexes still being built in the background are not usable yet
+		if ix.concurrently {
+			let mut run = self.txn.lock().await;
+			if BuildingState::get(&mut run, self.opt.ns(), self.opt.db(), ix).await?.is_some() {
+				return Ok(false);
+			}
+		}
+		Ok(true)
+	}
+
 	async fn find_index(&mut self, i: &Idiom) -> Result<Option<DefineIndexStatement>, Error> {
-		if self.indexes.is_none() {
-			let indexes = self
-				.txn
-				.clone()
-				.lock()
-				.await
-				.all_tb_indexes(self.opt.ns(), self.opt.db(), &self.table.0)
-				.await?;
-			self.indexes = Some(indexes);
+		for ix in self.load_indexes().await?.iter() {
+			if ix.cols.len() == 1 && ix.cols[0].eq(i) && self.is_usable(ix).await? {
+				return Ok(Some(ix.clone()));
+			}
 		}
-		if let Some(indexes) = &self.indexes {
-			for ix in indexes.as_ref() {
-				if ix.cols.len() == 1 && ix.cols[0].eq(i) {
-					return Ok(Some(ix.clone()));
+		Ok(None)
+	}
+
+	/// Finds the composite indexes having the idiom as one of their columns,
+	/// with the position of the column in the index
+	async fn find_composite_indexes(
+		&mut self,
+		i: &Idiom,
+	) -> Result<Vec<(DefineIndexStatement, usize)>, Error> {
+		let mut res = Vec::new();
+		for ix in self.load_indexes().await?.iter() {
+			if ix.cols.len() < 2
+				|| !matches!(ix.index, Index::Idx | Index::Uniq | Index::UniqNulls(_))
+			{
+				continue;
+			}
+			if let Some(col) = ix.cols.iter().position(|c| c.eq(i)) {
+				if self.is_usable(ix).await? {
+					res.push((ix.clone(), col));
 				}
 			}
 		}
-		Ok(None)
+		Ok(res)
 	}
 
 	#[cfg_attr(not(target_arch = "wasm32"), async_recursion)]
 	#[cfg_attr(target_arch = "wasm32", async_recursion(?Send))]
 	async fn eval_value(&mut self, v: &Value) -> Result<Node, Error> {
 		match v {
-			Value::Expression(e) => self.eval_expression(e).await,
+			Value::Expression(e) => match self.eval_computed(v).await? {
+				Some(node) => Ok(node),
+				None => self.eval_expression(e).await,
+			},
 			Value::Idiom(i) => self.eval_idiom(i).await,
 			Value::Strand(_) => Ok(Node::Scalar(v.to_owned())),
 			Value::Number(_) => Ok(Node::Scalar(v.to_owned())),
 			Value::Bool(_) => Ok(Node::Scalar(v.to_owned())),
 			Value::Thing(_) => Ok(Node::Scalar(v.to_owned())),
+			Value::Array(_) => Ok(Node::Scalar(v.to_owned())),
+			Value::Geometry(_) => Ok(Node::Scalar(v.to_owned())),
 			Value::Subquery(s) => self.eval_subquery(s).await,
+			Value::Function(_) => self.eval_function(v).await,
 			Value::Param(p) => {
 				let v = p.compute(self.ctx, self.opt, self.txn, None).await?;
 				self.eval_value(&v).await
@@ -95,6 +150,84 @@ impl<'a> TreeBuilder<'a> {
 		})
 	}
 
+	/// A function can be resolved by an index defined on the same expression,
+	/// or by a geospatial index defined on one of its arguments
+	async fn eval_function(&mut self, v: &Value) -> Result<Node, Error> {
+		if let Value::Function(f) = v {
+			if let Function::Normal(name, args) = f.as_ref() {
+				if let Some(node) = self.eval_geo_function(v, name, args).await? {
+					return Ok(node);
+				}
+			}
+		}
+		Ok(match self.eval_computed(v).await? {
+			Some(node) => node,
+			None => Node::Unsupported(format!("Unsupported value: {}", v)),
+		})
+	}
+
+	/// A computed expression is resolved by an index defined on the same expression
+	async fn eval_computed(&mut self, v: &Value) -> Result<Option<Node>, Error> {
+		let i = Idiom::from(vec![Part::Start(v.to_owned())]);
+		Ok(self.find_index(&i).await?.map(|ix| Node::IndexedField(i, ix)))
+	}
+
+	/// `geo::contains` is resolved by an R-tree index on any of its arguments,
+	/// while `geo::distance` is only resolved when it is compared with a number.
+	async fn eval_geo_function(
+		&mut self,
+		v: &Value,
+		name: &str,
+		args: &[Value],
+	) -> Result<Option<Node>, Error> {
+		if !matches!(name, "geo::contains" | "geo::distance") || args.len() != 2 {
+			return Ok(None);
+		}
+		if !args.iter().all(|a| matches!(a, Value::Idiom(_) | Value::Geometry(_) | Value::Param(_)))
+		{
+			return Ok(None);
+		}
+		let left = self.eval_value(&args[0]).await?;
+		let right = self.eval_value(&args[1]).await?;
+		let (id, ix, g, op) = match (left.is_indexed_field(), right.is_indexed_field()) {
+			(Some((id, ix)), None) => (id, ix, right.is_scalar(), Operator::Contain),
+			(None, Some((id, ix))) => (id, ix, left.is_scalar(), Operator::Inside),
+			_ => return Ok(None),
+		};
+		let (Index::RTree(_), Some(Value::Geometry(g))) = (&ix.index, g) else {
+			return Ok(None);
+		};
+		if name == "geo::distance" {
+			return Ok(match g {
+				Geometry::Point(_) => {
+					Some(Node::GeoDistance(id.clone(), ix.clone(), Value::Geometry(g.clone())))
+				}
+				_ => None,
+			});
+		}
+		// The function is indexed as the expression `geo::contains(...) = true`
+		let exp = Expression::Binary {
+			l: v.clone(),
+			o: Operator::Equal,
+			r: Value::Bool(true),
+		};
+		let io = IndexOption::new(
+			ix.clone(),
+			id.clone(),
+			op,
+			Array::from(Value::Geometry(g.clone())),
+			None,
+			None,
+		);
+		self.index_map.0.insert(exp.clone(), io.clone());
+		Ok(Some(Node::Expression {
+			io: Some(io),
+			left: Box::new(left),
+			right: Box::new(right),
+			exp,
+		}))
+	}
+
 	async fn eval_expression(&mut self, e: &Expression) -> Result<Node, Error> {
 		match e {
 			Expression::Unary {
@@ -107,6 +240,10 @@ impl<'a> TreeBuilder<'a> {
 			} => {
 				let left = self.eval_value(l).await?;
 				let right = self.eval_value(r).await?;
+				if Operator::Equal.eq(o) {
+					self.eval_index_column(l, &right, e).await?;
+					self.eval_index_column(r, &left, e).await?;
+				}
 				if let Some(io) = self.index_map.0.get(e) {
 					return Ok(Node::Expression {
 						io: Some(io.clone()),
@@ -116,7 +253,16 @@ impl<'a> TreeBuilder<'a> {
 					});
 				}
 				let mut io = None;
-				if let Some((id, ix)) = left.is_indexed_field() {
+				if let Some((id, ix, point)) = left.is_geo_distance() {
+					io = self.lookup_geo_distance(ix, o, id, point, &right, e);
+				} else if let Some((id, ix, point)) = right.is_geo_distance() {
+					let o = match o {
+						Operator::MoreThan => &Operator::LessThan,
+						Operator::MoreThanOrEqual => &Operator::LessThanOrEqual,
+						_ => &Operator::Equal,
+					};
+					io = self.lookup_geo_distance(ix, o, id, point, &left, e);
+				} else if let Some((id, ix)) = left.is_indexed_field() {
 					io = self.lookup_index_option(ix, o, id, &right, e);
 				} else if let Some((id, ix)) = right.is_indexed_field() {
 					io = self.lookup_index_option(ix, o, id, &left, e);
@@ -131,6 +277,42 @@ impl<'a> TreeBuilder<'a> {
 		}
 	}
 
+	/// Collects the equalities between a column of a composite index and a value,
+	/// from which the planner can build a scan over a prefix of the index keys
+	async fn eval_index_column(
+		&mut self,
+		i: &Value,
+		v: &Node,
+		e: &Expression,
+	) -> Result<(), Error> {
+		let i = match i {
+			Value::Idiom(i) => i.to_owned(),
+			Value::Function(_) | Value::Expression(_) => {
+				Idiom::from(vec![Part::Start(i.to_owned())])
+			}
+			_ => return Ok(()),
+		};
+		let Some(v) = v.is_scalar() else {
+			return Ok(());
+		};
+		if v.is_array() || v.is_geometry() {
+			return Ok(());
+		}
+		for (ix, col) in self.find_composite_indexes(&i).await? {
+			// Unique entries containing a none or null value may not be indexed
+			if ix.index == Index::UniqNulls(Nulls::Distinct) && v.is_none_or_null() {
+				continue;
+			}
+			self.columns.push(IndexColumn {
+				ix,
+				col,
+				value: v.clone(),
+				exp: e.clone(),
+			});
+		}
+		Ok(())
+	}
+
 	fn lookup_index_option(
 		&mut self,
 		ix: &DefineIndexStatement,
@@ -141,18 +323,33 @@ impl<'a> TreeBuilder<'a> {
 	) -> Option<IndexOption> {
 		if let Some(v) = v.is_scalar() {
 			let (found, mr, qs) = match &ix.index {
+				// Array values are only supported by vector indexes
+				_ if v.is_array() && !matches!(ix.index, Index::Hnsw(_)) => (false, None, None),
+				// Geometry values are only supported by geospatial indexes
+				_ if v.is_geometry() != matches!(ix.index, Index::RTree(_)) => (false, None, None),
 				Index::Idx => (Operator::Equal.eq(op), None, None),
 				Index::Uniq => (Operator::Equal.eq(op), None, None),
+				// Unique entries containing a none or null value may not be indexed
+				Index::UniqNulls(n) => (
+					Operator::Equal.eq(op) && (*n == Nulls::NotDistinct || !v.is_none_or_null()),
+					None,
+					None,
+				),
 				Index::Search {
 					..
-				} => {
-					if let Operator::Matches(mr) = op {
+				} => match op {
+					Operator::Matches(mr) | Operator::FuzzyMatches(mr, _) => {
 						(true, *mr, Some(v.clone().to_raw_string()))
-					} else {
-						(false, None, None)
 					}
-				}
+					_ => (false, None, None),
+				},
 				Index::MTree(_) => (false, None, None),
+				Index::Hnsw(_) => (matches!(op, Operator::Knn(_)), None, None),
+				Index::RTree(_) => (
+					matches!(op, Operator::Contain | Operator::Inside | Operator::Intersects),
+					None,
+					None,
+				),
 			};
 			if found {
 				let io = IndexOption::new(
@@ -170,6 +367,32 @@ impl<'a> TreeBuilder<'a> {
 		None
 	}
 
+	/// Resolves `geo::distance(field, point) < distance`
+	fn lookup_geo_distance(
+		&mut self,
+		ix: &DefineIndexStatement,
+		op: &Operator,
+		id: &Idiom,
+		point: &Value,
+		v: &Node,
+		e: &Expression,
+	) -> Option<IndexOption> {
+		if !matches!(op, Operator::LessThan | Operator::LessThanOrEqual) {
+			return None;
+		}
+		let distance = v.is_scalar().filter(|v| v.is_number())?;
+		let io = IndexOption::new(
+			ix.clone(),
+			id.clone(),
+			op.to_owned(),
+			Array::from(vec![point.clone(), distance.clone()]),
+			None,
+			None,
+		);
+		self.index_map.0.insert(e.clone(), io.clone());
+		Some(io)
+	}
+
 	async fn eval_subquery(&mut self, s: &Subquery) -> Result<Node, Error> {
 		match s {
 			Subquery::Value(v) => self.eval_value(v).await,
@@ -178,6 +401,39 @@ impl<'a> TreeBuilder<'a> {
 	}
 }
 
+/// Checks whether a condition implies the condition of a partial index,
+/// i.e. whether every expression of the index condition which is joined
+/// with an AND operator is also joined with an AND operator in the condition.
+fn implies(cond: &Value, ix: &Value) -> bool {
+	let mut conds = Vec::new();
+	conjuncts(cond, &mut conds);
+	let mut ixs = Vec::new();
+	conjuncts(ix, &mut ixs);
+	ixs.iter().all(|v| conds.contains(v))
+}
+
+/// Collects the expressions of a condition which are joined with AND operators
+fn conjuncts<'a>(v: &'a Value, res: &mut Vec<&'a Value>) {
+	match v {
+		Value::Expression(e) => match e.as_ref() {
+			Expression::Binary {
+				l,
+				o: Operator::And,
+				r,
+			} => {
+				conjuncts(l, res);
+				conjuncts(r, res);
+			}
+			_ => res.push(v),
+		},
+		Value::Subquery(s) => match s.as_ref() {
+			Subquery::Value(v) => conjuncts(v, res),
+			_ => res.push(v),
+		},
+		_ => res.push(v),
+	}
+}
+
 /// For each expression the a possible index option
 #[derive(Default)]
 pub(super) struct IndexMap(HashMap<Expression, IndexOption>);
@@ -188,6 +444,16 @@ impl IndexMap {
 	}
 }
 
+/// An equality between a column of a composite index and a value
+#[derive(Debug, Clone)]
+pub(super) struct IndexColumn {
+	pub(super) ix: DefineIndexStatement,
+	/// The position of the column in the index
+	pub(super) col: usize,
+	pub(super) value: Value,
+	pub(super) exp: Expression,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub(super) enum Node {
 	Expression {
@@ -197,6 +463,8 @@ pub(super) enum Node {
 		exp: Expression,
 	},
 	IndexedField(Idiom, DefineIndexStatement),
+	/// A `geo::distance` between a field with a geospatial index and a point
+	GeoDistance(Idiom, DefineIndexStatement, Value),
 	NonIndexedField,
 	Scalar(Value),
 	Unsupported(String),
@@ -218,4 +486,12 @@ impl Node {
 			None
 		}
 	}
+
+	pub(super) fn is_geo_distance(&self) -> Option<(&Idiom, &DefineIndexStatement, &Value)> {
+		if let Node::GeoDistance(id, ix, point) = self {
+			Some((id, ix, point))
+		} else {
+			None
+		}
+	}
 }
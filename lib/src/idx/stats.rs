@@ -0,0 +1,227 @@
+use crate::cnf::INDEX_STATS_SHARDS;
+use crate::err::Error;
+use crate::idx::VersionedSerdeState;
+use crate::key::index::ic::Ic;
+use crate::key::index::is::Is;
+use crate::kvs;
+use crate::kvs::Val;
+use crate::sql::statements::DefineIndexStatement;
+use crate::sql::{Datetime, Object, Value};
+use rand::Rng;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::ops::AddAssign;
+
+/// The statistics of an index.
+/// They are maintained incrementally each time the entries of the index are written.
+/// The changes to the counters are spread across a number of shards, which are
+/// added to the stored statistics when they are read.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[revisioned(revision = 1)]
+pub(crate) struct IndexStats {
+	/// The number of entries in the index
+	count: u64,
+	/// The approximate size in bytes of the index entries
+	size: u64,
+	/// When the index was last built
+	built: Option<Datetime>,
+	/// The number of records indexed
+	records: u64,
+	/// The number of distinct values indexed (excluding NONE and NULL values)
+	distinct: u64,
+	/// Whether the counters have been maintained since the index was built
+	counted: bool,
+}
+
+/// The changes applied to the statistics of an index by a single index operation
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[revisioned(revision = 1)]
+pub(crate) struct IndexStatsDelta {
+	/// The number of entries added to the index
+	pub(crate) count: i64,
+	/// The number of bytes added to the index
+	pub(crate) size: i64,
+	/// The number of records added to the index
+	pub(crate) records: i64,
+	/// The number of distinct values added to the index
+	pub(crate) distinct: i64,
+}
+
+impl AddAssign for IndexStatsDelta {
+	fn add_assign(&mut self, other: Self) {
+		self.count += other.count;
+		self.size += other.size;
+		self.records += other.records;
+		self.distinct += other.distinct;
+	}
+}
+
+impl VersionedSerdeState for IndexStats {}
+
+impl VersionedSerdeState for IndexStatsDelta {}
+
+impl IndexStatsDelta {
+	/// Adds the changes to the statistics to the changes recorded in a shard
+	fn merge(val: Option<Val>, change: Val) -> Result<Val, Error> {
+		let mut sum = match val {
+			Some(val) => Self::try_from_val(val)?,
+			None => Self::default(),
+		};
+		sum += Self::try_from_val(change)?;
+		sum.try_to_val()
+	}
+}
+
+impl IndexStats {
+	pub(crate) async fn get(
+		run: &mut kvs::Transaction,
+		ns: &str,
+		db: &str,
+		ix: &DefineIndexStatement,
+	) -> Result<Self, Error> {
+		let key = Is::new(ns, db, &ix.what, &ix.name);
+		let mut stats = match run.get(key).await? {
+			Some(val) => Self::try_from_val(val)?,
+			None => Self::default(),
+		};
+		// Add the changes recorded in each shard
+		let beg = Ic::prefix(ns, db, &ix.what, &ix.name);
+		let end = Ic::suffix(ns, db, &ix.what, &ix.name);
+		let mut delta = IndexStatsDelta::default();
+		for (_, val) in run.scan(beg..end, INDEX_STATS_SHARDS).await? {
+			delta += IndexStatsDelta::try_from_val(val)?;
+		}
+		stats.apply(delta);
+		Ok(stats)
+	}
+
+	async fn set(
+		&self,
+		run: &mut kvs::Transaction,
+		ns: &str,
+		db: &str,
+		ix: &DefineIndexStatement,
+	) -> Result<(), Error> {
+		let key = Is::new(ns, db, &ix.what, &ix.name);
+		run.set(key, self.try_to_val()?).await
+	}
+
+	pub(crate) async fn del(
+		run: &mut kvs::Transaction,
+		ns: &str,
+		db: &str,
+		ix: &DefineIndexStatement,
+	) -> Result<(), Error> {
+		let key = Is::new(ns, db, &ix.what, &ix.name);
+		run.del(key).await?;
+		let beg = Ic::prefix(ns, db, &ix.what, &ix.name);
+		let end = Ic::suffix(ns, db, &ix.what, &ix.name);
+		run.delr(beg..end, INDEX_STATS_SHARDS).await
+	}
+
+	fn apply(&mut self, delta: IndexStatsDelta) {
+		self.count = self.count.saturating_add_signed(delta.count);
+		self.size = self.size.saturating_add_signed(delta.size);
+		self.records = self.records.saturating_add_signed(delta.records);
+		self.distinct = self.distinct.saturating_add_signed(delta.distinct);
+	}
+
+	/// Applies the number of entries, bytes, records and distinct values,
+	/// added to (or removed from) an index, to a randomly chosen shard
+	pub(crate) async fn update(
+		run: &mut kvs::Transaction,
+		ns: &str,
+		db: &str,
+		ix: &DefineIndexStatement,
+		delta: IndexStatsDelta,
+	) -> Result<(), Error> {
+		if delta == IndexStatsDelta::default() {
+			return Ok(());
+		}
+		let shard = rand::thread_rng().gen_range(0..INDEX_STATS_SHARDS);
+		let key = Ic::new(ns, db, &ix.what, &ix.name, shard);
+		run.merge(key, delta.try_to_val()?, IndexStatsDelta::merge).await
+	}
+
+	/// Records that the index has just been built, merging the shards into the statistics
+	pub(crate) async fn built(
+		run: &mut kvs::Transaction,
+		ns: &str,
+		db: &str,
+		ix: &DefineIndexStatement,
+	) -> Result<(), Error> {
+		let mut stats = Self::get(run, ns, db, ix).await?;
+		stats.built = Some(Datetime::default());
+		stats.counted = true;
+		Self::del(run, ns, db, ix).await?;
+		stats.set(run, ns, db, ix).await
+	}
+
+	/// The number of entries in the index, if it has been maintained since the index was built
+	pub(crate) fn entries(&self) -> Option<u64> {
+		self.counted.then_some(self.count)
+	}
+
+	/// The number of records indexed, if it has been maintained since the index was built
+	pub(crate) fn records(&self) -> Option<u64> {
+		self.counted.then_some(self.records)
+	}
+
+	/// The number of distinct values indexed, if it has been maintained since the index was built
+	pub(crate) fn distinct(&self) -> Option<u64> {
+		self.counted.then_some(self.distinct)
+	}
+}
+
+impl From<IndexStats> for Object {
+	fn from(s: IndexStats) -> Self {
+		let mut res = Object::default();
+		res.insert("count".to_owned(), s.entries().map_or(Value::None, Value::from));
+		res.insert("size".to_owned(), s.size.into());
+		res.insert("built".to_owned(), s.built.map_or(Value::None, Value::from));
+		res
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::idx::stats::{IndexStats, IndexStatsDelta};
+	use crate::kvs::Datastore;
+	use crate::sql::statements::DefineIndexStatement;
+	use crate::sql::{Object, Value};
+
+	#[tokio::test]
+	async fn update_statistics() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let ix = DefineIndexStatement::default();
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		let delta = |count, size, records, distinct| IndexStatsDelta {
+			count,
+			size,
+			records,
+			distinct,
+		};
+		IndexStats::update(&mut tx, "ns", "db", &ix, delta(3, 90, 3, 2)).await.unwrap();
+		// The counters are not reported until the index has been built
+		let s = IndexStats::get(&mut tx, "ns", "db", &ix).await.unwrap();
+		assert_eq!(s.records(), None);
+		assert_eq!(s.distinct(), None);
+		assert_eq!(Object::from(s).get("count"), Some(&Value::None));
+		IndexStats::update(&mut tx, "ns", "db", &ix, delta(-1, -30, -1, 0)).await.unwrap();
+		IndexStats::built(&mut tx, "ns", "db", &ix).await.unwrap();
+		let s = IndexStats::get(&mut tx, "ns", "db", &ix).await.unwrap();
+		assert_eq!(s.count, 2);
+		assert_eq!(s.size, 60);
+		assert_eq!(s.records(), Some(2));
+		assert_eq!(s.distinct(), Some(2));
+		assert!(s.built.is_some());
+		// Removing more entries than indexed never underflows
+		IndexStats::update(&mut tx, "ns", "db", &ix, delta(-5, -500, -5, -5)).await.unwrap();
+		let s = IndexStats::get(&mut tx, "ns", "db", &ix).await.unwrap();
+		assert_eq!(s.count, 0);
+		assert_eq!(s.size, 0);
+		assert_eq!(s.records(), Some(0));
+		assert_eq!(s.distinct(), Some(0));
+		tx.cancel().await.unwrap();
+	}
+}
@@ -152,6 +152,14 @@ where
 	}
 }
 
+/// Accepts, or rejects, the keys of a BTree during a search
+pub(in crate::idx) trait KeyMatcher {
+	/// Checks if the key is accepted
+	fn matches(&self, key: &Key) -> bool;
+	/// Checks if any key starting with the prefix may be accepted
+	fn may_match_prefix(&self, prefix: &[u8]) -> bool;
+}
+
 struct SplitResult {
 	left_node_id: NodeId,
 	right_node_id: NodeId,
@@ -193,6 +201,59 @@ where
 		Ok(None)
 	}
 
+	/// Collects the keys, and their payloads, which are accepted by the matcher.
+	/// Every key of a subtree starts with the prefix shared by the keys which bound the subtree,
+	/// therefore a subtree is only visited if the matcher may accept this prefix.
+	pub(in crate::idx) async fn search_matching<M>(
+		&self,
+		tx: &mut Transaction,
+		store: &mut BTreeNodeStore<BK>,
+		matcher: &M,
+	) -> Result<Vec<(Key, Payload)>, Error>
+	where
+		M: KeyMatcher,
+	{
+		let mut res = Vec::new();
+		let mut next_nodes = VecDeque::new();
+		if let Some(node_id) = self.state.root {
+			next_nodes.push_back((node_id, None, None));
+		}
+		while let Some((node_id, lower, upper)) = next_nodes.pop_front() {
+			let current = store.get_node(tx, node_id).await?;
+			let keys = current.node.keys();
+			let mut bounds: Vec<Key> = Vec::with_capacity(keys.len() as usize);
+			for idx in 0..keys.len() as usize {
+				if let Some(key) = keys.get_key(idx) {
+					if matcher.matches(&key) {
+						if let Some(payload) = keys.get(&key) {
+							res.push((key.clone(), payload));
+						}
+					}
+					bounds.push(key);
+				}
+			}
+			if let BTreeNode::Internal(_, children) = &current.node {
+				for (idx, child_id) in children.iter().enumerate() {
+					let lower = if idx == 0 {
+						lower.clone()
+					} else {
+						bounds.get(idx - 1).cloned()
+					};
+					let upper = bounds.get(idx).cloned().or_else(|| upper.clone());
+					let visit = match (&lower, &upper) {
+						(Some(l), Some(u)) => matcher.may_match_prefix(common_prefix(l, u)),
+						_ => true,
+					};
+					if visit {
+						next_nodes.push_back((*child_id, lower, upper));
+					}
+				}
+			}
+			store.set_node(current, false)?;
+		}
+		Ok(res)
+	}
+
 	pub async fn insert(
 		&mut self,
 		tx: &mut Transaction,
@@ -671,12 +732,18 @@ where
 	}
 }
 
+/// The longest prefix shared by two keys
+fn common_prefix<'a>(k1: &'a Key, k2: &Key) -> &'a [u8] {
+	let len = k1.iter().zip(k2.iter()).take_while(|(b1, b2)| b1 == b2).count();
+	&k1[..len]
+}
+
 #[cfg(test)]
 mod tests {
 	use crate::err::Error;
 	use crate::idx::trees::bkeys::{BKeys, FstKeys, TrieKeys};
 	use crate::idx::trees::btree::{
-		BState, BStatistics, BStoredNode, BTree, BTreeNode, BTreeNodeStore, Payload,
+		BState, BStatistics, BStoredNode, BTree, BTreeNode, BTreeNodeStore, KeyMatcher, Payload,
 	};
 	use crate::idx::trees::store::{
 		NodeId, TreeNode, TreeNodeProvider, TreeNodeStore, TreeStoreType,
@@ -685,6 +752,7 @@ mod tests {
 	use crate::kvs::{Datastore, Key, Transaction};
 	use rand::prelude::SliceRandom;
 	use rand::thread_rng;
+	use std::cell::Cell;
 	use std::collections::{HashMap, VecDeque};
 	use test_log::test;
 
@@ -761,6 +829,41 @@ mod tests {
 		tx.cancel().await.unwrap();
 	}
 
+	/// Accepts the keys starting with a given byte, and counts the keys which are checked
+	struct FirstByteMatcher(u8, Cell<usize>);
+
+	impl KeyMatcher for FirstByteMatcher {
+		fn matches(&self, key: &Key) -> bool {
+			self.1.set(self.1.get() + 1);
+			key.first() == Some(&self.0)
+		}
+
+		fn may_match_prefix(&self, prefix: &[u8]) -> bool {
+			prefix.first().map_or(true, |b| *b == self.0)
+		}
+	}
+
+	#[test(tokio::test)]
+	async fn test_btree_fst_search_matching() {
+		let s = TreeNodeStore::new(TreeNodeProvider::Debug, TreeStoreType::Write, 20);
+		let mut s = s.lock().await;
+		let mut t = BTree::new(BState::new(3));
+		let ds = Datastore::new("memory").await.unwrap();
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		insertions_test::<_, FstKeys>(&mut tx, &mut s, &mut t, 100, get_key_value).await;
+		let matcher = FirstByteMatcher(b'4', Cell::new(0));
+		let mut res = t.search_matching(&mut tx, &mut s, &matcher).await.unwrap();
+		res.sort();
+		let mut expected: Vec<(Key, Payload)> =
+			[4, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49].into_iter().map(get_key_value).collect();
+		expected.sort();
+		assert_eq!(res, expected);
+		// The subtrees which can not contain any matching key are not visited
+		assert!(matcher.1.get() < 100);
+		s.finish(&mut tx).await.unwrap();
+		tx.commit().await.unwrap();
+	}
+
 	#[test(tokio::test)]
 	async fn test_btree_trie_small_order_sequential_insertions() {
 		let s = TreeNodeStore::new(TreeNodeProvider::Debug, TreeStoreType::Write, 20);
@@ -0,0 +1,223 @@
+//! Distance computations between vectors, for the vector indexes.
+//!
+//! Computing distances dominates the time taken by nearest neighbour searches, so when the
+//! `simd` feature is enabled these use SIMD instructions where the CPU supports them, which
+//! is detected at runtime. Otherwise, or on other CPUs, the portable implementations are used.
+
+/// Returns from the enclosing function with the result of the fastest implementation of a
+/// distance function which the CPU supports.
+macro_rules! dispatch {
+	($f:ident($a:expr, $b:expr)) => {{
+		let (a, b): (&[f64], &[f64]) = ($a, $b);
+		#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+		if is_x86_feature_detected!("avx") && is_x86_feature_detected!("fma") {
+			// SAFETY: the CPU features which the function is compiled for are available
+			return unsafe { x86::$f(a, b) };
+		}
+		#[cfg(all(feature = "simd", target_arch = "aarch64"))]
+		if std::arch::is_aarch64_feature_detected!("neon") {
+			// SAFETY: the CPU features which the function is compiled for are available
+			return unsafe { aarch64::$f(a, b) };
+		}
+		scalar::$f(a, b)
+	}};
+}
+
+/// Returns the euclidean distance between two vectors.
+pub(crate) fn euclidean(a: &[f64], b: &[f64]) -> f64 {
+	squared_euclidean(a, b).sqrt()
+}
+
+/// Returns the manhattan distance between two vectors.
+pub(crate) fn manhattan(a: &[f64], b: &[f64]) -> f64 {
+	dispatch!(manhattan(a, b))
+}
+
+/// Returns the cosine distance between two vectors, which is one minus their cosine
+/// similarity, or one if either vector has no magnitude.
+pub(crate) fn cosine(a: &[f64], b: &[f64]) -> f64 {
+	let mag = dot(a, a).sqrt() * dot(b, b).sqrt();
+	if mag == 0.0 {
+		return 1.0;
+	}
+	1.0 - dot(a, b) / mag
+}
+
+/// Returns the dot product of two vectors.
+pub(crate) fn dot(a: &[f64], b: &[f64]) -> f64 {
+	dispatch!(dot(a, b))
+}
+
+/// Returns the sum of the squared differences between two vectors.
+fn squared_euclidean(a: &[f64], b: &[f64]) -> f64 {
+	dispatch!(squared_euclidean(a, b))
+}
+
+/// The portable implementations, where vectors of different dimensions are truncated to
+/// the smaller dimension.
+mod scalar {
+	pub(super) fn dot(a: &[f64], b: &[f64]) -> f64 {
+		a.iter().zip(b.iter()).map(|(a, b)| a * b).sum()
+	}
+
+	pub(super) fn squared_euclidean(a: &[f64], b: &[f64]) -> f64 {
+		a.iter().zip(b.iter()).map(|(a, b)| (a - b).powi(2)).sum()
+	}
+
+	pub(super) fn manhattan(a: &[f64], b: &[f64]) -> f64 {
+		a.iter().zip(b.iter()).map(|(a, b)| (a - b).abs()).sum()
+	}
+}
+
+/// The implementations using AVX and FMA instructions, which process four values at once.
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod x86 {
+	use std::arch::x86_64::*;
+
+	const LANES: usize = 4;
+
+	#[target_feature(enable = "avx,fma")]
+	unsafe fn sum(v: __m256d) -> f64 {
+		let mut out = [0.0; LANES];
+		_mm256_storeu_pd(out.as_mut_ptr(), v);
+		out.iter().sum()
+	}
+
+	#[target_feature(enable = "avx,fma")]
+	pub(super) unsafe fn dot(a: &[f64], b: &[f64]) -> f64 {
+		let len = a.len().min(b.len());
+		let end = len - len % LANES;
+		let mut acc = _mm256_setzero_pd();
+		for i in (0..end).step_by(LANES) {
+			let va = _mm256_loadu_pd(a.as_ptr().add(i));
+			let vb = _mm256_loadu_pd(b.as_ptr().add(i));
+			acc = _mm256_fmadd_pd(va, vb, acc);
+		}
+		sum(acc) + super::scalar::dot(&a[end..len], &b[end..len])
+	}
+
+	#[target_feature(enable = "avx,fma")]
+	pub(super) unsafe fn squared_euclidean(a: &[f64], b: &[f64]) -> f64 {
+		let len = a.len().min(b.len());
+		let end = len - len % LANES;
+		let mut acc = _mm256_setzero_pd();
+		for i in (0..end).step_by(LANES) {
+			let va = _mm256_loadu_pd(a.as_ptr().add(i));
+			let vb = _mm256_loadu_pd(b.as_ptr().add(i));
+			let d = _mm256_sub_pd(va, vb);
+			acc = _mm256_fmadd_pd(d, d, acc);
+		}
+		sum(acc) + super::scalar::squared_euclidean(&a[end..len], &b[end..len])
+	}
+
+	#[target_feature(enable = "avx,fma")]
+	pub(super) unsafe fn manhattan(a: &[f64], b: &[f64]) -> f64 {
+		let len = a.len().min(b.len());
+		let end = len - len % LANES;
+		// Clearing the sign bit gives the absolute value
+		let sign = _mm256_set1_pd(-0.0);
+		let mut acc = _mm256_setzero_pd();
+		for i in (0..end).step_by(LANES) {
+			let va = _mm256_loadu_pd(a.as_ptr().add(i));
+			let vb = _mm256_loadu_pd(b.as_ptr().add(i));
+			let d = _mm256_andnot_pd(sign, _mm256_sub_pd(va, vb));
+			acc = _mm256_add_pd(acc, d);
+		}
+		sum(acc) + super::scalar::manhattan(&a[end..len], &b[end..len])
+	}
+}
+
+/// The implementations using NEON instructions, which process two values at once.
+#[cfg(all(feature = "simd", target_arch = "aarch64"))]
+mod aarch64 {
+	use std::arch::aarch64::*;
+
+	const LANES: usize = 2;
+
+	#[target_feature(enable = "neon")]
+	pub(super) unsafe fn dot(a: &[f64], b: &[f64]) -> f64 {
+		let len = a.len().min(b.len());
+		let end = len - len % LANES;
+		let mut acc = vdupq_n_f64(0.0);
+		for i in (0..end).step_by(LANES) {
+			let va = vld1q_f64(a.as_ptr().add(i));
+			let vb = vld1q_f64(b.as_ptr().add(i));
+			acc = vfmaq_f64(acc, va, vb);
+		}
+		vaddvq_f64(acc) + super::scalar::dot(&a[end..len], &b[end..len])
+	}
+
+	#[target_feature(enable = "neon")]
+	pub(super) unsafe fn squared_euclidean(a: &[f64], b: &[f64]) -> f64 {
+		let len = a.len().min(b.len());
+		let end = len - len % LANES;
+		let mut acc = vdupq_n_f64(0.0);
+		for i in (0..end).step_by(LANES) {
+			let va = vld1q_f64(a.as_ptr().add(i));
+			let vb = vld1q_f64(b.as_ptr().add(i));
+			let d = vsubq_f64(va, vb);
+			acc = vfmaq_f64(acc, d, d);
+		}
+		vaddvq_f64(acc) + super::scalar::squared_euclidean(&a[end..len], &b[end..len])
+	}
+
+	#[target_feature(enable = "neon")]
+	pub(super) unsafe fn manhattan(a: &[f64], b: &[f64]) -> f64 {
+		let len = a.len().min(b.len());
+		let end = len - len % LANES;
+		let mut acc = vdupq_n_f64(0.0);
+		for i in (0..end).step_by(LANES) {
+			let va = vld1q_f64(a.as_ptr().add(i));
+			let vb = vld1q_f64(b.as_ptr().add(i));
+			acc = vaddq_f64(acc, vabdq_f64(va, vb));
+		}
+		vaddvq_f64(acc) + super::scalar::manhattan(&a[end..len], &b[end..len])
+	}
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	fn vectors(len: usize) -> (Vec<f64>, Vec<f64>) {
+		let a = (0..len).map(|i| i as f64 * 0.5 - 3.0).collect();
+		let b = (0..len).map(|i| (len - i) as f64 * 1.25).collect();
+		(a, b)
+	}
+
+	fn assert_close(a: f64, b: f64) {
+		assert!((a - b).abs() <= 1e-9 * a.abs().max(1.0), "{a} != {b}");
+	}
+
+	#[test]
+	fn distances_match_scalar() {
+		// Cover dimensions which are shorter than, equal to, and not a multiple of the lanes
+		for len in 0..=19 {
+			let (a, b) = vectors(len);
+			assert_close(dot(&a, &b), scalar::dot(&a, &b));
+			assert_close(euclidean(&a, &b), scalar::squared_euclidean(&a, &b).sqrt());
+			assert_close(manhattan(&a, &b), scalar::manhattan(&a, &b));
+		}
+	}
+
+	#[test]
+	fn distances_of_known_vectors() {
+		let a = [1.0, 2.0, 3.0, 4.0, 5.0];
+		let b = [5.0, 4.0, 3.0, 2.0, 1.0];
+		assert_close(dot(&a, &b), 35.0);
+		assert_close(euclidean(&a, &b), 40f64.sqrt());
+		assert_close(manhattan(&a, &b), 12.0);
+		assert_close(cosine(&a, &a), 0.0);
+		assert_close(cosine(&a, &b), 1.0 - 35.0 / 55.0);
+		assert_close(cosine(&a, &[0.0; 5]), 1.0);
+	}
+
+	#[test]
+	fn distances_of_different_dimensions() {
+		let a = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+		let b = [1.0, 2.0, 3.0, 4.0, 6.0];
+		assert_close(euclidean(&a, &b), 1.0);
+		assert_close(euclidean(&b, &a), 1.0);
+	}
+}
@@ -0,0 +1,654 @@
+use crate::err::Error;
+use crate::idx::ft::docids::{DocId, DocIds};
+use crate::idx::trees::mtree::{check_vector, Vector};
+use crate::idx::trees::store::{NodeId, TreeNode, TreeNodeProvider, TreeNodeStore, TreeStoreType};
+use crate::idx::{IndexKeyBase, VersionedSerdeState};
+use crate::key::index::hn::Hn;
+use crate::kvs::{Key, Transaction, Val};
+use crate::sql::index::{Distance, HnswParams};
+use crate::sql::{Thing, Value};
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashSet};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// The highest layer a node can be assigned to
+const MAX_LEVEL: usize = 16;
+
+pub(in crate::idx) type HnswNodeStore = TreeNodeStore<HnswNode>;
+
+pub(crate) struct HnswIndex {
+	state_key: Key,
+	dim: usize,
+	ikb: IndexKeyBase,
+	doc_ids: DocIds,
+	hnsw: Hnsw,
+	store: Arc<Mutex<HnswNodeStore>>,
+}
+
+impl HnswIndex {
+	pub(crate) async fn new(
+		tx: &mut Transaction,
+		ikb: IndexKeyBase,
+		p: &HnswParams,
+		st: TreeStoreType,
+	) -> Result<Self, Error> {
+		let doc_ids = DocIds::new(tx, ikb.clone(), p.doc_ids_order, st).await?;
+		let state_key = ikb.new_hn_key(None);
+		let state: HState = if let Some(val) = tx.get(state_key.clone()).await? {
+			HState::try_from_val(val)?
+		} else {
+			HState::default()
+		};
+		let store =
+			TreeNodeStore::new(TreeNodeProvider::Graph(ikb.clone()), st, tx.index_cache_size());
+		let hnsw = Hnsw::new(state, p.distance.clone(), p.m, p.ef_construction);
+		Ok(Self {
+			state_key,
+			dim: p.dimension as usize,
+			ikb,
+			doc_ids,
+			hnsw,
+			store,
+		})
+	}
+
+	/// Indexes the vectors of a document.
+	/// Returns `true` if the document was not indexed yet.
+	pub(crate) async fn index_document(
+		&mut self,
+		tx: &mut Transaction,
+		rid: &Thing,
+		content: &[Value],
+	) -> Result<bool, Error> {
+		// Resolve the doc_id
+		let doc_key: Key = rid.into();
+		let resolved = self.doc_ids.resolve_doc_id(tx, doc_key).await?;
+		let doc_id = *resolved.doc_id();
+		// Collect the nodes already indexed for the document
+		let nodes_key = self.ikb.new_hd_key(doc_id);
+		let mut nodes: Vec<NodeId> = match tx.get(nodes_key.clone()).await? {
+			Some(val) => bincode::deserialize(&val)?,
+			None => Vec::with_capacity(content.len()),
+		};
+		// Insert a node for each vector
+		let mut store = self.store.lock().await;
+		for v in content {
+			// Records without a vector are not indexed
+			if v.is_none_or_null() {
+				continue;
+			}
+			let vector = check_vector(self.dim, v)?;
+			nodes.push(self.hnsw.insert(tx, &mut store, vector, doc_id).await?);
+		}
+		tx.set(nodes_key, bincode::serialize(&nodes)?).await?;
+		Ok(!resolved.was_existing())
+	}
+
+	/// Removes the vectors of a document.
+	/// Returns `true` if the document was indexed.
+	pub(crate) async fn remove_document(
+		&mut self,
+		tx: &mut Transaction,
+		rid: &Thing,
+	) -> Result<bool, Error> {
+		let doc_key: Key = rid.into();
+		let Some(doc_id) = self.doc_ids.remove_doc(tx, doc_key).await? else {
+			return Ok(false);
+		};
+		let nodes_key = self.ikb.new_hd_key(doc_id);
+		if let Some(val) = tx.get(nodes_key.clone()).await? {
+			let nodes: Vec<NodeId> = bincode::deserialize(&val)?;
+			let mut store = self.store.lock().await;
+			for node_id in nodes {
+				self.hnsw.delete(tx, &mut store, node_id).await?;
+			}
+			// The entry point was not connected to any other node,
+			// so a new one is picked from the remaining nodes.
+			if self.hnsw.is_orphaned() {
+				store.finish(tx).await?;
+				let beg = self.ikb.new_hn_key(Some(0));
+				let end = self.ikb.new_hn_key(Some(NodeId::MAX));
+				if let Some((k, _)) = tx.scan(beg..end, 1).await?.pop() {
+					if let Some(node_id) = Hn::decode(&k)?.node_id {
+						self.hnsw.set_entry(tx, &mut store, node_id).await?;
+					}
+				}
+			}
+			tx.del(nodes_key).await?;
+		}
+		Ok(true)
+	}
+
+	/// Returns the records of the k nearest neighbours of a vector, closest first
+	pub(crate) async fn knn_search(
+		&self,
+		tx: &mut Transaction,
+		v: &Value,
+		k: usize,
+	) -> Result<Vec<Thing>, Error> {
+		let vector = check_vector(self.dim, v)?;
+		let mut store = self.store.lock().await;
+		let neighbours = self.hnsw.knn(tx, &mut store, &vector, k).await?;
+		let mut res = Vec::with_capacity(neighbours.len());
+		for doc_id in neighbours {
+			if let Some(doc_key) = self.doc_ids.get_doc_key(tx, doc_id).await? {
+				res.push(doc_key.into());
+			}
+		}
+		Ok(res)
+	}
+
+	/// The number of layers of the graph
+	pub(crate) fn depth(&self) -> u32 {
+		self.hnsw.depth()
+	}
+
+	pub(crate) async fn finish(mut self, tx: &mut Transaction) -> Result<(), Error> {
+		self.doc_ids.finish(tx).await?;
+		let updated = self.store.lock().await.finish(tx).await?;
+		if self.hnsw.updated || updated {
+			tx.set(self.state_key, self.hnsw.state.try_to_val()?).await?;
+		}
+		Ok(())
+	}
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[revisioned(revision = 1)]
+pub(in crate::idx) struct HState {
+	/// The node every search starts from, which is on the top layer
+	entry: Option<NodeId>,
+	/// The top layer of the graph
+	level: u16,
+	/// The number of nodes in the graph
+	count: u64,
+	next_node_id: NodeId,
+}
+
+impl VersionedSerdeState for HState {}
+
+/// A vector of a document, and its links to its neighbours on each layer it belongs to.
+/// Links are symmetric, so that every link to a node can be found when it is removed.
+#[derive(Debug, Serialize, Deserialize)]
+pub(in crate::idx) struct HnswNode {
+	vector: Vector,
+	doc_id: DocId,
+	layers: Vec<Vec<NodeId>>,
+}
+
+impl TreeNode for HnswNode {
+	fn try_from_val(val: Val) -> Result<Self, Error> {
+		Ok(bincode::deserialize(&val)?)
+	}
+
+	fn try_into_val(&mut self) -> Result<Val, Error> {
+		Ok(bincode::serialize(self)?)
+	}
+}
+
+/// A node and its distance to the vector being searched for
+#[derive(Clone, Copy, Debug)]
+struct Scored(f64, NodeId);
+
+impl PartialEq for Scored {
+	fn eq(&self, other: &Self) -> bool {
+		self.cmp(other) == Ordering::Equal
+	}
+}
+
+impl Eq for Scored {}
+
+impl PartialOrd for Scored {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for Scored {
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.0.total_cmp(&other.0).then(self.1.cmp(&other.1))
+	}
+}
+
+/// A Hierarchical Navigable Small World graph
+pub(in crate::idx) struct Hnsw {
+	state: HState,
+	distance: Distance,
+	/// The maximum number of links of a node on the upper layers
+	m: usize,
+	/// The number of candidates considered while inserting a node
+	efc: usize,
+	/// The normalization factor of the random level generation
+	ml: f64,
+	updated: bool,
+}
+
+impl Hnsw {
+	pub(in crate::idx) fn new(state: HState, distance: Distance, m: u16, efc: u16) -> Self {
+		let m = m.max(2) as usize;
+		Self {
+			state,
+			distance,
+			m,
+			efc: efc.max(1) as usize,
+			ml: 1.0 / (m as f64).ln(),
+			updated: false,
+		}
+	}
+
+	/// The maximum number of links of a node on a given layer
+	fn max_links(&self, layer: usize) -> usize {
+		if layer == 0 {
+			self.m * 2
+		} else {
+			self.m
+		}
+	}
+
+	/// Draws the top layer of a new node, following an exponentially decaying distribution
+	fn random_level(&self) -> usize {
+		let r: f64 = rand::random();
+		((-(1.0 - r).ln() * self.ml).floor() as usize).min(MAX_LEVEL)
+	}
+
+	fn depth(&self) -> u32 {
+		if self.state.entry.is_some() {
+			self.state.level as u32 + 1
+		} else {
+			0
+		}
+	}
+
+	pub(in crate::idx) async fn insert(
+		&mut self,
+		tx: &mut Transaction,
+		store: &mut HnswNodeStore,
+		vector: Vector,
+		doc_id: DocId,
+	) -> Result<NodeId, Error> {
+		let node_id = self.new_node_id();
+		let level = self.random_level();
+		let mut layers = vec![Vec::new(); level + 1];
+		self.state.count += 1;
+		self.updated = true;
+		let Some(entry) = self.state.entry else {
+			// The graph is empty, the new node is the entry point
+			let node = store.new_node(
+				node_id,
+				HnswNode {
+					vector,
+					doc_id,
+					layers,
+				},
+			)?;
+			store.set_node(node, true)?;
+			self.state.entry = Some(node_id);
+			self.state.level = level as u16;
+			return Ok(node_id);
+		};
+		let top = self.state.level as usize;
+		let mut eps = vec![Scored(self.distance_to(tx, store, &vector, entry).await?, entry)];
+		// Greedy search of the closest node, down to the top layer of the new node
+		for layer in (level + 1..=top).rev() {
+			eps = self.search_layer(tx, store, &vector, eps, 1, layer).await?;
+		}
+		// Find the closest neighbours on each layer of the new node
+		for layer in (0..=level.min(top)).rev() {
+			eps = self.search_layer(tx, store, &vector, eps, self.efc, layer).await?;
+			layers[layer] = eps.iter().take(self.max_links(layer)).map(|s| s.1).collect();
+		}
+		let node = store.new_node(
+			node_id,
+			HnswNode {
+				vector,
+				doc_id,
+				layers: layers.clone(),
+			},
+		)?;
+		store.set_node(node, true)?;
+		// Link the neighbours back to the new node
+		for (layer, links) in layers.iter().enumerate() {
+			for &n in links {
+				self.link(tx, store, n, node_id, layer).await?;
+			}
+		}
+		if level > top {
+			self.state.entry = Some(node_id);
+			self.state.level = level as u16;
+		}
+		Ok(node_id)
+	}
+
+	pub(in crate::idx) async fn delete(
+		&mut self,
+		tx: &mut Transaction,
+		store: &mut HnswNodeStore,
+		node_id: NodeId,
+	) -> Result<(), Error> {
+		let node = store.get_node(tx, node_id).await?;
+		let layers = node.node.layers.clone();
+		store.remove_node(node.id, node.key)?;
+		self.state.count = self.state.count.saturating_sub(1);
+		self.updated = true;
+		for (layer, links) in layers.iter().enumerate() {
+			// Remove the links to the deleted node
+			for &n in links {
+				self.unlink(tx, store, n, node_id, layer, 0).await?;
+			}
+			// Reconnect the former neighbours with each other
+			for &n in links {
+				self.repair(tx, store, n, links, layer).await?;
+			}
+		}
+		if self.state.entry == Some(node_id) {
+			// The new entry point is the neighbour on the highest layer
+			self.state.entry = None;
+			self.state.level = 0;
+			if let Some(links) = layers.iter().rev().find(|l| !l.is_empty()) {
+				self.set_entry(tx, store, links[0]).await?;
+			}
+		}
+		Ok(())
+	}
+
+	/// Checks if the graph still has nodes, but no entry point
+	fn is_orphaned(&self) -> bool {
+		self.state.entry.is_none() && self.state.count > 0
+	}
+
+	async fn set_entry(
+		&mut self,
+		tx: &mut Transaction,
+		store: &mut HnswNodeStore,
+		node_id: NodeId,
+	) -> Result<(), Error> {
+		let node = store.get_node(tx, node_id).await?;
+		let level = node.node.layers.len().saturating_sub(1);
+		store.set_node(node, false)?;
+		self.state.entry = Some(node_id);
+		self.state.level = level as u16;
+		self.updated = true;
+		Ok(())
+	}
+
+	/// Returns the documents of the k nearest neighbours of a vector, closest first
+	pub(in crate::idx) async fn knn(
+		&self,
+		tx: &mut Transaction,
+		store: &mut HnswNodeStore,
+		vector: &Vector,
+		k: usize,
+	) -> Result<Vec<DocId>, Error> {
+		let Some(entry) = self.state.entry else {
+			return Ok(vec![]);
+		};
+		let mut eps = vec![Scored(self.distance_to(tx, store, vector, entry).await?, entry)];
+		for layer in (1..=self.state.level as usize).rev() {
+			eps = self.search_layer(tx, store, vector, eps, 1, layer).await?;
+		}
+		let candidates = self.search_layer(tx, store, vector, eps, self.efc.max(k), 0).await?;
+		// A document with several vectors is only returned once
+		let mut docs = Vec::with_capacity(k);
+		let mut seen = HashSet::with_capacity(k);
+		for Scored(_, node_id) in candidates {
+			if docs.len() == k {
+				break;
+			}
+			let node = store.get_node(tx, node_id).await?;
+			let doc_id = node.node.doc_id;
+			store.set_node(node, false)?;
+			if seen.insert(doc_id) {
+				docs.push(doc_id);
+			}
+		}
+		Ok(docs)
+	}
+
+	/// Returns the `ef` closest nodes to a vector on a layer, closest first
+	async fn search_layer(
+		&self,
+		tx: &mut Transaction,
+		store: &mut HnswNodeStore,
+		vector: &Vector,
+		eps: Vec<Scored>,
+		ef: usize,
+		layer: usize,
+	) -> Result<Vec<Scored>, Error> {
+		let mut visited: HashSet<NodeId> = eps.iter().map(|s| s.1).collect();
+		let mut candidates: BinaryHeap<Reverse<Scored>> =
+			eps.iter().copied().map(Reverse).collect();
+		let mut results: BinaryHeap<Scored> = eps.into_iter().collect();
+		while let Some(Reverse(c)) = candidates.pop() {
+			// Every remaining candidate is further than the furthest result
+			if results.len() >= ef && results.peek().map_or(false, |f| c.0 > f.0) {
+				break;
+			}
+			for n in self.links(tx, store, c.1, layer).await? {
+				if !visited.insert(n) {
+					continue;
+				}
+				let d = self.distance_to(tx, store, vector, n).await?;
+				if results.len() < ef || results.peek().map_or(true, |f| d < f.0) {
+					candidates.push(Reverse(Scored(d, n)));
+					results.push(Scored(d, n));
+					if results.len() > ef {
+						results.pop();
+					}
+				}
+			}
+		}
+		Ok(results.into_sorted_vec())
+	}
+
+	/// Adds a link from a node to another node.
+	/// If the node has too many links, its furthest neighbour is disconnected,
+	/// unless it would be left without any link.
+	async fn link(
+		&self,
+		tx: &mut Transaction,
+		store: &mut HnswNodeStore,
+		node_id: NodeId,
+		target: NodeId,
+		layer: usize,
+	) -> Result<(), Error> {
+		let mut node = store.get_node(tx, node_id).await?;
+		let Some(links) = node.node.layers.get_mut(layer) else {
+			return Err(Error::CorruptedIndex);
+		};
+		links.push(target);
+		if links.len() <= self.max_links(layer) {
+			store.set_node(node, true)?;
+			return Ok(());
+		}
+		let links = links.clone();
+		let mut scored = Vec::with_capacity(links.len());
+		for n in links {
+			scored.push(Scored(self.distance_to(tx, store, &node.node.vector, n).await?, n));
+		}
+		scored.sort();
+		for Scored(_, n) in scored.into_iter().rev() {
+			if self.unlink(tx, store, n, node_id, layer, 1).await? {
+				node.node.layers[layer].retain(|l| *l != n);
+				break;
+			}
+		}
+		store.set_node(node, true)
+	}
+
+	/// Removes the link from a node to another node, as long as the node keeps more than `min` links.
+	/// Returns `true` if the link has been removed.
+	async fn unlink(
+		&self,
+		tx: &mut Transaction,
+		store: &mut HnswNodeStore,
+		node_id: NodeId,
+		target: NodeId,
+		layer: usize,
+		min: usize,
+	) -> Result<bool, Error> {
+		let mut node = store.get_node(tx, node_id).await?;
+		let removed = match node.node.layers.get_mut(layer) {
+			Some(links) if links.len() > min => {
+				let len = links.len();
+				links.retain(|l| *l != target);
+				links.len() != len
+			}
+			_ => false,
+		};
+		store.set_node(node, removed)?;
+		Ok(removed)
+	}
+
+	/// Links a node which lost a neighbour to the closest of the candidates with a free slot
+	async fn repair(
+		&self,
+		tx: &mut Transaction,
+		store: &mut HnswNodeStore,
+		node_id: NodeId,
+		candidates: &[NodeId],
+		layer: usize,
+	) -> Result<(), Error> {
+		let max = self.max_links(layer);
+		let mut node = store.get_node(tx, node_id).await?;
+		let mut best: Option<Scored> = None;
+		if let Some(links) = node.node.layers.get(layer).filter(|l| l.len() < max) {
+			for &c in candidates {
+				if c == node_id || links.contains(&c) {
+					continue;
+				}
+				let other = store.get_node(tx, c).await?;
+				let d = match other.node.layers.get(layer) {
+					Some(l) if l.len() < max => {
+						Some(self.distance.compute(&node.node.vector, &other.node.vector))
+					}
+					_ => None,
+				};
+				store.set_node(other, false)?;
+				if let Some(d) = d.transpose()? {
+					if best.map_or(true, |b| d < b.0) {
+						best = Some(Scored(d, c));
+					}
+				}
+			}
+		}
+		let Some(Scored(_, c)) = best else {
+			return store.set_node(node, false);
+		};
+		node.node.layers[layer].push(c);
+		store.set_node(node, true)?;
+		let mut other = store.get_node(tx, c).await?;
+		other.node.layers[layer].push(node_id);
+		store.set_node(other, true)
+	}
+
+	async fn links(
+		&self,
+		tx: &mut Transaction,
+		store: &mut HnswNodeStore,
+		node_id: NodeId,
+		layer: usize,
+	) -> Result<Vec<NodeId>, Error> {
+		let node = store.get_node(tx, node_id).await?;
+		let links = node.node.layers.get(layer).cloned().unwrap_or_default();
+		store.set_node(node, false)?;
+		Ok(links)
+	}
+
+	async fn distance_to(
+		&self,
+		tx: &mut Transaction,
+		store: &mut HnswNodeStore,
+		vector: &Vector,
+		node_id: NodeId,
+	) -> Result<f64, Error> {
+		let node = store.get_node(tx, node_id).await?;
+		let d = self.distance.compute(vector, &node.node.vector);
+		store.set_node(node, false)?;
+		d
+	}
+
+	fn new_node_id(&mut self) -> NodeId {
+		let new_node_id = self.state.next_node_id;
+		self.state.next_node_id += 1;
+		new_node_id
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::idx::trees::hnsw::{HState, Hnsw, HnswNodeStore};
+	use crate::idx::trees::store::{TreeNodeProvider, TreeNodeStore, TreeStoreType};
+	use crate::kvs::{Datastore, Transaction};
+	use crate::sql::index::Distance;
+	use test_log::test;
+
+	fn vector(i: usize) -> Vec<f64> {
+		vec![(i % 7) as f64, (i % 13) as f64, i as f64]
+	}
+
+	/// Computes the exact k nearest neighbours by brute force
+	fn brute_force(docs: &[u64], q: &Vec<f64>, k: usize) -> Vec<u64> {
+		let mut res: Vec<(f64, u64)> = docs
+			.iter()
+			.map(|&d| (Distance::Euclidean.compute(q, &vector(d as usize)).unwrap(), d))
+			.collect();
+		res.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+		res.into_iter().take(k).map(|(_, d)| d).collect()
+	}
+
+	async fn check_knn(
+		tx: &mut Transaction,
+		s: &mut HnswNodeStore,
+		h: &Hnsw,
+		docs: &[u64],
+		q: &Vec<f64>,
+	) {
+		let res = h.knn(tx, s, q, 5).await.unwrap();
+		let expected = brute_force(docs, q, 5);
+		// Ties can be returned in any order, so only the distances are compared
+		let dist = |d: &u64| Distance::Euclidean.compute(q, &vector(*d as usize)).unwrap();
+		assert_eq!(
+			res.iter().map(dist).collect::<Vec<_>>(),
+			expected.iter().map(dist).collect::<Vec<_>>()
+		);
+	}
+
+	#[test(tokio::test)]
+	async fn test_hnsw_insertions_and_deletions() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let mut h = Hnsw::new(HState::default(), Distance::Euclidean, 4, 150);
+		let s = TreeNodeStore::new(TreeNodeProvider::Debug, TreeStoreType::Write, 20);
+		let mut s = s.lock().await;
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		let mut nodes = Vec::new();
+		for i in 0..100 {
+			nodes.push(h.insert(&mut tx, &mut s, vector(i), i as u64).await.unwrap());
+		}
+		assert_eq!(h.state.count, 100);
+		let docs: Vec<u64> = (0..100).collect();
+		for i in [0, 17, 50, 99, 150] {
+			check_knn(&mut tx, &mut s, &h, &docs, &vector(i)).await;
+		}
+		// Remove half of the documents
+		for i in (0..100).step_by(2) {
+			h.delete(&mut tx, &mut s, nodes[i]).await.unwrap();
+		}
+		assert_eq!(h.state.count, 50);
+		let docs: Vec<u64> = (1..100).step_by(2).collect();
+		for i in [0, 17, 50, 99, 150] {
+			check_knn(&mut tx, &mut s, &h, &docs, &vector(i)).await;
+		}
+		// Remove the remaining documents
+		for i in (1..100).step_by(2) {
+			h.delete(&mut tx, &mut s, nodes[i]).await.unwrap();
+		}
+		assert_eq!(h.state.count, 0);
+		assert_eq!(h.state.entry, None);
+		assert!(h.knn(&mut tx, &mut s, &vector(1), 5).await.unwrap().is_empty());
+		s.finish(&mut tx).await.unwrap();
+		tx.commit().await.unwrap();
+	}
+}
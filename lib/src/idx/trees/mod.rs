@@ -1,3 +1,7 @@
 pub mod bkeys;
 pub mod btree;
+pub(crate) mod distance;
+pub mod hnsw;
+pub mod mtree;
+pub mod rtree;
 pub mod store;
@@ -0,0 +1,624 @@
+use crate::err::Error;
+use crate::idx::ft::docids::{DocId, DocIds};
+use crate::idx::trees::store::{
+	NodeId, StoredNode, TreeNode, TreeNodeProvider, TreeNodeStore, TreeStoreType,
+};
+use crate::idx::{IndexKeyBase, VersionedSerdeState};
+use crate::kvs::{Key, Transaction, Val};
+use crate::sql::index::{Distance, MTreeParams};
+use crate::sql::{Thing, Value};
+use revision::revisioned;
+use roaring::RoaringTreemap;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+pub(crate) type Vector = Vec<f64>;
+
+type MStoredNode = StoredNode<MTreeNode>;
+pub(in crate::idx) type MTreeNodeStore = TreeNodeStore<MTreeNode>;
+
+pub(crate) struct MTreeIndex {
+	state_key: Key,
+	dim: usize,
+	doc_ids: DocIds,
+	mtree: MTree,
+	store: Arc<Mutex<MTreeNodeStore>>,
+}
+
+impl MTreeIndex {
+	pub(crate) async fn new(
+		tx: &mut Transaction,
+		ikb: IndexKeyBase,
+		p: &MTreeParams,
+		st: TreeStoreType,
+	) -> Result<Self, Error> {
+		let doc_ids = DocIds::new(tx, ikb.clone(), p.doc_ids_order, st).await?;
+		let state_key = ikb.new_vm_key(None);
+		let state: MState = if let Some(val) = tx.get(state_key.clone()).await? {
+			MState::try_from_val(val)?
+		} else {
+			MState::new(p.capacity)
+		};
+		let store = TreeNodeStore::new(TreeNodeProvider::Vector(ikb), st, tx.index_cache_size());
+		let mtree = MTree::new(state, p.distance.clone());
+		Ok(Self {
+			state_key,
+			dim: p.dimension as usize,
+			doc_ids,
+			mtree,
+			store,
+		})
+	}
+
+	/// Indexes the vectors of a document.
+	/// Returns `true` if the document was not indexed yet.
+	pub(crate) async fn index_document(
+		&mut self,
+		tx: &mut Transaction,
+		rid: &Thing,
+		content: &[Value],
+	) -> Result<bool, Error> {
+		// Resolve the doc_id
+		let doc_key: Key = rid.into();
+		let resolved = self.doc_ids.resolve_doc_id(tx, doc_key).await?;
+		let doc_id = *resolved.doc_id();
+		// Index the values
+		let mut store = self.store.lock().await;
+		for v in content {
+			// Records without a vector are not indexed
+			if v.is_none_or_null() {
+				continue;
+			}
+			// Extract the vector
+			let vector = check_vector(self.dim, v)?;
+			self.mtree.insert(tx, &mut store, vector, doc_id).await?;
+		}
+		Ok(!resolved.was_existing())
+	}
+
+	/// Removes the vectors of a document.
+	/// Returns `true` if the document was indexed.
+	pub(crate) async fn remove_document(
+		&mut self,
+		tx: &mut Transaction,
+		rid: &Thing,
+		content: &[Value],
+	) -> Result<bool, Error> {
+		let doc_key: Key = rid.into();
+		if let Some(doc_id) = self.doc_ids.remove_doc(tx, doc_key).await? {
+			// Remove the values
+			let mut store = self.store.lock().await;
+			for v in content {
+				// Records without a vector were not indexed
+				if v.is_none_or_null() {
+					continue;
+				}
+				// Extract the vector
+				let vector = check_vector(self.dim, v)?;
+				self.mtree.delete(tx, &mut store, vector, doc_id).await?;
+			}
+			return Ok(true);
+		}
+		Ok(false)
+	}
+
+	/// The number of levels of the tree
+	pub(crate) async fn depth(&self, tx: &mut Transaction) -> Result<u32, Error> {
+		let mut store = self.store.lock().await;
+		self.mtree.depth(tx, &mut store).await
+	}
+
+	pub(crate) async fn finish(mut self, tx: &mut Transaction) -> Result<(), Error> {
+		self.doc_ids.finish(tx).await?;
+		let updated = self.store.lock().await.finish(tx).await?;
+		if self.mtree.updated || updated {
+			tx.set(self.state_key, self.mtree.state.try_to_val()?).await?;
+		}
+		Ok(())
+	}
+}
+
+/// Extracts a vector of the expected dimension from a value
+pub(in crate::idx) fn check_vector(dim: usize, v: &Value) -> Result<Vector, Error> {
+	let vector = match v {
+		Value::Array(a) => a
+			.iter()
+			.map(|v| match v {
+				Value::Number(n) => Ok(n.to_float()),
+				v => Err(Error::InvalidVectorType {
+					current: v.to_string(),
+					expected: "Number".to_string(),
+				}),
+			})
+			.collect::<Result<Vector, Error>>()?,
+		v => {
+			return Err(Error::InvalidVectorValue {
+				current: v.to_string(),
+			})
+		}
+	};
+	if vector.len() != dim {
+		return Err(Error::InvalidVectorDimension {
+			current: vector.len(),
+			expected: dim,
+		});
+	}
+	Ok(vector)
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[revisioned(revision = 1)]
+pub(in crate::idx) struct MState {
+	capacity: u16,
+	root: Option<NodeId>,
+	next_node_id: NodeId,
+}
+
+impl VersionedSerdeState for MState {}
+
+impl MState {
+	pub(in crate::idx) fn new(capacity: u16) -> Self {
+		Self {
+			// A node must be able to hold at least the two promoted objects of a split
+			capacity: capacity.max(2),
+			root: None,
+			next_node_id: 0,
+		}
+	}
+}
+
+/// A routing object stored in an internal node.
+/// It references a child node, and covers every object in
+/// the subtree within the given radius around its center.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(in crate::idx) struct RoutingEntry {
+	center: Vector,
+	node: NodeId,
+	radius: f64,
+}
+
+/// A leaf object, which is a vector and the documents it is indexed by.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(in crate::idx) struct LeafEntry {
+	vector: Vector,
+	docs: RoaringTreemap,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(in crate::idx) enum MTreeNode {
+	Internal(Vec<RoutingEntry>),
+	Leaf(Vec<LeafEntry>),
+}
+
+impl TreeNode for MTreeNode {
+	fn try_from_val(val: Val) -> Result<Self, Error> {
+		Ok(bincode::deserialize(&val)?)
+	}
+
+	fn try_into_val(&mut self) -> Result<Val, Error> {
+		Ok(bincode::serialize(self)?)
+	}
+}
+
+impl MTreeNode {
+	fn len(&self) -> usize {
+		match self {
+			MTreeNode::Internal(e) => e.len(),
+			MTreeNode::Leaf(e) => e.len(),
+		}
+	}
+}
+
+pub(in crate::idx) struct MTree {
+	state: MState,
+	distance: Distance,
+	updated: bool,
+}
+
+impl MTree {
+	pub(in crate::idx) fn new(state: MState, distance: Distance) -> Self {
+		Self {
+			state,
+			distance,
+			updated: false,
+		}
+	}
+
+	pub(in crate::idx) async fn insert(
+		&mut self,
+		tx: &mut Transaction,
+		store: &mut MTreeNodeStore,
+		v: Vector,
+		doc_id: DocId,
+	) -> Result<(), Error> {
+		let Some(root_id) = self.state.root else {
+			// The tree is empty, we create the root leaf node
+			let new_root_id = self.new_node_id();
+			let mut docs = RoaringTreemap::new();
+			docs.insert(doc_id);
+			let node = MTreeNode::Leaf(vec![LeafEntry {
+				vector: v,
+				docs,
+			}]);
+			let node = store.new_node(new_root_id, node)?;
+			store.set_node(node, true)?;
+			self.state.root = Some(new_root_id);
+			self.updated = true;
+			return Ok(());
+		};
+		// The path from the root to the leaf as (node id, index of the routing entry)
+		let mut path: Vec<(NodeId, usize)> = Vec::new();
+		let mut node_id = root_id;
+		loop {
+			let mut node = store.get_node(tx, node_id).await?;
+			match &mut node.node {
+				MTreeNode::Internal(entries) => {
+					let (idx, dist) = self.choose_subtree(entries, &v)?;
+					// Enlarge the covering radius if required
+					let updated = if dist > entries[idx].radius {
+						entries[idx].radius = dist;
+						true
+					} else {
+						false
+					};
+					let child_id = entries[idx].node;
+					path.push((node.id, idx));
+					store.set_node(node, updated)?;
+					node_id = child_id;
+				}
+				MTreeNode::Leaf(entries) => {
+					// Is the vector already indexed?
+					if let Some(e) = entries.iter_mut().find(|e| e.vector == v) {
+						e.docs.insert(doc_id);
+						store.set_node(node, true)?;
+						self.updated = true;
+						return Ok(());
+					}
+					let mut docs = RoaringTreemap::new();
+					docs.insert(doc_id);
+					entries.push(LeafEntry {
+						vector: v,
+						docs,
+					});
+					self.updated = true;
+					if node.node.len() <= self.state.capacity as usize {
+						store.set_node(node, true)?;
+						return Ok(());
+					}
+					return self.split(tx, store, node, path).await;
+				}
+			}
+		}
+	}
+
+	/// The tree is balanced, so the depth is the length of any path from the root to a leaf
+	async fn depth(&self, tx: &mut Transaction, store: &mut MTreeNodeStore) -> Result<u32, Error> {
+		let mut depth = 0;
+		let mut next = self.state.root;
+		while let Some(node_id) = next {
+			depth += 1;
+			let node = store.get_node(tx, node_id).await?;
+			next = match &node.node {
+				MTreeNode::Internal(entries) => entries.first().map(|e| e.node),
+				MTreeNode::Leaf(_) => None,
+			};
+			store.set_node(node, false)?;
+		}
+		Ok(depth)
+	}
+
+	/// Choose the closest routing entry covering the vector, or else
+	/// the routing entry requiring the smallest enlargement of its radius
+	fn choose_subtree(&self, entries: &[RoutingEntry], v: &Vector) -> Result<(usize, f64), Error> {
+		let mut best: Option<(usize, f64, bool, f64)> = None;
+		for (i, e) in entries.iter().enumerate() {
+			let d = self.distance.compute(&e.center, v)?;
+			let (covered, score) = if d <= e.radius {
+				(true, d)
+			} else {
+				(false, d - e.radius)
+			};
+			let better = match best {
+				None => true,
+				Some((_, _, c, s)) => (covered && !c) || (covered == c && score < s),
+			};
+			if better {
+				best = Some((i, d, covered, score));
+			}
+		}
+		best.map(|(i, d, _, _)| (i, d)).ok_or(Error::CorruptedIndex)
+	}
+
+	async fn split(
+		&mut self,
+		tx: &mut Transaction,
+		store: &mut MTreeNodeStore,
+		mut node: MStoredNode,
+		mut path: Vec<(NodeId, usize)>,
+	) -> Result<(), Error> {
+		loop {
+			// Split the overflowing node into two nodes
+			let (left, left_center, left_radius, right, right_center, right_radius) =
+				match node.node {
+					MTreeNode::Internal(entries) => {
+						let centers: Vec<&Vector> = entries.iter().map(|e| &e.center).collect();
+						let (l, r) = self.promote(&centers)?;
+						let (lc, rc) = (entries[l].center.clone(), entries[r].center.clone());
+						let (mut le, mut re) = (Vec::new(), Vec::new());
+						let (mut lr, mut rr) = (0.0f64, 0.0f64);
+						for e in entries {
+							let dl = self.distance.compute(&lc, &e.center)?;
+							let dr = self.distance.compute(&rc, &e.center)?;
+							if dl <= dr {
+								lr = lr.max(dl + e.radius);
+								le.push(e);
+							} else {
+								rr = rr.max(dr + e.radius);
+								re.push(e);
+							}
+						}
+						(MTreeNode::Internal(le), lc, lr, MTreeNode::Internal(re), rc, rr)
+					}
+					MTreeNode::Leaf(entries) => {
+						let vectors: Vec<&Vector> = entries.iter().map(|e| &e.vector).collect();
+						let (l, r) = self.promote(&vectors)?;
+						let (lc, rc) = (entries[l].vector.clone(), entries[r].vector.clone());
+						let (mut le, mut re) = (Vec::new(), Vec::new());
+						let (mut lr, mut rr) = (0.0f64, 0.0f64);
+						for e in entries {
+							let dl = self.distance.compute(&lc, &e.vector)?;
+							let dr = self.distance.compute(&rc, &e.vector)?;
+							if dl <= dr {
+								lr = lr.max(dl);
+								le.push(e);
+							} else {
+								rr = rr.max(dr);
+								re.push(e);
+							}
+						}
+						(MTreeNode::Leaf(le), lc, lr, MTreeNode::Leaf(re), rc, rr)
+					}
+				};
+			// The left node keeps the id of the split node
+			let left_id = node.id;
+			let left = store.new_node(left_id, left)?;
+			store.set_node(left, true)?;
+			// The right node is a new node
+			let right_id = self.new_node_id();
+			let right = store.new_node(right_id, right)?;
+			store.set_node(right, true)?;
+			// The routing entries referencing the two nodes
+			let left_entry = RoutingEntry {
+				center: left_center,
+				node: left_id,
+				radius: left_radius,
+			};
+			let right_entry = RoutingEntry {
+				center: right_center,
+				node: right_id,
+				radius: right_radius,
+			};
+			match path.pop() {
+				None => {
+					// We split the root, so we create a new root
+					let new_root_id = self.new_node_id();
+					let new_root = MTreeNode::Internal(vec![left_entry, right_entry]);
+					let new_root = store.new_node(new_root_id, new_root)?;
+					store.set_node(new_root, true)?;
+					self.state.root = Some(new_root_id);
+					return Ok(());
+				}
+				Some((parent_id, idx)) => {
+					let mut parent = store.get_node(tx, parent_id).await?;
+					if let MTreeNode::Internal(entries) = &mut parent.node {
+						entries[idx] = left_entry;
+						entries.push(right_entry);
+					} else {
+						return Err(Error::CorruptedIndex);
+					}
+					if parent.node.len() <= self.state.capacity as usize {
+						store.set_node(parent, true)?;
+						return Ok(());
+					}
+					node = parent;
+				}
+			}
+		}
+	}
+
+	/// Promote the two objects which are the furthest apart
+	fn promote(&self, objects: &[&Vector]) -> Result<(usize, usize), Error> {
+		let mut res = (0, 1);
+		let mut max = f64::MIN;
+		for i in 0..objects.len() {
+			for j in (i + 1)..objects.len() {
+				let d = self.distance.compute(objects[i], objects[j])?;
+				if d > max {
+					max = d;
+					res = (i, j);
+				}
+			}
+		}
+		Ok(res)
+	}
+
+	pub(in crate::idx) async fn delete(
+		&mut self,
+		tx: &mut Transaction,
+		store: &mut MTreeNodeStore,
+		v: Vector,
+		doc_id: DocId,
+	) -> Result<bool, Error> {
+		let Some(root_id) = self.state.root else {
+			return Ok(false);
+		};
+		// Depth first search for the leaf containing the vector,
+		// keeping track of the path from the root to the node
+		let mut stack: VecDeque<(NodeId, Vec<NodeId>)> = VecDeque::from([(root_id, vec![])]);
+		while let Some((node_id, path)) = stack.pop_back() {
+			let mut node = store.get_node(tx, node_id).await?;
+			match &mut node.node {
+				MTreeNode::Internal(entries) => {
+					for e in entries.iter() {
+						if self.distance.compute(&e.center, &v)? <= e.radius {
+							let mut path = path.clone();
+							path.push(node_id);
+							stack.push_back((e.node, path));
+						}
+					}
+					store.set_node(node, false)?;
+				}
+				MTreeNode::Leaf(entries) => {
+					let Some(idx) = entries.iter().position(|e| e.vector == v) else {
+						store.set_node(node, false)?;
+						continue;
+					};
+					if !entries[idx].docs.remove(doc_id) {
+						store.set_node(node, false)?;
+						continue;
+					}
+					if entries[idx].docs.is_empty() {
+						entries.remove(idx);
+					}
+					self.updated = true;
+					if node.node.len() > 0 {
+						store.set_node(node, true)?;
+					} else {
+						// The leaf is empty, it is removed from its parent
+						store.remove_node(node.id, node.key)?;
+						self.remove_empty(tx, store, node_id, path).await?;
+					}
+					return Ok(true);
+				}
+			}
+		}
+		Ok(false)
+	}
+
+	/// Removes the routing entries referencing an empty node,
+	/// removing any internal node which becomes empty in turn.
+	async fn remove_empty(
+		&mut self,
+		tx: &mut Transaction,
+		store: &mut MTreeNodeStore,
+		mut node_id: NodeId,
+		mut path: Vec<NodeId>,
+	) -> Result<(), Error> {
+		while let Some(parent_id) = path.pop() {
+			let mut parent = store.get_node(tx, parent_id).await?;
+			if let MTreeNode::Internal(entries) = &mut parent.node {
+				entries.retain(|e| e.node != node_id);
+			}
+			if parent.node.len() > 0 {
+				store.set_node(parent, true)?;
+				return Ok(());
+			}
+			store.remove_node(parent.id, parent.key)?;
+			node_id = parent_id;
+		}
+		// Every node has been removed
+		self.state.root = None;
+		Ok(())
+	}
+
+	fn new_node_id(&mut self) -> NodeId {
+		let new_node_id = self.state.next_node_id;
+		self.state.next_node_id += 1;
+		new_node_id
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::idx::trees::mtree::{MState, MTree, MTreeNode, MTreeNodeStore};
+	use crate::idx::trees::store::{NodeId, TreeNodeProvider, TreeNodeStore, TreeStoreType};
+	use crate::kvs::{Datastore, Transaction};
+	use crate::sql::index::Distance;
+	use std::collections::VecDeque;
+	use test_log::test;
+
+	/// Checks every object is covered by the radius of all its ancestors, and returns the count of objects
+	async fn check_tree(tx: &mut Transaction, store: &mut MTreeNodeStore, t: &MTree) -> usize {
+		let mut count = 0;
+		let mut queue: VecDeque<(NodeId, Vec<(Vec<f64>, f64)>)> = VecDeque::new();
+		if let Some(root_id) = t.state.root {
+			queue.push_back((root_id, vec![]));
+		}
+		while let Some((node_id, ancestors)) = queue.pop_front() {
+			let node = store.get_node(tx, node_id).await.unwrap();
+			match &node.node {
+				MTreeNode::Internal(entries) => {
+					assert!(!entries.is_empty());
+					for e in entries {
+						let mut ancestors = ancestors.clone();
+						ancestors.push((e.center.clone(), e.radius));
+						queue.push_back((e.node, ancestors));
+					}
+				}
+				MTreeNode::Leaf(entries) => {
+					assert!(!entries.is_empty());
+					for e in entries {
+						for (center, radius) in &ancestors {
+							let d = t.distance.compute(center, &e.vector).unwrap();
+							assert!(d <= *radius + f64::EPSILON, "{d} > {radius}");
+						}
+						count += e.docs.len() as usize;
+					}
+				}
+			}
+			store.set_node(node, false).unwrap();
+		}
+		count
+	}
+
+	fn vector(i: usize) -> Vec<f64> {
+		vec![(i % 7) as f64, (i % 13) as f64, i as f64]
+	}
+
+	#[test(tokio::test)]
+	async fn test_mtree_insertions_and_deletions() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let mut t = MTree::new(MState::new(4), Distance::Euclidean);
+		let s = TreeNodeStore::new(TreeNodeProvider::Debug, TreeStoreType::Write, 20);
+		let mut s = s.lock().await;
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		for i in 0..100 {
+			t.insert(&mut tx, &mut s, vector(i), i as u64).await.unwrap();
+		}
+		assert_eq!(check_tree(&mut tx, &mut s, &t).await, 100);
+		// Remove half of the documents
+		for i in (0..100).step_by(2) {
+			assert!(t.delete(&mut tx, &mut s, vector(i), i as u64).await.unwrap());
+		}
+		assert_eq!(check_tree(&mut tx, &mut s, &t).await, 50);
+		// Documents can't be removed twice
+		assert!(!t.delete(&mut tx, &mut s, vector(42), 42).await.unwrap());
+		// Remove the remaining documents
+		for i in (1..100).step_by(2) {
+			assert!(t.delete(&mut tx, &mut s, vector(i), i as u64).await.unwrap());
+		}
+		assert_eq!(t.state.root, None);
+		s.finish(&mut tx).await.unwrap();
+		tx.commit().await.unwrap();
+	}
+
+	#[test(tokio::test)]
+	async fn test_mtree_shared_vectors() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let mut t = MTree::new(MState::new(3), Distance::Manhattan);
+		let s = TreeNodeStore::new(TreeNodeProvider::Debug, TreeStoreType::Write, 20);
+		let mut s = s.lock().await;
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		for i in 0..10 {
+			t.insert(&mut tx, &mut s, vec![1.0, 2.0], i).await.unwrap();
+		}
+		assert_eq!(check_tree(&mut tx, &mut s, &t).await, 10);
+		assert!(!t.delete(&mut tx, &mut s, vec![2.0, 2.0], 1).await.unwrap());
+		assert!(t.delete(&mut tx, &mut s, vec![1.0, 2.0], 1).await.unwrap());
+		assert_eq!(check_tree(&mut tx, &mut s, &t).await, 9);
+		s.finish(&mut tx).await.unwrap();
+		tx.commit().await.unwrap();
+	}
+}
@@ -0,0 +1,542 @@
+use crate::err::Error;
+use crate::idx::ft::docids::{DocId, DocIds};
+use crate::idx::trees::store::{NodeId, TreeNode, TreeNodeProvider, TreeNodeStore, TreeStoreType};
+use crate::idx::{IndexKeyBase, VersionedSerdeState};
+use crate::kvs::{Key, Transaction, Val};
+use crate::sql::index::RTreeParams;
+use crate::sql::{Geometry, Thing, Value};
+use geo::algorithm::bounding_rect::BoundingRect;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// The mean radius of the earth, as used by the haversine distance
+const EARTH_RADIUS: f64 = 6_371_008.8;
+
+pub(in crate::idx) type RTreeNodeStore = TreeNodeStore<RTreeNode>;
+
+pub(crate) struct RTreeIndex {
+	state_key: Key,
+	ikb: IndexKeyBase,
+	doc_ids: DocIds,
+	rtree: RTree,
+	store: Arc<Mutex<RTreeNodeStore>>,
+}
+
+impl RTreeIndex {
+	pub(crate) async fn new(
+		tx: &mut Transaction,
+		ikb: IndexKeyBase,
+		p: &RTreeParams,
+		st: TreeStoreType,
+	) -> Result<Self, Error> {
+		let doc_ids = DocIds::new(tx, ikb.clone(), p.doc_ids_order, st).await?;
+		let state_key = ikb.new_rn_key(None);
+		let state: RState = if let Some(val) = tx.get(state_key.clone()).await? {
+			RState::try_from_val(val)?
+		} else {
+			RState::default()
+		};
+		let store =
+			TreeNodeStore::new(TreeNodeProvider::Spatial(ikb.clone()), st, tx.index_cache_size());
+		let rtree = RTree::new(state, p.capacity);
+		Ok(Self {
+			state_key,
+			ikb,
+			doc_ids,
+			rtree,
+			store,
+		})
+	}
+
+	/// Indexes the bounding boxes of the geometries of a document.
+	/// Values which are not geometries are ignored.
+	/// Returns `true` if the document was not indexed yet.
+	pub(crate) async fn index_document(
+		&mut self,
+		tx: &mut Transaction,
+		rid: &Thing,
+		content: &[Value],
+	) -> Result<bool, Error> {
+		// Resolve the doc_id
+		let doc_key: Key = rid.into();
+		let resolved = self.doc_ids.resolve_doc_id(tx, doc_key).await?;
+		let doc_id = *resolved.doc_id();
+		// Collect the bounding boxes already indexed for the document
+		let rects_key = self.ikb.new_rd_key(doc_id);
+		let mut rects: Vec<Rect> = match tx.get(rects_key.clone()).await? {
+			Some(val) => bincode::deserialize(&val)?,
+			None => Vec::with_capacity(content.len()),
+		};
+		// Insert the bounding box of each geometry
+		let mut store = self.store.lock().await;
+		for v in content {
+			if let Value::Geometry(g) = v {
+				if let Some(rect) = Rect::of(g) {
+					self.rtree.insert(tx, &mut store, rect, doc_id).await?;
+					rects.push(rect);
+				}
+			}
+		}
+		tx.set(rects_key, bincode::serialize(&rects)?).await?;
+		Ok(!resolved.was_existing())
+	}
+
+	/// Removes the bounding boxes of a document.
+	/// Returns `true` if the document was indexed.
+	pub(crate) async fn remove_document(
+		&mut self,
+		tx: &mut Transaction,
+		rid: &Thing,
+	) -> Result<bool, Error> {
+		let doc_key: Key = rid.into();
+		let Some(doc_id) = self.doc_ids.remove_doc(tx, doc_key).await? else {
+			return Ok(false);
+		};
+		let rects_key = self.ikb.new_rd_key(doc_id);
+		if let Some(val) = tx.get(rects_key.clone()).await? {
+			let rects: Vec<Rect> = bincode::deserialize(&val)?;
+			let mut store = self.store.lock().await;
+			for rect in rects {
+				self.rtree.delete(tx, &mut store, rect, doc_id).await?;
+			}
+			tx.del(rects_key).await?;
+		}
+		Ok(true)
+	}
+
+	/// Returns the records with a geometry whose bounding box intersects the given area
+	pub(crate) async fn search(
+		&self,
+		tx: &mut Transaction,
+		area: Rect,
+	) -> Result<Vec<Thing>, Error> {
+		let mut store = self.store.lock().await;
+		let docs = self.rtree.search(tx, &mut store, area).await?;
+		let mut res = Vec::with_capacity(docs.len());
+		for doc_id in docs {
+			if let Some(doc_key) = self.doc_ids.get_doc_key(tx, doc_id).await? {
+				res.push(doc_key.into());
+			}
+		}
+		Ok(res)
+	}
+
+	/// The number of levels of the tree
+	pub(crate) fn depth(&self) -> u32 {
+		self.rtree.state.depth
+	}
+
+	pub(crate) async fn finish(mut self, tx: &mut Transaction) -> Result<(), Error> {
+		self.doc_ids.finish(tx).await?;
+		let updated = self.store.lock().await.finish(tx).await?;
+		if self.rtree.updated || updated {
+			tx.set(self.state_key, self.rtree.state.try_to_val()?).await?;
+		}
+		Ok(())
+	}
+}
+
+/// An axis-aligned bounding box, in longitude and latitude
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Rect {
+	min: [f64; 2],
+	max: [f64; 2],
+}
+
+impl Rect {
+	fn new(min: [f64; 2], max: [f64; 2]) -> Self {
+		Self {
+			min,
+			max,
+		}
+	}
+
+	/// The bounding box of a geometry, if the geometry is not empty
+	pub(crate) fn of(g: &Geometry) -> Option<Self> {
+		let r = geo::Geometry::from(g.clone()).bounding_rect()?;
+		Some(Self::new([r.min().x, r.min().y], [r.max().x, r.max().y]))
+	}
+
+	/// The bounding box of every point within a haversine distance (in metres) of a point
+	pub(crate) fn around(point: &geo::Point<f64>, distance: f64) -> Self {
+		let (lng, lat) = (point.x(), point.y());
+		let r = distance.max(0.0) / EARTH_RADIUS;
+		let dlat = r.to_degrees();
+		let (min_lat, max_lat) = (lat - dlat, lat + dlat);
+		// Near the poles, or when the distance wraps around the antimeridian,
+		// every longitude is within the distance.
+		if min_lat <= -90.0 || max_lat >= 90.0 || r.sin() >= lat.to_radians().cos() {
+			return Self::new([-180.0, min_lat.max(-90.0)], [180.0, max_lat.min(90.0)]);
+		}
+		let dlng = (r.sin() / lat.to_radians().cos()).asin().to_degrees();
+		if lng - dlng < -180.0 || lng + dlng > 180.0 {
+			return Self::new([-180.0, min_lat], [180.0, max_lat]);
+		}
+		Self::new([lng - dlng, min_lat], [lng + dlng, max_lat])
+	}
+
+	fn area(&self) -> f64 {
+		(self.max[0] - self.min[0]) * (self.max[1] - self.min[1])
+	}
+
+	fn union(&self, other: &Self) -> Self {
+		Self::new(
+			[self.min[0].min(other.min[0]), self.min[1].min(other.min[1])],
+			[self.max[0].max(other.max[0]), self.max[1].max(other.max[1])],
+		)
+	}
+
+	/// The increase of the area required to cover another bounding box
+	fn enlargement(&self, other: &Self) -> f64 {
+		self.union(other).area() - self.area()
+	}
+
+	fn intersects(&self, other: &Self) -> bool {
+		self.min[0] <= other.max[0]
+			&& other.min[0] <= self.max[0]
+			&& self.min[1] <= other.max[1]
+			&& other.min[1] <= self.max[1]
+	}
+
+	fn contains(&self, other: &Self) -> bool {
+		self.min[0] <= other.min[0]
+			&& self.min[1] <= other.min[1]
+			&& other.max[0] <= self.max[0]
+			&& other.max[1] <= self.max[1]
+	}
+
+	fn center(&self, axis: usize) -> f64 {
+		(self.min[axis] + self.max[axis]) / 2.0
+	}
+
+	/// The bounding box of a set of entries
+	fn bounds(entries: &[(Rect, u64)]) -> Option<Self> {
+		entries.iter().map(|(r, _)| *r).reduce(|a, b| a.union(&b))
+	}
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[revisioned(revision = 1)]
+pub(in crate::idx) struct RState {
+	root: Option<NodeId>,
+	/// The number of levels of the tree
+	depth: u32,
+	next_node_id: NodeId,
+}
+
+impl VersionedSerdeState for RState {}
+
+/// A node of the tree.
+/// The entries of a leaf refer to documents, the entries of an inner node refer to its children.
+#[derive(Debug, Serialize, Deserialize)]
+pub(in crate::idx) struct RTreeNode {
+	leaf: bool,
+	entries: Vec<(Rect, u64)>,
+}
+
+impl TreeNode for RTreeNode {
+	fn try_from_val(val: Val) -> Result<Self, Error> {
+		Ok(bincode::deserialize(&val)?)
+	}
+
+	fn try_into_val(&mut self) -> Result<Val, Error> {
+		Ok(bincode::serialize(self)?)
+	}
+}
+
+/// An R-tree of bounding boxes, where nodes are split along their widest axis
+pub(in crate::idx) struct RTree {
+	state: RState,
+	/// The maximum number of entries of a node
+	capacity: usize,
+	updated: bool,
+}
+
+impl RTree {
+	pub(in crate::idx) fn new(state: RState, capacity: u16) -> Self {
+		Self {
+			state,
+			capacity: capacity.max(4) as usize,
+			updated: false,
+		}
+	}
+
+	fn new_node_id(&mut self) -> NodeId {
+		let id = self.state.next_node_id;
+		self.state.next_node_id += 1;
+		id
+	}
+
+	pub(in crate::idx) async fn insert(
+		&mut self,
+		tx: &mut Transaction,
+		store: &mut RTreeNodeStore,
+		rect: Rect,
+		doc_id: DocId,
+	) -> Result<(), Error> {
+		self.updated = true;
+		let Some(root) = self.state.root else {
+			let id = self.new_node_id();
+			let node = RTreeNode {
+				leaf: true,
+				entries: vec![(rect, doc_id)],
+			};
+			let node = store.new_node(id, node)?;
+			store.set_node(node, true)?;
+			self.state.root = Some(id);
+			self.state.depth = 1;
+			return Ok(());
+		};
+		// Descend to the leaf whose bounding box requires the least enlargement
+		let mut path = Vec::new();
+		let mut node = store.get_node(tx, root).await?;
+		while !node.node.leaf {
+			let child = node
+				.node
+				.entries
+				.iter()
+				.min_by(|a, b| {
+					a.0.enlargement(&rect)
+						.total_cmp(&b.0.enlargement(&rect))
+						.then(a.0.area().total_cmp(&b.0.area()))
+				})
+				.map(|(_, id)| *id)
+				.ok_or(Error::Unreachable)?;
+			path.push(node.id);
+			store.set_node(node, false)?;
+			node = store.get_node(tx, child).await?;
+		}
+		node.node.entries.push((rect, doc_id));
+		// Update the bounding boxes up to the root, splitting the overflowing nodes
+		loop {
+			let sibling = if node.node.entries.len() > self.capacity {
+				let entries = Self::split(&mut node.node.entries);
+				let id = self.new_node_id();
+				let bounds = Rect::bounds(&entries).ok_or(Error::Unreachable)?;
+				let leaf = node.node.leaf;
+				let node = store.new_node(
+					id,
+					RTreeNode {
+						leaf,
+						entries,
+					},
+				)?;
+				store.set_node(node, true)?;
+				Some((bounds, id))
+			} else {
+				None
+			};
+			let child = (Rect::bounds(&node.node.entries).ok_or(Error::Unreachable)?, node.id);
+			store.set_node(node, true)?;
+			match path.pop() {
+				Some(parent) => {
+					node = store.get_node(tx, parent).await?;
+					if let Some(e) = node.node.entries.iter_mut().find(|(_, id)| *id == child.1) {
+						e.0 = child.0;
+					}
+					node.node.entries.extend(sibling);
+				}
+				None => {
+					// The root was split, so the tree grows by one level
+					if let Some(sibling) = sibling {
+						let id = self.new_node_id();
+						let node = RTreeNode {
+							leaf: false,
+							entries: vec![child, sibling],
+						};
+						let node = store.new_node(id, node)?;
+						store.set_node(node, true)?;
+						self.state.root = Some(id);
+						self.state.depth += 1;
+					}
+					return Ok(());
+				}
+			}
+		}
+	}
+
+	/// Sorts the entries along the widest axis of their centers,
+	/// and moves the upper half into the returned entries.
+	fn split(entries: &mut Vec<(Rect, u64)>) -> Vec<(Rect, u64)> {
+		let spread = |axis: usize| {
+			let (min, max) = entries.iter().fold((f64::MAX, f64::MIN), |(min, max), (r, _)| {
+				(min.min(r.center(axis)), max.max(r.center(axis)))
+			});
+			max - min
+		};
+		let axis = if spread(0) >= spread(1) {
+			0
+		} else {
+			1
+		};
+		entries.sort_by(|a, b| a.0.center(axis).total_cmp(&b.0.center(axis)));
+		entries.split_off(entries.len() / 2)
+	}
+
+	/// Removes the entry of a document.
+	/// Returns `true` if the entry was found.
+	pub(in crate::idx) async fn delete(
+		&mut self,
+		tx: &mut Transaction,
+		store: &mut RTreeNodeStore,
+		rect: Rect,
+		doc_id: DocId,
+	) -> Result<bool, Error> {
+		let Some(root) = self.state.root else {
+			return Ok(false);
+		};
+		// Look for the leaf holding the entry, following every child which covers it
+		let mut stack = vec![(root, Vec::new())];
+		let mut found = None;
+		while let Some((id, path)) = stack.pop() {
+			let mut node = store.get_node(tx, id).await?;
+			if node.node.leaf {
+				if let Some(pos) = node.node.entries.iter().position(|e| e == &(rect, doc_id)) {
+					node.node.entries.remove(pos);
+					found = Some((node, path));
+					break;
+				}
+			} else {
+				for (r, child) in &node.node.entries {
+					if r.contains(&rect) {
+						let mut path = path.clone();
+						path.push(id);
+						stack.push((*child, path));
+					}
+				}
+			}
+			store.set_node(node, false)?;
+		}
+		let Some((mut node, mut path)) = found else {
+			return Ok(false);
+		};
+		self.updated = true;
+		// Update the bounding boxes up to the root, removing the emptied nodes
+		loop {
+			let child = (node.id, Rect::bounds(&node.node.entries));
+			if child.1.is_none() && !path.is_empty() {
+				store.remove_node(node.id, node.key)?;
+			} else {
+				store.set_node(node, true)?;
+			}
+			let Some(parent) = path.pop() else {
+				break;
+			};
+			node = store.get_node(tx, parent).await?;
+			match child.1 {
+				Some(r) => {
+					if let Some(e) = node.node.entries.iter_mut().find(|(_, id)| *id == child.0) {
+						e.0 = r;
+					}
+				}
+				None => node.node.entries.retain(|(_, id)| *id != child.0),
+			}
+		}
+		// Shrink the tree while the root has less than two entries
+		while let Some(id) = self.state.root {
+			let node = store.get_node(tx, id).await?;
+			match (node.node.leaf, node.node.entries.len()) {
+				(_, 0) => {
+					self.state.root = None;
+					self.state.depth = 0;
+				}
+				(false, 1) => {
+					self.state.root = Some(node.node.entries[0].1);
+					self.state.depth -= 1;
+				}
+				_ => {
+					store.set_node(node, false)?;
+					break;
+				}
+			}
+			store.remove_node(node.id, node.key)?;
+		}
+		Ok(true)
+	}
+
+	/// Returns the documents whose bounding box intersects the given area
+	pub(in crate::idx) async fn search(
+		&self,
+		tx: &mut Transaction,
+		store: &mut RTreeNodeStore,
+		area: Rect,
+	) -> Result<Vec<DocId>, Error> {
+		let mut res = Vec::new();
+		let mut stack: Vec<NodeId> = self.state.root.into_iter().collect();
+		while let Some(id) = stack.pop() {
+			let node = store.get_node(tx, id).await?;
+			for (r, id) in &node.node.entries {
+				if r.intersects(&area) {
+					if node.node.leaf {
+						res.push(*id);
+					} else {
+						stack.push(*id);
+					}
+				}
+			}
+			store.set_node(node, false)?;
+		}
+		// A document with several geometries can be found more than once
+		res.sort_unstable();
+		res.dedup();
+		Ok(res)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::idx::trees::rtree::{RState, RTree, Rect};
+	use crate::idx::trees::store::{TreeNodeProvider, TreeNodeStore, TreeStoreType};
+	use crate::kvs::Datastore;
+	use test_log::test;
+
+	fn rect(i: u64) -> Rect {
+		let (x, y) = ((i % 10) as f64, (i / 10) as f64);
+		Rect::new([x, y], [x + 0.5, y + 0.5])
+	}
+
+	#[test]
+	fn test_rect_around() {
+		let p = geo::Point::new(-0.118092, 51.509865);
+		let r = Rect::around(&p, 10_000.0);
+		assert!(r.contains(&Rect::new([-0.2, 51.45], [-0.05, 51.55])));
+		assert!(!r.contains(&Rect::new([-0.5, 51.5], [-0.5, 51.5])));
+		// Every longitude is within the distance of a pole
+		let r = Rect::around(&geo::Point::new(0.0, 89.99), 10_000.0);
+		assert_eq!((r.min[0], r.max[0]), (-180.0, 180.0));
+	}
+
+	#[test(tokio::test)]
+	async fn test_rtree_insertions_and_deletions() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let mut t = RTree::new(RState::default(), 4);
+		let s = TreeNodeStore::new(TreeNodeProvider::Debug, TreeStoreType::Write, 20);
+		let mut s = s.lock().await;
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		for i in 0..100 {
+			t.insert(&mut tx, &mut s, rect(i), i).await.unwrap();
+		}
+		assert!(t.state.depth > 2);
+		let area = Rect::new([2.0, 3.0], [4.0, 4.0]);
+		let res = t.search(&mut tx, &mut s, area).await.unwrap();
+		assert_eq!(res, vec![32, 33, 34, 42, 43, 44]);
+		// Remove half of the documents
+		for i in (0..100).step_by(2) {
+			assert!(t.delete(&mut tx, &mut s, rect(i), i).await.unwrap());
+		}
+		assert!(!t.delete(&mut tx, &mut s, rect(0), 0).await.unwrap());
+		let res = t.search(&mut tx, &mut s, area).await.unwrap();
+		assert_eq!(res, vec![33, 43]);
+		// Remove the remaining documents
+		for i in (1..100).step_by(2) {
+			assert!(t.delete(&mut tx, &mut s, rect(i), i).await.unwrap());
+		}
+		assert_eq!(t.state.root, None);
+		assert_eq!(t.state.depth, 0);
+		assert!(t.search(&mut tx, &mut s, area).await.unwrap().is_empty());
+		s.finish(&mut tx).await.unwrap();
+		tx.commit().await.unwrap();
+	}
+}
@@ -238,6 +238,9 @@ pub enum TreeNodeProvider {
 	DocLengths(IndexKeyBase),
 	Postings(IndexKeyBase),
 	Terms(IndexKeyBase),
+	Vector(IndexKeyBase),
+	Graph(IndexKeyBase),
+	Spatial(IndexKeyBase),
 	Debug,
 }
 
@@ -248,6 +251,9 @@ impl TreeNodeProvider {
 			TreeNodeProvider::DocLengths(ikb) => ikb.new_bl_key(Some(node_id)),
 			TreeNodeProvider::Postings(ikb) => ikb.new_bp_key(Some(node_id)),
 			TreeNodeProvider::Terms(ikb) => ikb.new_bt_key(Some(node_id)),
+			TreeNodeProvider::Vector(ikb) => ikb.new_vm_key(Some(node_id)),
+			TreeNodeProvider::Graph(ikb) => ikb.new_hn_key(Some(node_id)),
+			TreeNodeProvider::Spatial(ikb) => ikb.new_rn_key(Some(node_id)),
 			TreeNodeProvider::Debug => node_id.to_be_bytes().to_vec(),
 		}
 	}
@@ -1,9 +1,15 @@
 pub mod all;
 pub mod az;
+pub mod cg;
 pub mod fc;
 pub mod lg;
+pub mod ma;
+pub mod mg;
+pub mod nq;
 pub mod pa;
 pub mod sc;
+pub mod sq;
+pub mod sv;
 pub mod tb;
 pub mod ti;
 pub mod tk;
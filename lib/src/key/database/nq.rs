@@ -0,0 +1,67 @@
+//! Stores a named query, which can be executed by its name
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Nq<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	_d: u8,
+	_e: u8,
+	pub nq: &'a str,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, nq: &'a str) -> Nq<'a> {
+	Nq::new(ns, db, nq)
+}
+
+pub fn prefix(ns: &str, db: &str) -> Vec<u8> {
+	let mut k = super::all::new(ns, db).encode().unwrap();
+	k.extend_from_slice(&[b'!', b'n', b'q', 0x00]);
+	k
+}
+
+pub fn suffix(ns: &str, db: &str) -> Vec<u8> {
+	let mut k = super::all::new(ns, db).encode().unwrap();
+	k.extend_from_slice(&[b'!', b'n', b'q', 0xff]);
+	k
+}
+
+impl<'a> Nq<'a> {
+	pub fn new(ns: &'a str, db: &'a str, nq: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'!',
+			_d: b'n',
+			_e: b'q',
+			nq,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Nq::new(
+			"testns",
+			"testdb",
+			"testnq",
+		);
+		let enc = Nq::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\0*testdb\0!nqtestnq\0");
+
+		let dec = Nq::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
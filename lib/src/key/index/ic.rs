@@ -0,0 +1,71 @@
+//! Stores a shard of the changes to the statistics of an index
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Ic<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub ix: &'a str,
+	pub shard: u32,
+}
+
+impl<'a> Ic<'a> {
+	pub fn new(ns: &'a str, db: &'a str, tb: &'a str, ix: &'a str, shard: u32) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'!',
+			_e: b'i',
+			_f: b'c',
+			ix,
+			shard,
+		}
+	}
+
+	pub fn prefix(ns: &str, db: &str, tb: &str, ix: &str) -> Vec<u8> {
+		let mut k = Self::new(ns, db, tb, ix, 0).encode().unwrap();
+		k.truncate(k.len() - 4);
+		k
+	}
+
+	pub fn suffix(ns: &str, db: &str, tb: &str, ix: &str) -> Vec<u8> {
+		let mut k = Self::prefix(ns, db, tb, ix);
+		k.push(0xff);
+		k
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Ic::new(
+			"testns",
+			"testdb",
+			"testtb",
+			"testix",
+			3
+		);
+		let enc = Ic::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\0*testdb\0*testtb\0!ictestix\0\0\0\0\x03");
+
+		let dec = Ic::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
@@ -11,6 +11,15 @@ pub mod bp;
 pub mod bs;
 pub mod bt;
 pub mod bu;
+pub mod hd;
+pub mod hn;
+pub mod ib;
+pub mod ic;
+pub mod ip;
+pub mod is;
+pub mod rd;
+pub mod rn;
+pub mod vm;
 
 use crate::sql::array::Array;
 use crate::sql::id::Id;
@@ -139,6 +148,23 @@ impl<'a> Index<'a> {
 		end.extend_from_slice(&[0xff]);
 		(beg, end)
 	}
+
+	/// The range of the entries whose values start with the given values
+	pub fn range_prefix_ids(
+		ns: &str,
+		db: &str,
+		tb: &str,
+		ix: &str,
+		fd: &Array,
+	) -> (Vec<u8>, Vec<u8>) {
+		let mut beg = PrefixIds::new(ns, db, tb, ix, fd).encode().unwrap();
+		// Remove the terminator of the values, so that longer arrays are matched
+		beg.pop();
+		let mut end = beg.clone();
+		beg.extend_from_slice(&[0x00]);
+		end.extend_from_slice(&[0xff]);
+		(beg, end)
+	}
 }
 
 #[cfg(test)]
@@ -159,4 +185,45 @@ mod tests {
 		let dec = Index::decode(&enc).unwrap();
 		assert_eq!(val, dec);
 	}
+
+	#[test]
+	fn range_prefix_ids() {
+		use super::*;
+		let fd = vec!["testfd1", "testfd2"].into();
+		let id = "testid".into();
+		let val = Index::new("testns", "testdb", "testtb", "testix", &fd, Some(&id));
+		let enc = Index::encode(&val).unwrap();
+		let (beg, end) = Index::range_prefix_ids(
+			"testns",
+			"testdb",
+			"testtb",
+			"testix",
+			&vec!["testfd1"].into(),
+		);
+		assert!(beg < enc && enc < end);
+		let (beg, end) =
+			Index::range_prefix_ids("testns", "testdb", "testtb", "testix", &vec!["testfd"].into());
+		assert!(!(beg < enc && enc < end));
+	}
+
+	#[test]
+	fn uuid_time_order() {
+		use super::*;
+		use crate::sql::{Uuid, Value};
+		// V7 UUIDs generated in the same millisecond are ordered by their random bits,
+		// while UUIDs generated in later milliseconds are ordered after all of them.
+		let uuids = [
+			"017f22e2-79b0-7000-8000-000000000000",
+			"017f22e2-79b0-7fff-bfff-ffffffffffff",
+			"017f22e2-79b1-7000-8000-000000000000",
+		];
+		let keys = uuids
+			.iter()
+			.map(|v| {
+				let fd = vec![Value::from(Uuid::try_from(*v).unwrap())].into();
+				Index::new("testns", "testdb", "testtb", "testix", &fd, None).encode().unwrap()
+			})
+			.collect::<Vec<_>>();
+		assert!(keys.windows(2).all(|w| w[0] < w[1]));
+	}
 }
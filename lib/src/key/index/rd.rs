@@ -0,0 +1,62 @@
+//! Stores the bounding boxes of a document indexed in an R-tree
+use crate::idx::ft::docids::DocId;
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Rd<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	pub ix: &'a str,
+	_e: u8,
+	_f: u8,
+	_g: u8,
+	pub doc_id: DocId,
+}
+
+impl<'a> Rd<'a> {
+	pub fn new(ns: &'a str, db: &'a str, tb: &'a str, ix: &'a str, doc_id: DocId) -> Self {
+		Rd {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'+',
+			ix,
+			_e: b'!',
+			_f: b'r',
+			_g: b'd',
+			doc_id,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Rd::new(
+			"testns",
+			"testdb",
+			"testtb",
+			"testix",
+			7
+		);
+		let enc = Rd::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\0*testdb\0*testtb\0+testix\0!rd\0\0\0\0\0\0\0\x07");
+
+		let dec = Rd::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
@@ -17,10 +17,16 @@
 ///
 /// crate::key::database::all            /*{ns}*{db}
 /// crate::key::database::az             /*{ns}*{db}!az{az}
+/// crate::key::database::cg             /*{ns}*{db}!cg{cg}
 /// crate::key::database::fc             /*{ns}*{db}!fn{fc}
 /// crate::key::database::lg             /*{ns}*{db}!lg{lg}
+/// crate::key::database::ma             /*{ns}*{db}!ma{mg}
+/// crate::key::database::mg             /*{ns}*{db}!mg{mg}
+/// crate::key::database::nq             /*{ns}*{db}!nq{nq}
 /// crate::key::database::pa             /*{ns}*{db}!pa{pa}
 /// crate::key::database::sc             /*{ns}*{db}!sc{sc}
+/// crate::key::database::sq             /*{ns}*{db}!sq{sq}
+/// crate::key::database::sv             /*{ns}*{db}!sv{sq}
 /// crate::key::database::tb             /*{ns}*{db}!tb{tb}
 /// crate::key::database::ti             /+{ns id}*{db id}!ti
 /// crate::key::database::tk             /*{ns}*{db}!tk{tk}
@@ -30,12 +36,21 @@
 /// crate::key::scope::all               /*{ns}*{db}±{sc}
 /// crate::key::scope::tk                /*{ns}*{db}±{sc}!tk{tk}
 ///
+/// crate::key::table::ag                /*{ns}*{db}*{tb}!ag{id}{fd}{val}
 /// crate::key::table::all               /*{ns}*{db}*{tb}
+/// crate::key::table::ar                /*{ns}*{db}*{tb}!ar{id}
+/// crate::key::table::cr                /*{ns}*{db}*{tb}!cr{hash}{id}
+/// crate::key::table::cs                /*{ns}*{db}*{tb}!cs{hash}
+/// crate::key::table::et                /*{ns}*{db}*{tb}!et{ts}{id}
 /// crate::key::table::ev                /*{ns}*{db}*{tb}!ev{ev}
+/// crate::key::table::ex                /*{ns}*{db}*{tb}!ex{id}
 /// crate::key::table::fd                /*{ns}*{db}*{tb}!fd{fd}
 /// crate::key::table::ft                /*{ns}*{db}*{tb}!ft{ft}
+/// crate::key::table::fv                /*{ns}*{db}*{tb}!fv{id}
 /// crate::key::table::ix                /*{ns}*{db}*{tb}!ix{ix}
 /// crate::key::table::lq                /*{ns}*{db}*{tb}!lq{lq}
+/// crate::key::table::rf                /*{ns}*{db}*{tb}!rf{id}{ft}{fk}{fd}
+/// crate::key::table::rv                /*{ns}*{db}*{tb}!rv{id}
 ///
 /// crate::key::index::all               /*{ns}*{db}*{tb}+{ix}
 /// crate::key::index::bc                /*{ns}*{db}*{tb}+{ix}!bc{id}
@@ -49,11 +64,22 @@
 /// crate::key::index::bs                /*{ns}*{db}*{tb}+{ix}!bs
 /// crate::key::index::bt                /*{ns}*{db}*{tb}+{ix}!bt{id}
 /// crate::key::index::bu                /*{ns}*{db}*{tb}+{ix}!bu{id}
+/// crate::key::index::hd                /*{ns}*{db}*{tb}+{ix}!hd{id}
+/// crate::key::index::hn                /*{ns}*{db}*{tb}+{ix}!hn{id}
+/// crate::key::index::ib                /*{ns}*{db}*{tb}!ib{ix}
+/// crate::key::index::ic                /*{ns}*{db}*{tb}!ic{ix}{shard}
+/// crate::key::index::ip                /*{ns}*{db}*{tb}+{ix}!ip{shard}
+/// crate::key::index::is                /*{ns}*{db}*{tb}!is{ix}
+/// crate::key::index::rd                /*{ns}*{db}*{tb}+{ix}!rd{id}
+/// crate::key::index::rn                /*{ns}*{db}*{tb}+{ix}!rn{id}
+/// crate::key::index::vm                /*{ns}*{db}*{tb}+{ix}!vm{id}
 /// crate::key::index                    /*{ns}*{db}*{tb}+{ix}*{fd}{id}
 ///
 /// crate::key::change                   /*{ns}*{db}#{ts}
 ///
 /// crate::key::thing                    /*{ns}*{db}*{tb}*{id}
+/// crate::kvs::placement                /*{ns}*{db}*{tb}%{family}{id}
+/// crate::kvs::placement                /*{ns}*{db}*{tb}&{shard}{id}
 ///
 /// crate::key::graph                    /*{ns}*{db}*{tb}~{id}{eg}{fk}
 ///
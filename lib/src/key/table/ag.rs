@@ -0,0 +1,202 @@
+//! Stores the auxiliary state of the aggregates of a group in a foreign table
+use crate::sql::id::Id;
+use crate::sql::value::Value;
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+// Each Ag key counts the records in a group with a value of an aggregated field, so that the
+// value can be retracted exactly when a record is updated or deleted. The field is the hash of
+// the aggregated field, or is empty when the key counts all of the records in the group.
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Ag<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub id: Id,
+	pub fd: &'a str,
+	pub val: Value,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+struct PrefixTb<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+}
+
+impl<'a> PrefixTb<'a> {
+	fn new(ns: &'a str, db: &'a str, tb: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'!',
+			_e: b'a',
+			_f: b'g',
+		}
+	}
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+struct Prefix<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub id: Id,
+}
+
+impl<'a> Prefix<'a> {
+	fn new(ns: &'a str, db: &'a str, tb: &'a str, id: &Id) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'!',
+			_e: b'a',
+			_f: b'g',
+			id: id.to_owned(),
+		}
+	}
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+struct PrefixFd<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub id: Id,
+	pub fd: &'a str,
+}
+
+impl<'a> PrefixFd<'a> {
+	fn new(ns: &'a str, db: &'a str, tb: &'a str, id: &Id, fd: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'!',
+			_e: b'a',
+			_f: b'g',
+			id: id.to_owned(),
+			fd,
+		}
+	}
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, tb: &'a str, id: &Id, fd: &'a str, val: &Value) -> Ag<'a> {
+	Ag::new(ns, db, tb, id.to_owned(), fd, val.to_owned())
+}
+
+/// Returns the prefix for the state of the aggregates of all of the groups of a table
+pub fn prefix_tb(ns: &str, db: &str, tb: &str) -> Vec<u8> {
+	PrefixTb::new(ns, db, tb).encode().unwrap()
+}
+
+/// Returns the suffix for the state of the aggregates of all of the groups of a table
+pub fn suffix_tb(ns: &str, db: &str, tb: &str) -> Vec<u8> {
+	let mut k = PrefixTb::new(ns, db, tb).encode().unwrap();
+	k.extend_from_slice(&[0xff]);
+	k
+}
+
+/// Returns the prefix for the state of all of the aggregates of a group
+pub fn prefix(ns: &str, db: &str, tb: &str, id: &Id) -> Vec<u8> {
+	Prefix::new(ns, db, tb, id).encode().unwrap()
+}
+
+/// Returns the prefix for the state of one aggregated field of a group
+pub fn prefix_fd(ns: &str, db: &str, tb: &str, id: &Id, fd: &str) -> Vec<u8> {
+	PrefixFd::new(ns, db, tb, id, fd).encode().unwrap()
+}
+
+impl<'a> Ag<'a> {
+	pub fn new(ns: &'a str, db: &'a str, tb: &'a str, id: Id, fd: &'a str, val: Value) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'!',
+			_e: b'a',
+			_f: b'g',
+			id,
+			fd,
+			val,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Ag::new(
+			"testns",
+			"testdb",
+			"testtb",
+			"testid".into(),
+			"testfd",
+			Value::from(1),
+		);
+		let enc = Ag::encode(&val).unwrap();
+		assert!(enc.starts_with(b"/*testns\0*testdb\0*testtb\0!ag\0\0\0\x01testid\0testfd\0"));
+		assert!(enc.starts_with(&prefix_fd(
+			"testns",
+			"testdb",
+			"testtb",
+			&"testid".into(),
+			"testfd"
+		)));
+		assert!(enc.starts_with(&prefix("testns", "testdb", "testtb", &"testid".into())));
+		assert!(enc < suffix_tb("testns", "testdb", "testtb"));
+		assert!(enc.starts_with(&prefix_tb("testns", "testdb", "testtb")));
+
+		let dec = Ag::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
@@ -0,0 +1,87 @@
+//! Stores the compressed records of a table which have been moved to the archive
+use crate::sql::id::Id;
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Ar<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub id: Id,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, tb: &'a str, id: &Id) -> Ar<'a> {
+	Ar::new(ns, db, tb, id.to_owned())
+}
+
+/// Returns the prefix for the archived records of the whole table
+pub fn prefix(ns: &str, db: &str, tb: &str) -> Vec<u8> {
+	let mut k = crate::key::table::all::new(ns, db, tb).encode().unwrap();
+	k.extend_from_slice(&[b'!', b'a', b'r', 0x00]);
+	k
+}
+
+/// Returns the suffix for the archived records of the whole table
+pub fn suffix(ns: &str, db: &str, tb: &str) -> Vec<u8> {
+	let mut k = crate::key::table::all::new(ns, db, tb).encode().unwrap();
+	k.extend_from_slice(&[b'!', b'a', b'r', 0xff]);
+	k
+}
+
+impl<'a> Ar<'a> {
+	pub fn new(ns: &'a str, db: &'a str, tb: &'a str, id: Id) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'!',
+			_e: b'a',
+			_f: b'r',
+			id,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Ar::new(
+			"testns",
+			"testdb",
+			"testtb",
+			"testid".into(),
+		);
+		let enc = Ar::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\0*testdb\0*testtb\0!ar\0\0\0\x01testid\0");
+
+		let dec = Ar::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+
+	#[test]
+	fn prefix() {
+		let val = super::prefix("testns", "testdb", "testtb");
+		assert_eq!(val, b"/*testns\0*testdb\0*testtb\0!ar\0");
+	}
+
+	#[test]
+	fn suffix() {
+		let val = super::suffix("testns", "testdb", "testtb");
+		assert_eq!(val, b"/*testns\0*testdb\0*testtb\0!ar\xff");
+	}
+}
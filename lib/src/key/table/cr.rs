@@ -0,0 +1,124 @@
+//! Stores a reference from a record to a value of a deduplicated field
+use crate::sql::id::Id;
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+struct Prefix<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub hash: &'a str,
+}
+
+impl<'a> Prefix<'a> {
+	fn new(ns: &'a str, db: &'a str, tb: &'a str, hash: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'!',
+			_e: b'c',
+			_f: b'r',
+			hash,
+		}
+	}
+}
+
+// Each Cr key is stored under the hash of the value, and is suffixed by the
+// referencing record, so that each record writes its own key, and a value
+// is no longer referred to once there are no keys under its hash.
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Cr<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub hash: &'a str,
+	pub id: Id,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, tb: &'a str, hash: &'a str, id: &Id) -> Cr<'a> {
+	Cr::new(ns, db, tb, hash, id.to_owned())
+}
+
+/// Returns the prefix for the references to a value
+pub fn prefix(ns: &str, db: &str, tb: &str, hash: &str) -> Vec<u8> {
+	let mut k = Prefix::new(ns, db, tb, hash).encode().unwrap();
+	k.extend_from_slice(&[0x00]);
+	k
+}
+
+/// Returns the suffix for the references to a value
+pub fn suffix(ns: &str, db: &str, tb: &str, hash: &str) -> Vec<u8> {
+	let mut k = Prefix::new(ns, db, tb, hash).encode().unwrap();
+	k.extend_from_slice(&[0xff]);
+	k
+}
+
+impl<'a> Cr<'a> {
+	pub fn new(ns: &'a str, db: &'a str, tb: &'a str, hash: &'a str, id: Id) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'!',
+			_e: b'c',
+			_f: b'r',
+			hash,
+			id,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Cr::new(
+			"testns",
+			"testdb",
+			"testtb",
+			"testhash",
+			"testid".into(),
+		);
+		let enc = Cr::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\0*testdb\0*testtb\0!crtesthash\0\0\0\0\x01testid\0");
+
+		let dec = Cr::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+
+	#[test]
+	fn range() {
+		use super::*;
+		let beg = prefix("testns", "testdb", "testtb", "testhash");
+		let end = suffix("testns", "testdb", "testtb", "testhash");
+		let val = Cr::new("testns", "testdb", "testtb", "testhash", "testid".into());
+		let enc = Cr::encode(&val).unwrap();
+		assert!(beg < enc && enc < end);
+	}
+}
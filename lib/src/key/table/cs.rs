@@ -0,0 +1,60 @@
+//! Stores a value of a deduplicated field, once for all of the records which hold the value
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Cs<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub hash: &'a str,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, tb: &'a str, hash: &'a str) -> Cs<'a> {
+	Cs::new(ns, db, tb, hash)
+}
+
+impl<'a> Cs<'a> {
+	pub fn new(ns: &'a str, db: &'a str, tb: &'a str, hash: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'!',
+			_e: b'c',
+			_f: b's',
+			hash,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Cs::new(
+			"testns",
+			"testdb",
+			"testtb",
+			"testhash",
+		);
+		let enc = Cs::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\0*testdb\0*testtb\0!cstesthash\0");
+
+		let dec = Cs::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
@@ -0,0 +1,91 @@
+//! Stores the records of a table with an expiry duration, ordered by their expiry time
+use crate::sql::id::Id;
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+// Each Et key is suffixed by the expiry time of the record in milliseconds,
+// so that the expired records are found by scanning up to the current time.
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Et<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub ts: u64,
+	pub id: Id,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, tb: &'a str, ts: u64, id: &Id) -> Et<'a> {
+	Et::new(ns, db, tb, ts, id.to_owned())
+}
+
+/// Returns the prefix for the expiry times of the whole table
+pub fn prefix(ns: &str, db: &str, tb: &str) -> Vec<u8> {
+	let mut k = crate::key::table::all::new(ns, db, tb).encode().unwrap();
+	k.extend_from_slice(&[b'!', b'e', b't']);
+	k
+}
+
+/// Returns the end of the range of the records which expire before the given time
+pub fn suffix(ns: &str, db: &str, tb: &str, ts: u64) -> Vec<u8> {
+	let mut k = prefix(ns, db, tb);
+	k.extend_from_slice(&ts.to_be_bytes());
+	k
+}
+
+impl<'a> Et<'a> {
+	pub fn new(ns: &'a str, db: &'a str, tb: &'a str, ts: u64, id: Id) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'!',
+			_e: b'e',
+			_f: b't',
+			ts,
+			id,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Et::new(
+			"testns",
+			"testdb",
+			"testtb",
+			1,
+			"testid".into(),
+		);
+		let enc = Et::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\0*testdb\0*testtb\0!et\0\0\0\0\0\0\0\x01\0\0\0\x01testid\0");
+
+		let dec = Et::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+
+	#[test]
+	fn range() {
+		use super::*;
+		let beg = prefix("testns", "testdb", "testtb");
+		let end = suffix("testns", "testdb", "testtb", 2);
+		let key = Et::new("testns", "testdb", "testtb", 1, "testid".into()).encode().unwrap();
+		assert!(beg < key && key < end);
+		let key = Et::new("testns", "testdb", "testtb", 2, "testid".into()).encode().unwrap();
+		assert!(key > end);
+	}
+}
@@ -1,6 +1,15 @@
+pub mod ag;
 pub mod all;
+pub mod ar;
+pub mod cr;
+pub mod cs;
+pub mod et;
 pub mod ev;
+pub mod ex;
 pub mod fd;
 pub mod ft;
+pub mod fv;
 pub mod ix;
 pub mod lq;
+pub mod rf;
+pub mod rv;
@@ -0,0 +1,143 @@
+//! Stores a reverse reference to a record, from a record link field of another record
+use crate::sql::id::Id;
+use crate::sql::thing::Thing;
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+struct Prefix<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub id: Id,
+}
+
+impl<'a> Prefix<'a> {
+	fn new(ns: &'a str, db: &'a str, tb: &'a str, id: &Id) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'!',
+			_e: b'r',
+			_f: b'f',
+			id: id.to_owned(),
+		}
+	}
+}
+
+// Each Rf key is stored on the referenced record, and is suffixed by the
+// referencing record and field, so that all of the records which refer
+// to a record are found by scanning the prefix of the referenced record.
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Rf<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub id: Id,
+	pub ft: &'a str,
+	pub fk: Id,
+	pub fd: &'a str,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, to: &'a Thing, fk: &'a Thing, fd: &'a str) -> Rf<'a> {
+	Rf::new(ns, db, &to.tb, to.id.to_owned(), &fk.tb, fk.id.to_owned(), fd)
+}
+
+/// Returns the prefix for the references to a record
+pub fn prefix(ns: &str, db: &str, tb: &str, id: &Id) -> Vec<u8> {
+	let mut k = Prefix::new(ns, db, tb, id).encode().unwrap();
+	k.extend_from_slice(&[0x00]);
+	k
+}
+
+/// Returns the suffix for the references to a record
+pub fn suffix(ns: &str, db: &str, tb: &str, id: &Id) -> Vec<u8> {
+	let mut k = Prefix::new(ns, db, tb, id).encode().unwrap();
+	k.extend_from_slice(&[0xff]);
+	k
+}
+
+impl<'a> Rf<'a> {
+	pub fn new(
+		ns: &'a str,
+		db: &'a str,
+		tb: &'a str,
+		id: Id,
+		ft: &'a str,
+		fk: Id,
+		fd: &'a str,
+	) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'!',
+			_e: b'r',
+			_f: b'f',
+			id,
+			ft,
+			fk,
+			fd,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Rf::new(
+			"testns",
+			"testdb",
+			"testtb",
+			"testid".into(),
+			"testft",
+			"testfk".into(),
+			"testfd",
+		);
+		let enc = Rf::encode(&val).unwrap();
+		assert_eq!(
+			enc,
+			b"/*testns\0*testdb\0*testtb\0!rf\0\0\0\x01testid\0testft\0\0\0\0\x01testfk\0testfd\0"
+		);
+
+		let dec = Rf::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+
+	#[test]
+	fn range() {
+		use super::*;
+		let id = Id::from("testid");
+		let beg = prefix("testns", "testdb", "testtb", &id);
+		let end = suffix("testns", "testdb", "testtb", &id);
+		let val = Rf::new("testns", "testdb", "testtb", id, "testft", "testfk".into(), "testfd");
+		let enc = Rf::encode(&val).unwrap();
+		assert!(beg < enc && enc < end);
+	}
+}
@@ -0,0 +1,40 @@
+//! Encodes the records which are moved into the archive key range of a table.
+//! Archived records are rarely read and never updated, so they are stored compressed.
+use crate::err::Error;
+use crate::sql::value::Value;
+use std::io;
+
+/// Serialises and compresses a record for the archive
+pub(crate) fn encode(val: &Value) -> Result<Vec<u8>, Error> {
+	let uncompressed: Vec<u8> = val.into();
+	let mut compressed: Vec<u8> = Vec::new();
+	{
+		let mut wtr = snap::write::FrameEncoder::new(&mut compressed);
+		io::copy(&mut uncompressed.as_slice(), &mut wtr)?;
+	}
+	Ok(compressed)
+}
+
+/// Decompresses and deserialises a record from the archive
+pub(crate) fn decode(val: &[u8]) -> Result<Value, Error> {
+	let mut uncompressed: Vec<u8> = Vec::new();
+	{
+		let mut rdr = snap::read::FrameDecoder::new(val);
+		io::copy(&mut rdr, &mut uncompressed)?;
+	}
+	Ok((&uncompressed).into())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sql::test::Parse;
+
+	#[test]
+	fn roundtrip() {
+		let val = Value::parse("{ id: log:1, message: 'started', time: d'2023-01-01T00:00:00Z' }");
+		let enc = encode(&val).unwrap();
+		let dec = decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
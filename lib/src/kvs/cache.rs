@@ -1,14 +1,17 @@
 use crate::idg::u32::U32;
 use crate::kvs::kv::Key;
 use crate::sql::statements::DefineAnalyzerStatement;
+use crate::sql::statements::DefineConfigStatement;
 use crate::sql::statements::DefineDatabaseStatement;
 use crate::sql::statements::DefineEventStatement;
 use crate::sql::statements::DefineFieldStatement;
 use crate::sql::statements::DefineFunctionStatement;
 use crate::sql::statements::DefineIndexStatement;
+use crate::sql::statements::DefineMigrationStatement;
 use crate::sql::statements::DefineNamespaceStatement;
 use crate::sql::statements::DefineParamStatement;
 use crate::sql::statements::DefineScopeStatement;
+use crate::sql::statements::DefineSequenceStatement;
 use crate::sql::statements::DefineTableStatement;
 use crate::sql::statements::DefineTokenStatement;
 use crate::sql::statements::DefineUserStatement;
@@ -19,14 +22,17 @@ use std::sync::Arc;
 #[derive(Clone)]
 pub enum Entry {
 	// Single definitions
+	Cg(Arc<DefineConfigStatement>),
 	Db(Arc<DefineDatabaseStatement>),
 	Fc(Arc<DefineFunctionStatement>),
 	Ix(Arc<DefineIndexStatement>),
 	Ns(Arc<DefineNamespaceStatement>),
 	Pa(Arc<DefineParamStatement>),
+	Sq(Arc<DefineSequenceStatement>),
 	Tb(Arc<DefineTableStatement>),
 	// Multi definitions
 	Azs(Arc<[DefineAnalyzerStatement]>),
+	Cgs(Arc<[DefineConfigStatement]>),
 	Dbs(Arc<[DefineDatabaseStatement]>),
 	Dts(Arc<[DefineTokenStatement]>),
 	Dus(Arc<[DefineUserStatement]>),
@@ -36,11 +42,13 @@ pub enum Entry {
 	Fts(Arc<[DefineTableStatement]>),
 	Ixs(Arc<[DefineIndexStatement]>),
 	Lvs(Arc<[LiveStatement]>),
+	Mgs(Arc<[DefineMigrationStatement]>),
 	Nss(Arc<[DefineNamespaceStatement]>),
 	Nts(Arc<[DefineTokenStatement]>),
 	Nus(Arc<[DefineUserStatement]>),
 	Pas(Arc<[DefineParamStatement]>),
 	Scs(Arc<[DefineScopeStatement]>),
+	Sqs(Arc<[DefineSequenceStatement]>),
 	Sts(Arc<[DefineTokenStatement]>),
 	Tbs(Arc<[DefineTableStatement]>),
 	// Sequences
@@ -0,0 +1,126 @@
+use crate::err::Error;
+use crate::key::debug::sprint_key;
+use crate::kvs::{Key, Val};
+use sha2::{Digest, Sha256};
+
+/// The byte which marks the start of a value with a checksum. A serialized
+/// value never starts with this byte, as it is not a valid revision number.
+const FLAG: u8 = 0xff;
+
+/// The number of bytes of the checksum which are stored
+const LEN: usize = 8;
+
+/// Check if a key stores the value of a record, as
+/// `/*{ns}*{db}*{tb}*{id}`
+pub(crate) fn is_record(key: &[u8]) -> bool {
+	let mut rest = match key.strip_prefix(b"/") {
+		Some(v) => v,
+		None => return false,
+	};
+	for _ in 0..3 {
+		rest = match rest.strip_prefix(b"*") {
+			Some(v) => v,
+			None => return false,
+		};
+		rest = match rest.iter().position(|&b| b == 0) {
+			Some(i) => &rest[i + 1..],
+			None => return false,
+		};
+	}
+	rest.first() == Some(&b'*')
+}
+
+/// Prefix the value of a record with a checksum
+pub(crate) fn seal(val: Val) -> Val {
+	let mut out = Vec::with_capacity(1 + LEN + val.len());
+	out.push(FLAG);
+	out.extend_from_slice(&checksum(&val));
+	out.extend_from_slice(&val);
+	out
+}
+
+/// Return the value of a record without its checksum, if the value has
+/// a checksum, verifying the checksum if checksums are enabled
+pub(crate) fn verify(key: &Key, mut val: Val, enabled: bool) -> Result<Val, Error> {
+	if !is_record(key) || val.first() != Some(&FLAG) {
+		return Ok(val);
+	}
+	if val.len() < 1 + LEN || (enabled && val[1..1 + LEN] != checksum(&val[1 + LEN..])) {
+		return Err(Error::CorruptValue {
+			key: sprint_key(key),
+		});
+	}
+	val.drain(..1 + LEN);
+	Ok(val)
+}
+
+fn checksum(val: &[u8]) -> [u8; LEN] {
+	let mut sum = [0; LEN];
+	sum.copy_from_slice(&Sha256::digest(val)[..LEN]);
+	sum
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::dbs::Session;
+	use crate::kvs::{Datastore, DatastoreOptions};
+
+	#[test]
+	fn record_keys() {
+		let key = crate::key::thing::new("test", "test", "person", &"tobie".into());
+		assert!(is_record(&Key::from(key)));
+		let key = crate::key::table::ex::new("test", "test", "person", &"tobie".into());
+		assert!(!is_record(&Key::from(key)));
+		let key = crate::key::database::tb::new("test", "test", "person");
+		assert!(!is_record(&Key::from(key)));
+	}
+
+	#[test]
+	fn seal_and_verify() {
+		let key = Key::from(crate::key::thing::new("test", "test", "person", &"tobie".into()));
+		let val = seal(b"value".to_vec());
+		assert_eq!(val[0], FLAG);
+		assert_eq!(verify(&key, val.clone(), true).unwrap(), b"value".to_vec());
+		// A value without a checksum is not verified
+		assert_eq!(verify(&key, b"value".to_vec(), true).unwrap(), b"value".to_vec());
+		// A corrupt value is detected
+		let mut bad = val;
+		bad[1] ^= 0xff;
+		assert!(matches!(verify(&key, bad.clone(), true), Err(Error::CorruptValue { .. })));
+		// The checksum is only removed when checksums are disabled
+		assert_eq!(verify(&key, bad, false).unwrap(), b"value".to_vec());
+	}
+
+	#[tokio::test]
+	async fn corrupt_records() {
+		let opts = DatastoreOptions::new().with_checksums(true);
+		let ds = Datastore::new_with_options("memory", opts).await.unwrap();
+		let ses = Session::owner().with_ns("test").with_db("test");
+		let sql = "CREATE person:one SET name = 'one'; CREATE person:two SET name = 'two';";
+		for res in ds.execute(sql, &ses, None).await.unwrap() {
+			res.result.unwrap();
+		}
+		// The records are read and verified
+		let res = &mut ds.execute("SELECT * FROM person", &ses, None).await.unwrap();
+		assert!(res.remove(0).result.is_ok());
+		assert!(ds.scrub_records().await.unwrap().is_empty());
+		// Corrupt one of the stored records
+		let key = Key::from(crate::key::thing::new("test", "test", "person", &"two".into()));
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		let mut val = tx.get_unverified(key.clone()).await.unwrap().unwrap();
+		val[2] ^= 0xff;
+		tx.checksums = false;
+		tx.set(key, val).await.unwrap();
+		tx.commit().await.unwrap();
+		// The corruption is detected when the record is read
+		let res = &mut ds.execute("SELECT * FROM person:two", &ses, None).await.unwrap();
+		assert!(matches!(res.remove(0).result, Err(Error::CorruptValue { .. })));
+		let res = &mut ds.execute("SELECT * FROM person:one", &ses, None).await.unwrap();
+		assert!(res.remove(0).result.is_ok());
+		// The corruption is detected by the scrubbing task
+		let bad = ds.scrub_records().await.unwrap();
+		assert_eq!(bad.len(), 1);
+		assert!(bad[0].contains("two"), "{bad:?}");
+	}
+}
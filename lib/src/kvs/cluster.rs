@@ -0,0 +1,114 @@
+use super::tx::Transaction;
+use crate::dbs::node::{ClusterMembership, Timestamp};
+use crate::err::Error;
+use crate::key::root::hb::Hb;
+use crate::key::root::nd::Nd;
+use std::collections::BTreeMap;
+
+/// The membership of a node in the cluster
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct NodeMembership {
+	/// The id of the node
+	pub id: uuid::Uuid,
+	/// When the node last sent a heartbeat, in milliseconds since the Unix epoch
+	pub last_seen: u64,
+	/// The live queries which are owned by the node
+	pub live_queries: Vec<uuid::Uuid>,
+}
+
+/// The latest heartbeat timestamp which can be scanned
+fn max_heartbeat() -> Timestamp {
+	Timestamp {
+		value: u64::MAX - 1,
+	}
+}
+
+/// Returns the membership of every registered node, ordered by node id
+pub(super) async fn membership(tx: &mut Transaction) -> Result<Vec<NodeMembership>, Error> {
+	let mut nodes = BTreeMap::new();
+	for (k, v) in tx.getr(Nd::prefix()..Nd::suffix(), u32::MAX).await? {
+		// Invalid entries are reported by the recovery check
+		if let (Ok(nd), Ok(cl)) = (Nd::decode(&k), ClusterMembership::try_from(&v)) {
+			nodes.insert(nd.nd, cl.heartbeat.value);
+		}
+	}
+	// The last seen time is the latest heartbeat of the node
+	for (k, _) in tx.getr(Hb::prefix()..Hb::suffix(&max_heartbeat()), u32::MAX).await? {
+		let hb = Hb::decode(&k)?;
+		if let Some(last_seen) = nodes.get_mut(&hb.nd) {
+			*last_seen = (*last_seen).max(hb.hb.value);
+		}
+	}
+	let mut res = Vec::with_capacity(nodes.len());
+	for (id, last_seen) in nodes {
+		let live_queries = tx.all_lq(&id).await?.into_iter().map(|lq| lq.lq.0).collect();
+		res.push(NodeMembership {
+			id,
+			last_seen,
+			live_queries,
+		});
+	}
+	Ok(res)
+}
+
+/// Records a heartbeat for a node, replacing its previous heartbeats.
+/// The node is registered again if it had been removed from the cluster.
+pub(super) async fn heartbeat(
+	tx: &mut Transaction,
+	node: uuid::Uuid,
+	timestamp: Timestamp,
+) -> Result<(), Error> {
+	if tx.get_nd(node).await?.is_none() {
+		tx.set_nd(node).await?;
+	}
+	del_heartbeats(tx, node).await?;
+	tx.set_hb(timestamp, node).await
+}
+
+/// Reassigns the live queries of the nodes which were last seen before the expiry
+/// to the given node, and removes those nodes from the cluster.
+/// Returns the ids of the reassigned live queries.
+pub(super) async fn reassign(
+	tx: &mut Transaction,
+	node: uuid::Uuid,
+	expiry: &Timestamp,
+) -> Result<Vec<uuid::Uuid>, Error> {
+	let mut reassigned = Vec::new();
+	for dead in membership(tx).await? {
+		if dead.id == node || dead.last_seen >= expiry.value {
+			continue;
+		}
+		trace!("Reassigning the live queries of node {} to node {}", dead.id, node);
+		for lq in tx.all_lq(&dead.id).await? {
+			tx.del(crate::key::node::lq::new(dead.id, lq.lq.0, &lq.ns, &lq.db)).await?;
+			// Live queries without a table entry can not be notified, so they are dropped
+			let stm = match tx.get_tb_live(&lq.ns, &lq.db, &lq.tb, &lq.lq.0).await {
+				Ok(stm) => stm,
+				Err(Error::LvNotFound {
+					..
+				}) => continue,
+				Err(e) => return Err(e),
+			};
+			let mut lv = stm.clone();
+			lv.node = node.into();
+			lv.archived = None;
+			tx.putc_tblq(&lq.ns, &lq.db, &lq.tb, lv, Some(stm)).await?;
+			let key = crate::key::node::lq::new(node, lq.lq.0, &lq.ns, &lq.db);
+			tx.put(key, lq.tb.as_str()).await?;
+			reassigned.push(lq.lq.0);
+		}
+		del_heartbeats(tx, dead.id).await?;
+		tx.del_nd(dead.id).await?;
+	}
+	Ok(reassigned)
+}
+
+async fn del_heartbeats(tx: &mut Transaction, node: uuid::Uuid) -> Result<(), Error> {
+	for (k, _) in tx.getr(Hb::prefix()..Hb::suffix(&max_heartbeat()), u32::MAX).await? {
+		if Hb::decode(&k)?.nd == node {
+			tx.del(k).await?;
+		}
+	}
+	Ok(())
+}
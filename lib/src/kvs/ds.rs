@@ -1,5 +1,29 @@
+use super::archive;
+use super::checksum;
+use super::cluster;
+use super::export::ExportFilter;
+use super::isolation;
+use super::named::NamedQuery;
+use super::plans::PlanCache;
+use super::queries::{OperationKind, RunningQueries};
+use super::recovery;
+use super::sequences::Sequences;
+use super::slow::SlowQueries;
 use super::tx::Transaction;
+use super::DatastoreOptions;
+use super::ExportOptions;
+use super::Key;
+use super::NodeMembership;
+use super::RecoveryReport;
+use super::Val;
+use super::{ImportOptions, ImportProgress, RecordConflict};
+use super::{IsolationOptions, IsolationReport};
 use crate::cf;
+use crate::cf::ConflictPolicy;
+use crate::cnf::INDEX_BUILD_RETRIES;
+use crate::cnf::PROCESSOR_BATCH_SIZE;
+use crate::cnf::PROTECTED_PARAM_NAMES;
+use crate::cnf::{SCRUB_BATCH_SIZE, SCRUB_INTERVAL};
 use crate::ctx::Context;
 use crate::dbs::node::Timestamp;
 use crate::dbs::Attach;
@@ -10,27 +34,34 @@ use crate::dbs::Options;
 use crate::dbs::Response;
 use crate::dbs::Session;
 use crate::dbs::Variables;
+use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::iam::ResourceKind;
 use crate::iam::{Action, Auth, Error as IamError, Role};
+use crate::idx::builder::{self, BuildingState};
+use crate::key::index::ib::Ib;
 use crate::key::root::hb::Hb;
 use crate::opt::auth::Root;
 use crate::sql;
-use crate::sql::statements::DefineUserStatement;
+use crate::sql::error::{RenderedError, Span};
+use crate::sql::statements::{DefineIndexStatement, DefineStatement, DefineUserStatement};
 use crate::sql::Base;
+use crate::sql::Kind;
+use crate::sql::ParseLimits;
 use crate::sql::Value;
-use crate::sql::{Query, Uuid};
+use crate::sql::{Query, Statement, Statements, Uuid};
 use crate::vs::Oracle;
 use channel::Receiver;
 use channel::Sender;
 use futures::lock::Mutex;
 use futures::Future;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
 use std::sync::Arc;
 use std::time::Duration;
 #[cfg(not(target_arch = "wasm32"))]
 use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::Semaphore;
 use tracing::instrument;
 use tracing::trace;
 #[cfg(target_arch = "wasm32")]
@@ -51,11 +82,13 @@ pub struct LqValue {
 #[allow(dead_code)]
 pub struct Datastore {
 	// The inner datastore type
-	inner: Inner,
+	inner: Arc<Inner>,
 	// The unique id of this datastore, used in notifications
 	id: Uuid,
 	// Whether this datastore runs in strict mode by default
 	strict: bool,
+	// Whether this datastore resolves nondeterministic functions before processing
+	deterministic: bool,
 	// Whether authentication is enabled on this datastore.
 	auth_enabled: bool,
 	// The maximum duration timeout for running multiple statements in a query
@@ -69,6 +102,42 @@ pub struct Datastore {
 	versionstamp_oracle: Arc<Mutex<Oracle>>,
 	// Whether this datastore enables live query notifications to subscribers
 	notification_channel: Option<(Sender<Notification>, Receiver<Notification>)>,
+	// The number of index tree nodes cached in memory by each index
+	index_cache_size: usize,
+	// The number of records whose index entries are written in each transaction
+	index_batch_size: Option<u32>,
+	// The number of workers which build each index in the background
+	index_workers: usize,
+	// Limits the number of index shards which are built at the same time
+	index_permits: Arc<Semaphore>,
+	// The indexes which are being built by the background tasks of this node
+	index_builds: Arc<std::sync::Mutex<HashSet<Key>>>,
+	// The number of expired records of each table which are deleted on each tick
+	expiry_batch_size: u32,
+	// The duration after which a node without any heartbeat is considered dead
+	node_timeout: Duration,
+	// Whether this datastore rejects all writes
+	read_only: bool,
+	// The parsed queries which are reused by prepared statements
+	plans: PlanCache,
+	// The number of bytes which each GROUP BY clause can hold in memory
+	group_memory_limit: usize,
+	// The number of bytes which each statement can hold in memory, if limited
+	statement_memory_limit: Option<usize>,
+	// Whether a checksum is appended to each stored record
+	checksums: bool,
+	// The statements and background operations which are currently running
+	queries: Arc<RunningQueries>,
+	// The recent statements which took longer than the slow query threshold
+	slow_queries: SlowQueries,
+	// The blocks of sequence values which are allocated to this node
+	sequences: Sequences,
+	// The key from which the next batch of records is scrubbed
+	scrub_cursor: Mutex<Option<Key>>,
+	// The timestamp at which the last batch of records was scrubbed
+	scrub_ts: Mutex<u64>,
+	// The key from which the next batch of records of each archived table is checked
+	archive_cursors: Mutex<HashMap<(String, String, String), Key>>,
 }
 
 /// We always want to be circulating the live query information
@@ -91,10 +160,78 @@ pub(super) enum Inner {
 	FoundationDB(super::fdb::Datastore),
 }
 
+/// Starts new transactions on a datastore. This is held by the jobs
+/// which outlive the borrow of the datastore they were started from.
+#[derive(Clone)]
+pub(crate) struct Transactor {
+	inner: Arc<Inner>,
+	vso: Arc<Mutex<Oracle>>,
+	index_cache_size: usize,
+	checksums: bool,
+	sequences: Sequences,
+}
+
+impl Transactor {
+	/// Create a new transaction on the datastore
+	pub(crate) async fn transaction(&self, write: bool, lock: bool) -> Result<Transaction, Error> {
+		#![allow(unused_variables)]
+		let inner = match self.inner.as_ref() {
+			#[cfg(feature = "kv-mem")]
+			Inner::Mem(v) => {
+				let tx = v.transaction(write, lock).await?;
+				super::tx::Inner::Mem(tx)
+			}
+			#[cfg(feature = "kv-rocksdb")]
+			Inner::RocksDB(v) => {
+				let tx = v.transaction(write, lock).await?;
+				super::tx::Inner::RocksDB(tx)
+			}
+			#[cfg(feature = "kv-speedb")]
+			Inner::SpeeDB(v) => {
+				let tx = v.transaction(write, lock).await?;
+				super::tx::Inner::SpeeDB(tx)
+			}
+			#[cfg(feature = "kv-indxdb")]
+			Inner::IndxDB(v) => {
+				let tx = v.transaction(write, lock).await?;
+				super::tx::Inner::IndxDB(tx)
+			}
+			#[cfg(feature = "kv-tikv")]
+			Inner::TiKV(v) => {
+				let tx = v.transaction(write, lock).await?;
+				super::tx::Inner::TiKV(tx)
+			}
+			#[cfg(feature = "kv-fdb")]
+			Inner::FoundationDB(v) => {
+				let tx = v.transaction(write, lock).await?;
+				super::tx::Inner::FoundationDB(tx)
+			}
+			#[allow(unreachable_patterns)]
+			_ => unreachable!(),
+		};
+
+		#[allow(unreachable_code)]
+		Ok(Transaction {
+			inner,
+			cache: super::cache::Cache::default(),
+			cf: cf::Writer::new(),
+			write_buffer: HashMap::new(),
+			vso: self.vso.clone(),
+			index_cache_size: self.index_cache_size,
+			checksums: self.checksums,
+			coalescing: 0,
+			coalesced: BTreeMap::new(),
+			savepoints: Vec::new(),
+			sequences: self.sequences.clone(),
+			allocated: HashMap::new(),
+		})
+	}
+}
+
 impl fmt::Display for Datastore {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		#![allow(unused_variables)]
-		match &self.inner {
+		match self.inner.as_ref() {
 			#[cfg(feature = "kv-mem")]
 			Inner::Mem(_) => write!(f, "memory"),
 			#[cfg(feature = "kv-rocksdb")]
@@ -152,6 +289,44 @@ impl Datastore {
 	/// # }
 	/// ```
 	pub async fn new(path: &str) -> Result<Datastore, Error> {
+		Self::new_with_options(path, DatastoreOptions::default()).await
+	}
+
+	/// Creates a new datastore instance in read-only mode
+	///
+	/// All mutating statements are rejected, and background maintenance
+	/// tasks which write to the datastore are skipped.
+	///
+	/// # Examples
+	///
+	/// ```rust,no_run
+	/// # use surrealdb::kvs::Datastore;
+	/// # use surrealdb::err::Error;
+	/// # #[tokio::main]
+	/// # async fn main() -> Result<(), Error> {
+	/// let ds = Datastore::new_readonly("file://temp.db").await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub async fn new_readonly(path: &str) -> Result<Datastore, Error> {
+		Self::new_with_options(path, DatastoreOptions::default().with_read_only(true)).await
+	}
+
+	/// Creates a new datastore instance, with the specified options
+	///
+	/// # Examples
+	///
+	/// ```rust,no_run
+	/// # use surrealdb::kvs::{Datastore, DatastoreOptions};
+	/// # use surrealdb::err::Error;
+	/// # #[tokio::main]
+	/// # async fn main() -> Result<(), Error> {
+	/// let opts = DatastoreOptions::new().with_block_cache_size(32 * 1024 * 1024);
+	/// let ds = Datastore::new_with_options("file://temp.db", opts).await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub async fn new_with_options(path: &str, opts: DatastoreOptions) -> Result<Datastore, Error> {
 		// Initiate the desired datastore
 		let inner = match path {
 			"memory" => {
@@ -172,7 +347,7 @@ impl Datastore {
 					info!("Starting kvs store at {}", path);
 					let s = s.trim_start_matches("file://");
 					let s = s.trim_start_matches("file:");
-					let v = super::rocksdb::Datastore::new(s).await.map(Inner::RocksDB);
+					let v = super::rocksdb::Datastore::new(s, &opts).await.map(Inner::RocksDB);
 					info!("Started kvs store at {}", path);
 					v
 				}
@@ -186,7 +361,7 @@ impl Datastore {
 					info!("Starting kvs store at {}", path);
 					let s = s.trim_start_matches("rocksdb://");
 					let s = s.trim_start_matches("rocksdb:");
-					let v = super::rocksdb::Datastore::new(s).await.map(Inner::RocksDB);
+					let v = super::rocksdb::Datastore::new(s, &opts).await.map(Inner::RocksDB);
 					info!("Started kvs store at {}", path);
 					v
 				}
@@ -200,7 +375,7 @@ impl Datastore {
 					info!("Starting kvs store at {}", path);
 					let s = s.trim_start_matches("speedb://");
 					let s = s.trim_start_matches("speedb:");
-					let v = super::speedb::Datastore::new(s).await.map(Inner::SpeeDB);
+					let v = super::speedb::Datastore::new(s, &opts).await.map(Inner::SpeeDB);
 					info!("Started kvs store at {}", path);
 					v
 				}
@@ -258,14 +433,33 @@ impl Datastore {
 		// Set the properties on the datastore
 		inner.map(|inner| Self {
 			id: Uuid::new_v4(),
-			inner,
+			inner: Arc::new(inner),
 			strict: false,
+			deterministic: false,
 			auth_enabled: false,
 			query_timeout: None,
 			transaction_timeout: None,
 			notification_channel: None,
 			capabilities: Capabilities::default(),
 			versionstamp_oracle: Arc::new(Mutex::new(Oracle::systime_counter())),
+			index_cache_size: opts.index_cache_size(),
+			index_batch_size: opts.index_batch_size,
+			index_workers: opts.index_workers(),
+			index_permits: Arc::new(Semaphore::new(opts.index_workers())),
+			index_builds: Arc::default(),
+			expiry_batch_size: opts.expiry_batch_size(),
+			node_timeout: opts.node_timeout(),
+			read_only: opts.read_only,
+			plans: PlanCache::new(opts.plan_cache_size()),
+			group_memory_limit: opts.group_memory_limit(),
+			statement_memory_limit: opts.statement_memory_limit,
+			checksums: opts.checksums,
+			queries: Arc::default(),
+			slow_queries: SlowQueries::default(),
+			sequences: Sequences::default(),
+			scrub_cursor: Mutex::new(None),
+			scrub_ts: Mutex::new(0),
+			archive_cursors: Mutex::new(HashMap::new()),
 		})
 	}
 
@@ -281,6 +475,14 @@ impl Datastore {
 		self
 	}
 
+	/// Specify whether this Datastore should run in deterministic mode, resolving
+	/// all nondeterministic functions to literal values before each statement is
+	/// processed, so that the resulting changes can be replayed deterministically
+	pub fn with_deterministic_mode(mut self, deterministic: bool) -> Self {
+		self.deterministic = deterministic;
+		self
+	}
+
 	/// Specify whether this datastore should enable live query notifications
 	pub fn with_notifications(mut self) -> Self {
 		self.notification_channel = Some(channel::bounded(100));
@@ -299,6 +501,12 @@ impl Datastore {
 		self
 	}
 
+	/// Log the statements which take longer than a threshold, to recommend indexes
+	pub fn with_slow_query_threshold(mut self, duration: Option<Duration>) -> Self {
+		self.slow_queries = SlowQueries::new(duration);
+		self
+	}
+
 	/// Set whether authentication is enabled for this Datastore
 	pub fn with_auth_enabled(mut self, enabled: bool) -> Self {
 		self.auth_enabled = enabled;
@@ -316,6 +524,36 @@ impl Datastore {
 		self.auth_enabled
 	}
 
+	/// Is this Datastore opened in read-only mode?
+	pub fn is_read_only(&self) -> bool {
+		self.read_only
+	}
+
+	/// The limits on the queries which are parsed by this Datastore
+	pub fn parse_limits(&self) -> &ParseLimits {
+		self.capabilities.parse_limits()
+	}
+
+	/// The number of records whose index entries are written in each transaction, if batched
+	pub(crate) fn index_batch_size(&self) -> Option<u32> {
+		self.index_batch_size
+	}
+
+	/// The number of bytes which each statement can hold in memory, if limited
+	pub(crate) fn statement_memory_limit(&self) -> Option<usize> {
+		self.statement_memory_limit
+	}
+
+	/// The statements which are currently being executed on this Datastore
+	pub(crate) fn queries(&self) -> &RunningQueries {
+		&self.queries
+	}
+
+	/// The recent statements which took longer than the slow query threshold
+	pub(crate) fn slow_queries(&self) -> &SlowQueries {
+		&self.slow_queries
+	}
+
 	/// Setup the initial credentials
 	pub async fn setup_initial_creds(&self, creds: Root<'_>) -> Result<(), Error> {
 		// Start a new writeable transaction
@@ -362,6 +600,10 @@ impl Datastore {
 	// ability.
 	pub async fn bootstrap(&self) -> Result<(), Error> {
 		trace!("Bootstrapping {}", self.id);
+		// A read-only datastore can not register this node
+		if self.read_only {
+			return Ok(());
+		}
 		let mut tx = self.transaction(true, false).await?;
 		let now = tx.clock();
 		let archived = match self.register_remove_and_archive(&mut tx, &self.id, now).await {
@@ -414,9 +656,71 @@ impl Datastore {
 			error!("Error bootstrapping sweep phase: {:?}", err);
 			return Err(Error::Tx(format!("Error bootstrapping sweep phase: {:?}", err)));
 		}
+		// Check the consistency of the datastore
+		let report = self.recover().await?;
+		if !report.is_clean() {
+			warn!("Recovered the datastore on startup: {report}");
+		}
 		Ok(())
 	}
 
+	/// Runs randomized, interleaved transactions against the datastore, and checks that
+	/// the committed transactions were snapshot isolated, reporting any write skew, and
+	/// that concurrent writes to an indexed table left the index consistent with its records.
+	///
+	/// The keys and the table which are written are removed once checked. This is
+	/// intended for validating a storage backend, and not for a datastore in use.
+	pub async fn check_isolation(&self, cfg: IsolationOptions) -> Result<IsolationReport, Error> {
+		// A read-only datastore can not be written to
+		if self.read_only {
+			return Err(Error::DsReadonly);
+		}
+		isolation::check(self, cfg).await
+	}
+
+	/// Checks the consistency of the datastore, and recovers from any interrupted operation.
+	///
+	/// The node and table metadata is validated, live queries registered on unknown nodes
+	/// or tables are removed, and incomplete background index builds are rolled back if
+	/// their index no longer exists. The other incomplete builds are only reported, as they
+	/// are resumed in the background by the next tick. Views whose groups were created
+	/// without any auxiliary state are rebuilt. This is run when bootstrapping.
+	pub async fn recover(&self) -> Result<RecoveryReport, Error> {
+		// A read-only datastore can not be recovered
+		if self.read_only {
+			return Ok(RecoveryReport::default());
+		}
+		let mut tx = self.transaction(true, false).await?;
+		let res = match recovery::check(&mut tx).await {
+			Ok(report) => recovery::stale_views(&mut tx).await.map(|views| (report, views)),
+			Err(e) => Err(e),
+		};
+		let (mut report, views) = match res {
+			Ok(v) => {
+				tx.commit().await?;
+				v
+			}
+			Err(e) => {
+				tx.cancel().await?;
+				return Err(e);
+			}
+		};
+		// Rebuild the views which have no auxiliary state, by defining them again
+		for (ns, db, mut tb) in views {
+			report.rebuilt_views.push(format!("{ns}.{db}.{}", tb.name));
+			tb.if_not_exists = false;
+			let sess = Session::owner().with_ns(&ns).with_db(&db);
+			let ast = Query(Statements(vec![Statement::Define(DefineStatement::Table(tb))]));
+			for res in self.process(ast, &sess, None).await? {
+				res.result?;
+			}
+		}
+		for v in &report.invalid_keys {
+			error!("Invalid metadata found in the datastore at key {v}");
+		}
+		Ok(report)
+	}
+
 	// Node registration + "mark" stage of mark-and-sweep gc
 	pub async fn register_remove_and_archive(
 		&self,
@@ -611,10 +915,39 @@ impl Datastore {
 	// It is handy for testing, because it allows you to specify the timestamp,
 	// without depending on a system clock.
 	pub async fn tick_at(&self, ts: u64) -> Result<(), Error> {
+		// A read-only datastore has no maintenance to perform
+		if self.read_only {
+			return Ok(());
+		}
+		// The heartbeat is recorded first, so that this node is a
+		// member of the cluster when the background tasks are claimed
+		self.tick_cluster(ts).await?;
 		self.save_timestamp_for_versionstamp(ts).await?;
 		self.garbage_collect_stale_change_feeds(ts).await?;
+		self.spawn_index_builds().await?;
+		self.expire_records(ts).await?;
+		self.archive_records().await?;
+		self.scrub_tick(ts).await?;
 		// TODO Add LQ GC
-		// TODO Add Node GC?
+		Ok(())
+	}
+
+	// tick_cluster records a heartbeat for this node, and takes over the live queries
+	// of the nodes which have not sent any heartbeat within the node timeout.
+	async fn tick_cluster(&self, ts: u64) -> Result<(), Error> {
+		let now = Timestamp {
+			value: ts.saturating_mul(1000),
+		};
+		let expiry = Timestamp {
+			value: now.value.saturating_sub(self.node_timeout.as_millis() as u64),
+		};
+		let mut tx = self.transaction(true, false).await?;
+		cluster::heartbeat(&mut tx, self.id.0, now).await?;
+		let reassigned = cluster::reassign(&mut tx, self.id.0, &expiry).await?;
+		tx.commit().await?;
+		if !reassigned.is_empty() {
+			info!("Took over {} live queries from dead nodes", reassigned.len());
+		}
 		Ok(())
 	}
 
@@ -645,13 +978,407 @@ impl Datastore {
 		Ok(())
 	}
 
+	// build_indexes builds every index defined with the CONCURRENTLY option which is not yet complete,
+	// returning once the builds claimed by this node have completed or failed.
+	// Each batch of records is indexed in its own transaction, so the tables remain writable.
+	pub async fn build_indexes(&self) -> Result<(), Error> {
+		// A read-only datastore can not build indexes
+		if self.read_only {
+			return Ok(());
+		}
+		let builder = self.index_builder();
+		for (ns, db, ix) in self.claim_index_builds().await? {
+			// An index which is already being built by a background task is left to the task
+			if let Some(_building) = self.start_build(&ns, &db, &ix) {
+				builder.build(&ns, &db, &ix).await;
+			}
+		}
+		Ok(())
+	}
+
+	// spawn_index_builds starts each index build claimed by this node on its own task,
+	// so that the other maintenance tasks of the tick do not wait for the indexes to be built.
+	// An index which is still being built from a previous tick is not started again.
+	async fn spawn_index_builds(&self) -> Result<(), Error> {
+		for (ns, db, ix) in self.claim_index_builds().await? {
+			let Some(building) = self.start_build(&ns, &db, &ix) else {
+				continue;
+			};
+			let builder = self.index_builder();
+			let task = async move {
+				builder.build(&ns, &db, &ix).await;
+				drop(building);
+			};
+			#[cfg(not(target_arch = "wasm32"))]
+			tokio::spawn(task);
+			#[cfg(target_arch = "wasm32")]
+			wasm_bindgen_futures::spawn_local(task);
+		}
+		Ok(())
+	}
+
+	// claim_index_builds finds the indexes defined with the CONCURRENTLY option which are not yet complete,
+	// and claims the builds which are not being run by another node of the cluster, taking over the builds
+	// of the nodes which have not sent a heartbeat within the node timeout. Returns the claimed builds.
+	async fn claim_index_builds(
+		&self,
+	) -> Result<Vec<(String, String, DefineIndexStatement)>, Error> {
+		// Find the indexes which are being built
+		let mut tx = self.transaction(false, false).await?;
+		let mut pending = Vec::new();
+		for ns in tx.all_ns().await?.iter() {
+			let ns = ns.name.as_str();
+			for db in tx.all_db(ns).await?.iter() {
+				let db = db.name.as_str();
+				for tb in tx.all_tb(ns, db).await?.iter() {
+					for ix in tx.all_tb_indexes(ns, db, tb.name.as_str()).await?.iter() {
+						if ix.concurrently
+							&& BuildingState::get(&mut tx, ns, db, ix)
+								.await?
+								.is_some_and(|s| !s.is_failed())
+						{
+							pending.push((ns.to_owned(), db.to_owned(), ix.clone()));
+						}
+					}
+				}
+			}
+		}
+		tx.cancel().await?;
+		// Claim the builds which are not being run by another node of the cluster,
+		// taking over the builds of the nodes whose lease has expired
+		let mut tx = self.transaction(true, false).await?;
+		let expiry = tx.clock().value.saturating_sub(self.node_timeout.as_millis() as u64);
+		let alive: Vec<_> = cluster::membership(&mut tx)
+			.await?
+			.into_iter()
+			.filter(|n| n.last_seen >= expiry)
+			.map(|n| n.id)
+			.collect();
+		let mut claimed = Vec::new();
+		for (ns, db, ix) in pending {
+			if builder::claim(&mut tx, &ns, &db, &ix, self.id.0, &alive).await? {
+				claimed.push((ns, db, ix));
+			}
+		}
+		tx.commit().await?;
+		Ok(claimed)
+	}
+
+	// start_build marks an index as being built on this node, until the returned value is dropped.
+	// Returns `None` if the index is already being built on this node.
+	fn start_build(&self, ns: &str, db: &str, ix: &DefineIndexStatement) -> Option<Building> {
+		let key: Key = Ib::new(ns, db, &ix.what, &ix.name).into();
+		let mut builds = self.index_builds.lock().ok()?;
+		if !builds.insert(key.clone()) {
+			return None;
+		}
+		Some(Building {
+			builds: self.index_builds.clone(),
+			key,
+		})
+	}
+
+	// index_builder returns a handle which builds indexes without borrowing the datastore
+	fn index_builder(&self) -> IndexBuilder {
+		IndexBuilder {
+			tr: self.transactor(),
+			id: self.id.0,
+			capabilities: self.capabilities.clone(),
+			batch: self.index_batch_size.unwrap_or(PROCESSOR_BATCH_SIZE),
+			workers: self.index_workers,
+			permits: self.index_permits.clone(),
+			queries: self.queries.clone(),
+		}
+	}
+
+	// expire_records deletes the records of the tables defined with EXPIRE AFTER whose expiry time has passed.
+	// The records are deleted with a DELETE statement, so that events and live queries are processed.
+	// The expired records of each database are found and deleted within the same transaction. A database
+	// whose expired records can not be deleted is skipped, so that the other databases are still processed.
+	pub async fn expire_records(&self, ts: u64) -> Result<(), Error> {
+		// A read-only datastore can not delete records
+		if self.read_only {
+			return Ok(());
+		}
+		let now = ts.saturating_mul(1000);
+		// Find the databases with tables which expire their records
+		let mut tx = self.transaction(false, false).await?;
+		let mut pending = Vec::new();
+		for ns in tx.all_ns().await?.iter() {
+			let ns = ns.name.as_str();
+			for db in tx.all_db(ns).await?.iter() {
+				let db = db.name.as_str();
+				if tx.all_tb(ns, db).await?.iter().any(|tb| tb.expire.is_some()) {
+					pending.push((ns.to_owned(), db.to_owned()));
+				}
+			}
+		}
+		tx.cancel().await?;
+		// Delete the expired records of each database
+		for (ns, db) in pending {
+			let opt = Options::default()
+				.with_id(self.id.0)
+				.with_ns(Some(ns.as_str().into()))
+				.with_db(Some(db.as_str().into()))
+				.with_auth(Arc::new(Auth::for_root(Role::Owner)))
+				.with_strict(self.strict);
+			// Buffer the live query notifications until the deletion is committed
+			let (send, recv) = channel::unbounded();
+			let opt = opt.new_with_sender(send);
+			let mut ctx = Context::default();
+			ctx.add_capabilities(self.capabilities.clone());
+			if let Err(e) = self.expire_database(&ctx, &opt, now).await {
+				error!("Error expiring the records of {ns}/{db}: {e}");
+				continue;
+			}
+			// Send the notifications of the committed deletion
+			if let Some((chn, _)) = &self.notification_channel {
+				while let Ok(v) = recv.try_recv() {
+					let _ = chn.send(v).await;
+				}
+			}
+		}
+		Ok(())
+	}
+
+	// expire_database deletes the expired records of a database in a new transaction
+	async fn expire_database(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		now: u64,
+	) -> Result<(), Error> {
+		let txn = Arc::new(Mutex::new(self.transaction(true, false).await?));
+		match Self::expire_batch(ctx, opt, &txn, now, self.expiry_batch_size).await {
+			Ok(_) => txn.lock().await.commit().await,
+			Err(e) => {
+				txn.lock().await.cancel().await?;
+				Err(e)
+			}
+		}
+	}
+
+	// expire_batch deletes the records of a database whose expiry time has passed. The expiry time of
+	// each record is checked again, as it is reset each time the record is written.
+	async fn expire_batch(
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &crate::dbs::Transaction,
+		now: u64,
+		limit: u32,
+	) -> Result<(), Error> {
+		let (ns, db) = (opt.ns(), opt.db());
+		let mut rids = Vec::new();
+		let mut run = txn.lock().await;
+		for tb in run.all_tb(ns, db).await?.iter() {
+			if tb.expire.is_none() {
+				continue;
+			}
+			let tb = tb.name.as_str();
+			let beg = crate::key::table::et::prefix(ns, db, tb);
+			let end = crate::key::table::et::suffix(ns, db, tb, now);
+			for (k, _) in run.scan(beg..end, limit).await? {
+				let et = crate::key::table::et::Et::decode(&k)?;
+				let key = crate::key::table::ex::new(ns, db, tb, &et.id);
+				match run.get(key).await? {
+					Some(v) if v.as_slice() == et.ts.to_be_bytes() => {
+						rids.push(Value::from(sql::Thing::from((tb, et.id))));
+					}
+					// The record was written again, or deleted, since this expiry time was set
+					_ => run.del(k).await?,
+				}
+			}
+		}
+		drop(run);
+		if rids.is_empty() {
+			return Ok(());
+		}
+		trace!("Expiring {} records in {ns}/{db}", rids.len());
+		let stm = sql::statements::DeleteStatement {
+			what: sql::Values(rids),
+			..Default::default()
+		};
+		stm.compute(ctx, opt, txn, None).await?;
+		Ok(())
+	}
+
+	// archive_records moves the records of the tables defined with ARCHIVE WHERE which match the archive condition
+	// into the compressed archive key range of the table, where they can still be selected WITH ARCHIVE.
+	// Each call checks the next batch of records of every table, continuing from where the previous call left
+	// off, and wrapping around to the start of the table once all records have been checked. The records are
+	// removed with a DELETE statement without processing table events, so that indexes and live queries are kept
+	// up to date.
+	pub async fn archive_records(&self) -> Result<(), Error> {
+		// A read-only datastore can not move records
+		if self.read_only {
+			return Ok(());
+		}
+		// Find the tables with an archive condition
+		let mut tx = self.transaction(false, false).await?;
+		let mut pending = Vec::new();
+		for ns in tx.all_ns().await?.iter() {
+			let ns = ns.name.as_str();
+			for db in tx.all_db(ns).await?.iter() {
+				let db = db.name.as_str();
+				for tb in tx.all_tb(ns, db).await?.iter() {
+					if let Some(cond) = &tb.archive {
+						pending.push((
+							ns.to_owned(),
+							db.to_owned(),
+							tb.name.to_raw(),
+							cond.clone(),
+						));
+					}
+				}
+			}
+		}
+		tx.cancel().await?;
+		// Forget the cursors of the tables which are no longer archived
+		let mut cursors = self.archive_cursors.lock().await;
+		cursors.retain(|k, _| {
+			pending.iter().any(|(ns, db, tb, _)| (ns, db, tb) == (&k.0, &k.1, &k.2))
+		});
+		// Archive the next batch of records of each table
+		for (ns, db, tb, cond) in pending {
+			let opt = Options::default()
+				.with_id(self.id.0)
+				.with_ns(Some(ns.as_str().into()))
+				.with_db(Some(db.as_str().into()))
+				.with_auth(Arc::new(Auth::for_root(Role::Owner)));
+			let mut ctx = Context::default();
+			ctx.add_capabilities(self.capabilities.clone());
+			let cursor = (ns, db, tb);
+			let beg = match cursors.get(&cursor) {
+				Some(k) => k.clone(),
+				None => crate::key::thing::prefix(&cursor.0, &cursor.1, &cursor.2),
+			};
+			let end = crate::key::thing::suffix(&cursor.0, &cursor.1, &cursor.2);
+			let txn = Arc::new(Mutex::new(self.transaction(true, false).await?));
+			let res = txn.lock().await.scan(beg..end, PROCESSOR_BATCH_SIZE).await;
+			let res = match res {
+				Ok(res) => res,
+				Err(e) => {
+					txn.lock().await.cancel().await?;
+					return Err(e);
+				}
+			};
+			match Self::archive_batch(&ctx, &opt, &txn, &cond, &res).await {
+				Ok(_) => txn.lock().await.commit().await?,
+				Err(e) => {
+					txn.lock().await.cancel().await?;
+					return Err(e);
+				}
+			}
+			// Continue from the last record of the batch
+			match res.last() {
+				Some((k, _)) if res.len() == PROCESSOR_BATCH_SIZE as usize => {
+					let mut k = k.clone();
+					k.push(0x00);
+					cursors.insert(cursor, k);
+				}
+				_ => {
+					cursors.remove(&cursor);
+				}
+			}
+		}
+		Ok(())
+	}
+
+	// scrub_tick scrubs the next batch of records, unless a batch was scrubbed within the scrub interval,
+	// so that the background task does not compete with the queries for the storage engine on each tick.
+	async fn scrub_tick(&self, ts: u64) -> Result<(), Error> {
+		let mut last = self.scrub_ts.lock().await;
+		if ts < last.saturating_add(SCRUB_INTERVAL) {
+			return Ok(());
+		}
+		*last = ts;
+		self.scrub_records().await?;
+		Ok(())
+	}
+
+	// scrub_records verifies the checksums of the next batch of stored records, continuing from where the
+	// previous call left off, and wrapping around to the start of the keyspace once all keys have been verified.
+	// The keys of any corrupt records are logged and returned, so that they can be restored from a backup.
+	pub async fn scrub_records(&self) -> Result<Vec<String>, Error> {
+		// Records only have a checksum when checksums are enabled
+		if !self.checksums {
+			return Ok(vec![]);
+		}
+		let mut cursor = self.scrub_cursor.lock().await;
+		let beg = cursor.clone().unwrap_or_else(|| b"/".to_vec());
+		let end = b"/\xff".to_vec();
+		let mut tx = self.transaction(false, false).await?;
+		let res = tx.scan_unverified(beg..end, SCRUB_BATCH_SIZE).await;
+		tx.cancel().await?;
+		let res = res?;
+		// Verify the records of the batch
+		let mut corrupt = Vec::new();
+		for (k, v) in res.iter() {
+			if let Err(Error::CorruptValue {
+				key,
+			}) = checksum::verify(k, v.clone(), true)
+			{
+				error!("The value stored at key {key} failed its checksum verification");
+				corrupt.push(key);
+			}
+		}
+		// Continue from the last key of the batch
+		*cursor = match res.last() {
+			Some((k, _)) if res.len() == SCRUB_BATCH_SIZE as usize => {
+				let mut k = k.clone();
+				k.push(0x00);
+				Some(k)
+			}
+			_ => None,
+		};
+		Ok(corrupt)
+	}
+
+	// archive_batch moves the records of a batch which match the archive condition into the archive.
+	async fn archive_batch(
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &crate::dbs::Transaction,
+		cond: &sql::Cond,
+		res: &[(Key, Val)],
+	) -> Result<(), Error> {
+		let mut rids = Vec::new();
+		for (k, v) in res.iter() {
+			let key: crate::key::thing::Thing = k.into();
+			let mut val: Value = v.into();
+			let rid = sql::Thing::from((key.tb, key.id));
+			crate::doc::resolve(opt, txn, &rid, &mut val).await?;
+			let doc = CursorDoc::new(None, Some(&rid), None, &val);
+			if cond.compute(ctx, opt, txn, Some(&doc)).await?.is_truthy() {
+				let key = crate::key::table::ar::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
+				txn.lock().await.set(key, archive::encode(&val)?).await?;
+				rids.push(Value::from(rid));
+			}
+		}
+		if rids.is_empty() {
+			return Ok(());
+		}
+		trace!("Archiving {} records in {}/{}", rids.len(), opt.ns(), opt.db());
+		// Remove the archived records from the table
+		let stm = sql::statements::DeleteStatement {
+			what: sql::Values(rids),
+			..Default::default()
+		};
+		stm.compute(ctx, &opt.new_with_events(false), txn, None).await?;
+		Ok(())
+	}
+
 	// Creates a heartbeat entry for the member indicating to the cluster
 	// that the node is alive.
 	// This is the preferred way of creating heartbeats inside the database, so try to use this.
 	pub async fn heartbeat(&self) -> Result<(), Error> {
+		// A read-only datastore does not take part in the cluster
+		if self.read_only {
+			return Ok(());
+		}
 		let mut tx = self.transaction(true, false).await?;
 		let timestamp = tx.clock();
-		self.heartbeat_full(&mut tx, timestamp, self.id.clone()).await?;
+		cluster::heartbeat(&mut tx, self.id.0, timestamp).await?;
 		tx.commit().await
 	}
 
@@ -668,6 +1395,37 @@ impl Datastore {
 		tx.set_hb(timestamp, node_id.0).await
 	}
 
+	/// The id of the node which this datastore registers in the cluster
+	pub fn node_id(&self) -> uuid::Uuid {
+		self.id.0
+	}
+
+	/// Returns the membership of every node registered in the cluster,
+	/// including when each node was last seen and the live queries it owns.
+	pub async fn cluster_membership(&self) -> Result<Vec<NodeMembership>, Error> {
+		let mut tx = self.transaction(false, false).await?;
+		let res = cluster::membership(&mut tx).await;
+		tx.cancel().await?;
+		res
+	}
+
+	/// Takes over the live queries of the nodes which have not sent a heartbeat
+	/// within the given timeout, and removes those nodes from the cluster.
+	/// Returns the ids of the live queries which are now owned by this node.
+	pub async fn reassign_dead_nodes(&self, timeout: Duration) -> Result<Vec<uuid::Uuid>, Error> {
+		// A read-only datastore does not take part in the cluster
+		if self.read_only {
+			return Ok(vec![]);
+		}
+		let mut tx = self.transaction(true, false).await?;
+		let expiry = Timestamp {
+			value: tx.clock().value.saturating_sub(timeout.as_millis() as u64),
+		};
+		let res = cluster::reassign(&mut tx, self.id.0, &expiry).await?;
+		tx.commit().await?;
+		Ok(res)
+	}
+
 	// -----
 	// End cluster helpers, storage functions here
 	// -----
@@ -687,50 +1445,23 @@ impl Datastore {
 	/// }
 	/// ```
 	pub async fn transaction(&self, write: bool, lock: bool) -> Result<Transaction, Error> {
-		#![allow(unused_variables)]
-		let inner = match &self.inner {
-			#[cfg(feature = "kv-mem")]
-			Inner::Mem(v) => {
-				let tx = v.transaction(write, lock).await?;
-				super::tx::Inner::Mem(tx)
-			}
-			#[cfg(feature = "kv-rocksdb")]
-			Inner::RocksDB(v) => {
-				let tx = v.transaction(write, lock).await?;
-				super::tx::Inner::RocksDB(tx)
-			}
-			#[cfg(feature = "kv-speedb")]
-			Inner::SpeeDB(v) => {
-				let tx = v.transaction(write, lock).await?;
-				super::tx::Inner::SpeeDB(tx)
-			}
-			#[cfg(feature = "kv-indxdb")]
-			Inner::IndxDB(v) => {
-				let tx = v.transaction(write, lock).await?;
-				super::tx::Inner::IndxDB(tx)
-			}
-			#[cfg(feature = "kv-tikv")]
-			Inner::TiKV(v) => {
-				let tx = v.transaction(write, lock).await?;
-				super::tx::Inner::TiKV(tx)
-			}
-			#[cfg(feature = "kv-fdb")]
-			Inner::FoundationDB(v) => {
-				let tx = v.transaction(write, lock).await?;
-				super::tx::Inner::FoundationDB(tx)
-			}
-			#[allow(unreachable_patterns)]
-			_ => unreachable!(),
-		};
+		// Writeable transactions are not allowed in read-only mode
+		if write && self.read_only {
+			return Err(Error::DsReadonly);
+		}
+		self.transactor().transaction(write, lock).await
+	}
 
-		#[allow(unreachable_code)]
-		Ok(Transaction {
-			inner,
-			cache: super::cache::Cache::default(),
-			cf: cf::Writer::new(),
-			write_buffer: HashMap::new(),
+	/// Get a handle which starts new transactions on this datastore,
+	/// and which can be held without borrowing the datastore
+	fn transactor(&self) -> Transactor {
+		Transactor {
+			inner: self.inner.clone(),
 			vso: self.versionstamp_oracle.clone(),
-		})
+			index_cache_size: self.index_cache_size,
+			checksums: self.checksums,
+			sequences: self.sequences.clone(),
+		}
 	}
 
 	/// Parse and execute an SQL query
@@ -757,9 +1488,236 @@ impl Datastore {
 		vars: Variables,
 	) -> Result<Vec<Response>, Error> {
 		// Parse the SQL query text
-		let ast = sql::parse(txt)?;
+		let ast = sql::parse_with_limits(txt, self.capabilities.parse_limits())?;
+		// Process the AST
+		let mut res = self.process(ast, sess, vars).await?;
+		// Locate the statements which failed
+		locate(txt, &mut res);
+		Ok(res)
+	}
+
+	/// Parse and execute an SQL query, returning any warnings about the query
+	/// alongside the query responses
+	///
+	/// ```rust,no_run
+	/// use surrealdb::kvs::Datastore;
+	/// use surrealdb::err::Error;
+	/// use surrealdb::dbs::Session;
+	///
+	/// #[tokio::main]
+	/// async fn main() -> Result<(), Error> {
+	///     let ds = Datastore::new("memory").await?;
+	///     let ses = Session::owner().with_ns("test").with_db("test");
+	///     let ast = "SELECT * FROM person WHERE id = 'person:tobie';";
+	///     let (res, warnings) = ds.execute_with_warnings(ast, &ses, None).await?;
+	///     for warning in warnings {
+	///         eprintln!("{warning}");
+	///     }
+	///     Ok(())
+	/// }
+	/// ```
+	#[instrument(level = "debug", skip_all)]
+	pub async fn execute_with_warnings(
+		&self,
+		txt: &str,
+		sess: &Session,
+		vars: Variables,
+	) -> Result<(Vec<Response>, Vec<RenderedError>), Error> {
+		// Parse the SQL query text
+		let mut warnings = Vec::new();
+		let ast = self.capabilities.parse_limits().apply(txt, |txt| {
+			let (ast, found) = sql::parse_with_warnings(txt)?;
+			warnings = found;
+			Ok(ast)
+		})?;
 		// Process the AST
-		self.process(ast, sess, vars).await
+		let mut res = self.process(ast, sess, vars).await?;
+		// Locate the statements which failed
+		locate(txt, &mut res);
+		Ok((res, warnings))
+	}
+
+	/// Parse an SQL query, reusing the parsed query of a previous call
+	/// with the same query text, if the schema has not changed since.
+	/// The returned query is shared with the cache, and can be executed
+	/// repeatedly, with different query parameters, using [`Datastore::process`].
+	///
+	/// ```rust,no_run
+	/// use surrealdb::kvs::Datastore;
+	/// use surrealdb::err::Error;
+	/// use surrealdb::dbs::Session;
+	/// use surrealdb::sql::{Query, Value};
+	/// use std::collections::BTreeMap;
+	///
+	/// #[tokio::main]
+	/// async fn main() -> Result<(), Error> {
+	///     let ds = Datastore::new("memory").await?;
+	///     let ses = Session::owner().with_ns("test").with_db("test");
+	///     for age in [18, 21] {
+	///         let ast = ds.prepare("SELECT * FROM person WHERE age > $age")?;
+	///         let vars = BTreeMap::from([("age".to_string(), Value::from(age))]);
+	///         let res = ds.process(Query::clone(&ast), &ses, Some(vars)).await?;
+	///     }
+	///     Ok(())
+	/// }
+	/// ```
+	pub fn prepare(&self, txt: &str) -> Result<Arc<Query>, Error> {
+		self.plans.get(txt, self.capabilities.parse_limits())
+	}
+
+	/// Invalidate the parsed queries of prepared statements after a schema change
+	pub(crate) fn schema_changed(&self) {
+		self.plans.invalidate()
+	}
+
+	/// Register a named query, which any session, including anonymous
+	/// sessions, can then execute by name using [`Datastore::execute_query`].
+	/// The query is stored in the namespace and database of the registering
+	/// session, and runs with the authorization of the registering user,
+	/// which is checked again each time the query is executed. The query
+	/// only accepts the declared parameters, each of which is coerced to
+	/// its declared type before the query is run. A query which has been
+	/// registered by another user can not be replaced.
+	///
+	/// ```rust,no_run
+	/// use surrealdb::kvs::Datastore;
+	/// use surrealdb::err::Error;
+	/// use surrealdb::dbs::Session;
+	/// use surrealdb::sql::{Kind, Value};
+	/// use std::collections::BTreeMap;
+	///
+	/// #[tokio::main]
+	/// async fn main() -> Result<(), Error> {
+	///     let ds = Datastore::new("memory").await?.with_auth_enabled(true);
+	///     let ses = Session::owner().with_ns("test").with_db("test");
+	///     let sql = "SELECT * FROM article WHERE published = true LIMIT $limit";
+	///     let params = BTreeMap::from([("limit".to_string(), Kind::Int)]);
+	///     ds.register_query("articles", sql, params, &ses).await?;
+	///     let ses = Session::default().with_ns("test").with_db("test");
+	///     let vars = BTreeMap::from([("limit".to_string(), Value::from(10))]);
+	///     let res = ds.execute_query("articles", &ses, Some(vars)).await?;
+	///     Ok(())
+	/// }
+	/// ```
+	pub async fn register_query(
+		&self,
+		name: &str,
+		txt: &str,
+		params: BTreeMap<String, Kind>,
+		sess: &Session,
+	) -> Result<(), Error> {
+		// Check the namespace and database are selected
+		let ns = sess.ns.as_deref().ok_or(Error::NsEmpty)?;
+		let db = sess.db.as_deref().ok_or(Error::DbEmpty)?;
+		// Skip auth for Anonymous users if auth is disabled
+		let skip_auth = !self.is_auth_enabled() && sess.au.is_anon();
+		if !skip_auth {
+			sess.au.is_allowed(Action::Edit, &ResourceKind::Database.on_db(ns, db))?;
+		}
+		// Check the declared parameter names
+		if let Some(name) = params.keys().find(|k| PROTECTED_PARAM_NAMES.contains(&k.as_str())) {
+			return Err(Error::InvalidParam {
+				name: name.to_owned(),
+			});
+		}
+		// Parse the SQL query text
+		let query = sql::parse_with_limits(txt, self.capabilities.parse_limits())?;
+		let query = NamedQuery {
+			query,
+			params,
+			owner: sess.au.as_ref().clone(),
+		};
+		// Store the query, unless another user registered it
+		let key = crate::key::database::nq::new(ns, db, name);
+		let mut tx = self.transaction(true, false).await?;
+		if let Some(v) = tx.get(key.clone()).await? {
+			if !NamedQuery::from(v).is_owned_by(&query.owner) {
+				tx.cancel().await?;
+				return Err(Error::NqAlreadyExists {
+					value: name.to_owned(),
+				});
+			}
+		}
+		match tx.set(key, query).await {
+			Ok(_) => tx.commit().await,
+			Err(e) => {
+				tx.cancel().await?;
+				Err(e)
+			}
+		}
+	}
+
+	/// Remove a named query which was registered with [`Datastore::register_query`]
+	pub async fn unregister_query(&self, name: &str, sess: &Session) -> Result<(), Error> {
+		// Check the namespace and database are selected
+		let ns = sess.ns.as_deref().ok_or(Error::NsEmpty)?;
+		let db = sess.db.as_deref().ok_or(Error::DbEmpty)?;
+		// Only users who could register the query can remove it
+		let skip_auth = !self.is_auth_enabled() && sess.au.is_anon();
+		if !skip_auth {
+			sess.au.is_allowed(Action::Edit, &ResourceKind::Database.on_db(ns, db))?;
+		}
+		// Remove the stored query
+		let key = crate::key::database::nq::new(ns, db, name);
+		let mut tx = self.transaction(true, false).await?;
+		if !tx.exi(key.clone()).await? {
+			tx.cancel().await?;
+			return Err(Error::NqNotFound {
+				value: name.to_owned(),
+			});
+		}
+		match tx.del(key).await {
+			Ok(_) => tx.commit().await,
+			Err(e) => {
+				tx.cancel().await?;
+				Err(e)
+			}
+		}
+	}
+
+	/// Execute a named query which was registered with [`Datastore::register_query`]
+	/// in the namespace and database of the session
+	#[instrument(level = "debug", skip(self, sess, vars))]
+	pub async fn execute_query(
+		&self,
+		name: &str,
+		sess: &Session,
+		vars: Variables,
+	) -> Result<Vec<Response>, Error> {
+		// Check the namespace and database are selected
+		let ns = sess.ns.as_deref().ok_or(Error::NsEmpty)?;
+		let db = sess.db.as_deref().ok_or(Error::DbEmpty)?;
+		// Fetch the registered query and the current authorization of its owner
+		let mut tx = self.transaction(false, false).await?;
+		let res = match tx.get(crate::key::database::nq::new(ns, db, name)).await {
+			Ok(Some(v)) => {
+				let query = NamedQuery::from(v);
+				match query.authorize(&mut tx, ns, db).await {
+					Ok(au) => Ok((query, au)),
+					Err(e) => Err(e),
+				}
+			}
+			Ok(None) => Err(Error::NqNotFound {
+				value: name.to_owned(),
+			}),
+			Err(e) => Err(e),
+		};
+		tx.cancel().await?;
+		let (query, au) = res?;
+		// Check and coerce the specified parameters
+		let vars = query.check(vars.unwrap_or_default())?;
+		// Run as the registering user, from the calling client
+		let sess = Session {
+			au: Arc::new(au),
+			ns: sess.ns.clone(),
+			db: sess.db.clone(),
+			ip: sess.ip.clone(),
+			or: sess.or.clone(),
+			id: sess.id.clone(),
+			..Session::default()
+		};
+		// Process the registered query
+		self.process(query.query, &sess, Some(vars)).await
 	}
 
 	/// Execute a pre-parsed SQL query
@@ -804,9 +1762,13 @@ impl Datastore {
 			.with_live(sess.live())
 			.with_auth(sess.au.clone())
 			.with_strict(self.strict)
+			.with_deterministic(self.deterministic)
+			.with_group_limit(self.group_memory_limit)
 			.with_auth_enabled(self.auth_enabled);
+		// Merge the execution options of the session
+		let opt = sess.options().apply(opt);
 		// Create a new query executor
-		let mut exe = Executor::new(self);
+		let mut exe = Executor::new(self, sess.op.clone());
 		// Create a default context
 		let mut ctx = Context::default();
 		ctx.add_capabilities(self.capabilities.clone());
@@ -818,8 +1780,13 @@ impl Datastore {
 		if let Some(channel) = &self.notification_channel {
 			ctx.add_notifications(Some(&channel.0));
 		}
+		// Allow running statements to be listed and cancelled
+		ctx.set_queries(&self.queries);
+		ctx.set_slow_queries(&self.slow_queries);
 		// Start an execution context
-		let ctx = sess.context(ctx);
+		let mut ctx = sess.context(ctx);
+		// Set the unique id of this request
+		ctx.add_value("request_id", Value::from(Uuid::new_v4()));
 		// Store the query variables
 		let ctx = vars.attach(ctx)?;
 		// Process all statements
@@ -869,6 +1836,8 @@ impl Datastore {
 			.with_live(sess.live())
 			.with_auth(sess.au.clone())
 			.with_strict(self.strict)
+			.with_deterministic(self.deterministic)
+			.with_group_limit(self.group_memory_limit)
 			.with_auth_enabled(self.auth_enabled);
 		// Create a default context
 		let mut ctx = Context::default();
@@ -938,6 +1907,8 @@ impl Datastore {
 			.with_live(sess.live())
 			.with_auth(sess.au.clone())
 			.with_strict(self.strict)
+			.with_deterministic(self.deterministic)
+			.with_group_limit(self.group_memory_limit)
 			.with_auth_enabled(self.auth_enabled);
 		// Create a default context
 		let mut ctx = Context::default();
@@ -1002,6 +1973,19 @@ impl Datastore {
 		ns: String,
 		db: String,
 		chn: Sender<Vec<u8>>,
+	) -> Result<impl Future<Output = Result<(), Error>>, Error> {
+		self.export_with_options(sess, ns, db, ExportOptions::default(), chn).await
+	}
+
+	/// Performs a database export as SQL, of the tables and records selected by the options
+	#[instrument(level = "debug", skip(self, sess, cfg, chn))]
+	pub async fn export_with_options(
+		&self,
+		sess: &Session,
+		ns: String,
+		db: String,
+		cfg: ExportOptions,
+		chn: Sender<Vec<u8>>,
 	) -> Result<impl Future<Output = Result<(), Error>>, Error> {
 		// Skip auth for Anonymous users if auth is disabled
 		let skip_auth = !self.is_auth_enabled() && sess.au.is_anon();
@@ -1009,11 +1993,35 @@ impl Datastore {
 			sess.au.is_allowed(Action::View, &ResourceKind::Any.on_db(&ns, &db))?;
 		}
 		// Create a new readonly transaction
-		let mut txn = self.transaction(false, false).await?;
+		let txn = self.transaction(false, false).await?.enclose();
+		// Create the options for evaluating the record filters
+		let opt = Options::default()
+			.with_id(self.id.0)
+			.with_ns(Some(ns.as_str().into()))
+			.with_db(Some(db.as_str().into()))
+			.with_auth(sess.au.clone())
+			.with_strict(self.strict)
+			.with_deterministic(self.deterministic)
+			.with_auth_enabled(self.auth_enabled);
+		let capabilities = self.capabilities.clone();
+		// Scan each batch of records in a new transaction, if iterating stably
+		let stable = cfg.stable.then(|| self.transactor());
 		// Return an async export job
 		Ok(async move {
+			// Create the context for evaluating the record filters
+			let mut ctx = Context::default();
+			ctx.add_capabilities(capabilities);
+			let flt = ExportFilter {
+				cfg,
+				ctx,
+				opt,
+			};
 			// Process the export
-			txn.export(&ns, &db, chn).await?;
+			let res =
+				Transaction::export_with_filter(&txn, &ns, &db, &flt, stable.as_ref(), chn).await;
+			// The export transaction is only ever read
+			txn.lock().await.cancel().await?;
+			res?;
 			// Everything ok
 			Ok(())
 		})
@@ -1033,4 +2041,418 @@ impl Datastore {
 		// Execute the SQL import
 		self.execute(sql, sess, None).await
 	}
+
+	/// Performs a database import from SQL, according to the specified [`ImportOptions`]
+	///
+	/// The import is parsed and executed incrementally, one statement, or one
+	/// transaction, at a time. Errors are collected instead of stopping the import.
+	#[instrument(level = "debug", skip(self, sql, sess, cfg))]
+	pub async fn import_with_options(
+		&self,
+		sql: &str,
+		sess: &Session,
+		cfg: ImportOptions,
+	) -> Result<ImportProgress, Error> {
+		// Skip auth for Anonymous users if auth is disabled
+		let skip_auth = !self.is_auth_enabled() && sess.au.is_anon();
+		if !skip_auth {
+			sess.au.is_allowed(
+				Action::Edit,
+				&ResourceKind::Any.on_level(sess.au.level().to_owned()),
+			)?;
+		}
+		// Register the import so that it can be killed
+		let mut ctx = Context::default();
+		let _running = self.queries.register_operation(
+			OperationKind::Import,
+			sess.ns.as_deref(),
+			sess.db.as_deref(),
+			format!("IMPORT of {} bytes", sql.len()),
+			ctx.add_cancel(),
+		);
+		// The session is updated by any USE statements
+		let mut sess = sess.clone();
+		// The OPTION statements apply to the rest of the import
+		let mut opts = Vec::new();
+		// The statements which are executed together
+		let mut batch = Vec::new();
+		// The statements in the batch which are skipped if their record exists
+		let mut guards = Vec::new();
+		let mut transaction = false;
+		let mut progress = ImportProgress::default();
+		let mut offset = 0;
+		while let Some((stm, next)) = sql::parser::next_statement(sql, offset)? {
+			// Stop the import if it was killed
+			if ctx.is_cancelled() {
+				return Err(Error::QueryKilled);
+			}
+			progress.bytes = next;
+			offset = next;
+			match stm {
+				Statement::Option(_) => {
+					opts.push(stm);
+					continue;
+				}
+				Statement::Begin(_) => {
+					transaction = true;
+					batch.push(stm);
+					continue;
+				}
+				Statement::Commit(_) | Statement::Cancel(_) => {
+					transaction = false;
+					batch.push(stm);
+				}
+				Statement::Use(ref v) => {
+					if let Some(ns) = &v.ns {
+						sess.ns = Some(ns.to_owned());
+					}
+					if let Some(db) = &v.db {
+						sess.db = Some(db.to_owned());
+					}
+					batch.push(stm);
+				}
+				stm => {
+					let ns = sess.ns.as_deref().unwrap_or_default();
+					let db = sess.db.as_deref().unwrap_or_default();
+					// Check if the record already exists, within the import transaction
+					let stm = match cfg.record(&stm) {
+						Some(rid) => {
+							guards.push((batch.len(), rid.clone()));
+							ImportOptions::guard(rid, stm)
+						}
+						None => stm,
+					};
+					// Check if the definition already exists
+					if let Some(key) = cfg.definition_key(ns, db, &stm) {
+						if self.exists(key).await? {
+							continue;
+						}
+					}
+					batch.push(stm);
+				}
+			}
+			if !transaction {
+				self.import_batch(&sess, &cfg, &opts, &mut batch, &mut guards, &mut progress)
+					.await?;
+				cfg.notify(&progress);
+			}
+		}
+		// Execute any transaction which was not committed
+		if !batch.is_empty() {
+			self.import_batch(&sess, &cfg, &opts, &mut batch, &mut guards, &mut progress).await?;
+			cfg.notify(&progress);
+		}
+		Ok(progress)
+	}
+
+	/// Checks if a key exists, within its own read transaction
+	async fn exists(&self, key: Key) -> Result<bool, Error> {
+		let mut tx = self.transaction(false, false).await?;
+		let res = tx.exi(key).await?;
+		tx.cancel().await?;
+		Ok(res)
+	}
+
+	/// Executes a batch of imported statements, and records its progress
+	///
+	/// The guarded statements output `false` when their record already
+	/// exists, in which case they are not counted as executed statements.
+	async fn import_batch(
+		&self,
+		sess: &Session,
+		cfg: &ImportOptions,
+		opts: &[Statement],
+		batch: &mut Vec<Statement>,
+		guards: &mut Vec<(usize, sql::Thing)>,
+		progress: &mut ImportProgress,
+	) -> Result<(), Error> {
+		if batch.is_empty() {
+			return Ok(());
+		}
+		let skip = opts.len();
+		let mut stms = opts.to_vec();
+		stms.append(batch);
+		let guards = std::mem::take(guards);
+		for res in self.process(Query(Statements(stms)), sess, None).await? {
+			let guard = guards.iter().find(|(i, _)| skip + i == res.statement);
+			match (guard, res.result) {
+				// The record already existed, so the statement was skipped
+				(Some((_, rid)), Ok(Value::Bool(false))) => {
+					if cfg.records == RecordConflict::Error {
+						progress.errors.push(
+							Error::RecordExists {
+								thing: rid.to_string(),
+							}
+							.to_string(),
+						);
+					}
+				}
+				(_, Ok(_)) => progress.statements += 1,
+				(_, Err(e)) => {
+					progress.statements += 1;
+					progress.errors.push(e.to_string());
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// Applies change sets, as produced by a change feed, within a single transaction
+	///
+	/// Changes which are older than the last change applied to the same record
+	/// are resolved according to the specified [`ConflictPolicy`].
+	#[instrument(level = "debug", skip(self, sess, changes, policy))]
+	pub async fn apply_changes(
+		&self,
+		sess: &Session,
+		ns: &str,
+		db: &str,
+		changes: Vec<cf::ChangeSet>,
+		policy: ConflictPolicy,
+	) -> Result<(), Error> {
+		// Skip auth for Anonymous users if auth is disabled
+		let skip_auth = !self.is_auth_enabled() && sess.au.is_anon();
+		if !skip_auth {
+			sess.au.is_allowed(Action::Edit, &ResourceKind::Any.on_db(ns, db))?;
+		}
+		// Create a new query options
+		let opt = Options::default()
+			.with_id(self.id.0)
+			.with_ns(Some(ns.into()))
+			.with_db(Some(db.into()))
+			.with_auth(sess.au.clone())
+			.with_strict(self.strict)
+			.with_auth_enabled(self.auth_enabled);
+		// Create a default context
+		let mut ctx = Context::default();
+		// Set context capabilities
+		ctx.add_capabilities(self.capabilities.clone());
+		// Setup the notification channel
+		if let Some(channel) = &self.notification_channel {
+			ctx.add_notifications(Some(&channel.0));
+		}
+		// Start a new transaction
+		let txn = self.transaction(true, false).await?.enclose();
+		// Apply the change sets in order
+		for cs in changes {
+			if let Err(e) = cf::apply::apply(&ctx, &opt, &txn, cs, &policy).await {
+				txn.lock().await.cancel().await?;
+				return Err(e);
+			}
+		}
+		// Commit all of the changes
+		txn.lock().await.commit().await
+	}
+}
+
+/// Adds the part of the query source containing each failed statement to its response
+fn locate(txt: &str, res: &mut [Response]) {
+	if res.iter().all(|r| r.result.is_ok()) {
+		return;
+	}
+	// The query has already been parsed, so the spans are only found when needed
+	let query = match sql::parse_spanned(txt) {
+		Ok(v) => v,
+		Err(_) => return,
+	};
+	for r in res.iter_mut().filter(|r| r.result.is_err()) {
+		r.span = query.statements.get(r.statement).map(|v| Span::of_range(v.span.clone(), txt));
+	}
+}
+
+/// Marks an index as being built by a background task of a node, until it is dropped
+struct Building {
+	builds: Arc<std::sync::Mutex<HashSet<Key>>>,
+	key: Key,
+}
+
+impl Drop for Building {
+	fn drop(&mut self) {
+		if let Ok(mut v) = self.builds.lock() {
+			v.remove(&self.key);
+		}
+	}
+}
+
+/// Builds the indexes defined with the `CONCURRENTLY` option. This is moved onto the
+/// background task of each build, as it is held without borrowing the datastore.
+struct IndexBuilder {
+	tr: Transactor,
+	id: uuid::Uuid,
+	capabilities: Capabilities,
+	batch: u32,
+	workers: usize,
+	permits: Arc<Semaphore>,
+	queries: Arc<RunningQueries>,
+}
+
+impl IndexBuilder {
+	// build builds an index, and marks the build as failed if it could not be completed
+	async fn build(&self, ns: &str, db: &str, ix: &DefineIndexStatement) {
+		// A failed build does not stop the builds of the other indexes
+		if let Err(e) = self.build_index(ns, db, ix).await {
+			error!("Error building index {} on {ns}/{db}: {e}", ix.name);
+			if let Err(e) = self.fail_build(ns, db, ix, &e).await {
+				error!("Error stopping the build of index {} on {ns}/{db}: {e}", ix.name);
+			}
+		}
+	}
+
+	// build_index builds a single index defined with the CONCURRENTLY option, until every
+	// record has been indexed, or until the build is killed.
+	async fn build_index(
+		&self,
+		ns: &str,
+		db: &str,
+		ix: &DefineIndexStatement,
+	) -> Result<(), Error> {
+		let opt = Options::default()
+			.with_id(self.id)
+			.with_ns(Some(ns.into()))
+			.with_db(Some(db.into()))
+			.with_auth(Arc::new(Auth::for_root(Role::Owner)))
+			.with_batch(self.batch);
+		let mut ctx = Context::default();
+		ctx.add_capabilities(self.capabilities.clone());
+		// Register the build so that it can be killed
+		let _running = self.queries.register_operation(
+			OperationKind::Index,
+			Some(ns),
+			Some(db),
+			ix.to_string(),
+			ctx.add_cancel(),
+		);
+		// Split the records into a shard for each worker
+		let mut tx = self.tr.transaction(true, false).await?;
+		let shards = match builder::plan(&mut tx, ns, db, ix, self.workers).await {
+			Ok(v) => {
+				tx.commit().await?;
+				v
+			}
+			Err(e) => {
+				tx.cancel().await?;
+				return Err(e);
+			}
+		};
+		if shards > 1 {
+			// A failed shard stops the other shards of the build
+			let canceller = ctx.add_cancel();
+			// Index the shards in parallel, each on its own task on the runtime
+			let mut tasks = Vec::with_capacity(shards as usize);
+			for shard in 0..shards {
+				let tr = self.tr.clone();
+				let ctx = ctx.detach();
+				let opt = opt.clone();
+				let ix = ix.clone();
+				let permits = self.permits.clone();
+				let task = async move {
+					// Only build as many shards at once as there are index workers
+					let _permit = permits.acquire_owned().await;
+					build_shard(&tr, &ctx, &opt, &ix, shard).await
+				};
+				#[cfg(not(target_arch = "wasm32"))]
+				let task = tokio::spawn(task);
+				tasks.push(task);
+			}
+			let mut res = Ok(());
+			for task in tasks {
+				#[cfg(not(target_arch = "wasm32"))]
+				let out = task.await.unwrap_or_else(|e| Err(Error::Internal(e.to_string())));
+				#[cfg(target_arch = "wasm32")]
+				let out = task.await;
+				if let Err(e) = out {
+					canceller.cancel();
+					res = res.and(Err(e));
+				}
+			}
+			res?;
+			// A killed build is not resumed until the index is defined again
+			if ctx.is_cancelled() {
+				return Err(Error::QueryKilled);
+			}
+			// Merge the progress of the shards
+			let mut tx = self.tr.transaction(true, false).await?;
+			return match builder::finish_shards(&mut tx, ns, db, ix).await {
+				Ok(_) => tx.commit().await,
+				Err(e) => {
+					tx.cancel().await?;
+					Err(e)
+				}
+			};
+		}
+		let mut retries = 0;
+		loop {
+			// A killed build is not resumed until the index is defined again
+			if ctx.is_cancelled() {
+				return Err(Error::QueryKilled);
+			}
+			let txn = Arc::new(Mutex::new(self.tr.transaction(true, false).await?));
+			let done = match builder::build_batch(&ctx, &opt, &txn, ix).await {
+				Ok(done) => done,
+				Err(e) => {
+					txn.lock().await.cancel().await?;
+					return Err(e);
+				}
+			};
+			// A batch which conflicts with a concurrent write is indexed again
+			match txn.lock().await.commit().await {
+				Ok(_) if done => return Ok(()),
+				Ok(_) => retries = 0,
+				Err(e) if retries >= INDEX_BUILD_RETRIES => return Err(e),
+				Err(_) => retries += 1,
+			}
+		}
+	}
+
+	// fail_build marks an index build which was killed or failed, so that it is reported
+	// by the INFO statements, and is not resumed by any node until the index is defined again.
+	async fn fail_build(
+		&self,
+		ns: &str,
+		db: &str,
+		ix: &DefineIndexStatement,
+		err: &Error,
+	) -> Result<(), Error> {
+		let mut tx = self.tr.transaction(true, false).await?;
+		match BuildingState::fail(&mut tx, ns, db, ix, err.to_string()).await {
+			Ok(_) => tx.commit().await,
+			Err(e) => {
+				tx.cancel().await?;
+				Err(e)
+			}
+		}
+	}
+}
+
+// build_shard indexes the records of one shard of an index being built in parallel,
+// with each batch of records indexed in its own transaction.
+async fn build_shard(
+	tr: &Transactor,
+	ctx: &Context<'_>,
+	opt: &Options,
+	ix: &DefineIndexStatement,
+	shard: u32,
+) -> Result<(), Error> {
+	let mut retries = 0;
+	loop {
+		// Stop indexing the shard if the build was killed
+		if ctx.is_cancelled() {
+			return Ok(());
+		}
+		let txn = Arc::new(Mutex::new(tr.transaction(true, false).await?));
+		let done = match builder::build_shard_batch(ctx, opt, &txn, ix, shard).await {
+			Ok(done) => done,
+			Err(e) => {
+				txn.lock().await.cancel().await?;
+				return Err(e);
+			}
+		};
+		// A batch which conflicts with a concurrent write is indexed again
+		match txn.lock().await.commit().await {
+			Ok(_) if done => return Ok(()),
+			Ok(_) => retries = 0,
+			Err(e) if retries >= INDEX_BUILD_RETRIES => return Err(e),
+			Err(_) => retries += 1,
+		}
+	}
 }
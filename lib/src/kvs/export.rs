@@ -0,0 +1,212 @@
+use crate::ctx::Context;
+use crate::dbs::{Options, Transaction};
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::sql::{Cond, Thing, Value};
+use std::collections::HashMap;
+
+/// Configures which tables and records are included in a database export.
+///
+/// By default every table, and every record, is exported.
+///
+/// ```rust,no_run
+/// # use surrealdb::kvs::{Datastore, ExportOptions};
+/// # use surrealdb::dbs::Session;
+/// # use surrealdb::err::Error;
+/// # use surrealdb::sql::Cond;
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Error> {
+/// let ds = Datastore::new("memory").await?;
+/// let ses = Session::owner();
+/// let opts = ExportOptions::new()
+/// 	.with_excluded_tables(vec!["audit".to_owned()])
+/// 	.with_filter(Cond(surrealdb::sql::value("tenant = 'acme'")?));
+/// let (send, recv) = surrealdb::channel::new(1);
+/// let export = ds.export_with_options(&ses, "test".to_owned(), "test".to_owned(), opts, send).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct ExportOptions {
+	/// The only tables which are exported, or all tables if not specified
+	pub(crate) tables: Option<Vec<String>>,
+	/// The tables which are not exported
+	pub(crate) excluded_tables: Vec<String>,
+	/// The condition which the records of every table must match to be exported
+	pub(crate) filter: Option<Cond>,
+	/// The conditions which the records of specific tables must match to be exported
+	pub(crate) table_filters: HashMap<String, Cond>,
+	/// The number of records which are scanned at a time
+	pub(crate) batch_size: u32,
+	/// Whether each batch of records is scanned in its own transaction
+	pub(crate) stable: bool,
+}
+
+impl Default for ExportOptions {
+	fn default() -> Self {
+		Self {
+			tables: None,
+			excluded_tables: Vec::new(),
+			filter: None,
+			table_filters: HashMap::new(),
+			batch_size: 1000,
+			stable: false,
+		}
+	}
+}
+
+impl ExportOptions {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Only export the given tables
+	pub fn with_tables(mut self, tables: Vec<String>) -> Self {
+		self.tables = Some(tables);
+		self
+	}
+
+	/// Do not export the given tables
+	pub fn with_excluded_tables(mut self, tables: Vec<String>) -> Self {
+		self.excluded_tables = tables;
+		self
+	}
+
+	/// Only export the records which match the condition.
+	/// This applies to every table without a table specific filter.
+	pub fn with_filter(mut self, cond: Cond) -> Self {
+		self.filter = Some(cond);
+		self
+	}
+
+	/// Only export the records of a table which match the condition
+	pub fn with_table_filter(mut self, table: &str, cond: Cond) -> Self {
+		self.table_filters.insert(table.to_owned(), cond);
+		self
+	}
+
+	/// Set the number of records which are scanned at a time
+	pub fn with_batch_size(mut self, size: u32) -> Self {
+		self.batch_size = size.max(1);
+		self
+	}
+
+	/// Scan each batch of records in its own transaction, continuing after the key of the
+	/// last record which was exported, instead of scanning every record in one transaction.
+	///
+	/// Every record which exists for the length of the export is then exported exactly once,
+	/// even on a backend which can not hold a snapshot, or a long-running transaction, for the
+	/// length of the export. A record which is written while the export runs is exported in
+	/// its state when its batch is scanned, or not at all if it is written behind the cursor.
+	pub fn with_stable_iteration(mut self, stable: bool) -> Self {
+		self.stable = stable;
+		self
+	}
+
+	/// Checks if the definition and the records of a table are exported
+	pub(crate) fn includes(&self, tb: &str) -> bool {
+		let included = match &self.tables {
+			Some(tables) => tables.iter().any(|t| t == tb),
+			None => true,
+		};
+		included && !self.excluded_tables.iter().any(|t| t == tb)
+	}
+
+	/// The condition which the records of a table must match to be exported
+	fn filter(&self, tb: &str) -> Option<&Cond> {
+		self.table_filters.get(tb).or(self.filter.as_ref())
+	}
+}
+
+/// Evaluates the record filters of an export.
+pub(crate) struct ExportFilter<'a> {
+	pub(crate) cfg: ExportOptions,
+	pub(crate) ctx: Context<'a>,
+	pub(crate) opt: Options,
+}
+
+impl<'a> ExportFilter<'a> {
+	/// Checks if a record of a table is exported, within the transaction which read it
+	pub(crate) async fn matches(
+		&self,
+		txn: &Transaction,
+		tb: &str,
+		rid: &Thing,
+		val: &Value,
+	) -> Result<bool, Error> {
+		match self.cfg.filter(tb) {
+			Some(cond) => {
+				let doc = CursorDoc::new(None, Some(rid), None, val);
+				Ok(cond.compute(&self.ctx, &self.opt, txn, Some(&doc)).await?.is_truthy())
+			}
+			None => Ok(true),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::dbs::Session;
+	use crate::kvs::{Datastore, ExportOptions};
+	use crate::sql::{self, Cond};
+
+	#[tokio::test]
+	async fn export_tenant_subset() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let ses = Session::owner().with_ns("test").with_db("test");
+		let sql = r"
+			CREATE person:1 SET tenant = 'acme';
+			CREATE person:2 SET tenant = 'other';
+			CREATE audit:1 SET tenant = 'acme';
+			CREATE invoice:1 SET tenant = 'other', owner = 'acme';
+		";
+		for res in ds.execute(sql, &ses, None).await.unwrap() {
+			res.result.unwrap();
+		}
+		let cfg = ExportOptions::new()
+			.with_excluded_tables(vec!["audit".to_owned()])
+			.with_filter(Cond(sql::value("tenant = 'acme'").unwrap()))
+			.with_table_filter("invoice", Cond(sql::value("owner = 'acme'").unwrap()));
+		let (send, recv) = channel::unbounded();
+		let export = ds.export_with_options(&ses, "test".into(), "test".into(), cfg, send);
+		export.await.unwrap().await.unwrap();
+		let mut out = String::new();
+		while let Ok(v) = recv.try_recv() {
+			out.push_str(&String::from_utf8(v).unwrap());
+			out.push('\n');
+		}
+		assert!(out.contains("UPDATE person:1 CONTENT"));
+		assert!(!out.contains("UPDATE person:2 CONTENT"));
+		assert!(out.contains("UPDATE invoice:1 CONTENT"));
+		assert!(!out.contains("audit"));
+	}
+
+	#[tokio::test]
+	async fn export_stable_iteration() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let ses = Session::owner().with_ns("test").with_db("test");
+		let sql = r"
+			DEFINE TABLE person SCHEMALESS;
+			CREATE |person:1..5|;
+			RELATE person:1->knows->person:2;
+		";
+		for res in ds.execute(sql, &ses, None).await.unwrap() {
+			res.result.unwrap();
+		}
+		let cfg = ExportOptions::new().with_batch_size(2).with_stable_iteration(true);
+		let (send, recv) = channel::unbounded();
+		let export = ds.export_with_options(&ses, "test".into(), "test".into(), cfg, send);
+		export.await.unwrap().await.unwrap();
+		let mut out = String::new();
+		while let Ok(v) = recv.try_recv() {
+			out.push_str(&String::from_utf8(v).unwrap());
+			out.push('\n');
+		}
+		assert!(out.contains("DEFINE TABLE person SCHEMALESS"));
+		for i in 1..=5 {
+			assert_eq!(out.matches(&format!("UPDATE person:{i} CONTENT")).count(), 1);
+		}
+		assert_eq!(out.matches("RELATE person:1 -> knows:").count(), 1);
+	}
+}
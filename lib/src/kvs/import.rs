@@ -0,0 +1,311 @@
+use crate::key;
+use crate::kvs::Key;
+use crate::sql::block::Entry;
+use crate::sql::statements::{DefineStatement, IfelseStatement};
+use crate::sql::{Block, Data, Idiom, Part, Statement, Thing, Value};
+use std::fmt;
+use std::sync::Arc;
+
+/// How records which already exist in the database are imported
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum RecordConflict {
+	/// The existing record is overwritten
+	#[default]
+	Overwrite,
+	/// The existing record is kept, and the imported record is skipped
+	Skip,
+	/// The imported record is skipped, and an error is collected
+	Error,
+}
+
+/// How definitions which already exist in the database are imported
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum DefinitionConflict {
+	/// The existing definition is replaced
+	#[default]
+	Replace,
+	/// The existing definition is kept, and only new definitions are imported
+	Merge,
+}
+
+/// The progress of a database import
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct ImportProgress {
+	/// The number of statements which have been executed
+	pub statements: usize,
+	/// The number of bytes of the import which have been read
+	pub bytes: usize,
+	/// The errors which have been collected so far
+	pub errors: Vec<String>,
+}
+
+/// A callback which is notified of the progress of an import
+pub type ImportCallback = Arc<dyn Fn(&ImportProgress) + Send + Sync>;
+
+/// Configures how a database import treats existing data, and how its progress is reported.
+///
+/// By default existing records are overwritten, and existing definitions are replaced.
+///
+/// ```rust,no_run
+/// # use surrealdb::kvs::{Datastore, ImportOptions, RecordConflict};
+/// # use surrealdb::dbs::Session;
+/// # use surrealdb::err::Error;
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Error> {
+/// let ds = Datastore::new("memory").await?;
+/// let ses = Session::owner().with_ns("test").with_db("test");
+/// let opts = ImportOptions::new()
+/// 	.with_records(RecordConflict::Skip)
+/// 	.with_progress(|p| println!("{} statements, {} bytes", p.statements, p.bytes));
+/// let progress = ds.import_with_options("UPDATE person:1;", &ses, opts).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default)]
+#[non_exhaustive]
+pub struct ImportOptions {
+	/// How records which already exist are imported
+	pub(crate) records: RecordConflict,
+	/// How definitions which already exist are imported
+	pub(crate) definitions: DefinitionConflict,
+	/// The callback which is notified after every executed batch of statements
+	pub(crate) progress: Option<ImportCallback>,
+}
+
+impl fmt::Debug for ImportOptions {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("ImportOptions")
+			.field("records", &self.records)
+			.field("definitions", &self.definitions)
+			.field("progress", &self.progress.is_some())
+			.finish()
+	}
+}
+
+impl ImportOptions {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Set how records which already exist are imported
+	pub fn with_records(mut self, records: RecordConflict) -> Self {
+		self.records = records;
+		self
+	}
+
+	/// Set how definitions which already exist are imported
+	pub fn with_definitions(mut self, definitions: DefinitionConflict) -> Self {
+		self.definitions = definitions;
+		self
+	}
+
+	/// Set a callback which is notified of the progress of the import
+	pub fn with_progress<F>(mut self, progress: F) -> Self
+	where
+		F: Fn(&ImportProgress) + Send + Sync + 'static,
+	{
+		self.progress = Some(Arc::new(progress));
+		self
+	}
+
+	/// Notifies the progress callback, if any
+	pub(crate) fn notify(&self, progress: &ImportProgress) {
+		if let Some(cb) = &self.progress {
+			cb(progress);
+		}
+	}
+
+	/// Returns the record which an imported statement writes,
+	/// if the statement needs to be checked for an existing record.
+	pub(crate) fn record(&self, stm: &Statement) -> Option<Thing> {
+		if self.records == RecordConflict::Overwrite {
+			return None;
+		}
+		let what = match stm {
+			Statement::Create(v) if v.what.0.len() == 1 => &v.what.0[0],
+			Statement::Update(v) if v.what.0.len() == 1 => &v.what.0[0],
+			Statement::Relate(v) => &v.kind,
+			Statement::Insert(v) => {
+				let Value::Table(tb) = &v.into else {
+					return None;
+				};
+				// Only an insert of a single record with a specified id is checked
+				let id = match &v.data {
+					Data::SingleExpression(Value::Object(v)) => v.get("id")?,
+					Data::ValuesExpression(v) if v.len() == 1 => {
+						&v[0].iter().find(|(k, _)| k.is_id())?.1
+					}
+					_ => return None,
+				};
+				return match id {
+					Value::None => None,
+					id => id.clone().generate(tb, true).ok(),
+				};
+			}
+			_ => return None,
+		};
+		match what {
+			Value::Thing(v) => Some(v.clone()),
+			_ => None,
+		}
+	}
+
+	/// Wraps an imported statement which writes the specified record, so that
+	/// the record is checked within the transaction in which the statement runs.
+	/// The statement outputs `false` and is skipped if the record already exists,
+	/// and otherwise outputs `true` once the record has been written.
+	pub(crate) fn guard(rid: Thing, stm: Statement) -> Statement {
+		let entry = match stm {
+			Statement::Create(v) => Entry::Create(v),
+			Statement::Update(v) => Entry::Update(v),
+			Statement::Relate(v) => Entry::Relate(v),
+			Statement::Insert(v) => Entry::Insert(v),
+			stm => return stm,
+		};
+		let exists = Idiom::from(vec![Part::Start(Value::Thing(rid)), Part::from("id")]);
+		Statement::Ifelse(IfelseStatement {
+			exprs: vec![(Value::Idiom(exists), Value::Bool(false))],
+			close: Some(Value::from(Block(vec![entry, Entry::Value(Value::Bool(true))]))),
+		})
+	}
+
+	/// Returns the key of the definition which an imported statement writes,
+	/// if the statement needs to be checked for an existing definition.
+	pub(crate) fn definition_key(&self, ns: &str, db: &str, stm: &Statement) -> Option<Key> {
+		if self.definitions == DefinitionConflict::Replace {
+			return None;
+		}
+		let key = match stm {
+			Statement::Define(DefineStatement::Namespace(v)) => key::root::ns::new(&v.name).into(),
+			Statement::Define(DefineStatement::Database(v)) => {
+				key::namespace::db::new(ns, &v.name).into()
+			}
+			Statement::Define(DefineStatement::Function(v)) => {
+				key::database::fc::new(ns, db, &v.name).into()
+			}
+			Statement::Define(DefineStatement::Analyzer(v)) => {
+				key::database::az::new(ns, db, &v.name).into()
+			}
+			Statement::Define(DefineStatement::Param(v)) => {
+				key::database::pa::new(ns, db, &v.name).into()
+			}
+			Statement::Define(DefineStatement::Config(v)) => {
+				key::database::cg::new(ns, db, &v.name).into()
+			}
+			Statement::Define(DefineStatement::Sequence(v)) => {
+				key::database::sq::new(ns, db, &v.name).into()
+			}
+			Statement::Define(DefineStatement::Migration(v)) => {
+				key::database::mg::new(ns, db, v.version).into()
+			}
+			Statement::Define(DefineStatement::Table(v)) => {
+				key::database::tb::new(ns, db, &v.name).into()
+			}
+			Statement::Define(DefineStatement::Scope(v)) => {
+				key::database::sc::new(ns, db, &v.name).into()
+			}
+			Statement::Define(DefineStatement::Event(v)) => {
+				key::table::ev::new(ns, db, &v.what, &v.name).into()
+			}
+			Statement::Define(DefineStatement::Field(v)) => {
+				key::table::fd::new(ns, db, &v.what, &v.name.to_string()).into()
+			}
+			Statement::Define(DefineStatement::Index(v)) => {
+				key::table::ix::new(ns, db, &v.what, &v.name).into()
+			}
+			// Tokens and users can be defined on different levels, and are always replaced
+			_ => return None,
+		};
+		Some(key)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::dbs::Session;
+	use crate::kvs::{Datastore, DefinitionConflict, ImportOptions, RecordConflict};
+	use crate::sql::test::Parse;
+	use crate::sql::Value;
+	use std::sync::{Arc, Mutex};
+
+	const EXPORT: &str = r"
+		OPTION IMPORT;
+		DEFINE TABLE person SCHEMALESS;
+		DEFINE FIELD name ON person TYPE string;
+		BEGIN TRANSACTION;
+		UPDATE person:1 CONTENT { name: 'Tobie' };
+		UPDATE person:2 CONTENT { name: 'Jaime' };
+		COMMIT TRANSACTION;
+	";
+
+	#[tokio::test]
+	async fn import_with_existing_records() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let ses = Session::owner().with_ns("test").with_db("test");
+		let sql = "DEFINE FIELD name ON person TYPE any; UPDATE person:1 SET name = 'Existing';";
+		for res in ds.execute(sql, &ses, None).await.unwrap() {
+			res.result.unwrap();
+		}
+		// Skip the existing records and definitions
+		let cfg = ImportOptions::new()
+			.with_records(RecordConflict::Skip)
+			.with_definitions(DefinitionConflict::Merge);
+		let progress = ds.import_with_options(EXPORT, &ses, cfg).await.unwrap();
+		assert_eq!(progress.statements, 1);
+		assert_eq!(progress.bytes, EXPORT.len());
+		assert!(progress.errors.is_empty());
+		let res = ds.execute("SELECT VALUE name FROM person", &ses, None).await.unwrap();
+		let val = res.into_iter().next().unwrap().result.unwrap();
+		assert_eq!(val, Value::parse("['Existing', 'Jaime']"));
+		let res = ds.execute("INFO FOR TABLE person", &ses, None).await.unwrap();
+		let val = res.into_iter().next().unwrap().result.unwrap();
+		assert!(val.to_string().contains("TYPE any"));
+		// Collect an error for the existing records
+		let cfg = ImportOptions::new().with_records(RecordConflict::Error);
+		let progress = ds.import_with_options(EXPORT, &ses, cfg).await.unwrap();
+		assert_eq!(progress.statements, 2);
+		assert_eq!(progress.errors.len(), 2);
+		// Overwrite the existing records and definitions
+		let calls = Arc::new(Mutex::new(Vec::new()));
+		let seen = calls.clone();
+		let cfg = ImportOptions::new().with_progress(move |p| {
+			seen.lock().unwrap().push((p.statements, p.bytes));
+		});
+		let progress = ds.import_with_options(EXPORT, &ses, cfg).await.unwrap();
+		assert_eq!(progress.statements, 4);
+		let calls = calls.lock().unwrap();
+		assert_eq!(calls.len(), 3);
+		assert_eq!(calls.last(), Some(&(4, EXPORT.len())));
+		let res = ds.execute("SELECT VALUE name FROM person", &ses, None).await.unwrap();
+		let val = res.into_iter().next().unwrap().result.unwrap();
+		assert_eq!(val, Value::parse("['Tobie', 'Jaime']"));
+	}
+
+	#[tokio::test]
+	async fn import_with_records_created_in_the_transaction() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let ses = Session::owner().with_ns("test").with_db("test");
+		let sql = r"
+			BEGIN TRANSACTION;
+			CREATE person:1 CONTENT { name: 'Tobie' };
+			INSERT INTO person { id: 1, name: 'Jaime' };
+			UPDATE person:1 CONTENT { name: 'Jaime' };
+			CREATE person:2 CONTENT { name: 'Jaime' };
+			COMMIT TRANSACTION;
+		";
+		let cfg = ImportOptions::new().with_records(RecordConflict::Error);
+		let progress = ds.import_with_options(sql, &ses, cfg).await.unwrap();
+		assert_eq!(progress.statements, 2);
+		assert_eq!(
+			progress.errors,
+			vec![
+				"Database record `person:1` already exists".to_string(),
+				"Database record `person:1` already exists".to_string(),
+			]
+		);
+		let res = ds.execute("SELECT VALUE name FROM person", &ses, None).await.unwrap();
+		let val = res.into_iter().next().unwrap().result.unwrap();
+		assert_eq!(val, Value::parse("['Tobie', 'Jaime']"));
+	}
+}
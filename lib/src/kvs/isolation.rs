@@ -0,0 +1,549 @@
+use super::ds::Datastore;
+use crate::dbs::Session;
+use crate::err::Error;
+use crate::sql::Value;
+use futures::StreamExt;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display};
+use std::task::Poll;
+
+/// The prefix of the keys which are written by the randomized transactions,
+/// which is outside of the keyspace used by the datastore itself
+const PREFIX: &[u8] = b"\xffisolation\x00";
+
+/// The table whose records and index are written by the randomized statements
+const TABLE: &str = "isolation";
+
+/// Configures the randomized, interleaved transactions which are run against a
+/// datastore, to verify that its backend isolates transactions correctly.
+///
+/// Each transaction reads a few keys, and each writeable transaction then
+/// appends its id to the value of some of the keys it read, so that the
+/// history of the committed transactions can be reconstructed once they
+/// complete. The keys which each transaction read, and what it read from
+/// them, are validated against that history, to check that the committed
+/// transactions were snapshot isolated, and to detect any write skew.
+/// Concurrent statements then update and delete the records of an indexed
+/// table, whose index entries are checked against the stored records.
+///
+/// ```rust,no_run
+/// # use surrealdb::kvs::{Datastore, IsolationOptions};
+/// # use surrealdb::err::Error;
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Error> {
+/// let ds = Datastore::new("memory").await?;
+/// let opts = IsolationOptions::new().with_transactions(500).with_seed(42);
+/// let report = ds.check_isolation(opts).await?;
+/// assert!(report.is_clean(), "{report}");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct IsolationOptions {
+	/// The number of transactions, and of statements, which are run
+	pub(crate) transactions: usize,
+	/// The number of transactions which run at the same time
+	pub(crate) concurrency: usize,
+	/// The number of keys, and of records, which the transactions contend on
+	pub(crate) keys: usize,
+	/// The seed of the randomized transactions
+	pub(crate) seed: u64,
+	/// The namespace of the indexed table
+	pub(crate) ns: String,
+	/// The database of the indexed table
+	pub(crate) db: String,
+}
+
+impl Default for IsolationOptions {
+	fn default() -> Self {
+		Self {
+			transactions: 200,
+			concurrency: 8,
+			keys: 8,
+			seed: rand::thread_rng().gen(),
+			ns: "isolation".to_owned(),
+			db: "isolation".to_owned(),
+		}
+	}
+}
+
+impl IsolationOptions {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Set the number of transactions, and of statements, which are run
+	pub fn with_transactions(mut self, transactions: usize) -> Self {
+		self.transactions = transactions;
+		self
+	}
+
+	/// Set the number of transactions which run at the same time
+	pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+		self.concurrency = concurrency.max(1);
+		self
+	}
+
+	/// Set the number of keys, and of records, which the transactions contend on
+	pub fn with_keys(mut self, keys: usize) -> Self {
+		self.keys = keys.max(1);
+		self
+	}
+
+	/// Set the seed of the randomized transactions, to reproduce a previous run
+	pub fn with_seed(mut self, seed: u64) -> Self {
+		self.seed = seed;
+		self
+	}
+
+	/// Set the namespace and database of the indexed table, which is removed once checked
+	pub fn with_database(mut self, ns: &str, db: &str) -> Self {
+		self.ns = ns.to_owned();
+		self.db = db.to_owned();
+		self
+	}
+}
+
+/// The outcome of the randomized transactions which were run against a datastore
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct IsolationReport {
+	/// The seed of the randomized transactions
+	pub seed: u64,
+	/// The number of transactions which were committed
+	pub committed: usize,
+	/// The number of transactions which failed, or were rejected as conflicting
+	pub aborted: usize,
+	/// The anomalies found in the history of the committed transactions, which
+	/// a datastore providing snapshot isolation does not allow
+	pub anomalies: Vec<String>,
+	/// The write skews found in the history of the committed transactions, which
+	/// snapshot isolation allows, but which are not serializable
+	pub write_skews: Vec<String>,
+	/// The index entries which do not match the stored records
+	pub inconsistent_indexes: Vec<String>,
+}
+
+impl IsolationReport {
+	/// Checks if the committed transactions were snapshot isolated, and the index consistent
+	pub fn is_clean(&self) -> bool {
+		self.anomalies.is_empty() && self.inconsistent_indexes.is_empty()
+	}
+
+	/// Checks if the committed transactions were also serializable
+	pub fn is_serializable(&self) -> bool {
+		self.is_clean() && self.write_skews.is_empty()
+	}
+}
+
+impl Display for IsolationReport {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			f,
+			"{} transactions committed, {} aborted, {} anomalies, {} write skews, {} inconsistent index entries (seed {})",
+			self.committed,
+			self.aborted,
+			self.anomalies.len(),
+			self.write_skews.len(),
+			self.inconsistent_indexes.len(),
+			self.seed,
+		)
+	}
+}
+
+/// A randomized transaction
+struct Plan {
+	/// The id which the transaction appends to the keys it writes
+	id: u64,
+	/// The keys which the transaction reads
+	reads: Vec<usize>,
+	/// The keys, out of those it reads, to which the transaction appends its id
+	writes: Vec<usize>,
+}
+
+/// A committed transaction
+struct Committed {
+	id: u64,
+	/// The read set of the transaction, with the ids which were stored in each key when it was read
+	reads: Vec<(usize, Vec<u64>)>,
+	/// The write set of the transaction
+	writes: Vec<usize>,
+}
+
+/// Runs the randomized transactions, and checks the history of those which committed,
+/// then runs the randomized statements, and checks the index against the records.
+pub(super) async fn check(ds: &Datastore, cfg: IsolationOptions) -> Result<IsolationReport, Error> {
+	let mut rng = StdRng::seed_from_u64(cfg.seed);
+	let mut report = IsolationReport {
+		seed: cfg.seed,
+		..IsolationReport::default()
+	};
+	// Remove any keys left by a previous run
+	clear(ds).await?;
+	// Plan the transactions
+	let plans = (1..=cfg.transactions as u64)
+		.map(|id| {
+			let n = rng.gen_range(1..=cfg.keys.min(3));
+			let reads = rand::seq::index::sample(&mut rng, cfg.keys, n).into_vec();
+			// Writing only some of the keys which were read allows write skew
+			let writes = match rng.gen_bool(0.75) {
+				true => {
+					let m = rng.gen_range(1..=n);
+					rand::seq::index::sample(&mut rng, n, m).iter().map(|i| reads[i]).collect()
+				}
+				false => vec![],
+			};
+			Plan {
+				id,
+				reads,
+				writes,
+			}
+		})
+		.collect::<Vec<_>>();
+	// Run the transactions concurrently
+	let outcomes = futures::stream::iter(plans.iter().map(|p| run(ds, p)))
+		.buffer_unordered(cfg.concurrency)
+		.collect::<Vec<_>>()
+		.await;
+	let mut committed = Vec::new();
+	for outcome in outcomes {
+		match outcome {
+			Some(v) => committed.push(v),
+			None => report.aborted += 1,
+		}
+	}
+	report.committed = committed.len();
+	// Read the final history of each key
+	let mut tx = ds.transaction(false, false).await?;
+	let mut history = Vec::with_capacity(cfg.keys);
+	for k in 0..cfg.keys {
+		match tx.get(key(k)).await {
+			Ok(v) => history.push(decode(v)),
+			Err(e) => {
+				tx.cancel().await?;
+				return Err(e);
+			}
+		}
+	}
+	tx.cancel().await?;
+	(report.anomalies, report.write_skews) = anomalies(&plans, &committed, &history);
+	clear(ds).await?;
+	// Check the index against the records
+	report.inconsistent_indexes = indexes(ds, &cfg, &mut rng).await?;
+	Ok(report)
+}
+
+/// Runs a randomized transaction, returning its read set if it committed
+async fn run(ds: &Datastore, plan: &Plan) -> Option<Committed> {
+	let write = !plan.writes.is_empty();
+	let mut tx = ds.transaction(write, false).await.ok()?;
+	let res = async {
+		let mut reads = Vec::with_capacity(plan.reads.len());
+		for k in plan.reads.iter() {
+			interleave().await;
+			reads.push((*k, decode(tx.get(key(*k)).await?)));
+		}
+		for (k, ids) in reads.iter().filter(|(k, _)| plan.writes.contains(k)) {
+			interleave().await;
+			let mut ids = ids.clone();
+			ids.push(plan.id);
+			tx.set(key(*k), encode(&ids)).await?;
+		}
+		interleave().await;
+		Ok::<_, Error>(reads)
+	}
+	.await;
+	match res {
+		Ok(reads) => {
+			let done = match write {
+				true => tx.commit().await,
+				false => tx.cancel().await,
+			};
+			done.ok().map(|_| Committed {
+				id: plan.id,
+				reads,
+				writes: plan.writes.clone(),
+			})
+		}
+		Err(_) => {
+			let _ = tx.cancel().await;
+			None
+		}
+	}
+}
+
+/// Validates the read set of each committed transaction against the final history of each
+/// key, returning the anomalies which snapshot isolation does not allow, and the write skews
+fn anomalies(
+	plans: &[Plan],
+	committed: &[Committed],
+	history: &[Vec<u64>],
+) -> (Vec<String>, Vec<String>) {
+	let mut out = Vec::new();
+	let done = committed.iter().map(|t| (t.id, t)).collect::<HashMap<_, _>>();
+	// Each key is written by every committed transaction which wrote it, exactly once
+	for (k, ids) in history.iter().enumerate() {
+		let mut seen = HashSet::new();
+		for id in ids {
+			if !seen.insert(*id) {
+				out.push(format!("key {k} was written twice by transaction {id}"));
+			}
+			if !done.contains_key(id) {
+				out.push(format!("key {k} was written by transaction {id}, which did not commit"));
+			}
+		}
+		for p in plans.iter().filter(|p| p.writes.contains(&k)) {
+			if done.contains_key(&p.id) && !seen.contains(&p.id) {
+				out.push(format!("key {k} lost the write of committed transaction {}", p.id));
+			}
+		}
+	}
+	for t in committed {
+		for (k, ids) in t.reads.iter() {
+			// Each read observed a state of the key which was committed
+			if !history[*k].starts_with(ids) {
+				out.push(format!(
+					"key {k} was read by transaction {} as {ids:?}, which was never committed",
+					t.id
+				));
+				continue;
+			}
+			// No other transaction wrote a key between the read and the write of a transaction
+			if t.writes.contains(k) && history[*k].get(ids.len()) != Some(&t.id) {
+				out.push(format!(
+					"key {k} was written by transaction {} over a write it did not read",
+					t.id
+				));
+			}
+		}
+	}
+	if !out.is_empty() {
+		return (out, vec![]);
+	}
+	// Build the dependency graph of the committed transactions
+	let mut deps: HashMap<u64, HashSet<u64>> = HashMap::new();
+	let mut anti: HashMap<u64, HashSet<u64>> = HashMap::new();
+	let add = |edges: &mut HashMap<u64, HashSet<u64>>, a: u64, b: u64| {
+		if a != b {
+			edges.entry(a).or_default().insert(b);
+		}
+	};
+	for ids in history {
+		for w in ids.windows(2) {
+			add(&mut deps, w[0], w[1]);
+		}
+	}
+	for t in committed {
+		for (k, ids) in t.reads.iter() {
+			// The transaction observed the last write it read
+			if let Some(w) = ids.last() {
+				add(&mut deps, *w, t.id);
+			}
+			// The transaction did not observe the next write
+			if let Some(w) = history[*k].get(ids.len()) {
+				add(&mut anti, t.id, *w);
+			}
+		}
+	}
+	// Snapshot isolation orders the transactions by their writes and reads
+	if let Some(ids) = cycle(committed, &deps) {
+		out.push(format!("transactions {ids:?} form a dependency cycle, and are not isolated"));
+		return (out, vec![]);
+	}
+	// Serializability also orders each transaction before the writes it did not observe
+	for (a, bs) in anti {
+		deps.entry(a).or_default().extend(bs);
+	}
+	let skews = match cycle(committed, &deps) {
+		Some(ids) => vec![format!(
+			"transactions {ids:?} each missed a write of another, and are not serializable"
+		)],
+		None => vec![],
+	};
+	(out, skews)
+}
+
+/// Orders the committed transactions by the edges of the dependency graph,
+/// returning the sorted ids of the transactions which are part of a cycle
+fn cycle(committed: &[Committed], edges: &HashMap<u64, HashSet<u64>>) -> Option<Vec<u64>> {
+	let mut incoming: HashMap<u64, usize> = committed.iter().map(|t| (t.id, 0)).collect();
+	for b in edges.values().flatten() {
+		*incoming.entry(*b).or_default() += 1;
+	}
+	let mut ready =
+		incoming.iter().filter(|(_, n)| **n == 0).map(|(id, _)| *id).collect::<Vec<_>>();
+	while let Some(a) = ready.pop() {
+		incoming.remove(&a);
+		for b in edges.get(&a).into_iter().flatten() {
+			if let Some(n) = incoming.get_mut(b) {
+				*n -= 1;
+				if *n == 0 {
+					ready.push(*b);
+				}
+			}
+		}
+	}
+	if incoming.is_empty() {
+		return None;
+	}
+	let mut ids = incoming.into_keys().collect::<Vec<_>>();
+	ids.sort_unstable();
+	Some(ids)
+}
+
+/// Updates and deletes the records of an indexed table concurrently, then checks
+/// that the index returns the same records as a scan of the table, for each value
+async fn indexes(
+	ds: &Datastore,
+	cfg: &IsolationOptions,
+	rng: &mut StdRng,
+) -> Result<Vec<String>, Error> {
+	let ses = Session::owner().with_ns(&cfg.ns).with_db(&cfg.db);
+	// Remove any records left by a previous run
+	let sql = format!("REMOVE TABLE {TABLE}; DEFINE INDEX value ON {TABLE} FIELDS value;");
+	ds.execute(&sql, &ses, None).await?;
+	// Run the statements concurrently
+	let stms = (0..cfg.transactions)
+		.map(|_| {
+			let k = rng.gen_range(0..cfg.keys);
+			match rng.gen_bool(0.8) {
+				true => format!("UPDATE {TABLE}:{k} SET value = {};", rng.gen_range(0..cfg.keys)),
+				false => format!("DELETE {TABLE}:{k};"),
+			}
+		})
+		.collect::<Vec<_>>();
+	futures::stream::iter(stms.iter().map(|sql| {
+		let ses = &ses;
+		async move {
+			interleave().await;
+			// Conflicting statements may be rejected
+			let _ = ds.execute(sql, ses, None).await;
+		}
+	}))
+	.buffer_unordered(cfg.concurrency)
+	.collect::<Vec<_>>()
+	.await;
+	// Compare the index with the records
+	let mut out = Vec::new();
+	for v in 0..cfg.keys {
+		let sql = format!("SELECT VALUE id FROM {TABLE} WITH INDEX value WHERE value = {v}");
+		let idx = ids(ds, &ses, &sql).await?;
+		let sql = format!("SELECT VALUE id FROM {TABLE} WITH NOINDEX WHERE value = {v}");
+		let tbl = ids(ds, &ses, &sql).await?;
+		if idx != tbl {
+			out.push(format!("value = {v}: the index has {idx:?}, but the table has {tbl:?}"));
+		}
+	}
+	ds.execute(&format!("REMOVE TABLE {TABLE};"), &ses, None).await?;
+	Ok(out)
+}
+
+/// The sorted ids of the records returned by a statement
+async fn ids(ds: &Datastore, ses: &Session, sql: &str) -> Result<Vec<String>, Error> {
+	let mut res = ds.execute(sql, ses, None).await?;
+	let mut ids = match res.pop().map(|r| r.result).transpose()? {
+		Some(Value::Array(v)) => v.iter().map(|v| v.to_string()).collect::<Vec<_>>(),
+		_ => vec![],
+	};
+	ids.sort_unstable();
+	Ok(ids)
+}
+
+/// Removes the keys written by the randomized transactions
+async fn clear(ds: &Datastore) -> Result<(), Error> {
+	let mut end = PREFIX.to_vec();
+	end.push(0xff);
+	let mut tx = ds.transaction(true, false).await?;
+	match tx.delr(PREFIX.to_vec()..end, u32::MAX).await {
+		Ok(_) => tx.commit().await,
+		Err(e) => {
+			tx.cancel().await?;
+			Err(e)
+		}
+	}
+}
+
+/// Yields to the other transactions, so that their operations are interleaved
+async fn interleave() {
+	let mut yielded = false;
+	futures::future::poll_fn(|cx| match yielded {
+		true => Poll::Ready(()),
+		false => {
+			yielded = true;
+			cx.waker().wake_by_ref();
+			Poll::Pending
+		}
+	})
+	.await
+}
+
+fn key(k: usize) -> Vec<u8> {
+	let mut key = PREFIX.to_vec();
+	key.extend_from_slice(&(k as u64).to_be_bytes());
+	key
+}
+
+fn encode(ids: &[u64]) -> Vec<u8> {
+	ids.iter().flat_map(|id| id.to_be_bytes()).collect()
+}
+
+fn decode(val: Option<Vec<u8>>) -> Vec<u64> {
+	val.unwrap_or_default()
+		.chunks_exact(8)
+		.map(|c| u64::from_be_bytes(c.try_into().unwrap_or_default()))
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn plan(id: u64, reads: &[usize], writes: &[usize]) -> Plan {
+		Plan {
+			id,
+			reads: reads.to_vec(),
+			writes: writes.to_vec(),
+		}
+	}
+
+	fn committed(id: u64, reads: &[(usize, &[u64])], writes: &[usize]) -> Committed {
+		Committed {
+			id,
+			reads: reads.iter().map(|(k, ids)| (*k, ids.to_vec())).collect(),
+			writes: writes.to_vec(),
+		}
+	}
+
+	#[test]
+	fn serializable_history() {
+		let plans = [plan(1, &[0, 1], &[0]), plan(2, &[0, 1], &[1])];
+		let done =
+			[committed(1, &[(0, &[]), (1, &[])], &[0]), committed(2, &[(0, &[1]), (1, &[])], &[1])];
+		let (out, skews) = anomalies(&plans, &done, &[vec![1], vec![2]]);
+		assert!(out.is_empty(), "{out:?}");
+		assert!(skews.is_empty(), "{skews:?}");
+	}
+
+	#[test]
+	fn write_skew() {
+		// Each transaction read the key which the other one wrote, before it was written
+		let plans = [plan(1, &[0, 1], &[0]), plan(2, &[0, 1], &[1])];
+		let done =
+			[committed(1, &[(0, &[]), (1, &[])], &[0]), committed(2, &[(0, &[]), (1, &[])], &[1])];
+		let (out, skews) = anomalies(&plans, &done, &[vec![1], vec![2]]);
+		assert!(out.is_empty(), "{out:?}");
+		assert_eq!(skews.len(), 1);
+	}
+
+	#[test]
+	fn lost_update() {
+		// Both transactions wrote the same key, over the same read
+		let plans = [plan(1, &[0], &[0]), plan(2, &[0], &[0])];
+		let done = [committed(1, &[(0, &[])], &[0]), committed(2, &[(0, &[])], &[0])];
+		let (out, _) = anomalies(&plans, &done, &[vec![2]]);
+		assert!(!out.is_empty());
+	}
+}
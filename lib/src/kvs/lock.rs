@@ -0,0 +1,149 @@
+#![cfg(any(feature = "kv-rocksdb", feature = "kv-speedb"))]
+
+use crate::err::Error;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+/// The name of the lock file, stored within the datastore directory
+const LOCK_FILE: &str = "surreal.lock";
+
+/// The process which currently holds a datastore lock
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub(crate) struct LockOwner {
+	/// The id of the process holding the lock
+	pub(crate) pid: u32,
+	/// When the lock was acquired
+	pub(crate) since: DateTime<Utc>,
+}
+
+impl LockOwner {
+	fn current() -> Self {
+		Self {
+			pid: std::process::id(),
+			since: Utc::now(),
+		}
+	}
+
+	/// Checks if the owning process is still running.
+	/// When this can not be determined, the process is assumed to be running.
+	fn is_running(&self) -> bool {
+		if cfg!(target_os = "linux") {
+			Path::new("/proc").join(self.pid.to_string()).exists()
+		} else {
+			true
+		}
+	}
+}
+
+impl Display for LockOwner {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "process {} (since {})", self.pid, self.since.to_rfc3339())
+	}
+}
+
+/// An exclusive lock on a file-backed datastore, held for as long as the
+/// datastore is open, so that a second process can not open the same path.
+#[derive(Debug)]
+pub(crate) struct FileLock {
+	path: PathBuf,
+}
+
+impl FileLock {
+	/// Acquire the lock on the datastore at the given path.
+	///
+	/// If `force` is set, any existing lock is removed first. This is used
+	/// to recover a datastore whose owning process crashed without unlocking.
+	pub(crate) fn acquire(dir: &str, force: bool) -> Result<Self, Error> {
+		let path = Path::new(dir).join(LOCK_FILE);
+		// Ensure the datastore directory exists
+		fs::create_dir_all(dir).map_err(|e| Error::Ds(e.to_string()))?;
+		// Attempt to create the lock file at most twice
+		for _ in 0..2 {
+			match OpenOptions::new().write(true).create_new(true).open(&path) {
+				Ok(mut file) => {
+					let owner = serde_json::to_vec(&LockOwner::current())
+						.map_err(|e| Error::Ds(e.to_string()))?;
+					file.write_all(&owner).and_then(|_| file.sync_all()).map_err(|e| {
+						let _ = fs::remove_file(&path);
+						Error::Ds(e.to_string())
+					})?;
+					return Ok(Self {
+						path,
+					});
+				}
+				Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+					let owner = Self::owner(&path);
+					if !force {
+						return Err(Error::DsLocked {
+							path: dir.to_owned(),
+							owner: match &owner {
+								Some(o) if !o.is_running() => {
+									format!("{o}, which is no longer running")
+								}
+								Some(o) => o.to_string(),
+								None => "an unknown process".to_owned(),
+							},
+						});
+					}
+					warn!("Removing the lock held on datastore {dir} by {owner:?}");
+					match fs::remove_file(&path) {
+						Err(e) if e.kind() != ErrorKind::NotFound => {
+							return Err(Error::Ds(e.to_string()))
+						}
+						_ => {}
+					}
+				}
+				Err(e) => return Err(Error::Ds(e.to_string())),
+			}
+		}
+		Err(Error::DsLocked {
+			path: dir.to_owned(),
+			owner: "another process".to_owned(),
+		})
+	}
+
+	/// Read the owner of an existing lock file, if it can be parsed
+	fn owner(path: &Path) -> Option<LockOwner> {
+		fs::read(path).ok().and_then(|v| serde_json::from_slice(&v).ok())
+	}
+}
+
+impl Drop for FileLock {
+	fn drop(&mut self) {
+		if let Err(e) = fs::remove_file(&self.path) {
+			warn!("Unable to remove the datastore lock file {}: {e}", self.path.display());
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use temp_dir::TempDir;
+
+	#[test]
+	fn lock_is_exclusive() {
+		let dir = TempDir::new().unwrap();
+		let path = dir.path().to_string_lossy().to_string();
+		let lock = FileLock::acquire(&path, false).unwrap();
+		assert!(matches!(FileLock::acquire(&path, false), Err(Error::DsLocked { .. })));
+		drop(lock);
+		FileLock::acquire(&path, false).unwrap();
+	}
+
+	#[test]
+	fn stale_lock_can_be_recovered() {
+		let dir = TempDir::new().unwrap();
+		let path = dir.path().to_string_lossy().to_string();
+		// Simulate a crashed process which left its lock behind
+		let lock = FileLock::acquire(&path, false).unwrap();
+		std::mem::forget(lock);
+		assert!(matches!(FileLock::acquire(&path, false), Err(Error::DsLocked { .. })));
+		let lock = FileLock::acquire(&path, true).unwrap();
+		assert_eq!(FileLock::owner(&lock.path).map(|o| o.pid), Some(std::process::id()));
+	}
+}
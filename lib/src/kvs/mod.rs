@@ -12,13 +12,28 @@
 //! - `speedb`: [SpeedyDB](https://github.com/speedb-io/speedb) fork of rocksDB making it faster (Redis is using speedb but this is not acid transactions)
 //! - `tikv`: [TiKV](https://github.com/tikv/tikv) a distributed, and transactional key-value database
 //! - `mem`: in-memory database
+pub(crate) mod archive;
 mod cache;
+mod checksum;
+mod cluster;
 mod ds;
+mod export;
 mod fdb;
+mod import;
 mod indxdb;
+mod isolation;
 mod kv;
+mod lock;
 mod mem;
+mod named;
+mod options;
+mod placement;
+mod plans;
+mod queries;
+mod recovery;
 mod rocksdb;
+mod sequences;
+mod slow;
 mod speedb;
 mod tikv;
 mod tx;
@@ -26,6 +41,17 @@ mod tx;
 #[cfg(test)]
 mod tests;
 
+pub use self::cluster::NodeMembership;
 pub use self::ds::*;
+pub use self::export::ExportOptions;
+pub use self::import::{
+	DefinitionConflict, ImportCallback, ImportOptions, ImportProgress, RecordConflict,
+};
+pub use self::isolation::{IsolationOptions, IsolationReport};
 pub use self::kv::*;
+pub use self::options::*;
+pub(crate) use self::queries::RunningQueries;
+pub use self::recovery::RecoveryReport;
+pub(crate) use self::slow::SlowQueries;
 pub use self::tx::*;
+pub use crate::cf::{ChangeSet, ConflictPolicy, MergeHook};
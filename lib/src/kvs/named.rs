@@ -0,0 +1,142 @@
+use crate::err::Error;
+use crate::iam::{Action, Actor, Auth, Level, ResourceKind};
+use crate::kvs::Transaction;
+use crate::sql::{Kind, Query, Value};
+use derive::Store;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A query which is registered under a name by a system user, and which
+/// any session, including anonymous sessions, can execute by its name,
+/// with the authorization of the user who registered the query. Named
+/// queries are stored in the database on which they run.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Store)]
+#[revisioned(revision = 1)]
+pub(crate) struct NamedQuery {
+	/// The parsed query which is executed
+	pub(crate) query: Query,
+	/// The parameters which can be specified, and their types
+	pub(crate) params: BTreeMap<String, Kind>,
+	/// The authorization of the user who registered the query
+	pub(crate) owner: Auth,
+}
+
+impl NamedQuery {
+	/// Check the parameters specified by a client against the declared
+	/// parameters, coercing each parameter to its declared type
+	pub(crate) fn check(
+		&self,
+		mut vars: BTreeMap<String, Value>,
+	) -> Result<BTreeMap<String, Value>, Error> {
+		// Reject any parameters which are not declared
+		if let Some(name) = vars.keys().find(|k| !self.params.contains_key(*k)) {
+			return Err(Error::InvalidParam {
+				name: name.to_owned(),
+			});
+		}
+		// Coerce each declared parameter to its type
+		self.params
+			.iter()
+			.map(|(k, kind)| {
+				let v = vars.remove(k).unwrap_or_default();
+				Ok((k.to_owned(), v.coerce_to(kind)?))
+			})
+			.collect()
+	}
+
+	/// Check whether the query was registered by the same user as the specified authorization
+	pub(crate) fn is_owned_by(&self, auth: &Auth) -> bool {
+		self.owner.is_system() == auth.is_system()
+			&& self.owner.id() == auth.id()
+			&& self.owner.level() == auth.level()
+	}
+
+	/// Fetch the current authorization of the user who registered the query,
+	/// so that a user who has since been removed, or whose roles have since
+	/// changed, can no longer run queries which they could not register
+	pub(crate) async fn authorize(
+		&self,
+		txn: &mut Transaction,
+		ns: &str,
+		db: &str,
+	) -> Result<Auth, Error> {
+		// Fetch the current roles of the user
+		let auth = match self.owner.level() {
+			_ if self.owner.is_system() => self.owner.clone(),
+			Level::Root => {
+				let us = txn.get_root_user(self.owner.id()).await?;
+				Auth::new(Actor::from((&us, Level::Root)))
+			}
+			Level::Namespace(v) => {
+				let us = txn.get_ns_user(v, self.owner.id()).await?;
+				Auth::new(Actor::from((&us, Level::Namespace(v.to_owned()))))
+			}
+			Level::Database(v, w) => {
+				let us = txn.get_db_user(v, w, self.owner.id()).await?;
+				Auth::new(Actor::from((&us, Level::Database(v.to_owned(), w.to_owned()))))
+			}
+			_ => self.owner.clone(),
+		};
+		// Check the user can still register the query
+		auth.is_allowed(Action::Edit, &ResourceKind::Database.on_db(ns, db))?;
+		Ok(auth)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::iam::Role;
+	use crate::sql::parse;
+
+	fn query() -> NamedQuery {
+		NamedQuery {
+			query: parse("SELECT * FROM person WHERE age > $age").unwrap(),
+			params: BTreeMap::from([
+				("age".to_owned(), Kind::Int),
+				("name".to_owned(), Kind::Option(Box::new(Kind::String))),
+			]),
+			owner: Auth::for_root(Role::Owner),
+		}
+	}
+
+	#[test]
+	fn check_params() {
+		let vars = BTreeMap::from([("age".to_owned(), Value::from(18))]);
+		let vars = query().check(vars).unwrap();
+		assert_eq!(vars.get("age"), Some(&Value::from(18)));
+		assert_eq!(vars.get("name"), Some(&Value::None));
+	}
+
+	#[test]
+	fn check_undeclared_params() {
+		let vars = BTreeMap::from([
+			("age".to_owned(), Value::from(18)),
+			("admin".to_owned(), Value::from(true)),
+		]);
+		assert!(matches!(query().check(vars), Err(Error::InvalidParam { .. })));
+	}
+
+	#[test]
+	fn check_param_types() {
+		let vars = BTreeMap::from([("age".to_owned(), Value::from("old"))]);
+		assert!(query().check(vars).is_err());
+		assert!(query().check(BTreeMap::new()).is_err());
+	}
+
+	#[test]
+	fn check_owner() {
+		assert!(query().is_owned_by(&Auth::for_root(Role::Editor)));
+		assert!(!query().is_owned_by(&Auth::for_db(Role::Owner, "test", "test")));
+		// A user with the same name as the system actor is not the system actor
+		let actor = Actor::new("system_auth".into(), vec![Role::Owner], Level::Root);
+		assert!(!query().is_owned_by(&Auth::new(actor)));
+	}
+
+	#[test]
+	fn serialize_and_deserialize() {
+		let val: Vec<u8> = query().into();
+		assert_eq!(NamedQuery::from(val), query());
+	}
+}
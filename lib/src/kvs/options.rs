@@ -0,0 +1,248 @@
+use crate::cnf::GROUP_MEMORY_LIMIT;
+use std::time::Duration;
+
+/// The default number of index tree nodes which are cached in memory per index
+pub(crate) const DEFAULT_INDEX_CACHE_SIZE: usize = 20;
+
+/// The default number of parsed queries which are cached in memory
+pub(crate) const DEFAULT_PLAN_CACHE_SIZE: usize = 1000;
+
+/// The maximum number of workers which build each index by default
+pub(crate) const MAX_INDEX_WORKERS: usize = 8;
+
+/// The default duration after which a node without any heartbeat is considered dead
+pub(crate) const DEFAULT_NODE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The default number of expired records of each table which are deleted on each tick
+pub(crate) const DEFAULT_EXPIRY_BATCH_SIZE: u32 = 1000;
+
+/// Configures the memory usage of a datastore.
+///
+/// Any option which is not specified uses the default of the storage engine.
+///
+/// ```rust,no_run
+/// # use surrealdb::kvs::{Datastore, DatastoreOptions};
+/// # use surrealdb::err::Error;
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Error> {
+/// let opts = DatastoreOptions::new()
+/// 	.with_block_cache_size(64 * 1024 * 1024)
+/// 	.with_write_buffer_size(16 * 1024 * 1024)
+/// 	.with_max_open_files(256);
+/// let ds = Datastore::new_with_options("rocksdb://temp.db", opts).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DatastoreOptions {
+	/// The size in bytes of the block cache (RocksDB and SpeeDB)
+	pub(crate) block_cache_size: Option<usize>,
+	/// The size in bytes of each write buffer (RocksDB and SpeeDB)
+	pub(crate) write_buffer_size: Option<usize>,
+	/// The maximum number of open files (RocksDB and SpeeDB)
+	pub(crate) max_open_files: Option<i32>,
+	/// The number of index tree nodes cached in memory by each index
+	pub(crate) index_cache_size: Option<usize>,
+	/// Whether the datastore rejects all writes
+	pub(crate) read_only: bool,
+	/// Whether an existing lock on the datastore is removed (RocksDB and SpeeDB)
+	pub(crate) force_unlock: bool,
+	/// The duration after which a node without any heartbeat is considered dead
+	pub(crate) node_timeout: Option<Duration>,
+	/// The number of records whose index entries are written in each transaction
+	pub(crate) index_batch_size: Option<u32>,
+	/// The number of workers which build each index defined with the `CONCURRENTLY` option
+	pub(crate) index_workers: Option<usize>,
+	/// The number of expired records of each table which are deleted on each tick
+	pub(crate) expiry_batch_size: Option<u32>,
+	/// The number of parsed queries which are cached in memory
+	pub(crate) plan_cache_size: Option<usize>,
+	/// The number of bytes which each GROUP BY clause can hold in memory
+	pub(crate) group_memory_limit: Option<usize>,
+	/// The number of bytes which each statement can hold in memory, if limited
+	pub(crate) statement_memory_limit: Option<usize>,
+	/// Whether a checksum is appended to each stored record
+	pub(crate) checksums: bool,
+}
+
+impl DatastoreOptions {
+	/// Creates a new set of options, using the storage engine defaults
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Set the size in bytes of the block cache
+	pub fn with_block_cache_size(mut self, size: usize) -> Self {
+		self.block_cache_size = Some(size);
+		self
+	}
+
+	/// Set the size in bytes of each write buffer
+	pub fn with_write_buffer_size(mut self, size: usize) -> Self {
+		self.write_buffer_size = Some(size);
+		self
+	}
+
+	/// Set the maximum number of files which can be open at once
+	pub fn with_max_open_files(mut self, files: i32) -> Self {
+		self.max_open_files = Some(files);
+		self
+	}
+
+	/// Set the number of index tree nodes cached in memory by each index
+	pub fn with_index_cache_size(mut self, size: usize) -> Self {
+		self.index_cache_size = Some(size.max(1));
+		self
+	}
+
+	/// Set whether the datastore is opened in read-only mode
+	pub fn with_read_only(mut self, read_only: bool) -> Self {
+		self.read_only = read_only;
+		self
+	}
+
+	/// Set whether a lock left behind by a crashed process is removed when opening
+	pub fn with_force_unlock(mut self, force: bool) -> Self {
+		self.force_unlock = force;
+		self
+	}
+
+	/// Set the duration after which a node without any heartbeat is considered dead.
+	/// This should be several times longer than the interval between two ticks.
+	pub fn with_node_timeout(mut self, timeout: Duration) -> Self {
+		self.node_timeout = Some(timeout);
+		self
+	}
+
+	/// Set the number of records whose index entries are written in each transaction.
+	/// Bulk updates and deletes of an indexed table, outside of an explicit transaction,
+	/// are then committed in batches, and indexes are built in batches of this size.
+	pub fn with_index_batch_size(mut self, size: u32) -> Self {
+		self.index_batch_size = Some(size.max(1));
+		self
+	}
+
+	/// Set the number of workers which build each index defined with the `CONCURRENTLY`
+	/// option, each indexing a separate range of the records of the table in its own
+	/// transactions. Only the indexes whose entries are independent of each other,
+	/// which are unique and non-unique indexes, are built by more than one worker.
+	pub fn with_index_workers(mut self, workers: usize) -> Self {
+		self.index_workers = Some(workers.max(1));
+		self
+	}
+
+	/// Set the number of expired records of each table which are deleted on each tick.
+	/// The records of a table which expire faster than they are deleted are deleted
+	/// over the following ticks.
+	pub fn with_expiry_batch_size(mut self, size: u32) -> Self {
+		self.expiry_batch_size = Some(size.max(1));
+		self
+	}
+
+	/// Set the number of parsed queries which are cached in memory, so
+	/// that prepared statements are not parsed on each execution
+	pub fn with_plan_cache_size(mut self, size: usize) -> Self {
+		self.plan_cache_size = Some(size.max(1));
+		self
+	}
+
+	/// Set the approximate number of bytes which the groups of each GROUP BY clause
+	/// can hold in memory, after which the groups are spilled to temporary files
+	pub fn with_group_memory_limit(mut self, size: usize) -> Self {
+		self.group_memory_limit = Some(size);
+		self
+	}
+
+	/// Set the approximate number of bytes which the records produced by each
+	/// statement can hold in memory, after which the statement is aborted
+	pub fn with_statement_memory_limit(mut self, size: usize) -> Self {
+		self.statement_memory_limit = Some(size);
+		self
+	}
+
+	/// Set whether a checksum is appended to the value of each stored record, which
+	/// is verified whenever the record is read, and by a background scrubbing task
+	pub fn with_checksums(mut self, enabled: bool) -> Self {
+		self.checksums = enabled;
+		self
+	}
+
+	/// The number of index tree nodes cached in memory by each index
+	pub(crate) fn index_cache_size(&self) -> usize {
+		self.index_cache_size.unwrap_or(DEFAULT_INDEX_CACHE_SIZE)
+	}
+
+	/// The number of workers which build each index, defaulting to the number of CPUs
+	pub(crate) fn index_workers(&self) -> usize {
+		self.index_workers.unwrap_or_else(|| {
+			std::thread::available_parallelism().map_or(1, usize::from).min(MAX_INDEX_WORKERS)
+		})
+	}
+
+	/// The number of expired records of each table which are deleted on each tick
+	pub(crate) fn expiry_batch_size(&self) -> u32 {
+		self.expiry_batch_size.unwrap_or(DEFAULT_EXPIRY_BATCH_SIZE)
+	}
+
+	/// The number of parsed queries which are cached in memory
+	pub(crate) fn plan_cache_size(&self) -> usize {
+		self.plan_cache_size.unwrap_or(DEFAULT_PLAN_CACHE_SIZE)
+	}
+
+	/// The number of bytes which each GROUP BY clause can hold in memory
+	pub(crate) fn group_memory_limit(&self) -> usize {
+		self.group_memory_limit.unwrap_or(GROUP_MEMORY_LIMIT)
+	}
+
+	/// The duration after which a node without any heartbeat is considered dead
+	pub(crate) fn node_timeout(&self) -> Duration {
+		self.node_timeout.unwrap_or(DEFAULT_NODE_TIMEOUT)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn index_cache_size() {
+		let opts = DatastoreOptions::new();
+		assert_eq!(opts.index_cache_size(), DEFAULT_INDEX_CACHE_SIZE);
+		let opts = DatastoreOptions::new().with_index_cache_size(100);
+		assert_eq!(opts.index_cache_size(), 100);
+		let opts = DatastoreOptions::new().with_index_cache_size(0);
+		assert_eq!(opts.index_cache_size(), 1);
+	}
+
+	#[test]
+	fn plan_cache_size() {
+		let opts = DatastoreOptions::new();
+		assert_eq!(opts.plan_cache_size(), DEFAULT_PLAN_CACHE_SIZE);
+		let opts = DatastoreOptions::new().with_plan_cache_size(0);
+		assert_eq!(opts.plan_cache_size(), 1);
+	}
+
+	#[test]
+	fn index_workers() {
+		let opts = DatastoreOptions::new();
+		assert!((1..=MAX_INDEX_WORKERS).contains(&opts.index_workers()));
+		let opts = DatastoreOptions::new().with_index_workers(0);
+		assert_eq!(opts.index_workers(), 1);
+	}
+
+	#[test]
+	fn index_batch_size() {
+		let opts = DatastoreOptions::new();
+		assert_eq!(opts.index_batch_size, None);
+		let opts = DatastoreOptions::new().with_index_batch_size(0);
+		assert_eq!(opts.index_batch_size, Some(1));
+	}
+
+	#[test]
+	fn expiry_batch_size() {
+		let opts = DatastoreOptions::new();
+		assert_eq!(opts.expiry_batch_size(), DEFAULT_EXPIRY_BATCH_SIZE);
+		let opts = DatastoreOptions::new().with_expiry_batch_size(0);
+		assert_eq!(opts.expiry_batch_size(), 1);
+	}
+}
@@ -0,0 +1,182 @@
+//! The physical placement of the records of a table with a `STORAGE` hint
+//!
+//! The record of a table is stored with the key `/*{ns}*{db}*{tb}*{id}`. The
+//! records of a table which is placed in a column family are instead stored
+//! with the key `/*{ns}*{db}*{tb}%{family}{id}`, in the column family itself
+//! on datastores which support column families, and the records of a table
+//! which is spread across shards are stored with the key
+//! `/*{ns}*{db}*{tb}&{shard}{id}`. The keys of placed records are therefore
+//! never mistaken for the keys of records, and keep their placement when
+//! they are scanned and deleted as part of a larger range of keys.
+use crate::kvs::Key;
+use crate::sql::Storage;
+use std::ops::Range;
+
+/// The marker of the key of a record
+const RECORD: u8 = b'*';
+
+/// The marker of the key of a record stored in a column family
+const FAMILY: u8 = b'%';
+
+/// The marker of the key of a record stored in a shard
+const SHARD: u8 = b'&';
+
+/// Splits a key of a table into its namespace, database, and table,
+/// and the position of the marker which follows the table
+fn table(key: &[u8]) -> Option<(&str, &str, &str, usize)> {
+	if key.first() != Some(&b'/') {
+		return None;
+	}
+	let mut names = [""; 3];
+	let mut pos = 1;
+	for name in names.iter_mut() {
+		if key.get(pos) != Some(&b'*') {
+			return None;
+		}
+		let len = key[pos + 1..].iter().position(|&b| b == 0)?;
+		*name = std::str::from_utf8(&key[pos + 1..pos + 1 + len]).ok()?;
+		pos += len + 2;
+	}
+	key.get(pos)?;
+	Some((names[0], names[1], names[2], pos))
+}
+
+/// Returns the namespace, database, and table of the key of a record, with
+/// the position of the marker of the record, if the key is the key of a record
+pub(crate) fn record(key: &[u8]) -> Option<(&str, &str, &str, usize)> {
+	table(key).filter(|(_, _, _, at)| key[*at] == RECORD)
+}
+
+/// Returns the column family in which a key is stored, if it is the key of a record
+/// of a table which is placed in a column family
+pub(crate) fn family(key: &[u8]) -> Option<&str> {
+	let (_, _, _, at) = table(key).filter(|(_, _, _, at)| key[*at] == FAMILY)?;
+	let len = key[at + 1..].iter().position(|&b| b == 0)?;
+	std::str::from_utf8(&key[at + 1..at + 1 + len]).ok()
+}
+
+/// Returns the shard in which the record with the encoded id is stored
+fn shard(id: &[u8], shards: u16) -> u16 {
+	// The FNV-1a hash is stable across platforms and releases
+	let hash = id
+		.iter()
+		.fold(0xcbf2_9ce4_8422_2325u64, |h, b| (h ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3));
+	(hash % u64::from(shards.max(1))) as u16
+}
+
+/// Returns the key at which the record with the key is stored,
+/// where the marker of the record is at the specified position
+pub(crate) fn place(key: &[u8], at: usize, storage: &Storage) -> Key {
+	let (prefix, id) = (&key[..at], &key[at + 1..]);
+	let mut out = Vec::with_capacity(key.len() + 3);
+	out.extend_from_slice(prefix);
+	match storage {
+		Storage::Family(name) => {
+			out.push(FAMILY);
+			out.extend_from_slice(name.as_bytes());
+			out.push(0);
+		}
+		Storage::Shards(n) => {
+			out.push(SHARD);
+			out.extend_from_slice(&shard(id, *n).to_be_bytes());
+		}
+	}
+	out.extend_from_slice(id);
+	out
+}
+
+/// Returns the ranges in which the records of a range of record keys are stored,
+/// if the range only contains the records of the table of its start key, where
+/// the marker of the start key is at the specified position
+pub(crate) fn ranges(rng: &Range<Key>, at: usize, storage: &Storage) -> Option<Vec<Range<Key>>> {
+	if !rng.end.starts_with(&rng.start[..=at]) {
+		return None;
+	}
+	let (beg, end) = (&rng.start[at + 1..], &rng.end[at + 1..]);
+	// The records of each shard, or of the column family, are stored under a prefix
+	let prefixes: Vec<Key> = match storage {
+		Storage::Family(name) => {
+			let mut prefix = rng.start[..at].to_vec();
+			prefix.push(FAMILY);
+			prefix.extend_from_slice(name.as_bytes());
+			prefix.push(0);
+			vec![prefix]
+		}
+		Storage::Shards(n) => (0..*n)
+			.map(|s| {
+				let mut prefix = rng.start[..at].to_vec();
+				prefix.push(SHARD);
+				prefix.extend_from_slice(&s.to_be_bytes());
+				prefix
+			})
+			.collect(),
+	};
+	let res =
+		prefixes.into_iter().map(|p| [p.as_slice(), beg].concat()..[p.as_slice(), end].concat());
+	Some(res.collect())
+}
+
+/// Returns the key of the record which is stored at a key,
+/// if the key is the key of a placed record
+pub(crate) fn unplace(key: Key) -> Key {
+	let Some((_, _, _, at)) = table(&key) else {
+		return key;
+	};
+	let len = match key[at] {
+		FAMILY => match key[at + 1..].iter().position(|&b| b == 0) {
+			Some(len) => len + 1,
+			None => return key,
+		},
+		SHARD => 2,
+		_ => return key,
+	};
+	let mut out = Vec::with_capacity(key.len());
+	out.extend_from_slice(&key[..at]);
+	out.push(RECORD);
+	out.extend_from_slice(&key[at + 1 + len..]);
+	out
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sql::id::Id;
+
+	#[test]
+	fn place_in_family() {
+		let key: Key = crate::key::thing::new("test", "test", "person", &Id::from("tobie")).into();
+		let (ns, db, tb, at) = record(&key).unwrap();
+		assert_eq!((ns, db, tb), ("test", "test", "person"));
+		let placed = place(&key, at, &Storage::Family("hot".into()));
+		assert!(record(&placed).is_none());
+		assert_eq!(family(&placed), Some("hot"));
+		assert_eq!(unplace(placed), key);
+	}
+
+	#[test]
+	fn place_in_shards() {
+		let key: Key = crate::key::thing::new("test", "test", "person", &Id::from("tobie")).into();
+		let (_, _, _, at) = record(&key).unwrap();
+		let storage = Storage::Shards(4);
+		let placed = place(&key, at, &storage);
+		assert!(record(&placed).is_none());
+		assert_eq!(family(&placed), None);
+		assert_eq!(unplace(placed.clone()), key);
+		// The placed key is within one of the ranges of the records of the table
+		let rng = crate::key::thing::prefix("test", "test", "person")
+			..crate::key::thing::suffix("test", "test", "person");
+		let ranges = ranges(&rng, at, &storage).unwrap();
+		assert_eq!(ranges.len(), 4);
+		assert_eq!(ranges.iter().filter(|r| r.contains(&placed)).count(), 1);
+	}
+
+	#[test]
+	fn keys_which_are_not_records() {
+		let key: Key = crate::key::table::fd::new("test", "test", "person", "name").into();
+		assert!(record(&key).is_none());
+		assert_eq!(unplace(key.clone()), key);
+		let key: Key = crate::key::database::tb::new("test", "test", "person").into();
+		assert!(record(&key).is_none());
+		assert_eq!(unplace(key.clone()), key);
+	}
+}
@@ -0,0 +1,111 @@
+use crate::err::Error;
+use crate::sql;
+use crate::sql::{ParseLimits, Query};
+use lru::LruCache;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// The parsed queries of a datastore, keyed on the query text, so that
+/// parameterised queries which are executed repeatedly are only parsed
+/// once. Each query is stored along with the version of the schema at
+/// the time it was parsed, and is parsed again after any table, field,
+/// or index changes.
+pub(crate) struct PlanCache {
+	/// The version of the schema, incremented on each schema change
+	version: AtomicU64,
+	/// The least recently used parsed queries
+	plans: Mutex<LruCache<String, (u64, Arc<Query>)>>,
+}
+
+impl PlanCache {
+	/// Create a cache holding at most the specified number of queries
+	pub(crate) fn new(size: usize) -> Self {
+		Self {
+			version: AtomicU64::new(0),
+			plans: Mutex::new(LruCache::new(NonZeroUsize::new(size.max(1)).unwrap())),
+		}
+	}
+
+	/// The current version of the schema
+	pub(crate) fn version(&self) -> u64 {
+		self.version.load(Ordering::Acquire)
+	}
+
+	/// Invalidate all cached queries after a schema change
+	pub(crate) fn invalidate(&self) {
+		let mut plans = self.plans.lock().unwrap();
+		self.version.fetch_add(1, Ordering::AcqRel);
+		plans.clear();
+	}
+
+	/// Fetch the parsed query for the query text, parsing it if
+	/// it is not cached for the current version of the schema
+	pub(crate) fn get(&self, txt: &str, limits: &ParseLimits) -> Result<Arc<Query>, Error> {
+		let version = self.version();
+		// Check if this query has already been parsed
+		if let Some((v, ast)) = self.plans.lock().unwrap().get(txt) {
+			if *v == version {
+				return Ok(ast.clone());
+			}
+		}
+		// Parse the SQL query text
+		let ast = Arc::new(sql::parse_with_limits(txt, limits)?);
+		// Store the parsed query, unless the schema changed while parsing
+		let mut plans = self.plans.lock().unwrap();
+		if self.version() == version {
+			plans.put(txt.to_owned(), (version, ast.clone()));
+		}
+		Ok(ast)
+	}
+
+	/// The number of parsed queries which are currently cached
+	#[cfg(test)]
+	pub(crate) fn len(&self) -> usize {
+		self.plans.lock().unwrap().len()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn reuses_parsed_queries() {
+		let cache = PlanCache::new(2);
+		let a =
+			cache.get("SELECT * FROM person WHERE age > $age", &ParseLimits::default()).unwrap();
+		let b =
+			cache.get("SELECT * FROM person WHERE age > $age", &ParseLimits::default()).unwrap();
+		assert!(Arc::ptr_eq(&a, &b));
+		assert_eq!(cache.len(), 1);
+	}
+
+	#[test]
+	fn evicts_least_recently_used() {
+		let cache = PlanCache::new(2);
+		cache.get("SELECT * FROM a", &ParseLimits::default()).unwrap();
+		cache.get("SELECT * FROM b", &ParseLimits::default()).unwrap();
+		cache.get("SELECT * FROM c", &ParseLimits::default()).unwrap();
+		assert_eq!(cache.len(), 2);
+	}
+
+	#[test]
+	fn invalidated_by_schema_changes() {
+		let cache = PlanCache::new(2);
+		let a = cache.get("SELECT * FROM person", &ParseLimits::default()).unwrap();
+		cache.invalidate();
+		assert_eq!(cache.version(), 1);
+		assert_eq!(cache.len(), 0);
+		let b = cache.get("SELECT * FROM person", &ParseLimits::default()).unwrap();
+		assert!(!Arc::ptr_eq(&a, &b));
+		assert_eq!(cache.len(), 1);
+	}
+
+	#[test]
+	fn parse_errors_are_not_cached() {
+		let cache = PlanCache::new(2);
+		assert!(cache.get("SELECT * FROM", &ParseLimits::default()).is_err());
+		assert_eq!(cache.len(), 0);
+	}
+}
@@ -0,0 +1,171 @@
+use crate::ctx::canceller::Canceller;
+use crate::dbs::Options;
+use crate::err::Error;
+use crate::iam::{Action, ResourceKind};
+use crate::sql::{Datetime, Duration, Object, Uuid, Value};
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::sync::RwLock;
+use trice::Instant;
+
+/// The kind of an operation which is running on a datastore
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum OperationKind {
+	/// A statement of a query
+	Query,
+	/// A build of an index defined with the CONCURRENTLY option
+	Index,
+	/// An import of SurrealQL statements
+	Import,
+}
+
+impl Display for OperationKind {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Query => f.write_str("query"),
+			Self::Index => f.write_str("index"),
+			Self::Import => f.write_str("import"),
+		}
+	}
+}
+
+/// An operation, such as a statement, which is currently running on a datastore
+struct RunningQuery {
+	/// The kind of the operation
+	kind: OperationKind,
+	/// The text of the statement, or a description of the operation
+	query: String,
+	/// The namespace the statement runs in
+	ns: Option<String>,
+	/// The database the statement runs in
+	db: Option<String>,
+	/// The time at which the statement started
+	started: Datetime,
+	/// The instant at which the statement started
+	now: Instant,
+	/// Cancels the context of the statement
+	canceller: Canceller,
+}
+
+impl RunningQuery {
+	/// Check if the statement is visible to, or can be cancelled by, a user
+	fn allowed(&self, opt: &Options, action: Action) -> bool {
+		// If auth is disabled, allow all actions for anonymous users
+		if !opt.auth_enabled && opt.auth.is_anon() {
+			return true;
+		}
+		let res = match (&self.ns, &self.db) {
+			(Some(ns), Some(db)) => ResourceKind::Any.on_db(ns, db),
+			(Some(ns), None) => ResourceKind::Any.on_ns(ns),
+			_ => ResourceKind::Any.on_root(),
+		};
+		opt.auth.is_allowed(action, &res).is_ok()
+	}
+}
+
+/// The statements, and background operations, which are currently running on a datastore
+#[derive(Default)]
+pub(crate) struct RunningQueries(RwLock<HashMap<Uuid, RunningQuery>>);
+
+/// Removes a statement from the running statements once it completes
+pub(crate) struct Running<'a> {
+	queries: &'a RunningQueries,
+	id: Uuid,
+}
+
+impl<'a> Drop for Running<'a> {
+	fn drop(&mut self) {
+		if let Ok(mut v) = self.queries.0.write() {
+			v.remove(&self.id);
+		}
+	}
+}
+
+impl RunningQueries {
+	/// Register a statement which has started, until the returned value is dropped
+	pub(crate) fn register(
+		&self,
+		opt: &Options,
+		query: String,
+		canceller: Canceller,
+	) -> Running<'_> {
+		let ns = opt.valid_for_ns().ok().map(|_| opt.ns());
+		let db = opt.valid_for_db().ok().map(|_| opt.db());
+		self.register_operation(OperationKind::Query, ns, db, query, canceller)
+	}
+
+	/// Register an operation which has started, until the returned value is dropped
+	pub(crate) fn register_operation(
+		&self,
+		kind: OperationKind,
+		ns: Option<&str>,
+		db: Option<&str>,
+		query: String,
+		canceller: Canceller,
+	) -> Running<'_> {
+		let id = Uuid::new_v4();
+		if let Ok(mut v) = self.0.write() {
+			v.insert(
+				id.clone(),
+				RunningQuery {
+					kind,
+					query,
+					ns: ns.map(str::to_owned),
+					db: db.map(str::to_owned),
+					started: Datetime::default(),
+					now: Instant::now(),
+					canceller,
+				},
+			);
+		}
+		Running {
+			queries: self,
+			id,
+		}
+	}
+
+	/// List the running statements which are visible to a user
+	pub(crate) fn list(&self, opt: &Options) -> Value {
+		self.list_operations(opt, Some(OperationKind::Query))
+	}
+
+	/// List the running operations, optionally of one kind, which are visible to a user
+	pub(crate) fn list_operations(&self, opt: &Options, kind: Option<OperationKind>) -> Value {
+		let v = match self.0.read() {
+			Ok(v) => v,
+			Err(_) => return Value::from(Vec::<Value>::new()),
+		};
+		let mut out = v
+			.iter()
+			.filter(|(_, q)| kind.map_or(true, |k| q.kind == k))
+			.filter(|(_, q)| q.allowed(opt, Action::View))
+			.map(|(id, q)| {
+				let obj = map! {
+					"id".to_owned() => Value::from(id.clone()),
+					"kind".to_owned() => Value::from(q.kind.to_string()),
+					"query".to_owned() => Value::from(q.query.as_str()),
+					"ns".to_owned() => q.ns.clone().map(Value::from).unwrap_or_default(),
+					"db".to_owned() => q.db.clone().map(Value::from).unwrap_or_default(),
+					"started".to_owned() => Value::from(q.started.clone()),
+					"elapsed".to_owned() => Value::from(Duration::from(q.now.elapsed())),
+				};
+				(q.started.clone(), Value::from(Object::from(obj)))
+			})
+			.collect::<Vec<_>>();
+		out.sort_by(|(a, _), (b, _)| a.cmp(b));
+		out.into_iter().map(|(_, v)| v).collect::<Vec<_>>().into()
+	}
+
+	/// Cancel a running operation, if it is visible to the user
+	pub(crate) fn cancel(&self, opt: &Options, id: &Uuid) -> Result<(), Error> {
+		match self.0.read().ok().as_ref().and_then(|v| v.get(id)) {
+			Some(q) if q.allowed(opt, Action::Edit) => {
+				q.canceller.cancel();
+				Ok(())
+			}
+			_ => Err(Error::QueryNotRunning {
+				value: id.to_raw(),
+			}),
+		}
+	}
+}
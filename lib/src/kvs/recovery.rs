@@ -0,0 +1,148 @@
+use super::tx::Transaction;
+use crate::dbs::node::ClusterMembership;
+use crate::err::Error;
+use crate::key::debug::sprint_key;
+use crate::key::index::ib::{self, Ib};
+use crate::key::root::nd::Nd;
+use crate::sql::statements::DefineTableStatement;
+use std::collections::HashSet;
+use std::fmt::{self, Display};
+
+/// The outcome of the consistency check which is performed when a datastore starts.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct RecoveryReport {
+	/// The metadata keys whose value could not be decoded
+	pub invalid_keys: Vec<String>,
+	/// The live queries whose registration was removed, as their node or table entry was missing
+	pub orphaned_live_queries: Vec<uuid::Uuid>,
+	/// The background index builds which are incomplete, as `ns.db.tb.ix`, and
+	/// which are resumed in the background by the next tick of the datastore
+	pub pending_index_builds: Vec<String>,
+	/// The background index builds which have been rolled back, as their index no longer exists
+	pub rolled_back_index_builds: Vec<String>,
+	/// The views which have been rebuilt, as their groups were created without any auxiliary state
+	pub rebuilt_views: Vec<String>,
+}
+
+impl RecoveryReport {
+	/// Checks if the datastore was found to be consistent
+	pub fn is_clean(&self) -> bool {
+		self.invalid_keys.is_empty()
+			&& self.orphaned_live_queries.is_empty()
+			&& self.pending_index_builds.is_empty()
+			&& self.rolled_back_index_builds.is_empty()
+			&& self.rebuilt_views.is_empty()
+	}
+}
+
+impl Display for RecoveryReport {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			f,
+			"{} invalid metadata keys, {} orphaned live queries removed, {} index builds pending, {} index builds rolled back, {} views rebuilt",
+			self.invalid_keys.len(),
+			self.orphaned_live_queries.len(),
+			self.pending_index_builds.len(),
+			self.rolled_back_index_builds.len(),
+			self.rebuilt_views.len(),
+		)
+	}
+}
+
+/// Validates the node and table metadata, removes orphaned live query registrations,
+/// and rolls back the state of background index builds whose index no longer exists.
+/// The index builds which can be resumed are listed in the report, but not resumed.
+pub(super) async fn check(tx: &mut Transaction) -> Result<RecoveryReport, Error> {
+	let mut report = RecoveryReport::default();
+	// Validate the cluster nodes
+	let mut nodes = HashSet::new();
+	for (k, v) in tx.getr(Nd::prefix()..Nd::suffix(), u32::MAX).await? {
+		match (Nd::decode(&k), ClusterMembership::try_from(&v)) {
+			(Ok(nd), Ok(_)) => {
+				nodes.insert(nd.nd);
+			}
+			_ => report.invalid_keys.push(sprint_key(&k)),
+		}
+	}
+	// Remove the live queries of unknown nodes, or of unknown tables
+	let beg = vec![b'/', b'$'];
+	let end = vec![b'/', b'$' + 1];
+	for (k, v) in tx.getr(beg..end, u32::MAX).await? {
+		let Ok(lq) = crate::key::node::lq::Lq::decode(&k) else {
+			continue;
+		};
+		let tb = String::from_utf8(v).unwrap_or_default();
+		let registered = nodes.contains(&lq.nd)
+			&& tx.exi(crate::key::table::lq::new(lq.ns, lq.db, &tb, lq.lq)).await?;
+		if !registered {
+			tx.del(k.clone()).await?;
+			tx.del(crate::key::table::lq::new(lq.ns, lq.db, &tb, lq.lq)).await?;
+			report.orphaned_live_queries.push(lq.lq);
+		}
+	}
+	// Validate the tables, and the background index builds
+	for ns in tx.all_ns().await?.iter() {
+		let ns = ns.name.as_str();
+		for db in tx.all_db(ns).await?.iter() {
+			let db = db.name.as_str();
+			let beg = crate::key::database::tb::prefix(ns, db);
+			let end = crate::key::database::tb::suffix(ns, db);
+			for (k, v) in tx.getr(beg..end, u32::MAX).await? {
+				let Ok(tb) = DefineTableStatement::try_from(&v) else {
+					report.invalid_keys.push(sprint_key(&k));
+					continue;
+				};
+				let tb = tb.name.as_str();
+				let ixs = tx.all_tb_indexes(ns, db, tb).await?;
+				for (k, _) in
+					tx.getr(ib::prefix(ns, db, tb)..ib::suffix(ns, db, tb), u32::MAX).await?
+				{
+					let Ok(key) = Ib::decode(&k) else {
+						report.invalid_keys.push(sprint_key(&k));
+						continue;
+					};
+					let name = format!("{ns}.{db}.{tb}.{}", key.ix);
+					if ixs.iter().any(|ix| ix.concurrently && ix.name.0 == key.ix) {
+						report.pending_index_builds.push(name);
+					} else {
+						tx.del(k).await?;
+						report.rolled_back_index_builds.push(name);
+					}
+				}
+			}
+		}
+	}
+	Ok(report)
+}
+
+/// Finds the views with a GROUP BY clause which have groups, but no auxiliary state, as
+/// their groups were created before the aggregates of each group were tracked. The records
+/// of these groups can not be retracted exactly, until the views are rebuilt.
+pub(super) async fn stale_views(
+	tx: &mut Transaction,
+) -> Result<Vec<(String, String, DefineTableStatement)>, Error> {
+	let mut out = Vec::new();
+	for ns in tx.all_ns().await?.iter() {
+		let ns = ns.name.as_str();
+		for db in tx.all_db(ns).await?.iter() {
+			let db = db.name.as_str();
+			for tb in tx.all_tb(ns, db).await?.iter() {
+				if !matches!(&tb.view, Some(v) if v.group.is_some()) {
+					continue;
+				}
+				let beg = crate::key::thing::prefix(ns, db, &tb.name);
+				let end = crate::key::thing::suffix(ns, db, &tb.name);
+				if tx.getr(beg..end, 1).await?.is_empty() {
+					continue;
+				}
+				let beg = crate::key::table::ag::prefix_tb(ns, db, &tb.name);
+				let end = crate::key::table::ag::suffix_tb(ns, db, &tb.name);
+				if tx.getr(beg..end, 1).await?.is_empty() {
+					out.push((ns.to_owned(), db.to_owned(), tb.clone()));
+				}
+			}
+		}
+	}
+	Ok(out)
+}
@@ -1,19 +1,59 @@
 #![cfg(feature = "kv-rocksdb")]
 
 use crate::err::Error;
+use crate::kvs::lock::FileLock;
+use crate::kvs::placement;
 use crate::kvs::Check;
+use crate::kvs::DatastoreOptions;
 use crate::kvs::Key;
 use crate::kvs::Val;
 use crate::vs::{try_to_u64_be, u64_to_versionstamp, Versionstamp};
 use futures::lock::Mutex;
-use rocksdb::{OptimisticTransactionDB, OptimisticTransactionOptions, ReadOptions, WriteOptions};
+use rocksdb::{
+	BlockBasedOptions, BoundColumnFamily, Cache, DBAccess, DBRawIteratorWithThreadMode,
+	DBWithThreadMode, MultiThreaded, OptimisticTransactionDB, OptimisticTransactionOptions,
+	Options, ReadOptions, WriteOptions, DB, DEFAULT_COLUMN_FAMILY_NAME,
+};
 use std::ops::Range;
 use std::pin::Pin;
 use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct Datastore {
-	db: Pin<Arc<OptimisticTransactionDB>>,
+	db: Db,
+	families: Arc<Families>,
+	// The lock must be released after the
+	// database is closed, so this must be
+	// declared last, so that it is dropped last.
+	// A read-only datastore is not locked.
+	_lock: Option<Arc<FileLock>>,
+}
+
+/// The underlying database, which is opened without
+/// transactions when the datastore is read-only
+#[derive(Clone)]
+enum Db {
+	Transactional(Pin<Arc<OptimisticTransactionDB<MultiThreaded>>>),
+	ReadOnly(Pin<Arc<DBWithThreadMode<MultiThreaded>>>),
+}
+
+impl Db {
+	/// Fetch the handle of a column family, if it exists
+	fn family(&self, name: &str) -> Option<Arc<BoundColumnFamily<'_>>> {
+		match self {
+			Db::Transactional(db) => db.cf_handle(name),
+			Db::ReadOnly(db) => db.cf_handle(name),
+		}
+	}
+}
+
+/// The column families of the database, in which the
+/// records of tables with a `STORAGE FAMILY` are stored
+struct Families {
+	/// The options with which column families are created
+	opts: Options,
+	/// The names of the column families of the database
+	names: Mutex<Vec<String>>,
 }
 
 pub struct Transaction {
@@ -23,15 +63,18 @@ pub struct Transaction {
 	write: bool,
 	/// Should we check unhandled transactions?
 	check: Check,
-	/// The underlying datastore transaction
-	inner: Arc<Mutex<Option<rocksdb::Transaction<'static, OptimisticTransactionDB>>>>,
+	/// The underlying datastore transaction, unless the datastore is read-only
+	inner:
+		Arc<Mutex<Option<rocksdb::Transaction<'static, OptimisticTransactionDB<MultiThreaded>>>>>,
 	/// The read options containing the Snapshot
 	ro: ReadOptions,
+	/// The column families of the database
+	families: Arc<Families>,
 	// The above, supposedly 'static transaction
 	// actually points here, so we need to ensure
 	// the memory is kept alive. This pointer must
 	// be declared last, so that it is dropped last
-	_db: Pin<Arc<OptimisticTransactionDB>>,
+	db: Db,
 }
 
 impl Drop for Transaction {
@@ -66,32 +109,76 @@ impl Drop for Transaction {
 
 impl Datastore {
 	/// Open a new database
-	pub(crate) async fn new(path: &str) -> Result<Datastore, Error> {
+	pub(crate) async fn new(path: &str, opts: &DatastoreOptions) -> Result<Datastore, Error> {
+		// Configure the database
+		let mut o = Options::default();
+		if let Some(size) = opts.write_buffer_size {
+			o.set_write_buffer_size(size);
+		}
+		if let Some(files) = opts.max_open_files {
+			o.set_max_open_files(files);
+		}
+		if let Some(size) = opts.block_cache_size {
+			let mut b = BlockBasedOptions::default();
+			b.set_block_cache(&Cache::new_lru_cache(size));
+			o.set_block_based_table_factory(&b);
+		}
+		// The column families of a new database are created when they are first written to
+		let names =
+			DB::list_cf(&o, path).unwrap_or_else(|_| vec![DEFAULT_COLUMN_FAMILY_NAME.into()]);
+		// A read-only datastore is opened without creating or locking its path
+		if opts.read_only {
+			return Ok(Datastore {
+				db: Db::ReadOnly(Arc::pin(DBWithThreadMode::open_cf_for_read_only(
+					&o, path, &names, false,
+				)?)),
+				families: Arc::new(Families {
+					opts: o,
+					names: Mutex::new(names),
+				}),
+				_lock: None,
+			});
+		}
+		// Lock the database path
+		let lock = FileLock::acquire(path, opts.force_unlock)?;
+		o.create_if_missing(true);
 		Ok(Datastore {
-			db: Arc::pin(OptimisticTransactionDB::open_default(path)?),
+			db: Db::Transactional(Arc::pin(OptimisticTransactionDB::open_cf(&o, path, &names)?)),
+			families: Arc::new(Families {
+				opts: o,
+				names: Mutex::new(names),
+			}),
+			_lock: Some(Arc::new(lock)),
 		})
 	}
 	/// Start a new transaction
 	pub(crate) async fn transaction(&self, write: bool, _: bool) -> Result<Transaction, Error> {
-		// Activate the snapshot options
-		let mut to = OptimisticTransactionOptions::default();
-		to.set_snapshot(true);
-		// Create a new transaction
-		let inner = self.db.transaction_opt(&WriteOptions::default(), &to);
-		// The database reference must always outlive
-		// the transaction. If it doesn't then this
-		// is undefined behaviour. This unsafe block
-		// ensures that the transaction reference is
-		// static, but will cause a crash if the
-		// datastore is dropped prematurely.
-		let inner = unsafe {
-			std::mem::transmute::<
-				rocksdb::Transaction<'_, OptimisticTransactionDB>,
-				rocksdb::Transaction<'static, OptimisticTransactionDB>,
-			>(inner)
+		let (inner, ro) = match &self.db {
+			Db::Transactional(db) => {
+				// Activate the snapshot options
+				let mut to = OptimisticTransactionOptions::default();
+				to.set_snapshot(true);
+				// Create a new transaction
+				let inner = db.transaction_opt(&WriteOptions::default(), &to);
+				// The database reference must always outlive
+				// the transaction. If it doesn't then this
+				// is undefined behaviour. This unsafe block
+				// ensures that the transaction reference is
+				// static, but will cause a crash if the
+				// datastore is dropped prematurely.
+				let inner = unsafe {
+					std::mem::transmute::<
+						rocksdb::Transaction<'_, OptimisticTransactionDB<MultiThreaded>>,
+						rocksdb::Transaction<'static, OptimisticTransactionDB<MultiThreaded>>,
+					>(inner)
+				};
+				let mut ro = ReadOptions::default();
+				ro.set_snapshot(&inner.snapshot());
+				(Some(inner), ro)
+			}
+			// A read-only database never changes, so it is read without a snapshot
+			Db::ReadOnly(_) => (None, ReadOptions::default()),
 		};
-		let mut ro = ReadOptions::default();
-		ro.set_snapshot(&inner.snapshot());
 		// Specify the check level
 		#[cfg(not(debug_assertions))]
 		let check = Check::Warn;
@@ -102,9 +189,10 @@ impl Datastore {
 			done: false,
 			write,
 			check,
-			inner: Arc::new(Mutex::new(Some(inner))),
+			inner: Arc::new(Mutex::new(inner)),
 			ro,
-			_db: self.db.clone(),
+			families: self.families.clone(),
+			db: self.db.clone(),
 		})
 	}
 }
@@ -127,10 +215,9 @@ impl Transaction {
 		// Mark this transaction as done
 		self.done = true;
 		// Cancel this transaction
-		match self.inner.lock().await.take() {
-			Some(inner) => inner.rollback()?,
-			None => unreachable!(),
-		};
+		if let Some(inner) = self.inner.lock().await.take() {
+			inner.rollback()?;
+		}
 		// Continue
 		Ok(())
 	}
@@ -147,13 +234,47 @@ impl Transaction {
 		// Mark this transaction as done
 		self.done = true;
 		// Cancel this transaction
-		match self.inner.lock().await.take() {
-			Some(inner) => inner.commit()?,
-			None => unreachable!(),
-		};
+		if let Some(inner) = self.inner.lock().await.take() {
+			inner.commit()?;
+		}
 		// Continue
 		Ok(())
 	}
+	/// Fetch the name of the column family in which a key is stored, creating
+	/// the column family if it does not exist and the key is to be written
+	async fn family<'a>(&self, key: &'a [u8], create: bool) -> Result<&'a str, Error> {
+		let name = placement::family(key).unwrap_or(DEFAULT_COLUMN_FAMILY_NAME);
+		if let (true, Db::Transactional(db)) = (create, &self.db) {
+			// Column families are created outside of the transaction
+			let mut names = self.families.names.lock().await;
+			if db.cf_handle(name).is_none() {
+				db.create_cf(name, &self.families.opts)?;
+				names.push(name.to_owned());
+			}
+		}
+		Ok(name)
+	}
+	/// Fetch the column families which store the keys of a range
+	async fn families(&self, rng: &Range<Key>) -> Vec<String> {
+		match placement::family(&rng.start) {
+			Some(name) if placement::family(&rng.end) == Some(name) => vec![name.to_owned()],
+			_ => self.families.names.lock().await.clone(),
+		}
+	}
+	/// Fetch a key from the transaction, or from the read-only database
+	async fn read(&self, key: Key) -> Result<Option<Val>, Error> {
+		let name = self.family(&key, false).await?;
+		let inner = self.inner.lock().await;
+		// A key in a column family which does not exist is not stored
+		let Some(cf) = self.db.family(name) else {
+			return Ok(None);
+		};
+		match (inner.as_ref(), &self.db) {
+			(Some(inner), _) => Ok(inner.get_cf_opt(&cf, key, &self.ro)?),
+			(None, Db::ReadOnly(db)) => Ok(db.get_cf_opt(&cf, key, &self.ro)?),
+			(None, Db::Transactional(_)) => unreachable!(),
+		}
+	}
 	/// Check if a key exists
 	pub(crate) async fn exi<K>(&mut self, key: K) -> Result<bool, Error>
 	where
@@ -164,8 +285,7 @@ impl Transaction {
 			return Err(Error::TxFinished);
 		}
 		// Check the key
-		let res =
-			self.inner.lock().await.as_ref().unwrap().get_opt(key.into(), &self.ro)?.is_some();
+		let res = self.read(key.into()).await?.is_some();
 		// Return result
 		Ok(res)
 	}
@@ -179,7 +299,7 @@ impl Transaction {
 			return Err(Error::TxFinished);
 		}
 		// Get the key
-		let res = self.inner.lock().await.as_ref().unwrap().get_opt(key.into(), &self.ro)?;
+		let res = self.read(key.into()).await?;
 		// Return result
 		Ok(res)
 	}
@@ -259,8 +379,14 @@ impl Transaction {
 		if !self.write {
 			return Err(Error::TxReadonly);
 		}
+		// Get the arguments
+		let key = key.into();
+		let name = self.family(&key, true).await?;
+		// Get the transaction
+		let inner = self.inner.lock().await;
+		let cf = self.db.family(name).unwrap();
 		// Set the key
-		self.inner.lock().await.as_ref().unwrap().put(key.into(), val.into())?;
+		inner.as_ref().unwrap().put_cf(&cf, key, val.into())?;
 		// Return result
 		Ok(())
 	}
@@ -278,15 +404,17 @@ impl Transaction {
 		if !self.write {
 			return Err(Error::TxReadonly);
 		}
-		// Get the transaction
-		let inner = self.inner.lock().await;
-		let inner = inner.as_ref().unwrap();
 		// Get the arguments
 		let key = key.into();
 		let val = val.into();
+		let name = self.family(&key, true).await?;
+		// Get the transaction
+		let inner = self.inner.lock().await;
+		let inner = inner.as_ref().unwrap();
+		let cf = self.db.family(name).unwrap();
 		// Set the key if empty
-		match inner.get_opt(&key, &self.ro)? {
-			None => inner.put(key, val)?,
+		match inner.get_cf_opt(&cf, &key, &self.ro)? {
+			None => inner.put_cf(&cf, key, val)?,
 			_ => return Err(Error::TxKeyAlreadyExists),
 		};
 		// Return result
@@ -306,17 +434,19 @@ impl Transaction {
 		if !self.write {
 			return Err(Error::TxReadonly);
 		}
-		// Get the transaction
-		let inner = self.inner.lock().await;
-		let inner = inner.as_ref().unwrap();
 		// Get the arguments
 		let key = key.into();
 		let val = val.into();
 		let chk = chk.map(Into::into);
+		let name = self.family(&key, true).await?;
+		// Get the transaction
+		let inner = self.inner.lock().await;
+		let inner = inner.as_ref().unwrap();
+		let cf = self.db.family(name).unwrap();
 		// Set the key if valid
-		match (inner.get_opt(&key, &self.ro)?, chk) {
-			(Some(v), Some(w)) if v == w => inner.put(key, val)?,
-			(None, None) => inner.put(key, val)?,
+		match (inner.get_cf_opt(&cf, &key, &self.ro)?, chk) {
+			(Some(v), Some(w)) if v == w => inner.put_cf(&cf, key, val)?,
+			(None, None) => inner.put_cf(&cf, key, val)?,
 			_ => return Err(Error::TxConditionNotMet),
 		};
 		// Return result
@@ -335,8 +465,17 @@ impl Transaction {
 		if !self.write {
 			return Err(Error::TxReadonly);
 		}
+		// Get the arguments
+		let key = key.into();
+		let name = self.family(&key, false).await?;
+		// Get the transaction
+		let inner = self.inner.lock().await;
+		// A key in a column family which does not exist is not stored
+		let Some(cf) = self.db.family(name) else {
+			return Ok(());
+		};
 		// Remove the key
-		self.inner.lock().await.as_ref().unwrap().delete(key.into())?;
+		inner.as_ref().unwrap().delete_cf(&cf, key)?;
 		// Return result
 		Ok(())
 	}
@@ -354,16 +493,18 @@ impl Transaction {
 		if !self.write {
 			return Err(Error::TxReadonly);
 		}
-		// Get the transaction
-		let inner = self.inner.lock().await;
-		let inner = inner.as_ref().unwrap();
 		// Get the arguments
 		let key = key.into();
 		let chk = chk.map(Into::into);
+		let name = self.family(&key, true).await?;
+		// Get the transaction
+		let inner = self.inner.lock().await;
+		let inner = inner.as_ref().unwrap();
+		let cf = self.db.family(name).unwrap();
 		// Delete the key if valid
-		match (inner.get_opt(&key, &self.ro)?, chk) {
-			(Some(v), Some(w)) if v == w => inner.delete(key)?,
-			(None, None) => inner.delete(key)?,
+		match (inner.get_cf_opt(&cf, &key, &self.ro)?, chk) {
+			(Some(v), Some(w)) if v == w => inner.delete_cf(&cf, key)?,
+			(None, None) => inner.delete_cf(&cf, key)?,
 			_ => return Err(Error::TxConditionNotMet),
 		};
 		// Return result
@@ -382,45 +523,155 @@ impl Transaction {
 		if self.done {
 			return Err(Error::TxFinished);
 		}
+		// Convert the range to bytes
+		let rng: Range<Key> = Range {
+			start: rng.start.into(),
+			end: rng.end.into(),
+		};
+		// Get the column families of the range
+		let names = self.families(&rng).await;
 		// Get the transaction
 		let inner = self.inner.lock().await;
-		let inner = inner.as_ref().unwrap();
+		// Scan each column family of the transaction, or of the read-only database
+		let mut res = vec![];
+		for cf in names.iter().filter_map(|name| self.db.family(name)) {
+			res.extend(match (inner.as_ref(), &self.db) {
+				(Some(inner), _) => {
+					// Set the ReadOptions with the snapshot
+					let mut ro = ReadOptions::default();
+					ro.set_snapshot(&inner.snapshot());
+					scan_iter(inner.raw_iterator_cf_opt(&cf, ro), rng.clone(), limit)
+				}
+				(None, Db::ReadOnly(db)) => scan_iter(db.raw_iterator_cf(&cf), rng.clone(), limit),
+				(None, Db::Transactional(_)) => unreachable!(),
+			});
+		}
+		// Merge the keys of the column families
+		if names.len() > 1 {
+			res.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+			res.truncate(limit as usize);
+		}
+		// Return result
+		Ok(res)
+	}
+
+	/// Retrieve a range of keys from the databases, in reverse order
+	pub(crate) async fn scanr<K>(
+		&mut self,
+		rng: Range<K>,
+		limit: u32,
+	) -> Result<Vec<(Key, Val)>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
 		// Convert the range to bytes
 		let rng: Range<Key> = Range {
 			start: rng.start.into(),
 			end: rng.end.into(),
 		};
-		// Create result set
+		// Get the column families of the range
+		let names = self.families(&rng).await;
+		// Get the transaction
+		let inner = self.inner.lock().await;
+		// Scan each column family of the transaction, or of the read-only database
 		let mut res = vec![];
-		// Set the key range
-		let beg = rng.start.as_slice();
-		let end = rng.end.as_slice();
-		// Set the ReadOptions with the snapshot
-		let mut ro = ReadOptions::default();
-		ro.set_snapshot(&inner.snapshot());
-		// Create the iterator
-		let mut iter = inner.raw_iterator_opt(ro);
-		// Seek to the start key
-		iter.seek(&rng.start);
-		// Scan the keys in the iterator
-		while iter.valid() {
-			// Check the scan limit
-			if res.len() < limit as usize {
-				// Get the key and value
-				let (k, v) = (iter.key(), iter.value());
-				// Check the key and value
-				if let (Some(k), Some(v)) = (k, v) {
-					if k >= beg && k < end {
-						res.push((k.to_vec(), v.to_vec()));
-						iter.next();
-						continue;
-					}
+		for cf in names.iter().filter_map(|name| self.db.family(name)) {
+			res.extend(match (inner.as_ref(), &self.db) {
+				(Some(inner), _) => {
+					// Set the ReadOptions with the snapshot
+					let mut ro = ReadOptions::default();
+					ro.set_snapshot(&inner.snapshot());
+					scanr_iter(inner.raw_iterator_cf_opt(&cf, ro), rng.clone(), limit)
 				}
-			}
-			// Exit
-			break;
+				(None, Db::ReadOnly(db)) => scanr_iter(db.raw_iterator_cf(&cf), rng.clone(), limit),
+				(None, Db::Transactional(_)) => unreachable!(),
+			});
+		}
+		// Merge the keys of the column families
+		if names.len() > 1 {
+			res.sort_unstable_by(|a, b| b.0.cmp(&a.0));
+			res.truncate(limit as usize);
 		}
 		// Return result
 		Ok(res)
 	}
 }
+
+/// Collects the keys of a range from an iterator, in order
+fn scan_iter<D: DBAccess>(
+	mut iter: DBRawIteratorWithThreadMode<'_, D>,
+	rng: Range<Key>,
+	limit: u32,
+) -> Vec<(Key, Val)> {
+	// Create result set
+	let mut res = vec![];
+	// Set the key range
+	let beg = rng.start.as_slice();
+	let end = rng.end.as_slice();
+	// Seek to the start key
+	iter.seek(beg);
+	// Scan the keys in the iterator
+	while iter.valid() {
+		// Check the scan limit
+		if res.len() < limit as usize {
+			// Get the key and value
+			let (k, v) = (iter.key(), iter.value());
+			// Check the key and value
+			if let (Some(k), Some(v)) = (k, v) {
+				if k >= beg && k < end {
+					res.push((k.to_vec(), v.to_vec()));
+					iter.next();
+					continue;
+				}
+			}
+		}
+		// Exit
+		break;
+	}
+	// Return result
+	res
+}
+
+/// Collects the keys of a range from an iterator, in reverse order
+fn scanr_iter<D: DBAccess>(
+	mut iter: DBRawIteratorWithThreadMode<'_, D>,
+	rng: Range<Key>,
+	limit: u32,
+) -> Vec<(Key, Val)> {
+	// Create result set
+	let mut res = vec![];
+	// Set the key range
+	let beg = rng.start.as_slice();
+	let end = rng.end.as_slice();
+	// Seek to the last key before the end key
+	iter.seek_for_prev(end);
+	// Scan the keys in the iterator
+	while iter.valid() {
+		// Check the scan limit
+		if res.len() < limit as usize {
+			// Get the key and value
+			let (k, v) = (iter.key(), iter.value());
+			// Check the key and value
+			if let (Some(k), Some(v)) = (k, v) {
+				// The end key is excluded from the range
+				if k >= end {
+					iter.prev();
+					continue;
+				}
+				if k >= beg {
+					res.push((k.to_vec(), v.to_vec()));
+					iter.prev();
+					continue;
+				}
+			}
+		}
+		// Exit
+		break;
+	}
+	// Return result
+	res
+}
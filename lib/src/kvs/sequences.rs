@@ -0,0 +1,130 @@
+use super::Key;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// A range of values of a sequence which has been allocated to this node
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct Block {
+	/// The version of the sequence definition which the block was allocated from
+	version: u64,
+	/// The next value of the block which has not been used
+	next: i64,
+	/// The first value after the end of the block
+	end: i64,
+}
+
+impl Block {
+	pub(crate) fn new(version: u64, beg: i64, end: i64) -> Self {
+		Self {
+			version,
+			next: beg,
+			end,
+		}
+	}
+
+	/// Take the next value of the block, if any values remain, and the
+	/// block was allocated from the current version of the sequence
+	pub(crate) fn take(&mut self, version: u64) -> Option<i64> {
+		if self.version != version || self.next >= self.end {
+			return None;
+		}
+		let v = self.next;
+		self.next += 1;
+		Some(v)
+	}
+}
+
+/// The blocks of sequence values which are cached by this node, so that most
+/// values are handed out without writing to the sequence in the datastore.
+///
+/// A block is only cached once the transaction which allocated it commits, so
+/// a value is never handed out twice. Any values which remain in a block when
+/// the node stops, or when the sequence is removed, are skipped. Each block is
+/// tied to the version of the definition which it was allocated from, so the
+/// blocks on every node are discarded once the sequence is defined again.
+#[derive(Clone, Default)]
+pub(crate) struct Sequences(Arc<Mutex<HashMap<Key, Block>>>);
+
+impl Sequences {
+	/// Take the next value of a sequence from the block cached by this node
+	pub(crate) fn take(&self, key: &Key, version: u64) -> Option<i64> {
+		let mut v = self.0.lock().ok()?;
+		match v.get_mut(key).and_then(|b| b.take(version)) {
+			Some(v) => Some(v),
+			None => {
+				v.remove(key);
+				None
+			}
+		}
+	}
+
+	/// Cache the remaining values of the blocks allocated by a committed transaction
+	pub(crate) fn put(&self, blocks: HashMap<Key, Block>) {
+		if let Ok(mut v) = self.0.lock() {
+			v.extend(blocks);
+		}
+	}
+
+	/// Discard the block which is cached for a sequence
+	pub(crate) fn remove(&self, key: &Key) {
+		if let Ok(mut v) = self.0.lock() {
+			v.remove(key);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::dbs::Session;
+	use crate::kvs::Datastore;
+	use crate::sql::statements::DefineSequenceStatement;
+	use crate::sql::Ident;
+
+	#[test]
+	fn take_from_cached_block() {
+		let key: Key = b"seq".to_vec();
+		let seqs = Sequences::default();
+		assert_eq!(seqs.take(&key, 1), None);
+		seqs.put(HashMap::from([(key.clone(), Block::new(1, 10, 12))]));
+		assert_eq!(seqs.take(&key, 1), Some(10));
+		assert_eq!(seqs.take(&key, 1), Some(11));
+		assert_eq!(seqs.take(&key, 1), None);
+		seqs.put(HashMap::from([(key.clone(), Block::new(1, 20, 30))]));
+		seqs.remove(&key);
+		assert_eq!(seqs.take(&key, 1), None);
+		// A block of a previous definition is discarded
+		seqs.put(HashMap::from([(key.clone(), Block::new(1, 20, 30))]));
+		assert_eq!(seqs.take(&key, 2), None);
+		assert_eq!(seqs.take(&key, 1), None);
+	}
+
+	#[tokio::test]
+	async fn discard_blocks_of_other_versions() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let ses = Session::owner().with_ns("test").with_db("test");
+		let res = &mut ds.execute("DEFINE SEQUENCE seq BATCH 10", &ses, None).await.unwrap();
+		assert!(res.remove(0).result.is_ok());
+		// Take a value, caching the rest of the block on this node
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		assert_eq!(tx.next_in_sequence("test", "test", "seq").await.unwrap(), 1);
+		tx.commit().await.unwrap();
+		// Define the sequence again, as another node would
+		let sq = DefineSequenceStatement {
+			name: Ident("seq".to_owned()),
+			batch: 10,
+			start: 100,
+			version: 2,
+			..Default::default()
+		};
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		tx.set(crate::key::database::sq::new("test", "test", "seq"), sq).await.unwrap();
+		let key = crate::key::database::sv::new("test", "test", "seq");
+		tx.set(key, 100i64.to_be_bytes().to_vec()).await.unwrap();
+		tx.commit().await.unwrap();
+		// The block cached by this node is discarded
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		assert_eq!(tx.next_in_sequence("test", "test", "seq").await.unwrap(), 100);
+		tx.cancel().await.unwrap();
+	}
+}
@@ -0,0 +1,186 @@
+use crate::dbs::Options;
+use crate::sql::statement::Statement;
+use crate::sql::{Cond, Expression, Idiom, Operator, Value};
+use std::collections::VecDeque;
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// The number of statements which are kept in the slow query log
+const SLOW_QUERY_LOG_SIZE: usize = 1000;
+
+/// A statement which filtered the records of a table, and took longer than the threshold
+pub(crate) struct SlowQuery {
+	/// The namespace the statement ran in
+	pub(crate) ns: String,
+	/// The database the statement ran in
+	pub(crate) db: String,
+	/// The tables which the statement iterated over
+	pub(crate) tables: Vec<String>,
+	/// The fields which the WHERE clause of the statement compared
+	pub(crate) fields: Vec<Idiom>,
+	/// The time which the statement took to run
+	pub(crate) time: Duration,
+}
+
+/// The recent statements which took longer than the slow query threshold
+#[derive(Default)]
+pub(crate) struct SlowQueries {
+	/// The duration after which a statement is logged, if enabled
+	threshold: Option<Duration>,
+	/// The logged statements, with the most recent last
+	log: RwLock<VecDeque<SlowQuery>>,
+}
+
+impl SlowQueries {
+	pub(crate) fn new(threshold: Option<Duration>) -> Self {
+		Self {
+			threshold,
+			log: RwLock::default(),
+		}
+	}
+
+	/// Inspect a statement before it runs, returning the entry to log if it is slow
+	pub(crate) fn inspect(&self, opt: &Options, stm: &Statement) -> Option<SlowQuery> {
+		// Check if the slow query log is enabled
+		self.threshold?;
+		// Only statements which filter tables can be sped up by an index
+		let (what, cond) = match stm {
+			Statement::Select(v) => (&v.what, &v.cond),
+			Statement::Update(v) => (&v.what, &v.cond),
+			Statement::Delete(v) => (&v.what, &v.cond),
+			_ => return None,
+		};
+		let tables = what
+			.0
+			.iter()
+			.filter_map(|v| match v {
+				Value::Table(v) => Some(v.0.to_owned()),
+				_ => None,
+			})
+			.collect::<Vec<_>>();
+		let fields = cond.as_ref().map(filters).unwrap_or_default();
+		if tables.is_empty() || fields.is_empty() {
+			return None;
+		}
+		Some(SlowQuery {
+			ns: opt.valid_for_db().ok().map(|_| opt.ns().to_owned())?,
+			db: opt.db().to_owned(),
+			tables,
+			fields,
+			time: Duration::ZERO,
+		})
+	}
+
+	/// Log a statement which was inspected, if it took longer than the threshold
+	pub(crate) fn record(&self, mut query: SlowQuery, time: Duration) {
+		match self.threshold {
+			Some(threshold) if time >= threshold => {
+				query.time = time;
+				if let Ok(mut v) = self.log.write() {
+					if v.len() >= SLOW_QUERY_LOG_SIZE {
+						v.pop_front();
+					}
+					v.push_back(query);
+				}
+			}
+			_ => (),
+		}
+	}
+
+	/// Each field compared by the slow statements on a table, with the time the statements took
+	pub(crate) fn filters(&self, ns: &str, db: &str) -> Vec<(String, Idiom, Duration)> {
+		let v = match self.log.read() {
+			Ok(v) => v,
+			Err(_) => return vec![],
+		};
+		v.iter()
+			.filter(|q| q.ns == ns && q.db == db)
+			.flat_map(|q| {
+				q.tables.iter().flat_map(move |tb| {
+					q.fields.iter().map(move |fd| (tb.to_owned(), fd.to_owned(), q.time))
+				})
+			})
+			.collect()
+	}
+}
+
+/// Collects the fields which are compared with a value in a condition
+fn filters(cond: &Cond) -> Vec<Idiom> {
+	let mut out = vec![];
+	compared(&cond.0, &mut out);
+	out
+}
+
+fn compared(val: &Value, out: &mut Vec<Idiom>) {
+	if let Value::Expression(e) = val {
+		if let Expression::Binary {
+			l,
+			o,
+			r,
+		} = e.as_ref()
+		{
+			match (l, o, r) {
+				// Both sides of an AND clause can use an index
+				(l, Operator::And, r) => {
+					compared(l, out);
+					compared(r, out);
+				}
+				// A field compared with a value can use an index
+				(Value::Idiom(v), o, r) | (r, o, Value::Idiom(v))
+					if is_indexable(o) && !matches!(r, Value::Idiom(_)) =>
+				{
+					if !out.contains(v) {
+						out.push(v.to_owned());
+					}
+				}
+				_ => (),
+			}
+		}
+	}
+}
+
+/// Check if an index can be used for an operator
+fn is_indexable(o: &Operator) -> bool {
+	matches!(
+		o,
+		Operator::Equal
+			| Operator::Exact
+			| Operator::LessThan
+			| Operator::LessThanOrEqual
+			| Operator::MoreThan
+			| Operator::MoreThanOrEqual
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sql::parse;
+
+	#[test]
+	fn inspects_filtered_statements() {
+		let log = SlowQueries::new(Some(Duration::ZERO));
+		let opt = Options::new().with_ns(Some("test".into())).with_db(Some("test".into()));
+		let qry = parse(
+			"SELECT * FROM user WHERE email = 'tobie@surrealdb.com' AND 18 <= age OR name = 'Tobie';
+			SELECT * FROM user WHERE email = 'tobie@surrealdb.com' AND age > 18;
+			SELECT * FROM user:tobie WHERE age > 18;
+			SELECT * FROM user WHERE email = name;",
+		)
+		.unwrap();
+		let mut out = qry.iter().map(|stm| log.inspect(&opt, stm)).collect::<Vec<_>>();
+		assert!(out[0].is_none());
+		assert!(out[2].is_none());
+		assert!(out[3].is_none());
+		let q = out.remove(1).unwrap();
+		assert_eq!(q.tables, vec!["user".to_owned()]);
+		assert_eq!(q.fields, vec![Idiom::from("email".to_owned()), Idiom::from("age".to_owned())]);
+		log.record(q, Duration::from_millis(5));
+		let filters = log.filters("test", "test");
+		assert_eq!(filters.len(), 2);
+		assert_eq!(
+			filters[0],
+			("user".to_owned(), Idiom::from("email".to_owned()), Duration::from_millis(5))
+		);
+	}
+}
@@ -293,3 +293,50 @@ async fn bootstrap_does_not_error_on_missing_live_queries() {
 	assert_eq!(0, found.len(), "Found: {:?}", found);
 	tx.cancel().await.unwrap();
 }
+
+#[tokio::test]
+#[serial]
+async fn live_queries_of_dead_nodes_are_reassigned() {
+	let dead_node = Uuid::parse_str("3c2e8f7a-6b1d-4e9a-8f2c-5d7b9a1e3c4f").unwrap();
+	let node = Uuid::parse_str("7e4a1b9c-2d8f-4c6a-9b3e-1f5d7a2c8e6b").unwrap();
+	let lq_id = Uuid::parse_str("9a1c3e5b-7d2f-4a8c-b6e4-2f8a1c3e5d7b").unwrap();
+	let test = init(node).await.unwrap();
+
+	// A node which sent its last heartbeat long ago, and owns a live query
+	let mut tx = test.db.transaction(true, false).await.unwrap();
+	tx.set_nd(dead_node).await.unwrap();
+	tx.set_hb(
+		Timestamp {
+			value: 1000,
+		},
+		dead_node,
+	)
+	.await
+	.unwrap();
+	let lv = LiveStatement {
+		id: sql::Uuid::from(lq_id),
+		node: sql::Uuid::from(dead_node),
+		what: Table(sql::Table::from("test_tb")),
+		..Default::default()
+	};
+	tx.putc_tblq("test_ns", "test_db", "test_tb", lv, None).await.unwrap();
+	let key = crate::key::node::lq::new(dead_node, lq_id, "test_ns", "test_db");
+	tx.putc(key, "test_tb", None).await.unwrap();
+	tx.commit().await.unwrap();
+	let membership = test.db.cluster_membership().await.unwrap();
+	let dead = membership.iter().find(|m| m.id == dead_node).unwrap();
+	assert_eq!(dead.last_seen, 1000);
+	assert_eq!(dead.live_queries, vec![lq_id]);
+
+	// The next tick takes over the live query, and removes the dead node
+	test.db.tick_at(1_000_000).await.unwrap();
+	let membership = test.db.cluster_membership().await.unwrap();
+	assert!(membership.iter().all(|m| m.id != dead_node));
+	let this = membership.iter().find(|m| m.id == node).unwrap();
+	assert_eq!(this.last_seen, 1_000_000_000);
+	assert_eq!(this.live_queries, vec![lq_id]);
+	let mut tx = test.db.transaction(false, false).await.unwrap();
+	let lv = tx.get_tb_live("test_ns", "test_db", "test_tb", &lq_id).await.unwrap();
+	assert_eq!(lv.node.0, node);
+	tx.cancel().await.unwrap();
+}
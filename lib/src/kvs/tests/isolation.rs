@@ -0,0 +1,26 @@
+#[tokio::test]
+#[serial]
+async fn isolation_of_interleaved_transactions() {
+	// Setup
+	let node_id = Uuid::parse_str("4d6e8f0a-1b3c-4d5e-8f7a-9b0c1d2e3f4a").unwrap();
+	let (ds, _) = new_ds(node_id).await;
+
+	// Run the randomized transactions
+	let opts = crate::kvs::IsolationOptions::new().with_transactions(100).with_seed(7);
+	let report = ds.check_isolation(opts).await.unwrap();
+	assert!(
+		report.is_clean(),
+		"{report}: {:?} {:?}",
+		report.anomalies,
+		report.inconsistent_indexes
+	);
+	assert_eq!(report.committed + report.aborted, 100);
+	assert!(report.committed > 0);
+	assert_eq!(report.seed, 7);
+
+	// The keys written by the transactions have been removed
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let res = tx.scan(b"\xff".to_vec()..b"\xff\xff".to_vec(), 10).await.unwrap();
+	assert!(res.is_empty());
+	tx.cancel().await.unwrap();
+}
@@ -36,6 +36,8 @@ mod mem {
 	include!("lq.rs");
 	include!("nq.rs");
 	include!("raw.rs");
+	include!("isolation.rs");
+	include!("recovery.rs");
 	include!("snapshot.rs");
 	include!("tb.rs");
 	include!("multireader.rs");
@@ -51,6 +53,8 @@ mod rocksdb {
 	use serial_test::serial;
 	use temp_dir::TempDir;
 
+	const SCHEME: &str = "rocksdb";
+
 	async fn new_ds(node_id: Uuid) -> (Datastore, Kvs) {
 		let path = TempDir::new().unwrap().path().to_string_lossy().to_string();
 		(
@@ -74,12 +78,15 @@ mod rocksdb {
 	include!("lq.rs");
 	include!("nq.rs");
 	include!("raw.rs");
+	include!("isolation.rs");
+	include!("recovery.rs");
 	include!("snapshot.rs");
 	include!("tb.rs");
 	include!("multireader.rs");
 	include!("multiwriter_different_keys.rs");
 	include!("multiwriter_same_keys_conflict.rs");
 	include!("timestamp_to_versionstamp.rs");
+	include!("readonly.rs");
 }
 
 #[cfg(feature = "kv-speedb")]
@@ -91,6 +98,8 @@ mod speedb {
 	use serial_test::serial;
 	use temp_dir::TempDir;
 
+	const SCHEME: &str = "speedb";
+
 	async fn new_ds(node_id: Uuid) -> (Datastore, Kvs) {
 		let path = TempDir::new().unwrap().path().to_string_lossy().to_string();
 		(
@@ -114,12 +123,15 @@ mod speedb {
 	include!("lq.rs");
 	include!("nq.rs");
 	include!("raw.rs");
+	include!("isolation.rs");
+	include!("recovery.rs");
 	include!("snapshot.rs");
 	include!("tb.rs");
 	include!("multireader.rs");
 	include!("multiwriter_different_keys.rs");
 	include!("multiwriter_same_keys_conflict.rs");
 	include!("timestamp_to_versionstamp.rs");
+	include!("readonly.rs");
 }
 
 #[cfg(feature = "kv-tikv")]
@@ -155,6 +167,8 @@ mod tikv {
 	include!("lq.rs");
 	include!("nq.rs");
 	include!("raw.rs");
+	include!("isolation.rs");
+	include!("recovery.rs");
 	include!("snapshot.rs");
 	include!("tb.rs");
 	include!("multireader.rs");
@@ -196,6 +210,8 @@ mod fdb {
 	include!("lq.rs");
 	include!("nq.rs");
 	include!("raw.rs");
+	include!("isolation.rs");
+	include!("recovery.rs");
 	include!("snapshot.rs");
 	include!("tb.rs");
 	include!("multireader.rs");
@@ -230,3 +230,36 @@ async fn scan() {
 	assert_eq!(val[1].1, b"2");
 	tx.cancel().await.unwrap();
 }
+
+#[tokio::test]
+#[serial]
+async fn merge() {
+	// Adds the number stored in a change to the stored number
+	fn add(val: Option<Vec<u8>>, change: Vec<u8>) -> Result<Vec<u8>, crate::err::Error> {
+		let num = |v: &[u8]| u64::from_be_bytes(v.try_into().unwrap_or_default());
+		Ok((val.as_deref().map_or(0, num) + num(&change)).to_be_bytes().to_vec())
+	}
+	// Create a new datastore
+	let node_id = Uuid::parse_str("8f0c2b6e-4d1a-4e7b-9c3f-2a5d6e7f8a9b").unwrap();
+	let (ds, _) = new_ds(node_id).await;
+	// Create a writeable transaction
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.merge("test", 1u64.to_be_bytes().to_vec(), add).await.unwrap();
+	// Coalesced changes are merged together without reading the key
+	tx.coalesce(true);
+	tx.merge("test", 2u64.to_be_bytes().to_vec(), add).await.unwrap();
+	tx.merge("test", 3u64.to_be_bytes().to_vec(), add).await.unwrap();
+	let val = tx.coalesced.get(b"test".as_slice());
+	assert!(
+		matches!(val, Some(crate::kvs::tx::Coalesced::Merge(v, _)) if v[..] == 5u64.to_be_bytes())
+	);
+	let val = tx.get("test").await.unwrap();
+	assert_eq!(val, Some(6u64.to_be_bytes().to_vec()));
+	tx.coalesce(false);
+	tx.commit().await.unwrap();
+	// Create a readonly transaction
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let val = tx.get("test").await.unwrap();
+	assert_eq!(val, Some(6u64.to_be_bytes().to_vec()));
+	tx.cancel().await.unwrap();
+}
@@ -0,0 +1,27 @@
+#[tokio::test]
+#[serial]
+async fn read_only_is_not_locked() {
+	let dir = TempDir::new().unwrap();
+	let path = format!("{SCHEME}:{}", dir.path().to_string_lossy());
+	// Write a key with the datastore which holds the lock
+	let ds = Datastore::new(&path).await.unwrap();
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.set("test", "ok").await.unwrap();
+	tx.commit().await.unwrap();
+	// A read-only datastore can be opened alongside it
+	let ro = Datastore::new_readonly(&path).await.unwrap();
+	let mut tx = ro.transaction(false, false).await.unwrap();
+	assert_eq!(tx.get("test").await.unwrap(), Some(b"ok".to_vec()));
+	assert_eq!(tx.scan("a".."z", 10).await.unwrap().len(), 1);
+	tx.cancel().await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn read_only_does_not_create_path() {
+	let dir = TempDir::new().unwrap();
+	let path = dir.path().join("missing");
+	let res = Datastore::new_readonly(&format!("{SCHEME}:{}", path.to_string_lossy())).await;
+	assert!(res.is_err());
+	assert!(!path.exists());
+}
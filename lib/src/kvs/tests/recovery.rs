@@ -0,0 +1,80 @@
+use crate::key::index::ib::Ib;
+
+#[tokio::test]
+#[serial]
+async fn recovery_removes_orphaned_state() {
+	// Setup
+	let node_id = Uuid::parse_str("b8a5c5b8-5d7e-4b5e-9d2a-3f7a1c2e4d6f").unwrap();
+	let test = init(node_id).await.unwrap();
+	let mut tx = test.db.transaction(true, false).await.unwrap();
+
+	// A live query registered by a node which no longer exists
+	let dead_node = Uuid::parse_str("0f8e3b1a-2c4d-4e6f-8a9b-1c2d3e4f5a6b").unwrap();
+	let live_query_id = Uuid::parse_str("5a6b7c8d-9e0f-4a1b-8c2d-3e4f5a6b7c8d").unwrap();
+	let key = crate::key::node::lq::new(dead_node, live_query_id, "test_ns", "test_db");
+	tx.putc(key, "test_tb", None).await.unwrap();
+
+	// The state of a background build, for an index which no longer exists
+	tx.add_ns("test_ns", false).await.unwrap();
+	tx.add_db("test_ns", "test_db", false).await.unwrap();
+	tx.add_tb("test_ns", "test_db", "test_tb", false).await.unwrap();
+	let key = Ib::new("test_ns", "test_db", "test_tb", "test_ix");
+	tx.set(key, vec![]).await.unwrap();
+	tx.commit().await.unwrap();
+
+	// Recover
+	let report = test.db.recover().await.unwrap();
+	assert!(report.orphaned_live_queries.contains(&live_query_id));
+	assert!(report
+		.rolled_back_index_builds
+		.contains(&"test_ns.test_db.test_tb.test_ix".to_owned()));
+	assert!(report.pending_index_builds.is_empty());
+
+	// The orphaned state has been removed
+	let mut tx = test.db.transaction(false, false).await.unwrap();
+	assert!(tx.scan_ndlq(&dead_node, 100).await.unwrap().is_empty());
+	assert!(!tx.exi(Ib::new("test_ns", "test_db", "test_tb", "test_ix")).await.unwrap());
+	tx.cancel().await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn recovery_rebuilds_views_without_auxiliary_state() {
+	// Setup
+	let node_id = Uuid::parse_str("3c1f9e2a-7b4d-4c6e-9f8a-2d5e6f7a8b9c").unwrap();
+	let test = init(node_id).await.unwrap();
+	let ses = Session::owner().with_ns("test_ns").with_db("test_db");
+	let sql = "
+		DEFINE TABLE person_by_age AS SELECT count(), math::max(score) AS high FROM person GROUP BY age;
+		CREATE person:one SET age = 39, score = 70;
+		CREATE person:two SET age = 39, score = 80;
+	";
+	for res in test.db.execute(sql, &ses, None).await.unwrap() {
+		res.result.unwrap();
+	}
+
+	// The view was created before the aggregates of its groups were tracked
+	let beg = crate::key::table::ag::prefix_tb("test_ns", "test_db", "person_by_age");
+	let end = crate::key::table::ag::suffix_tb("test_ns", "test_db", "person_by_age");
+	let mut tx = test.db.transaction(true, false).await.unwrap();
+	tx.delr(beg.clone()..end.clone(), u32::MAX).await.unwrap();
+	tx.commit().await.unwrap();
+
+	// Recover
+	let report = test.db.recover().await.unwrap();
+	assert_eq!(report.rebuilt_views, vec!["test_ns.test_db.person_by_age".to_owned()]);
+
+	// The auxiliary state has been rebuilt, so the records are retracted exactly
+	let mut tx = test.db.transaction(false, false).await.unwrap();
+	assert!(!tx.getr(beg..end, u32::MAX).await.unwrap().is_empty());
+	tx.cancel().await.unwrap();
+	let sql = "DELETE person:two; SELECT count, high FROM person_by_age;";
+	let res = &mut test.db.execute(sql, &ses, None).await.unwrap();
+	res.remove(0).result.unwrap();
+	let val = crate::sql::Value::parse("[{ count: 1, high: 70 }]");
+	assert_eq!(res.remove(0).result.unwrap(), val);
+
+	// The view is only rebuilt once
+	let report = test.db.recover().await.unwrap();
+	assert!(report.rebuilt_views.is_empty());
+}
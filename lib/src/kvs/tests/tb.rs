@@ -24,6 +24,13 @@ async fn table_definitions_can_be_scanned() {
 		permissions: Default::default(),
 		changefeed: None,
 		comment: None,
+		merge: false,
+		expire: None,
+		storage: None,
+		archive: None,
+		kind: Default::default(),
+		enumerated: None,
+		if_not_exists: false,
 	};
 	tx.set(&key, &value).await.unwrap();
 
@@ -61,6 +68,13 @@ async fn table_definitions_can_be_deleted() {
 		permissions: Default::default(),
 		changefeed: None,
 		comment: None,
+		merge: false,
+		expire: None,
+		storage: None,
+		archive: None,
+		kind: Default::default(),
+		enumerated: None,
+		if_not_exists: false,
 	};
 	tx.set(&key, &value).await.unwrap();
 
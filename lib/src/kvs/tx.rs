@@ -1,5 +1,10 @@
+use super::checksum;
+use super::ds::Transactor;
+use super::export::ExportFilter;
+use super::export::ExportOptions;
 use super::kv::Add;
 use super::kv::Convert;
+use super::placement;
 use super::Key;
 use super::Val;
 use crate::cf;
@@ -10,6 +15,7 @@ use crate::idg::u32::U32;
 use crate::key::debug;
 use crate::kvs::cache::Cache;
 use crate::kvs::cache::Entry;
+use crate::kvs::sequences::{Block, Sequences};
 use crate::kvs::Check;
 use crate::kvs::LqValue;
 use crate::sql;
@@ -17,6 +23,7 @@ use crate::sql::paths::EDGE;
 use crate::sql::paths::IN;
 use crate::sql::paths::OUT;
 use crate::sql::thing::Thing;
+use crate::sql::Storage;
 use crate::sql::Strand;
 use crate::sql::Value;
 use crate::vs::Oracle;
@@ -25,20 +32,25 @@ use channel::Sender;
 use futures::lock::Mutex;
 use sql::permission::Permissions;
 use sql::statements::DefineAnalyzerStatement;
+use sql::statements::DefineConfigStatement;
 use sql::statements::DefineDatabaseStatement;
 use sql::statements::DefineEventStatement;
 use sql::statements::DefineFieldStatement;
 use sql::statements::DefineFunctionStatement;
 use sql::statements::DefineIndexStatement;
+use sql::statements::DefineMigrationStatement;
 use sql::statements::DefineNamespaceStatement;
 use sql::statements::DefineParamStatement;
 use sql::statements::DefineScopeStatement;
+use sql::statements::DefineSequenceStatement;
 use sql::statements::DefineTableStatement;
 use sql::statements::DefineTokenStatement;
 use sql::statements::DefineUserStatement;
 use sql::statements::LiveStatement;
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::collections::VecDeque;
 use std::fmt;
 use std::fmt::Debug;
 use std::ops::Range;
@@ -49,6 +61,10 @@ use uuid::Uuid;
 #[cfg(target_arch = "wasm32")]
 use wasmtimer::std::{SystemTime, UNIX_EPOCH};
 
+/// The number of keys fetched at once when scanning a range backwards on a datastore
+/// which can only scan forwards
+const SCANR_BATCH_SIZE: u32 = 1000;
+
 /// A set of undoable updates and requests against a dataset.
 #[allow(dead_code)]
 pub struct Transaction {
@@ -57,6 +73,40 @@ pub struct Transaction {
 	pub(super) cf: cf::Writer,
 	pub(super) write_buffer: HashMap<Key, ()>,
 	pub(super) vso: Arc<Mutex<Oracle>>,
+	pub(super) index_cache_size: usize,
+	pub(super) checksums: bool,
+	/// The number of nested scopes in which writes are coalesced
+	pub(super) coalescing: usize,
+	/// The latest write to each coalesced key
+	pub(super) coalesced: BTreeMap<Key, Coalesced>,
+	/// The savepoints which the changes in this transaction can be rolled back to
+	pub(super) savepoints: Vec<Savepoint>,
+	/// The blocks of sequence values which are cached by the datastore
+	pub(super) sequences: Sequences,
+	/// The blocks of sequence values allocated by this transaction, cached once it commits
+	pub(super) allocated: HashMap<Key, Block>,
+}
+
+/// Merges a change into the value of a key, or into a previous change to the key
+pub(crate) type Merger = fn(Option<Val>, Val) -> Result<Val, Error>;
+
+/// A write which is held in memory until the coalesced writes are flushed
+pub(super) enum Coalesced {
+	/// The key was set to a value
+	Set(Val),
+	/// The key was deleted
+	Del,
+	/// Changes were merged together, to be merged into the value of the key
+	Merge(Val, Merger),
+}
+
+/// The changes made in a transaction since a named savepoint
+pub(super) struct Savepoint {
+	name: String,
+	/// The value of each changed key before the savepoint, or [`None`] if it didn't exist
+	undo: BTreeMap<Key, Option<Val>>,
+	/// The change feed mutations which were buffered before the savepoint
+	cf: cf::Writer,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -121,6 +171,174 @@ impl Transaction {
 		Arc::new(Mutex::new(self))
 	}
 
+	/// The number of index tree nodes cached in memory by each index
+	pub(crate) fn index_cache_size(&self) -> usize {
+		self.index_cache_size
+	}
+
+	/// Start or stop coalescing writes in this transaction.
+	///
+	/// While coalescing, writes and deletes are held in memory, so that
+	/// repeated writes to the same key are sent to the datastore once,
+	/// with the last value written. As reads of a coalesced key see its
+	/// latest value, repeated increments of a field are merged into a
+	/// single write. Scopes can be nested, and coalesced writes are kept
+	/// until the transaction is committed, or until an operation which
+	/// reads or writes ranges of keys needs them to be flushed.
+	pub(crate) fn coalesce(&mut self, enabled: bool) {
+		if enabled {
+			self.coalescing += 1;
+		} else {
+			self.coalescing = self.coalescing.saturating_sub(1);
+		}
+	}
+
+	/// Check if a write to a key should be held in memory
+	fn is_coalesced(&self, key: &Key) -> bool {
+		self.coalescing > 0 || self.coalesced.contains_key(key)
+	}
+
+	/// Send all coalesced writes to the datastore
+	async fn flush(&mut self) -> Result<(), Error> {
+		if self.coalesced.is_empty() {
+			return Ok(());
+		}
+		let depth = std::mem::take(&mut self.coalescing);
+		let writes = std::mem::take(&mut self.coalesced);
+		// Flushed writes don't change the values seen by the transaction
+		let savepoints = std::mem::take(&mut self.savepoints);
+		let res = async {
+			for (key, val) in writes {
+				match val {
+					Coalesced::Set(val) => self.set(key, val).await?,
+					Coalesced::Del => self.del(key).await?,
+					Coalesced::Merge(change, merger) => {
+						let val = self.get(key.clone()).await?;
+						self.set(key, merger(val, change)?).await?
+					}
+				}
+			}
+			Ok(())
+		}
+		.await;
+		self.coalescing = depth;
+		self.savepoints = savepoints;
+		res
+	}
+
+	/// Create a savepoint, which the changes made in this transaction can be rolled back to
+	pub fn savepoint(&mut self, name: &str) {
+		self.savepoints.push(Savepoint {
+			name: name.to_owned(),
+			undo: BTreeMap::new(),
+			cf: self.cf.clone(),
+		});
+	}
+
+	/// Roll back the changes made since a savepoint, keeping the savepoint itself.
+	///
+	/// Any savepoints created after the savepoint are released.
+	pub async fn rollback_to_savepoint(&mut self, name: &str) -> Result<(), Error> {
+		let Some(pos) = self.savepoints.iter().rposition(|v| v.name == name) else {
+			return Err(Error::SavepointNotFound {
+				value: name.to_owned(),
+			});
+		};
+		let mut savepoints = std::mem::take(&mut self.savepoints);
+		let res = async {
+			// Undo the changes of the newest savepoints first
+			for sp in savepoints[pos..].iter_mut().rev() {
+				for (key, val) in std::mem::take(&mut sp.undo) {
+					match val {
+						Some(val) => self.set(key, val).await?,
+						None => self.del(key).await?,
+					}
+				}
+			}
+			Ok(())
+		}
+		.await;
+		savepoints.truncate(pos + 1);
+		self.cf = savepoints[pos].cf.clone();
+		self.savepoints = savepoints;
+		// Cached definitions may have been rolled back
+		self.clear_cache();
+		// Allocated sequence values may have been rolled back
+		self.allocated.clear();
+		res
+	}
+
+	/// Release a savepoint, keeping the changes made since it was created.
+	///
+	/// Any savepoints created after the savepoint are released.
+	pub fn release_savepoint(&mut self, name: &str) -> Result<(), Error> {
+		let Some(pos) = self.savepoints.iter().rposition(|v| v.name == name) else {
+			return Err(Error::SavepointNotFound {
+				value: name.to_owned(),
+			});
+		};
+		// The changes can still be rolled back to an earlier savepoint
+		for sp in self.savepoints.split_off(pos) {
+			if let Some(last) = self.savepoints.last_mut() {
+				for (key, val) in sp.undo {
+					last.undo.entry(key).or_insert(val);
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// Keep the value of a key before it is changed, so that it can be rolled back
+	async fn undo(&mut self, key: &Key) -> Result<(), Error> {
+		if let Some(sp) = self.savepoints.last() {
+			if !sp.undo.contains_key(key) {
+				let val = self.get(key.clone()).await?;
+				if let Some(sp) = self.savepoints.last_mut() {
+					sp.undo.insert(key.clone(), val);
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// Returns the storage hint of the table of a record key, with the
+	/// position of the marker of the record key, if the table has one
+	async fn placement(&mut self, key: &[u8]) -> Result<Option<(usize, Storage)>, Error> {
+		let Some((ns, db, tb, at)) = placement::record(key) else {
+			return Ok(None);
+		};
+		let key = crate::key::database::tb::new(ns, db, tb).encode()?;
+		let storage = match self.cache.get(&key) {
+			Some(Entry::Tb(v)) => v.storage.clone(),
+			_ => {
+				let val = match self.coalesced.get(&key) {
+					Some(Coalesced::Set(val)) => Some(val.clone()),
+					Some(_) => None,
+					None => self.get_raw(key.clone()).await?,
+				};
+				match val {
+					Some(val) => {
+						let val: Arc<DefineTableStatement> = Arc::new(val.into());
+						let storage = val.storage.clone();
+						self.cache.set(key, Entry::Tb(val));
+						storage
+					}
+					None => None,
+				}
+			}
+		};
+		Ok(storage.map(|v| (at, v)))
+	}
+
+	/// Returns the key at which a key is stored, which differs from the key
+	/// for the records of a table with a storage hint
+	async fn place(&mut self, key: Key) -> Result<Key, Error> {
+		Ok(match self.placement(&key).await? {
+			Some((at, storage)) => placement::place(&key, at, &storage),
+			None => key,
+		})
+	}
+
 	// --------------------------------------------------
 	// Integral methods
 	// --------------------------------------------------
@@ -176,6 +394,9 @@ impl Transaction {
 	pub async fn cancel(&mut self) -> Result<(), Error> {
 		#[cfg(debug_assertions)]
 		trace!("Cancel");
+		self.coalesced.clear();
+		self.savepoints.clear();
+		self.allocated.clear();
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -215,10 +436,13 @@ impl Transaction {
 	/// Commit a transaction.
 	///
 	/// This attempts to commit all changes made within the transaction.
+	#[allow(unreachable_code)]
 	pub async fn commit(&mut self) -> Result<(), Error> {
 		#[cfg(debug_assertions)]
 		trace!("Commit");
-		match self {
+		self.flush().await?;
+		self.savepoints.clear();
+		let res = match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
 				inner: Inner::Mem(v),
@@ -251,7 +475,12 @@ impl Transaction {
 			} => v.commit().await,
 			#[allow(unreachable_patterns)]
 			_ => unreachable!(),
+		};
+		// Cache the sequence values which were allocated by this transaction
+		if res.is_ok() {
+			self.sequences.put(std::mem::take(&mut self.allocated));
 		}
+		res
 	}
 
 	/// Delete a key from the datastore.
@@ -262,6 +491,13 @@ impl Transaction {
 	{
 		#[cfg(debug_assertions)]
 		trace!("Del {:?}", crate::key::debug::sprint_key(&key.clone().into()));
+		let key: Key = key.into();
+		self.undo(&key).await?;
+		if self.is_coalesced(&key) {
+			self.coalesced.insert(key, Coalesced::Del);
+			return Ok(());
+		}
+		let key = self.place(key).await?;
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -306,6 +542,11 @@ impl Transaction {
 	{
 		#[cfg(debug_assertions)]
 		trace!("Exi {:?}", key);
+		let key: Key = key.into();
+		if let Some(val) = self.coalesced.get(&key) {
+			return Ok(!matches!(val, Coalesced::Del));
+		}
+		let key = self.place(key).await?;
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -343,13 +584,46 @@ impl Transaction {
 	}
 
 	/// Fetch a key from the datastore.
-	#[allow(unused_variables)]
+	///
+	/// The checksum of the value of a record is verified, if it has one.
 	pub async fn get<K>(&mut self, key: K) -> Result<Option<Val>, Error>
+	where
+		K: Into<Key> + Debug,
+	{
+		let key: Key = key.into();
+		match self.get_unverified(key.clone()).await? {
+			Some(val) => checksum::verify(&key, val, self.checksums).map(Some),
+			None => Ok(None),
+		}
+	}
+
+	/// Fetch a key from the datastore, without verifying its checksum.
+	#[allow(unused_variables)]
+	pub(crate) async fn get_unverified<K>(&mut self, key: K) -> Result<Option<Val>, Error>
 	where
 		K: Into<Key> + Debug,
 	{
 		#[cfg(debug_assertions)]
 		trace!("Get {:?}", key);
+		let key: Key = key.into();
+		let merge = match self.coalesced.get(&key) {
+			Some(Coalesced::Set(val)) => return Ok(Some(val.clone())),
+			Some(Coalesced::Del) => return Ok(None),
+			Some(Coalesced::Merge(change, merger)) => Some((change.clone(), *merger)),
+			None => None,
+		};
+		let key = self.place(key).await?;
+		let val = self.get_raw(key).await?;
+		// Merge any coalesced changes into the stored value
+		match merge {
+			Some((change, merger)) => merger(val, change).map(Some),
+			None => Ok(val),
+		}
+	}
+
+	/// Fetch a key from the datastore, exactly as it is stored.
+	#[allow(unused_variables)]
+	async fn get_raw(&mut self, key: Key) -> Result<Option<Val>, Error> {
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -386,6 +660,41 @@ impl Transaction {
 		}
 	}
 
+	/// Merge a change into the value of a key, such as an increment of a counter.
+	///
+	/// When writes are coalesced, the changes to a key are merged together,
+	/// without reading the key, and are only merged into its value once the
+	/// writes are flushed. The merger must therefore give the same value when
+	/// merging the changes one at a time, and when merging their merged change.
+	pub(crate) async fn merge<K>(
+		&mut self,
+		key: K,
+		change: Val,
+		merger: Merger,
+	) -> Result<(), Error>
+	where
+		K: Into<Key> + Debug,
+	{
+		#[cfg(debug_assertions)]
+		trace!("Merge {:?} => {:?}", key, change);
+		let key: Key = key.into();
+		if self.is_coalesced(&key) {
+			self.undo(&key).await?;
+			let val = match self.coalesced.remove(&key) {
+				Some(Coalesced::Set(val)) => Coalesced::Set(merger(Some(val), change)?),
+				Some(Coalesced::Del) => Coalesced::Set(merger(None, change)?),
+				Some(Coalesced::Merge(val, _)) => {
+					Coalesced::Merge(merger(Some(val), change)?, merger)
+				}
+				None => Coalesced::Merge(change, merger),
+			};
+			self.coalesced.insert(key, val);
+			return Ok(());
+		}
+		let val = self.get(key.clone()).await?;
+		self.set(key, merger(val, change)?).await
+	}
+
 	/// Insert or update a key in the datastore.
 	#[allow(unused_variables)]
 	pub async fn set<K, V>(&mut self, key: K, val: V) -> Result<(), Error>
@@ -395,6 +704,17 @@ impl Transaction {
 	{
 		#[cfg(debug_assertions)]
 		trace!("Set {:?} => {:?}", key, val);
+		let key: Key = key.into();
+		let mut val: Val = val.into();
+		self.undo(&key).await?;
+		if self.is_coalesced(&key) {
+			self.coalesced.insert(key, Coalesced::Set(val));
+			return Ok(());
+		}
+		if self.checksums && checksum::is_record(&key) {
+			val = checksum::seal(val);
+		}
+		let key = self.place(key).await?;
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -577,6 +897,14 @@ impl Transaction {
 	{
 		#[cfg(debug_assertions)]
 		trace!("Put {:?} => {:?}", key, val);
+		self.flush().await?;
+		let key: Key = key.into();
+		let mut val: Val = val.into();
+		self.undo(&key).await?;
+		if self.checksums && checksum::is_record(&key) {
+			val = checksum::seal(val);
+		}
+		let key = self.place(key).await?;
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -616,8 +944,24 @@ impl Transaction {
 	/// Retrieve a specific range of keys from the datastore.
 	///
 	/// This function fetches the full range of key-value pairs, in a single request to the underlying datastore.
-	#[allow(unused_variables)]
+	/// The checksums of the values of any records are verified, if they have one.
 	pub async fn scan<K>(&mut self, rng: Range<K>, limit: u32) -> Result<Vec<(Key, Val)>, Error>
+	where
+		K: Into<Key> + Debug + Clone,
+	{
+		let res = self.scan_unverified(rng, limit).await?;
+		res.into_iter()
+			.map(|(k, v)| checksum::verify(&k, v, self.checksums).map(|v| (k, v)))
+			.collect()
+	}
+
+	/// Retrieve a specific range of keys from the datastore, without verifying their checksums.
+	#[allow(unused_variables)]
+	pub(crate) async fn scan_unverified<K>(
+		&mut self,
+		rng: Range<K>,
+		limit: u32,
+	) -> Result<Vec<(Key, Val)>, Error>
 	where
 		K: Into<Key> + Debug + Clone,
 	{
@@ -627,6 +971,28 @@ impl Transaction {
 			debug::sprint_key(&rng.start.clone().into()),
 			debug::sprint_key(&rng.end.clone().into())
 		);
+		self.flush().await?;
+		let rng: Range<Key> = rng.start.into()..rng.end.into();
+		// The records of a table with a storage hint are scanned where they are stored
+		if let Some((at, storage)) = self.placement(&rng.start).await? {
+			if let Some(ranges) = placement::ranges(&rng, at, &storage) {
+				let mut res = Vec::new();
+				for rng in ranges {
+					for (k, v) in self.scan_raw(rng, limit).await? {
+						res.push((placement::unplace(k), v));
+					}
+				}
+				res.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+				res.truncate(limit as usize);
+				return Ok(res);
+			}
+		}
+		self.scan_raw(rng, limit).await
+	}
+
+	/// Retrieve a specific range of keys from the datastore, exactly as they are stored.
+	#[allow(unused_variables)]
+	async fn scan_raw(&mut self, rng: Range<Key>, limit: u32) -> Result<Vec<(Key, Val)>, Error> {
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -663,6 +1029,100 @@ impl Transaction {
 		}
 	}
 
+	/// Retrieve a specific range of keys from the datastore, in reverse order.
+	///
+	/// This function fetches the last key-value pairs of the range, starting from the end of the range.
+	pub async fn scanr<K>(&mut self, rng: Range<K>, limit: u32) -> Result<Vec<(Key, Val)>, Error>
+	where
+		K: Into<Key> + Debug + Clone,
+	{
+		#[cfg(debug_assertions)]
+		trace!(
+			"Scanr {:?} - {:?}",
+			debug::sprint_key(&rng.start.clone().into()),
+			debug::sprint_key(&rng.end.clone().into())
+		);
+		self.flush().await?;
+		let rng: Range<Key> = rng.start.into()..rng.end.into();
+		// The records of a table with a storage hint are scanned where they are stored
+		let placed = match self.placement(&rng.start).await? {
+			Some((at, storage)) => placement::ranges(&rng, at, &storage),
+			None => None,
+		};
+		let res = match placed {
+			Some(ranges) => {
+				let mut res = Vec::new();
+				for rng in ranges {
+					for (k, v) in self.scanr_raw(rng, limit).await? {
+						res.push((placement::unplace(k), v));
+					}
+				}
+				res.sort_unstable_by(|a, b| b.0.cmp(&a.0));
+				res.truncate(limit as usize);
+				res
+			}
+			None => self.scanr_raw(rng, limit).await?,
+		};
+		res.into_iter()
+			.map(|(k, v)| checksum::verify(&k, v, self.checksums).map(|v| (k, v)))
+			.collect()
+	}
+
+	/// Retrieve a specific range of keys from the datastore in reverse order, exactly as they are stored.
+	#[allow(unused_variables)]
+	async fn scanr_raw(&mut self, rng: Range<Key>, limit: u32) -> Result<Vec<(Key, Val)>, Error> {
+		match self {
+			#[cfg(feature = "kv-rocksdb")]
+			Transaction {
+				inner: Inner::RocksDB(v),
+				..
+			} => v.scanr(rng, limit).await,
+			#[cfg(feature = "kv-speedb")]
+			Transaction {
+				inner: Inner::SpeeDB(v),
+				..
+			} => v.scanr(rng, limit).await,
+			#[cfg(feature = "kv-fdb")]
+			Transaction {
+				inner: Inner::FoundationDB(v),
+				..
+			} => v.scanr(rng, limit).await,
+			// The other datastores can only scan forwards
+			#[allow(unreachable_patterns)]
+			_ => self.scanr_forwards(rng, limit).await,
+		}
+	}
+
+	/// Retrieve the last keys of a range by scanning the range forwards,
+	/// keeping at most `limit` key-value pairs in memory.
+	async fn scanr_forwards(
+		&mut self,
+		rng: Range<Key>,
+		limit: u32,
+	) -> Result<Vec<(Key, Val)>, Error> {
+		let mut beg: Key = rng.start;
+		let end: Key = rng.end;
+		let mut res = VecDeque::with_capacity(limit as usize);
+		loop {
+			let batch = self.scan_raw(beg.clone()..end.clone(), SCANR_BATCH_SIZE).await?;
+			let n = batch.len();
+			if let Some((k, _)) = batch.last() {
+				beg = k.clone();
+				beg.push(0x00);
+			}
+			for kv in batch {
+				if res.len() == limit as usize {
+					res.pop_front();
+				}
+				res.push_back(kv);
+			}
+			if n < SCANR_BATCH_SIZE as usize {
+				break;
+			}
+		}
+		Ok(res.into_iter().rev().collect())
+	}
+
 	/// Update a key in the datastore if the current value matches a condition.
 	#[allow(unused_variables)]
 	pub async fn putc<K, V>(&mut self, key: K, val: V, chk: Option<V>) -> Result<(), Error>
@@ -672,6 +1132,10 @@ impl Transaction {
 	{
 		#[cfg(debug_assertions)]
 		trace!("Putc {:?} if {:?} => {:?}", key, chk, val);
+		self.flush().await?;
+		let key: Key = key.into();
+		self.undo(&key).await?;
+		let key = self.place(key).await?;
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -717,6 +1181,10 @@ impl Transaction {
 	{
 		#[cfg(debug_assertions)]
 		trace!("Delc {:?} if {:?}", key, chk);
+		self.flush().await?;
+		let key: Key = key.into();
+		self.undo(&key).await?;
+		let key = self.place(key).await?;
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -831,14 +1299,14 @@ impl Transaction {
 					let min = beg.clone();
 					let max = end.clone();
 					let num = std::cmp::min(1000, num);
-					self.scan(min..max, num).await?
+					self.scan_unverified(min..max, num).await?
 				}
 				Some(ref mut beg) => {
 					beg.push(0x00);
 					let min = beg.clone();
 					let max = end.clone();
 					let num = std::cmp::min(1000, num);
-					self.scan(min..max, num).await?
+					self.scan_unverified(min..max, num).await?
 				}
 			};
 			// Get total results
@@ -934,14 +1402,14 @@ impl Transaction {
 					let min = beg.clone();
 					let max = end.clone();
 					let num = std::cmp::min(1000, num);
-					self.scan(min..max, num).await?
+					self.scan_unverified(min..max, num).await?
 				}
 				Some(ref mut beg) => {
 					beg.push(0);
 					let min = beg.clone();
 					let max = end.clone();
 					let num = std::cmp::min(1000, num);
-					self.scan(min..max, num).await?
+					self.scan_unverified(min..max, num).await?
 				}
 			};
 			// Get total results
@@ -1442,6 +1910,75 @@ impl Transaction {
 		})
 	}
 
+	/// Retrieve all config definitions for a specific database.
+	pub async fn all_db_configs(
+		&mut self,
+		ns: &str,
+		db: &str,
+	) -> Result<Arc<[DefineConfigStatement]>, Error> {
+		let key = crate::key::database::cg::prefix(ns, db);
+		Ok(if let Some(e) = self.cache.get(&key) {
+			if let Entry::Cgs(v) = e {
+				v
+			} else {
+				unreachable!();
+			}
+		} else {
+			let beg = crate::key::database::cg::prefix(ns, db);
+			let end = crate::key::database::cg::suffix(ns, db);
+			let val = self.getr(beg..end, u32::MAX).await?;
+			let val = val.convert().into();
+			self.cache.set(key, Entry::Cgs(Arc::clone(&val)));
+			val
+		})
+	}
+
+	/// Retrieve all sequence definitions for a specific database.
+	pub async fn all_db_sequences(
+		&mut self,
+		ns: &str,
+		db: &str,
+	) -> Result<Arc<[DefineSequenceStatement]>, Error> {
+		let key = crate::key::database::sq::prefix(ns, db);
+		Ok(if let Some(e) = self.cache.get(&key) {
+			if let Entry::Sqs(v) = e {
+				v
+			} else {
+				unreachable!();
+			}
+		} else {
+			let beg = crate::key::database::sq::prefix(ns, db);
+			let end = crate::key::database::sq::suffix(ns, db);
+			let val = self.getr(beg..end, u32::MAX).await?;
+			let val = val.convert().into();
+			self.cache.set(key, Entry::Sqs(Arc::clone(&val)));
+			val
+		})
+	}
+
+	/// Retrieve all migration definitions for a specific database, in order of their version.
+	pub async fn all_db_migrations(
+		&mut self,
+		ns: &str,
+		db: &str,
+	) -> Result<Arc<[DefineMigrationStatement]>, Error> {
+		let key = crate::key::database::mg::prefix(ns, db);
+		Ok(if let Some(e) = self.cache.get(&key) {
+			if let Entry::Mgs(v) = e {
+				v
+			} else {
+				unreachable!();
+			}
+		} else {
+			let beg = crate::key::database::mg::prefix(ns, db);
+			let end = crate::key::database::mg::suffix(ns, db);
+			let val = self.getr(beg..end, u32::MAX).await?;
+			let val = val.convert().into();
+			self.cache.set(key, Entry::Mgs(Arc::clone(&val)));
+			val
+		})
+	}
+
 	/// Retrieve all scope definitions for a specific database.
 	pub async fn all_sc(
 		&mut self,
@@ -2063,6 +2600,107 @@ impl Transaction {
 		})
 	}
 
+	/// Retrieve a specific config definition.
+	pub async fn get_and_cache_db_config(
+		&mut self,
+		ns: &str,
+		db: &str,
+		cg: &str,
+	) -> Result<Arc<DefineConfigStatement>, Error> {
+		let key = crate::key::database::cg::new(ns, db, cg).encode()?;
+		Ok(if let Some(e) = self.cache.get(&key) {
+			if let Entry::Cg(v) = e {
+				v
+			} else {
+				unreachable!();
+			}
+		} else {
+			let val = self.get(key.clone()).await?.ok_or(Error::CgNotFound {
+				value: cg.to_owned(),
+			})?;
+			let val: Arc<DefineConfigStatement> = Arc::new(val.into());
+			self.cache.set(key, Entry::Cg(Arc::clone(&val)));
+			val
+		})
+	}
+
+	/// Retrieve a specific sequence definition.
+	pub async fn get_and_cache_db_sequence(
+		&mut self,
+		ns: &str,
+		db: &str,
+		sq: &str,
+	) -> Result<Arc<DefineSequenceStatement>, Error> {
+		let key = crate::key::database::sq::new(ns, db, sq).encode()?;
+		Ok(if let Some(e) = self.cache.get(&key) {
+			if let Entry::Sq(v) = e {
+				v
+			} else {
+				unreachable!();
+			}
+		} else {
+			let val = self.get(key.clone()).await?.ok_or(Error::SqNotFound {
+				value: sq.to_owned(),
+			})?;
+			let val: Arc<DefineSequenceStatement> = Arc::new(val.into());
+			self.cache.set(key, Entry::Sq(Arc::clone(&val)));
+			val
+		})
+	}
+
+	/// Retrieve the first value of a sequence which has not been allocated to a node.
+	pub async fn get_sequence_value(
+		&mut self,
+		ns: &str,
+		db: &str,
+		sq: &DefineSequenceStatement,
+	) -> Result<i64, Error> {
+		let key = crate::key::database::sv::new(ns, db, &sq.name);
+		match self.get(key).await? {
+			Some(v) => {
+				let v: [u8; 8] = v.as_slice().try_into().map_err(|_| Error::Unreachable)?;
+				Ok(i64::from_be_bytes(v))
+			}
+			None => Ok(sq.start),
+		}
+	}
+
+	/// Discard the values of a sequence which are cached by this node.
+	pub fn clear_sequence(&mut self, ns: &str, db: &str, sq: &str) {
+		let key: Key = crate::key::database::sv::new(ns, db, sq).into();
+		self.allocated.remove(&key);
+		self.sequences.remove(&key);
+	}
+
+	/// Retrieve the next value of a sequence.
+	///
+	/// Values are handed out from a block of values which is cached by this
+	/// node, so that concurrent transactions only conflict when a new block
+	/// is allocated. Values are increasing on each node, but may have gaps.
+	pub async fn next_in_sequence(&mut self, ns: &str, db: &str, sq: &str) -> Result<i64, Error> {
+		let key: Key = crate::key::database::sv::new(ns, db, sq).into();
+		// Check the sequence is still defined
+		let sq = self.get_and_cache_db_sequence(ns, db, sq).await?;
+		// Take a value from a block allocated by this transaction
+		if let Some(v) = self.allocated.get_mut(&key).and_then(|b| b.take(sq.version)) {
+			return Ok(v);
+		}
+		// Take a value from a block cached by this node
+		if let Some(v) = self.sequences.take(&key, sq.version) {
+			return Ok(v);
+		}
+		// Allocate a new block of values to this node
+		let beg = self.get_sequence_value(ns, db, &sq).await?;
+		let end = beg
+			.checked_add(sq.batch.max(1) as i64)
+			.ok_or_else(|| Error::TryAdd(beg.to_string(), sq.batch.to_string()))?;
+		self.set(key.clone(), end.to_be_bytes().to_vec()).await?;
+		let mut block = Block::new(sq.version, beg, end);
+		let v = block.take(sq.version);
+		self.allocated.insert(key, block);
+		v.ok_or(Error::Unreachable)
+	}
+
 	/// Retrieve a specific table index definition.
 	pub async fn get_and_cache_tb_index(
 		&mut self,
@@ -2203,6 +2841,103 @@ impl Transaction {
 
 	/// Writes the full database contents as binary SQL.
 	pub async fn export(&mut self, ns: &str, db: &str, chn: Sender<Vec<u8>>) -> Result<(), Error> {
+		// Output the definitions
+		let tbs = self.export_definitions(ns, db, None, &chn).await?;
+		if tbs.is_empty() {
+			return Ok(());
+		}
+		// Start transaction
+		export_transaction(&chn, "BEGIN").await?;
+		// Output TABLE data
+		for tb in tbs.iter() {
+			export_table_data(&chn, &tb.name).await?;
+			let mut nxt = None;
+			loop {
+				let res = self.export_batch(ns, db, &tb.name, &mut nxt, 1000).await?;
+				if res.is_empty() {
+					break;
+				}
+				for (t, v) in res.iter() {
+					chn.send(bytes!(export_record(t, v))).await?;
+				}
+			}
+			chn.send(bytes!("")).await?;
+		}
+		// Commit transaction
+		export_transaction(&chn, "COMMIT").await?;
+		// Everything exported
+		Ok(())
+	}
+
+	/// Writes the database contents as binary SQL, reading from the given transaction.
+	/// Only the tables, and the records, which are matched by the filter are written.
+	/// If a transactor is given, each batch of records is scanned in a new transaction.
+	/// The filter of each record is evaluated within the transaction which scanned it.
+	pub(crate) async fn export_with_filter(
+		txn: &crate::dbs::Transaction,
+		ns: &str,
+		db: &str,
+		flt: &ExportFilter<'_>,
+		stable: Option<&Transactor>,
+		chn: Sender<Vec<u8>>,
+	) -> Result<(), Error> {
+		// Output the definitions
+		let tbs = txn.lock().await.export_definitions(ns, db, Some(&flt.cfg), &chn).await?;
+		if tbs.is_empty() {
+			return Ok(());
+		}
+		// Start transaction
+		export_transaction(&chn, "BEGIN").await?;
+		// Output TABLE data
+		for tb in tbs.iter() {
+			export_table_data(&chn, &tb.name).await?;
+			let mut nxt = None;
+			loop {
+				// Scan the batch in a new transaction, if iterating stably
+				let batch = match stable {
+					Some(tr) => Some(tr.transaction(false, false).await?.enclose()),
+					None => None,
+				};
+				let run = batch.as_ref().unwrap_or(txn);
+				let num = flt.cfg.batch_size;
+				let done = async {
+					let res =
+						run.lock().await.export_batch(ns, db, &tb.name, &mut nxt, num).await?;
+					for (t, v) in res.iter() {
+						// Check if this record is filtered out
+						if flt.matches(run, &tb.name, t, v).await? {
+							chn.send(bytes!(export_record(t, v))).await?;
+						}
+					}
+					Ok::<_, Error>(res.is_empty())
+				}
+				.await;
+				// The batch transaction is only ever read, so it is
+				// cancelled whether or not the batch was exported
+				if let Some(batch) = batch {
+					batch.lock().await.cancel().await?;
+				}
+				if done? {
+					break;
+				}
+			}
+			chn.send(bytes!("")).await?;
+		}
+		// Commit transaction
+		export_transaction(&chn, "COMMIT").await?;
+		// Everything exported
+		Ok(())
+	}
+
+	/// Writes the definitions of the database as binary SQL, returning the tables whose
+	/// records are exported. Only the tables which are included by the options are written.
+	async fn export_definitions(
+		&mut self,
+		ns: &str,
+		db: &str,
+		cfg: Option<&ExportOptions>,
+		chn: &Sender<Vec<u8>>,
+	) -> Result<Vec<DefineTableStatement>, Error> {
 		// Output OPTIONS
 		{
 			chn.send(bytes!("-- ------------------------------")).await?;
@@ -2254,6 +2989,54 @@ impl Transaction {
 				chn.send(bytes!("")).await?;
 			}
 		}
+		// Output CONFIGS
+		{
+			let cgs = self.all_db_configs(ns, db).await?;
+			if !cgs.is_empty() {
+				chn.send(bytes!("-- ------------------------------")).await?;
+				chn.send(bytes!("-- CONFIGS")).await?;
+				chn.send(bytes!("-- ------------------------------")).await?;
+				chn.send(bytes!("")).await?;
+				for cg in cgs.iter() {
+					chn.send(bytes!(format!("{cg};"))).await?;
+				}
+				chn.send(bytes!("")).await?;
+			}
+		}
+		// Output SEQUENCES
+		{
+			let sqs = self.all_db_sequences(ns, db).await?;
+			if !sqs.is_empty() {
+				chn.send(bytes!("-- ------------------------------")).await?;
+				chn.send(bytes!("-- SEQUENCES")).await?;
+				chn.send(bytes!("-- ------------------------------")).await?;
+				chn.send(bytes!("")).await?;
+				for sq in sqs.iter() {
+					// Continue the sequence from the values which are already allocated
+					let start = self.get_sequence_value(ns, db, sq).await?;
+					let sq = DefineSequenceStatement {
+						start,
+						..sq.clone()
+					};
+					chn.send(bytes!(format!("{sq};"))).await?;
+				}
+				chn.send(bytes!("")).await?;
+			}
+		}
+		// Output MIGRATIONS
+		{
+			let mgs = self.all_db_migrations(ns, db).await?;
+			if !mgs.is_empty() {
+				chn.send(bytes!("-- ------------------------------")).await?;
+				chn.send(bytes!("-- MIGRATIONS")).await?;
+				chn.send(bytes!("-- ------------------------------")).await?;
+				chn.send(bytes!("")).await?;
+				for mg in mgs.iter() {
+					chn.send(bytes!(format!("{mg};"))).await?;
+				}
+				chn.send(bytes!("")).await?;
+			}
+		}
 		// Output FUNCTIONS
 		{
 			let fcs = self.all_db_functions(ns, db).await?;
@@ -2308,122 +3091,78 @@ impl Transaction {
 			}
 		}
 		// Output TABLES
-		{
-			let tbs = self.all_tb(ns, db).await?;
-			if !tbs.is_empty() {
-				for tb in tbs.iter() {
-					// Output TABLE
-					chn.send(bytes!("-- ------------------------------")).await?;
-					chn.send(bytes!(format!("-- TABLE: {}", tb.name))).await?;
-					chn.send(bytes!("-- ------------------------------")).await?;
-					chn.send(bytes!("")).await?;
-					chn.send(bytes!(format!("{tb};"))).await?;
-					chn.send(bytes!("")).await?;
-					// Output FIELDS
-					let fds = self.all_tb_fields(ns, db, &tb.name).await?;
-					if !fds.is_empty() {
-						for fd in fds.iter() {
-							chn.send(bytes!(format!("{fd};"))).await?;
-						}
-						chn.send(bytes!("")).await?;
-					}
-					// Output INDEXES
-					let ixs = self.all_tb_indexes(ns, db, &tb.name).await?;
-					if !ixs.is_empty() {
-						for ix in ixs.iter() {
-							chn.send(bytes!(format!("{ix};"))).await?;
-						}
-						chn.send(bytes!("")).await?;
-					}
-					// Output EVENTS
-					let evs = self.all_tb_events(ns, db, &tb.name).await?;
-					if !evs.is_empty() {
-						for ev in evs.iter() {
-							chn.send(bytes!(format!("{ev};"))).await?;
-						}
-						chn.send(bytes!("")).await?;
-					}
+		let tbs = self.all_tb(ns, db).await?;
+		let tbs: Vec<_> = match cfg {
+			Some(cfg) => tbs.iter().filter(|tb| cfg.includes(&tb.name)).cloned().collect(),
+			None => tbs.to_vec(),
+		};
+		for tb in tbs.iter() {
+			// Output TABLE
+			chn.send(bytes!("-- ------------------------------")).await?;
+			chn.send(bytes!(format!("-- TABLE: {}", tb.name))).await?;
+			chn.send(bytes!("-- ------------------------------")).await?;
+			chn.send(bytes!("")).await?;
+			chn.send(bytes!(format!("{tb};"))).await?;
+			chn.send(bytes!("")).await?;
+			// Output FIELDS
+			let fds = self.all_tb_fields(ns, db, &tb.name).await?;
+			if !fds.is_empty() {
+				for fd in fds.iter() {
+					chn.send(bytes!(format!("{fd};"))).await?;
 				}
-				// Start transaction
-				chn.send(bytes!("-- ------------------------------")).await?;
-				chn.send(bytes!("-- TRANSACTION")).await?;
-				chn.send(bytes!("-- ------------------------------")).await?;
-				chn.send(bytes!("")).await?;
-				chn.send(bytes!("BEGIN TRANSACTION;")).await?;
 				chn.send(bytes!("")).await?;
-				// Output TABLE data
-				for tb in tbs.iter() {
-					// Start records
-					chn.send(bytes!("-- ------------------------------")).await?;
-					chn.send(bytes!(format!("-- TABLE DATA: {}", tb.name))).await?;
-					chn.send(bytes!("-- ------------------------------")).await?;
-					chn.send(bytes!("")).await?;
-					// Fetch records
-					let beg = crate::key::thing::prefix(ns, db, &tb.name);
-					let end = crate::key::thing::suffix(ns, db, &tb.name);
-					let mut nxt: Option<Vec<u8>> = None;
-					loop {
-						let res = match nxt {
-							None => {
-								let min = beg.clone();
-								let max = end.clone();
-								self.scan(min..max, 1000).await?
-							}
-							Some(ref mut beg) => {
-								beg.push(0x00);
-								let min = beg.clone();
-								let max = end.clone();
-								self.scan(min..max, 1000).await?
-							}
-						};
-						if !res.is_empty() {
-							// Get total results
-							let n = res.len();
-							// Exit when settled
-							if n == 0 {
-								break;
-							}
-							// Loop over results
-							for (i, (k, v)) in res.into_iter().enumerate() {
-								// Ready the next
-								if n == i + 1 {
-									nxt = Some(k.clone());
-								}
-								// Parse the key and the value
-								let k: crate::key::thing::Thing = (&k).into();
-								let v: Value = (&v).into();
-								let t = Thing::from((k.tb, k.id));
-								// Check if this is a graph edge
-								match (v.pick(&*EDGE), v.pick(&*IN), v.pick(&*OUT)) {
-									// This is a graph edge record
-									(Value::Bool(true), Value::Thing(l), Value::Thing(r)) => {
-										let sql = format!("RELATE {l} -> {t} -> {r} CONTENT {v};",);
-										chn.send(bytes!(sql)).await?;
-									}
-									// This is a normal record
-									_ => {
-										let sql = format!("UPDATE {t} CONTENT {v};");
-										chn.send(bytes!(sql)).await?;
-									}
-								}
-							}
-							continue;
-						}
-						break;
-					}
-					chn.send(bytes!("")).await?;
+			}
+			// Output INDEXES
+			let ixs = self.all_tb_indexes(ns, db, &tb.name).await?;
+			if !ixs.is_empty() {
+				for ix in ixs.iter() {
+					chn.send(bytes!(format!("{ix};"))).await?;
 				}
-				// Commit transaction
-				chn.send(bytes!("-- ------------------------------")).await?;
-				chn.send(bytes!("-- TRANSACTION")).await?;
-				chn.send(bytes!("-- ------------------------------")).await?;
 				chn.send(bytes!("")).await?;
-				chn.send(bytes!("COMMIT TRANSACTION;")).await?;
+			}
+			// Output EVENTS
+			let evs = self.all_tb_events(ns, db, &tb.name).await?;
+			if !evs.is_empty() {
+				for ev in evs.iter() {
+					chn.send(bytes!(format!("{ev};"))).await?;
+				}
 				chn.send(bytes!("")).await?;
 			}
 		}
-		// Everything exported
-		Ok(())
+		Ok(tbs)
+	}
+
+	/// Scans the next batch of records of a table, after the key of the previous batch
+	async fn export_batch(
+		&mut self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		nxt: &mut Option<Key>,
+		num: u32,
+	) -> Result<Vec<(Thing, Value)>, Error> {
+		let beg = match nxt.take() {
+			Some(mut k) => {
+				k.push(0x00);
+				k
+			}
+			None => crate::key::thing::prefix(ns, db, tb),
+		};
+		let end = crate::key::thing::suffix(ns, db, tb);
+		let res = self.scan(beg..end, num).await?;
+		// Ready the next
+		*nxt = res.last().map(|(k, _)| k.clone());
+		let mut out = Vec::with_capacity(res.len());
+		for (k, v) in res.iter() {
+			// Parse the key and the value
+			let k: crate::key::thing::Thing = k.into();
+			let mut v: Value = v.into();
+			let t = Thing::from((k.tb, k.id));
+			// Resolve the values of any deduplicated fields
+			crate::doc::resolve_with(self, ns, db, tb, &mut v).await?;
+			out.push((t, v));
+		}
+		Ok(out)
 	}
 
 	// change will record the change in the changefeed if enabled.
@@ -2718,6 +3457,39 @@ impl Transaction {
 	}
 }
 
+/// Writes the start or the end of the transaction around the exported records
+async fn export_transaction(chn: &Sender<Vec<u8>>, stm: &str) -> Result<(), Error> {
+	chn.send(bytes!("-- ------------------------------")).await?;
+	chn.send(bytes!("-- TRANSACTION")).await?;
+	chn.send(bytes!("-- ------------------------------")).await?;
+	chn.send(bytes!("")).await?;
+	chn.send(bytes!(format!("{stm} TRANSACTION;"))).await?;
+	chn.send(bytes!("")).await?;
+	Ok(())
+}
+
+/// Writes the header before the exported records of a table
+async fn export_table_data(chn: &Sender<Vec<u8>>, tb: &str) -> Result<(), Error> {
+	chn.send(bytes!("-- ------------------------------")).await?;
+	chn.send(bytes!(format!("-- TABLE DATA: {tb}"))).await?;
+	chn.send(bytes!("-- ------------------------------")).await?;
+	chn.send(bytes!("")).await?;
+	Ok(())
+}
+
+/// Returns the statement which recreates an exported record
+fn export_record(t: &Thing, v: &Value) -> String {
+	// Check if this is a graph edge
+	match (v.pick(&*EDGE), v.pick(&*IN), v.pick(&*OUT)) {
+		// This is a graph edge record
+		(Value::Bool(true), Value::Thing(l), Value::Thing(r)) => {
+			format!("RELATE {l} -> {t} -> {r} CONTENT {v};")
+		}
+		// This is a normal record
+		_ => format!("UPDATE {t} CONTENT {v};"),
+	}
+}
+
 #[cfg(test)]
 #[cfg(feature = "kv-mem")]
 mod tests {
@@ -164,6 +164,11 @@ pub mod channel {
 pub mod error {
 	pub use crate::api::err::Error as Api;
 	pub use crate::err::Error as Db;
+	pub use crate::err::ErrorCategory;
+	pub use crate::sql::error::{
+		ColorChoice, Diagnostic, Related, RenderOptions, RenderedError, Severity, Span,
+		StatementSpan,
+	};
 }
 
 /// An error originating from the SurrealDB client library
@@ -0,0 +1,275 @@
+//! Checks parsed queries for mistakes which would otherwise only be found when the query is
+//! run, such as parameters which are never defined, or functions which are called with the
+//! wrong number of arguments.
+
+use crate::cnf::PROTECTED_PARAM_NAMES;
+use crate::fnc;
+use crate::fnc::args::Arity;
+use crate::sql::error::SemanticError;
+use crate::sql::function::Function;
+use crate::sql::lexer::{lex, Token, TokenKind};
+use crate::sql::spanned::{SpannedQuery, SpannedValue};
+use crate::sql::special::contains_idiom_start;
+use crate::sql::statement::Statement;
+use crate::sql::statements::{DefineStatement, SelectStatement};
+use crate::sql::subquery::Subquery;
+use crate::sql::value::Value;
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// The parameters which are set by the database as a query is run, other than those which
+/// are protected.
+const BUILTIN_PARAMS: &[&str] =
+	&["this", "self", "parent", "value", "input", "before", "after", "event"];
+
+/// The keywords which define the parameter following them.
+const DEFINING_KEYWORDS: &[&str] = &["LET", "FOR", "PARAM"];
+
+/// Returns the mistakes found in a parsed query, in the order they appear in the input.
+///
+/// The `params` are the names of the parameters which are defined outside of the query, such
+/// as the parameters of the session, or those defined in the database.
+pub(crate) fn analyze<'a>(
+	input: &'a str,
+	query: &SpannedQuery,
+	params: &[&str],
+) -> Vec<SemanticError<&'a str>> {
+	let mut errors = Vec::new();
+	// The custom functions defined in the query, by name
+	let functions: HashMap<&str, usize> = query
+		.statements
+		.iter()
+		.filter_map(|v| match &v.statement {
+			Statement::Define(DefineStatement::Function(f)) => {
+				Some((f.name.as_str(), f.args.len()))
+			}
+			_ => None,
+		})
+		.collect();
+	// The parameters of a scope are only known when signing in or up
+	let ignored: Vec<Range<usize>> = query
+		.statements
+		.iter()
+		.filter(|v| matches!(v.statement, Statement::Define(DefineStatement::Scope(_))))
+		.map(|v| v.span.clone())
+		.collect();
+	check_params(input, params, &ignored, &mut errors);
+	for stm in query.statements.iter() {
+		if let Statement::Select(v) = &stm.statement {
+			check_fetch(input, stm.span.clone(), v, &mut errors);
+		}
+		for v in stm.expressions.iter() {
+			check_value(input, v, &functions, &mut errors);
+		}
+	}
+	errors.sort_by_key(|e| std::cmp::Reverse(e.tried().len()));
+	errors
+}
+
+/// Checks that every parameter is defined before it is used.
+///
+/// Parameters are defined by LET and FOR statements, by DEFINE PARAM statements, and as the
+/// arguments of DEFINE FUNCTION statements, which are each found from the tokens around the
+/// parameter, so that a parameter is only in scope after it is defined.
+fn check_params<'a>(
+	input: &'a str,
+	params: &[&str],
+	ignored: &[Range<usize>],
+	errors: &mut Vec<SemanticError<&'a str>>,
+) {
+	let tokens: Vec<Token> = lex(input).filter(|t| t.kind != TokenKind::Comment).collect();
+	let mut defined = Vec::new();
+	for (i, t) in tokens.iter().enumerate() {
+		if t.kind != TokenKind::Parameter {
+			continue;
+		}
+		let name = &input[t.span.start + 1..t.span.end];
+		let defines = match (i.checked_sub(1).map(|i| &tokens[i]), tokens.get(i + 1)) {
+			// A parameter following LET, FOR, or PARAM
+			(Some(p), _) if p.kind == TokenKind::Keyword => {
+				let word = &input[p.span.clone()];
+				DEFINING_KEYWORDS.iter().any(|k| word.eq_ignore_ascii_case(k))
+			}
+			// A function argument, which is followed by its type
+			(_, Some(n)) => &input[n.span.clone()] == ":",
+			_ => false,
+		};
+		if defines {
+			defined.push(name);
+			continue;
+		}
+		if defined.contains(&name)
+			|| params.contains(&name)
+			|| BUILTIN_PARAMS.contains(&name)
+			|| PROTECTED_PARAM_NAMES.contains(&name)
+			|| ignored.iter().any(|r| r.contains(&t.span.start))
+		{
+			continue;
+		}
+		errors.push(SemanticError::UnknownParam {
+			tried: &input[t.span.start..],
+			name: name.to_owned(),
+		});
+	}
+}
+
+/// Checks that the functions within an expression are called with the right number of
+/// arguments, along with any SELECT subqueries within it.
+fn check_value<'a>(
+	input: &'a str,
+	v: &SpannedValue,
+	functions: &HashMap<&str, usize>,
+	errors: &mut Vec<SemanticError<&'a str>>,
+) {
+	match &v.value {
+		Value::Function(f) => {
+			let (name, arity, found) = match f.as_ref() {
+				Function::Normal(name, args) => (name.clone(), fnc::arity(name), args.len()),
+				Function::Custom(name, args) => (
+					format!("fn::{name}"),
+					functions.get(name.as_str()).map(|&len| Arity {
+						min: len,
+						max: Some(len),
+					}),
+					args.len(),
+				),
+				_ => (String::new(), None, 0),
+			};
+			if let Some(arity) = arity.filter(|a| !a.accepts(found)) {
+				errors.push(SemanticError::Arity {
+					tried: &input[v.span.start..],
+					name,
+					expected: arity.to_string(),
+					found,
+				});
+			}
+		}
+		Value::Subquery(s) => {
+			if let Subquery::Select(s) = s.as_ref() {
+				check_fetch(input, v.span.clone(), s, errors);
+			}
+		}
+		_ => {}
+	}
+	for v in v.children.iter() {
+		check_value(input, v, functions, errors);
+	}
+}
+
+/// Checks that the fields in the FETCH clause of a SELECT statement are in its projection, as
+/// for the fields in its ORDER BY, GROUP BY, and SPLIT ON clauses.
+fn check_fetch<'a>(
+	input: &'a str,
+	span: Range<usize>,
+	stm: &SelectStatement,
+	errors: &mut Vec<SemanticError<&'a str>>,
+) {
+	if let Some(fetchs) = &stm.fetch {
+		for fetch in fetchs.iter() {
+			if !contains_idiom_start(&stm.expr, fetch) {
+				let idiom = fetch.to_string();
+				errors.push(SemanticError::Fetch {
+					tried: &input[fetch_offset(input, span.clone(), &idiom)..],
+					idiom,
+				});
+			}
+		}
+	}
+}
+
+/// Returns the offset of a field in the FETCH clause of a SELECT statement, or of the clause
+/// itself if the field is written differently to how it is displayed.
+fn fetch_offset(input: &str, span: Range<usize>, idiom: &str) -> usize {
+	let source = &input[span.clone()];
+	// The clause of this statement, rather than of a subquery within it, is nested within
+	// the same number of brackets as the SELECT keyword
+	let mut depth = 0usize;
+	let mut select = None;
+	let mut offset = span.start;
+	for t in lex(source) {
+		let text = &source[t.span.clone()];
+		match t.kind {
+			TokenKind::Punctuation if matches!(text, "(" | "[" | "{") => depth += 1,
+			TokenKind::Punctuation if matches!(text, ")" | "]" | "}") => {
+				depth = depth.saturating_sub(1)
+			}
+			TokenKind::Keyword if select.is_none() && text.eq_ignore_ascii_case("SELECT") => {
+				select = Some(depth)
+			}
+			TokenKind::Keyword if select == Some(depth) && text.eq_ignore_ascii_case("FETCH") => {
+				offset = span.start + t.span.start
+			}
+			_ => {}
+		}
+	}
+	match input[offset..span.end].find(idiom) {
+		Some(i) => offset + i,
+		None => offset,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+
+	use crate::sql::analyze;
+
+	fn codes(sql: &str, params: &[&str]) -> Vec<&'static str> {
+		match analyze(sql, params) {
+			Ok(_) => Vec::new(),
+			Err(e) => e.iter().map(|e| e.diagnostic.code).collect(),
+		}
+	}
+
+	#[test]
+	fn analyze_params() {
+		let sql =
+			"LET $a = 1; SELECT * FROM person WHERE age > $a AND name = $name AND id = $auth.id";
+		assert_eq!(codes(sql, &[]), vec!["unknown_param"]);
+		assert!(codes(sql, &["name"]).is_empty());
+		// A parameter can not be used before it is defined
+		assert_eq!(codes("RETURN $a; LET $a = 1", &[]), vec!["unknown_param"]);
+		let sql = "FOR $p IN (SELECT * FROM person) { UPDATE $p SET seen = $this.seen OR true };";
+		assert!(codes(sql, &[]).is_empty());
+		let sql = "DEFINE FUNCTION fn::greet($name: string) { RETURN 'Hello ' + $name; }";
+		assert!(codes(sql, &[]).is_empty());
+		let sql = "DEFINE SCOPE account SIGNIN (SELECT * FROM user WHERE email = $email)";
+		assert!(codes(sql, &[]).is_empty());
+	}
+
+	#[test]
+	fn analyze_function_arity() {
+		let sql =
+			"RETURN array::len([1, 2], 3); RETURN string::slice('abc', 1); RETURN rand::uuid()";
+		assert_eq!(codes(sql, &[]), vec!["invalid_arity"]);
+		let sql =
+			"DEFINE FUNCTION fn::add($a: int, $b: int) { RETURN $a + $b; }; RETURN fn::add(1)";
+		assert_eq!(codes(sql, &[]), vec!["invalid_arity"]);
+		// Functions defined in the database are not known
+		assert!(codes("RETURN fn::add(1)", &[]).is_empty());
+		let err = analyze("RETURN math::max()", &[]).unwrap_err();
+		assert_eq!(err[0].diagnostic.span.column, 8);
+		assert!(err[0]
+			.text
+			.starts_with("Function 'math::max' expects 1 argument, but was given 0"));
+	}
+
+	#[test]
+	fn analyze_fetch() {
+		let sql = "SELECT name, author FROM article FETCH author.profile, tags";
+		let err = analyze(sql, &[]).unwrap_err();
+		assert_eq!(err.len(), 1);
+		assert_eq!(err[0].diagnostic.code, "invalid_fetch");
+		assert_eq!(err[0].diagnostic.span.start, sql.find("tags").unwrap());
+		assert!(codes("SELECT * FROM article FETCH author", &[]).is_empty());
+		let sql = "RETURN (SELECT name FROM article FETCH author)";
+		assert_eq!(codes(sql, &[]), vec!["invalid_fetch"]);
+	}
+
+	#[test]
+	fn analyze_syntax_errors() {
+		let err = analyze("SELECT * FROM; RETURN $a", &[]).unwrap_err();
+		// Only the syntax errors are reported, until they are fixed
+		assert_eq!(err.len(), 1);
+		assert_ne!(err[0].diagnostic.code, "unknown_param");
+	}
+}
@@ -4,7 +4,7 @@ use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::sql::common::openbracket;
 use crate::sql::error::IResult;
-use crate::sql::fmt::{pretty_indent, Fmt, Pretty};
+use crate::sql::fmt::{pretty_indent, pretty_single_line, Fmt, Pretty};
 use crate::sql::number::Number;
 use crate::sql::operation::Operation;
 use crate::sql::value::{value, Value};
@@ -156,6 +156,9 @@ impl Array {
 impl Display for Array {
 	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
 		let mut f = Pretty::from(f);
+		if let Some(v) = pretty_single_line(self) {
+			return f.write_str(&v);
+		}
 		f.write_char('[')?;
 		if !self.is_empty() {
 			let indent = pretty_indent();
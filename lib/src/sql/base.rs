@@ -1,14 +1,14 @@
 use crate::sql::comment::shouldbespace;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::ident::{ident, Ident};
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::{cut, value};
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
-use super::error::expected;
+use super::error::{deprecated, expected};
 
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
 #[revisioned(revision = 1)]
@@ -45,7 +45,7 @@ pub fn base(i: &str) -> IResult<&str, Base> {
 			value(Base::Root, tag_no_case("ROOT")),
 			value(Base::Ns, tag_no_case("NS")),
 			value(Base::Db, tag_no_case("DB")),
-			value(Base::Root, tag_no_case("KV")),
+			value(Base::Root, deprecated("KV is deprecated, use ROOT instead", tag_no_case("KV"))),
 		)),
 	)(i)
 }
@@ -4,7 +4,7 @@ use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::sql::comment::mightbespace;
 use crate::sql::common::{closebraces, colons, openbraces};
-use crate::sql::error::IResult;
+use crate::sql::error::{warn, IResult, ParseWarning};
 use crate::sql::fmt::{is_pretty, pretty_indent, Fmt, Pretty};
 use crate::sql::statements::create::{create, CreateStatement};
 use crate::sql::statements::define::{define, DefineStatement};
@@ -60,6 +60,33 @@ impl Block {
 	pub(crate) fn writeable(&self) -> bool {
 		self.iter().any(Entry::writeable)
 	}
+	/// Substitute any nondeterministic functions with literal values
+	pub(crate) async fn resolve(&mut self, ctx: &Context<'_>, opt: &Options, txn: &Transaction) {
+		// Duplicate context
+		let mut ctx = Context::new(ctx);
+		// Loop over the statements
+		for v in self.0.iter_mut() {
+			match v {
+				Entry::Set(v) => {
+					v.resolve(&ctx, opt, txn).await;
+					// The value of the parameter is not known ahead of time
+					let val = Value::Param(v.name.to_owned().into());
+					ctx.add_value(v.name.to_owned(), val);
+				}
+				Entry::Value(v) => v.resolve(&ctx, opt, txn).await,
+				Entry::Foreach(v) => v.resolve(&ctx, opt, txn).await,
+				Entry::Ifelse(v) => v.resolve(&ctx, opt, txn).await,
+				Entry::Select(v) => v.resolve(&ctx, opt, txn).await,
+				Entry::Create(v) => v.resolve(&ctx, opt, txn).await,
+				Entry::Update(v) => v.resolve(&ctx, opt, txn).await,
+				Entry::Delete(v) => v.resolve(&ctx, opt, txn).await,
+				Entry::Relate(v) => v.resolve(&ctx, opt, txn).await,
+				Entry::Insert(v) => v.resolve(&ctx, opt, txn).await,
+				Entry::Output(v) => v.resolve(&ctx, opt, txn).await,
+				_ => (),
+			}
+		}
+	}
 	/// Process this type returning a computed simple Value
 	pub(crate) async fn compute(
 		&self,
@@ -188,9 +215,25 @@ pub fn block(i: &str) -> IResult<&str, Block> {
 	expect_delimited(
 		openbraces,
 		|i| {
-			let (i, v) = separated_list0(colons, entry)(i)?;
+			let (i, v) = separated_list0(colons, |i| {
+				let (rest, v) = entry(i)?;
+				Ok((rest, (i, v)))
+			})(i)?;
 			let (i, _) = many0(colons)(i)?;
-			Ok((i, Block(v)))
+			// Any entry after the block has finished never runs
+			let end = v.iter().position(|(_, v)| {
+				matches!(
+					v,
+					Entry::Output(_) | Entry::Throw(_) | Entry::Break(_) | Entry::Continue(_)
+				)
+			});
+			if let Some((tried, _)) = end.and_then(|n| v.get(n + 1)) {
+				let (tried, _) = mightbespace(tried)?;
+				warn(ParseWarning::Unreachable {
+					tried,
+				});
+			}
+			Ok((i, Block(v.into_iter().map(|(_, v)| v).collect())))
 		},
 		closebraces,
 	)(i)
@@ -346,4 +389,13 @@ mod tests {
 		let out = res.unwrap().1;
 		assert_eq!(sql, format!("{:#}", out))
 	}
+
+	#[test]
+	fn block_unreachable_warning() {
+		let sql = "RETURN { LET $a = 1; RETURN $a; CREATE person; }";
+		let (_, warnings) = crate::sql::parse_with_warnings(sql).unwrap();
+		assert_eq!(warnings.len(), 1);
+		assert_eq!(warnings[0].diagnostic.code, "unreachable_statement");
+		assert_eq!(warnings[0].diagnostic.span.start, sql.find("CREATE").unwrap());
+	}
 }
@@ -4,6 +4,7 @@ use nom::{
 	combinator::{opt, peek, value},
 	Err, IResult,
 };
+use once_cell::sync::Lazy;
 
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub enum BuiltinName<I> {
@@ -129,90 +130,132 @@ macro_rules! impl_builtins {
 	};
 }
 
-pub(crate) fn builtin_name(i: &str) -> IResult<&str, BuiltinName<&str>, ParseError<&str>> {
-	impl_builtins! {
-		array => {
-			add => { fn },
-			all => { fn },
-			any => { fn },
-			append => { fn },
-			at => { fn },
-			boolean_and => { fn },
-			boolean_not => { fn },
-			boolean_or => { fn },
-			boolean_xor => { fn },
-			clump => { fn },
-			combine => { fn },
-			complement => { fn },
-			concat => { fn },
-			difference => { fn },
-			distinct => { fn },
-			filter_index => { fn },
-			find_index => { fn },
-			first => { fn },
-			flatten => { fn },
-			group => { fn },
-			insert => { fn },
-			intersect=> { fn },
-			join => { fn },
-			last=> { fn },
-			len => { fn },
-			logical_and => { fn },
-			logical_or => { fn },
-			logical_xor => { fn },
-			matches => { fn },
-			max => { fn },
-			min => { fn },
-			pop => { fn },
-			prepend => { fn },
-			push => { fn },
-			remove => { fn },
-			reverse => { fn },
-			slice => { fn },
-			// says that sort is also itself a function
-			sort(func) => {
-				asc => {fn },
-				desc => {fn },
-			},
-			transpose => { fn },
-			r#union = "union" => { fn },
-		},
-		bytes => {
-			len => { fn }
-		},
-		crypto => {
-			argon2 => {
-				compare => { fn },
-				generate => { fn }
+/// A macro to generate a function which lists the full names of all the different functions and
+/// constants.
+macro_rules! impl_builtin_names {
+	($($name:ident$( ( $s:ident ) )? $(= $rename:expr)? => { $($t:tt)* }),*$(,)?) => {
+		fn _builtin_names() -> Vec<&'static str> {
+			let mut names = Vec::new();
+			$(
+				impl_builtin_names!{
+					@names,
+					names,
+					impl_builtins!(@rename, $name, $($rename)?),
+					$($s)?,
+					{ $($t)* }
+				}
+			)*
+			names
+		}
+	};
+
+	(@names, $out:ident, $full:expr, $($s:ident)?, { fn }) => {
+		$out.push($full);
+	};
+	(@names, $out:ident, $full:expr, $($s:ident)?, { const = $value:expr }) => {
+		$out.push($full);
+	};
+	(@names, $out:ident, $full:expr, $($s:ident)?, { $($name:ident $(($s2:ident))? $(= $rename:expr)? => { $($t:tt)* }),* $(,)? }) => {
+		impl_builtin_names!{@own, $out, $full, $($s)?}
+		$(
+			impl_builtin_names!{
+				@names,
+				$out,
+				concat!($full, "::", impl_builtins!(@rename, $name, $($rename)?)),
+				$($s2)?,
+				{ $($t)* }
+			}
+		)*
+	};
+
+	(@own, $out:ident, $full:expr, func) => {
+		$out.push($full);
+	};
+	(@own, $out:ident, $full:expr, cons) => {
+		$out.push($full);
+	};
+	(@own, $out:ident, $full:expr, ) => {};
+}
+
+/// A macro which passes the tree of all the builtin functions and constants to another macro.
+macro_rules! with_builtins {
+	($mac:ident) => {
+		$mac! {
+			array => {
+				add => { fn },
+				all => { fn },
+				any => { fn },
+				append => { fn },
+				at => { fn },
+				boolean_and => { fn },
+				boolean_not => { fn },
+				boolean_or => { fn },
+				boolean_xor => { fn },
+				clump => { fn },
+				combine => { fn },
+				complement => { fn },
+				concat => { fn },
+				difference => { fn },
+				distinct => { fn },
+				filter_index => { fn },
+				find_index => { fn },
+				first => { fn },
+				flatten => { fn },
+				group => { fn },
+				insert => { fn },
+				intersect=> { fn },
+				join => { fn },
+				last=> { fn },
+				len => { fn },
+				logical_and => { fn },
+				logical_or => { fn },
+				logical_xor => { fn },
+				matches => { fn },
+				max => { fn },
+				min => { fn },
+				pop => { fn },
+				prepend => { fn },
+				push => { fn },
+				remove => { fn },
+				reverse => { fn },
+				slice => { fn },
+				// says that sort is also itself a function
+				sort(func) => {
+					asc => {fn },
+					desc => {fn },
+				},
+				transpose => { fn },
+				r#union = "union" => { fn },
 			},
-			bcrypt => {
-				compare => { fn },
-				generate => { fn }
+			bytes => {
+				len => { fn }
 			},
-			pbkdf2 => {
-				compare => { fn },
-				generate => { fn }
+			config => {
+				get => { fn },
 			},
-			scrypt => {
-				compare => { fn },
-				generate => { fn }
+			crypto => {
+				argon2 => {
+					compare => { fn },
+					generate => { fn }
+				},
+				bcrypt => {
+					compare => { fn },
+					generate => { fn }
+				},
+				pbkdf2 => {
+					compare => { fn },
+					generate => { fn }
+				},
+				scrypt => {
+					compare => { fn },
+					generate => { fn }
+				},
+				md5 => { fn },
+				sha1 => { fn },
+				sha256 => { fn },
+				sha512 => { fn }
 			},
-			md5 => { fn },
-			sha1 => { fn },
-			sha256 => { fn },
-			sha512 => { fn }
-		},
-		duration => {
-			days => { fn },
-			hours => { fn },
-			micros => { fn },
-			millis => { fn },
-			mins => { fn },
-			nanos => { fn },
-			secs => { fn },
-			weeks => { fn },
-			years => { fn },
-			from => {
+			duration => {
 				days => { fn },
 				hours => { fn },
 				micros => { fn },
@@ -221,265 +264,312 @@ pub(crate) fn builtin_name(i: &str) -> IResult<&str, BuiltinName<&str>, ParseErr
 				nanos => { fn },
 				secs => { fn },
 				weeks => { fn },
+				years => { fn },
+				from => {
+					days => { fn },
+					hours => { fn },
+					micros => { fn },
+					millis => { fn },
+					mins => { fn },
+					nanos => { fn },
+					secs => { fn },
+					weeks => { fn },
+				},
 			},
-		},
-		encoding => {
-			base64 => {
-				decode => { fn },
-				encode => { fn },
-			}
-		},
-		geo => {
-			area => { fn },
-			bearing => { fn },
-			centroid => { fn },
-			distance => { fn },
-			hash => {
-				decode => { fn },
-				encode => { fn },
+			encoding => {
+				base64 => {
+					decode => { fn },
+					encode => { fn },
+				}
 			},
-		},
-		http => {
-			head => { fn },
-			get => { fn },
-			put => { fn },
-			post => { fn },
-			patch => { fn },
-			delete => { fn },
-		},
-		math => {
-			abs => { fn },
-			bottom => { fn },
-			ceil => { fn },
-			fixed => { fn },
-			floor => { fn },
-			interquartile => { fn },
-			max => { fn },
-			mean => { fn },
-			median => { fn },
-			midhinge => { fn },
-			min => { fn },
-			mode => { fn },
-			nearestrank => { fn },
-			percentile => { fn },
-			pow => { fn },
-			product => { fn },
-			round => { fn },
-			spread => { fn },
-			sqrt => { fn },
-			stddev => { fn },
-			sum => { fn },
-			top => { fn },
-			trimean => { fn },
-			variance => { fn },
-			E => { const = constant::Constant::MathE },
-			FRAC_1_PI => { const = constant::Constant::MathFrac1Pi },
-			FRAC_1_SQRT_2 => { const = constant::Constant::MathFrac1Sqrt2 },
-			FRAC_2_PI => { const = constant::Constant::MathFrac2Pi },
-			FRAC_2_SQRT_PI => { const = constant::Constant::MathFrac2SqrtPi },
-			FRAC_PI_2 => { const = constant::Constant::MathFracPi2 },
-			FRAC_PI_3 => { const = constant::Constant::MathFracPi3 },
-			FRAC_PI_4 => { const = constant::Constant::MathFracPi4 },
-			FRAC_PI_6 => { const = constant::Constant::MathFracPi6 },
-			FRAC_PI_8 => { const = constant::Constant::MathFracPi8 },
-			INF => { const = constant::Constant::MathInf },
-			LN_10 => { const = constant::Constant::MathLn10 },
-			LN_2 => { const = constant::Constant::MathLn2 },
-			LOG10_2 => { const = constant::Constant::MathLog102 },
-			LOG10_E => { const = constant::Constant::MathLog10E },
-			LOG2_10 => { const = constant::Constant::MathLog210 },
-			LOG2_E => { const = constant::Constant::MathLog2E },
-			PI => { const = constant::Constant::MathPi },
-			SQRT_2 => { const = constant::Constant::MathSqrt2 },
-			TAU => { const = constant::Constant::MathTau },
-		},
-		meta => {
-			id => { fn },
-			table => { fn },
-			tb => { fn },
-		},
-		parse => {
-			email => {
-				host => { fn },
-				user => { fn },
+			geo => {
+				area => { fn },
+				bearing => { fn },
+				centroid => { fn },
+				contains => { fn },
+				distance => { fn },
+				hash => {
+					decode => { fn },
+					encode => { fn },
+				},
 			},
-			url => {
-				domain => { fn },
-				fragment => { fn },
-				host => { fn },
-				path => { fn },
-				port => { fn },
-				query => { fn },
-				scheme => { fn },
-			}
-		},
-		rand(func) => {
-			r#bool = "bool" => { fn },
-			r#enum = "enum" => { fn },
-			float => { fn },
-			guid => { fn },
-			int => { fn },
-			string => { fn },
-			time => { fn },
-			ulid => { fn },
-			uuid(func) => {
-				v4 => { fn },
-				v7 => { fn },
+			graph => {
+				paths => { fn },
 			},
-		},
-		search => {
-			score => { fn },
-			highlight => { fn },
-			offsets => { fn },
-		},
-		session => {
-			db => { fn },
-			id => { fn },
-			ip => { fn },
-			ns => { fn },
-			origin => { fn },
-			sc => { fn },
-			sd => { fn },
-			token => { fn },
-		},
-		string => {
-			concat => { fn },
-			contains => { fn },
-			ends_with = "endsWith" => { fn },
-			join => { fn },
-			len => { fn },
-			lowercase => { fn },
-			repeat => { fn },
-			replace => { fn },
-			reverse => { fn },
-			slice => { fn },
-			slug => { fn },
-			split => { fn },
-			starts_with = "startsWith" => { fn },
-			trim => { fn },
-			uppercase => { fn },
-			words => { fn },
-			distance => {
-				hamming => { fn },
-				levenshtein => { fn },
+			http => {
+				head => { fn },
+				get => { fn },
+				put => { fn },
+				post => { fn },
+				patch => { fn },
+				delete => { fn },
 			},
-			similarity => {
-				fuzzy => { fn },
-				jaro => { fn },
-				smithwaterman => { fn },
+			math => {
+				abs => { fn },
+				bottom => { fn },
+				ceil => { fn },
+				corr => { fn },
+				covar => { fn },
+				fixed => { fn },
+				floor => { fn },
+				interquartile => { fn },
+				max => { fn },
+				mean => { fn },
+				median => { fn },
+				midhinge => { fn },
+				min => { fn },
+				mode => { fn },
+				nearestrank => { fn },
+				percentile => { fn },
+				pow => { fn },
+				product => { fn },
+				regr_intercept => { fn },
+				regr_slope => { fn },
+				round => { fn },
+				spread => { fn },
+				sqrt => { fn },
+				stddev => { fn },
+				sum => { fn },
+				top => { fn },
+				trimean => { fn },
+				variance => { fn },
+				E => { const = constant::Constant::MathE },
+				FRAC_1_PI => { const = constant::Constant::MathFrac1Pi },
+				FRAC_1_SQRT_2 => { const = constant::Constant::MathFrac1Sqrt2 },
+				FRAC_2_PI => { const = constant::Constant::MathFrac2Pi },
+				FRAC_2_SQRT_PI => { const = constant::Constant::MathFrac2SqrtPi },
+				FRAC_PI_2 => { const = constant::Constant::MathFracPi2 },
+				FRAC_PI_3 => { const = constant::Constant::MathFracPi3 },
+				FRAC_PI_4 => { const = constant::Constant::MathFracPi4 },
+				FRAC_PI_6 => { const = constant::Constant::MathFracPi6 },
+				FRAC_PI_8 => { const = constant::Constant::MathFracPi8 },
+				INF => { const = constant::Constant::MathInf },
+				LN_10 => { const = constant::Constant::MathLn10 },
+				LN_2 => { const = constant::Constant::MathLn2 },
+				LOG10_2 => { const = constant::Constant::MathLog102 },
+				LOG10_E => { const = constant::Constant::MathLog10E },
+				LOG2_10 => { const = constant::Constant::MathLog210 },
+				LOG2_E => { const = constant::Constant::MathLog2E },
+				PI => { const = constant::Constant::MathPi },
+				SQRT_2 => { const = constant::Constant::MathSqrt2 },
+				TAU => { const = constant::Constant::MathTau },
 			},
-			is => {
-				alphanum => { fn },
-				alpha => { fn },
-				ascii => { fn },
-				datetime => { fn },
-				domain => { fn },
-				email => { fn },
-				hexadecimal => { fn },
-				latitude => { fn },
-				longitude => { fn },
-				numeric => { fn },
-				semver => { fn },
-				url => { fn },
-				uuid => { fn },
-			}
-		},
-		time => {
-			ceil => { fn },
-			day => { fn },
-			floor => { fn },
-			format => { fn },
-			group => { fn },
-			hour => { fn },
-			minute => { fn },
-			max => { fn },
-			min => { fn },
-			month => { fn },
-			nano => { fn },
-			now => { fn },
-			round => { fn },
-			second => { fn },
-			timezone => { fn },
-			unix => { fn },
-			wday => { fn },
-			week => { fn },
-			yday => { fn },
-			year => { fn },
-			from => {
-				micros => {fn},
-				millis => {fn},
-				unix => {fn},
-				secs => {fn},
-			}
-		},
-		r#type = "type" => {
-			r#bool = "bool" => { fn },
-			datetime => { fn },
-			decimal => { fn },
-			duration => { fn },
-			fields => { fn },
-			field => { fn },
-			float => { fn },
-			int => { fn },
-			number => { fn },
-			point => { fn },
-			string => { fn },
-			table => { fn },
-			thing => { fn },
-			is => {
-				array => { fn },
+			meta => {
+				id => { fn },
+				table => { fn },
+				tb => { fn },
+			},
+			object => {
+				entries => { fn },
+				from_entries => { fn },
+				pivot => { fn },
+			},
+			parse => {
+				email => {
+					host => { fn },
+					user => { fn },
+				},
+				url => {
+					domain => { fn },
+					fragment => { fn },
+					host => { fn },
+					path => { fn },
+					port => { fn },
+					query => { fn },
+					scheme => { fn },
+				}
+			},
+			rand(func) => {
+				r#bool = "bool" => { fn },
+				r#enum = "enum" => { fn },
+				float => { fn },
+				guid => { fn },
+				int => { fn },
+				string => { fn },
+				time => { fn },
+				ulid => { fn },
+				uuid(func) => {
+					v4 => { fn },
+					v7 => { fn },
+				},
+			},
+			search => {
+				analyze => { fn },
+				score => { fn },
+				highlight => { fn },
+				offsets => { fn },
+			},
+			sequence => {
+				next => { fn },
+			},
+			session => {
+				db => { fn },
+				id => { fn },
+				ip => { fn },
+				ns => { fn },
+				origin => { fn },
+				sc => { fn },
+				sd => { fn },
+				token => { fn },
+			},
+			string => {
+				concat => { fn },
+				contains => { fn },
+				ends_with = "endsWith" => { fn },
+				join => { fn },
+				len => { fn },
+				lowercase => { fn },
+				repeat => { fn },
+				replace => { fn },
+				reverse => { fn },
+				slice => { fn },
+				slug => { fn },
+				split => { fn },
+				starts_with = "startsWith" => { fn },
+				trim => { fn },
+				uppercase => { fn },
+				words => { fn },
+				distance => {
+					hamming => { fn },
+					levenshtein => { fn },
+				},
+				similarity => {
+					fuzzy => { fn },
+					jaro => { fn },
+					smithwaterman => { fn },
+				},
+				is => {
+					alphanum => { fn },
+					alpha => { fn },
+					ascii => { fn },
+					datetime => { fn },
+					domain => { fn },
+					email => { fn },
+					hexadecimal => { fn },
+					latitude => { fn },
+					longitude => { fn },
+					numeric => { fn },
+					semver => { fn },
+					url => { fn },
+					uuid => { fn },
+				}
+			},
+			time => {
+				ceil => { fn },
+				day => { fn },
+				floor => { fn },
+				format => { fn },
+				group => { fn },
+				hour => { fn },
+				minute => { fn },
+				max => { fn },
+				min => { fn },
+				month => { fn },
+				nano => { fn },
+				now => { fn },
+				round => { fn },
+				second => { fn },
+				timezone => { fn },
+				unix => { fn },
+				wday => { fn },
+				week => { fn },
+				yday => { fn },
+				year => { fn },
+				from => {
+					micros => {fn},
+					millis => {fn},
+					unix => {fn},
+					secs => {fn},
+				}
+			},
+			r#type = "type" => {
 				r#bool = "bool" => { fn },
-				bytes => { fn },
-				collection => { fn },
 				datetime => { fn },
 				decimal => { fn },
 				duration => { fn },
+				fields => { fn },
+				field => { fn },
 				float => { fn },
-				geometry => { fn },
 				int => { fn },
-				line => { fn },
-				null => { fn },
-				multiline => { fn },
-				multipoint => { fn },
-				multipolygon => { fn },
 				number => { fn },
-				object => { fn },
 				point => { fn },
-				polygon => { fn },
-				record => { fn },
 				string => { fn },
-				uuid => { fn },
-			}
-		},
-		vector => {
-			add => { fn },
-			angle => { fn },
-			divide => { fn },
-			cross => { fn },
-			dot => { fn },
-			magnitude => { fn },
-			multiply => { fn },
-			normalize => { fn },
-			project => { fn },
-			subtract => { fn },
-			distance => {
-				chebyshev => { fn },
-				euclidean => { fn },
-				hamming => { fn },
-				mahalanobis => { fn },
-				manhattan => { fn },
-				minkowski => { fn },
+				table => { fn },
+				thing => { fn },
+				is => {
+					array => { fn },
+					r#bool = "bool" => { fn },
+					bytes => { fn },
+					collection => { fn },
+					datetime => { fn },
+					decimal => { fn },
+					duration => { fn },
+					float => { fn },
+					geometry => { fn },
+					int => { fn },
+					line => { fn },
+					null => { fn },
+					multiline => { fn },
+					multipoint => { fn },
+					multipolygon => { fn },
+					number => { fn },
+					object => { fn },
+					point => { fn },
+					polygon => { fn },
+					record => { fn },
+					string => { fn },
+					uuid => { fn },
+				}
 			},
-			similarity => {
-				cosine => {fn },
-				jaccard => {fn },
-				pearson => {fn },
-				spearman => {fn },
-			}
-		},
-		count => { fn },
-		not => { fn },
-		sleep => { fn },
-	}
+			uuid => {
+				time => { fn },
+				version => { fn },
+			},
+			vector => {
+				add => { fn },
+				angle => { fn },
+				divide => { fn },
+				cross => { fn },
+				dot => { fn },
+				magnitude => { fn },
+				multiply => { fn },
+				normalize => { fn },
+				project => { fn },
+				subtract => { fn },
+				distance => {
+					chebyshev => { fn },
+					euclidean => { fn },
+					hamming => { fn },
+					mahalanobis => { fn },
+					manhattan => { fn },
+					minkowski => { fn },
+				},
+				similarity => {
+					cosine => {fn },
+					jaccard => {fn },
+					pearson => {fn },
+					spearman => {fn },
+				}
+			},
+			count(func) => {
+				distinct => { fn },
+			},
+			not => { fn },
+			sleep => { fn },
+		}
+	};
+}
+
+pub(crate) fn builtin_name(i: &str) -> IResult<&str, BuiltinName<&str>, ParseError<&str>> {
+	with_builtins!(impl_builtins);
 	_parse_builtin_name(i)
 }
+
+/// Returns the full names of all the builtin functions and constants, such as `string::lowercase`.
+pub(crate) fn builtin_names() -> &'static [&'static str] {
+	static NAMES: Lazy<Vec<&'static str>> = Lazy::new(|| {
+		with_builtins!(impl_builtin_names);
+		_builtin_names()
+	});
+	&NAMES
+}
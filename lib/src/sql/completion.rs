@@ -0,0 +1,315 @@
+//! Finds the keywords, functions, and kinds of identifier which can be written at a position
+//! in a partly written query, for completing queries in shells and editors.
+//!
+//! The keywords are those which the parser tries at the position, so that a keyword is only
+//! returned if the parser treats it as a keyword, rather than as the name of a table or a field.
+//! Each kind of identifier is written at the position and the statement is parsed again, so
+//! that only the identifiers which the parser accepts there are returned.
+
+use crate::sql::builtin::builtin_names;
+use crate::sql::error::record_tried;
+use crate::sql::lexer::{lex, TokenKind, KEYWORDS};
+use crate::sql::parser::depth;
+use crate::sql::statement::statement;
+use nom::Err;
+use std::ops::Range;
+
+/// The keywords which are followed by the name of a table.
+const TABLE_KEYWORDS: &[&str] =
+	&["CREATE", "DELETE", "FROM", "INTO", "ON", "ONLY", "TABLE", "UPDATE", "UPSERT"];
+
+/// The keywords which are followed by a new name, or by the name of something other than
+/// a table or a field.
+const NAME_KEYWORDS: &[&str] = &[
+	"ANALYZER",
+	"AS",
+	"DATABASE",
+	"DB",
+	"EVENT",
+	"INDEX",
+	"MIGRATION",
+	"NAMESPACE",
+	"NS",
+	"SAVEPOINT",
+	"SCOPE",
+	"TOKEN",
+	"USER",
+];
+
+/// An identifier which is written in place of a candidate, which is never a keyword.
+const PLACEHOLDER: &str = "__completion";
+
+/// The completions which can be written at a position in a query.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct Completions {
+	/// The byte range of the partly written word before the position, which is replaced
+	/// when a completion is chosen. This is empty if there is no word before the position.
+	pub span: Range<usize>,
+	/// The keywords which can be written, in alphabetical order
+	pub keywords: Vec<&'static str>,
+	/// The built-in functions which can be written, in alphabetical order
+	pub functions: Vec<&'static str>,
+	/// The kinds of identifier which can be written
+	pub identifiers: Vec<IdentifierKind>,
+}
+
+/// A kind of identifier which can be written at a position in a query.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum IdentifierKind {
+	/// The name of a table
+	Table,
+	/// The name of a field of a record
+	Field,
+	/// A custom function, starting with `fn::`
+	Function,
+	/// A parameter, starting with `$`
+	Param,
+}
+
+/// The result of parsing a statement with a candidate written at the cursor.
+#[derive(Debug, Eq, PartialEq)]
+enum Outcome {
+	/// The statement can not be parsed with the candidate
+	Invalid,
+	/// The statement is not finished, but parses up to the end of the candidate
+	Incomplete,
+	/// The statement is finished, along with the debug output of the parsed statement
+	Complete(String),
+}
+
+/// Returns the completions which can be written at a byte offset of a query.
+pub(crate) fn complete(input: &str, cursor: usize) -> Completions {
+	let mut cursor = cursor.min(input.len());
+	while !input.is_char_boundary(cursor) {
+		cursor -= 1;
+	}
+	// The partly written word before the cursor, including any function path or `$`
+	let before = &input[..cursor];
+	let word = before.trim_end_matches(|c: char| c.is_alphanumeric() || c == '_' || c == ':');
+	let mut start = word.len();
+	if word.ends_with('$') {
+		start -= 1;
+	}
+	let prefix = &input[start..cursor];
+	let mut out = Completions {
+		span: start..cursor,
+		..Default::default()
+	};
+	let Some(context) = Context::find(&input[..start]) else {
+		return out;
+	};
+	let stm = &input[context.start..start];
+	// The first word of a statement is always a keyword, but is parsed as a value on its own
+	if lex(stm).all(|t| t.kind == TokenKind::Comment) {
+		out.keywords = matching(&expected(stm), prefix);
+		return out;
+	}
+	if !prefix.starts_with('$') {
+		out.keywords = matching(&expected(stm), prefix);
+		if attempt(stm, PLACEHOLDER) != Outcome::Invalid {
+			out.identifiers.extend(context.identifier());
+		}
+		if attempt(stm, &format!("fn::{PLACEHOLDER}()")) != Outcome::Invalid {
+			out.identifiers.push(IdentifierKind::Function);
+			// There are too many functions to offer before any of the name is written
+			if !prefix.is_empty() {
+				out.functions = matching(builtin_names(), prefix);
+				out.functions.sort_unstable();
+			}
+		}
+	}
+	if (prefix.is_empty() || prefix.starts_with('$'))
+		&& attempt(stm, &format!("${PLACEHOLDER}")) != Outcome::Invalid
+	{
+		out.identifiers.push(IdentifierKind::Param);
+	}
+	out
+}
+
+/// The statement in which the cursor is found, and the words before the cursor.
+struct Context {
+	/// The byte offset at which the statement starts
+	start: usize,
+	/// The last keyword before the cursor, in uppercase
+	keyword: Option<String>,
+	/// Whether the cursor follows a graph edge arrow
+	edge: bool,
+}
+
+impl Context {
+	/// Finds the statement which ends at the end of the input, or `None` if the end of the
+	/// input is within a string or a comment.
+	fn find(input: &str) -> Option<Self> {
+		let mut start = 0;
+		let mut depth = 0usize;
+		let mut keyword = None;
+		let mut last = None;
+		for t in lex(input) {
+			let text = &input[t.span.clone()];
+			match t.kind {
+				TokenKind::Punctuation if matches!(text, "(" | "{" | "[") => depth += 1,
+				TokenKind::Punctuation if matches!(text, ")" | "}" | "]") => {
+					depth = depth.saturating_sub(1)
+				}
+				// Statements within blocks are parsed along with the statement containing them
+				TokenKind::Punctuation if text == ";" && depth == 0 => {
+					start = t.span.end;
+					keyword = None;
+				}
+				TokenKind::Keyword => keyword = Some(text.to_uppercase()),
+				_ => {}
+			}
+			last = Some(t);
+		}
+		if let Some(t) = &last {
+			let text = &input[t.span.clone()];
+			let open = match t.kind {
+				// A line comment continues until the end of the line
+				TokenKind::Comment => {
+					!text.starts_with("/*") && !input[t.span.end..].contains('\n')
+				}
+				// An unterminated string or block comment continues until the end of the input
+				TokenKind::Invalid => {
+					text.starts_with(['\'', '"', '`', '⟨']) || text.starts_with("/*")
+				}
+				_ => false,
+			};
+			if open {
+				return None;
+			}
+		}
+		Some(Self {
+			start,
+			edge: last.is_some_and(|t| matches!(&input[t.span], "->" | "<-" | "<->")),
+			keyword,
+		})
+	}
+
+	/// Returns the kind of identifier which a plain word at the cursor would name.
+	fn identifier(&self) -> Option<IdentifierKind> {
+		if self.edge {
+			return Some(IdentifierKind::Table);
+		}
+		match self.keyword.as_deref() {
+			Some(k) if TABLE_KEYWORDS.contains(&k) => Some(IdentifierKind::Table),
+			Some(k) if NAME_KEYWORDS.contains(&k) => None,
+			_ => Some(IdentifierKind::Field),
+		}
+	}
+}
+
+/// Returns the candidates which start with a prefix, ignoring case.
+fn matching(candidates: &[&'static str], prefix: &str) -> Vec<&'static str> {
+	let prefix = prefix.to_lowercase();
+	candidates.iter().copied().filter(|c| c.to_lowercase().starts_with(&prefix)).collect()
+}
+
+/// Returns the keywords which the parser tries at the end of a statement, in alphabetical order.
+fn expected(stm: &str) -> Vec<&'static str> {
+	depth::reset();
+	let (_, tried) = record_tried(|| statement(stm));
+	let mut keywords: Vec<_> = tried
+		.into_iter()
+		.filter_map(|t| KEYWORDS.binary_search(&t.to_uppercase().as_str()).ok())
+		.map(|i| KEYWORDS[i])
+		.collect();
+	keywords.sort_unstable();
+	keywords.dedup();
+	keywords
+}
+
+/// Parses a statement with a candidate written at the end.
+fn attempt(stm: &str, candidate: &str) -> Outcome {
+	depth::reset();
+	let text = format!("{stm}{candidate} ");
+	let end = stm.len() + candidate.len();
+	let rest = match statement(&text) {
+		Ok(("", v)) => return Outcome::Complete(format!("{v:?}")),
+		Ok((rest, _)) => rest,
+		Err(Err::Error(e) | Err::Failure(e)) => e.tried(),
+		Err(Err::Incomplete(_)) => return Outcome::Incomplete,
+	};
+	match text.len() - rest.len() >= end {
+		true => Outcome::Incomplete,
+		false => Outcome::Invalid,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn complete_statement() {
+		let out = complete("SEL", 3);
+		assert_eq!(out.span, 0..3);
+		assert_eq!(out.keywords, vec!["SELECT"]);
+		assert!(out.identifiers.is_empty());
+		let sql = "SELECT * FROM person; upd";
+		let out = complete(sql, sql.len());
+		assert_eq!(out.span, 22..25);
+		assert_eq!(out.keywords, vec!["UPDATE", "UPSERT"]);
+	}
+
+	#[test]
+	fn complete_table() {
+		let sql = "SELECT * FROM ";
+		let out = complete(sql, sql.len());
+		assert_eq!(out.span, 14..14);
+		assert!(out.keywords.contains(&"ONLY"));
+		assert!(!out.keywords.contains(&"WHERE"));
+		assert!(out.identifiers.contains(&IdentifierKind::Table));
+		assert!(out.identifiers.contains(&IdentifierKind::Param));
+	}
+
+	#[test]
+	fn complete_clause() {
+		let sql = "SELECT * FROM person ";
+		let out = complete(sql, sql.len());
+		assert!(out.keywords.contains(&"WHERE"));
+		assert!(out.keywords.contains(&"LIMIT"));
+		assert!(!out.keywords.contains(&"SELECT"));
+		assert!(out.identifiers.is_empty());
+		// The cursor can be within the query
+		let sql = "SELECT * FR person";
+		let out = complete(sql, 11);
+		assert_eq!(out.span, 9..11);
+		assert_eq!(out.keywords, vec!["FROM"]);
+	}
+
+	#[test]
+	fn complete_function() {
+		let sql = "SELECT * FROM person WHERE string::low";
+		let out = complete(sql, sql.len());
+		assert_eq!(out.span, 27..sql.len());
+		assert_eq!(out.functions, vec!["string::lowercase"]);
+		assert!(out.identifiers.contains(&IdentifierKind::Field));
+		assert!(out.identifiers.contains(&IdentifierKind::Function));
+	}
+
+	#[test]
+	fn complete_param() {
+		let sql = "SELECT * FROM person WHERE age > $";
+		let out = complete(sql, sql.len());
+		assert_eq!(out.span, 33..34);
+		assert!(out.keywords.is_empty());
+		assert_eq!(out.identifiers, vec![IdentifierKind::Param]);
+	}
+
+	#[test]
+	fn complete_nothing_in_string() {
+		let sql = "SELECT * FROM person WHERE name = 'SEL";
+		assert_eq!(
+			complete(sql, sql.len()),
+			Completions {
+				span: 35..38,
+				..Default::default()
+			}
+		);
+		let sql = "SELECT * FROM person -- SEL";
+		assert!(complete(sql, sql.len()).keywords.is_empty());
+	}
+}
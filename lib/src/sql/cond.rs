@@ -1,7 +1,11 @@
 use crate::sql::comment::shouldbespace;
-use crate::sql::error::IResult;
+use crate::sql::error::tag_no_case;
+use crate::sql::error::{warn, IResult, ParseWarning};
+use crate::sql::expression::Expression;
+use crate::sql::lexer::{lex, TokenKind};
+use crate::sql::operator::Operator;
+use crate::sql::thing::{thing_raw, Thing};
 use crate::sql::value::{value, Value};
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
@@ -28,8 +32,47 @@ impl fmt::Display for Cond {
 pub fn cond(i: &str) -> IResult<&str, Cond> {
 	let (i, _) = tag_no_case("WHERE")(i)?;
 	let (i, _) = shouldbespace(i)?;
-	let (i, v) = cut(value)(i)?;
-	Ok((i, Cond(v)))
+	let (rest, v) = cut(value)(i)?;
+	// A quoted record id which is compared with the record id is suspicious
+	if let Some(t) = record_id_string(&v) {
+		let src = &i[..i.len() - rest.len()];
+		for tok in lex(src).filter(|tok| tok.kind == TokenKind::String) {
+			let quoted = &src[tok.span.start + 1..tok.span.end - 1];
+			if matches!(thing_raw(quoted), Ok(("", ref v)) if v == t) {
+				warn(ParseWarning::RecordIdString {
+					tried: &i[tok.span.start..],
+				});
+			}
+		}
+	}
+	Ok((rest, Cond(v)))
+}
+
+/// Returns a record id which is compared with the id field of the record.
+fn record_id_string(v: &Value) -> Option<&Thing> {
+	let Value::Expression(v) = v else {
+		return None;
+	};
+	match v.as_ref() {
+		Expression::Binary {
+			l,
+			o: Operator::And | Operator::Or,
+			r,
+		} => record_id_string(l).or_else(|| record_id_string(r)),
+		Expression::Binary {
+			l,
+			o: Operator::Equal | Operator::Exact | Operator::NotEqual,
+			r,
+		} => match (l, r) {
+			(Value::Idiom(f), Value::Thing(t)) | (Value::Thing(t), Value::Idiom(f))
+				if f.is_id() =>
+			{
+				Some(t)
+			}
+			_ => None,
+		},
+		_ => None,
+	}
 }
 
 #[cfg(test)]
@@ -4,13 +4,13 @@ use crate::err::Error;
 use crate::sql::comment::mightbespace;
 use crate::sql::comment::shouldbespace;
 use crate::sql::common::commas;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::fmt::Fmt;
 use crate::sql::idiom::{plain as idiom, Idiom};
 use crate::sql::operator::{assigner, Operator};
 use crate::sql::value::{value, Value};
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use nom::multi::separated_list1;
 use revision::revisioned;
@@ -71,6 +71,27 @@ impl Data {
 			_ => Ok(None),
 		}
 	}
+	/// Substitute any nondeterministic functions with literal values
+	pub(crate) async fn resolve(&mut self, ctx: &Context<'_>, opt: &Options, txn: &Transaction) {
+		match self {
+			Self::SetExpression(v) | Self::UpdateExpression(v) => {
+				for (_, _, v) in v.iter_mut() {
+					v.resolve(ctx, opt, txn).await;
+				}
+			}
+			Self::PatchExpression(v)
+			| Self::MergeExpression(v)
+			| Self::ReplaceExpression(v)
+			| Self::ContentExpression(v)
+			| Self::SingleExpression(v) => v.resolve(ctx, opt, txn).await,
+			Self::ValuesExpression(v) => {
+				for (_, v) in v.iter_mut().flatten() {
+					v.resolve(ctx, opt, txn).await;
+				}
+			}
+			_ => (),
+		}
+	}
 }
 
 impl Display for Data {
@@ -1,10 +1,10 @@
 use crate::sql::comment::comment;
 use crate::sql::comment::{mightbespace, shouldbespace};
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::operator::{assigner, binary};
 use nom::branch::alt;
 use nom::bytes::complete::tag;
-use nom::bytes::complete::tag_no_case;
 use nom::character::complete::char;
 use nom::character::complete::multispace1;
 use nom::combinator::peek;
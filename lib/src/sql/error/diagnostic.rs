@@ -0,0 +1,267 @@
+use super::{Location, ParseError};
+use serde::Serialize;
+use std::fmt;
+use std::ops::Range;
+
+/// A machine readable description of a parse error, for editors and SDKs.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct Diagnostic {
+	/// A stable identifier for the kind of error.
+	pub code: &'static str,
+	/// Whether this is an error, or a warning which did not stop the query being parsed.
+	pub severity: Severity,
+	/// The human readable error message.
+	pub message: String,
+	/// The part of the source where the error was found.
+	pub span: Span,
+	/// The tokens which the parser expected to find instead.
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub expected: Vec<&'static str>,
+	/// A possible explanation of the error.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub explanation: Option<&'static str>,
+	/// Other parts of the source which are relevant to the error.
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub related: Vec<Related>,
+	/// The keywords or function names which were possibly meant instead.
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub suggestions: Vec<&'static str>,
+	/// The statement of the query in which the error was found, if it is known.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub statement: Option<StatementSpan>,
+}
+
+/// How severe a diagnostic is.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+	#[default]
+	Error,
+	Warning,
+}
+
+/// A range of the source text.
+///
+/// Offsets are in bytes, with the end being exclusive, while lines and columns are 1 indexed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub struct Span {
+	pub start: usize,
+	pub end: usize,
+	pub line: usize,
+	pub column: usize,
+}
+
+/// A statement of a query, along with its position in the query.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub struct StatementSpan {
+	/// The index of the statement in the query, with the first statement being 0.
+	pub index: usize,
+	/// The part of the source containing the statement.
+	pub span: Span,
+}
+
+/// A span related to a diagnostic, with a note explaining its relevance.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Related {
+	pub span: Span,
+	pub message: &'static str,
+}
+
+impl Span {
+	/// Returns the span of the token at the start of a substring of the source.
+	pub fn of_in(substr: &str, s: &str) -> Self {
+		let location = Location::of_in(substr, s);
+		let start = s.len() - substr.len();
+		Span {
+			start,
+			end: start + Self::token_len(substr),
+			line: location.line,
+			column: location.column,
+		}
+	}
+
+	/// Returns the span of a byte range of the source.
+	pub fn of_range(range: Range<usize>, s: &str) -> Self {
+		let location = Location::of_in(&s[range.start..], s);
+		Span {
+			start: range.start,
+			end: range.end,
+			line: location.line,
+			column: location.column,
+		}
+	}
+
+	/// Returns the length in bytes of the first token of the input.
+	pub(super) fn token_len(i: &str) -> usize {
+		match i.chars().next() {
+			None => 0,
+			Some(c) if c.is_alphanumeric() || c == '_' => i
+				.char_indices()
+				.find(|(_, c)| !(c.is_alphanumeric() || *c == '_'))
+				.map(|(idx, _)| idx)
+				.unwrap_or(i.len()),
+			Some(c) => c.len_utf8(),
+		}
+	}
+}
+
+impl fmt::Display for Diagnostic {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.message)
+	}
+}
+
+impl ParseError<&str> {
+	/// Returns the error as a structured diagnostic on the original source text.
+	pub fn diagnose(&self, input: &str) -> Diagnostic {
+		self.diagnostic(input, self.render_text(input).0)
+	}
+
+	/// Returns the diagnostic for this error, using an already rendered message.
+	pub(super) fn diagnostic(&self, input: &str, message: String) -> Diagnostic {
+		let mut expected = Vec::new();
+		let mut explanation = None;
+		let mut related = Vec::new();
+		let code = match self {
+			ParseError::Base(_) => "unexpected_input",
+			ParseError::Expected {
+				expected: e,
+				..
+			} => {
+				expected.push(*e);
+				"expected"
+			}
+			ParseError::Explained {
+				explained,
+				..
+			} => {
+				explanation = Some(*explained);
+				"unexpected_input"
+			}
+			ParseError::ExplainedExpected {
+				expected: e,
+				explained,
+				..
+			} => {
+				expected.push(*e);
+				explanation = Some(*explained);
+				"expected"
+			}
+			ParseError::MissingDelimiter {
+				opened,
+				..
+			} => {
+				related.push(Related {
+					span: Span::of_in(opened, input),
+					message: "expected this delimiter to be closed",
+				});
+				"missing_delimiter"
+			}
+			ParseError::ExcessiveDepth(_) => "excessive_depth",
+			ParseError::Field(..) => "invalid_field",
+			ParseError::Split(..) => "invalid_split",
+			ParseError::Order(..) => "invalid_order",
+			ParseError::Group(..) => "invalid_group",
+			ParseError::Role(..) => "invalid_role",
+			ParseError::ParseInt {
+				..
+			} => "invalid_integer",
+			ParseError::ParseFloat {
+				..
+			} => "invalid_float",
+			ParseError::ParseDecimal {
+				..
+			} => "invalid_decimal",
+			ParseError::ParseRegex {
+				..
+			} => "invalid_regex",
+			ParseError::RangeError {
+				..
+			} => "out_of_range",
+			ParseError::InvalidUnicode {
+				..
+			} => "invalid_unicode",
+			ParseError::InvalidPath {
+				..
+			} => "invalid_path",
+		};
+		Diagnostic {
+			code,
+			severity: Severity::Error,
+			message,
+			span: Span::of_in(self.tried(), input),
+			expected,
+			explanation,
+			related,
+			suggestions: self.suggest(input).map(|s| s.candidates).unwrap_or_default(),
+			statement: None,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn diagnose_expected() {
+		let sql = "SELECT * FROM person WHERE";
+		let err = ParseError::Expected {
+			tried: &sql[20..],
+			expected: "a condition",
+		};
+		let out = err.diagnose(sql);
+		assert_eq!(out.code, "expected");
+		assert_eq!(out.expected, vec!["a condition"]);
+		assert_eq!(&sql[out.span.start..out.span.end], " ");
+		assert_eq!((out.span.line, out.span.column), (1, 21));
+		assert_eq!(out.message, err.render_on(sql).text);
+	}
+
+	#[test]
+	fn diagnose_missing_delimiter() {
+		let sql = "SELECT * FROM person WHERE (\n\tage > 18";
+		let err = ParseError::MissingDelimiter {
+			opened: &sql[27..],
+			tried: &sql[sql.len()..],
+		};
+		let out = err.diagnose(sql);
+		assert_eq!(out.code, "missing_delimiter");
+		assert_eq!(out.related.len(), 1);
+		let open = out.related[0].span;
+		assert_eq!(&sql[open.start..open.end], "(");
+		assert_eq!((open.line, open.column), (1, 28));
+		assert_eq!((out.span.start, out.span.end), (sql.len(), sql.len()));
+		assert_eq!((out.span.line, out.span.column), (2, 10));
+	}
+
+	#[test]
+	fn diagnose_to_json() {
+		let sql = "SELECT * FROM person WHERE (age > 18";
+		let err = ParseError::MissingDelimiter {
+			opened: &sql[27..],
+			tried: &sql[sql.len()..],
+		};
+		let out = serde_json::to_value(err.diagnose(sql)).unwrap();
+		assert_eq!(out["code"], "missing_delimiter");
+		assert_eq!(out["severity"], "error");
+		assert_eq!(out["span"]["start"], 36);
+		assert_eq!(out["related"][0]["span"]["start"], 27);
+		assert_eq!(out["related"][0]["span"]["end"], 28);
+		assert!(out.get("expected").is_none());
+		assert!(out.get("explanation").is_none());
+		assert!(out.get("suggestions").is_none());
+	}
+
+	#[test]
+	fn span_of_token() {
+		let sql = "SELECT héllo_1, 2";
+		let span = Span::of_in(&sql[7..], sql);
+		assert_eq!(&sql[span.start..span.end], "héllo_1");
+		let span = Span::of_in(&sql[15..], sql);
+		assert_eq!(&sql[span.start..span.end], ",");
+		let span = Span::of_in(&sql[sql.len()..], sql);
+		assert_eq!(span.start, span.end);
+	}
+}
@@ -0,0 +1,110 @@
+use super::{Diagnostic, Location, RenderedError, Severity, Snippet, Span};
+
+/// A part of a query which is valid, but which breaks one of the configured lint rules, as it
+/// is probably slow, or hard to maintain.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LintWarning<I> {
+	/// A statement which reads every record of a table, as no index matches its condition.
+	FullTableScan {
+		tried: I,
+		table: String,
+	},
+	/// A `SELECT *` projection in the definition of a view.
+	SelectAllInView {
+		tried: I,
+		table: String,
+	},
+	/// A literal value in a condition, which could be passed as a parameter instead.
+	Literal {
+		tried: I,
+		value: String,
+	},
+	/// A subquery or block which is nested more deeply than allowed.
+	Nesting {
+		tried: I,
+		max: usize,
+	},
+}
+
+impl<I: Clone> LintWarning<I> {
+	/// Returns the input value where the warning was found.
+	pub fn tried(&self) -> I {
+		let (Self::FullTableScan {
+			ref tried,
+			..
+		}
+		| Self::SelectAllInView {
+			ref tried,
+			..
+		}
+		| Self::Literal {
+			ref tried,
+			..
+		}
+		| Self::Nesting {
+			ref tried,
+			..
+		}) = self;
+		tried.clone()
+	}
+}
+
+impl LintWarning<&str> {
+	/// Returns the warning represented as a pretty printed string formatted on the original
+	/// source text.
+	pub fn render_on(&self, input: &str) -> RenderedError {
+		let tried = self.tried();
+		let location = Location::of_in(tried, input);
+		let (code, text, explained) = match self {
+			LintWarning::FullTableScan {
+				table,
+				..
+			} => (
+				"full_table_scan",
+				format!("Full scan of table '{table}'"),
+				"no index matches the condition, so every record of the table is read",
+			),
+			LintWarning::SelectAllInView {
+				table,
+				..
+			} => (
+				"select_all_in_view",
+				format!("Projection of all fields in view '{table}'"),
+				"the fields of this view change along with its source table, perhaps list them?",
+			),
+			LintWarning::Literal {
+				value,
+				..
+			} => (
+				"unparameterized_literal",
+				format!("Literal value {value} in condition"),
+				"this value is written into the query, perhaps pass it as a parameter?",
+			),
+			LintWarning::Nesting {
+				max,
+				..
+			} => (
+				"deep_nesting",
+				format!("Subquery nested more than {max} levels deep"),
+				"deeply nested subqueries are hard to read, perhaps use LET statements?",
+			),
+		};
+		let text = format!("{text} at line {} column {}", location.line, location.column);
+		let snippet = Snippet::from_source_location(input, location, Some(explained));
+		RenderedError {
+			diagnostic: Diagnostic {
+				code,
+				severity: Severity::Warning,
+				message: text.clone(),
+				span: Span::of_in(tried, input),
+				expected: Vec::new(),
+				explanation: Some(explained),
+				related: Vec::new(),
+				suggestions: Vec::new(),
+				statement: None,
+			},
+			text,
+			snippets: vec![snippet],
+		}
+	}
+}
@@ -7,11 +7,22 @@ use std::num::ParseFloatError;
 use std::num::ParseIntError;
 use std::ops::Bound;
 use thiserror::Error;
+use unicode_segmentation::UnicodeSegmentation;
 
 mod utils;
 pub use utils::*;
 mod render;
 pub use render::*;
+mod diagnostic;
+pub use diagnostic::*;
+mod suggest;
+pub use suggest::*;
+mod warning;
+pub use warning::*;
+mod semantic;
+pub use semantic::*;
+mod lint;
+pub use lint::*;
 
 #[derive(Error, Debug, Clone)]
 pub enum ParseError<I> {
@@ -134,6 +145,7 @@ impl<I: Clone> ParseError<I> {
 /// A location inside a string.
 ///
 /// Locations are 1 indexed, the first character on the first line being on line 1 column 1.
+/// Columns count graphemes, rather than bytes or characters.
 #[derive(Clone, Copy, Debug)]
 pub struct Location {
 	pub line: usize,
@@ -155,7 +167,8 @@ impl Location {
 			if new_total > offset {
 				// found line.
 				let line_offset = offset - total;
-				let column = line[..line_offset].chars().count();
+				// Columns count graphemes, so that a character with combining marks is one column
+				let column = line[..line_offset].graphemes(true).count();
 				// +1 because line and column are 1 index.
 				return Self {
 					line: idx + 1,
@@ -172,6 +185,18 @@ impl ParseError<&str> {
 	/// Returns the error represented as a pretty printed string formatted on the original source
 	/// text.
 	pub fn render_on(&self, input: &str) -> RenderedError {
+		let (text, mut snippets) = self.render_text(input);
+		if let (Some(s), Some(snippet)) = (self.suggest(input), snippets.first_mut()) {
+			snippet.hint = Some(s.to_string());
+		}
+		RenderedError {
+			diagnostic: self.diagnostic(input, text.clone()),
+			text,
+			snippets,
+		}
+	}
+
+	fn render_text(&self, input: &str) -> (String, Vec<Snippet>) {
 		match self {
 			ParseError::Base(i) => {
 				let location = Location::of_in(i, input);
@@ -180,10 +205,7 @@ impl ParseError<&str> {
 					location.line, location.column
 				);
 				let snippet = Snippet::from_source_location(input, location, None);
-				RenderedError {
-					text,
-					snippets: vec![snippet],
-				}
+				(text, vec![snippet])
 			}
 			ParseError::Expected {
 				tried,
@@ -196,10 +218,7 @@ impl ParseError<&str> {
 					location.line, location.column, expected
 				);
 				let snippet = Snippet::from_source_location(input, location, None);
-				RenderedError {
-					text,
-					snippets: vec![snippet],
-				}
+				(text, vec![snippet])
 			}
 			ParseError::Explained {
 				tried,
@@ -212,10 +231,7 @@ impl ParseError<&str> {
 					location.line, location.column
 				);
 				let snippet = Snippet::from_source_location(input, location, Some(*explained));
-				RenderedError {
-					text,
-					snippets: vec![snippet],
-				}
+				(text, vec![snippet])
 			}
 			ParseError::ExplainedExpected {
 				tried,
@@ -229,10 +245,7 @@ impl ParseError<&str> {
 					location.line, location.column, expected
 				);
 				let snippet = Snippet::from_source_location(input, location, Some(*explained));
-				RenderedError {
-					text,
-					snippets: vec![snippet],
-				}
+				(text, vec![snippet])
 			}
 			ParseError::InvalidPath {
 				tried,
@@ -245,10 +258,7 @@ impl ParseError<&str> {
 					location.line, location.column
 				);
 				let snippet = Snippet::from_source_location(input, location, None);
-				RenderedError {
-					text,
-					snippets: vec![snippet],
-				}
+				(text, vec![snippet])
 			}
 			ParseError::MissingDelimiter {
 				tried,
@@ -266,23 +276,19 @@ impl ParseError<&str> {
 					location,
 					Some("expected this delimiter to be closed"),
 				);
-				RenderedError {
-					text,
-					snippets: vec![error_snippet, open_snippet],
-				}
+				(text, vec![error_snippet, open_snippet])
 			}
 			ParseError::ExcessiveDepth(tried) => {
 				let location = Location::of_in(tried, input);
 				// Writing to a string can't return an error.
 				let text = format!(
-					"Exceeded maximum parse depth at line {} column {}",
-					location.line, location.column
+					"Exceeded maximum parse depth of {} at line {} column {}",
+					crate::sql::parser::depth::max(),
+					location.line,
+					location.column
 				);
 				let snippet = Snippet::from_source_location(input, location, None);
-				RenderedError {
-					text,
-					snippets: vec![snippet],
-				}
+				(text, vec![snippet])
 			}
 			ParseError::Field(tried, f) => {
 				let location = Location::of_in(tried, input);
@@ -291,10 +297,7 @@ impl ParseError<&str> {
 					location.line, location.column
 				);
 				let snippet = Snippet::from_source_location(input, location, None);
-				RenderedError {
-					text,
-					snippets: vec![snippet],
-				}
+				(text, vec![snippet])
 			}
 			ParseError::Split(tried, f) => {
 				let location = Location::of_in(tried, input);
@@ -303,10 +306,7 @@ impl ParseError<&str> {
 					location.line, location.column
 				);
 				let snippet = Snippet::from_source_location(input, location, None);
-				RenderedError {
-					text,
-					snippets: vec![snippet],
-				}
+				(text, vec![snippet])
 			}
 			ParseError::Order(tried, f) => {
 				let location = Location::of_in(tried, input);
@@ -315,10 +315,7 @@ impl ParseError<&str> {
 					location.line, location.column
 				);
 				let snippet = Snippet::from_source_location(input, location, None);
-				RenderedError {
-					text,
-					snippets: vec![snippet],
-				}
+				(text, vec![snippet])
 			}
 			ParseError::Group(tried, f) => {
 				let location = Location::of_in(tried, input);
@@ -327,10 +324,7 @@ impl ParseError<&str> {
 					location.line, location.column
 				);
 				let snippet = Snippet::from_source_location(input, location, None);
-				RenderedError {
-					text,
-					snippets: vec![snippet],
-				}
+				(text, vec![snippet])
 			}
 			ParseError::Role(tried, r) => {
 				let location = Location::of_in(tried, input);
@@ -339,10 +333,7 @@ impl ParseError<&str> {
 					location.line, location.column
 				);
 				let snippet = Snippet::from_source_location(input, location, None);
-				RenderedError {
-					text,
-					snippets: vec![snippet],
-				}
+				(text, vec![snippet])
 			}
 			ParseError::ParseInt {
 				tried,
@@ -352,10 +343,7 @@ impl ParseError<&str> {
 				// Writing to a string can't return an error.
 				let text = format!("Failed to parse '{tried}' as an integer: {error}.");
 				let snippet = Snippet::from_source_location(input, location, None);
-				RenderedError {
-					text,
-					snippets: vec![snippet],
-				}
+				(text, vec![snippet])
 			}
 			ParseError::ParseFloat {
 				tried,
@@ -365,10 +353,7 @@ impl ParseError<&str> {
 				// Writing to a string can't return an error.
 				let text = format!("Failed to parse '{tried}' as a float: {error}.");
 				let snippet = Snippet::from_source_location(input, location, None);
-				RenderedError {
-					text,
-					snippets: vec![snippet],
-				}
+				(text, vec![snippet])
 			}
 			ParseError::ParseDecimal {
 				tried,
@@ -378,10 +363,7 @@ impl ParseError<&str> {
 				// Writing to a string can't return an error.
 				let text = format!("Failed to parse '{tried}' as decimal: {error}.");
 				let snippet = Snippet::from_source_location(input, location, None);
-				RenderedError {
-					text,
-					snippets: vec![snippet],
-				}
+				(text, vec![snippet])
 			}
 			ParseError::ParseRegex {
 				tried,
@@ -391,10 +373,7 @@ impl ParseError<&str> {
 				// Writing to a string can't return an error.
 				let text = format!("Failed to parse '{tried}' as a regex: {error}.");
 				let snippet = Snippet::from_source_location(input, location, None);
-				RenderedError {
-					text,
-					snippets: vec![snippet],
-				}
+				(text, vec![snippet])
 			}
 
 			ParseError::RangeError {
@@ -419,10 +398,7 @@ impl ParseError<&str> {
 					Bound::Unbounded => {}
 				}
 				let snippet = Snippet::from_source_location(input, location, None);
-				RenderedError {
-					text,
-					snippets: vec![snippet],
-				}
+				(text, vec![snippet])
 			}
 			ParseError::InvalidUnicode {
 				tried,
@@ -430,10 +406,7 @@ impl ParseError<&str> {
 				let location = Location::of_in(tried, input);
 				let text = "Invalid unicode escape code.".to_string();
 				let snippet = Snippet::from_source_location(input, location, None);
-				RenderedError {
-					text,
-					snippets: vec![snippet],
-				}
+				(text, vec![snippet])
 			}
 		}
 	}
@@ -1,11 +1,16 @@
 use std::fmt;
 
-use super::Location;
+use super::{Diagnostic, Location, Severity, Span, StatementSpan};
+use std::io::IsTerminal;
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 #[derive(Clone, Debug)]
 pub struct RenderedError {
 	pub text: String,
 	pub snippets: Vec<Snippet>,
+	pub diagnostic: Diagnostic,
 }
 
 impl fmt::Display for RenderedError {
@@ -18,6 +23,169 @@ impl fmt::Display for RenderedError {
 	}
 }
 
+impl RenderedError {
+	/// Returns the error rendered with the given options, such as for printing to a terminal.
+	///
+	/// Rendering with the default options is the same as the [`Display`](fmt::Display)
+	/// output, except that it is colored when printed to a terminal, that source lines
+	/// are truncated to the width of the terminal, when it is known, and that snippets
+	/// are labelled with the number of the statement they are in, when it is known.
+	pub fn render(&self, options: &RenderOptions) -> String {
+		let palette = match options.colored() {
+			true => Palette::of(self.diagnostic.severity),
+			false => Palette::PLAIN,
+		};
+		let mut out = String::new();
+		// Writing to a string never fails
+		let _ = match options.compact {
+			true => self.write_compact(&mut out, &palette),
+			false => self.write_full(&mut out, options, &palette),
+		};
+		out
+	}
+
+	fn write_full(
+		&self,
+		f: &mut impl fmt::Write,
+		options: &RenderOptions,
+		palette: &Palette,
+	) -> fmt::Result {
+		writeln!(f, "{}{}{}", palette.marker, self.text, palette.reset)?;
+		for s in self.snippets.iter() {
+			s.write(f, options.source_width(s.location.line), palette, true)?;
+			writeln!(f)?;
+		}
+		Ok(())
+	}
+
+	/// Labels the error with the statement of the query in which it was found.
+	pub(crate) fn in_statement(mut self, index: usize, span: Span) -> Self {
+		self.diagnostic.statement = Some(StatementSpan {
+			index,
+			span,
+		});
+		for s in self.snippets.iter_mut() {
+			s.statement = Some(index);
+		}
+		self
+	}
+
+	/// Writes the error on a single line, with the explanation and the hint of each snippet.
+	fn write_compact(&self, f: &mut impl fmt::Write, palette: &Palette) -> fmt::Result {
+		let text = self.text.split_whitespace().collect::<Vec<_>>().join(" ");
+		write!(f, "{}{text}{}", palette.marker, palette.reset)?;
+		for s in self.snippets.iter() {
+			if let Some(ref explain) = s.explain {
+				write!(f, ": {explain}")?;
+			}
+			if let Some(ref hint) = s.hint {
+				write!(f, " ({}help:{} {hint})", palette.help, palette.reset)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+/// When rendered errors are colored with ANSI escape codes.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ColorChoice {
+	/// Errors are colored when the standard error stream is a terminal, and the `NO_COLOR`
+	/// environment variable is not set
+	#[default]
+	Auto,
+	/// Errors are always colored
+	Always,
+	/// Errors are never colored
+	Never,
+}
+
+/// The options which control how errors are rendered by [`RenderedError::render`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct RenderOptions {
+	/// When the output is colored
+	pub color: ColorChoice,
+	/// The width which lines are kept within, or `None` to use the width of the terminal
+	/// from the `COLUMNS` environment variable, if it is set
+	pub width: Option<usize>,
+	/// Whether the error is rendered on a single line, without the source, such as for logs
+	pub compact: bool,
+}
+
+impl RenderOptions {
+	/// Set when the output is colored
+	pub fn with_color(mut self, color: ColorChoice) -> Self {
+		self.color = color;
+		self
+	}
+	/// Set the width which lines are kept within
+	pub fn with_width(mut self, width: Option<usize>) -> Self {
+		self.width = width;
+		self
+	}
+	/// Set whether the error is rendered on a single line
+	pub fn with_compact(mut self, compact: bool) -> Self {
+		self.compact = compact;
+		self
+	}
+
+	fn colored(&self) -> bool {
+		match self.color {
+			ColorChoice::Auto => {
+				std::env::var_os("NO_COLOR").is_none() && std::io::stderr().is_terminal()
+			}
+			ColorChoice::Always => true,
+			ColorChoice::Never => false,
+		}
+	}
+
+	/// Returns the width which a source line is truncated to, after the line number.
+	fn source_width(&self, line: usize) -> usize {
+		let width = self.width.or_else(|| std::env::var("COLUMNS").ok()?.parse().ok());
+		match width {
+			// The line number is followed by ` | `
+			Some(width) => width
+				.saturating_sub(line.ilog10() as usize + 4)
+				.max(Snippet::MIN_SOURCE_DISPLAY_LEN),
+			None => Snippet::MAX_SOURCE_DISPLAY_LEN,
+		}
+	}
+}
+
+/// The ANSI escape codes for each part of a rendered error, which are empty when the error
+/// is not colored.
+struct Palette {
+	/// The message, and the underline of the error
+	marker: &'static str,
+	/// The line numbers and their separators
+	gutter: &'static str,
+	/// The hint on how to fix the error
+	help: &'static str,
+	/// Resets the color
+	reset: &'static str,
+}
+
+impl Palette {
+	const PLAIN: Palette = Palette {
+		marker: "",
+		gutter: "",
+		help: "",
+		reset: "",
+	};
+
+	fn of(severity: Severity) -> Self {
+		Palette {
+			marker: match severity {
+				Severity::Error => "\x1b[1;31m",
+				Severity::Warning => "\x1b[1;33m",
+			},
+			gutter: "\x1b[1;34m",
+			help: "\x1b[1;32m",
+			reset: "\x1b[0m",
+		}
+	}
+}
+
 /// Whether the snippet was truncated.
 #[derive(Clone, Copy, Eq, PartialEq, Debug)]
 pub enum Truncation {
@@ -34,23 +202,27 @@ pub enum Truncation {
 /// A piece of the source code with a location and an optional explenation.
 #[derive(Clone, Debug)]
 pub struct Snippet {
-	/// The part of the orignal source code,
-	source: String,
-	/// Wether part of the source line was truncated.
-	truncation: Truncation,
+	/// The line of the orignal source code containing the location.
+	line: String,
+	/// The byte range of the token at the location within the line, which is underlined.
+	range: Range<usize>,
 	/// The location of the snippet in the orignal source code.
 	location: Location,
-	/// The offset into the snippet where the location is.
-	offset: usize,
 	/// A possible explanation for this snippet.
 	explain: Option<String>,
+	/// A possible hint on how to fix the error.
+	pub(super) hint: Option<String>,
+	/// The index of the statement of the query containing the snippet, if it is known.
+	statement: Option<usize>,
 }
 
 impl Snippet {
-	/// How long with the source line have to be before it gets truncated.
+	/// How long with the source line have to be before it gets truncated, by default.
 	const MAX_SOURCE_DISPLAY_LEN: usize = 80;
-	/// How far the will have to be in the source line before everything before it gets truncated.
-	const MAX_ERROR_LINE_OFFSET: usize = 50;
+	/// The shortest width which a source line is truncated to, however narrow the terminal.
+	const MIN_SOURCE_DISPLAY_LEN: usize = 24;
+	/// How many columns a tab is displayed as.
+	const TAB_WIDTH: usize = 4;
 
 	pub fn from_source_location(
 		source: &str,
@@ -58,87 +230,273 @@ impl Snippet {
 		explain: Option<&'static str>,
 	) -> Self {
 		let line = source.split('\n').nth(location.line - 1).unwrap();
-		let (line, truncation, offset) = Self::truncate_line(line, location.column);
-
+		let line = line.strip_suffix('\r').unwrap_or(line);
+		// Find the token at the location, which columns count in graphemes
+		let start = line
+			.grapheme_indices(true)
+			.nth(location.column - 1)
+			.map(|(idx, _)| idx)
+			.unwrap_or(line.len());
+		let end = start + Span::token_len(&line[start..]);
 		Snippet {
-			source: line.to_owned(),
-			truncation,
+			line: line.to_owned(),
+			range: start..end,
 			location,
-			offset,
 			explain: explain.map(|x| x.into()),
+			hint: None,
+			statement: None,
 		}
 	}
 
-	/// Trims whitespace of an line and additionally truncates a string if it is too long.
-	fn truncate_line(mut line: &str, around_offset: usize) -> (&str, Truncation, usize) {
-		let full_line_length = line.len();
-		line = line.trim_start();
-		let mut offset = around_offset - (full_line_length - line.len());
-		line = line.trim_end();
+	/// Trims whitespace of a line, and truncates it if it is wider than `max`, returning the line
+	/// as it is displayed, along with the display column and the display width of a range of the
+	/// line. When the range is far along the line, the start of the line is truncated, keeping
+	/// some of the line before the range for context.
+	fn truncate_line(
+		line: &str,
+		range: Range<usize>,
+		max: usize,
+	) -> (String, Truncation, usize, usize) {
+		let trimmed = line.trim_start();
+		let skipped = line.len() - trimmed.len();
+		let start = range.start.saturating_sub(skipped);
+		let end = range.end.saturating_sub(skipped);
+		// Find the display width of each grapheme
+		let mut cells: Vec<(usize, &str, usize)> = trimmed
+			.trim_end()
+			.grapheme_indices(true)
+			.map(|(idx, g)| match g {
+				"\t" => (idx, g, Self::TAB_WIDTH),
+				g => (idx, g, g.width()),
+			})
+			.collect();
+		let width = |cells: &[(usize, &str, usize)]| cells.iter().map(|c| c.2).sum::<usize>();
+		let mut offset = width(&cells[..cells.partition_point(|c| c.0 < start)]);
 		let mut truncation = Truncation::None;
-
-		if around_offset > Self::MAX_ERROR_LINE_OFFSET {
+		if offset > max * 5 / 8 {
 			// Actual error is to far to the right, just truncated everything to the left.
 			// show some prefix for some extra context.
-			let extra_offset = around_offset - 10;
-			let mut chars = line.chars();
-			for _ in 0..extra_offset {
-				chars.next();
+			let mut skip = 0;
+			while offset > max / 8 {
+				offset -= cells[skip].2;
+				skip += 1;
 			}
-			offset -= extra_offset;
-			line = chars.as_str();
+			cells.drain(..skip);
 			truncation = Truncation::Start;
 		}
-
-		if line.chars().count() > Self::MAX_SOURCE_DISPLAY_LEN {
+		if width(&cells) > max {
 			// Line is too long, truncate to source
-			let mut size = Self::MAX_SOURCE_DISPLAY_LEN - 3;
+			let mut size = max - 3;
 			if truncation == Truncation::Start {
 				truncation = Truncation::Both;
 				size -= 3;
 			} else {
 				truncation = Truncation::End
 			}
-
-			// Unwrap because we just checked if the line length is longer then this.
-			let truncate_index = line.char_indices().nth(size).unwrap().0;
-			line = &line[..truncate_index];
+			let mut total = 0;
+			let keep = cells
+				.iter()
+				.take_while(|c| {
+					total += c.2;
+					total <= size
+				})
+				.count();
+			cells.truncate(keep);
 		}
-
-		(line, truncation, offset)
+		// Underline the range, or the location if the range is empty
+		let length = cells.iter().filter(|c| c.0 >= start && c.0 < end).map(|c| c.2).sum::<usize>();
+		let source = cells
+			.iter()
+			.map(|c| match c.1 {
+				"\t" => " ".repeat(Self::TAB_WIDTH),
+				g => g.to_owned(),
+			})
+			.collect();
+		(source, truncation, offset, length.max(1))
 	}
 }
 
 impl fmt::Display for Snippet {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		self.write(f, Self::MAX_SOURCE_DISPLAY_LEN, &Palette::PLAIN, false)
+	}
+}
+
+impl Snippet {
+	fn write(
+		&self,
+		f: &mut impl fmt::Write,
+		max: usize,
+		palette: &Palette,
+		label: bool,
+	) -> fmt::Result {
+		let (source, truncation, offset, length) =
+			Self::truncate_line(&self.line, self.range.clone(), max);
+		let Palette {
+			marker,
+			gutter,
+			help,
+			reset,
+		} = palette;
 		// extra spacing for the line number
 		let spacing = self.location.line.ilog10() as usize + 1;
-		writeln!(f, "{:>spacing$} |", "")?;
-		write!(f, "{:>spacing$} | ", self.location.line)?;
-		match self.truncation {
+		// Label the snippet with the number of the statement it is in
+		if let (true, Some(index)) = (label, self.statement) {
+			writeln!(f, "{gutter}{:>spacing$}--> statement {}{reset}", "", index + 1)?;
+		}
+		writeln!(f, "{gutter}{:>spacing$} |{reset}", "")?;
+		write!(f, "{gutter}{:>spacing$} |{reset} ", self.location.line)?;
+		match truncation {
 			Truncation::None => {
-				writeln!(f, "{}", self.source)?;
+				writeln!(f, "{}", source)?;
 			}
 			Truncation::Start => {
-				writeln!(f, "...{}", self.source)?;
+				writeln!(f, "...{}", source)?;
 			}
 			Truncation::End => {
-				writeln!(f, "{}...", self.source)?;
+				writeln!(f, "{}...", source)?;
 			}
 			Truncation::Both => {
-				writeln!(f, "...{}...", self.source)?;
+				writeln!(f, "...{}...", source)?;
 			}
 		}
-		let error_offset = self.offset
-			+ if matches!(self.truncation, Truncation::Start | Truncation::Both) {
+		let error_offset = offset
+			+ if matches!(truncation, Truncation::Start | Truncation::Both) {
 				3
 			} else {
 				0
 			};
-		write!(f, "{:>spacing$} | {:>error_offset$} ", "", "^",)?;
+		write!(f, "{gutter}{:>spacing$} |{reset} {:error_offset$}", "", "")?;
+		write!(f, "{marker}{}{reset} ", "^".repeat(length))?;
 		if let Some(ref explain) = self.explain {
-			write!(f, "{explain}")?;
+			write!(f, "{marker}{explain}{reset}")?;
+		}
+		if let Some(ref hint) = self.hint {
+			writeln!(f)?;
+			write!(f, "{:>spacing$} = {help}help:{reset} {hint}", "")?;
 		}
 		Ok(())
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn caret(source: &str, location: Location) -> String {
+		let out = Snippet::from_source_location(source, location, None).to_string();
+		out.lines().last().unwrap().trim_end().to_owned()
+	}
+
+	#[test]
+	fn snippet_underlines_token() {
+		let sql = "SELECT * FROM person WHERE nmae = 1";
+		let out = Snippet::from_source_location(sql, Location::of_in(&sql[27..], sql), None);
+		assert_eq!(
+			out.to_string(),
+			"  |\n1 | SELECT * FROM person WHERE nmae = 1\n  |                            ^^^^ "
+		);
+	}
+
+	#[test]
+	fn snippet_tabs_and_crlf() {
+		let sql = "SELECT *\r\n\tFROM\tperson\r\n\tWHERE";
+		let out = Snippet::from_source_location(sql, Location::of_in(&sql[16..], sql), None);
+		assert_eq!(out.to_string(), "  |\n2 | FROM    person\n  |         ^^^^^^ ");
+		assert_eq!(caret(sql, Location::of_in(&sql[sql.len()..], sql)), "  |      ^");
+	}
+
+	#[test]
+	fn snippet_wide_unicode() {
+		let sql = "SELECT '日本語', 'e\u{301}', 👍 FROM person";
+		let location = Location::of_in(&sql[sql.find('👍').unwrap()..], sql);
+		assert_eq!(location.column, 20);
+		assert_eq!(caret(sql, location), "  |                       ^^");
+	}
+
+	#[test]
+	fn snippet_truncated() {
+		let sql = format!("SELECT {} FROM person WHERE nmae = 1", "a, ".repeat(30));
+		let out = caret(&sql, Location::of_in(&sql[sql.find("nmae").unwrap()..], &sql));
+		assert_eq!(out, "  |              ^^^^");
+	}
+
+	fn rendered(sql: &str, offset: usize) -> RenderedError {
+		let location = Location::of_in(&sql[offset..], sql);
+		let mut snippet = Snippet::from_source_location(sql, location, Some("not a field"));
+		snippet.hint = Some("did you mean `name`?".to_owned());
+		RenderedError {
+			text: format!("Parse error at line 1 column {}", location.column),
+			snippets: vec![snippet],
+			diagnostic: Diagnostic {
+				code: "unexpected_input",
+				severity: Severity::Error,
+				message: String::new(),
+				span: Span::of_in(&sql[offset..], sql),
+				expected: Vec::new(),
+				explanation: None,
+				related: Vec::new(),
+				suggestions: Vec::new(),
+				statement: None,
+			},
+		}
+	}
+
+	#[test]
+	fn render_plain() {
+		let sql = "SELECT * FROM person WHERE nmae = 1";
+		let err = rendered(sql, 27);
+		let options = RenderOptions::default().with_color(ColorChoice::Never).with_width(Some(100));
+		assert_eq!(err.render(&options), err.to_string());
+	}
+
+	#[test]
+	fn render_colored() {
+		let sql = "SELECT * FROM person WHERE nmae = 1";
+		let err = rendered(sql, 27);
+		let options =
+			RenderOptions::default().with_color(ColorChoice::Always).with_width(Some(100));
+		let out = err.render(&options);
+		assert!(out.starts_with("\x1b[1;31mParse error at line 1 column 28\x1b[0m\n"));
+		assert!(out.contains("\x1b[1;31m^^^^\x1b[0m \x1b[1;31mnot a field\x1b[0m"));
+		assert!(out.contains("= \x1b[1;32mhelp:\x1b[0m did you mean `name`?"));
+	}
+
+	#[test]
+	fn render_narrow() {
+		let sql = format!("SELECT * FROM person WHERE {}nmae = 1", "a = 1 AND ".repeat(5));
+		let err = rendered(&sql, sql.find("nmae").unwrap());
+		let options = RenderOptions::default().with_color(ColorChoice::Never).with_width(Some(40));
+		let out = err.render(&options);
+		let lines: Vec<_> = out.lines().collect();
+		assert_eq!(lines[2], "1 | ...AND nmae = 1");
+		assert_eq!(lines[3], "  |        ^^^^ not a field");
+		assert!(lines.iter().all(|l| l.chars().count() <= 40));
+	}
+
+	#[test]
+	fn render_statement_label() {
+		let sql = "SELECT * FROM person; SELECT * FROM person WHERE nmae = 1";
+		let err = rendered(sql, 49).in_statement(1, Span::of_range(22..sql.len(), sql));
+		let options = RenderOptions::default().with_color(ColorChoice::Never).with_width(Some(100));
+		let out = err.render(&options);
+		let lines: Vec<_> = out.lines().collect();
+		assert_eq!(lines[1], " --> statement 2");
+		assert_eq!(lines[2], "  |");
+		assert_eq!(err.diagnostic.statement.unwrap().index, 1);
+		assert_eq!(err.diagnostic.statement.unwrap().span.column, 23);
+		// The label is not included in the plain output
+		assert!(!err.to_string().contains("statement 2"));
+	}
+
+	#[test]
+	fn render_compact() {
+		let sql = "SELECT * FROM person WHERE nmae = 1";
+		let err = rendered(sql, 27);
+		let options = RenderOptions::default().with_color(ColorChoice::Never).with_compact(true);
+		assert_eq!(
+			err.render(&options),
+			"Parse error at line 1 column 28: not a field (help: did you mean `name`?)"
+		);
+	}
+}
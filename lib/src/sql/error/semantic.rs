@@ -0,0 +1,98 @@
+use super::{Diagnostic, Location, RenderedError, Severity, Snippet, Span};
+
+/// A mistake in a query which parsed successfully, but which would fail, or do nothing, when
+/// the query is run.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SemanticError<I> {
+	/// A parameter which is not defined before it is used, and which is not a built-in
+	/// parameter.
+	UnknownParam {
+		tried: I,
+		name: String,
+	},
+	/// A function which is called with the wrong number of arguments.
+	Arity {
+		tried: I,
+		name: String,
+		expected: String,
+		found: usize,
+	},
+	/// A field in a FETCH clause which is not present in the SELECT expression.
+	Fetch {
+		tried: I,
+		idiom: String,
+	},
+}
+
+impl<I: Clone> SemanticError<I> {
+	/// Returns the input value where the error was found.
+	pub fn tried(&self) -> I {
+		let (Self::UnknownParam {
+			ref tried,
+			..
+		}
+		| Self::Arity {
+			ref tried,
+			..
+		}
+		| Self::Fetch {
+			ref tried,
+			..
+		}) = self;
+		tried.clone()
+	}
+}
+
+impl SemanticError<&str> {
+	/// Returns the error represented as a pretty printed string formatted on the original
+	/// source text.
+	pub fn render_on(&self, input: &str) -> RenderedError {
+		let tried = self.tried();
+		let location = Location::of_in(tried, input);
+		let (code, text, explained) = match self {
+			SemanticError::UnknownParam {
+				name,
+				..
+			} => (
+				"unknown_param",
+				format!("Unknown parameter '${name}'"),
+				"this parameter is not defined before it is used",
+			),
+			SemanticError::Arity {
+				name,
+				expected,
+				found,
+				..
+			} => (
+				"invalid_arity",
+				format!("Function '{name}' expects {expected}, but was given {found}"),
+				"this function is called with the wrong number of arguments",
+			),
+			SemanticError::Fetch {
+				idiom,
+				..
+			} => (
+				"invalid_fetch",
+				format!("Found '{idiom}' in FETCH clause, but field is not present in SELECT expression"),
+				"this field is not selected, so can not be fetched",
+			),
+		};
+		let text = format!("{text} at line {} column {}", location.line, location.column);
+		let snippet = Snippet::from_source_location(input, location, Some(explained));
+		RenderedError {
+			diagnostic: Diagnostic {
+				code,
+				severity: Severity::Error,
+				message: text.clone(),
+				span: Span::of_in(tried, input),
+				expected: Vec::new(),
+				explanation: Some(explained),
+				related: Vec::new(),
+				suggestions: Vec::new(),
+				statement: None,
+			},
+			text,
+			snippets: vec![snippet],
+		}
+	}
+}
@@ -0,0 +1,212 @@
+use super::ParseError;
+use crate::sql::builtin::builtin_names;
+use crate::sql::lexer::KEYWORDS;
+use std::fmt;
+
+/// The maximum number of candidates which are suggested.
+const MAX_CANDIDATES: usize = 3;
+
+/// A suggested replacement for a misspelled keyword or function name.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Suggestion {
+	/// The misspelled word in the source.
+	pub word: String,
+	/// The known words which are nearest to the misspelled word.
+	pub candidates: Vec<&'static str>,
+}
+
+impl fmt::Display for Suggestion {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "did you mean ")?;
+		for (i, c) in self.candidates.iter().enumerate() {
+			match i {
+				0 => {}
+				i if i + 1 == self.candidates.len() => write!(f, " or ")?,
+				_ => write!(f, ", ")?,
+			}
+			write!(f, "`{c}`")?;
+		}
+		write!(f, " instead of `{}`?", self.word)
+	}
+}
+
+impl ParseError<&str> {
+	/// Returns the known keywords or function names which are nearest to a misspelled word
+	/// where the error was found, if there are any.
+	pub fn suggest(&self, input: &str) -> Option<Suggestion> {
+		match self {
+			ParseError::InvalidPath {
+				tried,
+				parent,
+			} => {
+				// The function path starts with the parent path and a `::`
+				let start = (input.len() - tried.len()).checked_sub(parent.len() + 2)?;
+				nearest(path_at(&input[start..]), builtin_names())
+			}
+			ParseError::Base(tried)
+			| ParseError::Expected {
+				tried,
+				..
+			}
+			| ParseError::Explained {
+				tried,
+				..
+			}
+			| ParseError::ExplainedExpected {
+				tried,
+				..
+			} => {
+				let word = path_at(tried);
+				match word.contains("::") {
+					true => nearest(word, builtin_names()),
+					false => nearest(word, KEYWORDS),
+				}
+				// A misspelled keyword at the start of a statement is parsed as a value,
+				// and so the error is only found further along the statement.
+				.or_else(|| nearest(statement_start(input, tried), KEYWORDS))
+			}
+			_ => None,
+		}
+	}
+}
+
+/// Returns the keyword or function path at the start of the input.
+fn path_at(i: &str) -> &str {
+	let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+	let mut end = 0;
+	loop {
+		let rest = &i[end..];
+		let len = rest.find(|c: char| !is_ident(c)).unwrap_or(rest.len());
+		end += len;
+		match rest[len..].strip_prefix("::") {
+			Some(r) if len > 0 && r.starts_with(is_ident) => end += 2,
+			_ => break,
+		}
+	}
+	&i[..end]
+}
+
+/// Returns the first word of the statement in which the error was found.
+fn statement_start<'a>(input: &'a str, tried: &str) -> &'a str {
+	let offset = input.len() - tried.len();
+	let start = input[..offset].rfind(';').map(|i| i + 1).unwrap_or(0);
+	path_at(input[start..offset].trim_start())
+}
+
+/// Returns the candidates with the smallest edit distance to a word, if the word is not
+/// itself a candidate, and if the distance is small enough for the word to be a typo.
+fn nearest(word: &str, candidates: &[&'static str]) -> Option<Suggestion> {
+	let len = word.chars().count();
+	if len < 3 {
+		return None;
+	}
+	let lower = word.to_lowercase();
+	let mut best = if len < 8 {
+		1
+	} else {
+		2
+	};
+	let mut out = Vec::new();
+	for c in candidates {
+		let dist = distance(&lower, &c.to_lowercase());
+		if dist == 0 {
+			return None;
+		}
+		if dist < best {
+			best = dist;
+			out.clear();
+		}
+		if dist == best && out.len() < MAX_CANDIDATES {
+			out.push(*c);
+		}
+	}
+	match out.is_empty() {
+		true => None,
+		false => Some(Suggestion {
+			word: word.to_owned(),
+			candidates: out,
+		}),
+	}
+}
+
+/// Returns the edit distance between two strings, where inserting, removing,
+/// replacing, or swapping two adjacent characters each count as one edit.
+fn distance(a: &str, b: &str) -> usize {
+	let a: Vec<char> = a.chars().collect();
+	let b: Vec<char> = b.chars().collect();
+	let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+	for (i, row) in d.iter_mut().enumerate() {
+		row[0] = i;
+	}
+	for (j, cell) in d[0].iter_mut().enumerate() {
+		*cell = j;
+	}
+	for i in 1..=a.len() {
+		for j in 1..=b.len() {
+			let cost = usize::from(a[i - 1] != b[j - 1]);
+			d[i][j] = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+			if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+				d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+			}
+		}
+	}
+	d[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sql::parse;
+
+	#[test]
+	fn suggest_keyword() {
+		let sql = "SELETC * FROM person";
+		let err = ParseError::Expected {
+			tried: &sql[14..],
+			expected: "query to end",
+		};
+		let out = err.suggest(sql).unwrap();
+		assert_eq!(out.word, "SELETC");
+		assert_eq!(out.candidates, vec!["SELECT"]);
+		assert_eq!(out.to_string(), "did you mean `SELECT` instead of `SELETC`?");
+	}
+
+	#[test]
+	fn suggest_function() {
+		let sql = "SELECT string::lowercas(name) FROM person";
+		let err = ParseError::InvalidPath {
+			tried: &sql[15..],
+			parent: "string",
+		};
+		let out = err.suggest(sql).unwrap();
+		assert_eq!(out.word, "string::lowercas");
+		assert_eq!(out.candidates, vec!["string::lowercase"]);
+	}
+
+	#[test]
+	fn suggest_nothing() {
+		let sql = "SELECT * FROM person WHERE";
+		let err = ParseError::Expected {
+			tried: &sql[26..],
+			expected: "a condition",
+		};
+		assert_eq!(err.suggest(sql), None);
+		assert_eq!(nearest("xyzzy", KEYWORDS), None);
+	}
+
+	#[test]
+	fn suggest_rendered() {
+		let sql = "SELECT string::lowercas(name) FROM person";
+		let err = parse(sql).unwrap_err().to_string();
+		assert!(err.contains("did you mean `string::lowercase` instead of `string::lowercas`?"));
+	}
+
+	#[test]
+	fn edit_distance() {
+		assert_eq!(distance("seletc", "select"), 1);
+		assert_eq!(distance("person", "version"), 2);
+		assert_eq!(distance("lowercas", "lowercase"), 1);
+		assert_eq!(distance("", "abc"), 3);
+		assert_eq!(distance("abc", "abc"), 0);
+	}
+}
@@ -1,7 +1,12 @@
 use super::{IResult, ParseError};
-use nom::bytes::complete::tag_no_case;
 use nom::Err;
 use nom::Parser;
+use std::cell::RefCell;
+
+thread_local! {
+	/// The keywords which were tried at the end of the input, while they are being recorded.
+	static TRIED: RefCell<Option<Vec<&'static str>>> = RefCell::default();
+}
 
 pub fn expected<I, O, P>(expect: &'static str, mut parser: P) -> impl FnMut(I) -> IResult<I, O>
 where
@@ -132,3 +137,31 @@ pub fn expect_tag_no_case(tag: &'static str) -> impl FnMut(&str) -> IResult<&str
 		rest => rest,
 	}
 }
+
+/// Parses a keyword, ignoring case. The keyword is recorded if it is tried at the end of the
+/// input within [`record_tried`], so that the keywords which can follow a partly written
+/// query are found with a single parse.
+pub fn tag_no_case<'a, E>(tag: &'static str) -> impl Fn(&'a str) -> IResult<&'a str, &'a str, E>
+where
+	E: nom::error::ParseError<&'a str>,
+{
+	let parser = nom::bytes::complete::tag_no_case(tag);
+	move |input: &'a str| {
+		if input.is_empty() {
+			TRIED.with(|v| {
+				if let Some(v) = v.borrow_mut().as_mut() {
+					v.push(tag);
+				}
+			});
+		}
+		parser(input)
+	}
+}
+
+/// Runs a parser, returning its output along with the keywords it tried at the end of the input.
+pub(crate) fn record_tried<O>(f: impl FnOnce() -> O) -> (O, Vec<&'static str>) {
+	let prev = TRIED.with(|v| v.replace(Some(Vec::new())));
+	let out = f();
+	let tried = TRIED.with(|v| v.replace(prev)).unwrap_or_default();
+	(out, tried)
+}
@@ -0,0 +1,208 @@
+use super::{Diagnostic, IResult, Location, ParseError, RenderedError, Severity, Snippet, Span};
+use nom::Parser;
+use std::cell::RefCell;
+
+/// A part of a query which is deprecated or suspicious, but which does not stop the query
+/// from being parsed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseWarning<I> {
+	/// Syntax which is deprecated, and which may be removed in a future release.
+	Deprecated {
+		tried: I,
+		explained: &'static str,
+	},
+	/// A record id which is written as a string, and which is compared with a record id.
+	RecordIdString {
+		tried: I,
+	},
+	/// A statement following a RETURN, THROW, BREAK, or CONTINUE statement in a block,
+	/// which never runs.
+	Unreachable {
+		tried: I,
+	},
+}
+
+impl<I: Clone> ParseWarning<I> {
+	/// Returns the input value where the warning was found.
+	pub fn tried(&self) -> I {
+		let (Self::Deprecated {
+			ref tried,
+			..
+		}
+		| Self::RecordIdString {
+			ref tried,
+		}
+		| Self::Unreachable {
+			ref tried,
+		}) = self;
+		tried.clone()
+	}
+
+	fn map<O>(self, f: impl FnOnce(I) -> O) -> ParseWarning<O> {
+		match self {
+			Self::Deprecated {
+				tried,
+				explained,
+			} => ParseWarning::Deprecated {
+				tried: f(tried),
+				explained,
+			},
+			Self::RecordIdString {
+				tried,
+			} => ParseWarning::RecordIdString {
+				tried: f(tried),
+			},
+			Self::Unreachable {
+				tried,
+			} => ParseWarning::Unreachable {
+				tried: f(tried),
+			},
+		}
+	}
+}
+
+impl ParseWarning<&str> {
+	/// Returns the warning represented as a pretty printed string formatted on the original
+	/// source text.
+	pub fn render_on(&self, input: &str) -> RenderedError {
+		let tried = self.tried();
+		let location = Location::of_in(tried, input);
+		let (code, text, explained) = match self {
+			ParseWarning::Deprecated {
+				explained,
+				..
+			} => ("deprecated_syntax", "Deprecated syntax", *explained),
+			ParseWarning::RecordIdString {
+				..
+			} => (
+				"record_id_string",
+				"Record id written as a string",
+				"this string is converted to a record id, perhaps remove the quotes?",
+			),
+			ParseWarning::Unreachable {
+				..
+			} => (
+				"unreachable_statement",
+				"Unreachable statement",
+				"this never runs, as the block has already finished",
+			),
+		};
+		let text = format!("{text} at line {} column {}", location.line, location.column);
+		let snippet = Snippet::from_source_location(input, location, Some(explained));
+		RenderedError {
+			diagnostic: Diagnostic {
+				code,
+				severity: Severity::Warning,
+				message: text.clone(),
+				span: Span::of_in(tried, input),
+				expected: Vec::new(),
+				explanation: Some(explained),
+				related: Vec::new(),
+				suggestions: Vec::new(),
+				statement: None,
+			},
+			text,
+			snippets: vec![snippet],
+		}
+	}
+}
+
+thread_local! {
+	/// The warnings found while parsing, by the length of the input remaining at each
+	/// warning, if warnings are being collected.
+	static WARNINGS: RefCell<Option<Vec<ParseWarning<usize>>>> = RefCell::new(None);
+}
+
+/// Collects the warnings found while parsing until dropped.
+#[must_use = "must store and implicitly drop when parsing is complete"]
+pub(crate) struct Collecting;
+
+impl Collecting {
+	pub(crate) fn start() -> Self {
+		WARNINGS.with(|cell| cell.replace(Some(Vec::new())));
+		Collecting
+	}
+
+	/// Returns the collected warnings, in the order they appear in the input.
+	pub(crate) fn take<'a>(&self, input: &'a str) -> Vec<ParseWarning<&'a str>> {
+		let mut out =
+			WARNINGS.with(|cell| cell.borrow_mut().replace(Vec::new())).unwrap_or_default();
+		out.sort_by_key(|w| std::cmp::Reverse(w.tried()));
+		out.into_iter().map(|w| w.map(|len| &input[input.len() - len..])).collect()
+	}
+}
+
+impl Drop for Collecting {
+	fn drop(&mut self) {
+		WARNINGS.with(|cell| cell.replace(None));
+	}
+}
+
+/// Call from parsers to report a warning.
+///
+/// This does nothing unless warnings are being collected. The same part of the input may
+/// be parsed more than once, if an alternative fails, so a repeated warning is ignored.
+pub(crate) fn warn(warning: ParseWarning<&str>) {
+	WARNINGS.with(|cell| {
+		if let Some(warnings) = cell.borrow_mut().as_mut() {
+			let warning = warning.map(str::len);
+			if !warnings.contains(&warning) {
+				warnings.push(warning);
+			}
+		}
+	})
+}
+
+/// Reports a parser as deprecated whenever it succeeds.
+pub(crate) fn deprecated<'a, O, P>(
+	explained: &'static str,
+	mut parser: P,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O>
+where
+	P: Parser<&'a str, O, ParseError<&'a str>>,
+{
+	move |i: &'a str| {
+		let (rest, v) = parser.parse(i)?;
+		warn(ParseWarning::Deprecated {
+			tried: i,
+			explained,
+		});
+		Ok((rest, v))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sql::parse_with_warnings;
+
+	#[test]
+	fn warnings_collected_once() {
+		let sql = "INFO FOR KV; INFO FOR ROOT; INFO FOR kv";
+		let (_, warnings) = parse_with_warnings(sql).unwrap();
+		assert_eq!(warnings.len(), 2);
+		assert_eq!(warnings[0].diagnostic.code, "deprecated_syntax");
+		assert_eq!(warnings[0].diagnostic.severity, Severity::Warning);
+		assert_eq!(warnings[0].diagnostic.span.column, 10);
+		assert_eq!(warnings[1].diagnostic.span.column, 38);
+	}
+
+	#[test]
+	fn warnings_only_collected_when_asked() {
+		warn(ParseWarning::Unreachable {
+			tried: "RETURN 1",
+		});
+		let collecting = Collecting::start();
+		assert!(collecting.take("").is_empty());
+	}
+
+	#[test]
+	fn warning_rendered() {
+		let sql = "SELECT * FROM person WHERE id = 'person:tobie'";
+		let (_, warnings) = parse_with_warnings(sql).unwrap();
+		assert_eq!(warnings.len(), 1);
+		let out = warnings[0].to_string();
+		assert!(out.starts_with("Record id written as a string at line 1 column 33"));
+		assert!(out.contains("perhaps remove the quotes?"));
+	}
+}
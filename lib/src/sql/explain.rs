@@ -1,6 +1,6 @@
 use crate::sql::comment::shouldbespace;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::opt;
 use nom::sequence::tuple;
 use revision::revisioned;
@@ -21,6 +21,15 @@ impl fmt::Display for Explain {
 	}
 }
 
+/// Matches `EXPLAIN ANALYZE`, which executes the query and reports the actual rows and
+/// elapsed time of each iterator
+pub fn explain_analyze(i: &str) -> IResult<&str, ()> {
+	let (i, _) = tag_no_case("EXPLAIN")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("ANALYZE")(i)?;
+	Ok((i, ()))
+}
+
 pub fn explain(i: &str) -> IResult<&str, Explain> {
 	let (i, _) = tag_no_case("EXPLAIN")(i)?;
 	let (i, full) = opt(tuple((shouldbespace, tag_no_case("FULL"))))(i)?;
@@ -49,4 +58,12 @@ mod tests {
 		assert_eq!(out, Explain(true));
 		assert_eq!("EXPLAIN FULL", format!("{}", out));
 	}
+
+	#[test]
+	fn explain_analyze_statement() {
+		let sql = "EXPLAIN ANALYZE";
+		let res = explain_analyze(sql);
+		assert!(res.is_ok());
+		assert!(explain_analyze("EXPLAIN FULL").is_err());
+	}
 }
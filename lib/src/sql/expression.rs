@@ -1,5 +1,5 @@
 use crate::ctx::Context;
-use crate::dbs::{Options, Transaction};
+use crate::dbs::{Options, Transaction, Traversal};
 use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::fnc;
@@ -116,6 +116,14 @@ impl Expression {
 				o,
 				v,
 			} => {
+				// A negated graph traversal stops at the first record it reaches
+				if let (Operator::Not, Value::Idiom(i)) = (o, v) {
+					if let Some(t) = Traversal::new(i) {
+						if let Some(v) = t.exists(ctx, opt, txn, doc).await? {
+							return Ok(Value::Bool(!v));
+						}
+					}
+				}
 				let operand = v.compute(ctx, opt, txn, doc).await?;
 				return match o {
 					Operator::Neg => fnc::operate::neg(operand),
@@ -190,7 +198,10 @@ impl Expression {
 			Operator::NoneInside => fnc::operate::inside_none(&l, &r),
 			Operator::Outside => fnc::operate::outside(&l, &r),
 			Operator::Intersects => fnc::operate::intersects(&l, &r),
-			Operator::Matches(_) => fnc::operate::matches(ctx, txn, doc, self).await,
+			Operator::Matches(_) | Operator::FuzzyMatches(..) => {
+				fnc::operate::matches(ctx, txn, doc, self).await
+			}
+			Operator::Knn(_) => fnc::operate::knn(ctx, doc, self).await,
 			_ => unreachable!(),
 		}
 	}
@@ -1,10 +1,11 @@
 use crate::sql::comment::shouldbespace;
 use crate::sql::common::commas;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::fmt::Fmt;
 use crate::sql::idiom::{plain as idiom, Idiom};
-use nom::bytes::complete::tag_no_case;
-use nom::combinator::cut;
+use nom::character::complete::u64 as uint64;
+use nom::combinator::{cut, verify};
 use nom::multi::separated_list1;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
@@ -60,6 +61,16 @@ pub fn fetch(i: &str) -> IResult<&str, Fetchs> {
 	Ok((i, Fetchs(v)))
 }
 
+/// Matches `FETCH SIZE`, the number of records returned in each batch when
+/// the results of the statement are fetched through a cursor
+pub fn fetch_size(i: &str) -> IResult<&str, u64> {
+	let (i, _) = tag_no_case("FETCH")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("SIZE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	cut(verify(uint64, |v: &u64| *v > 0))(i)
+}
+
 fn fetch_raw(i: &str) -> IResult<&str, Fetch> {
 	let (i, v) = idiom(i)?;
 	Ok((i, Fetch(v)))
@@ -91,4 +102,14 @@ mod tests {
 		);
 		assert_eq!("FETCH field, other.field", format!("{}", out));
 	}
+
+	#[test]
+	fn fetch_size_statement() {
+		let sql = "FETCH SIZE 100";
+		let res = fetch_size(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out, 100);
+		assert!(fetch_size("FETCH SIZE 0").is_err());
+		assert!(fetch_size("FETCH size, other").is_err());
+	}
 }
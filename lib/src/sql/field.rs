@@ -5,14 +5,15 @@ use crate::err::Error;
 use crate::sql::comment::shouldbespace;
 use crate::sql::common::commas;
 use crate::sql::ending::field as ending;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::fmt::Fmt;
 use crate::sql::idiom::{plain, Idiom};
 use crate::sql::parser::idiom;
 use crate::sql::part::Part;
 use crate::sql::value::{value, Value};
+use crate::sql::window::{window, Window};
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::{cut, opt};
 // use nom::combinator::cut;
 use nom::multi::separated_list1;
@@ -39,6 +40,10 @@ impl Fields {
 	pub fn other(&self) -> impl Iterator<Item = &Field> {
 		self.0.iter().filter(|v| !matches!(v, Field::All))
 	}
+	/// Check to see if any field is a window function
+	pub fn has_window(&self) -> bool {
+		self.0.iter().any(|v| matches!(v, Field::Window { .. }))
+	}
 	/// Check to see if this field is a single VALUE clause
 	pub fn single(&self) -> Option<&Field> {
 		match (self.0.len(), self.1) {
@@ -126,6 +131,11 @@ impl Fields {
 							let x = match f.args().len() {
 								// If no function arguments, then compute the result
 								0 => f.compute(ctx, opt, txn, Some(doc)).await?,
+								// If paired arguments, then pass the first two values through
+								n if n > 1 && f.is_paired_aggregate() => Value::from(vec![
+									f.args()[0].compute(ctx, opt, txn, Some(doc)).await?,
+									f.args()[1].compute(ctx, opt, txn, Some(doc)).await?,
+								]),
 								// If arguments, then pass the first value through
 								_ => f.args()[0].compute(ctx, opt, txn, Some(doc)).await?,
 							};
@@ -236,6 +246,22 @@ impl Fields {
 						}
 					}
 				}
+				Field::Window {
+					expr,
+					alias,
+				} => {
+					let name = alias
+						.as_ref()
+						.map(Cow::Borrowed)
+						.unwrap_or_else(|| Cow::Owned(expr.to_idiom()));
+					// The window is computed once all records are processed
+					let x = expr.inputs(ctx, opt, txn, doc).await?;
+					// Check if this is a single VALUE field expression
+					match self.single().is_some() {
+						false => out.set(ctx, opt, txn, name.as_ref(), x).await?,
+						true => out = x,
+					}
+				}
 			}
 		}
 		Ok(out)
@@ -250,7 +276,7 @@ fn field_one(i: &str) -> IResult<&str, Fields> {
 	let (i, _) = tag_no_case("VALUE")(i)?;
 	let (i, _) = shouldbespace(i)?;
 	cut(|i| {
-		let (i, f) = alone(i)?;
+		let (i, f) = alt((over, alone))(i)?;
 		let (i, _) = ending(i)?;
 		Ok((i, Fields(vec![f], true)))
 	})(i)
@@ -273,6 +299,12 @@ pub enum Field {
 		/// The `quality` in `SELECT rating AS quality FROM ...`
 		alias: Option<Idiom>,
 	},
+	/// The 'rank() OVER (ORDER BY rating)' in `SELECT rank() OVER (ORDER BY rating) FROM ...`
+	Window {
+		expr: Window,
+		/// The `position` in `SELECT rank() OVER (ORDER BY rating) AS position FROM ...`
+		alias: Option<Idiom>,
+	},
 }
 
 impl Display for Field {
@@ -291,12 +323,24 @@ impl Display for Field {
 					Ok(())
 				}
 			}
+			Self::Window {
+				expr,
+				alias,
+			} => {
+				Display::fmt(expr, f)?;
+				if let Some(alias) = alias {
+					f.write_str(" AS ")?;
+					Display::fmt(alias, f)
+				} else {
+					Ok(())
+				}
+			}
 		}
 	}
 }
 
 pub fn field(i: &str) -> IResult<&str, Field> {
-	alt((all, alone))(i)
+	alt((all, over, alone))(i)
 }
 
 pub fn all(i: &str) -> IResult<&str, Field> {
@@ -304,6 +348,24 @@ pub fn all(i: &str) -> IResult<&str, Field> {
 	Ok((i, Field::All))
 }
 
+pub fn over(i: &str) -> IResult<&str, Field> {
+	let (i, expr) = window(i)?;
+	let (i, alias) =
+		if let (i, Some(_)) = opt(delimited(shouldbespace, tag_no_case("AS"), shouldbespace))(i)? {
+			let (i, alias) = cut(plain)(i)?;
+			(i, Some(alias))
+		} else {
+			(i, None)
+		};
+	Ok((
+		i,
+		Field::Window {
+			expr,
+			alias,
+		},
+	))
+}
+
 pub fn alone(i: &str) -> IResult<&str, Field> {
 	let (i, expr) = value(i)?;
 	let (i, alias) =
@@ -367,6 +429,32 @@ mod tests {
 		assert_eq!("VALUE field AS one", format!("{}", out));
 	}
 
+	#[test]
+	fn field_window() {
+		let sql = "name, rank() OVER (ORDER BY score DESC) AS position";
+		let res = fields(sql);
+		let out = res.unwrap().1;
+		assert!(out.has_window());
+		assert_eq!("name, rank() OVER (ORDER BY score DESC) AS position", format!("{}", out));
+	}
+
+	#[test]
+	fn field_value_window() {
+		let sql = "VALUE row_number() OVER (PARTITION BY country)";
+		let res = fields(sql);
+		let out = res.unwrap().1;
+		assert_eq!("VALUE row_number() OVER (PARTITION BY country)", format!("{}", out));
+	}
+
+	#[test]
+	fn field_aggregate_without_window() {
+		let sql = "count() AS total";
+		let res = fields(sql);
+		let out = res.unwrap().1;
+		assert!(!out.has_window());
+		assert_eq!("count() AS total", format!("{}", out));
+	}
+
 	#[test]
 	fn field_multiple() {
 		let sql = "field, other.field";
@@ -1,8 +1,10 @@
 use crate::sql::common::{closeparentheses, commas, openparentheses};
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
+use crate::sql::escape::quote_str;
 use crate::sql::language::{language, Language};
+use crate::sql::strand::strand_raw;
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
 use nom::character::complete::u16;
 use nom::combinator::cut;
 use nom::multi::separated_list1;
@@ -12,7 +14,7 @@ use std::fmt;
 use std::fmt::Display;
 
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub enum Filter {
 	Ascii,
 	EdgeNgram(u16, u16),
@@ -20,6 +22,8 @@ pub enum Filter {
 	Ngram(u16, u16),
 	Snowball(Language),
 	Uppercase,
+	#[revision(start = 2)]
+	Stopwords(Vec<String>),
 }
 
 impl Display for Filter {
@@ -31,6 +35,10 @@ impl Display for Filter {
 			Self::Ngram(min, max) => write!(f, "NGRAM({},{})", min, max),
 			Self::Snowball(lang) => write!(f, "SNOWBALL({})", lang),
 			Self::Uppercase => f.write_str("UPPERCASE"),
+			Self::Stopwords(words) => {
+				let words: Vec<String> = words.iter().map(|w| quote_str(w)).collect();
+				write!(f, "STOPWORDS({})", words.join(","))
+			}
 		}
 	}
 }
@@ -79,15 +87,46 @@ fn snowball(i: &str) -> IResult<&str, Filter> {
 	})(i)
 }
 
+fn stopwords(i: &str) -> IResult<&str, Filter> {
+	let (i, _) = tag_no_case("STOPWORDS")(i)?;
+	cut(|i| {
+		let (i, _) = openparentheses(i)?;
+		let (i, words) = separated_list1(commas, strand_raw)(i)?;
+		let (i, _) = closeparentheses(i)?;
+		Ok((i, Filter::Stopwords(words)))
+	})(i)
+}
+
 fn uppercase(i: &str) -> IResult<&str, Filter> {
 	let (i, _) = tag_no_case("UPPERCASE")(i)?;
 	Ok((i, Filter::Uppercase))
 }
 
 fn filter(i: &str) -> IResult<&str, Filter> {
-	alt((ascii, edgengram, lowercase, ngram, snowball, uppercase))(i)
+	alt((ascii, edgengram, lowercase, ngram, snowball, stopwords, uppercase))(i)
 }
 
 pub(super) fn filters(i: &str) -> IResult<&str, Vec<Filter>> {
 	separated_list1(commas, filter)(i)
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn filters_stopwords() {
+		let sql = "LOWERCASE,STOPWORDS('the', \"an\",'a'),SNOWBALL(english)";
+		let res = filters(sql);
+		let out = res.unwrap().1;
+		assert_eq!(
+			out,
+			vec![
+				Filter::Lowercase,
+				Filter::Stopwords(vec!["the".to_owned(), "an".to_owned(), "a".to_owned()]),
+				Filter::Snowball(Language::English),
+			]
+		);
+		assert_eq!(out[1].to_string(), "STOPWORDS('the','an','a')");
+	}
+}
@@ -158,6 +158,12 @@ thread_local! {
 	static INDENT: AtomicU32 = AtomicU32::new(0);
 	/// Whether the next formatting action should be preceded by a newline and indentation.
 	static NEW_LINE: AtomicBool = AtomicBool::new(false);
+	/// The maximum width of a line, or zero if lines are always broken.
+	static WIDTH: AtomicU32 = AtomicU32::new(0);
+	/// The number of spaces in one level of indentation, or zero to indent with tabs.
+	static SPACES: AtomicU32 = AtomicU32::new(0);
+	/// The width of the current line.
+	static COLUMN: AtomicU32 = AtomicU32::new(0);
 }
 
 /// An adapter that, if enabled, adds pretty print formatting.
@@ -183,6 +189,7 @@ impl<W: std::fmt::Write> Pretty<W> {
 			// Clean slate.
 			NEW_LINE.with(|new_line| new_line.store(false, Ordering::Relaxed));
 			INDENT.with(|indent| indent.store(0, Ordering::Relaxed));
+			COLUMN.with(|column| column.store(0, Ordering::Relaxed));
 		}
 		Self {
 			inner,
@@ -228,6 +235,66 @@ pub(crate) fn pretty_sequence_item() {
 	NEW_LINE.with(|new_line| new_line.store(true, Ordering::Relaxed));
 }
 
+/// Limits the width of lines, and sets the number of spaces in one level of indentation, when
+/// pretty printing (until the return value is dropped).
+///
+/// Objects and arrays which fit within the rest of the line are then kept on a single line.
+#[must_use = "hold for the span of the layout, then drop"]
+pub(crate) fn pretty_layout(width: u32, spaces: u32) -> LayoutGuard {
+	let width = WIDTH.with(|v| v.swap(width, Ordering::Relaxed));
+	let spaces = SPACES.with(|v| v.swap(spaces, Ordering::Relaxed));
+	LayoutGuard {
+		width,
+		spaces,
+	}
+}
+
+/// When dropped, restores the previous layout.
+pub(crate) struct LayoutGuard {
+	width: u32,
+	spaces: u32,
+}
+
+impl Drop for LayoutGuard {
+	fn drop(&mut self) {
+		WIDTH.with(|v| v.store(self.width, Ordering::Relaxed));
+		SPACES.with(|v| v.store(self.spaces, Ordering::Relaxed));
+	}
+}
+
+/// Returns the value formatted on a single line, if pretty printing is in effect with a limited
+/// line width, and the value fits within the rest of the current line.
+pub(crate) fn pretty_single_line<T: Display>(v: &T) -> Option<String> {
+	let width = WIDTH.with(|v| v.load(Ordering::Relaxed));
+	if width == 0 || !is_pretty() {
+		return None;
+	}
+	// Format the value without pretty printing
+	PRETTY.with(|pretty| pretty.store(false, Ordering::Relaxed));
+	let new_line = NEW_LINE.with(|new_line| new_line.load(Ordering::Relaxed));
+	let out = v.to_string();
+	NEW_LINE.with(|v| v.store(new_line, Ordering::Relaxed));
+	PRETTY.with(|pretty| pretty.store(true, Ordering::Relaxed));
+	// Check if the value fits on the line where it will be written
+	let column = match new_line {
+		true => indent_width(),
+		false => COLUMN.with(|v| v.load(Ordering::Relaxed)),
+	};
+	match !out.contains('\n') && column as usize + out.chars().count() < width as usize {
+		true => Some(out),
+		false => None,
+	}
+}
+
+/// Returns the width of the current indentation, counting a tab as four spaces.
+fn indent_width() -> u32 {
+	let indent = INDENT.with(|indent| indent.load(Ordering::Relaxed));
+	match SPACES.with(|v| v.load(Ordering::Relaxed)) {
+		0 => indent * 4,
+		n => indent * n,
+	}
+}
+
 /// When dropped, applies the opposite increment to the current indentation level.
 pub(crate) struct PrettyGuard {
 	increment: i8,
@@ -266,10 +333,24 @@ impl<W: std::fmt::Write> std::fmt::Write for Pretty<W> {
 		if self.active && NEW_LINE.with(|new_line| new_line.swap(false, Ordering::Relaxed)) {
 			// Newline.
 			self.inner.write_char('\n')?;
+			let spaces = SPACES.with(|v| v.load(Ordering::Relaxed));
 			for _ in 0..INDENT.with(|indent| indent.load(Ordering::Relaxed)) {
 				// One level of indentation.
-				self.inner.write_char('\t')?;
+				match spaces {
+					0 => self.inner.write_char('\t')?,
+					n => (0..n).try_for_each(|_| self.inner.write_char(' '))?,
+				}
 			}
+			COLUMN.with(|column| column.store(indent_width(), Ordering::Relaxed));
+		}
+		if self.active {
+			// Keep track of the width of the current line.
+			COLUMN.with(|column| match s.rfind('\n') {
+				Some(i) => column.store(s[i + 1..].chars().count() as u32, Ordering::Relaxed),
+				None => {
+					column.fetch_add(s.chars().count() as u32, Ordering::Relaxed);
+				}
+			});
 		}
 		// What we were asked to write.
 		self.inner.write_str(s)
@@ -0,0 +1,241 @@
+//! Formats SurrealQL queries for people to read.
+//!
+//! ```
+//! use surrealdb::sql::format::{format, FormatOptions};
+//!
+//! let sql = "select * from person where age > 18";
+//! let out = format(sql, &FormatOptions::default()).unwrap();
+//! assert_eq!(out, "SELECT * FROM person WHERE age > 18;\n");
+//! ```
+
+use crate::err::Error;
+use crate::sql::fmt::pretty_layout;
+use crate::sql::lexer::{lex, TokenKind, KEYWORDS};
+use crate::sql::statement::Statement;
+use crate::sql::{parse, parse_spanned};
+use std::ops::Range;
+
+/// How keywords are cased by the formatter.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum KeywordCase {
+	/// Keywords are written in uppercase, such as `SELECT`
+	#[default]
+	Upper,
+	/// Keywords are written in lowercase, such as `select`
+	Lower,
+}
+
+/// The options which control how queries are formatted.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct FormatOptions {
+	/// The number of spaces in one level of indentation, or zero to indent with tabs
+	pub indent: u32,
+	/// The width which lines are kept within, where possible
+	pub width: u32,
+	/// How keywords are cased
+	pub keywords: KeywordCase,
+}
+
+impl Default for FormatOptions {
+	fn default() -> Self {
+		Self {
+			indent: 0,
+			width: 100,
+			keywords: KeywordCase::Upper,
+		}
+	}
+}
+
+impl FormatOptions {
+	/// Set the number of spaces in one level of indentation, or zero to indent with tabs
+	pub fn with_indent(mut self, indent: u32) -> Self {
+		self.indent = indent;
+		self
+	}
+	/// Set the width which lines are kept within, where possible
+	pub fn with_width(mut self, width: u32) -> Self {
+		self.width = width;
+		self
+	}
+	/// Set how keywords are cased
+	pub fn with_keywords(mut self, keywords: KeywordCase) -> Self {
+		self.keywords = keywords;
+		self
+	}
+}
+
+/// Formats a SurrealQL query, writing each statement on its own line.
+///
+/// A statement which is longer than the line width is spread over multiple lines, with
+/// objects and arrays which do not fit on the line broken up, and indented. Comments are
+/// preserved, with any comments within a statement being moved to before the statement.
+pub fn format(input: &str, options: &FormatOptions) -> Result<String, Error> {
+	let query = parse_spanned(input)?;
+	let mut out = String::new();
+	let mut last = 0;
+	for v in query.statements.iter() {
+		// Keep the comments between the statements
+		comments(&mut out, &input[last..v.span.start], true);
+		// Keep the comments within the statement
+		for c in scan(&input[v.span.clone()]) {
+			out.push_str(c.trim_end());
+			out.push('\n');
+		}
+		out.push_str(&statement(&v.statement, options));
+		out.push(';');
+		last = v.span.end;
+	}
+	comments(&mut out, &input[last..], false);
+	out.push('\n');
+	Ok(out)
+}
+
+/// Formats a single statement, without a trailing semicolon.
+fn statement(stm: &Statement, options: &FormatOptions) -> String {
+	let width = options.width.max(1) as usize;
+	let out = stm.to_string();
+	let out = match out.contains('\n') || out.chars().count() >= width {
+		true => {
+			let _layout = pretty_layout(width as u32, options.indent);
+			format!("{stm:#}")
+		}
+		false => out,
+	};
+	match options.keywords {
+		KeywordCase::Upper => out,
+		KeywordCase::Lower => lowercase(&out),
+	}
+}
+
+/// Writes the comments found between two statements.
+///
+/// A comment on the same line as the end of the previous statement stays on that
+/// line, and a blank line between statements or comments is kept.
+fn comments(out: &mut String, gap: &str, next: bool) {
+	let mut rest = gap;
+	for (i, c) in scan(gap).into_iter().enumerate() {
+		let offset = c.as_ptr() as usize - gap.as_ptr() as usize;
+		let before = &rest[..offset - (gap.len() - rest.len())];
+		if !out.is_empty() {
+			match before.matches('\n').count() {
+				0 if i == 0 => out.push(' '),
+				0 | 1 => out.push('\n'),
+				_ => out.push_str("\n\n"),
+			}
+		}
+		out.push_str(c.trim_end());
+		rest = &gap[offset + c.len()..];
+	}
+	// Separate the next statement
+	if next && !out.is_empty() {
+		match rest.matches('\n').count() {
+			0 | 1 => out.push('\n'),
+			_ => out.push_str("\n\n"),
+		}
+	}
+}
+
+/// Returns the comments in a part of a query.
+fn scan(i: &str) -> Vec<&str> {
+	lex(i).filter(|t| t.kind == TokenKind::Comment).map(|t| &i[t.span]).collect()
+}
+
+/// Lowercases the keywords of a formatted statement.
+///
+/// Keywords are parsed without regard to their case, but identifiers are not,
+/// so a word is only lowercased where the statement is parsed the same way, which
+/// keeps any identifiers which are spelled the same as a keyword unchanged.
+fn lowercase(i: &str) -> String {
+	// Only keywords which were written in uppercase are lowercased
+	let words = lex(i)
+		.filter(|t| {
+			t.kind == TokenKind::Keyword && KEYWORDS.binary_search(&&i[t.span.clone()]).is_ok()
+		})
+		.map(|t| t.span)
+		.collect::<Vec<_>>();
+	let Ok(stm) = parse(i) else {
+		return i.to_owned();
+	};
+	let same = |out: &str| parse(out).is_ok_and(|v| v == stm);
+	// Most statements have no identifiers which are spelled the same as a keyword
+	let out = lower(i, &words);
+	if same(&out) {
+		return out;
+	}
+	let words = words.into_iter().filter(|w| same(&lower(i, &[w.clone()]))).collect::<Vec<_>>();
+	lower(i, &words)
+}
+
+/// Lowercases the words at the specified spans of the input.
+fn lower(i: &str, words: &[Range<usize>]) -> String {
+	let mut out = String::with_capacity(i.len());
+	let mut last = 0;
+	for w in words {
+		out.push_str(&i[last..w.start]);
+		out.push_str(&i[w.clone()].to_lowercase());
+		last = w.end;
+	}
+	out.push_str(&i[last..]);
+	out
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn format_statements() {
+		let sql = "select * from person where age > 18;create person:tobie set name='Tobie';;";
+		let out = format(sql, &FormatOptions::default()).unwrap();
+		assert_eq!(
+			out,
+			"SELECT * FROM person WHERE age > 18;\nCREATE person:tobie SET name = 'Tobie';\n"
+		);
+	}
+
+	#[test]
+	fn format_long_statement() {
+		let sql = "CREATE person:tobie CONTENT { name: 'Tobie', tags: ['rust', 'go'], address: { city: 'London', country: 'United Kingdom' } }";
+		let out = format(sql, &FormatOptions::default().with_width(50).with_indent(2)).unwrap();
+		assert_eq!(
+			out,
+			"CREATE person:tobie CONTENT {\n  address: {\n    city: 'London',\n    country: 'United Kingdom'\n  },\n  name: 'Tobie',\n  tags: ['rust', 'go']\n};\n"
+		);
+	}
+
+	#[test]
+	fn format_lowercase_keywords() {
+		let sql = "SELECT * FROM person WHERE name = 'SELECT' AND $SELECT AND math::PI > 3";
+		let options = FormatOptions::default().with_keywords(KeywordCase::Lower);
+		let out = format(sql, &options).unwrap();
+		assert_eq!(
+			out,
+			"select * from person where name = 'SELECT' and $SELECT and math::PI > 3;\n"
+		);
+	}
+
+	#[test]
+	fn format_lowercase_keywords_not_identifiers() {
+		let sql = "SELECT COMMENT FROM USER";
+		let options = FormatOptions::default().with_keywords(KeywordCase::Lower);
+		let out = format(sql, &options).unwrap();
+		assert_eq!(out, "select COMMENT from USER;\n");
+	}
+
+	#[test]
+	fn format_comments() {
+		let sql = "-- people\nSELECT * FROM person; # adults\n\n\n/* things */ SELECT * /* all */ FROM thing;";
+		let out = format(sql, &FormatOptions::default()).unwrap();
+		assert_eq!(
+			out,
+			"-- people\nSELECT * FROM person; # adults\n\n/* things */\n/* all */\nSELECT * FROM thing;\n"
+		);
+	}
+
+	#[test]
+	fn format_invalid() {
+		assert!(format("SELECT * FROM", &FormatOptions::default()).is_err());
+	}
+}
@@ -1,9 +1,10 @@
 use crate::ctx::Context;
-use crate::dbs::{Options, Transaction};
+use crate::dbs::{Options, Transaction, Traversal};
 use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::fnc;
 use crate::iam::Action;
+use crate::sql::array::Array;
 use crate::sql::comment::mightbespace;
 use crate::sql::common::val_char;
 use crate::sql::common::{commas, openparentheses};
@@ -11,6 +12,7 @@ use crate::sql::error::IResult;
 use crate::sql::fmt::Fmt;
 use crate::sql::idiom::Idiom;
 use crate::sql::script::{script as func, Script};
+use crate::sql::statements::DefineFunctionStatement;
 use crate::sql::value::{value, Value};
 use crate::sql::Permission;
 use async_recursion::async_recursion;
@@ -26,6 +28,7 @@ use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::fmt;
+use std::sync::Arc;
 
 use super::error::expected;
 use super::util::delimited_list0;
@@ -77,6 +80,24 @@ impl Function {
 	/// Convert this function to an aggregate
 	pub fn aggregate(&self, val: Value) -> Self {
 		match self {
+			// The aggregated values are pairs of the first two arguments
+			Self::Normal(n, a) if self.is_paired_aggregate() => {
+				let (mut x, mut y) = (Array::new(), Array::new());
+				if let Value::Array(pairs) = val {
+					for pair in pairs {
+						let mut pair = match pair {
+							Value::Array(v) => v.0.into_iter(),
+							v => vec![v].into_iter(),
+						};
+						x.push(pair.next().unwrap_or_default());
+						y.push(pair.next().unwrap_or_default());
+					}
+				}
+				let mut a: Vec<Value> = a.iter().skip(2).cloned().collect();
+				a.insert(0, y.into());
+				a.insert(0, x.into());
+				Self::Normal(n.to_owned(), a)
+			}
 			Self::Normal(n, a) => {
 				let mut a = a.to_owned();
 				match a.len() {
@@ -105,6 +126,7 @@ impl Function {
 	pub fn is_rolling(&self) -> bool {
 		match self {
 			Self::Normal(f, _) if f == "count" => true,
+			Self::Normal(f, _) if f == "count::distinct" => true,
 			Self::Normal(f, _) if f == "math::max" => true,
 			Self::Normal(f, _) if f == "math::mean" => true,
 			Self::Normal(f, _) if f == "math::min" => true,
@@ -114,6 +136,33 @@ impl Function {
 			_ => false,
 		}
 	}
+	/// Check if this function writes to the datastore
+	pub fn is_writeable(&self) -> bool {
+		matches!(self, Self::Normal(f, _) if f == "sequence::next")
+	}
+	/// Check if this function returns a different value on each invocation
+	pub fn is_nondeterministic(&self) -> bool {
+		match self {
+			Self::Normal(f, _) if f == "rand" => true,
+			Self::Normal(f, _) if f.starts_with("rand::") => true,
+			Self::Normal(f, _) if f == "sequence::next" => true,
+			Self::Normal(f, _) if f.starts_with("session::") => true,
+			Self::Normal(f, _) if f == "time::now" => true,
+			Self::Normal(f, _) if f == "time::timezone" => true,
+			_ => false,
+		}
+	}
+	/// Check if this function is a grouping function of pairs of values
+	pub fn is_paired_aggregate(&self) -> bool {
+		match self {
+			Self::Normal(f, _) if f == "math::corr" => true,
+			Self::Normal(f, _) if f == "math::covar" => true,
+			Self::Normal(f, _) if f == "math::regr_intercept" => true,
+			Self::Normal(f, _) if f == "math::regr_slope" => true,
+			Self::Normal(f, _) if f == "object::pivot" => true,
+			_ => false,
+		}
+	}
 	/// Check if this function is a grouping function
 	pub fn is_aggregate(&self) -> bool {
 		match self {
@@ -122,7 +171,10 @@ impl Function {
 			Self::Normal(f, _) if f == "array::group" => true,
 			Self::Normal(f, _) if f == "array::last" => true,
 			Self::Normal(f, _) if f == "count" => true,
+			Self::Normal(f, _) if f == "count::distinct" => true,
 			Self::Normal(f, _) if f == "math::bottom" => true,
+			Self::Normal(f, _) if f == "math::corr" => true,
+			Self::Normal(f, _) if f == "math::covar" => true,
 			Self::Normal(f, _) if f == "math::interquartile" => true,
 			Self::Normal(f, _) if f == "math::max" => true,
 			Self::Normal(f, _) if f == "math::mean" => true,
@@ -132,6 +184,8 @@ impl Function {
 			Self::Normal(f, _) if f == "math::mode" => true,
 			Self::Normal(f, _) if f == "math::nearestrank" => true,
 			Self::Normal(f, _) if f == "math::percentile" => true,
+			Self::Normal(f, _) if f == "math::regr_intercept" => true,
+			Self::Normal(f, _) if f == "math::regr_slope" => true,
 			Self::Normal(f, _) if f == "math::sample" => true,
 			Self::Normal(f, _) if f == "math::spread" => true,
 			Self::Normal(f, _) if f == "math::stddev" => true,
@@ -139,6 +193,7 @@ impl Function {
 			Self::Normal(f, _) if f == "math::top" => true,
 			Self::Normal(f, _) if f == "math::trimean" => true,
 			Self::Normal(f, _) if f == "math::variance" => true,
+			Self::Normal(f, _) if f == "object::pivot" => true,
 			Self::Normal(f, _) if f == "time::max" => true,
 			Self::Normal(f, _) if f == "time::min" => true,
 			_ => false,
@@ -164,60 +219,43 @@ impl Function {
 			Self::Normal(s, x) => {
 				// Check this function is allowed
 				ctx.check_allowed_function(s)?;
-				// Compute the function arguments
-				let a = try_join_all(x.iter().map(|v| v.compute(ctx, opt, txn, doc))).await?;
-				// Run the normal function
-				fnc::run(ctx, opt, txn, doc, s, a).await
-			}
-			Self::Custom(s, x) => {
-				// Check this function is allowed
-				ctx.check_allowed_function(format!("fn::{s}").as_str())?;
-				// Get the function definition
-				let val = {
-					// Claim transaction
-					let mut run = txn.lock().await;
-					// Get the function definition
-					run.get_and_cache_db_function(opt.ns(), opt.db(), s).await?
-				};
-				// Check permissions
-				if opt.check_perms(Action::View) {
-					match &val.permissions {
-						Permission::Full => (),
-						Permission::None => {
-							return Err(Error::FunctionPermissions {
-								name: s.to_owned(),
-							})
-						}
-						Permission::Specific(e) => {
-							// Disable permissions
-							let opt = &opt.new_with_perms(false);
-							// Process the PERMISSION clause
-							if !e.compute(ctx, opt, txn, doc).await?.is_truthy() {
-								return Err(Error::FunctionPermissions {
-									name: s.to_owned(),
-								});
-							}
+				// Count a graph traversal without collecting its records
+				if let ("count", [Value::Idiom(i)]) = (s.as_str(), x.as_slice()) {
+					if let Some(t) = Traversal::new(i) {
+						if let Some(n) = t.count(ctx, opt, txn, doc).await? {
+							return Ok(n.into());
 						}
 					}
 				}
-				// Return the value
-				// Check the function arguments
-				if x.len() != val.args.len() {
-					return Err(Error::InvalidArguments {
-						name: format!("fn::{}", val.name),
-						message: match val.args.len() {
-							1 => String::from("The function expects 1 argument."),
-							l => format!("The function expects {l} arguments."),
+				// Collect the paths of a graph traversal while walking it
+				if s == "graph::paths" {
+					return match x.as_slice() {
+						[Value::Idiom(i)] => match Traversal::new(i) {
+							Some(t) => t.paths(ctx, opt, txn, doc).await,
+							None => Err(Error::InvalidArguments {
+								name: s.to_owned(),
+								message: String::from("The argument must be a graph traversal, without projections or limits."),
+							}),
 						},
-					});
+						_ => Err(Error::InvalidArguments {
+							name: s.to_owned(),
+							message: String::from("Expected a single graph traversal argument."),
+						}),
+					};
 				}
 				// Compute the function arguments
 				let a = try_join_all(x.iter().map(|v| v.compute(ctx, opt, txn, doc))).await?;
+				// Run the normal function
+				fnc::run(ctx, opt, txn, doc, s, a).await
+			}
+			Self::Custom(_, _) => {
+				// Get the function definition and arguments
+				let (val, args) = self.prepare(ctx, opt, txn, doc).await?;
 				// Duplicate context
 				let mut ctx = Context::new(ctx);
 				// Process the function arguments
-				for (val, (name, kind)) in a.into_iter().zip(&val.args) {
-					ctx.add_value(name.to_raw(), val.coerce_to(kind)?);
+				for (name, val) in args {
+					ctx.add_value(name, val);
 				}
 				// Run the custom function
 				val.block.compute(&ctx, opt, txn, doc).await
@@ -242,6 +280,68 @@ impl Function {
 			}
 		}
 	}
+	/// Get the definition of a custom function, checking that it can be run,
+	/// along with the arguments to run it with, coerced to their types
+	pub(crate) async fn prepare(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&CursorDoc<'_>>,
+	) -> Result<(Arc<DefineFunctionStatement>, Vec<(String, Value)>), Error> {
+		let (s, x) = match self {
+			Self::Custom(s, x) => (s, x),
+			_ => unreachable!(),
+		};
+		// Check this function is allowed
+		ctx.check_allowed_function(format!("fn::{s}").as_str())?;
+		// Get the function definition
+		let val = {
+			// Claim transaction
+			let mut run = txn.lock().await;
+			// Get the function definition
+			run.get_and_cache_db_function(opt.ns(), opt.db(), s).await?
+		};
+		// Check permissions
+		if opt.check_perms(Action::View) {
+			match &val.permissions {
+				Permission::Full => (),
+				Permission::None => {
+					return Err(Error::FunctionPermissions {
+						name: s.to_owned(),
+					})
+				}
+				Permission::Specific(e) => {
+					// Disable permissions
+					let opt = &opt.new_with_perms(false);
+					// Process the PERMISSION clause
+					if !e.compute(ctx, opt, txn, doc).await?.is_truthy() {
+						return Err(Error::FunctionPermissions {
+							name: s.to_owned(),
+						});
+					}
+				}
+			}
+		}
+		// Check the function arguments
+		if x.len() != val.args.len() {
+			return Err(Error::InvalidArguments {
+				name: format!("fn::{}", val.name),
+				message: match val.args.len() {
+					1 => String::from("The function expects 1 argument."),
+					l => format!("The function expects {l} arguments."),
+				},
+			});
+		}
+		// Compute the function arguments
+		let a = try_join_all(x.iter().map(|v| v.compute(ctx, opt, txn, doc))).await?;
+		// Coerce the function arguments
+		let mut args = Vec::with_capacity(a.len());
+		for (v, (name, kind)) in a.into_iter().zip(&val.args) {
+			args.push((name.to_raw(), v.coerce_to(kind)?));
+		}
+		Ok((val, args))
+	}
 }
 
 impl fmt::Display for Function {
@@ -2,19 +2,25 @@ use crate::sql::comment::shouldbespace;
 use crate::sql::common::{closeparentheses, openparentheses};
 use crate::sql::cond::{cond, Cond};
 use crate::sql::dir::{dir, Dir};
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
+use crate::sql::expression::Expression;
 use crate::sql::field::Fields;
 use crate::sql::group::Groups;
 use crate::sql::idiom::{plain as idiom, Idiom};
 use crate::sql::limit::Limit;
+use crate::sql::operator::Operator;
 use crate::sql::order::Orders;
+use crate::sql::paths::ID;
 use crate::sql::split::Splits;
 use crate::sql::start::Start;
-use crate::sql::table::{table, tables, Tables};
+use crate::sql::table::{table, tables, Table, Tables};
+use crate::sql::thing::thing_raw;
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
-use nom::character::complete::char;
-use nom::combinator::{map, opt};
+use nom::bytes::complete::tag;
+use nom::character::complete::{char, u32};
+use nom::combinator::{map, opt, verify};
+use nom::sequence::preceded;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Formatter, Write};
@@ -22,7 +28,7 @@ use std::fmt::{self, Display, Formatter, Write};
 use super::util::expect_delimited;
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct Graph {
 	pub dir: Dir,
 	pub expr: Fields,
@@ -34,6 +40,26 @@ pub struct Graph {
 	pub limit: Option<Limit>,
 	pub start: Option<Start>,
 	pub alias: Option<Idiom>,
+	#[revision(start = 2)]
+	pub depth: Option<Depth>,
+}
+
+/// The number of times a recursive graph edge is followed, together with
+/// the record hop after it, such as `->knows{1..4}->person`
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[revisioned(revision = 1)]
+pub struct Depth {
+	pub min: u32,
+	pub max: u32,
+}
+
+impl Display for Depth {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		match self.min == self.max {
+			true => write!(f, "{{{}}}", self.min),
+			false => write!(f, "{{{}..{}}}", self.min, self.max),
+		}
+	}
 }
 
 impl Graph {
@@ -50,7 +76,7 @@ impl Display for Graph {
 			match self.what.len() {
 				0 => f.write_char('?'),
 				_ => Display::fmt(&self.what, f),
-			}
+			}?;
 		} else {
 			write!(f, "{}(", self.dir)?;
 			match self.what.len() {
@@ -78,14 +104,19 @@ impl Display for Graph {
 			if let Some(ref v) = self.alias {
 				write!(f, " AS {v}")?
 			}
-			f.write_char(')')
+			f.write_char(')')?;
+		}
+		if let Some(ref v) = self.depth {
+			Display::fmt(v, f)?
 		}
+		Ok(())
 	}
 }
 
 pub fn graph(i: &str) -> IResult<&str, Graph> {
 	let (i, dir) = dir(i)?;
 	let (i, (what, cond, alias)) = alt((simple, custom))(i)?;
+	let (i, depth) = opt(depth)(i)?;
 	Ok((
 		i,
 		Graph {
@@ -99,13 +130,42 @@ pub fn graph(i: &str) -> IResult<&str, Graph> {
 			order: None,
 			limit: None,
 			start: None,
+			depth,
 		},
 	))
 }
 
+/// The number of times a recursive graph edge is followed, as `{min..max}` or `{n}`
+fn depth(i: &str) -> IResult<&str, Depth> {
+	verify(
+		|i| {
+			let (i, _) = char('{')(i)?;
+			let (i, min) = u32(i)?;
+			let (i, max) = opt(preceded(tag(".."), u32))(i)?;
+			let (i, _) = char('}')(i)?;
+			Ok((
+				i,
+				Depth {
+					min,
+					max: max.unwrap_or(min),
+				},
+			))
+		},
+		|v: &Depth| v.min > 0 && v.min <= v.max,
+	)(i)
+}
+
 fn simple(i: &str) -> IResult<&str, (Tables, Option<Cond>, Option<Idiom>)> {
-	let (i, w) = alt((any, one))(i)?;
-	Ok((i, (w, None, None)))
+	alt((record, map(alt((any, one)), |w| (w, None, None))))(i)
+}
+
+/// A single record, such as `->user:tobie`, which is
+/// the same as `->(user WHERE id = user:tobie)`
+fn record(i: &str) -> IResult<&str, (Tables, Option<Cond>, Option<Idiom>)> {
+	let (i, v) = thing_raw(i)?;
+	let w = Tables::from(Table::from(v.tb.as_str()));
+	let c = Expression::new(Idiom::from(ID.as_ref()).into(), Operator::Equal, v.into());
+	Ok((i, (w, Some(Cond(c.into())), None)))
 }
 
 fn custom(i: &str) -> IResult<&str, (Tables, Option<Cond>, Option<Idiom>)> {
@@ -169,6 +229,14 @@ mod tests {
 		assert_eq!("<->likes", format!("{}", out));
 	}
 
+	#[test]
+	fn graph_record() {
+		let sql = "->user:tobie";
+		let res = graph(sql);
+		let out = res.unwrap().1;
+		assert_eq!("->(user WHERE id = user:tobie)", format!("{}", out));
+	}
+
 	#[test]
 	fn graph_multiple() {
 		let sql = "->(likes, follows)";
@@ -193,6 +261,38 @@ mod tests {
 		assert_eq!("->(likes, follows WHERE influencer = true)", format!("{}", out));
 	}
 
+	#[test]
+	fn graph_depth() {
+		let sql = "->knows{1..4}";
+		let res = graph(sql);
+		let out = res.unwrap().1;
+		assert_eq!("->knows{1..4}", format!("{}", out));
+		assert_eq!(
+			out.depth,
+			Some(Depth {
+				min: 1,
+				max: 4
+			})
+		);
+	}
+
+	#[test]
+	fn graph_depth_exact() {
+		let sql = "->(knows WHERE close = true){2}";
+		let res = graph(sql);
+		let out = res.unwrap().1;
+		assert_eq!("->(knows WHERE close = true){2}", format!("{}", out));
+	}
+
+	#[test]
+	fn graph_depth_invalid() {
+		let sql = "->knows{3..2}";
+		let res = graph(sql);
+		let out = res.unwrap();
+		assert_eq!(out.0, "{3..2}");
+		assert_eq!(out.1.depth, None);
+	}
+
 	#[test]
 	fn graph_conditions_aliases() {
 		let sql = "->(likes, follows WHERE influencer = true AS connections)";
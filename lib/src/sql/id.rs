@@ -1,5 +1,6 @@
 use crate::cnf::ID_CHARS;
 use crate::ctx::Context;
+use crate::dbs::seed;
 use crate::dbs::{Options, Transaction};
 use crate::doc::CursorDoc;
 use crate::err::Error;
@@ -137,21 +138,21 @@ impl From<Thing> for Id {
 impl Id {
 	/// Generate a new random ID
 	pub fn rand() -> Self {
-		Self::String(nanoid!(20, &ID_CHARS))
+		Self::String(nanoid!(20, &ID_CHARS, seed::bytes))
 	}
 	/// Generate a new random ULID
 	pub fn ulid() -> Self {
-		Self::String(Ulid::new().to_string())
+		Self::String(seed::with(|mut rng| Ulid::with_source(&mut rng)).to_string())
 	}
 	/// Generate a new random UUID
 	#[cfg(uuid_unstable)]
 	pub fn uuid() -> Self {
-		Self::String(Uuid::new_v7().to_raw())
+		Self::String(Uuid::new_v7_seeded().to_raw())
 	}
 	/// Generate a new random UUID
 	#[cfg(not(uuid_unstable))]
 	pub fn uuid() -> Self {
-		Self::String(Uuid::new_v4().to_raw())
+		Self::String(Uuid::new_v4_seeded().to_raw())
 	}
 	/// Convert the Id to a raw String
 	pub fn to_raw(&self) -> String {
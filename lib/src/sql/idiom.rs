@@ -1,5 +1,5 @@
 use crate::ctx::Context;
-use crate::dbs::{Options, Transaction};
+use crate::dbs::{Options, Transaction, Traversal};
 use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::sql::common::commas;
@@ -152,6 +152,12 @@ impl Idiom {
 		txn: &Transaction,
 		doc: Option<&CursorDoc<'_>>,
 	) -> Result<Value, Error> {
+		// Multi-hop graph traversals are looked up in bulk
+		if let Some(t) = Traversal::new(self).filter(|t| t.hops() > 1) {
+			if let Some(v) = t.records(ctx, opt, txn, doc).await? {
+				return Ok(v);
+			}
+		}
 		match self.first() {
 			// The starting part is a value
 			Some(Part::Start(v)) => {
@@ -456,6 +462,7 @@ mod tests {
 					order: None,
 					limit: None,
 					start: None,
+					depth: None,
 				}),
 				Part::Graph(Graph {
 					dir: Dir::Out,
@@ -468,6 +475,7 @@ mod tests {
 					order: None,
 					limit: None,
 					start: None,
+					depth: None,
 				}),
 			])
 		);
@@ -1,14 +1,18 @@
+use crate::err::Error;
 use crate::idx::ft::analyzer::Analyzers;
+use crate::idx::trees::distance;
 use crate::sql::comment::{mightbespace, shouldbespace};
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::ident::{ident, Ident};
 use crate::sql::scoring::{scoring, Scoring};
-use crate::sql::Number;
+use crate::sql::{Array, Number, Value};
 use nom::branch::alt;
-use nom::bytes::complete::{tag, tag_no_case};
+use nom::bytes::complete::tag;
 use nom::character::complete::u16 as uint16;
 use nom::character::complete::u32 as uint32;
 use nom::combinator::{cut, map, opt};
+use nom::sequence::tuple;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -26,6 +30,34 @@ pub enum Index {
 	Search(SearchParams),
 	/// M-Tree index for distance based metrics
 	MTree(MTreeParams),
+	/// HNSW index for approximate nearest neighbour searches
+	Hnsw(HnswParams),
+	/// R-tree index for geospatial bounding box and distance searches
+	RTree(RTreeParams),
+	/// Unique index with an explicit handling of none and null values
+	UniqNulls(Nulls),
+}
+
+/// How none and null values are compared by a unique index
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[revisioned(revision = 1)]
+pub enum Nulls {
+	/// Entries containing a none or null value never conflict, and are not indexed
+	Distinct,
+	/// None and null values are equal, so entries containing them can conflict
+	NotDistinct,
+}
+
+impl Index {
+	/// Checks if the values of a document are skipped by a unique index.
+	/// By default, only entries made entirely of none or null values are skipped.
+	pub(crate) fn skips_unique(&self, values: &Array) -> bool {
+		match self {
+			Self::UniqNulls(Nulls::Distinct) => values.iter().any(Value::is_none_or_null),
+			Self::UniqNulls(Nulls::NotDistinct) => false,
+			_ => values.is_all_none_or_null(),
+		}
+	}
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
@@ -49,6 +81,26 @@ pub struct MTreeParams {
 	pub doc_ids_order: u32,
 }
 
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[revisioned(revision = 1)]
+pub struct HnswParams {
+	pub dimension: u16,
+	pub distance: Distance,
+	/// The maximum number of neighbours of each node, per layer
+	pub m: u16,
+	/// The size of the candidate list used while inserting a node
+	pub ef_construction: u16,
+	pub doc_ids_order: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[revisioned(revision = 1)]
+pub struct RTreeParams {
+	/// The maximum number of entries of each node
+	pub capacity: u16,
+	pub doc_ids_order: u32,
+}
+
 #[derive(Default, Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
 #[revisioned(revision = 1)]
 pub enum Distance {
@@ -61,6 +113,29 @@ pub enum Distance {
 	Minkowski(Number),
 }
 
+impl Distance {
+	/// Compute the distance between two vectors of the same dimension
+	pub(crate) fn compute(&self, a: &[f64], b: &[f64]) -> Result<f64, Error> {
+		match self {
+			Self::Euclidean => Ok(distance::euclidean(a, b)),
+			Self::Manhattan => Ok(distance::manhattan(a, b)),
+			Self::Cosine => Ok(distance::cosine(a, b)),
+			Self::Hamming => Ok(a.iter().zip(b.iter()).filter(|(a, b)| a != b).count() as f64),
+			Self::Minkowski(order) => {
+				let order = order.to_float();
+				Ok(a.iter()
+					.zip(b.iter())
+					.map(|(a, b)| (a - b).abs().powf(order))
+					.sum::<f64>()
+					.powf(1.0 / order))
+			}
+			Self::Mahalanobis => Err(Error::FeatureNotYetImplemented {
+				feature: "Mahalanobis distance".to_string(),
+			}),
+		}
+	}
+}
+
 impl Display for Distance {
 	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
 		match self {
@@ -102,17 +177,53 @@ impl Display for Index {
 					p.dimension, p.distance, p.capacity, p.doc_ids_order
 				)
 			}
+			Self::Hnsw(p) => {
+				write!(
+					f,
+					"HNSW DIMENSION {} DIST {} M {} EFC {} DOC_IDS_ORDER {}",
+					p.dimension, p.distance, p.m, p.ef_construction, p.doc_ids_order
+				)
+			}
+			Self::RTree(p) => {
+				write!(f, "RTREE CAPACITY {} DOC_IDS_ORDER {}", p.capacity, p.doc_ids_order)
+			}
+			Self::UniqNulls(n) => write!(f, "UNIQUE {n}"),
+		}
+	}
+}
+
+impl Display for Nulls {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		match self {
+			Self::Distinct => f.write_str("NULLS DISTINCT"),
+			Self::NotDistinct => f.write_str("NULLS NOT DISTINCT"),
 		}
 	}
 }
 
 pub fn index(i: &str) -> IResult<&str, Index> {
-	alt((unique, search, mtree))(i)
+	alt((unique, search, mtree, hnsw, rtree))(i)
 }
 
 pub fn unique(i: &str) -> IResult<&str, Index> {
 	let (i, _) = tag_no_case("UNIQUE")(i)?;
-	Ok((i, Index::Uniq))
+	let (i, nulls) = opt(nulls)(i)?;
+	match nulls {
+		Some(n) => Ok((i, Index::UniqNulls(n))),
+		None => Ok((i, Index::Uniq)),
+	}
+}
+
+pub fn nulls(i: &str) -> IResult<&str, Nulls> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("NULLS")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	cut(alt((
+		map(tag_no_case("DISTINCT"), |_| Nulls::Distinct),
+		map(tuple((tag_no_case("NOT"), shouldbespace, tag_no_case("DISTINCT"))), |_| {
+			Nulls::NotDistinct
+		}),
+	)))(i)
 }
 
 pub fn analyzer(i: &str) -> IResult<&str, Ident> {
@@ -182,12 +293,13 @@ pub fn search(i: &str) -> IResult<&str, Index> {
 pub fn distance(i: &str) -> IResult<&str, Distance> {
 	let (i, _) = mightbespace(i)?;
 	let (i, _) = tag_no_case("DIST")(i)?;
+	let (i, _) = shouldbespace(i)?;
 	alt((
 		map(tag_no_case("EUCLIDEAN"), |_| Distance::Euclidean),
 		map(tag_no_case("MANHATTAN"), |_| Distance::Manhattan),
-		map(tag_no_case("COSINE"), |_| Distance::Manhattan),
-		map(tag_no_case("HAMMING"), |_| Distance::Manhattan),
-		map(tag_no_case("MAHALANOBIS"), |_| Distance::Manhattan),
+		map(tag_no_case("COSINE"), |_| Distance::Cosine),
+		map(tag_no_case("HAMMING"), |_| Distance::Hamming),
+		map(tag_no_case("MAHALANOBIS"), |_| Distance::Mahalanobis),
 		minkowski,
 	))(i)
 }
@@ -234,3 +346,56 @@ pub fn mtree(i: &str) -> IResult<&str, Index> {
 		))
 	})(i)
 }
+
+pub fn m(i: &str) -> IResult<&str, u16> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("M")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, m) = uint16(i)?;
+	Ok((i, m))
+}
+
+pub fn ef_construction(i: &str) -> IResult<&str, u16> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("EFC")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, efc) = uint16(i)?;
+	Ok((i, efc))
+}
+
+pub fn hnsw(i: &str) -> IResult<&str, Index> {
+	let (i, _) = tag_no_case("HNSW")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	cut(|i| {
+		let (i, dimension) = dimension(i)?;
+		let (i, distance) = opt(distance)(i)?;
+		let (i, m) = opt(m)(i)?;
+		let (i, ef_construction) = opt(ef_construction)(i)?;
+		let (i, doc_ids_order) = opt(doc_ids_order)(i)?;
+		Ok((
+			i,
+			Index::Hnsw(HnswParams {
+				dimension,
+				distance: distance.unwrap_or(Distance::Euclidean),
+				m: m.unwrap_or(12).max(2),
+				ef_construction: ef_construction.unwrap_or(150).max(1),
+				doc_ids_order: doc_ids_order.unwrap_or(100),
+			}),
+		))
+	})(i)
+}
+
+pub fn rtree(i: &str) -> IResult<&str, Index> {
+	let (i, _) = tag_no_case("RTREE")(i)?;
+	cut(|i| {
+		let (i, capacity) = opt(capacity)(i)?;
+		let (i, doc_ids_order) = opt(doc_ids_order)(i)?;
+		Ok((
+			i,
+			Index::RTree(RTreeParams {
+				capacity: capacity.unwrap_or(16).max(4),
+				doc_ids_order: doc_ids_order.unwrap_or(100),
+			}),
+		))
+	})(i)
+}
@@ -52,6 +52,15 @@ impl Kind {
 	fn is_any(&self) -> bool {
 		matches!(self, Kind::Any)
 	}
+	/// Check if this type can hold record links
+	pub(crate) fn is_record(&self) -> bool {
+		match self {
+			Kind::Record(_) => true,
+			Kind::Option(k) | Kind::Set(k, _) | Kind::Array(k, _) => k.is_record(),
+			Kind::Either(k) => k.iter().any(Kind::is_record),
+			_ => false,
+		}
+	}
 }
 
 impl From<&Kind> for Box<Kind> {
@@ -1,6 +1,6 @@
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::map;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
@@ -0,0 +1,415 @@
+//! Splits SurrealQL queries into tokens, for highlighting queries in editors.
+//!
+//! ```
+//! use surrealdb::sql::lexer::{lex, TokenKind};
+//!
+//! let sql = "SELECT name FROM person WHERE age > 18";
+//! let kinds: Vec<TokenKind> = lex(sql).map(|t| t.kind).collect();
+//! assert_eq!(kinds[0], TokenKind::Keyword);
+//! assert_eq!(kinds[1], TokenKind::Identifier);
+//! ```
+
+use crate::sql::comment::{block, dash, hash, slash};
+use crate::sql::duration::duration;
+use crate::sql::ident::{ident_raw, multi};
+use crate::sql::number::number;
+use crate::sql::operator::binary_symbol;
+use crate::sql::strand::strand_raw;
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::combinator::recognize;
+use std::ops::Range;
+
+/// The keywords of the language, in uppercase and in sorted order, which are
+/// highlighted, completed, and suggested in place of misspelled words.
+pub(crate) const KEYWORDS: &[&str] = &[
+	"AFTER",
+	"ALL",
+	"ALLINSIDE",
+	"ALTER",
+	"ANALYZE",
+	"ANALYZER",
+	"AND",
+	"ANY",
+	"ANYINSIDE",
+	"APPLY",
+	"AS",
+	"ASC",
+	"ASSERT",
+	"AT",
+	"BATCH",
+	"BEFORE",
+	"BEGIN",
+	"BREAK",
+	"BY",
+	"CANCEL",
+	"CASCADE",
+	"CHANGE",
+	"CHANGEFEED",
+	"CHECK",
+	"COLLATE",
+	"COMMENT",
+	"COMMIT",
+	"CONFIG",
+	"CONFLICT",
+	"CONTAINS",
+	"CONTAINSALL",
+	"CONTAINSANY",
+	"CONTAINSNONE",
+	"CONTAINSNOT",
+	"CONTENT",
+	"CONTINUE",
+	"CREATE",
+	"DATABASE",
+	"DB",
+	"DEFAULT",
+	"DEFINE",
+	"DELETE",
+	"DESC",
+	"DIFF",
+	"DISTINCT",
+	"DROP",
+	"ELSE",
+	"END",
+	"ENUMERATED",
+	"EVENT",
+	"EXISTS",
+	"EXPLAIN",
+	"FALSE",
+	"FETCH",
+	"FIELD",
+	"FIELDS",
+	"FILTERS",
+	"FIX",
+	"FLEXIBLE",
+	"FOR",
+	"FROM",
+	"FULL",
+	"FUNCTION",
+	"GROUP",
+	"IF",
+	"IGNORE",
+	"IN",
+	"INDEX",
+	"INDEXES",
+	"INFO",
+	"INSERT",
+	"INSIDE",
+	"INTERSECTS",
+	"INTO",
+	"IS",
+	"KILL",
+	"LET",
+	"LIMIT",
+	"LIVE",
+	"MERGE",
+	"MIGRATION",
+	"MIGRATIONS",
+	"NAMESPACE",
+	"NOINDEX",
+	"NONE",
+	"NONEINSIDE",
+	"NOT",
+	"NOTINSIDE",
+	"NS",
+	"NULL",
+	"NUMERIC",
+	"OMIT",
+	"ON",
+	"ONLY",
+	"OPERATIONS",
+	"OPTION",
+	"OR",
+	"ORDER",
+	"OUTSIDE",
+	"PARALLEL",
+	"PARAM",
+	"PASSHASH",
+	"PASSWORD",
+	"PATCH",
+	"PERMISSIONS",
+	"RAND",
+	"READ",
+	"READONLY",
+	"REFERENCES",
+	"RELATE",
+	"RELATIONS",
+	"RELEASE",
+	"REMOVE",
+	"RESTRICT",
+	"RETURN",
+	"ROLES",
+	"ROLLBACK",
+	"RUN",
+	"SAVEPOINT",
+	"SCHEMAFULL",
+	"SCHEMALESS",
+	"SCOPE",
+	"SEARCH",
+	"SELECT",
+	"SEQUENCE",
+	"SESSION",
+	"SET",
+	"SHOW",
+	"SIGNIN",
+	"SIGNUP",
+	"SINCE",
+	"SLEEP",
+	"SPLIT",
+	"START",
+	"SUGGEST",
+	"TABLE",
+	"THEN",
+	"THROW",
+	"TIMEOUT",
+	"TO",
+	"TOKEN",
+	"TOKENIZERS",
+	"TRANSACTION",
+	"TRUE",
+	"TTL",
+	"TYPE",
+	"UNIQUE",
+	"UNSET",
+	"UPDATE",
+	"UPSERT",
+	"USE",
+	"USER",
+	"VALUE",
+	"VALUES",
+	"VERSION",
+	"WHEN",
+	"WHERE",
+	"WITH",
+];
+
+/// The characters which separate the parts of a query.
+const PUNCTUATION: &[char] = &['(', ')', '{', '}', '[', ']', ',', ';', ':', '.', '|'];
+
+/// The kind of a token.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum TokenKind {
+	/// A keyword, such as `SELECT` or `WHERE`
+	Keyword,
+	/// An identifier, such as a table, a field, or a function name
+	Identifier,
+	/// A parameter, such as `$name`
+	Parameter,
+	/// A string, including any quotes
+	String,
+	/// A number or a duration
+	Number,
+	/// An operator, such as `>=` or `->`
+	Operator,
+	/// Brackets, commas, semicolons, and other separators
+	Punctuation,
+	/// A comment, including the comment markers
+	Comment,
+	/// Text which can not be parsed, such as an unterminated string
+	Invalid,
+}
+
+/// A token, along with its source span.
+///
+/// Spans are byte ranges of the source text, with the end being exclusive.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Token {
+	pub kind: TokenKind,
+	pub span: Range<usize>,
+}
+
+/// Returns an iterator over the tokens of a query, skipping any whitespace.
+///
+/// Strings, identifiers, numbers, and comments are recognised using the same rules as
+/// the parser, and the lexer never fails, with anything which can not be parsed being
+/// returned as an [`TokenKind::Invalid`] token. An unterminated string or comment
+/// continues until the end of the input.
+pub fn lex(input: &str) -> Lexer<'_> {
+	Lexer {
+		input,
+		offset: 0,
+	}
+}
+
+/// An iterator over the tokens of a query.
+#[derive(Clone, Debug)]
+pub struct Lexer<'a> {
+	input: &'a str,
+	offset: usize,
+}
+
+impl Iterator for Lexer<'_> {
+	type Item = Token;
+	fn next(&mut self) -> Option<Token> {
+		let rest = &self.input[self.offset..];
+		let i = rest.trim_start();
+		let start = self.offset + rest.len() - i.len();
+		if i.is_empty() {
+			self.offset = self.input.len();
+			return None;
+		}
+		let (kind, len) = self.token(start, i);
+		self.offset = start + len;
+		Some(Token {
+			kind,
+			span: start..start + len,
+		})
+	}
+}
+
+impl Lexer<'_> {
+	/// Returns the kind and the length of the token at the start of the input.
+	fn token(&self, start: usize, i: &str) -> (TokenKind, usize) {
+		let len = |rest: &str| i.len() - rest.len();
+		// Comments
+		if let Ok((rest, _)) = alt((block, slash, dash, hash))(i) {
+			return (TokenKind::Comment, i[..len(rest)].trim_end().len());
+		}
+		// Strings, escaped identifiers, parameters, and numbers
+		match i.chars().next() {
+			Some('\'' | '"') => {
+				return match strand_raw(i) {
+					Ok((rest, _)) => (TokenKind::String, len(rest)),
+					Err(_) => (TokenKind::Invalid, i.len()),
+				};
+			}
+			Some('`' | '⟨') => {
+				return match ident_raw(i) {
+					Ok((rest, _)) => (TokenKind::Identifier, len(rest)),
+					Err(_) => (TokenKind::Invalid, i.len()),
+				};
+			}
+			Some('$') => {
+				return match ident_raw(&i[1..]) {
+					Ok((rest, _)) => (TokenKind::Parameter, len(rest)),
+					Err(_) => (TokenKind::Invalid, 1),
+				};
+			}
+			Some('/') if i.starts_with("/*") => return (TokenKind::Invalid, i.len()),
+			Some(c) if c.is_ascii_digit() => {
+				if let Ok((rest, _)) = alt((recognize(duration), recognize(number)))(i) {
+					return (TokenKind::Number, len(rest));
+				}
+			}
+			_ => {}
+		}
+		// Words, and paths such as function names
+		if let Ok((rest, word)) = multi(i) {
+			// Keywords are never part of a path, a record id, or an object key
+			let prefixed = self.input[..start].ends_with([':', '.']);
+			let kind = match word.0.as_str() {
+				"NaN" => TokenKind::Number,
+				w if w.bytes().all(|b| b.is_ascii_digit()) => TokenKind::Number,
+				w if !prefixed && !rest.starts_with(':') && is_keyword(w) => TokenKind::Keyword,
+				_ => TokenKind::Identifier,
+			};
+			return (kind, len(rest));
+		}
+		// Operators, including the graph and range operators
+		if let Ok((rest, _)) = alt((
+			tag("<->"),
+			tag("->"),
+			tag("<-"),
+			tag(".."),
+			tag("+?="),
+			tag("+="),
+			tag("-="),
+			recognize(binary_symbol),
+			tag("!"),
+		))(i)
+		{
+			return (TokenKind::Operator, len(rest));
+		}
+		// Separators, and anything else
+		match i.chars().next() {
+			Some(c) if PUNCTUATION.contains(&c) => (TokenKind::Punctuation, 1),
+			Some(c) => (TokenKind::Invalid, c.len_utf8()),
+			None => (TokenKind::Invalid, 0),
+		}
+	}
+}
+
+/// Checks if a word is a keyword, in any case.
+fn is_keyword(word: &str) -> bool {
+	KEYWORDS.binary_search(&word.to_uppercase().as_str()).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	fn tokens(sql: &str) -> Vec<(TokenKind, &str)> {
+		lex(sql).map(|t| (t.kind, &sql[t.span])).collect()
+	}
+
+	#[test]
+	fn keywords_sorted() {
+		assert!(KEYWORDS.windows(2).all(|w| w[0] < w[1]));
+	}
+
+	#[test]
+	fn lex_statement() {
+		let sql = "select name, string::len(name) FROM person:tobie WHERE age >= 18.5;";
+		assert_eq!(
+			tokens(sql),
+			vec![
+				(TokenKind::Keyword, "select"),
+				(TokenKind::Identifier, "name"),
+				(TokenKind::Punctuation, ","),
+				(TokenKind::Identifier, "string::len"),
+				(TokenKind::Punctuation, "("),
+				(TokenKind::Identifier, "name"),
+				(TokenKind::Punctuation, ")"),
+				(TokenKind::Keyword, "FROM"),
+				(TokenKind::Identifier, "person"),
+				(TokenKind::Punctuation, ":"),
+				(TokenKind::Identifier, "tobie"),
+				(TokenKind::Keyword, "WHERE"),
+				(TokenKind::Identifier, "age"),
+				(TokenKind::Operator, ">="),
+				(TokenKind::Number, "18.5"),
+				(TokenKind::Punctuation, ";"),
+			]
+		);
+	}
+
+	#[test]
+	fn lex_strings_and_comments() {
+		let sql = "-- people\nUPDATE $id SET `first name` = 'it\\'s', value = 1h30m /* time */";
+		assert_eq!(
+			tokens(sql),
+			vec![
+				(TokenKind::Comment, "-- people"),
+				(TokenKind::Keyword, "UPDATE"),
+				(TokenKind::Parameter, "$id"),
+				(TokenKind::Keyword, "SET"),
+				(TokenKind::Identifier, "`first name`"),
+				(TokenKind::Operator, "="),
+				(TokenKind::String, "'it\\'s'"),
+				(TokenKind::Punctuation, ","),
+				(TokenKind::Keyword, "value"),
+				(TokenKind::Operator, "="),
+				(TokenKind::Number, "1h30m"),
+				(TokenKind::Comment, "/* time */"),
+			]
+		);
+	}
+
+	#[test]
+	fn lex_keywords_in_context() {
+		let sql = "{ select: person.value, id: type:1..5 }";
+		let kinds: Vec<TokenKind> = lex(sql).map(|t| t.kind).collect();
+		assert!(!kinds.contains(&TokenKind::Keyword));
+		assert_eq!(tokens("person->knows")[1], (TokenKind::Operator, "->"));
+	}
+
+	#[test]
+	fn lex_invalid() {
+		let sql = "SELECT 'unterminated FROM person";
+		assert_eq!(tokens(sql)[1], (TokenKind::Invalid, "'unterminated FROM person"));
+		assert_eq!(tokens("$ @"), vec![(TokenKind::Invalid, "$"), (TokenKind::Invalid, "@")]);
+		assert!(tokens(" \n\t").is_empty());
+	}
+}
@@ -0,0 +1,428 @@
+//! Checks parsed queries against configurable rules, for parts of a query which are valid, but
+//! which are probably slow or hard to maintain, such as statements which scan a whole table.
+
+use crate::sql::cond::Cond;
+use crate::sql::error::LintWarning;
+use crate::sql::expression::Expression;
+use crate::sql::lexer::{lex, TokenKind};
+use crate::sql::operator::Operator;
+use crate::sql::spanned::{SpannedQuery, SpannedValue};
+use crate::sql::statement::Statement;
+use crate::sql::statements::DefineStatement;
+use crate::sql::subquery::Subquery;
+use crate::sql::value::{Value, Values};
+use std::ops::Range;
+
+/// The rules which a query is checked against when it is linted.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct LintRules {
+	/// Whether to warn about statements which read every record of a table, as no index
+	/// matches their condition
+	pub full_table_scan: bool,
+	/// Whether to warn about `SELECT *` projections in the definitions of views
+	pub select_all_in_views: bool,
+	/// Whether to warn about literal strings, numbers, and datetimes which are compared with
+	/// fields, rather than being passed as parameters
+	pub literals: bool,
+	/// The maximum depth of nested subqueries and blocks, if limited
+	pub max_depth: Option<usize>,
+	/// The fields which are indexed in the database, as pairs of a table and a field, other
+	/// than those indexed by the query itself. Only the first field of an index on several
+	/// fields is given
+	pub indexes: Vec<(String, String)>,
+}
+
+impl Default for LintRules {
+	fn default() -> Self {
+		Self {
+			full_table_scan: true,
+			select_all_in_views: true,
+			literals: true,
+			max_depth: Some(3),
+			indexes: Vec::new(),
+		}
+	}
+}
+
+impl LintRules {
+	/// Set whether to warn about statements which scan a whole table
+	pub fn with_full_table_scan(mut self, enabled: bool) -> Self {
+		self.full_table_scan = enabled;
+		self
+	}
+	/// Set whether to warn about `SELECT *` projections in views
+	pub fn with_select_all_in_views(mut self, enabled: bool) -> Self {
+		self.select_all_in_views = enabled;
+		self
+	}
+	/// Set whether to warn about literal values in conditions
+	pub fn with_literals(mut self, enabled: bool) -> Self {
+		self.literals = enabled;
+		self
+	}
+	/// Set the maximum depth of nested subqueries and blocks
+	pub fn with_max_depth(mut self, depth: Option<usize>) -> Self {
+		self.max_depth = depth;
+		self
+	}
+	/// Add a field which is indexed in the database
+	pub fn with_index(mut self, table: &str, field: &str) -> Self {
+		self.indexes.push((table.to_owned(), field.to_owned()));
+		self
+	}
+}
+
+/// Returns the lint warnings found in a parsed query, in the order they appear in the input.
+pub(crate) fn check<'a>(
+	input: &'a str,
+	query: &SpannedQuery,
+	rules: &LintRules,
+) -> Vec<LintWarning<&'a str>> {
+	let mut lints = Lints {
+		input,
+		rules,
+		indexes: rules.indexes.clone(),
+		literals: rules.literals,
+		warnings: Vec::new(),
+	};
+	// The fields indexed by the query can be used by any statement in it, although only
+	// the first field of an index on several fields can be used on its own
+	for stm in query.statements.iter() {
+		if let Statement::Define(DefineStatement::Index(ix)) = &stm.statement {
+			if let Some(c) = ix.cols.first() {
+				lints.indexes.push((ix.what.to_raw(), c.to_string()));
+			}
+		}
+	}
+	for stm in query.statements.iter() {
+		let tried = &input[stm.span.start..];
+		match &stm.statement {
+			Statement::Select(v) => lints.check_scan(tried, &v.what, v.cond.as_ref()),
+			Statement::Update(v) => lints.check_scan(tried, &v.what, v.cond.as_ref()),
+			Statement::Delete(v) => lints.check_scan(tried, &v.what, v.cond.as_ref()),
+			Statement::Define(DefineStatement::Table(v)) if rules.select_all_in_views => {
+				if v.view.as_ref().is_some_and(|v| v.expr.is_all()) {
+					lints.warnings.push(LintWarning::SelectAllInView {
+						tried: &input[projection_offset(input, stm.span.clone())..],
+						table: v.name.to_raw(),
+					});
+				}
+			}
+			_ => {}
+		}
+		// The conditions within definitions are part of the schema, rather than of a query
+		lints.literals = rules.literals && !matches!(stm.statement, Statement::Define(_));
+		for v in stm.expressions.iter() {
+			lints.check_value(v, false, 0);
+		}
+	}
+	let mut warnings = lints.warnings;
+	warnings.sort_by_key(|w| std::cmp::Reverse(w.tried().len()));
+	warnings
+}
+
+/// The state of the checks of a query, along with the warnings found so far.
+struct Lints<'a, 'r> {
+	input: &'a str,
+	rules: &'r LintRules,
+	/// The indexed fields, as pairs of a table and a field
+	indexes: Vec<(String, String)>,
+	/// Whether to check the literals within the current statement
+	literals: bool,
+	warnings: Vec<LintWarning<&'a str>>,
+}
+
+impl<'a> Lints<'a, '_> {
+	/// Checks an expression within a statement, along with any subqueries within it, which is
+	/// nested within `depth` subqueries and blocks.
+	///
+	/// The literals in an expression are only checked from its outermost node, as the nodes
+	/// within an expression do not always match the parts of the parsed expression.
+	fn check_value(&mut self, v: &SpannedValue, expression: bool, depth: usize) {
+		let input = self.input;
+		let tried = &input[v.span.start..];
+		let (expression, depth) = match &v.value {
+			// A value in brackets is part of the expression around it
+			Value::Subquery(s) if matches!(s.as_ref(), Subquery::Value(_)) => (expression, depth),
+			Value::Subquery(s) => {
+				match s.as_ref() {
+					Subquery::Select(s) => self.check_scan(tried, &s.what, s.cond.as_ref()),
+					Subquery::Update(s) => self.check_scan(tried, &s.what, s.cond.as_ref()),
+					Subquery::Delete(s) => self.check_scan(tried, &s.what, s.cond.as_ref()),
+					_ => {}
+				}
+				self.check_depth(tried, depth + 1);
+				(false, depth + 1)
+			}
+			Value::Block(_) => {
+				self.check_depth(tried, depth + 1);
+				(false, depth + 1)
+			}
+			Value::Expression(_) if !expression => {
+				if self.literals {
+					self.check_literals(v);
+				}
+				(true, depth)
+			}
+			_ => (expression, depth),
+		};
+		for v in v.children.iter() {
+			self.check_value(v, expression, depth);
+		}
+	}
+
+	/// Checks whether a subquery or block is nested too deeply, reporting only the outermost
+	/// one which is.
+	fn check_depth(&mut self, tried: &'a str, depth: usize) {
+		if let Some(max) = self.rules.max_depth.filter(|max| depth == max + 1) {
+			self.warnings.push(LintWarning::Nesting {
+				tried,
+				max,
+			});
+		}
+	}
+
+	/// Checks whether a statement reads every record of the tables it reads from.
+	fn check_scan(&mut self, tried: &'a str, what: &Values, cond: Option<&Cond>) {
+		if !self.rules.full_table_scan {
+			return;
+		}
+		for v in what.iter() {
+			if let Value::Table(table) = v {
+				let fields: Vec<&str> = self
+					.indexes
+					.iter()
+					.filter(|(t, _)| t == table.as_str())
+					.map(|(_, f)| f.as_str())
+					.collect();
+				if !cond.is_some_and(|c| is_indexed(&c.0, &fields)) {
+					self.warnings.push(LintWarning::FullTableScan {
+						tried,
+						table: table.0.clone(),
+					});
+				}
+			}
+		}
+	}
+
+	/// Checks for fields which are compared with literal values within an expression.
+	fn check_literals(&mut self, v: &SpannedValue) {
+		let mut found = Vec::new();
+		compared_literals(&v.value, &mut found);
+		// The literals are found in source order, so each is the next literal in the source
+		// which has the same value
+		let mut spans = Vec::new();
+		literal_spans(v, &mut spans);
+		let mut spans = spans.into_iter();
+		for value in found {
+			if let Some(s) = spans.find(|s| &s.value == value) {
+				let input = self.input;
+				self.warnings.push(LintWarning::Literal {
+					tried: &input[s.span.start..],
+					value: value.to_string(),
+				});
+			}
+		}
+	}
+}
+
+/// Finds the literal values which are compared with fields in an expression, in source order.
+fn compared_literals<'v>(v: &'v Value, out: &mut Vec<&'v Value>) {
+	match v {
+		Value::Expression(e) => match e.as_ref() {
+			Expression::Binary {
+				l,
+				o,
+				r,
+			} => {
+				compared_literals(l, out);
+				if is_comparison(o) {
+					match (l, r) {
+						(Value::Idiom(_), v) | (v, Value::Idiom(_)) if is_literal(v) => out.push(v),
+						_ => {}
+					}
+				}
+				compared_literals(r, out);
+			}
+			Expression::Unary {
+				v,
+				..
+			} => compared_literals(v, out),
+		},
+		Value::Subquery(s) => {
+			if let Subquery::Value(v) = s.as_ref() {
+				compared_literals(v, out)
+			}
+		}
+		_ => {}
+	}
+}
+
+/// Finds the literal values written within an expression, in source order, other than those
+/// within subqueries and blocks.
+fn literal_spans<'s>(v: &'s SpannedValue, out: &mut Vec<&'s SpannedValue>) {
+	for c in v.children.iter() {
+		match &c.value {
+			Value::Subquery(s) if !matches!(s.as_ref(), Subquery::Value(_)) => {}
+			Value::Block(_) => {}
+			v if is_literal(v) => out.push(c),
+			_ => literal_spans(c, out),
+		}
+	}
+}
+
+/// Checks whether a condition can use one of the indexed fields.
+///
+/// This follows the query planner, which can use an index for either side of an AND, but
+/// only for both sides of an OR, and only for comparisons of a field with a value.
+fn is_indexed(v: &Value, fields: &[&str]) -> bool {
+	match v {
+		Value::Expression(e) => match e.as_ref() {
+			Expression::Binary {
+				l,
+				o: Operator::And,
+				r,
+			} => is_indexed(l, fields) || is_indexed(r, fields),
+			Expression::Binary {
+				l,
+				o: Operator::Or,
+				r,
+			} => is_indexed(l, fields) && is_indexed(r, fields),
+			Expression::Binary {
+				l,
+				o,
+				r,
+			} if is_comparison(o) => [l, r].iter().any(|v| match v {
+				Value::Idiom(i) => fields.contains(&i.to_string().as_str()),
+				_ => false,
+			}),
+			_ => false,
+		},
+		Value::Subquery(s) => match s.as_ref() {
+			Subquery::Value(v) => is_indexed(v, fields),
+			_ => false,
+		},
+		_ => false,
+	}
+}
+
+/// Checks whether an operator compares two values, in a way which can use an index.
+fn is_comparison(o: &Operator) -> bool {
+	matches!(
+		o,
+		Operator::Equal
+			| Operator::Exact
+			| Operator::LessThan
+			| Operator::LessThanOrEqual
+			| Operator::MoreThan
+			| Operator::MoreThanOrEqual
+			| Operator::Contain
+			| Operator::ContainAll
+			| Operator::ContainAny
+			| Operator::Inside
+			| Operator::Intersects
+			| Operator::Matches(_)
+			| Operator::FuzzyMatches(..)
+			| Operator::Knn(_)
+	)
+}
+
+/// Checks whether a value is a literal which could be passed as a parameter.
+fn is_literal(v: &Value) -> bool {
+	matches!(v, Value::Strand(_) | Value::Number(_) | Value::Datetime(_))
+}
+
+/// Returns the offset of the `*` projection in the definition of a view, or of the definition
+/// itself if it is not found.
+fn projection_offset(input: &str, span: Range<usize>) -> usize {
+	let source = &input[span.clone()];
+	let mut select = false;
+	for t in lex(source) {
+		let text = &source[t.span.clone()];
+		match t.kind {
+			TokenKind::Keyword if text.eq_ignore_ascii_case("SELECT") => select = true,
+			_ if select && text == "*" => return span.start + t.span.start,
+			_ => {}
+		}
+	}
+	span.start
+}
+
+#[cfg(test)]
+mod tests {
+
+	use crate::sql::{lint, LintRules};
+
+	fn codes(sql: &str, rules: &LintRules) -> Vec<&'static str> {
+		lint(sql, rules).unwrap().1.iter().map(|e| e.diagnostic.code).collect()
+	}
+
+	#[test]
+	fn lint_full_table_scan() {
+		let rules = LintRules::default().with_literals(false);
+		let sql = "SELECT * FROM person WHERE email = $email";
+		assert_eq!(codes(sql, &rules), vec!["full_table_scan"]);
+		assert!(codes(sql, &rules.clone().with_index("person", "email")).is_empty());
+		// Indexes defined in the query are known
+		let sql = "DEFINE INDEX email ON person FIELDS email; DELETE person WHERE email = $email";
+		assert!(codes(sql, &rules).is_empty());
+		// Only the first field of an index on several fields is used on its own
+		let sql = "DEFINE INDEX name ON person FIELDS last, first; \
+			SELECT * FROM person WHERE last = $last";
+		assert!(codes(sql, &rules).is_empty());
+		let sql = "DEFINE INDEX name ON person FIELDS last, first; \
+			SELECT * FROM person WHERE first = $first";
+		assert_eq!(codes(sql, &rules), vec!["full_table_scan"]);
+		// An index can only be used for both sides of an OR
+		let sql = "DEFINE INDEX email ON person FIELDS email; \
+			UPDATE person SET seen = true WHERE email = $email OR name = $name";
+		assert_eq!(codes(sql, &rules), vec!["full_table_scan"]);
+		// Records are read directly
+		assert!(codes("SELECT * FROM person:tobie", &rules).is_empty());
+		let sql = "RETURN (SELECT * FROM person)";
+		let out = lint(sql, &rules).unwrap().1;
+		assert_eq!(out.len(), 1);
+		assert_eq!(out[0].diagnostic.span.start, 7);
+	}
+
+	#[test]
+	fn lint_select_all_in_views() {
+		let rules = LintRules::default().with_full_table_scan(false);
+		let sql = "DEFINE TABLE adults AS SELECT * FROM person WHERE age >= 18";
+		let out = lint(sql, &rules).unwrap().1;
+		assert_eq!(out.len(), 1);
+		assert_eq!(out[0].diagnostic.code, "select_all_in_view");
+		assert_eq!(out[0].diagnostic.span.start, sql.find('*').unwrap());
+		let sql = "DEFINE TABLE adults AS SELECT name, age FROM person WHERE age >= 18";
+		assert!(codes(sql, &rules).is_empty());
+	}
+
+	#[test]
+	fn lint_literals() {
+		let rules = LintRules::default().with_full_table_scan(false);
+		let sql = "SELECT * FROM person WHERE age > 18 AND name = $name AND 'admin' IN roles";
+		let out = lint(sql, &rules).unwrap().1;
+		assert_eq!(out.len(), 2);
+		assert_eq!(out[0].diagnostic.code, "unparameterized_literal");
+		assert_eq!(out[0].diagnostic.span.start, sql.find("18").unwrap());
+		assert_eq!(out[1].diagnostic.span.start, sql.find("'admin'").unwrap());
+		assert!(codes(sql, &rules.with_literals(false)).is_empty());
+	}
+
+	#[test]
+	fn lint_nesting() {
+		let rules = LintRules::default().with_full_table_scan(false).with_max_depth(Some(1));
+		let sql = "SELECT * FROM (SELECT * FROM (SELECT * FROM (SELECT * FROM person)))";
+		let out = lint(sql, &rules).unwrap().1;
+		assert_eq!(out.len(), 1);
+		assert_eq!(out[0].diagnostic.code, "deep_nesting");
+		assert_eq!(out[0].diagnostic.span.start, 29);
+		assert!(codes(sql, &rules.with_max_depth(None)).is_empty());
+	}
+
+	#[test]
+	fn lint_syntax_errors() {
+		assert!(lint("SELECT * FROM;", &LintRules::default()).is_err());
+	}
+}
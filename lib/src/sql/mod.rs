@@ -1,6 +1,7 @@
 //! The full type definitions for the SurrealQL query language
 
 pub(crate) mod algorithm;
+pub(crate) mod analysis;
 pub(crate) mod array;
 pub(crate) mod base;
 pub(crate) mod block;
@@ -10,6 +11,7 @@ pub(crate) mod cast;
 pub(crate) mod changefeed;
 pub(crate) mod comment;
 pub(crate) mod common;
+pub(crate) mod completion;
 pub(crate) mod cond;
 pub(crate) mod constant;
 pub(crate) mod data;
@@ -38,6 +40,7 @@ pub(crate) mod index;
 pub(crate) mod kind;
 pub(crate) mod language;
 pub(crate) mod limit;
+pub(crate) mod lint;
 pub(crate) mod model;
 pub(crate) mod number;
 pub(crate) mod object;
@@ -53,16 +56,20 @@ pub(crate) mod paths;
 pub(crate) mod permission;
 pub(crate) mod query;
 pub(crate) mod range;
+pub(crate) mod reference;
 pub(crate) mod regex;
 pub(crate) mod scoring;
 pub(crate) mod script;
+pub(crate) mod spanned;
 pub(crate) mod special;
 pub(crate) mod split;
 pub(crate) mod start;
 pub(crate) mod statement;
+pub(crate) mod storage;
 pub(crate) mod strand;
 pub(crate) mod subquery;
 pub(crate) mod table;
+pub(crate) mod table_type;
 pub(crate) mod thing;
 pub(crate) mod timeout;
 pub(crate) mod tokenizer;
@@ -71,11 +78,14 @@ pub(crate) mod uuid;
 pub(crate) mod value;
 pub(crate) mod version;
 pub(crate) mod view;
+pub(crate) mod window;
 pub(crate) mod with;
 
 #[cfg(test)]
 pub(crate) mod test;
 
+pub mod format;
+pub mod lexer;
 pub mod serde;
 pub mod statements;
 
@@ -87,6 +97,7 @@ pub use self::base::Base;
 pub use self::block::Block;
 pub use self::bytes::Bytes;
 pub use self::cast::Cast;
+pub use self::completion::{Completions, IdentifierKind};
 pub use self::cond::Cond;
 pub use self::constant::Constant;
 pub use self::data::Data;
@@ -104,7 +115,7 @@ pub use self::field::Fields;
 pub use self::function::Function;
 pub use self::future::Future;
 pub use self::geometry::Geometry;
-pub use self::graph::Graph;
+pub use self::graph::{Depth, Graph};
 pub use self::group::Group;
 pub use self::group::Groups;
 pub use self::id::Id;
@@ -114,6 +125,7 @@ pub use self::idiom::Idioms;
 pub use self::index::Index;
 pub use self::kind::Kind;
 pub use self::limit::Limit;
+pub use self::lint::LintRules;
 pub use self::model::Model;
 pub use self::number::Number;
 pub use self::object::Object;
@@ -128,18 +140,22 @@ pub use self::permission::Permission;
 pub use self::permission::Permissions;
 pub use self::query::Query;
 pub use self::range::Range;
+pub use self::reference::Reference;
 pub use self::regex::Regex;
 pub use self::scoring::Scoring;
 pub use self::script::Script;
+pub use self::spanned::{SpannedQuery, SpannedStatement, SpannedValue};
 pub use self::split::Split;
 pub use self::split::Splits;
 pub use self::start::Start;
 pub use self::statement::Statement;
 pub use self::statement::Statements;
+pub use self::storage::Storage;
 pub use self::strand::Strand;
 pub use self::subquery::Subquery;
 pub use self::table::Table;
 pub use self::table::Tables;
+pub use self::table_type::{Relation, TableType};
 pub use self::thing::Thing;
 pub use self::timeout::Timeout;
 pub use self::uuid::Uuid;
@@ -147,6 +163,7 @@ pub use self::value::Value;
 pub use self::value::Values;
 pub use self::version::Version;
 pub use self::view::View;
+pub use self::window::Window;
 pub use self::with::With;
 
 pub use self::value::serde::to_value;
@@ -7,7 +7,7 @@ use crate::sql::common::{closebraces, openbraces};
 use crate::sql::common::{commas, val_char};
 use crate::sql::error::{expected, IResult};
 use crate::sql::escape::escape_key;
-use crate::sql::fmt::{is_pretty, pretty_indent, Fmt, Pretty};
+use crate::sql::fmt::{is_pretty, pretty_indent, pretty_single_line, Fmt, Pretty};
 use crate::sql::operation::Operation;
 use crate::sql::thing::Thing;
 use crate::sql::util::expect_terminator;
@@ -239,6 +239,9 @@ impl Object {
 impl Display for Object {
 	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
 		let mut f = Pretty::from(f);
+		if let Some(v) = pretty_single_line(self) {
+			return f.write_str(&v);
+		}
 		if is_pretty() {
 			f.write_char('{')?;
 		} else {
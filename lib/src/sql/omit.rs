@@ -1,7 +1,7 @@
 use crate::sql::comment::shouldbespace;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::idiom::{locals as idioms, Idioms};
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 
 pub fn omit(i: &str) -> IResult<&str, Idioms> {
@@ -1,15 +1,18 @@
 use crate::idx::ft::MatchRef;
 use crate::sql::comment::mightbespace;
 use crate::sql::comment::shouldbespace;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use nom::branch::alt;
 use nom::bytes::complete::tag;
-use nom::bytes::complete::tag_no_case;
 use nom::character::complete::char;
+use nom::character::complete::u32 as uint32;
 use nom::character::complete::u8 as uint8;
 use nom::combinator::cut;
 use nom::combinator::opt;
+use nom::combinator::peek;
 use nom::combinator::value;
+use nom::sequence::preceded;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -67,6 +70,10 @@ pub enum Operator {
 	//
 	Outside,
 	Intersects,
+	//
+	Knn(u32), // <|k|>
+	//
+	FuzzyMatches(Option<MatchRef>, u8), // @{ref}~{distance}@
 }
 
 impl Default for Operator {
@@ -141,6 +148,14 @@ impl fmt::Display for Operator {
 					f.write_str("@@")
 				}
 			}
+			Self::Knn(k) => write!(f, "<|{}|>", k),
+			Self::FuzzyMatches(reference, distance) => {
+				if let Some(r) = reference {
+					write!(f, "@{r}~{distance}@")
+				} else {
+					write!(f, "@~{distance}@")
+				}
+			}
 		}
 	}
 }
@@ -155,7 +170,7 @@ pub fn assigner(i: &str) -> IResult<&str, Operator> {
 }
 
 pub fn unary(i: &str) -> IResult<&str, Operator> {
-	unary_symbols(i)
+	alt((unary_symbols, unary_phrases))(i)
 }
 
 pub fn unary_symbols(i: &str) -> IResult<&str, Operator> {
@@ -165,13 +180,30 @@ pub fn unary_symbols(i: &str) -> IResult<&str, Operator> {
 	Ok((i, v))
 }
 
+/// The NOT keyword only negates graph traversals, such as `NOT ->blocked->user:tobie`,
+/// so that fields named `not` can still be selected
+pub fn unary_phrases(i: &str) -> IResult<&str, Operator> {
+	let (i, _) = mightbespace(i)?;
+	let (i, v) = value(Operator::Not, tag_no_case("NOT"))(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = peek(alt((tag("->"), tag("<-"))))(i)?;
+	Ok((i, v))
+}
+
 pub fn binary(i: &str) -> IResult<&str, Operator> {
 	alt((binary_symbols, binary_phrases))(i)
 }
 
 pub fn binary_symbols(i: &str) -> IResult<&str, Operator> {
 	let (i, _) = mightbespace(i)?;
-	let (i, v) = alt((
+	let (i, v) = binary_symbol(i)?;
+	let (i, _) = mightbespace(i)?;
+	Ok((i, v))
+}
+
+/// A binary operator symbol, without any surrounding whitespace
+pub(crate) fn binary_symbol(i: &str) -> IResult<&str, Operator> {
+	alt((
 		alt((
 			value(Operator::Or, tag("||")),
 			value(Operator::And, tag("&&")),
@@ -193,6 +225,7 @@ pub fn binary_symbols(i: &str) -> IResult<&str, Operator> {
 			matches,
 		)),
 		alt((
+			knn,
 			value(Operator::LessThanOrEqual, tag("<=")),
 			value(Operator::LessThan, char('<')),
 			value(Operator::MoreThanOrEqual, tag(">=")),
@@ -220,9 +253,7 @@ pub fn binary_symbols(i: &str) -> IResult<&str, Operator> {
 			value(Operator::AnyInside, char('⊂')),
 			value(Operator::NoneInside, char('⊄')),
 		)),
-	))(i)?;
-	let (i, _) = mightbespace(i)?;
-	Ok((i, v))
+	))(i)
 }
 
 pub fn binary_phrases(i: &str) -> IResult<&str, Operator> {
@@ -260,8 +291,21 @@ pub fn matches(i: &str) -> IResult<&str, Operator> {
 	// let (i, reference) = opt(|i| uint8(i))(i)?;
 	cut(|i| {
 		let (i, reference) = opt(uint8)(i)?;
+		let (i, distance) = opt(preceded(char('~'), uint8))(i)?;
 		let (i, _) = char('@')(i)?;
-		Ok((i, Operator::Matches(reference)))
+		match distance {
+			Some(distance) => Ok((i, Operator::FuzzyMatches(reference, distance))),
+			None => Ok((i, Operator::Matches(reference))),
+		}
+	})(i)
+}
+
+pub fn knn(i: &str) -> IResult<&str, Operator> {
+	let (i, _) = tag("<|")(i)?;
+	cut(|i| {
+		let (i, k) = uint32(i)?;
+		let (i, _) = tag("|>")(i)?;
+		Ok((i, Operator::Knn(k)))
 	})(i)
 }
 
@@ -269,6 +313,16 @@ pub fn matches(i: &str) -> IResult<&str, Operator> {
 mod tests {
 	use super::*;
 
+	#[test]
+	fn unary_not_keyword() {
+		let res = unary("NOT ->likes");
+		let (i, out) = res.unwrap();
+		assert_eq!("->likes", i);
+		assert_eq!(out, Operator::Not);
+		let res = unary("NOT name");
+		res.unwrap_err();
+	}
+
 	#[test]
 	fn matches_without_reference() {
 		let res = matches("@@");
@@ -290,4 +344,30 @@ mod tests {
 		let res = matches("@256@");
 		res.unwrap_err();
 	}
+
+	#[test]
+	fn matches_with_distance() {
+		let res = matches("@1~2@");
+		let out = res.unwrap().1;
+		assert_eq!("@1~2@", format!("{}", out));
+		assert_eq!(out, Operator::FuzzyMatches(Some(1u8), 2));
+		let res = matches("@~1@");
+		let out = res.unwrap().1;
+		assert_eq!("@~1@", format!("{}", out));
+		assert_eq!(out, Operator::FuzzyMatches(None, 1));
+	}
+
+	#[test]
+	fn knn_with_k() {
+		let res = knn("<|5|>");
+		let out = res.unwrap().1;
+		assert_eq!("<|5|>", format!("{}", out));
+		assert_eq!(out, Operator::Knn(5));
+	}
+
+	#[test]
+	fn knn_without_k() {
+		let res = knn("<||>");
+		res.unwrap_err();
+	}
 }
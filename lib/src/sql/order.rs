@@ -1,10 +1,10 @@
 use crate::sql::comment::shouldbespace;
 use crate::sql::common::commas;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::fmt::Fmt;
 use crate::sql::idiom::{basic, Idiom};
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::{cut, opt, value};
 use nom::multi::separated_list1;
 use nom::sequence::tuple;
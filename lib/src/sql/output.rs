@@ -1,8 +1,8 @@
 use crate::sql::comment::shouldbespace;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::field::{fields, Fields};
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::{cut, map, value};
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
@@ -102,8 +102,8 @@ impl Param {
 									}
 								}
 							}
-							// Return the value
-							Ok(val.value.to_owned())
+							// Check the value against the TYPE and ASSERT clauses
+							val.check(ctx, opt, txn, val.value.to_owned()).await
 						}
 						// The param has not been set globally
 						Err(_) => Ok(Value::None),
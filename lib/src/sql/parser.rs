@@ -1,10 +1,21 @@
+use crate::cnf::MAX_COMPUTATION_DEPTH;
 use crate::err::Error;
-use crate::sql::error::IResult;
+use crate::sql::analysis;
+use crate::sql::comment::mightbespace;
+use crate::sql::common::colons;
+use crate::sql::completion::{self, Completions};
+use crate::sql::error::{Collecting, IResult, ParseError, RenderedError, Span};
 use crate::sql::idiom::Idiom;
+use crate::sql::kind::Kind;
+use crate::sql::lint::{self, LintRules};
 use crate::sql::query::{query, Query};
+use crate::sql::spanned::{self, SpannedQuery};
+use crate::sql::statement::{statement, Statement, Statements};
 use crate::sql::subquery::Subquery;
 use crate::sql::thing::Thing;
 use crate::sql::value::Value;
+use nom::multi::many0;
+use nom::sequence::terminated;
 use nom::Finish;
 use std::str;
 use tracing::instrument;
@@ -21,7 +32,326 @@ use tracing::instrument;
 /// please [open an issue](https://github.com/surrealdb/surrealdb/issues)!
 #[instrument(level = "debug", name = "parser", skip_all, fields(length = input.len()))]
 pub fn parse(input: &str) -> Result<Query, Error> {
-	parse_impl(input, query)
+	parse_impl(input, query).map_err(|e| in_statement(input, e))
+}
+
+/// The limits on the queries which are parsed, for hardening deployments against
+/// queries which are too large, or too deeply nested, to parse safely.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct ParseLimits {
+	/// The maximum depth of nested values, such as arrays, objects, and subqueries,
+	/// measured in the same way as the maximum computation depth
+	pub depth: u8,
+	/// The maximum length of a query in bytes, if limited
+	pub query_size: Option<usize>,
+	/// The maximum number of statements in a query, if limited
+	pub statements: Option<usize>,
+}
+
+impl Default for ParseLimits {
+	fn default() -> Self {
+		Self {
+			depth: *MAX_COMPUTATION_DEPTH,
+			query_size: None,
+			statements: None,
+		}
+	}
+}
+
+impl ParseLimits {
+	/// Set the maximum depth of nested values
+	pub fn with_depth(mut self, depth: u8) -> Self {
+		self.depth = depth;
+		self
+	}
+	/// Set the maximum length of a query in bytes
+	pub fn with_query_size(mut self, size: Option<usize>) -> Self {
+		self.query_size = size;
+		self
+	}
+	/// Set the maximum number of statements in a query
+	pub fn with_statements(mut self, statements: Option<usize>) -> Self {
+		self.statements = statements;
+		self
+	}
+	/// Parses a query with a parser, checking the query against these limits
+	pub(crate) fn apply(
+		&self,
+		input: &str,
+		parser: impl FnOnce(&str) -> Result<Query, Error>,
+	) -> Result<Query, Error> {
+		// Check the length of the query
+		if let Some(max) = self.query_size {
+			if input.len() > max {
+				return Err(Error::QueryTooLarge {
+					size: input.len(),
+					max,
+				});
+			}
+		}
+		// Parse the query within the depth limit
+		let query = {
+			let _limit = depth::limit(self.depth);
+			parser(input)?
+		};
+		// Check the number of statements
+		if let Some(max) = self.statements {
+			if query.len() > max {
+				return Err(Error::QueryTooManyStatements {
+					count: query.len(),
+					max,
+				});
+			}
+		}
+		Ok(query)
+	}
+}
+
+/// Parses a SurrealQL [`Query`], checking the query against the given limits.
+///
+/// This is used in place of [`parse`] when parsing queries from untrusted sources, such as
+/// the clients of a server, where the limits are configured by the server.
+#[instrument(level = "debug", name = "parser", skip_all, fields(length = input.len()))]
+pub fn parse_with_limits(input: &str, limits: &ParseLimits) -> Result<Query, Error> {
+	limits.apply(input, parse)
+}
+
+/// Parses a SurrealQL [`Query`], along with any warnings about the query.
+///
+/// Warnings are found for parts of the query which are deprecated, or which are valid but
+/// probably not what was meant, such as statements which never run. They do not stop the
+/// query from being parsed, and can be printed in the same way as parse errors.
+#[instrument(level = "debug", name = "parser", skip_all, fields(length = input.len()))]
+pub fn parse_with_warnings(input: &str) -> Result<(Query, Vec<RenderedError>), Error> {
+	let collecting = Collecting::start();
+	let query = parse_impl(input, query).map_err(|e| in_statement(input, e))?;
+	let warnings = collecting.take(input).iter().map(|w| w.render_on(input)).collect();
+	Ok((query, warnings))
+}
+
+/// Parses a SurrealQL [`Query`], reporting every syntax error in the query at once.
+///
+/// Unlike [`parse`], which stops at the first error, this recovers from an error by skipping
+/// to the end of the failed statement, at the next `;` outside of any string, comment or block,
+/// and carries on parsing from there. This is useful when validating large scripts, such as
+/// migration files, where each error would otherwise need to be fixed in turn.
+#[instrument(level = "debug", name = "parser", skip_all, fields(length = input.len()))]
+pub fn validate(input: &str) -> Result<Query, Vec<RenderedError>> {
+	let mut statements = Vec::new();
+	let mut errors = Vec::new();
+	let mut count = 0;
+	let mut rest = input;
+	loop {
+		// Reset the parse depth limiter
+		depth::reset();
+		// Skip any whitespace, comments, and stray semicolons
+		rest = match mightbespace(rest) {
+			Ok((i, _)) => i,
+			Err(_) => rest,
+		};
+		if let Ok((i, _)) = many0(colons)(rest) {
+			rest = i;
+		}
+		// Check if there is anything left to parse
+		if rest.is_empty() {
+			break;
+		}
+		// The index of the statement in the query
+		let index = count;
+		let start = input.len() - rest.len();
+		count += 1;
+		match statement(rest).finish() {
+			// The statement must be followed by a semicolon, or the end of the query
+			Ok((i, v)) => match colons(i) {
+				Ok((i, _)) => {
+					statements.push(v);
+					rest = i;
+				}
+				Err(_) if matches!(mightbespace(i), Ok(("", _))) => {
+					statements.push(v);
+					rest = "";
+				}
+				Err(_) => {
+					let tried = i.trim_start();
+					let e = ParseError::ExplainedExpected {
+						tried,
+						expected: "query to end",
+						explained: "perhaps missing a semicolon on the previous statement?",
+					};
+					// The error is in the statement following the parsed statement
+					count += 1;
+					rest = recover(tried, tried);
+					let span = statement_span(input, input.len() - tried.len(), rest);
+					errors.push(e.render_on(input).in_statement(index + 1, span));
+				}
+			},
+			// Skip to the start of the next statement
+			Err(e) => {
+				rest = recover(rest, e.tried());
+				let span = statement_span(input, start, rest);
+				errors.push(e.render_on(input).in_statement(index, span));
+			}
+		}
+	}
+	match errors.is_empty() {
+		true => Ok(Query(Statements(statements))),
+		false => Err(errors),
+	}
+}
+
+/// Parses a SurrealQL [`Query`], and checks it for mistakes which would otherwise only be found
+/// when the query is run.
+///
+/// As well as every syntax error, as found by [`validate`], this reports parameters which are not
+/// defined before they are used, functions which are called with the wrong number of arguments,
+/// and fields in a FETCH clause which are not selected. The `params` are the names of any
+/// parameters which are defined outside of the query, such as those of the session. Functions
+/// defined in the database, rather than in the query, are not checked.
+#[instrument(level = "debug", name = "parser", skip_all, fields(length = input.len()))]
+pub fn analyze(input: &str, params: &[&str]) -> Result<Query, Vec<RenderedError>> {
+	let query = validate(input)?;
+	// The query is known to be valid, so this only fails if it is empty
+	let spanned = match parse_spanned(input) {
+		Ok(v) => v,
+		Err(_) => return Ok(query),
+	};
+	let errors = analysis::analyze(input, &spanned, params);
+	match errors.is_empty() {
+		true => Ok(query),
+		false => Err(errors.iter().map(|e| e.render_on(input)).collect()),
+	}
+}
+
+/// Parses a SurrealQL [`Query`], and checks it against a set of lint rules.
+///
+/// The lint warnings are returned along with the query, for parts of the query which are
+/// valid, but which are probably slow or hard to maintain, such as statements which scan a
+/// whole table, or values which could be passed as parameters. Any syntax errors are returned
+/// instead, as found by [`validate`]. Only the indexes defined in the query, and those given
+/// in the [`LintRules`], are known when checking for full table scans.
+#[instrument(level = "debug", name = "parser", skip_all, fields(length = input.len()))]
+pub fn lint(
+	input: &str,
+	rules: &LintRules,
+) -> Result<(Query, Vec<RenderedError>), Vec<RenderedError>> {
+	let query = validate(input)?;
+	// The query is known to be valid, so this only fails if it is empty
+	let spanned = match parse_spanned(input) {
+		Ok(v) => v,
+		Err(_) => return Ok((query, Vec::new())),
+	};
+	let warnings = lint::check(input, &spanned, rules).iter().map(|w| w.render_on(input)).collect();
+	Ok((query, warnings))
+}
+
+/// Returns the keywords, built-in functions, and kinds of identifier which can be written at
+/// a byte offset of a partly written query, for completing queries in shells and editors.
+///
+/// If the `cursor` follows part of a word, only the completions which start with that part are
+/// returned, and they replace the [`Completions::span`] of the word. Nothing is returned when
+/// the cursor is within a string or a comment. The completions are found by parsing the query
+/// with each candidate in place, so the names of tables, fields, and functions which are
+/// defined in the database are not known, and only the kinds of identifier are returned.
+#[instrument(level = "debug", name = "parser", skip_all, fields(length = input.len()))]
+pub fn complete(input: &str, cursor: usize) -> Completions {
+	completion::complete(input, cursor)
+}
+
+/// Labels an error found when parsing a query with the statement in which it was found.
+fn in_statement(input: &str, err: Error) -> Error {
+	let e = match err {
+		Error::InvalidQuery(e) => e,
+		e => return e,
+	};
+	let offset = e.diagnostic.span.start;
+	// Find the statement containing the error, after any statements which parse
+	let mut index = 0;
+	let mut start = 0;
+	while let Ok(Some((_, next))) = next_statement(input, start) {
+		if next > offset {
+			break;
+		}
+		index += 1;
+		start = next;
+	}
+	let start = match mightbespace(&input[start..]) {
+		Ok((i, _)) => input.len() - i.len(),
+		Err(_) => start,
+	};
+	let rest = recover(&input[start..], &input[offset.max(start)..]);
+	Error::InvalidQuery(e.in_statement(index, statement_span(input, start, rest)))
+}
+
+/// Returns the span of a statement which starts at a byte offset of the input, and
+/// which is followed by the `rest` of the input.
+fn statement_span(input: &str, start: usize, rest: &str) -> Span {
+	let end = input.len() - rest.len();
+	let end = start + input[start..end].trim_end_matches(';').trim_end().len();
+	Span::of_range(start..end, input)
+}
+
+/// Returns the input following the end of a statement which failed to parse at `tried`.
+///
+/// The end of the statement is the first `;` after the error which is not within a string,
+/// a comment, or a block. If the statement has an unclosed block, this falls back to the
+/// first `;` after the error, so that a single error does not hide the rest of the query.
+fn recover<'a>(input: &'a str, tried: &'a str) -> &'a str {
+	let error = input.len() - tried.len();
+	let mut chars = input.char_indices().peekable();
+	let mut nesting = 0usize;
+	while let Some((idx, c)) = chars.next() {
+		match c {
+			'\'' | '"' | '`' => {
+				while let Some((_, n)) = chars.next() {
+					match n {
+						'\\' => {
+							chars.next();
+						}
+						n if n == c => break,
+						_ => {}
+					}
+				}
+			}
+			'#' => {
+				chars.find(|(_, n)| *n == '\n');
+			}
+			'-' | '/' if chars.peek().map(|(_, n)| *n) == Some(c) => {
+				chars.find(|(_, n)| *n == '\n');
+			}
+			'/' if chars.peek().map(|(_, n)| *n) == Some('*') => {
+				chars.next();
+				let mut last = ' ';
+				for (_, n) in chars.by_ref() {
+					if last == '*' && n == '/' {
+						break;
+					}
+					last = n;
+				}
+			}
+			'(' | '[' | '{' => nesting += 1,
+			')' | ']' | '}' => nesting = nesting.saturating_sub(1),
+			';' if nesting == 0 && idx >= error => return &input[idx + 1..],
+			_ => {}
+		}
+	}
+	// The statement did not end, so end it at the first semicolon after the error
+	match tried.find(';') {
+		Some(idx) => &tried[idx + 1..],
+		None => "",
+	}
+}
+
+/// Parses a SurrealQL [`Query`], along with the byte range of the source of every statement
+/// and expression within it.
+///
+/// This is useful for tooling, such as formatters, linters, and editors, which need to map
+/// parts of the query back to where they were written. It is slower than [`parse`], so
+/// should not be used when the spans are not needed.
+#[instrument(level = "debug", name = "parser", skip_all, fields(length = input.len()))]
+pub fn parse_spanned(input: &str) -> Result<SpannedQuery, Error> {
+	let _recording = spanned::Recording::start(input);
+	parse_impl(input, |i| spanned::query(input, i)).map_err(|e| in_statement(input, e))
 }
 
 /// Parses a SurrealQL [`Thing`]
@@ -48,12 +378,44 @@ pub fn subquery(input: &str) -> Result<Subquery, Error> {
 	parse_impl(input, super::subquery::subquery)
 }
 
+/// Parses a SurrealQL [`Kind`]
+#[instrument(level = "debug", name = "parser", skip_all, fields(length = input.len()))]
+pub fn kind(input: &str) -> Result<Kind, Error> {
+	parse_impl(input, super::kind::kind)
+}
+
 /// Parses JSON into an inert SurrealQL [`Value`]
 #[instrument(level = "debug", name = "parser", skip_all, fields(length = input.len()))]
 pub fn json(input: &str) -> Result<Value, Error> {
 	parse_impl(input.trim(), super::value::json)
 }
 
+/// Parses the next SurrealQL [`Statement`] of a query, starting at a byte offset of the input.
+///
+/// Returns the statement along with the byte offset of the remaining input, or `None` if only
+/// whitespace and comments remain. This allows large queries to be processed incrementally.
+pub(crate) fn next_statement(
+	input: &str,
+	offset: usize,
+) -> Result<Option<(Statement, usize)>, Error> {
+	// Reset the parse depth limiter
+	depth::reset();
+	// Skip any whitespace and comments
+	let rest = match mightbespace(&input[offset..]) {
+		Ok((rest, _)) => rest,
+		Err(_) => &input[offset..],
+	};
+	// Check if there is anything left to parse
+	if rest.is_empty() {
+		return Ok(None);
+	}
+	// Parse the statement and any trailing semicolons
+	match terminated(statement, many0(colons))(rest).finish() {
+		Ok((rest, v)) => Ok(Some((v, input.len() - rest.len()))),
+		Err(e) => Err(Error::InvalidQuery(e.render_on(input))),
+	}
+}
+
 fn parse_impl<O>(input: &str, parser: impl Fn(&str) -> IResult<&str, O>) -> Result<O, Error> {
 	// Reset the parse depth limiter
 	depth::reset();
@@ -84,6 +446,8 @@ pub(crate) mod depth {
 	thread_local! {
 		/// How many recursion levels deep parsing is currently.
 		static DEPTH: Cell<u8> = Cell::default();
+		/// The maximum recursion depth, if a limit other than the default has been set.
+		static LIMIT: Cell<Option<u8>> = Cell::default();
 	}
 
 	/// Scale down `MAX_COMPUTATION_DEPTH` for parsing because:
@@ -96,20 +460,39 @@ pub(crate) mod depth {
 
 	/// Call when starting the parser to reset the recursion depth.
 	#[inline(never)]
-	pub(super) fn reset() {
+	pub(crate) fn reset() {
 		DEPTH.with(|cell| {
 			debug_assert_eq!(cell.get(), 0, "previous parsing stopped abruptly");
 			cell.set(0)
 		});
 	}
 
+	/// Call when starting the parser to limit the recursion depth until dropped.
+	pub(super) fn limit(max: u8) -> Limiting {
+		Limiting(LIMIT.with(|cell| cell.replace(Some(max))))
+	}
+
+	/// Returns the maximum recursion depth of parsing.
+	pub(crate) fn max() -> u8 {
+		LIMIT.with(|cell| cell.get()).unwrap_or(*MAX_COMPUTATION_DEPTH)
+	}
+
+	#[must_use = "must store and implicitly drop when parsing is complete"]
+	pub(super) struct Limiting(Option<u8>);
+
+	impl Drop for Limiting {
+		fn drop(&mut self) {
+			LIMIT.with(|cell| cell.set(self.0));
+		}
+	}
+
 	/// Call at least once in recursive parsing code paths to limit recursion depth.
 	#[inline(never)]
 	#[must_use = "must store and implicitly drop when returning"]
 	pub(crate) fn dive<I>(position: I) -> Result<Diving, Err<crate::sql::ParseError<I>>> {
 		DEPTH.with(|cell| {
 			let depth = cell.get().saturating_add(DEPTH_PER_DIVE);
-			if depth <= *MAX_COMPUTATION_DEPTH {
+			if depth <= max() {
 				cell.replace(depth);
 				Ok(Diving)
 			} else {
@@ -163,6 +546,91 @@ mod tests {
 		parse(sql).unwrap();
 	}
 
+	#[test]
+	fn parse_next_statement() {
+		let sql = "SELECT * FROM test;; -- comment\n UPDATE test:1; /* end */ ";
+		let (v, offset) = next_statement(sql, 0).unwrap().unwrap();
+		assert_eq!(v.to_string(), "SELECT * FROM test");
+		let (v, offset) = next_statement(sql, offset).unwrap().unwrap();
+		assert_eq!(v.to_string(), "UPDATE test:1");
+		assert!(next_statement(sql, offset).unwrap().is_none());
+	}
+
+	#[test]
+	fn parse_query_within_limits() {
+		let sql = "SELECT * FROM test; SELECT * FROM [[[1]]]";
+		let limits = ParseLimits::default().with_query_size(Some(64)).with_statements(Some(2));
+		assert_eq!(parse_with_limits(sql, &limits).unwrap().len(), 2);
+		// The query is too large
+		let res = parse_with_limits(sql, &limits.with_query_size(Some(16)));
+		assert!(matches!(
+			res,
+			Err(Error::QueryTooLarge {
+				size: 41,
+				max: 16
+			})
+		));
+		// The query has too many statements
+		let res = parse_with_limits(sql, &limits.with_statements(Some(1)));
+		assert!(matches!(
+			res,
+			Err(Error::QueryTooManyStatements {
+				count: 2,
+				max: 1
+			})
+		));
+		// The query is nested too deeply
+		let res = parse_with_limits(sql, &limits.with_depth(4));
+		assert!(res.unwrap_err().to_string().contains("Exceeded maximum parse depth of 4"));
+		// The limits only apply while parsing the query
+		assert_eq!(depth::max(), *MAX_COMPUTATION_DEPTH);
+	}
+
+	#[test]
+	fn validate_query_string() {
+		let sql = "SELECT * FROM test; UPDATE test:1 SET name = 'a;b' ";
+		let tmp = validate(sql).unwrap();
+		assert_eq!(tmp, parse(sql).unwrap());
+	}
+
+	#[test]
+	fn validate_multiple_errors() {
+		let sql = "SELECT * FROM test;\nSELEC * FROM test;\nCREATE test SET name = 'a;b' +;\nUPDATE test:1;\nDELETE test WHERE { a; ;";
+		let res = validate(sql).unwrap_err();
+		assert_eq!(res.len(), 3);
+		assert_eq!(res[0].diagnostic.span.line, 2);
+		assert_eq!(res[1].diagnostic.span.line, 3);
+		assert_eq!(res[2].diagnostic.span.line, 5);
+		// Each error is labelled with the statement it is in
+		let stm = res[0].diagnostic.statement.unwrap();
+		assert_eq!(stm.index, 1);
+		assert_eq!(&sql[stm.span.start..stm.span.end], "SELEC * FROM test");
+		assert_eq!(res[1].diagnostic.statement.unwrap().index, 2);
+		assert_eq!(res[2].diagnostic.statement.unwrap().index, 4);
+	}
+
+	#[test]
+	fn validate_missing_semicolon() {
+		let sql = "SELECT * FROM test\nSELECT * FROM temp; SELECT * FROM ;";
+		let res = validate(sql).unwrap_err();
+		assert_eq!(res.len(), 2);
+		assert_eq!(res[0].diagnostic.span.line, 2);
+		assert_eq!(res[0].diagnostic.statement.unwrap().index, 1);
+		assert_eq!(res[1].diagnostic.statement.unwrap().index, 2);
+	}
+
+	#[test]
+	fn parse_error_statement() {
+		let sql = "SELECT * FROM test;\nSELECT * FROM test WHERE;\nSELECT 1;";
+		let stm = match parse(sql) {
+			Err(Error::InvalidQuery(e)) => e.diagnostic.statement.unwrap(),
+			res => panic!("expected a parse error, found {res:?}"),
+		};
+		assert_eq!(stm.index, 1);
+		assert_eq!(&sql[stm.span.start..stm.span.end], "SELECT * FROM test WHERE");
+		assert_eq!((stm.span.line, stm.span.column), (2, 1));
+	}
+
 	#[test]
 	fn parse_complex_rubbish() {
 		let sql = "    SELECT    *    FROM    test    ; /* shouldbespace */ ;;;    ";
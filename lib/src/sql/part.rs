@@ -1,5 +1,6 @@
 use crate::sql::comment::shouldbespace;
 use crate::sql::ending::ident as ending;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::fmt::Fmt;
 use crate::sql::graph::{self, Graph};
@@ -11,7 +12,6 @@ use crate::sql::strand::{self, no_nul_bytes};
 use crate::sql::value::{self, Value};
 use nom::branch::alt;
 use nom::bytes::complete::tag;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::{self, cut, map, not, peek};
 use nom::sequence::{preceded, terminated};
 use revision::revisioned;
@@ -1,13 +1,13 @@
 use crate::sql::comment::shouldbespace;
 use crate::sql::common::commas;
 use crate::sql::common::commasorspace;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::fmt::is_pretty;
 use crate::sql::fmt::pretty_indent;
 use crate::sql::fmt::pretty_sequence_item;
 use crate::sql::value::{value, Value};
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator;
 use nom::combinator::cut;
 use nom::combinator::map;
@@ -0,0 +1,80 @@
+use crate::sql::comment::shouldbespace;
+use crate::sql::error::expect_tag_no_case;
+use crate::sql::error::expected;
+use crate::sql::error::tag_no_case;
+use crate::sql::error::IResult;
+use nom::branch::alt;
+use nom::combinator::value;
+use nom::sequence::tuple;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// What happens to a record link field when the record it links to is deleted
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[revisioned(revision = 1)]
+pub enum Reference {
+	/// The records which link to the deleted record are deleted too
+	Cascade,
+	/// The link is removed from the records which link to the deleted record
+	SetNull,
+	/// The record can not be deleted while any records link to it
+	Restrict,
+}
+
+impl Display for Reference {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		match self {
+			Self::Cascade => f.write_str("ON DELETE CASCADE"),
+			Self::SetNull => f.write_str("ON DELETE SET NULL"),
+			Self::Restrict => f.write_str("ON DELETE RESTRICT"),
+		}
+	}
+}
+
+pub fn reference(i: &str) -> IResult<&str, Reference> {
+	let (i, _) = tag_no_case("ON")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = expect_tag_no_case("DELETE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	expected(
+		"CASCADE, SET NULL, or RESTRICT",
+		alt((
+			value(Reference::Cascade, tag_no_case("CASCADE")),
+			value(
+				Reference::SetNull,
+				tuple((tag_no_case("SET"), shouldbespace, tag_no_case("NULL"))),
+			),
+			value(Reference::Restrict, tag_no_case("RESTRICT")),
+		)),
+	)(i)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn reference_cascade() {
+		let sql = "ON DELETE CASCADE";
+		let res = reference(sql);
+		let out = res.unwrap().1;
+		assert_eq!("ON DELETE CASCADE", format!("{}", out));
+		assert_eq!(out, Reference::Cascade);
+	}
+
+	#[test]
+	fn reference_set_null() {
+		let sql = "on delete set   null";
+		let res = reference(sql);
+		let out = res.unwrap().1;
+		assert_eq!("ON DELETE SET NULL", format!("{}", out));
+		assert_eq!(out, Reference::SetNull);
+	}
+
+	#[test]
+	fn reference_invalid() {
+		let res = reference("ON DELETE IGNORE");
+		assert!(res.is_err());
+	}
+}
@@ -1,7 +1,7 @@
 use crate::sql::common::{closeparentheses, commas, openparentheses};
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::{cut, map_res, value};
 use nom::number::complete::recognize_float;
 use revision::revisioned;
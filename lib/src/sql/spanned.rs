@@ -0,0 +1,268 @@
+use crate::sql::comment::mightbespace;
+use crate::sql::common::colons;
+use crate::sql::error::{IResult, ParseError};
+use crate::sql::query::Query;
+use crate::sql::statement::{statement_body, Statement, Statements};
+use crate::sql::value::Value;
+use nom::multi::many0;
+use nom::Err;
+use std::cell::RefCell;
+use std::ops::Range;
+
+/// A SurrealQL [`Query`] along with the source spans of its statements and expressions.
+///
+/// Spans are byte ranges of the source text, with the end being exclusive.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct SpannedQuery {
+	pub statements: Vec<SpannedStatement>,
+}
+
+/// A parsed [`Statement`] along with its source span.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct SpannedStatement {
+	/// The part of the source containing the statement, excluding any semicolon.
+	pub span: Range<usize>,
+	/// The parsed statement.
+	pub statement: Statement,
+	/// The outermost expressions of the statement, in source order.
+	pub expressions: Vec<SpannedValue>,
+}
+
+/// A parsed expression along with its source span.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct SpannedValue {
+	/// The part of the source containing the expression.
+	pub span: Range<usize>,
+	/// The parsed expression.
+	pub value: Value,
+	/// The expressions nested within this expression, in source order.
+	pub children: Vec<SpannedValue>,
+}
+
+impl From<SpannedQuery> for Query {
+	fn from(v: SpannedQuery) -> Self {
+		Query(Statements(v.statements.into_iter().map(|v| v.statement).collect()))
+	}
+}
+
+impl SpannedQuery {
+	/// Returns the statement containing a byte offset of the source, if there is one.
+	pub fn statement_at(&self, offset: usize) -> Option<&SpannedStatement> {
+		self.statements.iter().find(|v| v.span.contains(&offset))
+	}
+	/// Returns the innermost expression containing a byte offset of the source, if there is one.
+	pub fn value_at(&self, offset: usize) -> Option<&SpannedValue> {
+		self.statement_at(offset)?.value_at(offset)
+	}
+}
+
+impl SpannedStatement {
+	/// Returns the innermost expression containing a byte offset of the source, if there is one.
+	pub fn value_at(&self, offset: usize) -> Option<&SpannedValue> {
+		self.expressions.iter().find(|v| v.span.contains(&offset))?.value_at(offset)
+	}
+}
+
+impl SpannedValue {
+	/// Returns the innermost expression containing a byte offset of the source, if there is one.
+	pub fn value_at(&self, offset: usize) -> Option<&SpannedValue> {
+		if !self.span.contains(&offset) {
+			return None;
+		}
+		match self.children.iter().find_map(|v| v.value_at(offset)) {
+			Some(v) => Some(v),
+			None => Some(self),
+		}
+	}
+}
+
+thread_local! {
+	/// The spans recorded while parsing, if spans are being recorded.
+	static RECORDER: RefCell<Option<Recorder>> = RefCell::new(None);
+}
+
+struct Recorder {
+	/// The length of the source being parsed.
+	len: usize,
+	/// The expressions found within each expression which is currently being parsed.
+	frames: Vec<Vec<SpannedValue>>,
+}
+
+/// Records the spans of parsed expressions until dropped.
+#[must_use = "must store and implicitly drop when parsing is complete"]
+pub(crate) struct Recording;
+
+impl Recording {
+	pub(crate) fn start(input: &str) -> Self {
+		RECORDER.with(|cell| {
+			cell.replace(Some(Recorder {
+				len: input.len(),
+				frames: vec![Vec::new()],
+			}))
+		});
+		Recording
+	}
+}
+
+impl Drop for Recording {
+	fn drop(&mut self) {
+		RECORDER.with(|cell| cell.replace(None));
+	}
+}
+
+/// Call from expression parsers to record the span of the parsed expression.
+///
+/// This does nothing unless spans are being recorded, in which case expressions which
+/// were parsed by an alternative which was later abandoned are discarded.
+pub(crate) fn record<'a>(
+	i: &'a str,
+	parser: impl FnOnce(&'a str) -> IResult<&'a str, Value>,
+) -> IResult<&'a str, Value> {
+	let recording = RECORDER.with(|cell| match cell.borrow_mut().as_mut() {
+		Some(r) => {
+			r.frames.push(Vec::new());
+			true
+		}
+		None => false,
+	});
+	if !recording {
+		return parser(i);
+	}
+	let res = parser(i);
+	RECORDER.with(|cell| {
+		if let Some(r) = cell.borrow_mut().as_mut() {
+			let children = r.frames.pop().unwrap_or_default();
+			if let (Ok((rest, v)), Some(frame)) = (&res, r.frames.last_mut()) {
+				let span = (r.len - i.len())..(r.len - rest.len());
+				push(frame, SpannedValue::new(span, v.clone(), children));
+			}
+		}
+	});
+	res
+}
+
+/// Returns the outermost expressions recorded since this was last called.
+fn take() -> Vec<SpannedValue> {
+	RECORDER.with(|cell| match cell.borrow_mut().as_mut() {
+		Some(r) => r.frames.first_mut().map(std::mem::take).unwrap_or_default(),
+		None => Vec::new(),
+	})
+}
+
+impl SpannedValue {
+	fn new(span: Range<usize>, value: Value, mut children: Vec<SpannedValue>) -> Self {
+		// Discard anything parsed beyond the end of this expression
+		children.retain(|v| v.span.end <= span.end);
+		// An expression which wraps a single expression, such as a
+		// value without an operator, is the same node in the tree
+		if let [v] = children.as_mut_slice() {
+			if v.span == span {
+				children = std::mem::take(&mut v.children);
+			}
+		}
+		SpannedValue {
+			span,
+			value,
+			children,
+		}
+	}
+}
+
+/// Adds an expression to its siblings.
+///
+/// Sibling expressions never overlap, so any sibling which does overlap was
+/// parsed by an alternative which failed, and which was then parsed again.
+fn push(frame: &mut Vec<SpannedValue>, v: SpannedValue) {
+	frame.retain(|s| s.span.end <= v.span.start);
+	frame.push(v);
+}
+
+/// Parses a query, recording the span of each statement.
+pub(crate) fn query<'a>(input: &'a str, i: &'a str) -> IResult<&'a str, SpannedQuery> {
+	let mut statements = Vec::new();
+	let (mut i, _) = mightbespace(i)?;
+	loop {
+		let _ = take();
+		let (rest, statement) = statement_body(i)?;
+		statements.push(SpannedStatement {
+			span: (input.len() - i.len())..(input.len() - rest.len()),
+			statement,
+			expressions: take(),
+		});
+		// Statements are separated by semicolons
+		let (rest, ended) = match colons(rest) {
+			Ok((rest, _)) => (many0(colons)(rest)?.0, true),
+			Err(_) => (mightbespace(rest)?.0, false),
+		};
+		i = rest;
+		if i.is_empty() {
+			break;
+		}
+		if !ended {
+			return Err(Err::Failure(ParseError::ExplainedExpected {
+				tried: i,
+				expected: "query to end",
+				explained: "perhaps missing a semicolon on the previous statement?",
+			}));
+		}
+	}
+	Ok((
+		i,
+		SpannedQuery {
+			statements,
+		},
+	))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::sql::parse;
+	use crate::sql::parse_spanned;
+	use crate::sql::test::Parse;
+
+	#[test]
+	fn spanned_statements() {
+		let sql = "SELECT * FROM person WHERE age > 18;\n\n  CREATE person:tobie ; ;";
+		let res = parse_spanned(sql).unwrap();
+		assert_eq!(res.statements.len(), 2);
+		assert_eq!(&sql[res.statements[0].span.clone()], "SELECT * FROM person WHERE age > 18");
+		assert_eq!(&sql[res.statements[1].span.clone()], "CREATE person:tobie");
+		assert_eq!(Query::from(res), parse(sql).unwrap());
+	}
+
+	#[test]
+	fn spanned_expressions() {
+		let sql = "SELECT name FROM person WHERE age > 18 + 2";
+		let res = parse_spanned(sql).unwrap();
+		let exprs = &res.statements[0].expressions;
+		let spans: Vec<_> = exprs.iter().map(|v| &sql[v.span.clone()]).collect();
+		assert_eq!(spans, vec!["name", "person", "age > 18 + 2"]);
+		let cond = &exprs[2];
+		assert_eq!(cond.value, Value::parse("age > 18 + 2"));
+		let spans: Vec<_> = cond.children.iter().map(|v| &sql[v.span.clone()]).collect();
+		assert_eq!(spans, vec!["age", "18 + 2"]);
+	}
+
+	#[test]
+	fn spanned_value_at() {
+		let sql = "UPDATE person SET tags = array::add(tags, 'new')";
+		let res = parse_spanned(sql).unwrap();
+		let offset = sql.find("'new'").unwrap() + 1;
+		let v = res.value_at(offset).unwrap();
+		assert_eq!(&sql[v.span.clone()], "'new'");
+		assert_eq!(v.value, Value::from("new"));
+		assert!(res.value_at(sql.len()).is_none());
+	}
+
+	#[test]
+	fn spanned_missing_semicolon() {
+		let sql = "SELECT * FROM person\nSELECT * FROM person";
+		assert!(parse_spanned(sql).is_err());
+		assert!(parse_spanned("").is_err());
+	}
+}
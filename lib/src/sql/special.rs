@@ -32,10 +32,23 @@ fn contains_idiom(fields: &Fields, idiom: &Idiom) -> bool {
 					}
 				}
 			}
+			// Check the window function alias or name
+			Field::Window {
+				expr,
+				alias,
+			} => match alias {
+				Some(i) => i.as_ref() == idiom.as_ref(),
+				None => expr.to_idiom().as_ref() == idiom.as_ref(),
+			},
 		}
 	})
 }
 
+/// Check to see whether the expression, or a field which it is nested within, is in the SELECT clause
+pub(crate) fn contains_idiom_start(fields: &Fields, idiom: &Idiom) -> bool {
+	(1..=idiom.len()).any(|n| contains_idiom(fields, &Idiom::from(&idiom[..n])))
+}
+
 pub fn check_split_on_fields<'a>(
 	i: &'a str,
 	fields: &Fields,
@@ -74,6 +87,22 @@ pub fn check_order_by_fields<'a>(
 	Ok(())
 }
 
+/// Check that there are no window functions in the SELECT clause, as these
+/// can only be computed once all of the results of a statement are known
+pub fn check_no_window_fields<'a>(
+	i: &'a str,
+	fields: &Fields,
+	explained: &'static str,
+) -> Result<(), Err<ParseError<&'a str>>> {
+	if fields.has_window() {
+		return Err(Failure(ParseError::Explained {
+			tried: i,
+			explained,
+		}));
+	}
+	Ok(())
+}
+
 pub fn check_group_by_fields<'a>(
 	i: &'a str,
 	fields: &Fields,
@@ -88,6 +117,13 @@ pub fn check_group_by_fields<'a>(
 				return Err(Failure(ParseError::Group(i, group.to_string())));
 			}
 		}
+		// Window functions can not be computed over groups
+		if fields.has_window() {
+			return Err(Failure(ParseError::Explained {
+				tried: i,
+				explained: "window functions can not be used with a GROUP clause",
+			}));
+		}
 		// Check if this is a GROUP ALL clause or a GROUP BY clause
 		if !groups.is_empty() {
 			// Loop over each of the expressions in the SELECT clause
@@ -7,9 +7,12 @@ use crate::sql::common::colons;
 use crate::sql::error::IResult;
 use crate::sql::fmt::Fmt;
 use crate::sql::fmt::Pretty;
+use crate::sql::statements::alter::{alter, AlterStatement};
 use crate::sql::statements::analyze::{analyze, AnalyzeStatement};
+use crate::sql::statements::apply::{apply, ApplyStatement};
 use crate::sql::statements::begin::{begin, BeginStatement};
 use crate::sql::statements::cancel::{cancel, CancelStatement};
+use crate::sql::statements::check::{check, CheckStatement};
 use crate::sql::statements::commit::{commit, CommitStatement};
 use crate::sql::statements::create::{create, CreateStatement};
 use crate::sql::statements::define::{define, DefineStatement};
@@ -26,13 +29,20 @@ use crate::sql::statements::r#break::{r#break, BreakStatement};
 use crate::sql::statements::r#continue::{r#continue, ContinueStatement};
 use crate::sql::statements::r#use::{r#use, UseStatement};
 use crate::sql::statements::relate::{relate, RelateStatement};
+use crate::sql::statements::release::{release, ReleaseStatement};
 use crate::sql::statements::remove::{remove, RemoveStatement};
+use crate::sql::statements::rollback::{rollback, RollbackStatement};
+use crate::sql::statements::run::{run, RunStatement};
+use crate::sql::statements::savepoint::{savepoint, SavepointStatement};
 use crate::sql::statements::select::{select, SelectStatement};
 use crate::sql::statements::set::{set, SetStatement};
+use crate::sql::statements::setoption::{setoption, SetOptionStatement};
 use crate::sql::statements::show::{show, ShowStatement};
 use crate::sql::statements::sleep::{sleep, SleepStatement};
+use crate::sql::statements::suggest::{suggest, SuggestStatement};
 use crate::sql::statements::throw::{throw, ThrowStatement};
 use crate::sql::statements::update::{update, UpdateStatement};
+use crate::sql::statements::upsert::{upsert, UpsertStatement};
 use crate::sql::value::{value, Value};
 use derive::Store;
 use nom::branch::alt;
@@ -110,6 +120,16 @@ pub enum Statement {
 	Update(UpdateStatement),
 	Throw(ThrowStatement),
 	Use(UseStatement),
+	SetOption(SetOptionStatement),
+	Run(RunStatement),
+	Check(CheckStatement),
+	Alter(AlterStatement),
+	Upsert(UpsertStatement),
+	Savepoint(SavepointStatement),
+	Rollback(RollbackStatement),
+	Release(ReleaseStatement),
+	Suggest(SuggestStatement),
+	Apply(ApplyStatement),
 }
 
 impl Statement {
@@ -122,6 +142,7 @@ impl Statement {
 			Self::Relate(v) => v.timeout.as_ref().map(|v| *v.0),
 			Self::Select(v) => v.timeout.as_ref().map(|v| *v.0),
 			Self::Update(v) => v.timeout.as_ref().map(|v| *v.0),
+			Self::Upsert(v) => v.timeout.as_ref().map(|v| *v.0),
 			_ => None,
 		}
 	}
@@ -139,7 +160,7 @@ impl Statement {
 			Self::Ifelse(v) => v.writeable(),
 			Self::Info(_) => false,
 			Self::Insert(v) => v.writeable(),
-			Self::Kill(_) => true,
+			Self::Kill(v) => !v.query,
 			Self::Live(_) => true,
 			Self::Output(v) => v.writeable(),
 			Self::Option(_) => false,
@@ -152,9 +173,34 @@ impl Statement {
 			Self::Throw(_) => false,
 			Self::Update(v) => v.writeable(),
 			Self::Use(_) => false,
+			Self::SetOption(_) => false,
+			Self::Run(v) => v.writeable(),
+			Self::Check(v) => v.fix,
+			Self::Alter(_) => true,
+			Self::Upsert(v) => v.writeable(),
+			Self::Suggest(_) => false,
+			Self::Apply(_) => true,
 			_ => unreachable!(),
 		}
 	}
+	/// Substitute any nondeterministic functions with literal values
+	pub(crate) async fn resolve(&mut self, ctx: &Context<'_>, opt: &Options, txn: &Transaction) {
+		match self {
+			Self::Value(v) => v.resolve(ctx, opt, txn).await,
+			Self::Create(v) => v.resolve(ctx, opt, txn).await,
+			Self::Delete(v) => v.resolve(ctx, opt, txn).await,
+			Self::Foreach(v) => v.resolve(ctx, opt, txn).await,
+			Self::Ifelse(v) => v.resolve(ctx, opt, txn).await,
+			Self::Insert(v) => v.resolve(ctx, opt, txn).await,
+			Self::Output(v) => v.resolve(ctx, opt, txn).await,
+			Self::Relate(v) => v.resolve(ctx, opt, txn).await,
+			Self::Select(v) => v.resolve(ctx, opt, txn).await,
+			Self::Set(v) => v.resolve(ctx, opt, txn).await,
+			Self::Update(v) => v.resolve(ctx, opt, txn).await,
+			Self::Upsert(v) => v.resolve(ctx, opt, txn).await,
+			_ => (),
+		}
+	}
 	/// Process this type returning a computed simple Value
 	pub(crate) async fn compute(
 		&self,
@@ -185,6 +231,12 @@ impl Statement {
 			Self::Sleep(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Throw(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Update(v) => v.compute(ctx, opt, txn, doc).await,
+			Self::Run(v) => v.compute(ctx, opt, txn, doc).await,
+			Self::Check(v) => v.compute(ctx, opt, txn, doc).await,
+			Self::Alter(v) => v.compute(ctx, opt, txn, doc).await,
+			Self::Upsert(v) => v.compute(ctx, opt, txn, doc).await,
+			Self::Suggest(v) => v.compute(ctx, opt, txn, doc).await,
+			Self::Apply(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Value(v) => {
 				// Ensure futures are processed
 				let opt = &opt.new_with_futures(true);
@@ -226,48 +278,69 @@ impl Display for Statement {
 			Self::Throw(v) => write!(Pretty::from(f), "{v}"),
 			Self::Update(v) => write!(Pretty::from(f), "{v}"),
 			Self::Use(v) => write!(Pretty::from(f), "{v}"),
+			Self::SetOption(v) => write!(Pretty::from(f), "{v}"),
+			Self::Run(v) => write!(Pretty::from(f), "{v}"),
+			Self::Check(v) => write!(Pretty::from(f), "{v}"),
+			Self::Alter(v) => write!(Pretty::from(f), "{v}"),
+			Self::Upsert(v) => write!(Pretty::from(f), "{v}"),
+			Self::Savepoint(v) => write!(Pretty::from(f), "{v}"),
+			Self::Rollback(v) => write!(Pretty::from(f), "{v}"),
+			Self::Release(v) => write!(Pretty::from(f), "{v}"),
+			Self::Suggest(v) => write!(Pretty::from(f), "{v}"),
+			Self::Apply(v) => write!(Pretty::from(f), "{v}"),
 		}
 	}
 }
 
 pub fn statement(i: &str) -> IResult<&str, Statement> {
-	delimited(
-		mightbespace,
+	delimited(mightbespace, statement_body, mightbespace)(i)
+}
+
+/// Parses a statement without any surrounding whitespace or comments
+pub(crate) fn statement_body(i: &str) -> IResult<&str, Statement> {
+	alt((
+		alt((
+			map(alter, Statement::Alter),
+			map(analyze, Statement::Analyze),
+			map(apply, Statement::Apply),
+			map(begin, Statement::Begin),
+			map(r#break, Statement::Break),
+			map(cancel, Statement::Cancel),
+			map(check, Statement::Check),
+			map(commit, Statement::Commit),
+			map(r#continue, Statement::Continue),
+			map(create, Statement::Create),
+			map(define, Statement::Define),
+			map(delete, Statement::Delete),
+			map(foreach, Statement::Foreach),
+			map(ifelse, Statement::Ifelse),
+			map(info, Statement::Info),
+			map(insert, Statement::Insert),
+		)),
 		alt((
-			alt((
-				map(analyze, Statement::Analyze),
-				map(begin, Statement::Begin),
-				map(r#break, Statement::Break),
-				map(cancel, Statement::Cancel),
-				map(commit, Statement::Commit),
-				map(r#continue, Statement::Continue),
-				map(create, Statement::Create),
-				map(define, Statement::Define),
-				map(delete, Statement::Delete),
-				map(foreach, Statement::Foreach),
-				map(ifelse, Statement::Ifelse),
-				map(info, Statement::Info),
-				map(insert, Statement::Insert),
-			)),
-			alt((
-				map(kill, Statement::Kill),
-				map(live, Statement::Live),
-				map(option, Statement::Option),
-				map(output, Statement::Output),
-				map(relate, Statement::Relate),
-				map(remove, Statement::Remove),
-				map(select, Statement::Select),
-				map(set, Statement::Set),
-				map(show, Statement::Show),
-				map(sleep, Statement::Sleep),
-				map(throw, Statement::Throw),
-				map(update, Statement::Update),
-				map(r#use, Statement::Use),
-			)),
-			map(value, Statement::Value),
+			map(kill, Statement::Kill),
+			map(live, Statement::Live),
+			map(option, Statement::Option),
+			map(output, Statement::Output),
+			map(relate, Statement::Relate),
+			map(release, Statement::Release),
+			map(remove, Statement::Remove),
+			map(rollback, Statement::Rollback),
+			map(run, Statement::Run),
+			map(savepoint, Statement::Savepoint),
+			map(select, Statement::Select),
+			map(set, Statement::Set),
+			map(setoption, Statement::SetOption),
+			map(show, Statement::Show),
+			map(sleep, Statement::Sleep),
+			map(suggest, Statement::Suggest),
+			map(throw, Statement::Throw),
+			map(update, Statement::Update),
+			map(upsert, Statement::Upsert),
+			map(r#use, Statement::Use),
 		)),
-		mightbespace,
-	)(i)
+		map(value, Statement::Value),
+	))(i)
 }
 
 #[cfg(test)]
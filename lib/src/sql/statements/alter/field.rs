@@ -0,0 +1,289 @@
+use super::records;
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::iam::Action;
+use crate::iam::ResourceKind;
+use crate::sql::base::Base;
+use crate::sql::comment::shouldbespace;
+use crate::sql::ending;
+use crate::sql::error::expect_tag_no_case;
+use crate::sql::error::expected;
+use crate::sql::error::tag_no_case;
+use crate::sql::error::IResult;
+use crate::sql::fmt::is_pretty;
+use crate::sql::fmt::pretty_indent;
+use crate::sql::ident::{ident, Ident};
+use crate::sql::idiom;
+use crate::sql::idiom::Idiom;
+use crate::sql::kind::{kind, Kind};
+use crate::sql::permission::{permissions, Permissions};
+use crate::sql::statements::DefineFieldStatement;
+use crate::sql::strand::{strand, Strand};
+use crate::sql::value::{value, Value};
+use derive::Store;
+use nom::branch::alt;
+use nom::combinator::cut;
+use nom::combinator::opt;
+use nom::multi::many0;
+use nom::sequence::tuple;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Write};
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub struct AlterFieldStatement {
+	pub name: Idiom,
+	pub what: Ident,
+	pub kind: Option<Kind>,
+	pub assert: Option<Value>,
+	pub permissions: Option<Permissions>,
+	pub comment: Option<Strand>,
+}
+
+impl AlterFieldStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		_doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		// Allowed to run?
+		opt.is_allowed(Action::Edit, ResourceKind::Field, &Base::Db)?;
+		// Fetch the existing definition
+		let fd = self.name.to_string();
+		let key = crate::key::table::fd::new(opt.ns(), opt.db(), &self.what, &fd);
+		let mut def: DefineFieldStatement = match txn.lock().await.get(key.clone()).await? {
+			Some(v) => v.into(),
+			None => {
+				return Err(Error::FdNotFound {
+					value: fd,
+				})
+			}
+		};
+		// Modify only the specified properties
+		if let Some(v) = &self.kind {
+			def.kind = Some(v.clone());
+		}
+		if let Some(v) = &self.assert {
+			def.assert = Some(v.clone());
+		}
+		if let Some(v) = &self.permissions {
+			def.permissions = v.clone();
+		}
+		if let Some(v) = &self.comment {
+			def.comment = Some(v.clone());
+		}
+		// Check the existing records against the new constraints
+		if self.kind.is_some() || self.assert.is_some() {
+			self.validate(ctx, opt, txn, &def).await?;
+		}
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Clear the cache
+		run.clear_cache();
+		// Process the statement
+		run.set(key, &def).await?;
+		// Clear the cache
+		let key = crate::key::table::fd::prefix(opt.ns(), opt.db(), &self.what);
+		run.clr(key).await?;
+		// Ok all good
+		Ok(Value::None)
+	}
+	/// Check that the field of every existing record conforms to the TYPE and ASSERT
+	/// clauses of the altered definition
+	async fn validate(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		def: &DefineFieldStatement,
+	) -> Result<(), Error> {
+		let mut next = Some(crate::key::thing::prefix(opt.ns(), opt.db(), &self.what));
+		while let Some(beg) = next {
+			let (res, end) = records(opt, txn, &self.what, beg).await?;
+			for (rid, doc) in res.iter() {
+				let cur = CursorDoc::new(None, Some(rid), None, doc);
+				for (_, val) in doc.walk(&def.name).into_iter() {
+					// Check for a TYPE clause
+					let val = match &def.kind {
+						Some(kind) => val.coerce_to(kind).map_err(|e| match e {
+							// There was a conversion error
+							Error::CoerceTo {
+								from,
+								..
+							} => Error::FieldCheck {
+								thing: rid.to_string(),
+								field: def.name.clone(),
+								value: from.to_string(),
+								check: kind.to_string(),
+							},
+							// There was a different error
+							e => e,
+						})?,
+						None => val,
+					};
+					// Check for a ASSERT clause
+					if let Some(expr) = &def.assert {
+						// Configure the context
+						let mut ctx = Context::new(ctx);
+						ctx.add_value("value", &val);
+						ctx.add_value("after", &val);
+						ctx.add_value("before", &val);
+						// Process the ASSERT clause
+						if !expr.compute(&ctx, opt, txn, Some(&cur)).await?.is_truthy() {
+							return Err(Error::FieldValue {
+								thing: rid.to_string(),
+								field: def.name.clone(),
+								value: val.to_string(),
+								check: expr.to_string(),
+							});
+						}
+					}
+				}
+			}
+			next = end;
+		}
+		Ok(())
+	}
+}
+
+impl Display for AlterFieldStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "ALTER FIELD {} ON {}", self.name, self.what)?;
+		if let Some(ref v) = self.kind {
+			write!(f, " TYPE {v}")?
+		}
+		if let Some(ref v) = self.assert {
+			write!(f, " ASSERT {v}")?
+		}
+		if let Some(ref v) = self.comment {
+			write!(f, " COMMENT {v}")?
+		}
+		if let Some(ref v) = self.permissions {
+			let _indent = if is_pretty() {
+				Some(pretty_indent())
+			} else {
+				f.write_char(' ')?;
+				None
+			};
+			write!(f, "{v}")?;
+		}
+		Ok(())
+	}
+}
+
+pub fn field(i: &str) -> IResult<&str, AlterFieldStatement> {
+	let (i, _) = tag_no_case("FIELD")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, (name, what, opts)) = cut(|i| {
+		let (i, name) = idiom::local(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, _) = expect_tag_no_case("ON")(i)?;
+		let (i, _) = opt(tuple((shouldbespace, tag_no_case("TABLE"))))(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, what) = ident(i)?;
+		let (i, opts) = many0(field_opts)(i)?;
+		let (i, _) =
+			expected("one of TYPE, ASSERT, PERMISSIONS, or COMMENT", cut(ending::query))(i)?;
+		Ok((i, (name, what, opts)))
+	})(i)?;
+	// Create the base statement
+	let mut res = AlterFieldStatement {
+		name,
+		what,
+		..Default::default()
+	};
+	// Assign any altered options
+	for opt in opts {
+		match opt {
+			AlterFieldOption::Kind(v) => {
+				res.kind = Some(v);
+			}
+			AlterFieldOption::Assert(v) => {
+				res.assert = Some(v);
+			}
+			AlterFieldOption::Comment(v) => {
+				res.comment = Some(v);
+			}
+			AlterFieldOption::Permissions(v) => {
+				res.permissions = Some(v);
+			}
+		}
+	}
+	// Return the statement
+	Ok((i, res))
+}
+
+enum AlterFieldOption {
+	Kind(Kind),
+	Assert(Value),
+	Comment(Strand),
+	Permissions(Permissions),
+}
+
+fn field_opts(i: &str) -> IResult<&str, AlterFieldOption> {
+	alt((field_kind, field_assert, field_comment, field_permissions))(i)
+}
+
+fn field_kind(i: &str) -> IResult<&str, AlterFieldOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("TYPE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(kind)(i)?;
+	Ok((i, AlterFieldOption::Kind(v)))
+}
+
+fn field_assert(i: &str) -> IResult<&str, AlterFieldOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("ASSERT")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(value)(i)?;
+	Ok((i, AlterFieldOption::Assert(v)))
+}
+
+fn field_comment(i: &str) -> IResult<&str, AlterFieldOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("COMMENT")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = strand(i)?;
+	Ok((i, AlterFieldOption::Comment(v)))
+}
+
+fn field_permissions(i: &str) -> IResult<&str, AlterFieldOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = permissions(i)?;
+	Ok((i, AlterFieldOption::Permissions(v)))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn alter_field() {
+		let sql = "FIELD age ON person TYPE int ASSERT $value > 0 COMMENT 'years'";
+		let res = field(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out.kind, Some(Kind::Int));
+		assert_eq!(out.permissions, None);
+		assert_eq!(format!("ALTER {sql}"), format!("{}", out));
+
+		let serialized: Vec<u8> = (&out).try_into().unwrap();
+		let deserialized = AlterFieldStatement::try_from(&serialized).unwrap();
+		assert_eq!(out, deserialized);
+	}
+
+	#[test]
+	fn alter_field_invalid() {
+		let sql = "FIELD age ON person VALUE 1";
+		let res = field(sql);
+		assert!(res.is_err());
+	}
+}
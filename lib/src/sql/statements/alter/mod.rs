@@ -0,0 +1,115 @@
+mod field;
+mod table;
+
+pub use field::{field, AlterFieldStatement};
+pub use table::{table, AlterTableStatement};
+
+use crate::cnf::ALTER_BATCH_SIZE;
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::kvs::Key;
+use crate::sql::comment::shouldbespace;
+use crate::sql::error::tag_no_case;
+use crate::sql::error::IResult;
+use crate::sql::thing::Thing;
+use crate::sql::value::Value;
+use derive::Store;
+use nom::branch::alt;
+use nom::combinator::map;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub enum AlterStatement {
+	Table(AlterTableStatement),
+	Field(AlterFieldStatement),
+}
+
+impl AlterStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		match self {
+			Self::Table(ref v) => v.compute(ctx, opt, txn, doc).await,
+			Self::Field(ref v) => v.compute(ctx, opt, txn, doc).await,
+		}
+	}
+}
+
+impl Display for AlterStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Table(v) => Display::fmt(v, f),
+			Self::Field(v) => Display::fmt(v, f),
+		}
+	}
+}
+
+pub fn alter(i: &str) -> IResult<&str, AlterStatement> {
+	let (i, _) = tag_no_case("ALTER")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	alt((map(table, AlterStatement::Table), map(field, AlterStatement::Field)))(i)
+}
+
+/// Fetches a batch of the records of a table, starting at the given key, so that they can
+/// be validated against an altered definition. Returns the key to continue from, if there
+/// may be more records in the table.
+async fn records(
+	opt: &Options,
+	txn: &Transaction,
+	tb: &str,
+	beg: Key,
+) -> Result<(Vec<(Thing, Value)>, Option<Key>), Error> {
+	let end = crate::key::thing::suffix(opt.ns(), opt.db(), tb);
+	let res = txn.lock().await.scan(beg..end, ALTER_BATCH_SIZE).await?;
+	// Continue after the last record of a full batch
+	let next = match res.last() {
+		Some((k, _)) if res.len() == ALTER_BATCH_SIZE as usize => {
+			let mut k = k.clone();
+			k.push(0x00);
+			Some(k)
+		}
+		_ => None,
+	};
+	let mut out = Vec::with_capacity(res.len());
+	for (k, v) in res.iter() {
+		let key: crate::key::thing::Thing = k.into();
+		let mut val: Value = v.into();
+		let rid = Thing::from((key.tb, key.id));
+		crate::doc::resolve(opt, txn, &rid, &mut val).await?;
+		out.push((rid, val));
+	}
+	Ok((out, next))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn alter_statement() {
+		let sql = "ALTER TABLE person SCHEMAFULL";
+		let res = alter(sql);
+		let out = res.unwrap().1;
+		assert!(matches!(out, AlterStatement::Table(_)));
+		assert_eq!(sql, format!("{}", out));
+	}
+
+	#[test]
+	fn alter_statement_invalid() {
+		let sql = "ALTER INDEX age ON person";
+		let res = alter(sql);
+		assert!(res.is_err());
+	}
+}
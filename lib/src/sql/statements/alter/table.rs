@@ -0,0 +1,260 @@
+use super::records;
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::iam::Action;
+use crate::iam::ResourceKind;
+use crate::sql::base::Base;
+use crate::sql::comment::shouldbespace;
+use crate::sql::ending;
+use crate::sql::error::expected;
+use crate::sql::error::tag_no_case;
+use crate::sql::error::IResult;
+use crate::sql::fmt::is_pretty;
+use crate::sql::fmt::pretty_indent;
+use crate::sql::ident::{ident, Ident};
+use crate::sql::idiom::Idiom;
+use crate::sql::permission::{permissions, Permissions};
+use crate::sql::statements::DefineFieldStatement;
+use crate::sql::strand::{strand, Strand};
+use crate::sql::value::Value;
+use derive::Store;
+use nom::branch::alt;
+use nom::combinator::cut;
+use nom::multi::many0;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Write};
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub struct AlterTableStatement {
+	pub name: Ident,
+	/// Whether the table becomes schemafull or schemaless
+	pub full: Option<bool>,
+	pub permissions: Option<Permissions>,
+	pub comment: Option<Strand>,
+}
+
+impl AlterTableStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		_ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		_doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		// Allowed to run?
+		opt.is_allowed(Action::Edit, ResourceKind::Table, &Base::Db)?;
+		// Fetch the existing definition
+		let mut tb = txn.lock().await.get_tb(opt.ns(), opt.db(), &self.name).await?;
+		// Check the existing records before the table becomes schemafull
+		if self.full == Some(true) && !tb.full {
+			self.validate(opt, txn).await?;
+		}
+		// Modify only the specified properties
+		if let Some(v) = self.full {
+			tb.full = v;
+		}
+		if let Some(v) = &self.permissions {
+			tb.permissions = v.clone();
+		}
+		if let Some(v) = &self.comment {
+			tb.comment = Some(v.clone());
+		}
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Clear the cache
+		run.clear_cache();
+		// Process the statement
+		let key = crate::key::database::tb::new(opt.ns(), opt.db(), &self.name);
+		run.set(key, &tb).await?;
+		// Update the view config held by each foreign table
+		if let Some(view) = &tb.view {
+			for v in view.what.0.iter() {
+				let key = crate::key::table::ft::new(opt.ns(), opt.db(), v, &self.name);
+				run.set(key, &tb).await?;
+				// Clear the cache
+				let key = crate::key::table::ft::prefix(opt.ns(), opt.db(), v);
+				run.clr(key).await?;
+			}
+		}
+		// Ok all good
+		Ok(Value::None)
+	}
+	/// Check that every field of the existing records is defined, as any fields which are
+	/// not would be removed from a record the next time it is updated
+	async fn validate(&self, opt: &Options, txn: &Transaction) -> Result<(), Error> {
+		let fds = txn.lock().await.all_tb_fields(opt.ns(), opt.db(), &self.name).await?;
+		let mut next = Some(crate::key::thing::prefix(opt.ns(), opt.db(), &self.name));
+		while let Some(beg) = next {
+			let (res, end) = records(opt, txn, &self.name, beg).await?;
+			for (rid, val) in res.iter() {
+				if let Some(field) = undefined(&fds, val) {
+					return Err(Error::FieldUndefined {
+						thing: rid.to_string(),
+						field,
+					});
+				}
+			}
+			next = end;
+		}
+		Ok(())
+	}
+}
+
+/// Returns the first field of a record which is not defined on its table, in the same way
+/// as the fields of a record on a schemafull table are cleaned when it is written
+fn undefined(fds: &[DefineFieldStatement], val: &Value) -> Option<Idiom> {
+	let mut keys: Vec<Idiom> = vec![];
+	for fd in fds.iter() {
+		match fd.flex {
+			false => keys.extend(val.each(&fd.name)),
+			true => keys.extend(val.every(Some(&fd.name), true, true)),
+		}
+	}
+	val.every(None, true, true)
+		.into_iter()
+		.find(|fd| !keys.contains(fd) && !(fd.is_id() || fd.is_in() || fd.is_out() || fd.is_meta()))
+}
+
+impl Display for AlterTableStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "ALTER TABLE {}", self.name)?;
+		if let Some(full) = self.full {
+			f.write_str(if full {
+				" SCHEMAFULL"
+			} else {
+				" SCHEMALESS"
+			})?;
+		}
+		if let Some(ref v) = self.comment {
+			write!(f, " COMMENT {v}")?
+		}
+		if let Some(ref v) = self.permissions {
+			let _indent = if is_pretty() {
+				Some(pretty_indent())
+			} else {
+				f.write_char(' ')?;
+				None
+			};
+			write!(f, "{v}")?;
+		}
+		Ok(())
+	}
+}
+
+pub fn table(i: &str) -> IResult<&str, AlterTableStatement> {
+	let (i, _) = tag_no_case("TABLE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, name) = cut(ident)(i)?;
+	let (i, opts) = many0(table_opts)(i)?;
+	let (i, _) = expected("SCHEMALESS, SCHEMAFUL(L), PERMISSIONS, or COMMENT", ending::query)(i)?;
+	// Create the base statement
+	let mut res = AlterTableStatement {
+		name,
+		..Default::default()
+	};
+	// Assign any altered options
+	for opt in opts {
+		match opt {
+			AlterTableOption::Schemafull => {
+				res.full = Some(true);
+			}
+			AlterTableOption::Schemaless => {
+				res.full = Some(false);
+			}
+			AlterTableOption::Comment(v) => {
+				res.comment = Some(v);
+			}
+			AlterTableOption::Permissions(v) => {
+				res.permissions = Some(v);
+			}
+		}
+	}
+	// Return the statement
+	Ok((i, res))
+}
+
+enum AlterTableOption {
+	Schemaless,
+	Schemafull,
+	Comment(Strand),
+	Permissions(Permissions),
+}
+
+fn table_opts(i: &str) -> IResult<&str, AlterTableOption> {
+	alt((table_comment, table_schemaless, table_schemafull, table_permissions))(i)
+}
+
+fn table_schemaless(i: &str) -> IResult<&str, AlterTableOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("SCHEMALESS")(i)?;
+	Ok((i, AlterTableOption::Schemaless))
+}
+
+fn table_schemafull(i: &str) -> IResult<&str, AlterTableOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = alt((tag_no_case("SCHEMAFULL"), tag_no_case("SCHEMAFUL")))(i)?;
+	Ok((i, AlterTableOption::Schemafull))
+}
+
+fn table_comment(i: &str) -> IResult<&str, AlterTableOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("COMMENT")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = strand(i)?;
+	Ok((i, AlterTableOption::Comment(v)))
+}
+
+fn table_permissions(i: &str) -> IResult<&str, AlterTableOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = permissions(i)?;
+	Ok((i, AlterTableOption::Permissions(v)))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::sql::test::Parse;
+
+	#[test]
+	fn alter_table() {
+		let sql = "TABLE person SCHEMAFULL COMMENT 'people' PERMISSIONS FOR select FULL, FOR create, update, delete NONE";
+		let res = table(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out.full, Some(true));
+		assert!(out.permissions.is_some());
+		assert_eq!(format!("ALTER {sql}"), format!("{}", out));
+
+		let serialized: Vec<u8> = (&out).try_into().unwrap();
+		let deserialized = AlterTableStatement::try_from(&serialized).unwrap();
+		assert_eq!(out, deserialized);
+	}
+
+	#[test]
+	fn alter_table_unchanged() {
+		let sql = "TABLE person COMMENT 'people'";
+		let res = table(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out.full, None);
+		assert_eq!(out.permissions, None);
+		assert_eq!(format!("ALTER {sql}"), format!("{}", out));
+	}
+
+	#[test]
+	fn alter_table_undefined_field() {
+		let fd = DefineFieldStatement {
+			name: Idiom::from(String::from("name")),
+			..Default::default()
+		};
+		let val = Value::parse("{ id: person:one, name: 'Tobie' }");
+		assert_eq!(undefined(&[fd.clone()], &val), None);
+		let val = Value::parse("{ id: person:one, name: 'Tobie', age: 34 }");
+		assert_eq!(undefined(&[fd], &val), Some(Idiom::from(String::from("age"))));
+	}
+}
@@ -8,13 +8,13 @@ use crate::idx::ft::FtIndex;
 use crate::idx::trees::store::TreeStoreType;
 use crate::idx::IndexKeyBase;
 use crate::sql::comment::shouldbespace;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::ident::{ident, Ident};
 use crate::sql::index::Index;
 use crate::sql::value::Value;
 use crate::sql::Base;
 use derive::Store;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
@@ -0,0 +1,102 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::iam::Action;
+use crate::iam::ResourceKind;
+use crate::sql::base::Base;
+use crate::sql::comment::shouldbespace;
+use crate::sql::datetime::Datetime;
+use crate::sql::error::expect_tag_no_case;
+use crate::sql::error::tag_no_case;
+use crate::sql::error::IResult;
+use crate::sql::value::Value;
+use derive::Store;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// ApplyStatement runs the migrations of the database which have not been
+// applied yet, in order of their version, and records them as applied.
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub struct ApplyStatement;
+
+impl ApplyStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		_doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		// Allowed to run?
+		opt.is_allowed(Action::Edit, ResourceKind::Database, &Base::Db)?;
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Find the migrations which have not been applied
+		let mut pending = vec![];
+		for mg in run.all_db_migrations(opt.ns(), opt.db()).await?.iter() {
+			let key = crate::key::database::ma::new(opt.ns(), opt.db(), mg.version);
+			if !run.exi(key).await? {
+				pending.push(mg.clone());
+			}
+		}
+		// Release the transaction
+		drop(run);
+		// Apply the migrations in order of their version
+		let mut out = vec![];
+		for mg in pending {
+			// Any failure cancels the whole transaction
+			if let Err(e) = mg.block.compute(ctx, opt, txn, None).await {
+				return Err(Error::MgFailed {
+					value: mg.version,
+					message: e.to_string(),
+				});
+			}
+			// Record the migration as applied
+			let key = crate::key::database::ma::new(opt.ns(), opt.db(), mg.version);
+			let val = Value::from(Datetime::default());
+			txn.lock().await.set(key, &val).await?;
+			out.push(Value::from(mg.version));
+		}
+		// Return the applied versions
+		Ok(out.into())
+	}
+}
+
+impl fmt::Display for ApplyStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("APPLY MIGRATIONS")
+	}
+}
+
+pub fn apply(i: &str) -> IResult<&str, ApplyStatement> {
+	let (i, _) = tag_no_case("APPLY")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = expect_tag_no_case("MIGRATIONS")(i)?;
+	Ok((i, ApplyStatement))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn apply_statement() {
+		let sql = "APPLY MIGRATIONS";
+		let res = apply(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+	}
+
+	#[test]
+	fn apply_statement_invalid() {
+		let sql = "APPLY MIGRATION";
+		let res = apply(sql);
+		assert!(res.is_err());
+	}
+}
@@ -0,0 +1,333 @@
+use crate::cnf::CHECK_BATCH_SIZE;
+use crate::ctx::Context;
+use crate::dbs::{Options, Transaction};
+use crate::doc::{things, unlink, CursorDoc};
+use crate::err::Error;
+use crate::iam::{Action, ResourceKind};
+use crate::sql::comment::shouldbespace;
+use crate::sql::error::tag_no_case;
+use crate::sql::error::{expected, IResult};
+use crate::sql::ident::{ident, Ident};
+use crate::sql::limit::{limit, Limit};
+use crate::sql::paths::{EDGE, IN, OUT};
+use crate::sql::start::{start, Start};
+use crate::sql::statements::{DefineFieldStatement, DeleteStatement, UpdateStatement};
+use crate::sql::thing::Thing;
+use crate::sql::value::{Value, Values};
+use crate::sql::{Array, Base, Data, Object};
+use derive::Store;
+use nom::branch::alt;
+use nom::combinator::{cut, opt, value};
+use nom::sequence::{preceded, tuple};
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub struct CheckStatement {
+	pub what: Ident,
+	pub fix: bool,
+	pub limit: Option<Limit>,
+	pub start: Option<Start>,
+	/// Whether the record link fields are checked, instead of the relations
+	pub references: bool,
+	/// Whether dangling record links are removed, instead of their records
+	pub set_null: bool,
+}
+
+impl CheckStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		// Allowed to run?
+		match self.fix {
+			true => opt.is_allowed(Action::Edit, ResourceKind::Table, &Base::Db)?,
+			false => opt.is_allowed(Action::View, ResourceKind::Table, &Base::Db)?,
+		}
+		// Check that the table exists
+		txn.lock().await.check_ns_db_tb(opt.ns(), opt.db(), &self.what, opt.strict).await?;
+		// Get the record link fields to check
+		let fds: Vec<DefineFieldStatement> = match self.references {
+			true => {
+				let fds = txn.lock().await.all_tb_fields(opt.ns(), opt.db(), &self.what).await?;
+				fds.iter()
+					.filter(|fd| matches!(&fd.kind, Some(k) if k.is_record()))
+					.cloned()
+					.collect()
+			}
+			false => vec![],
+		};
+		// Compute the number of records to check
+		let mut remaining = match &self.limit {
+			Some(v) => Some(v.process(ctx, opt, txn, doc).await?),
+			None => None,
+		};
+		// Continue after the record where a previous check stopped
+		let mut beg = crate::key::thing::prefix(opt.ns(), opt.db(), &self.what);
+		if let Some(v) = &self.start {
+			match v.0.compute(ctx, opt, txn, doc).await? {
+				Value::Thing(v) if v.tb == self.what.0 => {
+					beg = crate::key::thing::new(opt.ns(), opt.db(), &v.tb, &v.id).into();
+					beg.push(0x00);
+				}
+				v => {
+					return Err(Error::InvalidStart {
+						value: v.as_string(),
+					})
+				}
+			}
+		}
+		let end = crate::key::thing::suffix(opt.ns(), opt.db(), &self.what);
+		// Check the records in batches
+		let mut checked: usize = 0;
+		let mut dangling = Vec::new();
+		let mut next = None;
+		loop {
+			let num = match remaining {
+				Some(0) => break,
+				Some(v) => CHECK_BATCH_SIZE.min(v as u32),
+				None => CHECK_BATCH_SIZE,
+			};
+			let res = txn.lock().await.scan(beg.clone()..end.clone(), num).await?;
+			let mut fixes = Vec::new();
+			let mut nulls = Vec::new();
+			for (k, v) in res.iter() {
+				let key: crate::key::thing::Thing = k.into();
+				let val: Value = v.into();
+				let rid = Thing::from((key.tb, key.id));
+				checked += 1;
+				// Check the record link fields of the record
+				if self.references {
+					let mut sets = Vec::new();
+					for fd in fds.iter() {
+						let val = val.pick(&fd.name);
+						let mut links = Vec::new();
+						things(&val, &mut links);
+						let mut missing = Vec::new();
+						for v in links {
+							if !Self::exists(opt, txn, v).await? {
+								missing.push(Value::from(v.clone()));
+							}
+						}
+						if missing.is_empty() {
+							continue;
+						}
+						// Remove the dangling links from arrays, or empty the field
+						if let Some(v) = unlink(fd, &val, &missing) {
+							sets.extend(v);
+						}
+						dangling.push(Value::from(Object::from(map! {
+							"id".to_string() => Value::from(rid.clone()),
+							"field".to_string() => Value::from(fd.name.to_string()),
+							"missing".to_string() => Value::from(Array::from(missing)),
+						})));
+					}
+					if self.fix && !sets.is_empty() {
+						match self.set_null {
+							true => nulls.push((rid, sets)),
+							false => fixes.push(Value::from(rid)),
+						}
+					}
+					continue;
+				}
+				// Only edges have relations to check
+				if !val.pick(&*EDGE).is_true() {
+					continue;
+				}
+				let mut missing = Vec::new();
+				for v in [val.pick(&*IN), val.pick(&*OUT)] {
+					match v {
+						Value::Thing(v) if Self::exists(opt, txn, &v).await? => {}
+						v => missing.push(v),
+					}
+				}
+				if !missing.is_empty() {
+					if self.fix {
+						fixes.push(Value::from(rid.clone()));
+					}
+					dangling.push(Value::from(Object::from(map! {
+						"id".to_string() => Value::from(rid),
+						"missing".to_string() => Value::from(Array::from(missing)),
+					})));
+				}
+			}
+			// Remove the dangling edges, along with their graph pointers
+			if !fixes.is_empty() {
+				let stm = DeleteStatement {
+					what: Values(fixes),
+					..Default::default()
+				};
+				stm.compute(ctx, opt, txn, None).await?;
+			}
+			// Remove the dangling record links from the records
+			for (rid, sets) in nulls {
+				let stm = UpdateStatement {
+					what: Values(vec![Value::from(rid)]),
+					data: Some(Data::SetExpression(sets)),
+					..Default::default()
+				};
+				stm.compute(ctx, opt, txn, None).await?;
+			}
+			// Continue from the last record of the batch
+			let last = match res.last() {
+				Some((k, _)) if res.len() == num as usize => k.clone(),
+				_ => break,
+			};
+			remaining = remaining.map(|v| v - res.len());
+			if remaining == Some(0) {
+				let key: crate::key::thing::Thing = (&last).into();
+				next = Some(Thing::from((key.tb, key.id)));
+			}
+			beg = last;
+			beg.push(0x00);
+		}
+		// Output the report
+		Ok(Value::from(Object::from(map! {
+			"checked".to_string() => Value::from(checked),
+			"dangling".to_string() => Value::from(Array::from(dangling)),
+			"fixed".to_string() => Value::from(self.fix),
+			"next".to_string() => next.map(Value::from).unwrap_or_default(),
+		})))
+	}
+
+	/// Check if the target of a relation or record link exists, either in its table or in its archive
+	async fn exists(opt: &Options, txn: &Transaction, rid: &Thing) -> Result<bool, Error> {
+		let mut run = txn.lock().await;
+		let key = crate::key::thing::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
+		if run.exi(key).await? {
+			return Ok(true);
+		}
+		let key = crate::key::table::ar::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
+		run.exi(key).await
+	}
+}
+
+impl fmt::Display for CheckStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self.references {
+			true => write!(f, "CHECK REFERENCES {}", self.what)?,
+			false => write!(f, "CHECK RELATIONS {}", self.what)?,
+		}
+		match (self.fix, self.references, self.set_null) {
+			(true, true, true) => f.write_str(" FIX SET NULL")?,
+			(true, true, false) => f.write_str(" FIX DELETE")?,
+			(true, false, _) => f.write_str(" FIX")?,
+			_ => (),
+		}
+		if let Some(ref v) = self.limit {
+			write!(f, " {v}")?
+		}
+		if let Some(ref v) = self.start {
+			write!(f, " {v}")?
+		}
+		Ok(())
+	}
+}
+
+pub fn check(i: &str) -> IResult<&str, CheckStatement> {
+	let (i, _) = tag_no_case("CHECK")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, references) =
+		alt((value(false, tag_no_case("RELATIONS")), value(true, tag_no_case("REFERENCES"))))(i)?;
+	cut(|i| {
+		let (i, _) = shouldbespace(i)?;
+		let (i, what) = ident(i)?;
+		let (i, fix) = match references {
+			true => opt(preceded(tuple((shouldbespace, tag_no_case("FIX"))), check_fix))(i)?,
+			false => opt(value(false, preceded(shouldbespace, tag_no_case("FIX"))))(i)?,
+		};
+		let (i, limit) = opt(preceded(shouldbespace, limit))(i)?;
+		let (i, start) = opt(preceded(shouldbespace, start))(i)?;
+		Ok((
+			i,
+			CheckStatement {
+				what,
+				fix: fix.is_some(),
+				limit,
+				start,
+				references,
+				set_null: fix.unwrap_or_default(),
+			},
+		))
+	})(i)
+}
+
+fn check_fix(i: &str) -> IResult<&str, bool> {
+	let (i, _) = shouldbespace(i)?;
+	cut(expected(
+		"SET NULL or DELETE",
+		alt((
+			value(true, tuple((tag_no_case("SET"), shouldbespace, tag_no_case("NULL")))),
+			value(false, tag_no_case("DELETE")),
+		)),
+	))(i)
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn check_relations() {
+		let sql = "CHECK RELATIONS likes";
+		let res = check(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out.what, Ident::from("likes"));
+		assert!(!out.fix);
+		assert_eq!("CHECK RELATIONS likes", format!("{}", out));
+	}
+
+	#[test]
+	fn check_relations_fix_batch() {
+		let sql = "check relations likes fix limit 100 start at likes:tobie";
+		let res = check(sql);
+		let out = res.unwrap().1;
+		assert!(out.fix);
+		assert_eq!("CHECK RELATIONS likes FIX LIMIT 100 START likes:tobie", format!("{}", out));
+	}
+
+	#[test]
+	fn check_references_fix_batch() {
+		let sql = "CHECK REFERENCES person FIX SET NULL LIMIT 100 START person:tobie";
+		let res = check(sql);
+		let out = res.unwrap().1;
+		assert!(out.references);
+		assert!(out.fix);
+		assert!(out.set_null);
+		assert_eq!(
+			"CHECK REFERENCES person FIX SET NULL LIMIT 100 START person:tobie",
+			format!("{}", out)
+		);
+	}
+
+	#[test]
+	fn check_references_fix_delete() {
+		let sql = "check references person fix delete";
+		let res = check(sql);
+		let out = res.unwrap().1;
+		assert!(out.fix);
+		assert!(!out.set_null);
+		assert_eq!("CHECK REFERENCES person FIX DELETE", format!("{}", out));
+	}
+
+	#[test]
+	fn check_references_fix_invalid() {
+		let sql = "CHECK REFERENCES person FIX";
+		let res = check(sql);
+		assert!(res.is_err());
+	}
+
+	#[test]
+	fn check_relations_invalid() {
+		let sql = "CHECK RELATIONS";
+		let res = check(sql);
+		assert!(res.is_err());
+	}
+}
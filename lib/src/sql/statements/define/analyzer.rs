@@ -9,15 +9,16 @@ use crate::sql::base::Base;
 use crate::sql::comment::shouldbespace;
 use crate::sql::ending;
 use crate::sql::error::expected;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::filter::{filters, Filter};
 use crate::sql::ident::{ident, Ident};
+use crate::sql::statements::define::if_not_exists;
 use crate::sql::strand::{strand, Strand};
 use crate::sql::tokenizer::{tokenizers, Tokenizer};
 use crate::sql::value::Value;
 use derive::Store;
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use nom::multi::many0;
 use revision::revisioned;
@@ -25,12 +26,14 @@ use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct DefineAnalyzerStatement {
 	pub name: Ident,
 	pub tokenizers: Option<Vec<Tokenizer>>,
 	pub filters: Option<Vec<Filter>>,
 	pub comment: Option<Strand>,
+	#[revision(start = 2)]
+	pub if_not_exists: bool,
 }
 
 impl DefineAnalyzerStatement {
@@ -49,19 +52,34 @@ impl DefineAnalyzerStatement {
 		run.clear_cache();
 		// Process the statement
 		let key = crate::key::database::az::new(opt.ns(), opt.db(), &self.name);
+		// Check if the definition already exists
+		if self.if_not_exists && run.exi(key.clone()).await? {
+			return Ok(Value::None);
+		}
 		run.add_ns(opt.ns(), opt.strict).await?;
 		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
-		run.set(key, self).await?;
+		run.set(
+			key,
+			DefineAnalyzerStatement {
+				if_not_exists: false,
+				..self.clone()
+			},
+		)
+		.await?;
 		// Release the transaction
 		drop(run); // Do we really need this?
-		   // Ok all good
+			 // Ok all good
 		Ok(Value::None)
 	}
 }
 
 impl Display for DefineAnalyzerStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "DEFINE ANALYZER {}", self.name)?;
+		write!(f, "DEFINE ANALYZER")?;
+		if self.if_not_exists {
+			write!(f, " IF NOT EXISTS")?
+		}
+		write!(f, " {}", self.name)?;
 		if let Some(v) = &self.tokenizers {
 			let tokens: Vec<String> = v.iter().map(|f| f.to_string()).collect();
 			write!(f, " TOKENIZERS {}", tokens.join(","))?;
@@ -80,12 +98,14 @@ impl Display for DefineAnalyzerStatement {
 pub fn analyzer(i: &str) -> IResult<&str, DefineAnalyzerStatement> {
 	let (i, _) = tag_no_case("ANALYZER")(i)?;
 	let (i, _) = shouldbespace(i)?;
+	let (i, if_not_exists) = if_not_exists(i)?;
 	let (i, name) = cut(ident)(i)?;
 	let (i, opts) = many0(analyzer_opts)(i)?;
 	let (i, _) = expected("one of FILTERS, TOKENIZERS, or COMMENT", ending::query)(i)?;
 	// Create the base statement
 	let mut res = DefineAnalyzerStatement {
 		name,
+		if_not_exists,
 		..Default::default()
 	};
 	// Assign any defined options
@@ -0,0 +1,183 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::iam::Action;
+use crate::iam::ResourceKind;
+use crate::sql::base::Base;
+use crate::sql::comment::{mightbespace, shouldbespace};
+use crate::sql::ending;
+use crate::sql::error::expected;
+use crate::sql::error::tag_no_case;
+use crate::sql::error::IResult;
+use crate::sql::fmt::is_pretty;
+use crate::sql::fmt::pretty_indent;
+use crate::sql::ident::{ident, Ident};
+use crate::sql::object::object;
+use crate::sql::permission::{permission, Permission};
+use crate::sql::statements::define::if_not_exists;
+use crate::sql::strand::{strand, Strand};
+use crate::sql::value::Value;
+use derive::Store;
+use nom::branch::alt;
+use nom::combinator::cut;
+use nom::multi::many0;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Write};
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub struct DefineConfigStatement {
+	pub name: Ident,
+	/// The settings of this config, which is always an object
+	pub value: Value,
+	pub comment: Option<Strand>,
+	pub permissions: Permission,
+	pub if_not_exists: bool,
+}
+
+impl DefineConfigStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		// Allowed to run?
+		opt.is_allowed(Action::Edit, ResourceKind::Config, &Base::Db)?;
+		// Compute the settings once, so that they are stored as plain values
+		let value = self.value.compute(ctx, opt, txn, doc).await?;
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Clear the cache
+		run.clear_cache();
+		// Process the statement
+		let key = crate::key::database::cg::new(opt.ns(), opt.db(), &self.name);
+		// Check if the definition already exists
+		if self.if_not_exists && run.exi(key.clone()).await? {
+			return Ok(Value::None);
+		}
+		run.add_ns(opt.ns(), opt.strict).await?;
+		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
+		run.set(
+			key,
+			DefineConfigStatement {
+				value,
+				if_not_exists: false,
+				..self.clone()
+			},
+		)
+		.await?;
+		// Ok all good
+		Ok(Value::None)
+	}
+}
+
+impl Display for DefineConfigStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "DEFINE CONFIG")?;
+		if self.if_not_exists {
+			write!(f, " IF NOT EXISTS")?
+		}
+		write!(f, " {} {}", self.name, self.value)?;
+		if let Some(ref v) = self.comment {
+			write!(f, " COMMENT {v}")?
+		}
+		if !self.permissions.is_full() {
+			let _indent = if is_pretty() {
+				Some(pretty_indent())
+			} else {
+				f.write_char(' ')?;
+				None
+			};
+			write!(f, "PERMISSIONS {}", self.permissions)?;
+		}
+		Ok(())
+	}
+}
+
+pub fn config(i: &str) -> IResult<&str, DefineConfigStatement> {
+	let (i, _) = tag_no_case("CONFIG")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, if_not_exists) = if_not_exists(i)?;
+	let (i, name) = cut(ident)(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, value) = cut(expected("an object of settings", object))(i)?;
+	let (i, opts) = many0(config_opts)(i)?;
+	let (i, _) = expected("PERMISSIONS, or COMMENT", ending::query)(i)?;
+	// Create the base statement
+	let mut res = DefineConfigStatement {
+		name,
+		value: value.into(),
+		if_not_exists,
+		..Default::default()
+	};
+	// Assign any defined options
+	for opt in opts {
+		match opt {
+			DefineConfigOption::Comment(v) => {
+				res.comment = Some(v);
+			}
+			DefineConfigOption::Permissions(v) => {
+				res.permissions = v;
+			}
+		}
+	}
+	// Return the statement
+	Ok((i, res))
+}
+
+enum DefineConfigOption {
+	Comment(Strand),
+	Permissions(Permission),
+}
+
+fn config_opts(i: &str) -> IResult<&str, DefineConfigOption> {
+	alt((config_comment, config_permissions))(i)
+}
+
+fn config_comment(i: &str) -> IResult<&str, DefineConfigOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("COMMENT")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(strand)(i)?;
+	Ok((i, DefineConfigOption::Comment(v)))
+}
+
+fn config_permissions(i: &str) -> IResult<&str, DefineConfigOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("PERMISSIONS")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(permission)(i)?;
+	Ok((i, DefineConfigOption::Permissions(v)))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn define_config() {
+		let sql = "CONFIG webhooks { retries: 3, url: 'https://example.com/hook' } COMMENT 'outgoing hooks' PERMISSIONS NONE";
+		let res = config(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out.permissions, Permission::None);
+		assert_eq!(format!("DEFINE {sql}"), format!("{}", out));
+
+		let serialized: Vec<u8> = (&out).try_into().unwrap();
+		let deserialized = DefineConfigStatement::try_from(&serialized).unwrap();
+		assert_eq!(out, deserialized);
+	}
+
+	#[test]
+	fn define_config_not_object() {
+		let sql = "CONFIG webhooks 'https://example.com/hook'";
+		let res = config(sql);
+		assert!(res.is_err());
+	}
+}
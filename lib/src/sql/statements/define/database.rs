@@ -10,13 +10,14 @@ use crate::sql::changefeed::{changefeed, ChangeFeed};
 use crate::sql::comment::shouldbespace;
 use crate::sql::ending;
 use crate::sql::error::expected;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::ident::{ident, Ident};
+use crate::sql::statements::define::if_not_exists;
 use crate::sql::strand::{strand, Strand};
 use crate::sql::value::Value;
 use derive::Store;
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use nom::multi::many0;
 use revision::revisioned;
@@ -24,12 +25,14 @@ use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct DefineDatabaseStatement {
 	pub id: Option<u32>,
 	pub name: Ident,
 	pub comment: Option<Strand>,
 	pub changefeed: Option<ChangeFeed>,
+	#[revision(start = 2)]
+	pub if_not_exists: bool,
 }
 
 impl DefineDatabaseStatement {
@@ -49,17 +52,21 @@ impl DefineDatabaseStatement {
 		run.clear_cache();
 		// Process the statement
 		let key = crate::key::namespace::db::new(opt.ns(), &self.name);
+		// Check if the definition already exists
+		if self.if_not_exists && run.exi(key.clone()).await? {
+			return Ok(Value::None);
+		}
 		let ns = run.add_ns(opt.ns(), opt.strict).await?;
+		let mut db = DefineDatabaseStatement {
+			if_not_exists: false,
+			..self.clone()
+		};
 		// Set the id
-		if self.id.is_none() && ns.id.is_some() {
-			let mut db = self.clone();
+		if db.id.is_none() && ns.id.is_some() {
 			db.id = Some(run.get_next_db_id(ns.id.unwrap()).await?);
-			// Store the db
-			run.set(key, db).await?;
-		} else {
-			// Store the db
-			run.set(key, self).await?;
 		}
+		// Store the db
+		run.set(key, db).await?;
 		// Ok all good
 		Ok(Value::None)
 	}
@@ -67,7 +74,11 @@ impl DefineDatabaseStatement {
 
 impl Display for DefineDatabaseStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "DEFINE DATABASE {}", self.name)?;
+		write!(f, "DEFINE DATABASE")?;
+		if self.if_not_exists {
+			write!(f, " IF NOT EXISTS")?
+		}
+		write!(f, " {}", self.name)?;
 		if let Some(ref v) = self.comment {
 			write!(f, " COMMENT {v}")?
 		}
@@ -81,6 +92,7 @@ impl Display for DefineDatabaseStatement {
 pub fn database(i: &str) -> IResult<&str, DefineDatabaseStatement> {
 	let (i, _) = alt((tag_no_case("DB"), tag_no_case("DATABASE")))(i)?;
 	let (i, _) = shouldbespace(i)?;
+	let (i, if_not_exists) = if_not_exists(i)?;
 	let (i, name) = cut(ident)(i)?;
 	let (i, opts) = many0(database_opts)(i)?;
 	let (i, _) = expected("COMMENT or CHANGEFEED", ending::query)(i)?;
@@ -88,6 +100,7 @@ pub fn database(i: &str) -> IResult<&str, DefineDatabaseStatement> {
 	// Create the base statement
 	let mut res = DefineDatabaseStatement {
 		name,
+		if_not_exists,
 		..Default::default()
 	};
 	// Assign any defined options
@@ -10,13 +10,14 @@ use crate::sql::comment::shouldbespace;
 use crate::sql::ending;
 use crate::sql::error::expect_tag_no_case;
 use crate::sql::error::expected;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::ident::{ident, Ident};
+use crate::sql::statements::define::if_not_exists;
 use crate::sql::strand::{strand, Strand};
 use crate::sql::value::{value, values, Value, Values};
 use derive::Store;
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use nom::combinator::opt;
 use nom::multi::many0;
@@ -26,13 +27,15 @@ use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct DefineEventStatement {
 	pub name: Ident,
 	pub what: Ident,
 	pub when: Value,
 	pub then: Values,
 	pub comment: Option<Strand>,
+	#[revision(start = 2)]
+	pub if_not_exists: bool,
 }
 
 impl DefineEventStatement {
@@ -52,10 +55,21 @@ impl DefineEventStatement {
 		run.clear_cache();
 		// Process the statement
 		let key = crate::key::table::ev::new(opt.ns(), opt.db(), &self.what, &self.name);
+		// Check if the definition already exists
+		if self.if_not_exists && run.exi(key.clone()).await? {
+			return Ok(Value::None);
+		}
 		run.add_ns(opt.ns(), opt.strict).await?;
 		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
 		run.add_tb(opt.ns(), opt.db(), &self.what, opt.strict).await?;
-		run.set(key, self).await?;
+		run.set(
+			key,
+			DefineEventStatement {
+				if_not_exists: false,
+				..self.clone()
+			},
+		)
+		.await?;
 		// Clear the cache
 		let key = crate::key::table::ev::prefix(opt.ns(), opt.db(), &self.what);
 		run.clr(key).await?;
@@ -66,11 +80,11 @@ impl DefineEventStatement {
 
 impl Display for DefineEventStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(
-			f,
-			"DEFINE EVENT {} ON {} WHEN {} THEN {}",
-			self.name, self.what, self.when, self.then
-		)?;
+		write!(f, "DEFINE EVENT")?;
+		if self.if_not_exists {
+			write!(f, " IF NOT EXISTS")?
+		}
+		write!(f, " {} ON {} WHEN {} THEN {}", self.name, self.what, self.when, self.then)?;
 		if let Some(ref v) = self.comment {
 			write!(f, " COMMENT {v}")?
 		}
@@ -81,6 +95,7 @@ impl Display for DefineEventStatement {
 pub fn event(i: &str) -> IResult<&str, DefineEventStatement> {
 	let (i, _) = tag_no_case("EVENT")(i)?;
 	let (i, _) = shouldbespace(i)?;
+	let (i, if_not_exists) = if_not_exists(i)?;
 	let (i, (name, what, opts)) = cut(|i| {
 		let (i, name) = ident(i)?;
 		let (i, _) = shouldbespace(i)?;
@@ -97,6 +112,7 @@ pub fn event(i: &str) -> IResult<&str, DefineEventStatement> {
 		name,
 		what,
 		when: Value::Bool(true),
+		if_not_exists,
 		..Default::default()
 	};
 	// Assign any defined options
@@ -10,6 +10,7 @@ use crate::sql::comment::shouldbespace;
 use crate::sql::ending;
 use crate::sql::error::expect_tag_no_case;
 use crate::sql::error::expected;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::fmt::is_pretty;
 use crate::sql::fmt::pretty_indent;
@@ -18,11 +19,12 @@ use crate::sql::idiom;
 use crate::sql::idiom::Idiom;
 use crate::sql::kind::{kind, Kind};
 use crate::sql::permission::{permissions, Permissions};
+use crate::sql::reference::{reference, Reference};
+use crate::sql::statements::define::if_not_exists;
 use crate::sql::strand::{strand, Strand};
 use crate::sql::value::{value, Value};
 use derive::Store;
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use nom::combinator::opt;
 use nom::multi::many0;
@@ -32,7 +34,7 @@ use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Write};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 4)]
 pub struct DefineFieldStatement {
 	pub name: Idiom,
 	pub what: Ident,
@@ -43,6 +45,12 @@ pub struct DefineFieldStatement {
 	pub default: Option<Value>,
 	pub permissions: Permissions,
 	pub comment: Option<Strand>,
+	#[revision(start = 2)]
+	pub dedup: bool,
+	#[revision(start = 3)]
+	pub reference: Option<Reference>,
+	#[revision(start = 4)]
+	pub if_not_exists: bool,
 }
 
 impl DefineFieldStatement {
@@ -56,6 +64,20 @@ impl DefineFieldStatement {
 	) -> Result<Value, Error> {
 		// Allowed to run?
 		opt.is_allowed(Action::Edit, ResourceKind::Field, &Base::Db)?;
+		// Check the field can hold record links
+		if self.reference.is_some() && !self.kind.as_ref().is_some_and(Kind::is_record) {
+			return Err(Error::FieldReferenceType {
+				field: self.name.to_string(),
+			});
+		}
+		// Check the links can be removed from the field
+		if self.reference == Some(Reference::SetNull)
+			&& !matches!(self.kind, Some(Kind::Option(_) | Kind::Array(..) | Kind::Set(..)))
+		{
+			return Err(Error::FieldReferenceSetNull {
+				field: self.name.to_string(),
+			});
+		}
 		// Claim transaction
 		let mut run = txn.lock().await;
 		// Clear the cache
@@ -63,13 +85,40 @@ impl DefineFieldStatement {
 		// Process the statement
 		let fd = self.name.to_string();
 		let key = crate::key::table::fd::new(opt.ns(), opt.db(), &self.what, &fd);
+		// Check if the definition already exists
+		if self.if_not_exists && run.exi(key.clone()).await? {
+			return Ok(Value::None);
+		}
 		run.add_ns(opt.ns(), opt.strict).await?;
 		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
 		run.add_tb(opt.ns(), opt.db(), &self.what, opt.strict).await?;
-		run.set(key, self).await?;
+		// Check if the field was deduplicated or had an ON DELETE clause
+		let (dedup, reference) = match run.get(key.clone()).await? {
+			Some(v) => {
+				let fd = DefineFieldStatement::from(v);
+				(fd.dedup, fd.reference)
+			}
+			None => (false, None),
+		};
+		run.set(
+			key,
+			DefineFieldStatement {
+				if_not_exists: false,
+				..self.clone()
+			},
+		)
+		.await?;
 		// Clear the cache
 		let key = crate::key::table::fd::prefix(opt.ns(), opt.db(), &self.what);
 		run.clr(key).await?;
+		// Store the values of a field which is no longer deduplicated in its records
+		if dedup && !self.dedup {
+			crate::doc::restore(&mut run, opt.ns(), opt.db(), &self.what, &self.name).await?;
+		}
+		// Store the references of the existing records once an ON DELETE clause is added
+		if reference.is_none() && self.reference.is_some() {
+			crate::doc::backfill(&mut run, opt.ns(), opt.db(), &self.what, &self.name).await?;
+		}
 		// Ok all good
 		Ok(Value::None)
 	}
@@ -77,13 +126,20 @@ impl DefineFieldStatement {
 
 impl Display for DefineFieldStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "DEFINE FIELD {} ON {}", self.name, self.what)?;
+		write!(f, "DEFINE FIELD")?;
+		if self.if_not_exists {
+			write!(f, " IF NOT EXISTS")?
+		}
+		write!(f, " {} ON {}", self.name, self.what)?;
 		if self.flex {
 			write!(f, " FLEXIBLE")?
 		}
 		if let Some(ref v) = self.kind {
 			write!(f, " TYPE {v}")?
 		}
+		if let Some(ref v) = self.reference {
+			write!(f, " {v}")?
+		}
 		if let Some(ref v) = self.default {
 			write!(f, " DEFAULT {v}")?
 		}
@@ -93,6 +149,9 @@ impl Display for DefineFieldStatement {
 		if let Some(ref v) = self.assert {
 			write!(f, " ASSERT {v}")?
 		}
+		if self.dedup {
+			write!(f, " DEDUP")?
+		}
 		if let Some(ref v) = self.comment {
 			write!(f, " COMMENT {v}")?
 		}
@@ -112,6 +171,7 @@ impl Display for DefineFieldStatement {
 pub fn field(i: &str) -> IResult<&str, DefineFieldStatement> {
 	let (i, _) = tag_no_case("FIELD")(i)?;
 	let (i, _) = shouldbespace(i)?;
+	let (i, if_not_exists) = if_not_exists(i)?;
 	let (i, (name, what, opts)) = cut(|i| {
 		let (i, name) = idiom::local(i)?;
 		let (i, _) = shouldbespace(i)?;
@@ -121,7 +181,7 @@ pub fn field(i: &str) -> IResult<&str, DefineFieldStatement> {
 		let (i, what) = ident(i)?;
 		let (i, opts) = many0(field_opts)(i)?;
 		let (i, _) = expected(
-			"one of FLEX(IBLE), TYPE, VALUE, ASSERT, DEFAULT, or COMMENT",
+			"one of FLEX(IBLE), TYPE, ON DELETE, VALUE, ASSERT, DEFAULT, DEDUP, or COMMENT",
 			cut(ending::query),
 		)(i)?;
 		Ok((i, (name, what, opts)))
@@ -130,6 +190,7 @@ pub fn field(i: &str) -> IResult<&str, DefineFieldStatement> {
 	let mut res = DefineFieldStatement {
 		name,
 		what,
+		if_not_exists,
 		..Default::default()
 	};
 	// Assign any defined options
@@ -141,6 +202,9 @@ pub fn field(i: &str) -> IResult<&str, DefineFieldStatement> {
 			DefineFieldOption::Kind(v) => {
 				res.kind = Some(v);
 			}
+			DefineFieldOption::Reference(v) => {
+				res.reference = Some(v);
+			}
 			DefineFieldOption::Value(v) => {
 				res.value = Some(v);
 			}
@@ -150,6 +214,9 @@ pub fn field(i: &str) -> IResult<&str, DefineFieldStatement> {
 			DefineFieldOption::Default(v) => {
 				res.default = Some(v);
 			}
+			DefineFieldOption::Dedup => {
+				res.dedup = true;
+			}
 			DefineFieldOption::Comment(v) => {
 				res.comment = Some(v);
 			}
@@ -165,9 +232,11 @@ pub fn field(i: &str) -> IResult<&str, DefineFieldStatement> {
 enum DefineFieldOption {
 	Flex,
 	Kind(Kind),
+	Reference(Reference),
 	Value(Value),
 	Assert(Value),
 	Default(Value),
+	Dedup,
 	Comment(Strand),
 	Permissions(Permissions),
 }
@@ -176,9 +245,11 @@ fn field_opts(i: &str) -> IResult<&str, DefineFieldOption> {
 	alt((
 		field_flex,
 		field_kind,
+		field_reference,
 		field_value,
 		field_assert,
 		field_default,
+		field_dedup,
 		field_comment,
 		field_permissions,
 	))(i)
@@ -198,6 +269,12 @@ fn field_kind(i: &str) -> IResult<&str, DefineFieldOption> {
 	Ok((i, DefineFieldOption::Kind(v)))
 }
 
+fn field_reference(i: &str) -> IResult<&str, DefineFieldOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = reference(i)?;
+	Ok((i, DefineFieldOption::Reference(v)))
+}
+
 fn field_value(i: &str) -> IResult<&str, DefineFieldOption> {
 	let (i, _) = shouldbespace(i)?;
 	let (i, _) = tag_no_case("VALUE")(i)?;
@@ -222,6 +299,12 @@ fn field_default(i: &str) -> IResult<&str, DefineFieldOption> {
 	Ok((i, DefineFieldOption::Default(v)))
 }
 
+fn field_dedup(i: &str) -> IResult<&str, DefineFieldOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("DEDUP")(i)?;
+	Ok((i, DefineFieldOption::Dedup))
+}
+
 fn field_comment(i: &str) -> IResult<&str, DefineFieldOption> {
 	let (i, _) = shouldbespace(i)?;
 	let (i, _) = tag_no_case("COMMENT")(i)?;
@@ -13,6 +13,7 @@ use crate::sql::common::commas;
 use crate::sql::common::openparentheses;
 use crate::sql::ending;
 use crate::sql::error::expected;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::fmt::is_pretty;
 use crate::sql::fmt::pretty_indent;
@@ -20,13 +21,13 @@ use crate::sql::ident;
 use crate::sql::ident::{ident, Ident};
 use crate::sql::kind::{kind, Kind};
 use crate::sql::permission::{permission, Permission};
+use crate::sql::statements::define::if_not_exists;
 use crate::sql::strand::{strand, Strand};
 use crate::sql::util::delimited_list0;
 use crate::sql::value::Value;
 use derive::Store;
 use nom::branch::alt;
 use nom::bytes::complete::tag;
-use nom::bytes::complete::tag_no_case;
 use nom::character::complete::char;
 use nom::combinator::cut;
 use nom::multi::many0;
@@ -35,13 +36,15 @@ use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Write};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct DefineFunctionStatement {
 	pub name: Ident,
 	pub args: Vec<(Ident, Kind)>,
 	pub block: Block,
 	pub comment: Option<Strand>,
 	pub permissions: Permission,
+	#[revision(start = 2)]
+	pub if_not_exists: bool,
 }
 
 impl DefineFunctionStatement {
@@ -61,9 +64,20 @@ impl DefineFunctionStatement {
 		run.clear_cache();
 		// Process the statement
 		let key = crate::key::database::fc::new(opt.ns(), opt.db(), &self.name);
+		// Check if the definition already exists
+		if self.if_not_exists && run.exi(key.clone()).await? {
+			return Ok(Value::None);
+		}
 		run.add_ns(opt.ns(), opt.strict).await?;
 		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
-		run.set(key, self).await?;
+		run.set(
+			key,
+			DefineFunctionStatement {
+				if_not_exists: false,
+				..self.clone()
+			},
+		)
+		.await?;
 		// Ok all good
 		Ok(Value::None)
 	}
@@ -71,7 +85,11 @@ impl DefineFunctionStatement {
 
 impl fmt::Display for DefineFunctionStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "DEFINE FUNCTION fn::{}(", self.name)?;
+		write!(f, "DEFINE FUNCTION")?;
+		if self.if_not_exists {
+			write!(f, " IF NOT EXISTS")?
+		}
+		write!(f, " fn::{}(", self.name)?;
 		for (i, (name, kind)) in self.args.iter().enumerate() {
 			if i > 0 {
 				f.write_str(", ")?;
@@ -99,6 +117,7 @@ impl fmt::Display for DefineFunctionStatement {
 pub fn function(i: &str) -> IResult<&str, DefineFunctionStatement> {
 	let (i, _) = tag_no_case("FUNCTION")(i)?;
 	let (i, _) = shouldbespace(i)?;
+	let (i, if_not_exists) = if_not_exists(i)?;
 	let (i, _) = tag("fn::")(i)?;
 	let (i, name) = ident::multi(i)?;
 	let (i, _) = mightbespace(i)?;
@@ -125,6 +144,7 @@ pub fn function(i: &str) -> IResult<&str, DefineFunctionStatement> {
 		name,
 		args,
 		block,
+		if_not_exists,
 		..Default::default()
 	};
 	// Assign any defined options
@@ -5,38 +5,60 @@ use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::iam::Action;
 use crate::iam::ResourceKind;
+use crate::idx::builder::BuildingState;
+use crate::idx::stats::IndexStats;
+use crate::sql::array::Array;
 use crate::sql::base::Base;
 use crate::sql::comment::shouldbespace;
+use crate::sql::common::commas;
+use crate::sql::cond::{cond, Cond};
 use crate::sql::ending;
 use crate::sql::error::expect_tag_no_case;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::ident::{ident, Ident};
 use crate::sql::idiom;
-use crate::sql::idiom::Idioms;
+use crate::sql::idiom::{Idiom, Idioms};
 use crate::sql::index;
 use crate::sql::index::Index;
+use crate::sql::part::Part;
+use crate::sql::statements::define::if_not_exists;
 use crate::sql::statements::UpdateStatement;
 use crate::sql::strand::{strand, Strand};
-use crate::sql::value::{Value, Values};
+use crate::sql::subquery::Subquery;
+use crate::sql::value::{value, Value, Values};
 use derive::Store;
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use nom::combinator::opt;
+use nom::combinator::recognize;
 use nom::multi::many0;
+use nom::multi::separated_list1;
 use nom::sequence::tuple;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::fmt::{self, Display};
+use unicode_normalization::UnicodeNormalization;
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 5)]
 pub struct DefineIndexStatement {
 	pub name: Ident,
 	pub what: Ident,
 	pub cols: Idioms,
 	pub index: Index,
 	pub comment: Option<Strand>,
+	#[revision(start = 2)]
+	pub concurrently: bool,
+	#[revision(start = 3)]
+	pub cond: Option<Cond>,
+	#[revision(start = 4)]
+	pub collate: bool,
+	#[revision(start = 4)]
+	pub numeric: bool,
+	#[revision(start = 5)]
+	pub if_not_exists: bool,
 }
 
 impl DefineIndexStatement {
@@ -56,16 +78,36 @@ impl DefineIndexStatement {
 		run.clear_cache();
 		// Process the statement
 		let key = crate::key::table::ix::new(opt.ns(), opt.db(), &self.what, &self.name);
+		// Check if the definition already exists
+		if self.if_not_exists && run.exi(key.clone()).await? {
+			return Ok(Value::None);
+		}
 		run.add_ns(opt.ns(), opt.strict).await?;
 		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
 		run.add_tb(opt.ns(), opt.db(), &self.what, opt.strict).await?;
-		run.set(key, self).await?;
+		run.set(
+			key,
+			DefineIndexStatement {
+				if_not_exists: false,
+				..self.clone()
+			},
+		)
+		.await?;
 		// Remove the index data
 		let key = crate::key::index::all::new(opt.ns(), opt.db(), &self.what, &self.name);
 		run.delp(key, u32::MAX).await?;
+		// Reset the index statistics
+		IndexStats::del(&mut run, opt.ns(), opt.db(), self).await?;
 		// Clear the cache
 		let key = crate::key::table::ix::prefix(opt.ns(), opt.db(), &self.what);
 		run.clr(key).await?;
+		// Build the index in the background
+		if self.concurrently {
+			BuildingState::default().set(&mut run, opt.ns(), opt.db(), self).await?;
+			return Ok(Value::None);
+		}
+		// Remove any background build state
+		BuildingState::del(&mut run, opt.ns(), opt.db(), self).await?;
 		// Release the transaction
 		drop(run);
 		// Force queries to run
@@ -82,20 +124,110 @@ impl DefineIndexStatement {
 			..UpdateStatement::default()
 		};
 		stm.compute(ctx, opt, txn, doc).await?;
+		// Record when the index was built
+		let mut run = txn.lock().await;
+		IndexStats::built(&mut run, opt.ns(), opt.db(), self).await?;
 		// Ok all good
 		Ok(Value::None)
 	}
+
+	/// Normalises the string values of an index entry, so that
+	/// values which are equal under the collation of the index have the same key
+	pub(crate) fn collated<'a>(&self, a: &'a Array) -> Cow<'a, Array> {
+		if !self.collate && !self.numeric {
+			return Cow::Borrowed(a);
+		}
+		Cow::Owned(a.iter().map(|v| self.collated_value(v)).collect())
+	}
+
+	fn collated_value(&self, v: &Value) -> Value {
+		match v {
+			Value::Strand(s) => {
+				let mut s = s.as_str().to_owned();
+				// Unicode case folding
+				if self.collate {
+					s = fold_case(&s);
+				}
+				// Leading zeros of numbers are not significant
+				if self.numeric {
+					s = strip_leading_zeros(&s);
+				}
+				Value::from(s)
+			}
+			Value::Array(a) => Value::Array(self.collated(a).into_owned()),
+			v => v.to_owned(),
+		}
+	}
+}
+
+/// Folds the case of a string, and normalises it to its canonical composed form, so that
+/// strings which only differ in case, or in how their accented characters are encoded, are equal.
+/// Besides lowercasing, the full case folding of `ß` to `ss`, and of the final sigma, is applied.
+fn fold_case(s: &str) -> String {
+	let mut out = String::with_capacity(s.len());
+	for c in s.nfd().flat_map(char::to_lowercase) {
+		match c {
+			'ß' => out.push_str("ss"),
+			'ς' => out.push('σ'),
+			c => out.push(c),
+		}
+	}
+	out.nfc().collect()
+}
+
+/// Removes the leading zeros of the integer part of every number, keeping at least one digit.
+/// The digits after a decimal point are kept, so that "1.05" and "1.5" remain distinct.
+fn strip_leading_zeros(s: &str) -> String {
+	let mut out = String::with_capacity(s.len());
+	let mut chars = s.chars().peekable();
+	let mut prev = None;
+	let mut fraction = false;
+	while let Some(c) = chars.next() {
+		if c.is_ascii_digit() {
+			// Check whether a new run of digits follows a decimal point
+			if !prev.is_some_and(|p: char| p.is_ascii_digit()) {
+				fraction = prev == Some('.');
+			}
+			if c == '0' && !fraction && !out.ends_with(|p: char| p.is_ascii_digit()) {
+				if let Some(n) = chars.peek() {
+					if n.is_ascii_digit() {
+						prev = Some(c);
+						continue;
+					}
+				}
+			}
+		}
+		prev = Some(c);
+		out.push(c);
+	}
+	out
 }
 
 impl Display for DefineIndexStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "DEFINE INDEX {} ON {} FIELDS {}", self.name, self.what, self.cols)?;
+		write!(f, "DEFINE INDEX")?;
+		if self.if_not_exists {
+			write!(f, " IF NOT EXISTS")?
+		}
+		write!(f, " {} ON {} FIELDS {}", self.name, self.what, self.cols)?;
+		if let Some(ref v) = self.cond {
+			write!(f, " {v}")?
+		}
 		if Index::Idx != self.index {
 			write!(f, " {}", self.index)?;
 		}
+		if self.collate {
+			write!(f, " COLLATE")?
+		}
+		if self.numeric {
+			write!(f, " NUMERIC")?
+		}
 		if let Some(ref v) = self.comment {
 			write!(f, " COMMENT {v}")?
 		}
+		if self.concurrently {
+			write!(f, " CONCURRENTLY")?
+		}
 		Ok(())
 	}
 }
@@ -103,6 +235,7 @@ impl Display for DefineIndexStatement {
 pub fn index(i: &str) -> IResult<&str, DefineIndexStatement> {
 	let (i, _) = tag_no_case("INDEX")(i)?;
 	let (i, _) = shouldbespace(i)?;
+	let (i, if_not_exists) = if_not_exists(i)?;
 	let (i, (name, what, opts)) = cut(|i| {
 		let (i, name) = ident(i)?;
 		let (i, _) = shouldbespace(i)?;
@@ -118,6 +251,7 @@ pub fn index(i: &str) -> IResult<&str, DefineIndexStatement> {
 	let mut res = DefineIndexStatement {
 		name,
 		what,
+		if_not_exists,
 		..Default::default()
 	};
 	// Assign any defined options
@@ -132,6 +266,18 @@ pub fn index(i: &str) -> IResult<&str, DefineIndexStatement> {
 			DefineIndexOption::Comment(v) => {
 				res.comment = Some(v);
 			}
+			DefineIndexOption::Concurrently => {
+				res.concurrently = true;
+			}
+			DefineIndexOption::Cond(v) => {
+				res.cond = Some(v);
+			}
+			DefineIndexOption::Collate => {
+				res.collate = true;
+			}
+			DefineIndexOption::Numeric => {
+				res.numeric = true;
+			}
 		}
 	}
 	// Check necessary options
@@ -146,10 +292,22 @@ enum DefineIndexOption {
 	Index(Index),
 	Columns(Idioms),
 	Comment(Strand),
+	Concurrently,
+	Cond(Cond),
+	Collate,
+	Numeric,
 }
 
 fn index_opts(i: &str) -> IResult<&str, DefineIndexOption> {
-	alt((index_kind, index_columns, index_comment))(i)
+	alt((
+		index_kind,
+		index_columns,
+		index_cond,
+		index_collate,
+		index_numeric,
+		index_comment,
+		index_concurrently,
+	))(i)
 }
 
 fn index_kind(i: &str) -> IResult<&str, DefineIndexOption> {
@@ -162,8 +320,39 @@ fn index_columns(i: &str) -> IResult<&str, DefineIndexOption> {
 	let (i, _) = shouldbespace(i)?;
 	let (i, _) = alt((tag_no_case("COLUMNS"), tag_no_case("FIELDS")))(i)?;
 	let (i, _) = shouldbespace(i)?;
-	let (i, v) = idiom::locals(i)?;
-	Ok((i, DefineIndexOption::Columns(v)))
+	let (i, v) = separated_list1(commas, index_column)(i)?;
+	Ok((i, DefineIndexOption::Columns(Idioms(v))))
+}
+
+/// A column is either a local idiom, or any expression computed from the document
+fn index_column(i: &str) -> IResult<&str, Idiom> {
+	let (o, v) = value(i)?;
+	match v {
+		Value::Idiom(_) => idiom::local(i),
+		Value::Subquery(v) => match *v {
+			Subquery::Value(v) => Ok((o, Idiom::from(vec![Part::Start(v)]))),
+			v => Ok((o, Idiom::from(vec![Part::Start(Value::from(v))]))),
+		},
+		v => Ok((o, Idiom::from(vec![Part::Start(v)]))),
+	}
+}
+
+fn index_cond(i: &str) -> IResult<&str, DefineIndexOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cond(i)?;
+	Ok((i, DefineIndexOption::Cond(v)))
+}
+
+fn index_collate(i: &str) -> IResult<&str, DefineIndexOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("COLLATE")(i)?;
+	Ok((i, DefineIndexOption::Collate))
+}
+
+fn index_numeric(i: &str) -> IResult<&str, DefineIndexOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("NUMERIC")(i)?;
+	Ok((i, DefineIndexOption::Numeric))
 }
 
 fn index_comment(i: &str) -> IResult<&str, DefineIndexOption> {
@@ -174,11 +363,20 @@ fn index_comment(i: &str) -> IResult<&str, DefineIndexOption> {
 	Ok((i, DefineIndexOption::Comment(v)))
 }
 
+fn index_concurrently(i: &str) -> IResult<&str, DefineIndexOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = alt((
+		tag_no_case("CONCURRENTLY"),
+		recognize(tuple((tag_no_case("IN"), shouldbespace, tag_no_case("BACKGROUND")))),
+	))(i)?;
+	Ok((i, DefineIndexOption::Concurrently))
+}
+
 #[cfg(test)]
 mod tests {
 
 	use super::*;
-	use crate::sql::index::SearchParams;
+	use crate::sql::index::{Distance, HnswParams, Nulls, RTreeParams, SearchParams};
 	use crate::sql::Ident;
 	use crate::sql::Idiom;
 	use crate::sql::Idioms;
@@ -198,6 +396,11 @@ mod tests {
 				cols: Idioms(vec![Idiom(vec![Part::Field(Ident("my_col".to_string()))])]),
 				index: Index::Idx,
 				comment: None,
+				concurrently: false,
+				cond: None,
+				collate: false,
+				numeric: false,
+				if_not_exists: false,
 			}
 		);
 		assert_eq!(idx.to_string(), "DEFINE INDEX my_index ON my_table FIELDS my_col");
@@ -215,11 +418,135 @@ mod tests {
 				cols: Idioms(vec![Idiom(vec![Part::Field(Ident("my_col".to_string()))])]),
 				index: Index::Uniq,
 				comment: None,
+				concurrently: false,
+				cond: None,
+				collate: false,
+				numeric: false,
+				if_not_exists: false,
 			}
 		);
 		assert_eq!(idx.to_string(), "DEFINE INDEX my_index ON my_table FIELDS my_col UNIQUE");
 	}
 
+	#[test]
+	fn check_create_index_concurrently() {
+		let sql = "INDEX my_index ON TABLE my_table COLUMNS my_col UNIQUE CONCURRENTLY";
+		let (_, idx) = index(sql).unwrap();
+		assert!(idx.concurrently);
+		assert_eq!(
+			idx.to_string(),
+			"DEFINE INDEX my_index ON my_table FIELDS my_col UNIQUE CONCURRENTLY"
+		);
+		let sql = "INDEX my_index ON TABLE my_table COLUMNS my_col IN BACKGROUND";
+		let (_, idx) = index(sql).unwrap();
+		assert!(idx.concurrently);
+		assert_eq!(idx.to_string(), "DEFINE INDEX my_index ON my_table FIELDS my_col CONCURRENTLY");
+	}
+
+	#[test]
+	fn check_create_expression_index() {
+		let sql = "INDEX my_index ON my_table COLUMNS string::lowercase(email), time::floor(created, 1d) UNIQUE";
+		let (_, idx) = index(sql).unwrap();
+		assert_eq!(idx.cols.len(), 2);
+		assert!(matches!(idx.cols[0].first(), Some(Part::Start(Value::Function(_)))));
+		assert_eq!(
+			idx.to_string(),
+			"DEFINE INDEX my_index ON my_table FIELDS string::lowercase(email), time::floor(created, 1d) UNIQUE"
+		);
+	}
+
+	#[test]
+	fn check_create_computed_expression_index() {
+		let sql = "INDEX my_index ON my_table COLUMNS price * quantity, (total - discount), name";
+		let (_, idx) = index(sql).unwrap();
+		assert_eq!(idx.cols.len(), 3);
+		assert!(matches!(idx.cols[0].first(), Some(Part::Start(Value::Expression(_)))));
+		assert!(matches!(idx.cols[1].first(), Some(Part::Start(Value::Expression(_)))));
+		assert!(matches!(idx.cols[2].first(), Some(Part::Field(_))));
+		assert_eq!(
+			idx.to_string(),
+			"DEFINE INDEX my_index ON my_table FIELDS price * quantity, total - discount, name"
+		);
+	}
+
+	#[test]
+	fn check_create_partial_index() {
+		let sql = "INDEX active_email ON user COLUMNS email WHERE active = true UNIQUE";
+		let (_, idx) = index(sql).unwrap();
+		assert!(idx.cond.is_some());
+		assert_eq!(idx.index, Index::Uniq);
+		assert_eq!(
+			idx.to_string(),
+			"DEFINE INDEX active_email ON user FIELDS email WHERE active = true UNIQUE"
+		);
+		let serialized: Vec<u8> = (&idx).try_into().unwrap();
+		let deserialized = DefineIndexStatement::try_from(&serialized).unwrap();
+		assert_eq!(idx, deserialized);
+	}
+
+	#[test]
+	fn check_create_unique_index_nulls() {
+		let sql = "INDEX my_index ON my_table COLUMNS a, b UNIQUE NULLS NOT DISTINCT";
+		let (_, idx) = index(sql).unwrap();
+		assert_eq!(idx.index, Index::UniqNulls(Nulls::NotDistinct));
+		assert_eq!(
+			idx.to_string(),
+			"DEFINE INDEX my_index ON my_table FIELDS a, b UNIQUE NULLS NOT DISTINCT"
+		);
+		let sql = "INDEX my_index ON my_table COLUMNS a, b UNIQUE NULLS DISTINCT COMMENT 'test'";
+		let (_, idx) = index(sql).unwrap();
+		assert_eq!(idx.index, Index::UniqNulls(Nulls::Distinct));
+		assert_eq!(
+			idx.to_string(),
+			"DEFINE INDEX my_index ON my_table FIELDS a, b UNIQUE NULLS DISTINCT COMMENT 'test'"
+		);
+		let serialized: Vec<u8> = (&idx).try_into().unwrap();
+		let deserialized = DefineIndexStatement::try_from(&serialized).unwrap();
+		assert_eq!(idx, deserialized);
+		assert!(index("INDEX my_index ON my_table COLUMNS a UNIQUE NULLS").is_err());
+	}
+
+	#[test]
+	fn check_create_collated_index() {
+		let sql = "INDEX my_index ON my_table COLUMNS name UNIQUE COLLATE NUMERIC";
+		let (_, idx) = index(sql).unwrap();
+		assert!(idx.collate);
+		assert!(idx.numeric);
+		assert_eq!(
+			idx.to_string(),
+			"DEFINE INDEX my_index ON my_table FIELDS name UNIQUE COLLATE NUMERIC"
+		);
+		let serialized: Vec<u8> = (&idx).try_into().unwrap();
+		let deserialized = DefineIndexStatement::try_from(&serialized).unwrap();
+		assert_eq!(idx, deserialized);
+		let a = Array::from(vec![Value::from("Ünïcode File007"), Value::from(10)]);
+		let c = idx.collated(&a);
+		assert_eq!(
+			c.into_owned(),
+			Array::from(vec![Value::from("ünïcode file7"), Value::from(10)])
+		);
+	}
+
+	#[test]
+	fn check_fold_case() {
+		assert_eq!(fold_case("Straße"), fold_case("STRASSE"));
+		assert_eq!(fold_case("ΟΔΟΣ"), fold_case("οδος"));
+		// Composed and decomposed accents are equal
+		assert_eq!(fold_case("Caf\u{e9}"), fold_case("CAFE\u{301}"));
+		assert_eq!(fold_case("Caf\u{e9}"), "caf\u{e9}");
+	}
+
+	#[test]
+	fn check_strip_leading_zeros() {
+		assert_eq!(strip_leading_zeros("007"), "7");
+		assert_eq!(strip_leading_zeros("0"), "0");
+		assert_eq!(strip_leading_zeros("x000"), "x0");
+		assert_eq!(strip_leading_zeros("v01.05.100"), "v1.05.100");
+		assert_eq!(strip_leading_zeros("1.05"), "1.05");
+		assert_eq!(strip_leading_zeros("001.5"), "1.5");
+		assert_eq!(strip_leading_zeros(".05"), ".05");
+	}
+
 	#[test]
 	fn check_create_search_index_with_highlights() {
 		let sql = "INDEX my_index ON TABLE my_table COLUMNS my_col SEARCH ANALYZER my_analyzer BM25(1.2,0.75) DOC_IDS_ORDER 1000 DOC_LENGTHS_ORDER 1000 POSTINGS_ORDER 1000 TERMS_ORDER 1000 HIGHLIGHTS";
@@ -243,6 +570,11 @@ mod tests {
 					terms_order: 1000,
 				}),
 				comment: None,
+				concurrently: false,
+				cond: None,
+				collate: false,
+				numeric: false,
+				if_not_exists: false,
 			}
 		);
 		assert_eq!(idx.to_string(), "DEFINE INDEX my_index ON my_table FIELDS my_col SEARCH ANALYZER my_analyzer BM25(1.2,0.75) DOC_IDS_ORDER 1000 DOC_LENGTHS_ORDER 1000 POSTINGS_ORDER 1000 TERMS_ORDER 1000 HIGHLIGHTS");
@@ -268,6 +600,11 @@ mod tests {
 					terms_order: 100,
 				}),
 				comment: None,
+				concurrently: false,
+				cond: None,
+				collate: false,
+				numeric: false,
+				if_not_exists: false,
 			}
 		);
 		assert_eq!(
@@ -275,4 +612,64 @@ mod tests {
 			"DEFINE INDEX my_index ON my_table FIELDS my_col SEARCH ANALYZER my_analyzer VS DOC_IDS_ORDER 100 DOC_LENGTHS_ORDER 100 POSTINGS_ORDER 100 TERMS_ORDER 100"
 		);
 	}
+
+	#[test]
+	fn check_create_hnsw_index() {
+		let sql =
+			"INDEX my_index ON TABLE my_table COLUMNS my_col HNSW DIMENSION 4 DIST COSINE M 8";
+		let (_, idx) = index(sql).unwrap();
+		assert_eq!(
+			idx,
+			DefineIndexStatement {
+				name: Ident("my_index".to_string()),
+				what: Ident("my_table".to_string()),
+				cols: Idioms(vec![Idiom(vec![Part::Field(Ident("my_col".to_string()))])]),
+				index: Index::Hnsw(HnswParams {
+					dimension: 4,
+					distance: Distance::Cosine,
+					m: 8,
+					ef_construction: 150,
+					doc_ids_order: 100,
+				}),
+				comment: None,
+				concurrently: false,
+				cond: None,
+				collate: false,
+				numeric: false,
+				if_not_exists: false,
+			}
+		);
+		assert_eq!(
+			idx.to_string(),
+			"DEFINE INDEX my_index ON my_table FIELDS my_col HNSW DIMENSION 4 DIST COSINE M 8 EFC 150 DOC_IDS_ORDER 100"
+		);
+	}
+
+	#[test]
+	fn check_create_rtree_index() {
+		let sql = "INDEX my_index ON TABLE my_table COLUMNS location RTREE CAPACITY 8";
+		let (_, idx) = index(sql).unwrap();
+		assert_eq!(
+			idx,
+			DefineIndexStatement {
+				name: Ident("my_index".to_string()),
+				what: Ident("my_table".to_string()),
+				cols: Idioms(vec![Idiom(vec![Part::Field(Ident("location".to_string()))])]),
+				index: Index::RTree(RTreeParams {
+					capacity: 8,
+					doc_ids_order: 100,
+				}),
+				comment: None,
+				concurrently: false,
+				cond: None,
+				collate: false,
+				numeric: false,
+				if_not_exists: false,
+			}
+		);
+		assert_eq!(
+			idx.to_string(),
+			"DEFINE INDEX my_index ON my_table FIELDS location RTREE CAPACITY 8 DOC_IDS_ORDER 100"
+		);
+	}
 }
@@ -0,0 +1,151 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::iam::Action;
+use crate::iam::ResourceKind;
+use crate::sql::base::Base;
+use crate::sql::block::{block, Block};
+use crate::sql::comment::{mightbespace, shouldbespace};
+use crate::sql::ending;
+use crate::sql::error::expected;
+use crate::sql::error::tag_no_case;
+use crate::sql::error::IResult;
+use crate::sql::statements::define::if_not_exists;
+use crate::sql::strand::{strand, Strand};
+use crate::sql::value::Value;
+use derive::Store;
+use nom::character::complete::u64 as uint64;
+use nom::combinator::{cut, opt};
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub struct DefineMigrationStatement {
+	/// The version of the migration, which orders the migrations
+	pub version: u64,
+	pub block: Block,
+	pub comment: Option<Strand>,
+	pub if_not_exists: bool,
+}
+
+impl DefineMigrationStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		_ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		_doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		// Allowed to run?
+		opt.is_allowed(Action::Edit, ResourceKind::Database, &Base::Db)?;
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Clear the cache
+		run.clear_cache();
+		// Check if the definition already exists
+		let key = crate::key::database::mg::new(opt.ns(), opt.db(), self.version);
+		if self.if_not_exists && run.exi(key).await? {
+			return Ok(Value::None);
+		}
+		// An applied migration can only be defined again unchanged
+		let key = crate::key::database::ma::new(opt.ns(), opt.db(), self.version);
+		if run.exi(key).await? {
+			let key = crate::key::database::mg::new(opt.ns(), opt.db(), self.version);
+			if let Some(v) = run.get(key).await? {
+				if DefineMigrationStatement::from(v).block != self.block {
+					return Err(Error::MgApplied {
+						value: self.version,
+					});
+				}
+			}
+		}
+		// Process the statement
+		let key = crate::key::database::mg::new(opt.ns(), opt.db(), self.version);
+		run.add_ns(opt.ns(), opt.strict).await?;
+		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
+		run.set(
+			key,
+			DefineMigrationStatement {
+				if_not_exists: false,
+				..self.clone()
+			},
+		)
+		.await?;
+		// Ok all good
+		Ok(Value::None)
+	}
+}
+
+impl Display for DefineMigrationStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "DEFINE MIGRATION")?;
+		if self.if_not_exists {
+			write!(f, " IF NOT EXISTS")?
+		}
+		write!(f, " {} ", self.version)?;
+		Display::fmt(&self.block, f)?;
+		if let Some(ref v) = self.comment {
+			write!(f, " COMMENT {v}")?
+		}
+		Ok(())
+	}
+}
+
+pub fn migration(i: &str) -> IResult<&str, DefineMigrationStatement> {
+	let (i, _) = tag_no_case("MIGRATION")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, if_not_exists) = if_not_exists(i)?;
+	let (i, version) = cut(expected("a positive integer", uint64))(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, block) = cut(block)(i)?;
+	let (i, comment) = opt(migration_comment)(i)?;
+	let (i, _) = expected("COMMENT", ending::query)(i)?;
+	Ok((
+		i,
+		DefineMigrationStatement {
+			version,
+			block,
+			comment,
+			if_not_exists,
+		},
+	))
+}
+
+fn migration_comment(i: &str) -> IResult<&str, Strand> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("COMMENT")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	cut(strand)(i)
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn define_migration() {
+		let sql = "MIGRATION 1 { DEFINE TABLE person SCHEMAFULL; } COMMENT 'people'";
+		let res = migration(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out.version, 1);
+		assert_eq!(out.block.len(), 1);
+		assert_eq!(format!("DEFINE {sql}"), format!("{}", out));
+
+		let serialized: Vec<u8> = (&out).try_into().unwrap();
+		let deserialized = DefineMigrationStatement::try_from(&serialized).unwrap();
+		assert_eq!(out, deserialized);
+	}
+
+	#[test]
+	fn define_migration_invalid_version() {
+		let sql = "MIGRATION latest { DEFINE TABLE person; }";
+		let res = migration(sql);
+		assert!(res.is_err());
+	}
+}
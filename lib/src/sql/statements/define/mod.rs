@@ -1,26 +1,32 @@
 mod analyzer;
+mod config;
 mod database;
 mod event;
 mod field;
 mod function;
 mod index;
+mod migration;
 mod namespace;
 mod param;
 mod scope;
+mod sequence;
 mod table;
 mod token;
 mod user;
 
+use crate::sql::error::tag_no_case;
 pub use analyzer::{analyzer, DefineAnalyzerStatement};
+pub use config::{config, DefineConfigStatement};
 pub use database::{database, DefineDatabaseStatement};
 pub use event::{event, DefineEventStatement};
 pub use field::{field, DefineFieldStatement};
 pub use function::{function, DefineFunctionStatement};
 pub use index::{index, DefineIndexStatement};
+pub use migration::{migration, DefineMigrationStatement};
 pub use namespace::{namespace, DefineNamespaceStatement};
-use nom::bytes::complete::tag_no_case;
 pub use param::{param, DefineParamStatement};
 pub use scope::{scope, DefineScopeStatement};
+pub use sequence::{sequence, DefineSequenceStatement};
 pub use table::{table, DefineTableStatement};
 pub use token::{token, DefineTokenStatement};
 pub use user::{user, DefineUserStatement};
@@ -35,7 +41,8 @@ use crate::sql::error::IResult;
 use crate::sql::value::Value;
 use derive::Store;
 use nom::branch::alt;
-use nom::combinator::map;
+use nom::combinator::{map, opt};
+use nom::sequence::tuple;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
@@ -55,6 +62,9 @@ pub enum DefineStatement {
 	Field(DefineFieldStatement),
 	Index(DefineIndexStatement),
 	User(DefineUserStatement),
+	Config(DefineConfigStatement),
+	Sequence(DefineSequenceStatement),
+	Migration(DefineMigrationStatement),
 }
 
 impl DefineStatement {
@@ -83,6 +93,9 @@ impl DefineStatement {
 			Self::Index(ref v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Analyzer(ref v) => v.compute(ctx, opt, txn, doc).await,
 			Self::User(ref v) => v.compute(ctx, opt, txn, doc).await,
+			Self::Config(ref v) => v.compute(ctx, opt, txn, doc).await,
+			Self::Sequence(ref v) => v.compute(ctx, opt, txn, doc).await,
+			Self::Migration(ref v) => v.compute(ctx, opt, txn, doc).await,
 		}
 	}
 }
@@ -102,6 +115,9 @@ impl Display for DefineStatement {
 			Self::Field(v) => Display::fmt(v, f),
 			Self::Index(v) => Display::fmt(v, f),
 			Self::Analyzer(v) => Display::fmt(v, f),
+			Self::Config(v) => Display::fmt(v, f),
+			Self::Sequence(v) => Display::fmt(v, f),
+			Self::Migration(v) => Display::fmt(v, f),
 		}
 	}
 }
@@ -122,9 +138,27 @@ pub fn define(i: &str) -> IResult<&str, DefineStatement> {
 		map(field, DefineStatement::Field),
 		map(index, DefineStatement::Index),
 		map(analyzer, DefineStatement::Analyzer),
+		map(config, DefineStatement::Config),
+		map(sequence, DefineStatement::Sequence),
+		map(migration, DefineStatement::Migration),
 	))(i)
 }
 
+/// Parses an optional `IF NOT EXISTS` clause, which makes a definition do nothing if it
+/// already exists. The clause is not stored with the definition, so that exported
+/// definitions replace the existing ones when they are imported
+pub(crate) fn if_not_exists(i: &str) -> IResult<&str, bool> {
+	let (i, v) = opt(tuple((
+		tag_no_case("IF"),
+		shouldbespace,
+		tag_no_case("NOT"),
+		shouldbespace,
+		tag_no_case("EXISTS"),
+		shouldbespace,
+	)))(i)?;
+	Ok((i, v.is_some()))
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -138,6 +172,26 @@ mod tests {
 			..Default::default()
 		});
 		let enc: Vec<u8> = stm.try_into().unwrap();
-		assert_eq!(11, enc.len());
+		assert_eq!(12, enc.len());
+	}
+
+	#[test]
+	fn define_if_not_exists() {
+		let sql = "DEFINE TABLE IF NOT EXISTS person SCHEMALESS";
+		let res = define(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		let sql = "DEFINE FIELD IF NOT EXISTS name ON person TYPE string";
+		let res = define(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		let sql = "DEFINE NAMESPACE IF NOT EXISTS test";
+		let res = define(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		let sql = "DEFINE SEQUENCE IF NOT EXISTS test BATCH 100 START 1";
+		let res = define(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
 	}
 }
@@ -9,13 +9,14 @@ use crate::sql::base::Base;
 use crate::sql::comment::shouldbespace;
 use crate::sql::ending;
 use crate::sql::error::expected;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::ident::{ident, Ident};
+use crate::sql::statements::define::if_not_exists;
 use crate::sql::strand::{strand, Strand};
 use crate::sql::value::Value;
 use derive::Store;
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use nom::multi::many0;
 use revision::revisioned;
@@ -23,11 +24,13 @@ use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct DefineNamespaceStatement {
 	pub id: Option<u32>,
 	pub name: Ident,
 	pub comment: Option<Strand>,
+	#[revision(start = 2)]
+	pub if_not_exists: bool,
 }
 
 impl DefineNamespaceStatement {
@@ -47,14 +50,19 @@ impl DefineNamespaceStatement {
 		let mut run = txn.lock().await;
 		// Clear the cache
 		run.clear_cache();
+		// Check if the definition already exists
+		if self.if_not_exists && run.exi(key.clone()).await? {
+			return Ok(Value::None);
+		}
+		let mut ns = DefineNamespaceStatement {
+			if_not_exists: false,
+			..self.clone()
+		};
 		// Set the id
-		if self.id.is_none() {
-			let mut ns = self.clone();
+		if ns.id.is_none() {
 			ns.id = Some(run.get_next_ns_id().await?);
-			run.set(key, ns).await?;
-		} else {
-			run.set(key, self).await?;
 		}
+		run.set(key, ns).await?;
 		// Ok all good
 		Ok(Value::None)
 	}
@@ -62,7 +70,11 @@ impl DefineNamespaceStatement {
 
 impl Display for DefineNamespaceStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "DEFINE NAMESPACE {}", self.name)?;
+		write!(f, "DEFINE NAMESPACE")?;
+		if self.if_not_exists {
+			write!(f, " IF NOT EXISTS")?
+		}
+		write!(f, " {}", self.name)?;
 		if let Some(ref v) = self.comment {
 			write!(f, " COMMENT {v}")?
 		}
@@ -73,12 +85,14 @@ impl Display for DefineNamespaceStatement {
 pub fn namespace(i: &str) -> IResult<&str, DefineNamespaceStatement> {
 	let (i, _) = alt((tag_no_case("NS"), tag_no_case("NAMESPACE")))(i)?;
 	let (i, _) = shouldbespace(i)?;
+	let (i, if_not_exists) = if_not_exists(i)?;
 	let (i, name) = cut(ident)(i)?;
 	let (i, opts) = many0(namespace_opts)(i)?;
 	let (i, _) = expected("COMMENT", ending::query)(i)?;
 	// Create the base statement
 	let mut res = DefineNamespaceStatement {
 		name,
+		if_not_exists,
 		..Default::default()
 	};
 	// Assign any defined options
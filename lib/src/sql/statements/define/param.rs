@@ -7,73 +7,204 @@ use crate::iam::Action;
 use crate::iam::ResourceKind;
 use crate::sql::base::Base;
 use crate::sql::comment::shouldbespace;
+use crate::sql::common::commas;
 use crate::sql::ending;
 use crate::sql::error::expected;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::fmt::is_pretty;
 use crate::sql::fmt::pretty_indent;
 use crate::sql::ident::{ident, Ident};
+use crate::sql::kind::{kind, Kind};
 use crate::sql::permission::{permission, Permission};
+use crate::sql::statements::define::if_not_exists;
 use crate::sql::strand::{strand, Strand};
 use crate::sql::value::{value, Value};
 use derive::Store;
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
 use nom::character::complete::char;
-use nom::combinator::cut;
-use nom::multi::many0;
+use nom::combinator::{self, cut, map};
+use nom::multi::{many0, separated_list1};
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Write};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 3)]
 pub struct DefineParamStatement {
 	pub name: Ident,
 	pub value: Value,
 	pub comment: Option<Strand>,
+	/// Who can read the parameter
 	pub permissions: Permission,
+	#[revision(start = 2)]
+	pub if_not_exists: bool,
+	/// The type which the value of the parameter is coerced to
+	#[revision(start = 3)]
+	pub kind: Option<Kind>,
+	/// A check which the value of the parameter must pass
+	#[revision(start = 3)]
+	pub assert: Option<Value>,
+	/// Who can change the parameter, with LET or DEFINE PARAM
+	#[revision(start = 3)]
+	pub change: Permission,
 }
 
 impl DefineParamStatement {
 	/// Process this type returning a computed simple Value
 	pub(crate) async fn compute(
 		&self,
-		_ctx: &Context<'_>,
+		ctx: &Context<'_>,
 		opt: &Options,
 		txn: &Transaction,
-		_doc: Option<&CursorDoc<'_>>,
+		doc: Option<&CursorDoc<'_>>,
 	) -> Result<Value, Error> {
 		// Allowed to run?
 		opt.is_allowed(Action::Edit, ResourceKind::Parameter, &Base::Db)?;
-		// Claim transaction
-		let mut run = txn.lock().await;
-		// Clear the cache
-		run.clear_cache();
 		// Process the statement
 		let key = crate::key::database::pa::new(opt.ns(), opt.db(), &self.name);
+		// Fetch the existing definition
+		let old: Option<DefineParamStatement> = {
+			// Claim transaction
+			let mut run = txn.lock().await;
+			// Clear the cache
+			run.clear_cache();
+			// Check if the definition already exists
+			if self.if_not_exists && run.exi(key.clone()).await? {
+				return Ok(Value::None);
+			}
+			run.get(key.clone()).await?.map(Into::into)
+		};
+		// Compute the value once, so that it is stored as a plain value
+		let val = self.value.compute(ctx, opt, txn, doc).await?;
+		// Check the value against the TYPE and ASSERT clauses
+		let val = self.check(ctx, opt, txn, val).await?;
+		// Check if the existing parameter can be changed
+		if let Some(old) = old {
+			old.check_change(ctx, opt, txn, &val).await?;
+		}
+		// Claim transaction
+		let mut run = txn.lock().await;
 		run.add_ns(opt.ns(), opt.strict).await?;
 		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
-		run.set(key, self).await?;
+		run.set(
+			key,
+			DefineParamStatement {
+				value: val,
+				if_not_exists: false,
+				..self.clone()
+			},
+		)
+		.await?;
 		// Ok all good
 		Ok(Value::None)
 	}
+	/// Checks a new value of the parameter against the TYPE and ASSERT clauses, returning the
+	/// value coerced to the declared type
+	pub(crate) async fn check(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		val: Value,
+	) -> Result<Value, Error> {
+		// Check for a TYPE clause
+		let val = match &self.kind {
+			Some(kind) => val.coerce_to(kind).map_err(|e| match e {
+				// There was a conversion error
+				Error::CoerceTo {
+					from,
+					..
+				} => Error::ParamCheck {
+					name: self.name.to_raw(),
+					value: from.to_string(),
+					check: kind.to_string(),
+				},
+				// There was a different error
+				e => e,
+			})?,
+			None => val,
+		};
+		// Check for an ASSERT clause
+		if let Some(expr) = &self.assert {
+			// Configure the context
+			let mut ctx = Context::new(ctx);
+			ctx.add_value("value", &val);
+			// Process the ASSERT clause
+			if !expr.compute(&ctx, opt, txn, None).await?.is_truthy() {
+				return Err(Error::ParamValue {
+					name: self.name.to_raw(),
+					value: val.to_string(),
+					check: expr.to_string(),
+				});
+			}
+		}
+		Ok(val)
+	}
+	/// Checks whether the parameter can be changed to a new value
+	pub(crate) async fn check_change(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		val: &Value,
+	) -> Result<(), Error> {
+		if opt.check_perms(Action::Edit) {
+			match &self.change {
+				Permission::Full => (),
+				Permission::None => {
+					return Err(Error::ParamChangePermissions {
+						name: self.name.to_raw(),
+					})
+				}
+				Permission::Specific(e) => {
+					// Disable permissions
+					let opt = &opt.new_with_perms(false);
+					// Configure the context
+					let mut ctx = Context::new(ctx);
+					ctx.add_value("value", val);
+					ctx.add_value("before", &self.value);
+					// Process the PERMISSION clause
+					if !e.compute(&ctx, opt, txn, None).await?.is_truthy() {
+						return Err(Error::ParamChangePermissions {
+							name: self.name.to_raw(),
+						});
+					}
+				}
+			}
+		}
+		Ok(())
+	}
 }
 
 impl Display for DefineParamStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "DEFINE PARAM ${} VALUE {}", self.name, self.value)?;
+		write!(f, "DEFINE PARAM")?;
+		if self.if_not_exists {
+			write!(f, " IF NOT EXISTS")?
+		}
+		write!(f, " ${} VALUE {}", self.name, self.value)?;
+		if let Some(ref v) = self.kind {
+			write!(f, " TYPE {v}")?
+		}
+		if let Some(ref v) = self.assert {
+			write!(f, " ASSERT {v}")?
+		}
 		if let Some(ref v) = self.comment {
 			write!(f, " COMMENT {v}")?
 		}
-		if !self.permissions.is_full() {
+		if !self.permissions.is_full() || !self.change.is_full() {
 			let _indent = if is_pretty() {
 				Some(pretty_indent())
 			} else {
 				f.write_char(' ')?;
 				None
 			};
-			write!(f, "PERMISSIONS {}", self.permissions)?;
+			if self.permissions == self.change {
+				write!(f, "PERMISSIONS {}", self.permissions)?;
+			} else {
+				write!(f, "PERMISSIONS FOR read {}, FOR change {}", self.permissions, self.change)?;
+			}
 		}
 		Ok(())
 	}
@@ -82,13 +213,15 @@ impl Display for DefineParamStatement {
 pub fn param(i: &str) -> IResult<&str, DefineParamStatement> {
 	let (i, _) = tag_no_case("PARAM")(i)?;
 	let (i, _) = shouldbespace(i)?;
+	let (i, if_not_exists) = if_not_exists(i)?;
 	let (i, _) = cut(char('$'))(i)?;
 	let (i, name) = cut(ident)(i)?;
 	let (i, opts) = many0(param_opts)(i)?;
-	let (i, _) = expected("VALUE, PERMISSIONS, or COMMENT", ending::query)(i)?;
+	let (i, _) = expected("VALUE, TYPE, ASSERT, PERMISSIONS, or COMMENT", ending::query)(i)?;
 	// Create the base statement
 	let mut res = DefineParamStatement {
 		name,
+		if_not_exists,
 		..Default::default()
 	};
 	// Assign any defined options
@@ -97,11 +230,18 @@ pub fn param(i: &str) -> IResult<&str, DefineParamStatement> {
 			DefineParamOption::Value(v) => {
 				res.value = v;
 			}
+			DefineParamOption::Kind(v) => {
+				res.kind = Some(v);
+			}
+			DefineParamOption::Assert(v) => {
+				res.assert = Some(v);
+			}
 			DefineParamOption::Comment(v) => {
 				res.comment = Some(v);
 			}
-			DefineParamOption::Permissions(v) => {
-				res.permissions = v;
+			DefineParamOption::Permissions(read, change) => {
+				res.permissions = read;
+				res.change = change;
 			}
 		}
 	}
@@ -115,12 +255,20 @@ pub fn param(i: &str) -> IResult<&str, DefineParamStatement> {
 
 enum DefineParamOption {
 	Value(Value),
+	Kind(Kind),
+	Assert(Value),
 	Comment(Strand),
-	Permissions(Permission),
+	Permissions(Permission, Permission),
+}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum ParamPermissionKind {
+	Read,
+	Change,
 }
 
 fn param_opts(i: &str) -> IResult<&str, DefineParamOption> {
-	alt((param_value, param_comment, param_permissions))(i)
+	alt((param_value, param_kind, param_assert, param_comment, param_permissions))(i)
 }
 
 fn param_value(i: &str) -> IResult<&str, DefineParamOption> {
@@ -131,6 +279,22 @@ fn param_value(i: &str) -> IResult<&str, DefineParamOption> {
 	Ok((i, DefineParamOption::Value(v)))
 }
 
+fn param_kind(i: &str) -> IResult<&str, DefineParamOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("TYPE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(kind)(i)?;
+	Ok((i, DefineParamOption::Kind(v)))
+}
+
+fn param_assert(i: &str) -> IResult<&str, DefineParamOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("ASSERT")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(value)(i)?;
+	Ok((i, DefineParamOption::Assert(v)))
+}
+
 fn param_comment(i: &str) -> IResult<&str, DefineParamOption> {
 	let (i, _) = shouldbespace(i)?;
 	let (i, _) = tag_no_case("COMMENT")(i)?;
@@ -143,6 +307,70 @@ fn param_permissions(i: &str) -> IResult<&str, DefineParamOption> {
 	let (i, _) = shouldbespace(i)?;
 	let (i, _) = tag_no_case("PERMISSIONS")(i)?;
 	let (i, _) = shouldbespace(i)?;
-	let (i, v) = cut(permission)(i)?;
-	Ok((i, DefineParamOption::Permissions(v)))
+	cut(alt((
+		map(separated_list1(commas, param_rule), |rules| {
+			let find = |kind| {
+				rules
+					.iter()
+					.flatten()
+					.find_map(|(k, v)| (*k == kind).then(|| v.clone()))
+					.unwrap_or_default()
+			};
+			DefineParamOption::Permissions(
+				find(ParamPermissionKind::Read),
+				find(ParamPermissionKind::Change),
+			)
+		}),
+		map(permission, |v| DefineParamOption::Permissions(v.clone(), v)),
+	)))(i)
+}
+
+fn param_rule(i: &str) -> IResult<&str, Vec<(ParamPermissionKind, Permission)>> {
+	let (i, _) = tag_no_case("FOR")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	cut(|i| {
+		let (i, kinds) = separated_list1(
+			commas,
+			alt((
+				combinator::value(ParamPermissionKind::Read, tag_no_case("READ")),
+				combinator::value(ParamPermissionKind::Change, tag_no_case("CHANGE")),
+			)),
+		)(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, v) = permission(i)?;
+		Ok((i, kinds.into_iter().map(|k| (k, v.clone())).collect()))
+	})(i)
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn define_param_with_type_and_assert() {
+		let sql = "PARAM $limit VALUE 10 TYPE int ASSERT $value > 0";
+		let res = param(sql);
+		let out = res.unwrap().1;
+		assert_eq!(format!("DEFINE {sql}"), format!("{}", out));
+		assert_eq!(out.kind, Some(Kind::Int));
+	}
+
+	#[test]
+	fn define_param_with_permissions() {
+		let sql = "PARAM $limit VALUE 10 PERMISSIONS NONE";
+		let out = param(sql).unwrap().1;
+		assert_eq!(format!("DEFINE {sql}"), format!("{}", out));
+		assert_eq!(out.permissions, Permission::None);
+		assert_eq!(out.change, Permission::None);
+		let sql = "PARAM $limit VALUE 10 PERMISSIONS FOR read FULL, FOR change NONE";
+		let out = param(sql).unwrap().1;
+		assert_eq!(format!("DEFINE {sql}"), format!("{}", out));
+		assert_eq!(out.permissions, Permission::Full);
+		assert_eq!(out.change, Permission::None);
+		// Kinds which are not given are not restricted
+		let out = param("PARAM $limit VALUE 10 PERMISSIONS FOR change NONE").unwrap().1;
+		assert_eq!(out.permissions, Permission::Full);
+		assert_eq!(out.change, Permission::None);
+	}
 }
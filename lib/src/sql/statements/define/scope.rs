@@ -10,13 +10,14 @@ use crate::sql::comment::shouldbespace;
 use crate::sql::duration::{duration, Duration};
 use crate::sql::ending;
 use crate::sql::error::expected;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::ident::{ident, Ident};
+use crate::sql::statements::define::if_not_exists;
 use crate::sql::strand::{strand, Strand};
 use crate::sql::value::{value, Value};
 use derive::Store;
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use nom::multi::many0;
 use rand::distributions::Alphanumeric;
@@ -26,7 +27,7 @@ use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct DefineScopeStatement {
 	pub name: Ident,
 	pub code: String,
@@ -34,6 +35,8 @@ pub struct DefineScopeStatement {
 	pub signup: Option<Value>,
 	pub signin: Option<Value>,
 	pub comment: Option<Strand>,
+	#[revision(start = 2)]
+	pub if_not_exists: bool,
 }
 
 impl DefineScopeStatement {
@@ -53,9 +56,20 @@ impl DefineScopeStatement {
 		run.clear_cache();
 		// Process the statement
 		let key = crate::key::database::sc::new(opt.ns(), opt.db(), &self.name);
+		// Check if the definition already exists
+		if self.if_not_exists && run.exi(key.clone()).await? {
+			return Ok(Value::None);
+		}
 		run.add_ns(opt.ns(), opt.strict).await?;
 		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
-		run.set(key, self).await?;
+		run.set(
+			key,
+			DefineScopeStatement {
+				if_not_exists: false,
+				..self.clone()
+			},
+		)
+		.await?;
 		// Ok all good
 		Ok(Value::None)
 	}
@@ -63,7 +77,11 @@ impl DefineScopeStatement {
 
 impl Display for DefineScopeStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "DEFINE SCOPE {}", self.name)?;
+		write!(f, "DEFINE SCOPE")?;
+		if self.if_not_exists {
+			write!(f, " IF NOT EXISTS")?
+		}
+		write!(f, " {}", self.name)?;
 		if let Some(ref v) = self.session {
 			write!(f, " SESSION {v}")?
 		}
@@ -83,6 +101,7 @@ impl Display for DefineScopeStatement {
 pub fn scope(i: &str) -> IResult<&str, DefineScopeStatement> {
 	let (i, _) = tag_no_case("SCOPE")(i)?;
 	let (i, _) = shouldbespace(i)?;
+	let (i, if_not_exists) = if_not_exists(i)?;
 	let (i, name) = cut(ident)(i)?;
 	let (i, opts) = many0(scope_opts)(i)?;
 	let (i, _) = expected("SESSION, SIGNUP, SIGNIN, or COMMENT", ending::query)(i)?;
@@ -94,6 +113,7 @@ pub fn scope(i: &str) -> IResult<&str, DefineScopeStatement> {
 			.take(128)
 			.map(char::from)
 			.collect::<String>(),
+		if_not_exists,
 		..Default::default()
 	};
 	// Assign any defined options
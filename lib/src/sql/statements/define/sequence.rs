@@ -0,0 +1,197 @@
+use crate::cnf::SEQUENCE_BATCH_SIZE;
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::iam::Action;
+use crate::iam::ResourceKind;
+use crate::sql::base::Base;
+use crate::sql::comment::shouldbespace;
+use crate::sql::ending;
+use crate::sql::error::expected;
+use crate::sql::error::tag_no_case;
+use crate::sql::error::IResult;
+use crate::sql::ident::{ident, Ident};
+use crate::sql::number::integer;
+use crate::sql::statements::define::if_not_exists;
+use crate::sql::strand::{strand, Strand};
+use crate::sql::value::Value;
+use derive::Store;
+use nom::branch::alt;
+use nom::character::complete::u32 as uint32;
+use nom::combinator::cut;
+use nom::multi::many0;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub struct DefineSequenceStatement {
+	pub name: Ident,
+	/// The number of values which are allocated to a node at once
+	pub batch: u32,
+	/// The first value of the sequence
+	pub start: i64,
+	pub comment: Option<Strand>,
+	/// The version of the definition, which changes each time the sequence
+	/// is defined, so that the blocks cached by each node can be discarded
+	pub version: u64,
+	pub if_not_exists: bool,
+}
+
+impl DefineSequenceStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		_ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		_doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		// Allowed to run?
+		opt.is_allowed(Action::Edit, ResourceKind::Sequence, &Base::Db)?;
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Clear the cache
+		run.clear_cache();
+		// Process the statement
+		let key = crate::key::database::sq::new(opt.ns(), opt.db(), &self.name);
+		// Check if the definition already exists
+		if self.if_not_exists && run.exi(key.clone()).await? {
+			return Ok(Value::None);
+		}
+		run.add_ns(opt.ns(), opt.strict).await?;
+		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
+		let sq = DefineSequenceStatement {
+			version: rand::random(),
+			if_not_exists: false,
+			..self.clone()
+		};
+		run.set(key, sq).await?;
+		// A redefined sequence never hands out a value below its new start
+		let next = run.get_sequence_value(opt.ns(), opt.db(), self).await?;
+		let key = crate::key::database::sv::new(opt.ns(), opt.db(), &self.name);
+		run.set(key, next.max(self.start).to_be_bytes().to_vec()).await?;
+		run.clear_sequence(opt.ns(), opt.db(), &self.name);
+		// Ok all good
+		Ok(Value::None)
+	}
+}
+
+impl Display for DefineSequenceStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "DEFINE SEQUENCE")?;
+		if self.if_not_exists {
+			write!(f, " IF NOT EXISTS")?
+		}
+		write!(f, " {} BATCH {} START {}", self.name, self.batch, self.start)?;
+		if let Some(ref v) = self.comment {
+			write!(f, " COMMENT {v}")?
+		}
+		Ok(())
+	}
+}
+
+pub fn sequence(i: &str) -> IResult<&str, DefineSequenceStatement> {
+	let (i, _) = tag_no_case("SEQUENCE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, if_not_exists) = if_not_exists(i)?;
+	let (i, name) = cut(ident)(i)?;
+	let (i, opts) = many0(sequence_opts)(i)?;
+	let (i, _) = expected("BATCH, START, or COMMENT", ending::query)(i)?;
+	// Create the base statement
+	let mut res = DefineSequenceStatement {
+		name,
+		batch: SEQUENCE_BATCH_SIZE,
+		start: 1,
+		if_not_exists,
+		..Default::default()
+	};
+	// Assign any defined options
+	for opt in opts {
+		match opt {
+			DefineSequenceOption::Batch(v) => {
+				res.batch = v;
+			}
+			DefineSequenceOption::Start(v) => {
+				res.start = v;
+			}
+			DefineSequenceOption::Comment(v) => {
+				res.comment = Some(v);
+			}
+		}
+	}
+	// Return the statement
+	Ok((i, res))
+}
+
+enum DefineSequenceOption {
+	Batch(u32),
+	Start(i64),
+	Comment(Strand),
+}
+
+fn sequence_opts(i: &str) -> IResult<&str, DefineSequenceOption> {
+	alt((sequence_batch, sequence_start, sequence_comment))(i)
+}
+
+fn sequence_batch(i: &str) -> IResult<&str, DefineSequenceOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("BATCH")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(expected("a positive integer", uint32))(i)?;
+	Ok((i, DefineSequenceOption::Batch(v)))
+}
+
+fn sequence_start(i: &str) -> IResult<&str, DefineSequenceOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("START")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(expected("an integer", integer))(i)?;
+	Ok((i, DefineSequenceOption::Start(v)))
+}
+
+fn sequence_comment(i: &str) -> IResult<&str, DefineSequenceOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("COMMENT")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(strand)(i)?;
+	Ok((i, DefineSequenceOption::Comment(v)))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn define_sequence() {
+		let sql = "SEQUENCE invoices";
+		let res = sequence(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out.batch, SEQUENCE_BATCH_SIZE);
+		assert_eq!(out.start, 1);
+		assert_eq!("DEFINE SEQUENCE invoices BATCH 100 START 1", format!("{}", out));
+	}
+
+	#[test]
+	fn define_sequence_options() {
+		let sql = "SEQUENCE invoices BATCH 10 START 1000 COMMENT 'invoice numbers'";
+		let res = sequence(sql);
+		let out = res.unwrap().1;
+		assert_eq!(format!("DEFINE {sql}"), format!("{}", out));
+
+		let serialized: Vec<u8> = (&out).try_into().unwrap();
+		let deserialized = DefineSequenceStatement::try_from(&serialized).unwrap();
+		assert_eq!(out, deserialized);
+	}
+
+	#[test]
+	fn define_sequence_invalid_batch() {
+		let sql = "SEQUENCE invoices BATCH -10";
+		let res = sequence(sql);
+		assert!(res.is_err());
+	}
+}
@@ -7,29 +7,44 @@ use crate::iam::Action;
 use crate::iam::ResourceKind;
 use crate::sql::base::Base;
 use crate::sql::changefeed::{changefeed, ChangeFeed};
-use crate::sql::comment::shouldbespace;
+use crate::sql::comment::{mightbespace, shouldbespace};
+use crate::sql::common::{closeparentheses, commas, openparentheses};
+use crate::sql::cond::{cond, Cond};
+use crate::sql::duration::{duration, Duration};
 use crate::sql::ending;
 use crate::sql::error::expected;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::fmt::is_pretty;
 use crate::sql::fmt::pretty_indent;
+use crate::sql::fmt::Fmt;
+use crate::sql::id::Id;
 use crate::sql::ident::{ident, Ident};
+use crate::sql::idiom::Idiom;
+use crate::sql::kind::Kind;
+use crate::sql::paths::{IN, OUT};
 use crate::sql::permission::{permissions, Permissions};
-use crate::sql::statements::UpdateStatement;
+use crate::sql::statements::define::if_not_exists;
+use crate::sql::statements::{
+	CreateStatement, DefineFieldStatement, DeleteStatement, UpdateStatement,
+};
+use crate::sql::storage::{storage, Storage};
 use crate::sql::strand::{strand, Strand};
+use crate::sql::table_type::{table_type, TableType};
+use crate::sql::thing::Thing;
 use crate::sql::value::{Value, Values};
 use crate::sql::view::{view, View};
 use derive::Store;
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use nom::multi::many0;
+use nom::multi::separated_list1;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Write};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 8)]
 pub struct DefineTableStatement {
 	pub id: Option<u32>,
 	pub name: Ident,
@@ -39,9 +54,36 @@ pub struct DefineTableStatement {
 	pub permissions: Permissions,
 	pub changefeed: Option<ChangeFeed>,
 	pub comment: Option<Strand>,
+	#[revision(start = 2)]
+	pub merge: bool,
+	/// The duration after its last update when a record is deleted
+	#[revision(start = 3)]
+	pub expire: Option<Duration>,
+	/// A hint for how the key-value store should place the table records
+	#[revision(start = 4)]
+	pub storage: Option<Storage>,
+	/// The condition which moves a record into the archive of the table
+	#[revision(start = 5)]
+	pub archive: Option<Cond>,
+	/// The type of the records which the table can hold
+	#[revision(start = 6)]
+	pub kind: TableType,
+	/// The closed set of record ids which the table holds
+	#[revision(start = 7)]
+	pub enumerated: Option<Vec<Strand>>,
+	/// Whether the statement does nothing if the table is already defined
+	#[revision(start = 8)]
+	pub if_not_exists: bool,
 }
 
 impl DefineTableStatement {
+	/// Check if the table can hold a record with the specified id
+	pub(crate) fn allows_member(&self, id: &Id) -> bool {
+		match &self.enumerated {
+			Some(v) => matches!(id, Id::String(id) if v.iter().any(|v| v.as_str() == id)),
+			None => true,
+		}
+	}
 	pub(crate) async fn compute(
 		&self,
 		ctx: &Context<'_>,
@@ -57,14 +99,125 @@ impl DefineTableStatement {
 		run.clear_cache();
 		// Process the statement
 		let key = crate::key::database::tb::new(opt.ns(), opt.db(), &self.name);
+		// Check if the definition already exists
+		if self.if_not_exists && run.exi(key.clone()).await? {
+			return Ok(Value::None);
+		}
+		// Fetch the expiry duration, members, and storage of the existing definition
+		let (expire, enumerated, storage) = match run.get(key.clone()).await? {
+			Some(v) => {
+				let tb = DefineTableStatement::from(v);
+				(tb.expire, tb.enumerated, tb.storage)
+			}
+			None => (None, None, None),
+		};
+		// The records of a table can not be moved to a different storage
+		if storage != self.storage {
+			let beg = crate::key::thing::prefix(opt.ns(), opt.db(), &self.name);
+			let end = crate::key::thing::suffix(opt.ns(), opt.db(), &self.name);
+			if !run.scan(beg..end, 1).await?.is_empty() {
+				return Err(Error::TbStorageInUse {
+					value: self.name.to_string(),
+				});
+			}
+		}
 		let ns = run.add_ns(opt.ns(), opt.strict).await?;
 		let db = run.add_db(opt.ns(), opt.db(), opt.strict).await?;
-		if self.id.is_none() && ns.id.is_some() && db.id.is_some() {
-			let mut tb = self.clone();
+		let mut tb = DefineTableStatement {
+			if_not_exists: false,
+			..self.clone()
+		};
+		if tb.id.is_none() && ns.id.is_some() && db.id.is_some() {
 			tb.id = Some(run.get_next_tb_id(ns.id.unwrap(), db.id.unwrap()).await?);
-			run.set(key, tb).await?;
-		} else {
-			run.set(key, self).await?;
+		}
+		run.set(key.clone(), &tb).await?;
+		// The records of the table are placed according to the new definition
+		run.clr(key).await?;
+		// Move the expiry times of the records if the duration changed
+		if let Some(old) = expire.filter(|v| Some(v) != self.expire.as_ref()) {
+			let beg = crate::key::table::et::prefix(opt.ns(), opt.db(), &self.name);
+			let mut end = beg.clone();
+			end.push(0xff);
+			for (k, _) in run.getr(beg..end, u32::MAX).await? {
+				let et = crate::key::table::et::Et::decode(&k)?;
+				let ex = crate::key::table::ex::new(opt.ns(), opt.db(), &self.name, &et.id);
+				run.del(k.clone()).await?;
+				match &self.expire {
+					// Shift the expiry time by the change in duration
+					Some(new) => {
+						let ts = et.ts.saturating_sub(old.as_millis() as u64);
+						let ts = ts.saturating_add(new.as_millis() as u64);
+						let key = crate::key::table::et::new(opt.ns(), opt.db(), et.tb, ts, &et.id);
+						run.set(key, vec![]).await?;
+						run.set(ex, ts.to_be_bytes().to_vec()).await?;
+					}
+					// The records of the table no longer expire
+					None => run.del(ex).await?,
+				}
+			}
+		}
+		// Type the in and out fields of a relation table, unless they are already defined
+		if let TableType::Relation(rel) = &self.kind {
+			let fields = [(&*IN, &rel.from), (&*OUT, &rel.to)];
+			for (name, kind) in fields {
+				let fd = DefineFieldStatement {
+					name: Idiom::from(name.to_vec()),
+					what: self.name.clone(),
+					kind: Some(kind.clone().unwrap_or_else(|| Kind::Record(vec![]))),
+					..Default::default()
+				};
+				let key = crate::key::table::fd::new(
+					opt.ns(),
+					opt.db(),
+					&self.name,
+					&fd.name.to_string(),
+				);
+				if !run.exi(key.clone()).await? {
+					run.set(key, &fd).await?;
+				}
+			}
+			// Clear the cache
+			let key = crate::key::table::fd::prefix(opt.ns(), opt.db(), &self.name);
+			run.clr(key).await?;
+		}
+		// Create the members of an enumerated table
+		if let Some(members) = &self.enumerated {
+			// Find the members which were removed
+			let removed: Vec<_> = enumerated
+				.iter()
+				.flatten()
+				.filter(|v| !members.contains(v))
+				.map(|v| Value::from(Thing::from((self.name.to_raw(), v.as_str().to_owned()))))
+				.collect();
+			// Find the members which don't exist yet
+			let mut what = vec![];
+			for v in members.iter() {
+				let id = Id::String(v.as_str().to_owned());
+				let key = crate::key::thing::new(opt.ns(), opt.db(), &self.name, &id);
+				if !run.exi(key).await? {
+					what.push(Value::from(Thing::from((self.name.to_raw(), id))));
+				}
+			}
+			// Release the transaction
+			drop(run);
+			// Process the removed members
+			if !removed.is_empty() {
+				let stm = DeleteStatement {
+					what: Values(removed),
+					..DeleteStatement::default()
+				};
+				stm.compute(ctx, opt, txn, doc).await?;
+			}
+			// Process the missing members
+			if !what.is_empty() {
+				let stm = CreateStatement {
+					what: Values(what),
+					..CreateStatement::default()
+				};
+				stm.compute(ctx, opt, txn, doc).await?;
+			}
+			// Claim transaction
+			run = txn.lock().await;
 		}
 		// Check if table is a view
 		if let Some(view) = &self.view {
@@ -75,7 +228,7 @@ impl DefineTableStatement {
 			for v in view.what.0.iter() {
 				// Save the view config
 				let key = crate::key::table::ft::new(opt.ns(), opt.db(), v, &self.name);
-				run.set(key, self).await?;
+				run.set(key, &tb).await?;
 				// Clear the cache
 				let key = crate::key::table::ft::prefix(opt.ns(), opt.db(), v);
 				run.clr(key).await?;
@@ -107,10 +260,20 @@ impl DefineTableStatement {
 
 impl Display for DefineTableStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "DEFINE TABLE {}", self.name)?;
+		write!(f, "DEFINE TABLE")?;
+		if self.if_not_exists {
+			write!(f, " IF NOT EXISTS")?
+		}
+		write!(f, " {}", self.name)?;
 		if self.drop {
 			f.write_str(" DROP")?;
 		}
+		if self.kind != TableType::Any {
+			write!(f, " {}", self.kind)?;
+		}
+		if let Some(ref v) = self.enumerated {
+			write!(f, " ENUMERATED ({})", Fmt::comma_separated(v))?;
+		}
 		f.write_str(if self.full {
 			" SCHEMAFULL"
 		} else {
@@ -125,6 +288,18 @@ impl Display for DefineTableStatement {
 		if let Some(ref v) = self.changefeed {
 			write!(f, " {v}")?;
 		}
+		if self.merge {
+			f.write_str(" MERGEABLE")?;
+		}
+		if let Some(ref v) = self.expire {
+			write!(f, " EXPIRE AFTER {v}")?;
+		}
+		if let Some(ref v) = self.archive {
+			write!(f, " ARCHIVE {v}")?;
+		}
+		if let Some(ref v) = self.storage {
+			write!(f, " {v}")?;
+		}
 		if !self.permissions.is_full() {
 			let _indent = if is_pretty() {
 				Some(pretty_indent())
@@ -141,15 +316,17 @@ impl Display for DefineTableStatement {
 pub fn table(i: &str) -> IResult<&str, DefineTableStatement> {
 	let (i, _) = tag_no_case("TABLE")(i)?;
 	let (i, _) = shouldbespace(i)?;
+	let (i, if_not_exists) = if_not_exists(i)?;
 	let (i, name) = cut(ident)(i)?;
 	let (i, opts) = many0(table_opts)(i)?;
 	let (i, _) = expected(
-		"DROP, SCHEMALESS, SCHEMAFUL(L), VIEW, CHANGEFEED, PERMISSIONS, or COMMENT",
+		"DROP, TYPE, ENUMERATED, SCHEMALESS, SCHEMAFUL(L), VIEW, CHANGEFEED, MERGEABLE, EXPIRE, ARCHIVE, STORAGE, PERMISSIONS, or COMMENT",
 		ending::query,
 	)(i)?;
 	// Create the base statement
 	let mut res = DefineTableStatement {
 		name,
+		if_not_exists,
 		..Default::default()
 	};
 	// Assign any defined options
@@ -176,6 +353,24 @@ pub fn table(i: &str) -> IResult<&str, DefineTableStatement> {
 			DefineTableOption::Permissions(v) => {
 				res.permissions = v;
 			}
+			DefineTableOption::Mergeable => {
+				res.merge = true;
+			}
+			DefineTableOption::Expire(v) => {
+				res.expire = Some(v);
+			}
+			DefineTableOption::Archive(v) => {
+				res.archive = Some(v);
+			}
+			DefineTableOption::Storage(v) => {
+				res.storage = Some(v);
+			}
+			DefineTableOption::Kind(v) => {
+				res.kind = v;
+			}
+			DefineTableOption::Enumerated(v) => {
+				res.enumerated = Some(v);
+			}
 		}
 	}
 	// Return the statement
@@ -190,6 +385,12 @@ enum DefineTableOption {
 	Comment(Strand),
 	Permissions(Permissions),
 	ChangeFeed(ChangeFeed),
+	Mergeable,
+	Expire(Duration),
+	Archive(Cond),
+	Storage(Storage),
+	Kind(TableType),
+	Enumerated(Vec<Strand>),
 }
 
 fn table_opts(i: &str) -> IResult<&str, DefineTableOption> {
@@ -201,6 +402,12 @@ fn table_opts(i: &str) -> IResult<&str, DefineTableOption> {
 		table_schemafull,
 		table_permissions,
 		table_changefeed,
+		table_mergeable,
+		table_expire,
+		table_archive,
+		table_storage,
+		table_kind,
+		table_enumerated,
 	))(i)
 }
 
@@ -216,6 +423,55 @@ fn table_changefeed(i: &str) -> IResult<&str, DefineTableOption> {
 	Ok((i, DefineTableOption::ChangeFeed(v)))
 }
 
+fn table_mergeable(i: &str) -> IResult<&str, DefineTableOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("MERGEABLE")(i)?;
+	Ok((i, DefineTableOption::Mergeable))
+}
+
+fn table_expire(i: &str) -> IResult<&str, DefineTableOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("EXPIRE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("AFTER")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(duration)(i)?;
+	Ok((i, DefineTableOption::Expire(v)))
+}
+
+fn table_archive(i: &str) -> IResult<&str, DefineTableOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("ARCHIVE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(cond)(i)?;
+	Ok((i, DefineTableOption::Archive(v)))
+}
+
+fn table_storage(i: &str) -> IResult<&str, DefineTableOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = storage(i)?;
+	Ok((i, DefineTableOption::Storage(v)))
+}
+
+fn table_kind(i: &str) -> IResult<&str, DefineTableOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = table_type(i)?;
+	Ok((i, DefineTableOption::Kind(v)))
+}
+
+fn table_enumerated(i: &str) -> IResult<&str, DefineTableOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("ENUMERATED")(i)?;
+	let (i, _) = mightbespace(i)?;
+	let (i, v) = cut(|i| {
+		let (i, _) = openparentheses(i)?;
+		let (i, v) = separated_list1(commas, strand)(i)?;
+		let (i, _) = closeparentheses(i)?;
+		Ok((i, v))
+	})(i)?;
+	Ok((i, DefineTableOption::Enumerated(v)))
+}
+
 fn table_view(i: &str) -> IResult<&str, DefineTableOption> {
 	let (i, _) = shouldbespace(i)?;
 	let (i, v) = view(i)?;
@@ -264,4 +520,83 @@ mod tests {
 		let deserialized = DefineTableStatement::try_from(&serialized).unwrap();
 		assert_eq!(out, deserialized);
 	}
+
+	#[test]
+	fn define_table_mergeable() {
+		let sql = "TABLE mytable SCHEMALESS CHANGEFEED 1h MERGEABLE";
+		let res = table(sql);
+		let out = res.unwrap().1;
+		assert!(out.merge);
+		assert_eq!(format!("DEFINE {sql}"), format!("{}", out));
+
+		let serialized: Vec<u8> = (&out).try_into().unwrap();
+		let deserialized = DefineTableStatement::try_from(&serialized).unwrap();
+		assert_eq!(out, deserialized);
+	}
+
+	#[test]
+	fn define_table_expire() {
+		let sql = "TABLE session SCHEMALESS EXPIRE AFTER 30m";
+		let res = table(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out.expire, Some("30m".parse().unwrap()));
+		assert_eq!(format!("DEFINE {sql}"), format!("{}", out));
+
+		let serialized: Vec<u8> = (&out).try_into().unwrap();
+		let deserialized = DefineTableStatement::try_from(&serialized).unwrap();
+		assert_eq!(out, deserialized);
+	}
+
+	#[test]
+	fn define_table_storage() {
+		let sql = "TABLE events SCHEMALESS EXPIRE AFTER 1d STORAGE FAMILY hot";
+		let res = table(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out.storage, Some(Storage::Family("hot".into())));
+		assert_eq!(format!("DEFINE {sql}"), format!("{}", out));
+
+		let serialized: Vec<u8> = (&out).try_into().unwrap();
+		let deserialized = DefineTableStatement::try_from(&serialized).unwrap();
+		assert_eq!(out, deserialized);
+	}
+
+	#[test]
+	fn define_table_relation() {
+		let sql = "TABLE likes TYPE RELATION FROM user TO post SCHEMAFULL";
+		let res = table(sql);
+		let out = res.unwrap().1;
+		assert!(out.kind.is_relation());
+		assert_eq!(format!("DEFINE {sql}"), format!("{}", out));
+
+		let serialized: Vec<u8> = (&out).try_into().unwrap();
+		let deserialized = DefineTableStatement::try_from(&serialized).unwrap();
+		assert_eq!(out, deserialized);
+	}
+
+	#[test]
+	fn define_table_archive() {
+		let sql = "TABLE log SCHEMALESS ARCHIVE WHERE time < time::now() - 30d";
+		let res = table(sql);
+		let out = res.unwrap().1;
+		assert!(out.archive.is_some());
+		assert_eq!(format!("DEFINE {sql}"), format!("{}", out));
+
+		let serialized: Vec<u8> = (&out).try_into().unwrap();
+		let deserialized = DefineTableStatement::try_from(&serialized).unwrap();
+		assert_eq!(out, deserialized);
+	}
+
+	#[test]
+	fn define_table_enumerated() {
+		let sql = "TABLE status ENUMERATED ('open', 'closed', 'pending') SCHEMALESS";
+		let res = table(sql);
+		let out = res.unwrap().1;
+		assert!(out.allows_member(&Id::from("open")));
+		assert!(!out.allows_member(&Id::from("archived")));
+		assert_eq!(format!("DEFINE {sql}"), format!("{}", out));
+
+		let serialized: Vec<u8> = (&out).try_into().unwrap();
+		let deserialized = DefineTableStatement::try_from(&serialized).unwrap();
+		assert_eq!(out, deserialized);
+	}
 }
@@ -11,14 +11,15 @@ use crate::sql::comment::shouldbespace;
 use crate::sql::ending;
 use crate::sql::error::expect_tag_no_case;
 use crate::sql::error::expected;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::escape::quote_str;
 use crate::sql::ident::{ident, Ident};
+use crate::sql::statements::define::if_not_exists;
 use crate::sql::strand::{strand, strand_raw, Strand};
 use crate::sql::value::Value;
 use derive::Store;
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use nom::multi::many0;
 use revision::revisioned;
@@ -26,13 +27,15 @@ use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct DefineTokenStatement {
 	pub name: Ident,
 	pub base: Base,
 	pub kind: Algorithm,
 	pub code: String,
 	pub comment: Option<Strand>,
+	#[revision(start = 2)]
+	pub if_not_exists: bool,
 }
 
 impl DefineTokenStatement {
@@ -54,8 +57,19 @@ impl DefineTokenStatement {
 				run.clear_cache();
 				// Process the statement
 				let key = crate::key::namespace::tk::new(opt.ns(), &self.name);
+				// Check if the definition already exists
+				if self.if_not_exists && run.exi(key.clone()).await? {
+					return Ok(Value::None);
+				}
 				run.add_ns(opt.ns(), opt.strict).await?;
-				run.set(key, self).await?;
+				run.set(
+					key,
+					DefineTokenStatement {
+						if_not_exists: false,
+						..self.clone()
+					},
+				)
+				.await?;
 				// Ok all good
 				Ok(Value::None)
 			}
@@ -66,9 +80,20 @@ impl DefineTokenStatement {
 				run.clear_cache();
 				// Process the statement
 				let key = crate::key::database::tk::new(opt.ns(), opt.db(), &self.name);
+				// Check if the definition already exists
+				if self.if_not_exists && run.exi(key.clone()).await? {
+					return Ok(Value::None);
+				}
 				run.add_ns(opt.ns(), opt.strict).await?;
 				run.add_db(opt.ns(), opt.db(), opt.strict).await?;
-				run.set(key, self).await?;
+				run.set(
+					key,
+					DefineTokenStatement {
+						if_not_exists: false,
+						..self.clone()
+					},
+				)
+				.await?;
 				// Ok all good
 				Ok(Value::None)
 			}
@@ -79,10 +104,21 @@ impl DefineTokenStatement {
 				run.clear_cache();
 				// Process the statement
 				let key = crate::key::scope::tk::new(opt.ns(), opt.db(), sc, &self.name);
+				// Check if the definition already exists
+				if self.if_not_exists && run.exi(key.clone()).await? {
+					return Ok(Value::None);
+				}
 				run.add_ns(opt.ns(), opt.strict).await?;
 				run.add_db(opt.ns(), opt.db(), opt.strict).await?;
 				run.add_sc(opt.ns(), opt.db(), sc, opt.strict).await?;
-				run.set(key, self).await?;
+				run.set(
+					key,
+					DefineTokenStatement {
+						if_not_exists: false,
+						..self.clone()
+					},
+				)
+				.await?;
 				// Ok all good
 				Ok(Value::None)
 			}
@@ -94,9 +130,13 @@ impl DefineTokenStatement {
 
 impl Display for DefineTokenStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "DEFINE TOKEN")?;
+		if self.if_not_exists {
+			write!(f, " IF NOT EXISTS")?
+		}
 		write!(
 			f,
-			"DEFINE TOKEN {} ON {} TYPE {} VALUE {}",
+			" {} ON {} TYPE {} VALUE {}",
 			self.name,
 			self.base,
 			self.kind,
@@ -112,6 +152,7 @@ impl Display for DefineTokenStatement {
 pub fn token(i: &str) -> IResult<&str, DefineTokenStatement> {
 	let (i, _) = tag_no_case("TOKEN")(i)?;
 	let (i, _) = shouldbespace(i)?;
+	let (i, if_not_exists) = if_not_exists(i)?;
 	let (i, (name, base, opts)) = cut(|i| {
 		let (i, name) = ident(i)?;
 		let (i, _) = shouldbespace(i)?;
@@ -126,6 +167,7 @@ pub fn token(i: &str) -> IResult<&str, DefineTokenStatement> {
 	let mut res = DefineTokenStatement {
 		name,
 		base,
+		if_not_exists,
 		..Default::default()
 	};
 	// Assign any defined options
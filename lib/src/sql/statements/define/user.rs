@@ -12,18 +12,19 @@ use crate::sql::common::commas;
 use crate::sql::ending;
 use crate::sql::error::expect_tag_no_case;
 use crate::sql::error::expected;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::error::ParseError as SqlError;
 use crate::sql::escape::quote_str;
 use crate::sql::fmt::Fmt;
 use crate::sql::ident::{ident, Ident};
+use crate::sql::statements::define::if_not_exists;
 use crate::sql::strand::{strand, strand_raw, Strand};
 use crate::sql::value::Value;
 use argon2::password_hash::{PasswordHasher, SaltString};
 use argon2::Argon2;
 use derive::Store;
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use nom::multi::many0;
 use nom::multi::separated_list1;
@@ -36,7 +37,7 @@ use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct DefineUserStatement {
 	pub name: Ident,
 	pub base: Base,
@@ -44,6 +45,8 @@ pub struct DefineUserStatement {
 	pub code: String,
 	pub roles: Vec<Ident>,
 	pub comment: Option<Strand>,
+	#[revision(start = 2)]
+	pub if_not_exists: bool,
 }
 
 impl From<(Base, &str, &str)> for DefineUserStatement {
@@ -62,6 +65,7 @@ impl From<(Base, &str, &str)> for DefineUserStatement {
 				.collect::<String>(),
 			roles: vec!["owner".into()],
 			comment: None,
+			if_not_exists: false,
 		}
 	}
 }
@@ -86,7 +90,18 @@ impl DefineUserStatement {
 				run.clear_cache();
 				// Process the statement
 				let key = crate::key::root::us::new(&self.name);
-				run.set(key, self).await?;
+				// Check if the definition already exists
+				if self.if_not_exists && run.exi(key.clone()).await? {
+					return Ok(Value::None);
+				}
+				run.set(
+					key,
+					DefineUserStatement {
+						if_not_exists: false,
+						..self.clone()
+					},
+				)
+				.await?;
 				// Ok all good
 				Ok(Value::None)
 			}
@@ -97,8 +112,19 @@ impl DefineUserStatement {
 				run.clear_cache();
 				// Process the statement
 				let key = crate::key::namespace::us::new(opt.ns(), &self.name);
+				// Check if the definition already exists
+				if self.if_not_exists && run.exi(key.clone()).await? {
+					return Ok(Value::None);
+				}
 				run.add_ns(opt.ns(), opt.strict).await?;
-				run.set(key, self).await?;
+				run.set(
+					key,
+					DefineUserStatement {
+						if_not_exists: false,
+						..self.clone()
+					},
+				)
+				.await?;
 				// Ok all good
 				Ok(Value::None)
 			}
@@ -109,9 +135,20 @@ impl DefineUserStatement {
 				run.clear_cache();
 				// Process the statement
 				let key = crate::key::database::us::new(opt.ns(), opt.db(), &self.name);
+				// Check if the definition already exists
+				if self.if_not_exists && run.exi(key.clone()).await? {
+					return Ok(Value::None);
+				}
 				run.add_ns(opt.ns(), opt.strict).await?;
 				run.add_db(opt.ns(), opt.db(), opt.strict).await?;
-				run.set(key, self).await?;
+				run.set(
+					key,
+					DefineUserStatement {
+						if_not_exists: false,
+						..self.clone()
+					},
+				)
+				.await?;
 				// Ok all good
 				Ok(Value::None)
 			}
@@ -123,9 +160,13 @@ impl DefineUserStatement {
 
 impl Display for DefineUserStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "DEFINE USER")?;
+		if self.if_not_exists {
+			write!(f, " IF NOT EXISTS")?
+		}
 		write!(
 			f,
-			"DEFINE USER {} ON {} PASSHASH {} ROLES {}",
+			" {} ON {} PASSHASH {} ROLES {}",
 			self.name,
 			self.base,
 			quote_str(&self.hash),
@@ -143,6 +184,7 @@ impl Display for DefineUserStatement {
 pub fn user(i: &str) -> IResult<&str, DefineUserStatement> {
 	let (i, _) = tag_no_case("USER")(i)?;
 	let (i, _) = shouldbespace(i)?;
+	let (i, if_not_exists) = if_not_exists(i)?;
 	let (i, (name, base, opts)) = cut(|i| {
 		let (i, name) = ident(i)?;
 		let (i, _) = shouldbespace(i)?;
@@ -163,6 +205,7 @@ pub fn user(i: &str) -> IResult<&str, DefineUserStatement> {
 			.take(128)
 			.map(char::from)
 			.collect::<String>(),
+		if_not_exists,
 		..Default::default()
 	};
 	// Assign any defined options
@@ -4,12 +4,12 @@ use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::sql::block::{block, Block, Entry};
 use crate::sql::comment::{mightbespace, shouldbespace};
+use crate::sql::error::tag_no_case;
 use crate::sql::error::{expect_tag_no_case, IResult};
 use crate::sql::param::{param, Param};
 use crate::sql::value::{value, Value};
 use async_recursion::async_recursion;
 use derive::Store;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
@@ -28,6 +28,17 @@ impl ForeachStatement {
 	pub(crate) fn writeable(&self) -> bool {
 		self.range.writeable() || self.block.writeable()
 	}
+	/// Substitute any nondeterministic functions with literal values
+	#[cfg_attr(not(target_arch = "wasm32"), async_recursion)]
+	#[cfg_attr(target_arch = "wasm32", async_recursion(?Send))]
+	pub(crate) async fn resolve(&mut self, ctx: &Context<'_>, opt: &Options, txn: &Transaction) {
+		self.range.resolve(ctx, opt, txn).await;
+		// The value of the loop parameter is not known ahead of time
+		let mut ctx = Context::new(ctx);
+		let key = self.param.0.to_raw();
+		ctx.add_value(key.clone(), Value::Param(key.into()));
+		self.block.resolve(&ctx, opt, txn).await;
+	}
 	/// Process this type returning a computed simple Value
 	#[cfg_attr(not(target_arch = "wasm32"), async_recursion)]
 	#[cfg_attr(target_arch = "wasm32", async_recursion(?Send))]
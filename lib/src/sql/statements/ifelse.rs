@@ -4,11 +4,11 @@ use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::sql::block::block;
 use crate::sql::comment::shouldbespace;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::fmt::{fmt_separated_by, is_pretty, pretty_indent, Fmt, Pretty};
 use crate::sql::value::{value, Value};
 use derive::Store;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use nom::combinator::{into, opt};
 use nom::sequence::terminated;
@@ -33,6 +33,16 @@ impl IfelseStatement {
 		}
 		self.close.as_ref().map_or(false, |v| v.writeable())
 	}
+	/// Substitute any nondeterministic functions with literal values
+	pub(crate) async fn resolve(&mut self, ctx: &Context<'_>, opt: &Options, txn: &Transaction) {
+		for (cond, then) in self.exprs.iter_mut() {
+			cond.resolve(ctx, opt, txn).await;
+			then.resolve(ctx, opt, txn).await;
+		}
+		if let Some(v) = &mut self.close {
+			v.resolve(ctx, opt, txn).await;
+		}
+	}
 	/// Check if we require a writeable transaction
 	pub(crate) fn bracketed(&self) -> bool {
 		self.exprs.iter().all(|(_, v)| matches!(v, Value::Block(_)))
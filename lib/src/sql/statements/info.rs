@@ -5,18 +5,28 @@ use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::iam::Action;
 use crate::iam::ResourceKind;
+use crate::idx::builder::BuildingState;
+use crate::idx::ft::FtIndex;
+use crate::idx::stats::IndexStats;
+use crate::idx::trees::hnsw::HnswIndex;
+use crate::idx::trees::mtree::MTreeIndex;
+use crate::idx::trees::rtree::RTreeIndex;
+use crate::idx::trees::store::TreeStoreType;
+use crate::idx::IndexKeyBase;
 use crate::sql::base::base;
 use crate::sql::comment::shouldbespace;
+use crate::sql::error::deprecated;
 use crate::sql::error::expected;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::ExplainResultExt;
 use crate::sql::error::IResult;
 use crate::sql::ident::{ident, Ident};
+use crate::sql::index::Index;
 use crate::sql::object::Object;
 use crate::sql::value::Value;
 use crate::sql::Base;
 use derive::Store;
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use nom::combinator::opt;
 use revision::revisioned;
@@ -32,6 +42,7 @@ pub enum InfoStatement {
 	Sc(Ident),
 	Tb(Ident),
 	User(Ident, Option<Base>),
+	Ix(Ident, Ident),
 }
 
 impl InfoStatement {
@@ -126,6 +137,24 @@ impl InfoStatement {
 					tmp.insert(v.name.to_string(), v.to_string().into());
 				}
 				res.insert("params".to_owned(), tmp.into());
+				// Process the configs
+				let mut tmp = Object::default();
+				for v in run.all_db_configs(opt.ns(), opt.db()).await?.iter() {
+					tmp.insert(v.name.to_string(), v.to_string().into());
+				}
+				res.insert("configs".to_owned(), tmp.into());
+				// Process the sequences
+				let mut tmp = Object::default();
+				for v in run.all_db_sequences(opt.ns(), opt.db()).await?.iter() {
+					tmp.insert(v.name.to_string(), v.to_string().into());
+				}
+				res.insert("sequences".to_owned(), tmp.into());
+				// Process the migrations
+				let mut tmp = Object::default();
+				for v in run.all_db_migrations(opt.ns(), opt.db()).await?.iter() {
+					tmp.insert(v.version.to_string(), v.to_string().into());
+				}
+				res.insert("migrations".to_owned(), tmp.into());
 				// Process the scopes
 				let mut tmp = Object::default();
 				for v in run.all_sc(opt.ns(), opt.db()).await?.iter() {
@@ -194,6 +223,21 @@ impl InfoStatement {
 					tmp.insert(v.name.to_string(), v.to_string().into());
 				}
 				res.insert("indexes".to_owned(), tmp.into());
+				// Process the indexes being built
+				let mut tmp = Object::default();
+				for v in run.all_tb_indexes(opt.ns(), opt.db(), tb).await?.iter() {
+					if v.concurrently {
+						if let Some(s) = BuildingState::get(&mut run, opt.ns(), opt.db(), v).await?
+						{
+							let s = s.status(&mut run, opt.ns(), opt.db(), v).await?;
+							tmp.insert(v.name.to_string(), s);
+						}
+					}
+				}
+				// Only output the indexes being built, if there are any
+				if !tmp.is_empty() {
+					res.insert("building".to_owned(), tmp.into());
+				}
 				// Process the live queries
 				let mut tmp = Object::default();
 				for v in run.all_tb_lives(opt.ns(), opt.db(), tb).await?.iter() {
@@ -220,6 +264,51 @@ impl InfoStatement {
 				// Ok all good
 				Value::from(res.to_string()).ok()
 			}
+			InfoStatement::Ix(tb, ix) => {
+				// Allowed to run?
+				opt.is_allowed(Action::View, ResourceKind::Index, &Base::Db)?;
+				// Claim transaction
+				let mut run = txn.lock().await;
+				// Read the index
+				let ix = run.get_and_cache_tb_index(opt.ns(), opt.db(), tb, ix).await?;
+				// Create the result set
+				let mut res: Object =
+					IndexStats::get(&mut run, opt.ns(), opt.db(), &ix).await?.into();
+				// Process the depth of the index trees
+				let ikb = IndexKeyBase::new(opt, &ix);
+				let depth = match &ix.index {
+					Index::Search(p) => {
+						let az = run.get_db_analyzer(opt.ns(), opt.db(), p.az.as_str()).await?;
+						let ft =
+							FtIndex::new(&mut run, az, ikb, p, TreeStoreType::Traversal).await?;
+						ft.statistics(&mut run).await?.max_depth().into()
+					}
+					Index::MTree(p) => {
+						let mt =
+							MTreeIndex::new(&mut run, ikb, p, TreeStoreType::Traversal).await?;
+						mt.depth(&mut run).await?.into()
+					}
+					Index::Hnsw(p) => {
+						let hnsw =
+							HnswIndex::new(&mut run, ikb, p, TreeStoreType::Traversal).await?;
+						hnsw.depth().into()
+					}
+					Index::RTree(p) => {
+						let rt =
+							RTreeIndex::new(&mut run, ikb, p, TreeStoreType::Traversal).await?;
+						rt.depth().into()
+					}
+					_ => Value::None,
+				};
+				res.insert("depth".to_owned(), depth);
+				// Process the background build
+				if let Some(s) = BuildingState::get(&mut run, opt.ns(), opt.db(), &ix).await? {
+					let s = s.status(&mut run, opt.ns(), opt.db(), &ix).await?;
+					res.insert("building".to_owned(), s);
+				}
+				// Ok all good
+				Value::from(res).ok()
+			}
 		}
 	}
 }
@@ -236,6 +325,7 @@ impl fmt::Display for InfoStatement {
 				Some(ref b) => write!(f, "INFO FOR USER {u} ON {b}"),
 				None => write!(f, "INFO FOR USER {u}"),
 			},
+			Self::Ix(ref t, ref i) => write!(f, "INFO FOR INDEX {i} ON {t}"),
 		}
 	}
 }
@@ -246,13 +336,16 @@ pub fn info(i: &str) -> IResult<&str, InfoStatement> {
 	let (i, _) = tag_no_case("FOR")(i)?;
 	let (i, _) = cut(shouldbespace)(i)?;
 	expected(
-		"ROOT, NAMESPACE, DATABASE, SCOPE, TABLE or USER",
-		cut(alt((root, ns, db, sc, tb, user))),
+		"ROOT, NAMESPACE, DATABASE, SCOPE, TABLE, USER or INDEX",
+		cut(alt((root, ns, db, sc, tb, user, ix))),
 	)(i)
 }
 
 fn root(i: &str) -> IResult<&str, InfoStatement> {
-	let (i, _) = alt((tag_no_case("ROOT"), tag_no_case("KV")))(i)?;
+	let (i, _) = alt((
+		tag_no_case("ROOT"),
+		deprecated("KV is deprecated, use ROOT instead", tag_no_case("KV")),
+	))(i)?;
 	Ok((i, InfoStatement::Root))
 }
 
@@ -280,6 +373,23 @@ fn tb(i: &str) -> IResult<&str, InfoStatement> {
 	Ok((i, InfoStatement::Tb(table)))
 }
 
+fn ix(i: &str) -> IResult<&str, InfoStatement> {
+	let (i, _) = alt((tag_no_case("INDEX"), tag_no_case("IX")))(i)?;
+	let (i, _) = shouldbespace(i)?;
+	cut(|i| {
+		let (i, index) = ident(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, _) = tag_no_case("ON")(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, _) = opt(|i| {
+			let (i, _) = tag_no_case("TABLE")(i)?;
+			shouldbespace(i)
+		})(i)?;
+		let (i, table) = ident(i)?;
+		Ok((i, InfoStatement::Ix(table, index)))
+	})(i)
+}
+
 fn user(i: &str) -> IResult<&str, InfoStatement> {
 	let (i, _) = alt((tag_no_case("USER"), tag_no_case("US")))(i)?;
 	let (i, _) = shouldbespace(i)?;
@@ -376,4 +486,13 @@ mod tests {
 		assert_eq!(out, InfoStatement::User(Ident::from("test"), None));
 		assert_eq!("INFO FOR USER test", format!("{}", out));
 	}
+
+	#[test]
+	fn info_query_ix() {
+		let sql = "INFO FOR INDEX idx ON TABLE test";
+		let res = info(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out, InfoStatement::Ix(Ident::from("test"), Ident::from("idx")));
+		assert_eq!("INFO FOR INDEX idx ON test", format!("{}", out));
+	}
 }
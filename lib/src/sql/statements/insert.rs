@@ -8,6 +8,7 @@ use crate::err::Error;
 use crate::sql::comment::shouldbespace;
 use crate::sql::data::{single, update, values, Data};
 use crate::sql::error::expected;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::ExplainResultExt;
 use crate::sql::error::IResult;
 use crate::sql::output::{output, Output};
@@ -18,7 +19,6 @@ use crate::sql::value::value;
 use crate::sql::value::Value;
 use derive::Store;
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use nom::combinator::{map, opt};
 use nom::sequence::preceded;
@@ -44,6 +44,14 @@ impl InsertStatement {
 	pub(crate) fn writeable(&self) -> bool {
 		true
 	}
+	/// Substitute any nondeterministic functions with literal values
+	pub(crate) async fn resolve(&mut self, ctx: &Context<'_>, opt: &Options, txn: &Transaction) {
+		self.into.resolve(ctx, opt, txn).await;
+		self.data.resolve(ctx, opt, txn).await;
+		if let Some(v) = &mut self.update {
+			v.resolve(ctx, opt, txn).await;
+		}
+	}
 	/// Process this type returning a computed simple Value
 	pub(crate) async fn compute(
 		&self,
@@ -4,24 +4,29 @@ use crate::dbs::Transaction;
 use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::sql::comment::shouldbespace;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::param::param;
-use crate::sql::uuid::uuid;
+use crate::sql::uuid::{uuid, Uuid};
 use crate::sql::value::Value;
 use derive::Store;
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::into;
+use nom::combinator::opt;
+use nom::sequence::terminated;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct KillStatement {
-	// Uuid of Live Query
-	// or Param resolving to Uuid of Live Query
+	// Uuid of Live Query or running operation
+	// or Param resolving to Uuid of Live Query or running operation
 	pub id: Value,
+	// Whether this cancels a running query, rather than a Live Query
+	#[revision(start = 2)]
+	pub query: bool,
 }
 
 impl KillStatement {
@@ -33,10 +38,10 @@ impl KillStatement {
 		txn: &Transaction,
 		_doc: Option<&CursorDoc<'_>>,
 	) -> Result<Value, Error> {
-		// Is realtime enabled?
-		opt.realtime()?;
-		// Valid options?
-		opt.valid_for_db()?;
+		// Cancel a running query
+		if self.query {
+			return self.kill_query(ctx, opt, txn).await;
+		}
 		// Resolve live query id
 		let live_query_id = match &self.id {
 			Value::Uuid(id) => id.clone(),
@@ -54,6 +59,16 @@ impl KillStatement {
 				})
 			}
 		};
+		// Cancel the running operation, such as an index build, with this id
+		if let Some(queries) = ctx.get_queries() {
+			if queries.cancel(opt, &live_query_id).is_ok() {
+				return Ok(Value::None);
+			}
+		}
+		// Is realtime enabled?
+		opt.realtime()?;
+		// Valid options?
+		opt.valid_for_db()?;
 		// Claim transaction
 		let mut run = txn.lock().await;
 		// Fetch the live query key
@@ -85,22 +100,65 @@ impl KillStatement {
 		// Return the query id
 		Ok(Value::None)
 	}
+
+	/// Cancel a query which is running on the datastore
+	async fn kill_query(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+	) -> Result<Value, Error> {
+		// Resolve the query id
+		let id = match self.id.compute(ctx, opt, txn, None).await? {
+			Value::Uuid(id) => id,
+			Value::Strand(id) => match Uuid::try_from(id.as_str()) {
+				Ok(id) => id,
+				Err(_) => {
+					return Err(Error::KillStatement {
+						value: self.id.to_string(),
+					})
+				}
+			},
+			_ => {
+				return Err(Error::KillStatement {
+					value: self.id.to_string(),
+				})
+			}
+		};
+		// Cancel the query
+		match ctx.get_queries() {
+			Some(queries) => queries.cancel(opt, &id)?,
+			None => {
+				return Err(Error::QueryNotRunning {
+					value: id.to_raw(),
+				})
+			}
+		}
+		// Return nothing
+		Ok(Value::None)
+	}
 }
 
 impl fmt::Display for KillStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "KILL {}", self.id)
+		if self.query {
+			write!(f, "KILL QUERY {}", self.id)
+		} else {
+			write!(f, "KILL {}", self.id)
+		}
 	}
 }
 
 pub fn kill(i: &str) -> IResult<&str, KillStatement> {
 	let (i, _) = tag_no_case("KILL")(i)?;
 	let (i, _) = shouldbespace(i)?;
+	let (i, query) = opt(terminated(tag_no_case("QUERY"), shouldbespace))(i)?;
 	let (i, v) = alt((into(uuid), into(param)))(i)?;
 	Ok((
 		i,
 		KillStatement {
 			id: v,
+			query: query.is_some(),
 		},
 	))
 }
@@ -121,7 +179,8 @@ mod tests {
 		assert_eq!(
 			out,
 			KillStatement {
-				id: Value::Uuid(Uuid::from(uuid::Uuid::parse_str(uuid_str).unwrap()))
+				id: Value::Uuid(Uuid::from(uuid::Uuid::parse_str(uuid_str).unwrap())),
+				query: false,
 			}
 		);
 		assert_eq!("KILL 'c005b8da-63a4-48bc-a371-07e95b39d58e'", format!("{}", out));
@@ -136,8 +195,24 @@ mod tests {
 			out,
 			KillStatement {
 				id: Value::Param(Param(Ident("id".to_string()))),
+				query: false,
 			}
 		);
 		assert_eq!("KILL $id", format!("{}", out));
 	}
+
+	#[test]
+	fn kill_query() {
+		let sql = "KILL QUERY $id";
+		let res = kill(sql);
+		let out = res.unwrap().1;
+		assert_eq!(
+			out,
+			KillStatement {
+				id: Value::Param(Param(Ident("id".to_string()))),
+				query: true,
+			}
+		);
+		assert_eq!("KILL QUERY $id", format!("{}", out));
+	}
 }
@@ -7,16 +7,17 @@ use crate::iam::Auth;
 use crate::sql::comment::shouldbespace;
 use crate::sql::cond::{cond, Cond};
 use crate::sql::error::expect_tag_no_case;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::fetch::{fetch, Fetchs};
 use crate::sql::field::{fields, Fields};
 use crate::sql::param::param;
+use crate::sql::special::check_no_window_fields;
 use crate::sql::table::table;
 use crate::sql::value::Value;
 use crate::sql::Uuid;
 use derive::Store;
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use nom::combinator::into;
 use nom::combinator::map;
@@ -133,6 +134,7 @@ pub fn live(i: &str) -> IResult<&str, LiveStatement> {
 	let (i, _) = shouldbespace(i)?;
 	cut(|i| {
 		let (i, expr) = alt((map(tag_no_case("DIFF"), |_| Fields::default()), fields))(i)?;
+		check_no_window_fields(i, &expr, "window functions can not be used in a LIVE statement")?;
 		let (i, _) = shouldbespace(i)?;
 		let (i, _) = expect_tag_no_case("FROM")(i)?;
 		let (i, _) = shouldbespace(i)?;
@@ -1,7 +1,10 @@
+pub(crate) mod alter;
 pub(crate) mod analyze;
+pub(crate) mod apply;
 pub(crate) mod begin;
 pub(crate) mod r#break;
 pub(crate) mod cancel;
+pub(crate) mod check;
 pub(crate) mod commit;
 pub(crate) mod r#continue;
 pub(crate) mod create;
@@ -16,17 +19,26 @@ pub(crate) mod live;
 pub(crate) mod option;
 pub(crate) mod output;
 pub(crate) mod relate;
+pub(crate) mod release;
 pub(crate) mod remove;
+pub(crate) mod rollback;
+pub(crate) mod run;
+pub(crate) mod savepoint;
 pub(crate) mod select;
 pub(crate) mod set;
+pub(crate) mod setoption;
 pub(crate) mod show;
 pub(crate) mod sleep;
+pub(crate) mod suggest;
 pub(crate) mod throw;
 pub(crate) mod update;
+pub(crate) mod upsert;
 pub(crate) mod r#use;
 
+pub use self::apply::ApplyStatement;
 pub use self::begin::BeginStatement;
 pub use self::cancel::CancelStatement;
+pub use self::check::CheckStatement;
 pub use self::commit::CommitStatement;
 pub use self::create::CreateStatement;
 pub use self::delete::DeleteStatement;
@@ -42,33 +54,50 @@ pub use self::r#break::BreakStatement;
 pub use self::r#continue::ContinueStatement;
 pub use self::r#use::UseStatement;
 pub use self::relate::RelateStatement;
+pub use self::release::ReleaseStatement;
+pub use self::rollback::RollbackStatement;
+pub use self::run::RunStatement;
+pub use self::savepoint::SavepointStatement;
 pub use self::select::SelectStatement;
 pub use self::set::SetStatement;
+pub use self::setoption::SetOptionStatement;
+pub use self::suggest::SuggestStatement;
 pub use self::throw::ThrowStatement;
 pub use self::update::UpdateStatement;
+pub use self::upsert::UpsertStatement;
+
+pub use self::alter::AlterFieldStatement;
+pub use self::alter::AlterStatement;
+pub use self::alter::AlterTableStatement;
 
 pub use self::define::DefineAnalyzerStatement;
+pub use self::define::DefineConfigStatement;
 pub use self::define::DefineDatabaseStatement;
 pub use self::define::DefineEventStatement;
 pub use self::define::DefineFieldStatement;
 pub use self::define::DefineFunctionStatement;
 pub use self::define::DefineIndexStatement;
+pub use self::define::DefineMigrationStatement;
 pub use self::define::DefineNamespaceStatement;
 pub use self::define::DefineParamStatement;
 pub use self::define::DefineScopeStatement;
+pub use self::define::DefineSequenceStatement;
 pub use self::define::DefineStatement;
 pub use self::define::DefineTableStatement;
 pub use self::define::DefineTokenStatement;
 pub use self::define::DefineUserStatement;
 
+pub use self::remove::RemoveConfigStatement;
 pub use self::remove::RemoveDatabaseStatement;
 pub use self::remove::RemoveEventStatement;
 pub use self::remove::RemoveFieldStatement;
 pub use self::remove::RemoveFunctionStatement;
 pub use self::remove::RemoveIndexStatement;
+pub use self::remove::RemoveMigrationStatement;
 pub use self::remove::RemoveNamespaceStatement;
 pub use self::remove::RemoveParamStatement;
 pub use self::remove::RemoveScopeStatement;
+pub use self::remove::RemoveSequenceStatement;
 pub use self::remove::RemoveStatement;
 pub use self::remove::RemoveTableStatement;
 pub use self::remove::RemoveTokenStatement;
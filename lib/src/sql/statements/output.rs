@@ -3,11 +3,11 @@ use crate::dbs::{Options, Transaction};
 use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::sql::comment::shouldbespace;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::fetch::{fetch, Fetchs};
 use crate::sql::value::{value, Value};
 use derive::Store;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::opt;
 use nom::sequence::preceded;
 use revision::revisioned;
@@ -26,6 +26,10 @@ impl OutputStatement {
 	pub(crate) fn writeable(&self) -> bool {
 		self.what.writeable()
 	}
+	/// Substitute any nondeterministic functions with literal values
+	pub(crate) async fn resolve(&mut self, ctx: &Context<'_>, opt: &Options, txn: &Transaction) {
+		self.what.resolve(ctx, opt, txn).await;
+	}
 	/// Process this type returning a computed simple Value
 	pub(crate) async fn compute(
 		&self,
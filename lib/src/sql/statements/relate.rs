@@ -10,6 +10,7 @@ use crate::sql::comment::mightbespace;
 use crate::sql::comment::shouldbespace;
 use crate::sql::data::{data, Data};
 use crate::sql::error::expected;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::output::{output, Output};
 use crate::sql::param::param;
@@ -20,7 +21,7 @@ use crate::sql::timeout::{timeout, Timeout};
 use crate::sql::value::Value;
 use derive::Store;
 use nom::branch::alt;
-use nom::bytes::complete::{tag, tag_no_case};
+use nom::bytes::complete::tag;
 use nom::combinator::cut;
 use nom::combinator::into;
 use nom::combinator::opt;
@@ -50,6 +51,15 @@ impl RelateStatement {
 	pub(crate) fn writeable(&self) -> bool {
 		true
 	}
+	/// Substitute any nondeterministic functions with literal values
+	pub(crate) async fn resolve(&mut self, ctx: &Context<'_>, opt: &Options, txn: &Transaction) {
+		self.kind.resolve(ctx, opt, txn).await;
+		self.from.resolve(ctx, opt, txn).await;
+		self.with.resolve(ctx, opt, txn).await;
+		if let Some(v) = &mut self.data {
+			v.resolve(ctx, opt, txn).await;
+		}
+	}
 	/// Process this type returning a computed simple Value
 	pub(crate) async fn compute(
 		&self,
@@ -0,0 +1,57 @@
+use crate::sql::comment::shouldbespace;
+use crate::sql::error::tag_no_case;
+use crate::sql::error::IResult;
+use crate::sql::ident::{ident, Ident};
+use derive::Store;
+use nom::combinator::{cut, opt};
+use nom::sequence::tuple;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub struct ReleaseStatement {
+	pub name: Ident,
+}
+
+impl fmt::Display for ReleaseStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "RELEASE SAVEPOINT {}", self.name)
+	}
+}
+
+pub fn release(i: &str) -> IResult<&str, ReleaseStatement> {
+	let (i, _) = tag_no_case("RELEASE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = opt(tuple((tag_no_case("SAVEPOINT"), shouldbespace)))(i)?;
+	let (i, name) = cut(ident)(i)?;
+	Ok((
+		i,
+		ReleaseStatement {
+			name,
+		},
+	))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn release_basic() {
+		let sql = "RELEASE before_payment";
+		let res = release(sql);
+		let out = res.unwrap().1;
+		assert_eq!("RELEASE SAVEPOINT before_payment", format!("{}", out))
+	}
+
+	#[test]
+	fn release_savepoint() {
+		let sql = "RELEASE SAVEPOINT before_payment";
+		let res = release(sql);
+		let out = res.unwrap().1;
+		assert_eq!("RELEASE SAVEPOINT before_payment", format!("{}", out))
+	}
+}
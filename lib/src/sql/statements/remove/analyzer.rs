@@ -5,20 +5,23 @@ use crate::err::Error;
 use crate::iam::{Action, ResourceKind};
 use crate::sql::base::Base;
 use crate::sql::comment::shouldbespace;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::ident::{ident, Ident};
+use crate::sql::statements::remove::if_exists;
 use crate::sql::value::Value;
 use derive::Store;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Formatter};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct RemoveAnalyzerStatement {
 	pub name: Ident,
+	#[revision(start = 2)]
+	pub if_exists: bool,
 }
 
 impl RemoveAnalyzerStatement {
@@ -36,6 +39,10 @@ impl RemoveAnalyzerStatement {
 		run.clear_cache();
 		// Delete the definition
 		let key = crate::key::database::az::new(opt.ns(), opt.db(), &self.name);
+		// Check if the definition exists
+		if self.if_exists && !run.exi(key.clone()).await? {
+			return Ok(Value::None);
+		}
 		run.del(key).await?;
 		// TODO Check that the analyzer is not used in any schema
 		// Ok all good
@@ -45,18 +52,24 @@ impl RemoveAnalyzerStatement {
 
 impl Display for RemoveAnalyzerStatement {
 	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-		write!(f, "REMOVE ANALYZER {}", self.name)
+		write!(f, "REMOVE ANALYZER")?;
+		if self.if_exists {
+			write!(f, " IF EXISTS")?
+		}
+		write!(f, " {}", self.name)
 	}
 }
 
 pub fn analyzer(i: &str) -> IResult<&str, RemoveAnalyzerStatement> {
 	let (i, _) = tag_no_case("ANALYZER")(i)?;
 	let (i, _) = shouldbespace(i)?;
+	let (i, if_exists) = if_exists(i)?;
 	let (i, name) = cut(ident)(i)?;
 	Ok((
 		i,
 		RemoveAnalyzerStatement {
 			name,
+			if_exists,
 		},
 	))
 }
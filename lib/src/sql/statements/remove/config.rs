@@ -0,0 +1,74 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::err::Error;
+use crate::iam::{Action, ResourceKind};
+use crate::sql::base::Base;
+use crate::sql::comment::shouldbespace;
+use crate::sql::error::tag_no_case;
+use crate::sql::error::IResult;
+use crate::sql::ident::{ident, Ident};
+use crate::sql::statements::remove::if_exists;
+use crate::sql::value::Value;
+use derive::Store;
+use nom::combinator::cut;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub struct RemoveConfigStatement {
+	pub name: Ident,
+	pub if_exists: bool,
+}
+
+impl RemoveConfigStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		_ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+	) -> Result<Value, Error> {
+		// Allowed to run?
+		opt.is_allowed(Action::Edit, ResourceKind::Config, &Base::Db)?;
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Clear the cache
+		run.clear_cache();
+		// Delete the definition
+		let key = crate::key::database::cg::new(opt.ns(), opt.db(), &self.name);
+		// Check if the definition exists
+		if self.if_exists && !run.exi(key.clone()).await? {
+			return Ok(Value::None);
+		}
+		run.del(key).await?;
+		// Ok all good
+		Ok(Value::None)
+	}
+}
+
+impl Display for RemoveConfigStatement {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		write!(f, "REMOVE CONFIG")?;
+		if self.if_exists {
+			write!(f, " IF EXISTS")?
+		}
+		write!(f, " {}", self.name)
+	}
+}
+
+pub fn config(i: &str) -> IResult<&str, RemoveConfigStatement> {
+	let (i, _) = tag_no_case("CONFIG")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, if_exists) = if_exists(i)?;
+	let (i, name) = cut(ident)(i)?;
+	Ok((
+		i,
+		RemoveConfigStatement {
+			name,
+			if_exists,
+		},
+	))
+}
@@ -5,21 +5,24 @@ use crate::err::Error;
 use crate::iam::{Action, ResourceKind};
 use crate::sql::base::Base;
 use crate::sql::comment::shouldbespace;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::ident::{ident, Ident};
+use crate::sql::statements::remove::if_exists;
 use crate::sql::value::Value;
 use derive::Store;
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Formatter};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct RemoveDatabaseStatement {
 	pub name: Ident,
+	#[revision(start = 2)]
+	pub if_exists: bool,
 }
 
 impl RemoveDatabaseStatement {
@@ -38,6 +41,10 @@ impl RemoveDatabaseStatement {
 		run.clear_cache();
 		// Delete the definition
 		let key = crate::key::namespace::db::new(opt.ns(), &self.name);
+		// Check if the definition exists
+		if self.if_exists && !run.exi(key.clone()).await? {
+			return Ok(Value::None);
+		}
 		run.del(key).await?;
 		// Delete the resource data
 		let key = crate::key::database::all::new(opt.ns(), &self.name);
@@ -49,18 +56,24 @@ impl RemoveDatabaseStatement {
 
 impl Display for RemoveDatabaseStatement {
 	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-		write!(f, "REMOVE DATABASE {}", self.name)
+		write!(f, "REMOVE DATABASE")?;
+		if self.if_exists {
+			write!(f, " IF EXISTS")?
+		}
+		write!(f, " {}", self.name)
 	}
 }
 
 pub fn database(i: &str) -> IResult<&str, RemoveDatabaseStatement> {
 	let (i, _) = alt((tag_no_case("DB"), tag_no_case("DATABASE")))(i)?;
 	let (i, _) = shouldbespace(i)?;
+	let (i, if_exists) = if_exists(i)?;
 	let (i, name) = cut(ident)(i)?;
 	Ok((
 		i,
 		RemoveDatabaseStatement {
 			name,
+			if_exists,
 		},
 	))
 }
@@ -6,11 +6,12 @@ use crate::iam::{Action, ResourceKind};
 use crate::sql::base::Base;
 use crate::sql::comment::shouldbespace;
 use crate::sql::error::expect_tag_no_case;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::ident::{ident, Ident};
+use crate::sql::statements::remove::if_exists;
 use crate::sql::value::Value;
 use derive::Store;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use nom::combinator::opt;
 use nom::sequence::tuple;
@@ -19,10 +20,12 @@ use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Formatter};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct RemoveEventStatement {
 	pub name: Ident,
 	pub what: Ident,
+	#[revision(start = 2)]
+	pub if_exists: bool,
 }
 
 impl RemoveEventStatement {
@@ -41,6 +44,10 @@ impl RemoveEventStatement {
 		run.clear_cache();
 		// Delete the definition
 		let key = crate::key::table::ev::new(opt.ns(), opt.db(), &self.what, &self.name);
+		// Check if the definition exists
+		if self.if_exists && !run.exi(key.clone()).await? {
+			return Ok(Value::None);
+		}
 		run.del(key).await?;
 		// Clear the cache
 		let key = crate::key::table::ev::prefix(opt.ns(), opt.db(), &self.what);
@@ -52,13 +59,18 @@ impl RemoveEventStatement {
 
 impl Display for RemoveEventStatement {
 	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-		write!(f, "REMOVE EVENT {} ON {}", self.name, self.what)
+		write!(f, "REMOVE EVENT")?;
+		if self.if_exists {
+			write!(f, " IF EXISTS")?
+		}
+		write!(f, " {} ON {}", self.name, self.what)
 	}
 }
 
 pub fn event(i: &str) -> IResult<&str, RemoveEventStatement> {
 	let (i, _) = tag_no_case("EVENT")(i)?;
 	let (i, _) = shouldbespace(i)?;
+	let (i, if_exists) = if_exists(i)?;
 	let (i, name) = cut(ident)(i)?;
 	let (i, _) = shouldbespace(i)?;
 	let (i, _) = expect_tag_no_case("ON")(i)?;
@@ -70,6 +82,7 @@ pub fn event(i: &str) -> IResult<&str, RemoveEventStatement> {
 		RemoveEventStatement {
 			name,
 			what,
+			if_exists,
 		},
 	))
 }
@@ -6,13 +6,15 @@ use crate::iam::{Action, ResourceKind};
 use crate::sql::base::Base;
 use crate::sql::comment::shouldbespace;
 use crate::sql::error::expect_tag_no_case;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::ident::{ident, Ident};
 use crate::sql::idiom;
 use crate::sql::idiom::Idiom;
+use crate::sql::statements::remove::if_exists;
+use crate::sql::statements::DefineFieldStatement;
 use crate::sql::value::Value;
 use derive::Store;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use nom::combinator::opt;
 use nom::sequence::tuple;
@@ -21,10 +23,12 @@ use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Formatter};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct RemoveFieldStatement {
 	pub name: Idiom,
 	pub what: Ident,
+	#[revision(start = 2)]
+	pub if_exists: bool,
 }
 
 impl RemoveFieldStatement {
@@ -44,10 +48,23 @@ impl RemoveFieldStatement {
 		// Delete the definition
 		let fd = self.name.to_string();
 		let key = crate::key::table::fd::new(opt.ns(), opt.db(), &self.what, &fd);
+		// Check if the definition exists
+		if self.if_exists && !run.exi(key.clone()).await? {
+			return Ok(Value::None);
+		}
+		// Check if the field was deduplicated
+		let dedup = match run.get(key.clone()).await? {
+			Some(v) => DefineFieldStatement::from(v).dedup,
+			None => false,
+		};
 		run.del(key).await?;
 		// Clear the cache
 		let key = crate::key::table::fd::prefix(opt.ns(), opt.db(), &self.what);
 		run.clr(key).await?;
+		// Store the values of a field which is no longer deduplicated in its records
+		if dedup {
+			crate::doc::restore(&mut run, opt.ns(), opt.db(), &self.what, &self.name).await?;
+		}
 		// Ok all good
 		Ok(Value::None)
 	}
@@ -55,13 +72,18 @@ impl RemoveFieldStatement {
 
 impl Display for RemoveFieldStatement {
 	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-		write!(f, "REMOVE FIELD {} ON {}", self.name, self.what)
+		write!(f, "REMOVE FIELD")?;
+		if self.if_exists {
+			write!(f, " IF EXISTS")?
+		}
+		write!(f, " {} ON {}", self.name, self.what)
 	}
 }
 
 pub fn field(i: &str) -> IResult<&str, RemoveFieldStatement> {
 	let (i, _) = tag_no_case("FIELD")(i)?;
 	let (i, _) = shouldbespace(i)?;
+	let (i, if_exists) = if_exists(i)?;
 	let (i, name) = cut(idiom::local)(i)?;
 	let (i, _) = shouldbespace(i)?;
 	let (i, _) = expect_tag_no_case("ON")(i)?;
@@ -73,6 +95,7 @@ pub fn field(i: &str) -> IResult<&str, RemoveFieldStatement> {
 		RemoveFieldStatement {
 			name,
 			what,
+			if_exists,
 		},
 	))
 }
@@ -5,13 +5,14 @@ use crate::err::Error;
 use crate::iam::{Action, ResourceKind};
 use crate::sql::base::Base;
 use crate::sql::comment::{mightbespace, shouldbespace};
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::ident;
 use crate::sql::ident::Ident;
+use crate::sql::statements::remove::if_exists;
 use crate::sql::value::Value;
 use derive::Store;
 use nom::bytes::complete::tag;
-use nom::bytes::complete::tag_no_case;
 use nom::character::complete::char;
 use nom::combinator::opt;
 use revision::revisioned;
@@ -19,9 +20,11 @@ use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct RemoveFunctionStatement {
 	pub name: Ident,
+	#[revision(start = 2)]
+	pub if_exists: bool,
 }
 
 impl RemoveFunctionStatement {
@@ -40,6 +43,10 @@ impl RemoveFunctionStatement {
 		run.clear_cache();
 		// Delete the definition
 		let key = crate::key::database::fc::new(opt.ns(), opt.db(), &self.name);
+		// Check if the definition exists
+		if self.if_exists && !run.exi(key.clone()).await? {
+			return Ok(Value::None);
+		}
 		run.del(key).await?;
 		// Ok all good
 		Ok(Value::None)
@@ -48,13 +55,18 @@ impl RemoveFunctionStatement {
 
 impl Display for RemoveFunctionStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "REMOVE FUNCTION fn::{}", self.name)
+		write!(f, "REMOVE FUNCTION")?;
+		if self.if_exists {
+			write!(f, " IF EXISTS")?
+		}
+		write!(f, " fn::{}", self.name)
 	}
 }
 
 pub fn function(i: &str) -> IResult<&str, RemoveFunctionStatement> {
 	let (i, _) = tag_no_case("FUNCTION")(i)?;
 	let (i, _) = shouldbespace(i)?;
+	let (i, if_exists) = if_exists(i)?;
 	let (i, _) = tag("fn::")(i)?;
 	let (i, name) = ident::plain(i)?;
 	let (i, _) = opt(|i| {
@@ -68,6 +80,7 @@ pub fn function(i: &str) -> IResult<&str, RemoveFunctionStatement> {
 		i,
 		RemoveFunctionStatement {
 			name,
+			if_exists,
 		},
 	))
 }
@@ -6,11 +6,12 @@ use crate::iam::{Action, ResourceKind};
 use crate::sql::base::Base;
 use crate::sql::comment::shouldbespace;
 use crate::sql::error::expect_tag_no_case;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::ident::{ident, Ident};
+use crate::sql::statements::remove::if_exists;
 use crate::sql::value::Value;
 use derive::Store;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use nom::combinator::opt;
 use nom::sequence::tuple;
@@ -19,10 +20,12 @@ use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Formatter};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct RemoveIndexStatement {
 	pub name: Ident,
 	pub what: Ident,
+	#[revision(start = 2)]
+	pub if_exists: bool,
 }
 
 impl RemoveIndexStatement {
@@ -41,10 +44,23 @@ impl RemoveIndexStatement {
 		run.clear_cache();
 		// Delete the definition
 		let key = crate::key::table::ix::new(opt.ns(), opt.db(), &self.what, &self.name);
+		// Check if the definition exists
+		if self.if_exists && !run.exi(key.clone()).await? {
+			return Ok(Value::None);
+		}
 		run.del(key).await?;
 		// Remove the index data
 		let key = crate::key::index::all::new(opt.ns(), opt.db(), &self.what, &self.name);
 		run.delp(key, u32::MAX).await?;
+		// Remove any background build state
+		let key = crate::key::index::ib::Ib::new(opt.ns(), opt.db(), &self.what, &self.name);
+		run.del(key).await?;
+		// Remove the index statistics
+		let key = crate::key::index::is::Is::new(opt.ns(), opt.db(), &self.what, &self.name);
+		run.del(key).await?;
+		let beg = crate::key::index::ic::Ic::prefix(opt.ns(), opt.db(), &self.what, &self.name);
+		let end = crate::key::index::ic::Ic::suffix(opt.ns(), opt.db(), &self.what, &self.name);
+		run.delr(beg..end, u32::MAX).await?;
 		// Clear the cache
 		let key = crate::key::table::ix::prefix(opt.ns(), opt.db(), &self.what);
 		run.clr(key).await?;
@@ -55,13 +71,18 @@ impl RemoveIndexStatement {
 
 impl Display for RemoveIndexStatement {
 	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-		write!(f, "REMOVE INDEX {} ON {}", self.name, self.what)
+		write!(f, "REMOVE INDEX")?;
+		if self.if_exists {
+			write!(f, " IF EXISTS")?
+		}
+		write!(f, " {} ON {}", self.name, self.what)
 	}
 }
 
 pub fn index(i: &str) -> IResult<&str, RemoveIndexStatement> {
 	let (i, _) = tag_no_case("INDEX")(i)?;
 	let (i, _) = shouldbespace(i)?;
+	let (i, if_exists) = if_exists(i)?;
 	let (i, name) = cut(ident)(i)?;
 	let (i, _) = shouldbespace(i)?;
 	let (i, _) = expect_tag_no_case("ON")(i)?;
@@ -73,6 +94,7 @@ pub fn index(i: &str) -> IResult<&str, RemoveIndexStatement> {
 		RemoveIndexStatement {
 			name,
 			what,
+			if_exists,
 		},
 	))
 }
@@ -1,26 +1,32 @@
 mod analyzer;
+mod config;
 mod database;
 mod event;
 mod field;
 mod function;
 mod index;
+mod migration;
 mod namespace;
 mod param;
 mod scope;
+mod sequence;
 mod table;
 mod token;
 mod user;
 
+use crate::sql::error::tag_no_case;
 pub use analyzer::{analyzer, RemoveAnalyzerStatement};
+pub use config::{config, RemoveConfigStatement};
 pub use database::{database, RemoveDatabaseStatement};
 pub use event::{event, RemoveEventStatement};
 pub use field::{field, RemoveFieldStatement};
 pub use function::{function, RemoveFunctionStatement};
 pub use index::{index, RemoveIndexStatement};
+pub use migration::{migration, RemoveMigrationStatement};
 pub use namespace::{namespace, RemoveNamespaceStatement};
-use nom::bytes::complete::tag_no_case;
 pub use param::{param, RemoveParamStatement};
 pub use scope::{scope, RemoveScopeStatement};
+pub use sequence::{sequence, RemoveSequenceStatement};
 pub use table::{table, RemoveTableStatement};
 pub use token::{token, RemoveTokenStatement};
 pub use user::{user, RemoveUserStatement};
@@ -35,7 +41,8 @@ use crate::sql::error::IResult;
 use crate::sql::value::Value;
 use derive::Store;
 use nom::branch::alt;
-use nom::combinator::map;
+use nom::combinator::{map, opt};
+use nom::sequence::tuple;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Formatter};
@@ -55,6 +62,9 @@ pub enum RemoveStatement {
 	Field(RemoveFieldStatement),
 	Index(RemoveIndexStatement),
 	User(RemoveUserStatement),
+	Config(RemoveConfigStatement),
+	Sequence(RemoveSequenceStatement),
+	Migration(RemoveMigrationStatement),
 }
 
 impl RemoveStatement {
@@ -83,6 +93,9 @@ impl RemoveStatement {
 			Self::Index(ref v) => v.compute(ctx, opt, txn).await,
 			Self::Analyzer(ref v) => v.compute(ctx, opt, txn).await,
 			Self::User(ref v) => v.compute(ctx, opt, txn).await,
+			Self::Config(ref v) => v.compute(ctx, opt, txn).await,
+			Self::Sequence(ref v) => v.compute(ctx, opt, txn).await,
+			Self::Migration(ref v) => v.compute(ctx, opt, txn).await,
 		}
 	}
 }
@@ -102,6 +115,9 @@ impl Display for RemoveStatement {
 			Self::Index(v) => Display::fmt(v, f),
 			Self::Analyzer(v) => Display::fmt(v, f),
 			Self::User(v) => Display::fmt(v, f),
+			Self::Config(v) => Display::fmt(v, f),
+			Self::Sequence(v) => Display::fmt(v, f),
+			Self::Migration(v) => Display::fmt(v, f),
 		}
 	}
 }
@@ -122,9 +138,20 @@ pub fn remove(i: &str) -> IResult<&str, RemoveStatement> {
 		map(index, RemoveStatement::Index),
 		map(analyzer, RemoveStatement::Analyzer),
 		map(user, RemoveStatement::User),
+		map(config, RemoveStatement::Config),
+		map(sequence, RemoveStatement::Sequence),
+		map(migration, RemoveStatement::Migration),
 	))(i)
 }
 
+/// Parses an optional `IF EXISTS` clause, which makes a removal do nothing if there is
+/// nothing to remove
+pub(crate) fn if_exists(i: &str) -> IResult<&str, bool> {
+	let (i, v) =
+		opt(tuple((tag_no_case("IF"), shouldbespace, tag_no_case("EXISTS"), shouldbespace)))(i)?;
+	Ok((i, v.is_some()))
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -135,8 +162,25 @@ mod tests {
 	fn check_remove_serialize() {
 		let stm = RemoveStatement::Namespace(RemoveNamespaceStatement {
 			name: Ident::from("test"),
+			..Default::default()
 		});
 		let enc: Vec<u8> = stm.try_into().unwrap();
-		assert_eq!(9, enc.len());
+		assert_eq!(10, enc.len());
+	}
+
+	#[test]
+	fn remove_if_exists() {
+		let sql = "REMOVE TABLE IF EXISTS person";
+		let res = remove(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		let sql = "REMOVE FIELD IF EXISTS name ON person";
+		let res = remove(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		let sql = "REMOVE NAMESPACE IF EXISTS test";
+		let res = remove(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
 	}
 }
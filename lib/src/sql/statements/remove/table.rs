@@ -5,20 +5,23 @@ use crate::err::Error;
 use crate::iam::{Action, ResourceKind};
 use crate::sql::base::Base;
 use crate::sql::comment::shouldbespace;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::ident::{ident, Ident};
+use crate::sql::statements::remove::if_exists;
 use crate::sql::value::Value;
 use derive::Store;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Formatter};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct RemoveTableStatement {
 	pub name: Ident,
+	#[revision(start = 2)]
+	pub if_exists: bool,
 }
 
 impl RemoveTableStatement {
@@ -36,7 +39,12 @@ impl RemoveTableStatement {
 		// Clear the cache
 		run.clear_cache();
 		// Get the defined table
-		let tb = run.get_tb(opt.ns(), opt.db(), &self.name).await?;
+		let tb = match run.get_tb(opt.ns(), opt.db(), &self.name).await {
+			Err(Error::TbNotFound {
+				..
+			}) if self.if_exists => return Ok(Value::None),
+			v => v?,
+		};
 		// Delete the definition
 		let key = crate::key::database::tb::new(opt.ns(), opt.db(), &self.name);
 		run.del(key).await?;
@@ -59,18 +67,24 @@ impl RemoveTableStatement {
 
 impl Display for RemoveTableStatement {
 	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-		write!(f, "REMOVE TABLE {}", self.name)
+		write!(f, "REMOVE TABLE")?;
+		if self.if_exists {
+			write!(f, " IF EXISTS")?
+		}
+		write!(f, " {}", self.name)
 	}
 }
 
 pub fn table(i: &str) -> IResult<&str, RemoveTableStatement> {
 	let (i, _) = tag_no_case("TABLE")(i)?;
 	let (i, _) = shouldbespace(i)?;
+	let (i, if_exists) = if_exists(i)?;
 	let (i, name) = cut(ident)(i)?;
 	Ok((
 		i,
 		RemoveTableStatement {
 			name,
+			if_exists,
 		},
 	))
 }
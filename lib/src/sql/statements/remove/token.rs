@@ -6,21 +6,24 @@ use crate::iam::{Action, ResourceKind};
 use crate::sql::base::{base_or_scope, Base};
 use crate::sql::comment::shouldbespace;
 use crate::sql::error::expect_tag_no_case;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::ident::{ident, Ident};
+use crate::sql::statements::remove::if_exists;
 use crate::sql::value::Value;
 use derive::Store;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Formatter};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct RemoveTokenStatement {
 	pub name: Ident,
 	pub base: Base,
+	#[revision(start = 2)]
+	pub if_exists: bool,
 }
 
 impl RemoveTokenStatement {
@@ -42,6 +45,10 @@ impl RemoveTokenStatement {
 				run.clear_cache();
 				// Delete the definition
 				let key = crate::key::namespace::tk::new(opt.ns(), &self.name);
+				// Check if the definition exists
+				if self.if_exists && !run.exi(key.clone()).await? {
+					return Ok(Value::None);
+				}
 				run.del(key).await?;
 				// Ok all good
 				Ok(Value::None)
@@ -53,6 +60,10 @@ impl RemoveTokenStatement {
 				run.clear_cache();
 				// Delete the definition
 				let key = crate::key::database::tk::new(opt.ns(), opt.db(), &self.name);
+				// Check if the definition exists
+				if self.if_exists && !run.exi(key.clone()).await? {
+					return Ok(Value::None);
+				}
 				run.del(key).await?;
 				// Ok all good
 				Ok(Value::None)
@@ -64,6 +75,10 @@ impl RemoveTokenStatement {
 				run.clear_cache();
 				// Delete the definition
 				let key = crate::key::scope::tk::new(opt.ns(), opt.db(), sc, &self.name);
+				// Check if the definition exists
+				if self.if_exists && !run.exi(key.clone()).await? {
+					return Ok(Value::None);
+				}
 				run.del(key).await?;
 				// Ok all good
 				Ok(Value::None)
@@ -75,13 +90,18 @@ impl RemoveTokenStatement {
 
 impl Display for RemoveTokenStatement {
 	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-		write!(f, "REMOVE TOKEN {} ON {}", self.name, self.base)
+		write!(f, "REMOVE TOKEN")?;
+		if self.if_exists {
+			write!(f, " IF EXISTS")?
+		}
+		write!(f, " {} ON {}", self.name, self.base)
 	}
 }
 
 pub fn token(i: &str) -> IResult<&str, RemoveTokenStatement> {
 	let (i, _) = tag_no_case("TOKEN")(i)?;
 	let (i, _) = shouldbespace(i)?;
+	let (i, if_exists) = if_exists(i)?;
 	let (i, name) = cut(ident)(i)?;
 	let (i, _) = shouldbespace(i)?;
 	let (i, _) = expect_tag_no_case("ON")(i)?;
@@ -92,6 +112,7 @@ pub fn token(i: &str) -> IResult<&str, RemoveTokenStatement> {
 		RemoveTokenStatement {
 			name,
 			base,
+			if_exists,
 		},
 	))
 }
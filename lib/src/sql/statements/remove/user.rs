@@ -6,21 +6,24 @@ use crate::iam::{Action, ResourceKind};
 use crate::sql::base::{base, Base};
 use crate::sql::comment::shouldbespace;
 use crate::sql::error::expect_tag_no_case;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::ident::{ident, Ident};
+use crate::sql::statements::remove::if_exists;
 use crate::sql::value::Value;
 use derive::Store;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Formatter};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct RemoveUserStatement {
 	pub name: Ident,
 	pub base: Base,
+	#[revision(start = 2)]
+	pub if_exists: bool,
 }
 
 impl RemoveUserStatement {
@@ -42,6 +45,10 @@ impl RemoveUserStatement {
 				run.clear_cache();
 				// Process the statement
 				let key = crate::key::root::us::new(&self.name);
+				// Check if the definition exists
+				if self.if_exists && !run.exi(key.clone()).await? {
+					return Ok(Value::None);
+				}
 				run.del(key).await?;
 				// Ok all good
 				Ok(Value::None)
@@ -53,6 +60,10 @@ impl RemoveUserStatement {
 				run.clear_cache();
 				// Delete the definition
 				let key = crate::key::namespace::us::new(opt.ns(), &self.name);
+				// Check if the definition exists
+				if self.if_exists && !run.exi(key.clone()).await? {
+					return Ok(Value::None);
+				}
 				run.del(key).await?;
 				// Ok all good
 				Ok(Value::None)
@@ -64,6 +75,10 @@ impl RemoveUserStatement {
 				run.clear_cache();
 				// Delete the definition
 				let key = crate::key::database::us::new(opt.ns(), opt.db(), &self.name);
+				// Check if the definition exists
+				if self.if_exists && !run.exi(key.clone()).await? {
+					return Ok(Value::None);
+				}
 				run.del(key).await?;
 				// Ok all good
 				Ok(Value::None)
@@ -75,13 +90,18 @@ impl RemoveUserStatement {
 
 impl Display for RemoveUserStatement {
 	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-		write!(f, "REMOVE USER {} ON {}", self.name, self.base)
+		write!(f, "REMOVE USER")?;
+		if self.if_exists {
+			write!(f, " IF EXISTS")?
+		}
+		write!(f, " {} ON {}", self.name, self.base)
 	}
 }
 
 pub fn user(i: &str) -> IResult<&str, RemoveUserStatement> {
 	let (i, _) = tag_no_case("USER")(i)?;
 	let (i, _) = shouldbespace(i)?;
+	let (i, if_exists) = if_exists(i)?;
 	let (i, name) = cut(ident)(i)?;
 	let (i, _) = shouldbespace(i)?;
 	let (i, _) = expect_tag_no_case("ON")(i)?;
@@ -92,6 +112,7 @@ pub fn user(i: &str) -> IResult<&str, RemoveUserStatement> {
 		RemoveUserStatement {
 			name,
 			base,
+			if_exists,
 		},
 	))
 }
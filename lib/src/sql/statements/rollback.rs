@@ -0,0 +1,60 @@
+use crate::sql::comment::shouldbespace;
+use crate::sql::error::expect_tag_no_case;
+use crate::sql::error::tag_no_case;
+use crate::sql::error::IResult;
+use crate::sql::ident::{ident, Ident};
+use derive::Store;
+use nom::combinator::{cut, opt};
+use nom::sequence::tuple;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub struct RollbackStatement {
+	pub name: Ident,
+}
+
+impl fmt::Display for RollbackStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "ROLLBACK TO SAVEPOINT {}", self.name)
+	}
+}
+
+pub fn rollback(i: &str) -> IResult<&str, RollbackStatement> {
+	let (i, _) = tag_no_case("ROLLBACK")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = expect_tag_no_case("TO")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = opt(tuple((tag_no_case("SAVEPOINT"), shouldbespace)))(i)?;
+	let (i, name) = cut(ident)(i)?;
+	Ok((
+		i,
+		RollbackStatement {
+			name,
+		},
+	))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn rollback_basic() {
+		let sql = "ROLLBACK TO before_payment";
+		let res = rollback(sql);
+		let out = res.unwrap().1;
+		assert_eq!("ROLLBACK TO SAVEPOINT before_payment", format!("{}", out))
+	}
+
+	#[test]
+	fn rollback_savepoint() {
+		let sql = "ROLLBACK TO SAVEPOINT before_payment";
+		let res = rollback(sql);
+		let out = res.unwrap().1;
+		assert_eq!("ROLLBACK TO SAVEPOINT before_payment", format!("{}", out))
+	}
+}
@@ -0,0 +1,136 @@
+use crate::ctx::Context;
+use crate::dbs::{Options, Transaction};
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::iam::{Action, Auth, ResourceKind};
+use crate::sql::block::{block, Block};
+use crate::sql::comment::{mightbespace, shouldbespace};
+use crate::sql::error::tag_no_case;
+use crate::sql::error::{expect_tag_no_case, IResult};
+use crate::sql::ident::{ident, Ident};
+use crate::sql::value::{value, Value};
+use crate::sql::Base;
+use async_recursion::async_recursion;
+use derive::Store;
+use nom::combinator::{cut, opt};
+use nom::sequence::preceded;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+use std::sync::Arc;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub struct RunStatement {
+	pub what: Value,
+	pub scope: Option<Ident>,
+	pub block: Block,
+}
+
+impl RunStatement {
+	/// Check if we require a writeable transaction
+	pub(crate) fn writeable(&self) -> bool {
+		self.what.writeable() || self.block.writeable()
+	}
+	/// Process this type returning a computed simple Value
+	#[cfg_attr(not(target_arch = "wasm32"), async_recursion)]
+	#[cfg_attr(target_arch = "wasm32", async_recursion(?Send))]
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&'async_recursion CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		// Allowed to run?
+		opt.is_allowed(Action::Edit, ResourceKind::Actor, &Base::Ns)?;
+		// Selected DB?
+		opt.valid_for_db()?;
+		// Check the impersonated record
+		let rid = match self.what.compute(ctx, opt, txn, doc).await? {
+			Value::Thing(v) => v,
+			v => {
+				return Err(Error::InvalidStatementTarget {
+					value: v.to_string(),
+				})
+			}
+		};
+		// Run as the scope user, with permissions checked
+		let sc = self.scope.as_ref().map(Ident::to_raw);
+		let auth = Auth::for_sc(rid.to_string(), opt.ns(), opt.db(), sc.as_deref().unwrap_or(""));
+		let opt = &opt.clone().with_auth(Arc::new(auth)).with_perms(true);
+		// Set the scope authentication data
+		let mut ctx = Context::new(ctx);
+		ctx.add_value("auth", Value::from(rid));
+		ctx.add_value("scope", Value::from(sc));
+		// Process the block
+		self.block.compute(&ctx, opt, txn, doc).await
+	}
+}
+
+impl Display for RunStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "RUN AS SCOPE {}", self.what)?;
+		if let Some(ref v) = self.scope {
+			write!(f, " ON {v}")?
+		}
+		write!(f, " {}", self.block)
+	}
+}
+
+pub fn run(i: &str) -> IResult<&str, RunStatement> {
+	let (i, _) = tag_no_case("RUN")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("AS")(i)?;
+	let (i, (what, scope, block)) = cut(|i| {
+		let (i, _) = shouldbespace(i)?;
+		let (i, _) = expect_tag_no_case("SCOPE")(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, what) = value(i)?;
+		let (i, scope) = opt(preceded(
+			shouldbespace,
+			preceded(tag_no_case("ON"), preceded(shouldbespace, ident)),
+		))(i)?;
+		let (i, _) = mightbespace(i)?;
+		let (i, block) = block(i)?;
+		Ok((i, (what, scope, block)))
+	})(i)?;
+	Ok((
+		i,
+		RunStatement {
+			what,
+			scope,
+			block,
+		},
+	))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn run_statement() {
+		let sql = "RUN AS SCOPE user:tobie { SELECT * FROM person; }";
+		let res = run(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out))
+	}
+
+	#[test]
+	fn run_statement_on_scope() {
+		let sql = "RUN AS SCOPE user:tobie ON account { CREATE post SET title = 'test'; }";
+		let res = run(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out.scope, Some(Ident::from("account")));
+		assert_eq!(sql, format!("{}", out))
+	}
+
+	#[test]
+	fn run_statement_without_scope_keyword() {
+		let sql = "RUN AS user:tobie { SELECT * FROM person; }";
+		let res = run(sql);
+		assert!(res.is_err());
+	}
+}
@@ -0,0 +1,47 @@
+use crate::sql::comment::shouldbespace;
+use crate::sql::error::tag_no_case;
+use crate::sql::error::IResult;
+use crate::sql::ident::{ident, Ident};
+use derive::Store;
+use nom::combinator::cut;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub struct SavepointStatement {
+	pub name: Ident,
+}
+
+impl fmt::Display for SavepointStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "SAVEPOINT {}", self.name)
+	}
+}
+
+pub fn savepoint(i: &str) -> IResult<&str, SavepointStatement> {
+	let (i, _) = tag_no_case("SAVEPOINT")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, name) = cut(ident)(i)?;
+	Ok((
+		i,
+		SavepointStatement {
+			name,
+		},
+	))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn savepoint_basic() {
+		let sql = "SAVEPOINT before_payment";
+		let res = savepoint(sql);
+		let out = res.unwrap().1;
+		assert_eq!("SAVEPOINT before_payment", format!("{}", out))
+	}
+}
@@ -5,32 +5,38 @@ use crate::dbs::Statement;
 use crate::dbs::{Iterable, Transaction};
 use crate::doc::CursorDoc;
 use crate::err::Error;
+use crate::idx::planner::count::count_from_indexes;
 use crate::idx::planner::QueryPlanner;
+use crate::sql::block::Entry;
 use crate::sql::comment::shouldbespace;
 use crate::sql::cond::{cond, Cond};
 use crate::sql::ending;
 use crate::sql::error::expect_tag_no_case;
 use crate::sql::error::expected;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
-use crate::sql::explain::{explain, Explain};
-use crate::sql::fetch::{fetch, Fetchs};
+use crate::sql::explain::{explain, explain_analyze, Explain};
+use crate::sql::expression::Expression;
+use crate::sql::fetch::{fetch, fetch_size, Fetchs};
 use crate::sql::field::{fields, Field, Fields};
 use crate::sql::group::{group, Groups};
 use crate::sql::idiom::Idioms;
 use crate::sql::limit::{limit, Limit};
 use crate::sql::omit::omit;
+use crate::sql::operator::Operator;
 use crate::sql::order::{order, Orders};
 use crate::sql::special::check_group_by_fields;
 use crate::sql::special::check_order_by_fields;
 use crate::sql::special::check_split_on_fields;
 use crate::sql::split::{split, Splits};
 use crate::sql::start::{start, Start};
+use crate::sql::statements::OutputStatement;
+use crate::sql::subquery::Subquery;
 use crate::sql::timeout::{timeout, Timeout};
 use crate::sql::value::{selects, Value, Values};
 use crate::sql::version::{version, Version};
 use crate::sql::with::{with, With};
 use derive::Store;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use nom::combinator::opt;
 use nom::combinator::peek;
@@ -40,7 +46,7 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 2)]
+#[revisioned(revision = 5)]
 pub struct SelectStatement {
 	pub expr: Fields,
 	pub omit: Option<Idioms>,
@@ -59,6 +65,14 @@ pub struct SelectStatement {
 	pub timeout: Option<Timeout>,
 	pub parallel: bool,
 	pub explain: Option<Explain>,
+	#[revision(start = 3)]
+	pub profile: bool,
+	/// Whether the actual rows and elapsed time of each iterator are explained
+	#[revision(start = 4)]
+	pub analyze: bool,
+	/// The number of records in each batch, when the results are fetched through a cursor
+	#[revision(start = 5)]
+	pub fetch_size: Option<u64>,
 }
 
 impl SelectStatement {
@@ -70,6 +84,10 @@ impl SelectStatement {
 				expr,
 				..
 			} => expr.writeable(),
+			Field::Window {
+				expr,
+				..
+			} => expr.writeable(),
 		}) {
 			return true;
 		}
@@ -78,6 +96,24 @@ impl SelectStatement {
 		}
 		self.cond.as_ref().map_or(false, |v| v.writeable())
 	}
+	/// Substitute any nondeterministic functions with literal values
+	pub(crate) async fn resolve(&mut self, ctx: &Context<'_>, opt: &Options, txn: &Transaction) {
+		for v in self.expr.0.iter_mut() {
+			if let Field::Single {
+				expr,
+				..
+			} = v
+			{
+				expr.resolve(ctx, opt, txn).await;
+			}
+		}
+		for v in self.what.0.iter_mut() {
+			v.resolve(ctx, opt, txn).await;
+		}
+		if let Some(v) = &mut self.cond {
+			v.0.resolve(ctx, opt, txn).await;
+		}
+	}
 
 	/// Process this type returning a computed simple Value
 	pub(crate) async fn compute(
@@ -89,22 +125,64 @@ impl SelectStatement {
 	) -> Result<Value, Error> {
 		// Valid options?
 		opt.valid_for_db()?;
+		// Inline the query of a parameterised view
+		if let Some((stm, args)) = self.inline(ctx, opt, txn, doc).await? {
+			// Duplicate context
+			let mut ctx = Context::new(ctx);
+			// Process the view arguments
+			for (name, val) in args {
+				ctx.add_value(name, val);
+			}
+			// Iterate over the view targets
+			return stm.iterate(&ctx, opt, txn, doc).await;
+		}
+		// Iterate over the select targets
+		self.iterate(ctx, opt, txn, doc).await
+	}
+
+	/// Process the targets of this statement, returning the selected records
+	async fn iterate(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
 		// Create a new iterator
 		let mut i = Iterator::new();
 		// Ensure futures are stored
 		let opt = &opt.new_with_futures(false).with_projections(true);
+		// Grouped counts may be answered from the statistics of the indexes
+		if let Some(v) = count_from_indexes(ctx, opt, txn, self).await? {
+			return Ok(v);
+		}
+		// Only plain projections can be answered from an index
+		let fields = match (&self.split, &self.group, &self.order, &self.fetch) {
+			(None, None, None, None) => Some(&self.expr),
+			_ => None,
+		};
+		// Only the records of a single table can be iterated in order
+		let order = match (&self.order, &self.split, &self.group, self.what.0.len()) {
+			(Some(o), None, None, 1) if !self.parallel => Some((o, &self.expr)),
+			_ => None,
+		};
 		// Get a query planner
-		let mut planner = QueryPlanner::new(opt, &self.with, &self.cond);
+		let mut planner = QueryPlanner::new(opt, &self.with, &self.cond, fields, order);
+		// Check if the archived records are queried
+		let archive = matches!(self.with, Some(With::Archive(_)));
 		// Loop over the select targets
 		for w in self.what.0.iter() {
 			let v = w.compute(ctx, opt, txn, doc).await?;
 			match v {
 				Value::Table(t) => {
+					if archive {
+						i.ingest(Iterable::Archive(t.clone()));
+					}
 					planner.add_iterables(ctx, txn, t, &mut i).await?;
 				}
 				Value::Thing(v) => i.ingest(Iterable::Thing(v)),
 				Value::Range(v) => i.ingest(Iterable::Range(*v)),
-				Value::Edges(v) => i.ingest(Iterable::Edges(*v)),
+				Value::Edges(v) => planner.add_edge_iterables(ctx, txn, *v, &mut i).await?,
 				Value::Model(v) => {
 					for v in v {
 						i.ingest(Iterable::Thing(v));
@@ -114,10 +192,15 @@ impl SelectStatement {
 					for v in v {
 						match v {
 							Value::Table(t) => {
+								if archive {
+									i.ingest(Iterable::Archive(t.clone()));
+								}
 								planner.add_iterables(ctx, txn, t, &mut i).await?;
 							}
 							Value::Thing(v) => i.ingest(Iterable::Thing(v)),
-							Value::Edges(v) => i.ingest(Iterable::Edges(*v)),
+							Value::Edges(v) => {
+								planner.add_edge_iterables(ctx, txn, *v, &mut i).await?
+							}
 							Value::Model(v) => {
 								for v in v {
 									i.ingest(Iterable::Thing(v));
@@ -134,22 +217,114 @@ impl SelectStatement {
 		let mut ctx = Context::new(ctx);
 		// Assign the statement
 		let stm = Statement::from(self);
-		// Add query executors if any
-		if planner.has_executors() {
+		// Add query executors, or the reasons the indexes were not used, if any
+		if planner.has_executors() || !planner.fallbacks().is_empty() {
 			ctx.set_query_planner(&planner);
 		}
 		// Output the results
-		match i.output(&ctx, opt, txn, &stm).await? {
+		let res = i.output(&ctx, opt, txn, &stm).await?;
+		// Get the profile of the statement, if profiled
+		let profile = match &res {
+			Value::Array(a) => i.profile(a.len()),
+			_ => None,
+		};
+		let res = match res {
 			// This is a single record result
 			Value::Array(mut a) if self.only => match a.len() {
 				// There was exactly one result
-				1 => Ok(a.remove(0)),
+				1 => a.remove(0),
 				// There were no results
-				_ => Err(Error::SingleOnlyOutput),
+				_ => return Err(Error::SingleOnlyOutput),
 			},
 			// This is standard query result
-			v => Ok(v),
+			v => v,
+		};
+		// Output the profile alongside the results
+		match profile {
+			Some(profile) => Ok(Value::from(map! {
+				String::from("result") => res,
+				String::from("profile") => profile,
+			})),
+			None => Ok(res),
+		}
+	}
+
+	/// A custom function which returns a single SELECT * statement is a
+	/// parameterised view. When a view is selected from, the query of the
+	/// view is returned with the conditions of this statement pushed into
+	/// it, so that the records can be found using the indexes of the view
+	/// tables, along with the view arguments which the query refers to.
+	async fn inline(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&CursorDoc<'_>>,
+	) -> Result<Option<(SelectStatement, Vec<(String, Value)>)>, Error> {
+		// Only a single view can be inlined
+		let f = match self.what.0.as_slice() {
+			[Value::Function(f)] if f.is_custom() => f,
+			_ => return Ok(None),
+		};
+		// Get the function definition and arguments
+		let (def, args) = f.prepare(ctx, opt, txn, doc).await?;
+		// Check the function returns a single SELECT statement
+		let view = match def.block.0.as_slice() {
+			[Entry::Select(v)] => v,
+			[Entry::Output(OutputStatement {
+				what: Value::Subquery(v),
+				fetch: None,
+			})]
+			| [Entry::Value(Value::Subquery(v))] => match v.as_ref() {
+				Subquery::Select(v) => v,
+				_ => return Ok(None),
+			},
+			_ => return Ok(None),
+		};
+		// Check the view selects whole records, without altering them
+		if view.expr != Fields::all()
+			|| view.omit.is_some()
+			|| view.only
+			|| view.with.is_some()
+			|| view.split.is_some()
+			|| view.group.is_some()
+			|| view.order.is_some()
+			|| view.limit.is_some()
+			|| view.start.is_some()
+			|| view.fetch.is_some()
+			|| view.version.is_some()
+			|| view.explain.is_some()
+			|| view.profile
+		{
+			return Ok(None);
+		}
+		// Check this statement does not refer to a parameter which the view arguments hide
+		let outer = SelectStatement {
+			what: Values::default(),
+			..self.clone()
+		}
+		.to_string();
+		if args.iter().any(|(name, _)| outer.contains(&format!("${name}"))) {
+			return Ok(None);
 		}
+		// Push the conditions of this statement into the view query
+		let cond = match (&view.cond, &self.cond) {
+			(Some(v), Some(c)) => Some(Cond(Value::from(Expression::Binary {
+				l: v.0.to_owned(),
+				o: Operator::And,
+				r: c.0.to_owned(),
+			}))),
+			(v, c) => v.to_owned().or_else(|| c.to_owned()),
+		};
+		// Select from the view tables
+		let stm = SelectStatement {
+			what: view.what.to_owned(),
+			cond,
+			timeout: self.timeout.to_owned().or_else(|| view.timeout.to_owned()),
+			parallel: self.parallel || view.parallel,
+			..self.clone()
+		};
+		Ok(Some((stm, args)))
 	}
 }
 
@@ -188,6 +363,9 @@ impl fmt::Display for SelectStatement {
 		if let Some(ref v) = self.fetch {
 			write!(f, " {v}")?
 		}
+		if let Some(v) = self.fetch_size {
+			write!(f, " FETCH SIZE {v}")?
+		}
 		if let Some(ref v) = self.version {
 			write!(f, " {v}")?
 		}
@@ -197,9 +375,14 @@ impl fmt::Display for SelectStatement {
 		if self.parallel {
 			f.write_str(" PARALLEL")?
 		}
-		if let Some(ref v) = self.explain {
+		if self.analyze {
+			f.write_str(" EXPLAIN ANALYZE")?
+		} else if let Some(ref v) = self.explain {
 			write!(f, " {v}")?
 		}
+		if self.profile {
+			f.write_str(" PROFILE")?
+		}
 		Ok(())
 	}
 }
@@ -224,13 +407,23 @@ pub fn select(i: &str) -> IResult<&str, SelectStatement> {
 	check_order_by_fields(i, &expr, &order)?;
 	let (i, limit) = opt(preceded(shouldbespace, limit))(i)?;
 	let (i, start) = opt(preceded(shouldbespace, start))(i)?;
+	let (i, fetch_size) = opt(preceded(shouldbespace, fetch_size))(i)?;
 	let (i, fetch) = opt(preceded(shouldbespace, fetch))(i)?;
+	let (i, fetch_size) = match fetch_size {
+		Some(v) => (i, Some(v)),
+		None => opt(preceded(shouldbespace, fetch_size))(i)?,
+	};
 	let (i, version) = opt(preceded(shouldbespace, version))(i)?;
 	let (i, timeout) = opt(preceded(shouldbespace, timeout))(i)?;
 	let (i, parallel) = opt(preceded(shouldbespace, tag_no_case("PARALLEL")))(i)?;
-	let (i, explain) = opt(preceded(shouldbespace, explain))(i)?;
+	let (i, analyze) = opt(preceded(shouldbespace, explain_analyze))(i)?;
+	let (i, explain) = match analyze {
+		Some(_) => (i, Some(Explain(true))),
+		None => opt(preceded(shouldbespace, explain))(i)?,
+	};
+	let (i, profile) = opt(preceded(shouldbespace, tag_no_case("PROFILE")))(i)?;
 	let (i, _) = expected(
-		"one of WITH, WHERE, SPLIT, GROUP, ORDER, LIMIT, START, FETCH, VERSION, TIMEOUT, PARELLEL, or EXPLAIN",
+		"one of WITH, WHERE, SPLIT, GROUP, ORDER, LIMIT, START, FETCH, VERSION, TIMEOUT, PARELLEL, EXPLAIN, or PROFILE",
 		cut(peek(ending::query))
 	)(i)?;
 
@@ -253,6 +446,9 @@ pub fn select(i: &str) -> IResult<&str, SelectStatement> {
 			timeout,
 			parallel: parallel.is_some(),
 			explain,
+			profile: profile.is_some(),
+			analyze: analyze.is_some(),
+			fetch_size,
 		},
 	))
 }
@@ -303,6 +499,27 @@ mod tests {
 		assert_eq!(sql, format!("{}", out))
 	}
 
+	#[test]
+	fn select_statement_fetch_size() {
+		let sql = "SELECT * FROM test FETCH author FETCH SIZE 100";
+		let res = select(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		assert_eq!(out.fetch_size, Some(100));
+		let res = select("SELECT * FROM test FETCH SIZE 100 FETCH author");
+		assert_eq!(res.unwrap().1, out);
+	}
+
+	#[test]
+	fn select_statement_profile() {
+		let sql = "SELECT * FROM test WHERE age > 18 EXPLAIN ANALYZE PROFILE";
+		let res = select(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		assert!(out.profile);
+		assert!(out.analyze);
+	}
+
 	#[test]
 	fn select_statement_table_thing() {
 		let sql = "SELECT *, ((1 + 3) / 4), 1.3999f AS tester FROM test, test:thingy";
@@ -6,24 +6,33 @@ use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::sql::comment::mightbespace;
 use crate::sql::comment::shouldbespace;
+use crate::sql::duration::{duration, Duration};
+use crate::sql::error::expected;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::ident::ident_raw;
 use crate::sql::value::{value, Value};
 use derive::Store;
-use nom::bytes::complete::tag_no_case;
+use nom::branch::alt;
 use nom::character::complete::char;
 use nom::combinator::cut;
 use nom::combinator::opt;
-use nom::sequence::{preceded, terminated};
+use nom::sequence::{preceded, terminated, tuple};
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct SetStatement {
 	pub name: String,
 	pub what: Value,
+	/// Whether the parameter is kept on the session for the following queries
+	#[revision(start = 2)]
+	pub session: bool,
+	/// How long the parameter is kept on the session
+	#[revision(start = 2)]
+	pub ttl: Option<Duration>,
 }
 
 impl SetStatement {
@@ -31,6 +40,10 @@ impl SetStatement {
 	pub(crate) fn writeable(&self) -> bool {
 		self.what.writeable()
 	}
+	/// Substitute any nondeterministic functions with literal values
+	pub(crate) async fn resolve(&mut self, ctx: &Context<'_>, opt: &Options, txn: &Transaction) {
+		self.what.resolve(ctx, opt, txn).await;
+	}
 	/// Process this type returning a computed simple Value
 	pub(crate) async fn compute(
 		&self,
@@ -42,7 +55,10 @@ impl SetStatement {
 		// Check if the variable is a protected variable
 		match PROTECTED_PARAM_NAMES.contains(&self.name.as_str()) {
 			// The variable isn't protected and can be stored
-			false => self.what.compute(ctx, opt, txn, doc).await,
+			false => {
+				let val = self.what.compute(ctx, opt, txn, doc).await?;
+				self.check(ctx, opt, txn, val).await
+			}
 			// The user tried to set a protected variable
 			true => Err(Error::InvalidParam {
 				// Move the parameter name, as we no longer need it
@@ -50,16 +66,54 @@ impl SetStatement {
 			}),
 		}
 	}
+	/// Checks the value against any database parameter defined with the same name
+	async fn check(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		val: Value,
+	) -> Result<Value, Error> {
+		// Database parameters can only be defined within a database
+		if opt.valid_for_db().is_err() {
+			return Ok(val);
+		}
+		let def = {
+			// Claim transaction
+			let mut run = txn.lock().await;
+			// Get the param definition
+			run.get_and_cache_db_param(opt.ns(), opt.db(), &self.name).await
+		};
+		match def {
+			// The param has been defined, so check the new value
+			Ok(def) => {
+				def.check_change(ctx, opt, txn, &val).await?;
+				def.check(ctx, opt, txn, val).await
+			}
+			// The param has not been defined
+			Err(Error::PaNotFound {
+				..
+			}) => Ok(val),
+			Err(e) => Err(e),
+		}
+	}
 }
 
 impl fmt::Display for SetStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "LET ${} = {}", self.name, self.what)
+		write!(f, "LET ${} = {}", self.name, self.what)?;
+		if self.session {
+			f.write_str(" FOR SESSION")?;
+		}
+		if let Some(ref v) = self.ttl {
+			write!(f, " TTL {v}")?;
+		}
+		Ok(())
 	}
 }
 
 pub fn set(i: &str) -> IResult<&str, SetStatement> {
-	let (i, _) = opt(terminated(tag_no_case("LET"), shouldbespace))(i)?;
+	let (i, _) = opt(terminated(alt((tag_no_case("LET"), tag_no_case("SET"))), shouldbespace))(i)?;
 	let (i, n) = preceded(char('$'), cut(ident_raw))(i)?;
 	let (i, _) = mightbespace(i)?;
 	let (i, _) = char('=')(i)?;
@@ -67,15 +121,30 @@ pub fn set(i: &str) -> IResult<&str, SetStatement> {
 		let (i, _) = mightbespace(i)?;
 		value(i)
 	})(i)?;
+	let (i, s) = opt(session)(i)?;
 	Ok((
 		i,
 		SetStatement {
 			name: n,
 			what: w,
+			session: s.is_some(),
+			ttl: s.flatten(),
 		},
 	))
 }
 
+fn session(i: &str) -> IResult<&str, Option<Duration>> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("FOR")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("SESSION")(i)?;
+	let (i, v) = opt(preceded(
+		tuple((shouldbespace, tag_no_case("TTL"), shouldbespace)),
+		cut(expected("a duration", duration)),
+	))(i)?;
+	Ok((i, v))
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -88,4 +157,33 @@ mod tests {
 		let out = res.unwrap().1;
 		assert_eq!("LET $name = NULL", format!("{}", out));
 	}
+
+	#[test]
+	fn let_statement_for_session() {
+		let sql = "SET $tenant = 'acme' FOR SESSION";
+		let res = set(sql);
+		let out = res.unwrap().1;
+		assert!(out.session);
+		assert_eq!(out.ttl, None);
+		assert_eq!("LET $tenant = 'acme' FOR SESSION", format!("{}", out));
+	}
+
+	#[test]
+	fn let_statement_for_session_with_ttl() {
+		let sql = "LET $beta = true FOR SESSION TTL 1h";
+		let res = set(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+
+		let serialized: Vec<u8> = (&out).try_into().unwrap();
+		let deserialized = SetStatement::try_from(&serialized).unwrap();
+		assert_eq!(out, deserialized);
+	}
+
+	#[test]
+	fn let_statement_for_session_invalid_ttl() {
+		let sql = "LET $beta = true FOR SESSION TTL soon";
+		let res = set(sql);
+		assert!(res.is_err());
+	}
 }
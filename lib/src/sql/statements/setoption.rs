@@ -0,0 +1,75 @@
+use crate::sql::comment::{mightbespace, shouldbespace};
+use crate::sql::error::tag_no_case;
+use crate::sql::error::IResult;
+use crate::sql::ident::{ident, Ident};
+use crate::sql::value::{value, Value};
+use derive::Store;
+use nom::character::complete::char;
+use nom::combinator::cut;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub struct SetOptionStatement {
+	pub name: Ident,
+	pub what: Value,
+}
+
+impl fmt::Display for SetOptionStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "SET OPTION {} = {}", self.name, self.what)
+	}
+}
+
+pub fn setoption(i: &str) -> IResult<&str, SetOptionStatement> {
+	let (i, _) = tag_no_case("SET")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("OPTION")(i)?;
+	cut(|i| {
+		let (i, _) = shouldbespace(i)?;
+		let (i, name) = ident(i)?;
+		let (i, _) = mightbespace(i)?;
+		let (i, _) = char('=')(i)?;
+		let (i, _) = mightbespace(i)?;
+		let (i, what) = value(i)?;
+		Ok((
+			i,
+			SetOptionStatement {
+				name,
+				what,
+			},
+		))
+	})(i)
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn set_option_statement() {
+		let sql = "SET OPTION TIMEOUT = 5s";
+		let res = setoption(sql);
+		let out = res.unwrap().1;
+		assert_eq!("SET OPTION TIMEOUT = 5s", format!("{}", out));
+	}
+
+	#[test]
+	fn set_option_statement_spaces() {
+		let sql = "set option batch_size=100";
+		let res = setoption(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out.what, Value::from(100));
+		assert_eq!("SET OPTION batch_size = 100", format!("{}", out));
+	}
+
+	#[test]
+	fn set_option_statement_invalid() {
+		let sql = "SET OPTION STRICT";
+		let res = setoption(sql);
+		assert!(res.is_err());
+	}
+}
@@ -10,14 +10,16 @@ use crate::sql::common::take_u64;
 use crate::sql::datetime::datetime;
 use crate::sql::error::expect_tag_no_case;
 use crate::sql::error::expected;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
+use crate::sql::statements::LiveStatement;
 use crate::sql::table::{table, Table};
 use crate::sql::value::Value;
 use crate::sql::Base;
 use crate::sql::Datetime;
+use crate::sql::Object;
 use derive::Store;
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
 use nom::character::complete::u32;
 use nom::combinator::cut;
 use nom::combinator::map;
@@ -36,24 +38,43 @@ pub enum ShowSince {
 }
 
 // ShowStatement is used to show changes in a table or database via
-// the SHOW CHANGES statement.
+// the SHOW CHANGES statement, or the running queries via the SHOW
+// QUERIES statement, or all the running operations via the SHOW OPERATIONS
+// statement.
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 3)]
 pub struct ShowStatement {
 	pub table: Option<Table>,
 	pub since: ShowSince,
 	pub limit: Option<u32>,
+	// Whether this lists the running queries, rather than the changes
+	#[revision(start = 2)]
+	pub queries: bool,
+	// Whether this lists the running operations and live queries, rather than the changes
+	#[revision(start = 3)]
+	pub operations: bool,
 }
 
 impl ShowStatement {
 	/// Process this type returning a computed simple Value
 	pub(crate) async fn compute(
 		&self,
-		_ctx: &Context<'_>,
+		ctx: &Context<'_>,
 		opt: &Options,
 		txn: &Transaction,
 		_doc: Option<&CursorDoc<'_>>,
 	) -> Result<Value, Error> {
+		// List the running queries
+		if self.queries {
+			return Ok(match ctx.get_queries() {
+				Some(queries) => queries.list(opt),
+				None => Value::from(Vec::<Value>::new()),
+			});
+		}
+		// List the running operations
+		if self.operations {
+			return self.operations(ctx, opt, txn).await;
+		}
 		// Selected DB?
 		opt.is_allowed(Action::View, ResourceKind::Table, &Base::Db)?;
 		// Clone transaction
@@ -80,10 +101,59 @@ impl ShowStatement {
 		let v: Value = Value::Array(crate::sql::array::Array(a));
 		Ok(v)
 	}
+
+	/// List the running operations, and the live queries of the selected database on this node
+	async fn operations(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+	) -> Result<Value, Error> {
+		let mut out = match ctx.get_queries() {
+			Some(queries) => queries.list_operations(opt, None),
+			None => Value::from(Vec::<Value>::new()),
+		};
+		// Live queries are only listed for users who can view the whole database
+		if opt.valid_for_db().is_err()
+			|| opt.is_allowed(Action::View, ResourceKind::Any, &Base::Db).is_err()
+		{
+			return Ok(out);
+		}
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Fetch the live queries of this node
+		for lq in run.scan_ndlq(&opt.id()?, u32::MAX).await? {
+			if lq.ns != opt.ns() || lq.db != opt.db() {
+				continue;
+			}
+			let key = crate::key::table::lq::new(opt.ns(), opt.db(), &lq.tb, lq.lq.0);
+			let query = match run.get(key).await? {
+				Some(v) => Value::from(LiveStatement::from(v).to_string()),
+				None => continue,
+			};
+			let obj = map! {
+				"id".to_owned() => Value::from(lq.lq),
+				"kind".to_owned() => Value::from("live"),
+				"query".to_owned() => query,
+				"ns".to_owned() => Value::from(lq.ns),
+				"db".to_owned() => Value::from(lq.db),
+			};
+			if let Value::Array(v) = &mut out {
+				v.push(Value::from(Object::from(obj)));
+			}
+		}
+		Ok(out)
+	}
 }
 
 impl fmt::Display for ShowStatement {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if self.queries {
+			return write!(f, "SHOW QUERIES");
+		}
+		if self.operations {
+			return write!(f, "SHOW OPERATIONS");
+		}
 		write!(f, "SHOW CHANGES FOR")?;
 		match self.table {
 			Some(ref v) => write!(f, " TABLE {}", v)?,
@@ -127,6 +197,38 @@ pub fn limit(i: &str) -> IResult<&str, u32> {
 pub fn show(i: &str) -> IResult<&str, ShowStatement> {
 	let (i, _) = tag_no_case("SHOW")(i)?;
 	let (i, _) = shouldbespace(i)?;
+	alt((queries, operations, changes))(i)
+}
+
+fn queries(i: &str) -> IResult<&str, ShowStatement> {
+	let (i, _) = tag_no_case("QUERIES")(i)?;
+	Ok((
+		i,
+		ShowStatement {
+			table: None,
+			since: ShowSince::Versionstamp(0),
+			limit: None,
+			queries: true,
+			operations: false,
+		},
+	))
+}
+
+fn operations(i: &str) -> IResult<&str, ShowStatement> {
+	let (i, _) = tag_no_case("OPERATIONS")(i)?;
+	Ok((
+		i,
+		ShowStatement {
+			table: None,
+			since: ShowSince::Versionstamp(0),
+			limit: None,
+			queries: false,
+			operations: true,
+		},
+	))
+}
+
+fn changes(i: &str) -> IResult<&str, ShowStatement> {
 	let (i, _) = tag_no_case("CHANGES")(i)?;
 	let (i, _) = shouldbespace(i)?;
 	cut(|i| {
@@ -141,6 +243,8 @@ pub fn show(i: &str) -> IResult<&str, ShowStatement> {
 				table,
 				since,
 				limit,
+				queries: false,
+				operations: false,
 			},
 		))
 	})(i)
@@ -165,6 +269,24 @@ mod tests {
 		assert!(res.unwrap().1.is_none())
 	}
 
+	#[test]
+	fn show_queries() {
+		let sql = "SHOW QUERIES";
+		let res = show(sql);
+		let out = res.unwrap().1;
+		assert!(out.queries);
+		assert_eq!(sql, format!("{}", out))
+	}
+
+	#[test]
+	fn show_operations() {
+		let sql = "SHOW OPERATIONS";
+		let res = show(sql);
+		let out = res.unwrap().1;
+		assert!(out.operations);
+		assert_eq!(sql, format!("{}", out))
+	}
+
 	#[test]
 	fn show_table_changes() {
 		let sql = "SHOW CHANGES FOR TABLE person";
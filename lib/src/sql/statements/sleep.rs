@@ -5,13 +5,17 @@ use crate::err::Error;
 use crate::iam::{Action, ResourceKind};
 use crate::sql::comment::shouldbespace;
 use crate::sql::duration::duration;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::{Base, Duration, Value};
 use derive::Store;
-use nom::bytes::complete::tag_no_case;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use trice::Instant;
+
+/// The interval at which a sleeping statement checks if it was cancelled
+const SLEEP_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
 #[revisioned(revision = 1)]
@@ -35,11 +39,18 @@ impl SleepStatement {
 			(Some(t), d) if t < d => t,
 			(_, d) => d,
 		};
-		// Sleep for the specified time
-		#[cfg(target_arch = "wasm32")]
-		wasmtimer::tokio::sleep(dur).await;
-		#[cfg(not(target_arch = "wasm32"))]
-		tokio::time::sleep(dur).await;
+		// Sleep for the specified time, or until the query is cancelled
+		let end = Instant::now() + dur;
+		while ctx.is_ok() {
+			let dur = end.saturating_duration_since(Instant::now());
+			if dur.is_zero() {
+				break;
+			}
+			#[cfg(target_arch = "wasm32")]
+			wasmtimer::tokio::sleep(dur.min(SLEEP_INTERVAL)).await;
+			#[cfg(not(target_arch = "wasm32"))]
+			tokio::time::sleep(dur.min(SLEEP_INTERVAL)).await;
+		}
 		// Ok all good
 		Ok(Value::None)
 	}
@@ -0,0 +1,174 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::iam::Action;
+use crate::iam::ResourceKind;
+use crate::idx::stats::IndexStats;
+use crate::sql::comment::shouldbespace;
+use crate::sql::error::expect_tag_no_case;
+use crate::sql::error::tag_no_case;
+use crate::sql::error::IResult;
+use crate::sql::statements::DefineIndexStatement;
+use crate::sql::{Base, Duration, Ident, Idiom, Idioms, Object, Value};
+use derive::Store;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// The number of records which are sampled to estimate the selectivity of a field
+const SAMPLE_SIZE: u32 = 1000;
+
+// SuggestStatement recommends the indexes which would speed up the
+// statements recorded in the slow query log of the datastore.
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub struct SuggestStatement;
+
+impl SuggestStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		_doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		// Allowed to run?
+		opt.is_allowed(Action::View, ResourceKind::Index, &Base::Db)?;
+		// Get the slow statements in this database
+		let filters = match ctx.get_slow_queries() {
+			Some(log) => log.filters(opt.ns(), opt.db()),
+			None => vec![],
+		};
+		// Total the slow statements by table and field
+		let mut found: Vec<(String, Idiom)> = vec![];
+		let mut totals: HashMap<(String, Idiom), (usize, std::time::Duration)> = HashMap::new();
+		for (tb, fd, time) in filters {
+			let key = (tb, fd);
+			let total = totals.entry(key.clone()).or_insert_with(|| {
+				found.push(key);
+				(0, std::time::Duration::ZERO)
+			});
+			total.0 += 1;
+			total.1 += time;
+		}
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Recommend an index for each field
+		let mut out = vec![];
+		for (tb, fd) in found {
+			// Skip fields which are already the first field of an index
+			let ix = run.all_tb_indexes(opt.ns(), opt.db(), &tb).await?;
+			if ix.iter().any(|ix| ix.cols.0.first() == Some(&fd)) {
+				continue;
+			}
+			// Estimate the number of records in the table from the statistics of its indexes
+			let mut records = None;
+			for ix in ix.iter() {
+				let stats = IndexStats::get(&mut run, opt.ns(), opt.db(), ix).await?;
+				records = records.max(stats.records());
+			}
+			// Estimate the selectivity of the field from a sample of the records
+			let beg = crate::key::thing::prefix(opt.ns(), opt.db(), &tb);
+			let end = crate::key::thing::suffix(opt.ns(), opt.db(), &tb);
+			let res = run.scan(beg..end, SAMPLE_SIZE).await?;
+			if res.is_empty() {
+				continue;
+			}
+			let mut distinct = HashMap::new();
+			for (_, v) in res.iter() {
+				let val: Value = v.into();
+				*distinct.entry(val.pick(&fd)).or_insert(0) += 1;
+			}
+			let once = distinct.values().filter(|&&v| v == 1).count();
+			let selectivity = match records {
+				// The sample holds every record in the table
+				Some(n) if n <= res.len() as u64 => distinct.len() as f64 / res.len() as f64,
+				// Extrapolate the distinct values in the sample to the whole table
+				Some(n) => estimate(distinct.len(), once, res.len(), n) / n as f64,
+				// The size of the table is unknown
+				None => distinct.len() as f64 / res.len() as f64,
+			};
+			// The number of entries which the index would hold
+			let records = records.unwrap_or(res.len() as u64);
+			// The time which the index could have saved
+			let (queries, time) = totals[&(tb.clone(), fd.clone())];
+			let benefit = time.mul_f64(selectivity);
+			// The statement which defines the index
+			let name = fd.to_string().replace(|c: char| !c.is_alphanumeric(), "_");
+			let stm = DefineIndexStatement {
+				name: Ident(format!("{tb}_{name}")),
+				what: Ident(tb.clone()),
+				cols: Idioms(vec![fd.clone()]),
+				..Default::default()
+			};
+			let obj = map! {
+				"table".to_owned() => Value::from(tb),
+				"field".to_owned() => Value::from(fd.to_string()),
+				"statement".to_owned() => Value::from(stm.to_string()),
+				"queries".to_owned() => Value::from(queries),
+				"time".to_owned() => Value::from(Duration::from(time)),
+				"selectivity".to_owned() => Value::from(selectivity),
+				"records".to_owned() => Value::from(records),
+				"benefit".to_owned() => Value::from(Duration::from(benefit)),
+			};
+			out.push((benefit, Value::from(Object::from(obj))));
+		}
+		// Recommend the most beneficial indexes first
+		out.sort_by(|(a, _), (b, _)| b.cmp(a));
+		Ok(out.into_iter().map(|(_, v)| v).collect::<Vec<_>>().into())
+	}
+}
+
+/// Estimates the number of distinct values in a table from a sample of its
+/// records, using the Duj1 estimator of Haas et al. The values which are seen
+/// only once in the sample are the ones most likely to be distinct in the table.
+fn estimate(distinct: usize, once: usize, sample: usize, records: u64) -> f64 {
+	let (d, f, n, total) = (distinct as f64, once as f64, sample as f64, records as f64);
+	let v = n * d / (n - f + f * n / total);
+	v.clamp(d, total)
+}
+
+impl fmt::Display for SuggestStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("SUGGEST INDEXES")
+	}
+}
+
+pub fn suggest(i: &str) -> IResult<&str, SuggestStatement> {
+	let (i, _) = tag_no_case("SUGGEST")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = expect_tag_no_case("INDEXES")(i)?;
+	Ok((i, SuggestStatement))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn suggest_indexes() {
+		let sql = "SUGGEST INDEXES";
+		let res = suggest(sql);
+		let out = res.unwrap().1;
+		assert_eq!("SUGGEST INDEXES", format!("{}", out))
+	}
+
+	#[test]
+	fn estimate_distinct() {
+		// Every value in the sample is unique
+		assert_eq!(estimate(10, 10, 10, 100), 100.0);
+		// No value in the sample is unique
+		assert_eq!(estimate(2, 0, 10, 100), 2.0);
+	}
+
+	#[test]
+	fn suggest_invalid() {
+		let res = suggest("SUGGEST TABLES");
+		assert!(res.is_err());
+	}
+}
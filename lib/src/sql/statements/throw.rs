@@ -3,10 +3,10 @@ use crate::dbs::{Options, Transaction};
 use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::sql::comment::shouldbespace;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::value::{value, Value};
 use derive::Store;
-use nom::bytes::complete::tag_no_case;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt;
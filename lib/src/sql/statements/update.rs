@@ -8,12 +8,12 @@ use crate::err::Error;
 use crate::sql::comment::shouldbespace;
 use crate::sql::cond::{cond, Cond};
 use crate::sql::data::{data, Data};
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::output::{output, Output};
 use crate::sql::timeout::{timeout, Timeout};
 use crate::sql::value::{whats, Value, Values};
 use derive::Store;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::opt;
 use nom::sequence::preceded;
 use revision::revisioned;
@@ -38,6 +38,18 @@ impl UpdateStatement {
 	pub(crate) fn writeable(&self) -> bool {
 		true
 	}
+	/// Substitute any nondeterministic functions with literal values
+	pub(crate) async fn resolve(&mut self, ctx: &Context<'_>, opt: &Options, txn: &Transaction) {
+		for v in self.what.0.iter_mut() {
+			v.resolve(ctx, opt, txn).await;
+		}
+		if let Some(v) = &mut self.data {
+			v.resolve(ctx, opt, txn).await;
+		}
+		if let Some(v) = &mut self.cond {
+			v.0.resolve(ctx, opt, txn).await;
+		}
+	}
 	/// Process this type returning a computed simple Value
 	pub(crate) async fn compute(
 		&self,
@@ -0,0 +1,194 @@
+use crate::ctx::Context;
+use crate::dbs::Iterator;
+use crate::dbs::Options;
+use crate::dbs::Statement;
+use crate::dbs::Transaction;
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::sql::comment::shouldbespace;
+use crate::sql::data::{data, Data};
+use crate::sql::error::expect_tag_no_case;
+use crate::sql::error::expected;
+use crate::sql::error::tag_no_case;
+use crate::sql::error::IResult;
+use crate::sql::output::{output, Output};
+use crate::sql::timeout::{timeout, Timeout};
+use crate::sql::value::{whats, Value, Values};
+use derive::Store;
+use nom::branch::alt;
+use nom::combinator::cut;
+use nom::combinator::{opt, value};
+use nom::sequence::preceded;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub struct UpsertStatement {
+	pub only: bool,
+	pub what: Values,
+	pub data: Option<Data>,
+	/// Whether a conflicting record is left unchanged, rather than updated
+	pub ignore: bool,
+	pub output: Option<Output>,
+	pub timeout: Option<Timeout>,
+	pub parallel: bool,
+}
+
+impl UpsertStatement {
+	/// Check if we require a writeable transaction
+	pub(crate) fn writeable(&self) -> bool {
+		true
+	}
+	/// Substitute any nondeterministic functions with literal values
+	pub(crate) async fn resolve(&mut self, ctx: &Context<'_>, opt: &Options, txn: &Transaction) {
+		for v in self.what.0.iter_mut() {
+			v.resolve(ctx, opt, txn).await;
+		}
+		if let Some(v) = &mut self.data {
+			v.resolve(ctx, opt, txn).await;
+		}
+	}
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		// Valid options?
+		opt.valid_for_db()?;
+		// Create a new iterator
+		let mut i = Iterator::new();
+		// Assign the statement
+		let stm = Statement::from(self);
+		// Ensure futures are stored
+		let opt = &opt.new_with_futures(false);
+		// Loop over the upsert targets
+		for w in self.what.0.iter() {
+			let v = w.compute(ctx, opt, txn, doc).await?;
+			i.prepare(ctx, opt, txn, &stm, v).await.map_err(|e| match e {
+				Error::InvalidStatementTarget {
+					value: v,
+				} => Error::UpsertStatement {
+					value: v,
+				},
+				e => e,
+			})?;
+		}
+		// Output the results
+		match i.output(ctx, opt, txn, &stm).await? {
+			// This is a single record result
+			Value::Array(mut a) if self.only => match a.len() {
+				// There was exactly one result
+				1 => Ok(a.remove(0)),
+				// There were no results
+				_ => Err(Error::SingleOnlyOutput),
+			},
+			// This is standard query result
+			v => Ok(v),
+		}
+	}
+}
+
+impl fmt::Display for UpsertStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "UPSERT")?;
+		if self.only {
+			f.write_str(" ONLY")?
+		}
+		write!(f, " {}", self.what)?;
+		if let Some(ref v) = self.data {
+			write!(f, " {v}")?
+		}
+		if self.ignore {
+			f.write_str(" ON CONFLICT IGNORE")?
+		}
+		if let Some(ref v) = self.output {
+			write!(f, " {v}")?
+		}
+		if let Some(ref v) = self.timeout {
+			write!(f, " {v}")?
+		}
+		if self.parallel {
+			f.write_str(" PARALLEL")?
+		}
+		Ok(())
+	}
+}
+
+pub fn upsert(i: &str) -> IResult<&str, UpsertStatement> {
+	let (i, _) = tag_no_case("UPSERT")(i)?;
+	let (i, only) = opt(preceded(shouldbespace, tag_no_case("ONLY")))(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, what) = whats(i)?;
+	let (i, (data, ignore, output, timeout, parallel)) = cut(|i| {
+		let (i, data) = opt(preceded(shouldbespace, data))(i)?;
+		let (i, ignore) = opt(preceded(shouldbespace, conflict))(i)?;
+		let (i, output) = opt(preceded(shouldbespace, output))(i)?;
+		let (i, timeout) = opt(preceded(shouldbespace, timeout))(i)?;
+		let (i, parallel) = opt(preceded(shouldbespace, tag_no_case("PARALLEL")))(i)?;
+		Ok((i, (data, ignore, output, timeout, parallel)))
+	})(i)?;
+	Ok((
+		i,
+		UpsertStatement {
+			only: only.is_some(),
+			what,
+			data,
+			ignore: ignore.unwrap_or_default(),
+			output,
+			timeout,
+			parallel: parallel.is_some(),
+		},
+	))
+}
+
+/// Parses the ON CONFLICT clause, returning whether conflicting records are ignored
+fn conflict(i: &str) -> IResult<&str, bool> {
+	let (i, _) = tag_no_case("ON")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = expect_tag_no_case("CONFLICT")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	expected(
+		"IGNORE or UPDATE",
+		alt((value(true, tag_no_case("IGNORE")), value(false, tag_no_case("UPDATE")))),
+	)(i)
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn upsert_statement() {
+		let sql = "UPSERT user SET email = 'tobie@surrealdb.com', visits += 1";
+		let res = upsert(sql);
+		let out = res.unwrap().1;
+		assert!(!out.ignore);
+		assert_eq!(sql, format!("{}", out))
+	}
+
+	#[test]
+	fn upsert_statement_conflict() {
+		let sql = "UPSERT ONLY user CONTENT { email: 'tobie@surrealdb.com' } ON CONFLICT IGNORE RETURN AFTER";
+		let res = upsert(sql);
+		let out = res.unwrap().1;
+		assert!(out.ignore);
+		assert_eq!(sql, format!("{}", out));
+		// Updating a conflicting record is the default
+		let res = upsert("UPSERT user SET visits += 1 ON CONFLICT UPDATE");
+		let out = res.unwrap().1;
+		assert!(!out.ignore);
+		assert_eq!("UPSERT user SET visits += 1", format!("{}", out));
+	}
+
+	#[test]
+	fn upsert_statement_invalid_conflict() {
+		let res = upsert("UPSERT user SET visits += 1 ON CONFLICT REPLACE");
+		assert!(res.is_err());
+	}
+}
@@ -1,8 +1,8 @@
 use crate::sql::comment::shouldbespace;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::ident::ident_raw;
 use derive::Store;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::opt;
 use nom::sequence::{preceded, tuple};
 use nom::{
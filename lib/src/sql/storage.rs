@@ -0,0 +1,81 @@
+use crate::sql::comment::shouldbespace;
+use crate::sql::error::IResult;
+use crate::sql::ident::{ident, Ident};
+use nom::branch::alt;
+use nom::bytes::complete::tag_no_case;
+use nom::character::complete::u16 as uint16;
+use nom::combinator::{cut, verify};
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// A placement hint describing how the records of a table should be laid out
+/// by the underlying key-value store. Storage engines which do not support a
+/// hint store the table with the rest of the data.
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[revisioned(revision = 1)]
+pub enum Storage {
+	/// Store the table in a dedicated column family
+	Family(Ident),
+	/// Spread the table across a number of key-prefix shards
+	Shards(u16),
+}
+
+impl Display for Storage {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		match self {
+			Self::Family(v) => write!(f, "STORAGE FAMILY {v}"),
+			Self::Shards(v) => write!(f, "STORAGE SHARDS {v}"),
+		}
+	}
+}
+
+pub fn storage(i: &str) -> IResult<&str, Storage> {
+	let (i, _) = tag_no_case("STORAGE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	cut(alt((family, shards)))(i)
+}
+
+fn family(i: &str) -> IResult<&str, Storage> {
+	let (i, _) = tag_no_case("FAMILY")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(ident)(i)?;
+	Ok((i, Storage::Family(v)))
+}
+
+fn shards(i: &str) -> IResult<&str, Storage> {
+	let (i, _) = tag_no_case("SHARDS")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(verify(uint16, |v: &u16| *v > 0))(i)?;
+	Ok((i, Storage::Shards(v)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn storage_family() {
+		let sql = "STORAGE FAMILY hot";
+		let res = storage(sql);
+		let out = res.unwrap().1;
+		assert_eq!("STORAGE FAMILY hot", format!("{}", out));
+		assert_eq!(out, Storage::Family(Ident::from("hot")));
+	}
+
+	#[test]
+	fn storage_shards() {
+		let sql = "STORAGE SHARDS 8";
+		let res = storage(sql);
+		let out = res.unwrap().1;
+		assert_eq!("STORAGE SHARDS 8", format!("{}", out));
+		assert_eq!(out, Storage::Shards(8));
+	}
+
+	#[test]
+	fn storage_shards_zero() {
+		let sql = "STORAGE SHARDS 0";
+		let res = storage(sql);
+		assert!(res.is_err());
+	}
+}
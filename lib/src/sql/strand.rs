@@ -7,12 +7,14 @@ use nom::character::complete::char;
 use nom::combinator::value;
 use nom::sequence::preceded;
 use nom::Err;
-use revision::revisioned;
+use revision::Revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Formatter};
+use std::io::{Read, Write};
 use std::ops::Deref;
 use std::ops::{self, RangeInclusive};
 use std::str;
+use std::sync::Arc;
 
 use super::error::expected;
 
@@ -28,15 +30,17 @@ const LEADING_SURROGATES: RangeInclusive<u16> = 0xD800..=0xDBFF;
 const TRAILING_SURROGATES: RangeInclusive<u16> = 0xDC00..=0xDFFF;
 
 /// A string that doesn't contain NUL bytes.
+///
+/// The storage of the string is shared between the copies of a strand,
+/// so that equal strings can be interned to share the same storage.
 #[derive(Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize, Hash)]
 #[serde(rename = "$surrealdb::private::sql::Strand")]
-#[revisioned(revision = 1)]
-pub struct Strand(#[serde(with = "no_nul_bytes")] pub String);
+pub struct Strand(#[serde(with = "no_nul_bytes")] pub Arc<String>);
 
 impl From<String> for Strand {
 	fn from(s: String) -> Self {
 		debug_assert!(!s.contains('\0'));
-		Strand(s)
+		Strand(Arc::new(s))
 	}
 }
 
@@ -56,7 +60,7 @@ impl Deref for Strand {
 
 impl From<Strand> for String {
 	fn from(s: Strand) -> Self {
-		s.0
+		s.as_string()
 	}
 }
 
@@ -65,13 +69,33 @@ impl Strand {
 	pub fn as_str(&self) -> &str {
 		self.0.as_str()
 	}
-	/// Returns the underlying String
+	/// Returns the underlying String, which is only
+	/// copied if its storage is shared with another strand
 	pub fn as_string(self) -> String {
-		self.0
+		Arc::try_unwrap(self.0).unwrap_or_else(|s| (*s).clone())
 	}
 	/// Convert the Strand to a raw String
 	pub fn to_raw(self) -> String {
-		self.0
+		self.as_string()
+	}
+}
+
+// The strand is stored as its string, as it was before its storage was shared
+impl Revisioned for Strand {
+	fn revision() -> u16 {
+		1
+	}
+	fn serialize_revisioned<W: Write>(&self, w: &mut W) -> Result<(), revision::Error> {
+		Self::revision().serialize_revisioned(w)?;
+		String::serialize_revisioned(&self.0, w)
+	}
+	fn deserialize_revisioned<R: Read>(r: &mut R) -> Result<Self, revision::Error> {
+		match u16::deserialize_revisioned(r)? {
+			1 => Ok(Strand(Arc::new(String::deserialize_revisioned(r)?))),
+			v => Err(revision::Error::Deserialize(format!(
+				"Invalid revision `{v}` for type `Strand`"
+			))),
+		}
 	}
 }
 
@@ -84,14 +108,14 @@ impl Display for Strand {
 impl ops::Add for Strand {
 	type Output = Self;
 	fn add(mut self, other: Self) -> Self {
-		self.0.push_str(other.as_str());
+		Arc::make_mut(&mut self.0).push_str(other.as_str());
 		self
 	}
 }
 
 pub fn strand(i: &str) -> IResult<&str, Strand> {
 	let (i, v) = strand_raw(i)?;
-	Ok((i, Strand(v)))
+	Ok((i, Strand::from(v)))
 }
 
 pub fn strand_raw(i: &str) -> IResult<&str, String> {
@@ -248,9 +272,10 @@ pub(crate) mod no_nul_bytes {
 		serializer.serialize_str(s)
 	}
 
-	pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<String, D::Error>
+	pub(crate) fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
 	where
 		D: Deserializer<'de>,
+		T: From<String>,
 	{
 		struct NoNulBytesVisitor;
 
@@ -284,7 +309,7 @@ pub(crate) mod no_nul_bytes {
 			}
 		}
 
-		deserializer.deserialize_string(NoNulBytesVisitor)
+		deserializer.deserialize_string(NoNulBytesVisitor).map(T::from)
 	}
 }
 
@@ -381,4 +406,17 @@ mod tests {
 		// Unpaired surrogate.
 		assert!(strand("\"\\u{DBFF}\"").is_err());
 	}
+
+	#[test]
+	fn strand_revision() {
+		let out = Strand::from("test");
+		let mut buf = Vec::new();
+		out.serialize_revisioned(&mut buf).unwrap();
+		// The strand is stored exactly as it was before its storage was shared
+		let mut old = Vec::new();
+		1u16.serialize_revisioned(&mut old).unwrap();
+		String::from("test").serialize_revisioned(&mut old).unwrap();
+		assert_eq!(buf, old);
+		assert_eq!(Strand::deserialize_revisioned(&mut buf.as_slice()).unwrap(), out);
+	}
 }
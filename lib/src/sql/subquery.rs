@@ -4,6 +4,7 @@ use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::sql::common::{closeparentheses, openparentheses};
 use crate::sql::ending::subquery as ending;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::statements::create::{create, CreateStatement};
 use crate::sql::statements::define::{define, DefineStatement};
@@ -17,7 +18,7 @@ use crate::sql::statements::select::{select, SelectStatement};
 use crate::sql::statements::update::{update, UpdateStatement};
 use crate::sql::value::{value, Value};
 use nom::branch::alt;
-use nom::bytes::complete::{tag, tag_no_case};
+use nom::bytes::complete::tag;
 use nom::combinator::{map, opt, peek};
 use nom::sequence::tuple;
 use revision::revisioned;
@@ -73,6 +74,21 @@ impl Subquery {
 			Self::Remove(v) => v.writeable(),
 		}
 	}
+	/// Substitute any nondeterministic functions with literal values
+	pub(crate) async fn resolve(&mut self, ctx: &Context<'_>, opt: &Options, txn: &Transaction) {
+		match self {
+			Self::Value(v) => v.resolve(ctx, opt, txn).await,
+			Self::Ifelse(v) => v.resolve(ctx, opt, txn).await,
+			Self::Output(v) => v.resolve(ctx, opt, txn).await,
+			Self::Select(v) => v.resolve(ctx, opt, txn).await,
+			Self::Create(v) => v.resolve(ctx, opt, txn).await,
+			Self::Update(v) => v.resolve(ctx, opt, txn).await,
+			Self::Delete(v) => v.resolve(ctx, opt, txn).await,
+			Self::Relate(v) => v.resolve(ctx, opt, txn).await,
+			Self::Insert(v) => v.resolve(ctx, opt, txn).await,
+			Self::Define(_) | Self::Remove(_) => (),
+		}
+	}
 	/// Process this type returning a computed simple Value
 	pub(crate) async fn compute(
 		&self,
@@ -0,0 +1,191 @@
+use crate::sql::comment::shouldbespace;
+use crate::sql::common::verbar;
+use crate::sql::error::tag_no_case;
+use crate::sql::error::IResult;
+use crate::sql::fmt::Fmt;
+use crate::sql::kind::Kind;
+use crate::sql::table::table;
+use nom::branch::alt;
+use nom::combinator::{cut, map, opt, value};
+use nom::multi::separated_list1;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// The type of the records which a table can hold
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[revisioned(revision = 1)]
+pub enum TableType {
+	/// The table can hold both normal records and relations
+	Any,
+	/// The table can only hold normal records
+	Normal,
+	/// The table can only hold relations created with RELATE
+	Relation(Relation),
+}
+
+/// The tables which a relation can connect
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[revisioned(revision = 1)]
+pub struct Relation {
+	/// The type of the `in` field of the relation
+	pub from: Option<Kind>,
+	/// The type of the `out` field of the relation
+	pub to: Option<Kind>,
+}
+
+impl Default for TableType {
+	fn default() -> Self {
+		Self::Any
+	}
+}
+
+impl TableType {
+	/// Check if this table can only hold relations
+	pub fn is_relation(&self) -> bool {
+		matches!(self, Self::Relation(_))
+	}
+	/// Check if a record of this type can be stored in this table
+	pub fn allows_relation(&self, relation: bool) -> bool {
+		match self {
+			Self::Any => true,
+			Self::Normal => !relation,
+			Self::Relation(_) => relation,
+		}
+	}
+}
+
+impl Relation {
+	/// Check if the `in` field of the relation can point to the given table
+	pub fn allows_from(&self, tb: &str) -> bool {
+		Self::allows(&self.from, tb)
+	}
+	/// Check if the `out` field of the relation can point to the given table
+	pub fn allows_to(&self, tb: &str) -> bool {
+		Self::allows(&self.to, tb)
+	}
+
+	fn allows(kind: &Option<Kind>, tb: &str) -> bool {
+		match kind {
+			Some(Kind::Record(v)) if !v.is_empty() => v.iter().any(|v| v.0 == tb),
+			_ => true,
+		}
+	}
+}
+
+impl Display for TableType {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		match self {
+			Self::Any => f.write_str("TYPE ANY"),
+			Self::Normal => f.write_str("TYPE NORMAL"),
+			Self::Relation(v) => write!(f, "TYPE RELATION{v}"),
+		}
+	}
+}
+
+impl Display for Relation {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		if let Some(Kind::Record(v)) = &self.from {
+			write!(f, " FROM {}", Fmt::verbar_separated(v))?;
+		}
+		if let Some(Kind::Record(v)) = &self.to {
+			write!(f, " TO {}", Fmt::verbar_separated(v))?;
+		}
+		Ok(())
+	}
+}
+
+pub fn table_type(i: &str) -> IResult<&str, TableType> {
+	let (i, _) = tag_no_case("TYPE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	cut(alt((
+		value(TableType::Any, tag_no_case("ANY")),
+		value(TableType::Normal, tag_no_case("NORMAL")),
+		map(relation, TableType::Relation),
+	)))(i)
+}
+
+fn relation(i: &str) -> IResult<&str, Relation> {
+	let (i, _) = tag_no_case("RELATION")(i)?;
+	let (i, from) = opt(relation_from)(i)?;
+	let (i, to) = opt(relation_to)(i)?;
+	Ok((
+		i,
+		Relation {
+			from,
+			to,
+		},
+	))
+}
+
+fn relation_from(i: &str) -> IResult<&str, Kind> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = alt((tag_no_case("FROM"), tag_no_case("IN")))(i)?;
+	let (i, _) = shouldbespace(i)?;
+	cut(relation_tables)(i)
+}
+
+fn relation_to(i: &str) -> IResult<&str, Kind> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = alt((tag_no_case("TO"), tag_no_case("OUT")))(i)?;
+	let (i, _) = shouldbespace(i)?;
+	cut(relation_tables)(i)
+}
+
+fn relation_tables(i: &str) -> IResult<&str, Kind> {
+	let (i, v) = separated_list1(verbar, table)(i)?;
+	Ok((i, Kind::Record(v)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sql::Table;
+
+	#[test]
+	fn table_type_normal() {
+		let sql = "TYPE NORMAL";
+		let res = table_type(sql);
+		let out = res.unwrap().1;
+		assert_eq!("TYPE NORMAL", format!("{}", out));
+		assert_eq!(out, TableType::Normal);
+	}
+
+	#[test]
+	fn table_type_relation() {
+		let sql = "TYPE RELATION FROM user TO post | comment";
+		let res = table_type(sql);
+		let out = res.unwrap().1;
+		assert_eq!("TYPE RELATION FROM user TO post | comment", format!("{}", out));
+		assert_eq!(
+			out,
+			TableType::Relation(Relation {
+				from: Some(Kind::Record(vec![Table::from("user")])),
+				to: Some(Kind::Record(vec![Table::from("post"), Table::from("comment")])),
+			})
+		);
+	}
+
+	#[test]
+	fn table_type_relation_in_out() {
+		let sql = "TYPE RELATION IN user OUT post";
+		let res = table_type(sql);
+		let out = res.unwrap().1;
+		assert_eq!("TYPE RELATION FROM user TO post", format!("{}", out));
+		let TableType::Relation(r) = out else {
+			panic!("expected a relation");
+		};
+		assert!(r.allows_from("user"));
+		assert!(!r.allows_from("post"));
+		assert!(r.allows_to("post"));
+	}
+
+	#[test]
+	fn table_type_relation_any() {
+		let sql = "TYPE RELATION";
+		let res = table_type(sql);
+		let out = res.unwrap().1;
+		assert_eq!("TYPE RELATION", format!("{}", out));
+		assert!(out.is_relation());
+	}
+}
@@ -1,7 +1,7 @@
 use crate::sql::common::commas;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::value;
 use nom::multi::separated_list1;
 use revision::revisioned;
@@ -1,13 +1,17 @@
+use crate::dbs::seed;
 use crate::sql::common::is_hex;
+use crate::sql::datetime::Datetime;
 use crate::sql::error::IResult;
 use crate::sql::escape::quote_str;
 use crate::sql::strand::Strand;
+use chrono::{TimeZone, Utc};
 use nom::branch::alt;
 use nom::bytes::complete::take_while_m_n;
 use nom::character::complete::char;
 use nom::combinator::recognize;
 use nom::sequence::delimited;
 use nom::sequence::tuple;
+use rand::Rng;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Formatter};
@@ -17,6 +21,9 @@ use std::str::FromStr;
 
 pub(crate) const TOKEN: &str = "$surrealdb::private::sql::Uuid";
 
+/// The number of 100 nanosecond intervals between the start of the Gregorian calendar and the Unix epoch
+const GREGORIAN_OFFSET: u64 = 0x01B2_1DD2_1381_4000;
+
 #[derive(Clone, Debug, Default, Eq, Ord, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
 #[serde(rename = "$surrealdb::private::sql::Uuid")]
 #[revisioned(revision = 1)]
@@ -88,11 +95,62 @@ impl Uuid {
 	pub fn new_v4() -> Self {
 		Self(uuid::Uuid::new_v4())
 	}
+	/// Generate a new UUID, from the seed of the running statement if it is seeded
+	pub(crate) fn new_seeded() -> Self {
+		#[cfg(uuid_unstable)]
+		{
+			Self::new_v7_seeded()
+		}
+		#[cfg(not(uuid_unstable))]
+		{
+			Self::new_v4_seeded()
+		}
+	}
+	/// Generate a new V4 UUID, from the seed of the running statement if it is seeded
+	pub(crate) fn new_v4_seeded() -> Self {
+		Self(uuid::Builder::from_random_bytes(seed::with(|rng| rng.gen())).into_uuid())
+	}
+	/// Generate a new V7 UUID, with the random bits drawn from the seed of the running
+	/// statement if it is seeded
+	#[cfg(uuid_unstable)]
+	pub(crate) fn new_v7_seeded() -> Self {
+		let ms = Utc::now().timestamp_millis() as u64;
+		let bytes = seed::with(|rng| rng.gen());
+		Self(uuid::Builder::from_unix_timestamp_millis(ms, &bytes).into_uuid())
+	}
 	/// Generate a new V7 UUID
 	#[cfg(uuid_unstable)]
 	pub fn new_v7() -> Self {
 		Self(uuid::Uuid::now_v7())
 	}
+	/// Get the time at which a time-ordered V1, V6, or V7 UUID was generated
+	pub fn time(&self) -> Option<Datetime> {
+		let v = self.0.as_u128();
+		let time = match self.0.get_version_num() {
+			// The timestamp is a count of 100 nanosecond intervals since the
+			// Gregorian epoch, stored in reverse order of significance in V1
+			1 => {
+				let ts = (((v >> 64) & 0x0fff) << 48) | (((v >> 80) & 0xffff) << 32) | (v >> 96);
+				Self::gregorian(ts as u64)?
+			}
+			// The timestamp is stored in order of significance in V6
+			6 => {
+				let ts = ((v >> 96) << 28) | (((v >> 80) & 0xffff) << 12) | ((v >> 64) & 0x0fff);
+				Self::gregorian(ts as u64)?
+			}
+			// The timestamp is a count of milliseconds since the Unix epoch in V7
+			7 => Utc.timestamp_millis_opt((v >> 80) as i64).single()?,
+			_ => return None,
+		};
+		Some(Datetime::from(time))
+	}
+	/// Convert a count of 100 nanosecond intervals since the Gregorian epoch to a time
+	fn gregorian(ts: u64) -> Option<chrono::DateTime<Utc>> {
+		let ts = ts.checked_sub(GREGORIAN_OFFSET)?;
+		let secs = (ts / 10_000_000) as i64;
+		let nanos = (ts % 10_000_000) as u32 * 100;
+		Utc.timestamp_opt(secs, nanos).single()
+	}
 	/// Convert the Uuid to a raw String
 	pub fn to_raw(&self) -> String {
 		self.0.to_string()
@@ -166,4 +224,17 @@ mod tests {
 		assert_eq!("'b19bc00b-aa98-486c-ae37-c8e1c54295b1'", format!("{}", out));
 		assert_eq!(out, Uuid::try_from("b19bc00b-aa98-486c-ae37-c8e1c54295b1").unwrap());
 	}
+
+	#[test]
+	fn uuid_time() {
+		let time = Datetime::try_from("2022-02-22T19:22:22Z").unwrap();
+		let v1 = Uuid::try_from("c232ab00-9414-11ec-b3c8-9f6bdeced846").unwrap();
+		assert_eq!(v1.time(), Some(time.clone()));
+		let v6 = Uuid::try_from("1ec9414c-232a-6b00-b3c8-9f6bdeced846").unwrap();
+		assert_eq!(v6.time(), Some(time.clone()));
+		let v7 = Uuid::try_from("017f22e2-79b0-7cc3-98c4-dc0c0c07398f").unwrap();
+		assert_eq!(v7.time(), Some(time));
+		let v4 = Uuid::try_from("b19bc00b-aa98-486c-ae37-c8e1c54295b1").unwrap();
+		assert_eq!(v4.time(), None);
+	}
 }
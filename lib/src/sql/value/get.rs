@@ -1,5 +1,5 @@
 use crate::ctx::Context;
-use crate::dbs::{Options, Transaction};
+use crate::dbs::{recurse, Options, Transaction};
 use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::exe::try_join_all_buffered;
@@ -175,6 +175,24 @@ impl Value {
 						0 => Ok(Value::Thing(val)),
 						// Remote embedded field, so fetch the thing
 						_ => match p {
+							// This is a recursive graph traversal expression
+							Part::Graph(g) if g.depth.is_some() => {
+								let (node, rest) = match path.next() {
+									[Part::Graph(n), rest @ ..] => (Some(n), rest),
+									rest => (None, rest),
+								};
+								let v = Value::from(
+									recurse(ctx, opt, txn, val, g, node)
+										.await?
+										.into_iter()
+										.map(Value::Thing)
+										.collect::<Vec<_>>(),
+								);
+								match rest.len() {
+									0 => Ok(v),
+									_ => v.get(ctx, opt, txn, None, rest).await?.flatten().ok(),
+								}
+							}
 							// This is a graph traversal expression
 							Part::Graph(g) => {
 								let stm = SelectStatement {
@@ -26,10 +26,12 @@ mod inc;
 mod increment;
 mod last;
 mod merge;
+mod nones;
 mod patch;
 mod pick;
 mod put;
 mod replace;
+mod resolve;
 mod rid;
 mod set;
 mod walk;
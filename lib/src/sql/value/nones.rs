@@ -0,0 +1,61 @@
+use crate::dbs::NoneOutput;
+use crate::sql::value::Value;
+
+impl Value {
+	/// Converts any `NONE` values within this value, ready to be output
+	pub(crate) fn output_nones(&mut self, out: NoneOutput) {
+		match out {
+			NoneOutput::Keep => (),
+			NoneOutput::Null => self.replace_nones(false),
+			NoneOutput::Omit => self.replace_nones(true),
+		}
+	}
+
+	fn replace_nones(&mut self, omit: bool) {
+		match self {
+			Value::None => *self = Value::Null,
+			Value::Array(v) => v.iter_mut().for_each(|v| v.replace_nones(omit)),
+			Value::Object(v) => {
+				if omit {
+					v.retain(|_, v| !v.is_none());
+				}
+				v.values_mut().for_each(|v| v.replace_nones(omit));
+			}
+			_ => (),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::sql::test::Parse;
+
+	#[test]
+	fn output_nones_keep() {
+		let mut val = Value::parse("{ test: NONE, other: [NONE, null] }");
+		let res = val.clone();
+		val.output_nones(NoneOutput::Keep);
+		assert_eq!(res, val);
+	}
+
+	#[test]
+	fn output_nones_null() {
+		let mut val = Value::parse("{ test: NONE, other: [NONE, null], nested: { test: NONE } }");
+		let res = Value::parse("{ test: null, other: [null, null], nested: { test: null } }");
+		val.output_nones(NoneOutput::Null);
+		assert_eq!(res, val);
+	}
+
+	#[test]
+	fn output_nones_omit() {
+		let mut val = Value::parse("{ test: NONE, other: [NONE, null], nested: { test: NONE } }");
+		let res = Value::parse("{ other: [null, null], nested: {} }");
+		val.output_nones(NoneOutput::Omit);
+		assert_eq!(res, val);
+		let mut val = Value::None;
+		val.output_nones(NoneOutput::Omit);
+		assert_eq!(Value::Null, val);
+	}
+}
@@ -0,0 +1,162 @@
+use crate::ctx::Context;
+use crate::dbs::{Options, Transaction};
+use crate::fnc;
+use crate::sql::expression::Expression;
+use crate::sql::function::Function;
+use crate::sql::value::Value;
+use async_recursion::async_recursion;
+
+impl Value {
+	/// Substitute any nondeterministic functions within this value
+	/// with the literal values they compute to, so that the value
+	/// can be replayed deterministically at a later point in time.
+	/// Custom functions are computed too, as they may call any
+	/// nondeterministic functions.
+	#[cfg_attr(not(target_arch = "wasm32"), async_recursion)]
+	#[cfg_attr(target_arch = "wasm32", async_recursion(?Send))]
+	pub(crate) async fn resolve(&mut self, ctx: &Context<'_>, opt: &Options, txn: &Transaction) {
+		match self {
+			Value::Array(v) => {
+				for v in v.iter_mut() {
+					v.resolve(ctx, opt, txn).await;
+				}
+			}
+			Value::Object(v) => {
+				for v in v.values_mut() {
+					v.resolve(ctx, opt, txn).await;
+				}
+			}
+			Value::Cast(v) => v.1.resolve(ctx, opt, txn).await,
+			Value::Expression(v) => match v.as_mut() {
+				Expression::Unary {
+					v,
+					..
+				} => v.resolve(ctx, opt, txn).await,
+				Expression::Binary {
+					l,
+					r,
+					..
+				} => {
+					l.resolve(ctx, opt, txn).await;
+					r.resolve(ctx, opt, txn).await;
+				}
+			},
+			Value::Subquery(v) => v.resolve(ctx, opt, txn).await,
+			Value::Block(v) => v.resolve(ctx, opt, txn).await,
+			Value::Future(v) => v.0.resolve(ctx, opt, txn).await,
+			Value::Function(f) => {
+				// Resolve the function arguments first
+				let args = match f.as_mut() {
+					Function::Normal(_, a) => a,
+					Function::Custom(_, a) => a,
+					Function::Script(_, a) => a,
+				};
+				for v in args.iter_mut() {
+					v.resolve(ctx, opt, txn).await;
+				}
+				// Compute the function if it is nondeterministic
+				if let Some(v) = computed(ctx, opt, txn, f).await {
+					*self = v;
+				}
+			}
+			_ => (),
+		}
+	}
+}
+
+/// Computes a function ahead of time, if it may be nondeterministic,
+/// and if the values of all of its arguments are already known
+async fn computed(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	f: &Function,
+) -> Option<Value> {
+	// Parameters are substituted with their values, if they have been set
+	let args = f
+		.args()
+		.iter()
+		.map(|v| match v {
+			Value::Param(p) => ctx.value(p).filter(|v| v.is_static()).cloned(),
+			v => v.is_static().then(|| v.to_owned()),
+		})
+		.collect::<Option<Vec<_>>>()?;
+	// Any errors are left to be raised when the statement is processed
+	match f {
+		Function::Normal(s, _) if f.is_nondeterministic() => {
+			ctx.check_allowed_function(s).ok()?;
+			fnc::synchronous(ctx, s, args).ok()
+		}
+		Function::Custom(s, _) if opt.valid_for_db().is_ok() => {
+			let f = Function::Custom(s.to_owned(), args);
+			f.compute(ctx, opt, txn, None).await.ok()
+		}
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::dbs::test::mock;
+	use crate::sql::parse;
+	use crate::sql::statement::Statement;
+	use crate::sql::test::Parse;
+
+	fn statement(sql: &str) -> Statement {
+		parse(sql).unwrap().0 .0.remove(0)
+	}
+
+	#[tokio::test]
+	async fn resolve_nondeterministic() {
+		let (ctx, opt, txn) = mock().await;
+		let mut val = Value::parse("{ test: rand::int(1, 1), other: string::len('test') }");
+		val.resolve(&ctx, &opt, &txn).await;
+		let res = Value::parse("{ test: 1, other: string::len('test') }");
+		assert_eq!(res, val);
+	}
+
+	#[tokio::test]
+	async fn resolve_nested_arguments() {
+		let (ctx, opt, txn) = mock().await;
+		let mut val = Value::parse("[array::len([rand::int(5, 5)]), rand::int(2, 2) + 1]");
+		val.resolve(&ctx, &opt, &txn).await;
+		let res = Value::parse("[array::len([5]), 2 + 1]");
+		assert_eq!(res, val);
+	}
+
+	#[tokio::test]
+	async fn resolve_param_arguments() {
+		let (mut ctx, opt, txn) = mock().await;
+		ctx.add_value("max", Value::from(3));
+		let mut val = Value::parse("[rand::int($max, $max), rand::int($unknown, 3)]");
+		val.resolve(&ctx, &opt, &txn).await;
+		let res = Value::parse("[3, rand::int($unknown, 3)]");
+		assert_eq!(res, val);
+	}
+
+	#[tokio::test]
+	async fn resolve_statements() {
+		let (mut ctx, opt, txn) = mock().await;
+		// Parameters set within a block shadow the parameters of the query
+		ctx.add_value("num", Value::from(9));
+		let mut stm = statement(
+			"IF rand::int(1, 1) THEN (CREATE test SET num = rand::int(2, 2)) ELSE { LET $num = 3; RETURN rand::int($num, $num) } END",
+		);
+		stm.resolve(&ctx, &opt, &txn).await;
+		let res = statement(
+			"IF 1 THEN (CREATE test SET num = 2) ELSE { LET $num = 3; RETURN rand::int($num, $num) } END",
+		);
+		assert_eq!(res, stm);
+		let mut stm =
+			statement("FOR $v IN [rand::int(4, 4)] { CREATE test SET num = rand::int($v, 5) }");
+		stm.resolve(&ctx, &opt, &txn).await;
+		let res = statement("FOR $v IN [4] { CREATE test SET num = rand::int($v, 5) }");
+		assert_eq!(res, stm);
+		let mut stm = statement("UPSERT test SET num = rand::int(6, 6)");
+		stm.resolve(&ctx, &opt, &txn).await;
+		let res = statement("UPSERT test SET num = 6");
+		assert_eq!(res, stm);
+	}
+}
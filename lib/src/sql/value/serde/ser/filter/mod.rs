@@ -52,6 +52,9 @@ impl ser::Serializer for Serializer {
 	{
 		match variant {
 			"Snowball" => Ok(Filter::Snowball(value.serialize(ser::language::Serializer.wrap())?)),
+			"Stopwords" => {
+				Ok(Filter::Stopwords(value.serialize(ser::string::vec::Serializer.wrap())?))
+			}
 			variant => {
 				Err(Error::custom(format!("unexpected newtype variant `{name}::{variant}`")))
 			}
@@ -151,4 +154,11 @@ mod tests {
 		let serialized = filter.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(filter, serialized);
 	}
+
+	#[test]
+	fn stopwords() {
+		let filter = Filter::Stopwords(vec!["the".to_owned(), "a".to_owned()]);
+		let serialized = filter.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(filter, serialized);
+	}
 }
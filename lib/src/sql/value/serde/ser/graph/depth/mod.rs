@@ -0,0 +1,86 @@
+pub(super) mod opt;
+
+use crate::err::Error;
+use crate::sql::value::serde::ser;
+use crate::sql::Depth;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Depth;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Depth, Error>;
+	type SerializeTuple = Impossible<Depth, Error>;
+	type SerializeTupleStruct = Impossible<Depth, Error>;
+	type SerializeTupleVariant = Impossible<Depth, Error>;
+	type SerializeMap = Impossible<Depth, Error>;
+	type SerializeStruct = SerializeDepth;
+	type SerializeStructVariant = Impossible<Depth, Error>;
+
+	const EXPECTED: &'static str = "a struct `Depth`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeDepth::default())
+	}
+}
+
+#[derive(Default)]
+pub struct SerializeDepth {
+	min: u32,
+	max: u32,
+}
+
+impl serde::ser::SerializeStruct for SerializeDepth {
+	type Ok = Depth;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"min" => {
+				self.min = value.serialize(ser::primitive::u32::Serializer.wrap())?;
+			}
+			"max" => {
+				self.max = value.serialize(ser::primitive::u32::Serializer.wrap())?;
+			}
+			key => {
+				return Err(Error::custom(format!("unexpected field `Depth::{key}`")));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		Ok(Depth {
+			min: self.min,
+			max: self.max,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn depth() {
+		let depth = Depth {
+			min: 1,
+			max: 4,
+		};
+		let value: Depth = depth.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, depth);
+	}
+}
@@ -0,0 +1,58 @@
+use crate::err::Error;
+use crate::sql::value::serde::ser;
+use crate::sql::Depth;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Option<Depth>;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Option<Depth>, Error>;
+	type SerializeTuple = Impossible<Option<Depth>, Error>;
+	type SerializeTupleStruct = Impossible<Option<Depth>, Error>;
+	type SerializeTupleVariant = Impossible<Option<Depth>, Error>;
+	type SerializeMap = Impossible<Option<Depth>, Error>;
+	type SerializeStruct = Impossible<Option<Depth>, Error>;
+	type SerializeStructVariant = Impossible<Option<Depth>, Error>;
+
+	const EXPECTED: &'static str = "an `Option<Depth>`";
+
+	#[inline]
+	fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+		Ok(None)
+	}
+
+	#[inline]
+	fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		Ok(Some(value.serialize(super::Serializer.wrap())?))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ser::Serializer as _;
+
+	#[test]
+	fn none() {
+		let option: Option<Depth> = None;
+		let serialized = option.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(option, serialized);
+	}
+
+	#[test]
+	fn some() {
+		let option = Some(Depth {
+			min: 1,
+			max: 4,
+		});
+		let serialized = option.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(option, serialized);
+	}
+}
@@ -1,3 +1,5 @@
+mod depth;
+
 use crate::err::Error;
 use crate::sql::field::Fields;
 use crate::sql::group::Groups;
@@ -7,6 +9,7 @@ use crate::sql::split::Splits;
 use crate::sql::start::Start;
 use crate::sql::value::serde::ser;
 use crate::sql::Cond;
+use crate::sql::Depth;
 use crate::sql::Dir;
 use crate::sql::Graph;
 use crate::sql::Idiom;
@@ -54,6 +57,7 @@ pub(super) struct SerializeGraph {
 	limit: Option<Limit>,
 	start: Option<Start>,
 	alias: Option<Idiom>,
+	depth: Option<Depth>,
 }
 
 impl serde::ser::SerializeStruct for SerializeGraph {
@@ -95,6 +99,9 @@ impl serde::ser::SerializeStruct for SerializeGraph {
 			"alias" => {
 				self.alias = value.serialize(ser::part::vec::opt::Serializer.wrap())?.map(Idiom);
 			}
+			"depth" => {
+				self.depth = value.serialize(depth::opt::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!("unexpected field `Graph::{key}`")));
 			}
@@ -115,6 +122,7 @@ impl serde::ser::SerializeStruct for SerializeGraph {
 				limit: self.limit,
 				start: self.start,
 				alias: self.alias,
+				depth: self.depth,
 			}),
 			_ => Err(Error::custom("`Graph` missing required field(s)")),
 		}
@@ -152,4 +160,17 @@ mod tests {
 		let serialized = graph.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(graph, serialized);
 	}
+
+	#[test]
+	fn with_depth() {
+		let graph = Graph {
+			depth: Some(Depth {
+				min: 1,
+				max: 4,
+			}),
+			..Default::default()
+		};
+		let serialized = graph.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(graph, serialized);
+	}
 }
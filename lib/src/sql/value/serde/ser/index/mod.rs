@@ -1,4 +1,7 @@
+mod hnswparams;
 mod mtreeparams;
+mod nulls;
+mod rtreeparams;
 mod searchparams;
 
 use crate::err::Error;
@@ -52,6 +55,9 @@ impl ser::Serializer for Serializer {
 		match variant {
 			"Search" => Ok(Index::Search(value.serialize(searchparams::Serializer.wrap())?)),
 			"MTree" => Ok(Index::MTree(value.serialize(mtreeparams::Serializer.wrap())?)),
+			"Hnsw" => Ok(Index::Hnsw(value.serialize(hnswparams::Serializer.wrap())?)),
+			"RTree" => Ok(Index::RTree(value.serialize(rtreeparams::Serializer.wrap())?)),
+			"UniqNulls" => Ok(Index::UniqNulls(value.serialize(nulls::Serializer.wrap())?)),
 			variant => {
 				Err(Error::custom(format!("unexpected newtype variant `{name}::{variant}`")))
 			}
@@ -62,7 +68,7 @@ impl ser::Serializer for Serializer {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::sql::index::SearchParams;
+	use crate::sql::index::{Nulls, SearchParams};
 	use crate::sql::value::serde::ser::Serializer;
 	use crate::sql::Scoring;
 
@@ -80,6 +86,13 @@ mod tests {
 		assert_eq!(idx, serialized);
 	}
 
+	#[test]
+	fn uniq_nulls() {
+		let idx = Index::UniqNulls(Nulls::NotDistinct);
+		let serialized = idx.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(idx, serialized);
+	}
+
 	#[test]
 	fn search() {
 		let idx = Index::Search(SearchParams {
@@ -0,0 +1,57 @@
+use crate::err::Error;
+use crate::sql::index::Nulls;
+use crate::sql::value::serde::ser;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+
+pub(super) struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Nulls;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Nulls, Error>;
+	type SerializeTuple = Impossible<Nulls, Error>;
+	type SerializeTupleStruct = Impossible<Nulls, Error>;
+	type SerializeTupleVariant = Impossible<Nulls, Error>;
+	type SerializeMap = Impossible<Nulls, Error>;
+	type SerializeStruct = Impossible<Nulls, Error>;
+	type SerializeStructVariant = Impossible<Nulls, Error>;
+
+	const EXPECTED: &'static str = "an enum `Nulls`";
+
+	#[inline]
+	fn serialize_unit_variant(
+		self,
+		name: &'static str,
+		_variant_index: u32,
+		variant: &'static str,
+	) -> Result<Self::Ok, Error> {
+		match variant {
+			"Distinct" => Ok(Nulls::Distinct),
+			"NotDistinct" => Ok(Nulls::NotDistinct),
+			variant => Err(Error::custom(format!("unknown variant `{name}::{variant}`"))),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ser::Serializer as _;
+	use serde::Serialize;
+
+	#[test]
+	fn distinct() {
+		let nulls = Nulls::Distinct;
+		let serialized = nulls.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(nulls, serialized);
+	}
+
+	#[test]
+	fn not_distinct() {
+		let nulls = Nulls::NotDistinct;
+		let serialized = nulls.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(nulls, serialized);
+	}
+}
@@ -0,0 +1,90 @@
+use crate::err::Error;
+use crate::sql::index::RTreeParams;
+use crate::sql::value::serde::ser;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::Serialize;
+
+pub(super) struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = RTreeParams;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<RTreeParams, Error>;
+	type SerializeTuple = Impossible<RTreeParams, Error>;
+	type SerializeTupleStruct = Impossible<RTreeParams, Error>;
+	type SerializeTupleVariant = Impossible<RTreeParams, Error>;
+	type SerializeMap = Impossible<RTreeParams, Error>;
+	type SerializeStruct = SerializeRTree;
+	type SerializeStructVariant = Impossible<RTreeParams, Error>;
+
+	const EXPECTED: &'static str = "a struct `RTreeParams`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeRTree::default())
+	}
+
+	#[inline]
+	fn serialize_newtype_struct<T>(
+		self,
+		_name: &'static str,
+		value: &T,
+	) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		value.serialize(self.wrap())
+	}
+}
+
+#[derive(Default)]
+pub(super) struct SerializeRTree {
+	capacity: u16,
+	doc_ids_order: u32,
+}
+impl serde::ser::SerializeStruct for SerializeRTree {
+	type Ok = RTreeParams;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"capacity" => {
+				self.capacity = value.serialize(ser::primitive::u16::Serializer.wrap())?;
+			}
+			"doc_ids_order" => {
+				self.doc_ids_order = value.serialize(ser::primitive::u32::Serializer.wrap())?;
+			}
+			key => {
+				return Err(Error::custom(format!("unexpected field `RTreeParams {{ {key} }}`")));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		Ok(RTreeParams {
+			capacity: self.capacity,
+			doc_ids_order: self.doc_ids_order,
+		})
+	}
+}
+
+#[test]
+fn rtree_params() {
+	let params = RTreeParams {
+		capacity: 1,
+		doc_ids_order: 2,
+	};
+	let serialized = params.serialize(Serializer.wrap()).unwrap();
+	assert_eq!(params, serialized);
+}
@@ -40,14 +40,17 @@ mod permission;
 mod permissions;
 mod primitive;
 mod range;
+mod reference;
 mod scoring;
 mod split;
 mod start;
 mod statement;
+mod storage;
 mod strand;
 mod string;
 mod subquery;
 mod table;
+mod table_type;
 mod thing;
 mod timeout;
 mod tokenizer;
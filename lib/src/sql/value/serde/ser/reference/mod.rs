@@ -0,0 +1,67 @@
+pub(super) mod opt;
+
+use crate::err::Error;
+use crate::sql::value::serde::ser;
+use crate::sql::Reference;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+
+pub(super) struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Reference;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Reference, Error>;
+	type SerializeTuple = Impossible<Reference, Error>;
+	type SerializeTupleStruct = Impossible<Reference, Error>;
+	type SerializeTupleVariant = Impossible<Reference, Error>;
+	type SerializeMap = Impossible<Reference, Error>;
+	type SerializeStruct = Impossible<Reference, Error>;
+	type SerializeStructVariant = Impossible<Reference, Error>;
+
+	const EXPECTED: &'static str = "an enum `Reference`";
+
+	#[inline]
+	fn serialize_unit_variant(
+		self,
+		name: &'static str,
+		_variant_index: u32,
+		variant: &'static str,
+	) -> Result<Self::Ok, Error> {
+		match variant {
+			"Cascade" => Ok(Reference::Cascade),
+			"SetNull" => Ok(Reference::SetNull),
+			"Restrict" => Ok(Reference::Restrict),
+			variant => Err(Error::custom(format!("unexpected unit variant `{name}::{variant}`"))),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ser::Serializer as _;
+	use serde::Serialize;
+
+	#[test]
+	fn cascade() {
+		let reference = Reference::Cascade;
+		let serialized = reference.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(reference, serialized);
+	}
+
+	#[test]
+	fn set_null() {
+		let reference = Reference::SetNull;
+		let serialized = reference.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(reference, serialized);
+	}
+
+	#[test]
+	fn restrict() {
+		let reference = Reference::Restrict;
+		let serialized = reference.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(reference, serialized);
+	}
+}
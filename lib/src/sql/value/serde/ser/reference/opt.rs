@@ -0,0 +1,55 @@
+use crate::err::Error;
+use crate::sql::value::serde::ser;
+use crate::sql::Reference;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Option<Reference>;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Option<Reference>, Error>;
+	type SerializeTuple = Impossible<Option<Reference>, Error>;
+	type SerializeTupleStruct = Impossible<Option<Reference>, Error>;
+	type SerializeTupleVariant = Impossible<Option<Reference>, Error>;
+	type SerializeMap = Impossible<Option<Reference>, Error>;
+	type SerializeStruct = Impossible<Option<Reference>, Error>;
+	type SerializeStructVariant = Impossible<Option<Reference>, Error>;
+
+	const EXPECTED: &'static str = "an `Option<Reference>`";
+
+	#[inline]
+	fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+		Ok(None)
+	}
+
+	#[inline]
+	fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		Ok(Some(value.serialize(super::Serializer.wrap())?))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ser::Serializer as _;
+
+	#[test]
+	fn none() {
+		let option: Option<Reference> = None;
+		let serialized = option.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(option, serialized);
+	}
+
+	#[test]
+	fn some() {
+		let option = Some(Reference::Cascade);
+		let serialized = option.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(option, serialized);
+	}
+}
@@ -42,6 +42,7 @@ pub struct SerializeDefineAnalyzerStatement {
 	tokenizers: Option<Vec<Tokenizer>>,
 	filters: Option<Vec<Filter>>,
 	comment: Option<Strand>,
+	if_not_exists: bool,
 }
 
 impl serde::ser::SerializeStruct for SerializeDefineAnalyzerStatement {
@@ -65,6 +66,9 @@ impl serde::ser::SerializeStruct for SerializeDefineAnalyzerStatement {
 			"comment" => {
 				self.comment = value.serialize(ser::strand::opt::Serializer.wrap())?;
 			}
+			"if_not_exists" => {
+				self.if_not_exists = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!(
 					"unexpected field `DefineAnalyzerStatement::{key}`"
@@ -80,6 +84,7 @@ impl serde::ser::SerializeStruct for SerializeDefineAnalyzerStatement {
 			tokenizers: self.tokenizers,
 			filters: self.filters,
 			comment: self.comment,
+			if_not_exists: self.if_not_exists,
 		})
 	}
 }
@@ -94,4 +99,14 @@ mod tests {
 		let value: DefineAnalyzerStatement = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(value, stmt);
 	}
+
+	#[test]
+	fn with_if_not_exists() {
+		let stmt = DefineAnalyzerStatement {
+			if_not_exists: true,
+			..Default::default()
+		};
+		let value: DefineAnalyzerStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
 }
@@ -41,6 +41,7 @@ pub struct SerializeDefineDatabaseStatement {
 	changefeed: Option<ChangeFeed>,
 	id: Option<u32>,
 	comment: Option<Strand>,
+	if_not_exists: bool,
 }
 
 impl serde::ser::SerializeStruct for SerializeDefineDatabaseStatement {
@@ -64,6 +65,9 @@ impl serde::ser::SerializeStruct for SerializeDefineDatabaseStatement {
 			"comment" => {
 				self.comment = value.serialize(ser::strand::opt::Serializer.wrap())?;
 			}
+			"if_not_exists" => {
+				self.if_not_exists = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!(
 					"unexpected field `DefineDatabaseStatement::{key}`"
@@ -79,6 +83,7 @@ impl serde::ser::SerializeStruct for SerializeDefineDatabaseStatement {
 			changefeed: self.changefeed,
 			id: self.id,
 			comment: self.comment,
+			if_not_exists: self.if_not_exists,
 		})
 	}
 }
@@ -93,4 +98,14 @@ mod tests {
 		let value: DefineDatabaseStatement = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(value, stmt);
 	}
+
+	#[test]
+	fn with_if_not_exists() {
+		let stmt = DefineDatabaseStatement {
+			if_not_exists: true,
+			..Default::default()
+		};
+		let value: DefineDatabaseStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
 }
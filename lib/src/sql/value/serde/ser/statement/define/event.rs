@@ -43,6 +43,7 @@ pub struct SerializeDefineEventStatement {
 	when: Value,
 	then: Values,
 	comment: Option<Strand>,
+	if_not_exists: bool,
 }
 
 impl serde::ser::SerializeStruct for SerializeDefineEventStatement {
@@ -69,6 +70,9 @@ impl serde::ser::SerializeStruct for SerializeDefineEventStatement {
 			"comment" => {
 				self.comment = value.serialize(ser::strand::opt::Serializer.wrap())?;
 			}
+			"if_not_exists" => {
+				self.if_not_exists = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!(
 					"unexpected field `DefineEventStatement::{key}`"
@@ -85,6 +89,7 @@ impl serde::ser::SerializeStruct for SerializeDefineEventStatement {
 			when: self.when,
 			then: self.then,
 			comment: self.comment,
+			if_not_exists: self.if_not_exists,
 		})
 	}
 }
@@ -99,4 +104,14 @@ mod tests {
 		let value: DefineEventStatement = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(value, stmt);
 	}
+
+	#[test]
+	fn with_if_not_exists() {
+		let stmt = DefineEventStatement {
+			if_not_exists: true,
+			..Default::default()
+		};
+		let value: DefineEventStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
 }
@@ -5,6 +5,7 @@ use crate::sql::Ident;
 use crate::sql::Idiom;
 use crate::sql::Kind;
 use crate::sql::Permissions;
+use crate::sql::Reference;
 use crate::sql::Strand;
 use crate::sql::Value;
 use ser::Serializer as _;
@@ -49,6 +50,9 @@ pub struct SerializeDefineFieldStatement {
 	default: Option<Value>,
 	permissions: Permissions,
 	comment: Option<Strand>,
+	dedup: bool,
+	reference: Option<Reference>,
+	if_not_exists: bool,
 }
 
 impl serde::ser::SerializeStruct for SerializeDefineFieldStatement {
@@ -87,6 +91,15 @@ impl serde::ser::SerializeStruct for SerializeDefineFieldStatement {
 			"comment" => {
 				self.comment = value.serialize(ser::strand::opt::Serializer.wrap())?;
 			}
+			"dedup" => {
+				self.dedup = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
+			"reference" => {
+				self.reference = value.serialize(ser::reference::opt::Serializer.wrap())?;
+			}
+			"if_not_exists" => {
+				self.if_not_exists = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!(
 					"unexpected field `DefineFieldStatement::{key}`"
@@ -107,6 +120,9 @@ impl serde::ser::SerializeStruct for SerializeDefineFieldStatement {
 			default: self.default,
 			permissions: self.permissions,
 			comment: self.comment,
+			dedup: self.dedup,
+			reference: self.reference,
+			if_not_exists: self.if_not_exists,
 		})
 	}
 }
@@ -121,4 +137,34 @@ mod tests {
 		let value: DefineFieldStatement = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(value, stmt);
 	}
+
+	#[test]
+	fn with_dedup() {
+		let stmt = DefineFieldStatement {
+			dedup: true,
+			..Default::default()
+		};
+		let value: DefineFieldStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_reference() {
+		let stmt = DefineFieldStatement {
+			reference: Some(Reference::SetNull),
+			..Default::default()
+		};
+		let value: DefineFieldStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_if_not_exists() {
+		let stmt = DefineFieldStatement {
+			if_not_exists: true,
+			..Default::default()
+		};
+		let value: DefineFieldStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
 }
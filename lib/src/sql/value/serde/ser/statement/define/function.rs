@@ -44,6 +44,7 @@ pub struct SerializeDefineFunctionStatement {
 	block: Block,
 	comment: Option<Strand>,
 	permissions: Permission,
+	if_not_exists: bool,
 }
 
 impl serde::ser::SerializeStruct for SerializeDefineFunctionStatement {
@@ -70,6 +71,9 @@ impl serde::ser::SerializeStruct for SerializeDefineFunctionStatement {
 			"permissions" => {
 				self.permissions = value.serialize(ser::permission::Serializer.wrap())?;
 			}
+			"if_not_exists" => {
+				self.if_not_exists = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!(
 					"unexpected field `DefineFunctionStatement::{key}`"
@@ -86,6 +90,7 @@ impl serde::ser::SerializeStruct for SerializeDefineFunctionStatement {
 			block: self.block,
 			comment: self.comment,
 			permissions: self.permissions,
+			if_not_exists: self.if_not_exists,
 		})
 	}
 }
@@ -199,4 +204,14 @@ mod tests {
 		let value: DefineFunctionStatement = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(value, stmt);
 	}
+
+	#[test]
+	fn with_if_not_exists() {
+		let stmt = DefineFunctionStatement {
+			if_not_exists: true,
+			..Default::default()
+		};
+		let value: DefineFunctionStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
 }
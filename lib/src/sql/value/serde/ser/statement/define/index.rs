@@ -2,6 +2,7 @@ use crate::err::Error;
 use crate::sql::index::Index;
 use crate::sql::statements::DefineIndexStatement;
 use crate::sql::value::serde::ser;
+use crate::sql::Cond;
 use crate::sql::Ident;
 use crate::sql::Idioms;
 use crate::sql::Strand;
@@ -43,6 +44,11 @@ pub struct SerializeDefineIndexStatement {
 	cols: Idioms,
 	index: Index,
 	comment: Option<Strand>,
+	concurrently: bool,
+	cond: Option<Cond>,
+	collate: bool,
+	numeric: bool,
+	if_not_exists: bool,
 }
 
 impl serde::ser::SerializeStruct for SerializeDefineIndexStatement {
@@ -69,6 +75,21 @@ impl serde::ser::SerializeStruct for SerializeDefineIndexStatement {
 			"comment" => {
 				self.comment = value.serialize(ser::strand::opt::Serializer.wrap())?;
 			}
+			"concurrently" => {
+				self.concurrently = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
+			"cond" => {
+				self.cond = value.serialize(ser::cond::opt::Serializer.wrap())?;
+			}
+			"collate" => {
+				self.collate = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
+			"numeric" => {
+				self.numeric = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
+			"if_not_exists" => {
+				self.if_not_exists = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!(
 					"unexpected field `DefineIndexStatement::{key}`"
@@ -85,6 +106,11 @@ impl serde::ser::SerializeStruct for SerializeDefineIndexStatement {
 			cols: self.cols,
 			index: self.index,
 			comment: self.comment,
+			concurrently: self.concurrently,
+			cond: self.cond,
+			collate: self.collate,
+			numeric: self.numeric,
+			if_not_exists: self.if_not_exists,
 		})
 	}
 }
@@ -99,4 +125,25 @@ mod tests {
 		let value: DefineIndexStatement = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(value, stmt);
 	}
+
+	#[test]
+	fn with_collation() {
+		let stmt = DefineIndexStatement {
+			collate: true,
+			numeric: true,
+			..Default::default()
+		};
+		let value: DefineIndexStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_if_not_exists() {
+		let stmt = DefineIndexStatement {
+			if_not_exists: true,
+			..Default::default()
+		};
+		let value: DefineIndexStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
 }
@@ -0,0 +1,116 @@
+use crate::err::Error;
+use crate::sql::statements::DefineMigrationStatement;
+use crate::sql::value::serde::ser;
+use crate::sql::Block;
+use crate::sql::Strand;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = DefineMigrationStatement;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<DefineMigrationStatement, Error>;
+	type SerializeTuple = Impossible<DefineMigrationStatement, Error>;
+	type SerializeTupleStruct = Impossible<DefineMigrationStatement, Error>;
+	type SerializeTupleVariant = Impossible<DefineMigrationStatement, Error>;
+	type SerializeMap = Impossible<DefineMigrationStatement, Error>;
+	type SerializeStruct = SerializeDefineMigrationStatement;
+	type SerializeStructVariant = Impossible<DefineMigrationStatement, Error>;
+
+	const EXPECTED: &'static str = "a struct `DefineMigrationStatement`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeDefineMigrationStatement::default())
+	}
+}
+
+#[derive(Default)]
+pub struct SerializeDefineMigrationStatement {
+	version: u64,
+	block: Block,
+	comment: Option<Strand>,
+	if_not_exists: bool,
+}
+
+impl serde::ser::SerializeStruct for SerializeDefineMigrationStatement {
+	type Ok = DefineMigrationStatement;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"version" => {
+				self.version = value.serialize(ser::primitive::u64::Serializer.wrap())?;
+			}
+			"block" => {
+				self.block = Block(value.serialize(ser::block::entry::vec::Serializer.wrap())?);
+			}
+			"comment" => {
+				self.comment = value.serialize(ser::strand::opt::Serializer.wrap())?;
+			}
+			"if_not_exists" => {
+				self.if_not_exists = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
+			key => {
+				return Err(Error::custom(format!(
+					"unexpected field `DefineMigrationStatement::{key}`"
+				)));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		Ok(DefineMigrationStatement {
+			version: self.version,
+			block: self.block,
+			comment: self.comment,
+			if_not_exists: self.if_not_exists,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default() {
+		let stmt = DefineMigrationStatement::default();
+		let value: DefineMigrationStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_version() {
+		let stmt = DefineMigrationStatement {
+			version: 20240101,
+			comment: Some(Default::default()),
+			..Default::default()
+		};
+		let value: DefineMigrationStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_if_not_exists() {
+		let stmt = DefineMigrationStatement {
+			if_not_exists: true,
+			..Default::default()
+		};
+		let value: DefineMigrationStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+}
@@ -1,12 +1,15 @@
 mod analyzer;
+mod config;
 mod database;
 mod event;
 mod field;
 mod function;
 mod index;
+mod migration;
 mod namespace;
 mod param;
 mod scope;
+mod sequence;
 mod table;
 mod token;
 mod user;
@@ -66,6 +69,13 @@ impl ser::Serializer for Serializer {
 			"Field" => Ok(DefineStatement::Field(value.serialize(field::Serializer.wrap())?)),
 			"Index" => Ok(DefineStatement::Index(value.serialize(index::Serializer.wrap())?)),
 			"User" => Ok(DefineStatement::User(value.serialize(user::Serializer.wrap())?)),
+			"Config" => Ok(DefineStatement::Config(value.serialize(config::Serializer.wrap())?)),
+			"Sequence" => {
+				Ok(DefineStatement::Sequence(value.serialize(sequence::Serializer.wrap())?))
+			}
+			"Migration" => {
+				Ok(DefineStatement::Migration(value.serialize(migration::Serializer.wrap())?))
+			}
 			variant => {
 				Err(Error::custom(format!("unexpected newtype variant `{name}::{variant}`")))
 			}
@@ -161,4 +171,25 @@ mod tests {
 		let serialized = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(stmt, serialized);
 	}
+
+	#[test]
+	fn config() {
+		let stmt = DefineStatement::Config(Default::default());
+		let serialized = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(stmt, serialized);
+	}
+
+	#[test]
+	fn sequence() {
+		let stmt = DefineStatement::Sequence(Default::default());
+		let serialized = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(stmt, serialized);
+	}
+
+	#[test]
+	fn migration() {
+		let stmt = DefineStatement::Migration(Default::default());
+		let serialized = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(stmt, serialized);
+	}
 }
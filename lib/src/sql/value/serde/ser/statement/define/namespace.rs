@@ -39,6 +39,7 @@ pub struct SerializeDefineNamespaceStatement {
 	name: Ident,
 	id: Option<u32>,
 	comment: Option<Strand>,
+	if_not_exists: bool,
 }
 
 impl serde::ser::SerializeStruct for SerializeDefineNamespaceStatement {
@@ -59,6 +60,9 @@ impl serde::ser::SerializeStruct for SerializeDefineNamespaceStatement {
 			"comment" => {
 				self.comment = value.serialize(ser::strand::opt::Serializer.wrap())?;
 			}
+			"if_not_exists" => {
+				self.if_not_exists = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!(
 					"unexpected field `DefineNamespaceStatement::{key}`"
@@ -73,6 +77,7 @@ impl serde::ser::SerializeStruct for SerializeDefineNamespaceStatement {
 			name: self.name,
 			id: self.id,
 			comment: self.comment,
+			if_not_exists: self.if_not_exists,
 		})
 	}
 }
@@ -87,4 +92,14 @@ mod tests {
 		let value: DefineNamespaceStatement = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(value, stmt);
 	}
+
+	#[test]
+	fn with_if_not_exists() {
+		let stmt = DefineNamespaceStatement {
+			if_not_exists: true,
+			..Default::default()
+		};
+		let value: DefineNamespaceStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
 }
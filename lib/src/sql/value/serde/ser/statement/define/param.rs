@@ -2,6 +2,7 @@ use crate::err::Error;
 use crate::sql::statements::DefineParamStatement;
 use crate::sql::value::serde::ser;
 use crate::sql::Ident;
+use crate::sql::Kind;
 use crate::sql::Permission;
 use crate::sql::Strand;
 use crate::sql::Value;
@@ -42,6 +43,10 @@ pub struct SerializeDefineParamStatement {
 	value: Value,
 	comment: Option<Strand>,
 	permissions: Permission,
+	if_not_exists: bool,
+	kind: Option<Kind>,
+	assert: Option<Value>,
+	change: Permission,
 }
 
 impl serde::ser::SerializeStruct for SerializeDefineParamStatement {
@@ -65,6 +70,18 @@ impl serde::ser::SerializeStruct for SerializeDefineParamStatement {
 			"permissions" => {
 				self.permissions = value.serialize(ser::permission::Serializer.wrap())?;
 			}
+			"if_not_exists" => {
+				self.if_not_exists = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
+			"kind" => {
+				self.kind = value.serialize(ser::kind::opt::Serializer.wrap())?;
+			}
+			"assert" => {
+				self.assert = value.serialize(ser::value::opt::Serializer.wrap())?;
+			}
+			"change" => {
+				self.change = value.serialize(ser::permission::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!(
 					"unexpected field `DefineParamStatement::{key}`"
@@ -80,6 +97,10 @@ impl serde::ser::SerializeStruct for SerializeDefineParamStatement {
 			value: self.value,
 			comment: self.comment,
 			permissions: self.permissions,
+			if_not_exists: self.if_not_exists,
+			kind: self.kind,
+			assert: self.assert,
+			change: self.change,
 		})
 	}
 }
@@ -94,4 +115,44 @@ mod tests {
 		let value: DefineParamStatement = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(value, stmt);
 	}
+
+	#[test]
+	fn with_if_not_exists() {
+		let stmt = DefineParamStatement {
+			if_not_exists: true,
+			..Default::default()
+		};
+		let value: DefineParamStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_kind() {
+		let stmt = DefineParamStatement {
+			kind: Some(Kind::Int),
+			..Default::default()
+		};
+		let value: DefineParamStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_assert() {
+		let stmt = DefineParamStatement {
+			assert: Some(Value::Bool(true)),
+			..Default::default()
+		};
+		let value: DefineParamStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_change() {
+		let stmt = DefineParamStatement {
+			change: Permission::None,
+			..Default::default()
+		};
+		let value: DefineParamStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
 }
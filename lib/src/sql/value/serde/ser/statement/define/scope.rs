@@ -44,6 +44,7 @@ pub struct SerializeDefineScopeStatement {
 	signup: Option<Value>,
 	signin: Option<Value>,
 	comment: Option<Strand>,
+	if_not_exists: bool,
 }
 
 impl serde::ser::SerializeStruct for SerializeDefineScopeStatement {
@@ -74,6 +75,9 @@ impl serde::ser::SerializeStruct for SerializeDefineScopeStatement {
 			"comment" => {
 				self.comment = value.serialize(ser::strand::opt::Serializer.wrap())?;
 			}
+			"if_not_exists" => {
+				self.if_not_exists = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!(
 					"unexpected field `DefineScopeStatement::{key}`"
@@ -91,6 +95,7 @@ impl serde::ser::SerializeStruct for SerializeDefineScopeStatement {
 			signup: self.signup,
 			signin: self.signin,
 			comment: self.comment,
+			if_not_exists: self.if_not_exists,
 		})
 	}
 }
@@ -105,4 +110,14 @@ mod tests {
 		let value: DefineScopeStatement = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(value, stmt);
 	}
+
+	#[test]
+	fn with_if_not_exists() {
+		let stmt = DefineScopeStatement {
+			if_not_exists: true,
+			..Default::default()
+		};
+		let value: DefineScopeStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
 }
@@ -0,0 +1,126 @@
+use crate::err::Error;
+use crate::sql::statements::DefineSequenceStatement;
+use crate::sql::value::serde::ser;
+use crate::sql::Ident;
+use crate::sql::Strand;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = DefineSequenceStatement;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<DefineSequenceStatement, Error>;
+	type SerializeTuple = Impossible<DefineSequenceStatement, Error>;
+	type SerializeTupleStruct = Impossible<DefineSequenceStatement, Error>;
+	type SerializeTupleVariant = Impossible<DefineSequenceStatement, Error>;
+	type SerializeMap = Impossible<DefineSequenceStatement, Error>;
+	type SerializeStruct = SerializeDefineSequenceStatement;
+	type SerializeStructVariant = Impossible<DefineSequenceStatement, Error>;
+
+	const EXPECTED: &'static str = "a struct `DefineSequenceStatement`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeDefineSequenceStatement::default())
+	}
+}
+
+#[derive(Default)]
+pub struct SerializeDefineSequenceStatement {
+	name: Ident,
+	batch: u32,
+	start: i64,
+	comment: Option<Strand>,
+	version: u64,
+	if_not_exists: bool,
+}
+
+impl serde::ser::SerializeStruct for SerializeDefineSequenceStatement {
+	type Ok = DefineSequenceStatement;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"name" => {
+				self.name = Ident(value.serialize(ser::string::Serializer.wrap())?);
+			}
+			"batch" => {
+				self.batch = value.serialize(ser::primitive::u32::Serializer.wrap())?;
+			}
+			"start" => {
+				self.start = value.serialize(ser::primitive::i64::Serializer.wrap())?;
+			}
+			"comment" => {
+				self.comment = value.serialize(ser::strand::opt::Serializer.wrap())?;
+			}
+			"version" => {
+				self.version = value.serialize(ser::primitive::u64::Serializer.wrap())?;
+			}
+			"if_not_exists" => {
+				self.if_not_exists = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
+			key => {
+				return Err(Error::custom(format!(
+					"unexpected field `DefineSequenceStatement::{key}`"
+				)));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		Ok(DefineSequenceStatement {
+			name: self.name,
+			batch: self.batch,
+			start: self.start,
+			comment: self.comment,
+			version: self.version,
+			if_not_exists: self.if_not_exists,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default() {
+		let stmt = DefineSequenceStatement::default();
+		let value: DefineSequenceStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_start() {
+		let stmt = DefineSequenceStatement {
+			batch: 10,
+			start: -100,
+			..Default::default()
+		};
+		let value: DefineSequenceStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_if_not_exists() {
+		let stmt = DefineSequenceStatement {
+			if_not_exists: true,
+			..Default::default()
+		};
+		let value: DefineSequenceStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+}
@@ -1,10 +1,14 @@
 use crate::err::Error;
 use crate::sql::changefeed::ChangeFeed;
+use crate::sql::duration::Duration;
 use crate::sql::statements::DefineTableStatement;
+use crate::sql::storage::Storage;
 use crate::sql::value::serde::ser;
+use crate::sql::Cond;
 use crate::sql::Ident;
 use crate::sql::Permissions;
 use crate::sql::Strand;
+use crate::sql::TableType;
 use crate::sql::View;
 use ser::Serializer as _;
 use serde::ser::Error as _;
@@ -47,6 +51,13 @@ pub struct SerializeDefineTableStatement {
 	permissions: Permissions,
 	changefeed: Option<ChangeFeed>,
 	comment: Option<Strand>,
+	merge: bool,
+	expire: Option<Duration>,
+	storage: Option<Storage>,
+	archive: Option<Cond>,
+	kind: TableType,
+	enumerated: Option<Vec<Strand>>,
+	if_not_exists: bool,
 }
 
 impl serde::ser::SerializeStruct for SerializeDefineTableStatement {
@@ -82,6 +93,28 @@ impl serde::ser::SerializeStruct for SerializeDefineTableStatement {
 			"comment" => {
 				self.comment = value.serialize(ser::strand::opt::Serializer.wrap())?;
 			}
+			"merge" => {
+				self.merge = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
+			"expire" => {
+				self.expire =
+					value.serialize(ser::duration::opt::Serializer.wrap())?.map(Into::into);
+			}
+			"storage" => {
+				self.storage = value.serialize(ser::storage::opt::Serializer.wrap())?;
+			}
+			"archive" => {
+				self.archive = value.serialize(ser::cond::opt::Serializer.wrap())?;
+			}
+			"kind" => {
+				self.kind = value.serialize(ser::table_type::Serializer.wrap())?;
+			}
+			"enumerated" => {
+				self.enumerated = value.serialize(ser::strand::vec::opt::Serializer.wrap())?;
+			}
+			"if_not_exists" => {
+				self.if_not_exists = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!(
 					"unexpected field `DefineTableStatement::{key}`"
@@ -101,6 +134,13 @@ impl serde::ser::SerializeStruct for SerializeDefineTableStatement {
 			permissions: self.permissions,
 			changefeed: self.changefeed,
 			comment: self.comment,
+			merge: self.merge,
+			expire: self.expire,
+			storage: self.storage,
+			archive: self.archive,
+			kind: self.kind,
+			enumerated: self.enumerated,
+			if_not_exists: self.if_not_exists,
 		})
 	}
 }
@@ -115,4 +155,64 @@ mod tests {
 		let value: DefineTableStatement = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(value, stmt);
 	}
+
+	#[test]
+	fn with_expire() {
+		let stmt = DefineTableStatement {
+			expire: Some(Default::default()),
+			..Default::default()
+		};
+		let value: DefineTableStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_storage() {
+		let stmt = DefineTableStatement {
+			storage: Some(Storage::Shards(4)),
+			..Default::default()
+		};
+		let value: DefineTableStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_archive() {
+		let stmt = DefineTableStatement {
+			archive: Some(Default::default()),
+			..Default::default()
+		};
+		let value: DefineTableStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_kind() {
+		let stmt = DefineTableStatement {
+			kind: TableType::Normal,
+			..Default::default()
+		};
+		let value: DefineTableStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_enumerated() {
+		let stmt = DefineTableStatement {
+			enumerated: Some(vec![Strand::from("open")]),
+			..Default::default()
+		};
+		let value: DefineTableStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_if_not_exists() {
+		let stmt = DefineTableStatement {
+			if_not_exists: true,
+			..Default::default()
+		};
+		let value: DefineTableStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
 }
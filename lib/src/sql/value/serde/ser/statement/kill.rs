@@ -36,6 +36,7 @@ impl ser::Serializer for Serializer {
 #[derive(Default)]
 pub struct SerializeKillStatement {
 	id: Option<Value>,
+	query: bool,
 }
 
 impl serde::ser::SerializeStruct for SerializeKillStatement {
@@ -50,6 +51,9 @@ impl serde::ser::SerializeStruct for SerializeKillStatement {
 			"id" => {
 				self.id = Some(value.serialize(ser::value::Serializer.wrap())?);
 			}
+			"query" => {
+				self.query = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!("unexpected field `KillStatement::{key}`")));
 			}
@@ -61,6 +65,7 @@ impl serde::ser::SerializeStruct for SerializeKillStatement {
 		match self.id {
 			Some(id) => Ok(KillStatement {
 				id,
+				query: self.query,
 			}),
 			None => Err(Error::custom("`KillStatement` missing required field")),
 		}
@@ -77,4 +82,14 @@ mod tests {
 		let value: KillStatement = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(value, stmt);
 	}
+
+	#[test]
+	fn with_query() {
+		let stmt = KillStatement {
+			query: true,
+			..Default::default()
+		};
+		let value: KillStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
 }
@@ -18,6 +18,7 @@ pub mod relate;
 pub mod remove;
 pub mod select;
 pub mod set;
+pub mod setoption;
 pub mod show;
 pub mod sleep;
 pub mod throw;
@@ -85,6 +86,7 @@ impl ser::Serializer for Serializer {
 			"Throw" => Ok(Statement::Throw(value.serialize(throw::Serializer.wrap())?)),
 			"Update" => Ok(Statement::Update(value.serialize(update::Serializer.wrap())?)),
 			"Use" => Ok(Statement::Use(value.serialize(yuse::Serializer.wrap())?)),
+			"SetOption" => Ok(Statement::SetOption(value.serialize(setoption::Serializer.wrap())?)),
 			variant => {
 				Err(Error::custom(format!("unexpected newtype variant `{name}::{variant}`")))
 			}
@@ -256,4 +258,11 @@ mod tests {
 		let serialized = statement.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(statement, serialized);
 	}
+
+	#[test]
+	fn set_option() {
+		let statement = Statement::SetOption(Default::default());
+		let serialized = statement.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(statement, serialized);
+	}
 }
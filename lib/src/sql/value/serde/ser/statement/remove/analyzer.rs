@@ -36,6 +36,7 @@ impl ser::Serializer for Serializer {
 #[derive(Default)]
 pub struct SerializeRemoveAnalyzerStatement {
 	name: Ident,
+	if_exists: bool,
 }
 
 impl serde::ser::SerializeStruct for SerializeRemoveAnalyzerStatement {
@@ -50,6 +51,9 @@ impl serde::ser::SerializeStruct for SerializeRemoveAnalyzerStatement {
 			"name" => {
 				self.name = Ident(value.serialize(ser::string::Serializer.wrap())?);
 			}
+			"if_exists" => {
+				self.if_exists = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!(
 					"unexpected field `RemoveAnalyzerStatement::{key}`"
@@ -62,6 +66,7 @@ impl serde::ser::SerializeStruct for SerializeRemoveAnalyzerStatement {
 	fn end(self) -> Result<Self::Ok, Error> {
 		Ok(RemoveAnalyzerStatement {
 			name: self.name,
+			if_exists: self.if_exists,
 		})
 	}
 }
@@ -76,4 +81,14 @@ mod tests {
 		let value: RemoveAnalyzerStatement = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(value, stmt);
 	}
+
+	#[test]
+	fn with_if_exists() {
+		let stmt = RemoveAnalyzerStatement {
+			if_exists: true,
+			..Default::default()
+		};
+		let value: RemoveAnalyzerStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
 }
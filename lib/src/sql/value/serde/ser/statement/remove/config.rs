@@ -0,0 +1,94 @@
+use crate::err::Error;
+use crate::sql::statements::RemoveConfigStatement;
+use crate::sql::value::serde::ser;
+use crate::sql::Ident;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = RemoveConfigStatement;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<RemoveConfigStatement, Error>;
+	type SerializeTuple = Impossible<RemoveConfigStatement, Error>;
+	type SerializeTupleStruct = Impossible<RemoveConfigStatement, Error>;
+	type SerializeTupleVariant = Impossible<RemoveConfigStatement, Error>;
+	type SerializeMap = Impossible<RemoveConfigStatement, Error>;
+	type SerializeStruct = SerializeRemoveConfigStatement;
+	type SerializeStructVariant = Impossible<RemoveConfigStatement, Error>;
+
+	const EXPECTED: &'static str = "a struct `RemoveConfigStatement`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeRemoveConfigStatement::default())
+	}
+}
+
+#[derive(Default)]
+pub struct SerializeRemoveConfigStatement {
+	name: Ident,
+	if_exists: bool,
+}
+
+impl serde::ser::SerializeStruct for SerializeRemoveConfigStatement {
+	type Ok = RemoveConfigStatement;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"name" => {
+				self.name = Ident(value.serialize(ser::string::Serializer.wrap())?);
+			}
+			"if_exists" => {
+				self.if_exists = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
+			key => {
+				return Err(Error::custom(format!(
+					"unexpected field `RemoveConfigStatement::{key}`"
+				)));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		Ok(RemoveConfigStatement {
+			name: self.name,
+			if_exists: self.if_exists,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default() {
+		let stmt = RemoveConfigStatement::default();
+		let value: RemoveConfigStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_if_exists() {
+		let stmt = RemoveConfigStatement {
+			if_exists: true,
+			..Default::default()
+		};
+		let value: RemoveConfigStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+}
@@ -37,6 +37,7 @@ impl ser::Serializer for Serializer {
 pub struct SerializeRemoveEventStatement {
 	name: Ident,
 	what: Ident,
+	if_exists: bool,
 }
 
 impl serde::ser::SerializeStruct for SerializeRemoveEventStatement {
@@ -54,6 +55,9 @@ impl serde::ser::SerializeStruct for SerializeRemoveEventStatement {
 			"what" => {
 				self.what = Ident(value.serialize(ser::string::Serializer.wrap())?);
 			}
+			"if_exists" => {
+				self.if_exists = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!(
 					"unexpected field `RemoveEventStatement::{key}`"
@@ -67,6 +71,7 @@ impl serde::ser::SerializeStruct for SerializeRemoveEventStatement {
 		Ok(RemoveEventStatement {
 			name: self.name,
 			what: self.what,
+			if_exists: self.if_exists,
 		})
 	}
 }
@@ -81,4 +86,14 @@ mod tests {
 		let value: RemoveEventStatement = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(value, stmt);
 	}
+
+	#[test]
+	fn with_if_exists() {
+		let stmt = RemoveEventStatement {
+			if_exists: true,
+			..Default::default()
+		};
+		let value: RemoveEventStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
 }
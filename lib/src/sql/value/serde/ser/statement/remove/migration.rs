@@ -0,0 +1,93 @@
+use crate::err::Error;
+use crate::sql::statements::RemoveMigrationStatement;
+use crate::sql::value::serde::ser;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = RemoveMigrationStatement;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<RemoveMigrationStatement, Error>;
+	type SerializeTuple = Impossible<RemoveMigrationStatement, Error>;
+	type SerializeTupleStruct = Impossible<RemoveMigrationStatement, Error>;
+	type SerializeTupleVariant = Impossible<RemoveMigrationStatement, Error>;
+	type SerializeMap = Impossible<RemoveMigrationStatement, Error>;
+	type SerializeStruct = SerializeRemoveMigrationStatement;
+	type SerializeStructVariant = Impossible<RemoveMigrationStatement, Error>;
+
+	const EXPECTED: &'static str = "a struct `RemoveMigrationStatement`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeRemoveMigrationStatement::default())
+	}
+}
+
+#[derive(Default)]
+pub struct SerializeRemoveMigrationStatement {
+	version: u64,
+	if_exists: bool,
+}
+
+impl serde::ser::SerializeStruct for SerializeRemoveMigrationStatement {
+	type Ok = RemoveMigrationStatement;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"version" => {
+				self.version = value.serialize(ser::primitive::u64::Serializer.wrap())?;
+			}
+			"if_exists" => {
+				self.if_exists = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
+			key => {
+				return Err(Error::custom(format!(
+					"unexpected field `RemoveMigrationStatement::{key}`"
+				)));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		Ok(RemoveMigrationStatement {
+			version: self.version,
+			if_exists: self.if_exists,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default() {
+		let stmt = RemoveMigrationStatement::default();
+		let value: RemoveMigrationStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_if_exists() {
+		let stmt = RemoveMigrationStatement {
+			if_exists: true,
+			..Default::default()
+		};
+		let value: RemoveMigrationStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+}
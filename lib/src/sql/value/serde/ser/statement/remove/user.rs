@@ -38,6 +38,7 @@ impl ser::Serializer for Serializer {
 pub struct SerializeRemoveUserStatement {
 	name: Ident,
 	base: Base,
+	if_exists: bool,
 }
 
 impl serde::ser::SerializeStruct for SerializeRemoveUserStatement {
@@ -55,6 +56,9 @@ impl serde::ser::SerializeStruct for SerializeRemoveUserStatement {
 			"base" => {
 				self.base = value.serialize(ser::base::Serializer.wrap())?;
 			}
+			"if_exists" => {
+				self.if_exists = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!(
 					"unexpected field `RemoveUserStatement::{key}`"
@@ -68,6 +72,7 @@ impl serde::ser::SerializeStruct for SerializeRemoveUserStatement {
 		Ok(RemoveUserStatement {
 			name: self.name,
 			base: self.base,
+			if_exists: self.if_exists,
 		})
 	}
 }
@@ -82,4 +87,14 @@ mod tests {
 		let value: RemoveUserStatement = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(value, stmt);
 	}
+
+	#[test]
+	fn with_if_exists() {
+		let stmt = RemoveUserStatement {
+			if_exists: true,
+			..Default::default()
+		};
+		let value: RemoveUserStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
 }
@@ -64,6 +64,9 @@ pub struct SerializeSelectStatement {
 	timeout: Option<Timeout>,
 	parallel: Option<bool>,
 	explain: Option<Explain>,
+	profile: Option<bool>,
+	analyze: Option<bool>,
+	fetch_size: Option<u64>,
 }
 
 impl serde::ser::SerializeStruct for SerializeSelectStatement {
@@ -123,6 +126,15 @@ impl serde::ser::SerializeStruct for SerializeSelectStatement {
 			"explain" => {
 				self.explain = value.serialize(ser::explain::opt::Serializer.wrap())?;
 			}
+			"profile" => {
+				self.profile = Some(value.serialize(ser::primitive::bool::Serializer.wrap())?);
+			}
+			"analyze" => {
+				self.analyze = Some(value.serialize(ser::primitive::bool::Serializer.wrap())?);
+			}
+			"fetch_size" => {
+				self.fetch_size = value.serialize(ser::primitive::u64::opt::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!("unexpected field `SelectStatement::{key}`")));
 			}
@@ -140,6 +152,9 @@ impl serde::ser::SerializeStruct for SerializeSelectStatement {
 				with: self.with,
 				parallel,
 				explain: self.explain,
+				profile: self.profile.is_some_and(|v| v),
+				analyze: self.analyze.is_some_and(|v| v),
+				fetch_size: self.fetch_size,
 				cond: self.cond,
 				split: self.split,
 				group: self.group,
@@ -276,6 +291,37 @@ mod tests {
 		assert_eq!(value, stmt);
 	}
 
+	#[test]
+	fn with_explain_analyze() {
+		let stmt = SelectStatement {
+			explain: Some(Explain(true)),
+			analyze: true,
+			..Default::default()
+		};
+		let value: SelectStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_fetch_size() {
+		let stmt = SelectStatement {
+			fetch_size: Some(100),
+			..Default::default()
+		};
+		let value: SelectStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_profile() {
+		let stmt = SelectStatement {
+			profile: true,
+			..Default::default()
+		};
+		let value: SelectStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
 	#[test]
 	fn with_with_noindex() {
 		let stmt = SelectStatement {
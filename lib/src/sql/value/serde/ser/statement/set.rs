@@ -1,6 +1,7 @@
 use crate::err::Error;
 use crate::sql::statements::SetStatement;
 use crate::sql::value::serde::ser;
+use crate::sql::Duration;
 use crate::sql::Value;
 use ser::Serializer as _;
 use serde::ser::Error as _;
@@ -37,6 +38,8 @@ impl ser::Serializer for Serializer {
 pub struct SerializeSetStatement {
 	name: Option<String>,
 	what: Option<Value>,
+	session: bool,
+	ttl: Option<Duration>,
 }
 
 impl serde::ser::SerializeStruct for SerializeSetStatement {
@@ -54,6 +57,12 @@ impl serde::ser::SerializeStruct for SerializeSetStatement {
 			"what" => {
 				self.what = Some(value.serialize(ser::value::Serializer.wrap())?);
 			}
+			"session" => {
+				self.session = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
+			"ttl" => {
+				self.ttl = value.serialize(ser::duration::opt::Serializer.wrap())?.map(Into::into);
+			}
 			key => {
 				return Err(Error::custom(format!("unexpected field `SetStatement::{key}`")));
 			}
@@ -66,6 +75,8 @@ impl serde::ser::SerializeStruct for SerializeSetStatement {
 			(Some(name), Some(what)) => Ok(SetStatement {
 				name,
 				what,
+				session: self.session,
+				ttl: self.ttl,
 			}),
 			_ => Err(Error::custom("`SetStatement` missing required field(s)")),
 		}
@@ -82,4 +93,15 @@ mod tests {
 		let value: SetStatement = stmt.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(value, stmt);
 	}
+
+	#[test]
+	fn with_session() {
+		let stmt = SetStatement {
+			session: true,
+			ttl: Some(Default::default()),
+			..Default::default()
+		};
+		let value: SetStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
 }
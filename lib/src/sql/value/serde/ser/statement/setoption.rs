@@ -0,0 +1,93 @@
+use crate::err::Error;
+use crate::sql::statements::SetOptionStatement;
+use crate::sql::value::serde::ser;
+use crate::sql::Ident;
+use crate::sql::Value;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = SetOptionStatement;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<SetOptionStatement, Error>;
+	type SerializeTuple = Impossible<SetOptionStatement, Error>;
+	type SerializeTupleStruct = Impossible<SetOptionStatement, Error>;
+	type SerializeTupleVariant = Impossible<SetOptionStatement, Error>;
+	type SerializeMap = Impossible<SetOptionStatement, Error>;
+	type SerializeStruct = SerializeSetOptionStatement;
+	type SerializeStructVariant = Impossible<SetOptionStatement, Error>;
+
+	const EXPECTED: &'static str = "a struct `SetOptionStatement`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeSetOptionStatement::default())
+	}
+}
+
+#[derive(Default)]
+pub struct SerializeSetOptionStatement {
+	name: Ident,
+	what: Value,
+}
+
+impl serde::ser::SerializeStruct for SerializeSetOptionStatement {
+	type Ok = SetOptionStatement;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"name" => {
+				self.name = Ident(value.serialize(ser::string::Serializer.wrap())?);
+			}
+			"what" => {
+				self.what = value.serialize(ser::value::Serializer.wrap())?;
+			}
+			key => {
+				return Err(Error::custom(format!("unexpected field `SetOptionStatement::{key}`")));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		Ok(SetOptionStatement {
+			name: self.name,
+			what: self.what,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default() {
+		let stmt = SetOptionStatement::default();
+		let value: SetOptionStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_value() {
+		let stmt = SetOptionStatement {
+			name: Ident::from("TIMEOUT"),
+			what: Value::from(10),
+		};
+		let value: SetOptionStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+}
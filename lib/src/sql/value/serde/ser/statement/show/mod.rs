@@ -41,6 +41,8 @@ pub struct SerializeShowStatement {
 	table: Option<Table>,
 	since: Option<ShowSince>,
 	limit: Option<u32>,
+	queries: bool,
+	operations: bool,
 }
 
 impl serde::ser::SerializeStruct for SerializeShowStatement {
@@ -61,6 +63,12 @@ impl serde::ser::SerializeStruct for SerializeShowStatement {
 			"limit" => {
 				self.limit = value.serialize(ser::primitive::u32::opt::Serializer.wrap())?;
 			}
+			"queries" => {
+				self.queries = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
+			"operations" => {
+				self.operations = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!("unexpected field `ShowStatement::{key}`")));
 			}
@@ -74,6 +82,8 @@ impl serde::ser::SerializeStruct for SerializeShowStatement {
 				since,
 				table: self.table,
 				limit: self.limit,
+				queries: self.queries,
+				operations: self.operations,
 			}),
 			None => Err(Error::custom("`ShowStatement` missing required field(s)")),
 		}
@@ -97,6 +107,8 @@ mod tests {
 				table: None,
 				since: Default::default(),
 				limit: None,
+				queries: false,
+				operations: false,
 			}
 		}
 	}
@@ -118,6 +130,26 @@ mod tests {
 		assert_eq!(value, stmt);
 	}
 
+	#[test]
+	fn with_queries() {
+		let stmt = ShowStatement {
+			queries: true,
+			..Default::default()
+		};
+		let value: ShowStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
+	#[test]
+	fn with_operations() {
+		let stmt = ShowStatement {
+			operations: true,
+			..Default::default()
+		};
+		let value: ShowStatement = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+
 	#[test]
 	fn with_limit() {
 		let stmt = ShowStatement {
@@ -0,0 +1,70 @@
+pub(super) mod opt;
+
+use crate::err::Error;
+use crate::sql::storage::Storage;
+use crate::sql::value::serde::ser;
+use crate::sql::Ident;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Storage;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Storage, Error>;
+	type SerializeTuple = Impossible<Storage, Error>;
+	type SerializeTupleStruct = Impossible<Storage, Error>;
+	type SerializeTupleVariant = Impossible<Storage, Error>;
+	type SerializeMap = Impossible<Storage, Error>;
+	type SerializeStruct = Impossible<Storage, Error>;
+	type SerializeStructVariant = Impossible<Storage, Error>;
+
+	const EXPECTED: &'static str = "an enum `Storage`";
+
+	#[inline]
+	fn serialize_newtype_variant<T>(
+		self,
+		name: &'static str,
+		_variant_index: u32,
+		variant: &'static str,
+		value: &T,
+	) -> Result<Self::Ok, Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match variant {
+			"Family" => {
+				Ok(Storage::Family(Ident(value.serialize(ser::string::Serializer.wrap())?)))
+			}
+			"Shards" => {
+				Ok(Storage::Shards(value.serialize(ser::primitive::u16::Serializer.wrap())?))
+			}
+			variant => {
+				Err(Error::custom(format!("unexpected newtype variant `{name}::{variant}`")))
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ser::Serializer as _;
+
+	#[test]
+	fn family() {
+		let storage = Storage::Family(Ident::from("hot"));
+		let serialized = storage.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(storage, serialized);
+	}
+
+	#[test]
+	fn shards() {
+		let storage = Storage::Shards(4);
+		let serialized = storage.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(storage, serialized);
+	}
+}
@@ -0,0 +1,55 @@
+use crate::err::Error;
+use crate::sql::storage::Storage;
+use crate::sql::value::serde::ser;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Option<Storage>;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Option<Storage>, Error>;
+	type SerializeTuple = Impossible<Option<Storage>, Error>;
+	type SerializeTupleStruct = Impossible<Option<Storage>, Error>;
+	type SerializeTupleVariant = Impossible<Option<Storage>, Error>;
+	type SerializeMap = Impossible<Option<Storage>, Error>;
+	type SerializeStruct = Impossible<Option<Storage>, Error>;
+	type SerializeStructVariant = Impossible<Option<Storage>, Error>;
+
+	const EXPECTED: &'static str = "an `Option<Storage>`";
+
+	#[inline]
+	fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+		Ok(None)
+	}
+
+	#[inline]
+	fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		Ok(Some(value.serialize(super::Serializer.wrap())?))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ser::Serializer as _;
+
+	#[test]
+	fn none() {
+		let option: Option<Storage> = None;
+		let serialized = option.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(option, serialized);
+	}
+
+	#[test]
+	fn some() {
+		let option = Some(Storage::Shards(4));
+		let serialized = option.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(option, serialized);
+	}
+}
@@ -1 +1,2 @@
 pub(super) mod opt;
+pub(super) mod vec;
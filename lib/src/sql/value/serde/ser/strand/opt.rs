@@ -30,7 +30,7 @@ impl ser::Serializer for Serializer {
 	where
 		T: ?Sized + Serialize,
 	{
-		Ok(Some(Strand(value.serialize(ser::string::Serializer.wrap())?)))
+		Ok(Some(Strand::from(value.serialize(ser::string::Serializer.wrap())?)))
 	}
 }
 
@@ -0,0 +1,79 @@
+pub mod opt;
+
+use crate::err::Error;
+use crate::sql::value::serde::ser;
+use crate::sql::Strand;
+use ser::Serializer as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Vec<Strand>;
+	type Error = Error;
+
+	type SerializeSeq = SerializeStrandVec;
+	type SerializeTuple = Impossible<Vec<Strand>, Error>;
+	type SerializeTupleStruct = Impossible<Vec<Strand>, Error>;
+	type SerializeTupleVariant = Impossible<Vec<Strand>, Error>;
+	type SerializeMap = Impossible<Vec<Strand>, Error>;
+	type SerializeStruct = Impossible<Vec<Strand>, Error>;
+	type SerializeStructVariant = Impossible<Vec<Strand>, Error>;
+
+	const EXPECTED: &'static str = "a `Vec<Strand>`";
+
+	fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+		Ok(SerializeStrandVec(Vec::with_capacity(len.unwrap_or_default())))
+	}
+
+	#[inline]
+	fn serialize_newtype_struct<T>(
+		self,
+		_name: &'static str,
+		value: &T,
+	) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		value.serialize(self.wrap())
+	}
+}
+
+pub struct SerializeStrandVec(Vec<Strand>);
+
+impl serde::ser::SerializeSeq for SerializeStrandVec {
+	type Ok = Vec<Strand>;
+	type Error = Error;
+
+	fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+	where
+		T: Serialize + ?Sized,
+	{
+		self.0.push(Strand::from(value.serialize(ser::string::Serializer.wrap())?));
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Ok(self.0)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn empty() {
+		let vec: Vec<Strand> = Vec::new();
+		let serialized = vec.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(vec, serialized);
+	}
+
+	#[test]
+	fn vec() {
+		let vec = vec![Strand::default()];
+		let serialized = vec.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(vec, serialized);
+	}
+}
@@ -0,0 +1,55 @@
+use crate::err::Error;
+use crate::sql::value::serde::ser;
+use crate::sql::Strand;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Option<Vec<Strand>>;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Option<Vec<Strand>>, Error>;
+	type SerializeTuple = Impossible<Option<Vec<Strand>>, Error>;
+	type SerializeTupleStruct = Impossible<Option<Vec<Strand>>, Error>;
+	type SerializeTupleVariant = Impossible<Option<Vec<Strand>>, Error>;
+	type SerializeMap = Impossible<Option<Vec<Strand>>, Error>;
+	type SerializeStruct = Impossible<Option<Vec<Strand>>, Error>;
+	type SerializeStructVariant = Impossible<Option<Vec<Strand>>, Error>;
+
+	const EXPECTED: &'static str = "an `Option<Vec<Strand>>`";
+
+	#[inline]
+	fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+		Ok(None)
+	}
+
+	#[inline]
+	fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		Ok(Some(value.serialize(super::Serializer.wrap())?))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ser::Serializer as _;
+
+	#[test]
+	fn none() {
+		let option: Option<Vec<Strand>> = None;
+		let serialized = option.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(option, serialized);
+	}
+
+	#[test]
+	fn some() {
+		let option = Some(vec![Strand::default()]);
+		let serialized = option.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(option, serialized);
+	}
+}
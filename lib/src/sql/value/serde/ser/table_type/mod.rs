@@ -0,0 +1,89 @@
+mod relation;
+
+use crate::err::Error;
+use crate::sql::value::serde::ser;
+use crate::sql::TableType;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub(super) struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = TableType;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<TableType, Error>;
+	type SerializeTuple = Impossible<TableType, Error>;
+	type SerializeTupleStruct = Impossible<TableType, Error>;
+	type SerializeTupleVariant = Impossible<TableType, Error>;
+	type SerializeMap = Impossible<TableType, Error>;
+	type SerializeStruct = Impossible<TableType, Error>;
+	type SerializeStructVariant = Impossible<TableType, Error>;
+
+	const EXPECTED: &'static str = "an enum `TableType`";
+
+	#[inline]
+	fn serialize_unit_variant(
+		self,
+		name: &'static str,
+		_variant_index: u32,
+		variant: &'static str,
+	) -> Result<Self::Ok, Error> {
+		match variant {
+			"Any" => Ok(TableType::Any),
+			"Normal" => Ok(TableType::Normal),
+			variant => Err(Error::custom(format!("unexpected unit variant `{name}::{variant}`"))),
+		}
+	}
+
+	#[inline]
+	fn serialize_newtype_variant<T>(
+		self,
+		name: &'static str,
+		_variant_index: u32,
+		variant: &'static str,
+		value: &T,
+	) -> Result<Self::Ok, Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match variant {
+			"Relation" => Ok(TableType::Relation(value.serialize(relation::Serializer.wrap())?)),
+			variant => {
+				Err(Error::custom(format!("unexpected newtype variant `{name}::{variant}`")))
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sql::{Kind, Relation, Table};
+	use ser::Serializer as _;
+
+	#[test]
+	fn any() {
+		let kind = TableType::Any;
+		let serialized = kind.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(kind, serialized);
+	}
+
+	#[test]
+	fn normal() {
+		let kind = TableType::Normal;
+		let serialized = kind.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(kind, serialized);
+	}
+
+	#[test]
+	fn relation() {
+		let kind = TableType::Relation(Relation {
+			from: Some(Kind::Record(vec![Table::from("user")])),
+			to: None,
+		});
+		let serialized = kind.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(kind, serialized);
+	}
+}
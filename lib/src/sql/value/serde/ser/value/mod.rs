@@ -199,7 +199,7 @@ impl ser::Serializer for Serializer {
 	{
 		match name {
 			sql::strand::TOKEN => {
-				Ok(Value::Strand(Strand(value.serialize(ser::string::Serializer.wrap())?)))
+				Ok(Value::Strand(Strand::from(value.serialize(ser::string::Serializer.wrap())?)))
 			}
 			sql::block::TOKEN => Ok(Value::Block(Box::new(Block(
 				value.serialize(ser::block::entry::vec::Serializer.wrap())?,
@@ -669,7 +669,7 @@ mod tests {
 
 	#[test]
 	fn strand() {
-		let strand = Strand("foobar".to_owned());
+		let strand = Strand::from("foobar");
 		let value = to_value(&strand).unwrap();
 		let expected = Value::Strand(strand);
 		assert_eq!(value, expected);
@@ -677,13 +677,13 @@ mod tests {
 
 		let strand = "foobar".to_owned();
 		let value = to_value(&strand).unwrap();
-		let expected = Value::Strand(Strand(strand));
+		let expected = Value::Strand(Strand::from(strand));
 		assert_eq!(value, expected);
 		assert_eq!(expected, to_value(&expected).unwrap());
 
 		let strand = "foobar";
 		let value = to_value(strand).unwrap();
-		let expected = Value::Strand(Strand(strand.to_owned()));
+		let expected = Value::Strand(Strand::from(strand));
 		assert_eq!(value, expected);
 		assert_eq!(expected, to_value(&expected).unwrap());
 	}
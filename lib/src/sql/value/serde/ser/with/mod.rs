@@ -49,6 +49,10 @@ impl ser::Serializer for Serializer {
 	{
 		match variant {
 			"Index" => Ok(With::Index(value.serialize(ser::string::vec::Serializer.wrap())?)),
+			"Archive" => Ok(With::Archive(value.serialize(opt::Serializer.wrap())?.map(Box::new))),
+			"NoIndexes" => {
+				Ok(With::NoIndexes(value.serialize(ser::string::vec::Serializer.wrap())?))
+			}
 			variant => {
 				Err(Error::custom(format!("unexpected newtype variant `{name}::{variant}`")))
 			}
@@ -75,4 +79,18 @@ mod tests {
 		let serialized = with.serialize(Serializer.wrap()).unwrap();
 		assert_eq!(with, serialized);
 	}
+
+	#[test]
+	fn with_archive() {
+		let with = With::Archive(Some(Box::new(With::NoIndex)));
+		let serialized = with.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(with, serialized);
+	}
+
+	#[test]
+	fn with_no_indexes() {
+		let with = With::NoIndexes(vec!["person.idx".to_string(), "likes.*".to_string()]);
+		let serialized = with.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(with, serialized);
+	}
 }
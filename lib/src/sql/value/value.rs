@@ -18,6 +18,7 @@ use crate::sql::datetime::{datetime, Datetime};
 use crate::sql::duration::{duration, Duration};
 use crate::sql::edges::{edges, Edges};
 use crate::sql::ending::keyword;
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::expression::{unary, Expression};
 use crate::sql::fmt::{Fmt, Pretty};
@@ -35,6 +36,7 @@ use crate::sql::param::{param, Param};
 use crate::sql::part::Part;
 use crate::sql::range::{range, Range};
 use crate::sql::regex::{regex, Regex};
+use crate::sql::spanned;
 use crate::sql::strand::{strand, Strand};
 use crate::sql::subquery::{subquery, Subquery};
 use crate::sql::table::{table, Table};
@@ -46,7 +48,6 @@ use chrono::{DateTime, Utc};
 use derive::Store;
 use geo::Point;
 use nom::branch::alt;
-use nom::bytes::complete::tag_no_case;
 use nom::character::complete::char;
 use nom::combinator::{self, cut, into, opt};
 use nom::multi::separated_list0;
@@ -101,7 +102,7 @@ pub fn selects(i: &str) -> IResult<&str, Values> {
 }
 
 pub fn whats(i: &str) -> IResult<&str, Values> {
-	let (i, v) = separated_list1(commas, what)(i)?;
+	let (i, v) = separated_list1(commas, |i| spanned::record(i, what))(i)?;
 	Ok((i, Values(v)))
 }
 
@@ -1007,7 +1008,7 @@ impl Value {
 	/// Convert this Value into a String
 	pub fn as_string(self) -> String {
 		match self {
-			Value::Strand(v) => v.0,
+			Value::Strand(v) => v.as_string(),
 			Value::Uuid(v) => v.to_raw(),
 			Value::Datetime(v) => v.to_raw(),
 			_ => self.to_string(),
@@ -1017,7 +1018,7 @@ impl Value {
 	/// Converts this Value into an unquoted String
 	pub fn as_raw_string(self) -> String {
 		match self {
-			Value::Strand(v) => v.0,
+			Value::Strand(v) => v.as_string(),
 			Value::Uuid(v) => v.to_raw(),
 			Value::Datetime(v) => v.to_raw(),
 			_ => self.to_string(),
@@ -1031,7 +1032,7 @@ impl Value {
 	/// Converts this Value into an unquoted String
 	pub fn to_raw_string(&self) -> String {
 		match self {
-			Value::Strand(v) => v.0.to_owned(),
+			Value::Strand(v) => v.as_str().to_owned(),
 			Value::Uuid(v) => v.to_raw(),
 			Value::Datetime(v) => v.to_raw(),
 			_ => self.to_string(),
@@ -1103,7 +1104,7 @@ impl Value {
 	/// Treat a string as a table name
 	pub fn could_be_table(self) -> Value {
 		match self {
-			Value::Strand(v) => Table::from(v.0).into(),
+			Value::Strand(v) => Table::from(v.as_string()).into(),
 			_ => self,
 		}
 	}
@@ -2054,7 +2055,7 @@ impl Value {
 			// Bytes are allowed
 			Value::Bytes(v) => Ok(v),
 			// Strings can be converted to bytes
-			Value::Strand(s) => Ok(Bytes(s.0.into_bytes())),
+			Value::Strand(s) => Ok(Bytes(s.as_string().into_bytes())),
 			// Anything else raises an error
 			_ => Err(Error::ConvertTo {
 				from: self,
@@ -2554,7 +2555,10 @@ impl Value {
 			Value::Array(v) => v.iter().any(Value::writeable),
 			Value::Object(v) => v.iter().any(|(_, v)| v.writeable()),
 			Value::Function(v) => {
-				v.is_custom() || v.is_script() || v.args().iter().any(Value::writeable)
+				v.is_custom()
+					|| v.is_script()
+					|| v.is_writeable()
+					|| v.args().iter().any(Value::writeable)
 			}
 			Value::Subquery(v) => v.writeable(),
 			Value::Expression(v) => v.writeable(),
@@ -2705,18 +2709,20 @@ impl TryNeg for Value {
 
 /// Parse any `Value` including expressions
 pub fn value(i: &str) -> IResult<&str, Value> {
-	let (i, start) = single(i)?;
-	if let (i, Some(o)) = opt(operator::binary)(i)? {
-		let (i, r) = cut(value)(i)?;
-		let expr = match r {
-			Value::Expression(r) => r.augment(start, o),
-			_ => Expression::new(start, o, r),
-		};
-		let v = Value::from(expr);
-		Ok((i, v))
-	} else {
-		Ok((i, start))
-	}
+	spanned::record(i, |i| {
+		let (i, start) = spanned::record(i, single)?;
+		if let (i, Some(o)) = opt(operator::binary)(i)? {
+			let (i, r) = cut(value)(i)?;
+			let expr = match r {
+				Value::Expression(r) => r.augment(start, o),
+				_ => Expression::new(start, o, r),
+			};
+			let v = Value::from(expr);
+			Ok((i, v))
+		} else {
+			Ok((i, start))
+		}
+	})
 }
 
 /// Parse any `Value` excluding binary expressions
@@ -2815,24 +2821,26 @@ pub fn function_or_const(i: &str) -> IResult<&str, Value> {
 }
 
 pub fn select(i: &str) -> IResult<&str, Value> {
-	let _diving = crate::sql::parser::depth::dive(i)?;
-	let (i, start) = select_start(i)?;
-	if let (i, Some(op)) = opt(operator::binary)(i)? {
-		// In a binary expression single ident's arent tables but paths.
-		let start = match start {
-			Value::Table(Table(x)) => Value::Idiom(Idiom::from(x)),
-			x => x,
-		};
-		let (i, r) = cut(value)(i)?;
-		let expr = match r {
-			Value::Expression(r) => r.augment(start, op),
-			_ => Expression::new(start, op, r),
-		};
-		let v = Value::from(expr);
-		Ok((i, v))
-	} else {
-		Ok((i, start))
-	}
+	spanned::record(i, |i| {
+		let _diving = crate::sql::parser::depth::dive(i)?;
+		let (i, start) = spanned::record(i, select_start)?;
+		if let (i, Some(op)) = opt(operator::binary)(i)? {
+			// In a binary expression single ident's arent tables but paths.
+			let start = match start {
+				Value::Table(Table(x)) => Value::Idiom(Idiom::from(x)),
+				x => x,
+			};
+			let (i, r) = cut(value)(i)?;
+			let expr = match r {
+				Value::Expression(r) => r.augment(start, op),
+				_ => Expression::new(start, op, r),
+			};
+			let v = Value::from(expr);
+			Ok((i, v))
+		} else {
+			Ok((i, start))
+		}
+	})
 }
 
 /// Used in CREATE, UPDATE, and DELETE clauses
@@ -2983,7 +2991,7 @@ mod tests {
 		assert_eq!(104, std::mem::size_of::<Error>());
 		assert_eq!(104, std::mem::size_of::<Result<Value, Error>>());
 		assert_eq!(24, std::mem::size_of::<crate::sql::number::Number>());
-		assert_eq!(24, std::mem::size_of::<crate::sql::strand::Strand>());
+		assert_eq!(8, std::mem::size_of::<crate::sql::strand::Strand>());
 		assert_eq!(16, std::mem::size_of::<crate::sql::duration::Duration>());
 		assert_eq!(12, std::mem::size_of::<crate::sql::datetime::Datetime>());
 		assert_eq!(24, std::mem::size_of::<crate::sql::array::Array>());
@@ -1,7 +1,7 @@
 use crate::sql::comment::shouldbespace;
 use crate::sql::datetime::{datetime, Datetime};
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
-use nom::bytes::complete::tag_no_case;
 use nom::combinator::cut;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
@@ -1,11 +1,13 @@
 use crate::sql::comment::shouldbespace;
 use crate::sql::cond::{cond, Cond};
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::field::{fields, Fields};
 use crate::sql::group::{group, Groups};
+use crate::sql::special::check_no_window_fields;
 use crate::sql::table::{tables, Tables};
 use nom::branch::alt;
-use nom::bytes::complete::{tag, tag_no_case};
+use nom::bytes::complete::tag;
 use nom::combinator::{cut, opt};
 use nom::sequence::preceded;
 use revision::revisioned;
@@ -42,6 +44,7 @@ pub fn view(i: &str) -> IResult<&str, View> {
 		cut(|i| {
 			let (i, _) = shouldbespace(i)?;
 			let (i, expr) = fields(i)?;
+			check_no_window_fields(i, &expr, "window functions can not be used in a view")?;
 			let (i, _) = shouldbespace(i)?;
 			let (i, _) = expect_tag_no_case("FROM")(i)?;
 			let (i, _) = shouldbespace(i)?;
@@ -113,6 +116,12 @@ mod tests {
 		assert_eq!("AS SELECT temp FROM test WHERE temp != NONE GROUP BY temp", format!("{}", out))
 	}
 
+	#[test]
+	fn view_disallow_window_functions() {
+		let sql = "AS SELECT rank() OVER (ORDER BY temp) AS position FROM test";
+		view(sql).unwrap_err();
+	}
+
 	#[test]
 	fn view_disallow_unbalanced_brackets() {
 		let sql = "AS (SELECT temp FROM test WHERE temp IS NOT NONE GROUP BY temp";
@@ -0,0 +1,226 @@
+use crate::ctx::Context;
+use crate::dbs::{Options, Transaction};
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::sql::builtin::{builtin_name, BuiltinName};
+use crate::sql::comment::{mightbespace, shouldbespace};
+use crate::sql::common::{closeparentheses, commas, openparentheses};
+use crate::sql::error::tag_no_case;
+use crate::sql::error::{IResult, ParseError};
+use crate::sql::function::{builtin_function, Function};
+use crate::sql::idiom::{basic, Idiom, Idioms};
+use crate::sql::order::{order, Orders};
+use crate::sql::value::Value;
+use nom::branch::alt;
+use nom::combinator::{cut, opt, value};
+use nom::multi::separated_list1;
+use nom::sequence::terminated;
+use nom::Err;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// The functions which can only be computed over a window
+const WINDOW_FUNCTIONS: [&str; 5] = ["dense_rank", "lag", "lead", "rank", "row_number"];
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[revisioned(revision = 1)]
+pub struct Window {
+	/// The window function, or the aggregate function
+	pub func: Function,
+	/// The `country` in `OVER (PARTITION BY country)`
+	pub partition: Option<Idioms>,
+	/// The `age` in `OVER (ORDER BY age)`
+	pub order: Option<Orders>,
+}
+
+impl Window {
+	/// Check if this function is a running aggregate function
+	pub(crate) fn is_aggregate(&self) -> bool {
+		self.func.is_aggregate()
+	}
+	/// Check if we require a writeable transaction
+	pub(crate) fn writeable(&self) -> bool {
+		self.func.args().iter().any(Value::writeable)
+	}
+	/// Convert the window function to a field name
+	pub(crate) fn to_idiom(&self) -> Idiom {
+		self.func.to_idiom()
+	}
+	/// Compute the inputs of this window function for a document, as
+	/// the window function is only computed once all of the documents
+	/// of the statement have been processed.
+	pub(crate) async fn inputs(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: &CursorDoc<'_>,
+	) -> Result<Value, Error> {
+		// Compute the function arguments
+		let args = match self.func.args().len() {
+			// If an aggregate has no arguments, then compute the result
+			0 if self.is_aggregate() => self.func.compute(ctx, opt, txn, Some(doc)).await?,
+			// If a paired aggregate, then pass the first two values through
+			n if n > 1 && self.func.is_paired_aggregate() => Value::from(vec![
+				self.func.args()[0].compute(ctx, opt, txn, Some(doc)).await?,
+				self.func.args()[1].compute(ctx, opt, txn, Some(doc)).await?,
+			]),
+			// If an aggregate, then pass the first value through
+			_ if self.is_aggregate() => {
+				self.func.args()[0].compute(ctx, opt, txn, Some(doc)).await?
+			}
+			// Otherwise compute all of the arguments
+			_ => {
+				let mut args = Vec::with_capacity(self.func.args().len());
+				for v in self.func.args() {
+					args.push(v.compute(ctx, opt, txn, Some(doc)).await?);
+				}
+				Value::from(args)
+			}
+		};
+		// Compute the partition of the document
+		let mut partition = Vec::new();
+		for v in self.partition.iter().flat_map(|p| p.iter()) {
+			partition.push(v.compute(ctx, opt, txn, Some(doc)).await?);
+		}
+		// Compute the order of the document within its partition
+		let mut order = Vec::new();
+		for v in self.order.iter().flat_map(|o| o.iter()) {
+			order.push(v.order.compute(ctx, opt, txn, Some(doc)).await?);
+		}
+		Ok(Value::from(vec![args, Value::from(partition), Value::from(order)]))
+	}
+}
+
+impl Display for Window {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		write!(f, "{} OVER (", self.func)?;
+		if let Some(ref v) = self.partition {
+			write!(f, "PARTITION BY {v}")?;
+			if self.order.is_some() {
+				f.write_str(" ")?;
+			}
+		}
+		if let Some(ref v) = self.order {
+			Display::fmt(v, f)?;
+		}
+		f.write_str(")")
+	}
+}
+
+pub fn window(i: &str) -> IResult<&str, Window> {
+	let (i, func) = function(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("OVER")(i)?;
+	// Only window and aggregate functions can be computed over a window
+	if !WINDOW_FUNCTIONS.contains(&func.name()) && !func.is_aggregate() {
+		return Err(Err::Failure(ParseError::Expected {
+			tried: i,
+			expected: "a window function or an aggregate function",
+		}));
+	}
+	cut(|i| {
+		let (i, _) = mightbespace(i)?;
+		let (i, _) = openparentheses(i)?;
+		let (i, partition) = opt(terminated(partition, mightbespace))(i)?;
+		let (i, order) = opt(order)(i)?;
+		let (i, _) = closeparentheses(i)?;
+		Ok((
+			i,
+			Window {
+				func: func.clone(),
+				partition,
+				order,
+			},
+		))
+	})(i)
+}
+
+fn function(i: &str) -> IResult<&str, Function> {
+	alt((
+		|i| {
+			let (i, name) = alt((
+				value("dense_rank", tag_no_case("dense_rank")),
+				value("row_number", tag_no_case("row_number")),
+				value("rank", tag_no_case("rank")),
+				value("lag", tag_no_case("lag")),
+				value("lead", tag_no_case("lead")),
+			))(i)?;
+			builtin_function(name, i)
+		},
+		|i| match builtin_name(i)? {
+			(i, BuiltinName::Function(name)) => builtin_function(name, i),
+			(i, _) => Err(Err::Error(ParseError::Base(i))),
+		},
+	))(i)
+}
+
+fn partition(i: &str) -> IResult<&str, Idioms> {
+	let (i, _) = tag_no_case("PARTITION")(i)?;
+	cut(|i| {
+		let (i, _) = shouldbespace(i)?;
+		let (i, _) = tag_no_case("BY")(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, v) = separated_list1(commas, basic)(i)?;
+		Ok((i, Idioms(v)))
+	})(i)
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn window_row_number() {
+		let sql = "row_number() OVER (PARTITION BY country ORDER BY age DESC)";
+		let res = window(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		assert_eq!(out.func, Function::Normal("row_number".to_owned(), vec![]));
+		assert_eq!(out.to_idiom(), Idiom::from("row_number".to_owned()));
+	}
+
+	#[test]
+	fn window_lag() {
+		let sql = "lag(age, 2, 0) OVER (ORDER BY age)";
+		let res = window(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		assert_eq!(out.func.args().len(), 3);
+		assert!(out.partition.is_none());
+	}
+
+	#[test]
+	fn window_running_aggregate() {
+		let sql = "math::sum(total) OVER (PARTITION BY country, city)";
+		let res = window(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		assert!(out.is_aggregate());
+		assert!(out.order.is_none());
+	}
+
+	#[test]
+	fn window_empty() {
+		let sql = "count() OVER ()";
+		let res = window(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+	}
+
+	#[test]
+	fn window_without_over() {
+		let sql = "count()";
+		let res = window(sql);
+		assert!(matches!(res, Err(Err::Error(_))));
+	}
+
+	#[test]
+	fn window_not_an_aggregate() {
+		let sql = "string::lowercase(name) OVER (ORDER BY age)";
+		let res = window(sql);
+		assert!(matches!(res, Err(Err::Failure(_))));
+	}
+}
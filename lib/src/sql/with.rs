@@ -1,50 +1,162 @@
 use crate::sql::comment::shouldbespace;
-use crate::sql::common::commas;
+use crate::sql::common::{commas, val_char};
+use crate::sql::error::tag_no_case;
 use crate::sql::error::IResult;
 use crate::sql::ident::ident_raw;
-use nom::bytes::complete::tag_no_case;
+use nom::character::complete::{char, satisfy};
+use nom::combinator::{not, opt, peek, recognize, value};
 use nom::multi::separated_list1;
+use nom::sequence::{preceded, terminated, tuple};
 use nom::{branch::alt, combinator::cut};
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter, Result};
 
+/// An index hint is either the name of an index, which applies to every table
+/// of the query, or the name of a table and an index, as in `person.age`.
+/// The index name `*` matches every index of the table.
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
 #[revisioned(revision = 1)]
 pub enum With {
 	NoIndex,
+	/// Only use the listed indexes
 	Index(Vec<String>),
+	/// Also iterate over the archived records of the tables, with any index hints
+	Archive(Option<Box<With>>),
+	/// Never use the listed indexes
+	NoIndexes(Vec<String>),
 }
 
 impl Display for With {
 	fn fmt(&self, f: &mut Formatter) -> Result {
 		f.write_str("WITH")?;
+		self.fmt_options(f)
+	}
+}
+
+impl With {
+	fn fmt_options(&self, f: &mut Formatter) -> Result {
 		match self {
 			With::NoIndex => f.write_str(" NOINDEX"),
 			With::Index(i) => {
 				f.write_str(" INDEX ")?;
 				f.write_str(&i.join(","))
 			}
+			With::Archive(w) => {
+				f.write_str(" ARCHIVE")?;
+				match w {
+					Some(w) => w.fmt_options(f),
+					None => Ok(()),
+				}
+			}
+			With::NoIndexes(i) => {
+				f.write_str(" NOINDEX ")?;
+				f.write_str(&i.join(","))
+			}
+		}
+	}
+
+	/// Returns the index hints of the clause, if any
+	pub(crate) fn index_hints(&self) -> Option<&With> {
+		match self {
+			With::Archive(w) => w.as_deref(),
+			w => Some(w),
 		}
 	}
+
+	/// Returns the hints of a list which apply to the given table.
+	/// Hints which are qualified with another table are skipped.
+	pub(crate) fn hints<'a>(hints: &'a [String], tb: &str) -> Vec<&'a str> {
+		hints
+			.iter()
+			.filter_map(|h| match h.split_once('.') {
+				Some((t, ix)) if t == tb => Some(ix),
+				Some(_) => None,
+				None => Some(h.as_str()),
+			})
+			.collect()
+	}
+
+	/// Checks if an index of a table may be used by the query
+	pub(crate) fn allows(&self, tb: &str, ix: &str) -> bool {
+		match self {
+			With::NoIndex => false,
+			With::Index(hints) => {
+				let hints = Self::hints(hints, tb);
+				hints.is_empty() || hints.iter().any(|h| *h == ix || *h == "*")
+			}
+			With::NoIndexes(hints) => !Self::hints(hints, tb).iter().any(|h| *h == ix || *h == "*"),
+			With::Archive(w) => w.as_ref().map_or(true, |w| w.allows(tb, ix)),
+		}
+	}
+}
+
+fn hint(i: &str) -> IResult<&str, String> {
+	let (i, v) = ident_raw(i)?;
+	let (i, ix) =
+		opt(preceded(char('.'), alt((ident_raw, value(String::from("*"), char('*'))))))(i)?;
+	match ix {
+		Some(ix) => Ok((i, format!("{v}.{ix}"))),
+		None => Ok((i, v)),
+	}
+}
+
+/// Matches the clauses which can follow a WITH clause
+fn clause(i: &str) -> IResult<&str, ()> {
+	let (i, _) = alt((
+		tag_no_case("WHERE"),
+		tag_no_case("SPLIT"),
+		tag_no_case("GROUP"),
+		tag_no_case("ORDER"),
+		tag_no_case("LIMIT"),
+		tag_no_case("START"),
+		tag_no_case("FETCH"),
+		tag_no_case("VERSION"),
+		tag_no_case("TIMEOUT"),
+		tag_no_case("PARALLEL"),
+		tag_no_case("EXPLAIN"),
+	))(i)?;
+	let (i, _) = not(peek(satisfy(val_char)))(i)?;
+	Ok((i, ()))
+}
+
+/// Matches `NOINDEX`, which can also be written as `NO INDEX`
+fn noindex(i: &str) -> IResult<&str, &str> {
+	alt((
+		tag_no_case("NOINDEX"),
+		recognize(tuple((tag_no_case("NO"), shouldbespace, tag_no_case("INDEX")))),
+	))(i)
+}
+
+fn no_indexes(i: &str) -> IResult<&str, With> {
+	let (i, _) = terminated(noindex, shouldbespace)(i)?;
+	let (i, _) = not(peek(clause))(i)?;
+	let (i, v) = separated_list1(commas, hint)(i)?;
+	Ok((i, With::NoIndexes(v)))
 }
 
 fn no_index(i: &str) -> IResult<&str, With> {
-	let (i, _) = tag_no_case("NOINDEX")(i)?;
+	let (i, _) = noindex(i)?;
 	Ok((i, With::NoIndex))
 }
 
 fn index(i: &str) -> IResult<&str, With> {
 	let (i, _) = tag_no_case("INDEX")(i)?;
 	let (i, _) = shouldbespace(i)?;
-	let (i, v) = cut(separated_list1(commas, ident_raw))(i)?;
+	let (i, v) = cut(separated_list1(commas, hint))(i)?;
 	Ok((i, With::Index(v)))
 }
 
+fn archive(i: &str) -> IResult<&str, With> {
+	let (i, _) = tag_no_case("ARCHIVE")(i)?;
+	let (i, v) = opt(preceded(shouldbespace, alt((no_indexes, no_index, index))))(i)?;
+	Ok((i, With::Archive(v.map(Box::new))))
+}
+
 pub fn with(i: &str) -> IResult<&str, With> {
 	let (i, _) = tag_no_case("WITH")(i)?;
 	let (i, _) = shouldbespace(i)?;
-	cut(alt((no_index, index)))(i)
+	cut(alt((no_indexes, no_index, index, archive)))(i)
 }
 
 #[cfg(test)]
@@ -69,4 +181,78 @@ mod tests {
 		assert_eq!(out, With::Index(vec!["idx".to_string(), "uniq".to_string()]));
 		assert_eq!("WITH INDEX idx,uniq", format!("{}", out));
 	}
+
+	#[test]
+	fn with_archive() {
+		let sql = "WITH ARCHIVE";
+		let res = with(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out, With::Archive(None));
+		assert_eq!("WITH ARCHIVE", format!("{}", out));
+	}
+
+	#[test]
+	fn with_archive_index() {
+		let sql = "WITH ARCHIVE INDEX person.idx WHERE";
+		let res = with(sql);
+		let (i, out) = res.unwrap();
+		assert_eq!(out, With::Archive(Some(Box::new(With::Index(vec!["person.idx".to_string()])))));
+		assert_eq!("WITH ARCHIVE INDEX person.idx", format!("{}", out));
+		assert_eq!(i, " WHERE");
+		assert!(out.allows("person", "idx"));
+		assert!(!out.allows("person", "other"));
+		let sql = "WITH ARCHIVE NOINDEX";
+		let res = with(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out, With::Archive(Some(Box::new(With::NoIndex))));
+		assert_eq!("WITH ARCHIVE NOINDEX", format!("{}", out));
+		assert_eq!(out.index_hints(), Some(&With::NoIndex));
+	}
+
+	#[test]
+	fn with_table_index() {
+		let sql = "WITH INDEX person.idx,uniq";
+		let res = with(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out, With::Index(vec!["person.idx".to_string(), "uniq".to_string()]));
+		assert_eq!("WITH INDEX person.idx,uniq", format!("{}", out));
+		assert!(out.allows("person", "idx"));
+		assert!(!out.allows("person", "other"));
+		assert!(out.allows("likes", "uniq"));
+	}
+
+	#[test]
+	fn with_no_indexes() {
+		let sql = "WITH NOINDEX person.idx, likes.*";
+		let res = with(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out, With::NoIndexes(vec!["person.idx".to_string(), "likes.*".to_string()]));
+		assert_eq!("WITH NOINDEX person.idx,likes.*", format!("{}", out));
+		assert!(!out.allows("person", "idx"));
+		assert!(out.allows("person", "other"));
+		assert!(!out.allows("likes", "uniq"));
+	}
+
+	#[test]
+	fn with_no_index_words() {
+		let sql = "WITH NO INDEX";
+		let res = with(sql);
+		let out = res.unwrap().1;
+		assert_eq!(out, With::NoIndex);
+		assert_eq!("WITH NOINDEX", format!("{}", out));
+		let sql = "WITH NO INDEX person.idx WHERE";
+		let res = with(sql);
+		let (i, out) = res.unwrap();
+		assert_eq!(out, With::NoIndexes(vec!["person.idx".to_string()]));
+		assert_eq!(i, " WHERE");
+	}
+
+	#[test]
+	fn with_no_index_before_clause() {
+		let sql = "WITH NOINDEX WHERE";
+		let res = with(sql);
+		let (i, out) = res.unwrap();
+		assert_eq!(out, With::NoIndex);
+		assert_eq!(i, " WHERE");
+	}
 }
@@ -0,0 +1,104 @@
+mod parse;
+use parse::Parse;
+mod helpers;
+use helpers::new_ds;
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::sql::Value;
+
+#[tokio::test]
+async fn alter_statement_table() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person SCHEMALESS;
+		DEFINE FIELD age ON person TYPE number;
+		CREATE person:one SET age = 34, name = 'Tobie';
+		ALTER TABLE person SCHEMAFULL;
+		DEFINE FIELD name ON person TYPE string;
+		ALTER TABLE person SCHEMAFULL COMMENT 'people';
+		CREATE person:two SET age = 20, name = 'Jaime', admin = true;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Found field `name`, with record `person:one`, but the field is not defined on the schemafull table"
+	));
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:two, age: 20, name: 'Jaime' }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn alter_statement_field() -> Result<(), Error> {
+	let sql = "
+		DEFINE FIELD age ON person TYPE number COMMENT 'years' PERMISSIONS FOR select, create, delete FULL, FOR update NONE;
+		CREATE person:one SET age = 34;
+		ALTER FIELD age ON person TYPE string;
+		ALTER FIELD age ON person ASSERT $value >= 40;
+		ALTER FIELD age ON person TYPE int ASSERT $value >= 18;
+		ALTER FIELD name ON person TYPE string;
+		INFO FOR TABLE person;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Found 34 for field `age`, with record `person:one`, but expected a string"
+	));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Found 34 for field `age`, with record `person:one`, but field must conform to: $value >= 40"
+	));
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "The field 'name' does not exist"
+	));
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			events: {},
+			fields: { age: 'DEFINE FIELD age ON person TYPE int ASSERT $value >= 18 COMMENT \\'years\\' PERMISSIONS FOR select, create, delete FULL, FOR update NONE' },
+			tables: {},
+			indexes: {},
+			lives: {},
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
@@ -5,6 +5,7 @@ mod helpers;
 use helpers::new_ds;
 use surrealdb::dbs::Session;
 use surrealdb::err::Error;
+use surrealdb::kvs::{ChangeSet, ConflictPolicy};
 use surrealdb::sql::Value;
 
 #[tokio::test]
@@ -385,3 +386,41 @@ async fn changefeed_with_ts() -> Result<(), Error> {
 	assert_eq!(array.len(), 0);
 	Ok(())
 }
+
+#[tokio::test]
+async fn apply_changes_to_mergeable_table() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person MERGEABLE;
+		CREATE person:test SET name = 'Tobie', age = 30;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let changes = vec![
+		ChangeSet::try_from(Value::parse(
+			"{ versionstamp: 2, changes: [{ update: { id: person:test, name: 'Jaime', age: 31 } }] }",
+		))?,
+		ChangeSet::try_from(Value::parse(
+			"{ versionstamp: 1, changes: [{ update: { id: person:test, name: 'Tobie', city: 'London' } }] }",
+		))?,
+	];
+	dbs.apply_changes(&ses, "test", "test", changes, ConflictPolicy::default()).await?;
+	//
+	let res = &mut dbs.execute("SELECT * FROM person", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: person:test,
+				name: 'Jaime',
+				age: 31,
+				city: 'London',
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
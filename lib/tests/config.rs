@@ -0,0 +1,96 @@
+mod parse;
+use parse::Parse;
+mod helpers;
+use helpers::new_ds;
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::sql::Thing;
+use surrealdb::sql::Value;
+
+#[tokio::test]
+async fn define_config_and_get() -> Result<(), Error> {
+	let sql = "
+		DEFINE CONFIG limits { page: 10 * 5, burst: { size: 3 } } COMMENT 'rate limits';
+		RETURN config::get('limits');
+		RETURN config::get('limits').burst.size;
+		DEFINE FIELD page ON post VALUE config::get('limits').page;
+		CREATE post:one;
+		REMOVE CONFIG limits;
+		RETURN config::get('limits');
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{ page: 50, burst: { size: 3 } }");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("3");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: post:one, page: 50 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "The config 'limits' does not exist"
+	));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_config_permissions() -> Result<(), Error> {
+	let sql = "
+		DEFINE CONFIG webhooks { url: 'https://example.com/hook' } PERMISSIONS NONE;
+		DEFINE CONFIG public { theme: 'dark' } PERMISSIONS WHERE $auth = user:one;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let sql = "
+		RETURN config::get('public').theme;
+		RETURN config::get('webhooks');
+	";
+	let ses = Session::for_scope("test", "test", "test", Thing::from(("user", "one")).into());
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("'dark'");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "You don't have permission to view the webhooks config"
+	));
+	//
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute("RETURN config::get('webhooks').url", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("'https://example.com/hook'");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
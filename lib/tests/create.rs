@@ -9,6 +9,108 @@ use surrealdb::sql::Part;
 use surrealdb::sql::Thing;
 use surrealdb::sql::Value;
 
+#[tokio::test]
+async fn create_with_deterministic_functions() -> Result<(), Error> {
+	let sql = "
+		CREATE test:1, test:2 SET num = rand::int(1, 1000000), uid = rand::uuid();
+		OPTION DETERMINISTIC;
+		CREATE test:3, test:4 SET num = rand::int(1, 1000000), uid = rand::uuid();
+		SELECT VALUE uid FROM test:1, test:2;
+		SELECT VALUE uid FROM test:3, test:4;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let Value::Array(v) = tmp else {
+		panic!("expected an array");
+	};
+	assert_eq!(v.len(), 2);
+	assert_ne!(v[0], v[1]);
+	//
+	let tmp = res.remove(0).result?;
+	let Value::Array(v) = tmp else {
+		panic!("expected an array");
+	};
+	assert_eq!(v.len(), 2);
+	assert_eq!(v[0], v[1]);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn create_with_deterministic_custom_functions() -> Result<(), Error> {
+	let sql = "
+		DEFINE FUNCTION fn::uid() { RETURN rand::uuid(); };
+		LET $max = 1000000;
+		OPTION DETERMINISTIC;
+		CREATE test:1, test:2 SET uid = fn::uid(), num = rand::int(1, $max);
+		SELECT VALUE uid FROM test:1, test:2;
+		SELECT VALUE num FROM test:1, test:2;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	for _ in 0..3 {
+		res.remove(0).result?;
+	}
+	// Custom functions, and functions with parameter arguments, are resolved once
+	for _ in 0..2 {
+		let tmp = res.remove(0).result?;
+		let Value::Array(v) = tmp else {
+			panic!("expected an array");
+		};
+		assert_eq!(v.len(), 2);
+		assert_eq!(v[0], v[1]);
+	}
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn create_with_seeded_random_values() -> Result<(), Error> {
+	let mut out = Vec::new();
+	for seed in [42, 42, 7] {
+		let sql = format!(
+			"
+			SET OPTION SEED = {seed};
+			CREATE person, person SET num = rand::int(1, 1000000), tag = rand::string(8),
+				uid = rand::uuid(), uid4 = rand::uuid::v4();
+		"
+		);
+		let dbs = new_ds().await?;
+		let ses = Session::owner().with_ns("test").with_db("test");
+		let res = &mut dbs.execute(&sql, &ses, None).await?;
+		assert_eq!(res.len(), 2);
+		//
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+		//
+		let tmp = res.remove(0).result?;
+		let Value::Array(v) = tmp else {
+			panic!("expected an array");
+		};
+		assert_eq!(v.len(), 2);
+		assert_ne!(v[0], v[1]);
+		out.push(v);
+	}
+	// The same seed generates the same ids and values
+	assert_eq!(out[0], out[1]);
+	assert_ne!(out[0], out[2]);
+	//
+	Ok(())
+}
+
 #[tokio::test]
 async fn create_with_id() -> Result<(), Error> {
 	let sql = "
@@ -397,6 +499,106 @@ async fn create_with_unique_index_on_two_fields() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn create_with_unique_index_nulls_distinct() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX test ON user FIELDS account, email UNIQUE NULLS DISTINCT;
+		CREATE user:1 SET account = 'Apple', email = NULL;
+		CREATE user:2 SET account = 'Apple', email = NULL;
+		CREATE user:3 SET account = 'Apple';
+		CREATE user:4 SET account = 'Apple', email = 'a@example.com';
+		CREATE user:5 SET account = 'Apple', email = 'a@example.com';
+	";
+
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	//
+	for _ in 0..5 {
+		let _ = res.remove(0).result?;
+	}
+	//
+	let tmp = res.remove(0).result;
+	if let Err(e) = tmp {
+		assert_eq!(e.to_string(), "Database index `test` already contains ['Apple', 'a@example.com'], with record `user:4`");
+	} else {
+		panic!("An error was expected.")
+	}
+	Ok(())
+}
+
+#[tokio::test]
+async fn create_with_unique_index_nulls_not_distinct() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX test ON user FIELDS email UNIQUE NULLS NOT DISTINCT;
+		CREATE user:1 SET email = NULL;
+		CREATE user:2 SET email = NULL;
+		SELECT * FROM user WHERE email = NULL;
+	";
+
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	for _ in 0..2 {
+		let _ = res.remove(0).result?;
+	}
+	//
+	let tmp = res.remove(0).result;
+	if let Err(e) = tmp {
+		assert_eq!(
+			e.to_string(),
+			"Database index `test` already contains NULL, with record `user:1`"
+		);
+	} else {
+		panic!("An error was expected.")
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: user:1, email: NULL }]");
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn create_with_collated_unique_index() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX test ON user FIELDS email UNIQUE COLLATE;
+		CREATE user:1 SET email = 'Tobie@SurrealDB.com';
+		CREATE user:2 SET email = 'tobie@surrealdb.com';
+		CREATE user:3 SET email = 'jaime@surrealdb.com';
+		SELECT id FROM user WHERE email = 'Tobie@SurrealDB.com';
+	";
+
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	for _ in 0..2 {
+		let _ = res.remove(0).result?;
+	}
+	//
+	let tmp = res.remove(0).result;
+	if let Err(e) = tmp {
+		assert_eq!(
+			e.to_string(),
+			"Database index `test` already contains 'tobie@surrealdb.com', with record `user:1`"
+		);
+	} else {
+		panic!("An error was expected.")
+	}
+	//
+	let _ = res.remove(0).result?;
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: user:1 }]");
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
 //
 // Permissions
 //
@@ -9,6 +9,7 @@ use std::collections::HashMap;
 use surrealdb::dbs::Session;
 use surrealdb::err::Error;
 use surrealdb::iam::Role;
+use surrealdb::kvs::{Datastore, DatastoreOptions};
 use surrealdb::sql::Idiom;
 use surrealdb::sql::{Part, Value};
 
@@ -86,11 +87,14 @@ async fn define_statement_function() -> Result<(), Error> {
 		"{
 			analyzers: {},
 			tokens: {},
+			configs: {},
 			functions: { test: 'DEFINE FUNCTION fn::test($first: string, $last: string) { RETURN $first + $last; }' },
 			params: {},
 			scopes: {},
 			params: {},
 			scopes: {},
+			sequences: {},
+			migrations: {},
 			tables: {},
 			users: {},
 		}",
@@ -119,9 +123,12 @@ async fn define_statement_table_drop() -> Result<(), Error> {
 		"{
 			analyzers: {},
 			tokens: {},
+			configs: {},
 			functions: {},
 			params: {},
 			scopes: {},
+			sequences: {},
+			migrations: {},
 			tables: { test: 'DEFINE TABLE test DROP SCHEMALESS' },
 			users: {},
 		}",
@@ -150,9 +157,12 @@ async fn define_statement_table_schemaless() -> Result<(), Error> {
 		"{
 			analyzers: {},
 			tokens: {},
+			configs: {},
 			functions: {},
 			params: {},
 			scopes: {},
+			sequences: {},
+			migrations: {},
 			tables: { test: 'DEFINE TABLE test SCHEMALESS' },
 			users: {},
 		}",
@@ -185,9 +195,12 @@ async fn define_statement_table_schemafull() -> Result<(), Error> {
 		"{
 			analyzers: {},
 			tokens: {},
+			configs: {},
 			functions: {},
 			params: {},
 			scopes: {},
+			sequences: {},
+			migrations: {},
 			tables: { test: 'DEFINE TABLE test SCHEMAFULL' },
 			users: {},
 		}",
@@ -216,9 +229,12 @@ async fn define_statement_table_schemaful() -> Result<(), Error> {
 		"{
 			analyzers: {},
 			tokens: {},
+			configs: {},
 			functions: {},
 			params: {},
 			scopes: {},
+			sequences: {},
+			migrations: {},
 			tables: { test: 'DEFINE TABLE test SCHEMAFULL' },
 			users: {},
 		}",
@@ -228,6 +244,153 @@ async fn define_statement_table_schemaful() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn define_statement_table_expire() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE session SCHEMALESS EXPIRE AFTER 1h;
+		DEFINE EVENT expired ON session WHEN $event = 'DELETE' THEN (CREATE log SET session = $before.id);
+		CREATE session:1, session:2;
+		INFO FOR DB;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			analyzers: {},
+			tokens: {},
+			configs: {},
+			functions: {},
+			params: {},
+			scopes: {},
+			sequences: {},
+			migrations: {},
+			tables: { session: 'DEFINE TABLE session SCHEMALESS EXPIRE AFTER 1h' },
+			users: {},
+		}",
+	);
+	assert_eq!(tmp, val);
+	// The records have not expired yet
+	dbs.expire_records(0).await?;
+	let sql = "SELECT VALUE id FROM session";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::parse("[session:1, session:2]"));
+	// The records have expired
+	dbs.expire_records(u64::MAX / 1000).await?;
+	let sql = "
+		SELECT VALUE id FROM session;
+		SELECT VALUE session FROM log ORDER BY session;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::parse("[]"));
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::parse("[session:1, session:2]"));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_statement_table_archive() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE log SCHEMALESS ARCHIVE WHERE level = 'debug';
+		DEFINE INDEX level ON log FIELDS level;
+		CREATE log:1 SET level = 'debug';
+		CREATE log:2 SET level = 'error';
+		CREATE log:3 SET level = 'debug';
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	for _ in 0..5 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// Move the matching records to the archive
+	dbs.archive_records().await?;
+	let sql = "
+		SELECT VALUE id FROM log;
+		SELECT VALUE id FROM log WHERE level = 'debug';
+		SELECT VALUE id FROM log WITH ARCHIVE ORDER BY id;
+		SELECT VALUE id FROM log WITH ARCHIVE WHERE level = 'debug' ORDER BY id;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::parse("[log:2]"));
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::parse("[]"));
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::parse("[log:1, log:2, log:3]"));
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::parse("[log:1, log:3]"));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_statement_table_storage() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person SCHEMALESS STORAGE SHARDS 4;
+		DEFINE TABLE event SCHEMALESS STORAGE FAMILY hot;
+		CREATE person:1, person:2, person:3, person:4, person:5;
+		CREATE event:1, event:2;
+		DELETE person:3;
+		SELECT VALUE id FROM person;
+		SELECT * FROM event:2;
+		DEFINE TABLE person SCHEMALESS STORAGE FAMILY hot;
+		REMOVE TABLE event;
+		SELECT VALUE id FROM event;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 10);
+	//
+	for _ in 0..5 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[person:1, person:2, person:4, person:5]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: event:2 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "The storage of the table 'person' can't change while it contains records"
+	));
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
 #[tokio::test]
 async fn define_statement_table_foreigntable() -> Result<(), Error> {
 	let sql = "
@@ -255,9 +418,12 @@ async fn define_statement_table_foreigntable() -> Result<(), Error> {
 		"{
 			analyzers: {},
 			tokens: {},
+			configs: {},
 			functions: {},
 			params: {},
 			scopes: {},
+			sequences: {},
+			migrations: {},
 			tables: {
 				test: 'DEFINE TABLE test SCHEMAFULL',
 				view: 'DEFINE TABLE view SCHEMALESS AS SELECT count() FROM test GROUP ALL',
@@ -287,9 +453,12 @@ async fn define_statement_table_foreigntable() -> Result<(), Error> {
 		"{
 			analyzers: {},
 			tokens: {},
+			configs: {},
 			functions: {},
 			params: {},
 			scopes: {},
+			sequences: {},
+			migrations: {},
 			tables: {
 				test: 'DEFINE TABLE test SCHEMAFULL',
 			},
@@ -487,6 +656,47 @@ async fn define_statement_event_when_logic() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn define_statement_event_coalesced_writes() -> Result<(), Error> {
+	let sql = "
+		DEFINE EVENT test ON user WHEN $event = 'CREATE' THEN (
+			UPDATE counter:user SET total += 1;
+			UPDATE counter:user SET total += 1;
+			DELETE counter:temp;
+			CREATE counter:temp SET last = $after.id;
+		);
+		BEGIN TRANSACTION;
+		CREATE user:one, user:two, user:three;
+		SELECT * FROM counter ORDER BY id;
+		COMMIT TRANSACTION;
+		SELECT * FROM counter ORDER BY id;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let val = Value::parse(
+		"[
+			{ id: counter:temp, last: user:three },
+			{ id: counter:user, total: 6 },
+		]",
+	);
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
 #[tokio::test]
 async fn define_statement_field() -> Result<(), Error> {
 	let sql = "
@@ -1171,9 +1381,12 @@ async fn define_statement_analyzer() -> Result<(), Error> {
 				english: 'DEFINE ANALYZER english TOKENIZERS BLANK,CLASS FILTERS LOWERCASE,SNOWBALL(ENGLISH)',
 			},
 			tokens: {},
+			configs: {},
 			functions: {},
 			params: {},
 			scopes: {},
+			sequences: {},
+			migrations: {},
 			tables: {},
 			users: {},
 		}",
@@ -1242,6 +1455,236 @@ async fn define_statement_search_index() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn define_statement_mtree_index() -> Result<(), Error> {
+	let sql = r#"
+		CREATE pts:1 SET point = [1,2,3,4];
+		CREATE pts:2 SET point = [4,5,6,7];
+		DEFINE INDEX mt_pts ON pts FIELDS point MTREE DIMENSION 4 DIST EUCLIDEAN CAPACITY 2;
+		CREATE pts:3 SET point = [8,9,10,11];
+		CREATE pts:4 SET point = [8,9,10,11];
+		UPDATE pts:1 SET point = [2,3,4,5];
+		DELETE pts:2;
+		INFO FOR TABLE pts;
+		CREATE pts:5 SET point = [1,2,3];
+		CREATE pts:6 SET point = 'test';
+		CREATE pts:7;
+		UPDATE pts:7 SET point = NULL;
+		DELETE pts:7;
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 13);
+	//
+	for i in 0..7 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok(), "{}", i);
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			events: {},
+			fields: {},
+			tables: {},
+			indexes: { mt_pts: 'DEFINE INDEX mt_pts ON pts FIELDS point MTREE DIMENSION 4 DIST EUCLIDEAN CAPACITY 2 DOC_IDS_ORDER 100' },
+			lives: {},
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Incorrect vector dimension (3). Expected a vector of 4 dimension."
+	));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "The value cannot be converted to a vector: 'test'"
+	));
+	// Records without a vector are not indexed
+	for i in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok(), "{}", i);
+	}
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_statement_index_concurrently() -> Result<(), Error> {
+	let sql = "
+		CREATE user:1 SET email = 'a@surrealdb.com';
+		CREATE user:2 SET email = 'b@surrealdb.com';
+		DEFINE INDEX test ON user FIELDS email UNIQUE CONCURRENTLY;
+		INFO FOR TABLE user;
+		CREATE user:3 SET email = 'c@surrealdb.com';
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			events: {},
+			fields: {},
+			tables: {},
+			indexes: { test: 'DEFINE INDEX test ON user FIELDS email UNIQUE CONCURRENTLY' },
+			building: { test: { count: 0, status: 'building' } },
+			lives: {},
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	// Build the index in the background
+	dbs.build_indexes().await?;
+	//
+	let sql = "
+		INFO FOR TABLE user;
+		CREATE user:4 SET email = 'c@surrealdb.com';
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			events: {},
+			fields: {},
+			tables: {},
+			indexes: { test: 'DEFINE INDEX test ON user FIELDS email UNIQUE CONCURRENTLY' },
+			lives: {},
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == r#"Database index `test` already contains 'c@surrealdb.com', with record `user:3`"#
+	));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_statement_index_concurrently_in_parallel() -> Result<(), Error> {
+	let opts = DatastoreOptions::new().with_index_workers(4).with_index_batch_size(3);
+	let dbs = Datastore::new_with_options("memory", opts).await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let mut sql: String = (1..=50).map(|i| format!("CREATE user:{i} SET n = {i};")).collect();
+	sql.push_str("DEFINE INDEX test ON user FIELDS n CONCURRENTLY;");
+	let res = dbs.execute(&sql, &ses, None).await?;
+	assert!(res.into_iter().all(|r| r.result.is_ok()));
+	// Build the index in the background
+	dbs.build_indexes().await?;
+	//
+	let sql = "
+		INFO FOR INDEX test ON user;
+		SELECT VALUE id FROM user WHERE n = 25;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp.pick(&[Part::from("count")]), Value::from(50));
+	assert!(tmp.pick(&[Part::from("built")]).is_some());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[user:25]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_statement_index_concurrently_with_writes() -> Result<(), Error> {
+	let sql = "
+		CREATE user:1 SET n = 1;
+		CREATE user:2 SET n = 2;
+		CREATE user:3 SET n = 3;
+		DEFINE INDEX test ON user FIELDS n UNIQUE CONCURRENTLY;
+		UPDATE user:1 SET n = 4;
+		DELETE user:2;
+		CREATE user:5 SET n = 5;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = dbs.execute(sql, &ses, None).await?;
+	assert!(res.into_iter().all(|r| r.result.is_ok()));
+	// Build the index in the background
+	dbs.build_indexes().await?;
+	//
+	let sql = "
+		INFO FOR INDEX test ON user;
+		CREATE user:6 SET n = 4;
+		CREATE user:7 SET n = 1;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	// The records written during the build are only indexed once
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp.pick(&[Part::from("count")]), Value::from(3));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == r#"Database index `test` already contains 4, with record `user:1`"#
+	));
+	// The entries of the previous values are removed
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_statement_index_with_condition() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX test ON user FIELDS email WHERE active = true UNIQUE;
+		CREATE user:1 SET email = 'a@surrealdb.com', active = false;
+		CREATE user:2 SET email = 'a@surrealdb.com', active = true;
+		CREATE user:3 SET email = 'a@surrealdb.com', active = false;
+		CREATE user:4 SET email = 'a@surrealdb.com', active = true;
+		UPDATE user:2 SET active = false;
+		CREATE user:5 SET email = 'a@surrealdb.com', active = true;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..4 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == r#"Database index `test` already contains 'a@surrealdb.com', with record `user:2`"#
+	));
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	Ok(())
+}
+
 #[tokio::test]
 async fn define_statement_user_root() -> Result<(), Error> {
 	let sql = "
@@ -1489,8 +1932,8 @@ async fn permissions_checks_define_function() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: {  }, functions: { greet: \"DEFINE FUNCTION fn::greet() { RETURN 'Hello'; }\" }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ analyzers: {  }, configs: {  }, functions: { greet: \"DEFINE FUNCTION fn::greet() { RETURN 'Hello'; }\" }, migrations: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"],
+		vec!["{ analyzers: {  }, configs: {  }, functions: {  }, migrations: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1531,8 +1974,8 @@ async fn permissions_checks_define_analyzer() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: { analyzer: 'DEFINE ANALYZER analyzer TOKENIZERS BLANK' }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ analyzers: { analyzer: 'DEFINE ANALYZER analyzer TOKENIZERS BLANK' }, configs: {  }, functions: {  }, migrations: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"],
+		vec!["{ analyzers: {  }, configs: {  }, functions: {  }, migrations: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1615,8 +2058,8 @@ async fn permissions_checks_define_token_db() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: { token: \"DEFINE TOKEN token ON DATABASE TYPE HS512 VALUE 'secret'\" }, users: {  } }"],
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ analyzers: {  }, configs: {  }, functions: {  }, migrations: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: { token: \"DEFINE TOKEN token ON DATABASE TYPE HS512 VALUE 'secret'\" }, users: {  } }"],
+		vec!["{ analyzers: {  }, configs: {  }, functions: {  }, migrations: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1741,8 +2184,8 @@ async fn permissions_checks_define_user_db() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: { user: \"DEFINE USER user ON DATABASE PASSHASH 'secret' ROLES VIEWER\" } }"],
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ analyzers: {  }, configs: {  }, functions: {  }, migrations: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: { user: \"DEFINE USER user ON DATABASE PASSHASH 'secret' ROLES VIEWER\" } }"],
+		vec!["{ analyzers: {  }, configs: {  }, functions: {  }, migrations: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1783,8 +2226,8 @@ async fn permissions_checks_define_scope() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: { account: 'DEFINE SCOPE account SESSION 1h' }, tables: {  }, tokens: {  }, users: {  } }"],
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ analyzers: {  }, configs: {  }, functions: {  }, migrations: {  }, params: {  }, scopes: { account: 'DEFINE SCOPE account SESSION 1h' }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"],
+		vec!["{ analyzers: {  }, configs: {  }, functions: {  }, migrations: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1825,8 +2268,8 @@ async fn permissions_checks_define_param() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: {  }, functions: {  }, params: { param: \"DEFINE PARAM $param VALUE 'foo'\" }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ analyzers: {  }, configs: {  }, functions: {  }, migrations: {  }, params: { param: \"DEFINE PARAM $param VALUE 'foo'\" }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"],
+		vec!["{ analyzers: {  }, configs: {  }, functions: {  }, migrations: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1864,8 +2307,8 @@ async fn permissions_checks_define_table() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: { TB: 'DEFINE TABLE TB SCHEMALESS' }, tokens: {  }, users: {  } }"],
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ analyzers: {  }, configs: {  }, functions: {  }, migrations: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: { TB: 'DEFINE TABLE TB SCHEMALESS' }, tokens: {  }, users: {  } }"],
+		vec!["{ analyzers: {  }, configs: {  }, functions: {  }, migrations: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -2021,3 +2464,77 @@ async fn permissions_checks_define_index() {
 	let res = iam_check_cases(test_cases.iter(), &scenario, check_results).await;
 	assert!(res.is_ok(), "{}", res.unwrap_err());
 }
+
+#[tokio::test]
+async fn define_statement_if_not_exists() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE test SCHEMALESS;
+		DEFINE TABLE IF NOT EXISTS test SCHEMAFULL;
+		DEFINE FIELD IF NOT EXISTS name ON test TYPE string;
+		DEFINE FIELD IF NOT EXISTS name ON test TYPE int;
+		INFO FOR TABLE test;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::None);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::None);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			events: {},
+			fields: { name: 'DEFINE FIELD name ON test TYPE string' },
+			tables: {},
+			indexes: {},
+			lives: {},
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_statement_if_not_exists_database_definitions() -> Result<(), Error> {
+	let sql = "
+		DEFINE CONFIG IF NOT EXISTS hooks { retries: 3 };
+		DEFINE CONFIG IF NOT EXISTS hooks { retries: 5 };
+		DEFINE SEQUENCE IF NOT EXISTS seq BATCH 100 START 10;
+		DEFINE SEQUENCE IF NOT EXISTS seq BATCH 100 START 20;
+		DEFINE MIGRATION IF NOT EXISTS 1 { CREATE person:one };
+		DEFINE MIGRATION IF NOT EXISTS 1 { CREATE person:two };
+		INFO FOR DB;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..6 {
+		let tmp = res.remove(0).result?;
+		assert_eq!(tmp, Value::None);
+	}
+	// The definitions are stored without the IF NOT EXISTS clause
+	let tmp = res.remove(0).result?;
+	let val = tmp.pick(&[Part::from("configs"), Part::from("hooks")]);
+	assert_eq!(val, Value::from("DEFINE CONFIG hooks { retries: 3 }"));
+	let val = tmp.pick(&[Part::from("sequences"), Part::from("seq")]);
+	assert_eq!(val, Value::from("DEFINE SEQUENCE seq BATCH 100 START 10"));
+	let val = tmp.pick(&[Part::from("migrations"), Part::from("1")]).as_raw_string();
+	assert!(val.starts_with("DEFINE MIGRATION 1 "), "{val}");
+	assert!(val.contains("person:one"), "{val}");
+	//
+	Ok(())
+}
@@ -2,7 +2,7 @@ mod parse;
 use parse::Parse;
 mod helpers;
 use helpers::new_ds;
-use surrealdb::dbs::Session;
+use surrealdb::dbs::{Session, SessionOptions};
 use surrealdb::err::Error;
 use surrealdb::sql::Thing;
 use surrealdb::sql::Value;
@@ -844,3 +844,486 @@ async fn field_definition_edge_permissions() -> Result<(), Error> {
 	//
 	Ok(())
 }
+
+#[tokio::test]
+async fn field_definition_permissions_across_records() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE post SCHEMALESS PERMISSIONS FOR select WHERE public = true OR author = $auth;
+		DEFINE FIELD draft ON TABLE post PERMISSIONS FOR select WHERE author = $auth;
+		CREATE post:one SET author = user:one, public = false, draft = 'one';
+		CREATE post:two SET author = user:two, public = true, draft = 'two';
+		CREATE post:three SET author = user:one, public = true, draft = 'three';
+		CREATE post:four SET author = user:two, public = false, draft = 'four';
+	";
+	let dbs = new_ds().await?.with_auth_enabled(true);
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	for _ in 0..6 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let sql = "SELECT * FROM post";
+	let ses = Session::for_scope("test", "test", "test", Thing::from(("user", "one")).into());
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 1);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				author: user:one,
+				draft: 'one',
+				id: post:one,
+				public: false,
+			},
+			{
+				author: user:one,
+				draft: 'three',
+				id: post:three,
+				public: true,
+			},
+			{
+				author: user:two,
+				id: post:two,
+				public: true,
+			},
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn field_definition_permissions_debug() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE post SCHEMALESS PERMISSIONS FOR select, update WHERE public = true OR author = $auth;
+		DEFINE FIELD draft ON TABLE post PERMISSIONS FOR update WHERE author = $auth;
+		CREATE post:one SET author = user:two, public = true, draft = 'one';
+		CREATE post:two SET author = user:two, public = false, draft = 'two';
+	";
+	let dbs = new_ds().await?.with_auth_enabled(true);
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	for _ in 0..4 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// Scope users can not enable permission debugging
+	let ses = Session::for_scope("test", "test", "test", Thing::from(("user", "one")).into());
+	let res = &mut dbs.execute("SET OPTION DEBUG_PERMISSIONS = true", &ses, None).await?;
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_err());
+	assert_eq!(ses.options().debug_permissions, None);
+	// Denied records are ignored without permission debugging
+	let res = &mut dbs.execute("SELECT * FROM post:two", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::parse("[]"));
+	// Denials are explained once permission debugging is enabled
+	let mut opts = SessionOptions::default();
+	opts.set("DEBUG_PERMISSIONS", Value::Bool(true))?;
+	let ses = ses.with_options(opts);
+	let res = &mut dbs.execute("SELECT * FROM post:two", &ses, None).await?;
+	let tmp = res.remove(0).result;
+	match tmp {
+		Err(Error::PermissionDenied {
+			target,
+			action,
+			rule,
+			values,
+		}) => {
+			assert_eq!(target, "the `post` table");
+			assert_eq!(action, "select");
+			assert_eq!(rule, "WHERE public = true OR author = $auth");
+			assert!(values.contains("author: user:two"), "{values}");
+			assert!(values.contains("public: false"), "{values}");
+		}
+		v => panic!("expected a permission denial, got {v:?}"),
+	}
+	//
+	let res = &mut dbs.execute("UPDATE post:one SET draft = 'changed'", &ses, None).await?;
+	let tmp = res.remove(0).result;
+	match tmp {
+		Err(Error::PermissionDenied {
+			target,
+			action,
+			rule,
+			..
+		}) => {
+			assert_eq!(target, "the `draft` field");
+			assert_eq!(action, "update");
+			assert_eq!(rule, "WHERE author = $auth");
+		}
+		v => panic!("expected a permission denial, got {v:?}"),
+	}
+	// Denied fields which are left unchanged are not explained
+	let res = &mut dbs.execute("UPDATE post:one SET tag = 'new'", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				author: user:two,
+				draft: 'one',
+				id: post:one,
+				public: true,
+				tag: 'new'
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn field_definition_dedup() -> Result<(), Error> {
+	let sql = "
+		DEFINE FIELD template ON person DEDUP;
+		LET $template = 'A large template which is held by many of the records of the person table';
+		CREATE person:one SET template = $template, name = 'one';
+		CREATE person:two SET template = $template, name = 'two';
+		CREATE person:three SET template = $template, name = 'three';
+		CREATE person:four SET template = 'small', name = 'four';
+		SELECT name, template FROM person ORDER BY name;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..6 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ name: 'four', template: 'small' },
+			{ name: 'one', template: 'A large template which is held by many of the records of the person table' },
+			{ name: 'three', template: 'A large template which is held by many of the records of the person table' },
+			{ name: 'two', template: 'A large template which is held by many of the records of the person table' }
+		]",
+	);
+	assert_eq!(tmp, val);
+	// The large template is only stored once
+	let beg = b"/*test\0*test\0*person\0!cs".to_vec();
+	let end = b"/*test\0*test\0*person\0!ct".to_vec();
+	let mut tx = dbs.transaction(false, false).await?;
+	assert_eq!(tx.scan(beg.clone()..end.clone(), 100).await?.len(), 1);
+	tx.cancel().await?;
+	//
+	let sql = "
+		UPDATE person:one SET name = 'first';
+		UPDATE person:two SET template = 'changed';
+		DELETE person:three;
+		SELECT name, template FROM person ORDER BY name;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ name: 'first', template: 'A large template which is held by many of the records of the person table' },
+			{ name: 'four', template: 'small' },
+			{ name: 'two', template: 'changed' }
+		]",
+	);
+	assert_eq!(tmp, val);
+	// The large template is removed once no records hold it
+	let mut tx = dbs.transaction(false, false).await?;
+	assert_eq!(tx.scan(beg.clone()..end.clone(), 100).await?.len(), 1);
+	tx.cancel().await?;
+	dbs.execute("DELETE person:one", &ses, None).await?;
+	let mut tx = dbs.transaction(false, false).await?;
+	assert_eq!(tx.scan(beg..end, 100).await?.len(), 0);
+	tx.cancel().await?;
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn field_definition_dedup_removed() -> Result<(), Error> {
+	let sql = "
+		DEFINE FIELD template ON person DEDUP;
+		DEFINE FIELD footer ON person DEDUP;
+		LET $template = 'A large template which is held by many of the records of the person table';
+		CREATE person:one SET template = $template, footer = $template;
+		CREATE person:two SET template = $template;
+		DEFINE FIELD footer ON person;
+		SELECT * FROM person ORDER BY id;
+		REMOVE FIELD template ON person;
+		SELECT * FROM person ORDER BY id;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 9);
+	//
+	for _ in 0..6 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// The values of a field which is no longer deduplicated are stored in the records
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				footer: 'A large template which is held by many of the records of the person table',
+				id: person:one,
+				template: 'A large template which is held by many of the records of the person table'
+			},
+			{
+				id: person:two,
+				template: 'A large template which is held by many of the records of the person table'
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	// The template is still deduplicated for the other field
+	let beg = b"/*test\0*test\0*person\0!cr".to_vec();
+	let end = b"/*test\0*test\0*person\0!ct".to_vec();
+	let mut tx = dbs.transaction(false, false).await?;
+	assert_eq!(tx.scan(beg.clone()..end.clone(), 100).await?.len(), 3);
+	tx.cancel().await?;
+	// The values of a removed field are stored in the records
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, val);
+	// The template is removed once no fields are deduplicated
+	let mut tx = dbs.transaction(false, false).await?;
+	assert_eq!(tx.scan(beg..end, 100).await?.len(), 0);
+	tx.cancel().await?;
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn field_definition_reference_actions() -> Result<(), Error> {
+	let sql = "
+		DEFINE FIELD author ON post TYPE record<user> ON DELETE CASCADE;
+		DEFINE FIELD editor ON post TYPE option<record<user>> ON DELETE SET NULL;
+		DEFINE FIELD likes ON post TYPE array<record<user>> ON DELETE SET NULL;
+		DEFINE FIELD owner ON team TYPE record<user> ON DELETE RESTRICT;
+		CREATE user:tobie, user:jaime, user:john;
+		CREATE post:one SET author = user:tobie, editor = user:jaime, likes = [user:jaime, user:john];
+		CREATE post:two SET author = user:jaime, likes = [user:tobie];
+		CREATE team:one SET owner = user:john;
+		DELETE user:jaime;
+		SELECT * FROM post;
+		DELETE user:john;
+		DELETE team:one;
+		DELETE user:john;
+		SELECT * FROM post;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 14);
+	//
+	for _ in 0..9 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// The linking post is deleted, and the links are removed from the other post
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: post:one, author: user:tobie, likes: [user:john] }]");
+	assert_eq!(tmp, val);
+	// The user can not be deleted while a team links to it
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Unable to delete record `user:john`, as it is referenced by the field `owner` of record `team:one`"
+	));
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: post:one, author: user:tobie, likes: [] }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn field_definition_reference_without_record_type() -> Result<(), Error> {
+	let sql = "
+		DEFINE FIELD author ON post TYPE string ON DELETE CASCADE;
+		DEFINE FIELD editor ON post TYPE record<user> ON DELETE SET NULL;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "The ON DELETE clause can only be specified for the field `author` if it has a record type"
+	));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "The ON DELETE SET NULL clause can only be specified for the field `editor` if it has an option or array type"
+	));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn field_definition_reference_existing_records() -> Result<(), Error> {
+	let sql = "
+		DEFINE FIELD author ON post TYPE record<user>;
+		CREATE user:tobie, user:jaime;
+		CREATE post:one SET author = user:tobie;
+		CREATE post:two SET author = user:jaime;
+		DEFINE FIELD author ON post TYPE record<user> ON DELETE RESTRICT;
+		DELETE user:tobie;
+		UPDATE post:two SET author = user:tobie;
+		DELETE user:jaime;
+		SELECT * FROM user;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 9);
+	//
+	for _ in 0..5 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// The links which existed before the clause was added are restricted
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Unable to delete record `user:tobie`, as it is referenced by the field `author` of record `post:one`"
+	));
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: user:tobie }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn field_definition_check_references() -> Result<(), Error> {
+	let sql = "
+		DEFINE FIELD author ON post TYPE option<record<user>>;
+		DEFINE FIELD readers ON post TYPE array<record<user>>;
+		CREATE user:tobie, user:jaime;
+		CREATE post:1 SET author = user:tobie, readers = [user:tobie, user:jaime];
+		CREATE post:2 SET author = user:ghost, readers = [user:jaime, user:ghost];
+		CREATE post:3 SET author = user:ghost, readers = [];
+		CHECK REFERENCES post;
+		CHECK REFERENCES post FIX SET NULL LIMIT 2;
+		CHECK REFERENCES post FIX DELETE START post:2;
+		SELECT * FROM post;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 10);
+	//
+	for _ in 0..6 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			checked: 3,
+			dangling: [
+				{ id: post:2, field: 'author', missing: [user:ghost] },
+				{ id: post:2, field: 'readers', missing: [user:ghost] },
+				{ id: post:3, field: 'author', missing: [user:ghost] },
+			],
+			fixed: false,
+			next: NONE,
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			checked: 2,
+			dangling: [
+				{ id: post:2, field: 'author', missing: [user:ghost] },
+				{ id: post:2, field: 'readers', missing: [user:ghost] },
+			],
+			fixed: true,
+			next: post:2,
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			checked: 1,
+			dangling: [
+				{ id: post:3, field: 'author', missing: [user:ghost] },
+			],
+			fixed: true,
+			next: NONE,
+		}",
+	);
+	assert_eq!(tmp, val);
+	// The dangling links are removed, and the record which linked to a missing record is deleted
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: post:1, author: user:tobie, readers: [user:tobie, user:jaime] },
+			{ id: post:2, readers: [user:jaime] },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn field_definition_check_references_required() -> Result<(), Error> {
+	let sql = "
+		DEFINE FIELD author ON post TYPE record<user>;
+		CREATE post:1 SET author = user:ghost;
+		CHECK REFERENCES post FIX SET NULL;
+		SELECT * FROM post;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// A field which must hold a record link is not emptied
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: post:1, author: user:ghost }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
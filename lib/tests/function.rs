@@ -1333,6 +1333,33 @@ async fn function_count() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn function_count_distinct() -> Result<(), Error> {
+	let sql = r#"
+		RETURN count::distinct([1, 2, 2, 'a', 'a']);
+		RETURN count::distinct([[1, 2], [2, 3], NONE, NULL]);
+		RETURN count::distinct(1);
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(3);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(3);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(1);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
 // --------------------------------------------------
 // crypto
 // --------------------------------------------------
@@ -1962,6 +1989,40 @@ async fn function_parse_geo_centroid() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn function_parse_geo_contains() -> Result<(), Error> {
+	let sql = r#"
+		LET $area = {
+			type: 'Polygon',
+			coordinates: [[
+				[-0.38314819, 51.37692386], [0.1785278, 51.37692386],
+				[0.1785278, 51.61460570], [-0.38314819, 51.61460570],
+				[-0.38314819, 51.37692386]
+			]]
+		};
+		RETURN geo::contains($area, (-0.118092, 51.509865));
+		RETURN geo::contains($area, (-73.971321, 40.776676));
+		RETURN geo::contains($area, 'London');
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	res.remove(0).result?;
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::Bool(true));
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::Bool(false));
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::None);
+	//
+	Ok(())
+}
+
 #[tokio::test]
 async fn function_parse_geo_distance() -> Result<(), Error> {
 	let sql = r#"
@@ -2118,6 +2179,78 @@ async fn function_math_ceil() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn function_math_corr() -> Result<(), Error> {
+	let sql = r#"
+		RETURN math::corr([], []);
+		RETURN math::corr([1, 2, 3], [2, 4, 6]);
+		RETURN math::corr([1, 2, 3, 4], [2, 4, 5, 9]);
+		RETURN math::corr([1, 2], [1]);
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result?;
+	assert!(tmp.is_nan());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(1.0);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(0.9647638212377322);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(
+		matches!(
+			&tmp,
+			Err(e) if e.to_string() == "Incorrect arguments for function math::corr(). The two arrays must have the same length."
+		),
+		"{tmp:?}"
+	);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn function_math_covar() -> Result<(), Error> {
+	let sql = r#"
+		RETURN math::covar([], []);
+		RETURN math::covar([1, 2, 3], [2, 4, 6]);
+		RETURN math::covar([1, 2, 3, 4], [2, 4, 5, 9]);
+		RETURN math::covar([1, 2], [1]);
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result?;
+	assert!(tmp.is_nan());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(2.0);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(3.6666666666666665);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(
+		matches!(
+			&tmp,
+			Err(e) if e.to_string() == "Incorrect arguments for function math::covar(). The two arrays must have the same length."
+		),
+		"{tmp:?}"
+	);
+	//
+	Ok(())
+}
+
 #[tokio::test]
 async fn function_math_fixed() -> Result<(), Error> {
 	let sql = r#"
@@ -2458,6 +2591,78 @@ async fn function_math_product() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn function_math_regr_intercept() -> Result<(), Error> {
+	let sql = r#"
+		RETURN math::regr_intercept([], []);
+		RETURN math::regr_intercept([1, 2, 3], [2, 4, 6]);
+		RETURN math::regr_intercept([1, 2, 3, 4], [2, 4, 5, 9]);
+		RETURN math::regr_intercept([1, 2], [1]);
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result?;
+	assert!(tmp.is_nan());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(0.0);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(-0.5);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(
+		matches!(
+			&tmp,
+			Err(e) if e.to_string() == "Incorrect arguments for function math::regr_intercept(). The two arrays must have the same length."
+		),
+		"{tmp:?}"
+	);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn function_math_regr_slope() -> Result<(), Error> {
+	let sql = r#"
+		RETURN math::regr_slope([], []);
+		RETURN math::regr_slope([1, 2, 3], [2, 4, 6]);
+		RETURN math::regr_slope([1, 2, 3, 4], [2, 4, 5, 9]);
+		RETURN math::regr_slope([1, 2], [1]);
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result?;
+	assert!(tmp.is_nan());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(2.0);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(2.2);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(
+		matches!(
+			&tmp,
+			Err(e) if e.to_string() == "Incorrect arguments for function math::regr_slope(). The two arrays must have the same length."
+		),
+		"{tmp:?}"
+	);
+	//
+	Ok(())
+}
+
 #[tokio::test]
 async fn function_math_round() -> Result<(), Error> {
 	let sql = r#"
@@ -2771,6 +2976,110 @@ async fn function_not() -> Result<(), Error> {
 	Ok(())
 }
 
+// --------------------------------------------------
+// object
+// --------------------------------------------------
+
+#[tokio::test]
+async fn function_object_entries() -> Result<(), Error> {
+	let sql = r#"
+		RETURN object::entries({});
+		RETURN object::entries({ a: 1, b: 'two' });
+		RETURN object::entries('test');
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[['a', 1], ['b', 'two']]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_err());
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn function_object_from_entries() -> Result<(), Error> {
+	let sql = r#"
+		RETURN object::from_entries([]);
+		RETURN object::from_entries([['a', 1], ['b', 'two'], ['a', 3]]);
+		RETURN object::from_entries(object::entries({ x: [1, 2], y: NONE }));
+		RETURN object::from_entries([['a']]);
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{}");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{ a: 3, b: 'two' }");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{ x: [1, 2], y: NONE }");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(
+		matches!(
+			&tmp,
+			Err(e) if e.to_string() == "Incorrect arguments for function object::from_entries(). Every entry must be an array of a key and a value."
+		),
+		"{tmp:?}"
+	);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn function_object_pivot() -> Result<(), Error> {
+	let sql = r#"
+		RETURN object::pivot([], []);
+		RETURN object::pivot(['jan', 'feb', 3], [10, 20, 30]);
+		RETURN object::pivot(['jan', 'jan'], [10, 20]);
+		RETURN object::pivot(['jan'], []);
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{}");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{ jan: 10, feb: 20, '3': 30 }");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{ jan: 20 }");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(
+		matches!(
+			&tmp,
+			Err(e) if e.to_string() == "Incorrect arguments for function object::pivot(). The two arrays must have the same length."
+		),
+		"{tmp:?}"
+	);
+	//
+	Ok(())
+}
+
 // --------------------------------------------------
 // parse
 // --------------------------------------------------
@@ -5338,6 +5647,42 @@ async fn function_type_thing() -> Result<(), Error> {
 	Ok(())
 }
 
+// --------------------------------------------------
+// uuid
+// --------------------------------------------------
+
+#[tokio::test]
+async fn function_uuid_time() -> Result<(), Error> {
+	test_queries(
+		r#"RETURN uuid::time(<uuid> "c232ab00-9414-11ec-b3c8-9f6bdeced846");
+RETURN uuid::time(<uuid> "1ec9414c-232a-6b00-b3c8-9f6bdeced846");
+RETURN uuid::time(<uuid> "017f22e2-79b0-7cc3-98c4-dc0c0c07398f");
+RETURN uuid::time(<uuid> "b19bc00b-aa98-486c-ae37-c8e1c54295b1");
+RETURN uuid::time(rand::uuid::v7()) <= time::now();"#,
+		&[
+			"'2022-02-22T19:22:22Z'",
+			"'2022-02-22T19:22:22Z'",
+			"'2022-02-22T19:22:22Z'",
+			"NONE",
+			"true",
+		],
+	)
+	.await?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn function_uuid_version() -> Result<(), Error> {
+	test_queries(
+		r#"RETURN uuid::version(<uuid> "c232ab00-9414-11ec-b3c8-9f6bdeced846");
+RETURN uuid::version(rand::uuid::v4());
+RETURN uuid::version(rand::uuid::v7());"#,
+		&["1", "4", "7"],
+	)
+	.await?;
+	Ok(())
+}
+
 #[tokio::test]
 async fn function_vector_add() -> Result<(), Error> {
 	test_queries(
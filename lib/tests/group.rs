@@ -4,6 +4,7 @@ mod helpers;
 use helpers::new_ds;
 use surrealdb::dbs::Session;
 use surrealdb::err::Error;
+use surrealdb::kvs::{Datastore, DatastoreOptions};
 use surrealdb::sql::Value;
 
 #[tokio::test]
@@ -460,3 +461,191 @@ async fn select_multi_aggregate_composed() -> Result<(), Error> {
 	//
 	Ok(())
 }
+
+#[tokio::test]
+async fn select_paired_aggregate() -> Result<(), Error> {
+	let sql = "
+		CREATE point:1 SET category = 'a', x = 1, y = 2;
+		CREATE point:2 SET category = 'a', x = 2, y = 4;
+		CREATE point:3 SET category = 'a', x = 3, y = 6;
+		CREATE point:4 SET category = 'b', x = 1, y = 2;
+		CREATE point:5 SET category = 'b', x = 2, y = 4;
+		CREATE point:6 SET category = 'b', x = 3, y = 5;
+		CREATE point:7 SET category = 'b', x = 4, y = 9;
+		SELECT
+			category,
+			math::corr(x, y) AS corr,
+			math::covar(x, y) AS covar,
+			math::regr_slope(x, y) AS slope,
+			math::regr_intercept(x, y) AS intercept
+		FROM point GROUP BY category;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 8);
+	//
+	for _ in 0..7 {
+		let _ = res.remove(0).result?;
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				category: 'a',
+				corr: 1.0,
+				covar: 2.0,
+				slope: 2.0,
+				intercept: 0.0
+			},
+			{
+				category: 'b',
+				corr: 0.9647638212377322,
+				covar: 3.6666666666666665,
+				slope: 2.2,
+				intercept: -0.5
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_pivot_aggregate() -> Result<(), Error> {
+	let sql = "
+		CREATE sale:1 SET region = 'eu', month = 'jan', total = 10;
+		CREATE sale:2 SET region = 'eu', month = 'feb', total = 20;
+		CREATE sale:3 SET region = 'us', month = 'jan', total = 30;
+		CREATE sale:4 SET region = 'us', month = 'mar', total = 40;
+		SELECT region, object::pivot(month, total) AS months FROM sale GROUP BY region;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	for _ in 0..4 {
+		let _ = res.remove(0).result?;
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				region: 'eu',
+				months: { jan: 10, feb: 20 }
+			},
+			{
+				region: 'us',
+				months: { jan: 30, mar: 40 }
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_count_from_index_statistics() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX country ON temperature FIELDS country;
+		DEFINE INDEX tags ON temperature FIELDS tags UNIQUE;
+		CREATE temperature:1 SET country = 'GBP', tags = ['a', 'b'];
+		CREATE temperature:2 SET country = 'GBP', tags = ['c'];
+		CREATE temperature:3 SET country = 'EUR';
+		CREATE temperature:4 SET country = NULL;
+		CREATE temperature:5 SET country = 'USD', tags = ['d'];
+		UPDATE temperature:3 SET country = 'GBP';
+		DELETE temperature:5;
+		SELECT count(), count::distinct(country) AS countries, count::distinct(tags) AS tags FROM temperature GROUP ALL;
+		SELECT count(), count::distinct(country) AS countries, count::distinct(tags) AS tags FROM temperature WHERE true GROUP ALL;
+		SELECT VALUE count() FROM temperature GROUP ALL;
+		DELETE temperature;
+		SELECT count() FROM temperature GROUP ALL;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 13);
+	//
+	for _ in 0..7 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// Answered from the statistics of the indexes
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				count: 4,
+				countries: 1,
+				tags: 3
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	// Answered by scanning the records
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[4]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_group_spilled_to_disk() -> Result<(), Error> {
+	let sql = "
+		CREATE |temperature:1..60| SET country = 'GBP', temp = 1;
+		CREATE |temperature:61..130| SET country = 'USD', temp = 2;
+		CREATE |temperature:131..200| SET country = 'EUR', temp = 3;
+		SELECT country, count() AS total, math::sum(temp) AS sum FROM temperature GROUP BY country;
+	";
+	let opts = DatastoreOptions::new().with_group_memory_limit(1024);
+	let dbs = Datastore::new_with_options("memory", opts).await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// The groups are merged back in order
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				country: 'EUR',
+				sum: 210,
+				total: 70
+			},
+			{
+				country: 'GBP',
+				sum: 60,
+				total: 60
+			},
+			{
+				country: 'USD',
+				sum: 140,
+				total: 70
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
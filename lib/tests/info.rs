@@ -74,18 +74,21 @@ async fn info_for_db() {
         DEFINE FUNCTION fn::greet() {RETURN "Hello";};
         DEFINE PARAM $param VALUE "foo";
         DEFINE ANALYZER analyzer TOKENIZERS BLANK;
+        DEFINE CONFIG config { retries: 3 };
+        DEFINE SEQUENCE sequence;
+        DEFINE MIGRATION 1 { DEFINE TABLE migrated; };
         INFO FOR DB
     "#;
 	let dbs = new_ds().await.unwrap();
 	let ses = Session::owner().with_ns("ns").with_db("db");
 
 	let mut res = dbs.execute(sql, &ses, None).await.unwrap();
-	assert_eq!(res.len(), 8);
+	assert_eq!(res.len(), 11);
 
 	let out = res.pop().unwrap().output();
 	assert!(out.is_ok(), "Unexpected error: {:?}", out);
 
-	let output_regex = Regex::new(r"\{ analyzers: \{ analyzer: .* \}, functions: \{ greet: .* \}, params: \{ param: .* \}, scopes: \{ account: .* \}, tables: \{ TB: .* \}, tokens: \{ token: .* \}, users: \{ user: .* \} \}").unwrap();
+	let output_regex = Regex::new(r"\{ analyzers: \{ analyzer: .* \}, configs: \{ config: .* \}, functions: \{ greet: .* \}, migrations: \{ .* \}, params: \{ param: .* \}, scopes: \{ account: .* \}, sequences: \{ sequence: .* \}, tables: \{ TB: .* \}, tokens: \{ token: .* \}, users: \{ user: .* \} \}").unwrap();
 	let out_str = out.unwrap().to_string();
 	assert!(
 		output_regex.is_match(&out_str),
@@ -152,6 +155,36 @@ async fn info_for_table() {
 	);
 }
 
+#[tokio::test]
+async fn info_for_index() {
+	let sql = r#"
+        CREATE person:1 SET name = 'Tobie';
+        CREATE person:2 SET name = 'Jaime';
+        DEFINE INDEX idx_name ON TABLE person FIELDS name;
+        CREATE person:3 SET name = 'Lizzie';
+        DELETE person:1;
+        INFO FOR INDEX idx_name ON person;
+    "#;
+	let dbs = new_ds().await.unwrap();
+	let ses = Session::owner().with_ns("ns").with_db("db");
+
+	let mut res = dbs.execute(sql, &ses, None).await.unwrap();
+	assert_eq!(res.len(), 6);
+
+	let out = res.pop().unwrap().output();
+	assert!(out.is_ok(), "Unexpected error: {:?}", out);
+
+	let output_regex =
+		Regex::new(r"\{ built: '.*', count: 2, depth: NONE, size: [1-9][0-9]* \}").unwrap();
+	let out_str = out.unwrap().to_string();
+	assert!(
+		output_regex.is_match(&out_str),
+		"Output '{}' doesn't match regex '{}'",
+		out_str,
+		output_regex
+	);
+}
+
 #[tokio::test]
 async fn info_for_user() {
 	let sql = r#"
@@ -312,8 +345,8 @@ async fn permissions_checks_info_db() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
-        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
+        vec!["{ analyzers: {  }, configs: {  }, functions: {  }, migrations: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"],
+        vec!["{ analyzers: {  }, configs: {  }, functions: {  }, migrations: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"],
     ];
 
 	let test_cases = [
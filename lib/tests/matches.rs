@@ -353,3 +353,150 @@ async fn select_where_matches_without_using_index_and_score() -> Result<(), Erro
 	assert_eq!(tmp, val);
 	Ok(())
 }
+
+#[tokio::test]
+async fn select_where_matches_using_index_with_stopwords() -> Result<(), Error> {
+	let sql = r"
+		CREATE blog:1 SET title = 'The-Product-Name is the best';
+		CREATE blog:2 SET title = 'This is the worst';
+		DEFINE ANALYZER simple TOKENIZERS blank FILTERS lowercase,stopwords('the','is');
+		DEFINE INDEX blog_title ON blog FIELDS title SEARCH ANALYZER simple BM25;
+		SELECT id FROM blog WHERE title @@ 'the best';
+		SELECT id FROM blog WHERE title @@ 'the-product-name';
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	//
+	for _ in 0..4 {
+		let _ = res.remove(0).result?;
+	}
+	// The stopwords are neither indexed, nor searched
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: blog:1 }]");
+	assert_eq!(tmp, val);
+	// Terms which are not stopwords are kept as they are
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: blog:1 }]");
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_fuzzy_matches_using_index() -> Result<(), Error> {
+	let sql = r"
+		CREATE blog:1 SET title = 'Hello World!';
+		CREATE blog:2 SET title = 'Bye World!';
+		DEFINE ANALYZER simple TOKENIZERS blank,class FILTERS lowercase;
+		DEFINE INDEX blog_title ON blog FIELDS title SEARCH ANALYZER simple BM25 HIGHLIGHTS;
+		SELECT id, search::highlight('<em>', '</em>', 1) AS title FROM blog WHERE title @1~1@ 'helo';
+		SELECT id FROM blog WHERE title @~2@ 'wrd' ORDER BY id;
+		SELECT id FROM blog WHERE title @1@ 'helo';
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..4 {
+		let _ = res.remove(0).result?;
+	}
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: blog:1, title: '<em>Hello</em> World!' }]");
+	assert_eq!(tmp, val);
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: blog:1 }, { id: blog:2 }]");
+	assert_eq!(tmp, val);
+	// Without an edit distance, only exact terms are matched
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_matches_using_index_with_phrases() -> Result<(), Error> {
+	let sql = r#"
+		CREATE car:1 SET description = 'A red sports car';
+		CREATE car:2 SET description = 'A red car, and a sports bike';
+		CREATE car:3 SET description = 'Sports car, painted red';
+		DEFINE ANALYZER simple TOKENIZERS blank,class FILTERS lowercase;
+		DEFINE INDEX car_description ON car FIELDS description SEARCH ANALYZER simple BM25 HIGHLIGHTS;
+		SELECT id FROM car WHERE description @@ '"red sports car"';
+		SELECT id FROM car WHERE description @@ '"red car"';
+		SELECT id FROM car WHERE description @@ '"red car"~2' ORDER BY id;
+		SELECT id FROM car WHERE description @@ 'painted "sports car"';
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 9);
+	//
+	for _ in 0..5 {
+		let _ = res.remove(0).result?;
+	}
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: car:1 }]");
+	assert_eq!(tmp, val);
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: car:2 }]");
+	assert_eq!(tmp, val);
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: car:1 }, { id: car:2 }]");
+	assert_eq!(tmp, val);
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: car:3 }]");
+	assert_eq!(tmp, val);
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_matches_with_phrases_requires_highlights() -> Result<(), Error> {
+	let sql = r#"
+		CREATE car:1 SET description = 'A red sports car';
+		DEFINE ANALYZER simple TOKENIZERS blank,class FILTERS lowercase;
+		DEFINE INDEX car_description ON car FIELDS description SEARCH ANALYZER simple BM25;
+		SELECT id FROM car WHERE description @@ '"red sports car"';
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	for _ in 0..3 {
+		let _ = res.remove(0).result?;
+	}
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::PhraseSearchNotSupported)));
+	Ok(())
+}
+
+#[tokio::test]
+async fn search_analyze_sample_string() -> Result<(), Error> {
+	let sql = r"
+		DEFINE ANALYZER simple TOKENIZERS blank,class FILTERS lowercase,edgengram(2,3);
+		RETURN search::analyze('simple', 'Hello World!');
+		RETURN search::analyze('unknown', 'Hello World!');
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let _ = res.remove(0).result?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ token: 'he', position: 0, start: 0, end: 5 },
+			{ token: 'hel', position: 0, start: 0, end: 5 },
+			{ token: 'wo', position: 1, start: 6, end: 11 },
+			{ token: 'wor', position: 1, start: 6, end: 11 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::AzNotFound { .. })));
+	Ok(())
+}
@@ -0,0 +1,118 @@
+mod parse;
+use parse::Parse;
+mod helpers;
+use helpers::new_ds;
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::sql::Part;
+use surrealdb::sql::Value;
+
+#[tokio::test]
+async fn apply_migrations_in_order() -> Result<(), Error> {
+	let sql = "
+		DEFINE MIGRATION 2 { DEFINE FIELD name ON person TYPE string; };
+		DEFINE MIGRATION 1 { DEFINE TABLE person SCHEMAFULL; };
+		APPLY MIGRATIONS;
+		APPLY MIGRATIONS;
+		DEFINE MIGRATION 3 { CREATE person:tobie SET name = 'Tobie'; };
+		APPLY MIGRATIONS;
+		SELECT * FROM person;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[1, 2]");
+	assert_eq!(tmp, val);
+	// Applied migrations are not applied again
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[3]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:tobie, name: 'Tobie' }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn apply_migrations_failure_applies_nothing() -> Result<(), Error> {
+	let sql = "
+		DEFINE MIGRATION 1 { DEFINE TABLE person SCHEMAFULL; };
+		DEFINE MIGRATION 2 { THROW 'broken'; };
+		APPLY MIGRATIONS;
+		INFO FOR DB;
+		REMOVE MIGRATION 2;
+		APPLY MIGRATIONS;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string().starts_with("The migration '2' failed: ")
+	));
+	// The changes of the earlier migrations are rolled back
+	let tmp = res.remove(0).result?.pick(&[Part::from("tables")]);
+	let val = Value::parse("{}");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[1]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_applied_migration() -> Result<(), Error> {
+	let sql = "
+		DEFINE MIGRATION 1 { DEFINE TABLE person; };
+		APPLY MIGRATIONS;
+		DEFINE MIGRATION 1 { DEFINE TABLE person; } COMMENT 'unchanged';
+		DEFINE MIGRATION 1 { DEFINE TABLE user; };
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// An applied migration can not be changed
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "The migration '1' has already been applied, and can not be changed"
+	));
+	//
+	Ok(())
+}
@@ -4,6 +4,7 @@ mod helpers;
 use helpers::new_ds;
 use surrealdb::dbs::Session;
 use surrealdb::err::Error;
+use surrealdb::sql::Thing;
 use surrealdb::sql::Value;
 
 #[tokio::test]
@@ -28,9 +29,12 @@ async fn define_global_param() -> Result<(), Error> {
 		"{
 			analyzers: {},
 			tokens: {},
+			configs: {},
 			functions: {},
 			params: { test: 'DEFINE PARAM $test VALUE 12345' },
 			scopes: {},
+			sequences: {},
+			migrations: {},
 			tables: {},
 			users: {},
 		}",
@@ -85,3 +89,285 @@ async fn define_protected_param() -> Result<(), Error> {
 	//
 	Ok(())
 }
+
+#[tokio::test]
+async fn query_metadata_params() -> Result<(), Error> {
+	let sql = "
+		RETURN [$now, time::now(), $now];
+		RETURN $now;
+		RETURN $request_id;
+		RETURN $request_id;
+		RETURN $session.au;
+		LET $now = d'2020-01-01T00:00:00Z';
+		RETURN $now;
+		LET $request_id = 'test';
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 8);
+	//
+	let tmp = res.remove(0).result?;
+	let Value::Array(arr) = tmp else {
+		panic!("expected an array");
+	};
+	assert!(arr[0].is_datetime());
+	assert!(arr[1] >= arr[0]);
+	assert_eq!(arr[0], arr[2]);
+	//
+	let tmp = res.remove(0).result?;
+	assert!(tmp.is_datetime());
+	assert!(tmp >= arr[0]);
+	//
+	let first = res.remove(0).result?;
+	assert!(first.is_uuid());
+	//
+	let second = res.remove(0).result?;
+	assert_eq!(first, second);
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::from("/"));
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::None);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("d'2020-01-01T00:00:00Z'");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == r#"Found 'request_id' but it is not possible to set a variable with this name"#
+	));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_session_param() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res =
+		&mut dbs.execute("SET $tenant = 'acme' FOR SESSION; RETURN $tenant;", &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::from("acme"));
+	// The parameter is kept for the following queries on the session
+	let res = &mut dbs.execute("RETURN $tenant;", &ses.clone(), None).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::from("acme"));
+	// The parameter is not seen by other sessions
+	let other = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute("RETURN $tenant;", &other, None).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::None);
+	// Parameters which are not set for the session are not kept
+	let res = &mut dbs.execute("LET $other = true; RETURN $tenant;", &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	let res = &mut dbs.execute("RETURN $other;", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::None);
+	// The parameter is removed by setting it to NONE
+	let res = &mut dbs.execute("LET $tenant = NONE FOR SESSION;", &ses, None).await?;
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	let res = &mut dbs.execute("RETURN $tenant;", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::None);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_session_param_in_transaction() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	// The parameter is not kept when the transaction is cancelled
+	let sql = "BEGIN; LET $tenant = 'acme' FOR SESSION; CANCEL;";
+	dbs.execute(sql, &ses, None).await?;
+	let res = &mut dbs.execute("RETURN $tenant;", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::None);
+	// The parameter is not kept when the transaction fails
+	let sql = "BEGIN; LET $tenant = 'acme' FOR SESSION; CREATE test:1; CREATE test:1; COMMIT;";
+	dbs.execute(sql, &ses, None).await?;
+	let res = &mut dbs.execute("RETURN $tenant;", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::None);
+	// The parameter is kept once the transaction commits
+	let sql = "BEGIN; LET $tenant = 'acme' FOR SESSION; COMMIT;";
+	dbs.execute(sql, &ses, None).await?;
+	let res = &mut dbs.execute("RETURN $tenant;", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::from("acme"));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_session_param_with_ttl() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute("LET $beta = true FOR SESSION TTL 100ms;", &ses, None).await?;
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let res = &mut dbs.execute("RETURN $beta;", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::Bool(true));
+	// The parameter is removed once it expires
+	tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+	let res = &mut dbs.execute("RETURN $beta;", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::None);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_protected_session_param() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute("LET $session = 'test' FOR SESSION;", &ses, None).await?;
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == r#"Found 'session' but it is not possible to set a variable with this name"#
+	));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_typed_param() -> Result<(), Error> {
+	let sql = "
+		DEFINE PARAM $limit VALUE 10 TYPE int ASSERT $value > 0;
+		RETURN $limit;
+		LET $limit = 'ten';
+		LET $limit = -5;
+		DEFINE PARAM $limit VALUE 0 TYPE int ASSERT $value > 0;
+		LET $limit = 20;
+		RETURN $limit;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("10");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::ParamCheck { .. })));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::ParamValue { .. })));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::ParamValue { .. })));
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("20");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_param_checked_when_resolved() -> Result<(), Error> {
+	let sql = "
+		DEFINE PARAM $min VALUE 0;
+		DEFINE PARAM $total VALUE 2 + 3;
+		DEFINE PARAM $limit VALUE 5 TYPE int ASSERT $value > $min;
+		RETURN $limit;
+		DEFINE PARAM $min VALUE 10;
+		RETURN $limit;
+		INFO FOR DB;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("5");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	// The ASSERT clause is checked each time the param is used
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::ParamValue { .. })));
+	// The value is computed when the param is defined
+	let tmp = res.remove(0).result?;
+	let val = tmp.pick(&["params".into(), "total".into()]);
+	assert_eq!(val, Value::from("DEFINE PARAM $total VALUE 5"));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_param_permissions() -> Result<(), Error> {
+	let sql = "
+		DEFINE PARAM $theme VALUE 'dark' PERMISSIONS FOR read FULL, FOR change NONE;
+		DEFINE PARAM $secret VALUE 'hidden' PERMISSIONS NONE;
+		DEFINE PARAM $page VALUE 1 PERMISSIONS FOR change WHERE $value < 10;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let sql = "
+		RETURN $theme;
+		LET $theme = 'light';
+		RETURN $secret;
+		LET $page = 5;
+		LET $page = 50;
+	";
+	let ses = Session::for_scope("test", "test", "test", Thing::from(("user", "one")).into());
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("'dark'");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "You don't have permission to change the $theme parameter"
+	));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::ParamPermissions { .. })));
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::ParamChangePermissions { .. })));
+	//
+	Ok(())
+}
@@ -4,7 +4,7 @@ mod helpers;
 use helpers::new_ds;
 use surrealdb::dbs::{Response, Session};
 use surrealdb::err::Error;
-use surrealdb::sql::Value;
+use surrealdb::sql::{Part, Value};
 
 #[tokio::test]
 async fn select_where_iterate_three_multi_index() -> Result<(), Error> {
@@ -49,7 +49,7 @@ async fn select_where_iterate_three_multi_index_with_one_ft_index() -> Result<()
 	let mut res = execute_test(&three_multi_index_query("WITH INDEX ft_company", ""), 12).await?;
 	// OR results
 	check_result(&mut res, "[{ name: 'Jaime' }, { name: 'Lizzie' }, { name: 'Tobie' } ]")?;
-	check_result(&mut res, THREE_TABLE_EXPLAIN)?;
+	check_result(&mut res, &table_explain_ignored_hint(3, "ft_company"))?;
 	// AND results
 	check_result(&mut res, "[{name: 'Jaime'}]")?;
 	check_result(&mut res, SINGLE_INDEX_FT_EXPLAIN)?;
@@ -61,7 +61,7 @@ async fn select_where_iterate_three_multi_index_with_one_index() -> Result<(), E
 	let mut res = execute_test(&three_multi_index_query("WITH INDEX uniq_name", ""), 12).await?;
 	// OR results
 	check_result(&mut res, "[{ name: 'Jaime' }, { name: 'Lizzie' }, { name: 'Tobie' } ]")?;
-	check_result(&mut res, THREE_TABLE_EXPLAIN)?;
+	check_result(&mut res, &table_explain_ignored_hint(3, "uniq_name"))?;
 	// AND results
 	check_result(&mut res, "[{name: 'Jaime'}]")?;
 	check_result(&mut res, SINGLE_INDEX_UNIQ_EXPLAIN)?;
@@ -85,7 +85,7 @@ async fn select_where_iterate_two_multi_index_with_one_index() -> Result<(), Err
 	let mut res = execute_test(&two_multi_index_query("WITH INDEX idx_genre", ""), 9).await?;
 	// OR results
 	check_result(&mut res, "[{ name: 'Jaime' }, { name: 'Tobie' }]")?;
-	check_result(&mut res, &table_explain(2))?;
+	check_result(&mut res, &table_explain_ignored_hint(2, "idx_genre"))?;
 	// AND results
 	check_result(&mut res, "[{name: 'Jaime'}]")?;
 	check_result(&mut res, SINGLE_INDEX_IDX_EXPLAIN)?;
@@ -105,6 +105,44 @@ async fn select_where_iterate_two_multi_index_with_two_index() -> Result<(), Err
 	Ok(())
 }
 
+#[tokio::test]
+async fn select_where_iterate_two_multi_index_with_table_index() -> Result<(), Error> {
+	let mut res =
+		execute_test(&two_multi_index_query("WITH INDEX person.idx_genre", ""), 9).await?;
+	// OR results
+	check_result(&mut res, "[{ name: 'Jaime' }, { name: 'Tobie' }]")?;
+	check_result(&mut res, &table_explain_ignored_hint(2, "idx_genre"))?;
+	// AND results
+	check_result(&mut res, "[{name: 'Jaime'}]")?;
+	check_result(&mut res, SINGLE_INDEX_IDX_EXPLAIN)?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_iterate_two_multi_index_with_other_table_index() -> Result<(), Error> {
+	let mut res = execute_test(&two_multi_index_query("WITH INDEX other.idx_genre", ""), 9).await?;
+	// OR results
+	check_result(&mut res, "[{ name: 'Jaime' }, { name: 'Tobie' }]")?;
+	check_result(&mut res, TWO_MULTI_INDEX_EXPLAIN)?;
+	// AND results
+	check_result(&mut res, "[{name: 'Jaime'}]")?;
+	check_result(&mut res, SINGLE_INDEX_IDX_EXPLAIN)?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_iterate_two_multi_index_without_one_index() -> Result<(), Error> {
+	let mut res =
+		execute_test(&two_multi_index_query("WITH NOINDEX person.idx_genre", ""), 9).await?;
+	// OR results
+	check_result(&mut res, "[{ name: 'Jaime' }, { name: 'Tobie' }]")?;
+	check_result(&mut res, &table_explain(2))?;
+	// AND results
+	check_result(&mut res, "[{name: 'Jaime'}]")?;
+	check_result(&mut res, SINGLE_INDEX_UNIQ_EXPLAIN)?;
+	Ok(())
+}
+
 #[tokio::test]
 async fn select_where_iterate_two_no_index() -> Result<(), Error> {
 	let mut res = execute_test(&two_multi_index_query("WITH NOINDEX", ""), 9).await?;
@@ -117,6 +155,18 @@ async fn select_where_iterate_two_no_index() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn select_where_iterate_two_no_index_words() -> Result<(), Error> {
+	let mut res = execute_test(&two_multi_index_query("WITH NO INDEX", ""), 9).await?;
+	// OR results
+	check_result(&mut res, "[{ name: 'Jaime' }, { name: 'Tobie' }]")?;
+	check_result(&mut res, &table_explain_no_index(2))?;
+	// AND results
+	check_result(&mut res, "[{name: 'Jaime'}]")?;
+	check_result(&mut res, &table_explain_no_index(1))?;
+	Ok(())
+}
+
 async fn execute_test(sql: &str, expected_result: usize) -> Result<Vec<Response>, Error> {
 	let dbs = new_ds().await?;
 	let ses = Session::owner().with_ns("test").with_db("test");
@@ -185,6 +235,31 @@ fn table_explain(fetch_count: usize) -> String {
 	)
 }
 
+fn table_explain_ignored_hint(fetch_count: usize, hint: &str) -> String {
+	format!(
+		"[
+			{{
+				detail: {{
+					table: 'person'
+				}},
+				operation: 'Iterate Table'
+			}},
+			{{
+				detail: {{
+					reason: \"Index hint '{hint}' ignored on table 'person': the condition can not be resolved with this index\"
+				}},
+				operation: 'Fallback'
+			}},
+			{{
+				detail: {{
+					count: {fetch_count}
+				}},
+				operation: 'Fetch'
+			}}
+		]"
+	)
+}
+
 fn table_explain_no_index(fetch_count: usize) -> String {
 	format!(
 		"[
@@ -210,24 +285,13 @@ fn table_explain_no_index(fetch_count: usize) -> String {
 	)
 }
 
-const THREE_TABLE_EXPLAIN: &str = "[
-	{
-		detail: {
-			table: 'person'
-		},
-		operation: 'Iterate Table'
-	},
-	{
-		detail: {
-			count: 3
-		},
-		operation: 'Fetch'
-	}
-]";
-
 const THREE_MULTI_INDEX_EXPLAIN: &str = "[
 				{
 					detail: {
+						estimate: {
+							rows: 1,
+							cost: 2
+						},
 						plan: {
 							index: 'uniq_name',
 							operator: '=',
@@ -239,6 +303,10 @@ const THREE_MULTI_INDEX_EXPLAIN: &str = "[
 				},
                 {
 					detail: {
+						estimate: {
+							rows: 2,
+							cost: 4
+						},
 						plan: {
 							index: 'idx_genre',
 							operator: '=',
@@ -290,6 +358,10 @@ const SINGLE_INDEX_FT_EXPLAIN: &str = "[
 const SINGLE_INDEX_UNIQ_EXPLAIN: &str = "[
 				{
 					detail: {
+						estimate: {
+							rows: 1,
+							cost: 2
+						},
 						plan: {
 							index: 'uniq_name',
 							operator: '=',
@@ -310,6 +382,10 @@ const SINGLE_INDEX_UNIQ_EXPLAIN: &str = "[
 const SINGLE_INDEX_IDX_EXPLAIN: &str = "[
 	{
 		detail: {
+			estimate: {
+				rows: 2,
+				cost: 4
+			},
 			plan: {
 				index: 'idx_genre',
 				operator: '=',
@@ -330,6 +406,10 @@ const SINGLE_INDEX_IDX_EXPLAIN: &str = "[
 const TWO_MULTI_INDEX_EXPLAIN: &str = "[
 				{
 					detail: {
+						estimate: {
+							rows: 1,
+							cost: 2
+						},
 						plan: {
 							index: 'uniq_name',
 							operator: '=',
@@ -341,6 +421,10 @@ const TWO_MULTI_INDEX_EXPLAIN: &str = "[
 				},
                 {
 					detail: {
+						estimate: {
+							rows: 2,
+							cost: 4
+						},
 						plan: {
 							index: 'idx_genre',
 							operator: '=',
@@ -414,3 +498,626 @@ async fn select_unsupported_unary_operator() -> Result<(), Error> {
 	assert_eq!(format!("{:#}", tmp), format!("{:#}", val));
 	Ok(())
 }
+
+#[tokio::test]
+async fn select_where_partial_index() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie SET name = 'Tobie', active = true;
+		CREATE person:jaime SET name = 'Jaime', active = false;
+		DEFINE INDEX idx_name ON TABLE person COLUMNS name WHERE active = true;
+		SELECT name FROM person WHERE name = 'Jaime';
+		SELECT name FROM person WHERE name = 'Jaime' EXPLAIN;
+		SELECT name FROM person WHERE name = 'Tobie' AND active = true;
+		SELECT name FROM person WHERE name = 'Tobie' AND active = true EXPLAIN;
+	";
+	let mut res = execute_test(sql, 7).await?;
+	//
+	check_result(&mut res, "[{ name: 'Jaime' }]")?;
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					table: 'person'
+				},
+				operation: 'Iterate Table'
+			},
+			{
+				detail: {
+					reason: 'NO INDEX FOUND'
+				},
+				operation: 'Fallback'
+			}
+		]",
+	)?;
+	//
+	check_result(&mut res, "[{ name: 'Tobie' }]")?;
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					estimate: {
+						rows: 1,
+						cost: 2
+					},
+					plan: {
+						index: 'idx_name',
+						operator: '=',
+						value: 'Tobie'
+					},
+					table: 'person',
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	)?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_expression_index() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie SET name = 'Tobie';
+		CREATE person:jaime SET name = 'Jaime';
+		DEFINE INDEX idx_name ON TABLE person COLUMNS string::lowercase(name);
+		SELECT name FROM person WHERE string::lowercase(name) = 'tobie';
+		SELECT name FROM person WHERE string::lowercase(name) = 'tobie' EXPLAIN;
+		SELECT name FROM person WHERE string::uppercase(name) = 'TOBIE';
+		SELECT name FROM person WHERE string::uppercase(name) = 'TOBIE' EXPLAIN;
+	";
+	let mut res = execute_test(sql, 7).await?;
+	//
+	check_result(&mut res, "[{ name: 'Tobie' }]")?;
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					estimate: {
+						rows: 1,
+						cost: 2
+					},
+					plan: {
+						index: 'idx_name',
+						operator: '=',
+						value: 'tobie'
+					},
+					table: 'person',
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	)?;
+	//
+	check_result(&mut res, "[{ name: 'Tobie' }]")?;
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					table: 'person'
+				},
+				operation: 'Iterate Table'
+			},
+			{
+				detail: {
+					reason: 'Unsupported value: string::uppercase(name)'
+				},
+				operation: 'Fallback'
+			}
+		]",
+	)?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_computed_expression_index() -> Result<(), Error> {
+	let sql = "
+		CREATE item:1 SET price = 5, quantity = 2;
+		CREATE item:2 SET price = 3, quantity = 3;
+		DEFINE INDEX idx_total ON TABLE item COLUMNS price * quantity;
+		SELECT id FROM item WHERE price * quantity = 10;
+		SELECT id FROM item WHERE price * quantity = 10 EXPLAIN;
+		SELECT id FROM item WHERE (price * quantity) = 9;
+		SELECT id FROM item WHERE (price * quantity) = 9 EXPLAIN;
+	";
+	let mut res = execute_test(sql, 7).await?;
+	//
+	check_result(&mut res, "[{ id: item:1 }]")?;
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					plan: {
+						index: 'idx_total',
+						operator: '=',
+						value: 10
+					},
+					table: 'item',
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	)?;
+	//
+	check_result(&mut res, "[{ id: item:2 }]")?;
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					plan: {
+						index: 'idx_total',
+						operator: '=',
+						value: 9
+					},
+					table: 'item',
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	)?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_covering_index() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie SET name = 'Tobie', genre = 'm';
+		CREATE person:jaime SET name = 'Jaime', genre = 'm';
+		DEFINE INDEX idx_name ON TABLE person COLUMNS name;
+		SELECT id, name FROM person WHERE name = 'Tobie';
+		SELECT id, name FROM person WHERE name = 'Tobie' EXPLAIN;
+		SELECT name, genre FROM person WHERE name = 'Tobie';
+		SELECT name, genre FROM person WHERE name = 'Tobie' EXPLAIN;
+	";
+	let mut res = execute_test(sql, 7).await?;
+	//
+	check_result(&mut res, "[{ id: person:tobie, name: 'Tobie' }]")?;
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					estimate: {
+						rows: 1,
+						cost: 1
+					},
+					plan: {
+						covering: true,
+						index: 'idx_name',
+						operator: '=',
+						value: 'Tobie'
+					},
+					table: 'person',
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	)?;
+	//
+	check_result(&mut res, "[{ name: 'Tobie', genre: 'm' }]")?;
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					estimate: {
+						rows: 1,
+						cost: 2
+					},
+					plan: {
+						index: 'idx_name',
+						operator: '=',
+						value: 'Tobie'
+					},
+					table: 'person',
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	)?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_knn_hnsw_index() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX hnsw_pts ON TABLE pts FIELDS point HNSW DIMENSION 4 DIST EUCLIDEAN M 4;
+		CREATE pts:1 SET point = [1, 2, 3, 4];
+		CREATE pts:2 SET point = [4, 5, 6, 7];
+		CREATE pts:3 SET point = [8, 9, 10, 11];
+		CREATE pts:4;
+		SELECT id FROM pts WHERE point <|2|> [2, 3, 4, 5];
+		SELECT id FROM pts WHERE point <|2|> [2, 3, 4, 5] EXPLAIN;
+		DELETE pts:1;
+		SELECT id FROM pts WHERE point <|2|> [2, 3, 4, 5];
+	";
+	let mut res = execute_test(sql, 9).await?;
+	//
+	check_result(&mut res, "[{ id: pts:1 }, { id: pts:2 }]")?;
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					estimate: {
+						rows: 2,
+						cost: 4
+					},
+					plan: {
+						index: 'hnsw_pts',
+						operator: '<|2|>',
+						value: [2, 3, 4, 5]
+					},
+					table: 'pts',
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	)?;
+	check_result(&mut res, "[]")?;
+	check_result(&mut res, "[{ id: pts:2 }, { id: pts:3 }]")?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_geo_rtree_index() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX rtree_location ON TABLE city FIELDS location RTREE CAPACITY 4;
+		CREATE city:london SET location = (-0.118092, 51.509865);
+		CREATE city:paris SET location = (2.352222, 48.856614);
+		CREATE city:new_york SET location = (-73.935242, 40.730610);
+		LET $europe = {
+			type: 'Polygon',
+			coordinates: [[[-10.0, 35.0], [30.0, 35.0], [30.0, 60.0], [-10.0, 60.0], [-10.0, 35.0]]]
+		};
+		SELECT id FROM city WHERE geo::distance(location, (-0.118092, 51.509865)) < 400000;
+		SELECT id FROM city WHERE geo::distance(location, (-0.118092, 51.509865)) < 400000 EXPLAIN;
+		SELECT id FROM city WHERE geo::contains($europe, location);
+		SELECT id FROM city WHERE geo::contains($europe, location) EXPLAIN;
+	";
+	let mut res = execute_test(sql, 9).await?;
+	//
+	check_result(&mut res, "[{ id: city:london }, { id: city:paris }]")?;
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					plan: {
+						index: 'rtree_location',
+						operator: '<',
+						value: [(-0.118092, 51.509865), 400000]
+					},
+					table: 'city',
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	)?;
+	check_result(&mut res, "[{ id: city:london }, { id: city:paris }]")?;
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					plan: {
+						index: 'rtree_location',
+						operator: 'INSIDE',
+						value: {
+							type: 'Polygon',
+							coordinates: [[[-10.0, 35.0], [30.0, 35.0], [30.0, 60.0], [-10.0, 60.0], [-10.0, 35.0]]]
+						}
+					},
+					table: 'city',
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	)?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_where_composite_index_prefix() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie SET first = 'Tobie', last = 'Hitchcock', age = 30;
+		CREATE person:jaime SET first = 'Jaime', last = 'Hitchcock', age = 25;
+		CREATE person:john SET first = 'John', last = 'Doe', age = 30;
+		DEFINE INDEX idx_name ON TABLE person COLUMNS last, first, age;
+		SELECT id FROM person WHERE last = 'Hitchcock';
+		SELECT id FROM person WHERE last = 'Hitchcock' EXPLAIN;
+		SELECT id FROM person WHERE first = 'Tobie' AND last = 'Hitchcock';
+		SELECT id FROM person WHERE first = 'Tobie' AND last = 'Hitchcock' EXPLAIN;
+		SELECT id FROM person WHERE last = 'Hitchcock' AND age = 25;
+		SELECT id FROM person WHERE first = 'Tobie';
+		SELECT id FROM person WHERE first = 'Tobie' EXPLAIN;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let mut res = dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 11);
+	for _ in 0..4 {
+		let _ = res.remove(0).result?;
+	}
+	//
+	check_result(&mut res, "[{ id: person:jaime }, { id: person:tobie }]")?;
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					plan: {
+						index: 'idx_name',
+						operator: '=',
+						prefix: true,
+						value: 'Hitchcock'
+					},
+					table: 'person',
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	)?;
+	//
+	check_result(&mut res, "[{ id: person:tobie }]")?;
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					plan: {
+						index: 'idx_name',
+						operator: '=',
+						prefix: true,
+						value: ['Hitchcock', 'Tobie']
+					},
+					table: 'person',
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	)?;
+	// The unmatched columns are filtered after the scan
+	check_result(&mut res, "[{ id: person:jaime }]")?;
+	// The first column of the index is not constrained
+	check_result(&mut res, "[{ id: person:tobie }]")?;
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					table: 'person'
+				},
+				operation: 'Iterate Table'
+			},
+			{
+				detail: {
+					reason: 'NO INDEX FOUND'
+				},
+				operation: 'Fallback'
+			}
+		]",
+	)?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_order_by_index() -> Result<(), Error> {
+	let sql = "
+		DEFINE FIELD age ON TABLE person TYPE int;
+		DEFINE INDEX idx_age ON TABLE person COLUMNS age;
+		CREATE person:tobie SET name = 'Tobie', age = 30;
+		CREATE person:jaime SET name = 'Jaime', age = 25;
+		CREATE person:john SET name = 'John', age = 40;
+		SELECT name, age FROM person ORDER BY age LIMIT 2;
+		SELECT name, age FROM person ORDER BY age LIMIT 2 EXPLAIN;
+		SELECT name, age FROM person WHERE name != 'John' ORDER BY age DESC;
+		SELECT name, age FROM person WHERE name != 'John' ORDER BY age DESC EXPLAIN;
+		SELECT name FROM person ORDER BY name;
+		SELECT name FROM person ORDER BY name EXPLAIN;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let mut res = dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 11);
+	for _ in 0..5 {
+		let _ = res.remove(0).result?;
+	}
+	//
+	check_result(&mut res, "[{ name: 'Jaime', age: 25 }, { name: 'Tobie', age: 30 }]")?;
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					estimate: {
+						rows: 3,
+						cost: 6
+					},
+					plan: {
+						index: 'idx_age',
+						order: 'ASC'
+					},
+					table: 'person',
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	)?;
+	// The descending order is read backwards from the index
+	check_result(&mut res, "[{ name: 'Tobie', age: 30 }, { name: 'Jaime', age: 25 }]")?;
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					estimate: {
+						rows: 3,
+						cost: 6
+					},
+					plan: {
+						index: 'idx_age',
+						order: 'DESC'
+					},
+					table: 'person',
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	)?;
+	// Fields without an index are sorted in memory
+	check_result(&mut res, "[{ name: 'Jaime' }, { name: 'John' }, { name: 'Tobie' }]")?;
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					table: 'person'
+				},
+				operation: 'Iterate Table'
+			}
+		]",
+	)?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_order_by_unique_index_with_optional_field() -> Result<(), Error> {
+	let sql = "
+		DEFINE FIELD email ON TABLE person TYPE option<string>;
+		DEFINE INDEX uniq_email ON TABLE person COLUMNS email UNIQUE;
+		CREATE person:tobie SET email = 'tobie@surrealdb.com';
+		CREATE person:jaime;
+		SELECT VALUE id FROM person ORDER BY email;
+		SELECT VALUE id FROM person ORDER BY email EXPLAIN;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let mut res = dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	for _ in 0..4 {
+		let _ = res.remove(0).result?;
+	}
+	// The records without a value are not in the unique index
+	check_result(&mut res, "[person:jaime, person:tobie]")?;
+	check_result(
+		&mut res,
+		"[
+			{
+				detail: {
+					table: 'person'
+				},
+				operation: 'Iterate Table'
+			}
+		]",
+	)?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_explain_analyze() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX idx_genre ON TABLE person COLUMNS genre;
+		CREATE person:tobie SET name = 'Tobie', genre = 'm';
+		CREATE person:jaime SET name = 'Jaime', genre = 'm';
+		CREATE person:lizzie SET name = 'Lizzie', genre = 'f';
+		SELECT name FROM person WHERE genre = 'm' EXPLAIN ANALYZE;
+		SELECT name FROM person EXPLAIN ANALYZE;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let mut res = dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	for _ in 0..4 {
+		let _ = res.remove(0).result?;
+	}
+	// The elapsed time of each iterator is measured
+	let elapsed: [Part; 4] = [0_usize.into(), "detail".into(), "actual".into(), "elapsed".into()];
+	for expected in [
+		"[
+			{
+				detail: {
+					actual: {
+						elapsed: 0s,
+						rows: 2
+					},
+					estimate: {
+						rows: 2,
+						cost: 4
+					},
+					plan: {
+						index: 'idx_genre',
+						operator: '=',
+						value: 'm'
+					},
+					table: 'person',
+				},
+				operation: 'Iterate Index'
+			},
+			{
+				detail: {
+					count: 2
+				},
+				operation: 'Fetch'
+			}
+		]",
+		"[
+			{
+				detail: {
+					actual: {
+						elapsed: 0s,
+						rows: 3
+					},
+					table: 'person'
+				},
+				operation: 'Iterate Table'
+			},
+			{
+				detail: {
+					count: 3
+				},
+				operation: 'Fetch'
+			}
+		]",
+	] {
+		let mut tmp = res.remove(0).result?;
+		assert!(tmp.pick(&elapsed).is_duration());
+		tmp.put(&elapsed, Value::parse("0s"));
+		assert_eq!(format!("{:#}", tmp), format!("{:#}", Value::parse(expected)));
+	}
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_edges_with_index() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX idx_weight ON TABLE likes COLUMNS weight;
+		RELATE person:tobie->likes:1->post:1 SET weight = 1;
+		RELATE person:tobie->likes:2->post:2 SET weight = 2;
+		RELATE person:jaime->likes:3->post:1 SET weight = 1;
+		SELECT VALUE id FROM person:tobie->likes WITH INDEX likes.idx_weight WHERE weight = 1;
+		SELECT VALUE id FROM post:1<-likes WITH INDEX likes.idx_weight WHERE weight = 1;
+		SELECT VALUE id FROM person:tobie->likes WITH INDEX likes.idx_weight WHERE weight = 1 EXPLAIN;
+		SELECT VALUE id FROM person:tobie->likes WHERE weight = 1 EXPLAIN;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let mut res = dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 8);
+	for _ in 0..4 {
+		let _ = res.remove(0).result?;
+	}
+	check_result(&mut res, "[likes:1]")?;
+	check_result(&mut res, "[likes:1, likes:3]")?;
+	// The edges are only iterated from the index when it is forced
+	let operation: [Part; 2] = [0_usize.into(), "operation".into()];
+	for expected in ["Iterate Index Edges", "Iterate Edges"] {
+		let tmp = res.remove(0).result?;
+		assert_eq!(tmp.pick(&operation), Value::from(expected));
+	}
+	Ok(())
+}
@@ -2,9 +2,14 @@ mod parse;
 use parse::Parse;
 mod helpers;
 use helpers::new_ds;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
 use surrealdb::dbs::Session;
 use surrealdb::err::Error;
-use surrealdb::sql::Value;
+use surrealdb::iam::{Actor, Auth, Role};
+use surrealdb::kvs::{Datastore, DatastoreOptions, ImportOptions};
+use surrealdb::sql::{Kind, Value};
 
 #[tokio::test]
 async fn query_basic() -> Result<(), Error> {
@@ -161,3 +166,350 @@ async fn query_root_record() -> Result<(), Error> {
 	//
 	Ok(())
 }
+
+#[tokio::test]
+async fn query_named() -> Result<(), Error> {
+	let dbs = new_ds().await?.with_auth_enabled(true).with_capabilities(Default::default());
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let sql = "
+		CREATE person:tobie SET age = 33;
+		CREATE person:jaime SET age = 29;
+		DEFINE USER alice ON DATABASE PASSWORD 'alice' ROLES EDITOR;
+	";
+	dbs.execute(sql, &ses, None).await?;
+	// Register a named query as the owner
+	let sql = "SELECT VALUE id FROM person WHERE age > $age ORDER BY id";
+	let params = BTreeMap::from([("age".to_owned(), Kind::Int)]);
+	dbs.register_query("older", sql, params, &ses).await?;
+	// Anonymous sessions can not run arbitrary queries
+	let anon = Session::default().with_ns("test").with_db("test");
+	let res = dbs.execute("SELECT * FROM person", &anon, None).await;
+	assert!(res.is_err());
+	// Anonymous sessions can run the named query
+	let vars = BTreeMap::from([("age".to_owned(), Value::from(30))]);
+	let res = &mut dbs.execute_query("older", &anon, Some(vars)).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[person:tobie]");
+	assert_eq!(tmp, val);
+	// Named queries are registered on a single database
+	let other = Session::default().with_ns("test").with_db("other");
+	let res = dbs.execute_query("older", &other, None).await;
+	assert!(matches!(res, Err(Error::NqNotFound { .. })));
+	// Parameters are coerced to their declared types
+	let vars = BTreeMap::from([("age".to_owned(), Value::from("old"))]);
+	let res = dbs.execute_query("older", &anon, Some(vars)).await;
+	assert!(res.is_err());
+	// Undeclared parameters are rejected
+	let vars = BTreeMap::from([("session".to_owned(), Value::from(true))]);
+	let res = dbs.execute_query("older", &anon, Some(vars)).await;
+	assert!(matches!(res, Err(Error::InvalidParam { .. })));
+	// Anonymous sessions can not register or remove queries
+	let res = dbs.register_query("all", "SELECT * FROM person", BTreeMap::new(), &anon).await;
+	assert!(res.is_err());
+	assert!(dbs.unregister_query("older", &anon).await.is_err());
+	// Queries registered by another user can not be replaced
+	let alice = Session {
+		au: Arc::new(Auth::new(Actor::new(
+			"alice".to_owned(),
+			vec![Role::Editor],
+			("test", "test").into(),
+		))),
+		..Session::default().with_ns("test").with_db("test")
+	};
+	let res = dbs.register_query("older", "SELECT * FROM person", BTreeMap::new(), &alice).await;
+	assert!(matches!(res, Err(Error::NqAlreadyExists { .. })));
+	// Queries are run with the current roles of the registering user
+	dbs.register_query("all", "SELECT VALUE id FROM person", BTreeMap::new(), &alice).await?;
+	let res = &mut dbs.execute_query("all", &anon, None).await?;
+	assert_eq!(res.remove(0).result?, Value::parse("[person:jaime, person:tobie]"));
+	dbs.execute("REMOVE USER alice ON DATABASE", &ses, None).await?;
+	let res = dbs.execute_query("all", &anon, None).await;
+	assert!(matches!(res, Err(Error::UserDbNotFound { .. })));
+	// Unknown queries can not be run
+	dbs.unregister_query("older", &ses).await?;
+	let res = dbs.execute_query("older", &anon, None).await;
+	assert!(matches!(res, Err(Error::NqNotFound { .. })));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn query_kill() -> Result<(), Error> {
+	let dbs = Arc::new(new_ds().await?);
+	let ses = Session::owner().with_ns("test").with_db("test");
+	// Start a long running query
+	let run = tokio::spawn({
+		let dbs = dbs.clone();
+		let ses = ses.clone();
+		async move { dbs.execute("SLEEP 10s; RETURN 'done';", &ses, None).await }
+	});
+	// Find the running query
+	let id = loop {
+		let res = &mut dbs.execute("SHOW QUERIES", &ses, None).await?;
+		let tmp = res.remove(0).result?;
+		let found = match &tmp {
+			Value::Array(v) => {
+				v.iter().find(|q| q.pick(&["query".into()]) == Value::from("SLEEP 10s"))
+			}
+			_ => None,
+		};
+		if let Some(q) = found {
+			assert!(q.pick(&["elapsed".into()]).is_duration());
+			break q.pick(&["id".into()]);
+		}
+		tokio::time::sleep(Duration::from_millis(10)).await;
+	};
+	// Cancel the running query
+	let vars = BTreeMap::from([("id".to_owned(), id.clone())]);
+	let res = &mut dbs.execute("KILL QUERY $id", &ses, Some(vars)).await?;
+	res.remove(0).result?;
+	// The query stops, and the following statements still run
+	let res = &mut tokio::time::timeout(Duration::from_secs(5), run).await.unwrap().unwrap()?;
+	assert!(matches!(res.remove(0).result, Err(Error::QueryKilled)));
+	assert_eq!(res.remove(0).result?, Value::from("done"));
+	// The query is no longer running
+	let vars = BTreeMap::from([("id".to_owned(), id)]);
+	let res = &mut dbs.execute("KILL QUERY $id", &ses, Some(vars)).await?;
+	assert!(matches!(res.remove(0).result, Err(Error::QueryNotRunning { .. })));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn show_and_kill_operations() -> Result<(), Error> {
+	let dbs = Arc::new(new_ds().await?.with_notifications());
+	let ses = Session::owner().with_ns("test").with_db("test").with_rt(true);
+	// Start a live query
+	let res = &mut dbs.execute("LIVE SELECT * FROM person", &ses, None).await?;
+	let live = res.remove(0).result?;
+	// Start a long running query
+	let run = tokio::spawn({
+		let dbs = dbs.clone();
+		let ses = ses.clone();
+		async move { dbs.execute("SLEEP 10s", &ses, None).await }
+	});
+	// Find the running query, and the live query
+	let id = loop {
+		let res = &mut dbs.execute("SHOW OPERATIONS", &ses, None).await?;
+		let tmp = res.remove(0).result?;
+		let ops = match &tmp {
+			Value::Array(v) => v.clone(),
+			_ => panic!("expected an array of operations"),
+		};
+		let live = ops.iter().find(|q| q.pick(&["id".into()]) == live).cloned();
+		let sleep = ops.iter().find(|q| q.pick(&["query".into()]) == Value::from("SLEEP 10s"));
+		if let Some(q) = sleep {
+			let live = live.unwrap();
+			assert_eq!(live.pick(&["kind".into()]), Value::from("live"));
+			assert_eq!(live.pick(&["query".into()]), Value::from("LIVE SELECT * FROM person"));
+			assert_eq!(q.pick(&["kind".into()]), Value::from("query"));
+			break q.pick(&["id".into()]);
+		}
+		tokio::time::sleep(Duration::from_millis(10)).await;
+	};
+	// Running operations are cancelled with the KILL statement
+	let vars = BTreeMap::from([("id".to_owned(), id)]);
+	let res = &mut dbs.execute("KILL $id", &ses, Some(vars)).await?;
+	res.remove(0).result?;
+	let res = &mut tokio::time::timeout(Duration::from_secs(5), run).await.unwrap().unwrap()?;
+	assert!(matches!(res.remove(0).result, Err(Error::QueryKilled)));
+	// Live queries are still killed with the KILL statement
+	let vars = BTreeMap::from([("id".to_owned(), live.clone())]);
+	let res = &mut dbs.execute("KILL $id; SHOW OPERATIONS;", &ses, Some(vars)).await?;
+	res.remove(0).result?;
+	let tmp = res.remove(0).result?;
+	let ops = match &tmp {
+		Value::Array(v) => v.clone(),
+		_ => panic!("expected an array of operations"),
+	};
+	assert!(ops.iter().all(|q| q.pick(&["id".into()]) != live), "{tmp}");
+	//
+	Ok(())
+}
+
+// Finds the id of a running operation of the given kind
+async fn find_operation(dbs: &Datastore, ses: &Session, kind: &str) -> Result<Value, Error> {
+	loop {
+		let res = &mut dbs.execute("SHOW OPERATIONS", ses, None).await?;
+		let tmp = res.remove(0).result?;
+		let ops = match &tmp {
+			Value::Array(v) => v.clone(),
+			_ => panic!("expected an array of operations"),
+		};
+		if let Some(q) = ops.iter().find(|q| q.pick(&["kind".into()]) == Value::from(kind)) {
+			return Ok(q.pick(&["id".into()]));
+		}
+		tokio::time::sleep(Duration::from_millis(10)).await;
+	}
+}
+
+#[tokio::test]
+async fn kill_index_build() -> Result<(), Error> {
+	let opts = DatastoreOptions::new().with_index_batch_size(1);
+	let dbs = Arc::new(Datastore::new_with_options("memory", opts).await?);
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let sql = "
+		CREATE |person:1..20000| SET n = 1;
+		DEFINE INDEX test ON person FIELDS n CONCURRENTLY;
+	";
+	let res = dbs.execute(sql, &ses, None).await?;
+	assert!(res.into_iter().all(|r| r.result.is_ok()));
+	// Start building the index in the background
+	let run = tokio::spawn({
+		let dbs = dbs.clone();
+		async move { dbs.build_indexes().await }
+	});
+	// Index builds are cancelled with the KILL statement
+	let id = find_operation(&dbs, &ses, "index").await?;
+	let vars = BTreeMap::from([("id".to_owned(), id)]);
+	let res = &mut dbs.execute("KILL $id", &ses, Some(vars)).await?;
+	res.remove(0).result?;
+	tokio::time::timeout(Duration::from_secs(5), run).await.unwrap().unwrap()?;
+	// The killed build is reported, and is not resumed
+	dbs.build_indexes().await?;
+	let res = &mut dbs.execute("INFO FOR INDEX test ON person", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let building = tmp.pick(&["building".into()]);
+	assert_eq!(building.pick(&["status".into()]), Value::from("failed"));
+	assert_eq!(building.pick(&["error".into()]), Value::from(Error::QueryKilled.to_string()));
+	assert!(tmp.pick(&["built".into()]).is_none());
+	// The index is built again once it is defined again
+	let sql = "DEFINE INDEX test ON person FIELDS n CONCURRENTLY";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	res.remove(0).result?;
+	dbs.build_indexes().await?;
+	let res = &mut dbs.execute("INFO FOR INDEX test ON person", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	assert!(tmp.pick(&["building".into()]).is_none());
+	assert!(tmp.pick(&["built".into()]).is_some());
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn kill_import() -> Result<(), Error> {
+	let dbs = Arc::new(new_ds().await?);
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let sql: String = (1..=20000).map(|i| format!("CREATE person:{i};")).collect();
+	// Start a long running import
+	let run = tokio::spawn({
+		let dbs = dbs.clone();
+		let ses = ses.clone();
+		async move { dbs.import_with_options(&sql, &ses, ImportOptions::new()).await }
+	});
+	// Imports are cancelled with the KILL statement
+	let id = find_operation(&dbs, &ses, "import").await?;
+	let vars = BTreeMap::from([("id".to_owned(), id)]);
+	let res = &mut dbs.execute("KILL $id", &ses, Some(vars)).await?;
+	res.remove(0).result?;
+	let res = tokio::time::timeout(Duration::from_secs(5), run).await.unwrap().unwrap();
+	assert!(matches!(res, Err(Error::QueryKilled)));
+	// The statements after the KILL statement are not imported
+	let res = &mut dbs.execute("RETURN count(SELECT id FROM person)", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	assert!(tmp < Value::from(20000), "{tmp}");
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn query_error_statement() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie;
+		CREATE person:tobie;
+		SELECT * FROM person;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0);
+	assert_eq!(tmp.statement, 0);
+	assert!(tmp.span.is_none());
+	// The failed statement is located in the query
+	let tmp = res.remove(0);
+	assert!(matches!(tmp.result, Err(Error::RecordExists { .. })));
+	assert_eq!(tmp.statement, 1);
+	let span = tmp.span.unwrap();
+	assert_eq!(&sql[span.start..span.end], "CREATE person:tobie");
+	assert_eq!((span.line, span.column), (3, 3));
+	//
+	let tmp = res.remove(0);
+	assert_eq!(tmp.statement, 2);
+	assert!(tmp.span.is_none());
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn query_none_output_option() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie SET name = 'Tobie', age = NONE, email = NULL;
+		RETURN [NONE, { id: person:tobie.id, nick: person:tobie.nick }];
+		SET OPTION NONE_OUTPUT = 'null';
+		RETURN [NONE, { id: person:tobie.id, nick: person:tobie.nick }];
+		SET OPTION NONE_OUTPUT = 'omit';
+		RETURN [NONE, { id: person:tobie.id, nick: person:tobie.nick }];
+		SET OPTION NONE_OUTPUT = 'none';
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[NONE, { id: person:tobie, nick: NONE }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::Null);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[NULL, { id: person:tobie, nick: NULL }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::Null);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[NULL, { id: person:tobie }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp.err(), Some(Error::InvalidOption { .. })));
+	// The option applies to the following queries of the session
+	let res = &mut dbs.execute("RETURN { nick: NONE }", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{}");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn query_with_warnings() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie;
+		SELECT * FROM person WHERE id = 'person:tobie';
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let (res, warnings) = &mut dbs.execute_with_warnings(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	// The query still runs
+	res.remove(0).result?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:tobie }]");
+	assert_eq!(tmp, val);
+	// The warning is reported alongside the responses
+	assert_eq!(warnings.len(), 1);
+	assert_eq!(warnings[0].diagnostic.code, "record_id_string");
+	assert_eq!((warnings[0].diagnostic.span.line, warnings[0].diagnostic.span.column), (3, 35));
+	assert!(warnings[0].to_string().contains("perhaps remove the quotes?"));
+	//
+	Ok(())
+}
@@ -105,3 +105,506 @@ async fn relate_and_overwrite() -> Result<(), Error> {
 	//
 	Ok(())
 }
+
+#[tokio::test]
+async fn relate_into_relation_table() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE likes TYPE RELATION FROM user TO post;
+		CREATE user:tobie;
+		CREATE post:one;
+		RELATE user:tobie->likes->post:one SET id = likes:one;
+		RELATE user:tobie->likes->user:tobie;
+		CREATE likes:two;
+		SELECT ->likes->post AS posts, <-likes AS liked FROM user:tobie;
+		SELECT VALUE ->likes FROM post:one;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 8);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: likes:one,
+				in: user:tobie,
+				out: post:one,
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	// The out field of the relation is typed
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(Error::FieldCheck {
+			check,
+			..
+		}) if check == "record<post>"
+	));
+	// Normal records can not be stored in a relation table
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Found record: `likes:two` which is not a relation, but expected a relation"
+	));
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				posts: [post:one],
+				liked: [],
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	// A post is never the origin of a like
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[[]]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn relate_into_relation_table_with_defined_fields() -> Result<(), Error> {
+	let sql = "
+		DEFINE FIELD out ON likes TYPE record<post> ASSERT $value.id != 'hidden';
+		DEFINE TABLE likes TYPE RELATION FROM user TO post;
+		RELATE user:tobie->likes->post:hidden;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// The field which was already defined is kept
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(Error::FieldValue {
+			check,
+			..
+		}) if check == "$value.id != 'hidden'"
+	));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn relate_into_normal_table() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person TYPE NORMAL;
+		RELATE user:tobie->person->post:one;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(Error::TableCheck {
+			relation: true,
+			..
+		})
+	));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn relate_traverse_both_directions() -> Result<(), Error> {
+	let sql = "
+		RELATE person:tobie->knows->person:jaime SET id = knows:one;
+		RELATE person:jaime->knows->person:tobie SET id = knows:two;
+		RELATE person:jaime->knows->person:john SET id = knows:three;
+		SELECT id, $direction AS direction FROM person:jaime<->knows ORDER BY id;
+		SELECT id, $direction AS direction FROM person:jaime<->knows ORDER BY id PARALLEL;
+		SELECT VALUE <->(knows WHERE $direction = 'in') FROM person:jaime;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let val = Value::parse(
+		"[
+			{
+				id: knows:one,
+				direction: 'in',
+			},
+			{
+				id: knows:three,
+				direction: 'out',
+			},
+			{
+				id: knows:two,
+				direction: 'out',
+			}
+		]",
+	);
+	// The direction is also exposed when the edges are processed in parallel
+	for _ in 0..2 {
+		let tmp = res.remove(0).result?;
+		assert_eq!(tmp, val);
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[[knows:one]]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn relate_count_and_exists_traversals() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie;
+		CREATE person:jaime;
+		CREATE person:john;
+		RELATE person:tobie->knows->person:jaime;
+		RELATE person:tobie->knows->person:john;
+		RELATE person:jaime->knows->person:john;
+		RELATE person:john->likes->post:one;
+		SELECT id, count(->knows->person) AS count, array::len(->knows->person) AS len FROM person ORDER BY id;
+		SELECT VALUE count(->knows->person->likes->post) FROM person:tobie;
+		RETURN count(person:jaime<-knows<-person);
+		SELECT VALUE id FROM person WHERE ->knows->person ORDER BY id;
+		SELECT VALUE id FROM person WHERE <-knows<-person ORDER BY id;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 12);
+	//
+	for _ in 0..7 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:jaime, count: 1, len: 1 },
+			{ id: person:john, count: 0, len: 0 },
+			{ id: person:tobie, count: 2, len: 2 }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[1]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("1");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[person:jaime, person:tobie]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[person:jaime, person:john]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn relate_traversal_paths() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie;
+		CREATE person:jaime;
+		CREATE person:john;
+		RELATE person:tobie->knows->person:jaime SET id = knows:one;
+		RELATE person:tobie->knows->person:john SET id = knows:two;
+		RELATE person:jaime->knows->person:john SET id = knows:three;
+		SELECT VALUE graph::paths(->knows->person->knows->person) FROM person:tobie;
+		RETURN graph::paths(person:tobie->knows->(person WHERE id = person:john));
+		RETURN graph::paths(person:tobie.name);
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 9);
+	//
+	for _ in 0..6 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			[
+				[person:tobie, knows:one, person:jaime, knows:three, person:john]
+			]
+		]",
+	);
+	assert_eq!(tmp, val);
+	// Only the paths reaching records which match the condition are returned
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[[person:tobie, knows:two, person:john]]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(Error::InvalidArguments {
+			name,
+			..
+		}) if name == "graph::paths"
+	));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn relate_negated_traversals() -> Result<(), Error> {
+	let sql = "
+		CREATE user:alice, user:bob, user:carol;
+		RELATE user:bob->blocked->user:alice;
+		RELATE user:carol->blocked->user:bob;
+		SELECT VALUE id FROM user WHERE NOT ->blocked->user:alice ORDER BY id;
+		SELECT VALUE id FROM user WHERE ->blocked->user:alice ORDER BY id;
+		SELECT VALUE id FROM user WHERE !->blocked->(user WHERE id != user:alice) ORDER BY id;
+		SELECT VALUE id FROM user WHERE NOT <-blocked<-user:dave ORDER BY id;
+		SELECT id, count(->blocked->user:bob) AS count FROM user ORDER BY id;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 8);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[user:alice, user:carol]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[user:bob]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[user:alice, user:bob]");
+	assert_eq!(tmp, val);
+	// Records which do not exist are never reached
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[user:alice, user:bob, user:carol]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: user:alice, count: 0 },
+			{ id: user:bob, count: 0 },
+			{ id: user:carol, count: 1 }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn relate_multi_hop_traversals() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie, person:jaime, person:john;
+		CREATE post:one SET public = false;
+		CREATE post:two SET public = true;
+		RELATE person:tobie->knows->person:jaime SET id = knows:one;
+		RELATE person:tobie->knows->person:john SET id = knows:two;
+		RELATE person:john->likes->post:one SET id = likes:one;
+		RELATE person:john->likes->post:two SET id = likes:two;
+		RELATE person:jaime->likes->post:two SET id = likes:three;
+		SELECT VALUE ->knows->person->likes->post FROM person:tobie;
+		RETURN person:tobie->knows->person->likes->post;
+		RETURN person:tobie->knows->(person WHERE id != person:jaime)->likes->post;
+		RETURN person:tobie->knows->person->likes->(post WHERE public = true);
+		RETURN person:tobie->knows->person->likes->post:one;
+		RETURN person:tobie<-knows<-person;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 14);
+	//
+	for _ in 0..8 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[[post:two, post:one, post:two]]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[post:two, post:one, post:two]");
+	assert_eq!(tmp, val);
+	// Only the records which match the condition are followed
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[post:one, post:two]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[post:two, post:two]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[post:one]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn relate_recursive_traversals() -> Result<(), Error> {
+	let sql = "
+		CREATE person:a, person:b, person:c, person:d;
+		RELATE person:a->knows->person:b SET id = knows:1;
+		RELATE person:b->knows->person:c SET id = knows:2;
+		RELATE person:c->knows->person:a SET id = knows:3;
+		RELATE person:c->knows->person:d SET id = knows:4;
+		SELECT ->knows{1..3}->person AS reach FROM person:a;
+		RETURN person:a->knows{2..3}->person;
+		RETURN person:a->knows{1}->person;
+		RETURN person:d<-knows{1..10}<-person;
+		RETURN graph::paths(person:a->knows{1..3}->person);
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 10);
+	//
+	for _ in 0..5 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// The cycle back to the starting record is not followed
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ reach: [person:b, person:c, person:d] }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[person:c, person:d]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[person:b]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[person:c, person:b, person:a]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			[person:a, knows:1, person:b],
+			[person:a, knows:1, person:b, knows:2, person:c],
+			[person:a, knows:1, person:b, knows:2, person:c, knows:4, person:d]
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn relate_and_check_relations() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie, person:jaime;
+		RELATE person:tobie->likes->person:jaime SET id = likes:1;
+		RELATE person:tobie->likes->person:ghost SET id = likes:2;
+		RELATE person:ghost->likes->person:jaime SET id = likes:3;
+		CHECK RELATIONS likes;
+		CHECK RELATIONS likes LIMIT 2;
+		CHECK RELATIONS likes FIX START likes:2;
+		SELECT VALUE id FROM likes;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 8);
+	//
+	for _ in 0..4 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			checked: 3,
+			dangling: [
+				{ id: likes:2, missing: [person:ghost] },
+				{ id: likes:3, missing: [person:ghost] },
+			],
+			fixed: false,
+			next: NONE,
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			checked: 2,
+			dangling: [
+				{ id: likes:2, missing: [person:ghost] },
+			],
+			fixed: false,
+			next: likes:2,
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			checked: 1,
+			dangling: [
+				{ id: likes:3, missing: [person:ghost] },
+			],
+			fixed: true,
+			next: NONE,
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[likes:1, likes:2]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
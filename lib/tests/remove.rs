@@ -37,9 +37,12 @@ async fn remove_statement_table() -> Result<(), Error> {
 		"{
 			analyzers: {},
 			tokens: {},
+			configs: {},
 			functions: {},
 			params: {},
 			scopes: {},
+			sequences: {},
+			migrations: {},
 			tables: {},
 			users: {}
 		}",
@@ -71,9 +74,12 @@ async fn remove_statement_analyzer() -> Result<(), Error> {
 		"{
 			analyzers: {},
 			tokens: {},
+			configs: {},
 			functions: {},
 			params: {},
 			scopes: {},
+			sequences: {},
+			migrations: {},
 			tables: {},
 			users: {}
 		}",
@@ -221,8 +227,8 @@ async fn permissions_checks_remove_function() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
-        vec!["{ analyzers: {  }, functions: { greet: \"DEFINE FUNCTION fn::greet() { RETURN 'Hello'; }\" }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
+		vec!["{ analyzers: {  }, configs: {  }, functions: {  }, migrations: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"],
+        vec!["{ analyzers: {  }, configs: {  }, functions: { greet: \"DEFINE FUNCTION fn::greet() { RETURN 'Hello'; }\" }, migrations: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"],
     ];
 
 	let test_cases = [
@@ -263,8 +269,8 @@ async fn permissions_checks_remove_analyzer() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
-        vec!["{ analyzers: { analyzer: 'DEFINE ANALYZER analyzer TOKENIZERS BLANK' }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
+		vec!["{ analyzers: {  }, configs: {  }, functions: {  }, migrations: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"],
+        vec!["{ analyzers: { analyzer: 'DEFINE ANALYZER analyzer TOKENIZERS BLANK' }, configs: {  }, functions: {  }, migrations: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"],
     ];
 
 	let test_cases = [
@@ -347,8 +353,8 @@ async fn permissions_checks_remove_db_token() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
-        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: { token: \"DEFINE TOKEN token ON DATABASE TYPE HS512 VALUE 'secret'\" }, users: {  } }"],
+		vec!["{ analyzers: {  }, configs: {  }, functions: {  }, migrations: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"],
+        vec!["{ analyzers: {  }, configs: {  }, functions: {  }, migrations: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: { token: \"DEFINE TOKEN token ON DATABASE TYPE HS512 VALUE 'secret'\" }, users: {  } }"],
     ];
 
 	let test_cases = [
@@ -473,8 +479,8 @@ async fn permissions_checks_remove_db_user() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
-        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: { user: \"DEFINE USER user ON DATABASE PASSHASH 'secret' ROLES VIEWER\" } }"],
+		vec!["{ analyzers: {  }, configs: {  }, functions: {  }, migrations: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"],
+        vec!["{ analyzers: {  }, configs: {  }, functions: {  }, migrations: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: { user: \"DEFINE USER user ON DATABASE PASSHASH 'secret' ROLES VIEWER\" } }"],
     ];
 
 	let test_cases = [
@@ -515,8 +521,8 @@ async fn permissions_checks_remove_scope() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
-        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: { account: 'DEFINE SCOPE account SESSION 1h' }, tables: {  }, tokens: {  }, users: {  } }"],
+		vec!["{ analyzers: {  }, configs: {  }, functions: {  }, migrations: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"],
+        vec!["{ analyzers: {  }, configs: {  }, functions: {  }, migrations: {  }, params: {  }, scopes: { account: 'DEFINE SCOPE account SESSION 1h' }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"],
     ];
 
 	let test_cases = [
@@ -557,8 +563,8 @@ async fn permissions_checks_remove_param() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
-        vec!["{ analyzers: {  }, functions: {  }, params: { param: \"DEFINE PARAM $param VALUE 'foo'\" }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
+		vec!["{ analyzers: {  }, configs: {  }, functions: {  }, migrations: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"],
+        vec!["{ analyzers: {  }, configs: {  }, functions: {  }, migrations: {  }, params: { param: \"DEFINE PARAM $param VALUE 'foo'\" }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"],
     ];
 
 	let test_cases = [
@@ -599,8 +605,8 @@ async fn permissions_checks_remove_table() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
-        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: { TB: 'DEFINE TABLE TB SCHEMALESS' }, tokens: {  }, users: {  } }"],
+		vec!["{ analyzers: {  }, configs: {  }, functions: {  }, migrations: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: {  }, tokens: {  }, users: {  } }"],
+        vec!["{ analyzers: {  }, configs: {  }, functions: {  }, migrations: {  }, params: {  }, scopes: {  }, sequences: {  }, tables: { TB: 'DEFINE TABLE TB SCHEMALESS' }, tokens: {  }, users: {  } }"],
     ];
 
 	let test_cases = [
@@ -756,3 +762,30 @@ async fn permissions_checks_remove_index() {
 	let res = iam_check_cases(test_cases.iter(), &scenario, check_results).await;
 	assert!(res.is_ok(), "{}", res.unwrap_err());
 }
+
+#[tokio::test]
+async fn remove_statement_if_exists() -> Result<(), Error> {
+	let sql = "
+		REMOVE TABLE IF EXISTS test;
+		REMOVE FIELD IF EXISTS name ON test;
+		REMOVE ANALYZER IF EXISTS simple;
+		REMOVE CONFIG IF EXISTS hooks;
+		REMOVE SEQUENCE IF EXISTS seq;
+		REMOVE MIGRATION IF EXISTS 1;
+		REMOVE TABLE test;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..6 {
+		let tmp = res.remove(0).result?;
+		assert_eq!(tmp, Value::None);
+	}
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::TbNotFound { .. })));
+	//
+	Ok(())
+}
@@ -0,0 +1,84 @@
+mod parse;
+use parse::Parse;
+mod helpers;
+use helpers::new_ds;
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::iam::Role;
+use surrealdb::sql::Value;
+
+#[tokio::test]
+async fn run_as_scope_user() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE post SCHEMALESS PERMISSIONS FOR select WHERE author = $auth, FOR create WHERE $scope = 'account';
+		CREATE post:one SET author = user:one;
+		CREATE post:two SET author = user:two;
+		RUN AS SCOPE user:one ON account {
+			SELECT * FROM post;
+		};
+		RUN AS SCOPE user:two {
+			CREATE post:three SET author = $auth;
+		};
+		SELECT * FROM post;
+	";
+	let dbs = new_ds().await?.with_auth_enabled(true);
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// Only the records visible to the scope user are returned
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				author: user:one,
+				id: post:one
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	// The record is not created without the right scope
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	// The session is not impersonated after the block
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				author: user:one,
+				id: post:one
+			},
+			{
+				author: user:two,
+				id: post:two
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn run_as_scope_user_not_allowed() -> Result<(), Error> {
+	let sql = "RUN AS SCOPE user:one { SELECT * FROM post; }";
+	let dbs = new_ds().await?.with_auth_enabled(true);
+	// Database users can not impersonate scope users
+	let ses =
+		Session::for_level(("test", "test").into(), Role::Owner).with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_err());
+	// Namespace users can impersonate scope users
+	let ses = Session::for_level(("test",).into(), Role::Owner).with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	Ok(())
+}
@@ -5,7 +5,8 @@ use helpers::new_ds;
 use surrealdb::dbs::Session;
 use surrealdb::err::Error;
 use surrealdb::iam::Role;
-use surrealdb::sql::Value;
+use surrealdb::kvs::{Datastore, DatastoreOptions};
+use surrealdb::sql::{Part, Value};
 
 #[tokio::test]
 async fn select_field_value() -> Result<(), Error> {
@@ -513,6 +514,10 @@ async fn select_where_field_is_thing_and_with_index() -> Result<(), Error> {
 		"[
 				{
 					detail: {
+						estimate: {
+							rows: 2,
+							cost: 4
+						},
 						plan: {
 							index: 'author',
 							operator: '=',
@@ -531,6 +536,10 @@ async fn select_where_field_is_thing_and_with_index() -> Result<(), Error> {
 		"[
 				{
 					detail: {
+						estimate: {
+							rows: 2,
+							cost: 4
+						},
 						plan: {
 							index: 'author',
 							operator: '=',
@@ -589,6 +598,10 @@ async fn select_where_and_with_index() -> Result<(), Error> {
 		"[
 				{
 					detail: {
+						estimate: {
+							rows: 1,
+							cost: 2
+						},
 						plan: {
 							index: 'person_name',
 							operator: '=',
@@ -636,6 +649,10 @@ async fn select_where_and_with_unique_index() -> Result<(), Error> {
 		"[
 				{
 					detail: {
+						estimate: {
+							rows: 1,
+							cost: 2
+						},
 						plan: {
 							index: 'person_name',
 							operator: '=',
@@ -996,3 +1013,149 @@ async fn check_permissions_auth_disabled() {
 		);
 	}
 }
+
+fn reset_elapsed(v: &mut Value) {
+	match v {
+		Value::Object(o) => {
+			for (k, v) in o.iter_mut() {
+				match k.as_str() {
+					"elapsed" => {
+						assert!(v.is_duration());
+						*v = Value::parse("0s");
+					}
+					_ => reset_elapsed(v),
+				}
+			}
+		}
+		Value::Array(a) => a.iter_mut().for_each(reset_elapsed),
+		_ => (),
+	}
+}
+
+#[tokio::test]
+async fn select_profile() -> Result<(), Error> {
+	let sql = "
+		CREATE person:1 SET name = 'Amy', country = 'GBR', score = 10;
+		CREATE person:2 SET name = 'Ben', country = 'GBR', score = 20;
+		CREATE person:3 SET name = 'Cal', country = 'USA', score = 30;
+		CREATE person:4 SET name = 'Dan', country = 'USA', score = 40;
+		SELECT name FROM person WHERE score > 10 ORDER BY name LIMIT 2 PROFILE;
+		SELECT country, count() AS total FROM person GROUP BY country FETCH country PROFILE;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	//
+	for _ in 0..4 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let mut tmp = res.remove(0).result?;
+	reset_elapsed(&mut tmp);
+	let val = Value::parse(
+		"{
+			result: [
+				{ name: 'Ben' },
+				{ name: 'Cal' }
+			],
+			profile: {
+				stage: 'statement',
+				rows: 2,
+				elapsed: 0s,
+				children: [
+					{
+						stage: 'scan',
+						rows: 3,
+						elapsed: 0s,
+						children: [
+							{
+								stage: 'iterate',
+								rows: 3,
+								elapsed: 0s,
+								detail: {
+									detail: { table: 'person' },
+									operation: 'Iterate Table'
+								}
+							}
+						]
+					},
+					{ stage: 'filter', input: 4, rows: 3, elapsed: 0s },
+					{ stage: 'order', input: 3, rows: 3, elapsed: 0s },
+					{ stage: 'limit', input: 3, rows: 2, elapsed: 0s }
+				]
+			}
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ country: 'GBR', total: 2 },
+			{ country: 'USA', total: 2 }
+		]",
+	);
+	assert_eq!(tmp.pick(&["result".into()]), val);
+	let stages: [Part; 4] = ["profile".into(), "children".into(), Part::All, "stage".into()];
+	let val = Value::parse("['scan', 'group', 'fetch']");
+	assert_eq!(tmp.pick(&stages), val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_memory_limit() -> Result<(), Error> {
+	let sql = "
+		CREATE |person:1..10| SET name = 'Tobie';
+		SELECT * FROM person LIMIT 1;
+		CREATE |person:11..500| SET name = 'Jaime' RETURN NONE;
+		SELECT * FROM person;
+		SELECT name, count() AS total FROM person GROUP BY name;
+		SELECT count() AS total FROM (SELECT * FROM person LIMIT 150) GROUP ALL;
+	";
+	let opts = DatastoreOptions::new().with_statement_memory_limit(64 * 1024);
+	let dbs = Datastore::new_with_options("memory", opts).await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	// The peak memory of each statement is reported
+	let tmp = res.remove(0);
+	assert!(tmp.result.is_ok());
+	assert!(tmp.memory > 0);
+	let tmp = res.remove(0);
+	assert!(tmp.result.is_ok());
+	assert!(tmp.memory > 0 && tmp.memory < 1024);
+	// Statements holding more than the limit are aborted
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp,
+		Err(Error::MemoryLimitExceeded {
+			limit: 65536
+		})
+	));
+	// Grouped records are spilled to disk once over the limit
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				name: 'Jaime',
+				total: 490
+			},
+			{
+				name: 'Tobie',
+				total: 10
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	// The records of a nested statement are only counted once
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ total: 150 }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
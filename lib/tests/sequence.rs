@@ -0,0 +1,109 @@
+mod parse;
+use parse::Parse;
+mod helpers;
+use helpers::new_ds;
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::sql::Value;
+
+#[tokio::test]
+async fn define_sequence_and_next() -> Result<(), Error> {
+	let sql = "
+		DEFINE SEQUENCE invoice BATCH 2 START 1000;
+		RETURN sequence::next('invoice');
+		RETURN sequence::next('invoice');
+		RETURN sequence::next('invoice');
+		BEGIN;
+		RETURN sequence::next('invoice');
+		CANCEL;
+		RETURN sequence::next('invoice');
+		CREATE invoice CONTENT { id: sequence::next('invoice') } RETURN id;
+		REMOVE SEQUENCE invoice;
+		RETURN sequence::next('invoice');
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 9);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	for v in ["1000", "1001", "1002"] {
+		let tmp = res.remove(0).result?;
+		let val = Value::parse(v);
+		assert_eq!(tmp, val);
+	}
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_err());
+	// The value taken by the cancelled transaction is skipped
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("1004");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: invoice:1005 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "The sequence 'invoice' does not exist"
+	));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_sequence_is_monotonic() -> Result<(), Error> {
+	let sql = "
+		DEFINE SEQUENCE ticket;
+		BEGIN;
+		RETURN sequence::next('ticket');
+		CANCEL;
+		RETURN sequence::next('ticket');
+		RETURN sequence::next('ticket');
+		DEFINE SEQUENCE ticket START 500;
+		RETURN sequence::next('ticket');
+		DEFINE SEQUENCE ticket START 10;
+		RETURN sequence::next('ticket');
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 8);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_err());
+	// The block allocated by the cancelled transaction is allocated again
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("1");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("2");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("500");
+	assert_eq!(tmp, val);
+	// A lower start never moves the sequence backwards
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("600");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
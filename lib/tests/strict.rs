@@ -254,9 +254,12 @@ async fn loose_mode_all_ok() -> Result<(), Error> {
 		"{
 			analyzers: {},
 			tokens: {},
+			configs: {},
 			functions: {},
 			params: {},
 			scopes: {},
+			sequences: {},
+			migrations: {},
 			tables: { test: 'DEFINE TABLE test SCHEMALESS PERMISSIONS NONE' },
 			users: {},
 		}",
@@ -277,3 +280,46 @@ async fn loose_mode_all_ok() -> Result<(), Error> {
 	//
 	Ok(())
 }
+
+#[tokio::test]
+async fn strict_mode_session_option() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let sql = "
+		DEFINE NAMESPACE test;
+		DEFINE DATABASE test;
+		SET OPTION STRICT = true;
+		SET OPTION BATCH_SIZE = 0;
+		SET OPTION UNKNOWN = true;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	for _ in 0..3 {
+		let _ = res.remove(0).result?;
+	}
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp.err(), Some(Error::InvalidOption { .. })));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp.err(), Some(Error::InvalidOption { .. })));
+	// The option applies to the following queries of the session
+	assert_eq!(ses.options().strict, Some(true));
+	let res = &mut dbs.execute("CREATE test:tester", &ses, None).await?;
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(Error::TbNotFound {
+			value: _
+		})
+	));
+	// Other sessions are not affected
+	let other = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute("CREATE test:tester", &other, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: test:tester }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
@@ -0,0 +1,76 @@
+mod parse;
+use parse::Parse;
+mod helpers;
+use helpers::new_ds;
+use std::time::Duration;
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::sql::Value;
+
+#[tokio::test]
+async fn suggest_indexes_from_slow_queries() -> Result<(), Error> {
+	let sql = "
+		CREATE user:1 SET email = 'tobie@surrealdb.com', age = 33;
+		CREATE user:2 SET email = 'jaime@surrealdb.com', age = 33;
+		DEFINE INDEX age ON user FIELDS age;
+		SELECT id FROM user WHERE email = 'tobie@surrealdb.com';
+		SELECT id FROM user WHERE age = 33 AND email = 'jaime@surrealdb.com';
+		SELECT id FROM user WHERE age > 18;
+		SUGGEST INDEXES;
+	";
+	let dbs = new_ds().await?.with_slow_query_threshold(Some(Duration::ZERO));
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..6 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// The age field is already indexed
+	let tmp = res.remove(0).result?;
+	let Value::Array(mut tmp) = tmp else {
+		panic!("Expected an array of recommendations");
+	};
+	assert_eq!(tmp.len(), 1);
+	let Value::Object(tmp) = tmp.remove(0) else {
+		panic!("Expected a recommendation object");
+	};
+	assert_eq!(tmp.get("table"), Some(&Value::from("user")));
+	assert_eq!(tmp.get("field"), Some(&Value::from("email")));
+	assert_eq!(
+		tmp.get("statement"),
+		Some(&Value::from("DEFINE INDEX user_email ON user FIELDS email"))
+	);
+	assert_eq!(tmp.get("queries"), Some(&Value::from(2)));
+	assert_eq!(tmp.get("selectivity"), Some(&Value::from(1.0)));
+	assert_eq!(tmp.get("records"), Some(&Value::from(2)));
+	assert!(tmp.get("time").is_some_and(|v| v.is_duration()));
+	assert!(tmp.get("benefit").is_some_and(|v| v.is_duration()));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn suggest_indexes_without_slow_query_log() -> Result<(), Error> {
+	let sql = "
+		CREATE user:1 SET email = 'tobie@surrealdb.com';
+		SELECT id FROM user WHERE email = 'tobie@surrealdb.com';
+		SUGGEST INDEXES;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
@@ -106,3 +106,174 @@ async fn define_foreign_table() -> Result<(), Error> {
 	//
 	Ok(())
 }
+
+#[tokio::test]
+async fn define_foreign_table_retraction() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person SCHEMALESS;
+		DEFINE TABLE person_by_age AS
+			SELECT
+				count(),
+				age,
+				math::min(score) AS low,
+				math::max(score) AS high,
+				count::distinct(tag) AS tags
+			FROM person
+			GROUP BY age
+		;
+		CREATE person:one SET age = 39, score = 70, tag = 'a';
+		CREATE person:two SET age = 39, score = 80, tag = 'b';
+		CREATE person:three SET age = 39, score = 90, tag = 'b';
+		SELECT * FROM person_by_age;
+		UPDATE person:three SET score = 75;
+		DELETE person:one;
+		SELECT * FROM person_by_age;
+		DELETE person:two, person:three;
+		SELECT * FROM person_by_age;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 11);
+	//
+	for _ in 0..5 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				age: 39,
+				count: 3,
+				high: 90,
+				id: person_by_age:[39],
+				low: 70,
+				tags: 2
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				age: 39,
+				count: 2,
+				high: 80,
+				id: person_by_age:[39],
+				low: 75,
+				tags: 1
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_enumerated_table() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE status ENUMERATED ('open', 'closed', 'pending');
+		DEFINE FIELD status ON task TYPE record<status>;
+		SELECT id FROM status ORDER BY id;
+		UPDATE status:open SET label = 'Open';
+		CREATE status:archived;
+		CREATE task:one SET status = status:open;
+		CREATE task:two SET status = status:archived;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: status:closed }, { id: status:open }, { id: status:pending }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: status:open, label: 'Open' }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Found record: `status:archived` which is not a member of the enumerated table `status`"
+	));
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: task:one, status: status:open }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Found record: `status:archived` which is not a member of the enumerated table `status`"
+	));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_enumerated_table_members() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE status ENUMERATED ('open', 'closed', 'pending');
+		CREATE task:one SET state = { current: status:archived };
+		DELETE status:open;
+		DEFINE TABLE status ENUMERATED ('open', 'closed');
+		SELECT id FROM status ORDER BY id;
+		DELETE status:pending;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Found record: `status:archived` which is not a member of the enumerated table `status`"
+	));
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Found record: `status:open` which is a member of the enumerated table `status`, and can not be deleted"
+	));
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: status:closed }, { id: status:open }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
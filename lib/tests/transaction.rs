@@ -4,6 +4,7 @@ mod helpers;
 use helpers::new_ds;
 use surrealdb::dbs::Session;
 use surrealdb::err::Error;
+use surrealdb::kvs::Datastore;
 use surrealdb::sql::Value;
 
 #[tokio::test]
@@ -185,3 +186,130 @@ async fn transaction_with_throw_and_return() -> Result<(), Error> {
 	//
 	Ok(())
 }
+
+#[tokio::test]
+async fn transaction_on_read_only_datastore() -> Result<(), Error> {
+	let sql = "
+		BEGIN;
+		SELECT * FROM person;
+		COMMIT;
+		CREATE person:tobie;
+		SELECT * FROM person;
+	";
+	let dbs = Datastore::new_readonly("memory").await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Couldn't write to a datastore which was opened in read-only mode"
+	));
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn transaction_with_savepoint_after_failure() -> Result<(), Error> {
+	let sql = "
+		BEGIN;
+		CREATE person:tobie;
+		SAVEPOINT before_jaime;
+		CREATE person:jaime;
+		CREATE person:tobie;
+		ROLLBACK TO SAVEPOINT before_jaime;
+		CREATE person:jamie;
+		RELEASE SAVEPOINT before_jaime;
+		COMMIT;
+		SELECT id FROM person ORDER BY id;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 8);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:tobie }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::None);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:jaime }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Database record `person:tobie` already exists"
+	));
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::None);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:jamie }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::None);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:jamie }, { id: person:tobie }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn transaction_with_nested_savepoints() -> Result<(), Error> {
+	let sql = "
+		ROLLBACK TO SAVEPOINT one;
+		BEGIN;
+		SAVEPOINT one;
+		CREATE person:tobie;
+		SAVEPOINT two;
+		UPDATE person:tobie SET name = 'Tobie';
+		ROLLBACK TO SAVEPOINT one;
+		RELEASE SAVEPOINT two;
+		COMMIT;
+		SELECT * FROM person;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 8);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Savepoints can only be used within a transaction"
+	));
+	// A failed RELEASE fails the transaction
+	for _ in 0..5 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_err());
+	}
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "The savepoint 'two' does not exist"
+	));
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
@@ -5,6 +5,7 @@ use helpers::new_ds;
 use surrealdb::dbs::Session;
 use surrealdb::err::Error;
 use surrealdb::iam::Role;
+use surrealdb::kvs::{Datastore, DatastoreOptions};
 use surrealdb::sql::Value;
 
 #[tokio::test]
@@ -281,6 +282,66 @@ async fn update_with_return_clause() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn update_indexed_table_in_batches() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX age ON person FIELDS age;
+		CREATE person:1, person:2, person:3, person:4, person:5 SET age = 18;
+		UPDATE person SET age = 25 RETURN age;
+		SELECT id FROM person WHERE age = 25 ORDER BY id;
+		DELETE person WHERE id > person:3 RETURN BEFORE;
+		SELECT id FROM person WHERE age = 25 ORDER BY id;
+	";
+	let opts = DatastoreOptions::new().with_index_batch_size(2);
+	let dbs = Datastore::new_with_options("memory", opts).await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ age: 25 }, { age: 25 }, { age: 25 }, { age: 25 }, { age: 25 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:1 },
+			{ id: person:2 },
+			{ id: person:3 },
+			{ id: person:4 },
+			{ id: person:5 }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ age: 25, id: person:4 },
+			{ age: 25, id: person:5 }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:1 },
+			{ id: person:2 },
+			{ id: person:3 }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
 //
 // Permissions
 //
@@ -0,0 +1,129 @@
+mod parse;
+use parse::Parse;
+mod helpers;
+use helpers::new_ds;
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::sql::Value;
+
+#[tokio::test]
+async fn upsert_statement_unique_index() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX email ON user FIELDS email UNIQUE;
+		UPSERT user SET email = 'tobie@surrealdb.com', name = 'Tobie', visits += 1 RETURN email, name, visits;
+		UPSERT user SET email = 'tobie@surrealdb.com', name = 'Tobie Morgan', visits += 1 RETURN email, name, visits;
+		UPSERT user SET email = 'jaime@surrealdb.com', name = 'Jaime', visits += 1 RETURN email, name, visits;
+		SELECT email, name, visits FROM user ORDER BY email;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ email: 'tobie@surrealdb.com', name: 'Tobie', visits: 1 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ email: 'tobie@surrealdb.com', name: 'Tobie Morgan', visits: 2 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ email: 'jaime@surrealdb.com', name: 'Jaime', visits: 1 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ email: 'jaime@surrealdb.com', name: 'Jaime', visits: 1 },
+			{ email: 'tobie@surrealdb.com', name: 'Tobie Morgan', visits: 2 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn upsert_statement_record_id() -> Result<(), Error> {
+	let sql = "
+		UPSERT user:tobie SET visits += 1;
+		UPSERT ONLY user:tobie SET visits += 1;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: user:tobie, visits: 1 }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{ id: user:tobie, visits: 2 }");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn upsert_statement_on_conflict_ignore() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX email ON user FIELDS email UNIQUE;
+		CREATE user:tobie SET email = 'tobie@surrealdb.com', name = 'Tobie';
+		UPSERT user SET email = 'tobie@surrealdb.com', name = 'Jaime' ON CONFLICT IGNORE;
+		UPSERT user:tobie SET name = 'Jaime' ON CONFLICT IGNORE;
+		SELECT * FROM user;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result?;
+		let val = Value::parse("[]");
+		assert_eq!(tmp, val);
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: user:tobie, email: 'tobie@surrealdb.com', name: 'Tobie' }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn upsert_statement_conflicting_update() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX email ON user FIELDS email UNIQUE;
+		CREATE user:tobie SET email = 'tobie@surrealdb.com';
+		CREATE user:jaime SET email = 'jaime@surrealdb.com';
+		UPSERT user:jaime SET email = 'tobie@surrealdb.com';
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// A record which already exists is not merged into another record
+	let tmp = res.remove(0).result;
+	assert!(matches!(
+		tmp.err(),
+		Some(e) if e.to_string() == "Database index `email` already contains 'tobie@surrealdb.com', with record `user:tobie`"
+	));
+	//
+	Ok(())
+}
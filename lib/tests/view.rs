@@ -0,0 +1,102 @@
+mod parse;
+use parse::Parse;
+mod helpers;
+use helpers::new_ds;
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::sql::Value;
+
+const VIEW: &str = "
+	DEFINE INDEX email ON user FIELDS email;
+	DEFINE FUNCTION fn::active_users($since: datetime) {
+		RETURN SELECT * FROM user WHERE last_login > $since;
+	};
+	CREATE user:tobie SET email = 'tobie@surrealdb.com', last_login = '2023-06-01T00:00:00Z';
+	CREATE user:jaime SET email = 'jaime@surrealdb.com', last_login = '2022-06-01T00:00:00Z';
+	CREATE user:john SET email = 'john@surrealdb.com', last_login = '2023-03-01T00:00:00Z';
+";
+
+#[tokio::test]
+async fn select_from_parameterised_view() -> Result<(), Error> {
+	let sql = "
+		SELECT email FROM fn::active_users('2023-01-01T00:00:00Z') ORDER BY email;
+		SELECT email FROM fn::active_users('2023-01-01T00:00:00Z') WHERE email = 'tobie@surrealdb.com';
+		SELECT email FROM fn::active_users('2023-01-01T00:00:00Z') WHERE email = 'jaime@surrealdb.com';
+		SELECT * FROM fn::active_users('2023-01-01T00:00:00Z') WHERE email = 'tobie@surrealdb.com' EXPLAIN;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&format!("{VIEW}{sql}"), &ses, None).await?;
+	assert_eq!(res.len(), 9);
+	//
+	for _ in 0..5 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ email: 'john@surrealdb.com' }, { email: 'tobie@surrealdb.com' }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ email: 'tobie@surrealdb.com' }]");
+	assert_eq!(tmp, val);
+	// The conditions of the view still apply
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	// The conditions are pushed into the view query, which uses the index
+	let tmp = res.remove(0).result?.to_string();
+	assert!(tmp.contains("operation: 'Iterate Index'"), "{tmp}");
+	assert!(tmp.contains("index: 'email'"), "{tmp}");
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_from_parameterised_view_with_shadowed_parameter() -> Result<(), Error> {
+	let sql = "
+		LET $since = '2023-05-01T00:00:00Z';
+		SELECT email FROM fn::active_users('2023-01-01T00:00:00Z') WHERE last_login < $since;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&format!("{VIEW}{sql}"), &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..6 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// The parameter of the statement is not replaced by the view argument
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ email: 'john@surrealdb.com' }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_from_function_which_is_not_a_view() -> Result<(), Error> {
+	let sql = "
+		DEFINE FUNCTION fn::latest_user() {
+			RETURN SELECT * FROM user ORDER BY last_login DESC LIMIT 1;
+		};
+		SELECT email FROM fn::latest_user() WHERE email != NONE;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(&format!("{VIEW}{sql}"), &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..6 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ email: 'tobie@surrealdb.com' }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
@@ -0,0 +1,136 @@
+mod parse;
+use parse::Parse;
+mod helpers;
+use helpers::new_ds;
+use surrealdb::dbs::Session;
+use surrealdb::err::Error;
+use surrealdb::sql::Value;
+
+const PEOPLE: &str = "
+	CREATE person:1 SET name = 'Amy', country = 'GBR', score = 10;
+	CREATE person:2 SET name = 'Ben', country = 'GBR', score = 20;
+	CREATE person:3 SET name = 'Cal', country = 'GBR', score = 20;
+	CREATE person:4 SET name = 'Dan', country = 'USA', score = 5;
+	CREATE person:5 SET name = 'Eve', country = 'USA', score = 15;
+";
+
+#[tokio::test]
+async fn select_window_ranking() -> Result<(), Error> {
+	let sql = "
+		SELECT
+			name,
+			row_number() OVER (PARTITION BY country ORDER BY score DESC) AS num,
+			rank() OVER (PARTITION BY country ORDER BY score DESC) AS rank,
+			dense_rank() OVER (PARTITION BY country ORDER BY score DESC) AS dense
+		FROM person ORDER BY name;
+		SELECT VALUE row_number() OVER (ORDER BY score DESC, name) FROM person;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	dbs.execute(PEOPLE, &ses, None).await?;
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ dense: 2, name: 'Amy', num: 3, rank: 3 },
+			{ dense: 1, name: 'Ben', num: 1, rank: 1 },
+			{ dense: 1, name: 'Cal', num: 2, rank: 1 },
+			{ dense: 2, name: 'Dan', num: 2, rank: 2 },
+			{ dense: 1, name: 'Eve', num: 1, rank: 1 }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[4, 1, 2, 5, 3]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_window_offsets_and_aggregates() -> Result<(), Error> {
+	let sql = "
+		SELECT
+			name,
+			lag(score) OVER (ORDER BY id) AS prev,
+			lead(score, 2, 0) OVER (ORDER BY id) AS next,
+			math::sum(score) OVER (ORDER BY id) AS total,
+			count() OVER () AS people
+		FROM person ORDER BY name;
+		SELECT name, count() OVER () AS people FROM person LIMIT 2;
+		SELECT name, math::max(score) OVER (PARTITION BY country) AS best FROM person ORDER BY name;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	dbs.execute(PEOPLE, &ses, None).await?;
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ name: 'Amy', next: 20, people: 5, prev: NONE, total: 10 },
+			{ name: 'Ben', next: 5, people: 5, prev: 10, total: 30 },
+			{ name: 'Cal', next: 15, people: 5, prev: 20, total: 50 },
+			{ name: 'Dan', next: 0, people: 5, prev: 20, total: 55 },
+			{ name: 'Eve', next: 0, people: 5, prev: 5, total: 70 }
+		]",
+	);
+	assert_eq!(tmp, val);
+	// The window is computed over all records before the LIMIT
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ name: 'Amy', people: 5 },
+			{ name: 'Ben', people: 5 }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ best: 20, name: 'Amy' },
+			{ best: 20, name: 'Ben' },
+			{ best: 20, name: 'Cal' },
+			{ best: 15, name: 'Dan' },
+			{ best: 15, name: 'Eve' }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_window_invalid() -> Result<(), Error> {
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	dbs.execute(PEOPLE, &ses, None).await?;
+	// Window functions can not be computed over groups
+	let sql = "SELECT country, count() OVER () AS n FROM person GROUP BY country";
+	let res = dbs.execute(sql, &ses, None).await;
+	assert!(res.is_err());
+	// Only window and aggregate functions can be computed over a window
+	let sql = "SELECT string::lowercase(name) OVER () AS n FROM person";
+	let res = dbs.execute(sql, &ses, None).await;
+	assert!(res.is_err());
+	// Window functions can not be computed over live results
+	let sql = "LIVE SELECT rank() OVER (ORDER BY score) AS n FROM person";
+	let res = dbs.execute(sql, &ses, None).await;
+	assert!(res.is_err());
+	// Window functions can not be computed over views
+	let sql = "DEFINE TABLE ranked AS SELECT rank() OVER (ORDER BY score) AS n FROM person";
+	let res = dbs.execute(sql, &ses, None).await;
+	assert!(res.is_err());
+	// Ranking functions do not accept arguments
+	let sql = "SELECT rank(score) OVER (ORDER BY score) AS n FROM person";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::InvalidArguments { .. })));
+	//
+	Ok(())
+}
@@ -15,6 +15,9 @@ pub struct ExportCommandArguments {
 	#[arg(default_value = "-")]
 	#[arg(index = 1)]
 	file: String,
+	/// Whether each batch of records is scanned in its own transaction
+	#[arg(long)]
+	stable: bool,
 
 	#[command(flatten)]
 	conn: DatabaseConnectionArguments,
@@ -27,6 +30,7 @@ pub struct ExportCommandArguments {
 pub async fn init(
 	ExportCommandArguments {
 		file,
+		stable,
 		conn: DatabaseConnectionArguments {
 			endpoint,
 		},
@@ -71,7 +75,10 @@ pub async fn init(
 	// Export the data from the database
 	if file == "-" {
 		// Prepare the backup
-		let mut backup = client.export(()).await?;
+		let mut backup = match stable {
+			true => client.export(()).stable().await?,
+			false => client.export(()).await?,
+		};
 		// Get a handle to standard output
 		let mut stdout = io::stdout();
 		// Write the backup to standard output
@@ -79,7 +86,10 @@ pub async fn init(
 			stdout.write_all(&bytes?).await?;
 		}
 	} else {
-		client.export(file).await?;
+		match stable {
+			true => client.export(file).stable().await?,
+			false => client.export(file).await?,
+		}
 	}
 	info!("The SQL file was exported successfully");
 	// Everything OK
@@ -0,0 +1,92 @@
+use crate::err::Error;
+use clap::Args;
+use glob::glob;
+use std::io::{Error as IoError, ErrorKind};
+use surrealdb::sql::format::{format, FormatOptions, KeywordCase};
+
+#[derive(Args, Debug)]
+pub struct FmtCommandArguments {
+	#[arg(help = "Glob pattern for the files to format")]
+	#[arg(default_value = "**/*.surql")]
+	pattern: String,
+	#[arg(help = "The number of spaces in one level of indentation, or 0 to indent with tabs")]
+	#[arg(long, default_value_t = 0)]
+	indent: u32,
+	#[arg(help = "The width which lines are kept within, where possible")]
+	#[arg(long, default_value_t = 100)]
+	width: u32,
+	#[arg(help = "Whether keywords should be written in lowercase")]
+	#[arg(long)]
+	lowercase: bool,
+	#[arg(
+		help = "Whether to overwrite the files, instead of writing the formatted queries to stdout"
+	)]
+	#[arg(short, long)]
+	write: bool,
+}
+
+pub async fn init(args: FmtCommandArguments) -> Result<(), Error> {
+	let FmtCommandArguments {
+		pattern,
+		indent,
+		width,
+		lowercase,
+		write,
+	} = args;
+
+	let options = FormatOptions::default().with_indent(indent).with_width(width).with_keywords(
+		match lowercase {
+			true => KeywordCase::Lower,
+			false => KeywordCase::Upper,
+		},
+	);
+
+	let entries = match glob(&pattern) {
+		Ok(entries) => entries,
+		Err(error) => {
+			eprintln!("Error parsing glob pattern {pattern}: {error}");
+
+			return Err(Error::Io(IoError::new(
+				ErrorKind::Other,
+				format!("Error parsing glob pattern {pattern}: {error}"),
+			)));
+		}
+	};
+
+	let mut has_entries = false;
+
+	for entry in entries.flatten() {
+		let file_content = tokio::fs::read_to_string(entry.clone()).await?;
+
+		match format(&file_content, &options) {
+			Ok(formatted) if write => {
+				if formatted != file_content {
+					tokio::fs::write(entry.clone(), formatted).await?;
+				}
+				println!("{}: OK", entry.display());
+			}
+			Ok(formatted) => {
+				print!("{formatted}");
+			}
+			Err(error) => {
+				println!("{}: KO", entry.display());
+				eprintln!("{error}");
+
+				return Err(crate::err::Error::from(error));
+			}
+		}
+
+		has_entries = true;
+	}
+
+	if !has_entries {
+		eprintln!("No files found for pattern {pattern}");
+
+		return Err(Error::Io(IoError::new(
+			ErrorKind::NotFound,
+			format!("No files found for pattern {pattern}"),
+		)));
+	}
+
+	Ok(())
+}
@@ -0,0 +1,95 @@
+use crate::cli::abstraction::{
+	AuthArguments, DatabaseConnectionArguments, DatabaseSelectionArguments,
+};
+use crate::err::Error;
+use clap::Args;
+use glob::glob;
+use std::io::{Error as IoError, ErrorKind};
+use surrealdb::dbs::Capabilities;
+use surrealdb::engine::any::connect;
+use surrealdb::opt::auth::Root;
+use surrealdb::opt::Config;
+
+#[derive(Args, Debug)]
+pub struct MigrateCommandArguments {
+	#[arg(help = "Glob pattern for the files which define the migrations to apply")]
+	#[arg(index = 1)]
+	pattern: Option<String>,
+	#[command(flatten)]
+	conn: DatabaseConnectionArguments,
+	#[command(flatten)]
+	auth: AuthArguments,
+	#[command(flatten)]
+	sel: DatabaseSelectionArguments,
+}
+
+pub async fn init(
+	MigrateCommandArguments {
+		pattern,
+		conn: DatabaseConnectionArguments {
+			endpoint,
+		},
+		auth: AuthArguments {
+			username,
+			password,
+		},
+		sel: DatabaseSelectionArguments {
+			namespace: ns,
+			database: db,
+		},
+	}: MigrateCommandArguments,
+) -> Result<(), Error> {
+	// Initialize opentelemetry and logging
+	crate::telemetry::builder().with_log_level("info").init();
+	// Default datastore configuration for local engines
+	let config = Config::new().capabilities(Capabilities::all());
+
+	let client = if let Some((username, password)) = username.zip(password) {
+		let root = Root {
+			username: &username,
+			password: &password,
+		};
+
+		// Connect to the database engine with authentication
+		//
+		// * For local engines, here we enable authentication and in the signin below we actually authenticate.
+		// * For remote engines, we connect to the endpoint and then signin.
+		#[cfg(feature = "has-storage")]
+		let address = (endpoint, config.user(root));
+		#[cfg(not(feature = "has-storage"))]
+		let address = endpoint;
+		let client = connect(address).await?;
+
+		// Sign in to the server
+		client.signin(root).await?;
+		client
+	} else {
+		connect((endpoint, config)).await?
+	};
+
+	// Use the specified namespace / database
+	client.use_ns(ns).use_db(db).await?;
+	// Define the migrations from the specified files
+	if let Some(pattern) = pattern {
+		let entries = glob(&pattern).map_err(|e| {
+			Error::Io(IoError::new(
+				ErrorKind::Other,
+				format!("Error parsing glob pattern {pattern}: {e}"),
+			))
+		})?;
+		for entry in entries.flatten() {
+			let sql = tokio::fs::read_to_string(&entry).await?;
+			client.query(sql).await?.check()?;
+			info!("The migrations in {} were defined", entry.display());
+		}
+	}
+	// Apply the migrations which have not been applied
+	let mut res = client.query("APPLY MIGRATIONS").await?.check()?;
+	let applied: Vec<u64> = res.take(0)?;
+	match applied.is_empty() {
+		true => info!("The database is already up to date"),
+		false => info!("Applied the migrations {applied:?}"),
+	}
+	// Everything OK
+	Ok(())
+}
@@ -2,8 +2,10 @@ pub(crate) mod abstraction;
 mod backup;
 mod config;
 mod export;
+mod fmt;
 mod import;
 mod isready;
+mod migrate;
 mod sql;
 #[cfg(feature = "has-storage")]
 mod start;
@@ -18,8 +20,10 @@ use clap::{Parser, Subcommand};
 #[cfg(feature = "has-storage")]
 pub use config::CF;
 use export::ExportCommandArguments;
+use fmt::FmtCommandArguments;
 use import::ImportCommandArguments;
 use isready::IsReadyCommandArguments;
+use migrate::MigrateCommandArguments;
 use sql::SqlCommandArguments;
 #[cfg(feature = "has-storage")]
 use start::StartCommandArguments;
@@ -62,6 +66,8 @@ enum Commands {
 	Import(ImportCommandArguments),
 	#[command(about = "Export an existing database as a SurrealQL script")]
 	Export(ExportCommandArguments),
+	#[command(about = "Apply the migrations of an existing database")]
+	Migrate(MigrateCommandArguments),
 	#[command(about = "Output the command-line tool and remote server version information")]
 	Version(VersionCommandArguments),
 	#[command(about = "Upgrade to the latest stable version")]
@@ -75,6 +81,8 @@ enum Commands {
 	IsReady(IsReadyCommandArguments),
 	#[command(about = "Validate SurrealQL query files")]
 	Validate(ValidateCommandArguments),
+	#[command(about = "Format SurrealQL query files")]
+	Fmt(FmtCommandArguments),
 }
 
 pub async fn init() -> ExitCode {
@@ -85,11 +93,13 @@ pub async fn init() -> ExitCode {
 		Commands::Backup(args) => backup::init(args).await,
 		Commands::Import(args) => import::init(args).await,
 		Commands::Export(args) => export::init(args).await,
+		Commands::Migrate(args) => migrate::init(args).await,
 		Commands::Version(args) => version::init(args).await,
 		Commands::Upgrade(args) => upgrade::init(args).await,
 		Commands::Sql(args) => sql::init(args).await,
 		Commands::IsReady(args) => isready::init(args).await,
 		Commands::Validate(args) => validate::init(args).await,
+		Commands::Fmt(args) => fmt::init(args).await,
 	};
 	if let Err(e) = output {
 		error!("{}", e);
@@ -3,9 +3,10 @@ use crate::cli::abstraction::{
 };
 use crate::err::Error;
 use clap::Args;
+use rustyline::completion::Completer;
 use rustyline::error::ReadlineError;
 use rustyline::validate::{ValidationContext, ValidationResult, Validator};
-use rustyline::{Completer, Editor, Helper, Highlighter, Hinter};
+use rustyline::{Context, Editor, Helper, Highlighter, Hinter};
 use serde::Serialize;
 use serde_json::ser::PrettyFormatter;
 use surrealdb::dbs::Capabilities;
@@ -249,7 +250,7 @@ fn process(pretty: bool, json: bool, res: surrealdb::Result<Response>) -> Result
 	})
 }
 
-#[derive(Completer, Helper, Highlighter, Hinter)]
+#[derive(Helper, Highlighter, Hinter)]
 struct InputValidator {
 	/// If omitting semicolon causes newline.
 	multi: bool,
@@ -280,6 +281,26 @@ impl Validator for InputValidator {
 	}
 }
 
+impl Completer for InputValidator {
+	type Candidate = String;
+	fn complete(
+		&self,
+		line: &str,
+		pos: usize,
+		_: &Context<'_>,
+	) -> rustyline::Result<(usize, Vec<String>)> {
+		let out = sql::complete(line, pos);
+		// Match the case of the keyword which is being written
+		let lower = line[out.span.clone()].starts_with(|c: char| c.is_lowercase());
+		let keywords = out.keywords.iter().map(|k| match lower {
+			true => k.to_lowercase(),
+			false => k.to_string(),
+		});
+		let functions = out.functions.iter().map(|f| f.to_string());
+		Ok((out.span.start, keywords.chain(functions).collect()))
+	}
+}
+
 fn filter_line_continuations(line: &str) -> String {
 	line.replace("\\\n", "").replace("\\\r\n", "")
 }
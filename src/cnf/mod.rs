@@ -29,6 +29,10 @@ pub const APP_ENDPOINT: &str = "https://surrealdb.com/app";
 #[cfg(feature = "has-storage")]
 pub const MAX_CONCURRENT_CALLS: usize = 24;
 
+/// How many cursors can be held open at once on a WebSocket
+#[cfg(feature = "has-storage")]
+pub const MAX_CURSORS_PER_CONNECTION: usize = 32;
+
 /// Specifies the frequency with which ping messages should be sent to the client
 #[cfg(feature = "has-storage")]
 pub const WEBSOCKET_PING_FREQUENCY: Duration = Duration::from_secs(5);
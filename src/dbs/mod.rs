@@ -6,6 +6,7 @@ use std::time::Duration;
 use surrealdb::dbs::capabilities::{Capabilities, FuncTarget, NetTarget, Targets};
 use surrealdb::kvs::Datastore;
 use surrealdb::opt::auth::Root;
+use surrealdb::sql::ParseLimits;
 
 pub static DB: OnceLock<Datastore> = OnceLock::new();
 
@@ -23,6 +24,19 @@ pub struct StartCommandDbsOptions {
 	#[arg(env = "SURREAL_TRANSACTION_TIMEOUT", long)]
 	#[arg(value_parser = super::cli::validator::duration)]
 	transaction_timeout: Option<Duration>,
+	#[arg(help = "The maximum depth of nested values, such as subqueries, in a query")]
+	#[arg(env = "SURREAL_QUERY_MAX_DEPTH", long)]
+	query_max_depth: Option<u8>,
+	#[arg(help = "The maximum length of a query in bytes")]
+	#[arg(env = "SURREAL_QUERY_MAX_SIZE", long)]
+	query_max_size: Option<usize>,
+	#[arg(help = "The maximum number of statements in a query")]
+	#[arg(env = "SURREAL_QUERY_MAX_STATEMENTS", long)]
+	query_max_statements: Option<usize>,
+	#[arg(help = "The duration after which a statement is logged as slow, to recommend indexes")]
+	#[arg(env = "SURREAL_SLOW_QUERY_THRESHOLD", long)]
+	#[arg(value_parser = super::cli::validator::duration)]
+	slow_query_threshold: Option<Duration>,
 	#[arg(help = "Whether to enable authentication", help_heading = "Authentication")]
 	#[arg(env = "SURREAL_AUTH", long = "auth")]
 	#[arg(default_value_t = false)]
@@ -203,6 +217,10 @@ pub async fn init(
 		strict_mode,
 		query_timeout,
 		transaction_timeout,
+		query_max_depth,
+		query_max_size,
+		query_max_statements,
+		slow_query_threshold,
 		auth_enabled,
 		caps,
 	}: StartCommandDbsOptions,
@@ -219,6 +237,10 @@ pub async fn init(
 	if let Some(v) = transaction_timeout {
 		debug!("Maximum transaction processing timeout is {v:?}");
 	}
+	// Log specified slow query threshold
+	if let Some(v) = slow_query_threshold {
+		debug!("Slow query logging threshold is {v:?}");
+	}
 	// Log whether authentication is enabled
 	if auth_enabled {
 		info!("✅🔒 Authentication is enabled 🔒✅");
@@ -226,7 +248,16 @@ pub async fn init(
 		warn!("❌🔒 IMPORTANT: Authentication is disabled. This is not recommended for production use. 🔒❌");
 	}
 
-	let caps = caps.into();
+	// Set the limits on the queries which are parsed
+	let mut limits = ParseLimits::default()
+		.with_query_size(query_max_size)
+		.with_statements(query_max_statements);
+	if let Some(v) = query_max_depth {
+		limits = limits.with_depth(v);
+	}
+	debug!("Query parsing limits are {limits:?}");
+
+	let caps = Capabilities::from(caps).with_parse_limits(limits);
 	debug!("Server capabilities: {caps}");
 
 	// Parse and setup the desired kv datastore
@@ -236,6 +267,7 @@ pub async fn init(
 		.with_strict_mode(strict_mode)
 		.with_query_timeout(query_timeout)
 		.with_transaction_timeout(transaction_timeout)
+		.with_slow_query_threshold(slow_query_threshold)
 		.with_auth_enabled(auth_enabled)
 		.with_capabilities(caps);
 
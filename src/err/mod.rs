@@ -10,6 +10,7 @@ use serde_pack::encode::Error as PackError;
 use std::io::Error as IoError;
 use std::string::FromUtf8Error as Utf8Error;
 use surrealdb::error::Db as SurrealDbError;
+use surrealdb::error::Diagnostic;
 use surrealdb::iam::Error as SurrealIamError;
 use surrealdb::Error as SurrealError;
 use thiserror::Error;
@@ -37,6 +38,15 @@ pub enum Error {
 	#[error("The operation is unsupported")]
 	OperationUnsupported,
 
+	#[error("The specified cursor does not exist")]
+	InvalidCursor,
+
+	#[error("Too many cursors are open on this connection")]
+	TooManyCursors,
+
+	#[error("The specified prepared statement does not exist")]
+	InvalidStatement,
+
 	#[error("There was a problem with the database: {0}")]
 	Db(#[from] SurrealError),
 
@@ -86,6 +96,16 @@ impl From<surrealdb::error::Db> for Error {
 	}
 }
 
+impl Error {
+	/// Returns the structured diagnostic of a query which failed to parse
+	pub fn diagnostic(&self) -> Option<&Diagnostic> {
+		match self {
+			Error::Db(SurrealError::Db(e)) => e.diagnostic(),
+			_ => None,
+		}
+	}
+}
+
 impl Serialize for Error {
 	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
 	where
@@ -103,6 +123,8 @@ pub(super) struct Message {
 	description: Option<String>,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	information: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	diagnostic: Option<Diagnostic>,
 }
 
 impl IntoResponse for Error {
@@ -115,6 +137,7 @@ impl IntoResponse for Error {
 					details: Some("Authentication failed".to_string()),
 					description: Some("Your authentication details are invalid. Reauthenticate using valid authentication parameters.".to_string()),
 					information: Some(err.to_string()),
+					diagnostic: None,
 				})
 			),
 			err @ Error::Db(SurrealError::Db(SurrealDbError::IamError(SurrealIamError::NotAllowed { .. }))) => (
@@ -124,6 +147,7 @@ impl IntoResponse for Error {
 					details: Some("Forbidden".to_string()),
 					description: Some("Not allowed to do this.".to_string()),
 					information: Some(err.to_string()),
+					diagnostic: None,
 				})
 			),
 			Error::InvalidType => (
@@ -133,6 +157,7 @@ impl IntoResponse for Error {
 					details: Some("Unsupported media type".to_string()),
 					description: Some("The request needs to adhere to certain constraints. Refer to the documentation for supported content types.".to_string()),
 					information: None,
+					diagnostic: None,
 				}),
 			),
 			Error::InvalidStorage => (
@@ -142,6 +167,7 @@ impl IntoResponse for Error {
 					details: Some("Health check failed".to_string()),
 					description: Some("The database health check for this instance failed. There was an issue with the underlying storage engine.".to_string()),
 					information: Some(self.to_string()),
+					diagnostic: None,
 				}),
 			),
 			_ => (
@@ -151,6 +177,7 @@ impl IntoResponse for Error {
 					details: Some("Request problems detected".to_string()),
 					description: Some("There is a problem with your request. Refer to the documentation for further information.".to_string()),
 					information: Some(self.to_string()),
+					diagnostic: self.diagnostic().cloned(),
 				}),
 			),
 		}.into_response()
@@ -4,11 +4,20 @@ use axum::response::IntoResponse;
 use axum::routing::get;
 use axum::Router;
 use axum::{response::Response, Extension};
+use axum_extra::extract::Query;
 use bytes::Bytes;
 use http::StatusCode;
 use http_body::Body as HttpBody;
 use hyper::body::Body;
+use serde::Deserialize;
 use surrealdb::dbs::Session;
+use surrealdb::kvs::ExportOptions;
+
+#[derive(Default, Deserialize, Debug, Clone)]
+struct ExportParams {
+	#[serde(default)]
+	pub stable: bool,
+}
 
 pub(super) fn router<S, B>() -> Router<S, B>
 where
@@ -20,6 +29,7 @@ where
 
 async fn handler(
 	Extension(session): Extension<Session>,
+	Query(params): Query<ExportParams>,
 ) -> Result<impl IntoResponse, impl IntoResponse> {
 	// Get the datastore reference
 	let db = DB.get().unwrap();
@@ -37,8 +47,11 @@ async fn handler(
 	let (mut chn, bdy) = Body::channel();
 	// Create a new bounded channel
 	let (snd, rcv) = surrealdb::channel::new(1);
+	// Scan each batch of records in its own transaction, if requested
+	let cfg = ExportOptions::new().with_stable_iteration(params.stable);
 
-	let export_job = db.export(&session, nsv, dbv, snd).await.map_err(Error::from)?;
+	let export_job =
+		db.export_with_options(&session, nsv, dbv, cfg, snd).await.map_err(Error::from)?;
 	// Spawn a new database export job
 	tokio::spawn(export_job);
 	// Process all processed values
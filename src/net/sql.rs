@@ -16,7 +16,7 @@ use axum::TypedHeader;
 use bytes::Bytes;
 use futures::{SinkExt, StreamExt};
 use http_body::Body as HttpBody;
-use surrealdb::dbs::Session;
+use surrealdb::dbs::{DetailedResponse, Response, Session};
 use tower_http::limit::RequestBodyLimitLayer;
 
 use super::headers::Accept;
@@ -50,9 +50,9 @@ async fn post_handler(
 	match db.execute(sql, &session, params.0.parse().into()).await {
 		Ok(res) => match output.as_deref() {
 			// Simple serialization
-			Some(Accept::ApplicationJson) => Ok(output::json(&output::simplify(res))),
-			Some(Accept::ApplicationCbor) => Ok(output::cbor(&output::simplify(res))),
-			Some(Accept::ApplicationPack) => Ok(output::pack(&output::simplify(res))),
+			Some(Accept::ApplicationJson) => Ok(output::json(&output::simplify(detailed(&res)))),
+			Some(Accept::ApplicationCbor) => Ok(output::cbor(&output::simplify(detailed(&res)))),
+			Some(Accept::ApplicationPack) => Ok(output::pack(&output::simplify(detailed(&res)))),
 			// Internal serialization
 			Some(Accept::Surrealdb) => Ok(output::full(&res)),
 			// An incorrect content-type was requested
@@ -82,7 +82,7 @@ async fn handle_socket(ws: WebSocket, session: Session) {
 				// Execute the received sql query
 				let _ = match db.execute(sql, &session, None).await {
 					// Convert the response to JSON
-					Ok(v) => match serde_json::to_string(&v) {
+					Ok(v) => match serde_json::to_string(&detailed(&v)) {
 						// Send the JSON response to the client
 						Ok(v) => tx.send(Message::Text(v)).await,
 						// There was an error converting to JSON
@@ -95,3 +95,8 @@ async fn handle_socket(ws: WebSocket, session: Session) {
 		}
 	}
 }
+
+/// Include the code and category of each error in the query responses
+fn detailed(res: &[Response]) -> Vec<DetailedResponse<'_>> {
+	res.iter().map(Response::detailed).collect()
+}
@@ -19,6 +19,23 @@ pub fn init(ct: CancellationToken) -> JoinHandle<()> {
 	// This requires the nodes::init function to be called after the dbs::init function.
 	let dbs = crate::dbs::DB.get().unwrap();
 
+	// Heartbeats are sent from their own task, so that a slow tick
+	// does not cause this node to be considered dead by the cluster
+	tokio::spawn({
+		let ct = ct.clone();
+		async move {
+			loop {
+				if let Err(e) = dbs.heartbeat().await {
+					error!("Error sending node heartbeat: {}", e);
+				}
+				tokio::select! {
+					_ = ct.cancelled() => break,
+					_ = tokio::time::sleep(tick_interval) => {}
+				}
+			}
+		}
+	});
+
 	tokio::spawn(async move {
 		loop {
 			if let Err(e) = dbs.tick().await {
@@ -6,6 +6,7 @@ pub trait Take {
 	fn needs_two(self) -> Result<(Value, Value), ()>;
 	fn needs_one_or_two(self) -> Result<(Value, Value), ()>;
 	fn needs_one_two_or_three(self) -> Result<(Value, Value, Value), ()>;
+	fn needs_two_or_three(self) -> Result<(Value, Value, Value), ()>;
 }
 
 impl Take for Array {
@@ -57,4 +58,16 @@ impl Take for Array {
 			(_, _, _) => Ok((Value::None, Value::None, Value::None)),
 		}
 	}
+	/// Convert the array to three arguments
+	fn needs_two_or_three(self) -> Result<(Value, Value, Value), ()> {
+		if self.len() < 2 {
+			return Err(());
+		}
+		let mut x = self.into_iter();
+		match (x.next(), x.next(), x.next()) {
+			(Some(a), Some(b), Some(c)) => Ok((a, b, c)),
+			(Some(a), Some(b), None) => Ok((a, b, Value::None)),
+			(_, _, _) => Ok((Value::None, Value::None, Value::None)),
+		}
+	}
 }
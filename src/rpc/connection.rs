@@ -84,7 +84,11 @@ impl Connection {
 		// Add this WebSocket to the list
 		WEBSOCKETS.write().await.insert(
 			ws_id,
-			WebSocketRef(internal_sender.clone(), rpc.read().await.graceful_shutdown.clone()),
+			WebSocketRef(
+				internal_sender.clone(),
+				rpc.read().await.graceful_shutdown.clone(),
+				rpc.read().await.processor.options(),
+			),
 		);
 		let mut live_queries_to_gc = Vec::new();
 		// Remove all live queries
@@ -251,7 +255,10 @@ impl Connection {
 							// Find which WebSocket the notification belongs to
 							if let Some(ws_id) = LIVE_QUERIES.read().await.get(&notification.id) {
 								// Check to see if the WebSocket exists
-								if let Some(WebSocketRef(ws, _)) = WEBSOCKETS.read().await.get(ws_id) {
+								if let Some(WebSocketRef(ws, _, op)) = WEBSOCKETS.read().await.get(ws_id) {
+									// Output any NONE values as set on the session of the live query
+									let nones = op.get().none_output.unwrap_or_default();
+									let notification = notification.with_none_output(nones);
 									// Serialize the message to send
 									let message = success(None, notification);
 									// Get the current output format
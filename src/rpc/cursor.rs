@@ -0,0 +1,164 @@
+use crate::dbs::DB;
+use crate::err::Error;
+use std::collections::BTreeMap;
+use std::ops::Bound;
+use surrealdb::dbs::Session;
+use surrealdb::sql::statements::SelectStatement;
+use surrealdb::sql::{
+	Array, Field, Id, Limit, Query, Range, Start, Statement, Statements, Value, Values,
+};
+
+/// A paginated SELECT statement, which is held open on a
+/// WebSocket connection, and which returns its results in
+/// batches of a fixed size instead of all at once.
+pub struct Cursor {
+	/// The SELECT statement being paginated
+	stm: SelectStatement,
+	/// The query parameters for the statement
+	vars: BTreeMap<String, Value>,
+	/// The number of records returned in each batch
+	size: usize,
+	/// The position of the next batch in the results
+	next: usize,
+	/// The position after the last requested result, if the statement has a LIMIT clause
+	end: Option<usize>,
+	/// The table whose records are iterated in key order, if the statement allows it
+	table: Option<String>,
+	/// The id of the last returned record, after which the next batch continues
+	last: Option<Id>,
+}
+
+impl Cursor {
+	/// Open a cursor for a single SELECT statement. The size of the batches
+	/// defaults to the FETCH SIZE clause of the statement, if specified.
+	pub async fn new(
+		sql: Value,
+		size: Option<usize>,
+		vars: BTreeMap<String, Value>,
+		session: &Session,
+	) -> Result<Self, Error> {
+		// Parse the SQL query text, within the limits of the datastore
+		let mut ast = match sql {
+			Value::Query(v) => v,
+			Value::Strand(v) => {
+				surrealdb::sql::parse_with_limits(&v, DB.get().unwrap().parse_limits())?
+			}
+			_ => return Err(Error::Request),
+		};
+		// Only a single SELECT statement can be paginated
+		let mut stm = match ast.0 .0.pop() {
+			Some(Statement::Select(v)) if ast.is_empty() && v.explain.is_none() => v,
+			_ => return Err(Error::Request),
+		};
+		// Batches must contain at least one record
+		let size = match size.or(stm.fetch_size.map(|v| v as usize)) {
+			Some(size) if size > 0 => size,
+			_ => return Err(Error::Request),
+		};
+		// Compute the START and LIMIT clauses once
+		let next = match stm.start.take() {
+			Some(Start(v)) => position(v, &vars, session).await?,
+			None => 0,
+		};
+		let end = match stm.limit.take() {
+			Some(Limit(v)) => Some(next + position(v, &vars, session).await?),
+			None => None,
+		};
+		Ok(Self {
+			table: resumable(&stm),
+			stm,
+			vars,
+			size,
+			next,
+			end,
+			last: None,
+		})
+	}
+
+	/// Fetch the next batch of results, and whether this was the last batch
+	pub async fn next(&mut self, session: &Session) -> Result<(Value, bool), Error> {
+		// Get a database reference
+		let kvs = DB.get().unwrap();
+		// Don't fetch beyond the LIMIT clause
+		let size = match self.end {
+			Some(end) => self.size.min(end.saturating_sub(self.next)),
+			None => self.size,
+		};
+		if size == 0 {
+			return Ok((Value::from(Array::new()), true));
+		}
+		// Fetch one more record than requested, to detect the last batch
+		let mut stm = self.stm.clone();
+		match (&self.table, &self.last) {
+			// Continue after the last returned record
+			(Some(tb), Some(id)) => {
+				stm.what = Values(vec![Value::Range(Box::new(Range {
+					tb: tb.clone(),
+					beg: Bound::Excluded(id.clone()),
+					end: Bound::Unbounded,
+				}))]);
+			}
+			// Skip the records which have already been returned
+			_ => stm.start = Some(Start(self.next.into())),
+		}
+		stm.limit = Some(Limit((size + 1).into()));
+		let ast = Query(Statements(vec![Statement::Select(stm)]));
+		// Execute the query on the database
+		let mut res = kvs.process(ast, session, Some(self.vars.clone())).await?;
+		// Extract the first query result
+		let mut res: Array = match res.remove(0).result? {
+			Value::Array(v) => v,
+			v => return Ok((v, true)),
+		};
+		// Check if there are further results
+		let done = res.len() <= size;
+		res.truncate(size);
+		self.next += res.len();
+		// Remember where the next batch continues
+		if let Some(tb) = &self.table {
+			self.last = match res.last().map(Value::rid) {
+				Some(Value::Thing(v)) if v.tb == *tb => Some(v.id),
+				_ => None,
+			};
+		}
+		// Return the batch to the client
+		Ok((res.into(), done))
+	}
+}
+
+/// Returns the table of a statement whose records are returned in key order, so that
+/// each batch can continue after the last returned record, instead of skipping over
+/// all of the records which have already been returned
+fn resumable(stm: &SelectStatement) -> Option<String> {
+	// The records must be output in the order of their keys
+	if stm.order.is_some() || stm.group.is_some() || stm.split.is_some() || stm.parallel {
+		return None;
+	}
+	// The records must be output with their ids
+	if stm.expr.1 || !stm.expr.0.iter().any(|f| matches!(f, Field::All)) {
+		return None;
+	}
+	if stm.omit.as_ref().is_some_and(|v| v.iter().any(|i| i.to_string() == "id")) {
+		return None;
+	}
+	// The records must be selected from a single table
+	match stm.what.0.as_slice() {
+		[Value::Table(t)] => Some(t.0.clone()),
+		_ => None,
+	}
+}
+
+/// Compute the value of a START or LIMIT clause
+async fn position(
+	val: Value,
+	vars: &BTreeMap<String, Value>,
+	session: &Session,
+) -> Result<usize, Error> {
+	// Get a database reference
+	let kvs = DB.get().unwrap();
+	// Compute the clause with the query parameters
+	match kvs.compute(val, session, Some(vars.clone())).await? {
+		Value::Number(v) if v.is_integer() && !v.is_negative() => Ok(v.as_usize()),
+		_ => Err(Error::Request),
+	}
+}
@@ -1,5 +1,6 @@
 pub mod args;
 pub mod connection;
+pub mod cursor;
 pub mod processor;
 pub mod request;
 pub mod res;
@@ -9,6 +10,7 @@ use std::{collections::HashMap, time::Duration};
 use axum::extract::ws::Message;
 use once_cell::sync::Lazy;
 use surrealdb::channel::Sender;
+use surrealdb::dbs::SharedOptions;
 use tokio::sync::RwLock;
 use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
@@ -16,7 +18,7 @@ use uuid::Uuid;
 static CONN_CLOSED_ERR: &str = "Connection closed normally";
 
 // Mapping of WebSocketID to WebSocket
-pub struct WebSocketRef(Sender<Message>, CancellationToken);
+pub struct WebSocketRef(Sender<Message>, CancellationToken, SharedOptions);
 type WebSockets = RwLock<HashMap<Uuid, WebSocketRef>>;
 // Mapping of LiveQueryID to WebSocketID
 type LiveQueries = RwLock<HashMap<Uuid, Uuid>>;
@@ -26,7 +28,7 @@ pub(crate) static LIVE_QUERIES: Lazy<LiveQueries> = Lazy::new(LiveQueries::defau
 
 pub(crate) async fn graceful_shutdown() {
 	// Close all WebSocket connections. Queued messages will still be processed.
-	for (_, WebSocketRef(_, cancel_token)) in WEBSOCKETS.read().await.iter() {
+	for (_, WebSocketRef(_, cancel_token, _)) in WEBSOCKETS.read().await.iter() {
 		cancel_token.cancel();
 	}
 
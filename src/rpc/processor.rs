@@ -1,14 +1,19 @@
+use crate::cnf::MAX_CURSORS_PER_CONNECTION;
 use crate::cnf::PKG_NAME;
 use crate::cnf::PKG_VERSION;
 use crate::dbs::DB;
 use crate::err::Error;
 use crate::rpc::args::Take;
+use crate::rpc::cursor::Cursor;
 use crate::rpc::LIVE_QUERIES;
 use std::collections::BTreeMap;
+use std::collections::HashMap;
 
 use surrealdb::dbs::QueryType;
 use surrealdb::dbs::Response;
+use surrealdb::dbs::SharedOptions;
 use surrealdb::sql::Object;
+use surrealdb::sql::Query;
 use surrealdb::sql::Strand;
 use surrealdb::sql::Value;
 use surrealdb::{dbs::Session, sql::Array};
@@ -21,6 +26,8 @@ pub struct Processor {
 	session: Session,
 	pub format: OutputFormat,
 	vars: BTreeMap<String, Value>,
+	cursors: HashMap<Uuid, Cursor>,
+	prepared: HashMap<Uuid, String>,
 }
 
 impl Processor {
@@ -30,6 +37,8 @@ impl Processor {
 			session,
 			format,
 			vars,
+			cursors: HashMap::new(),
+			prepared: HashMap::new(),
 		}
 	}
 
@@ -82,6 +91,18 @@ impl Processor {
 				Ok(v) => self.kill(v).await.map(Into::into).map_err(Into::into),
 				_ => Err(Failure::INVALID_PARAMS),
 			},
+			// Cancel a running query using its id
+			"kill_query" => match params.needs_one() {
+				Ok(v) if v.is_uuid() || v.is_strand() => {
+					self.kill_query(v).await.map(Into::into).map_err(Into::into)
+				}
+				_ => Err(Failure::INVALID_PARAMS),
+			},
+			// List the running queries, with their ids and elapsed time
+			"queries" => match params.len() {
+				0 => self.queries().await.map(Into::into).map_err(Into::into),
+				_ => Err(Failure::INVALID_PARAMS),
+			},
 			// Setup a live query on a specific table
 			"live" => match params.needs_one_or_two() {
 				Ok((v, d)) if v.is_table() => {
@@ -159,6 +180,85 @@ impl Processor {
 				}
 				_ => Err(Failure::INVALID_PARAMS),
 			},
+			// Open a cursor which returns the results of a SELECT statement in batches
+			"cursor" => match params.needs_one_two_or_three() {
+				Ok((v, Value::Number(n), o))
+					if (v.is_strand() || v.is_query())
+						&& n.is_integer() && n.is_positive()
+						&& (o.is_none_or_null() || o.is_object()) =>
+				{
+					self.cursor(v, Some(n.as_usize()), o).await.map(Into::into).map_err(Into::into)
+				}
+				Ok((v, Value::Object(o), Value::None)) if v.is_strand() || v.is_query() => {
+					self.cursor(v, None, Value::Object(o)).await.map(Into::into).map_err(Into::into)
+				}
+				Ok((v, n, o))
+					if (v.is_strand() || v.is_query())
+						&& n.is_none_or_null()
+						&& (o.is_none_or_null() || o.is_object()) =>
+				{
+					self.cursor(v, None, o).await.map(Into::into).map_err(Into::into)
+				}
+				_ => Err(Failure::INVALID_PARAMS),
+			},
+			// Fetch the next batch of results from a cursor
+			"next" => match params.needs_one() {
+				Ok(v) if v.is_uuid() || v.is_strand() => {
+					self.next(v).await.map(Into::into).map_err(Into::into)
+				}
+				_ => Err(Failure::INVALID_PARAMS),
+			},
+			// Close a cursor before all of its results have been fetched
+			"close" => match params.needs_one() {
+				Ok(v) if v.is_uuid() || v.is_strand() => {
+					self.close(v).await.map(Into::into).map_err(Into::into)
+				}
+				_ => Err(Failure::INVALID_PARAMS),
+			},
+			// Prepare a query which is executed repeatedly with different parameters
+			"prepare" => match params.needs_one() {
+				Ok(Value::Strand(v)) => self.prepare(v).await.map(Into::into).map_err(Into::into),
+				_ => Err(Failure::INVALID_PARAMS),
+			},
+			// Execute a prepared query with the specified parameters
+			"execute" => match params.needs_one_or_two() {
+				Ok((v, o))
+					if (v.is_uuid() || v.is_strand()) && (o.is_none_or_null() || o.is_object()) =>
+				{
+					self.execute(v, o).await.map(Into::into).map_err(Into::into)
+				}
+				_ => Err(Failure::INVALID_PARAMS),
+			},
+			// Release a prepared query which is no longer needed
+			"unprepare" => match params.needs_one() {
+				Ok(v) if v.is_uuid() || v.is_strand() => {
+					self.unprepare(v).await.map(Into::into).map_err(Into::into)
+				}
+				_ => Err(Failure::INVALID_PARAMS),
+			},
+			// Register a named query which any session can run by name
+			"register" => match params.needs_two_or_three() {
+				Ok((Value::Strand(n), Value::Strand(v), o))
+					if o.is_none_or_null() || o.is_object() =>
+				{
+					self.register(n, v, o).await.map(Into::into).map_err(Into::into)
+				}
+				_ => Err(Failure::INVALID_PARAMS),
+			},
+			// Remove a registered named query
+			"unregister" => match params.needs_one() {
+				Ok(Value::Strand(n)) => {
+					self.unregister(n).await.map(Into::into).map_err(Into::into)
+				}
+				_ => Err(Failure::INVALID_PARAMS),
+			},
+			// Run a registered named query with the specified parameters
+			"run" => match params.needs_one_or_two() {
+				Ok((Value::Strand(n), o)) if o.is_none_or_null() || o.is_object() => {
+					self.run(n, o).await.map(Into::into).map_err(Into::into)
+				}
+				_ => Err(Failure::INVALID_PARAMS),
+			},
 			_ => Err(Failure::METHOD_NOT_FOUND),
 		}
 	}
@@ -179,10 +279,10 @@ impl Processor {
 
 	async fn yuse(&mut self, ns: Value, db: Value) -> Result<Value, Error> {
 		if let Value::Strand(ns) = ns {
-			self.session.ns = Some(ns.0);
+			self.session.ns = Some(ns.as_string());
 		}
 		if let Value::Strand(db) = db {
-			self.session.db = Some(db.0);
+			self.session.db = Some(db.as_string());
 		}
 		Ok(Value::None)
 	}
@@ -210,7 +310,7 @@ impl Processor {
 
 	async fn authenticate(&mut self, token: Strand) -> Result<Value, Error> {
 		let kvs = DB.get().unwrap();
-		surrealdb::iam::verify::token(kvs, &mut self.session, &token.0).await?;
+		surrealdb::iam::verify::token(kvs, &mut self.session, token.as_str()).await?;
 		Ok(Value::None)
 	}
 
@@ -243,15 +343,15 @@ impl Processor {
 		// Compute the specified parameter
 		match kvs.compute(val, &self.session, var).await? {
 			// Remove the variable if undefined
-			Value::None => self.vars.remove(&key.0),
+			Value::None => self.vars.remove(key.as_str()),
 			// Store the variable if defined
-			v => self.vars.insert(key.0, v),
+			v => self.vars.insert(key.as_string(), v),
 		};
 		Ok(Value::Null)
 	}
 
 	async fn unset(&mut self, key: Strand) -> Result<Value, Error> {
-		self.vars.remove(&key.0);
+		self.vars.remove(key.as_str());
 		Ok(Value::Null)
 	}
 
@@ -259,7 +359,7 @@ impl Processor {
 	// Methods for live queries
 	// ------------------------------
 
-	async fn kill(&self, id: Value) -> Result<Value, Error> {
+	async fn kill(&mut self, id: Value) -> Result<Value, Error> {
 		// Specify the SQL query string
 		let sql = "KILL $id";
 		// Specify the query parameters
@@ -277,7 +377,42 @@ impl Processor {
 		}
 	}
 
-	async fn live(&self, tb: Value, diff: Value) -> Result<Value, Error> {
+	// ------------------------------
+	// Methods for running queries
+	// ------------------------------
+
+	async fn kill_query(&mut self, id: Value) -> Result<Value, Error> {
+		// Specify the SQL query string
+		let sql = "KILL QUERY $id";
+		// Specify the query parameters
+		let var = map! {
+			String::from("id") => id,
+			=> &self.vars
+		};
+		// Execute the query on the database
+		let mut res = self.query_with(Value::from(sql), Object::from(var)).await?;
+		// Extract the first query result
+		let response = res.remove(0);
+		match response.result {
+			Ok(v) => Ok(v),
+			Err(e) => Err(Error::from(e)),
+		}
+	}
+
+	async fn queries(&mut self) -> Result<Value, Error> {
+		// Specify the SQL query string
+		let sql = "SHOW QUERIES";
+		// Execute the query on the database
+		let mut res = self.query(Value::from(sql)).await?;
+		// Extract the first query result
+		let response = res.remove(0);
+		match response.result {
+			Ok(v) => Ok(v),
+			Err(e) => Err(Error::from(e)),
+		}
+	}
+
+	async fn live(&mut self, tb: Value, diff: Value) -> Result<Value, Error> {
 		// Specify the SQL query string
 		let sql = match diff.is_true() {
 			true => "LIVE SELECT DIFF FROM $tb",
@@ -511,7 +646,7 @@ impl Processor {
 	// Methods for querying
 	// ------------------------------
 
-	async fn query(&self, sql: Value) -> Result<Vec<Response>, Error> {
+	async fn query(&mut self, sql: Value) -> Result<Vec<Response>, Error> {
 		// Get a database reference
 		let kvs = DB.get().unwrap();
 		// Specify the query parameters
@@ -527,11 +662,13 @@ impl Processor {
 		for response in &res {
 			self.handle_live_query_results(response).await;
 		}
+		// Use the output format set with SET OPTION
+		self.update_format();
 		// Return the result to the client
 		Ok(res)
 	}
 
-	async fn query_with(&self, sql: Value, mut vars: Object) -> Result<Vec<Response>, Error> {
+	async fn query_with(&mut self, sql: Value, mut vars: Object) -> Result<Vec<Response>, Error> {
 		// Get a database reference
 		let kvs = DB.get().unwrap();
 		// Specify the query parameters
@@ -546,6 +683,158 @@ impl Processor {
 		for response in &res {
 			self.handle_live_query_results(response).await;
 		}
+		// Use the output format set with SET OPTION
+		self.update_format();
+		// Return the result to the client
+		Ok(res)
+	}
+
+	// ------------------------------
+	// Methods for cursors
+	// ------------------------------
+
+	async fn cursor(
+		&mut self,
+		sql: Value,
+		size: Option<usize>,
+		vars: Value,
+	) -> Result<Value, Error> {
+		// Limit the number of cursors held open by this connection
+		if self.cursors.len() >= MAX_CURSORS_PER_CONNECTION {
+			return Err(Error::TooManyCursors);
+		}
+		// Specify the query parameters
+		let var = match vars {
+			Value::Object(mut vars) => mrg! { vars.0, &self.vars },
+			_ => self.vars.clone(),
+		};
+		// Open the cursor and fetch the first batch
+		let id = Uuid::new_v4();
+		let mut cursor = Cursor::new(sql, size, var, &self.session).await?;
+		let (res, done) = cursor.next(&self.session).await?;
+		// Keep the cursor open if there are further results
+		if !done {
+			self.cursors.insert(id, cursor);
+		}
+		// Return the result to the client
+		Ok(cursor_batch(id, res, done))
+	}
+
+	async fn next(&mut self, id: Value) -> Result<Value, Error> {
+		// Find the specified cursor
+		let id = cursor_id(id)?;
+		let cursor = self.cursors.get_mut(&id).ok_or(Error::InvalidCursor)?;
+		// Fetch the next batch of results
+		let (res, done) = cursor.next(&self.session).await?;
+		// Close the cursor after the last batch
+		if done {
+			self.cursors.remove(&id);
+		}
+		// Return the result to the client
+		Ok(cursor_batch(id, res, done))
+	}
+
+	async fn close(&mut self, id: Value) -> Result<Value, Error> {
+		// Remove the specified cursor
+		let id = cursor_id(id)?;
+		self.cursors.remove(&id).ok_or(Error::InvalidCursor)?;
+		Ok(Value::None)
+	}
+
+	// ------------------------------
+	// Methods for prepared statements
+	// ------------------------------
+
+	async fn prepare(&mut self, sql: Strand) -> Result<Value, Error> {
+		// Get a database reference
+		let kvs = DB.get().unwrap();
+		// Parse the query, checking that it is valid
+		kvs.prepare(&sql)?;
+		// Store the query text on this connection
+		let id = Uuid::new_v4();
+		self.prepared.insert(id, sql.as_string());
+		// Return the statement id to the client
+		Ok(Value::Uuid(id.into()))
+	}
+
+	async fn execute(&mut self, id: Value, vars: Value) -> Result<Vec<Response>, Error> {
+		// Get a database reference
+		let kvs = DB.get().unwrap();
+		// Find the specified prepared statement
+		let id = statement_id(id)?;
+		let sql = self.prepared.get(&id).ok_or(Error::InvalidStatement)?;
+		// Specify the query parameters
+		let var = match vars {
+			Value::Object(mut vars) => mrg! { vars.0, &self.vars },
+			_ => self.vars.clone(),
+		};
+		// Reuse the parsed query, unless the schema has changed
+		let ast = kvs.prepare(sql)?;
+		// Execute the query on the database
+		let res = kvs.process(Query::clone(&ast), &self.session, Some(var)).await?;
+		// Post-process hooks for web layer
+		for response in &res {
+			self.handle_live_query_results(response).await;
+		}
+		// Use the output format set with SET OPTION
+		self.update_format();
+		// Return the result to the client
+		Ok(res)
+	}
+
+	async fn unprepare(&mut self, id: Value) -> Result<Value, Error> {
+		// Remove the specified prepared statement
+		let id = statement_id(id)?;
+		self.prepared.remove(&id).ok_or(Error::InvalidStatement)?;
+		Ok(Value::None)
+	}
+
+	// ------------------------------
+	// Methods for named queries
+	// ------------------------------
+
+	async fn register(&mut self, name: Strand, sql: Strand, params: Value) -> Result<Value, Error> {
+		// Get a database reference
+		let kvs = DB.get().unwrap();
+		// Parse the type of each declared parameter
+		let params = match params {
+			Value::Object(o) => {
+				o.0.into_iter()
+					.map(|(k, v)| match v {
+						Value::Strand(v) => Ok((k, surrealdb::sql::kind(&v)?)),
+						_ => Err(Error::InvalidType),
+					})
+					.collect::<Result<_, Error>>()?
+			}
+			_ => BTreeMap::new(),
+		};
+		// Register the query with the authorization of this session
+		kvs.register_query(&name, &sql, params, &self.session).await?;
+		Ok(Value::None)
+	}
+
+	async fn unregister(&mut self, name: Strand) -> Result<Value, Error> {
+		// Get a database reference
+		let kvs = DB.get().unwrap();
+		// Remove the registered query
+		kvs.unregister_query(&name, &self.session).await?;
+		Ok(Value::None)
+	}
+
+	async fn run(&mut self, name: Strand, vars: Value) -> Result<Vec<Response>, Error> {
+		// Get a database reference
+		let kvs = DB.get().unwrap();
+		// Only the parameters declared by the query are accepted
+		let var = match vars {
+			Value::Object(vars) => Some(vars.0),
+			_ => None,
+		};
+		// Execute the registered query on the database
+		let res = kvs.execute_query(&name, &self.session, var).await?;
+		// Post-process hooks for web layer
+		for response in &res {
+			self.handle_live_query_results(response).await;
+		}
 		// Return the result to the client
 		Ok(res)
 	}
@@ -554,6 +843,20 @@ impl Processor {
 	// Private methods
 	// ------------------------------
 
+	/// The execution options of the session, which are shared with its live query notifications
+	pub fn options(&self) -> SharedOptions {
+		self.session.op.clone()
+	}
+
+	fn update_format(&mut self) {
+		match self.session.options().format.as_deref() {
+			Some("json") => self.format = OutputFormat::Json,
+			Some("cbor") => self.format = OutputFormat::Cbor,
+			Some("pack") => self.format = OutputFormat::Pack,
+			_ => (),
+		}
+	}
+
 	async fn handle_live_query_results(&self, res: &Response) {
 		match &res.query_type {
 			QueryType::Live => {
@@ -575,3 +878,30 @@ impl Processor {
 		}
 	}
 }
+
+/// Get the id of a cursor from an RPC parameter
+fn cursor_id(id: Value) -> Result<Uuid, Error> {
+	match id {
+		Value::Uuid(v) => Ok(v.0),
+		Value::Strand(v) => Uuid::parse_str(&v).map_err(|_| Error::InvalidCursor),
+		_ => Err(Error::InvalidCursor),
+	}
+}
+
+/// Output a batch of results from a cursor
+fn cursor_batch(id: Uuid, result: Value, done: bool) -> Value {
+	Value::from(map! {
+		String::from("id") => Value::Uuid(id.into()),
+		String::from("result") => result,
+		String::from("done") => Value::from(done),
+	})
+}
+
+/// Get the id of a prepared statement from an RPC parameter
+fn statement_id(id: Value) -> Result<Uuid, Error> {
+	match id {
+		Value::Uuid(v) => Ok(v.0),
+		Value::Strand(v) => Uuid::parse_str(&v).map_err(|_| Error::InvalidStatement),
+		_ => Err(Error::InvalidStatement),
+	}
+}
@@ -6,6 +6,7 @@ use std::borrow::Cow;
 use surrealdb::channel::Sender;
 use surrealdb::dbs;
 use surrealdb::dbs::Notification;
+use surrealdb::error::Diagnostic;
 use surrealdb::sql;
 use surrealdb::sql::Value;
 use tracing::Span;
@@ -74,7 +75,10 @@ impl Response {
 		let mut value = match self.result {
 			Ok(data) => {
 				let value = match data {
-					Data::Query(vec) => sql::to_value(vec).unwrap(),
+					Data::Query(vec) => {
+						let vec: Vec<_> = vec.iter().map(dbs::Response::detailed).collect();
+						sql::to_value(vec).unwrap()
+					}
 					Data::Live(notification) => sql::to_value(notification).unwrap(),
 					Data::Other(value) => value,
 				};
@@ -143,6 +147,8 @@ impl Response {
 pub struct Failure {
 	code: i64,
 	message: Cow<'static, str>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	data: Option<Diagnostic>,
 }
 
 #[allow(dead_code)]
@@ -150,26 +156,31 @@ impl Failure {
 	pub const PARSE_ERROR: Failure = Failure {
 		code: -32700,
 		message: Cow::Borrowed("Parse error"),
+		data: None,
 	};
 
 	pub const INVALID_REQUEST: Failure = Failure {
 		code: -32600,
 		message: Cow::Borrowed("Invalid Request"),
+		data: None,
 	};
 
 	pub const METHOD_NOT_FOUND: Failure = Failure {
 		code: -32601,
 		message: Cow::Borrowed("Method not found"),
+		data: None,
 	};
 
 	pub const INVALID_PARAMS: Failure = Failure {
 		code: -32602,
 		message: Cow::Borrowed("Invalid params"),
+		data: None,
 	};
 
 	pub const INTERNAL_ERROR: Failure = Failure {
 		code: -32603,
 		message: Cow::Borrowed("Internal error"),
+		data: None,
 	};
 
 	pub fn custom<S>(message: S) -> Failure
@@ -179,6 +190,7 @@ impl Failure {
 		Failure {
 			code: -32000,
 			message: message.into(),
+			data: None,
 		}
 	}
 }
@@ -201,7 +213,10 @@ pub fn failure(id: Option<Value>, err: Failure) -> Response {
 
 impl From<err::Error> for Failure {
 	fn from(err: err::Error) -> Self {
-		Failure::custom(err.to_string())
+		Failure {
+			data: err.diagnostic().cloned(),
+			..Failure::custom(err.to_string())
+		}
 	}
 }
 
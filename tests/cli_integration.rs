@@ -65,6 +65,14 @@ mod cli_integration {
 			assert!(output.contains("UPDATE thing:one CONTENT { id: thing:one };"));
 		}
 
+		info!("* Export to stdout with stable iteration");
+		{
+			let args = format!("export --conn http://{addr} {creds} --ns N --db D --stable -");
+			let output = common::run(&args).output().expect("failed to run stable export: {args}");
+			assert!(output.contains("DEFINE TABLE thing SCHEMALESS PERMISSIONS NONE;"));
+			assert!(output.contains("UPDATE thing:one CONTENT { id: thing:one };"));
+		}
+
 		info!("* Export to file");
 		let exported = {
 			let exported = common::tmp_file("exported.surql");
@@ -89,6 +97,23 @@ mod cli_integration {
 			);
 		}
 
+		info!("* Apply migrations from a file");
+		{
+			let file = common::tmp_file("migrations.surql");
+			fs::write(&file, "DEFINE MIGRATION 1 { CREATE thing:migrated; };").unwrap();
+			let args = format!("migrate --conn http://{addr} {creds} --ns N --db D {file}");
+			common::run(&args).output().expect("failed to run migrate: {args}");
+			// The applied migration is not applied again
+			let args = format!("migrate --conn http://{addr} {creds} --ns N --db D {file}");
+			common::run(&args).output().expect("failed to run migrate: {args}");
+			let args = format!("sql --conn http://{addr} {creds} --ns N --db D");
+			assert_eq!(
+				common::run(&args).input("SELECT * FROM thing:migrated;\n").output(),
+				Ok("[{ id: thing:migrated }]\n\n".to_owned()),
+				"failed to send sql: {args}"
+			);
+		}
+
 		info!("* Unfinished backup CLI");
 		{
 			let file = common::tmp_file("backup.db");
@@ -406,6 +431,34 @@ mod cli_integration {
 		assert!(common::run_in_dir("validate", &temp_dir).output().is_err());
 	}
 
+	#[test]
+	fn fmt_writes_formatted_surql_files() {
+		let temp_dir = assert_fs::TempDir::new().unwrap();
+
+		let statement_file = temp_dir.child("statement.surql");
+
+		statement_file.touch().unwrap();
+		statement_file.write_str("-- things\ncreate thing:success;select * from thing").unwrap();
+
+		assert!(common::run_in_dir("fmt --write --lowercase", &temp_dir).output().is_ok());
+		assert_eq!(
+			std::fs::read_to_string(statement_file.path()).unwrap(),
+			"-- things\ncreate thing:success;\nselect * from thing;\n"
+		);
+	}
+
+	#[test]
+	fn fmt_failed_due_to_invalid_surql_files_syntax() {
+		let temp_dir = assert_fs::TempDir::new().unwrap();
+
+		let statement_file = temp_dir.child("statement.surql");
+
+		statement_file.touch().unwrap();
+		statement_file.write_str("CREATE $thing WHERE value = '';").unwrap();
+
+		assert!(common::run_in_dir("fmt", &temp_dir).output().is_err());
+	}
+
 	#[test(tokio::test)]
 	async fn test_server_graceful_shutdown() {
 		let (_, mut server) = common::start_server_with_defaults().await.unwrap();
@@ -710,4 +763,28 @@ mod cli_integration {
 			assert!(output.contains("[1]"), "unexpected output: {output:?}");
 		}
 	}
+
+	#[test(tokio::test)]
+	async fn test_slow_query_threshold() {
+		let (addr, _server) = common::start_server(StartServerArguments {
+			args: "--slow-query-threshold 0ns".to_owned(),
+			..Default::default()
+		})
+		.await
+		.unwrap();
+
+		let cmd = format!("sql --conn ws://{addr} -u root -p root --ns N --db D --multi");
+
+		let query = "CREATE user:1 SET email = 'tobie@surrealdb.com';\n\n";
+		common::run(&cmd).input(query).output().unwrap();
+		let query = "SELECT id FROM user WHERE email = 'tobie@surrealdb.com';\n\n";
+		common::run(&cmd).input(query).output().unwrap();
+
+		let query = "SUGGEST INDEXES;\n\n";
+		let output = common::run(&cmd).input(query).output().unwrap();
+		assert!(
+			output.contains("DEFINE INDEX user_email ON user FIELDS email"),
+			"unexpected output: {output:?}"
+		);
+	}
 }
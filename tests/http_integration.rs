@@ -179,6 +179,19 @@ mod http_integration {
 			assert!(body.contains("DEFINE TABLE foo"), "body: {}", body);
 		}
 
+		// When stable iteration is requested, it returns the contents of the DB
+		{
+			let res = client
+				.get(format!("{url}?stable=true"))
+				.basic_auth(USER, Some(PASS))
+				.send()
+				.await?;
+			assert_eq!(res.status(), 200, "body: {}", res.text().await?);
+			let body = res.text().await?;
+			assert!(body.contains("DEFINE TABLE foo"), "body: {}", body);
+			assert!(body.contains("UPDATE foo:"), "body: {}", body);
+		}
+
 		Ok(())
 	}
 
@@ -522,6 +535,21 @@ mod http_integration {
 			assert_eq!(res.status(), 415);
 		}
 
+		// A query which fails to parse returns a structured diagnostic
+		{
+			let res = client
+				.post(url)
+				.basic_auth(USER, Some(PASS))
+				.body("SELECT * FROM (foo")
+				.send()
+				.await?;
+			assert_eq!(res.status(), 400);
+
+			let body: serde_json::Value = serde_json::from_str(&res.text().await?).unwrap();
+			assert!(body["diagnostic"]["code"].is_string(), "body: {}", body);
+			assert_eq!(body["diagnostic"]["span"]["line"], 1, "body: {}", body);
+		}
+
 		// Test WebSocket upgrade
 		{
 			let res = client
@@ -374,6 +374,8 @@ mod ws_integration {
 			"result: {:?}",
 			res
 		);
+		assert_eq!(res[0]["name"], "IAM_ERROR", "result: {:?}", res);
+		assert_eq!(res[0]["category"], "permission", "result: {:?}", res);
 		Ok(())
 	}
 
@@ -731,6 +733,62 @@ mod ws_integration {
 		Ok(())
 	}
 
+	#[test(tokio::test)]
+	async fn live_query_none_output() -> Result<(), Box<dyn std::error::Error>> {
+		let (addr, _server) = common::start_server_without_auth().await.unwrap();
+		let table_name = "table_0C7E5B1D8A2F4E6B9D3C1A5F7E9B2D4C".to_string();
+
+		let socket = &mut common::connect_ws(&addr).await?;
+
+		let ns = "5d0b7e2c9a4f41e8b6c3d1a7f9e2b4c6";
+		let db = "8e1f3a5c7b9d4f2a6c8e0b2d4f6a8c1e";
+		let _ = common::ws_signin(socket, USER, PASS, None, None, None).await?;
+		let _ = common::ws_use(socket, Some(ns), Some(db)).await?;
+
+		// Omit the NONE fields of the responses of this session
+		let res = common::ws_query(socket, "SET OPTION NONE_OUTPUT = 'omit';").await?;
+		assert_eq!(res.len(), 1, "Expected 1 result got: {:?}", res);
+
+		// The missing field is NONE in each notification
+		let lq_res = common::ws_query(
+			socket,
+			format!("LIVE SELECT name, missing FROM {};", table_name).as_str(),
+		)
+		.await?;
+		assert_eq!(lq_res.len(), 1, "Expected 1 result got: {:?}", lq_res);
+		let live_id = lq_res[0]["result"].as_str().unwrap();
+
+		// Create some data for notification
+		let query = format!(r#"CREATE {}:one SET name = "ok";"#, table_name);
+		let json = json!({
+			"id": "1",
+			"method": "query",
+			"params": [query],
+		});
+
+		common::ws_send_msg(socket, serde_json::to_string(&json).unwrap()).await?;
+
+		// Wait some time for all messages to arrive, and then search for the notification message
+		let msgs = common::ws_recv_all_msgs(socket, 2, Duration::from_millis(500)).await;
+		assert!(msgs.is_ok(), "Error waiting for messages: {:?}", msgs.err());
+		let msgs = msgs.unwrap();
+		let lq_notif = msgs.iter().find(|v| common::ws_msg_is_notification_from_lq(v, live_id));
+		assert!(
+			lq_notif.is_some(),
+			"Expected to find a notification for LQ id {}: {:#?}",
+			live_id,
+			msgs
+		);
+
+		// The NONE field is omitted from the notification
+		let lq_notif = lq_notif.unwrap()["result"].as_object().unwrap();
+		let result = lq_notif["result"].as_object().unwrap();
+		assert_eq!(result["name"].as_str(), Some("ok"), "unexpected notification: {:?}", lq_notif);
+		assert!(!result.contains_key("missing"), "unexpected notification: {:?}", lq_notif);
+
+		Ok(())
+	}
+
 	#[test(tokio::test)]
 	async fn let_and_set() -> Result<(), Box<dyn std::error::Error>> {
 		let (addr, _server) = common::start_server_with_defaults().await.unwrap();
@@ -1427,6 +1485,296 @@ mod ws_integration {
 		Ok(())
 	}
 
+	#[test(tokio::test)]
+	async fn cursor() -> Result<(), Box<dyn std::error::Error>> {
+		let (addr, _server) = common::start_server_with_defaults().await.unwrap();
+		let socket = &mut common::connect_ws(&addr).await?;
+
+		//
+		// Prepare the connection
+		//
+		let res = common::ws_signin(socket, USER, PASS, None, None, None).await;
+		assert!(res.is_ok(), "result: {:?}", res);
+		let res = common::ws_use(socket, Some("N"), Some("D")).await;
+		assert!(res.is_ok(), "result: {:?}", res);
+
+		//
+		// Setup the database
+		//
+		let res = common::ws_query(socket, "CREATE |foo:1..5|").await;
+		assert!(res.is_ok(), "result: {:?}", res);
+
+		//
+		// Open a cursor over the records
+		//
+		let res = common::ws_send_msg_and_wait_response(
+			socket,
+			serde_json::to_string(&json!({
+				"id": "1",
+				"method": "cursor",
+				"params": [
+					"SELECT * FROM foo START 1",
+					2,
+				],
+			}))
+			.unwrap(),
+		)
+		.await;
+		assert!(res.is_ok(), "result: {:?}", res);
+		let res = res.unwrap();
+		assert_eq!(res["result"]["done"], false, "result: {:?}", res);
+		let cursor = res["result"]["id"].as_str().unwrap().to_owned();
+		let res = res["result"]["result"].as_array().unwrap();
+		assert_eq!(res.len(), 2, "result: {:?}", res);
+		assert_eq!(res[0]["id"], "foo:2", "result: {:?}", res);
+
+		//
+		// Fetch the remaining batches
+		//
+		let res = common::ws_send_msg_and_wait_response(
+			socket,
+			serde_json::to_string(&json!({
+				"id": "1",
+				"method": "next",
+				"params": [
+					cursor,
+				],
+			}))
+			.unwrap(),
+		)
+		.await;
+		assert!(res.is_ok(), "result: {:?}", res);
+		let res = res.unwrap();
+		assert_eq!(res["result"]["done"], true, "result: {:?}", res);
+		let res = res["result"]["result"].as_array().unwrap();
+		assert_eq!(res.len(), 2, "result: {:?}", res);
+		assert_eq!(res[1]["id"], "foo:5", "result: {:?}", res);
+
+		//
+		// The cursor is closed after the last batch
+		//
+		let res = common::ws_send_msg_and_wait_response(
+			socket,
+			serde_json::to_string(&json!({
+				"id": "1",
+				"method": "close",
+				"params": [
+					cursor,
+				],
+			}))
+			.unwrap(),
+		)
+		.await;
+		assert!(res.is_ok(), "result: {:?}", res);
+		let res = res.unwrap();
+		assert!(res["error"].is_object(), "result: {:?}", res);
+
+		Ok(())
+	}
+
+	#[test(tokio::test)]
+	async fn prepare_and_execute() -> Result<(), Box<dyn std::error::Error>> {
+		let (addr, _server) = common::start_server_with_defaults().await.unwrap();
+		let socket = &mut common::connect_ws(&addr).await?;
+
+		//
+		// Prepare the connection
+		//
+		let res = common::ws_signin(socket, USER, PASS, None, None, None).await;
+		assert!(res.is_ok(), "result: {:?}", res);
+		let res = common::ws_use(socket, Some("N"), Some("D")).await;
+		assert!(res.is_ok(), "result: {:?}", res);
+
+		//
+		// Setup the database
+		//
+		let res = common::ws_query(socket, "CREATE |foo:1..5|").await;
+		assert!(res.is_ok(), "result: {:?}", res);
+
+		//
+		// Prepare a parameterised query
+		//
+		let res = common::ws_send_msg_and_wait_response(
+			socket,
+			serde_json::to_string(&json!({
+				"id": "1",
+				"method": "prepare",
+				"params": [
+					"SELECT * FROM foo WHERE meta::id(id) > $min",
+				],
+			}))
+			.unwrap(),
+		)
+		.await;
+		assert!(res.is_ok(), "result: {:?}", res);
+		let res = res.unwrap();
+		let stmt = res["result"].as_str().unwrap().to_owned();
+
+		//
+		// Execute the query with different parameters
+		//
+		for (min, len) in [(2, 3), (4, 1)] {
+			let res = common::ws_send_msg_and_wait_response(
+				socket,
+				serde_json::to_string(&json!({
+					"id": "1",
+					"method": "execute",
+					"params": [
+						stmt,
+						{ "min": min },
+					],
+				}))
+				.unwrap(),
+			)
+			.await;
+			assert!(res.is_ok(), "result: {:?}", res);
+			let res = res.unwrap();
+			let res = res["result"][0]["result"].as_array().unwrap();
+			assert_eq!(res.len(), len, "result: {:?}", res);
+		}
+
+		//
+		// The query is not available once released
+		//
+		let res = common::ws_send_msg_and_wait_response(
+			socket,
+			serde_json::to_string(&json!({
+				"id": "1",
+				"method": "unprepare",
+				"params": [
+					stmt,
+				],
+			}))
+			.unwrap(),
+		)
+		.await;
+		assert!(res.is_ok(), "result: {:?}", res);
+		let res = common::ws_send_msg_and_wait_response(
+			socket,
+			serde_json::to_string(&json!({
+				"id": "1",
+				"method": "execute",
+				"params": [
+					stmt,
+				],
+			}))
+			.unwrap(),
+		)
+		.await;
+		assert!(res.is_ok(), "result: {:?}", res);
+		let res = res.unwrap();
+		assert!(res["error"].is_object(), "result: {:?}", res);
+
+		Ok(())
+	}
+
+	#[test(tokio::test)]
+	async fn register_and_run() -> Result<(), Box<dyn std::error::Error>> {
+		let (addr, _server) = common::start_server_with_defaults().await.unwrap();
+		let socket = &mut common::connect_ws(&addr).await?;
+
+		//
+		// Prepare the connection
+		//
+		let res = common::ws_signin(socket, USER, PASS, None, None, None).await;
+		assert!(res.is_ok(), "result: {:?}", res);
+		let res = common::ws_use(socket, Some("N"), Some("D")).await;
+		assert!(res.is_ok(), "result: {:?}", res);
+
+		//
+		// Setup the database
+		//
+		let res = common::ws_query(socket, "CREATE |foo:1..5|").await;
+		assert!(res.is_ok(), "result: {:?}", res);
+
+		//
+		// Register a named query
+		//
+		let res = common::ws_send_msg_and_wait_response(
+			socket,
+			serde_json::to_string(&json!({
+				"id": "1",
+				"method": "register",
+				"params": [
+					"foos",
+					"SELECT * FROM foo WHERE meta::id(id) > $min",
+					{ "min": "int" },
+				],
+			}))
+			.unwrap(),
+		)
+		.await;
+		assert!(res.is_ok(), "result: {:?}", res);
+		let res = res.unwrap();
+		assert!(res["error"].is_null(), "result: {:?}", res);
+
+		//
+		// Run the query from an anonymous connection
+		//
+		let socket = &mut common::connect_ws(&addr).await?;
+		let res = common::ws_use(socket, Some("N"), Some("D")).await;
+		assert!(res.is_ok(), "result: {:?}", res);
+		let res = common::ws_send_msg_and_wait_response(
+			socket,
+			serde_json::to_string(&json!({
+				"id": "1",
+				"method": "run",
+				"params": [
+					"foos",
+					{ "min": 2 },
+				],
+			}))
+			.unwrap(),
+		)
+		.await;
+		assert!(res.is_ok(), "result: {:?}", res);
+		let res = res.unwrap();
+		let res = res["result"][0]["result"].as_array().unwrap();
+		assert_eq!(res.len(), 3, "result: {:?}", res);
+
+		//
+		// Undeclared parameters are rejected
+		//
+		let res = common::ws_send_msg_and_wait_response(
+			socket,
+			serde_json::to_string(&json!({
+				"id": "1",
+				"method": "run",
+				"params": [
+					"foos",
+					{ "min": 2, "max": 4 },
+				],
+			}))
+			.unwrap(),
+		)
+		.await;
+		assert!(res.is_ok(), "result: {:?}", res);
+		let res = res.unwrap();
+		assert!(res["error"].is_object(), "result: {:?}", res);
+
+		//
+		// Anonymous connections can not register queries
+		//
+		let res = common::ws_send_msg_and_wait_response(
+			socket,
+			serde_json::to_string(&json!({
+				"id": "1",
+				"method": "register",
+				"params": [
+					"all",
+					"SELECT * FROM foo",
+				],
+			}))
+			.unwrap(),
+		)
+		.await;
+		assert!(res.is_ok(), "result: {:?}", res);
+		let res = res.unwrap();
+		assert!(res["error"].is_object(), "result: {:?}", res);
+
+		Ok(())
+	}
+
 	#[test(tokio::test)]
 	async fn version() -> Result<(), Box<dyn std::error::Error>> {
 		let (addr, _server) = common::start_server_with_defaults().await.unwrap();